@@ -193,6 +193,8 @@ fn create_workspace_with_tree(file_count: usize) -> Workspace {
         sidebar_visible: true,
         sidebar_width_logical: metrics.sidebar_default_width_logical,
         scroll_offset: 0,
+        config: Default::default(),
+        name_edit: None,
     }
 }
 
@@ -291,6 +293,8 @@ fn reveal_file_deep_path(depth: usize) {
         sidebar_visible: true,
         sidebar_width_logical: 250.0,
         scroll_offset: 0,
+        config: Default::default(),
+        name_edit: None,
     };
 
     // Create a deep path
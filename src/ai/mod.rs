@@ -0,0 +1,381 @@
+//! AI chat panel: conversation state, prompt building, and chat providers
+//!
+//! Requests run on a background thread (see `Cmd::AiChatRequest`). The
+//! provider streams response deltas back to the main thread as
+//! `Msg::AiChat(AiChatMsg::StreamDelta)`. Context actions that write answers
+//! into a buffer go through `DocumentMsg::InsertText` / `PasteText`, so they
+//! are regular undoable edits.
+
+mod provider;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::editable::{EditConstraints, EditableState, StringBuffer};
+
+pub use provider::{create_provider, parse_sse_line, ChatProvider, OpenAiProvider, SseEvent};
+
+/// Backend used for chat requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AiProviderKind {
+    /// Any server implementing the OpenAI `/chat/completions` streaming API
+    /// (OpenAI, OpenRouter, Ollama, LM Studio, llama.cpp, ...)
+    #[default]
+    OpenAi,
+}
+
+/// `ai:` section of `config.yaml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AiConfig {
+    #[serde(default)]
+    pub provider: AiProviderKind,
+
+    /// API base URL, without the `/chat/completions` suffix
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+
+    /// Model identifier sent with each request
+    #[serde(default = "default_model")]
+    pub model: String,
+
+    /// Environment variable holding the API key. The key itself is never
+    /// stored in the config file. Local servers usually need no key.
+    #[serde(default = "default_api_key_env")]
+    pub api_key_env: String,
+
+    /// System prompt prepended to every conversation
+    #[serde(default = "default_system_prompt")]
+    pub system_prompt: String,
+}
+
+fn default_base_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+fn default_api_key_env() -> String {
+    "OPENAI_API_KEY".to_string()
+}
+
+fn default_system_prompt() -> String {
+    "You are a programming assistant embedded in a code editor. \
+     Answer concisely. When asked for code, reply with a single fenced code block."
+        .to_string()
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            provider: AiProviderKind::default(),
+            base_url: default_base_url(),
+            model: default_model(),
+            api_key_env: default_api_key_env(),
+            system_prompt: default_system_prompt(),
+        }
+    }
+}
+
+/// Author of a chat message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+}
+
+impl ChatRole {
+    /// Role name used by the OpenAI wire format
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChatRole::System => "system",
+            ChatRole::User => "user",
+            ChatRole::Assistant => "assistant",
+        }
+    }
+
+    /// Label shown above the message in the panel
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChatRole::System => "System",
+            ChatRole::User => "You",
+            ChatRole::Assistant => "Assistant",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn new(role: ChatRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+        }
+    }
+}
+
+/// State of the AI chat panel
+#[derive(Debug, Clone)]
+pub struct AiChatState {
+    /// Prompt input field
+    pub input: EditableState<StringBuffer>,
+    /// Conversation transcript (without the system prompt)
+    pub messages: Vec<ChatMessage>,
+    /// Id of the most recent request; deltas from older requests are dropped
+    pub request_id: u64,
+    /// Whether a response is currently streaming in
+    pub is_streaming: bool,
+    /// Error from the last request, shown below the transcript
+    pub error: Option<String>,
+    /// Transcript lines scrolled up from the bottom (0 = follow new output)
+    pub scroll_offset: usize,
+    /// Cancellation flag shared with the in-flight request thread
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Default for AiChatState {
+    fn default() -> Self {
+        Self {
+            input: EditableState::new(StringBuffer::new(), EditConstraints::single_line()),
+            messages: Vec::new(),
+            request_id: 0,
+            is_streaming: false,
+            error: None,
+            scroll_offset: 0,
+            cancel: None,
+        }
+    }
+}
+
+impl AiChatState {
+    /// Start a new request, cancelling any request still in flight.
+    ///
+    /// Returns the new request id and its cancellation flag.
+    pub fn begin_request(&mut self) -> (u64, Arc<AtomicBool>) {
+        self.cancel_request();
+        self.request_id = self.request_id.wrapping_add(1);
+        self.is_streaming = true;
+        self.error = None;
+        self.scroll_offset = 0;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.cancel = Some(cancel.clone());
+        (self.request_id, cancel)
+    }
+
+    /// Signal the in-flight request (if any) to stop
+    pub fn cancel_request(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        self.is_streaming = false;
+    }
+
+    /// Content of the most recent non-empty assistant message
+    pub fn last_answer(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|msg| msg.role == ChatRole::Assistant && !msg.content.trim().is_empty())
+            .map(|msg| msg.content.as_str())
+    }
+
+    /// Full conversation to send to the provider, including the system prompt
+    pub fn request_messages(&self, system_prompt: &str) -> Vec<ChatMessage> {
+        let mut messages = Vec::with_capacity(self.messages.len() + 1);
+        if !system_prompt.trim().is_empty() {
+            messages.push(ChatMessage::new(ChatRole::System, system_prompt));
+        }
+        messages.extend(
+            self.messages
+                .iter()
+                .filter(|msg| !msg.content.is_empty())
+                .cloned(),
+        );
+        messages
+    }
+
+    /// Transcript rows for display, followed by the last error (if any)
+    pub fn display_rows(&self, max_cols: usize) -> Vec<TranscriptRow> {
+        let mut rows = transcript_rows(&self.messages, max_cols);
+        if let Some(error) = &self.error {
+            if !rows.is_empty() {
+                rows.push(TranscriptRow::Spacer);
+            }
+            rows.extend(
+                wrap_text(error, max_cols)
+                    .into_iter()
+                    .map(TranscriptRow::Error),
+            );
+        }
+        rows
+    }
+}
+
+/// Prompt for the "explain selection" context action
+pub fn explain_selection_prompt(code: &str, language: &str, file_name: &str) -> String {
+    let fence = language.to_lowercase().replace(' ', "");
+    format!(
+        "Explain what the following {} code from `{}` does:\n\n```{}\n{}\n```",
+        language,
+        file_name,
+        fence,
+        code.trim_end_matches('\n')
+    )
+}
+
+/// Text to write into the buffer for an answer.
+///
+/// If the answer contains a fenced code block, only the first block's body
+/// is used; otherwise the whole answer is used as-is.
+pub fn answer_insert_text(answer: &str) -> &str {
+    let Some(open) = answer.find("```") else {
+        return answer.trim();
+    };
+    let after_fence = &answer[open + 3..];
+    // Skip the info string (language tag) on the opening fence line
+    let Some(body_start) = after_fence.find('\n') else {
+        return answer.trim();
+    };
+    let body = &after_fence[body_start + 1..];
+    match body.find("```") {
+        Some(close) => body[..close].trim_end_matches(['\n', '\r']),
+        // Still streaming (or malformed): use everything after the fence
+        None => body.trim_end(),
+    }
+}
+
+/// Wrap text into rows of at most `max_cols` chars, breaking at spaces when
+/// possible. Explicit newlines always start a new row.
+pub fn wrap_text(text: &str, max_cols: usize) -> Vec<String> {
+    let max_cols = max_cols.max(1);
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.is_empty() {
+            rows.push(String::new());
+            continue;
+        }
+        let mut start = 0;
+        while start < chars.len() {
+            if chars.len() - start <= max_cols {
+                rows.push(chars[start..].iter().collect());
+                break;
+            }
+            let limit = start + max_cols;
+            // Prefer the space right after a full row, then the last space in it
+            let break_at = if chars[limit] == ' ' {
+                Some(limit)
+            } else {
+                chars[start..limit]
+                    .iter()
+                    .rposition(|ch| *ch == ' ')
+                    .map(|idx| start + idx)
+                    .filter(|&idx| idx > start)
+            };
+            match break_at {
+                Some(idx) => {
+                    rows.push(chars[start..idx].iter().collect());
+                    start = idx + 1;
+                }
+                None => {
+                    rows.push(chars[start..limit].iter().collect());
+                    start = limit;
+                }
+            }
+        }
+    }
+    rows
+}
+
+/// A rendered transcript row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptRow {
+    /// Role header ("You", "Assistant")
+    Header(ChatRole),
+    /// Wrapped message text
+    Text(String),
+    /// Blank separator between messages
+    Spacer,
+    /// Wrapped error text from the last request
+    Error(String),
+}
+
+/// Flatten a conversation into wrapped display rows
+pub fn transcript_rows(messages: &[ChatMessage], max_cols: usize) -> Vec<TranscriptRow> {
+    let mut rows = Vec::new();
+    for (idx, message) in messages.iter().enumerate() {
+        if idx > 0 {
+            rows.push(TranscriptRow::Spacer);
+        }
+        rows.push(TranscriptRow::Header(message.role));
+        rows.extend(
+            wrap_text(&message.content, max_cols)
+                .into_iter()
+                .map(TranscriptRow::Text),
+        );
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_text_prefers_first_code_block() {
+        let answer = "Here you go:\n\n```rust\nfn main() {}\n```\n\nAnything else?";
+        assert_eq!(answer_insert_text(answer), "fn main() {}");
+    }
+
+    #[test]
+    fn answer_text_without_code_block_is_trimmed_answer() {
+        assert_eq!(answer_insert_text("  just text \n"), "just text");
+    }
+
+    #[test]
+    fn wrap_breaks_at_spaces_and_hard_wraps_long_words() {
+        assert_eq!(wrap_text("hello world foo", 11), vec!["hello world", "foo"]);
+        assert_eq!(wrap_text("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        assert_eq!(wrap_text("a\n\nb", 10), vec!["a", "", "b"]);
+    }
+
+    #[test]
+    fn request_messages_prepend_system_prompt_and_skip_empty() {
+        let mut state = AiChatState::default();
+        state.messages.push(ChatMessage::new(ChatRole::User, "hi"));
+        state
+            .messages
+            .push(ChatMessage::new(ChatRole::Assistant, ""));
+
+        let messages = state.request_messages("be brief");
+        assert_eq!(
+            messages,
+            vec![
+                ChatMessage::new(ChatRole::System, "be brief"),
+                ChatMessage::new(ChatRole::User, "hi"),
+            ]
+        );
+    }
+
+    #[test]
+    fn begin_request_cancels_previous_request() {
+        let mut state = AiChatState::default();
+        let (first_id, first_cancel) = state.begin_request();
+        let (second_id, second_cancel) = state.begin_request();
+
+        assert_ne!(first_id, second_id);
+        assert!(first_cancel.load(Ordering::Relaxed));
+        assert!(!second_cancel.load(Ordering::Relaxed));
+        assert!(state.is_streaming);
+    }
+}
@@ -0,0 +1,278 @@
+//! Chat providers
+//!
+//! A provider turns a conversation into a stream of text deltas. Providers
+//! run on a background thread and call `on_delta` for every chunk; returning
+//! `false` from the callback stops the stream early.
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+use super::{AiConfig, AiProviderKind, ChatMessage};
+
+/// A chat completion backend
+pub trait ChatProvider: Send {
+    /// Human-readable provider name (for status and error messages)
+    fn name(&self) -> &str;
+
+    /// Stream a completion for `messages`, calling `on_delta` for each chunk
+    /// of the answer. Blocks until the response is complete, the callback
+    /// returns `false`, or the request fails.
+    fn stream_chat(
+        &self,
+        messages: &[ChatMessage],
+        on_delta: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<(), String>;
+}
+
+/// Build the provider described by the `ai:` config section
+pub fn create_provider(config: &AiConfig) -> Result<Box<dyn ChatProvider>, String> {
+    match config.provider {
+        AiProviderKind::OpenAi => {
+            let api_key = if config.api_key_env.is_empty() {
+                None
+            } else {
+                std::env::var(&config.api_key_env)
+                    .ok()
+                    .filter(|key| !key.is_empty())
+            };
+            Ok(Box::new(OpenAiProvider {
+                base_url: config.base_url.clone(),
+                model: config.model.clone(),
+                api_key,
+            }))
+        }
+    }
+}
+
+/// One parsed server-sent-events line of a streaming completion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SseEvent {
+    /// A chunk of answer text
+    Delta(String),
+    /// End of stream (`data: [DONE]`)
+    Done,
+    /// Error object sent by the server
+    Error(String),
+}
+
+/// Extract `error.message` (or the whole error value) from a JSON error body
+fn error_message(value: &serde_json::Value) -> Option<String> {
+    let error = value.get("error")?;
+    Some(
+        error
+            .get("message")
+            .and_then(|m| m.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string()),
+    )
+}
+
+/// Parse one line of an OpenAI-style streaming response.
+///
+/// Returns `None` for lines that carry no answer text (comments, blank
+/// keep-alive lines, role-only deltas).
+pub fn parse_sse_line(line: &str) -> Option<SseEvent> {
+    let payload = line.trim_end_matches('\r').strip_prefix("data:")?.trim();
+    if payload == "[DONE]" {
+        return Some(SseEvent::Done);
+    }
+    let value: serde_json::Value = serde_json::from_str(payload).ok()?;
+    if let Some(message) = error_message(&value) {
+        return Some(SseEvent::Error(message));
+    }
+    let content = value
+        .get("choices")?
+        .get(0)?
+        .get("delta")?
+        .get("content")?
+        .as_str()?;
+    (!content.is_empty()).then(|| SseEvent::Delta(content.to_string()))
+}
+
+/// Escape a value for a double-quoted curl config string
+fn curl_config_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for ch in value.chars() {
+        match ch {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// OpenAI-compatible `/chat/completions` backend.
+///
+/// Token has no HTTP/TLS stack of its own, so requests are made with the
+/// system `curl`. The request (including the API key) is passed to curl as a
+/// config file on stdin, which keeps the key out of the process list.
+#[derive(Debug, Clone)]
+pub struct OpenAiProvider {
+    pub base_url: String,
+    pub model: String,
+    pub api_key: Option<String>,
+}
+
+impl OpenAiProvider {
+    fn request_body(&self, messages: &[ChatMessage]) -> serde_json::Value {
+        let messages: Vec<serde_json::Value> = messages
+            .iter()
+            .map(|msg| serde_json::json!({ "role": msg.role.as_str(), "content": msg.content }))
+            .collect();
+        serde_json::json!({
+            "model": self.model,
+            "messages": messages,
+            "stream": true,
+        })
+    }
+
+    fn curl_config(&self, messages: &[ChatMessage]) -> String {
+        let url = format!("{}/chat/completions", self.base_url.trim_end_matches('/'));
+        let mut config = format!("url = {}\n", curl_config_quote(&url));
+        config.push_str("header = \"Content-Type: application/json\"\n");
+        config.push_str("header = \"Accept: text/event-stream\"\n");
+        if let Some(key) = &self.api_key {
+            config.push_str(&format!(
+                "header = {}\n",
+                curl_config_quote(&format!("Authorization: Bearer {}", key))
+            ));
+        }
+        config.push_str(&format!(
+            "data-binary = {}\n",
+            curl_config_quote(&self.request_body(messages).to_string())
+        ));
+        config
+    }
+}
+
+impl ChatProvider for OpenAiProvider {
+    fn name(&self) -> &str {
+        "OpenAI-compatible"
+    }
+
+    fn stream_chat(
+        &self,
+        messages: &[ChatMessage],
+        on_delta: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<(), String> {
+        let mut child = Command::new("curl")
+            .args([
+                "--silent",
+                "--show-error",
+                "--no-buffer",
+                "--fail-with-body",
+                "--config",
+                "-",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(self.curl_config(messages).as_bytes())
+                .map_err(|e| format!("Failed to send request: {}", e))?;
+        }
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| "Failed to read response".to_string())?;
+
+        // Non-SSE output is an error body (`--fail-with-body`)
+        let mut other_output = String::new();
+        let mut stream_error = None;
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            match parse_sse_line(&line) {
+                Some(SseEvent::Delta(text)) if !on_delta(&text) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Ok(());
+                }
+                Some(SseEvent::Delta(_)) => {}
+                Some(SseEvent::Done) => break,
+                Some(SseEvent::Error(message)) => {
+                    stream_error = Some(message);
+                    break;
+                }
+                None if !line.starts_with("data:") => {
+                    other_output.push_str(&line);
+                    other_output.push('\n');
+                }
+                None => {}
+            }
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("curl failed: {}", e))?;
+        if let Some(message) = stream_error {
+            return Err(message);
+        }
+        if !output.status.success() {
+            let body_error = serde_json::from_str::<serde_json::Value>(other_output.trim())
+                .ok()
+                .and_then(|value| error_message(&value));
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(body_error
+                .or_else(|| (!stderr.is_empty()).then_some(stderr))
+                .unwrap_or_else(|| format!("Request failed ({})", output.status)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ChatRole;
+
+    #[test]
+    fn parses_content_deltas_and_done() {
+        assert_eq!(
+            parse_sse_line(r#"data: {"choices":[{"delta":{"content":"Hel"}}]}"#),
+            Some(SseEvent::Delta("Hel".to_string()))
+        );
+        assert_eq!(parse_sse_line("data: [DONE]"), Some(SseEvent::Done));
+        assert_eq!(
+            parse_sse_line(r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#),
+            None
+        );
+        assert_eq!(parse_sse_line(": keep-alive"), None);
+    }
+
+    #[test]
+    fn parses_stream_errors() {
+        assert_eq!(
+            parse_sse_line(r#"data: {"error":{"message":"rate limited"}}"#),
+            Some(SseEvent::Error("rate limited".to_string()))
+        );
+    }
+
+    #[test]
+    fn curl_config_escapes_body_and_includes_key() {
+        let provider = OpenAiProvider {
+            base_url: "http://localhost:11434/v1/".to_string(),
+            model: "llama3".to_string(),
+            api_key: Some("secret".to_string()),
+        };
+        let config = provider.curl_config(&[ChatMessage::new(ChatRole::User, "say \"hi\"\\n\nok")]);
+
+        assert!(config.contains("url = \"http://localhost:11434/v1/chat/completions\"\n"));
+        assert!(config.contains("header = \"Authorization: Bearer secret\"\n"));
+        // Every line is a single `key = value` pair: the body has no raw newlines
+        assert_eq!(config.lines().count(), 5);
+        assert!(config.contains(r#"say \\\"hi\\\"\\\\n\\nok"#));
+    }
+}
@@ -262,6 +262,13 @@ fn create_model(args: &Args) -> Result<token::model::AppModel> {
         terminal: token::terminal::TerminalState::default(),
         outline_panel: token::model::OutlinePanelState::default(),
         recent_files: token::recent_files::RecentFiles::default(),
+        workspace_trust: token::workspace_trust::WorkspaceTrust::default(),
+        file_ops: Default::default(),
+        vim: Default::default(),
+        ai_chat: token::ai::AiChatState::default(),
+        lsp: token::lsp::LspState::default(),
+        todo_panel: token::model::TodoPanelState::default(),
+        search_panel: token::model::SearchPanelState::default(),
         #[cfg(debug_assertions)]
         debug_overlay: None,
     };
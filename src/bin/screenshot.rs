@@ -284,6 +284,13 @@ fn create_model_from_scenario(scenario: &Scenario, theme: Theme) -> Result<AppMo
         terminal: token::terminal::TerminalState::default(),
         outline_panel: token::model::OutlinePanelState::default(),
         recent_files: token::recent_files::RecentFiles::default(),
+        workspace_trust: token::workspace_trust::WorkspaceTrust::default(),
+        file_ops: Default::default(),
+        vim: Default::default(),
+        ai_chat: token::ai::AiChatState::default(),
+        lsp: token::lsp::LspState::default(),
+        todo_panel: token::model::TodoPanelState::default(),
+        search_panel: token::model::SearchPanelState::default(),
         #[cfg(debug_assertions)]
         debug_overlay: None,
     };
@@ -411,7 +418,7 @@ fn apply_view_modes(model: &mut AppModel, scenario: &Scenario) {
                 if let Ok(data) = parse_csv(&text, delimiter) {
                     if !data.is_empty() && data.column_count() > 0 {
                         let line_height = model.line_height.max(1);
-                        let tab_bar_height = model.metrics.tab_bar_height;
+                        let tab_bar_height = model.metrics.header_height();
                         let status_bar_height = line_height;
                         let col_header_height = line_height;
                         let content_height = (model.window_size.1 as usize)
@@ -528,7 +535,7 @@ fn apply_modal(model: &mut AppModel, config: &ModalConfig) {
             }
             state.replace_mode = config.replace_mode;
             state.case_sensitive = config.case_sensitive;
-            ModalState::FindReplace(state)
+            ModalState::FindReplace(Box::new(state))
         }
         ModalId::ThemePicker => {
             let current_id = model.config.theme.clone();
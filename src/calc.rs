@@ -0,0 +1,436 @@
+//! Calculator for "Evaluate Selection"
+//!
+//! Arithmetic (`+ - * / % **`), bit operations (`& | ^ ~ << >>`) on whole
+//! numbers, hex/binary/octal literals, byte sizes (`1.5 GiB`, `20 kB`) and a
+//! trailing conversion: `0xff in bin`, `1 GiB in bytes`, `3e9 in GB`.
+
+/// Byte size units, matched case-insensitively
+const SIZE_UNITS: &[(&str, f64)] = &[
+    ("b", 1.0),
+    ("byte", 1.0),
+    ("bytes", 1.0),
+    ("kb", 1e3),
+    ("mb", 1e6),
+    ("gb", 1e9),
+    ("tb", 1e12),
+    ("pb", 1e15),
+    ("kib", 1024.0),
+    ("mib", 1_048_576.0),
+    ("gib", 1_073_741_824.0),
+    ("tib", 1_099_511_627_776.0),
+    ("pib", 1_125_899_906_842_624.0),
+];
+
+/// Evaluate `input` and format the result, converted as asked by a trailing
+/// `in <target>` (or `to`/`as`)
+pub fn evaluate(input: &str) -> Result<String, String> {
+    let input = input.trim().trim_end_matches('=').trim_end();
+    let (expression, target) = split_conversion(input);
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return Err("Nothing to evaluate".to_string());
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    let value = parser.expression()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(format!("Unexpected {}", token.describe()));
+    }
+    if !value.is_finite() {
+        return Err("Result is not a finite number".to_string());
+    }
+    match target {
+        Some(target) => convert(value, target),
+        None => Ok(format_number(value)),
+    }
+}
+
+/// Split `expr in target` into the expression and the target, when the last
+/// word names a known target
+fn split_conversion(input: &str) -> (&str, Option<&str>) {
+    let mut words = input.rsplitn(3, char::is_whitespace);
+    if let (Some(target), Some(keyword), Some(expression)) =
+        (words.next(), words.next(), words.next())
+    {
+        let keyword = keyword.to_lowercase();
+        if matches!(keyword.as_str(), "in" | "to" | "as") && is_target(target) {
+            return (expression, Some(target));
+        }
+    }
+    (input, None)
+}
+
+fn is_target(target: &str) -> bool {
+    let target = target.to_lowercase();
+    matches!(
+        target.as_str(),
+        "hex" | "bin" | "binary" | "oct" | "octal" | "dec" | "decimal"
+    ) || size_unit(&target).is_some()
+}
+
+fn size_unit(name: &str) -> Option<f64> {
+    let name = name.to_lowercase();
+    SIZE_UNITS
+        .iter()
+        .find(|(unit, _)| *unit == name)
+        .map(|&(_, factor)| factor)
+}
+
+fn convert(value: f64, target: &str) -> Result<String, String> {
+    let radix = match target.to_lowercase().as_str() {
+        "hex" => Some(16),
+        "bin" | "binary" => Some(2),
+        "oct" | "octal" => Some(8),
+        "dec" | "decimal" => return Ok(format_number(value)),
+        _ => None,
+    };
+    match radix {
+        Some(radix) => {
+            let int = to_integer(value)?;
+            Ok(match radix {
+                16 => format!("{:#x}", int),
+                2 => format!("{:#b}", int),
+                _ => format!("{:#o}", int),
+            })
+        }
+        None => {
+            let factor = size_unit(target).ok_or_else(|| format!("Unknown unit {}", target))?;
+            let value = value / factor;
+            if factor == 1.0 {
+                Ok(format_number(value))
+            } else {
+                Ok(format!("{} {}", format_number(value), target))
+            }
+        }
+    }
+}
+
+/// Whole numbers without a fraction, others rounded to 10 decimals
+pub fn format_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    if value.abs() >= 1e16 || value.abs() < 1e-6 {
+        return format!("{:e}", value);
+    }
+    if value.fract() == 0.0 {
+        return format!("{}", value as i64);
+    }
+    let fixed = format!("{:.10}", value);
+    fixed
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn to_integer(value: f64) -> Result<i64, String> {
+    if value.fract() != 0.0 || value.abs() > i64::MAX as f64 {
+        return Err("Bit operations need whole numbers".to_string());
+    }
+    Ok(value as i64)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Word(String),
+    Op(&'static str),
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Number(value) => format_number(*value),
+            Token::Word(word) => format!("'{}'", word),
+            Token::Op(op) => format!("'{}'", op),
+            Token::Open => "'('".to_string(),
+            Token::Close => "')'".to_string(),
+        }
+    }
+}
+
+const OPERATORS: &[&str] = &[
+    "**", "<<", ">>", "+", "-", "*", "×", "/", "÷", "%", "&", "|", "^", "~",
+];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        if ch.is_whitespace() {
+            i += 1;
+        } else if ch == '(' {
+            tokens.push(Token::Open);
+            i += 1;
+        } else if ch == ')' {
+            tokens.push(Token::Close);
+            i += 1;
+        } else if ch.is_ascii_digit()
+            || (ch == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let (value, len) = number(&chars[i..])?;
+            tokens.push(Token::Number(value));
+            i += len;
+        } else if ch.is_alphabetic() {
+            let len = chars[i..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric())
+                .count();
+            tokens.push(Token::Word(chars[i..i + len].iter().collect()));
+            i += len;
+        } else if let Some(op) = OPERATORS.iter().find(|op| {
+            op.chars()
+                .enumerate()
+                .all(|(n, c)| chars.get(i + n) == Some(&c))
+        }) {
+            tokens.push(Token::Op(match *op {
+                "×" => "*",
+                "÷" => "/",
+                op => op,
+            }));
+            i += op.chars().count();
+        } else {
+            return Err(format!("Unexpected '{}'", ch));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parse the number literal at the start of `chars`, returning its value
+/// and length
+fn number(chars: &[char]) -> Result<(f64, usize), String> {
+    let radix = match (chars.first(), chars.get(1).map(|c| c.to_ascii_lowercase())) {
+        (Some('0'), Some('x')) => 16,
+        (Some('0'), Some('b')) => 2,
+        (Some('0'), Some('o')) => 8,
+        _ => 10,
+    };
+    if radix != 10 {
+        let len = chars[2..]
+            .iter()
+            .take_while(|c| c.is_digit(radix) || **c == '_')
+            .count();
+        let digits: String = chars[2..2 + len].iter().filter(|c| **c != '_').collect();
+        let value = i64::from_str_radix(&digits, radix).map_err(|_| {
+            format!(
+                "Invalid number {}",
+                chars[..2 + len].iter().collect::<String>()
+            )
+        })?;
+        return Ok((value as f64, 2 + len));
+    }
+
+    let mut len = chars
+        .iter()
+        .take_while(|c| c.is_ascii_digit() || **c == '_' || **c == '.')
+        .count();
+    // Exponent, only when digits follow (`2e3`, `1.5E-2`, but not `2 EB`)
+    if matches!(chars.get(len), Some('e' | 'E')) {
+        let sign = usize::from(matches!(chars.get(len + 1), Some('+' | '-')));
+        if chars.get(len + 1 + sign).is_some_and(char::is_ascii_digit) {
+            len += 1 + sign;
+            len += chars[len..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .count();
+        }
+    }
+    let text: String = chars[..len].iter().filter(|c| **c != '_').collect();
+    let value = text
+        .parse::<f64>()
+        .map_err(|_| format!("Invalid number {}", text))?;
+    Ok((value, len))
+}
+
+/// Recursive descent, loosest binding first: `|`, `^`, `&`, shifts,
+/// `+ -`, `* / %`, unary, `**`, then numbers with an optional unit
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn expression(&mut self) -> Result<f64, String> {
+        self.bit_or()
+    }
+
+    fn eat(&mut self, op: &'static str) -> bool {
+        let found = self.tokens.get(self.pos) == Some(&Token::Op(op));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn bit_or(&mut self) -> Result<f64, String> {
+        let mut value = self.bit_xor()?;
+        while self.eat("|") {
+            value = (to_integer(value)? | to_integer(self.bit_xor()?)?) as f64;
+        }
+        Ok(value)
+    }
+
+    fn bit_xor(&mut self) -> Result<f64, String> {
+        let mut value = self.bit_and()?;
+        while self.eat("^") {
+            value = (to_integer(value)? ^ to_integer(self.bit_and()?)?) as f64;
+        }
+        Ok(value)
+    }
+
+    fn bit_and(&mut self) -> Result<f64, String> {
+        let mut value = self.shift()?;
+        while self.eat("&") {
+            value = (to_integer(value)? & to_integer(self.shift()?)?) as f64;
+        }
+        Ok(value)
+    }
+
+    fn shift(&mut self) -> Result<f64, String> {
+        let mut value = self.sum()?;
+        loop {
+            let left = self.eat("<<");
+            if !left && !self.eat(">>") {
+                return Ok(value);
+            }
+            let amount = to_integer(self.sum()?)?;
+            if !(0..64).contains(&amount) {
+                return Err("Shift amount must be between 0 and 63".to_string());
+            }
+            let int = to_integer(value)?;
+            value = if left { int << amount } else { int >> amount } as f64;
+        }
+    }
+
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut value = self.product()?;
+        loop {
+            if self.eat("+") {
+                value += self.product()?;
+            } else if self.eat("-") {
+                value -= self.product()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat("*") {
+                value *= self.unary()?;
+            } else if self.eat("/") {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err("Division by zero".to_string());
+                }
+                value /= divisor;
+            } else if self.eat("%") {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err("Division by zero".to_string());
+                }
+                value %= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        if self.eat("-") {
+            Ok(-self.unary()?)
+        } else if self.eat("+") {
+            self.unary()
+        } else if self.eat("~") {
+            Ok(!to_integer(self.unary()?)? as f64)
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.quantity()?;
+        if self.eat("**") {
+            // Right-associative, and binds tighter than a unary minus on its left
+            Ok(base.powf(self.unary()?))
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// A number or parenthesized expression, times the size unit after it
+    fn quantity(&mut self) -> Result<f64, String> {
+        let value = match self.tokens.get(self.pos).cloned() {
+            Some(Token::Number(value)) => {
+                self.pos += 1;
+                value
+            }
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("pi") => {
+                self.pos += 1;
+                std::f64::consts::PI
+            }
+            Some(Token::Open) => {
+                self.pos += 1;
+                let value = self.expression()?;
+                if self.tokens.get(self.pos) != Some(&Token::Close) {
+                    return Err("Missing ')'".to_string());
+                }
+                self.pos += 1;
+                value
+            }
+            Some(token) => return Err(format!("Unexpected {}", token.describe())),
+            None => return Err("Incomplete expression".to_string()),
+        };
+        if let Some(Token::Word(word)) = self.tokens.get(self.pos) {
+            if let Some(factor) = size_unit(word) {
+                self.pos += 1;
+                return Ok(value * factor);
+            }
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_arithmetic_and_bit_operations() {
+        assert_eq!(evaluate("1 + 2 * 3").unwrap(), "7");
+        assert_eq!(evaluate("(1 + 2) * 3 =").unwrap(), "9");
+        assert_eq!(evaluate("-2 ** 2").unwrap(), "-4");
+        assert_eq!(evaluate("2 ** 3 ** 2").unwrap(), "512");
+        assert_eq!(evaluate("10 / 4").unwrap(), "2.5");
+        assert_eq!(evaluate("0.1 + 0.2").unwrap(), "0.3");
+        assert_eq!(evaluate("1_000 % 7").unwrap(), "6");
+        assert_eq!(evaluate("0xff & 0b1010 | 1 << 8").unwrap(), "266");
+        assert_eq!(evaluate("~0 ^ 0o7").unwrap(), "-8");
+        assert_eq!(evaluate("1.5e3").unwrap(), "1500");
+
+        assert_eq!(evaluate("1 / 0").unwrap_err(), "Division by zero");
+        assert_eq!(
+            evaluate("1.5 | 1").unwrap_err(),
+            "Bit operations need whole numbers"
+        );
+        assert_eq!(evaluate("2 +").unwrap_err(), "Incomplete expression");
+        assert_eq!(evaluate("2 apples").unwrap_err(), "Unexpected 'apples'");
+    }
+
+    #[test]
+    fn converts_bases_and_byte_sizes() {
+        assert_eq!(evaluate("0xff").unwrap(), "255");
+        assert_eq!(evaluate("255 in hex").unwrap(), "0xff");
+        assert_eq!(evaluate("0xff to bin").unwrap(), "0b11111111");
+        assert_eq!(evaluate("8 as oct").unwrap(), "0o10");
+        assert_eq!(evaluate("1 GiB in bytes").unwrap(), "1073741824");
+        assert_eq!(evaluate("1.5 MiB").unwrap(), "1572864");
+        assert_eq!(evaluate("3e9 B in GB").unwrap(), "3 GB");
+        assert_eq!(evaluate("2048 kib in MiB").unwrap(), "2 MiB");
+    }
+}
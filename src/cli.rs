@@ -5,6 +5,7 @@
 //! - Jump to line/column
 //! - Wait mode for git integration
 //! - New empty buffer mode
+//! - Recording and replaying editing sessions
 
 use clap::Parser;
 use std::path::PathBuf;
@@ -32,6 +33,14 @@ pub struct CliArgs {
     /// Go to column N (used with --line)
     #[arg(long, value_name = "N")]
     pub column: Option<usize>,
+
+    /// Record editing messages to FILE for replaying with --replay
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Open the files of a recording made with --record and replay it
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
 }
 
 /// The startup mode determines what to open
@@ -59,12 +68,28 @@ pub struct StartupConfig {
     pub initial_position: Option<(usize, usize)>,
     /// Wait for files to close before process exits
     pub wait_mode: bool,
+    /// Record the session to this file
+    pub record: Option<PathBuf>,
+    /// Recording to replay (its files replace any given paths)
+    pub replay: Option<crate::replay::Replay>,
 }
 
 impl CliArgs {
     /// Convert parsed CLI args into startup configuration
     pub fn into_config(self) -> Result<StartupConfig, String> {
-        let mode = if self.new || self.paths.is_empty() {
+        let replay = self
+            .replay
+            .as_deref()
+            .map(crate::replay::Replay::load)
+            .transpose()?;
+
+        let mode = if let Some(replay) = &replay {
+            match replay.file_paths().as_slice() {
+                [] => StartupMode::Empty,
+                [path] => StartupMode::SingleFile(path.clone()),
+                paths => StartupMode::MultipleFiles(paths.to_vec()),
+            }
+        } else if self.new || self.paths.is_empty() {
             StartupMode::Empty
         } else if self.paths.len() == 1 {
             let path = &self.paths[0];
@@ -104,6 +129,8 @@ impl CliArgs {
             mode,
             initial_position,
             wait_mode: self.wait,
+            record: self.record,
+            replay,
         })
     }
 }
@@ -140,6 +167,8 @@ mod tests {
             wait: false,
             line: None,
             column: None,
+            record: None,
+            replay: None,
         };
         let config = args.into_config().unwrap();
         assert!(matches!(config.mode, StartupMode::Empty));
@@ -153,6 +182,8 @@ mod tests {
             wait: false,
             line: None,
             column: None,
+            record: None,
+            replay: None,
         };
         let config = args.into_config().unwrap();
         assert!(matches!(config.mode, StartupMode::Empty));
@@ -166,6 +197,8 @@ mod tests {
             wait: false,
             line: None,
             column: None,
+            record: None,
+            replay: None,
         };
         let config = args.into_config().unwrap();
         assert!(matches!(config.mode, StartupMode::SingleFile(_)));
@@ -179,6 +212,8 @@ mod tests {
             wait: false,
             line: None,
             column: None,
+            record: None,
+            replay: None,
         };
         let config = args.into_config().unwrap();
         if let StartupMode::MultipleFiles(files) = config.mode {
@@ -196,6 +231,8 @@ mod tests {
             wait: false,
             line: Some(42),
             column: Some(10),
+            record: None,
+            replay: None,
         };
         let config = args.into_config().unwrap();
         // 1-indexed to 0-indexed: line 42 → 41, column 10 → 9
@@ -210,6 +247,8 @@ mod tests {
             wait: false,
             line: Some(10),
             column: None,
+            record: None,
+            replay: None,
         };
         let config = args.into_config().unwrap();
         // Column defaults to 1, so 0-indexed: line 10 → 9, column 1 → 0
@@ -224,6 +263,8 @@ mod tests {
             wait: true,
             line: None,
             column: None,
+            record: None,
+            replay: None,
         };
         let config = args.into_config().unwrap();
         assert!(config.wait_mode);
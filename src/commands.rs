@@ -4,9 +4,11 @@
 
 use std::path::PathBuf;
 
+use crate::external_tools::ExternalTool;
 use crate::keymap::{Command as KeymapCommand, Keymap};
-use crate::model::editor_area::DocumentId;
+use crate::model::editor_area::{DocumentId, TabColor};
 use crate::syntax::LanguageId;
+use crate::transform::TextTransform;
 
 // ============================================================================
 // Command Palette Registry
@@ -21,28 +23,52 @@ pub enum CommandId {
     FuzzyFileFinder,
     SaveFile,
     SaveFileAs,
+    SaveAllFiles,
 
     // Edit operations
     Undo,
     Redo,
     Cut,
     Copy,
+    /// Copy the selection (or the whole buffer) through a text transform
+    CopyAs(TextTransform),
     Paste,
     SelectAll,
 
     // Navigation
     GotoLine,
+    GotoSymbol,
 
     // View operations
     SplitHorizontal,
     SplitVertical,
     CloseGroup,
+    ScrollOtherGroupUp,
+    ScrollOtherGroupDown,
+    ToggleScrollLock,
     NextTab,
     PrevTab,
     CloseTab,
+    CloseOtherTabs,
+    CloseTabsToRight,
+    CloseAllTabs,
+    TogglePinTab,
+    /// Set (`None`: clear) the active tab's color label
+    SetTabColor(Option<TabColor>),
+    GroupTabsByColor,
 
     // Find/Replace
     Find,
+    FindNextMatch,
+    FindPreviousMatch,
+    SearchWordUnderCursor,
+    SearchWordUnderCursorBackward,
+    ClearSearchHighlight,
+    SearchInWorkspace,
+
+    // Language servers
+    GoToDefinition,
+    ShowHover,
 
     // UI
     ShowCommandPalette,
@@ -50,16 +76,69 @@ pub enum CommandId {
     // Theme
     SwitchTheme,
 
+    // Language
+    ChangeLanguageMode,
+
     // Settings
     OpenConfigDirectory,
+    OpenSettings,
     OpenKeybindings,
     ReloadConfiguration,
+    ShowKeymapDiagnostics,
+
+    // Logging
+    ToggleLogViewer,
+    ToggleInputLogging,
+    ToggleRenderLogging,
+    ToggleSyntaxLogging,
+    ToggleIoLogging,
+    ToggleSessionRecording,
+
+    // Crash recovery
+    RestoreCrashedSession,
+
+    // Workspace trust
+    TrustWorkspace,
+    RestrictWorkspace,
+
+    // Updates
+    CheckForUpdates,
+    ViewReleaseNotes,
 
     // CSV
     ToggleCsvView,
+    CsvSortAscending,
+    CsvSortDescending,
+    CsvToggleHeaderRow,
+    CsvInsertRowAbove,
+    CsvInsertRowBelow,
+    CsvDeleteRow,
+    CsvInsertColumnLeft,
+    CsvInsertColumnRight,
+    CsvDeleteColumn,
+    CsvToggleFilter,
+
+    // Diff view
+    DiffWithSaved,
+    DiffWithHead,
+    DiffWithOtherSplit,
+    DiffNextChange,
+    DiffPrevChange,
+    DiffClose,
+
+    // Folding
+    ToggleFold,
+    FoldAll,
+    UnfoldAll,
 
     // Markdown
     ToggleMarkdownPreview,
+    ToggleMarkdownHybrid,
+    CycleHeadingFold,
+    CycleAllHeadingFolds,
+
+    // Performance
+    TogglePerformanceMode,
 
     // Debug/Troubleshooting
     OpenLogFile,
@@ -71,16 +150,64 @@ pub enum CommandId {
     ToggleFileExplorer,
     ToggleTerminal,
     ToggleOutline,
+    ToggleBreadcrumbs,
+    ToggleRenderWhitespace,
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+    ToggleTodoList,
+    ToggleAiChat,
     CloseFocusedDock,
+    ToggleLeftDock,
+    ToggleRightDock,
+    ToggleBottomDock,
+    FocusFileExplorer,
+    FocusTerminal,
+    FocusOutline,
+    FocusTodoList,
+    FocusAiChat,
+    FocusNextArea,
+    FocusPreviousArea,
+
+    // AI chat
+    AiExplainSelection,
+    AiInsertAnswer,
+    AiReplaceSelection,
+    AiClearChat,
 
     // File path operations
     RevealInFinder,
     CopyAbsolutePath,
     CopyRelativePath,
+    CopyPermalink,
+    ToggleGitBlame,
+    EvaluateSelection,
+    EvaluateAndReplace,
+    EvaluateAndAppend,
+    UndoFileOperation,
+    ExplorerNewFile,
+    ExplorerNewFolder,
+    ExplorerRename,
+    ExplorerDelete,
+    RenamePastedImage,
+
+    // Completion
+    CompletePath,
+
+    // Markdown notes
+    FollowLink,
+    ShowBacklinks,
 
     // Recent files
     OpenRecentFiles,
 
+    // Scratchpad
+    OpenScratchpad,
+    OpenLanguageScratchpad,
+
+    // Help
+    ShowWelcome,
+
     // Application
     Quit,
 
@@ -104,7 +231,7 @@ pub static COMMANDS: &[CommandDef] = &[
     CommandDef {
         id: CommandId::NewFile,
         label: "New File",
-        keybinding: Some("⇧⌘N"),
+        keybinding: Some("⌘N"),
     },
     CommandDef {
         id: CommandId::OpenFile,
@@ -114,7 +241,7 @@ pub static COMMANDS: &[CommandDef] = &[
     CommandDef {
         id: CommandId::FuzzyFileFinder,
         label: "Go to File...",
-        keybinding: Some("⇧⌘O"),
+        keybinding: Some("⌘P"),
     },
     CommandDef {
         id: CommandId::SaveFile,
@@ -126,6 +253,11 @@ pub static COMMANDS: &[CommandDef] = &[
         label: "Save File As...",
         keybinding: Some("⇧⌘S"),
     },
+    CommandDef {
+        id: CommandId::SaveAllFiles,
+        label: "Save All Files",
+        keybinding: Some("⌥⌘S"),
+    },
     CommandDef {
         id: CommandId::Undo,
         label: "Undo",
@@ -146,6 +278,26 @@ pub static COMMANDS: &[CommandDef] = &[
         label: "Copy",
         keybinding: Some("⌘C"),
     },
+    CommandDef {
+        id: CommandId::CopyAs(TextTransform::JsonString),
+        label: "Copy as JSON String",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CopyAs(TextTransform::RustRawString),
+        label: "Copy as Rust Raw String",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CopyAs(TextTransform::HtmlEscape),
+        label: "Copy as HTML-Escaped Text",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CopyAs(TextTransform::LineNumbers),
+        label: "Copy with Line Numbers",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::Paste,
         label: "Paste",
@@ -161,6 +313,11 @@ pub static COMMANDS: &[CommandDef] = &[
         label: "Go to Line...",
         keybinding: Some("⌘L"),
     },
+    CommandDef {
+        id: CommandId::GotoSymbol,
+        label: "Go to Symbol...",
+        keybinding: Some("⇧⌘O"),
+    },
     CommandDef {
         id: CommandId::SplitHorizontal,
         label: "Split Editor Right",
@@ -171,6 +328,21 @@ pub static COMMANDS: &[CommandDef] = &[
         label: "Split Editor Down",
         keybinding: Some("⇧⌥⌘V"),
     },
+    CommandDef {
+        id: CommandId::ScrollOtherGroupUp,
+        label: "Scroll Other Group Up",
+        keybinding: Some("⌃⌥↑"),
+    },
+    CommandDef {
+        id: CommandId::ScrollOtherGroupDown,
+        label: "Scroll Other Group Down",
+        keybinding: Some("⌃⌥↓"),
+    },
+    CommandDef {
+        id: CommandId::ToggleScrollLock,
+        label: "Toggle Scroll Lock",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::CloseGroup,
         label: "Close Editor Group",
@@ -191,11 +363,111 @@ pub static COMMANDS: &[CommandDef] = &[
         label: "Close Tab",
         keybinding: Some("⌘W"),
     },
+    CommandDef {
+        id: CommandId::CloseOtherTabs,
+        label: "Close Other Tabs",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CloseTabsToRight,
+        label: "Close Tabs to the Right",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CloseAllTabs,
+        label: "Close All Tabs",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::TogglePinTab,
+        label: "Pin/Unpin Tab",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::SetTabColor(Some(TabColor::Red)),
+        label: "Tab Color: Red",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::SetTabColor(Some(TabColor::Orange)),
+        label: "Tab Color: Orange",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::SetTabColor(Some(TabColor::Yellow)),
+        label: "Tab Color: Yellow",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::SetTabColor(Some(TabColor::Green)),
+        label: "Tab Color: Green",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::SetTabColor(Some(TabColor::Blue)),
+        label: "Tab Color: Blue",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::SetTabColor(Some(TabColor::Purple)),
+        label: "Tab Color: Purple",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::SetTabColor(None),
+        label: "Tab Color: None",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::GroupTabsByColor,
+        label: "Group Tabs by Color",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::Find,
         label: "Find...",
         keybinding: Some("⌘F"),
     },
+    CommandDef {
+        id: CommandId::SearchInWorkspace,
+        label: "Search: Find in Workspace...",
+        keybinding: Some("⇧⌘F"),
+    },
+    CommandDef {
+        id: CommandId::FindNextMatch,
+        label: "Find Next",
+        keybinding: Some("F3"),
+    },
+    CommandDef {
+        id: CommandId::FindPreviousMatch,
+        label: "Find Previous",
+        keybinding: Some("⇧F3"),
+    },
+    CommandDef {
+        id: CommandId::SearchWordUnderCursor,
+        label: "Search Word Under Cursor Forward",
+        keybinding: Some("⌘F3"),
+    },
+    CommandDef {
+        id: CommandId::SearchWordUnderCursorBackward,
+        label: "Search Word Under Cursor Backward",
+        keybinding: Some("⇧⌘F3"),
+    },
+    CommandDef {
+        id: CommandId::ClearSearchHighlight,
+        label: "Clear Search Highlight",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::GoToDefinition,
+        label: "Go to Definition",
+        keybinding: Some("F12"),
+    },
+    CommandDef {
+        id: CommandId::ShowHover,
+        label: "Show Hover Info",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::ShowCommandPalette,
         label: "Show Command Palette",
@@ -206,11 +478,21 @@ pub static COMMANDS: &[CommandDef] = &[
         label: "Switch Theme...",
         keybinding: None,
     },
+    CommandDef {
+        id: CommandId::ChangeLanguageMode,
+        label: "Change Language Mode...",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::OpenConfigDirectory,
         label: "Open Config Directory",
         keybinding: None,
     },
+    CommandDef {
+        id: CommandId::OpenSettings,
+        label: "Open Settings",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::OpenKeybindings,
         label: "Open Keymap",
@@ -221,16 +503,191 @@ pub static COMMANDS: &[CommandDef] = &[
         label: "Reload Configuration",
         keybinding: None,
     },
+    CommandDef {
+        id: CommandId::ShowKeymapDiagnostics,
+        label: "Keymap: Show Diagnostics",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleLogViewer,
+        label: "Toggle Log Viewer",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleInputLogging,
+        label: "Toggle Verbose Logging: Input",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleRenderLogging,
+        label: "Toggle Verbose Logging: Render",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleSyntaxLogging,
+        label: "Toggle Verbose Logging: Syntax",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleIoLogging,
+        label: "Toggle Verbose Logging: IO",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleSessionRecording,
+        label: "Toggle Session Recording",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::RestoreCrashedSession,
+        label: "Restore Crashed Session",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::TrustWorkspace,
+        label: "Trust Workspace",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::RestrictWorkspace,
+        label: "Restrict Workspace (Restricted Mode)",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CheckForUpdates,
+        label: "Check for Updates",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ViewReleaseNotes,
+        label: "View Release Notes",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::ToggleCsvView,
         label: "Toggle CSV View",
         keybinding: None,
     },
+    CommandDef {
+        id: CommandId::CsvSortAscending,
+        label: "CSV: Sort Column Ascending",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CsvSortDescending,
+        label: "CSV: Sort Column Descending",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CsvToggleHeaderRow,
+        label: "CSV: Toggle Header Row",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CsvInsertRowAbove,
+        label: "CSV: Insert Row Above",
+        keybinding: Some("⇧⌘↩"),
+    },
+    CommandDef {
+        id: CommandId::CsvInsertRowBelow,
+        label: "CSV: Insert Row Below",
+        keybinding: Some("⌘↩"),
+    },
+    CommandDef {
+        id: CommandId::CsvDeleteRow,
+        label: "CSV: Delete Row",
+        keybinding: Some("⌘⌫"),
+    },
+    CommandDef {
+        id: CommandId::CsvInsertColumnLeft,
+        label: "CSV: Insert Column Left",
+        keybinding: Some("⌥⇧⌘↩"),
+    },
+    CommandDef {
+        id: CommandId::CsvInsertColumnRight,
+        label: "CSV: Insert Column Right",
+        keybinding: Some("⌥⌘↩"),
+    },
+    CommandDef {
+        id: CommandId::CsvDeleteColumn,
+        label: "CSV: Delete Column",
+        keybinding: Some("⌥⌘⌫"),
+    },
+    CommandDef {
+        id: CommandId::CsvToggleFilter,
+        label: "CSV: Toggle Filter Row",
+        keybinding: Some("⇧⌘L"),
+    },
+    CommandDef {
+        id: CommandId::DiffWithSaved,
+        label: "Diff: Compare with Saved",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::DiffWithHead,
+        label: "Diff: Compare with HEAD",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::DiffWithOtherSplit,
+        label: "Diff: Compare with Other Split",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::DiffNextChange,
+        label: "Diff: Next Change",
+        keybinding: Some("F7"),
+    },
+    CommandDef {
+        id: CommandId::DiffPrevChange,
+        label: "Diff: Previous Change",
+        keybinding: Some("⇧F7"),
+    },
+    CommandDef {
+        id: CommandId::DiffClose,
+        label: "Diff: Close",
+        keybinding: Some("⎋"),
+    },
+    CommandDef {
+        id: CommandId::ToggleFold,
+        label: "Toggle Fold",
+        keybinding: Some("⌥⌘["),
+    },
+    CommandDef {
+        id: CommandId::FoldAll,
+        label: "Fold All",
+        keybinding: Some("⌥⌘0"),
+    },
+    CommandDef {
+        id: CommandId::UnfoldAll,
+        label: "Unfold All",
+        keybinding: Some("⌥⌘J"),
+    },
     CommandDef {
         id: CommandId::ToggleMarkdownPreview,
         label: "Markdown: Toggle Preview",
         keybinding: Some("⇧⌘V"),
     },
+    CommandDef {
+        id: CommandId::ToggleMarkdownHybrid,
+        label: "Markdown: Toggle Hybrid View",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CycleHeadingFold,
+        label: "Markdown: Cycle Heading Fold",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CycleAllHeadingFolds,
+        label: "Markdown: Cycle All Heading Folds",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::TogglePerformanceMode,
+        label: "Toggle Performance Mode",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::OpenLogFile,
         label: "Open Log File",
@@ -239,7 +696,7 @@ pub static COMMANDS: &[CommandDef] = &[
     CommandDef {
         id: CommandId::OpenFolder,
         label: "Open Folder...",
-        keybinding: None,
+        keybinding: Some("⌥⌘O"),
     },
     CommandDef {
         id: CommandId::ToggleFileExplorer,
@@ -256,11 +713,116 @@ pub static COMMANDS: &[CommandDef] = &[
         label: "View: Toggle Outline",
         keybinding: Some("⌘7"),
     },
+    CommandDef {
+        id: CommandId::ToggleBreadcrumbs,
+        label: "View: Toggle Breadcrumbs",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleRenderWhitespace,
+        label: "View: Toggle Render Whitespace",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ZoomIn,
+        label: "View: Zoom In",
+        keybinding: Some("⌘="),
+    },
+    CommandDef {
+        id: CommandId::ZoomOut,
+        label: "View: Zoom Out",
+        keybinding: Some("⌘-"),
+    },
+    CommandDef {
+        id: CommandId::ResetZoom,
+        label: "View: Reset Zoom",
+        keybinding: Some("⌘0"),
+    },
+    CommandDef {
+        id: CommandId::ToggleTodoList,
+        label: "View: Toggle TODO List",
+        keybinding: Some("⌘6"),
+    },
+    CommandDef {
+        id: CommandId::ToggleAiChat,
+        label: "View: Toggle AI Chat",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::CloseFocusedDock,
         label: "View: Close Panel",
         keybinding: None,
     },
+    CommandDef {
+        id: CommandId::ToggleLeftDock,
+        label: "View: Toggle Left Dock",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleRightDock,
+        label: "View: Toggle Right Dock",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleBottomDock,
+        label: "View: Toggle Bottom Dock",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::FocusFileExplorer,
+        label: "View: Focus File Explorer",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::FocusTerminal,
+        label: "View: Focus Terminal",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::FocusOutline,
+        label: "View: Focus Outline",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::FocusTodoList,
+        label: "View: Focus TODO List",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::FocusAiChat,
+        label: "View: Focus AI Chat",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::FocusNextArea,
+        label: "View: Focus Next Area",
+        keybinding: Some("F6"),
+    },
+    CommandDef {
+        id: CommandId::FocusPreviousArea,
+        label: "View: Focus Previous Area",
+        keybinding: Some("⇧F6"),
+    },
+    CommandDef {
+        id: CommandId::AiExplainSelection,
+        label: "AI: Explain Selection",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::AiInsertAnswer,
+        label: "AI: Insert Answer at Cursor",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::AiReplaceSelection,
+        label: "AI: Replace Selection with Answer",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::AiClearChat,
+        label: "AI: Clear Chat",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::RevealInFinder,
         label: "Reveal Current File in Finder",
@@ -276,11 +838,96 @@ pub static COMMANDS: &[CommandDef] = &[
         label: "Copy Relative Path",
         keybinding: None,
     },
+    CommandDef {
+        id: CommandId::CopyPermalink,
+        label: "Copy GitHub/GitLab Permalink",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ToggleGitBlame,
+        label: "Git: Toggle Blame",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::EvaluateSelection,
+        label: "Evaluate Selection",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::EvaluateAndReplace,
+        label: "Evaluate Selection: Replace with Result",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::EvaluateAndAppend,
+        label: "Evaluate Selection: Append Result",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::UndoFileOperation,
+        label: "Undo Last File Operation",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ExplorerNewFile,
+        label: "File Explorer: New File",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ExplorerNewFolder,
+        label: "File Explorer: New Folder",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ExplorerRename,
+        label: "File Explorer: Rename",
+        keybinding: Some("F2"),
+    },
+    CommandDef {
+        id: CommandId::ExplorerDelete,
+        label: "File Explorer: Delete",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::RenamePastedImage,
+        label: "Rename Pasted Image",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::CompletePath,
+        label: "Complete Path",
+        keybinding: Some("⌃Space"),
+    },
+    CommandDef {
+        id: CommandId::FollowLink,
+        label: "Follow Link",
+        keybinding: Some("⌘Click"),
+    },
+    CommandDef {
+        id: CommandId::ShowBacklinks,
+        label: "Show Backlinks",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::OpenRecentFiles,
         label: "Open Recent Files",
         keybinding: Some("⌘E"),
     },
+    CommandDef {
+        id: CommandId::OpenScratchpad,
+        label: "Open Scratchpad",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::OpenLanguageScratchpad,
+        label: "Open Scratchpad for Current Language",
+        keybinding: None,
+    },
+    CommandDef {
+        id: CommandId::ShowWelcome,
+        label: "Help: Welcome",
+        keybinding: None,
+    },
     CommandDef {
         id: CommandId::Quit,
         label: "Quit",
@@ -358,6 +1005,67 @@ fn fuzzy_match_score(query: &str, target: &str) -> Option<i32> {
     }
 }
 
+/// Entry of the command palette
+#[derive(Debug, Clone, Copy)]
+pub enum PaletteEntry<'a> {
+    Command(&'static CommandDef),
+    /// External tool, with its index in the config's `external_tools`
+    Tool(usize, &'a ExternalTool),
+}
+
+impl<'a> PaletteEntry<'a> {
+    pub fn label(&self) -> &'a str {
+        match self {
+            PaletteEntry::Command(def) => def.label,
+            PaletteEntry::Tool(_, tool) => &tool.name,
+        }
+    }
+
+    /// Keybinding shown next to the label
+    pub fn keybinding(&self) -> Option<&'static str> {
+        match self {
+            PaletteEntry::Command(def) => def.keybinding,
+            PaletteEntry::Tool(..) => None,
+        }
+    }
+
+    /// What choosing the entry does
+    pub fn action(&self) -> PaletteAction {
+        match self {
+            PaletteEntry::Command(def) => PaletteAction::Command(def.id),
+            PaletteEntry::Tool(index, _) => PaletteAction::Tool(*index),
+        }
+    }
+}
+
+/// What choosing a command palette entry does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteAction {
+    Command(CommandId),
+    /// Run the external tool at this index of the config's `external_tools`
+    Tool(usize),
+}
+
+/// Commands and external tools matching `query`, best match first
+pub fn palette_entries<'a>(query: &str, tools: &'a [ExternalTool]) -> Vec<PaletteEntry<'a>> {
+    let all = all_commands().into_iter().map(PaletteEntry::Command).chain(
+        tools
+            .iter()
+            .enumerate()
+            .map(|(index, tool)| PaletteEntry::Tool(index, tool)),
+    );
+
+    if query.is_empty() {
+        return all.collect();
+    }
+
+    let mut matches: Vec<(PaletteEntry<'a>, i32)> = all
+        .filter_map(|entry| fuzzy_match_score(query, entry.label()).map(|score| (entry, score)))
+        .collect();
+    matches.sort_by_key(|a| std::cmp::Reverse(a.1));
+    matches.into_iter().map(|(entry, _)| entry).collect()
+}
+
 /// Get all available commands (including debug commands in debug builds)
 fn all_commands() -> Vec<&'static CommandDef> {
     #[allow(unused_mut)]
@@ -390,42 +1098,139 @@ pub fn filter_commands(query: &str) -> Vec<&'static CommandDef> {
 impl CommandId {
     pub fn to_keymap_command(self) -> Option<KeymapCommand> {
         match self {
-            CommandId::NewFile => Some(KeymapCommand::NewTab), // NewFile maps to NewTab
+            CommandId::NewFile => Some(KeymapCommand::NewFile),
             CommandId::OpenFile => Some(KeymapCommand::OpenFile),
             CommandId::FuzzyFileFinder => Some(KeymapCommand::FuzzyFileFinder),
             CommandId::SaveFile => Some(KeymapCommand::SaveFile),
             CommandId::SaveFileAs => Some(KeymapCommand::SaveFileAs),
+            CommandId::SaveAllFiles => Some(KeymapCommand::SaveAllFiles),
             CommandId::Undo => Some(KeymapCommand::Undo),
             CommandId::Redo => Some(KeymapCommand::Redo),
             CommandId::Cut => Some(KeymapCommand::Cut),
             CommandId::Copy => Some(KeymapCommand::Copy),
+            CommandId::CopyAs(_) => None,
             CommandId::Paste => Some(KeymapCommand::Paste),
             CommandId::SelectAll => Some(KeymapCommand::SelectAll),
             CommandId::GotoLine => Some(KeymapCommand::ToggleGotoLine),
+            CommandId::GotoSymbol => Some(KeymapCommand::ToggleGotoSymbol),
             CommandId::SplitHorizontal => Some(KeymapCommand::SplitHorizontal),
             CommandId::SplitVertical => Some(KeymapCommand::SplitVertical),
+            CommandId::ScrollOtherGroupUp => Some(KeymapCommand::ScrollOtherGroupUp),
+            CommandId::ScrollOtherGroupDown => Some(KeymapCommand::ScrollOtherGroupDown),
+            CommandId::ToggleScrollLock => None,
             CommandId::CloseGroup => None, // No direct mapping yet
             CommandId::NextTab => Some(KeymapCommand::NextTab),
             CommandId::PrevTab => Some(KeymapCommand::PrevTab),
             CommandId::CloseTab => Some(KeymapCommand::CloseTab),
+            CommandId::CloseOtherTabs => None,
+            CommandId::CloseTabsToRight => None,
+            CommandId::CloseAllTabs => None,
+            CommandId::TogglePinTab => None,
+            CommandId::SetTabColor(_) => None,
+            CommandId::GroupTabsByColor => None,
             CommandId::Find => Some(KeymapCommand::ToggleFindReplace),
+            CommandId::SearchInWorkspace => Some(KeymapCommand::SearchInWorkspace),
+            CommandId::FindNextMatch => Some(KeymapCommand::FindNextMatch),
+            CommandId::FindPreviousMatch => Some(KeymapCommand::FindPreviousMatch),
+            CommandId::SearchWordUnderCursor => Some(KeymapCommand::SearchWordUnderCursor),
+            CommandId::SearchWordUnderCursorBackward => {
+                Some(KeymapCommand::SearchWordUnderCursorBackward)
+            }
+            CommandId::ClearSearchHighlight => Some(KeymapCommand::ClearSearchHighlight),
+            CommandId::GoToDefinition => Some(KeymapCommand::GoToDefinition),
+            CommandId::ShowHover => None,
             CommandId::ShowCommandPalette => Some(KeymapCommand::ToggleCommandPalette),
             CommandId::SwitchTheme => None,
+            CommandId::ChangeLanguageMode => None,
             CommandId::OpenConfigDirectory => None,
+            CommandId::OpenSettings => None,
             CommandId::OpenKeybindings => None,
             CommandId::ReloadConfiguration => None,
+            CommandId::ShowKeymapDiagnostics => None,
+            CommandId::ToggleLogViewer => None,
+            CommandId::ToggleInputLogging => None,
+            CommandId::ToggleRenderLogging => None,
+            CommandId::ToggleSyntaxLogging => None,
+            CommandId::ToggleIoLogging => None,
+            CommandId::ToggleSessionRecording => None,
+            CommandId::RestoreCrashedSession => None,
+            CommandId::TrustWorkspace => None,
+            CommandId::RestrictWorkspace => None,
+            CommandId::CheckForUpdates => None,
+            CommandId::ViewReleaseNotes => None,
             CommandId::ToggleCsvView => Some(KeymapCommand::CsvToggle),
+            CommandId::CsvSortAscending => None,
+            CommandId::CsvSortDescending => None,
+            CommandId::CsvToggleHeaderRow => None,
+            CommandId::CsvInsertRowAbove => Some(KeymapCommand::CsvInsertRowAbove),
+            CommandId::CsvInsertRowBelow => Some(KeymapCommand::CsvInsertRowBelow),
+            CommandId::CsvDeleteRow => Some(KeymapCommand::CsvDeleteRow),
+            CommandId::CsvInsertColumnLeft => Some(KeymapCommand::CsvInsertColumnLeft),
+            CommandId::CsvInsertColumnRight => Some(KeymapCommand::CsvInsertColumnRight),
+            CommandId::CsvDeleteColumn => Some(KeymapCommand::CsvDeleteColumn),
+            CommandId::CsvToggleFilter => Some(KeymapCommand::CsvToggleFilter),
+            CommandId::DiffWithSaved => None,
+            CommandId::DiffWithHead => None,
+            CommandId::DiffWithOtherSplit => None,
+            CommandId::DiffNextChange => Some(KeymapCommand::DiffNextChange),
+            CommandId::DiffPrevChange => Some(KeymapCommand::DiffPrevChange),
+            CommandId::DiffClose => Some(KeymapCommand::DiffClose),
+            CommandId::ToggleFold => Some(KeymapCommand::ToggleFold),
+            CommandId::FoldAll => Some(KeymapCommand::FoldAll),
+            CommandId::UnfoldAll => Some(KeymapCommand::UnfoldAll),
             CommandId::ToggleMarkdownPreview => Some(KeymapCommand::MarkdownTogglePreview),
+            CommandId::ToggleMarkdownHybrid => Some(KeymapCommand::MarkdownToggleHybrid),
+            CommandId::CycleHeadingFold => Some(KeymapCommand::MarkdownCycleHeadingFold),
+            CommandId::CycleAllHeadingFolds => Some(KeymapCommand::MarkdownCycleAllHeadingFolds),
+            CommandId::TogglePerformanceMode => None,
             CommandId::OpenLogFile => Some(KeymapCommand::OpenLogFile),
-            CommandId::OpenFolder => None,
+            CommandId::OpenFolder => Some(KeymapCommand::OpenFolder),
             CommandId::ToggleFileExplorer => Some(KeymapCommand::ToggleFileExplorer),
             CommandId::ToggleTerminal => Some(KeymapCommand::ToggleTerminal),
             CommandId::ToggleOutline => Some(KeymapCommand::ToggleOutline),
+            CommandId::ToggleBreadcrumbs => None,
+            CommandId::ToggleRenderWhitespace => None,
+            CommandId::ZoomIn => Some(KeymapCommand::ZoomIn),
+            CommandId::ZoomOut => Some(KeymapCommand::ZoomOut),
+            CommandId::ResetZoom => Some(KeymapCommand::ResetZoom),
+            CommandId::ToggleTodoList => Some(KeymapCommand::ToggleTodoList),
+            CommandId::ToggleAiChat => Some(KeymapCommand::ToggleAiChat),
+            CommandId::AiExplainSelection => Some(KeymapCommand::AiExplainSelection),
+            CommandId::AiInsertAnswer => Some(KeymapCommand::AiInsertAnswer),
+            CommandId::AiReplaceSelection => Some(KeymapCommand::AiReplaceSelection),
+            CommandId::AiClearChat => None,
             CommandId::CloseFocusedDock => Some(KeymapCommand::CloseFocusedDock),
+            CommandId::ToggleLeftDock => Some(KeymapCommand::ToggleLeftDock),
+            CommandId::ToggleRightDock => Some(KeymapCommand::ToggleRightDock),
+            CommandId::ToggleBottomDock => Some(KeymapCommand::ToggleBottomDock),
+            CommandId::FocusFileExplorer => Some(KeymapCommand::FocusFileExplorer),
+            CommandId::FocusTerminal => Some(KeymapCommand::FocusTerminal),
+            CommandId::FocusOutline => Some(KeymapCommand::FocusOutline),
+            CommandId::FocusTodoList => Some(KeymapCommand::FocusTodoList),
+            CommandId::FocusAiChat => Some(KeymapCommand::FocusAiChat),
+            CommandId::FocusNextArea => Some(KeymapCommand::FocusNextArea),
+            CommandId::FocusPreviousArea => Some(KeymapCommand::FocusPreviousArea),
             CommandId::RevealInFinder => None,
             CommandId::CopyAbsolutePath => None,
             CommandId::CopyRelativePath => None,
+            CommandId::CopyPermalink => None,
+            CommandId::ToggleGitBlame => None,
+            CommandId::EvaluateSelection => None,
+            CommandId::EvaluateAndReplace => None,
+            CommandId::EvaluateAndAppend => None,
+            CommandId::UndoFileOperation => None,
+            CommandId::ExplorerNewFile => None,
+            CommandId::ExplorerNewFolder => None,
+            CommandId::ExplorerRename => None,
+            CommandId::ExplorerDelete => None,
+            CommandId::RenamePastedImage => None,
+            CommandId::CompletePath => Some(KeymapCommand::CompletePath),
+            CommandId::FollowLink => None,
+            CommandId::ShowBacklinks => None,
             CommandId::OpenRecentFiles => Some(KeymapCommand::OpenRecentFiles),
+            CommandId::OpenScratchpad => None,
+            CommandId::OpenLanguageScratchpad => None,
+            CommandId::ShowWelcome => None,
             CommandId::Quit => Some(KeymapCommand::Quit),
             #[cfg(debug_assertions)]
             CommandId::TogglePerfOverlay => None,
@@ -618,14 +1423,66 @@ pub enum Cmd {
     RedrawAreas(Vec<DamageArea>),
     /// Save file asynchronously
     SaveFile { path: PathBuf, content: String },
+    /// Save a document that isn't necessarily focused, without being asked
+    /// Sends `AppMsg::AutosaveCompleted` when done
+    AutosaveFile {
+        document_id: DocumentId,
+        path: PathBuf,
+        content: String,
+        /// Undo stack length of the saved content
+        saved_revision: usize,
+    },
     /// Load file asynchronously
     LoadFile { path: PathBuf },
+    /// Stream a large file into the placeholder document opened for it
+    /// Sends `AppMsg::LargeFileProgress` as it reads and
+    /// `AppMsg::LargeFileLoaded` when done
+    LoadLargeFile {
+        document_id: DocumentId,
+        path: PathBuf,
+    },
+    /// Read the file of an open document that changed on disk
+    /// Sends `AppMsg::FileChangedOnDisk` when done
+    ReadChangedFile {
+        document_id: DocumentId,
+        path: PathBuf,
+    },
+    /// Run an external tool's expanded command in the background
+    /// Sends `AppMsg::ExternalToolFinished` when done
+    RunExternalTool {
+        name: String,
+        command: String,
+        cwd: Option<PathBuf>,
+    },
     /// Open a path in the system file explorer/finder
     OpenInExplorer { path: PathBuf },
+    /// Open a URL in the default browser
+    OpenUrl { url: String },
+    /// Look up the latest release in the background
+    /// Sends `AppMsg::UpdateCheckCompleted` when done
+    CheckForUpdates {
+        /// Requested by the user, so "up to date" and errors are reported
+        manual: bool,
+    },
     /// Reveal a file in the system file manager (select it)
     RevealFileInFinder { path: PathBuf },
     /// Open a file in a new tab for editing
     OpenFileInEditor { path: PathBuf },
+    /// Move a path to the trash asynchronously
+    /// Sends `WorkspaceMsg::FileOperationCompleted` when done
+    TrashPath { path: PathBuf },
+    /// Rename a path asynchronously, trashing an existing target first
+    /// Sends `WorkspaceMsg::FileOperationCompleted` when done
+    RenamePath { from: PathBuf, to: PathBuf },
+    /// Undo a file operation asynchronously
+    /// Sends `WorkspaceMsg::FileOperationReverted` when done
+    RevertFileOperation(crate::file_ops::FileOperation),
+    /// Create an empty file asynchronously
+    /// Sends `WorkspaceMsg::FileCreated` when done
+    CreateFile { path: PathBuf },
+    /// Create an empty folder asynchronously
+    /// Sends `WorkspaceMsg::FolderCreated` when done
+    CreateFolder { path: PathBuf },
     /// Execute multiple commands
     Batch(Vec<Cmd>),
 
@@ -639,6 +1496,8 @@ pub enum Cmd {
     },
     /// Show native save file dialog
     ShowSaveFileDialog {
+        /// Document being saved, handed back with the result
+        document_id: Option<crate::model::editor_area::DocumentId>,
         /// Suggested file path (for pre-filling name/directory)
         suggested_path: Option<PathBuf>,
     },
@@ -663,6 +1522,8 @@ pub enum Cmd {
         revision: u64,
         source: String,
         language: LanguageId,
+        /// Edits since the previous request, if they are all known
+        edits: Option<crate::syntax::SyntaxEdits>,
     },
     /// Drop debounced parse state and worker-side cached parse trees for a document.
     ClearSyntaxState { document_id: DocumentId },
@@ -679,12 +1540,26 @@ pub enum Cmd {
     SaveRecentFiles {
         recent: crate::recent_files::RecentFiles,
     },
+    /// Save workspace trust decisions asynchronously
+    SaveWorkspaceTrust {
+        trust: crate::workspace_trust::WorkspaceTrust,
+    },
     /// Copy a string to the system clipboard
     CopyToClipboard(String),
     /// Request pasting text from the system clipboard
     RequestClipboardPaste,
+    /// Save an image pasted into a Markdown document as PNG at `path` (or
+    /// the next free name), replying with `AppMsg::PastedImageSaved`
+    SavePastedImage {
+        document_id: crate::model::editor_area::DocumentId,
+        path: PathBuf,
+        image: crate::paste_image::ClipboardImage,
+    },
     /// Create default keymap file asynchronously
     CreateDefaultKeymapFile { path: PathBuf },
+    /// Load the keymap files again and swap in the new bindings. Sends
+    /// `Msg::App(KeymapReloaded)` when done.
+    ReloadKeymap,
 
     // === Terminal Commands ===
     /// Spawn a PTY + shell for a new terminal session. The runtime spawns
@@ -697,6 +1572,55 @@ pub enum Cmd {
         cols: u16,
     },
 
+    // === TODO Panel Commands ===
+    /// Scan open documents and workspace files for TODO/FIXME/HACK comments
+    /// on a background thread. Sends `Msg::Todo(ScanCompleted)` when done.
+    ScanTodos {
+        generation: u64,
+        request: crate::todo::TodoScanRequest,
+    },
+
+    // === Workspace Search Commands ===
+    /// Search the files of the workspace on a background thread. Sends
+    /// `Msg::Search(ResultsFound)` as files with matches are found and
+    /// `Finished` at the end; setting `cancel` stops the search.
+    SearchProject {
+        generation: u64,
+        request: crate::project_search::ProjectSearchRequest,
+        cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    },
+
+    // === Notes Commands ===
+    /// Scan workspace Markdown files for links to a note on a background
+    /// thread. Sends `Msg::Notes(BacklinksFound)` when done.
+    FindBacklinks {
+        request: crate::notes::BacklinkScanRequest,
+    },
+
+    // === Git Commands ===
+    /// Diff a document against its HEAD version on a background thread,
+    /// reading that version first if the request has none. Sends
+    /// `Msg::Git(DiffComputed)` when done.
+    ComputeGitDiff(crate::git::GitDiffRequest),
+    /// Blame a document's text on a background thread. Sends
+    /// `Msg::Git(BlameComputed)` when done.
+    ComputeGitBlame(crate::git::GitBlameRequest),
+
+    // === AI Chat Commands ===
+    /// Stream a chat completion on a background thread. Sends
+    /// `Msg::AiChat(StreamDelta)` per chunk and `StreamFinished` at the end.
+    AiChatRequest {
+        request_id: u64,
+        config: crate::ai::AiConfig,
+        messages: Vec<crate::ai::ChatMessage>,
+        cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    },
+
+    // === Language Server Commands ===
+    /// Send a notification or request to a language server, starting it on
+    /// first use. Answers come back as `Msg::Lsp`.
+    Lsp(crate::lsp::LspRequest),
+
     // === Debug Commands ===
     /// Toggle performance overlay (debug builds only)
     #[cfg(debug_assertions)]
@@ -739,10 +1663,26 @@ impl Cmd {
             }
             // File operations may cause full redraw (file load changes content)
             Cmd::SaveFile { .. } => Damage::Full,
+            // The completion message triggers its own redraw
+            Cmd::AutosaveFile { .. } => Damage::Areas(vec![]),
             Cmd::LoadFile { .. } => Damage::Full,
+            // Progress and completion messages trigger their own redraws
+            Cmd::LoadLargeFile { .. } => Damage::Areas(vec![]),
+            // The file contents arrive in a message that triggers its own redraw
+            Cmd::ReadChangedFile { .. } => Damage::Areas(vec![]),
+            // The completion message triggers its own redraw
+            Cmd::RunExternalTool { .. } => Damage::Areas(vec![]),
             Cmd::OpenInExplorer { .. } => Damage::Full,
+            Cmd::OpenUrl { .. } => Damage::Areas(vec![]),
+            Cmd::CheckForUpdates { .. } => Damage::Areas(vec![]),
             Cmd::RevealFileInFinder { .. } => Damage::Areas(vec![]),
             Cmd::OpenFileInEditor { .. } => Damage::Full,
+            // The completion message triggers its own redraw
+            Cmd::TrashPath { .. } => Damage::Areas(vec![]),
+            Cmd::RenamePath { .. } => Damage::Areas(vec![]),
+            Cmd::RevertFileOperation(_) => Damage::Areas(vec![]),
+            Cmd::CreateFile { .. } => Damage::Areas(vec![]),
+            Cmd::CreateFolder { .. } => Damage::Areas(vec![]),
             // Batch: merge all damages
             Cmd::Batch(cmds) => {
                 let mut damage = Damage::Areas(vec![]);
@@ -768,12 +1708,23 @@ impl Cmd {
             // Quit doesn't need redraw - app is exiting
             Cmd::Quit => Damage::Areas(vec![]),
             Cmd::SaveRecentFiles { .. } => Damage::Areas(vec![]),
+            Cmd::SaveWorkspaceTrust { .. } => Damage::Areas(vec![]),
             Cmd::CopyToClipboard(_) => Damage::Areas(vec![]),
             Cmd::RequestClipboardPaste => Damage::Areas(vec![]),
+            Cmd::SavePastedImage { .. } => Damage::Areas(vec![]),
             Cmd::CreateDefaultKeymapFile { .. } => Damage::Areas(vec![]),
+            Cmd::ReloadKeymap => Damage::Areas(vec![]),
             // Spawning doesn't need immediate redraw; the PtyOutput that
             // follows shortly after will request one.
             Cmd::SpawnTerminal { .. } => Damage::Areas(vec![]),
+            // The scan result message triggers its own redraw
+            Cmd::ScanTodos { .. } => Damage::Areas(vec![]),
+            Cmd::SearchProject { .. } => Damage::Areas(vec![]),
+            Cmd::FindBacklinks { .. } => Damage::Areas(vec![]),
+            Cmd::ComputeGitDiff(_) => Damage::Areas(vec![]),
+            Cmd::ComputeGitBlame(_) => Damage::Areas(vec![]),
+            Cmd::AiChatRequest { .. } => Damage::Areas(vec![]),
+            Cmd::Lsp(_) => Damage::Areas(vec![]),
             // Debug overlay toggle triggers full redraw
             #[cfg(debug_assertions)]
             Cmd::TogglePerfOverlay => Damage::Full,
@@ -1037,4 +1988,17 @@ mod tests {
         let cmd = Cmd::redraw_cursor_lines(vec![]);
         assert!(matches!(cmd, Cmd::None));
     }
+
+    #[test]
+    fn test_every_text_transform_has_a_copy_as_command() {
+        for transform in TextTransform::ALL {
+            assert!(
+                COMMANDS
+                    .iter()
+                    .any(|cmd| cmd.id == CommandId::CopyAs(transform)),
+                "no Copy as command for {:?}",
+                transform
+            );
+        }
+    }
 }
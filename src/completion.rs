@@ -0,0 +1,463 @@
+//! Completion of file paths in strings and Markdown links
+//!
+//! Inside a Markdown link (`[text](docs/`), a wiki-link (`[[ide`) or a
+//! string literal in code (`"./assets/`), the text typed so far is a path
+//! query. Candidates come from the workspace file list: the entries of the
+//! folder the query points into (relative to the document, or to the
+//! workspace root for a leading `/`), then files anywhere in the workspace
+//! whose name matches, inserted as a path relative to the document.
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::notes::{is_markdown_path, percent_decode};
+
+/// Most items offered at once
+const MAX_ITEMS: usize = 100;
+
+/// Where the path being completed is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSyntax {
+    /// Target of a Markdown link, after `](`
+    MarkdownLink,
+    /// Page name of a wiki-link, after `[[`
+    WikiLink,
+    /// Contents of a quoted string
+    String,
+}
+
+/// The partial path before the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathQuery {
+    pub syntax: PathSyntax,
+    /// Char column where the path starts (the text replaced on accept)
+    pub start: usize,
+    /// Text from `start` to the cursor
+    pub text: String,
+}
+
+impl PathQuery {
+    /// Whether typing `/` here should open completions without asking: the
+    /// query is a plain path (a string with spaces is more likely prose)
+    pub fn is_path_like(&self) -> bool {
+        self.syntax != PathSyntax::WikiLink && !self.text.contains(char::is_whitespace)
+    }
+}
+
+/// One entry of the completion popup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionItem {
+    /// Shown in the list
+    pub label: String,
+    /// Shown dimmed after the label
+    pub detail: String,
+    /// Replaces the query text when accepted
+    pub insert_text: String,
+    /// Accepting it opens completions again (a folder)
+    pub retrigger: bool,
+}
+
+/// The path query at char `column` of `line`: inside a link target or
+/// wiki-link in Markdown, inside a string literal otherwise
+pub fn path_query_at(line: &str, column: usize, markdown: bool) -> Option<PathQuery> {
+    let before: Vec<char> = line.chars().take(column).collect();
+    if before.len() < column {
+        return None;
+    }
+    if markdown {
+        wiki_link_query(&before).or_else(|| markdown_link_query(&before))
+    } else {
+        string_query(&before)
+    }
+}
+
+/// `[[name` with the cursor before any `]`, `|` or `#`
+fn wiki_link_query(before: &[char]) -> Option<PathQuery> {
+    let mut i = before.len();
+    while i >= 2 {
+        match before[i - 1] {
+            ']' | '|' | '#' => return None,
+            '[' if before[i - 2] == '[' => {
+                return Some(PathQuery {
+                    syntax: PathSyntax::WikiLink,
+                    start: i,
+                    text: before[i..].iter().collect(),
+                })
+            }
+            _ => i -= 1,
+        }
+    }
+    None
+}
+
+/// `](target` with the cursor before the closing `)` or a title
+fn markdown_link_query(before: &[char]) -> Option<PathQuery> {
+    let mut i = before.len();
+    while i >= 2 {
+        match before[i - 1] {
+            '(' if before[i - 2] == ']' => {
+                return Some(PathQuery {
+                    syntax: PathSyntax::MarkdownLink,
+                    start: i,
+                    text: before[i..].iter().collect(),
+                })
+            }
+            '(' | ')' => return None,
+            ch if ch.is_whitespace() => return None,
+            _ => i -= 1,
+        }
+    }
+    None
+}
+
+/// The unclosed quoted string the cursor is in
+fn string_query(before: &[char]) -> Option<PathQuery> {
+    let mut open: Option<(char, usize)> = None;
+    let mut chars = before.iter().enumerate();
+    while let Some((i, &ch)) = chars.next() {
+        match (ch, open) {
+            ('\\', Some(_)) => {
+                chars.next();
+            }
+            ('"' | '\'' | '`', None) => open = Some((ch, i)),
+            (_, Some((quote, _))) if ch == quote => open = None,
+            _ => {}
+        }
+    }
+    let (_, quote_index) = open?;
+    let start = quote_index + 1;
+    let text: String = before[start..].iter().collect();
+    // URLs and the like aren't workspace paths
+    if text.contains("://") {
+        return None;
+    }
+    Some(PathQuery {
+        syntax: PathSyntax::String,
+        start,
+        text,
+    })
+}
+
+/// Completions for `query` in a document in `document_dir`
+pub fn path_completions(
+    query: &PathQuery,
+    document_dir: &Path,
+    workspace_root: Option<&Path>,
+    workspace_files: &[PathBuf],
+) -> Vec<CompletionItem> {
+    let mut items = match query.syntax {
+        PathSyntax::WikiLink => wiki_completions(&query.text, workspace_root, workspace_files),
+        PathSyntax::MarkdownLink | PathSyntax::String => {
+            file_completions(query, document_dir, workspace_root, workspace_files)
+        }
+    };
+    items.truncate(MAX_ITEMS);
+    items
+}
+
+/// Markdown notes whose name contains `text`, inserted by name
+fn wiki_completions(
+    text: &str,
+    workspace_root: Option<&Path>,
+    workspace_files: &[PathBuf],
+) -> Vec<CompletionItem> {
+    let wanted = text.to_lowercase();
+    let mut ranked: Vec<(usize, CompletionItem)> = workspace_files
+        .iter()
+        .filter(|path| is_markdown_path(path))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            let rank = match_rank(&name, &wanted)?;
+            let folder = path
+                .parent()
+                .map(|dir| display_dir(dir, workspace_root))
+                .unwrap_or_default();
+            Some((
+                rank,
+                CompletionItem {
+                    label: name.clone(),
+                    detail: folder,
+                    insert_text: name,
+                    retrigger: false,
+                },
+            ))
+        })
+        .collect();
+    ranked.sort_by(|(a_rank, a), (b_rank, b)| {
+        (a_rank, a.label.to_lowercase(), &a.detail).cmp(&(
+            b_rank,
+            b.label.to_lowercase(),
+            &b.detail,
+        ))
+    });
+    ranked.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Entries of the folder the query points into, then (for a bare name)
+/// matching files elsewhere in the workspace
+fn file_completions(
+    query: &PathQuery,
+    document_dir: &Path,
+    workspace_root: Option<&Path>,
+    workspace_files: &[PathBuf],
+) -> Vec<CompletionItem> {
+    let markdown = query.syntax == PathSyntax::MarkdownLink;
+    let (dir_part, name_part) = match query.text.rfind('/') {
+        Some(slash) => query.text.split_at(slash + 1),
+        None => ("", query.text.as_str()),
+    };
+    let decoded_dir = if markdown {
+        percent_decode(dir_part)
+    } else {
+        dir_part.to_string()
+    };
+    let base = match (decoded_dir.strip_prefix('/'), workspace_root) {
+        (Some(rest), Some(root)) => root.join(rest),
+        _ => document_dir.join(&decoded_dir),
+    };
+    let base = normalize_lexically(&base);
+    let wanted = if markdown {
+        percent_decode(name_part)
+    } else {
+        name_part.to_string()
+    }
+    .to_lowercase();
+    let show_hidden = wanted.starts_with('.');
+
+    // Folder entries: name -> is a folder
+    let mut entries: BTreeMap<String, bool> = BTreeMap::new();
+    for path in workspace_files {
+        let Ok(rest) = path.strip_prefix(&base) else {
+            continue;
+        };
+        let mut components = rest.components();
+        let Some(Component::Normal(first)) = components.next() else {
+            continue;
+        };
+        let is_dir = components.next().is_some();
+        *entries
+            .entry(first.to_string_lossy().to_string())
+            .or_default() |= is_dir;
+    }
+
+    let mut ranked: Vec<(usize, bool, CompletionItem)> = entries
+        .into_iter()
+        .filter(|(name, _)| show_hidden || !name.starts_with('.'))
+        .filter_map(|(name, is_dir)| {
+            let rank = match_rank(&name, &wanted)?;
+            let mut insert_text = format!("{}{}", dir_part, escape(&name, markdown));
+            let mut label = name;
+            if is_dir {
+                insert_text.push('/');
+                label.push('/');
+            }
+            Some((
+                rank,
+                !is_dir,
+                CompletionItem {
+                    label,
+                    detail: if is_dir {
+                        "folder".to_string()
+                    } else {
+                        String::new()
+                    },
+                    insert_text,
+                    retrigger: is_dir,
+                },
+            ))
+        })
+        .collect();
+
+    // A bare name also finds files elsewhere in the workspace
+    if dir_part.is_empty() && !wanted.is_empty() && !show_hidden {
+        for path in workspace_files {
+            if path.parent() == Some(base.as_path()) {
+                continue;
+            }
+            let Some(name) = path.file_name().map(|name| name.to_string_lossy()) else {
+                continue;
+            };
+            let Some(rank) = match_rank(&name, &wanted) else {
+                continue;
+            };
+            let relative = relative_path(document_dir, path);
+            if relative
+                .split('/')
+                .any(|part| part.starts_with('.') && part != "..")
+            {
+                continue;
+            }
+            ranked.push((
+                rank + 2,
+                true,
+                CompletionItem {
+                    label: name.to_string(),
+                    detail: relative.clone(),
+                    insert_text: escape(&relative, markdown),
+                    retrigger: false,
+                },
+            ));
+        }
+    }
+
+    ranked.sort_by(|(a_rank, a_file, a), (b_rank, b_file, b)| {
+        (a_rank, a_file, a.label.to_lowercase(), &a.detail).cmp(&(
+            b_rank,
+            b_file,
+            b.label.to_lowercase(),
+            &b.detail,
+        ))
+    });
+    ranked.into_iter().map(|(_, _, item)| item).collect()
+}
+
+/// 0 for a prefix match, 1 for a substring match (`wanted` is lowercase)
+fn match_rank(name: &str, wanted: &str) -> Option<usize> {
+    let name = name.to_lowercase();
+    if name.starts_with(wanted) {
+        Some(0)
+    } else if name.contains(wanted) {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+/// Spaces in Markdown link targets are written `%20`
+fn escape(path: &str, markdown: bool) -> String {
+    if markdown {
+        path.replace(' ', "%20")
+    } else {
+        path.to_string()
+    }
+}
+
+/// Folder shown next to a wiki-link candidate: workspace-relative
+fn display_dir(dir: &Path, workspace_root: Option<&Path>) -> String {
+    let relative = workspace_root
+        .and_then(|root| dir.strip_prefix(root).ok())
+        .unwrap_or(dir);
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// `path` relative to the folder `from`, with `/` separators and `..` to
+/// climb out of it
+pub fn relative_path(from: &Path, path: &Path) -> String {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = path.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+/// Remove `.` and `..` segments without touching the file system
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> Vec<PathBuf> {
+        [
+            "/ws/README.md",
+            "/ws/notes/idea.md",
+            "/ws/notes/ideas for later.md",
+            "/ws/notes/img/diagram.png",
+            "/ws/src/main.rs",
+            "/ws/.git/config",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect()
+    }
+
+    fn complete(line: &str, markdown: bool, document_dir: &str) -> Vec<(String, String)> {
+        let query = path_query_at(line, line.chars().count(), markdown).unwrap();
+        path_completions(
+            &query,
+            Path::new(document_dir),
+            Some(Path::new("/ws")),
+            &files(),
+        )
+        .into_iter()
+        .map(|item| (item.label, item.insert_text))
+        .collect()
+    }
+
+    #[test]
+    fn queries_come_from_links_and_strings() {
+        let query = path_query_at("see [x](../notes/id", 19, true).unwrap();
+        assert_eq!(query.syntax, PathSyntax::MarkdownLink);
+        assert_eq!((query.start, query.text.as_str()), (8, "../notes/id"));
+
+        let query = path_query_at("[[ide", 5, true).unwrap();
+        assert_eq!(query.syntax, PathSyntax::WikiLink);
+        assert_eq!(query.text, "ide");
+
+        assert!(path_query_at("[x](done) and (", 15, true).is_none());
+        assert!(path_query_at("plain / text", 7, true).is_none());
+
+        let query = path_query_at(r#"load("a\"b", "./src/"#, 20, false).unwrap();
+        assert_eq!((query.start, query.text.as_str()), (14, "./src/"));
+        assert!(path_query_at(r#"load("done") /"#, 14, false).is_none());
+    }
+
+    #[test]
+    fn folder_entries_resolve_from_the_document() {
+        assert_eq!(
+            complete("[x](../notes/", true, "/ws/src"),
+            vec![
+                ("img/".to_string(), "../notes/img/".to_string()),
+                ("idea.md".to_string(), "../notes/idea.md".to_string()),
+                (
+                    "ideas for later.md".to_string(),
+                    "../notes/ideas%20for%20later.md".to_string()
+                ),
+            ]
+        );
+        // A leading slash is workspace-relative; hidden files stay hidden
+        assert_eq!(
+            complete("\"/", false, "/ws/notes"),
+            vec![
+                ("notes/".to_string(), "/notes/".to_string()),
+                ("src/".to_string(), "/src/".to_string()),
+                ("README.md".to_string(), "/README.md".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_names_match_files_anywhere_and_wiki_links_by_name() {
+        assert_eq!(
+            complete("[x](diag", true, "/ws/src"),
+            vec![(
+                "diagram.png".to_string(),
+                "../notes/img/diagram.png".to_string()
+            )]
+        );
+        assert_eq!(
+            complete("[[ide", true, "/ws"),
+            vec![
+                ("idea".to_string(), "idea".to_string()),
+                ("ideas for later".to_string(), "ideas for later".to_string()),
+            ]
+        );
+    }
+}
@@ -34,15 +34,268 @@ pub struct EditorConfig {
     #[serde(default = "default_true")]
     pub auto_surround: bool,
 
+    /// Complete the innermost open tag when typing `</` in markup files (default: true)
+    #[serde(default = "default_true")]
+    pub auto_close_tags: bool,
+
+    /// Mirror edits of an HTML/XML/JSX tag name onto its paired tag (default: true)
+    #[serde(default = "default_true")]
+    pub linked_tag_editing: bool,
+
     /// Highlight matching bracket when cursor is adjacent to one (default: true)
     #[serde(default = "default_true")]
     pub bracket_matching: bool,
 
+    /// Re-indent the block closed by a typed `}` in brace languages (default: false)
+    #[serde(default)]
+    pub reindent_on_close_brace: bool,
+
+    /// Indent with tabs or spaces: Tab, Indent and Unindent use it
+    /// (default: tabs)
+    #[serde(default)]
+    pub indent_style: IndentStyle,
+
+    /// Columns per indentation level: spaces inserted per level with
+    /// `indent_style: spaces`, and at most removed by Unindent (default: 4)
+    #[serde(default = "default_indent_width")]
+    pub indent_width: usize,
+
+    /// Vim-style modal editing: normal, insert and visual modes (default: false)
+    #[serde(default)]
+    pub vim_mode: bool,
+
     /// Show scrollbars in editor panes (default: true)
     ///
     /// When false, no scrollbars are rendered and no space is reserved for them.
     #[serde(default = "default_true")]
     pub show_scrollbar: bool,
+
+    /// Where images pasted into Markdown are saved, relative to the document's
+    /// folder. `{doc}` is the document's file name without extension and
+    /// `{timestamp}` the time of the paste (default: "assets/{doc}-{timestamp}.png")
+    #[serde(default = "default_paste_image_path")]
+    pub paste_image_path: String,
+
+    /// Style Markdown source in place: emphasis, headings, inline code and
+    /// link URLs (default: false)
+    #[serde(default)]
+    pub markdown_hybrid: bool,
+
+    /// Check for a newer release on startup (default: false)
+    #[serde(default)]
+    pub check_for_updates: bool,
+
+    /// Reopen the tabs and splits of the last session of a workspace when
+    /// launched without files (default: true)
+    #[serde(default = "default_true")]
+    pub restore_session: bool,
+
+    /// Log targets with debug logging on at startup (default: none)
+    #[serde(default)]
+    pub verbose_logging: Vec<crate::tracing::LogTarget>,
+
+    /// Renderer: auto, software or gpu; applies on restart (default: auto)
+    #[serde(default)]
+    pub renderer: crate::view::RendererBackend,
+
+    /// Text antialiasing: auto, grayscale or subpixel (default: auto)
+    #[serde(default)]
+    pub text_antialiasing: crate::view::TextAntialiasing,
+
+    /// Editor font size in points, changed by zooming with Cmd+= / Cmd+- and
+    /// reset with Cmd+0 (default: 14)
+    #[serde(default = "default_font_size")]
+    pub font_size: f32,
+
+    /// Editor font, by family name among the installed fonts; the embedded
+    /// font is used when it isn't installed (default: "JetBrains Mono")
+    #[serde(default = "default_font_family")]
+    pub font_family: String,
+
+    /// Fonts tried in order for characters the editor font has no glyph
+    /// for, such as CJK and symbols; missing ones are skipped (default: a
+    /// list of common fonts for the platform)
+    #[serde(default = "default_font_fallbacks")]
+    pub font_fallbacks: Vec<String>,
+
+    /// Align tab-separated columns across adjacent lines instead of using
+    /// fixed tab stops; display only (default: false)
+    #[serde(default)]
+    pub elastic_tabstops: bool,
+
+    /// Show the symbol path at the cursor in a strip under the tab bar
+    /// (default: false)
+    #[serde(default)]
+    pub breadcrumbs: bool,
+
+    /// Draw spaces, tabs and line ends: none, selection (only inside
+    /// selections) or all (default: none)
+    #[serde(default)]
+    pub render_whitespace: RenderWhitespace,
+
+    /// Draw a vertical line at each indentation level, highlighting the
+    /// one of the block the cursor is in (default: true)
+    #[serde(default = "default_true")]
+    pub show_indent_guides: bool,
+
+    /// Columns to draw vertical rulers at, e.g. `[80, 120]` (default: none)
+    #[serde(default)]
+    pub rulers: Vec<usize>,
+
+    /// Highlight the other visible occurrences of the word the cursor rests
+    /// in (default: true)
+    #[serde(default = "default_true")]
+    pub highlight_occurrences: bool,
+
+    /// Save modified files automatically when the window loses focus or
+    /// when switching tabs (default: off); a workspace can override it
+    #[serde(default)]
+    pub autosave: AutosaveConfig,
+
+    /// How consecutive edits are grouped into one undo step
+    #[serde(default)]
+    pub undo: UndoConfig,
+
+    /// Git blame annotations, toggled with "Git: Toggle Blame"
+    #[serde(default)]
+    pub blame: BlameConfig,
+
+    /// Shell commands offered in the command palette (default: none)
+    #[serde(default)]
+    pub external_tools: Vec<crate::external_tools::ExternalTool>,
+
+    /// Turn off costly visuals such as cursor blink: auto, on or off
+    /// (default: auto, on while on battery or once rendering is slow)
+    #[serde(default)]
+    pub performance_mode: crate::performance::PerformanceMode,
+
+    /// AI chat panel provider settings
+    #[serde(default)]
+    pub ai: crate::ai::AiConfig,
+
+    /// Language servers (default: off, with servers for common languages)
+    #[serde(default)]
+    pub lsp: crate::lsp::LspConfig,
+}
+
+/// `indent_style` in config.yaml
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndentStyle {
+    /// One hard tab per level
+    #[default]
+    Tabs,
+    /// `indent_width` spaces per level
+    Spaces,
+}
+
+/// `render_whitespace` in config.yaml
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderWhitespace {
+    /// Whitespace is blank
+    #[default]
+    None,
+    /// Whitespace inside selections is drawn
+    Selection,
+    /// All whitespace is drawn
+    All,
+}
+
+fn default_indent_width() -> usize {
+    4
+}
+
+/// `autosave:` section of `config.yaml`. Untitled documents are never
+/// saved automatically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutosaveConfig {
+    /// Save every modified file when the editor window loses focus
+    #[serde(default)]
+    pub on_window_blur: bool,
+
+    /// Save a modified file when switching away from its tab
+    #[serde(default)]
+    pub on_tab_switch: bool,
+
+    /// Save every modified file this often, in milliseconds (default: off)
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+}
+
+/// `undo:` section of `config.yaml`. Typing and deleting characters one
+/// after another at the same spot undo together; a pause, moving the
+/// cursor or saving starts a new undo step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndoConfig {
+    /// Group consecutive typed or deleted characters (default: true)
+    #[serde(default = "default_true")]
+    pub group_typing: bool,
+
+    /// A pause longer than this, in milliseconds, starts a new undo step
+    /// (default: 1000)
+    #[serde(default = "default_undo_group_timeout_ms")]
+    pub group_timeout_ms: u64,
+
+    /// Start a new undo step at each word typed (default: true)
+    #[serde(default = "default_true")]
+    pub group_by_word: bool,
+}
+
+fn default_undo_group_timeout_ms() -> u64 {
+    1000
+}
+
+impl Default for UndoConfig {
+    fn default() -> Self {
+        Self {
+            group_typing: true,
+            group_timeout_ms: default_undo_group_timeout_ms(),
+            group_by_word: true,
+        }
+    }
+}
+
+/// `blame:` section of `config.yaml`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlameConfig {
+    /// Annotate only the line with the cursor instead of every line
+    /// (default: false)
+    #[serde(default)]
+    pub current_line_only: bool,
+}
+
+/// Settings a workspace overrides, from `.token/config.yaml` in its root.
+/// Each section present replaces the one in the user's config as a whole.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct WorkspaceConfig {
+    #[serde(default)]
+    pub autosave: Option<AutosaveConfig>,
+}
+
+impl WorkspaceConfig {
+    /// Load the overrides of the workspace at `root`; none if it has no
+    /// config file or it can't be read
+    pub fn load(root: &std::path::Path) -> Self {
+        let path = crate::config_paths::workspace_config_file(root);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match serde_yaml::from_str(&content) {
+            Ok(config) => {
+                tracing::info!("Loaded workspace config from {}", path.display());
+                config
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to parse workspace config at {}: {}",
+                    path.display(),
+                    e
+                );
+                Self::default()
+            }
+        }
+    }
 }
 
 fn default_theme() -> String {
@@ -53,6 +306,56 @@ fn default_cursor_blink_ms() -> u64 {
     600
 }
 
+/// Font size a zoom reset returns to
+pub const DEFAULT_FONT_SIZE: f32 = 14.0;
+/// Smallest font size zooming out reaches
+pub const MIN_FONT_SIZE: f32 = 6.0;
+/// Largest font size zooming in reaches
+pub const MAX_FONT_SIZE: f32 = 72.0;
+/// Points added or removed by one zoom step
+const FONT_SIZE_STEP: f32 = 1.0;
+
+fn default_font_size() -> f32 {
+    DEFAULT_FONT_SIZE
+}
+
+fn default_font_family() -> String {
+    crate::view::fonts::EMBEDDED_FAMILY.to_string()
+}
+
+fn default_font_fallbacks() -> Vec<String> {
+    let families: &[&str] = if cfg!(target_os = "macos") {
+        &[
+            "Menlo",
+            "Apple Symbols",
+            "PingFang SC",
+            "Hiragino Sans",
+            "Apple SD Gothic Neo",
+        ]
+    } else if cfg!(target_os = "windows") {
+        &[
+            "Consolas",
+            "Segoe UI Symbol",
+            "Microsoft YaHei",
+            "Yu Gothic",
+            "Malgun Gothic",
+        ]
+    } else {
+        &[
+            "DejaVu Sans Mono",
+            "Noto Sans Symbols",
+            "Noto Sans Symbols 2",
+            "Noto Sans CJK SC",
+            "WenQuanYi Micro Hei",
+        ]
+    };
+    families.iter().map(|family| family.to_string()).collect()
+}
+
+fn default_paste_image_path() -> String {
+    "assets/{doc}-{timestamp}.png".to_string()
+}
+
 fn default_true() -> bool {
     true
 }
@@ -63,13 +366,63 @@ impl Default for EditorConfig {
             theme: default_theme(),
             cursor_blink_ms: default_cursor_blink_ms(),
             auto_surround: true,
+            auto_close_tags: true,
+            linked_tag_editing: true,
             bracket_matching: true,
+            reindent_on_close_brace: false,
+            indent_style: IndentStyle::default(),
+            indent_width: default_indent_width(),
+            vim_mode: false,
             show_scrollbar: true,
+            paste_image_path: default_paste_image_path(),
+            markdown_hybrid: false,
+            check_for_updates: false,
+            restore_session: true,
+            verbose_logging: Vec::new(),
+            renderer: crate::view::RendererBackend::Auto,
+            text_antialiasing: crate::view::TextAntialiasing::Auto,
+            font_size: default_font_size(),
+            font_family: default_font_family(),
+            font_fallbacks: default_font_fallbacks(),
+            elastic_tabstops: false,
+            breadcrumbs: false,
+            render_whitespace: RenderWhitespace::None,
+            show_indent_guides: true,
+            rulers: Vec::new(),
+            highlight_occurrences: true,
+            autosave: AutosaveConfig::default(),
+            undo: UndoConfig::default(),
+            blame: BlameConfig::default(),
+            external_tools: Vec::new(),
+            performance_mode: crate::performance::PerformanceMode::Auto,
+            ai: crate::ai::AiConfig::default(),
+            lsp: crate::lsp::LspConfig::default(),
         }
     }
 }
 
 impl EditorConfig {
+    /// Text of one indentation level
+    pub fn indent_unit(&self) -> String {
+        match self.indent_style {
+            IndentStyle::Tabs => "\t".to_string(),
+            IndentStyle::Spaces => " ".repeat(self.indent_width.max(1)),
+        }
+    }
+
+    /// Font size one zoom step in (`steps` > 0) or out from the current
+    /// one, within `MIN_FONT_SIZE..=MAX_FONT_SIZE`. A size set by hand
+    /// between steps snaps to the next whole point.
+    pub fn zoomed_font_size(&self, steps: i32) -> f32 {
+        let current = self.font_size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+        let zoomed = match steps.signum() {
+            1 => current.floor() + FONT_SIZE_STEP * steps as f32,
+            -1 => current.ceil() + FONT_SIZE_STEP * steps as f32,
+            _ => current,
+        };
+        zoomed.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE)
+    }
+
     /// Load config from disk, or return defaults if not found
     pub fn load() -> Self {
         let Some(path) = crate::config_paths::config_file() else {
@@ -48,6 +48,11 @@ pub fn config_file() -> Option<PathBuf> {
     config_dir().map(|dir| dir.join("config.yaml"))
 }
 
+/// `<workspace>/.token/config.yaml`, a workspace's overrides of `config.yaml`
+pub fn workspace_config_file(root: &Path) -> PathBuf {
+    root.join(".token").join("config.yaml")
+}
+
 /// `~/.config/token-editor/keymap.yaml`
 pub fn keymap_file() -> Option<PathBuf> {
     config_dir().map(|dir| dir.join("keymap.yaml"))
@@ -58,11 +63,62 @@ pub fn recent_files_path() -> Option<PathBuf> {
     config_dir().map(|dir| dir.join("recent.json"))
 }
 
+/// `~/.config/token-editor/session.json`
+pub fn session_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("session.json"))
+}
+
+/// `~/.config/token-editor/sessions/`
+pub fn sessions_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("sessions"))
+}
+
+/// `~/.config/token-editor/sessions/<hash>.json` holding the open tabs of
+/// the workspace at `root`, or `sessions/default.json` without a workspace
+pub fn editor_session_file(root: Option<&Path>) -> Option<PathBuf> {
+    let name = match root {
+        Some(root) => crate::replay::content_hash(&root.to_string_lossy()),
+        None => "default".to_string(),
+    };
+    sessions_dir().map(|dir| dir.join(format!("{}.json", name)))
+}
+
+/// `~/.config/token-editor/trust.json`
+pub fn trust_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("trust.json"))
+}
+
 /// `~/.config/token-editor/logs/`
 pub fn logs_dir() -> Option<PathBuf> {
     config_dir().map(|dir| dir.join("logs"))
 }
 
+/// `~/.config/token-editor/scratch/`
+pub fn scratch_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("scratch"))
+}
+
+/// `~/.config/token-editor/crashes/`
+pub fn crash_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("crashes"))
+}
+
+/// `~/.config/token-editor/crashes/pending`, holding the path of a crash
+/// report not yet shown to the user
+pub fn pending_crash_file() -> Option<PathBuf> {
+    crash_dir().map(|dir| dir.join("pending"))
+}
+
+/// `~/.config/token-editor/recordings/`
+pub fn recordings_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("recordings"))
+}
+
+/// Whether `path` is a scratchpad file in [`scratch_dir`]
+pub fn is_scratchpad_path(path: &Path) -> bool {
+    scratch_dir().is_some_and(|dir| path.parent() == Some(dir.as_path()))
+}
+
 /// Returns the most recent log file in `~/.config/token-editor/logs/`
 /// (e.g., `token.log.2026-01-07`)
 ///
@@ -123,6 +179,38 @@ pub fn ensure_logs_dir() -> Result<PathBuf, String> {
     Ok(logs)
 }
 
+/// Ensure scratch dir exists, returning it
+pub fn ensure_scratch_dir() -> Result<PathBuf, String> {
+    let config = ensure_config_dir()?;
+    let scratch = config.join("scratch");
+    ensure_dir(&scratch)?;
+    Ok(scratch)
+}
+
+/// Ensure crash report dir exists, returning it
+pub fn ensure_crash_dir() -> Result<PathBuf, String> {
+    let config = ensure_config_dir()?;
+    let crashes = config.join("crashes");
+    ensure_dir(&crashes)?;
+    Ok(crashes)
+}
+
+/// Ensure session recordings dir exists, returning it
+pub fn ensure_recordings_dir() -> Result<PathBuf, String> {
+    let config = ensure_config_dir()?;
+    let recordings = config.join("recordings");
+    ensure_dir(&recordings)?;
+    Ok(recordings)
+}
+
+/// Ensure editor sessions dir exists, returning it
+pub fn ensure_sessions_dir() -> Result<PathBuf, String> {
+    let config = ensure_config_dir()?;
+    let sessions = config.join("sessions");
+    ensure_dir(&sessions)?;
+    Ok(sessions)
+}
+
 /// Ensure full config structure (config dir + themes)
 pub fn ensure_all_config_dirs() {
     match ensure_themes_dir() {
@@ -0,0 +1,273 @@
+//! Crash reports
+//!
+//! [`install_hook`] adds a panic hook that writes a JSON report to
+//! `~/.config/token-editor/crashes/` with the panic message, a backtrace and
+//! a summary of the editor state: the workspace, open file paths, cursor
+//! positions and the names of the most recent messages. Buffer contents and
+//! message arguments are never included.
+//!
+//! The hook can't reach the model, so the runtime keeps a summary up to date
+//! with [`record_state`] and [`record_message`]. On the next launch
+//! [`take_pending_report`] returns the report so the files it lists can be
+//! reopened.
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::messages::Msg;
+use crate::model::AppModel;
+
+/// Number of message names kept for the report
+const RECENT_MESSAGES: usize = 50;
+
+/// What the panic hook knows about the editor
+struct CrashState {
+    editor: EditorSummary,
+    recent_messages: VecDeque<String>,
+}
+
+static STATE: Mutex<CrashState> = Mutex::new(CrashState {
+    editor: EditorSummary {
+        workspace: None,
+        focused_file: None,
+        open_files: Vec::new(),
+    },
+    recent_messages: VecDeque::new(),
+});
+
+/// Open files and cursors, without any file contents
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EditorSummary {
+    pub workspace: Option<PathBuf>,
+    pub focused_file: Option<PathBuf>,
+    pub open_files: Vec<OpenFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenFile {
+    pub path: PathBuf,
+    /// Had unsaved changes (which are lost)
+    pub modified: bool,
+    /// `(line, column)` of each cursor, zero-based
+    pub cursors: Vec<(usize, usize)>,
+}
+
+impl EditorSummary {
+    pub fn from_model(model: &AppModel) -> Self {
+        let area = &model.editor_area;
+        let mut open_files: Vec<OpenFile> = area
+            .documents
+            .iter()
+            .filter_map(|(doc_id, doc)| {
+                let path = doc.file_path.clone()?;
+                let cursors = area
+                    .editors
+                    .values()
+                    .find(|editor| editor.document_id == Some(*doc_id))
+                    .map(|editor| {
+                        editor
+                            .cursors
+                            .iter()
+                            .map(|cursor| (cursor.line, cursor.column))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Some(OpenFile {
+                    path,
+                    modified: doc.is_modified,
+                    cursors,
+                })
+            })
+            .collect();
+        open_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Self {
+            workspace: model.workspace.as_ref().map(|ws| ws.root.clone()),
+            focused_file: area
+                .focused_document()
+                .and_then(|doc| doc.file_path.clone()),
+            open_files,
+        }
+    }
+}
+
+/// Written by the panic hook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub app_version: String,
+    pub os: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub editor: EditorSummary,
+    /// Oldest first
+    pub recent_messages: Vec<String>,
+}
+
+/// Install the panic hook. The default hook still runs afterwards.
+pub fn install_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = build_report(info);
+        match write_report(&report) {
+            Ok(path) => {
+                tracing::error!("Crash report written to {}", path.display());
+                eprintln!("Crash report written to {}", path.display());
+            }
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+        previous(info);
+    }));
+}
+
+/// Update the editor summary included in crash reports
+pub fn record_state(model: &AppModel) {
+    let editor = EditorSummary::from_model(model);
+    if let Ok(mut state) = STATE.lock() {
+        state.editor = editor;
+    }
+}
+
+/// Remember a processed message by name (variant names only)
+pub fn record_message(msg: &Msg) {
+    let name = message_name(msg);
+    if let Ok(mut state) = STATE.lock() {
+        if state.recent_messages.len() >= RECENT_MESSAGES {
+            state.recent_messages.pop_front();
+        }
+        state.recent_messages.push_back(name);
+    }
+}
+
+/// The report left by a crash in the previous run, if any. The report file
+/// itself is kept; only the marker that makes it pending is removed.
+pub fn take_pending_report() -> Option<(PathBuf, CrashReport)> {
+    let marker = crate::config_paths::pending_crash_file()?;
+    let report_path = PathBuf::from(std::fs::read_to_string(&marker).ok()?.trim());
+    let _ = std::fs::remove_file(&marker);
+    match read_report(&report_path) {
+        Ok(report) => Some((report_path, report)),
+        Err(e) => {
+            tracing::warn!("Ignoring crash report {}: {}", report_path.display(), e);
+            None
+        }
+    }
+}
+
+pub fn read_report(path: &Path) -> Result<CrashReport, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&contents).map_err(|e| e.to_string())
+}
+
+fn build_report(info: &PanicHookInfo) -> CrashReport {
+    let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    };
+
+    // A panic while the state is locked must not deadlock the hook
+    let (editor, recent_messages) = match STATE.try_lock() {
+        Ok(state) => (
+            state.editor.clone(),
+            state.recent_messages.iter().cloned().collect(),
+        ),
+        Err(_) => (EditorSummary::default(), Vec::new()),
+    };
+
+    CrashReport {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        thread: std::thread::current()
+            .name()
+            .unwrap_or("<unnamed>")
+            .to_string(),
+        message,
+        location: info.location().map(|l| l.to_string()),
+        backtrace: Backtrace::force_capture().to_string(),
+        editor,
+        recent_messages,
+    }
+}
+
+/// Write `crash-<timestamp>.json` and mark it as pending for the next launch
+fn write_report(report: &CrashReport) -> Result<PathBuf, String> {
+    let dir = crate::config_paths::ensure_crash_dir()?;
+    let path = dir.join(format!("crash-{}.json", report.timestamp));
+    let contents = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    if let Some(marker) = crate::config_paths::pending_crash_file() {
+        std::fs::write(marker, path.to_string_lossy().as_bytes()).map_err(|e| e.to_string())?;
+    }
+    Ok(path)
+}
+
+/// `Category::Variant` for a message, e.g. `Document::InsertChar`
+fn message_name(msg: &Msg) -> String {
+    let mut name = VariantPath::default();
+    // Stops with an error at the first argument, so payloads (file
+    // contents, typed text) are never formatted
+    let _ = write!(name, "{:?}", msg);
+    name.path
+}
+
+/// Collects the leading `Outer(Inner(` variant names of a `Debug` output
+#[derive(Default)]
+struct VariantPath {
+    path: String,
+    depth: usize,
+}
+
+impl std::fmt::Write for VariantPath {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for ch in s.chars() {
+            if ch.is_alphanumeric() || ch == '_' {
+                self.path.push(ch);
+            } else if ch == '(' && self.depth == 0 {
+                self.path.push_str("::");
+                self.depth = 1;
+            } else {
+                return Err(std::fmt::Error);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{AppMsg, DocumentMsg, EditorMsg};
+
+    #[test]
+    fn message_names_leave_out_arguments() {
+        assert_eq!(
+            message_name(&Msg::Document(DocumentMsg::InsertChar('x'))),
+            "Document::InsertChar"
+        );
+        assert_eq!(
+            message_name(&Msg::App(AppMsg::Resize(800, 600))),
+            "App::Resize"
+        );
+        assert_eq!(
+            message_name(&Msg::Editor(EditorMsg::SelectAll)),
+            "Editor::SelectAll"
+        );
+    }
+}
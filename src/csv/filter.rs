@@ -0,0 +1,205 @@
+//! Row filtering for CSV mode
+//!
+//! Each column can have a filter text, typed into a row under the column
+//! headers. A row stays visible when every filtered column contains its
+//! text (ignoring case); the header row, if any, is always shown. Hidden
+//! rows stay in `CsvData`: `row_map` lists the data rows on screen, so the
+//! selection and cell edits keep using real row indices and write back to
+//! the right record.
+
+use super::model::{CellPosition, CsvState};
+
+impl CsvState {
+    /// Whether any column has a filter
+    pub fn is_filtered(&self) -> bool {
+        self.row_map.is_some()
+    }
+
+    /// Number of rows on screen
+    pub fn visible_row_count(&self) -> usize {
+        match &self.row_map {
+            Some(rows) => rows.len(),
+            None => self.data.row_count(),
+        }
+    }
+
+    /// Data row shown at position `view_row` of the grid
+    pub fn data_row(&self, view_row: usize) -> Option<usize> {
+        match &self.row_map {
+            Some(rows) => rows.get(view_row).copied(),
+            None => (view_row < self.data.row_count()).then_some(view_row),
+        }
+    }
+
+    /// Position on the grid of `data_row`, or of the nearest row shown
+    /// after it (before it at the end) when it's hidden
+    pub fn view_row(&self, data_row: usize) -> usize {
+        match &self.row_map {
+            Some(rows) => rows
+                .partition_point(|&row| row < data_row)
+                .min(rows.len().saturating_sub(1)),
+            None => data_row,
+        }
+    }
+
+    /// Filter text of a column
+    pub fn filter(&self, col: usize) -> &str {
+        self.filters.get(col).map(String::as_str).unwrap_or("")
+    }
+
+    /// Show the filter row and type into a column's filter
+    pub fn focus_filter(&mut self, col: usize) {
+        if !self.show_filter_row {
+            self.show_filter_row = true;
+            self.viewport.visible_rows = self.viewport.visible_rows.saturating_sub(1).max(1);
+        }
+        self.filter_focus = Some(col.min(self.data.column_count().saturating_sub(1)));
+    }
+
+    /// Hide the filter row, showing every row again
+    pub fn close_filter_row(&mut self) {
+        if self.show_filter_row {
+            self.show_filter_row = false;
+            self.viewport.visible_rows += 1;
+        }
+        self.filter_focus = None;
+        self.filters.clear();
+        self.apply_filters();
+    }
+
+    /// Move filter typing to the next (`delta` 1) or previous column
+    pub fn move_filter_focus(&mut self, delta: i32) {
+        let Some(col) = self.filter_focus else {
+            return;
+        };
+        let last = self.data.column_count().saturating_sub(1) as i32;
+        let col = (col as i32 + delta).clamp(0, last) as usize;
+        self.filter_focus = Some(col);
+        self.selected_cell.col = col;
+        self.ensure_selection_visible();
+    }
+
+    /// Type a character into the focused column's filter
+    pub fn filter_insert_char(&mut self, ch: char) {
+        if let Some(filter) = self.focused_filter_mut() {
+            filter.push(ch);
+            self.apply_filters();
+        }
+    }
+
+    /// Delete the last character of the focused column's filter
+    pub fn filter_delete_backward(&mut self) {
+        if let Some(filter) = self.focused_filter_mut() {
+            if filter.pop().is_some() {
+                self.apply_filters();
+            }
+        }
+    }
+
+    fn focused_filter_mut(&mut self) -> Option<&mut String> {
+        let col = self.filter_focus?;
+        if self.filters.len() <= col {
+            self.filters.resize(col + 1, String::new());
+        }
+        self.filters.get_mut(col)
+    }
+
+    /// Work out which rows the filters let through, moving the selection to
+    /// the nearest row still shown
+    pub fn apply_filters(&mut self) {
+        let filters: Vec<(usize, String)> = self
+            .filters
+            .iter()
+            .enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(col, text)| (col, text.to_lowercase()))
+            .collect();
+
+        self.row_map = (!filters.is_empty()).then(|| {
+            (0..self.data.row_count())
+                .filter(|&row| {
+                    (row == 0 && self.has_header_row)
+                        || filters.iter().all(|(col, text)| {
+                            self.data.get(row, *col).to_lowercase().contains(text)
+                        })
+                })
+                .collect()
+        });
+
+        self.selection_anchor = None;
+        if let Some(row) = self.data_row(self.view_row(self.selected_cell.row)) {
+            self.selected_cell = CellPosition::new(row, self.selected_cell.col);
+        }
+        self.ensure_selection_visible();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::csv::{parse_csv, CellPosition, CsvState, Delimiter};
+
+    fn state() -> CsvState {
+        let content = "name,city\nAnn,Oslo\nBob,Bergen\nCid,oslo\nDan,Paris\n";
+        let mut csv = CsvState::new(
+            parse_csv(content, Delimiter::Comma).unwrap(),
+            Delimiter::Comma,
+        );
+        csv.set_viewport_size(10, 5);
+        csv
+    }
+
+    fn type_filter(csv: &mut CsvState, col: usize, text: &str) {
+        csv.focus_filter(col);
+        for ch in text.chars() {
+            csv.filter_insert_char(ch);
+        }
+    }
+
+    #[test]
+    fn test_filter_keeps_header_and_matches_ignoring_case() {
+        let mut csv = state();
+        type_filter(&mut csv, 1, "OSL");
+        assert_eq!(csv.row_map, Some(vec![0, 1, 3]));
+        assert_eq!(csv.visible_row_count(), 3);
+        assert_eq!(csv.data_row(2), Some(3));
+        assert_eq!(csv.data_row(3), None);
+
+        // Filters on several columns must all match
+        type_filter(&mut csv, 0, "c");
+        assert_eq!(csv.row_map, Some(vec![0, 3]));
+
+        csv.filter_delete_backward();
+        assert_eq!(csv.row_map, Some(vec![0, 1, 3]));
+    }
+
+    #[test]
+    fn test_navigation_skips_hidden_rows() {
+        let mut csv = state();
+        csv.select_cell(2, 0);
+        type_filter(&mut csv, 1, "o");
+        // Bob's row is hidden, so the selection moves on to Cid
+        assert_eq!(csv.row_map, Some(vec![0, 1, 3]));
+        assert_eq!(csv.selected_cell, CellPosition::new(3, 0));
+
+        csv.move_selection(-1, 0);
+        assert_eq!(csv.selected_cell.row, 1);
+        csv.move_to_last_cell();
+        assert_eq!(csv.selected_cell, CellPosition::new(3, 1));
+        csv.move_to_next_cell();
+        assert_eq!(csv.selected_cell, CellPosition::new(3, 1));
+    }
+
+    #[test]
+    fn test_close_filter_row_shows_everything() {
+        let mut csv = state();
+        type_filter(&mut csv, 0, "zzz");
+        assert_eq!(csv.row_map, Some(vec![0]));
+        assert_eq!(csv.viewport.visible_rows, 9);
+
+        csv.close_filter_row();
+        assert!(!csv.is_filtered());
+        assert!(!csv.show_filter_row);
+        assert_eq!(csv.visible_row_count(), 5);
+        assert_eq!(csv.viewport.visible_rows, 10);
+    }
+}
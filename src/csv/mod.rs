@@ -3,7 +3,13 @@
 //! Provides a spreadsheet-like view for CSV/TSV/PSV files with:
 //! - Grid rendering with row/column headers
 //! - Cell navigation (arrow keys, Tab, Enter)
+//! - Range selection (Shift+arrows, mouse drag) copied as TSV
+//! - Column resizing by dragging header edges, double-click to auto-fit
 //! - Cell editing with sync back to document buffer (Phase 2)
+//! - Cell-aware find/replace that re-escapes each changed cell
+//! - Column sorting that moves whole records in the document
+//! - Row and column insertion and deletion
+//! - Filter row that hides rows not matching per-column filters
 //!
 //! # Architecture
 //!
@@ -20,12 +26,25 @@
 //!             └── CellEditState (when editing)
 //! ```
 
+mod filter;
 mod model;
 mod navigation;
 mod parser;
 pub mod render;
+mod resize;
+mod selection;
+mod sort;
+mod structure;
 mod viewport;
+mod writeback;
 
-pub use model::{CellEdit, CellEditState, CellPosition, CsvData, CsvState, Delimiter};
+pub use model::{
+    CellEdit, CellEditState, CellPosition, ColumnResize, CsvData, CsvState, Delimiter,
+};
 pub use parser::{detect_delimiter, escape_csv_value, parse_csv, ParseError};
+pub use sort::{compare_cells, reorder_records, sorted_row_order};
+pub use structure::{delete_column, delete_row, insert_column, insert_row};
 pub use viewport::CsvViewport;
+pub use writeback::{
+    cell_byte_range, cell_byte_ranges, cell_write, escape_cell, replace_in_cell, write_cells,
+};
@@ -2,13 +2,19 @@
 //!
 //! Memory-efficient storage using delimited strings instead of Vec<Vec<String>>.
 
+use std::collections::HashMap;
+
 use crate::editable::{EditConstraints, EditableState, MoveTarget, StringBuffer};
 
+use super::render::column_to_letters;
 use super::viewport::CsvViewport;
 
 /// Internal delimiter for cell storage (0xFA - rarely used in real data)
 pub const CELL_DELIMITER: char = '\u{00FA}';
 
+/// Narrowest a column gets, in characters
+pub const MIN_COLUMN_WIDTH: usize = 4;
+
 /// Supported CSV delimiters
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Delimiter {
@@ -372,6 +378,29 @@ impl CsvData {
     pub fn is_empty(&self) -> bool {
         self.rows.is_empty()
     }
+
+    /// Rearrange rows so that row `order[i]` becomes row `i`
+    pub fn reorder_rows(&mut self, order: &[usize]) {
+        let mut rows: Vec<Option<String>> = std::mem::take(&mut self.rows)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.rows = order
+            .iter()
+            .filter_map(|&from| rows.get_mut(from)?.take())
+            .collect();
+    }
+}
+
+/// A column being resized by dragging its right edge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnResize {
+    /// Column being resized
+    pub col: usize,
+    /// Mouse x position when the drag started
+    pub start_x: f64,
+    /// Column width (in characters) when the drag started
+    pub start_width: usize,
 }
 
 /// State for CSV view mode
@@ -379,18 +408,38 @@ impl CsvData {
 pub struct CsvState {
     /// Parsed CSV data
     pub data: CsvData,
-    /// Currently selected cell
+    /// Currently selected cell (the head of a range selection)
     pub selected_cell: CellPosition,
+    /// Other corner of a range selection, `None` when only the selected
+    /// cell is selected
+    pub selection_anchor: Option<CellPosition>,
     /// Viewport for visible region
     pub viewport: CsvViewport,
     /// Original delimiter used in file
     pub delimiter: Delimiter,
-    /// Whether first row is a header
+    /// Whether first row is a header: its values name the columns, and it
+    /// stays put when sorting and filtering
     pub has_header_row: bool,
     /// Calculated column widths (in characters)
     pub column_widths: Vec<usize>,
+    /// Widths (in characters) of columns the user resized or auto-fitted,
+    /// kept when the grid is re-read from the document
+    pub manual_widths: HashMap<usize, usize>,
+    /// Column edge drag in progress
+    pub column_resize: Option<ColumnResize>,
+    /// Whether the filter row is shown under the column headers
+    pub show_filter_row: bool,
+    /// Filter text per column (empty or missing for no filter)
+    pub filters: Vec<String>,
+    /// Column whose filter is being typed into
+    pub filter_focus: Option<usize>,
+    /// Data rows shown on the grid, in order, while a filter is set
+    pub row_map: Option<Vec<usize>>,
     /// Cell editing state (Some when editing a cell)
     pub editing: Option<CellEditState>,
+    /// Column and direction (`true` for ascending) of the last sort, shown
+    /// in that column's header until the grid is re-read from the document
+    pub sorted_by: Option<(usize, bool)>,
 }
 
 impl CsvState {
@@ -401,20 +450,27 @@ impl CsvState {
         Self {
             data,
             selected_cell: CellPosition::default(),
+            selection_anchor: None,
             viewport: CsvViewport::default(),
             delimiter,
             has_header_row: true,
             column_widths,
+            manual_widths: HashMap::new(),
+            column_resize: None,
+            show_filter_row: false,
+            filters: Vec::new(),
+            filter_focus: None,
+            row_map: None,
             editing: None,
+            sorted_by: None,
         }
     }
 
     /// Calculate optimal column widths based on content
     fn calculate_column_widths(data: &CsvData) -> Vec<usize> {
-        const MIN_WIDTH: usize = 4;
         const MAX_WIDTH: usize = 40;
 
-        let mut widths = vec![MIN_WIDTH; data.column_count()];
+        let mut widths = vec![MIN_COLUMN_WIDTH; data.column_count()];
 
         for row in 0..data.row_count().min(100) {
             for (col, cell) in data.row_cells(row).enumerate() {
@@ -428,6 +484,23 @@ impl CsvState {
         widths
     }
 
+    /// Swap in freshly parsed data (after the document changed underneath),
+    /// keeping the selection where it was if it still exists and filtering
+    /// the new rows
+    pub fn replace_data(&mut self, data: CsvData) {
+        self.column_widths = Self::calculate_column_widths(&data);
+        for (&col, &width) in &self.manual_widths {
+            if let Some(column_width) = self.column_widths.get_mut(col) {
+                *column_width = width;
+            }
+        }
+        self.data = data;
+        self.sorted_by = None;
+        self.selection_anchor = None;
+        self.clamp_selection();
+        self.apply_filters();
+    }
+
     /// Ensure selected cell is within valid bounds
     pub fn clamp_selection(&mut self) {
         let max_row = self.data.row_count().saturating_sub(1);
@@ -437,16 +510,40 @@ impl CsvState {
         self.selected_cell.col = self.selected_cell.col.min(max_col);
     }
 
+    /// Direction a click on `col`'s header sorts in: ascending, unless the
+    /// grid is already sorted ascending by that column
+    pub fn header_click_ascending(&self, col: usize) -> bool {
+        self.sorted_by != Some((col, true))
+    }
+
+    /// Name shown over a column: its value in the header row, or its
+    /// letters (A, B, C, ...) without a header row or when that's empty
+    pub fn column_name(&self, col: usize) -> String {
+        let label = if self.has_header_row {
+            self.data.get(0, col).lines().next().unwrap_or("").trim()
+        } else {
+            ""
+        };
+        if label.is_empty() {
+            column_to_letters(col)
+        } else {
+            label.to_string()
+        }
+    }
+
+    /// Treat the first row as the header, or as an ordinary row
+    pub fn toggle_header_row(&mut self) {
+        self.has_header_row = !self.has_header_row;
+        self.sorted_by = None;
+        self.apply_filters();
+    }
+
     /// Select a specific cell and ensure it's visible
     pub fn select_cell(&mut self, row: usize, col: usize) {
         self.selected_cell = CellPosition::new(row, col);
+        self.selection_anchor = None;
         self.clamp_selection();
-        self.viewport.ensure_visible(
-            self.selected_cell.row,
-            self.selected_cell.col,
-            self.data.row_count(),
-            self.data.column_count(),
-        );
+        self.ensure_selection_visible();
     }
 
     /// Check if currently editing a cell
@@ -610,6 +707,31 @@ mod tests {
         assert_eq!(data.get(0, 1), "also updated");
     }
 
+    #[test]
+    fn test_csv_data_reorder_rows() {
+        let rows = ["h", "x", "y", "z"].map(|s| vec![s.to_string()]).to_vec();
+        let mut data = CsvData::from_rows(rows);
+
+        data.reorder_rows(&[0, 3, 1, 2]);
+        let firsts: Vec<&str> = (0..4).map(|row| data.get(row, 0)).collect();
+        assert_eq!(firsts, vec!["h", "z", "x", "y"]);
+    }
+
+    #[test]
+    fn test_column_name_from_header_row() {
+        let data = CsvData::from_rows(vec![
+            vec!["name".to_string(), " ".to_string()],
+            vec!["ann".to_string(), "30".to_string()],
+        ]);
+        let mut csv = CsvState::new(data, Delimiter::Comma);
+        assert_eq!(csv.column_name(0), "name");
+        assert_eq!(csv.column_name(1), "B");
+
+        csv.toggle_header_row();
+        assert!(!csv.has_header_row);
+        assert_eq!(csv.column_name(0), "A");
+    }
+
     #[test]
     fn test_delimiter_from_extension() {
         assert_eq!(Delimiter::from_extension("csv"), Delimiter::Comma);
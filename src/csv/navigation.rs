@@ -1,35 +1,48 @@
 //! Cell navigation logic for CSV mode
 //!
 //! Handles arrow key movement, Tab/Shift+Tab, Enter, and viewport scrolling.
+//! Movement goes by grid position, so rows hidden by a filter are skipped.
 
 use super::model::{CellPosition, CsvState};
 
 impl CsvState {
+    /// Select the cell at grid position `view_row` (skipping rows a filter
+    /// hides) and column `col`
+    fn select_view_cell(&mut self, view_row: usize, col: usize) {
+        if let Some(row) = self.data_row(view_row) {
+            self.selected_cell = CellPosition::new(row, col);
+        } else {
+            self.selected_cell.col = col;
+        }
+        self.ensure_selection_visible();
+    }
+
     /// Move selection by delta (handles bounds)
     pub fn move_selection(&mut self, delta_row: i32, delta_col: i32) {
-        let new_row = (self.selected_cell.row as i32 + delta_row)
+        self.selection_anchor = None;
+        let new_row = (self.view_row(self.selected_cell.row) as i32 + delta_row)
             .max(0)
-            .min(self.data.row_count().saturating_sub(1) as i32) as usize;
+            .min(self.visible_row_count().saturating_sub(1) as i32) as usize;
 
         let new_col = (self.selected_cell.col as i32 + delta_col)
             .max(0)
             .min(self.data.column_count().saturating_sub(1) as i32) as usize;
 
-        self.selected_cell = CellPosition::new(new_row, new_col);
-        self.ensure_selection_visible();
+        self.select_view_cell(new_row, new_col);
     }
 
     /// Move to next cell (Tab behavior)
     pub fn move_to_next_cell(&mut self) {
+        self.selection_anchor = None;
         let col_count = self.data.column_count();
-        let row_count = self.data.row_count();
+        let row_count = self.visible_row_count();
 
         if col_count == 0 || row_count == 0 {
             return;
         }
 
         let mut new_col = self.selected_cell.col + 1;
-        let mut new_row = self.selected_cell.row;
+        let mut new_row = self.view_row(self.selected_cell.row);
 
         if new_col >= col_count {
             new_col = 0;
@@ -40,85 +53,92 @@ impl CsvState {
             }
         }
 
-        self.selected_cell = CellPosition::new(new_row, new_col);
-        self.ensure_selection_visible();
+        self.select_view_cell(new_row, new_col);
     }
 
     /// Move to previous cell (Shift+Tab behavior)
     pub fn move_to_prev_cell(&mut self) {
+        self.selection_anchor = None;
         let col_count = self.data.column_count();
-        let row_count = self.data.row_count();
+        let row_count = self.visible_row_count();
 
         if col_count == 0 || row_count == 0 {
             return;
         }
 
+        let view_row = self.view_row(self.selected_cell.row);
         if self.selected_cell.col > 0 {
-            self.selected_cell.col -= 1;
-        } else if self.selected_cell.row > 0 {
-            self.selected_cell.row -= 1;
-            self.selected_cell.col = col_count - 1;
+            self.select_view_cell(view_row, self.selected_cell.col - 1);
+        } else if view_row > 0 {
+            self.select_view_cell(view_row - 1, col_count - 1);
+        } else {
+            self.ensure_selection_visible();
         }
-
-        self.ensure_selection_visible();
     }
 
     /// Move to first cell (Cmd+Home)
     pub fn move_to_first_cell(&mut self) {
-        self.selected_cell = CellPosition::new(0, 0);
-        self.ensure_selection_visible();
+        self.selection_anchor = None;
+        self.select_view_cell(0, 0);
     }
 
     /// Move to last cell (Cmd+End)
     pub fn move_to_last_cell(&mut self) {
-        let row = self.data.row_count().saturating_sub(1);
+        self.selection_anchor = None;
+        let row = self.visible_row_count().saturating_sub(1);
         let col = self.data.column_count().saturating_sub(1);
-        self.selected_cell = CellPosition::new(row, col);
-        self.ensure_selection_visible();
+        self.select_view_cell(row, col);
     }
 
     /// Move to first column in current row (Home)
     pub fn move_to_row_start(&mut self) {
+        self.selection_anchor = None;
         self.selected_cell.col = 0;
         self.ensure_selection_visible();
     }
 
     /// Move to last column in current row (End)
     pub fn move_to_row_end(&mut self) {
+        self.selection_anchor = None;
         self.selected_cell.col = self.data.column_count().saturating_sub(1);
         self.ensure_selection_visible();
     }
 
     /// Page up navigation
     pub fn page_up(&mut self) {
+        self.selection_anchor = None;
         let page_size = self.viewport.visible_rows.max(1);
-        let new_row = self.selected_cell.row.saturating_sub(page_size);
-        self.selected_cell.row = new_row;
-        self.ensure_selection_visible();
+        let new_row = self
+            .view_row(self.selected_cell.row)
+            .saturating_sub(page_size);
+        self.select_view_cell(new_row, self.selected_cell.col);
     }
 
     /// Page down navigation
     pub fn page_down(&mut self) {
+        self.selection_anchor = None;
         let page_size = self.viewport.visible_rows.max(1);
-        let new_row =
-            (self.selected_cell.row + page_size).min(self.data.row_count().saturating_sub(1));
-        self.selected_cell.row = new_row;
-        self.ensure_selection_visible();
+        let new_row = (self.view_row(self.selected_cell.row) + page_size)
+            .min(self.visible_row_count().saturating_sub(1));
+        self.select_view_cell(new_row, self.selected_cell.col);
     }
 
     /// Ensure the selected cell is visible, scrolling viewport if necessary
     pub fn ensure_selection_visible(&mut self) {
         self.viewport.ensure_visible(
-            self.selected_cell.row,
+            self.view_row(self.selected_cell.row),
             self.selected_cell.col,
-            self.data.row_count(),
+            self.visible_row_count(),
             self.data.column_count(),
         );
     }
 
-    /// Set viewport dimensions (called on resize)
+    /// Set viewport dimensions (called on resize). The filter row, when
+    /// shown, takes one of the rows.
     pub fn set_viewport_size(&mut self, rows: usize, cols: usize) {
-        self.viewport.visible_rows = rows;
+        self.viewport.visible_rows = rows
+            .saturating_sub(usize::from(self.show_filter_row))
+            .max(1);
         self.viewport.visible_cols = cols;
         self.ensure_selection_visible();
     }
@@ -126,8 +146,7 @@ impl CsvState {
     /// Scroll viewport vertically (from mouse wheel)
     pub fn scroll_vertical(&mut self, delta: i32) {
         let max_top = self
-            .data
-            .row_count()
+            .visible_row_count()
             .saturating_sub(self.viewport.visible_rows.max(1));
         let new_top = (self.viewport.top_row as i32 + delta)
             .max(0)
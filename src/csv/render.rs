@@ -3,6 +3,7 @@
 //! Renders the CSV spreadsheet view with:
 //! - Row numbers column
 //! - Column headers (A, B, C, ...)
+//! - Filter row under the column headers (when shown)
 //! - Cell grid with horizontal/vertical scrolling
 //! - Selected cell highlight
 
@@ -56,6 +57,10 @@ pub struct CsvRenderLayout {
     pub col_header_y: usize,
     /// Height of column header row
     pub col_header_height: usize,
+    /// Y offset of the filter row (below the column headers)
+    pub filter_row_y: usize,
+    /// Height of the filter row (0 when hidden)
+    pub filter_row_height: usize,
     /// Y offset where grid data starts
     pub data_y: usize,
     /// Visible column indices and their x positions
@@ -83,9 +88,11 @@ impl CsvRenderLayout {
         let grid_x = rect_x + row_header_width;
         let grid_w = rect_w.saturating_sub(row_header_width);
 
-        // Column header height
+        // Column header height, then the filter row when shown
         let col_header_height = line_height;
-        let data_y = content_y + col_header_height;
+        let filter_row_y = content_y + col_header_height;
+        let filter_row_height = if csv.show_filter_row { line_height } else { 0 };
+        let data_y = filter_row_y + filter_row_height;
 
         // Calculate visible columns
         let mut visible_columns = Vec::new();
@@ -111,6 +118,8 @@ impl CsvRenderLayout {
             grid_x,
             col_header_y: content_y,
             col_header_height,
+            filter_row_y,
+            filter_row_height,
             data_y,
             visible_columns,
             column_widths_px,
@@ -118,6 +127,128 @@ impl CsvRenderLayout {
     }
 }
 
+/// Hit-test the column header row given window coordinates, returning the
+/// column whose header was hit.
+pub fn pixel_to_csv_column_header(
+    csv: &CsvState,
+    group_rect: &Rect,
+    x: f64,
+    y: f64,
+    line_height: usize,
+    char_width: f32,
+    tab_bar_height: usize,
+) -> Option<usize> {
+    let local_x = x - group_rect.x as f64;
+    let local_y = y - group_rect.y as f64;
+
+    let layout = CsvRenderLayout::calculate(
+        csv,
+        0,
+        group_rect.width as usize,
+        tab_bar_height,
+        line_height,
+        char_width,
+    );
+    let header_top = layout.col_header_y as f64;
+    if local_y < header_top || local_y >= header_top + layout.col_header_height as f64 {
+        return None;
+    }
+
+    let cell_x_in_grid = local_x - layout.grid_x as f64;
+    layout
+        .visible_columns
+        .iter()
+        .zip(&layout.column_widths_px)
+        .find(|((_, col_x), width)| {
+            cell_x_in_grid >= *col_x as f64 && cell_x_in_grid < (*col_x + **width) as f64
+        })
+        .map(|((col_index, _), _)| *col_index)
+}
+
+/// How far (in pixels) either side of a column's right edge in the header
+/// row grabs the edge for resizing
+pub const COLUMN_RESIZE_GRAB_PX: f64 = 4.0;
+
+/// Hit-test the grid lines between column headers given window
+/// coordinates, returning the column whose right edge was hit.
+///
+/// Takes precedence over [`pixel_to_csv_column_header`], so a press on an
+/// edge resizes the column instead of sorting by it.
+pub fn pixel_to_csv_column_border(
+    csv: &CsvState,
+    group_rect: &Rect,
+    x: f64,
+    y: f64,
+    line_height: usize,
+    char_width: f32,
+    tab_bar_height: usize,
+) -> Option<usize> {
+    let local_x = x - group_rect.x as f64;
+    let local_y = y - group_rect.y as f64;
+
+    let layout = CsvRenderLayout::calculate(
+        csv,
+        0,
+        group_rect.width as usize,
+        tab_bar_height,
+        line_height,
+        char_width,
+    );
+    let header_top = layout.col_header_y as f64;
+    if local_y < header_top || local_y >= header_top + layout.col_header_height as f64 {
+        return None;
+    }
+
+    let cell_x_in_grid = local_x - layout.grid_x as f64;
+    layout
+        .visible_columns
+        .iter()
+        .zip(&layout.column_widths_px)
+        .find(|((_, col_x), width)| {
+            let edge = (*col_x + **width) as f64;
+            (cell_x_in_grid - edge).abs() <= COLUMN_RESIZE_GRAB_PX
+        })
+        .map(|((col_index, _), _)| *col_index)
+}
+
+/// Hit-test the filter row given window coordinates, returning the column
+/// whose filter was hit
+pub fn pixel_to_csv_filter(
+    csv: &CsvState,
+    group_rect: &Rect,
+    x: f64,
+    y: f64,
+    line_height: usize,
+    char_width: f32,
+    tab_bar_height: usize,
+) -> Option<usize> {
+    let local_x = x - group_rect.x as f64;
+    let local_y = y - group_rect.y as f64;
+
+    let layout = CsvRenderLayout::calculate(
+        csv,
+        0,
+        group_rect.width as usize,
+        tab_bar_height,
+        line_height,
+        char_width,
+    );
+    let top = layout.filter_row_y as f64;
+    if local_y < top || local_y >= top + layout.filter_row_height as f64 {
+        return None;
+    }
+
+    let cell_x_in_grid = local_x - layout.grid_x as f64;
+    layout
+        .visible_columns
+        .iter()
+        .zip(&layout.column_widths_px)
+        .find(|((_, col_x), width)| {
+            cell_x_in_grid >= *col_x as f64 && cell_x_in_grid < (*col_x + **width) as f64
+        })
+        .map(|((col_index, _), _)| *col_index)
+}
+
 /// Hit-test a CSV cell given window coordinates.
 ///
 /// Returns None if the click is outside the data grid (e.g., in headers or padding).
@@ -156,10 +287,7 @@ pub fn pixel_to_csv_cell(
     }
 
     let row_idx_in_view = ((local_y - layout.data_y as f64) / line_height as f64).floor() as usize;
-    let row = csv.viewport.top_row + row_idx_in_view;
-    if row >= csv.data.row_count() {
-        return None;
-    }
+    let row = csv.data_row(csv.viewport.top_row + row_idx_in_view)?;
 
     let cell_x_in_grid = local_x - layout.grid_x as f64;
 
@@ -199,6 +327,45 @@ mod tests {
         assert!(!is_number("12abc"));
     }
 
+    #[test]
+    fn test_pixel_to_csv_column_border() {
+        use crate::csv::{parse_csv, Delimiter};
+
+        let data = parse_csv("a,b\n1,2\n", Delimiter::Comma).unwrap();
+        let csv = CsvState::new(data, Delimiter::Comma);
+        let rect = Rect::new(0.0, 0.0, 800.0, 600.0);
+        let border = |x, y| pixel_to_csv_column_border(&csv, &rect, x, y, 20, 10.0, 30);
+
+        // Row header is 46px wide and each 4-char column 52px, so column A's
+        // right edge sits at x = 98 in the 20px header row below the tab bar
+        assert_eq!(border(98.0, 35.0), Some(0));
+        assert_eq!(border(101.0, 35.0), Some(0));
+        assert_eq!(border(150.0, 45.0), Some(1));
+        assert_eq!(border(70.0, 35.0), None);
+        assert_eq!(border(98.0, 60.0), None);
+    }
+
+    #[test]
+    fn test_pixel_to_csv_filter_pushes_cells_down() {
+        use crate::csv::{parse_csv, Delimiter};
+
+        let data = parse_csv("a,b\n1,2\n", Delimiter::Comma).unwrap();
+        let mut csv = CsvState::new(data, Delimiter::Comma);
+        let rect = Rect::new(0.0, 0.0, 800.0, 600.0);
+        let filter = |csv: &CsvState, x, y| pixel_to_csv_filter(csv, &rect, x, y, 20, 10.0, 30);
+        let cell = |csv: &CsvState, x, y| pixel_to_csv_cell(csv, &rect, x, y, 20, 10.0, 30);
+
+        assert_eq!(filter(&csv, 70.0, 55.0), None);
+        assert_eq!(cell(&csv, 70.0, 55.0), Some(CellPosition::new(0, 0)));
+
+        // The filter row takes the 20px under the column headers
+        csv.focus_filter(0);
+        assert_eq!(filter(&csv, 70.0, 55.0), Some(0));
+        assert_eq!(filter(&csv, 120.0, 69.0), Some(1));
+        assert_eq!(cell(&csv, 70.0, 55.0), None);
+        assert_eq!(cell(&csv, 70.0, 75.0), Some(CellPosition::new(0, 0)));
+    }
+
     #[test]
     fn test_truncate_text() {
         assert_eq!(truncate_text("hello", 10), "hello");
@@ -0,0 +1,126 @@
+//! Column width resizing for CSV mode
+//!
+//! Widths are kept in characters, like the ones worked out from the
+//! content. A width set by dragging a column's edge or by auto-fitting is
+//! remembered in `manual_widths`, so it survives the grid being re-read
+//! after an edit.
+
+use super::model::{ColumnResize, CsvState, MIN_COLUMN_WIDTH};
+
+/// Widest a column can be dragged or auto-fitted, in characters
+pub const MAX_COLUMN_WIDTH: usize = 200;
+
+impl CsvState {
+    /// Set a column's width (clamped to a sane range) and remember it
+    pub fn set_column_width(&mut self, col: usize, width: usize) {
+        let Some(column_width) = self.column_widths.get_mut(col) else {
+            return;
+        };
+        let width = width.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+        *column_width = width;
+        self.manual_widths.insert(col, width);
+    }
+
+    /// Width that fits every value in a column
+    pub fn fit_column_width(&self, col: usize) -> usize {
+        (0..self.data.row_count())
+            .map(|row| self.data.get(row, col).chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH)
+    }
+
+    /// Start dragging a column's right edge from mouse position `x`
+    pub fn start_column_resize(&mut self, col: usize, x: f64) {
+        if let Some(&start_width) = self.column_widths.get(col) {
+            self.column_resize = Some(ColumnResize {
+                col,
+                start_x: x,
+                start_width,
+            });
+        }
+    }
+
+    /// Follow the mouse to `x` while dragging a column's edge. Returns
+    /// whether the width changed.
+    pub fn update_column_resize(&mut self, x: f64, char_width: f32) -> bool {
+        let Some(resize) = self.column_resize else {
+            return false;
+        };
+        let delta = ((x - resize.start_x) / char_width.max(1.0) as f64).round() as i64;
+        let width = (resize.start_width as i64 + delta).max(0) as usize;
+        let before = self.column_widths.get(resize.col).copied();
+        self.set_column_width(resize.col, width);
+        before != self.column_widths.get(resize.col).copied()
+    }
+
+    /// Keep remembered widths with their columns when a column is inserted
+    /// (`inserted`) or deleted at `at`
+    pub fn shift_manual_widths(&mut self, at: usize, inserted: bool) {
+        self.manual_widths = self
+            .manual_widths
+            .drain()
+            .filter_map(|(col, width)| match col {
+                col if col < at => Some((col, width)),
+                col if inserted => Some((col + 1, width)),
+                col if col == at => None,
+                col => Some((col - 1, width)),
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::csv::{parse_csv, CsvState, Delimiter};
+
+    fn state(content: &str) -> CsvState {
+        CsvState::new(
+            parse_csv(content, Delimiter::Comma).unwrap(),
+            Delimiter::Comma,
+        )
+    }
+
+    #[test]
+    fn test_drag_resize_in_characters() {
+        let mut csv = state("name,age\nann,30\n");
+        assert_eq!(csv.column_widths, vec![4, 4]);
+
+        csv.start_column_resize(0, 100.0);
+        assert!(csv.update_column_resize(164.0, 8.0));
+        assert_eq!(csv.column_widths[0], 12);
+        // Can't be dragged narrower than the minimum
+        csv.update_column_resize(0.0, 8.0);
+        assert_eq!(csv.column_widths[0], 4);
+        assert!(!csv.update_column_resize(10.0, 8.0));
+        csv.column_resize = None;
+        assert!(!csv.update_column_resize(300.0, 8.0));
+    }
+
+    #[test]
+    fn test_manual_widths_survive_reparse() {
+        let mut csv = state("a,b\n1,2\n");
+        csv.set_column_width(1, 25);
+        csv.replace_data(parse_csv("a,b\n1,2\n3,4\n", Delimiter::Comma).unwrap());
+        assert_eq!(csv.column_widths, vec![4, 25]);
+
+        csv.shift_manual_widths(0, true);
+        assert_eq!(csv.manual_widths.get(&2), Some(&25));
+        csv.shift_manual_widths(1, false);
+        assert_eq!(csv.manual_widths.get(&1), Some(&25));
+        csv.shift_manual_widths(1, false);
+        assert!(csv.manual_widths.is_empty());
+    }
+
+    #[test]
+    fn test_fit_column_width_reads_every_row() {
+        let mut content = "h\n".to_string();
+        for _ in 0..150 {
+            content.push_str("x\n");
+        }
+        content.push_str("a much longer value at the end\n");
+        let csv = state(&content);
+        assert_eq!(csv.column_widths[0], 4);
+        assert_eq!(csv.fit_column_width(0), 30);
+    }
+}
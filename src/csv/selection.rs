@@ -0,0 +1,129 @@
+//! Cell range selection for CSV mode
+//!
+//! The range runs from an anchor cell to the selected cell (the head), like
+//! a text selection: Shift+arrows and mouse drags move the head while the
+//! anchor stays put, and any plain navigation drops the anchor again.
+
+use super::model::{CellPosition, CsvState, Delimiter};
+use super::parser::escape_csv_value;
+
+impl CsvState {
+    /// Grow or shrink the range by moving its head cell by delta
+    pub fn extend_selection(&mut self, delta_row: i32, delta_col: i32) {
+        let anchor = self.selection_anchor.unwrap_or(self.selected_cell);
+        self.move_selection(delta_row, delta_col);
+        self.selection_anchor = Some(anchor);
+    }
+
+    /// Move the range's head to a cell (Shift+click, mouse drag)
+    pub fn extend_selection_to(&mut self, row: usize, col: usize) {
+        let anchor = self.selection_anchor.unwrap_or(self.selected_cell);
+        self.select_cell(row, col);
+        self.selection_anchor = Some(anchor);
+    }
+
+    /// Whether more than the selected cell is selected
+    pub fn has_range(&self) -> bool {
+        self.selection_anchor
+            .is_some_and(|anchor| anchor != self.selected_cell)
+    }
+
+    /// Top-left and bottom-right corners of the selected range (both the
+    /// selected cell when there is no range)
+    pub fn selection_range(&self) -> (CellPosition, CellPosition) {
+        let anchor = self.selection_anchor.unwrap_or(self.selected_cell);
+        let head = self.selected_cell;
+        (
+            CellPosition::new(anchor.row.min(head.row), anchor.col.min(head.col)),
+            CellPosition::new(anchor.row.max(head.row), anchor.col.max(head.col)),
+        )
+    }
+
+    /// Data rows inside the selected range, leaving out rows a filter hides
+    pub fn selected_rows(&self) -> impl Iterator<Item = usize> + '_ {
+        let (start, end) = self.selection_range();
+        (self.view_row(start.row)..=self.view_row(end.row))
+            .filter_map(|view_row| self.data_row(view_row))
+            .filter(move |row| (start.row..=end.row).contains(row))
+    }
+
+    /// Whether a cell lies inside the selected range
+    pub fn is_cell_selected(&self, row: usize, col: usize) -> bool {
+        let (start, end) = self.selection_range();
+        (start.col..=end.col).contains(&col) && self.selected_rows().any(|r| r == row)
+    }
+
+    /// The selected range as tab-separated values, one line per row, the
+    /// way spreadsheets paste it. Cells with tabs, quotes or line breaks
+    /// are quoted.
+    pub fn selection_as_tsv(&self) -> String {
+        let (start, end) = self.selection_range();
+        self.selected_rows()
+            .map(|row| {
+                (start.col..=end.col)
+                    .map(|col| escape_csv_value(self.data.get(row, col), Delimiter::Tab))
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::csv::{parse_csv, CellPosition, CsvState, Delimiter};
+
+    fn state(content: &str) -> CsvState {
+        CsvState::new(
+            parse_csv(content, Delimiter::Comma).unwrap(),
+            Delimiter::Comma,
+        )
+    }
+
+    #[test]
+    fn test_extend_selection_keeps_anchor() {
+        let mut csv = state("a,b,c\n1,2,3\n4,5,6\n");
+        csv.select_cell(1, 1);
+        csv.extend_selection(1, 0);
+        csv.extend_selection(0, -1);
+        assert!(csv.has_range());
+        assert_eq!(
+            csv.selection_range(),
+            (CellPosition::new(1, 0), CellPosition::new(2, 1))
+        );
+        assert!(csv.is_cell_selected(2, 0));
+        assert!(!csv.is_cell_selected(0, 0));
+
+        // Plain movement drops the range
+        csv.move_selection(-1, 0);
+        assert!(!csv.has_range());
+        assert_eq!(csv.selection_range().0, csv.selected_cell);
+    }
+
+    #[test]
+    fn test_extend_selection_to_backwards() {
+        let mut csv = state("a,b,c\n1,2,3\n4,5,6\n");
+        csv.select_cell(2, 2);
+        csv.extend_selection_to(0, 1);
+        assert_eq!(
+            csv.selection_range(),
+            (CellPosition::new(0, 1), CellPosition::new(2, 2))
+        );
+        assert_eq!(csv.selected_cell, CellPosition::new(0, 1));
+    }
+
+    #[test]
+    fn test_selection_as_tsv() {
+        let mut csv = state("a,b,c\n1,\"x\ty\",3\n4,\"say \"\"hi\"\"\",6\n");
+        csv.select_cell(0, 0);
+        csv.extend_selection_to(2, 1);
+        assert_eq!(
+            csv.selection_as_tsv(),
+            "a\tb\n1\t\"x\ty\"\n4\t\"say \"\"hi\"\"\""
+        );
+
+        csv.select_cell(1, 2);
+        assert_eq!(csv.selection_as_tsv(), "3");
+    }
+}
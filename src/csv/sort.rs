@@ -0,0 +1,122 @@
+//! Column sorting
+//!
+//! Sorting works out a new row order from the grid, then moves whole
+//! records around in the document text. Each record keeps its raw text
+//! (quoting, spacing), so the only change to the file is the order of its
+//! rows.
+
+use std::cmp::Ordering;
+
+use super::model::{CsvData, Delimiter};
+use super::writeback::record_byte_ranges;
+
+/// Compare two cell values for sorting
+///
+/// Numbers compare by value and come before text; text compares
+/// case-insensitively, falling back to the exact text for a stable tie
+/// break. Empty cells come last.
+pub fn compare_cells(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.trim(), b.trim());
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        (false, false) => {}
+    }
+    // "nan" and "inf" parse as floats but read as words
+    let number = |s: &str| s.parse::<f64>().ok().filter(|n| n.is_finite());
+    match (number(a), number(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a
+            .to_lowercase()
+            .cmp(&b.to_lowercase())
+            .then_with(|| a.cmp(b)),
+    }
+}
+
+/// Row indices of `data` in the order sorting by `col` puts them
+///
+/// The sort is stable, so rows with equal values keep their order. Empty
+/// cells stay at the bottom in both directions. With `skip_header` the
+/// first row stays where it is.
+pub fn sorted_row_order(
+    data: &CsvData,
+    col: usize,
+    ascending: bool,
+    skip_header: bool,
+) -> Vec<usize> {
+    let first = usize::from(skip_header).min(data.row_count());
+    let mut order: Vec<usize> = (first..data.row_count()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (data.get(a, col), data.get(b, col));
+        let blank = (a.trim().is_empty(), b.trim().is_empty());
+        if ascending || blank.0 || blank.1 {
+            compare_cells(a, b)
+        } else {
+            compare_cells(b, a)
+        }
+    });
+    (0..first).chain(order).collect()
+}
+
+/// `content` with its records rearranged so that record `order[i]` ends up
+/// in the `i`-th place. Line breaks and blank lines between records stay
+/// where they are. `None` when `order` doesn't cover every record.
+pub fn reorder_records(content: &str, order: &[usize], delimiter: Delimiter) -> Option<String> {
+    let records = record_byte_ranges(content, delimiter);
+    if records.len() != order.len() {
+        return None;
+    }
+
+    let mut result = String::with_capacity(content.len());
+    let mut copied = 0;
+    for (slot, &from) in records.iter().zip(order) {
+        result.push_str(&content[copied..slot.start]);
+        result.push_str(&content[records.get(from)?.clone()]);
+        copied = slot.end;
+    }
+    result.push_str(&content[copied..]);
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv::parse_csv;
+
+    fn order(content: &str, col: usize, ascending: bool, skip_header: bool) -> Vec<usize> {
+        let data = parse_csv(content, Delimiter::Comma).unwrap();
+        sorted_row_order(&data, col, ascending, skip_header)
+    }
+
+    #[test]
+    fn test_compare_cells_numbers_by_value() {
+        assert_eq!(compare_cells("9", "10"), Ordering::Less);
+        assert_eq!(compare_cells("-1.5", "-2"), Ordering::Greater);
+        assert_eq!(compare_cells("42", "apple"), Ordering::Less);
+        assert_eq!(compare_cells("apple", "Banana"), Ordering::Less);
+        assert_eq!(compare_cells("", "apple"), Ordering::Greater);
+        assert_eq!(compare_cells("nan", "banana"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_sorted_row_order_keeps_header_and_is_stable() {
+        let content = "name,age\nann,30\nbob,9\ncid,30\ndan,\n";
+        assert_eq!(order(content, 1, true, true), vec![0, 2, 1, 3, 4]);
+        // Equal values keep their order and blanks stay last when descending
+        assert_eq!(order(content, 1, false, true), vec![0, 1, 3, 2, 4]);
+        assert_eq!(order(content, 0, false, false), vec![0, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_reorder_records_moves_raw_rows() {
+        let content = "h1,h2\r\n\"b, x\",2\r\n\r\na,\"multi\nline\"\r\n";
+        assert_eq!(
+            reorder_records(content, &[0, 2, 1], Delimiter::Comma).unwrap(),
+            "h1,h2\r\na,\"multi\nline\"\r\n\r\n\"b, x\",2\r\n"
+        );
+        assert_eq!(reorder_records(content, &[0, 1], Delimiter::Comma), None);
+    }
+}
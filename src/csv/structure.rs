@@ -0,0 +1,167 @@
+//! Row and column insertion and deletion
+//!
+//! Like cell write-back, these change the document text in place: a new row
+//! or column is empty cells joined with the file's delimiter, and every
+//! record that isn't touched keeps its raw text.
+
+use super::model::Delimiter;
+use super::writeback::{apply_writes, cell_byte_ranges, record_byte_ranges};
+
+/// The line break the file uses between records
+fn line_ending(content: &str) -> &'static str {
+    if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Raw text of a record with `column_count` empty cells. A single empty
+/// cell is written as `""`, since an empty line is skipped as blank.
+fn empty_record(column_count: usize, delimiter: Delimiter) -> String {
+    if column_count <= 1 {
+        "\"\"".to_string()
+    } else {
+        delimiter.char().to_string().repeat(column_count - 1)
+    }
+}
+
+/// `content` with an empty row inserted so that it becomes row `at`
+/// (`at` may be the row count, to append). `None` past the end.
+pub fn insert_row(
+    content: &str,
+    at: usize,
+    column_count: usize,
+    delimiter: Delimiter,
+) -> Option<String> {
+    let records = record_byte_ranges(content, delimiter);
+    if at > records.len() {
+        return None;
+    }
+    let record = empty_record(column_count, delimiter);
+    let eol = line_ending(content);
+    let write = if let Some(next) = records.get(at) {
+        (next.start..next.start, format!("{record}{eol}"))
+    } else if let Some(last) = records.last() {
+        (last.end..last.end, format!("{eol}{record}"))
+    } else {
+        (content.len()..content.len(), record)
+    };
+    Some(apply_writes(content, vec![write]))
+}
+
+/// `content` without row `row` and its line break
+pub fn delete_row(content: &str, row: usize, delimiter: Delimiter) -> Option<String> {
+    let records = record_byte_ranges(content, delimiter);
+    let record = records.get(row)?;
+    let range = if let Some(next) = records.get(row + 1) {
+        record.start..next.start
+    } else if let Some(previous) = row.checked_sub(1).and_then(|row| records.get(row)) {
+        previous.end..record.end
+    } else {
+        record.clone()
+    };
+    Some(apply_writes(content, vec![(range, String::new())]))
+}
+
+/// `content` with an empty cell inserted at column `at` in every row that
+/// reaches it (`at` may be one past a row's last cell, to append)
+pub fn insert_column(content: &str, at: usize, delimiter: Delimiter) -> String {
+    let delim = delimiter.char().to_string();
+    let writes = cell_byte_ranges(content, delimiter)
+        .iter()
+        .filter_map(|cells| {
+            let offset = match cells.get(at) {
+                Some(cell) => cell.start,
+                None if cells.len() == at => cells.last()?.end,
+                None => return None,
+            };
+            Some((offset..offset, delim.clone()))
+        })
+        .collect();
+    apply_writes(content, writes)
+}
+
+/// `content` with the cell in column `col` removed from every row that has
+/// it, along with one delimiter next to it
+pub fn delete_column(content: &str, col: usize, delimiter: Delimiter) -> String {
+    let writes = cell_byte_ranges(content, delimiter)
+        .iter()
+        .filter_map(|cells| {
+            let cell = cells.get(col)?;
+            let write = if let Some(next) = cells.get(col + 1) {
+                (cell.start..next.start, String::new())
+            } else if col > 0 {
+                (cells[col - 1].end..cell.end, String::new())
+            } else {
+                // The row's only cell: keep the row, now with one empty cell
+                (cell.clone(), "\"\"".to_string())
+            };
+            Some(write)
+        })
+        .collect();
+    apply_writes(content, writes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMA: Delimiter = Delimiter::Comma;
+
+    #[test]
+    fn test_insert_row_above_below_and_at_end() {
+        let content = "a,b,c\n1,2,3\n";
+        assert_eq!(
+            insert_row(content, 1, 3, COMMA).unwrap(),
+            "a,b,c\n,,\n1,2,3\n"
+        );
+        assert_eq!(
+            insert_row(content, 2, 3, COMMA).unwrap(),
+            "a,b,c\n1,2,3\n,,\n"
+        );
+        assert_eq!(
+            insert_row("a;b\r\nc;d", 2, 2, Delimiter::Semicolon).unwrap(),
+            "a;b\r\nc;d\r\n;"
+        );
+        assert_eq!(insert_row("x\n", 0, 1, COMMA).unwrap(), "\"\"\nx\n");
+        assert_eq!(insert_row(content, 3, 3, COMMA), None);
+    }
+
+    #[test]
+    fn test_delete_row_takes_its_line_break() {
+        let content = "a,b\n\"multi\nline\",2\nc,d\n";
+        assert_eq!(delete_row(content, 1, COMMA).unwrap(), "a,b\nc,d\n");
+        assert_eq!(
+            delete_row(content, 2, COMMA).unwrap(),
+            "a,b\n\"multi\nline\",2\n"
+        );
+        assert_eq!(delete_row(content, 3, COMMA), None);
+    }
+
+    #[test]
+    fn test_insert_column_left_right_and_ragged_rows() {
+        let content = "a,b\n1,\"x,y\"\nshort\n";
+        assert_eq!(
+            insert_column(content, 0, COMMA),
+            ",a,b\n,1,\"x,y\"\n,short\n"
+        );
+        assert_eq!(
+            insert_column(content, 1, COMMA),
+            "a,,b\n1,,\"x,y\"\nshort,\n"
+        );
+        assert_eq!(
+            insert_column(content, 2, COMMA),
+            "a,b,\n1,\"x,y\",\nshort\n"
+        );
+    }
+
+    #[test]
+    fn test_delete_column_with_its_delimiter() {
+        let content = "a\tb\tc\n1\t2\t3\nonly\n";
+        let tab = Delimiter::Tab;
+        assert_eq!(delete_column(content, 0, tab), "b\tc\n2\t3\n\"\"\n");
+        assert_eq!(delete_column(content, 1, tab), "a\tc\n1\t3\nonly\n");
+        assert_eq!(delete_column(content, 2, tab), "a\tb\n1\t2\nonly\n");
+    }
+}
@@ -0,0 +1,283 @@
+//! Cell-aware write-back from the grid to the document text
+//!
+//! Cell editing and find/replace in CSV mode change cell values, not raw
+//! text. This layer finds where each cell sits in the file and escapes its
+//! new value for that spot, so the file stays valid CSV and everything
+//! outside the changed cells is left byte-for-byte as it was.
+
+use std::ops::Range;
+
+use super::model::{CellPosition, Delimiter};
+use super::parser::escape_csv_value;
+
+/// Byte ranges of every cell's raw text (quotes included), row by row
+///
+/// Quote-aware, so quoted cells may hold delimiters and line breaks. Rows
+/// are numbered like [`parse_csv`](super::parse_csv) numbers them: blank
+/// lines are skipped and a trailing `\r` is not part of the last cell.
+pub fn cell_byte_ranges(content: &str, delimiter: Delimiter) -> Vec<Vec<Range<usize>>> {
+    let delim = delimiter.char();
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field_start = 0;
+    let mut in_quotes = false;
+
+    let mut end_row = |row: &mut Vec<Range<usize>>| {
+        let row = std::mem::take(row);
+        let blank = row.len() == 1 && row[0].is_empty();
+        if !blank {
+            rows.push(row);
+        }
+    };
+
+    for (i, ch) in content.char_indices() {
+        if ch == '"' {
+            in_quotes = !in_quotes;
+        } else if ch == delim && !in_quotes {
+            row.push(field_start..i);
+            field_start = i + ch.len_utf8();
+        } else if ch == '\n' && !in_quotes {
+            let end = if content[..i].ends_with('\r') {
+                (i - 1).max(field_start)
+            } else {
+                i
+            };
+            row.push(field_start..end);
+            end_row(&mut row);
+            field_start = i + 1;
+        }
+    }
+    if field_start < content.len() || !row.is_empty() {
+        row.push(field_start..content.len());
+        end_row(&mut row);
+    }
+
+    rows
+}
+
+/// Byte range of every record's raw text, from the start of its first cell
+/// to the end of its last (line break not included)
+pub(super) fn record_byte_ranges(content: &str, delimiter: Delimiter) -> Vec<Range<usize>> {
+    cell_byte_ranges(content, delimiter)
+        .iter()
+        .filter_map(|cells| Some(cells.first()?.start..cells.last()?.end))
+        .collect()
+}
+
+/// `content` with `writes` applied, each replacing a byte range with new
+/// text. The ranges must not overlap.
+pub(super) fn apply_writes(content: &str, mut writes: Vec<(Range<usize>, String)>) -> String {
+    // From the end, so earlier ranges stay valid
+    writes.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    let mut result = content.to_string();
+    for (range, text) in writes {
+        result.replace_range(range, &text);
+    }
+    result
+}
+
+/// Byte range of the cell at `position` in `content`
+pub fn cell_byte_range(
+    content: &str,
+    position: CellPosition,
+    delimiter: Delimiter,
+) -> Option<Range<usize>> {
+    cell_byte_ranges(content, delimiter)
+        .get(position.row)?
+        .get(position.col)
+        .cloned()
+}
+
+/// Raw text for `value` in a cell that currently reads `raw`
+///
+/// A cell that was quoted stays quoted; otherwise quotes are added only
+/// when the new value needs them (delimiter, quote or line break).
+pub fn escape_cell(raw: &str, value: &str, delimiter: Delimiter) -> String {
+    if raw.starts_with('"') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        escape_csv_value(value, delimiter)
+    }
+}
+
+/// Where to write a cell's new value: its byte range in the document and
+/// the escaped text to put there
+pub fn cell_write(
+    content: &str,
+    position: CellPosition,
+    value: &str,
+    delimiter: Delimiter,
+) -> Option<(Range<usize>, String)> {
+    let range = cell_byte_range(content, position, delimiter)?;
+    let text = escape_cell(&content[range.clone()], value, delimiter);
+    Some((range, text))
+}
+
+/// `content` with each cell in `edits` set to its new value. Cells that
+/// don't exist in the text are skipped.
+pub fn write_cells(
+    content: &str,
+    edits: &[(CellPosition, String)],
+    delimiter: Delimiter,
+) -> String {
+    let ranges = cell_byte_ranges(content, delimiter);
+    let writes = edits
+        .iter()
+        .filter_map(|(position, value)| {
+            let range = ranges.get(position.row)?.get(position.col)?.clone();
+            let text = escape_cell(&content[range.clone()], value, delimiter);
+            Some((range, text))
+        })
+        .collect();
+    apply_writes(content, writes)
+}
+
+/// `value` with every match of `query` replaced, or `None` when nothing
+/// matched
+pub fn replace_in_cell(
+    value: &str,
+    query: &str,
+    replacement: &str,
+    case_sensitive: bool,
+) -> Option<String> {
+    if query.is_empty() {
+        return None;
+    }
+    let query: Vec<char> = query.chars().collect();
+    let chars: Vec<char> = value.chars().collect();
+    let same = |a: char, b: char| {
+        if case_sensitive {
+            a == b
+        } else {
+            a.to_lowercase().eq(b.to_lowercase())
+        }
+    };
+
+    let mut result = String::with_capacity(value.len());
+    let mut replaced = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let is_match = chars.len() - i >= query.len()
+            && chars[i..i + query.len()]
+                .iter()
+                .zip(&query)
+                .all(|(&a, &b)| same(a, b));
+        if is_match {
+            result.push_str(replacement);
+            i += query.len();
+            replaced = true;
+        } else {
+            result.push(chars[i]);
+            i += 1;
+        }
+    }
+
+    replaced.then_some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv::parse_csv;
+
+    fn range(content: &str, row: usize, col: usize, delimiter: Delimiter) -> Option<&str> {
+        cell_byte_range(content, CellPosition::new(row, col), delimiter).map(|r| &content[r])
+    }
+
+    #[test]
+    fn test_cell_byte_range_rows_and_fields() {
+        let content = "a,b,c\nalice,30,engineer\nx,y,z";
+
+        assert_eq!(
+            cell_byte_range(content, CellPosition::new(0, 0), Delimiter::Comma),
+            Some(0..1)
+        );
+        assert_eq!(range(content, 1, 0, Delimiter::Comma), Some("alice"));
+        assert_eq!(range(content, 1, 1, Delimiter::Comma), Some("30"));
+        assert_eq!(range(content, 1, 2, Delimiter::Comma), Some("engineer"));
+        assert_eq!(range(content, 1, 3, Delimiter::Comma), None);
+        assert_eq!(range(content, 2, 2, Delimiter::Comma), Some("z"));
+        assert_eq!(range(content, 3, 0, Delimiter::Comma), None);
+    }
+
+    #[test]
+    fn test_cell_byte_range_quoted() {
+        let content =
+            "\"hello, world\",test,123\n\"two\nlines\",\"say \"\"hi\"\"\",x\r\nlast,1\r\n";
+
+        assert_eq!(
+            range(content, 0, 0, Delimiter::Comma),
+            Some("\"hello, world\"")
+        );
+        assert_eq!(range(content, 0, 1, Delimiter::Comma), Some("test"));
+        assert_eq!(range(content, 0, 2, Delimiter::Comma), Some("123"));
+        assert_eq!(
+            range(content, 1, 0, Delimiter::Comma),
+            Some("\"two\nlines\"")
+        );
+        assert_eq!(
+            range(content, 1, 1, Delimiter::Comma),
+            Some("\"say \"\"hi\"\"\"")
+        );
+        assert_eq!(range(content, 1, 2, Delimiter::Comma), Some("x"));
+        assert_eq!(range(content, 2, 1, Delimiter::Comma), Some("1"));
+    }
+
+    #[test]
+    fn test_cell_byte_range_tab_delimiter() {
+        let content = "a\tb\tc";
+
+        assert_eq!(range(content, 0, 0, Delimiter::Tab), Some("a"));
+        assert_eq!(range(content, 0, 1, Delimiter::Tab), Some("b"));
+        assert_eq!(range(content, 0, 2, Delimiter::Tab), Some("c"));
+    }
+
+    #[test]
+    fn test_rows_match_parser_with_blank_lines() {
+        let content = "a,b\n\nc,d\n\n";
+        let data = parse_csv(content, Delimiter::Comma).unwrap();
+        let ranges = cell_byte_ranges(content, Delimiter::Comma);
+
+        assert_eq!(ranges.len(), data.row_count());
+        assert_eq!(range(content, 1, 1, Delimiter::Comma), Some(data.get(1, 1)));
+    }
+
+    #[test]
+    fn test_write_cells_requotes_per_cell() {
+        let content = "name,note\nbob,\"plain\"\nann,hi\n";
+        let edits = vec![
+            (CellPosition::new(2, 1), "hi, there".to_string()),
+            (CellPosition::new(1, 1), "said \"no\"".to_string()),
+            (CellPosition::new(1, 0), "rob".to_string()),
+            (CellPosition::new(9, 0), "missing".to_string()),
+        ];
+
+        let written = write_cells(content, &edits, Delimiter::Comma);
+        assert_eq!(
+            written,
+            "name,note\nrob,\"said \"\"no\"\"\"\nann,\"hi, there\"\n"
+        );
+
+        let data = parse_csv(&written, Delimiter::Comma).unwrap();
+        assert_eq!(data.get(1, 1), "said \"no\"");
+        assert_eq!(data.get(2, 1), "hi, there");
+        assert_eq!(data.column_count(), 2);
+    }
+
+    #[test]
+    fn test_replace_in_cell() {
+        assert_eq!(
+            replace_in_cell("Foo foo", "foo", "bar", true),
+            Some("Foo bar".to_string())
+        );
+        assert_eq!(
+            replace_in_cell("Foo foo", "FOO", "bar", false),
+            Some("bar bar".to_string())
+        );
+        assert_eq!(
+            replace_in_cell("café", "É", "e", false),
+            Some("cafe".to_string())
+        );
+        assert_eq!(replace_in_cell("abc", "x", "y", false), None);
+    }
+}
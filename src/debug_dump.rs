@@ -111,6 +111,7 @@ pub struct TabDump {
     pub editor_id: u64,
     pub is_pinned: bool,
     pub is_preview: bool,
+    pub color: Option<token::model::TabColor>,
 }
 
 #[derive(Serialize)]
@@ -285,6 +286,7 @@ impl EditorAreaDump {
                                 editor_id: t.editor_id.0,
                                 is_pinned: t.is_pinned,
                                 is_preview: t.is_preview,
+                                color: t.color,
                             })
                             .collect(),
                         active_tab_index: group.active_tab_index,
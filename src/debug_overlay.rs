@@ -1,6 +1,9 @@
 //! In-editor debug overlay for real-time state visibility
 //!
-//! Toggle with F8 in debug builds.
+//! Toggle with F8 in debug builds. Shift+F8 makes it interactive: Up/Down
+//! scrub through the message timeline, P pins a snapshot of the focused
+//! editor's state to compare against, Escape leaves. F9 pauses redraws
+//! (frame-step mode) and F10 draws a single frame while paused.
 
 use std::collections::VecDeque;
 use std::time::Instant;
@@ -13,6 +16,9 @@ const MESSAGE_HISTORY_SIZE: usize = 50;
 /// Maximum number of syntax events to retain
 const SYNTAX_EVENT_HISTORY_SIZE: usize = 20;
 
+/// Timeline rows shown at once
+const TIMELINE_ROWS: usize = 10;
+
 #[derive(Debug)]
 pub struct DebugOverlay {
     /// Whether the overlay is visible
@@ -29,15 +35,39 @@ pub struct DebugOverlay {
     pub message_history: VecDeque<MessageEntry>,
     /// Recent syntax events history
     pub syntax_events: VecDeque<SyntaxEventEntry>,
+    /// Keyboard drives the timeline and pinning instead of the editor
+    pub interactive: bool,
+    /// Selected timeline entry, counted back from the newest (0 = newest)
+    pub timeline_selected: usize,
+    /// Editor state captured with P, shown next to the live values
+    pub pinned: Option<PinnedState>,
+    /// Frame-step mode: redraws are paused until a step is requested
+    pub frame_step: bool,
+    /// A single frame may be drawn while in frame-step mode
+    step_requested: bool,
+    /// Sequence number for the next recorded message
+    next_seq: u64,
 }
 
 #[derive(Debug, Clone)]
 pub struct MessageEntry {
+    /// Position in the stream of messages, starting at 0
+    pub seq: u64,
     pub timestamp: Instant,
     pub msg_type: String,
     pub cursor_diff: Option<String>,
 }
 
+/// Snapshot of the focused editor's state taken with [`DebugOverlay::pin`]
+#[derive(Debug, Clone)]
+pub struct PinnedState {
+    /// Sequence number of the last message processed before the pin
+    pub after_seq: Option<u64>,
+    pub timestamp: Instant,
+    /// Rendered state lines (cursors, selections, viewport)
+    pub lines: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SyntaxEventEntry {
     pub timestamp: Instant,
@@ -73,12 +103,84 @@ impl DebugOverlay {
             show_syntax: true,
             message_history: VecDeque::with_capacity(MESSAGE_HISTORY_SIZE),
             syntax_events: VecDeque::with_capacity(SYNTAX_EVENT_HISTORY_SIZE),
+            interactive: false,
+            timeline_selected: 0,
+            pinned: None,
+            frame_step: false,
+            step_requested: false,
+            next_seq: 0,
         }
     }
 
     /// Toggle overlay visibility
     pub fn toggle(&mut self) {
         self.visible = !self.visible;
+        if !self.visible {
+            self.interactive = false;
+        }
+    }
+
+    /// Toggle interactive mode, showing the overlay when entering it
+    pub fn toggle_interactive(&mut self) {
+        self.interactive = !self.interactive;
+        if self.interactive {
+            self.visible = true;
+        }
+    }
+
+    /// Move the timeline selection towards older messages
+    pub fn select_older(&mut self) {
+        let oldest = self.message_history.len().saturating_sub(1);
+        self.timeline_selected = (self.timeline_selected + 1).min(oldest);
+    }
+
+    /// Move the timeline selection towards newer messages
+    pub fn select_newer(&mut self) {
+        self.timeline_selected = self.timeline_selected.saturating_sub(1);
+    }
+
+    /// The selected timeline entry
+    pub fn selected_message(&self) -> Option<&MessageEntry> {
+        let index = self
+            .message_history
+            .len()
+            .checked_sub(self.timeline_selected + 1)?;
+        self.message_history.get(index)
+    }
+
+    /// Pin the focused editor's current state, or unpin if already pinned
+    pub fn toggle_pin(&mut self, editor: Option<&EditorState>) {
+        if self.pinned.take().is_some() {
+            return;
+        }
+        let Some(editor) = editor else {
+            return;
+        };
+        self.pinned = Some(PinnedState {
+            after_seq: self.message_history.back().map(|entry| entry.seq),
+            timestamp: Instant::now(),
+            lines: self.render_editor_state(editor),
+        });
+    }
+
+    /// Toggle frame-step mode (paused redraws)
+    pub fn toggle_frame_step(&mut self) {
+        self.frame_step = !self.frame_step;
+        // Draw one more frame so the paused state shows
+        self.step_requested = self.frame_step;
+    }
+
+    /// Allow one frame to be drawn while in frame-step mode
+    pub fn request_step(&mut self) {
+        self.step_requested = true;
+    }
+
+    /// Whether a frame may be drawn now; consumes a requested step
+    pub fn take_frame_permit(&mut self) -> bool {
+        if !self.frame_step {
+            return true;
+        }
+        std::mem::take(&mut self.step_requested)
     }
 
     /// Record a message in history
@@ -87,10 +189,16 @@ impl DebugOverlay {
             self.message_history.pop_front();
         }
         self.message_history.push_back(MessageEntry {
+            seq: self.next_seq,
             timestamp: Instant::now(),
             msg_type,
             cursor_diff,
         });
+        self.next_seq += 1;
+        // Keep a scrubbed-back selection on the same message
+        if self.timeline_selected > 0 {
+            self.select_older();
+        }
     }
 
     /// Record a syntax event
@@ -119,7 +227,14 @@ impl DebugOverlay {
             return Vec::new();
         }
 
-        let mut lines = vec!["─── DEBUG OVERLAY (F8 to hide) ───".to_string()];
+        let mut lines = vec![if self.interactive {
+            "─── DEBUG INSPECTOR (↑↓ scrub, P pin, Esc leave) ───".to_string()
+        } else {
+            "─── DEBUG OVERLAY (F8 to hide, Shift+F8 inspect) ───".to_string()
+        }];
+        if self.frame_step {
+            lines.push("⏸ FRAME STEP: redraws paused (F10 step, F9 resume)".to_string());
+        }
 
         // Focus & Hover info (always shown at top)
         lines.push(String::new());
@@ -142,15 +257,81 @@ impl DebugOverlay {
             lines.extend(self.render_syntax_info(model));
         }
 
+        if let Some(ref pinned) = self.pinned {
+            lines.push(String::new());
+            let after = pinned
+                .after_seq
+                .map(|seq| format!("after #{}", seq))
+                .unwrap_or_else(|| "before any message".to_string());
+            lines.push(format!(
+                "Pinned ({}, {}ms ago):",
+                after,
+                pinned.timestamp.elapsed().as_millis()
+            ));
+            lines.extend(pinned.lines.iter().map(|line| format!("  {}", line)));
+            if let Some(editor) = model.focused_editor() {
+                let current = self.render_editor_state(editor);
+                let changed = current != pinned.lines;
+                lines.push(format!(
+                    "  {}",
+                    if changed {
+                        "≠ differs from current state"
+                    } else {
+                        "= matches current state"
+                    }
+                ));
+            }
+        }
+
         if self.show_messages && !self.message_history.is_empty() {
             lines.push(String::new());
-            lines.push("Recent Messages:".to_string());
-            for entry in self.message_history.iter().rev().take(10) {
-                let age_ms = entry.timestamp.elapsed().as_millis();
-                let diff_str = entry.cursor_diff.as_deref().unwrap_or("-");
+            lines.extend(self.render_timeline());
+        }
+
+        lines
+    }
+
+    /// The message timeline: a window of entries around the selection,
+    /// newest first, then the selected entry's details
+    fn render_timeline(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Messages ({} kept, {} total):",
+            self.message_history.len(),
+            self.next_seq
+        )];
+
+        let first = self
+            .timeline_selected
+            .saturating_sub(TIMELINE_ROWS / 2)
+            .min(self.message_history.len().saturating_sub(TIMELINE_ROWS));
+        for (back, entry) in self
+            .message_history
+            .iter()
+            .rev()
+            .enumerate()
+            .skip(first)
+            .take(TIMELINE_ROWS)
+        {
+            let marker = if self.interactive && back == self.timeline_selected {
+                "▶"
+            } else {
+                " "
+            };
+            let age_ms = entry.timestamp.elapsed().as_millis();
+            let diff_str = entry.cursor_diff.as_deref().unwrap_or("-");
+            lines.push(format!(
+                " {} #{:<5} [{:>5}ms] {} → {}",
+                marker, entry.seq, age_ms, entry.msg_type, diff_str
+            ));
+        }
+
+        if self.interactive {
+            if let Some(entry) = self.selected_message() {
+                lines.push(format!("Selected #{}:", entry.seq));
+                lines.push(format!("  {}", entry.msg_type));
                 lines.push(format!(
-                    "  [{:>4}ms] {} → {}",
-                    age_ms, entry.msg_type, diff_str
+                    "  cursors: {}",
+                    entry.cursor_diff.as_deref().unwrap_or("unchanged")
                 ));
             }
         }
@@ -158,6 +339,21 @@ impl DebugOverlay {
         lines
     }
 
+    /// Cursor, selection and viewport values of `editor`
+    fn render_editor_state(&self, editor: &EditorState) -> Vec<String> {
+        let mut lines = self.render_cursor_info(editor);
+        lines.extend(self.render_selection_info(editor));
+        let viewport = &editor.viewport;
+        lines.push(format!(
+            "Viewport: top {} left {} ({}×{})",
+            viewport.top_line,
+            viewport.left_column,
+            viewport.visible_columns,
+            viewport.visible_lines
+        ));
+        lines
+    }
+
     fn render_cursor_info(&self, editor: &EditorState) -> Vec<String> {
         let mut lines = vec![format!(
             "Cursors: {} (active: #{})",
@@ -294,3 +490,42 @@ impl DebugOverlay {
         lines
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeline_selection_follows_its_message() {
+        let mut overlay = DebugOverlay::new();
+        for name in ["a", "b", "c"] {
+            overlay.record_message(name.to_string(), None);
+        }
+        assert_eq!(overlay.selected_message().unwrap().msg_type, "c");
+
+        overlay.select_older();
+        overlay.select_older();
+        overlay.select_older();
+        assert_eq!(overlay.selected_message().unwrap().msg_type, "a");
+
+        overlay.select_newer();
+        overlay.record_message("d".to_string(), None);
+        assert_eq!(overlay.selected_message().unwrap().msg_type, "b");
+    }
+
+    #[test]
+    fn frame_step_draws_only_requested_frames() {
+        let mut overlay = DebugOverlay::new();
+        assert!(overlay.take_frame_permit());
+
+        overlay.toggle_frame_step();
+        assert!(overlay.take_frame_permit());
+        assert!(!overlay.take_frame_permit());
+        overlay.request_step();
+        assert!(overlay.take_frame_permit());
+        assert!(!overlay.take_frame_permit());
+
+        overlay.toggle_frame_step();
+        assert!(overlay.take_frame_permit());
+    }
+}
@@ -0,0 +1,211 @@
+//! Row alignment for the diff view
+
+use std::ops::Range;
+
+use crate::git::matched_lines;
+
+/// How a row of the diff view differs between its two sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    Unchanged,
+    /// Only on the right
+    Added,
+    /// Only on the left
+    Removed,
+    /// On both sides, with the differing characters marked
+    Modified,
+}
+
+/// One side of a diff row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    /// Line index in its text
+    pub line: usize,
+    pub text: String,
+    /// Characters (not bytes) that differ from the other side, on modified
+    /// rows
+    pub changed: Option<Range<usize>>,
+}
+
+/// A row of the diff view; a side is `None` where the other side's line
+/// has no counterpart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRow {
+    pub kind: RowKind,
+    pub left: Option<DiffLine>,
+    pub right: Option<DiffLine>,
+}
+
+impl DiffRow {
+    fn unchanged(left_line: usize, right_line: usize, text: &str) -> Self {
+        Self {
+            kind: RowKind::Unchanged,
+            left: Some(DiffLine::plain(left_line, text)),
+            right: Some(DiffLine::plain(right_line, text)),
+        }
+    }
+
+    /// `old` and `new` side by side, marking where they differ
+    fn modified(left_line: usize, old: &str, right_line: usize, new: &str) -> Self {
+        if old == new {
+            return Self::unchanged(left_line, right_line, old);
+        }
+        let (left, right) = changed_chars(old, new);
+        Self {
+            kind: RowKind::Modified,
+            left: Some(DiffLine {
+                changed: Some(left),
+                ..DiffLine::plain(left_line, old)
+            }),
+            right: Some(DiffLine {
+                changed: Some(right),
+                ..DiffLine::plain(right_line, new)
+            }),
+        }
+    }
+}
+
+impl DiffLine {
+    fn plain(line: usize, text: &str) -> Self {
+        Self {
+            line,
+            text: text.to_string(),
+            changed: None,
+        }
+    }
+}
+
+/// Rows lining the lines of `old` (left) up with those of `new` (right)
+pub fn diff_rows(old: &str, new: &str) -> Vec<DiffRow> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    let mut rows = Vec::with_capacity(old.len().max(new.len()));
+    rows.extend((0..prefix).map(|line| DiffRow::unchanged(line, line, old[line])));
+
+    // With too many changes to search, everything between the common prefix
+    // and suffix is one gap
+    let matches = matched_lines(a, b).unwrap_or_default();
+    let (mut x, mut y) = (0, 0);
+    for (match_x, match_y) in matches.into_iter().chain([(a.len(), b.len())]) {
+        push_gap(
+            &mut rows,
+            prefix + x,
+            &a[x..match_x],
+            prefix + y,
+            &b[y..match_y],
+        );
+        if match_x < a.len() {
+            rows.push(DiffRow::unchanged(
+                prefix + match_x,
+                prefix + match_y,
+                a[match_x],
+            ));
+        }
+        (x, y) = (match_x + 1, match_y + 1);
+    }
+
+    let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+    rows.extend(
+        (0..suffix).map(|i| DiffRow::unchanged(old_end + i, new_end + i, old[old_end + i])),
+    );
+    rows
+}
+
+/// Rows for the lines between two matches: as many as both sides have are
+/// paired up as modified, the rest are removed or added
+fn push_gap(
+    rows: &mut Vec<DiffRow>,
+    old_start: usize,
+    old: &[&str],
+    new_start: usize,
+    new: &[&str],
+) {
+    for (i, (a, b)) in old.iter().zip(new).enumerate() {
+        rows.push(DiffRow::modified(old_start + i, a, new_start + i, b));
+    }
+    for (i, text) in old.iter().enumerate().skip(new.len()) {
+        rows.push(DiffRow {
+            kind: RowKind::Removed,
+            left: Some(DiffLine::plain(old_start + i, text)),
+            right: None,
+        });
+    }
+    for (i, text) in new.iter().enumerate().skip(old.len()) {
+        rows.push(DiffRow {
+            kind: RowKind::Added,
+            left: None,
+            right: Some(DiffLine::plain(new_start + i, text)),
+        });
+    }
+}
+
+/// Characters of `old` and `new` between their common prefix and suffix
+fn changed_chars(old: &str, new: &str) -> (Range<usize>, Range<usize>) {
+    let old: Vec<char> = old.chars().collect();
+    let new: Vec<char> = new.chars().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    (prefix..old.len() - suffix, prefix..new.len() - suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_rows, RowKind};
+
+    fn kinds(old: &str, new: &str) -> Vec<RowKind> {
+        diff_rows(old, new).iter().map(|row| row.kind).collect()
+    }
+
+    #[test]
+    fn identical_texts_are_unchanged_rows() {
+        let rows = diff_rows("a\nb\n", "a\nb\n");
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.kind == RowKind::Unchanged));
+    }
+
+    #[test]
+    fn rows_line_up_added_removed_and_modified_lines() {
+        assert_eq!(
+            kinds("a\nb\nc\n", "a\nB\nc\nd\n"),
+            [
+                RowKind::Unchanged,
+                RowKind::Modified,
+                RowKind::Unchanged,
+                RowKind::Added
+            ]
+        );
+
+        let rows = diff_rows("a\nb\nc\n", "a\nc\n");
+        assert_eq!(
+            rows.iter().map(|row| row.kind).collect::<Vec<_>>(),
+            [RowKind::Unchanged, RowKind::Removed, RowKind::Unchanged]
+        );
+        assert!(rows[1].right.is_none());
+        // Line numbers keep counting on each side
+        assert_eq!(rows[2].left.as_ref().unwrap().line, 2);
+        assert_eq!(rows[2].right.as_ref().unwrap().line, 1);
+    }
+
+    #[test]
+    fn modified_rows_mark_the_changed_characters() {
+        let rows = diff_rows("let x = 1;\n", "let x = 42;\n");
+        assert_eq!(rows[0].kind, RowKind::Modified);
+        assert_eq!(rows[0].left.as_ref().unwrap().changed, Some(8..9));
+        assert_eq!(rows[0].right.as_ref().unwrap().changed, Some(8..10));
+    }
+}
@@ -0,0 +1,210 @@
+//! Diff view mode
+//!
+//! Shows a document side by side with another version of it:
+//! - The file as last saved, its HEAD version, or the document in another split
+//! - Changed lines paired up with the differing characters marked
+//! - Both halves scrolled together, row by row
+//! - Next/previous change navigation
+//!
+//! # Architecture
+//!
+//! Like the CSV grid, the diff view is an alternate view of an existing
+//! `EditorState`; the document stays on the right and is followed as it is
+//! edited elsewhere.
+//!
+//! ```text
+//! EditorState
+//! └── ViewMode
+//!     ├── Text (default)
+//!     └── Diff(DiffState)
+//!             ├── DiffBase (what the left half shows)
+//!             └── Vec<DiffRow> (aligned lines of both halves)
+//! ```
+
+mod engine;
+
+pub use engine::{diff_rows, DiffLine, DiffRow, RowKind};
+
+use crate::model::editor_area::DocumentId;
+
+/// Rows kept above a change that is scrolled to
+const CHANGE_CONTEXT_ROWS: usize = 3;
+
+/// What the left half of a diff view shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffBase {
+    /// The file as it was on disk when the view opened
+    Saved,
+    /// The file as committed in HEAD
+    Head,
+    /// Another open document, followed as it is edited
+    Document(DocumentId),
+}
+
+/// State for the diff view mode
+#[derive(Debug, Clone)]
+pub struct DiffState {
+    pub base: DiffBase,
+    /// Title above the left half
+    pub base_title: String,
+    /// Title above the right half
+    pub title: String,
+    /// Text of the left half
+    pub base_text: String,
+    /// Revision of the document the rows were made for
+    pub revision: u64,
+    /// Revision of the base document the rows were made for
+    pub base_revision: Option<u64>,
+    pub rows: Vec<DiffRow>,
+    /// First row shown
+    pub top_row: usize,
+    /// First column shown in both halves
+    pub left_column: usize,
+    /// Index into [`DiffState::change_starts`] of the change last moved to
+    pub current_change: Option<usize>,
+}
+
+impl DiffState {
+    /// Diff `text` (the document at `revision`) against `base_text`
+    pub fn new(
+        base: DiffBase,
+        base_title: String,
+        title: String,
+        base_text: String,
+        text: &str,
+        revision: u64,
+    ) -> Self {
+        let rows = diff_rows(&base_text, text);
+        Self {
+            base,
+            base_title,
+            title,
+            base_text,
+            revision,
+            base_revision: None,
+            rows,
+            top_row: 0,
+            left_column: 0,
+            current_change: None,
+        }
+    }
+
+    /// Diff again after the document or the base changed, keeping the
+    /// scroll position where possible
+    pub fn refresh(&mut self, text: &str, revision: u64) {
+        self.rows = diff_rows(&self.base_text, text);
+        self.revision = revision;
+        self.top_row = self.top_row.min(self.rows.len().saturating_sub(1));
+        let changes = self.change_starts().len();
+        self.current_change = self
+            .current_change
+            .filter(|_| changes > 0)
+            .map(|index| index.min(changes - 1));
+    }
+
+    /// First row of each run of changed rows
+    pub fn change_starts(&self) -> Vec<usize> {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(index, row)| {
+                row.kind != RowKind::Unchanged
+                    && (*index == 0 || self.rows[index - 1].kind == RowKind::Unchanged)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Lines only on the right and only on the left, counting both sides of
+    /// a modified row
+    pub fn line_counts(&self) -> (usize, usize) {
+        self.rows
+            .iter()
+            .fold((0, 0), |(added, removed), row| match row.kind {
+                RowKind::Unchanged => (added, removed),
+                RowKind::Added => (added + 1, removed),
+                RowKind::Removed => (added, removed + 1),
+                RowKind::Modified => (added + 1, removed + 1),
+            })
+    }
+
+    /// Move to the next change (`forward`) or the previous one, scrolling
+    /// it into view. Without a current change, starts from the top row.
+    /// Returns `false` when there is none in that direction.
+    pub fn move_to_change(&mut self, forward: bool) -> bool {
+        let starts = self.change_starts();
+        let target = match (self.current_change, forward) {
+            (Some(index), true) => (index + 1 < starts.len()).then_some(index + 1),
+            (Some(index), false) => index.checked_sub(1),
+            (None, true) => starts.iter().position(|&row| row >= self.top_row),
+            (None, false) => starts.iter().rposition(|&row| row < self.top_row),
+        };
+        let Some(index) = target else {
+            return false;
+        };
+        self.current_change = Some(index);
+        self.top_row = starts[index].saturating_sub(CHANGE_CONTEXT_ROWS);
+        true
+    }
+
+    /// Scroll by `delta` rows, keeping a page of `visible_rows` filled
+    pub fn scroll(&mut self, delta: i32, visible_rows: usize) {
+        let max_top = self.rows.len().saturating_sub(visible_rows);
+        self.top_row = (self.top_row as i64 + delta as i64).clamp(0, max_top as i64) as usize;
+    }
+
+    /// Scroll both halves sideways by `delta` columns
+    pub fn scroll_horizontal(&mut self, delta: i32) {
+        self.left_column = (self.left_column as i64 + delta as i64).max(0) as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffBase, DiffState};
+
+    fn state() -> DiffState {
+        let base = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let text = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        DiffState::new(
+            DiffBase::Saved,
+            "Saved".to_string(),
+            "notes.txt".to_string(),
+            base.to_string(),
+            text,
+            1,
+        )
+    }
+
+    #[test]
+    fn moves_between_changes_and_stops_at_the_ends() {
+        let mut diff = state();
+        assert_eq!(diff.change_starts(), [1, 10]);
+        assert_eq!(diff.line_counts(), (2, 1));
+
+        assert!(diff.move_to_change(true));
+        assert_eq!((diff.current_change, diff.top_row), (Some(0), 0));
+        assert!(diff.move_to_change(true));
+        assert_eq!((diff.current_change, diff.top_row), (Some(1), 7));
+        assert!(!diff.move_to_change(true));
+        assert!(diff.move_to_change(false));
+        assert_eq!(diff.current_change, Some(0));
+        assert!(!diff.move_to_change(false));
+    }
+
+    #[test]
+    fn refresh_follows_edits_and_keeps_the_current_change_in_range() {
+        let mut diff = state();
+        diff.move_to_change(true);
+        diff.move_to_change(true);
+        diff.refresh("a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\n", 2);
+        assert_eq!(diff.change_starts(), [10]);
+        assert_eq!(diff.current_change, Some(0));
+        assert_eq!(diff.revision, 2);
+
+        diff.scroll(100, 4);
+        assert_eq!(diff.top_row, 7);
+        diff.scroll(-100, 4);
+        assert_eq!(diff.top_row, 0);
+    }
+}
@@ -0,0 +1,406 @@
+//! Pending edit review: line diffs for automated changes
+//!
+//! Bulk edits produced by tools (replace all, AI answers) are not applied to
+//! the buffer directly. They are turned into an `EditReviewState` holding the
+//! proposed diff as hunks, shown in the edit review modal, and only the
+//! hunks the user accepts are written back as a single undoable edit.
+
+use crate::model::editor_area::DocumentId;
+
+/// Above this many line edits the diff falls back to a single hunk
+const MAX_EDIT_DISTANCE: usize = 2000;
+
+/// Unchanged lines shown above and below each hunk
+pub const CONTEXT_LINES: usize = 2;
+
+/// What produced a pending edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditReviewSource {
+    /// Find/replace "Replace All"
+    ReplaceAll,
+    /// Answer from the AI chat panel
+    AiAnswer,
+}
+
+impl EditReviewSource {
+    /// Label shown in the review modal title
+    pub fn label(&self) -> &'static str {
+        match self {
+            EditReviewSource::ReplaceAll => "Replace All",
+            EditReviewSource::AiAnswer => "AI Answer",
+        }
+    }
+}
+
+/// A contiguous block of changed lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// 0-indexed line in the original text where the hunk starts
+    pub old_start: usize,
+    /// Original lines replaced by the hunk (with line endings)
+    pub old_lines: Vec<String>,
+    /// Proposed lines (with line endings)
+    pub new_lines: Vec<String>,
+    /// Whether the hunk will be applied
+    pub accepted: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Split text into lines, keeping line endings so hunks round-trip exactly
+fn split_lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// Shortest edit script between `a` and `b` (Myers' greedy algorithm).
+///
+/// Returns `None` if more than `max_d` insertions/deletions are needed.
+fn myers_ops(a: &[&str], b: &[&str], max_d: usize) -> Option<Vec<DiffOp>> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = (a.len() + b.len()).min(max_d) as isize;
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    // V after each step d, stored for k in -d..=d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    let mut found = None;
+    'outer: for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+                found = Some(d);
+                break 'outer;
+            }
+        }
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+    }
+    let total_d = found?;
+
+    let mut ops = Vec::with_capacity((n + m) as usize);
+    let (mut x, mut y) = (n, m);
+    for d in (1..=total_d).rev() {
+        let prev = &trace[d as usize - 1];
+        let at = |k: isize| prev[(k + d - 1) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            ops.push(DiffOp::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        ops.push(if x == prev_x {
+            DiffOp::Insert
+        } else {
+            DiffOp::Delete
+        });
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.extend(std::iter::repeat_n(DiffOp::Equal, x as usize));
+    ops.reverse();
+    Some(ops)
+}
+
+/// Compute the line hunks that turn `old` into `new`. All hunks start out
+/// accepted.
+pub fn compute_hunks(old: &str, new: &str) -> Vec<DiffHunk> {
+    let a = split_lines(old);
+    let b = split_lines(new);
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let a_mid = &a[prefix..a.len() - suffix];
+    let b_mid = &b[prefix..b.len() - suffix];
+
+    let ops = myers_ops(a_mid, b_mid, MAX_EDIT_DISTANCE).unwrap_or_else(|| {
+        let mut ops = vec![DiffOp::Delete; a_mid.len()];
+        ops.extend(std::iter::repeat_n(DiffOp::Insert, b_mid.len()));
+        ops
+    });
+
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let (mut ai, mut bi) = (0, 0);
+    let mut in_hunk = false;
+    for op in ops {
+        match op {
+            DiffOp::Equal => {
+                in_hunk = false;
+                ai += 1;
+                bi += 1;
+            }
+            DiffOp::Delete | DiffOp::Insert => {
+                if !in_hunk {
+                    hunks.push(DiffHunk {
+                        old_start: prefix + ai,
+                        old_lines: Vec::new(),
+                        new_lines: Vec::new(),
+                        accepted: true,
+                    });
+                    in_hunk = true;
+                }
+                let hunk = hunks.last_mut().expect("hunk pushed above");
+                if op == DiffOp::Delete {
+                    hunk.old_lines.push(a_mid[ai].to_string());
+                    ai += 1;
+                } else {
+                    hunk.new_lines.push(b_mid[bi].to_string());
+                    bi += 1;
+                }
+            }
+        }
+    }
+    hunks
+}
+
+/// Rebuild `old` with only the accepted hunks applied
+pub fn apply_hunks(old: &str, hunks: &[DiffHunk]) -> String {
+    let lines = split_lines(old);
+    let mut result = String::with_capacity(old.len());
+    let mut line = 0;
+    for hunk in hunks {
+        for text in &lines[line..hunk.old_start] {
+            result.push_str(text);
+        }
+        let replacement = if hunk.accepted {
+            &hunk.new_lines
+        } else {
+            &hunk.old_lines
+        };
+        for text in replacement {
+            result.push_str(text);
+        }
+        line = hunk.old_start + hunk.old_lines.len();
+    }
+    for text in &lines[line..] {
+        result.push_str(text);
+    }
+    result
+}
+
+/// A row of the review modal's diff listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewRow {
+    /// Hunk header (index into `hunks`)
+    Header(usize),
+    /// Unchanged line around a hunk
+    Context(String),
+    /// Line removed by a hunk
+    Removed(String),
+    /// Line added by a hunk
+    Added(String),
+}
+
+/// State of the edit review modal
+#[derive(Debug, Clone)]
+pub struct EditReviewState {
+    /// Document the edit applies to
+    pub document_id: DocumentId,
+    /// Document revision the diff was computed against
+    pub base_revision: u64,
+    pub source: EditReviewSource,
+    /// Buffer contents the diff was computed against
+    pub original: String,
+    pub hunks: Vec<DiffHunk>,
+    /// Selected hunk
+    pub selected_index: usize,
+    /// First visible row
+    pub scroll_offset: usize,
+}
+
+impl EditReviewState {
+    /// Build a review of `proposed` against `original`. Returns `None` when
+    /// the proposal doesn't change anything.
+    pub fn new(
+        document_id: DocumentId,
+        base_revision: u64,
+        source: EditReviewSource,
+        original: String,
+        proposed: &str,
+    ) -> Option<Self> {
+        let hunks = compute_hunks(&original, proposed);
+        if hunks.is_empty() {
+            return None;
+        }
+        Some(Self {
+            document_id,
+            base_revision,
+            source,
+            original,
+            hunks,
+            selected_index: 0,
+            scroll_offset: 0,
+        })
+    }
+
+    /// Number of accepted hunks
+    pub fn accepted_count(&self) -> usize {
+        self.hunks.iter().filter(|hunk| hunk.accepted).count()
+    }
+
+    /// Toggle the selected hunk
+    pub fn toggle_selected(&mut self) {
+        if let Some(hunk) = self.hunks.get_mut(self.selected_index) {
+            hunk.accepted = !hunk.accepted;
+        }
+    }
+
+    /// Accept or reject every hunk
+    pub fn set_all(&mut self, accepted: bool) {
+        for hunk in &mut self.hunks {
+            hunk.accepted = accepted;
+        }
+    }
+
+    /// Buffer contents with the accepted hunks applied
+    pub fn result_text(&self) -> String {
+        apply_hunks(&self.original, &self.hunks)
+    }
+
+    /// Flatten the hunks into display rows with surrounding context
+    pub fn rows(&self) -> Vec<ReviewRow> {
+        let lines = split_lines(&self.original);
+        let trim = |line: &str| line.trim_end_matches(['\n', '\r']).to_string();
+        let mut rows = Vec::new();
+        for (idx, hunk) in self.hunks.iter().enumerate() {
+            rows.push(ReviewRow::Header(idx));
+            let context_start = hunk.old_start.saturating_sub(CONTEXT_LINES);
+            for line in &lines[context_start..hunk.old_start] {
+                rows.push(ReviewRow::Context(trim(line)));
+            }
+            rows.extend(hunk.old_lines.iter().map(|l| ReviewRow::Removed(trim(l))));
+            rows.extend(hunk.new_lines.iter().map(|l| ReviewRow::Added(trim(l))));
+            let after = hunk.old_start + hunk.old_lines.len();
+            let context_end = (after + CONTEXT_LINES).min(lines.len());
+            for line in &lines[after..context_end] {
+                rows.push(ReviewRow::Context(trim(line)));
+            }
+        }
+        rows
+    }
+
+    /// Row index of the selected hunk's header
+    pub fn selected_row(&self) -> usize {
+        self.rows()
+            .iter()
+            .position(|row| *row == ReviewRow::Header(self.selected_index))
+            .unwrap_or(0)
+    }
+
+    /// Scroll so the selected hunk's header is visible
+    pub fn scroll_to_selected(&mut self, visible_rows: usize) {
+        let row = self.selected_row();
+        if row < self.scroll_offset {
+            self.scroll_offset = row;
+        } else if row >= self.scroll_offset + visible_rows {
+            self.scroll_offset = row + 1 - visible_rows.max(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separate_changes_become_separate_hunks() {
+        let old = "a\nb\nc\nd\ne\n";
+        let new = "a\nB\nc\nd\nE\nf\n";
+        let hunks = compute_hunks(old, new);
+
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].old_start, 1);
+        assert_eq!(hunks[0].old_lines, vec!["b\n"]);
+        assert_eq!(hunks[0].new_lines, vec!["B\n"]);
+        assert_eq!(hunks[1].old_start, 4);
+        assert_eq!(hunks[1].old_lines, vec!["e\n"]);
+        assert_eq!(hunks[1].new_lines, vec!["E\n", "f\n"]);
+        assert_eq!(apply_hunks(old, &hunks), new);
+    }
+
+    #[test]
+    fn rejected_hunks_keep_original_lines() {
+        let old = "one\ntwo\nthree\nfour\n";
+        let new = "ONE\ntwo\nthree\nFOUR\n";
+        let mut hunks = compute_hunks(old, new);
+        hunks[0].accepted = false;
+
+        assert_eq!(apply_hunks(old, &hunks), "one\ntwo\nthree\nFOUR\n");
+    }
+
+    #[test]
+    fn pure_insertions_and_deletions() {
+        let hunks = compute_hunks("a\nc\n", "a\nb\nc\n");
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].old_start, 1);
+        assert!(hunks[0].old_lines.is_empty());
+
+        let hunks = compute_hunks("a\nb\nc", "a\nc");
+        assert_eq!(apply_hunks("a\nb\nc", &hunks), "a\nc");
+    }
+
+    #[test]
+    fn identical_text_has_no_review() {
+        assert!(EditReviewState::new(
+            DocumentId(1),
+            0,
+            EditReviewSource::ReplaceAll,
+            "same\n".into(),
+            "same\n"
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn rows_include_context_around_hunks() {
+        let state = EditReviewState::new(
+            DocumentId(1),
+            0,
+            EditReviewSource::AiAnswer,
+            "1\n2\n3\n4\n5\n".into(),
+            "1\n2\nx\n4\n5\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            state.rows(),
+            vec![
+                ReviewRow::Header(0),
+                ReviewRow::Context("1".into()),
+                ReviewRow::Context("2".into()),
+                ReviewRow::Removed("3".into()),
+                ReviewRow::Added("x".into()),
+                ReviewRow::Context("4".into()),
+                ReviewRow::Context("5".into()),
+            ]
+        );
+    }
+}
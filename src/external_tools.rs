@@ -0,0 +1,176 @@
+//! External tools: user-configured shell commands run from the command palette
+//!
+//! A tool's command is a template. `$FILE`, `$LINE`, `$COLUMN`, `$DIR` and
+//! `$WORKSPACE` are replaced with the focused file, the cursor position
+//! (1-based), the file's folder and the workspace root, each quoted for the
+//! shell. Tools run in the background (see `Cmd::RunExternalTool`) and their
+//! output is reported when they finish.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+/// Entry of `external_tools:` in `config.yaml`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalTool {
+    /// Shown in the command palette
+    pub name: String,
+    /// Shell command, with placeholders
+    pub command: String,
+}
+
+/// Values for a tool's placeholders
+#[derive(Debug, Clone, Default)]
+pub struct ToolContext {
+    /// The focused document's file; `None` when it is untitled
+    pub file: Option<PathBuf>,
+    /// Cursor position, 0-based
+    pub line: usize,
+    pub column: usize,
+    pub workspace: Option<PathBuf>,
+}
+
+impl ToolContext {
+    /// `$DIR`: the file's folder, else the workspace root
+    pub fn dir(&self) -> Option<&Path> {
+        self.file
+            .as_deref()
+            .and_then(Path::parent)
+            .or(self.workspace.as_deref())
+    }
+}
+
+impl ExternalTool {
+    /// The command with its placeholders replaced, or the placeholder that
+    /// has no value (`$FILE` of an untitled document)
+    pub fn expand(&self, context: &ToolContext) -> Result<String, &'static str> {
+        let mut command = self.command.clone();
+        let path = |placeholder: &'static str, path: Option<&Path>| {
+            path.map(|path| shell_quote(&path.to_string_lossy()))
+                .ok_or(placeholder)
+        };
+        if command.contains("$FILE") {
+            command = command.replace("$FILE", &path("$FILE", context.file.as_deref())?);
+        }
+        if command.contains("$DIR") {
+            command = command.replace("$DIR", &path("$DIR", context.dir())?);
+        }
+        if command.contains("$WORKSPACE") {
+            let workspace = context.workspace.as_deref().or(context.dir());
+            command = command.replace("$WORKSPACE", &path("$WORKSPACE", workspace)?);
+        }
+        Ok(command
+            .replace("$LINE", &(context.line + 1).to_string())
+            .replace("$COLUMN", &(context.column + 1).to_string()))
+    }
+}
+
+/// What a finished tool printed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolOutput {
+    pub success: bool,
+    /// Exit code, if it wasn't killed by a signal
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ToolOutput {
+    /// First line worth showing: of stderr if the tool failed, else stdout
+    pub fn summary(&self) -> Option<&str> {
+        let (first, second) = if self.success {
+            (&self.stdout, &self.stderr)
+        } else {
+            (&self.stderr, &self.stdout)
+        };
+        first
+            .lines()
+            .chain(second.lines())
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+    }
+}
+
+/// Run `command` through the shell in `cwd` and wait for it. Blocks; called
+/// from a background thread.
+pub fn run(command: &str, cwd: Option<&Path>) -> Result<ToolOutput, String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    if let Some(cwd) = cwd {
+        shell.current_dir(cwd);
+    }
+    let output = shell.output().map_err(|e| e.to_string())?;
+    Ok(ToolOutput {
+        success: output.status.success(),
+        code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+/// `value` as a single shell word
+fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn tool(command: &str) -> ExternalTool {
+        ExternalTool {
+            name: "Tool".to_string(),
+            command: command.to_string(),
+        }
+    }
+
+    #[test]
+    fn placeholders_are_replaced_with_quoted_values() {
+        let context = ToolContext {
+            file: Some(PathBuf::from("/work/it's here/main.rs")),
+            line: 9,
+            column: 0,
+            workspace: Some(PathBuf::from("/work")),
+        };
+        assert_eq!(
+            tool("code --goto $FILE:$LINE:$COLUMN").expand(&context),
+            Ok(r"code --goto '/work/it'\''s here/main.rs':10:1".to_string())
+        );
+        assert_eq!(
+            tool("cd $DIR && ls $WORKSPACE").expand(&context),
+            Ok(r"cd '/work/it'\''s here' && ls '/work'".to_string())
+        );
+
+        let untitled = ToolContext {
+            workspace: Some(PathBuf::from("/work")),
+            ..ToolContext::default()
+        };
+        assert_eq!(tool("rustfmt $FILE").expand(&untitled), Err("$FILE"));
+        assert_eq!(
+            tool("ls $DIR").expand(&untitled),
+            Ok("ls '/work'".to_string())
+        );
+    }
+
+    #[test]
+    fn output_of_a_run_is_captured() {
+        let output = run("echo hello; echo oops >&2; exit 3", None).unwrap();
+        assert!(!output.success);
+        assert_eq!(output.code, Some(3));
+        assert_eq!(output.stdout, "hello\n");
+        assert_eq!(output.summary(), Some("oops"));
+    }
+}
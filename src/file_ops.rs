@@ -0,0 +1,386 @@
+//! File operations started from the editor, with trash and undo
+//!
+//! Deleting from the file tree never removes anything permanently: the path
+//! is moved to the OS trash (the freedesktop trash on Linux, `~/.Trash` on
+//! macOS, the editor's own trash folder elsewhere). A rename that would
+//! overwrite an existing file trashes that file first. Every completed
+//! operation is recorded in a [`FileOpJournal`] so the most recent ones can
+//! be undone.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::util::{filename_for_display, UtcDateTime};
+
+/// Maximum number of operations kept for undo
+pub const MAX_JOURNAL_ENTRIES: usize = 20;
+
+/// A completed file operation that can be reverted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileOperation {
+    /// `original` was moved to the trash and now lives at `trashed`
+    Trashed {
+        original: PathBuf,
+        trashed: PathBuf,
+        /// Freedesktop `.trashinfo` file written alongside, if any
+        info: Option<PathBuf>,
+    },
+    /// `from` was renamed to `to`; a file that was already at `to` was
+    /// trashed first
+    Renamed {
+        from: PathBuf,
+        to: PathBuf,
+        overwritten: Option<Box<FileOperation>>,
+    },
+}
+
+impl FileOperation {
+    /// Short past-tense description for the status bar ("Deleted foo.rs")
+    pub fn describe(&self) -> String {
+        match self {
+            FileOperation::Trashed { original, .. } => {
+                format!("Deleted {}", filename_for_display(original))
+            }
+            FileOperation::Renamed { from, to, .. } => format!(
+                "Renamed {} → {}",
+                filename_for_display(from),
+                filename_for_display(to)
+            ),
+        }
+    }
+
+    /// Undo the operation on disk
+    pub fn revert(&self) -> io::Result<()> {
+        match self {
+            FileOperation::Trashed {
+                original,
+                trashed,
+                info,
+            } => {
+                if original.exists() {
+                    return Err(already_exists(original));
+                }
+                if let Some(parent) = original.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::rename(trashed, original)?;
+                if let Some(info) = info {
+                    let _ = fs::remove_file(info);
+                }
+                Ok(())
+            }
+            FileOperation::Renamed {
+                from,
+                to,
+                overwritten,
+            } => {
+                if from.exists() {
+                    return Err(already_exists(from));
+                }
+                fs::rename(to, from)?;
+                match overwritten {
+                    Some(overwritten) => overwritten.revert(),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+fn already_exists(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("{} already exists", path.display()),
+    )
+}
+
+/// Where trashed files go
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trash {
+    /// Directory the trashed paths are moved into
+    pub files: PathBuf,
+    /// Directory for freedesktop `.trashinfo` files, when the trash uses them
+    pub info: Option<PathBuf>,
+}
+
+impl Trash {
+    /// The user's trash on this platform
+    pub fn system() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            return dirs::home_dir().map(|home| Trash {
+                files: home.join(".Trash"),
+                info: None,
+            });
+        }
+        if cfg!(all(unix, not(target_os = "macos"))) {
+            return dirs::data_dir().map(|data| Trash::freedesktop(data.join("Trash")));
+        }
+        crate::config_paths::config_dir().map(|dir| Trash {
+            files: dir.join("trash"),
+            info: None,
+        })
+    }
+
+    /// A freedesktop.org trash directory (`files/` and `info/` subfolders)
+    pub fn freedesktop(root: PathBuf) -> Self {
+        Trash {
+            files: root.join("files"),
+            info: Some(root.join("info")),
+        }
+    }
+
+    /// Move `path` into the trash.
+    ///
+    /// The move is a rename, so it fails rather than copying when the trash
+    /// is on another filesystem; nothing is ever deleted permanently.
+    pub fn trash(&self, path: &Path) -> io::Result<FileOperation> {
+        let original = std::path::absolute(path)?;
+        let name = original
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+            .to_string_lossy()
+            .to_string();
+
+        fs::create_dir_all(&self.files)?;
+        let trashed = unique_path(&self.files, &name, |candidate| {
+            let info_taken = self
+                .info
+                .as_ref()
+                .is_some_and(|info| info.join(format!("{}.trashinfo", candidate)).exists());
+            info_taken || self.files.join(candidate).exists()
+        });
+
+        let info = match &self.info {
+            Some(info_dir) => {
+                fs::create_dir_all(info_dir)?;
+                let trashed_name = trashed.file_name().unwrap_or_default().to_string_lossy();
+                let info_path = info_dir.join(format!("{}.trashinfo", trashed_name));
+                fs::write(&info_path, trash_info(&original))?;
+                Some(info_path)
+            }
+            None => None,
+        };
+
+        if let Err(e) = fs::rename(&original, &trashed) {
+            if let Some(info_path) = &info {
+                let _ = fs::remove_file(info_path);
+            }
+            return Err(e);
+        }
+        Ok(FileOperation::Trashed {
+            original,
+            trashed,
+            info,
+        })
+    }
+
+    /// Rename `from` to `to`, trashing whatever is already at `to`
+    pub fn rename(&self, from: &Path, to: &Path) -> io::Result<FileOperation> {
+        if !from.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} does not exist", from.display()),
+            ));
+        }
+        let overwritten = if to.exists() {
+            Some(Box::new(self.trash(to)?))
+        } else {
+            None
+        };
+        if let Err(e) = fs::rename(from, to) {
+            if let Some(overwritten) = &overwritten {
+                let _ = overwritten.revert();
+            }
+            return Err(e);
+        }
+        Ok(FileOperation::Renamed {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+            overwritten,
+        })
+    }
+}
+
+/// Create an empty file at `path`, and any missing parent folders. Fails if
+/// something already exists there.
+pub fn create_file(path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map(|_| ())
+}
+
+/// Create an empty folder at `path`, and any missing parent folders. Fails
+/// if something already exists there.
+pub fn create_folder(path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::create_dir(path)
+}
+
+/// `dir/name`, or `dir/name.N` for the first free N when `taken(name)`
+fn unique_path(dir: &Path, name: &str, taken: impl Fn(&str) -> bool) -> PathBuf {
+    if !taken(name) {
+        return dir.join(name);
+    }
+    let mut n = 2;
+    loop {
+        let candidate = format!("{}.{}", name, n);
+        if !taken(&candidate) {
+            return dir.join(candidate);
+        }
+        n += 1;
+    }
+}
+
+/// Contents of a freedesktop `.trashinfo` file
+fn trash_info(original: &Path) -> String {
+    format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        percent_encode_path(original),
+        UtcDateTime::now().to_iso8601()
+    )
+}
+
+/// Percent-encode a path as the trash spec requires (keeping `/`)
+fn percent_encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Recently completed file operations, newest last
+#[derive(Debug, Clone, Default)]
+pub struct FileOpJournal {
+    entries: Vec<FileOperation>,
+}
+
+impl FileOpJournal {
+    /// Record a completed operation, dropping the oldest beyond the cap
+    pub fn push(&mut self, operation: FileOperation) {
+        self.entries.push(operation);
+        if self.entries.len() > MAX_JOURNAL_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// Take the most recent operation for undoing
+    pub fn pop(&mut self) -> Option<FileOperation> {
+        self.entries.pop()
+    }
+
+    /// The most recent operation
+    pub fn last(&self) -> Option<&FileOperation> {
+        self.entries.last()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_trash(dir: &Path) -> Trash {
+        Trash::freedesktop(dir.join("Trash"))
+    }
+
+    #[test]
+    fn trash_moves_file_and_writes_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = test_trash(dir.path());
+        let path = dir.path().join("my notes.txt");
+        fs::write(&path, "keep").unwrap();
+
+        let op = trash.trash(&path).unwrap();
+        assert!(!path.exists());
+        let FileOperation::Trashed { trashed, info, .. } = &op else {
+            panic!("expected trashed operation");
+        };
+        assert_eq!(fs::read_to_string(trashed).unwrap(), "keep");
+        let info = fs::read_to_string(info.as_ref().unwrap()).unwrap();
+        assert!(info.contains("my%20notes.txt"));
+        assert_eq!(op.describe(), "Deleted my notes.txt");
+
+        op.revert().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "keep");
+        assert!(fs::read_dir(dir.path().join("Trash/info"))
+            .unwrap()
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn trashing_same_name_twice_keeps_both() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = test_trash(dir.path());
+        let path = dir.path().join("a.rs");
+
+        fs::write(&path, "first").unwrap();
+        let first = trash.trash(&path).unwrap();
+        fs::write(&path, "second").unwrap();
+        let second = trash.trash(&path).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(
+            fs::read_dir(dir.path().join("Trash/files"))
+                .unwrap()
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn rename_over_existing_file_can_be_undone() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = test_trash(dir.path());
+        let from = dir.path().join("new.rs");
+        let to = dir.path().join("old.rs");
+        fs::write(&from, "new").unwrap();
+        fs::write(&to, "old").unwrap();
+
+        let op = trash.rename(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "new");
+
+        op.revert().unwrap();
+        assert_eq!(fs::read_to_string(&from).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&to).unwrap(), "old");
+    }
+
+    #[test]
+    fn journal_keeps_most_recent_entries() {
+        let mut journal = FileOpJournal::default();
+        for i in 0..MAX_JOURNAL_ENTRIES + 5 {
+            journal.push(FileOperation::Renamed {
+                from: PathBuf::from(format!("{}", i)),
+                to: PathBuf::from("x"),
+                overwritten: None,
+            });
+        }
+        assert_eq!(journal.len(), MAX_JOURNAL_ENTRIES);
+        assert!(matches!(
+            journal.pop(),
+            Some(FileOperation::Renamed { ref from, .. }) if from == Path::new("24")
+        ));
+    }
+}
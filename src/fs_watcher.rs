@@ -1,10 +1,13 @@
 //! File system watching for workspace file tree updates
 //!
 //! Uses the `notify` crate with debouncing to detect file system changes
-//! and refresh the workspace file tree automatically.
+//! and refresh the workspace file tree automatically. Open files outside
+//! the workspace are watched separately, so edits made to them by other
+//! programs are noticed too.
 
 use notify_debouncer_mini::{new_debouncer, DebouncedEventKind, Debouncer};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver};
 use std::time::Duration;
 
@@ -153,6 +156,83 @@ impl FileSystemWatcher {
     }
 }
 
+/// Watches the folders of open files that live outside the workspace
+///
+/// Folders are watched rather than the files themselves: editors that save
+/// by writing a new file and renaming it over the old one would otherwise
+/// end the watch on the first save.
+pub struct OpenFileWatcher {
+    debouncer: Debouncer<notify::RecommendedWatcher>,
+    rx: Receiver<Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>>,
+    /// Folders being watched (non-recursively)
+    dirs: HashSet<PathBuf>,
+}
+
+impl OpenFileWatcher {
+    /// Create a watcher that watches nothing until `sync` is called
+    pub fn new() -> Result<Self, notify::Error> {
+        let (tx, rx) = mpsc::channel();
+        let debouncer = new_debouncer(Duration::from_millis(500), tx)?;
+        Ok(Self {
+            debouncer,
+            rx,
+            dirs: HashSet::new(),
+        })
+    }
+
+    /// Watch exactly the folders holding `files`
+    pub fn sync<'a>(&mut self, files: impl IntoIterator<Item = &'a Path>) {
+        let wanted: HashSet<PathBuf> = files
+            .into_iter()
+            .filter_map(|file| file.parent())
+            .map(Path::to_path_buf)
+            .collect();
+        if wanted == self.dirs {
+            return;
+        }
+
+        let watcher = self.debouncer.watcher();
+        for dir in self.dirs.difference(&wanted) {
+            if let Err(e) = watcher.unwatch(dir) {
+                tracing::debug!("Failed to stop watching {}: {}", dir.display(), e);
+            }
+        }
+        for dir in wanted.difference(&self.dirs) {
+            if let Err(e) = watcher.watch(dir, notify::RecursiveMode::NonRecursive) {
+                tracing::warn!("Failed to watch {}: {}", dir.display(), e);
+            }
+        }
+        self.dirs = wanted;
+    }
+
+    /// Folders currently being watched
+    pub fn dirs(&self) -> &HashSet<PathBuf> {
+        &self.dirs
+    }
+
+    /// Poll for paths that changed in the watched folders (non-blocking)
+    pub fn poll_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        while let Ok(result) = self.rx.try_recv() {
+            match result {
+                Ok(events) => {
+                    for event in events {
+                        if event.kind != DebouncedEventKind::AnyContinuous
+                            && !paths.contains(&event.path)
+                        {
+                            paths.push(event.path);
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Open file watcher error: {:?}", e);
+                }
+            }
+        }
+        paths
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -328,6 +408,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_open_file_watcher_follows_open_files() {
+        let dir = tempdir().expect("Failed to create temp dir");
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        fs::create_dir(&a).unwrap();
+        fs::create_dir(&b).unwrap();
+
+        let Ok(mut watcher) = OpenFileWatcher::new() else {
+            return;
+        };
+        watcher.sync([a.join("one.txt").as_path(), a.join("two.txt").as_path()]);
+        assert_eq!(watcher.dirs().len(), 1);
+        assert!(watcher.dirs().contains(&a));
+
+        watcher.sync([b.join("three.txt").as_path()]);
+        assert_eq!(watcher.dirs().iter().collect::<Vec<_>>(), vec![&b]);
+
+        watcher.sync(std::iter::empty());
+        assert!(watcher.dirs().is_empty());
+    }
+
     // ========================================================================
     // FileSystemEvent tests
     // ========================================================================
@@ -0,0 +1,728 @@
+//! Git repository metadata read straight from the `.git` directory
+//!
+//! Enough to link to a file on its hosting service: the checked-out commit
+//! and the remote URL, without spawning `git`. Worktrees (a `.git` file
+//! pointing at the real git dir) and packed refs are supported.
+//!
+//! The gutter diff needs a file's committed contents, which may sit in a
+//! pack; those come from `git show` on a background thread (see
+//! `Cmd::ComputeGitDiff`), diffed line by line against the buffer. Blame
+//! annotations come from `git blame --porcelain` run on the buffer's text
+//! (see `Cmd::ComputeGitBlame`), so edited lines show as not committed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use crate::model::editor_area::DocumentId;
+use crate::util::time::UtcDateTime;
+
+/// A git working tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repository {
+    /// Top-level folder of the working tree
+    pub root: PathBuf,
+    /// Git dir of this working tree (`HEAD` lives here)
+    git_dir: PathBuf,
+    /// Shared git dir (refs and config); differs from `git_dir` in worktrees
+    common_dir: PathBuf,
+}
+
+impl Repository {
+    /// The repository containing `path`, searching its ancestors
+    pub fn discover(path: &Path) -> Option<Self> {
+        path.ancestors().find_map(Self::open)
+    }
+
+    /// The repository whose working tree root is `root`
+    fn open(root: &Path) -> Option<Self> {
+        let dot_git = root.join(".git");
+        let git_dir = if dot_git.is_dir() {
+            dot_git
+        } else {
+            let content = fs::read_to_string(&dot_git).ok()?;
+            let target = content.trim().strip_prefix("gitdir:")?.trim();
+            root.join(target)
+        };
+        if !git_dir.join("HEAD").is_file() {
+            return None;
+        }
+        let common_dir = fs::read_to_string(git_dir.join("commondir"))
+            .map(|dir| git_dir.join(dir.trim()))
+            .unwrap_or_else(|_| git_dir.clone());
+        Some(Self {
+            root: root.to_path_buf(),
+            git_dir,
+            common_dir,
+        })
+    }
+
+    /// Hash of the checked-out commit
+    pub fn head_commit(&self) -> Option<String> {
+        let head = fs::read_to_string(self.git_dir.join("HEAD")).ok()?;
+        match head.trim().strip_prefix("ref:") {
+            Some(name) => self.resolve_ref(name.trim()),
+            None => is_hash(head.trim()).then(|| head.trim().to_string()),
+        }
+    }
+
+    /// URL of the `origin` remote, or of the first remote configured
+    pub fn remote_url(&self) -> Option<String> {
+        let config = fs::read_to_string(self.common_dir.join("config")).ok()?;
+        let remotes = remote_urls(&config);
+        remotes
+            .iter()
+            .find(|(name, _)| name == "origin")
+            .or_else(|| remotes.first())
+            .map(|(_, url)| url.clone())
+    }
+
+    /// Contents of `path` in the checked-out commit; `None` when it isn't
+    /// committed (or `git` can't be run)
+    pub fn head_file(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let spec: Vec<String> = relative
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.root)
+            .args(["show", "--no-textconv"])
+            .arg(format!("HEAD:{}", spec.join("/")))
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    }
+
+    /// Last commit of each line of `text`, the current contents of `path`;
+    /// `None` when the file isn't committed (or `git` can't be run)
+    pub fn blame(&self, path: &Path, text: &str) -> Option<Vec<Option<Arc<BlameLine>>>> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(&self.root)
+            .args(["blame", "--porcelain", "--contents", "-", "--"])
+            .arg(relative)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        // Written from another thread: git may fill the stdout pipe before
+        // it has read all of stdin
+        let mut stdin = child.stdin.take()?;
+        let input = text.to_string();
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let output = child.wait_with_output().ok()?;
+        let _ = writer.join();
+        if !output.status.success() {
+            return None;
+        }
+        let lines = text.lines().count().max(1);
+        Some(parse_blame(&String::from_utf8_lossy(&output.stdout), lines))
+    }
+
+    fn resolve_ref(&self, name: &str) -> Option<String> {
+        for dir in [&self.git_dir, &self.common_dir] {
+            if let Ok(hash) = fs::read_to_string(dir.join(name)) {
+                let hash = hash.trim();
+                if is_hash(hash) {
+                    return Some(hash.to_string());
+                }
+            }
+        }
+        let packed = fs::read_to_string(self.common_dir.join("packed-refs")).ok()?;
+        packed.lines().find_map(|line| {
+            let (hash, ref_name) = line.split_once(' ')?;
+            (ref_name == name && is_hash(hash)).then(|| hash.to_string())
+        })
+    }
+}
+
+/// Hosting service of a remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Host {
+    GitHub,
+    GitLab,
+}
+
+impl Host {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Host::GitHub => "GitHub",
+            Host::GitLab => "GitLab",
+        }
+    }
+}
+
+/// A repository on GitHub or GitLab (including self-hosted GitLab)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteRepo {
+    pub host: Host,
+    /// Web URL of the repository, e.g. `https://github.com/owner/repo`
+    pub web_url: String,
+}
+
+impl RemoteRepo {
+    /// Parse an HTTPS, SSH (`ssh://`) or scp-like (`git@host:path`) remote
+    /// URL; `None` for hosts other than GitHub and GitLab
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit('@').next()?;
+            // Drop an SSH port; HTTPS ports belong to the web URL
+            let host = if url.starts_with("ssh://") {
+                host.split(':').next()?
+            } else {
+                host
+            };
+            (host, path)
+        } else {
+            let (authority, path) = url.split_once(':')?;
+            (authority.rsplit('@').next()?, path)
+        };
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        if host.is_empty() || !path.contains('/') {
+            return None;
+        }
+
+        let lower = host.to_lowercase();
+        let kind = if lower == "github.com" || lower.ends_with(".github.com") {
+            Host::GitHub
+        } else if lower.contains("gitlab") {
+            Host::GitLab
+        } else {
+            return None;
+        };
+        Some(Self {
+            host: kind,
+            web_url: format!("https://{}/{}", host, path),
+        })
+    }
+
+    /// Link to lines `start..=end` (1-based) of `path` at `commit`
+    pub fn permalink(&self, commit: &str, path: &str, start: usize, end: usize) -> String {
+        let path = encode_path(path);
+        let lines = match (self.host, start == end) {
+            (_, true) => format!("L{}", start),
+            (Host::GitHub, false) => format!("L{}-L{}", start, end),
+            (Host::GitLab, false) => format!("L{}-{}", start, end),
+        };
+        let blob = match self.host {
+            Host::GitHub => "blob",
+            Host::GitLab => "-/blob",
+        };
+        format!("{}/{}/{}/{}#{}", self.web_url, blob, commit, path, lines)
+    }
+}
+
+// ============================================================================
+// Gutter diff
+// ============================================================================
+
+/// Longest edit script searched for; beyond it, everything between the
+/// common prefix and suffix counts as modified
+const MAX_EDIT_DISTANCE: usize = 1000;
+
+/// HEAD version of a document's file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum HeadVersion {
+    /// Not read yet, or to be read again (after a save or reload)
+    #[default]
+    Unknown,
+    /// Not in a git repository, or not committed
+    Untracked,
+    Loaded(Arc<str>),
+}
+
+/// How a run of lines differs from HEAD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkKind {
+    Added,
+    Modified,
+    /// Lines removed above `start`; the hunk covers no current lines
+    Deleted,
+}
+
+/// A run of changed lines, `start..end` of the current text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub kind: HunkKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Changes of a document against its HEAD version
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitDiff {
+    /// Document revision the diff was made for
+    pub revision: u64,
+    /// Sorted by `start`
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl GitDiff {
+    /// Change marked on `line`: added or modified, or lines deleted just
+    /// above it
+    pub fn change_at(&self, line: usize) -> Option<HunkKind> {
+        let index = self.hunks.partition_point(|hunk| hunk.start <= line);
+        let hunk = self.hunks[..index].last()?;
+        match hunk.kind {
+            HunkKind::Deleted => (hunk.start == line).then_some(HunkKind::Deleted),
+            kind => (line < hunk.end).then_some(kind),
+        }
+    }
+
+    /// Whether lines were deleted after the last line, `last_line`
+    pub fn deleted_at_end(&self, last_line: usize) -> bool {
+        self.hunks
+            .last()
+            .is_some_and(|hunk| hunk.kind == HunkKind::Deleted && hunk.start > last_line)
+    }
+}
+
+/// A document to diff against HEAD, for `Cmd::ComputeGitDiff`
+#[derive(Debug, Clone)]
+pub struct GitDiffRequest {
+    pub document_id: DocumentId,
+    pub revision: u64,
+    pub path: PathBuf,
+    pub text: String,
+    /// Already read HEAD version; read from the repository when `None`
+    pub head: Option<Arc<str>>,
+}
+
+/// Answer to a [`GitDiffRequest`]
+#[derive(Debug, Clone)]
+pub struct GitDiffResult {
+    pub document_id: DocumentId,
+    pub head: HeadVersion,
+    /// `None` when the file isn't tracked
+    pub diff: Option<GitDiff>,
+}
+
+/// Diff a document against its HEAD version, reading that first if needed
+pub fn compute_diff(request: GitDiffRequest) -> GitDiffResult {
+    let head = request.head.or_else(|| {
+        Repository::discover(&request.path)?
+            .head_file(&request.path)
+            .map(Arc::from)
+    });
+    let Some(head) = head else {
+        return GitDiffResult {
+            document_id: request.document_id,
+            head: HeadVersion::Untracked,
+            diff: None,
+        };
+    };
+    let diff = GitDiff {
+        revision: request.revision,
+        hunks: diff_lines(&head, &request.text),
+    };
+    GitDiffResult {
+        document_id: request.document_id,
+        head: HeadVersion::Loaded(head),
+        diff: Some(diff),
+    }
+}
+
+/// Commit that last changed a line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlameLine {
+    pub author: String,
+    /// Author time, in seconds since the Unix epoch
+    pub time: u64,
+    pub summary: String,
+    /// Changed in the working tree or buffer, not committed yet
+    pub uncommitted: bool,
+}
+
+impl BlameLine {
+    /// Annotation text, e.g. `Jane Doe, 2024-05-01 • Fix typo`
+    pub fn label(&self) -> String {
+        if self.uncommitted {
+            return "Not committed yet".to_string();
+        }
+        let date = UtcDateTime::from_unix_secs(self.time).to_date();
+        format!("{}, {} • {}", self.author, date, self.summary)
+    }
+}
+
+/// Blame of a document's text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitBlame {
+    /// Document revision the blame was made for
+    pub revision: u64,
+    /// By line; empty when the file isn't committed
+    pub lines: Vec<Option<Arc<BlameLine>>>,
+    /// Line annotated when only the cursor line is
+    pub annotated_line: Option<usize>,
+}
+
+/// A document to blame, for `Cmd::ComputeGitBlame`
+#[derive(Debug, Clone)]
+pub struct GitBlameRequest {
+    pub document_id: DocumentId,
+    pub revision: u64,
+    pub path: PathBuf,
+    pub text: String,
+}
+
+/// Answer to a [`GitBlameRequest`]
+#[derive(Debug, Clone)]
+pub struct GitBlameResult {
+    pub document_id: DocumentId,
+    pub blame: GitBlame,
+}
+
+/// Blame a document's text
+pub fn compute_blame(request: GitBlameRequest) -> GitBlameResult {
+    let lines = Repository::discover(&request.path)
+        .and_then(|repo| repo.blame(&request.path, &request.text))
+        .unwrap_or_default();
+    GitBlameResult {
+        document_id: request.document_id,
+        blame: GitBlame {
+            revision: request.revision,
+            lines,
+            annotated_line: None,
+        },
+    }
+}
+
+/// Lines of `git blame --porcelain` output: each commit's details follow
+/// the first line it's blamed for
+fn parse_blame(output: &str, line_count: usize) -> Vec<Option<Arc<BlameLine>>> {
+    let mut lines = vec![None; line_count];
+    let mut commits: HashMap<&str, Arc<BlameLine>> = HashMap::new();
+    let mut current: Option<(&str, usize)> = None;
+    let mut details = BlameLine::default();
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            // The line's content ends each entry
+            let Some((hash, final_line)) = current.take() else {
+                continue;
+            };
+            let commit = commits.entry(hash).or_insert_with(|| {
+                Arc::new(BlameLine {
+                    uncommitted: hash.bytes().all(|byte| byte == b'0'),
+                    ..std::mem::take(&mut details)
+                })
+            });
+            if let Some(slot) = final_line.checked_sub(1).and_then(|i| lines.get_mut(i)) {
+                *slot = Some(commit.clone());
+            }
+        } else if let Some(author) = line.strip_prefix("author ") {
+            details.author = author.to_string();
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            details.time = time.parse().unwrap_or(0);
+        } else if let Some(summary) = line.strip_prefix("summary ") {
+            details.summary = summary.to_string();
+        } else {
+            let mut fields = line.split(' ');
+            if let (Some(hash), Some(_), Some(final_line)) =
+                (fields.next(), fields.next(), fields.next())
+            {
+                if is_hash(hash) {
+                    current = final_line.parse().ok().map(|line| (hash, line));
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// Changed lines of `current` against `base`
+pub fn diff_lines(base: &str, current: &str) -> Vec<DiffHunk> {
+    let base: Vec<&str> = base.lines().collect();
+    let current: Vec<&str> = current.lines().collect();
+    let prefix = base
+        .iter()
+        .zip(&current)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = base[prefix..]
+        .iter()
+        .rev()
+        .zip(current[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &base[prefix..base.len() - suffix];
+    let b = &current[prefix..current.len() - suffix];
+
+    let matches = matched_lines(a, b).unwrap_or_default();
+    let mut hunks = Vec::new();
+    let (mut x, mut y) = (0, 0);
+    // Each gap between matched lines is one hunk
+    for (match_x, match_y) in matches.into_iter().chain([(a.len(), b.len())]) {
+        let kind = match (match_x - x, match_y - y) {
+            (0, 0) => None,
+            (0, _) => Some(HunkKind::Added),
+            (_, 0) => Some(HunkKind::Deleted),
+            _ => Some(HunkKind::Modified),
+        };
+        if let Some(kind) = kind {
+            hunks.push(DiffHunk {
+                kind,
+                start: prefix + y,
+                end: prefix + match_y,
+            });
+        }
+        (x, y) = (match_x + 1, match_y + 1);
+    }
+    hunks
+}
+
+/// `(a, b)` index pairs of the lines a shortest edit script keeps (Myers'
+/// algorithm); `None` when the script is longer than `MAX_EDIT_DISTANCE`
+pub(crate) fn matched_lines(a: &[&str], b: &[&str]) -> Option<Vec<(usize, usize)>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = a.len() + b.len();
+    let offset = max as isize + 1;
+    let index = |k: isize| (k + offset) as usize;
+    // Furthest x reached on each diagonal k = x - y
+    let mut furthest = vec![0isize; 2 * max + 3];
+    // `furthest` for diagonals -d..=d before each round d, to walk back
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    for d in 0..=max.min(MAX_EDIT_DISTANCE) as isize {
+        trace.push(furthest[index(-d)..=index(d)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && furthest[index(k - 1)] < furthest[index(k + 1)]) {
+                furthest[index(k + 1)]
+            } else {
+                furthest[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            furthest[index(k)] = x;
+            if x >= n && y >= m {
+                return Some(walk_back(&trace, n, m));
+            }
+        }
+    }
+    None
+}
+
+/// Matched line pairs along the path found by [`matched_lines`]
+fn walk_back(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, furthest) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let at = |k: isize| furthest[(k + d) as usize];
+            let k = x - y;
+            let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+                k + 1
+            } else {
+                k - 1
+            };
+            (at(prev_k), at(prev_k) - prev_k)
+        };
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            matches.push((x as usize, y as usize));
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    matches.reverse();
+    matches
+}
+
+/// `(name, url)` of each `[remote "name"]` section in a git config file
+fn remote_urls(config: &str) -> Vec<(String, String)> {
+    let mut remotes = Vec::new();
+    let mut section: Option<String> = None;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line
+                .strip_prefix("[remote \"")
+                .and_then(|rest| rest.strip_suffix("\"]"))
+                .map(str::to_string);
+        } else if let (Some(name), Some((key, value))) = (&section, line.split_once('=')) {
+            if key.trim() == "url" {
+                remotes.push((name.clone(), value.trim().to_string()));
+            }
+        }
+    }
+    remotes
+}
+
+fn is_hash(text: &str) -> bool {
+    text.len() >= 40 && text.chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+/// Percent-encode a repository path for a URL, keeping `/`
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    #[test]
+    fn parses_remote_urls_and_builds_permalinks() {
+        let github = RemoteRepo::parse("git@github.com:owner/repo.git").unwrap();
+        assert_eq!(github.web_url, "https://github.com/owner/repo");
+        assert_eq!(
+            github.permalink(HASH, "src/my file.rs", 3, 7),
+            format!("https://github.com/owner/repo/blob/{HASH}/src/my%20file.rs#L3-L7")
+        );
+
+        let gitlab =
+            RemoteRepo::parse("ssh://git@gitlab.example.com:2222/group/sub/repo.git").unwrap();
+        assert_eq!(gitlab.web_url, "https://gitlab.example.com/group/sub/repo");
+        assert_eq!(
+            gitlab.permalink(HASH, "a.rs", 4, 9),
+            format!("https://gitlab.example.com/group/sub/repo/-/blob/{HASH}/a.rs#L4-9")
+        );
+        assert_eq!(
+            RemoteRepo::parse("https://user@github.com/owner/repo/")
+                .unwrap()
+                .permalink(HASH, "a.rs", 2, 2),
+            format!("https://github.com/owner/repo/blob/{HASH}/a.rs#L2")
+        );
+        assert!(RemoteRepo::parse("https://example.com/owner/repo.git").is_none());
+    }
+
+    #[test]
+    fn reads_head_commit_and_remote_from_git_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = dir.path().join(".git");
+        fs::create_dir_all(git.join("refs/heads")).unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(git.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(
+            git.join("config"),
+            "[core]\n\tbare = false\n[remote \"upstream\"]\n\turl = git@github.com:a/b.git\n\
+             [remote \"origin\"]\n\turl = https://gitlab.com/c/d.git\n",
+        )
+        .unwrap();
+        fs::write(
+            git.join("packed-refs"),
+            format!("# pack-refs\n{HASH} refs/heads/main\n"),
+        )
+        .unwrap();
+
+        let repo = Repository::discover(&dir.path().join("src")).unwrap();
+        assert_eq!(repo.root, dir.path());
+        assert_eq!(repo.head_commit().as_deref(), Some(HASH));
+        assert_eq!(
+            repo.remote_url().as_deref(),
+            Some("https://gitlab.com/c/d.git")
+        );
+
+        // Loose refs win over packed ones
+        let loose = "fedcba9876543210fedcba9876543210fedcba98";
+        fs::write(git.join("refs/heads/main"), format!("{loose}\n")).unwrap();
+        assert_eq!(repo.head_commit().as_deref(), Some(loose));
+    }
+
+    fn hunk(kind: HunkKind, start: usize, end: usize) -> DiffHunk {
+        DiffHunk { kind, start, end }
+    }
+
+    #[test]
+    fn diff_marks_added_modified_and_deleted_lines() {
+        let base = "a\nb\nc\nd\ne\nf\n";
+        let current = "a\nnew\nb\nC\nd\nf\n";
+        assert_eq!(
+            diff_lines(base, current),
+            vec![
+                hunk(HunkKind::Added, 1, 2),
+                hunk(HunkKind::Modified, 3, 4),
+                hunk(HunkKind::Deleted, 5, 5),
+            ]
+        );
+        assert!(diff_lines(base, base).is_empty());
+        assert_eq!(
+            diff_lines("a\nb\n", "a\n"),
+            vec![hunk(HunkKind::Deleted, 1, 1)]
+        );
+
+        let diff = GitDiff {
+            revision: 0,
+            hunks: diff_lines(base, current),
+        };
+        assert_eq!(diff.change_at(0), None);
+        assert_eq!(diff.change_at(1), Some(HunkKind::Added));
+        assert_eq!(diff.change_at(3), Some(HunkKind::Modified));
+        assert_eq!(diff.change_at(4), None);
+        assert_eq!(diff.change_at(5), Some(HunkKind::Deleted));
+        assert!(!diff.deleted_at_end(5));
+    }
+
+    #[test]
+    fn compute_diff_uses_a_loaded_head_version() {
+        let result = compute_diff(GitDiffRequest {
+            document_id: DocumentId(1),
+            revision: 7,
+            path: PathBuf::from("/nowhere/a.rs"),
+            text: "one\ntwo\n".into(),
+            head: Some(Arc::from("one\n")),
+        });
+        let diff = result.diff.unwrap();
+        assert_eq!(diff.revision, 7);
+        assert_eq!(diff.hunks, vec![hunk(HunkKind::Added, 1, 2)]);
+
+        // Not in a repository
+        let untracked = compute_diff(GitDiffRequest {
+            document_id: DocumentId(1),
+            revision: 0,
+            path: PathBuf::from("/nowhere/a.rs"),
+            text: String::new(),
+            head: None,
+        });
+        assert_eq!(untracked.head, HeadVersion::Untracked);
+        assert!(untracked.diff.is_none());
+    }
+
+    #[test]
+    fn parses_blame_porcelain_output() {
+        let zero = "0".repeat(40);
+        let output = format!(
+            "{HASH} 1 1 2\nauthor Jane Doe\nauthor-mail <jane@example.com>\n\
+             author-time 1714521600\nauthor-tz +0000\nsummary Fix typo\n\
+             filename a.txt\n\tone\n\
+             {zero} 2 2 1\nauthor Not Committed Yet\nauthor-time 1714600000\n\
+             summary Version of a.txt from a.txt\nfilename a.txt\n\ttwo\n\
+             {HASH} 2 3\n\tthree\n"
+        );
+        let lines = parse_blame(&output, 4);
+        let first = lines[0].as_ref().unwrap();
+        assert_eq!(first.label(), "Jane Doe, 2024-05-01 • Fix typo");
+        assert_eq!(lines[1].as_ref().unwrap().label(), "Not committed yet");
+        // Details are given once per commit
+        assert!(Arc::ptr_eq(first, lines[2].as_ref().unwrap()));
+        assert!(lines[3].is_none());
+    }
+}
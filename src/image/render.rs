@@ -4,6 +4,7 @@
 //! into the framebuffer using nearest-neighbor sampling.
 
 use crate::image::ImageState;
+use crate::model::editor_area::Rect;
 use crate::theme::ImagePreviewTheme;
 use crate::view::frame::Frame;
 
@@ -41,6 +42,24 @@ pub fn render_image(
         0.0
     };
 
+    if frame.is_recording() {
+        let area = Rect::new(
+            area_x as f32,
+            area_y as f32,
+            area_width as f32,
+            area_height as f32,
+        );
+        frame.record_checkerboard(area, cell, light, dark);
+        let dest = Rect::new(
+            (area_x as f64 + center_x - image.offset_x * image.scale) as f32,
+            (area_y as f64 + center_y - image.offset_y * image.scale) as f32,
+            (image.width as f64 * image.scale) as f32,
+            (image.height as f64 * image.scale) as f32,
+        );
+        frame.record_image(&image.pixels, image.width, image.height, dest, area);
+        return;
+    }
+
     let buf_width = frame.width();
     let buf_height = frame.height();
     let buffer = frame.buffer_mut();
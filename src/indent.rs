@@ -0,0 +1,228 @@
+//! Bracket-based re-indentation
+//!
+//! When `}` closes a block, the lines between it and its `{` are indented
+//! one unit deeper than the line holding the `{` for every bracket still
+//! open before them, and the line holding the `}` is lined up with the `{`
+//! line. Brackets inside strings and comments are skipped by the caller's
+//! `in_literal` check; lines starting inside a string are left alone.
+
+use ropey::Rope;
+
+/// Most characters scanned backwards for the opening `{`
+const MAX_SCAN_CHARS: usize = 200_000;
+
+/// New leading whitespace for one line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndent {
+    pub line: usize,
+    /// Length in chars of the whitespace being replaced
+    pub old_len: usize,
+    pub indent: String,
+}
+
+/// Indentation changes for the block closed by the `}` at `close_offset`,
+/// top to bottom; only lines whose indentation changes are returned.
+///
+/// `in_literal(line, column)` tells whether a character is part of a string
+/// or comment.
+pub fn reindent_closed_block(
+    rope: &Rope,
+    close_offset: usize,
+    unit: &str,
+    in_literal: impl Fn(usize, usize) -> bool,
+) -> Vec<LineIndent> {
+    let Some(open_offset) = find_open_brace(rope, close_offset, &in_literal) else {
+        return Vec::new();
+    };
+    let open_line = rope.char_to_line(open_offset);
+    let close_line = rope.char_to_line(close_offset);
+    if open_line == close_line {
+        return Vec::new();
+    }
+
+    let open_text = line_text(rope, open_line);
+    let base = leading_whitespace(&open_text);
+    let line_start = rope.line_to_char(open_line);
+    let mut depth = bracket_delta(&open_text, open_line, open_offset - line_start, &in_literal);
+
+    let mut changes = Vec::new();
+    for line in open_line + 1..=close_line {
+        let text = line_text(rope, line);
+        let old = leading_whitespace(&text);
+        let old_len = old.chars().count();
+        let body = &text[old.len()..];
+        let last = line == close_line;
+        if in_literal(line, 0) && !last {
+            depth += bracket_delta(&text, line, 0, &in_literal);
+            continue;
+        }
+
+        let closers = body
+            .chars()
+            .enumerate()
+            .take_while(|&(i, ch)| matches!(ch, '}' | ')' | ']') && !in_literal(line, old_len + i))
+            .count() as isize;
+        let level = if last {
+            0
+        } else {
+            (depth - closers).max(0) as usize
+        };
+        let indent = if body.is_empty() {
+            String::new()
+        } else {
+            format!("{}{}", base, unit.repeat(level))
+        };
+        if indent != old {
+            changes.push(LineIndent {
+                line,
+                old_len,
+                indent,
+            });
+        }
+        depth += bracket_delta(&text, line, 0, &in_literal);
+    }
+    changes
+}
+
+/// Indentation unit used by the document: a tab when most indented lines
+/// start with one, otherwise the most common step in leading spaces from
+/// one line to the next (4 if there is none)
+pub fn detect_indent_unit(rope: &Rope) -> String {
+    let mut tabs = 0;
+    let mut spaced = 0;
+    let mut steps = [0usize; 9];
+    let mut previous = 0;
+    for line in rope.lines().take(1000) {
+        let text: String = line.chars().collect();
+        let body = text.trim_start_matches([' ', '\t']);
+        if body.trim().is_empty() {
+            continue;
+        }
+        let indent = &text[..text.len() - body.len()];
+        if indent.starts_with('\t') {
+            tabs += 1;
+            continue;
+        }
+        let spaces = indent.len();
+        if spaces > 0 {
+            spaced += 1;
+        }
+        if spaces > previous && spaces - previous >= 2 {
+            steps[(spaces - previous).min(8)] += 1;
+        }
+        previous = spaces;
+    }
+    if tabs > spaced {
+        return "\t".to_string();
+    }
+    let step = (2..=8)
+        .rev()
+        .max_by_key(|&step| steps[step])
+        .filter(|&step| steps[step] > 0)
+        .unwrap_or(4);
+    " ".repeat(step)
+}
+
+/// Offset of the `{` matching the `}` at `close_offset`
+fn find_open_brace(
+    rope: &Rope,
+    close_offset: usize,
+    in_literal: &impl Fn(usize, usize) -> bool,
+) -> Option<usize> {
+    let mut depth = 0usize;
+    let stop = close_offset.saturating_sub(MAX_SCAN_CHARS);
+    for offset in (stop..close_offset).rev() {
+        let ch = rope.char(offset);
+        if !matches!(ch, '{' | '}') {
+            continue;
+        }
+        let line = rope.char_to_line(offset);
+        if in_literal(line, offset - rope.line_to_char(line)) {
+            continue;
+        }
+        if ch == '}' {
+            depth += 1;
+        } else if depth == 0 {
+            return Some(offset);
+        } else {
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Net brackets opened on `text` from char column `from` on
+fn bracket_delta(
+    text: &str,
+    line: usize,
+    from: usize,
+    in_literal: &impl Fn(usize, usize) -> bool,
+) -> isize {
+    text.chars()
+        .enumerate()
+        .skip(from)
+        .filter(|&(column, _)| !in_literal(line, column))
+        .map(|(_, ch)| match ch {
+            '{' | '(' | '[' => 1,
+            '}' | ')' | ']' => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn line_text(rope: &Rope, line: usize) -> String {
+    rope.line(line)
+        .chars()
+        .filter(|&ch| ch != '\n' && ch != '\r')
+        .collect()
+}
+
+fn leading_whitespace(text: &str) -> &str {
+    let end = text
+        .find(|ch: char| ch != ' ' && ch != '\t')
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reindent(text: &str) -> String {
+        let mut rope = Rope::from_str(text);
+        let close_offset = rope.len_chars() - 1;
+        let changes = reindent_closed_block(&rope, close_offset, "    ", |_, _| false);
+        for change in changes.iter().rev() {
+            let start = rope.line_to_char(change.line);
+            rope.remove(start..start + change.old_len);
+            rope.insert(start, &change.indent);
+        }
+        rope.to_string()
+    }
+
+    #[test]
+    fn closing_brace_reindents_nested_block() {
+        let text = "fn main() {\n    if x {\n  a(\nb);\n        }\n  }";
+        assert_eq!(
+            reindent(text),
+            "fn main() {\n    if x {\n        a(\n            b);\n    }\n}"
+        );
+
+        // Only the block just closed is touched, blank lines are emptied
+        let text = "fn a() {\n  x();\n}\nfn b() {\n\t  \n y();\n   }";
+        assert_eq!(
+            reindent(text),
+            "fn a() {\n  x();\n}\nfn b() {\n\n    y();\n}"
+        );
+    }
+
+    #[test]
+    fn indent_unit_follows_the_document() {
+        assert_eq!(detect_indent_unit(&Rope::from_str("a\n\tb\n\tc\n")), "\t");
+        assert_eq!(
+            detect_indent_unit(&Rope::from_str("a {\n  b {\n    c\n  }\n}\n")),
+            "  "
+        );
+        assert_eq!(detect_indent_unit(&Rope::from_str("a\n")), "    ");
+    }
+}
@@ -4,12 +4,12 @@
 //! Each command maps to one or more `Msg` values for the Elm-style update loop.
 
 use crate::messages::{
-    AppMsg, CsvMsg, Direction, DockMsg, DocumentMsg, EditorMsg, ImageMsg, LayoutMsg, Msg,
-    PreviewMsg, UiMsg, WorkspaceMsg,
+    AiChatMsg, AppMsg, CompletionMsg, CsvMsg, DiffMsg, Direction, DockMsg, DocumentMsg, EditorMsg,
+    ImageMsg, LayoutMsg, LspMsg, Msg, PreviewMsg, SearchMsg, UiMsg, VimMsg, WorkspaceMsg,
 };
 use crate::model::editor_area::SplitDirection;
 use crate::model::ModalId;
-use crate::panel::PanelId;
+use crate::panel::{Dock, DockPosition, PanelId};
 
 /// All executable editor commands that can be bound to keys
 ///
@@ -44,6 +44,10 @@ pub enum Command {
     PageUp,
     /// Move cursor down by page
     PageDown,
+    /// Scroll the view up one line without moving the cursor
+    ScrollLineUp,
+    /// Scroll the view down one line without moving the cursor
+    ScrollLineDown,
 
     // ========================================================================
     // Selection Movement (extend selection)
@@ -72,6 +76,14 @@ pub enum Command {
     PageUpWithSelection,
     /// Page down, extending selection
     PageDownWithSelection,
+    /// Grow a column (rectangle) selection up a line
+    ColumnSelectUp,
+    /// Grow a column (rectangle) selection down a line
+    ColumnSelectDown,
+    /// Grow a column (rectangle) selection left a column
+    ColumnSelectLeft,
+    /// Grow a column (rectangle) selection right a column
+    ColumnSelectRight,
 
     // ========================================================================
     // Selection Commands
@@ -102,6 +114,22 @@ pub enum Command {
     SelectNextOccurrence,
     /// Unselect the last added occurrence
     UnselectOccurrence,
+    /// Skip the last added occurrence and select the next one
+    SkipOccurrence,
+    /// Select every occurrence of the current word/selection
+    SelectAllOccurrences,
+    /// Search for the word under the cursor, jumping to the next match
+    SearchWordUnderCursor,
+    /// Search for the word under the cursor, jumping to the previous match
+    SearchWordUnderCursorBackward,
+    /// Jump to the next match of the current search term
+    FindNextMatch,
+    /// Jump to the previous match of the current search term
+    FindPreviousMatch,
+    /// Jump to the definition of the symbol at the cursor (language server)
+    GoToDefinition,
+    /// Stop highlighting search matches
+    ClearSearchHighlight,
 
     // ========================================================================
     // Text Editing
@@ -120,12 +148,20 @@ pub enum Command {
     DeleteLine,
     /// Duplicate current line or selection
     Duplicate,
+    /// Move the current or selected lines up one line
+    MoveLinesUp,
+    /// Move the current or selected lines down one line
+    MoveLinesDown,
     /// Indent selected lines
     IndentLines,
     /// Unindent selected lines
     UnindentLines,
     /// Insert a tab character (when no selection)
     InsertTab,
+    /// Offer file path completions for the string or link at the cursor
+    CompletePath,
+    /// Leave Vim insert or visual mode
+    VimNormalMode,
 
     // ========================================================================
     // Clipboard
@@ -152,9 +188,13 @@ pub enum Command {
     SaveFile,
     /// Save current file with new name
     SaveFileAs,
+    /// Save every modified file
+    SaveAllFiles,
     /// Open file dialog
     OpenFile,
-    /// Fuzzy file finder - search workspace files (Cmd+Shift+O)
+    /// Open folder dialog, opening the folder as the workspace
+    OpenFolder,
+    /// Quick Open - fuzzy search workspace files (Cmd+P)
     FuzzyFileFinder,
     /// Create new file
     NewFile,
@@ -168,8 +208,12 @@ pub enum Command {
     ToggleCommandPalette,
     /// Toggle goto line dialog
     ToggleGotoLine,
+    /// Toggle go to symbol dialog for the current document (Cmd+Shift+O)
+    ToggleGotoSymbol,
     /// Toggle find/replace dialog
     ToggleFindReplace,
+    /// Open the search panel to search the workspace (Cmd+Shift+F)
+    SearchInWorkspace,
     /// Open recent files modal
     OpenRecentFiles,
 
@@ -192,6 +236,10 @@ pub enum Command {
     FocusNextGroup,
     /// Focus previous editor group
     FocusPrevGroup,
+    /// Scroll the next editor group up one line, keeping focus
+    ScrollOtherGroupUp,
+    /// Scroll the next editor group down one line, keeping focus
+    ScrollOtherGroupDown,
     /// Focus editor group by index (1-4)
     FocusGroup1,
     FocusGroup2,
@@ -223,8 +271,46 @@ pub enum Command {
     ToggleTerminal,
     /// Toggle outline panel (right dock)
     ToggleOutline,
+    /// Toggle TODO list panel (bottom dock)
+    ToggleTodoList,
+    /// Toggle AI chat panel (right dock)
+    ToggleAiChat,
     /// Close the currently focused dock
     CloseFocusedDock,
+    /// Show/hide the left dock
+    ToggleLeftDock,
+    /// Show/hide the right dock
+    ToggleRightDock,
+    /// Show/hide the bottom dock
+    ToggleBottomDock,
+    /// Open and focus the file explorer (never closes it)
+    FocusFileExplorer,
+    /// Open and focus the terminal
+    FocusTerminal,
+    /// Open and focus the outline
+    FocusOutline,
+    /// Open and focus the TODO list
+    FocusTodoList,
+    /// Open and focus the AI chat
+    FocusAiChat,
+    /// Grow the focused dock
+    GrowDock,
+    /// Shrink the focused dock
+    ShrinkDock,
+    /// Move focus to the next area (editor → open docks)
+    FocusNextArea,
+    /// Move focus to the previous area
+    FocusPreviousArea,
+    /// Switch to the next panel tab in the focused dock
+    NextPanelInDock,
+    /// Switch to the previous panel tab in the focused dock
+    PrevPanelInDock,
+    /// Make the editor font larger
+    ZoomIn,
+    /// Make the editor font smaller
+    ZoomOut,
+    /// Back to the default font size
+    ResetZoom,
 
     // ========================================================================
     // Special
@@ -236,6 +322,16 @@ pub enum Command {
     /// Open the log file in the editor
     OpenLogFile,
 
+    // ========================================================================
+    // Folding
+    // ========================================================================
+    /// Fold or unfold the region at the cursor
+    ToggleFold,
+    /// Fold every region of the document
+    FoldAll,
+    /// Unfold everything
+    UnfoldAll,
+
     // ========================================================================
     // Markdown Preview
     // ========================================================================
@@ -243,6 +339,22 @@ pub enum Command {
     MarkdownTogglePreview,
     /// Open markdown preview to the side
     MarkdownOpenPreviewToSide,
+    /// Toggle in-place styling of Markdown source
+    MarkdownToggleHybrid,
+    /// Cycle the heading under the cursor: folded, children, subtree
+    MarkdownCycleHeadingFold,
+    /// Cycle all headings: overview, contents, show all
+    MarkdownCycleAllHeadingFolds,
+
+    // ========================================================================
+    // AI Chat
+    // ========================================================================
+    /// Ask the assistant to explain the selection
+    AiExplainSelection,
+    /// Insert the last answer at the cursor
+    AiInsertAnswer,
+    /// Replace the selection with the last answer
+    AiReplaceSelection,
 
     // ========================================================================
     // CSV Mode
@@ -263,8 +375,17 @@ pub enum Command {
     CsvPageUp,
     CsvPageDown,
     CsvExit,
-
-    // Image viewer
+    /// CSV row and column editing (bound with the csv_mode condition)
+    CsvInsertRowAbove,
+    CsvInsertRowBelow,
+    CsvDeleteRow,
+    CsvInsertColumnLeft,
+    CsvInsertColumnRight,
+    CsvDeleteColumn,
+    /// Show or hide the CSV filter row (bound with the csv_mode condition)
+    CsvToggleFilter,
+
+    // Image viewer (bound with the image_mode condition)
     /// Zoom in (image mode)
     ImageZoomIn,
     /// Zoom out (image mode)
@@ -273,6 +394,14 @@ pub enum Command {
     ImageFitToWindow,
     /// Show image at actual size (1:1)
     ImageActualSize,
+
+    // Diff view (bound with the diff_mode condition)
+    /// Scroll to the next change
+    DiffNextChange,
+    /// Scroll to the previous change
+    DiffPrevChange,
+    /// Back to the text view
+    DiffClose,
 }
 
 impl Command {
@@ -301,6 +430,8 @@ impl Command {
             }
             PageUp => vec![Msg::Editor(EditorMsg::PageUp)],
             PageDown => vec![Msg::Editor(EditorMsg::PageDown)],
+            ScrollLineUp => vec![Msg::Editor(EditorMsg::ScrollLines(-1))],
+            ScrollLineDown => vec![Msg::Editor(EditorMsg::ScrollLines(1))],
 
             // Selection movement
             MoveCursorUpWithSelection => {
@@ -343,6 +474,18 @@ impl Command {
             )],
             PageUpWithSelection => vec![Msg::Editor(EditorMsg::PageUpWithSelection)],
             PageDownWithSelection => vec![Msg::Editor(EditorMsg::PageDownWithSelection)],
+            ColumnSelectUp => vec![Msg::Editor(EditorMsg::ExtendRectangleSelection(
+                Direction::Up,
+            ))],
+            ColumnSelectDown => vec![Msg::Editor(EditorMsg::ExtendRectangleSelection(
+                Direction::Down,
+            ))],
+            ColumnSelectLeft => vec![Msg::Editor(EditorMsg::ExtendRectangleSelection(
+                Direction::Left,
+            ))],
+            ColumnSelectRight => vec![Msg::Editor(EditorMsg::ExtendRectangleSelection(
+                Direction::Right,
+            ))],
 
             // Selection commands
             SelectAll => vec![Msg::Editor(EditorMsg::SelectAll)],
@@ -358,6 +501,20 @@ impl Command {
             CollapseToSingleCursor => vec![Msg::Editor(EditorMsg::CollapseToSingleCursor)],
             SelectNextOccurrence => vec![Msg::Editor(EditorMsg::SelectNextOccurrence)],
             UnselectOccurrence => vec![Msg::Editor(EditorMsg::UnselectOccurrence)],
+            SkipOccurrence => vec![Msg::Editor(EditorMsg::SkipOccurrence)],
+            SelectAllOccurrences => vec![Msg::Editor(EditorMsg::SelectAllOccurrences)],
+            SearchWordUnderCursor => vec![Msg::Editor(EditorMsg::SearchWordUnderCursor {
+                forward: true,
+            })],
+            SearchWordUnderCursorBackward => {
+                vec![Msg::Editor(EditorMsg::SearchWordUnderCursor {
+                    forward: false,
+                })]
+            }
+            FindNextMatch => vec![Msg::Editor(EditorMsg::FindNextMatch)],
+            FindPreviousMatch => vec![Msg::Editor(EditorMsg::FindPreviousMatch)],
+            GoToDefinition => vec![Msg::Lsp(LspMsg::GoToDefinition)],
+            ClearSearchHighlight => vec![Msg::Editor(EditorMsg::ClearSearchHighlight)],
 
             // Text editing
             InsertNewline => vec![Msg::Document(DocumentMsg::InsertNewline)],
@@ -367,9 +524,13 @@ impl Command {
             DeleteWordForward => vec![Msg::Document(DocumentMsg::DeleteWordForward)],
             DeleteLine => vec![Msg::Document(DocumentMsg::DeleteLine)],
             Duplicate => vec![Msg::Document(DocumentMsg::Duplicate)],
-            IndentLines => vec![Msg::Document(DocumentMsg::IndentLines)],
-            UnindentLines => vec![Msg::Document(DocumentMsg::UnindentLines)],
+            MoveLinesUp => vec![Msg::Document(DocumentMsg::MoveLinesUp)],
+            MoveLinesDown => vec![Msg::Document(DocumentMsg::MoveLinesDown)],
+            IndentLines => vec![Msg::Document(DocumentMsg::IndentSelection)],
+            UnindentLines => vec![Msg::Document(DocumentMsg::DedentSelection)],
             InsertTab => vec![Msg::Document(DocumentMsg::InsertChar('\t'))],
+            CompletePath => vec![Msg::Completion(CompletionMsg::TriggerPath)],
+            VimNormalMode => vec![Msg::Vim(VimMsg::NormalMode)],
 
             // Clipboard
             Copy => vec![Msg::Document(DocumentMsg::Copy)],
@@ -383,7 +544,9 @@ impl Command {
             // File operations
             SaveFile => vec![Msg::App(AppMsg::SaveFile)],
             SaveFileAs => vec![Msg::App(AppMsg::SaveFileAs)],
+            SaveAllFiles => vec![Msg::App(AppMsg::SaveAllFiles)],
             OpenFile => vec![Msg::App(AppMsg::OpenFileDialog)],
+            OpenFolder => vec![Msg::App(AppMsg::OpenFolderDialog)],
             FuzzyFileFinder => vec![Msg::Ui(UiMsg::OpenFuzzyFileFinder)],
             NewFile => vec![Msg::App(AppMsg::NewFile)],
             Quit => vec![Msg::App(AppMsg::Quit)],
@@ -393,7 +556,9 @@ impl Command {
                 vec![Msg::Ui(UiMsg::ToggleModal(ModalId::CommandPalette))]
             }
             ToggleGotoLine => vec![Msg::Ui(UiMsg::ToggleModal(ModalId::GotoLine))],
+            ToggleGotoSymbol => vec![Msg::Ui(UiMsg::ToggleModal(ModalId::GotoSymbol))],
             ToggleFindReplace => vec![Msg::Ui(UiMsg::ToggleModal(ModalId::FindReplace))],
+            SearchInWorkspace => vec![Msg::Search(SearchMsg::Open)],
             OpenRecentFiles => vec![Msg::Ui(UiMsg::ToggleModal(ModalId::RecentFiles))],
 
             // Layout
@@ -413,6 +578,8 @@ impl Command {
             }
             FocusNextGroup => vec![Msg::Layout(LayoutMsg::FocusNextGroup)],
             FocusPrevGroup => vec![Msg::Layout(LayoutMsg::FocusPrevGroup)],
+            ScrollOtherGroupUp => vec![Msg::Layout(LayoutMsg::ScrollOtherGroup(-1))],
+            ScrollOtherGroupDown => vec![Msg::Layout(LayoutMsg::ScrollOtherGroup(1))],
             FocusGroup1 => vec![Msg::Layout(LayoutMsg::FocusGroupByIndex(1))],
             FocusGroup2 => vec![Msg::Layout(LayoutMsg::FocusGroupByIndex(2))],
             FocusGroup3 => vec![Msg::Layout(LayoutMsg::FocusGroupByIndex(3))],
@@ -434,7 +601,30 @@ impl Command {
             ))],
             ToggleTerminal => vec![Msg::Dock(DockMsg::FocusOrTogglePanel(PanelId::TERMINAL))],
             ToggleOutline => vec![Msg::Dock(DockMsg::FocusOrTogglePanel(PanelId::OUTLINE))],
+            ToggleTodoList => vec![Msg::Dock(DockMsg::FocusOrTogglePanel(PanelId::TODO_LIST))],
+            ToggleAiChat => vec![Msg::Dock(DockMsg::FocusOrTogglePanel(PanelId::AI_CHAT))],
             CloseFocusedDock => vec![Msg::Dock(DockMsg::CloseFocusedDock)],
+            ToggleLeftDock => vec![Msg::Dock(DockMsg::ToggleDock(DockPosition::Left))],
+            ToggleRightDock => vec![Msg::Dock(DockMsg::ToggleDock(DockPosition::Right))],
+            ToggleBottomDock => vec![Msg::Dock(DockMsg::ToggleDock(DockPosition::Bottom))],
+            FocusFileExplorer => vec![Msg::Dock(DockMsg::ActivatePanel(PanelId::FILE_EXPLORER))],
+            FocusTerminal => vec![Msg::Dock(DockMsg::ActivatePanel(PanelId::TERMINAL))],
+            FocusOutline => vec![Msg::Dock(DockMsg::ActivatePanel(PanelId::OUTLINE))],
+            FocusTodoList => vec![Msg::Dock(DockMsg::ActivatePanel(PanelId::TODO_LIST))],
+            FocusAiChat => vec![Msg::Dock(DockMsg::ActivatePanel(PanelId::AI_CHAT))],
+            GrowDock => vec![Msg::Dock(DockMsg::ResizeFocusedDock {
+                delta: Dock::KEYBOARD_RESIZE_STEP,
+            })],
+            ShrinkDock => vec![Msg::Dock(DockMsg::ResizeFocusedDock {
+                delta: -Dock::KEYBOARD_RESIZE_STEP,
+            })],
+            FocusNextArea => vec![Msg::Dock(DockMsg::FocusNextArea)],
+            FocusPreviousArea => vec![Msg::Dock(DockMsg::FocusPreviousArea)],
+            NextPanelInDock => vec![Msg::Dock(DockMsg::NextPanelInDock)],
+            PrevPanelInDock => vec![Msg::Dock(DockMsg::PrevPanelInDock)],
+            ZoomIn => vec![Msg::Ui(UiMsg::ZoomIn)],
+            ZoomOut => vec![Msg::Ui(UiMsg::ZoomOut)],
+            ResetZoom => vec![Msg::Ui(UiMsg::ResetZoom)],
 
             // Special - these need context-aware handling
             EscapeSmartClear => {
@@ -451,9 +641,22 @@ impl Command {
                 }
             }
 
+            // Folding
+            ToggleFold => vec![Msg::Editor(EditorMsg::ToggleFold)],
+            FoldAll => vec![Msg::Editor(EditorMsg::FoldAll)],
+            UnfoldAll => vec![Msg::Editor(EditorMsg::UnfoldAll)],
+
             // Markdown preview
             MarkdownTogglePreview => vec![Msg::Preview(PreviewMsg::Toggle)],
             MarkdownOpenPreviewToSide => vec![Msg::Preview(PreviewMsg::Open)],
+            MarkdownToggleHybrid => vec![Msg::Preview(PreviewMsg::ToggleHybrid)],
+            MarkdownCycleHeadingFold => vec![Msg::Editor(EditorMsg::CycleHeadingFold)],
+            MarkdownCycleAllHeadingFolds => vec![Msg::Editor(EditorMsg::CycleAllHeadingFolds)],
+
+            // AI chat
+            AiExplainSelection => vec![Msg::AiChat(AiChatMsg::ExplainSelection)],
+            AiInsertAnswer => vec![Msg::AiChat(AiChatMsg::InsertAnswerAtCursor)],
+            AiReplaceSelection => vec![Msg::AiChat(AiChatMsg::ReplaceSelectionWithAnswer)],
 
             // CSV mode
             CsvToggle => vec![Msg::Csv(CsvMsg::Toggle)],
@@ -470,6 +673,13 @@ impl Command {
             CsvPageUp => vec![Msg::Csv(CsvMsg::PageUp)],
             CsvPageDown => vec![Msg::Csv(CsvMsg::PageDown)],
             CsvExit => vec![Msg::Csv(CsvMsg::Exit)],
+            CsvInsertRowAbove => vec![Msg::Csv(CsvMsg::InsertRowAbove)],
+            CsvInsertRowBelow => vec![Msg::Csv(CsvMsg::InsertRowBelow)],
+            CsvDeleteRow => vec![Msg::Csv(CsvMsg::DeleteRow)],
+            CsvInsertColumnLeft => vec![Msg::Csv(CsvMsg::InsertColumnLeft)],
+            CsvInsertColumnRight => vec![Msg::Csv(CsvMsg::InsertColumnRight)],
+            CsvDeleteColumn => vec![Msg::Csv(CsvMsg::DeleteColumn)],
+            CsvToggleFilter => vec![Msg::Csv(CsvMsg::ToggleFilterRow)],
 
             // Image viewer
             ImageZoomIn => vec![Msg::Image(ImageMsg::Zoom {
@@ -484,6 +694,11 @@ impl Command {
             })],
             ImageFitToWindow => vec![Msg::Image(ImageMsg::FitToWindow)],
             ImageActualSize => vec![Msg::Image(ImageMsg::ActualSize)],
+
+            // Diff view
+            DiffNextChange => vec![Msg::Diff(DiffMsg::NextChange)],
+            DiffPrevChange => vec![Msg::Diff(DiffMsg::PrevChange)],
+            DiffClose => vec![Msg::Diff(DiffMsg::Close)],
         }
     }
 
@@ -504,16 +719,41 @@ impl Command {
             self,
             Command::ToggleCommandPalette
                 | Command::ToggleGotoLine
+                | Command::ToggleGotoSymbol
                 | Command::ToggleFindReplace
+                | Command::SearchInWorkspace
                 | Command::OpenRecentFiles
                 | Command::FuzzyFileFinder
                 | Command::ToggleSidebar
                 | Command::ToggleFileExplorer
                 | Command::ToggleTerminal
                 | Command::ToggleOutline
+                | Command::ToggleTodoList
+                | Command::ToggleAiChat
                 | Command::CloseFocusedDock
+                | Command::ToggleLeftDock
+                | Command::ToggleRightDock
+                | Command::ToggleBottomDock
+                | Command::FocusFileExplorer
+                | Command::FocusTerminal
+                | Command::FocusOutline
+                | Command::FocusTodoList
+                | Command::FocusAiChat
+                | Command::GrowDock
+                | Command::ShrinkDock
+                | Command::FocusNextArea
+                | Command::FocusPreviousArea
+                | Command::NextPanelInDock
+                | Command::PrevPanelInDock
+                | Command::ZoomIn
+                | Command::ZoomOut
+                | Command::ResetZoom
                 | Command::Quit
                 | Command::SaveFile
+                | Command::SaveAllFiles
+                | Command::OpenFile
+                | Command::OpenFolder
+                | Command::NewFile
                 | Command::NewTab
                 | Command::CloseTab
         )
@@ -536,6 +776,8 @@ impl Command {
             MoveCursorWordRight => "Move Word Right",
             PageUp => "Page Up",
             PageDown => "Page Down",
+            ScrollLineUp => "Scroll Line Up",
+            ScrollLineDown => "Scroll Line Down",
 
             MoveCursorUpWithSelection => "Select Up",
             MoveCursorDownWithSelection => "Select Down",
@@ -549,6 +791,10 @@ impl Command {
             MoveCursorWordRightWithSelection => "Select Word Right",
             PageUpWithSelection => "Select Page Up",
             PageDownWithSelection => "Select Page Down",
+            ColumnSelectUp => "Column Select Up",
+            ColumnSelectDown => "Column Select Down",
+            ColumnSelectLeft => "Column Select Left",
+            ColumnSelectRight => "Column Select Right",
 
             SelectAll => "Select All",
             SelectWord => "Select Word",
@@ -562,6 +808,14 @@ impl Command {
             CollapseToSingleCursor => "Single Cursor",
             SelectNextOccurrence => "Select Next Occurrence",
             UnselectOccurrence => "Unselect Occurrence",
+            SkipOccurrence => "Skip Occurrence",
+            SelectAllOccurrences => "Select All Occurrences",
+            SearchWordUnderCursor => "Search Word Under Cursor Forward",
+            SearchWordUnderCursorBackward => "Search Word Under Cursor Backward",
+            FindNextMatch => "Find Next",
+            FindPreviousMatch => "Find Previous",
+            GoToDefinition => "Go to Definition",
+            ClearSearchHighlight => "Clear Search Highlight",
 
             InsertNewline => "Insert Newline",
             DeleteBackward => "Delete Backward",
@@ -570,9 +824,13 @@ impl Command {
             DeleteWordForward => "Delete Word Forward",
             DeleteLine => "Delete Line",
             Duplicate => "Duplicate Line",
+            MoveLinesUp => "Move Lines Up",
+            MoveLinesDown => "Move Lines Down",
             IndentLines => "Indent",
             UnindentLines => "Unindent",
             InsertTab => "Insert Tab",
+            CompletePath => "Complete Path",
+            VimNormalMode => "Vim: Normal Mode",
 
             Copy => "Copy",
             Cut => "Cut",
@@ -583,14 +841,18 @@ impl Command {
 
             SaveFile => "Save File",
             SaveFileAs => "Save File As",
+            SaveAllFiles => "Save All Files",
             OpenFile => "Open File",
+            OpenFolder => "Open Folder",
             FuzzyFileFinder => "Go to File",
             NewFile => "New File",
             Quit => "Quit",
 
             ToggleCommandPalette => "Command Palette",
             ToggleGotoLine => "Go to Line",
+            ToggleGotoSymbol => "Go to Symbol",
             ToggleFindReplace => "Find and Replace",
+            SearchInWorkspace => "Find in Workspace",
             OpenRecentFiles => "Open Recent Files",
 
             NewTab => "New Tab",
@@ -601,6 +863,8 @@ impl Command {
             SplitVertical => "Split Down",
             FocusNextGroup => "Focus Next Group",
             FocusPrevGroup => "Focus Previous Group",
+            ScrollOtherGroupUp => "Scroll Other Group Up",
+            ScrollOtherGroupDown => "Scroll Other Group Down",
             FocusGroup1 => "Focus Group 1",
             FocusGroup2 => "Focus Group 2",
             FocusGroup3 => "Focus Group 3",
@@ -616,14 +880,44 @@ impl Command {
             ToggleFileExplorer => "View: Toggle File Explorer",
             ToggleTerminal => "View: Toggle Terminal",
             ToggleOutline => "View: Toggle Outline",
+            ToggleTodoList => "View: Toggle TODO List",
+            ToggleAiChat => "View: Toggle AI Chat",
             CloseFocusedDock => "View: Close Panel",
+            ToggleLeftDock => "View: Toggle Left Dock",
+            ToggleRightDock => "View: Toggle Right Dock",
+            ToggleBottomDock => "View: Toggle Bottom Dock",
+            FocusFileExplorer => "View: Focus File Explorer",
+            FocusTerminal => "View: Focus Terminal",
+            FocusOutline => "View: Focus Outline",
+            FocusTodoList => "View: Focus TODO List",
+            FocusAiChat => "View: Focus AI Chat",
+            GrowDock => "View: Grow Panel",
+            ShrinkDock => "View: Shrink Panel",
+            FocusNextArea => "View: Focus Next Area",
+            FocusPreviousArea => "View: Focus Previous Area",
+            NextPanelInDock => "View: Next Panel Tab",
+            PrevPanelInDock => "View: Previous Panel Tab",
+            ZoomIn => "View: Zoom In",
+            ZoomOut => "View: Zoom Out",
+            ResetZoom => "View: Reset Zoom",
 
             EscapeSmartClear => "Escape",
             Unbound => "Unbound",
             OpenLogFile => "Open Log File",
 
+            ToggleFold => "Toggle Fold",
+            FoldAll => "Fold All",
+            UnfoldAll => "Unfold All",
+
             MarkdownTogglePreview => "Toggle Markdown Preview",
             MarkdownOpenPreviewToSide => "Open Markdown Preview to Side",
+            MarkdownToggleHybrid => "Toggle Markdown Hybrid View",
+            MarkdownCycleHeadingFold => "Cycle Heading Fold",
+            MarkdownCycleAllHeadingFolds => "Cycle All Heading Folds",
+
+            AiExplainSelection => "AI: Explain Selection",
+            AiInsertAnswer => "AI: Insert Answer at Cursor",
+            AiReplaceSelection => "AI: Replace Selection with Answer",
 
             CsvToggle => "Toggle CSV View",
             CsvMoveUp => "CSV Move Up",
@@ -639,11 +933,22 @@ impl Command {
             CsvPageUp => "CSV Page Up",
             CsvPageDown => "CSV Page Down",
             CsvExit => "Exit CSV View",
+            CsvInsertRowAbove => "CSV Insert Row Above",
+            CsvInsertRowBelow => "CSV Insert Row Below",
+            CsvDeleteRow => "CSV Delete Row",
+            CsvInsertColumnLeft => "CSV Insert Column Left",
+            CsvInsertColumnRight => "CSV Insert Column Right",
+            CsvDeleteColumn => "CSV Delete Column",
+            CsvToggleFilter => "CSV Toggle Filter Row",
 
             ImageZoomIn => "Image: Zoom In",
             ImageZoomOut => "Image: Zoom Out",
             ImageFitToWindow => "Image: Fit to Window",
             ImageActualSize => "Image: Actual Size",
+
+            DiffNextChange => "Diff: Next Change",
+            DiffPrevChange => "Diff: Previous Change",
+            DiffClose => "Diff: Close",
         }
     }
 }
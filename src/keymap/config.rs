@@ -1,10 +1,14 @@
 //! YAML configuration parsing for keymaps
 //!
-//! Parses keymap.yaml files into Keybinding structs.
+//! Parses keymap.yaml files into Keybinding structs, finds bindings that
+//! conflict with each other, and watches the user keymap for changes.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
 
+use notify_debouncer_mini::{new_debouncer, DebouncedEventKind, Debouncer};
 use serde::Deserialize;
 
 use super::binding::Keybinding;
@@ -38,13 +42,27 @@ pub fn load_keymap_file(path: &Path) -> Result<Vec<Keybinding>, KeymapError> {
 
 /// Parse keybindings from YAML string
 pub fn parse_keymap_yaml(yaml: &str) -> Result<Vec<Keybinding>, KeymapError> {
+    Ok(parse_keymap_yaml_with_lines(yaml)?
+        .into_iter()
+        .map(|(binding, _)| binding)
+        .collect())
+}
+
+/// Keybindings with the 0-indexed line each one starts on (when known)
+pub type BindingsWithLines = Vec<(Keybinding, Option<usize>)>;
+
+/// Parse keybindings from YAML string, with the line each binding starts on
+pub fn parse_keymap_yaml_with_lines(yaml: &str) -> Result<BindingsWithLines, KeymapError> {
     let config: KeymapConfig =
         serde_yaml::from_str(yaml).map_err(|e| KeymapError::ParseError(e.to_string()))?;
 
     let current_platform = get_current_platform();
+    let lines = binding_lines(yaml);
+    // Only trust the lines when every entry was found
+    let lines = (lines.len() == config.bindings.len()).then_some(lines);
     let mut bindings = Vec::new();
 
-    for entry in config.bindings {
+    for (index, entry) in config.bindings.into_iter().enumerate() {
         // Skip if platform-specific and doesn't match current platform
         if let Some(ref platform) = entry.platform {
             if platform != current_platform {
@@ -52,20 +70,207 @@ pub fn parse_keymap_yaml(yaml: &str) -> Result<Vec<Keybinding>, KeymapError> {
             }
         }
 
-        let keystroke = parse_key_string(&entry.key)?;
+        let keystrokes = parse_key_sequence(&entry.key)?;
         let command = parse_command(&entry.command)?;
         let conditions = parse_conditions(&entry.when)?;
 
-        let mut binding = Keybinding::new(keystroke, command);
+        let mut binding = Keybinding::chord(keystrokes, command);
         if let Some(conds) = conditions {
             binding = binding.when(conds);
         }
-        bindings.push(binding);
+        bindings.push((binding, lines.as_ref().map(|lines| lines[index])));
     }
 
     Ok(bindings)
 }
 
+/// Lines where the entries of the `bindings:` list start: the `- ` items
+/// indented like the first one
+fn binding_lines(yaml: &str) -> Vec<usize> {
+    let mut lines = yaml.lines().enumerate();
+    if !lines.any(|(_, line)| line.trim_end() == "bindings:") {
+        return Vec::new();
+    }
+
+    let mut indent = None;
+    let mut starts = Vec::new();
+    for (index, line) in lines {
+        let trimmed = line.trim_start();
+        if !(trimmed.starts_with("- ") || trimmed == "-") {
+            continue;
+        }
+        let this_indent = line.len() - trimmed.len();
+        if *indent.get_or_insert(this_indent) == this_indent {
+            starts.push(index);
+        }
+    }
+    starts
+}
+
+/// A problem found while loading a keymap, listed by "Keymap: Show
+/// Diagnostics"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapDiagnostic {
+    /// The keymap file, or `None` for the embedded defaults
+    pub path: Option<PathBuf>,
+    /// 0-indexed line of the binding, when known
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Report bindings on the same keystroke in the same context. Only the
+/// first of them can ever run, so each later one is reported, at its own
+/// line. A single-keystroke binding is also reported when a chord in the
+/// same context starts with its key, since the chord takes that key.
+pub fn find_conflicts(
+    bindings: &[(Keybinding, Option<usize>)],
+    path: Option<&Path>,
+) -> Vec<KeymapDiagnostic> {
+    let mut diagnostics = Vec::new();
+    for (index, (binding, line)) in bindings.iter().enumerate() {
+        let context = context_key(binding);
+        let mut keys = keys_display(binding);
+        if !context.is_empty() {
+            let names: Vec<_> = context.iter().map(|c| c.name()).collect();
+            keys.push_str(&format!(" when [{}]", names.join(", ")));
+        }
+
+        let duplicate = bindings[..index].iter().find(|(other, _)| {
+            other.keystrokes == binding.keystrokes && context_key(other) == context
+        });
+        let message = if let Some((first, first_line)) = duplicate {
+            let earlier = line_display(*first_line, "an earlier binding");
+            if first.command == binding.command {
+                format!(
+                    "{}: {} is bound twice (also on {})",
+                    keys,
+                    binding.command.display_name(),
+                    earlier
+                )
+            } else {
+                format!(
+                    "{}: {} never runs, {} on {} takes the key",
+                    keys,
+                    binding.command.display_name(),
+                    first.command.display_name(),
+                    earlier
+                )
+            }
+        } else if let Some((chord, chord_line)) = bindings.iter().find(|(other, _)| {
+            !binding.is_chord()
+                && other.is_chord()
+                && other.keystrokes[0] == binding.keystrokes[0]
+                && context_key(other) == context
+        }) {
+            format!(
+                "{}: {} never runs, the chord {} ({}) on {} starts with it",
+                keys,
+                binding.command.display_name(),
+                keys_display(chord),
+                chord.command.display_name(),
+                line_display(*chord_line, "another binding")
+            )
+        } else {
+            continue;
+        };
+        diagnostics.push(KeymapDiagnostic {
+            path: path.map(Path::to_path_buf),
+            line: *line,
+            message,
+        });
+    }
+    diagnostics
+}
+
+/// A binding's keystrokes as written in a keymap, e.g. "ctrl+k ctrl+c"
+fn keys_display(binding: &Keybinding) -> String {
+    binding
+        .keystrokes
+        .iter()
+        .map(|k| k.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// "line N" for a known line, else the fallback
+fn line_display(line: Option<usize>, fallback: &str) -> String {
+    match line {
+        Some(line) => format!("line {}", line + 1),
+        None => fallback.to_string(),
+    }
+}
+
+/// A binding's conditions as a set, so `[a, b]` and `[b, a]` compare equal
+fn context_key(binding: &Keybinding) -> Vec<Condition> {
+    let mut conditions = binding.when.clone().unwrap_or_default();
+    conditions.sort_by_key(|c| c.name());
+    conditions.dedup();
+    conditions
+}
+
+/// Watches the user keymap file so bindings can be reloaded when it is
+/// saved
+///
+/// The folder is watched rather than the file, so the watch survives
+/// editors that save by renaming a new file over the old one, and a keymap
+/// created after launch is picked up too.
+pub struct KeymapWatcher {
+    path: PathBuf,
+    _debouncer: Debouncer<notify::RecommendedWatcher>,
+    rx: Receiver<Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>>,
+}
+
+impl KeymapWatcher {
+    /// Watch `path` (the user keymap). Fails when its folder doesn't exist.
+    pub fn new(path: PathBuf) -> Result<Self, notify::Error> {
+        let (tx, rx) = mpsc::channel();
+        let mut debouncer = new_debouncer(Duration::from_millis(300), tx)?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        debouncer
+            .watcher()
+            .watch(dir, notify::RecursiveMode::NonRecursive)?;
+        Ok(Self {
+            path,
+            _debouncer: debouncer,
+            rx,
+        })
+    }
+
+    /// The watched keymap file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the keymap changed since the last poll (non-blocking)
+    pub fn poll_changed(&self) -> bool {
+        let mut changed = false;
+        while let Ok(result) = self.rx.try_recv() {
+            match result {
+                Ok(events) => {
+                    changed |= events.iter().any(|event| {
+                        event.kind != DebouncedEventKind::AnyContinuous && event.path == self.path
+                    });
+                }
+                Err(e) => tracing::warn!("Keymap watcher error: {:?}", e),
+            }
+        }
+        changed
+    }
+}
+
+/// Parse a space separated key sequence like "cmd+k cmd+c" into the
+/// keystrokes of a chord (a single keystroke when there are no spaces)
+pub fn parse_key_sequence(key_str: &str) -> Result<Vec<Keystroke>, KeymapError> {
+    let keystrokes = key_str
+        .split_whitespace()
+        .map(parse_key_string)
+        .collect::<Result<Vec<_>, _>>()?;
+    if keystrokes.is_empty() {
+        return Err(KeymapError::InvalidKey(key_str.to_string()));
+    }
+    Ok(keystrokes)
+}
+
 /// Parse a key string like "cmd+shift+s" into a Keystroke
 pub fn parse_key_string(key_str: &str) -> Result<Keystroke, KeymapError> {
     let parts: Vec<&str> = key_str.split('+').collect();
@@ -210,6 +415,13 @@ fn parse_condition(cond: &str) -> Result<Condition, KeymapError> {
         "modal_active" | "modalactive" | "modal" => Ok(Condition::ModalActive),
         "modal_inactive" | "modalinactive" | "no_modal" | "nomodal" => Ok(Condition::ModalInactive),
         "editor_focused" | "editorfocused" | "editor" => Ok(Condition::EditorFocused),
+        "column_selection" | "columnselection" => Ok(Condition::ColumnSelection),
+        "vim_normal" | "vimnormal" => Ok(Condition::VimNormal),
+        "vim_insert" | "viminsert" => Ok(Condition::VimInsert),
+        "vim_visual" | "vimvisual" => Ok(Condition::VimVisual),
+        "csv_mode" | "csvmode" | "csv" => Ok(Condition::CsvMode),
+        "diff_mode" | "diffmode" | "diff" => Ok(Condition::DiffMode),
+        "image_mode" | "imagemode" | "image" => Ok(Condition::ImageMode),
         _ => Err(KeymapError::InvalidCondition(cond.to_string())),
     }
 }
@@ -268,6 +480,8 @@ impl FromStr for Command {
             "MoveCursorWordRight" => Ok(Command::MoveCursorWordRight),
             "PageUp" => Ok(Command::PageUp),
             "PageDown" => Ok(Command::PageDown),
+            "ScrollLineUp" => Ok(Command::ScrollLineUp),
+            "ScrollLineDown" => Ok(Command::ScrollLineDown),
 
             // Selection movement
             "MoveCursorUpWithSelection" => Ok(Command::MoveCursorUpWithSelection),
@@ -284,6 +498,10 @@ impl FromStr for Command {
             "MoveCursorWordRightWithSelection" => Ok(Command::MoveCursorWordRightWithSelection),
             "PageUpWithSelection" => Ok(Command::PageUpWithSelection),
             "PageDownWithSelection" => Ok(Command::PageDownWithSelection),
+            "ColumnSelectUp" => Ok(Command::ColumnSelectUp),
+            "ColumnSelectDown" => Ok(Command::ColumnSelectDown),
+            "ColumnSelectLeft" => Ok(Command::ColumnSelectLeft),
+            "ColumnSelectRight" => Ok(Command::ColumnSelectRight),
 
             // Selection commands
             "SelectAll" => Ok(Command::SelectAll),
@@ -299,6 +517,14 @@ impl FromStr for Command {
             "CollapseToSingleCursor" => Ok(Command::CollapseToSingleCursor),
             "SelectNextOccurrence" => Ok(Command::SelectNextOccurrence),
             "UnselectOccurrence" => Ok(Command::UnselectOccurrence),
+            "SkipOccurrence" => Ok(Command::SkipOccurrence),
+            "SelectAllOccurrences" => Ok(Command::SelectAllOccurrences),
+            "SearchWordUnderCursor" => Ok(Command::SearchWordUnderCursor),
+            "SearchWordUnderCursorBackward" => Ok(Command::SearchWordUnderCursorBackward),
+            "FindNextMatch" => Ok(Command::FindNextMatch),
+            "FindPreviousMatch" => Ok(Command::FindPreviousMatch),
+            "GoToDefinition" => Ok(Command::GoToDefinition),
+            "ClearSearchHighlight" => Ok(Command::ClearSearchHighlight),
 
             // Text editing
             "InsertNewline" => Ok(Command::InsertNewline),
@@ -308,9 +534,13 @@ impl FromStr for Command {
             "DeleteWordForward" => Ok(Command::DeleteWordForward),
             "DeleteLine" => Ok(Command::DeleteLine),
             "Duplicate" => Ok(Command::Duplicate),
+            "MoveLinesUp" => Ok(Command::MoveLinesUp),
+            "MoveLinesDown" => Ok(Command::MoveLinesDown),
             "IndentLines" => Ok(Command::IndentLines),
             "UnindentLines" => Ok(Command::UnindentLines),
             "InsertTab" => Ok(Command::InsertTab),
+            "CompletePath" => Ok(Command::CompletePath),
+            "VimNormalMode" => Ok(Command::VimNormalMode),
 
             // Clipboard
             "Copy" => Ok(Command::Copy),
@@ -324,7 +554,9 @@ impl FromStr for Command {
             // File operations
             "SaveFile" => Ok(Command::SaveFile),
             "SaveFileAs" => Ok(Command::SaveFileAs),
+            "SaveAllFiles" => Ok(Command::SaveAllFiles),
             "OpenFile" => Ok(Command::OpenFile),
+            "OpenFolder" => Ok(Command::OpenFolder),
             "FuzzyFileFinder" => Ok(Command::FuzzyFileFinder),
             "NewFile" => Ok(Command::NewFile),
             "Quit" => Ok(Command::Quit),
@@ -332,7 +564,9 @@ impl FromStr for Command {
             // Modals
             "ToggleCommandPalette" => Ok(Command::ToggleCommandPalette),
             "ToggleGotoLine" => Ok(Command::ToggleGotoLine),
+            "ToggleGotoSymbol" => Ok(Command::ToggleGotoSymbol),
             "ToggleFindReplace" => Ok(Command::ToggleFindReplace),
+            "SearchInWorkspace" => Ok(Command::SearchInWorkspace),
             "OpenRecentFiles" => Ok(Command::OpenRecentFiles),
 
             // Layout
@@ -344,6 +578,8 @@ impl FromStr for Command {
             "SplitVertical" => Ok(Command::SplitVertical),
             "FocusNextGroup" => Ok(Command::FocusNextGroup),
             "FocusPrevGroup" => Ok(Command::FocusPrevGroup),
+            "ScrollOtherGroupUp" => Ok(Command::ScrollOtherGroupUp),
+            "ScrollOtherGroupDown" => Ok(Command::ScrollOtherGroupDown),
             "FocusGroup1" => Ok(Command::FocusGroup1),
             "FocusGroup2" => Ok(Command::FocusGroup2),
             "FocusGroup3" => Ok(Command::FocusGroup3),
@@ -361,11 +597,52 @@ impl FromStr for Command {
             "ToggleFileExplorer" => Ok(Command::ToggleFileExplorer),
             "ToggleTerminal" => Ok(Command::ToggleTerminal),
             "ToggleOutline" => Ok(Command::ToggleOutline),
+            "ToggleTodoList" => Ok(Command::ToggleTodoList),
+            "ToggleAiChat" => Ok(Command::ToggleAiChat),
             "CloseFocusedDock" => Ok(Command::CloseFocusedDock),
+            "ToggleLeftDock" => Ok(Command::ToggleLeftDock),
+            "ToggleRightDock" => Ok(Command::ToggleRightDock),
+            "ToggleBottomDock" => Ok(Command::ToggleBottomDock),
+            "FocusFileExplorer" => Ok(Command::FocusFileExplorer),
+            "FocusTerminal" => Ok(Command::FocusTerminal),
+            "FocusOutline" => Ok(Command::FocusOutline),
+            "FocusTodoList" => Ok(Command::FocusTodoList),
+            "FocusAiChat" => Ok(Command::FocusAiChat),
+            "GrowDock" => Ok(Command::GrowDock),
+            "ShrinkDock" => Ok(Command::ShrinkDock),
+            "FocusNextArea" => Ok(Command::FocusNextArea),
+            "FocusPreviousArea" => Ok(Command::FocusPreviousArea),
+            "NextPanelInDock" => Ok(Command::NextPanelInDock),
+            "PrevPanelInDock" => Ok(Command::PrevPanelInDock),
+            "ZoomIn" => Ok(Command::ZoomIn),
+            "ZoomOut" => Ok(Command::ZoomOut),
+            "ResetZoom" => Ok(Command::ResetZoom),
+
+            // Folding
+            "ToggleFold" => Ok(Command::ToggleFold),
+            "FoldAll" => Ok(Command::FoldAll),
+            "UnfoldAll" => Ok(Command::UnfoldAll),
 
             // Markdown preview
             "MarkdownTogglePreview" => Ok(Command::MarkdownTogglePreview),
             "MarkdownOpenPreviewToSide" => Ok(Command::MarkdownOpenPreviewToSide),
+            "MarkdownToggleHybrid" => Ok(Command::MarkdownToggleHybrid),
+            "MarkdownCycleHeadingFold" => Ok(Command::MarkdownCycleHeadingFold),
+            "MarkdownCycleAllHeadingFolds" => Ok(Command::MarkdownCycleAllHeadingFolds),
+
+            // AI chat
+            "AiExplainSelection" => Ok(Command::AiExplainSelection),
+            "AiInsertAnswer" => Ok(Command::AiInsertAnswer),
+            "AiReplaceSelection" => Ok(Command::AiReplaceSelection),
+
+            // CSV mode
+            "CsvInsertRowAbove" => Ok(Command::CsvInsertRowAbove),
+            "CsvInsertRowBelow" => Ok(Command::CsvInsertRowBelow),
+            "CsvDeleteRow" => Ok(Command::CsvDeleteRow),
+            "CsvInsertColumnLeft" => Ok(Command::CsvInsertColumnLeft),
+            "CsvInsertColumnRight" => Ok(Command::CsvInsertColumnRight),
+            "CsvDeleteColumn" => Ok(Command::CsvDeleteColumn),
+            "CsvToggleFilter" => Ok(Command::CsvToggleFilter),
 
             // Image viewer
             "ImageZoomIn" => Ok(Command::ImageZoomIn),
@@ -373,6 +650,11 @@ impl FromStr for Command {
             "ImageFitToWindow" => Ok(Command::ImageFitToWindow),
             "ImageActualSize" => Ok(Command::ImageActualSize),
 
+            // Diff view
+            "DiffNextChange" => Ok(Command::DiffNextChange),
+            "DiffPrevChange" => Ok(Command::DiffPrevChange),
+            "DiffClose" => Ok(Command::DiffClose),
+
             // Special
             "EscapeSmartClear" => Ok(Command::EscapeSmartClear),
             "Unbound" => Ok(Command::Unbound),
@@ -451,6 +733,26 @@ bindings:
         assert_eq!(bindings[1].command, Command::Undo);
     }
 
+    #[test]
+    fn test_parse_yaml_chord() {
+        let yaml = r#"
+bindings:
+  - key: "ctrl+k  ctrl+c"
+    command: Copy
+"#;
+
+        let bindings = parse_keymap_yaml(yaml).unwrap();
+        assert_eq!(
+            bindings[0].keystrokes,
+            vec![
+                parse_key_string("ctrl+k").unwrap(),
+                parse_key_string("ctrl+c").unwrap()
+            ]
+        );
+        assert!(parse_key_sequence("   ").is_err());
+        assert!(parse_key_sequence("ctrl+k ctrl+nope").is_err());
+    }
+
     #[test]
     fn test_parse_yaml_with_platform() {
         let yaml = r#"
@@ -18,6 +18,17 @@ pub struct KeyContext {
     pub editor_focused: bool,
     /// Whether the sidebar file tree has focus
     pub sidebar_focused: bool,
+    /// Whether a column selection is being grown with Shift+Alt+arrows
+    pub column_selection: bool,
+    /// The Vim mode, when Vim emulation is on
+    pub vim_mode: Option<crate::vim::VimMode>,
+    /// Whether the focused editor shows a CSV grid (and no cell is being
+    /// edited)
+    pub csv_mode: bool,
+    /// Whether the focused editor shows a diff view
+    pub diff_mode: bool,
+    /// Whether the focused editor shows an image
+    pub image_mode: bool,
 }
 
 impl KeyContext {
@@ -29,6 +40,11 @@ impl KeyContext {
             modal_active: false,
             editor_focused: true,
             sidebar_focused: false,
+            column_selection: false,
+            vim_mode: None,
+            csv_mode: false,
+            diff_mode: false,
+            image_mode: false,
         }
     }
 
@@ -40,6 +56,11 @@ impl KeyContext {
             modal_active: true,
             editor_focused: false,
             sidebar_focused: false,
+            column_selection: false,
+            vim_mode: None,
+            csv_mode: false,
+            diff_mode: false,
+            image_mode: false,
         }
     }
 }
@@ -66,9 +87,44 @@ pub enum Condition {
     EditorFocused,
     /// Binding only active when sidebar has focus
     SidebarFocused,
+    /// Binding only active while a column selection is being grown
+    ColumnSelection,
+    /// Binding only active in Vim normal mode
+    VimNormal,
+    /// Binding only active in Vim insert mode
+    VimInsert,
+    /// Binding only active in Vim visual mode
+    VimVisual,
+    /// Binding only active in the CSV grid view
+    CsvMode,
+    /// Binding only active in the diff view
+    DiffMode,
+    /// Binding only active in the image viewer
+    ImageMode,
 }
 
 impl Condition {
+    /// The name used in keymap files
+    pub fn name(self) -> &'static str {
+        match self {
+            Condition::HasSelection => "has_selection",
+            Condition::NoSelection => "no_selection",
+            Condition::HasMultipleCursors => "has_multiple_cursors",
+            Condition::SingleCursor => "single_cursor",
+            Condition::ModalActive => "modal_active",
+            Condition::ModalInactive => "modal_inactive",
+            Condition::EditorFocused => "editor_focused",
+            Condition::SidebarFocused => "sidebar_focused",
+            Condition::ColumnSelection => "column_selection",
+            Condition::VimNormal => "vim_normal",
+            Condition::VimInsert => "vim_insert",
+            Condition::VimVisual => "vim_visual",
+            Condition::CsvMode => "csv_mode",
+            Condition::DiffMode => "diff_mode",
+            Condition::ImageMode => "image_mode",
+        }
+    }
+
     /// Evaluate this condition against the current context
     pub fn evaluate(self, ctx: &KeyContext) -> bool {
         match self {
@@ -80,6 +136,13 @@ impl Condition {
             Condition::ModalInactive => !ctx.modal_active,
             Condition::EditorFocused => ctx.editor_focused,
             Condition::SidebarFocused => ctx.sidebar_focused,
+            Condition::ColumnSelection => ctx.column_selection,
+            Condition::VimNormal => ctx.vim_mode == Some(crate::vim::VimMode::Normal),
+            Condition::VimInsert => ctx.vim_mode == Some(crate::vim::VimMode::Insert),
+            Condition::VimVisual => ctx.vim_mode == Some(crate::vim::VimMode::Visual),
+            Condition::CsvMode => ctx.csv_mode,
+            Condition::DiffMode => ctx.diff_mode,
+            Condition::ImageMode => ctx.image_mode,
         }
     }
 
@@ -139,6 +202,47 @@ mod tests {
         assert!(!Condition::EditorFocused.evaluate(&ctx));
     }
 
+    #[test]
+    fn test_condition_vim_mode() {
+        use crate::vim::VimMode;
+
+        let mut ctx = KeyContext::editor_default();
+        assert!(!Condition::VimNormal.evaluate(&ctx));
+        assert!(!Condition::VimInsert.evaluate(&ctx));
+
+        ctx.vim_mode = Some(VimMode::Insert);
+        assert!(Condition::VimInsert.evaluate(&ctx));
+        assert!(!Condition::VimNormal.evaluate(&ctx));
+        assert!(!Condition::VimVisual.evaluate(&ctx));
+    }
+
+    #[test]
+    fn test_condition_csv_mode() {
+        let mut ctx = KeyContext::editor_default();
+        assert!(!Condition::CsvMode.evaluate(&ctx));
+
+        ctx.csv_mode = true;
+        assert!(Condition::CsvMode.evaluate(&ctx));
+    }
+
+    #[test]
+    fn test_condition_diff_mode() {
+        let mut ctx = KeyContext::editor_default();
+        assert!(!Condition::DiffMode.evaluate(&ctx));
+
+        ctx.diff_mode = true;
+        assert!(Condition::DiffMode.evaluate(&ctx));
+    }
+
+    #[test]
+    fn test_condition_image_mode() {
+        let mut ctx = KeyContext::editor_default();
+        assert!(!Condition::ImageMode.evaluate(&ctx));
+
+        ctx.image_mode = true;
+        assert!(Condition::ImageMode.evaluate(&ctx));
+    }
+
     #[test]
     fn test_evaluate_all_empty() {
         let ctx = KeyContext::default();
@@ -3,9 +3,14 @@
 //! These are the standard keybindings that ship with the editor.
 //! Can be loaded from keymap.yaml at project root, or falls back to hardcoded defaults.
 
+use std::path::PathBuf;
+
 use super::binding::Keybinding;
 use super::command::Command;
-use super::config::load_keymap_file;
+use super::config::{
+    find_conflicts, parse_keymap_yaml_with_lines, BindingsWithLines, KeymapDiagnostic, KeymapError,
+};
+use super::context::Condition;
 use super::types::{KeyCode, Keystroke, Modifiers};
 
 /// Default keymap YAML embedded at compile time
@@ -24,11 +29,48 @@ pub fn get_default_keymap_yaml() -> &'static str {
 ///
 /// User bindings with `command: Unbound` will remove matching default bindings.
 pub fn load_default_keymap() -> Vec<Keybinding> {
+    load_keymap().bindings
+}
+
+/// Bindings loaded from the keymap files, with the problems found in them
+#[derive(Debug, Clone, Default)]
+pub struct LoadedKeymap {
+    pub bindings: Vec<Keybinding>,
+    pub diagnostics: Vec<KeymapDiagnostic>,
+    /// Whether the user keymap exists but could not be loaded
+    pub user_keymap_failed: bool,
+}
+
+/// Load and merge keymaps like [`load_default_keymap`], also reporting
+/// conflicting bindings and a user keymap that failed to load
+pub fn load_keymap() -> LoadedKeymap {
+    let user_keymap = crate::config_paths::keymap_file()
+        .filter(|path| path.exists())
+        .map(|path| {
+            let result = std::fs::read_to_string(&path)
+                .map_err(|e| KeymapError::IoError(e.to_string()))
+                .and_then(|yaml| parse_keymap_yaml_with_lines(&yaml));
+            (path, result)
+        });
+    merge_keymaps(DEFAULT_KEYMAP_YAML, user_keymap)
+}
+
+/// Merge the default keymap YAML with the parsed user keymap, if any
+pub(super) fn merge_keymaps(
+    default_yaml: &str,
+    user_keymap: Option<(PathBuf, Result<BindingsWithLines, KeymapError>)>,
+) -> LoadedKeymap {
+    let mut loaded = LoadedKeymap::default();
+
     // Load base defaults from embedded YAML
-    let mut bindings = match super::config::parse_keymap_yaml(DEFAULT_KEYMAP_YAML) {
-        Ok(b) => {
-            tracing::info!("Loaded embedded default keymap ({} bindings)", b.len());
-            b
+    loaded.bindings = match parse_keymap_yaml_with_lines(default_yaml) {
+        Ok(bindings) => {
+            tracing::info!(
+                "Loaded embedded default keymap ({} bindings)",
+                bindings.len()
+            );
+            loaded.diagnostics.extend(find_conflicts(&bindings, None));
+            bindings.into_iter().map(|(binding, _)| binding).collect()
         }
         Err(e) => {
             tracing::warn!(
@@ -39,30 +81,37 @@ pub fn load_default_keymap() -> Vec<Keybinding> {
         }
     };
 
-    // Try loading user config
-    if let Some(user_path) = crate::config_paths::keymap_file() {
-        if user_path.exists() {
-            match load_keymap_file(&user_path) {
-                Ok(user_bindings) => {
-                    tracing::info!(
-                        "Merging user keymap from {} ({} bindings)",
-                        user_path.display(),
-                        user_bindings.len()
-                    );
-                    bindings = merge_bindings(bindings, user_bindings);
-                }
-                Err(e) => {
-                    tracing::warn!(
-                        "Failed to load user keymap from {}: {}",
-                        user_path.display(),
-                        e
-                    );
-                }
-            }
+    // Merge the user config
+    match user_keymap {
+        Some((user_path, Ok(user_bindings))) => {
+            tracing::info!(
+                "Merging user keymap from {} ({} bindings)",
+                user_path.display(),
+                user_bindings.len()
+            );
+            loaded
+                .diagnostics
+                .extend(find_conflicts(&user_bindings, Some(&user_path)));
+            let user_bindings = user_bindings.into_iter().map(|(binding, _)| binding);
+            loaded.bindings = merge_bindings(loaded.bindings, user_bindings.collect());
         }
+        Some((user_path, Err(e))) => {
+            tracing::warn!(
+                "Failed to load user keymap from {}: {}",
+                user_path.display(),
+                e
+            );
+            loaded.diagnostics.push(KeymapDiagnostic {
+                path: Some(user_path),
+                line: None,
+                message: format!("Keymap not loaded: {}", e),
+            });
+            loaded.user_keymap_failed = true;
+        }
+        None => {}
     }
 
-    bindings
+    loaded
 }
 
 /// Merge user bindings into base bindings
@@ -114,6 +163,7 @@ pub fn default_bindings() -> Vec<Keybinding> {
     let alt_shift = Modifiers::ALT | Modifiers::SHIFT;
     let ctrl = Modifiers::CTRL;
     let ctrl_shift = Modifiers::CTRL | Modifiers::SHIFT;
+    let ctrl_alt = Modifiers::CTRL | Modifiers::ALT;
     let none = Modifiers::NONE;
 
     let mut bindings = vec![
@@ -122,11 +172,13 @@ pub fn default_bindings() -> Vec<Keybinding> {
         // ====================================================================
         bind(KeyCode::Char('s'), cmd, Command::SaveFile),
         bind(KeyCode::Char('s'), cmd_shift, Command::SaveFileAs),
+        bind(KeyCode::Char('s'), cmd_alt, Command::SaveAllFiles),
         bind(KeyCode::Char('o'), cmd, Command::OpenFile),
+        bind(KeyCode::Char('o'), cmd_alt, Command::OpenFolder),
         // TODO: Remove OpenFolder command - merge with OpenFile using auto-detection
-        // Shift+Cmd+O will be used for Quick Open (file search)
         // See docs/feature/workspace-management.md for design
-        bind(KeyCode::Char('o'), cmd_shift, Command::FuzzyFileFinder),
+        bind(KeyCode::Char('p'), cmd, Command::FuzzyFileFinder), // Quick Open
+        bind(KeyCode::Char('n'), cmd, Command::NewFile),
         bind(KeyCode::Char('n'), cmd_shift, Command::NewTab), // Shift+Cmd+N
         bind(KeyCode::Char('w'), cmd, Command::CloseTab),
         bind(KeyCode::Char('e'), cmd, Command::OpenRecentFiles), // Cmd+E
@@ -146,15 +198,29 @@ pub fn default_bindings() -> Vec<Keybinding> {
         // Selection
         // ====================================================================
         bind(KeyCode::Char('a'), cmd, Command::SelectAll),
-        bind(KeyCode::Char('d'), cmd, Command::Duplicate),
-        bind(KeyCode::Char('j'), cmd, Command::SelectNextOccurrence),
+        bind(KeyCode::Char('d'), cmd_shift, Command::Duplicate),
+        bind(KeyCode::Char('d'), cmd, Command::SelectNextOccurrence),
+        bind(KeyCode::Char('j'), cmd, Command::SelectNextOccurrence), // Alternative
         bind(KeyCode::Char('j'), cmd_shift, Command::UnselectOccurrence),
+        bind(KeyCode::Char('k'), cmd, Command::SkipOccurrence),
+        bind(KeyCode::Char('l'), cmd_shift, Command::SelectAllOccurrences),
         // ====================================================================
         // Modals/Dialogs
         // ====================================================================
         bind(KeyCode::Char('a'), cmd_shift, Command::ToggleCommandPalette),
         bind(KeyCode::Char('l'), cmd, Command::ToggleGotoLine),
+        bind(KeyCode::Char('o'), cmd_shift, Command::ToggleGotoSymbol),
         bind(KeyCode::Char('f'), cmd, Command::ToggleFindReplace),
+        bind(KeyCode::Char('f'), cmd_shift, Command::SearchInWorkspace),
+        bind(KeyCode::F(3), none, Command::FindNextMatch),
+        bind(KeyCode::F(3), shift, Command::FindPreviousMatch),
+        bind(KeyCode::F(3), cmd, Command::SearchWordUnderCursor),
+        bind(
+            KeyCode::F(3),
+            cmd_shift,
+            Command::SearchWordUnderCursorBackward,
+        ),
+        bind(KeyCode::F(12), none, Command::GoToDefinition),
         // ====================================================================
         // Layout: Splits
         // ====================================================================
@@ -182,11 +248,25 @@ pub fn default_bindings() -> Vec<Keybinding> {
         bind(KeyCode::NumpadAdd, none, Command::SplitVertical),
         bind(KeyCode::NumpadSubtract, none, Command::SplitHorizontal),
         // ====================================================================
-        // Panels/Docks (IntelliJ-style Cmd+1/2/7)
+        // Panels/Docks (IntelliJ-style Cmd+1/2/6/7)
         // ====================================================================
         bind(KeyCode::Char('1'), cmd, Command::ToggleFileExplorer),
         bind(KeyCode::Char('2'), cmd, Command::ToggleTerminal),
+        bind(KeyCode::Char('6'), cmd, Command::ToggleTodoList),
         bind(KeyCode::Char('7'), cmd, Command::ToggleOutline),
+        bind(KeyCode::F(6), none, Command::FocusNextArea),
+        bind(KeyCode::F(6), shift, Command::FocusPreviousArea),
+        bind(KeyCode::Char('='), cmd_alt, Command::GrowDock),
+        bind(KeyCode::Char('-'), cmd_alt, Command::ShrinkDock),
+        // Editor font size; in the image viewer these zoom the image
+        bind(KeyCode::Char('='), cmd, Command::ZoomIn),
+        bind(KeyCode::Char('-'), cmd, Command::ZoomOut),
+        bind(KeyCode::Char('0'), cmd, Command::ResetZoom),
+        bind(KeyCode::Char('='), cmd, Command::ImageZoomIn).when_single(Condition::ImageMode),
+        bind(KeyCode::Char('-'), cmd, Command::ImageZoomOut).when_single(Condition::ImageMode),
+        bind(KeyCode::Char('0'), cmd, Command::ImageFitToWindow).when_single(Condition::ImageMode),
+        bind(KeyCode::Char('0'), cmd_shift, Command::ImageActualSize)
+            .when_single(Condition::ImageMode),
         // ====================================================================
         // Basic Navigation (no selection)
         // ====================================================================
@@ -198,6 +278,11 @@ pub fn default_bindings() -> Vec<Keybinding> {
         bind(KeyCode::End, none, Command::MoveCursorLineEnd),
         bind(KeyCode::PageUp, none, Command::PageUp),
         bind(KeyCode::PageDown, none, Command::PageDown),
+        // Scroll without moving the cursor (Ctrl+Arrow)
+        bind(KeyCode::Up, ctrl, Command::ScrollLineUp),
+        bind(KeyCode::Down, ctrl, Command::ScrollLineDown),
+        bind(KeyCode::Up, ctrl_alt, Command::ScrollOtherGroupUp),
+        bind(KeyCode::Down, ctrl_alt, Command::ScrollOtherGroupDown),
         // Word navigation (Alt+Arrow)
         bind(KeyCode::Left, alt, Command::MoveCursorWordLeft),
         bind(KeyCode::Right, alt, Command::MoveCursorWordRight),
@@ -242,14 +327,43 @@ pub fn default_bindings() -> Vec<Keybinding> {
         // For now, default to InsertTab, context system will refine this
         bind(KeyCode::Tab, none, Command::InsertTab),
         bind(KeyCode::Tab, shift, Command::UnindentLines),
+        bind(KeyCode::Up, alt, Command::MoveLinesUp),
+        bind(KeyCode::Down, alt, Command::MoveLinesDown),
+        // ====================================================================
+        // Folding
+        // ====================================================================
+        bind(KeyCode::Char('['), cmd_alt, Command::ToggleFold),
+        bind(KeyCode::Char('0'), cmd_alt, Command::FoldAll),
+        bind(KeyCode::Char('j'), cmd_alt, Command::UnfoldAll),
+        // ====================================================================
+        // Expand/Shrink Selection (Ctrl+Shift+Up/Down)
+        // ====================================================================
+        bind(KeyCode::Up, ctrl_shift, Command::ExpandSelection),
+        bind(KeyCode::Down, ctrl_shift, Command::ShrinkSelection),
+        // ====================================================================
+        // CSV grid: rows, columns and filtering
+        // ====================================================================
+        bind(KeyCode::Enter, cmd_shift, Command::CsvInsertRowAbove).when_single(Condition::CsvMode),
+        bind(KeyCode::Enter, cmd, Command::CsvInsertRowBelow).when_single(Condition::CsvMode),
+        bind(KeyCode::Backspace, cmd, Command::CsvDeleteRow).when_single(Condition::CsvMode),
+        bind(KeyCode::Enter, cmd_shift_alt, Command::CsvInsertColumnLeft)
+            .when_single(Condition::CsvMode),
+        bind(KeyCode::Enter, cmd_alt, Command::CsvInsertColumnRight)
+            .when_single(Condition::CsvMode),
+        bind(KeyCode::Backspace, cmd_alt, Command::CsvDeleteColumn).when_single(Condition::CsvMode),
+        bind(KeyCode::Char('l'), cmd_shift, Command::CsvToggleFilter)
+            .when_single(Condition::CsvMode),
         // ====================================================================
-        // Expand/Shrink Selection (Option+Up/Down)
+        // Diff view: change navigation
         // ====================================================================
-        bind(KeyCode::Up, alt, Command::ExpandSelection),
-        bind(KeyCode::Down, alt, Command::ShrinkSelection),
+        bind(KeyCode::F(7), none, Command::DiffNextChange).when_single(Condition::DiffMode),
+        bind(KeyCode::F(7), shift, Command::DiffPrevChange).when_single(Condition::DiffMode),
         // ====================================================================
         // Escape (smart clear)
         // ====================================================================
+        bind(KeyCode::Escape, none, Command::DiffClose).when_single(Condition::DiffMode),
+        bind(KeyCode::Escape, none, Command::VimNormalMode).when_single(Condition::VimInsert),
+        bind(KeyCode::Escape, none, Command::VimNormalMode).when_single(Condition::VimVisual),
         bind(KeyCode::Escape, none, Command::EscapeSmartClear),
     ];
 
@@ -1,12 +1,16 @@
 //! Keymap struct for storing and looking up keybindings
 
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use super::binding::Keybinding;
 use super::command::Command;
 use super::context::{Condition, KeyContext};
 use super::types::Keystroke;
 
+/// How long a chord prefix waits for the next keystroke before it is dropped
+pub const CHORD_TIMEOUT: Duration = Duration::from_millis(2000);
+
 /// Result of handling a keystroke
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum KeyAction {
@@ -30,6 +34,8 @@ pub struct Keymap {
     chord_prefixes: HashMap<Keystroke, Vec<usize>>, // indices into bindings
     /// Current chord state (pending keystrokes)
     pending_chord: Vec<Keystroke>,
+    /// When the last keystroke of the pending chord was pressed
+    pending_since: Option<Instant>,
 }
 
 impl Keymap {
@@ -40,6 +46,7 @@ impl Keymap {
             single_lookup: HashMap::new(),
             chord_prefixes: HashMap::new(),
             pending_chord: Vec::new(),
+            pending_since: None,
         }
     }
 
@@ -81,6 +88,25 @@ impl Keymap {
     /// Clear pending chord state
     pub fn reset(&mut self) {
         self.pending_chord.clear();
+        self.pending_since = None;
+    }
+
+    /// When the pending chord times out, if one is in progress
+    pub fn chord_deadline(&self) -> Option<Instant> {
+        self.pending_since.map(|since| since + CHORD_TIMEOUT)
+    }
+
+    /// Drop the pending chord if it has waited longer than [`CHORD_TIMEOUT`]
+    ///
+    /// Returns true if a chord was dropped.
+    pub fn expire_chord(&mut self, now: Instant) -> bool {
+        match self.chord_deadline() {
+            Some(deadline) if now >= deadline => {
+                self.reset();
+                true
+            }
+            _ => false,
+        }
     }
 
     /// Handle a keystroke and return the action to take
@@ -95,6 +121,8 @@ impl Keymap {
     ///
     /// Bindings are checked in order; first matching binding wins.
     /// Bindings with conditions are checked before unconditional ones.
+    /// A chord whose conditions match takes its first keystroke away from a
+    /// single-keystroke binding on the same key.
     pub fn handle_keystroke_with_context(
         &mut self,
         keystroke: Keystroke,
@@ -103,9 +131,22 @@ impl Keymap {
         // If we have a pending chord, try to complete it
         if !self.pending_chord.is_empty() {
             self.pending_chord.push(keystroke);
+            self.pending_since = Some(Instant::now());
             return self.try_complete_chord(context);
         }
 
+        // Check if this starts a chord
+        let starts_chord = self.chord_prefixes.get(&keystroke).is_some_and(|indices| {
+            indices
+                .iter()
+                .any(|&idx| Self::conditions_match(&self.bindings[idx], context))
+        });
+        if starts_chord {
+            self.pending_chord.push(keystroke);
+            self.pending_since = Some(Instant::now());
+            return KeyAction::AwaitMore;
+        }
+
         // Try single-keystroke binding
         if let Some(indices) = self.single_lookup.get(&keystroke) {
             if let Some(command) = self.find_matching_binding(indices, context) {
@@ -113,15 +154,19 @@ impl Keymap {
             }
         }
 
-        // Check if this starts a chord
-        if self.chord_prefixes.contains_key(&keystroke) {
-            self.pending_chord.push(keystroke);
-            return KeyAction::AwaitMore;
-        }
-
         KeyAction::NoMatch
     }
 
+    /// Whether a binding applies in the context (bindings with conditions
+    /// never apply without one)
+    fn conditions_match(binding: &Keybinding, context: Option<&KeyContext>) -> bool {
+        match (&binding.when, context) {
+            (None, _) => true,
+            (Some(conditions), Some(ctx)) => Condition::evaluate_all(conditions, ctx),
+            (Some(_), None) => false,
+        }
+    }
+
     /// Find first binding that matches the context
     fn find_matching_binding(
         &self,
@@ -190,6 +235,7 @@ impl Keymap {
             let binding = &self.bindings[idx];
             binding.keystrokes.len() > self.pending_chord.len()
                 && binding.keystrokes[..self.pending_chord.len()] == self.pending_chord
+                && Self::conditions_match(binding, context)
         });
 
         if could_match {
@@ -364,4 +410,65 @@ mod tests {
         keymap.reset();
         assert!(!keymap.has_pending_chord());
     }
+
+    #[test]
+    fn test_chord_prefix_beats_single_binding() {
+        let mut keymap = Keymap::with_bindings(vec![
+            Keybinding::new(ctrl_k(), Command::DeleteLine),
+            Keybinding::chord(vec![ctrl_k(), ctrl_c()], Command::Copy),
+        ]);
+
+        assert_eq!(keymap.handle_keystroke(ctrl_k()), KeyAction::AwaitMore);
+        assert_eq!(
+            keymap.pending_chord_display(),
+            Some(ctrl_k().display_string())
+        );
+        assert_eq!(
+            keymap.handle_keystroke(ctrl_c()),
+            KeyAction::Execute(Command::Copy)
+        );
+    }
+
+    #[test]
+    fn test_chord_conditions_gate_the_prefix() {
+        let mut keymap = Keymap::with_bindings(vec![
+            Keybinding::new(ctrl_k(), Command::DeleteLine),
+            Keybinding::chord(vec![ctrl_k(), ctrl_c()], Command::Copy)
+                .when_single(Condition::HasSelection),
+        ]);
+
+        let ctx = KeyContext::default();
+        assert_eq!(
+            keymap.handle_keystroke_with_context(ctrl_k(), Some(&ctx)),
+            KeyAction::Execute(Command::DeleteLine)
+        );
+        assert!(!keymap.has_pending_chord());
+
+        let ctx = KeyContext {
+            has_selection: true,
+            ..KeyContext::default()
+        };
+        assert_eq!(
+            keymap.handle_keystroke_with_context(ctrl_k(), Some(&ctx)),
+            KeyAction::AwaitMore
+        );
+    }
+
+    #[test]
+    fn test_pending_chord_expires() {
+        let mut keymap = Keymap::with_bindings(vec![Keybinding::chord(
+            vec![ctrl_k(), ctrl_c()],
+            Command::Copy,
+        )]);
+        assert_eq!(keymap.chord_deadline(), None);
+
+        keymap.handle_keystroke(ctrl_k());
+        let deadline = keymap.chord_deadline().unwrap();
+        assert!(!keymap.expire_chord(deadline - Duration::from_millis(1)));
+        assert!(keymap.has_pending_chord());
+
+        assert!(keymap.expire_chord(deadline));
+        assert!(!keymap.has_pending_chord());
+        assert_eq!(keymap.chord_deadline(), None);
+    }
 }
@@ -34,10 +34,14 @@ mod winit_adapter;
 
 pub use binding::Keybinding;
 pub use command::Command;
-pub use config::{load_keymap_file, parse_keymap_yaml, KeymapError};
+pub use config::{
+    find_conflicts, load_keymap_file, parse_keymap_yaml, parse_keymap_yaml_with_lines,
+    BindingsWithLines, KeymapDiagnostic, KeymapError, KeymapWatcher,
+};
 pub use context::{Condition, KeyContext};
 pub use defaults::{
-    default_bindings, get_default_keymap_yaml, load_default_keymap, merge_bindings,
+    default_bindings, get_default_keymap_yaml, load_default_keymap, load_keymap, merge_bindings,
+    LoadedKeymap,
 };
 pub use keymap::{KeyAction, Keymap};
 pub use types::{KeyCode, Keystroke, Modifiers};
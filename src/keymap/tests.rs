@@ -51,6 +51,131 @@ fn test_keymap_lookup_save() {
     assert_eq!(command, Some(Command::SaveFile));
 }
 
+#[test]
+fn test_keymap_file_dialog_bindings() {
+    let cmd = Modifiers::cmd();
+    let cmd_alt = cmd | Modifiers::ALT;
+    for keymap in [
+        Keymap::with_bindings(load_default_keymap()),
+        Keymap::with_bindings(default_bindings()),
+    ] {
+        assert_eq!(
+            keymap.lookup(&Keystroke::new(KeyCode::Char('o'), cmd)),
+            Some(Command::OpenFile)
+        );
+        assert_eq!(
+            keymap.lookup(&Keystroke::new(KeyCode::Char('o'), cmd_alt)),
+            Some(Command::OpenFolder)
+        );
+        assert_eq!(
+            keymap.lookup(&Keystroke::new(KeyCode::Char('s'), cmd_alt)),
+            Some(Command::SaveAllFiles)
+        );
+    }
+    // Work from the sidebar and docks too
+    assert!(Command::OpenFile.is_global());
+    assert!(Command::OpenFolder.is_global());
+}
+
+#[test]
+fn test_keymap_goto_symbol_binding() {
+    let cmd_shift = Modifiers::cmd() | Modifiers::SHIFT;
+    for keymap in [
+        Keymap::with_bindings(load_default_keymap()),
+        Keymap::with_bindings(default_bindings()),
+    ] {
+        assert_eq!(
+            keymap.lookup(&Keystroke::new(KeyCode::Char('o'), cmd_shift)),
+            Some(Command::ToggleGotoSymbol)
+        );
+    }
+}
+
+#[test]
+fn test_keymap_csv_row_bindings_only_in_csv_mode() {
+    let cmd_backspace = Keystroke::new(KeyCode::Backspace, Modifiers::cmd());
+    let cmd_alt_enter = Keystroke::new(KeyCode::Enter, Modifiers::cmd() | Modifiers::ALT);
+    let csv = KeyContext {
+        csv_mode: true,
+        ..KeyContext::editor_default()
+    };
+    for keymap in [
+        Keymap::with_bindings(load_default_keymap()),
+        Keymap::with_bindings(default_bindings()),
+    ] {
+        assert_eq!(
+            keymap.lookup_with_context(&cmd_backspace, Some(&csv)),
+            Some(Command::CsvDeleteRow)
+        );
+        assert_eq!(
+            keymap.lookup_with_context(&cmd_alt_enter, Some(&csv)),
+            Some(Command::CsvInsertColumnRight)
+        );
+        assert_eq!(
+            keymap.lookup_with_context(&cmd_backspace, Some(&KeyContext::editor_default())),
+            Some(Command::DeleteLine)
+        );
+    }
+}
+
+#[test]
+fn test_keymap_diff_bindings_only_in_diff_mode() {
+    let f7 = Keystroke::new(KeyCode::F(7), Modifiers::NONE);
+    let escape = Keystroke::new(KeyCode::Escape, Modifiers::NONE);
+    let diff = KeyContext {
+        diff_mode: true,
+        ..KeyContext::editor_default()
+    };
+    for keymap in [
+        Keymap::with_bindings(load_default_keymap()),
+        Keymap::with_bindings(default_bindings()),
+    ] {
+        assert_eq!(
+            keymap.lookup_with_context(&f7, Some(&diff)),
+            Some(Command::DiffNextChange)
+        );
+        assert_eq!(
+            keymap.lookup_with_context(&escape, Some(&diff)),
+            Some(Command::DiffClose)
+        );
+        assert_eq!(
+            keymap.lookup_with_context(&f7, Some(&KeyContext::editor_default())),
+            None
+        );
+    }
+}
+
+#[test]
+fn test_keymap_zoom_bindings_zoom_the_image_in_image_mode() {
+    let cmd_equal = Keystroke::new(KeyCode::Char('='), Modifiers::cmd());
+    let cmd_zero = Keystroke::new(KeyCode::Char('0'), Modifiers::cmd());
+    let image = KeyContext {
+        image_mode: true,
+        ..KeyContext::editor_default()
+    };
+    for keymap in [
+        Keymap::with_bindings(load_default_keymap()),
+        Keymap::with_bindings(default_bindings()),
+    ] {
+        assert_eq!(
+            keymap.lookup_with_context(&cmd_equal, Some(&KeyContext::editor_default())),
+            Some(Command::ZoomIn)
+        );
+        assert_eq!(
+            keymap.lookup_with_context(&cmd_zero, Some(&KeyContext::editor_default())),
+            Some(Command::ResetZoom)
+        );
+        assert_eq!(
+            keymap.lookup_with_context(&cmd_equal, Some(&image)),
+            Some(Command::ImageZoomIn)
+        );
+        assert_eq!(
+            keymap.lookup_with_context(&cmd_zero, Some(&image)),
+            Some(Command::ImageFitToWindow)
+        );
+    }
+}
+
 #[test]
 fn test_keymap_lookup_undo() {
     let keymap = Keymap::with_bindings(default_bindings());
@@ -85,6 +210,40 @@ fn test_keymap_lookup_arrow_keys() {
     );
 }
 
+#[test]
+fn test_keymap_alt_arrows_move_lines() {
+    let keymap = Keymap::with_bindings(load_default_keymap());
+    let alt = Modifiers::ALT;
+
+    assert_eq!(
+        keymap.lookup(&Keystroke::new(KeyCode::Up, alt)),
+        Some(Command::MoveLinesUp)
+    );
+    assert_eq!(
+        keymap.lookup(&Keystroke::new(KeyCode::Down, alt)),
+        Some(Command::MoveLinesDown)
+    );
+}
+
+#[test]
+fn test_keymap_folding_bindings() {
+    let keymap = Keymap::with_bindings(load_default_keymap());
+    let cmd_alt = Modifiers::cmd() | Modifiers::ALT;
+
+    assert_eq!(
+        keymap.lookup(&Keystroke::new(KeyCode::Char('['), cmd_alt)),
+        Some(Command::ToggleFold)
+    );
+    assert_eq!(
+        keymap.lookup(&Keystroke::new(KeyCode::Char('0'), cmd_alt)),
+        Some(Command::FoldAll)
+    );
+    assert_eq!(
+        keymap.lookup(&Keystroke::new(KeyCode::Char('j'), cmd_alt)),
+        Some(Command::UnfoldAll)
+    );
+}
+
 #[test]
 fn test_keymap_lookup_shift_arrows() {
     let keymap = Keymap::with_bindings(default_bindings());
@@ -180,6 +339,11 @@ fn test_context_aware_tab_with_selection() {
         modal_active: false,
         editor_focused: true,
         sidebar_focused: false,
+        column_selection: false,
+        vim_mode: None,
+        csv_mode: false,
+        diff_mode: false,
+        image_mode: false,
     };
 
     let result = keymap.lookup_with_context(&tab, Some(&ctx_selection));
@@ -198,6 +362,11 @@ fn test_context_aware_tab_without_selection() {
         modal_active: false,
         editor_focused: true,
         sidebar_focused: false,
+        column_selection: false,
+        vim_mode: None,
+        csv_mode: false,
+        diff_mode: false,
+        image_mode: false,
     };
 
     let result = keymap.lookup_with_context(&tab, Some(&ctx_no_selection));
@@ -216,6 +385,11 @@ fn test_context_aware_escape_multi_cursor() {
         modal_active: false,
         editor_focused: true,
         sidebar_focused: false,
+        column_selection: false,
+        vim_mode: None,
+        csv_mode: false,
+        diff_mode: false,
+        image_mode: false,
     };
 
     let result = keymap.lookup_with_context(&escape, Some(&ctx));
@@ -234,6 +408,11 @@ fn test_context_aware_escape_selection() {
         modal_active: false,
         editor_focused: true,
         sidebar_focused: false,
+        column_selection: false,
+        vim_mode: None,
+        csv_mode: false,
+        diff_mode: false,
+        image_mode: false,
     };
 
     let result = keymap.lookup_with_context(&escape, Some(&ctx));
@@ -252,6 +431,11 @@ fn test_context_aware_escape_fallback() {
         modal_active: false,
         editor_focused: true,
         sidebar_focused: false,
+        column_selection: false,
+        vim_mode: None,
+        csv_mode: false,
+        diff_mode: false,
+        image_mode: false,
     };
 
     let result = keymap.lookup_with_context(&escape, Some(&ctx));
@@ -323,3 +507,152 @@ fn test_dock_keybindings_exist() {
         result
     );
 }
+
+#[test]
+fn test_context_aware_column_selection_arrows() {
+    let keymap = Keymap::with_bindings(load_default_keymap());
+    let alt_shift = Modifiers::ALT | Modifiers::SHIFT;
+    let left = Keystroke::new(KeyCode::Left, alt_shift);
+    let down = Keystroke::new(KeyCode::Down, alt_shift);
+
+    // Up/Down start a column selection; Left/Right select by word...
+    let mut ctx = KeyContext::editor_default();
+    assert_eq!(
+        keymap.lookup_with_context(&down, Some(&ctx)),
+        Some(Command::ColumnSelectDown)
+    );
+    assert_eq!(
+        keymap.lookup_with_context(&left, Some(&ctx)),
+        Some(Command::MoveCursorWordLeftWithSelection)
+    );
+
+    // ...unless one is being grown
+    ctx.column_selection = true;
+    assert_eq!(
+        keymap.lookup_with_context(&left, Some(&ctx)),
+        Some(Command::ColumnSelectLeft)
+    );
+}
+
+#[test]
+fn test_context_aware_escape_in_vim_mode() {
+    use crate::vim::VimMode;
+
+    let keymap = Keymap::with_bindings(load_default_keymap());
+    let escape = Keystroke::new(KeyCode::Escape, Modifiers::NONE);
+
+    let mut ctx = KeyContext::editor_default();
+    ctx.has_selection = true;
+    assert_eq!(
+        keymap.lookup_with_context(&escape, Some(&ctx)),
+        Some(Command::ClearSelection)
+    );
+
+    ctx.vim_mode = Some(VimMode::Visual);
+    assert_eq!(
+        keymap.lookup_with_context(&escape, Some(&ctx)),
+        Some(Command::VimNormalMode)
+    );
+
+    ctx.vim_mode = Some(VimMode::Normal);
+    assert_eq!(
+        keymap.lookup_with_context(&escape, Some(&ctx)),
+        Some(Command::ClearSelection)
+    );
+}
+
+#[test]
+fn test_embedded_keymap_has_no_conflicts() {
+    let bindings = parse_keymap_yaml_with_lines(DEFAULT_KEYMAP_YAML).unwrap();
+    let conflicts = find_conflicts(&bindings, None);
+    assert!(conflicts.is_empty(), "{:#?}", conflicts);
+}
+
+#[test]
+fn test_binding_lines_are_reported() {
+    let yaml = r#"
+bindings:
+  - key: "cmd+s"
+    command: SaveFile
+
+  # comment
+  - key: "tab"
+    command: IndentLines
+    when:
+      - has_selection
+"#;
+    let bindings = parse_keymap_yaml_with_lines(yaml).unwrap();
+    let lines: Vec<_> = bindings.iter().map(|(_, line)| *line).collect();
+    assert_eq!(lines, vec![Some(2), Some(6)]);
+}
+
+#[test]
+fn test_conflicts_in_the_same_context() {
+    let yaml = r#"
+bindings:
+  - key: "cmd+d"
+    command: Duplicate
+  - key: "tab"
+    command: IndentLines
+    when: ["has_selection", "single_cursor"]
+  - key: "cmd+d"
+    command: SelectNextOccurrence
+  - key: "tab"
+    command: InsertTab
+    when: ["single_cursor", "has_selection"]
+  - key: "tab"
+    command: InsertTab
+  - key: "cmd+s"
+    command: SaveFile
+  - key: "cmd+s"
+    command: SaveFile
+"#;
+    let bindings = parse_keymap_yaml_with_lines(yaml).unwrap();
+    let path = std::path::Path::new("/config/keymap.yaml");
+    let conflicts = find_conflicts(&bindings, Some(path));
+    let lines: Vec<_> = conflicts.iter().map(|c| c.line).collect();
+    assert_eq!(lines, vec![Some(7), Some(9), Some(16)]);
+    assert!(conflicts[0]
+        .message
+        .contains("Select Next Occurrence never runs"));
+    assert!(conflicts[0].message.contains("line 3"));
+    assert!(conflicts[1]
+        .message
+        .contains("when [has_selection, single_cursor]"));
+    assert!(conflicts[2].message.contains("bound twice"));
+    assert_eq!(conflicts[0].path.as_deref(), Some(path));
+}
+
+#[test]
+fn test_chord_shadowing_a_single_binding_is_reported() {
+    let yaml = r#"
+bindings:
+  - key: "ctrl+k"
+    command: DeleteLine
+  - key: "ctrl+k ctrl+c"
+    command: Copy
+  - key: "ctrl+j"
+    command: Undo
+    when: ["has_selection"]
+  - key: "ctrl+j ctrl+c"
+    command: Copy
+"#;
+    let bindings = parse_keymap_yaml_with_lines(yaml).unwrap();
+    let conflicts = find_conflicts(&bindings, None);
+    assert_eq!(conflicts.len(), 1, "{:#?}", conflicts);
+    assert_eq!(conflicts[0].line, Some(2));
+    assert!(conflicts[0].message.contains("Delete Line never runs"));
+    assert!(conflicts[0].message.contains("line 5"));
+}
+
+#[test]
+fn test_broken_user_keymap_is_reported() {
+    let path = std::path::PathBuf::from("/config/keymap.yaml");
+    let user =
+        parse_keymap_yaml_with_lines("bindings:\n  - key: \"cmd+nope\"\n    command: SaveFile\n");
+    let loaded = defaults::merge_keymaps(DEFAULT_KEYMAP_YAML, Some((path.clone(), user)));
+    assert!(loaded.user_keymap_failed);
+    assert_eq!(loaded.diagnostics.len(), 1);
+    assert_eq!(loaded.diagnostics[0].path, Some(path));
+    assert!(!loaded.bindings.is_empty(), "defaults still load");
+}
@@ -0,0 +1,107 @@
+//! Large file mode: text files over [`LARGE_FILE_SIZE`] open at once as an
+//! empty tab and are streamed into a rope on a background thread, with
+//! their progress shown in the status bar. Once loaded they skip the work
+//! that covers the whole file on every edit: syntax highlighting, the git
+//! gutter and blame, and undo grouping. The find index is only built when a
+//! search needs it.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use ropey::{Rope, RopeBuilder};
+
+use crate::replay::ContentHasher;
+pub use crate::util::LARGE_FILE_SIZE;
+
+/// Bytes read at a time
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The same error `std::fs::read_to_string` gives
+const INVALID_UTF8: &str = "stream did not contain valid UTF-8";
+
+/// A large file read into memory
+#[derive(Debug, Clone)]
+pub struct LoadedFile {
+    pub text: Rope,
+    /// `replay::content_hash` of the file contents
+    pub disk_hash: String,
+}
+
+/// Read a UTF-8 file into a rope a chunk at a time, calling `progress` with
+/// the bytes read so far and the file size after each chunk
+pub fn stream_file(path: &Path, mut progress: impl FnMut(u64, u64)) -> Result<LoadedFile, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut builder = RopeBuilder::new();
+    let mut hasher = ContentHasher::new();
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+    // Read bytes not appended yet: the start of a character split across
+    // two chunks
+    let mut pending = Vec::new();
+    let mut loaded = 0;
+
+    loop {
+        let read = file.read(&mut chunk).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        loaded += read as u64;
+        hasher.update(&chunk[..read]);
+        pending.extend_from_slice(&chunk[..read]);
+
+        let valid = match std::str::from_utf8(&pending) {
+            Ok(text) => text.len(),
+            // Cut off in the middle of a character
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => return Err(INVALID_UTF8.to_string()),
+        };
+        let text = std::str::from_utf8(&pending[..valid]).map_err(|e| e.to_string())?;
+        builder.append(text);
+        pending.drain(..valid);
+        progress(loaded, total.max(loaded));
+    }
+
+    if !pending.is_empty() {
+        return Err(INVALID_UTF8.to_string());
+    }
+    Ok(LoadedFile {
+        text: builder.finish(),
+        disk_hash: hasher.finish(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::content_hash;
+
+    #[test]
+    fn streams_characters_split_across_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.txt");
+        // A two-byte character straddles the first chunk boundary
+        let text = format!("{}é\r\nend", "a".repeat(CHUNK_SIZE - 1));
+        std::fs::write(&path, &text).unwrap();
+
+        let mut reports = Vec::new();
+        let loaded = stream_file(&path, |loaded, total| reports.push((loaded, total))).unwrap();
+
+        assert_eq!(loaded.text.to_string(), text);
+        assert_eq!(loaded.disk_hash, content_hash(&text));
+        let size = text.len() as u64;
+        assert_eq!(reports, vec![(CHUNK_SIZE as u64, size), (size, size)]);
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.txt");
+        std::fs::write(&path, b"ok\xff\xfe").unwrap();
+        assert_eq!(stream_file(&path, |_, _| {}).unwrap_err(), INVALID_UTF8);
+
+        // A truncated character at the very end
+        std::fs::write(&path, b"ok\xc3").unwrap();
+        assert_eq!(stream_file(&path, |_, _| {}).unwrap_err(), INVALID_UTF8);
+    }
+}
@@ -3,33 +3,60 @@
 //! This crate provides the core types and logic for a minimal text editor
 //! implementing the Elm Architecture pattern.
 
+pub mod ai;
+pub mod calc;
 pub mod cli;
 pub mod commands;
+pub mod completion;
 pub mod config;
 pub mod config_paths;
+pub mod crash;
 pub mod csv;
 #[cfg(debug_assertions)]
 pub mod debug_overlay;
+pub mod diff;
+pub mod edit_review;
 pub mod editable;
+pub mod external_tools;
+pub mod file_ops;
 pub mod fs_watcher;
+pub mod git;
 pub mod image;
+pub mod indent;
 pub mod keymap;
+pub mod large_file;
+pub mod lsp;
 pub mod markdown;
+pub mod menu;
 pub mod messages;
 pub mod model;
+pub mod notes;
 pub mod outline;
 pub mod overlay;
 pub mod panel;
 pub mod panels;
+pub mod paste_image;
 pub mod perf;
+pub mod performance;
+pub mod project_search;
+pub mod quick_open;
 pub mod recent_files;
+pub mod replay;
+pub mod search_index;
+pub mod session;
 pub mod syntax;
 pub mod terminal;
 pub mod theme;
+pub mod todo;
 pub mod tracing;
+pub mod transform;
 pub mod update;
+pub mod update_check;
 pub mod util;
 pub mod view;
+pub mod vim;
+pub mod welcome;
+pub mod workspace_trust;
 
 pub mod rendering {
     //! Rendering utilities exposed for benchmarks
@@ -57,6 +84,60 @@ pub mod rendering {
 
         0xFF000000 | (r << 16) | (g << 8) | b
     }
+
+    /// Gamma used to blend text in linear light (close to sRGB)
+    const TEXT_GAMMA: f32 = 2.2;
+
+    struct GammaTables {
+        /// 8-bit channel to 16-bit linear
+        to_linear: [u16; 256],
+        /// 12-bit linear back to an 8-bit channel
+        to_encoded: [u8; 4096],
+    }
+
+    fn gamma_tables() -> &'static GammaTables {
+        static TABLES: std::sync::OnceLock<GammaTables> = std::sync::OnceLock::new();
+        TABLES.get_or_init(|| {
+            let mut to_linear = [0u16; 256];
+            for (i, value) in to_linear.iter_mut().enumerate() {
+                *value = ((i as f32 / 255.0).powf(TEXT_GAMMA) * 65535.0).round() as u16;
+            }
+            let mut to_encoded = [0u8; 4096];
+            for (i, value) in to_encoded.iter_mut().enumerate() {
+                *value = ((i as f32 / 4095.0).powf(1.0 / TEXT_GAMMA) * 255.0).round() as u8;
+            }
+            GammaTables {
+                to_linear,
+                to_encoded,
+            }
+        })
+    }
+
+    /// Blend a foreground color onto a background color with separate
+    /// red, green and blue coverage (subpixel antialiasing), in linear light.
+    ///
+    /// Blending gamma-encoded values directly (as [`blend_pixel_u8`] does)
+    /// makes partially covered pixels too dark, which thins dark-on-light
+    /// text and fringes subpixel edges.
+    #[inline]
+    pub fn blend_subpixel_u8(bg: u32, fg: u32, coverage: [u8; 3]) -> u32 {
+        let tables = gamma_tables();
+        let channel = |shift: u32, alpha: u8| {
+            // Exact at the ends, where the tables' rounding would show
+            match alpha {
+                0 => return bg & (0xFF << shift),
+                255 => return fg & (0xFF << shift),
+                _ => {}
+            }
+            let bg = tables.to_linear[((bg >> shift) & 0xFF) as usize] as u32;
+            let fg = tables.to_linear[((fg >> shift) & 0xFF) as usize] as u32;
+            let a = alpha as u32;
+            let linear = (fg * a + bg * (255 - a)) / 255;
+            (tables.to_encoded[(linear >> 4) as usize] as u32) << shift
+        };
+
+        0xFF000000 | channel(16, coverage[0]) | channel(8, coverage[1]) | channel(0, coverage[2])
+    }
 }
 
 // Re-export commonly used types
@@ -0,0 +1,372 @@
+//! Language server processes and the threads that talk to them
+
+use std::collections::HashMap;
+use std::io::{BufReader, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde_json::{json, Value};
+
+use super::protocol;
+use super::{LspAction, LspRequest, LspServerSpec};
+use crate::messages::{LspMsg, Msg};
+
+/// What a request in flight was for, to route its response
+#[derive(Debug, Clone, Copy)]
+enum Pending {
+    Initialize,
+    Hover(u64),
+    Definition(u64),
+}
+
+type PendingRequests = Arc<Mutex<HashMap<u64, Pending>>>;
+
+/// One running language server
+///
+/// A writer thread owns the server's stdin and a reader thread its stdout.
+/// Messages sent before the server answers `initialize` wait in the writer's
+/// queue, so callers never block on the server.
+pub struct LspClient {
+    child: Child,
+    outgoing: Sender<Value>,
+    pending: PendingRequests,
+    next_id: u64,
+}
+
+impl LspClient {
+    /// Start the server for `spec`. Answers and notifications are sent to
+    /// `msg_tx` as `Msg::Lsp`; when the server exits, `ServerStopped` is.
+    pub fn spawn(spec: &LspServerSpec, msg_tx: Sender<Msg>) -> std::io::Result<Self> {
+        let name = spec.name();
+        let mut child = Command::new(&spec.config.command)
+            .args(&spec.config.args)
+            .current_dir(&spec.root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let (outgoing, outgoing_rx) = mpsc::channel::<Value>();
+        let (ready_tx, ready_rx) = mpsc::channel::<()>();
+        let pending = PendingRequests::default();
+
+        // Writer: `initialize` first, then nothing else until it's answered
+        thread::Builder::new()
+            .name(format!("lsp-writer-{name}"))
+            .spawn(move || {
+                let mut initialized = false;
+                while let Ok(message) = outgoing_rx.recv() {
+                    if stdin.write_all(&protocol::encode(&message)).is_err()
+                        || stdin.flush().is_err()
+                    {
+                        break;
+                    }
+                    if !initialized {
+                        if ready_rx.recv().is_err() {
+                            break;
+                        }
+                        initialized = true;
+                        let message = protocol::notification("initialized", json!({}));
+                        if stdin.write_all(&protocol::encode(&message)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            })?;
+
+        // Reader: route responses, answer server requests, forward diagnostics
+        {
+            let pending = Arc::clone(&pending);
+            let outgoing = outgoing.clone();
+            let name = name.clone();
+            thread::Builder::new()
+                .name(format!("lsp-reader-{name}"))
+                .spawn(move || {
+                    let mut reader = BufReader::new(stdout);
+                    let mut ready_tx = Some(ready_tx);
+                    let error = loop {
+                        let message = match protocol::read_message(&mut reader) {
+                            Ok(Some(message)) => message,
+                            Ok(None) => break "exited".to_string(),
+                            Err(e) => break e.to_string(),
+                        };
+                        let msg = match (message.get("id"), message.get("method")) {
+                            (Some(id), Some(method)) => {
+                                let reply = server_request_result(method, &message);
+                                let _ = outgoing.send(protocol::response(id.clone(), reply));
+                                None
+                            }
+                            (None, Some(method)) => notification_msg(method, &message),
+                            (Some(id), None) => {
+                                let kind =
+                                    id.as_u64().and_then(|id| pending.lock().ok()?.remove(&id));
+                                let result = message.get("result").unwrap_or(&Value::Null);
+                                match kind {
+                                    Some(Pending::Initialize) => {
+                                        if let Some(ready_tx) = ready_tx.take() {
+                                            let _ = ready_tx.send(());
+                                        }
+                                        None
+                                    }
+                                    Some(Pending::Hover(request_id)) => Some(LspMsg::HoverResult {
+                                        request_id,
+                                        contents: protocol::parse_hover(result),
+                                    }),
+                                    Some(Pending::Definition(request_id)) => {
+                                        Some(LspMsg::DefinitionResult {
+                                            request_id,
+                                            location: protocol::parse_definition(result),
+                                        })
+                                    }
+                                    None => None,
+                                }
+                            }
+                            (None, None) => None,
+                        };
+                        if let Some(msg) = msg {
+                            if msg_tx.send(Msg::Lsp(msg)).is_err() {
+                                return;
+                            }
+                        }
+                    };
+                    tracing::info!("Language server {name} stopped: {error}");
+                    let _ = msg_tx.send(Msg::Lsp(LspMsg::ServerStopped {
+                        server: name,
+                        error,
+                    }));
+                })?;
+        }
+
+        let mut client = Self {
+            child,
+            outgoing,
+            pending,
+            next_id: 0,
+        };
+        client.request(
+            "initialize",
+            protocol::initialize_params(&spec.root),
+            Pending::Initialize,
+        );
+        Ok(client)
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        let _ = self.outgoing.send(protocol::notification(method, params));
+    }
+
+    fn request(&mut self, method: &str, params: Value, kind: Pending) {
+        self.next_id += 1;
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(self.next_id, kind);
+        }
+        let _ = self
+            .outgoing
+            .send(protocol::request(self.next_id, method, params));
+    }
+
+    pub fn send(&mut self, action: LspAction) {
+        match action {
+            LspAction::DidOpen {
+                path,
+                language_id,
+                version,
+                text,
+            } => self.notify(
+                "textDocument/didOpen",
+                protocol::did_open_params(&path, language_id, version, &text),
+            ),
+            LspAction::DidChange {
+                path,
+                version,
+                text,
+            } => self.notify(
+                "textDocument/didChange",
+                protocol::did_change_params(&path, version, &text),
+            ),
+            LspAction::DidSave { path } => self.notify(
+                "textDocument/didSave",
+                protocol::text_document_params(&path),
+            ),
+            LspAction::DidClose { path } => self.notify(
+                "textDocument/didClose",
+                protocol::text_document_params(&path),
+            ),
+            LspAction::Hover {
+                request_id,
+                path,
+                position,
+            } => self.request(
+                "textDocument/hover",
+                protocol::position_params(&path, position),
+                Pending::Hover(request_id),
+            ),
+            LspAction::Definition {
+                request_id,
+                path,
+                position,
+            } => self.request(
+                "textDocument/definition",
+                protocol::position_params(&path, position),
+                Pending::Definition(request_id),
+            ),
+        }
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Result for a request the server sent us. Only `workspace/configuration`
+/// needs a particular shape (one entry per item asked for); everything else
+/// is acknowledged with `null`.
+fn server_request_result(method: &Value, message: &Value) -> Value {
+    if method.as_str() == Some("workspace/configuration") {
+        let items = message
+            .pointer("/params/items")
+            .and_then(Value::as_array)
+            .map_or(0, Vec::len);
+        return Value::Array(vec![Value::Null; items]);
+    }
+    Value::Null
+}
+
+fn notification_msg(method: &Value, message: &Value) -> Option<LspMsg> {
+    if method.as_str() != Some("textDocument/publishDiagnostics") {
+        return None;
+    }
+    let (path, version, diagnostics) = protocol::parse_diagnostics(message.get("params")?)?;
+    Some(LspMsg::Diagnostics {
+        path,
+        version,
+        diagnostics,
+    })
+}
+
+/// The running language servers, keyed by command line — lives in the
+/// runtime, which hands it every `Cmd::Lsp`
+pub struct LspManager {
+    msg_tx: Sender<Msg>,
+    clients: HashMap<String, LspClient>,
+}
+
+impl LspManager {
+    pub fn new(msg_tx: Sender<Msg>) -> Self {
+        Self {
+            msg_tx,
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Send `request` to its server, starting the server first if needed.
+    /// A server that can't be started is reported as `ServerStopped`.
+    pub fn handle(&mut self, request: LspRequest) {
+        let name = request.server.name();
+        if !self.clients.contains_key(&name) {
+            match LspClient::spawn(&request.server, self.msg_tx.clone()) {
+                Ok(client) => {
+                    tracing::info!("Started language server {name}");
+                    self.clients.insert(name.clone(), client);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to start language server {name}: {e}");
+                    let _ = self.msg_tx.send(Msg::Lsp(LspMsg::ServerStopped {
+                        server: name,
+                        error: e.to_string(),
+                    }));
+                    return;
+                }
+            }
+        }
+        if let Some(client) = self.clients.get_mut(&name) {
+            client.send(request.action);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::lsp::{LspConfig, LspServerConfig};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    /// A "server" that answers `initialize` and publishes one diagnostic
+    /// once it has been told about a document
+    fn fake_server() -> LspServerSpec {
+        let initialize = r#"{"jsonrpc":"2.0","id":1,"result":{"capabilities":{}}}"#;
+        let diagnostics = r#"{"jsonrpc":"2.0","method":"textDocument/publishDiagnostics","params":{"uri":"file:///tmp/a.rs","version":3,"diagnostics":[{"range":{"start":{"line":0,"character":1},"end":{"line":0,"character":2}},"severity":1,"message":"boom"}]}}"#;
+        let script = format!(
+            "head -c 1 >/dev/null; printf 'Content-Length: {}\\r\\n\\r\\n%s' '{initialize}'; \
+             sleep 0.2; printf 'Content-Length: {}\\r\\n\\r\\n%s' '{diagnostics}'; cat >/dev/null",
+            initialize.len(),
+            diagnostics.len(),
+        );
+        LspServerSpec {
+            config: LspServerConfig {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script],
+            },
+            root: std::env::temp_dir(),
+        }
+    }
+
+    #[test]
+    fn diagnostics_arrive_as_messages() {
+        let (msg_tx, msg_rx) = mpsc::channel();
+        let mut manager = LspManager::new(msg_tx);
+        manager.handle(LspRequest {
+            server: fake_server(),
+            action: LspAction::DidOpen {
+                path: PathBuf::from("/tmp/a.rs"),
+                language_id: "rust",
+                version: 3,
+                text: "fn main() {}".to_string(),
+            },
+        });
+
+        let msg = msg_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        let Msg::Lsp(LspMsg::Diagnostics {
+            path,
+            version,
+            diagnostics,
+        }) = msg
+        else {
+            panic!("expected diagnostics, got {msg:?}");
+        };
+        assert_eq!(path, PathBuf::from("/tmp/a.rs"));
+        assert_eq!(version, Some(3));
+        assert_eq!(diagnostics[0].message, "boom");
+    }
+
+    #[test]
+    fn missing_server_is_reported_as_stopped() {
+        let (msg_tx, msg_rx) = mpsc::channel();
+        let mut manager = LspManager::new(msg_tx);
+        let mut config = LspConfig::default().servers["rust"].clone();
+        config.command = "token-test-no-such-language-server".to_string();
+        manager.handle(LspRequest {
+            server: LspServerSpec {
+                config,
+                root: std::env::temp_dir(),
+            },
+            action: LspAction::DidClose {
+                path: PathBuf::from("/tmp/a.rs"),
+            },
+        });
+
+        let msg = msg_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(
+            matches!(msg, Msg::Lsp(LspMsg::ServerStopped { ref server, .. }) if server == "token-test-no-such-language-server"),
+            "{msg:?}"
+        );
+    }
+}
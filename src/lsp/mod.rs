@@ -0,0 +1,300 @@
+//! Language Server Protocol client: diagnostics, hover and go-to-definition
+//!
+//! Servers are spawned per command line on first use (see
+//! [`LspManager`]) and spoken to with JSON-RPC over stdio. Documents are
+//! synced with full-text `didOpen`/`didChange` notifications once edits
+//! settle, piggybacking on the syntax parse debounce, and each document's
+//! revision is sent as its LSP version. Answers come back to the main
+//! thread as `Msg::Lsp`; diagnostics become line decorations and
+//! end-of-line annotations stamped with the revision they were made for,
+//! so they disappear as soon as the text changes under them.
+//!
+//! LSP counts columns in UTF-16 code units, the editor in chars; convert
+//! with [`utf16_column`] and [`char_column`].
+
+mod client;
+pub mod protocol;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::editor_area::DocumentId;
+use crate::syntax::LanguageId;
+
+pub use client::{LspClient, LspManager};
+
+/// `lsp:` section of `config.yaml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LspConfig {
+    /// Start language servers for opened files. Off by default, since
+    /// servers run project code (build scripts, macros) when they start.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Server for each LSP language id (`rust`, `typescript`, ...).
+    /// Languages that share a command share one server process.
+    #[serde(default = "default_servers")]
+    pub servers: BTreeMap<String, LspServerConfig>,
+}
+
+impl Default for LspConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            servers: default_servers(),
+        }
+    }
+}
+
+/// Command that starts a language server speaking LSP on stdio
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LspServerConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl LspServerConfig {
+    fn new(command: &str, args: &[&str]) -> Self {
+        Self {
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    /// Command line, which also names the server
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn default_servers() -> BTreeMap<String, LspServerConfig> {
+    let typescript = LspServerConfig::new("typescript-language-server", &["--stdio"]);
+    let clangd = LspServerConfig::new("clangd", &[]);
+    BTreeMap::from([
+        (
+            "rust".to_string(),
+            LspServerConfig::new("rust-analyzer", &[]),
+        ),
+        ("go".to_string(), LspServerConfig::new("gopls", &[])),
+        (
+            "python".to_string(),
+            LspServerConfig::new("pyright-langserver", &["--stdio"]),
+        ),
+        ("typescript".to_string(), typescript.clone()),
+        ("typescriptreact".to_string(), typescript.clone()),
+        ("javascript".to_string(), typescript.clone()),
+        ("javascriptreact".to_string(), typescript),
+        ("c".to_string(), clangd.clone()),
+        ("cpp".to_string(), clangd),
+    ])
+}
+
+/// LSP language identifier of `language`, the key of its server in
+/// [`LspConfig::servers`]
+pub fn language_id(language: LanguageId) -> Option<&'static str> {
+    Some(match language {
+        LanguageId::PlainText => return None,
+        LanguageId::Yaml => "yaml",
+        LanguageId::Markdown => "markdown",
+        LanguageId::Rust => "rust",
+        LanguageId::Html => "html",
+        LanguageId::Css => "css",
+        LanguageId::JavaScript => "javascript",
+        LanguageId::TypeScript => "typescript",
+        LanguageId::Tsx => "typescriptreact",
+        LanguageId::Jsx => "javascriptreact",
+        LanguageId::Json => "json",
+        LanguageId::Toml => "toml",
+        LanguageId::Python => "python",
+        LanguageId::Go => "go",
+        LanguageId::Php => "php",
+        LanguageId::C => "c",
+        LanguageId::Cpp => "cpp",
+        LanguageId::Java => "java",
+        LanguageId::Bash => "shellscript",
+        LanguageId::Scheme => "scheme",
+        LanguageId::Ini => "ini",
+        LanguageId::Xml => "xml",
+        LanguageId::Sema => "sema",
+        LanguageId::Blade => "blade",
+        LanguageId::Vue => "vue",
+        LanguageId::Just => "just",
+    })
+}
+
+/// Position as LSP counts it: 0-based line and UTF-16 code unit offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct LspPosition {
+    pub line: u32,
+    pub character: u32,
+}
+
+/// UTF-16 column of char column `column` in `line`
+pub fn utf16_column(line: &str, column: usize) -> u32 {
+    line.chars()
+        .take(column)
+        .map(|ch| ch.len_utf16() as u32)
+        .sum()
+}
+
+/// Char column of UTF-16 column `utf16` in `line`, clamped to its end
+pub fn char_column(line: &str, utf16: u32) -> usize {
+    let mut units = 0;
+    for (column, ch) in line.chars().enumerate() {
+        if units >= utf16 {
+            return column;
+        }
+        units += ch.len_utf16() as u32;
+    }
+    line.chars().count()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiagnosticSeverity {
+    Hint,
+    Information,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub start: LspPosition,
+    pub end: LspPosition,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    /// Tool that produced it, e.g. `rustc` or `clippy`
+    pub source: Option<String>,
+}
+
+/// A place in a file, as returned by go-to-definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub position: LspPosition,
+}
+
+/// Which server a request goes to; it is started on first use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspServerSpec {
+    pub config: LspServerConfig,
+    /// Workspace folder the server is started for
+    pub root: PathBuf,
+}
+
+impl LspServerSpec {
+    /// Name of the server in messages and status text
+    pub fn name(&self) -> String {
+        self.config.command_line()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspRequest {
+    pub server: LspServerSpec,
+    pub action: LspAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LspAction {
+    DidOpen {
+        path: PathBuf,
+        language_id: &'static str,
+        version: u64,
+        text: String,
+    },
+    DidChange {
+        path: PathBuf,
+        version: u64,
+        text: String,
+    },
+    /// Servers that check on save (e.g. `cargo check`) run now
+    DidSave {
+        path: PathBuf,
+    },
+    DidClose {
+        path: PathBuf,
+    },
+    /// Answered with `LspMsg::HoverResult`
+    Hover {
+        request_id: u64,
+        path: PathBuf,
+        position: LspPosition,
+    },
+    /// Answered with `LspMsg::DefinitionResult`
+    Definition {
+        request_id: u64,
+        path: PathBuf,
+        position: LspPosition,
+    },
+}
+
+/// A document a server has been told about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenDocument {
+    pub server: LspServerSpec,
+    pub path: PathBuf,
+    /// Last version sent, the document revision at the time
+    pub version: u64,
+}
+
+/// Lookup the user is waiting for; answers to older ones are dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingLookup {
+    Hover(u64),
+    Definition(u64),
+}
+
+/// Language server state — lives in `AppModel`. The server processes
+/// themselves are owned by the runtime's [`LspManager`].
+#[derive(Debug, Default)]
+pub struct LspState {
+    pub open_documents: HashMap<DocumentId, OpenDocument>,
+    /// Servers that failed to start or exited; nothing more is sent to them
+    pub failed_servers: HashSet<String>,
+    pub pending: Option<PendingLookup>,
+    next_request_id: u64,
+}
+
+impl LspState {
+    pub fn next_request_id(&mut self) -> u64 {
+        self.next_request_id += 1;
+        self.next_request_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_convert_between_chars_and_utf16() {
+        let line = "a😀b"; // the emoji is two UTF-16 units
+        assert_eq!(utf16_column(line, 0), 0);
+        assert_eq!(utf16_column(line, 2), 3);
+        assert_eq!(utf16_column(line, 9), 4);
+        assert_eq!(char_column(line, 3), 2);
+        assert_eq!(char_column(line, 4), 3);
+        assert_eq!(char_column(line, 40), 3);
+    }
+
+    #[test]
+    fn servers_are_keyed_by_lsp_language_id() {
+        let config = LspConfig::default();
+        for language in [LanguageId::Rust, LanguageId::Tsx, LanguageId::Cpp] {
+            let id = language_id(language).unwrap();
+            assert!(config.servers.contains_key(id), "{id}");
+        }
+        assert_eq!(
+            config.servers["typescriptreact"].command_line(),
+            "typescript-language-server --stdio"
+        );
+        assert_eq!(language_id(LanguageId::PlainText), None);
+    }
+}
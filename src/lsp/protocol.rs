@@ -0,0 +1,360 @@
+//! JSON-RPC framing and the LSP messages the client sends and reads
+//!
+//! Messages are JSON bodies behind a `Content-Length` header. Builders
+//! return the `params` of a request or notification; parsers take the
+//! `params` or `result` a server sent and return `None` for anything they
+//! don't understand.
+
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use super::{Diagnostic, DiagnosticSeverity, Location, LspPosition};
+
+/// `message` framed for the wire
+pub fn encode(message: &Value) -> Vec<u8> {
+    let body = message.to_string();
+    let mut bytes = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+    bytes.extend_from_slice(body.as_bytes());
+    bytes
+}
+
+/// Read the next message, or `None` at end of stream
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+pub fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// Reply to a request the server sent
+pub fn response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// `file://` URI of an absolute path
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+/// Path of a `file://` URI
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let hex = encoded
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (encoded[i], hex) {
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                bytes.push(byte);
+                i += 1;
+            }
+        }
+    }
+    let mut path = String::from_utf8(bytes).ok()?;
+    // `/C:/dir` is a Windows drive path
+    if let [b'/', drive, b':', b'/', ..] = path.as_bytes() {
+        if drive.is_ascii_alphabetic() {
+            path.remove(0);
+        }
+    }
+    Some(PathBuf::from(path))
+}
+
+pub fn initialize_params(root: &Path) -> Value {
+    let root_uri = path_to_uri(root);
+    let name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    json!({
+        "processId": std::process::id(),
+        "clientInfo": { "name": "token", "version": env!("CARGO_PKG_VERSION") },
+        "rootUri": root_uri,
+        "workspaceFolders": [{ "uri": root_uri, "name": name }],
+        "capabilities": {
+            "general": { "positionEncodings": ["utf-16"] },
+            "textDocument": {
+                "synchronization": { "dynamicRegistration": false },
+                "publishDiagnostics": { "versionSupport": true },
+                "hover": { "contentFormat": ["plaintext", "markdown"] },
+                "definition": { "linkSupport": true },
+            },
+        },
+    })
+}
+
+pub fn did_open_params(path: &Path, language_id: &str, version: u64, text: &str) -> Value {
+    json!({
+        "textDocument": {
+            "uri": path_to_uri(path),
+            "languageId": language_id,
+            "version": version,
+            "text": text,
+        }
+    })
+}
+
+/// Full-text change: the whole document is sent each time
+pub fn did_change_params(path: &Path, version: u64, text: &str) -> Value {
+    json!({
+        "textDocument": { "uri": path_to_uri(path), "version": version },
+        "contentChanges": [{ "text": text }],
+    })
+}
+
+/// Params naming just the document, for `didSave` and `didClose`
+pub fn text_document_params(path: &Path) -> Value {
+    json!({ "textDocument": { "uri": path_to_uri(path) } })
+}
+
+pub fn position_params(path: &Path, position: LspPosition) -> Value {
+    json!({
+        "textDocument": { "uri": path_to_uri(path) },
+        "position": { "line": position.line, "character": position.character },
+    })
+}
+
+fn parse_position(value: &Value) -> Option<LspPosition> {
+    Some(LspPosition {
+        line: value.get("line")?.as_u64()? as u32,
+        character: value.get("character")?.as_u64()? as u32,
+    })
+}
+
+/// Path, version and diagnostics of `textDocument/publishDiagnostics`
+pub fn parse_diagnostics(params: &Value) -> Option<(PathBuf, Option<u64>, Vec<Diagnostic>)> {
+    let path = uri_to_path(params.get("uri")?.as_str()?)?;
+    let version = params.get("version").and_then(Value::as_u64);
+    let diagnostics = params
+        .get("diagnostics")?
+        .as_array()?
+        .iter()
+        .filter_map(|diagnostic| {
+            let range = diagnostic.get("range")?;
+            let severity = match diagnostic.get("severity").and_then(Value::as_u64) {
+                Some(2) => DiagnosticSeverity::Warning,
+                Some(3) => DiagnosticSeverity::Information,
+                Some(4) => DiagnosticSeverity::Hint,
+                // Missing severity is up to the client; treat it as an error
+                _ => DiagnosticSeverity::Error,
+            };
+            Some(Diagnostic {
+                start: parse_position(range.get("start")?)?,
+                end: parse_position(range.get("end")?)?,
+                severity,
+                message: diagnostic.get("message")?.as_str()?.to_string(),
+                source: diagnostic
+                    .get("source")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            })
+        })
+        .collect();
+    Some((path, version, diagnostics))
+}
+
+/// Text of a `textDocument/hover` result
+pub fn parse_hover(result: &Value) -> Option<String> {
+    fn marked_string(value: &Value) -> Option<String> {
+        match value {
+            Value::String(text) => Some(text.clone()),
+            // `MarkupContent` and `{ language, value }` both carry `value`
+            Value::Object(object) => object.get("value")?.as_str().map(str::to_string),
+            _ => None,
+        }
+    }
+
+    let contents = result.get("contents")?;
+    let text = match contents {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(marked_string)
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        other => marked_string(other)?,
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// First target of a `textDocument/definition` result
+pub fn parse_definition(result: &Value) -> Option<Location> {
+    let first = match result {
+        Value::Array(items) => items.first()?,
+        other => other,
+    };
+    // `LocationLink` when the server uses link support, else `Location`
+    let (uri, range) = match first.get("targetUri") {
+        Some(uri) => (
+            uri,
+            first
+                .get("targetSelectionRange")
+                .or_else(|| first.get("targetRange"))?,
+        ),
+        None => (first.get("uri")?, first.get("range")?),
+    };
+    Some(Location {
+        path: uri_to_path(uri.as_str()?)?,
+        position: parse_position(range.get("start")?)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_round_trip_through_framing() {
+        let first = request(1, "initialize", json!({ "rootUri": null }));
+        let second = notification("initialized", json!({}));
+        let mut wire = encode(&first);
+        // Extra headers are allowed and ignored
+        wire.extend_from_slice(b"Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n");
+        wire.extend_from_slice(&encode(&second));
+
+        let mut reader = io::BufReader::new(wire.as_slice());
+        assert_eq!(read_message(&mut reader).unwrap(), Some(first));
+        assert_eq!(read_message(&mut reader).unwrap(), Some(second));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn uris_escape_and_unescape_paths() {
+        let path = Path::new("/tmp/my project/main#1.rs");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///tmp/my%20project/main%231.rs");
+        assert_eq!(uri_to_path(&uri).as_deref(), Some(path));
+        assert_eq!(
+            uri_to_path("file:///C:/src/lib.rs"),
+            Some(PathBuf::from("C:/src/lib.rs"))
+        );
+        assert_eq!(uri_to_path("https://example.com"), None);
+    }
+
+    #[test]
+    fn diagnostics_parse_with_default_severity() {
+        let params = json!({
+            "uri": "file:///src/main.rs",
+            "version": 4,
+            "diagnostics": [
+                {
+                    "range": { "start": { "line": 2, "character": 4 }, "end": { "line": 2, "character": 9 } },
+                    "severity": 2,
+                    "source": "rustc",
+                    "message": "unused variable",
+                },
+                {
+                    "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 1 } },
+                    "message": "no severity",
+                },
+                { "message": "no range" },
+            ],
+        });
+
+        let (path, version, diagnostics) = parse_diagnostics(&params).unwrap();
+        assert_eq!(path, PathBuf::from("/src/main.rs"));
+        assert_eq!(version, Some(4));
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(
+            diagnostics[0].start,
+            LspPosition {
+                line: 2,
+                character: 4
+            }
+        );
+        assert_eq!(diagnostics[0].source.as_deref(), Some("rustc"));
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Error);
+    }
+
+    #[test]
+    fn hover_accepts_every_content_shape() {
+        let markup =
+            json!({ "contents": { "kind": "markdown", "value": "```rust\nfn main()\n```" } });
+        assert_eq!(
+            parse_hover(&markup).as_deref(),
+            Some("```rust\nfn main()\n```")
+        );
+        let marked = json!({ "contents": ["plain", { "language": "rust", "value": "u32" }] });
+        assert_eq!(parse_hover(&marked).as_deref(), Some("plain\n\nu32"));
+        assert_eq!(parse_hover(&json!({ "contents": "" })), None);
+        assert_eq!(parse_hover(&Value::Null), None);
+    }
+
+    #[test]
+    fn definition_accepts_locations_and_links() {
+        let range =
+            json!({ "start": { "line": 7, "character": 3 }, "end": { "line": 7, "character": 8 } });
+        let expected = Location {
+            path: PathBuf::from("/src/lib.rs"),
+            position: LspPosition {
+                line: 7,
+                character: 3,
+            },
+        };
+
+        let location = json!({ "uri": "file:///src/lib.rs", "range": range });
+        assert_eq!(parse_definition(&location), Some(expected.clone()));
+        assert_eq!(parse_definition(&json!([location])), Some(expected.clone()));
+        let link = json!([{
+            "targetUri": "file:///src/lib.rs",
+            "targetRange": { "start": { "line": 6, "character": 0 }, "end": { "line": 9, "character": 1 } },
+            "targetSelectionRange": range,
+        }]);
+        assert_eq!(parse_definition(&link), Some(expected));
+        assert_eq!(parse_definition(&json!([])), None);
+        assert_eq!(parse_definition(&Value::Null), None);
+    }
+}
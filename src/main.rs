@@ -23,13 +23,24 @@ fn main() -> Result<()> {
     let _profiler = dhat::Profiler::new_heap();
 
     let _trace_guard = token::tracing::init();
+    token::crash::install_hook();
 
     // Parse command-line arguments
     let args = CliArgs::parse();
     let startup_config = args.into_config().map_err(|e| anyhow::anyhow!(e))?;
 
+    // On macOS the app installs its own menu bar instead of winit's default
+    #[cfg(target_os = "macos")]
+    let event_loop = {
+        use winit::platform::macos::EventLoopBuilderExtMacOS;
+        EventLoop::builder().with_default_menu(false).build()?
+    };
+    #[cfg(not(target_os = "macos"))]
     let event_loop = EventLoop::new()?;
     let mut app = App::new(800, 600, startup_config);
+    app.wake_on_messages(event_loop.create_proxy());
+    #[cfg(target_os = "macos")]
+    app.set_menu_proxy(event_loop.create_proxy());
     event_loop.run_app(&mut app)?;
 
     Ok(())
@@ -46,8 +57,9 @@ mod tests {
     use token::config::EditorConfig;
     use token::messages::{DocumentMsg, EditorMsg, Msg};
     use token::model::{
-        AppModel, Cursor, Document, EditorArea, EditorState, Position, RectangleSelectionState,
-        Selection, TabContent, UiState, ViewMode, Viewport,
+        AppModel, Cursor, Document, EditorArea, EditorState, FoldState, Position,
+        RectangleSelectionState, Selection, TabContent, UiState, ViewMode, Viewport,
+        WordHighlights,
     };
     use token::theme::Theme;
     use token::update::update;
@@ -86,11 +98,14 @@ mod tests {
             },
             scroll_padding: 1,
             rectangle_selection: RectangleSelectionState::default(),
+            text_drag: None,
             occurrence_state: None,
             selection_history: Vec::new(),
             view_mode: ViewMode::default(),
             tab_content: TabContent::default(),
             matched_brackets: None,
+            word_highlights: WordHighlights::default(),
+            folds: FoldState::default(),
         };
         let editor_area = EditorArea::single_document(document, editor);
         AppModel {
@@ -107,6 +122,13 @@ mod tests {
             terminal: token::terminal::TerminalState::default(),
             outline_panel: token::model::OutlinePanelState::default(),
             recent_files: token::recent_files::RecentFiles::default(),
+            workspace_trust: token::workspace_trust::WorkspaceTrust::default(),
+            file_ops: Default::default(),
+            vim: Default::default(),
+            ai_chat: token::ai::AiChatState::default(),
+            lsp: token::lsp::LspState::default(),
+            todo_panel: token::model::TodoPanelState::default(),
+            search_panel: token::model::SearchPanelState::default(),
             #[cfg(debug_assertions)]
             debug_overlay: None,
         }
@@ -687,11 +709,14 @@ mod tests {
             },
             scroll_padding: 1,
             rectangle_selection: RectangleSelectionState::default(),
+            text_drag: None,
             occurrence_state: None,
             selection_history: Vec::new(),
             view_mode: ViewMode::default(),
             tab_content: TabContent::default(),
             matched_brackets: None,
+            word_highlights: WordHighlights::default(),
+            folds: FoldState::default(),
         };
         let editor_area = EditorArea::single_document(document, editor);
         let mut model = AppModel {
@@ -708,6 +733,13 @@ mod tests {
             terminal: token::terminal::TerminalState::default(),
             outline_panel: token::model::OutlinePanelState::default(),
             recent_files: token::recent_files::RecentFiles::default(),
+            workspace_trust: token::workspace_trust::WorkspaceTrust::default(),
+            file_ops: Default::default(),
+            vim: Default::default(),
+            ai_chat: token::ai::AiChatState::default(),
+            lsp: token::lsp::LspState::default(),
+            todo_panel: token::model::TodoPanelState::default(),
+            search_panel: token::model::SearchPanelState::default(),
             #[cfg(debug_assertions)]
             debug_overlay: None,
         };
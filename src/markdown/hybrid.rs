@@ -0,0 +1,286 @@
+//! Inline styling for the hybrid Markdown view
+//!
+//! The hybrid view styles Markdown source in place instead of rendering it
+//! in a separate pane: emphasis is drawn bold/italic, headings bold in the
+//! title color, inline code on a background chip, and syntax markers and
+//! link URLs dimmed. Every character stays where it is in the monospace
+//! grid, so the buffer remains fully editable and cursor positions don't
+//! change.
+//!
+//! Styles are computed per line from the source text; only fenced code
+//! blocks need state across lines (see [`fenced_lines`]).
+
+/// How a single character is drawn in the hybrid view
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CharStyle {
+    pub bold: bool,
+    pub italic: bool,
+    /// Inside an inline code span (drawn on a chip)
+    pub code: bool,
+    /// Syntax marker or link URL (drawn dimmed)
+    pub dim: bool,
+}
+
+/// Styles for one line of Markdown source
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HybridLine {
+    /// `Some(1..=6)` for ATX heading lines
+    pub heading_level: Option<u8>,
+    /// One entry per char of the line
+    pub styles: Vec<CharStyle>,
+}
+
+/// Whether `line` opens or closes a fenced code block
+pub fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    line.len() - trimmed.len() < 4 && (trimmed.starts_with("```") || trimmed.starts_with("~~~"))
+}
+
+/// For each line in `first..first + count`, whether it is part of a fenced
+/// code block (fences included). `lines` must start at line 0 so fences
+/// above `first` are taken into account.
+pub fn fenced_lines<'a>(
+    lines: impl Iterator<Item = std::borrow::Cow<'a, str>>,
+    first: usize,
+    count: usize,
+) -> Vec<bool> {
+    let mut fenced = Vec::with_capacity(count);
+    let mut in_fence = false;
+    for (index, line) in lines.take(first + count).enumerate() {
+        let fence = is_fence(&line);
+        if index >= first {
+            fenced.push(in_fence || fence);
+        }
+        if fence {
+            in_fence = !in_fence;
+        }
+    }
+    fenced
+}
+
+/// Compute hybrid styles for a line outside fenced code blocks
+pub fn style_line(line: &str) -> HybridLine {
+    let chars: Vec<char> = line.chars().collect();
+    let mut styles = vec![CharStyle::default(); chars.len()];
+
+    let heading_level = heading_level(&chars);
+    let mut start = 0;
+    if let Some(level) = heading_level {
+        let marker_end = (level as usize + 1).min(chars.len());
+        for style in &mut styles[..marker_end] {
+            style.dim = true;
+        }
+        for style in &mut styles[marker_end..] {
+            style.bold = true;
+        }
+        start = marker_end;
+    }
+
+    scan_inline(&chars, start, chars.len(), &mut styles);
+    HybridLine {
+        heading_level,
+        styles,
+    }
+}
+
+/// Level of an ATX heading line (`## Title` is 2)
+pub fn atx_heading_level(line: &str) -> Option<u8> {
+    let prefix: Vec<char> = line
+        .trim_end_matches(['\n', '\r'])
+        .chars()
+        .take(7)
+        .collect();
+    heading_level(&prefix)
+}
+
+/// `#` count of an ATX heading (`# Title`), if the line is one
+fn heading_level(chars: &[char]) -> Option<u8> {
+    let level = chars.iter().take_while(|&&ch| ch == '#').count();
+    let followed_by_space = chars.get(level).is_none_or(|ch| *ch == ' ');
+    ((1..=6).contains(&level) && followed_by_space).then_some(level as u8)
+}
+
+/// Apply inline styles to `chars[start..end]`
+fn scan_inline(chars: &[char], start: usize, end: usize, styles: &mut [CharStyle]) {
+    let mut i = start;
+    while i < end {
+        match chars[i] {
+            '\\' if i + 1 < end && chars[i + 1].is_ascii_punctuation() => {
+                styles[i].dim = true;
+                i += 2;
+            }
+            '`' => i = code_span(chars, i, end, styles),
+            '*' | '_' => i = emphasis(chars, i, end, styles),
+            '!' if chars.get(i + 1) == Some(&'[') => match link(chars, i + 1, end, styles) {
+                Some(next) => {
+                    styles[i].dim = true;
+                    i = next;
+                }
+                None => i += 1,
+            },
+            '[' => i = link(chars, i, end, styles).unwrap_or(i + 1),
+            _ => i += 1,
+        }
+    }
+}
+
+fn run_length(chars: &[char], at: usize, end: usize, ch: char) -> usize {
+    chars[at..end].iter().take_while(|&&c| c == ch).count()
+}
+
+/// `` `code` `` starting at `at`; returns where scanning continues
+fn code_span(chars: &[char], at: usize, end: usize, styles: &mut [CharStyle]) -> usize {
+    let ticks = run_length(chars, at, end, '`');
+    let mut i = at + ticks;
+    while i < end {
+        if chars[i] == '`' {
+            let closing = run_length(chars, i, end, '`');
+            if closing == ticks {
+                for style in &mut styles[at..at + ticks] {
+                    style.dim = true;
+                    style.code = true;
+                }
+                for style in &mut styles[at + ticks..i] {
+                    style.code = true;
+                }
+                for style in &mut styles[i..i + ticks] {
+                    style.dim = true;
+                    style.code = true;
+                }
+                return i + ticks;
+            }
+            i += closing;
+        } else {
+            i += 1;
+        }
+    }
+    at + ticks
+}
+
+/// `*em*`, `**strong**` or `***both***` (or with `_`) starting at `at`
+fn emphasis(chars: &[char], at: usize, end: usize, styles: &mut [CharStyle]) -> usize {
+    let marker = chars[at];
+    let run = run_length(chars, at, end, marker);
+    let width = run.min(3);
+    let content_start = at + run;
+
+    // Opening run must be followed by text, and `_` must not be inside a word
+    let opens = chars
+        .get(content_start)
+        .is_some_and(|ch| !ch.is_whitespace())
+        && (marker == '*' || at == 0 || !chars[at - 1].is_alphanumeric());
+    if !opens || content_start >= end {
+        return content_start;
+    }
+
+    let mut i = content_start;
+    while i < end {
+        if chars[i] == '`' {
+            // Markers inside code spans don't count
+            let ticks = run_length(chars, i, end, '`');
+            let closes_at = (i + ticks..end).find(|&j| run_length(chars, j, end, '`') == ticks);
+            i = closes_at.map_or(i + ticks, |j| j + ticks);
+            continue;
+        }
+        if chars[i] == marker {
+            let closing = run_length(chars, i, end, marker);
+            let after_ok = marker == '*'
+                || chars
+                    .get(i + closing)
+                    .is_none_or(|ch| !ch.is_alphanumeric());
+            if closing == run && !chars[i - 1].is_whitespace() && after_ok {
+                let (bold, italic) = match width {
+                    1 => (false, true),
+                    2 => (true, false),
+                    _ => (true, true),
+                };
+                for style in &mut styles[content_start..i] {
+                    style.bold |= bold;
+                    style.italic |= italic;
+                }
+                for index in (at..content_start).chain(i..i + closing) {
+                    styles[index].dim = true;
+                }
+                scan_inline(chars, content_start, i, styles);
+                return i + closing;
+            }
+            i += closing;
+        } else {
+            i += 1;
+        }
+    }
+    content_start
+}
+
+/// `[text](url)` starting at the `[` at `at`; `None` if it isn't a link
+fn link(chars: &[char], at: usize, end: usize, styles: &mut [CharStyle]) -> Option<usize> {
+    let mut depth = 0;
+    let text_end = (at..end).find(|&i| {
+        match chars[i] {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => {}
+        }
+        depth == 0
+    })?;
+    if chars.get(text_end + 1) != Some(&'(') {
+        return None;
+    }
+    let close = (text_end + 2..end).find(|&i| chars[i] == ')')?;
+
+    styles[at].dim = true;
+    for style in &mut styles[text_end..=close] {
+        style.dim = true;
+    }
+    scan_inline(chars, at + 1, text_end, styles);
+    Some(close + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render styles as one char per source char: B bold, I italic,
+    /// X bold italic, C code, ~ dimmed, . plain
+    fn sketch(line: &str) -> String {
+        style_line(line)
+            .styles
+            .iter()
+            .map(|s| match (s.dim, s.code, s.bold, s.italic) {
+                (true, _, _, _) => '~',
+                (_, true, _, _) => 'C',
+                (_, _, true, true) => 'X',
+                (_, _, true, false) => 'B',
+                (_, _, false, true) => 'I',
+                _ => '.',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn emphasis_and_code_spans() {
+        assert_eq!(sketch("a **bold** b"), "..~~BBBB~~..");
+        assert_eq!(sketch("*it* and `x*y`"), "~II~.....~CCC~");
+        assert_eq!(sketch("***all***"), "~~~XXX~~~");
+        assert_eq!(sketch("snake_case_name"), "...............");
+        assert_eq!(sketch("2 * 3 * 4"), ".........");
+    }
+
+    #[test]
+    fn headings_links_and_escapes() {
+        let heading = style_line("## Title *x*");
+        assert_eq!(heading.heading_level, Some(2));
+        assert_eq!(sketch("## Title *x*"), "~~~BBBBBB~X~");
+        assert_eq!(style_line("#hashtag").heading_level, None);
+
+        assert_eq!(sketch("[**a**](u.md)"), "~~~B~~~~~~~~~");
+        assert_eq!(sketch("\\*not*"), "~.....");
+    }
+
+    #[test]
+    fn fenced_blocks_span_lines() {
+        let text = ["intro", "```rust", "let x = 1;", "```", "after"];
+        let lines = text.iter().map(|l| std::borrow::Cow::Borrowed(*l));
+        assert_eq!(fenced_lines(lines, 1, 4), vec![true, true, true, false]);
+    }
+}
@@ -1,11 +1,14 @@
 //! Markdown preview module
 //!
-//! Provides live Markdown preview with webview rendering and scroll synchronization.
+//! Provides live Markdown preview with webview rendering and scroll synchronization,
+//! and the in-place styling of the hybrid view.
 
+mod hybrid;
 mod preview;
 mod renderer;
 mod theme;
 
+pub use hybrid::{atx_heading_level, fenced_lines, is_fence, style_line, CharStyle, HybridLine};
 pub use preview::{MarkdownStyle, PreviewPane, RenderedLine, StyledSegment};
-pub use renderer::{content_to_preview_html, markdown_to_html};
+pub use renderer::{content_to_preview_html, markdown_to_html, scroll_to_line_on_load};
 pub use theme::PreviewTheme;
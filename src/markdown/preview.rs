@@ -13,6 +13,9 @@ pub struct PreviewPane {
     pub rendered_lines: Vec<RenderedLine>,
     pub scroll_offset: usize,
     pub scroll_sync_enabled: bool,
+    /// Source line the hosted webview is scrolled to, as last scrolled or
+    /// reported; `None` before its first load
+    pub webview_line: Option<usize>,
     pub rect: Rect,
     pub last_revision: u64,
 }
@@ -64,6 +67,7 @@ impl PreviewPane {
             rendered_lines: Vec::new(),
             scroll_offset: 0,
             scroll_sync_enabled: true,
+            webview_line: None,
             rect: Rect::default(),
             last_revision: 0,
         }
@@ -72,4 +76,14 @@ impl PreviewPane {
     pub fn needs_refresh(&self, document_revision: u64) -> bool {
         self.last_revision != document_revision
     }
+
+    /// Source line the webview should show: the editor's while scroll sync
+    /// is on, otherwise wherever the reader left it
+    pub fn webview_target_line(&self) -> usize {
+        if self.scroll_sync_enabled {
+            self.scroll_offset
+        } else {
+            self.webview_line.unwrap_or(0)
+        }
+    }
 }
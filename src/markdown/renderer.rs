@@ -58,6 +58,20 @@ pub fn content_to_preview_html(
     }
 }
 
+/// Make a rendered preview scroll to `line` (0-based source line) once it
+/// has loaded, so reloading it after an edit keeps the reader's place
+pub fn scroll_to_line_on_load(mut html: String, line: usize) -> String {
+    let script = format!(
+        "<script>window.addEventListener('load', function() {{ if (window.scrollToLine) window.scrollToLine({}); }});</script>\n",
+        line + 1
+    );
+    match html.rfind("</body>") {
+        Some(end) => html.insert_str(end, &script),
+        None => html.push_str(&script),
+    }
+    html
+}
+
 /// Wrap raw HTML content for preview display
 ///
 /// For HTML files, we display the content as-is without additional styling.
@@ -291,11 +305,20 @@ if (typeof hljs !== 'undefined') {
     hljs.highlightAll();
 }
 
-// Scroll to a specific source line
+// Scroll so the block containing a (1-based) source line is at the top
+let ignoreScrollUntil = 0;
 window.scrollToLine = function(line) {
-    const el = document.querySelector(`[data-line="${line}"]`);
-    if (el) {
-        el.scrollIntoView({ behavior: 'smooth', block: 'start' });
+    let target = null;
+    for (const el of document.querySelectorAll('[data-line]')) {
+        if (parseInt(el.getAttribute('data-line'), 10) > line) break;
+        target = el;
+    }
+    // Don't report our own scrolling back to the editor
+    ignoreScrollUntil = Date.now() + 250;
+    if (target) {
+        target.scrollIntoView({ block: 'start' });
+    } else {
+        window.scrollTo(0, 0);
     }
 };
 
@@ -304,6 +327,7 @@ let scrollTimeout = null;
 window.addEventListener('scroll', function() {
     if (scrollTimeout) clearTimeout(scrollTimeout);
     scrollTimeout = setTimeout(function() {
+        if (Date.now() < ignoreScrollUntil) return;
         const elements = document.querySelectorAll('[data-line]');
         let visibleLine = null;
         const viewportTop = window.scrollY;
@@ -316,8 +340,8 @@ window.addEventListener('scroll', function() {
             }
         }
         
-        if (visibleLine !== null && window.webkit && window.webkit.messageHandlers) {
-            window.webkit.messageHandlers.scrollSync.postMessage({ line: visibleLine });
+        if (visibleLine !== null && window.ipc) {
+            window.ipc.postMessage(String(visibleLine));
         }
     }, 100);
 });
@@ -472,4 +496,13 @@ mod tests {
 
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_scroll_to_line_on_load_scrolls_to_one_based_line() {
+        let theme = PreviewTheme::default();
+        let html = scroll_to_line_on_load(markdown_to_html("# A\n\nB", &theme), 2);
+
+        let script = html.find("window.scrollToLine(3)").unwrap();
+        assert!(script < html.rfind("</body>").unwrap());
+    }
 }
@@ -0,0 +1,237 @@
+//! Application menu bar layout
+//!
+//! The menus are plain data so they can be checked on every platform; the
+//! runtime builds the native menu bar from them on macOS. Items run palette
+//! commands, so a menu item does exactly what the command of the same name
+//! does in the command palette.
+
+use crate::commands::CommandId;
+
+/// A top-level menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Menu {
+    pub title: &'static str,
+    pub role: MenuRole,
+    pub items: Vec<MenuItem>,
+}
+
+/// Menus macOS treats specially
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuRole {
+    /// The application menu, titled with the app name
+    App,
+    /// Gets the list of open windows added by the system
+    Window,
+    /// Gets the system's help search field
+    Help,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    /// Runs a palette command
+    Command {
+        id: CommandId,
+        label: &'static str,
+    },
+    /// An item implemented by the system (About, Hide, Services, …)
+    Standard(StandardItem),
+    Separator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StandardItem {
+    About,
+    Services,
+    Hide,
+    HideOthers,
+    ShowAll,
+    Minimize,
+    Zoom,
+    Fullscreen,
+    BringAllToFront,
+}
+
+const fn command(id: CommandId, label: &'static str) -> MenuItem {
+    MenuItem::Command { id, label }
+}
+
+/// The menu bar: the app menu, then File, Edit, View, Window and Help
+pub fn menu_bar() -> Vec<Menu> {
+    use CommandId::*;
+    use MenuItem::{Separator, Standard};
+    use StandardItem::*;
+
+    vec![
+        Menu {
+            title: "Token",
+            role: MenuRole::App,
+            items: vec![
+                Standard(About),
+                Separator,
+                command(OpenSettings, "Settings…"),
+                command(OpenKeybindings, "Keybindings…"),
+                command(SwitchTheme, "Theme…"),
+                Separator,
+                Standard(Services),
+                Separator,
+                Standard(Hide),
+                Standard(HideOthers),
+                Standard(ShowAll),
+                Separator,
+                command(Quit, "Quit Token"),
+            ],
+        },
+        Menu {
+            title: "File",
+            role: MenuRole::Other,
+            items: vec![
+                command(NewFile, "New File"),
+                command(OpenFile, "Open File…"),
+                command(OpenFolder, "Open Folder…"),
+                command(OpenRecentFiles, "Open Recent…"),
+                command(FuzzyFileFinder, "Go to File…"),
+                Separator,
+                command(SaveFile, "Save"),
+                command(SaveFileAs, "Save As…"),
+                command(SaveAllFiles, "Save All"),
+                Separator,
+                command(RevealInFinder, "Reveal in Finder"),
+                command(CopyAbsolutePath, "Copy Path"),
+                Separator,
+                command(CloseTab, "Close Tab"),
+            ],
+        },
+        Menu {
+            title: "Edit",
+            role: MenuRole::Other,
+            items: vec![
+                command(Undo, "Undo"),
+                command(Redo, "Redo"),
+                Separator,
+                command(Cut, "Cut"),
+                command(Copy, "Copy"),
+                command(Paste, "Paste"),
+                command(SelectAll, "Select All"),
+                Separator,
+                command(Find, "Find…"),
+                command(FindNextMatch, "Find Next"),
+                command(FindPreviousMatch, "Find Previous"),
+                command(SearchInWorkspace, "Find in Workspace…"),
+                Separator,
+                command(GotoLine, "Go to Line…"),
+                command(GotoSymbol, "Go to Symbol…"),
+            ],
+        },
+        Menu {
+            title: "View",
+            role: MenuRole::Other,
+            items: vec![
+                command(ShowCommandPalette, "Command Palette…"),
+                Separator,
+                command(ToggleFileExplorer, "File Explorer"),
+                command(ToggleOutline, "Outline"),
+                command(ToggleTodoList, "TODO List"),
+                command(ToggleTerminal, "Terminal"),
+                command(ToggleAiChat, "AI Chat"),
+                Separator,
+                command(ZoomIn, "Zoom In"),
+                command(ZoomOut, "Zoom Out"),
+                command(ResetZoom, "Actual Size"),
+                Separator,
+                command(ToggleRenderWhitespace, "Render Whitespace"),
+                Separator,
+                command(SplitHorizontal, "Split Right"),
+                command(SplitVertical, "Split Down"),
+                command(CloseGroup, "Close Split"),
+                Separator,
+                command(ToggleMarkdownPreview, "Markdown Preview"),
+                command(ToggleCsvView, "CSV Grid"),
+                Separator,
+                Standard(Fullscreen),
+            ],
+        },
+        Menu {
+            title: "Window",
+            role: MenuRole::Window,
+            items: vec![
+                Standard(Minimize),
+                Standard(Zoom),
+                Separator,
+                command(NextTab, "Next Tab"),
+                command(PrevTab, "Previous Tab"),
+                Separator,
+                Standard(BringAllToFront),
+            ],
+        },
+        Menu {
+            title: "Help",
+            role: MenuRole::Help,
+            items: vec![
+                command(ShowWelcome, "Welcome"),
+                command(OpenLogFile, "Open Log File"),
+                command(ToggleLogViewer, "Log Viewer"),
+                Separator,
+                command(CheckForUpdates, "Check for Updates…"),
+                command(ViewReleaseNotes, "Release Notes"),
+            ],
+        },
+    ]
+}
+
+/// Menu of a tab (right-clicking it makes it the active tab, which the
+/// commands act on)
+pub fn tab_context_menu(pinned: bool) -> Vec<MenuItem> {
+    use CommandId::*;
+    use MenuItem::Separator;
+
+    vec![
+        command(CloseTab, "Close"),
+        command(CloseOtherTabs, "Close Others"),
+        command(CloseTabsToRight, "Close to the Right"),
+        command(CloseAllTabs, "Close All"),
+        Separator,
+        command(TogglePinTab, if pinned { "Unpin" } else { "Pin" }),
+        Separator,
+        command(CopyAbsolutePath, "Copy Path"),
+        command(RevealInFinder, "Reveal in File Explorer"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::COMMANDS;
+
+    #[test]
+    fn menu_items_are_palette_commands_listed_once() {
+        let mut seen = Vec::new();
+        for menu in menu_bar() {
+            assert!(!menu.items.is_empty(), "{} menu is empty", menu.title);
+            assert_ne!(menu.items.first(), Some(&MenuItem::Separator));
+            assert_ne!(menu.items.last(), Some(&MenuItem::Separator));
+            for item in &menu.items {
+                if let MenuItem::Command { id, .. } = item {
+                    assert!(
+                        COMMANDS.iter().any(|def| def.id == *id),
+                        "{:?} is not a palette command",
+                        id
+                    );
+                    assert!(!seen.contains(id), "{:?} is in the menus twice", id);
+                    seen.push(*id);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tab_context_menu_items_are_palette_commands() {
+        for pinned in [false, true] {
+            for item in tab_context_menu(pinned) {
+                if let MenuItem::Command { id, .. } = item {
+                    assert!(COMMANDS.iter().any(|def| def.id == id), "{:?}", id);
+                }
+            }
+        }
+    }
+}
@@ -4,10 +4,12 @@
 
 use std::path::PathBuf;
 
+use serde::{Deserialize, Serialize};
+
 use crate::editable::{EditContext, TextEditMsg};
 
 /// Direction for cursor movement
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
@@ -16,7 +18,9 @@ pub enum Direction {
 }
 
 /// Editor-specific messages (cursor movement, viewport scrolling)
-#[derive(Debug, Clone)]
+///
+/// Serializable so sessions can be recorded and replayed (see `crate::replay`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EditorMsg {
     // === Basic Movement ===
     /// Move cursor in a direction
@@ -41,6 +45,9 @@ pub enum EditorMsg {
     Scroll(i32),
     /// Scroll viewport horizontally (positive = right, negative = left)
     ScrollHorizontal(i32),
+    /// Scroll the view by lines without moving the cursor (Ctrl+Up/Down).
+    /// The cursor is only pulled back once it is more than a page out of view.
+    ScrollLines(i32),
 
     // === Selection Movement (Shift+key) ===
     /// Move cursor with selection (Shift+Arrow)
@@ -85,12 +92,24 @@ pub enum EditorMsg {
     RemoveCursor(usize),
 
     // === Find & Select ===
-    /// Select next occurrence of word/selection (Cmd+J)
+    /// Select next occurrence of word/selection (Cmd+D / Cmd+J)
     SelectNextOccurrence,
     /// Unselect last added occurrence (Shift+Cmd+J)
     UnselectOccurrence,
+    /// Drop the last added occurrence and select the next one instead
+    /// (Cmd+K)
+    SkipOccurrence,
     /// Select all occurrences (Cmd+Shift+L)
     SelectAllOccurrences,
+    /// Search for the word under the cursor (or the selection) and jump to
+    /// its next or previous occurrence (Cmd+F3 / Cmd+Shift+F3)
+    SearchWordUnderCursor { forward: bool },
+    /// Jump to the next match of the current search term (F3)
+    FindNextMatch,
+    /// Jump to the previous match of the current search term (Shift+F3)
+    FindPreviousMatch,
+    /// Stop highlighting matches of the current search term
+    ClearSearchHighlight,
 
     // === Expand/Shrink Selection ===
     /// Expand selection to next semantic level (Option+Up)
@@ -100,7 +119,7 @@ pub enum EditorMsg {
     /// Restores previous selection from history stack
     ShrinkSelection,
 
-    // === Rectangle Selection (Middle mouse) ===
+    // === Rectangle Selection (Middle mouse, Shift+Alt+arrows) ===
     /// Start rectangle selection at position (visual column = screen position)
     StartRectangleSelection { line: usize, visual_col: usize },
     /// Update rectangle selection to position (visual column = screen position)
@@ -109,10 +128,37 @@ pub enum EditorMsg {
     FinishRectangleSelection,
     /// Cancel rectangle selection
     CancelRectangleSelection,
+    /// Grow a rectangle selection from the cursor by a line or column
+    /// (Shift+Alt+arrows), starting one if needed
+    ExtendRectangleSelection(Direction),
+
+    // === Selection Drag-and-Drop (Left mouse) ===
+    /// Mouse pressed inside the selection: arm a drag instead of placing the cursor
+    StartTextDrag { line: usize, column: usize },
+    /// Move the drop position while dragging
+    UpdateTextDrag { line: usize, column: usize },
+    /// Mouse released without dragging: place the cursor where it was pressed
+    CancelTextDrag,
+
+    // === Folding ===
+    /// Cycle the Markdown heading under the cursor: folded → children → subtree
+    CycleHeadingFold,
+    /// Cycle all Markdown headings: overview → contents → show all
+    CycleAllHeadingFolds,
+    /// Fold or unfold the region at the cursor (or the one around it)
+    ToggleFold,
+    /// Fold or unfold the region headed by a line (gutter click)
+    ToggleFoldAt { line: usize },
+    /// Fold every region of the document
+    FoldAll,
+    /// Unfold everything
+    UnfoldAll,
 }
 
 /// Document-specific messages (text editing, undo/redo)
-#[derive(Debug, Clone)]
+///
+/// Serializable so sessions can be recorded and replayed (see `crate::replay`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DocumentMsg {
     /// Insert a character at cursor
     InsertChar(char),
@@ -136,6 +182,8 @@ pub enum DocumentMsg {
     Undo,
     /// Redo last undone edit
     Redo,
+    /// End the undo step being typed: the next edit undoes on its own
+    UndoGroupBoundary,
     /// Copy selection to clipboard (Cmd+C)
     Copy,
     /// Cut selection to clipboard (Cmd+X)
@@ -146,13 +194,35 @@ pub enum DocumentMsg {
     PasteText(String),
     /// Duplicate current line or selection (Cmd+D)
     Duplicate,
-    /// Indent selected lines (Tab with selection)
-    IndentLines,
-    /// Unindent current line or selected lines (Shift+Tab)
-    UnindentLines,
+    /// Swap the lines of every cursor and selection with the line above (Alt+Up)
+    MoveLinesUp,
+    /// Swap the lines of every cursor and selection with the line below (Alt+Down)
+    MoveLinesDown,
+    /// Indent the lines of every cursor and selection by one unit of
+    /// `indent_style` (Tab with selection)
+    IndentSelection,
+    /// Remove one unit of indentation from the lines of every cursor and
+    /// selection (Shift+Tab)
+    DedentSelection,
+    /// Drop the dragged selection at the drag's drop position, moving it
+    /// (or copying it when `copy` is set, Alt held) as a single undo step
+    DropDraggedText { copy: bool },
+    /// Replace every match of `query` in the document as a single undo
+    /// step. With `regex`, `query` is a regular expression and
+    /// `replacement` can refer to its capture groups (`$1`, `${name}`).
+    ReplaceAll {
+        query: String,
+        replacement: String,
+        regex: bool,
+        case_sensitive: bool,
+    },
 }
 
-use crate::model::{GroupId, ModalId, SegmentContent, SegmentId, SplitDirection, TabId};
+use crate::model::editor_area::PreviewId;
+use crate::model::{
+    DeletedFileChoice, DropEdge, FileChangedChoice, GroupId, ModalId, SegmentContent, SegmentId,
+    SplitDirection, TabColor, TabId, UnsavedChangesChoice, WorkspaceTrustChoice,
+};
 
 /// Modal-specific messages (command palette, goto line, find/replace)
 #[derive(Debug, Clone)]
@@ -227,16 +297,46 @@ pub enum ModalMsg {
     // === Find/Replace Specific ===
     /// Toggle between query and replace fields (Tab)
     ToggleFindReplaceField,
-    /// Toggle case sensitivity for find/replace
+    /// Toggle case sensitivity for find/replace (Alt+C)
     ToggleFindReplaceCaseSensitive,
+    /// Toggle treating the query as a regular expression (Alt+R)
+    ToggleFindRegex,
+    /// Toggle limiting find/replace to the selection the modal opened with
+    ToggleFindInSelection,
     /// Find next occurrence (Enter in find field or F3)
     FindNext,
     /// Find previous occurrence (Shift+Enter or Shift+F3)
     FindPrevious,
-    /// Replace current match and find next
+    /// Replace current match and find next (Enter in the replace field)
     ReplaceAndFindNext,
-    /// Replace all occurrences
+    /// Replace all occurrences, after review (Cmd+Alt+Enter)
     ReplaceAll,
+
+    // === Edit Review Specific ===
+    /// Toggle whether the selected hunk will be applied (Space)
+    ToggleReviewHunk,
+    /// Accept (true) or reject (false) every hunk
+    SetAllReviewHunks(bool),
+
+    // === Unsaved Changes Specific ===
+    /// Answer the unsaved changes prompt directly (S / D shortcuts)
+    ResolveUnsavedChanges(UnsavedChangesChoice),
+
+    // === Deleted File Specific ===
+    /// Answer the deleted file prompt directly (R / A shortcuts)
+    ResolveDeletedFile(DeletedFileChoice),
+
+    // === Changed File Specific ===
+    /// Answer the changed file prompt directly (R / K shortcuts)
+    ResolveFileChanged(FileChangedChoice),
+
+    // === Workspace Trust Specific ===
+    /// Answer the workspace trust prompt directly (T / R shortcuts)
+    ResolveWorkspaceTrust(WorkspaceTrustChoice),
+
+    // === Context Menu Specific ===
+    /// Highlight the context menu item at this index (mouse hover)
+    SelectContextMenuItem(usize),
 }
 
 /// UI-specific messages (status bar, cursor blink, modals)
@@ -246,6 +346,31 @@ pub enum UiMsg {
     SetStatus(String),
     /// Toggle cursor blink state
     BlinkCursor,
+    /// The cursor has rested in a word long enough to highlight the word's
+    /// other occurrences
+    HighlightWordOccurrences,
+    /// The window gained (true) or lost (false) keyboard focus
+    WindowFocused(bool),
+    /// The window was hidden (true) or shown again (false), e.g. minimized
+    WindowOccluded(bool),
+    /// Turn performance mode on or off, overriding `auto`
+    TogglePerformanceMode,
+    /// Show or hide the breadcrumb strip under the tab bars
+    ToggleBreadcrumbs,
+    /// Switch `render_whitespace` between off and drawing all whitespace
+    ToggleRenderWhitespace,
+    /// Make the editor font one step larger
+    ZoomIn,
+    /// Make the editor font one step smaller
+    ZoomOut,
+    /// Back to the default font size
+    ResetZoom,
+    /// The machine switched to (true) or from (false) battery power
+    PowerSourceChanged(bool),
+    /// Rendering has been slow for a stretch of frames
+    SlowFrames,
+    /// Scroll the log viewer (positive = towards the newest entry)
+    ScrollLogViewer { lines: i32 },
     /// Update a specific status bar segment
     UpdateSegment {
         id: SegmentId,
@@ -255,12 +380,17 @@ pub enum UiMsg {
     SetTransientMessage { text: String, duration_ms: u64 },
     /// Clear the transient message
     ClearTransientMessage,
+    /// A chord is waiting for its next key (`None` once it completes, is
+    /// cancelled or times out)
+    ChordPending(Option<String>),
+    /// The keys of a chord that matched no binding
+    ChordNotBound(String),
     /// Modal messages
     Modal(ModalMsg),
     /// Toggle a modal (open if closed, close if open)
     ToggleModal(ModalId),
 
-    /// Open fuzzy file finder modal (Cmd+Shift+O)
+    /// Open the Quick Open modal (Cmd+P)
     OpenFuzzyFileFinder,
 
     // === File Drag-and-Drop ===
@@ -268,6 +398,8 @@ pub enum UiMsg {
     FileHovered(PathBuf),
     /// Hover was cancelled (dragged away from window)
     FileHoverCancelled,
+    /// A file or folder was dropped onto the window
+    FileDropped(PathBuf),
 
     // === Scrollbar Interaction ===
     /// User clicked the vertical scrollbar track (not the thumb); jump to position
@@ -314,6 +446,10 @@ pub enum LayoutMsg {
     /// Open a file in a new tab in the focused group
     OpenFileInNewTab(PathBuf),
 
+    /// Open a file in the focused group's preview tab, replacing the file
+    /// previewed there (single click in the file tree)
+    OpenFileInPreviewTab(PathBuf),
+
     /// Split the focused group in the given direction
     /// Creates a new group with a copy of the current editor view
     SplitFocused(SplitDirection),
@@ -349,15 +485,63 @@ pub enum LayoutMsg {
     /// Move a tab to a new index within its current group (drag reorder)
     ReorderTab { tab_id: TabId, to_index: usize },
 
+    /// Move a tab into a new group split off an edge of a group (tab
+    /// dropped on the edge)
+    MoveTabToNewSplit {
+        tab_id: TabId,
+        group_id: GroupId,
+        edge: DropEdge,
+    },
+
+    /// Set (or clear) the color label of the focused group's active tab
+    SetTabColor(Option<TabColor>),
+
+    /// Sort the focused group's tabs by color label, unlabeled tabs last
+    GroupTabsByColor,
+
     /// Scroll a group's tab bar horizontally by a pixel delta
     ScrollTabBar { group_id: GroupId, delta_px: i32 },
 
+    /// Scroll the next group's editor by lines, keeping focus where it is
+    /// (Ctrl+Alt+Up/Down)
+    ScrollOtherGroup(i32),
+
+    /// Lock (or unlock) scrolling of the focused group together with the
+    /// other groups showing its document
+    ToggleScrollLock,
+
     /// Close a specific tab
     CloseTab(TabId),
 
     /// Close the active tab in the focused group
     CloseFocusedTab,
 
+    /// Close the focused group's tabs other than the active one, keeping
+    /// pinned tabs
+    CloseOtherTabs,
+
+    /// Close the focused group's tabs right of the active one, keeping
+    /// pinned tabs
+    CloseTabsToRight,
+
+    /// Close the focused group's tabs, keeping pinned tabs
+    CloseAllTabs,
+
+    /// Pin or unpin the active tab in the focused group. Pinning keeps a
+    /// preview tab open.
+    TogglePinTab,
+
+    /// Keep a preview tab open instead of replacing it with the next
+    /// previewed file (double-click on the tab)
+    KeepTabOpen(TabId),
+
+    /// Make a tab active and open its context menu at a window position
+    /// (right-click on the tab)
+    ShowTabContextMenu {
+        tab_id: TabId,
+        position: (usize, usize),
+    },
+
     /// Switch to next tab in focused group
     NextTab,
 
@@ -402,21 +586,70 @@ pub enum AppMsg {
     NewFile,
     /// File save completed (async result)
     SaveCompleted(Result<(), String>),
+    /// External tool finished running
+    ExternalToolFinished {
+        name: String,
+        result: Result<crate::external_tools::ToolOutput, String>,
+    },
+    /// Save every modified file (Save All)
+    SaveAllFiles,
+    /// The autosave interval elapsed
+    AutosaveInterval,
+    /// Automatic save of a document completed
+    AutosaveCompleted {
+        document_id: crate::model::editor_area::DocumentId,
+        /// Undo stack length of the saved content
+        saved_revision: usize,
+        result: Result<(), String>,
+    },
     /// File load completed (async result)
     FileLoaded {
         path: PathBuf,
         result: Result<String, String>,
     },
+    /// A large file has read `loaded` of its `total` bytes
+    LargeFileProgress {
+        document_id: crate::model::editor_area::DocumentId,
+        loaded: u64,
+        total: u64,
+    },
+    /// A large file finished streaming in
+    LargeFileLoaded {
+        document_id: crate::model::editor_area::DocumentId,
+        result: Result<crate::large_file::LoadedFile, String>,
+    },
+    /// The file of an open document was changed by another program; holds
+    /// its new contents
+    FileChangedOnDisk {
+        document_id: crate::model::editor_area::DocumentId,
+        content: String,
+    },
     /// Quit the application
     Quit,
+    /// Run a palette command (from the native menu bar)
+    RunCommand(crate::commands::CommandId),
+    /// The previous run crashed; offer to reopen its files
+    CrashReportFound {
+        report: std::path::PathBuf,
+        session: crate::crash::EditorSummary,
+    },
+    /// Latest release lookup finished
+    UpdateCheckCompleted {
+        manual: bool,
+        result: Result<crate::update_check::Release, String>,
+    },
     /// Reload configuration from disk
     ReloadConfiguration,
 
     // === File Dialog Messages ===
     /// User requested "Save As..." dialog
     SaveFileAs,
-    /// Save As dialog returned a path (or None if cancelled)
-    SaveFileAsDialogResult { path: Option<PathBuf> },
+    /// Save As dialog returned a path (or None if cancelled) for the
+    /// document it was opened for
+    SaveFileAsDialogResult {
+        document_id: Option<crate::model::editor_area::DocumentId>,
+        path: Option<PathBuf>,
+    },
 
     /// User requested "Open File..." dialog
     OpenFileDialog,
@@ -430,11 +663,25 @@ pub enum AppMsg {
 
     /// Paste text retrieved from system clipboard
     PasteFromClipboard(String),
+    /// The clipboard held an image rather than text
+    PasteImageFromClipboard(crate::paste_image::ClipboardImage),
+    /// An image pasted into a Markdown document was saved (or failed to)
+    PastedImageSaved {
+        document_id: crate::model::editor_area::DocumentId,
+        result: Result<PathBuf, String>,
+    },
     /// Default keymap file was created asynchronously
     KeymapCreated {
         path: PathBuf,
         result: Result<(), String>,
     },
+    /// The keymap files were loaded again (after a change or Reload
+    /// Configuration); `applied` is false when the user keymap failed to
+    /// load and the previous bindings were kept
+    KeymapReloaded {
+        diagnostics: Vec<crate::keymap::KeymapDiagnostic>,
+        applied: bool,
+    },
 }
 
 /// Syntax highlighting messages
@@ -472,10 +719,13 @@ pub enum PreviewMsg {
     Refresh,
     /// Scroll preview to line (from source scroll)
     ScrollToLine(usize),
-    /// Scroll source to line (from preview scroll via IPC)
-    SyncFromPreview(usize),
+    /// A preview's webview was scrolled to `line` (0-based source line) by
+    /// the user; scroll the source along
+    SyncFromPreview { preview_id: PreviewId, line: usize },
     /// Toggle scroll synchronization
     ToggleSync,
+    /// Toggle in-place styling of Markdown source (hybrid view)
+    ToggleHybrid,
 }
 
 /// CSV mode messages
@@ -511,10 +761,71 @@ pub enum CsvMsg {
         row: usize,
         col: usize,
     },
+    /// Grow the range selection by moving its head cell (Shift+arrows)
+    ExtendUp,
+    ExtendDown,
+    ExtendLeft,
+    ExtendRight,
+    /// Grow the range selection to a cell (Shift+click, mouse drag)
+    ExtendToCell {
+        row: usize,
+        col: usize,
+    },
+    /// Copy the selected cells to the clipboard as tab-separated values
+    CopySelection,
+    /// Start dragging a column's right edge (press on a header grid line)
+    StartColumnResize {
+        col: usize,
+        x: f64,
+    },
+    /// Follow the mouse while dragging a column's edge
+    UpdateColumnResize {
+        x: f64,
+    },
+    /// Finish dragging a column's edge
+    EndColumnResize,
+    /// Fit a column to its widest value (double-click on its edge)
+    AutoFitColumn(usize),
     /// Scroll viewport vertically (from mouse wheel)
     ScrollVertical(i32),
     /// Scroll viewport horizontally (from mouse wheel)
     ScrollHorizontal(i32),
+    /// Sort the rows by a column (header click or command), keeping the
+    /// header row first
+    SortByColumn {
+        col: usize,
+        ascending: bool,
+    },
+    /// Insert an empty row above the selected cell
+    InsertRowAbove,
+    /// Insert an empty row below the selected cell
+    InsertRowBelow,
+    /// Delete the selected cell's row
+    DeleteRow,
+    /// Insert an empty column left of the selected cell
+    InsertColumnLeft,
+    /// Insert an empty column right of the selected cell
+    InsertColumnRight,
+    /// Delete the selected cell's column
+    DeleteColumn,
+    /// Show the filter row and type into the selected column's filter, or
+    /// hide it again and show every row
+    ToggleFilterRow,
+    /// Type into a column's filter (click on the filter row)
+    FocusFilter(usize),
+    /// Type a character into the focused filter
+    FilterInsertChar(char),
+    /// Delete the last character of the focused filter
+    FilterDeleteBackward,
+    /// Move filter typing to the next column (Tab)
+    FilterNextColumn,
+    /// Move filter typing to the previous column (Shift+Tab)
+    FilterPrevColumn,
+    /// Go back to the grid, keeping the filters (Enter, Escape, Down)
+    LeaveFilter,
+    /// Treat the first row as the header naming the columns, or as an
+    /// ordinary row that sorts and filters with the rest
+    ToggleHeaderRow,
 
     // === Cell Editing (Phase 2) ===
     /// Start editing the selected cell (Enter or F2)
@@ -608,6 +919,199 @@ pub enum OutlineMsg {
     OpenSelected,
     /// Scroll the outline panel
     Scroll { lines: i32 },
+    /// List the symbols next to the one at `depth` of the focused editor's
+    /// breadcrumbs
+    ShowBreadcrumbSymbols { depth: usize },
+}
+
+/// TODO list panel messages
+#[derive(Debug, Clone)]
+pub enum TodoMsg {
+    /// Rescan open documents (and the workspace, if any) for tagged comments
+    Refresh,
+    /// Background scan finished
+    ScanCompleted {
+        generation: u64,
+        files: Vec<crate::todo::TodoFile>,
+    },
+    /// Click on a row in the TODO panel
+    ClickRow { index: usize },
+    /// Navigate up in the list
+    SelectPrevious,
+    /// Navigate down in the list
+    SelectNext,
+    /// Jump to the selected item
+    OpenSelected,
+    /// Scroll the TODO panel
+    Scroll { lines: i32 },
+}
+
+/// Workspace search panel messages
+#[derive(Debug, Clone)]
+pub enum SearchMsg {
+    /// Open and focus the search panel, taking the query from a selection
+    /// within one line
+    Open,
+    /// Edit the query input
+    Input(TextEditMsg),
+    /// Search the workspace for the query
+    Submit,
+    /// Stop the search in flight
+    Cancel,
+    /// Toggle case-sensitive matching (Alt+C)
+    ToggleCaseSensitive,
+    /// Toggle whole-word matching (Alt+W)
+    ToggleWholeWord,
+    /// Toggle regular expression queries (Alt+R)
+    ToggleRegex,
+    /// Files with matches, streamed from the search thread
+    ResultsFound {
+        generation: u64,
+        files: Vec<crate::project_search::SearchFileResult>,
+    },
+    /// The search thread finished (or was cancelled)
+    Finished {
+        generation: u64,
+        summary: crate::project_search::SearchSummary,
+    },
+    /// Click on a row in the results
+    ClickRow { index: usize },
+    /// Navigate up in the results
+    SelectPrevious,
+    /// Navigate down in the results
+    SelectNext,
+    /// Jump to the selected match
+    OpenSelected,
+    /// Scroll the results
+    Scroll { lines: i32 },
+}
+
+/// Links between Markdown notes
+#[derive(Debug, Clone)]
+pub enum NotesMsg {
+    /// Open the target of the link at a position in the focused document
+    /// (Cmd+Click); a missing target prompts to create it
+    FollowLinkAt { line: usize, column: usize },
+    /// Open the target of the link under the primary cursor
+    FollowLinkAtCursor,
+    /// List other workspace notes linking to the focused document
+    ShowBacklinks,
+    /// Background backlink scan finished
+    BacklinksFound {
+        target: PathBuf,
+        backlinks: Vec<crate::notes::Backlink>,
+    },
+}
+
+/// Git gutter and blame messages
+#[derive(Debug, Clone)]
+pub enum GitMsg {
+    /// Background diff of a document against HEAD finished
+    DiffComputed(crate::git::GitDiffResult),
+    /// Show or hide blame annotations
+    ToggleBlame,
+    /// Background blame of a document finished
+    BlameComputed(crate::git::GitBlameResult),
+}
+
+/// Completion popup messages (act on the focused editor)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionMsg {
+    /// Offer file path completions for the string or link at the cursor
+    TriggerPath,
+    /// Highlight the previous item
+    SelectPrevious,
+    /// Highlight the next item
+    SelectNext,
+    /// Replace the typed text with the highlighted item
+    Accept,
+    /// Close the popup
+    Dismiss,
+}
+
+/// Vim emulation messages (ignored unless `vim_mode` is on)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimMsg {
+    /// A key typed in normal or visual mode
+    Key(char),
+    /// Back to normal mode, dropping a partly typed command (Escape)
+    NormalMode,
+}
+
+/// Welcome tab messages (all but `Show` act on the focused welcome tab)
+#[derive(Debug, Clone)]
+pub enum WelcomeMsg {
+    /// Open the welcome tab, or switch to it if already open
+    Show,
+    /// Move the selection up one entry
+    SelectPrevious,
+    /// Move the selection down one entry
+    SelectNext,
+    /// Select the first entry
+    SelectFirst,
+    /// Select the last entry
+    SelectLast,
+    /// Run the selected entry
+    ActivateSelected,
+    /// Run the entry at `index` (mouse click)
+    Activate { index: usize },
+}
+
+/// AI chat panel messages
+#[derive(Debug, Clone)]
+pub enum AiChatMsg {
+    /// Edit the prompt input
+    Input(TextEditMsg),
+    /// Send the prompt input as a new user message
+    Submit,
+    /// Stop the streaming response
+    Cancel,
+    /// Clear the conversation
+    Clear,
+    /// A chunk of the response arrived (sent by the request worker thread)
+    StreamDelta { request_id: u64, text: String },
+    /// The response finished, successfully or with an error
+    StreamFinished {
+        request_id: u64,
+        error: Option<String>,
+    },
+    /// Ask the assistant to explain the editor selection
+    ExplainSelection,
+    /// Insert the last answer at the editor cursor
+    InsertAnswerAtCursor,
+    /// Replace the editor selection with the last answer
+    ReplaceSelectionWithAnswer,
+    /// Scroll the transcript (positive = towards older messages)
+    Scroll { lines: i32 },
+}
+
+/// Language server messages
+#[derive(Debug, Clone)]
+pub enum LspMsg {
+    /// Show hover information for the symbol at the cursor
+    Hover,
+    /// Jump to the definition of the symbol at the cursor
+    GoToDefinition,
+    /// A server published diagnostics for a file (sent by its reader thread).
+    /// `version` is the document revision they were computed for, when the
+    /// server says.
+    Diagnostics {
+        path: std::path::PathBuf,
+        version: Option<u64>,
+        diagnostics: Vec<crate::lsp::Diagnostic>,
+    },
+    /// Answer to a hover request
+    HoverResult {
+        request_id: u64,
+        contents: Option<String>,
+    },
+    /// Answer to a go-to-definition request
+    DefinitionResult {
+        request_id: u64,
+        location: Option<crate::lsp::Location>,
+    },
+    /// A server failed to start or exited
+    ServerStopped { server: String, error: String },
 }
 
 /// Terminal panel messages.
@@ -710,6 +1214,21 @@ pub enum DockMsg {
 
     /// Toggle dock visibility at position
     ToggleDock(crate::panel::DockPosition),
+
+    /// Collapse an open dock to its icon strip, or expand it again
+    /// (double-click on the dock header)
+    ToggleCollapsed(crate::panel::DockPosition),
+
+    /// Grow (positive) or shrink (negative) the focused dock by a number of
+    /// logical pixels, clamped to the dock's min/max size
+    ResizeFocusedDock { delta: f32 },
+
+    /// Move focus to the next area: editor → open docks (left, bottom,
+    /// right) → editor
+    FocusNextArea,
+
+    /// Move focus to the previous area (reverse of `FocusNextArea`)
+    FocusPreviousArea,
 }
 
 /// Workspace messages (file tree sidebar)
@@ -769,6 +1288,59 @@ pub enum WorkspaceMsg {
     /// File system change detected by watcher (triggers tree refresh)
     /// Contains the paths that changed for incremental updates.
     FileSystemChange { paths: Vec<PathBuf> },
+
+    /// A file or directory was renamed/moved from `from` to `to`; open
+    /// documents and recent files under it follow the move
+    PathRenamed { from: PathBuf, to: PathBuf },
+
+    /// Move a file or directory to the trash (Delete in the file tree)
+    DeletePath(PathBuf),
+
+    /// Rename a file or directory, trashing anything already at `to`
+    RenamePath { from: PathBuf, to: PathBuf },
+
+    /// Undo the most recent delete or rename made from the editor
+    UndoFileOperation,
+
+    /// Create an empty file (and any missing parent folders), then open it
+    CreateFile(PathBuf),
+
+    /// Creating a file finished on the background thread
+    FileCreated(Result<PathBuf, String>),
+
+    /// Create an empty folder (and any missing parent folders)
+    CreateFolder(PathBuf),
+
+    /// Creating a folder finished on the background thread
+    FolderCreated(Result<PathBuf, String>),
+
+    /// Type the name of a new file in the file tree, in the selected folder
+    /// (or the selected file's folder)
+    StartNewFile,
+
+    /// Type the name of a new folder in the file tree
+    StartNewFolder,
+
+    /// Edit the name of the selected file or folder in place
+    StartRename,
+
+    /// Edit the name being typed in the file tree
+    NameInput(TextEditMsg),
+
+    /// Create or rename with the typed name (Enter)
+    ConfirmNameEdit,
+
+    /// Stop typing a name without changing anything (Escape)
+    CancelNameEdit,
+
+    /// A delete or rename finished on the background thread
+    FileOperationCompleted(Result<crate::file_ops::FileOperation, String>),
+
+    /// Undoing `operation` finished on the background thread
+    FileOperationReverted {
+        operation: crate::file_ops::FileOperation,
+        result: Result<(), String>,
+    },
 }
 
 /// Image viewer messages
@@ -794,6 +1366,33 @@ pub enum ImageMsg {
     MouseMove { x: f64, y: f64 },
 }
 
+/// Diff view messages
+#[derive(Debug, Clone)]
+pub enum DiffMsg {
+    /// Diff the focused document against the file on disk
+    CompareWithSaved,
+    /// Diff the focused document against its HEAD version
+    CompareWithHead,
+    /// Diff the focused document against the one shown in another split
+    CompareWithOtherSplit,
+    /// Scroll to the next change
+    NextChange,
+    /// Scroll to the previous change
+    PrevChange,
+    /// Scroll both halves by rows
+    Scroll(i32),
+    /// Scroll both halves sideways by columns
+    ScrollHorizontal(i32),
+    PageUp,
+    PageDown,
+    /// Scroll to the first row
+    Top,
+    /// Scroll to the last row
+    Bottom,
+    /// Back to the text view
+    Close,
+}
+
 /// Top-level message type
 #[derive(Debug, Clone)]
 pub enum Msg {
@@ -813,6 +1412,8 @@ pub enum Msg {
     Csv(CsvMsg),
     /// Image viewer messages
     Image(ImageMsg),
+    /// Diff view messages
+    Diff(DiffMsg),
     /// Markdown preview messages
     Preview(PreviewMsg),
     /// Workspace messages (file tree)
@@ -821,10 +1422,28 @@ pub enum Msg {
     Dock(DockMsg),
     /// Outline panel messages
     Outline(OutlineMsg),
+    /// TODO list panel messages
+    Todo(TodoMsg),
+    /// Workspace search panel messages
+    Search(SearchMsg),
+    /// AI chat panel messages
+    AiChat(AiChatMsg),
+    /// Language server messages
+    Lsp(LspMsg),
+    /// Welcome tab messages
+    Welcome(WelcomeMsg),
+    /// Markdown note link messages
+    Notes(NotesMsg),
+    /// Completion popup messages
+    Completion(CompletionMsg),
+    /// Vim emulation messages
+    Vim(VimMsg),
     /// Unified text editing messages (Phase 2 - editable system)
     TextEdit(EditContext, TextEditMsg),
     /// Terminal panel messages
     Terminal(TerminalMsg),
+    /// Git gutter and blame messages
+    Git(GitMsg),
 }
 
 // Convenience constructors for common messages
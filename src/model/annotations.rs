@@ -0,0 +1,187 @@
+//! Inline annotations: virtual text shown among a document's text
+//!
+//! Features attach read-only text to document positions (diagnostic
+//! summaries, blame, hints). It is drawn dimmed but is not part of the
+//! buffer, so cursors, selections and edits never see it. Each source
+//! replaces all of its annotations at once, stamped with the document
+//! revision they were computed for; once the document changes they are
+//! hidden until the source sets them again, so an edit never leaves an
+//! annotation next to the wrong text.
+
+/// Feature that owns a set of annotations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnnotationSource {
+    Diagnostics,
+    GitBlame,
+    Ai,
+    ParameterHints,
+}
+
+/// Where an annotation is drawn on its line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationPlacement {
+    /// In front of the character at `column`, pushing the rest of the line
+    /// to the right
+    Inline { column: usize },
+    /// After the end of the line
+    EndOfLine,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub line: usize,
+    pub placement: AnnotationPlacement,
+    pub text: String,
+}
+
+impl Annotation {
+    pub fn inline(line: usize, column: usize, text: impl Into<String>) -> Self {
+        Self {
+            line,
+            placement: AnnotationPlacement::Inline { column },
+            text: text.into(),
+        }
+    }
+
+    pub fn end_of_line(line: usize, text: impl Into<String>) -> Self {
+        Self {
+            line,
+            placement: AnnotationPlacement::EndOfLine,
+            text: text.into(),
+        }
+    }
+
+    /// Columns the annotation's text takes up
+    pub fn width(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Sort key within a line: inline annotations by column, then
+    /// end-of-line ones
+    fn order(&self) -> (usize, usize) {
+        match self.placement {
+            AnnotationPlacement::Inline { column } => (self.line, column),
+            AnnotationPlacement::EndOfLine => (self.line, usize::MAX),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SourceAnnotations {
+    source: AnnotationSource,
+    /// Document revision the annotations were computed for
+    revision: u64,
+    /// Sorted by line, then position on the line
+    annotations: Vec<Annotation>,
+}
+
+/// Annotations of one document, by source
+#[derive(Debug, Clone, Default)]
+pub struct Annotations {
+    /// Sorted by source, which orders annotations at the same place
+    sources: Vec<SourceAnnotations>,
+}
+
+impl Annotations {
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Replace the annotations of `source` with ones computed for document
+    /// `revision`. Line breaks and tabs in their text become spaces.
+    pub fn set(
+        &mut self,
+        source: AnnotationSource,
+        revision: u64,
+        annotations: impl IntoIterator<Item = Annotation>,
+    ) {
+        let mut annotations: Vec<Annotation> = annotations
+            .into_iter()
+            .filter(|annotation| !annotation.text.is_empty())
+            .map(|mut annotation| {
+                if annotation.text.contains(['\n', '\r', '\t']) {
+                    annotation.text = annotation.text.replace(['\n', '\r', '\t'], " ");
+                }
+                annotation
+            })
+            .collect();
+        annotations.sort_by_key(Annotation::order);
+
+        self.clear(source);
+        if annotations.is_empty() {
+            return;
+        }
+        let index = self.sources.partition_point(|s| s.source < source);
+        self.sources.insert(
+            index,
+            SourceAnnotations {
+                source,
+                revision,
+                annotations,
+            },
+        );
+    }
+
+    /// Remove the annotations of `source`
+    pub fn clear(&mut self, source: AnnotationSource) {
+        self.sources.retain(|s| s.source != source);
+    }
+
+    /// Annotations on `line` that are current at document `revision`:
+    /// inline ones by column, then end-of-line ones; ties in source order
+    pub fn on_line(&self, line: usize, revision: u64) -> Vec<&Annotation> {
+        let mut found = Vec::new();
+        for source in self.sources.iter().filter(|s| s.revision == revision) {
+            let start = source.annotations.partition_point(|a| a.line < line);
+            found.extend(
+                source.annotations[start..]
+                    .iter()
+                    .take_while(|a| a.line == line),
+            );
+        }
+        // Stable, so sources keep their order at the same place
+        found.sort_by_key(|a| a.order());
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotations_merge_by_position_then_source() {
+        let mut annotations = Annotations::default();
+        annotations.set(
+            AnnotationSource::ParameterHints,
+            3,
+            [
+                Annotation::end_of_line(1, "hint"),
+                Annotation::inline(1, 4, "x:"),
+            ],
+        );
+        annotations.set(
+            AnnotationSource::Diagnostics,
+            3,
+            [
+                Annotation::end_of_line(1, "error\nhere"),
+                Annotation::inline(1, 2, "a:"),
+                Annotation::inline(2, 0, "other line"),
+            ],
+        );
+
+        let texts: Vec<_> = annotations
+            .on_line(1, 3)
+            .iter()
+            .map(|a| a.text.as_str())
+            .collect();
+        assert_eq!(texts, ["a:", "x:", "error here", "hint"]);
+
+        // Stale at another revision, and replaced as a whole
+        assert!(annotations.on_line(1, 4).is_empty());
+        annotations.set(AnnotationSource::Diagnostics, 3, []);
+        assert_eq!(annotations.on_line(1, 3).len(), 2);
+        annotations.clear(AnnotationSource::ParameterHints);
+        assert!(annotations.is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+//! Whole-line decorations: background tints and gutter icons
+//!
+//! Features mark lines of a document (search results, lint warnings, the
+//! debugger's current line, snippet tab stops) rather than drawing their own
+//! highlights. Like [`Annotations`](super::Annotations), each source
+//! replaces its decorations as a whole, stamped with the document revision
+//! they were made for, and stale ones are hidden. When several decorate one
+//! line, the tint and the icon are each taken from the highest priority
+//! decoration that has one; ties go to the source listed first.
+
+/// Feature that owns a set of line decorations. The order breaks priority
+/// ties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DecorationSource {
+    Debugger,
+    Diagnostics,
+    Search,
+    Snippet,
+}
+
+/// What a decoration means, which picks its theme color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecorationKind {
+    /// Search results and other neutral marks
+    Highlight,
+    Success,
+    Warning,
+    Error,
+}
+
+/// Icon drawn at the left edge of the gutter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GutterIcon {
+    Dot,
+    Arrow,
+    Diamond,
+}
+
+impl GutterIcon {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            GutterIcon::Dot => "●",
+            GutterIcon::Arrow => "▶",
+            GutterIcon::Diamond => "◆",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineDecoration {
+    pub line: usize,
+    pub kind: DecorationKind,
+    /// Tint the line's background
+    pub tint: bool,
+    pub icon: Option<GutterIcon>,
+    /// Higher wins when decorations meet on a line
+    pub priority: i32,
+}
+
+impl LineDecoration {
+    /// Background tint for `line`
+    pub fn tint(line: usize, kind: DecorationKind) -> Self {
+        Self {
+            line,
+            kind,
+            tint: true,
+            icon: None,
+            priority: 0,
+        }
+    }
+
+    /// Gutter icon for `line`, without a tint
+    pub fn icon(line: usize, kind: DecorationKind, icon: GutterIcon) -> Self {
+        Self {
+            line,
+            kind,
+            tint: false,
+            icon: Some(icon),
+            priority: 0,
+        }
+    }
+
+    pub fn with_icon(mut self, icon: GutterIcon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// How a line is decorated, after merging every source
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineStyle {
+    pub tint: Option<DecorationKind>,
+    pub icon: Option<(GutterIcon, DecorationKind)>,
+}
+
+impl LineStyle {
+    pub fn is_plain(&self) -> bool {
+        self.tint.is_none() && self.icon.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SourceDecorations {
+    source: DecorationSource,
+    /// Document revision the decorations were made for
+    revision: u64,
+    /// Sorted by line
+    decorations: Vec<LineDecoration>,
+}
+
+/// Line decorations of one document, by source
+#[derive(Debug, Clone, Default)]
+pub struct LineDecorations {
+    /// Sorted by source
+    sources: Vec<SourceDecorations>,
+}
+
+impl LineDecorations {
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Replace the decorations of `source` with ones made for document
+    /// `revision`
+    pub fn set(
+        &mut self,
+        source: DecorationSource,
+        revision: u64,
+        decorations: impl IntoIterator<Item = LineDecoration>,
+    ) {
+        let mut decorations: Vec<LineDecoration> = decorations
+            .into_iter()
+            .filter(|decoration| decoration.tint || decoration.icon.is_some())
+            .collect();
+        decorations.sort_by_key(|decoration| decoration.line);
+
+        self.clear(source);
+        if decorations.is_empty() {
+            return;
+        }
+        let index = self.sources.partition_point(|s| s.source < source);
+        self.sources.insert(
+            index,
+            SourceDecorations {
+                source,
+                revision,
+                decorations,
+            },
+        );
+    }
+
+    /// Remove the decorations of `source`
+    pub fn clear(&mut self, source: DecorationSource) {
+        self.sources.retain(|s| s.source != source);
+    }
+
+    /// Merged decoration of `line` at document `revision`
+    pub fn style(&self, line: usize, revision: u64) -> LineStyle {
+        let mut style = LineStyle::default();
+        let mut tint_priority = i32::MIN;
+        let mut icon_priority = i32::MIN;
+        // Sources in order, so a later one needs a strictly higher priority
+        for source in self.sources.iter().filter(|s| s.revision == revision) {
+            let start = source.decorations.partition_point(|d| d.line < line);
+            for decoration in source.decorations[start..]
+                .iter()
+                .take_while(|d| d.line == line)
+            {
+                if decoration.tint && (style.tint.is_none() || decoration.priority > tint_priority)
+                {
+                    style.tint = Some(decoration.kind);
+                    tint_priority = decoration.priority;
+                }
+                if let Some(icon) = decoration.icon {
+                    if style.icon.is_none() || decoration.priority > icon_priority {
+                        style.icon = Some((icon, decoration.kind));
+                        icon_priority = decoration.priority;
+                    }
+                }
+            }
+        }
+        style
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decorations_merge_by_priority_then_source() {
+        let mut decorations = LineDecorations::default();
+        decorations.set(
+            DecorationSource::Search,
+            1,
+            [
+                LineDecoration::tint(4, DecorationKind::Highlight),
+                LineDecoration::tint(2, DecorationKind::Highlight),
+            ],
+        );
+        decorations.set(
+            DecorationSource::Diagnostics,
+            1,
+            [
+                LineDecoration::icon(2, DecorationKind::Warning, GutterIcon::Dot),
+                LineDecoration::tint(4, DecorationKind::Error)
+                    .with_icon(GutterIcon::Dot)
+                    .with_priority(-1),
+            ],
+        );
+        decorations.set(
+            DecorationSource::Debugger,
+            1,
+            [LineDecoration::icon(
+                4,
+                DecorationKind::Success,
+                GutterIcon::Arrow,
+            )],
+        );
+
+        // Search's tint on its own, Diagnostics' icon on its own
+        assert_eq!(
+            decorations.style(2, 1),
+            LineStyle {
+                tint: Some(DecorationKind::Highlight),
+                icon: Some((GutterIcon::Dot, DecorationKind::Warning)),
+            }
+        );
+        // Search outranks the lowered diagnostic; Debugger is listed first
+        assert_eq!(
+            decorations.style(4, 1),
+            LineStyle {
+                tint: Some(DecorationKind::Highlight),
+                icon: Some((GutterIcon::Arrow, DecorationKind::Success)),
+            }
+        );
+        assert!(decorations.style(3, 1).is_plain());
+        assert!(decorations.style(2, 2).is_plain());
+    }
+}
@@ -2,10 +2,15 @@
 
 use ropey::Rope;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+use super::annotations::Annotations;
+use super::decorations::LineDecorations;
 use super::editor::Cursor;
 use super::editor_area::DocumentId;
-use crate::syntax::{LanguageId, SyntaxHighlights};
+use crate::git::{GitBlame, GitDiff, HeadVersion};
+use crate::syntax::{EditLog, LanguageId, SyntaxHighlights, TextEdit};
+use crate::util::text::{char_col_to_visual_col, visual_col_to_char_col};
 
 /// Represents an edit operation for undo/redo functionality
 #[derive(Debug, Clone)]
@@ -41,6 +46,159 @@ pub enum EditOperation {
     },
 }
 
+impl EditOperation {
+    /// The text changes this operation makes, in the order they are applied
+    pub fn text_edits(&self) -> Vec<TextEdit> {
+        match self {
+            EditOperation::Insert { position, text, .. } => vec![TextEdit::insert(*position, text)],
+            EditOperation::Delete { position, text, .. } => vec![TextEdit::delete(*position, text)],
+            EditOperation::Replace {
+                position,
+                deleted_text,
+                inserted_text,
+                ..
+            } => vec![TextEdit::replace(*position, deleted_text, inserted_text)],
+            EditOperation::Batch { operations, .. } => {
+                operations.iter().flat_map(|op| op.text_edits()).collect()
+            }
+        }
+    }
+
+    /// The text changes undoing this operation makes
+    pub fn undo_text_edits(&self) -> Vec<TextEdit> {
+        match self {
+            EditOperation::Insert { position, text, .. } => vec![TextEdit::delete(*position, text)],
+            EditOperation::Delete { position, text, .. } => vec![TextEdit::insert(*position, text)],
+            EditOperation::Replace {
+                position,
+                deleted_text,
+                inserted_text,
+                ..
+            } => vec![TextEdit::replace(*position, inserted_text, deleted_text)],
+            EditOperation::Batch { operations, .. } => operations
+                .iter()
+                .rev()
+                .flat_map(|op| op.undo_text_edits())
+                .collect(),
+        }
+    }
+
+    /// Combine this edit with the one typed right after it into a single
+    /// undo step, if `next` continues it at the same spot: more characters
+    /// typed after an insert (or after typing over a selection), or more
+    /// characters deleted with Backspace or Delete. With `split_words`,
+    /// typing a word after whitespace doesn't continue the edit.
+    pub fn merge(&self, next: &EditOperation, split_words: bool) -> Option<EditOperation> {
+        let starts_word = |before: &str, after: &str| {
+            split_words
+                && before.ends_with(char::is_whitespace)
+                && after.starts_with(|c: char| !c.is_whitespace())
+        };
+        match (self, next) {
+            (
+                EditOperation::Insert {
+                    position,
+                    text,
+                    cursor_before,
+                    ..
+                },
+                EditOperation::Insert {
+                    position: next_position,
+                    text: next_text,
+                    cursor_after,
+                    ..
+                },
+            ) if *next_position == position + text.chars().count()
+                && !starts_word(text, next_text) =>
+            {
+                Some(EditOperation::Insert {
+                    position: *position,
+                    text: format!("{text}{next_text}"),
+                    cursor_before: *cursor_before,
+                    cursor_after: *cursor_after,
+                })
+            }
+            (
+                EditOperation::Replace {
+                    position,
+                    deleted_text,
+                    inserted_text,
+                    cursor_before,
+                    ..
+                },
+                EditOperation::Insert {
+                    position: next_position,
+                    text: next_text,
+                    cursor_after,
+                    ..
+                },
+            ) if *next_position == position + inserted_text.chars().count()
+                && !starts_word(inserted_text, next_text) =>
+            {
+                Some(EditOperation::Replace {
+                    position: *position,
+                    deleted_text: deleted_text.clone(),
+                    inserted_text: format!("{inserted_text}{next_text}"),
+                    cursor_before: *cursor_before,
+                    cursor_after: *cursor_after,
+                })
+            }
+            (
+                EditOperation::Delete {
+                    position,
+                    text,
+                    cursor_before,
+                    ..
+                },
+                EditOperation::Delete {
+                    position: next_position,
+                    text: next_text,
+                    cursor_after,
+                    ..
+                },
+            ) => {
+                let text = if *next_position == *position {
+                    // Delete key: the text after the deleted text
+                    format!("{text}{next_text}")
+                } else if next_position + next_text.chars().count() == *position {
+                    // Backspace: the text before it
+                    format!("{next_text}{text}")
+                } else {
+                    return None;
+                };
+                Some(EditOperation::Delete {
+                    position: *position.min(next_position),
+                    text,
+                    cursor_before: *cursor_before,
+                    cursor_after: *cursor_after,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How far a large file has been read while it streams in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadProgress {
+    /// Bytes read so far
+    pub loaded: u64,
+    /// Size of the file in bytes
+    pub total: u64,
+    /// Whether the background read has been started
+    pub requested: bool,
+}
+
+impl LoadProgress {
+    /// Share of the file read so far, 0-100
+    pub fn percent(&self) -> u64 {
+        (self.loaded * 100)
+            .checked_div(self.total)
+            .unwrap_or(0)
+            .min(100)
+    }
+}
+
 /// Document state - the text buffer and associated file metadata
 #[derive(Debug, Clone)]
 pub struct Document {
@@ -51,10 +209,19 @@ pub struct Document {
     pub buffer: Rope,
     /// Path to the file on disk (None for new/unsaved files)
     pub file_path: Option<PathBuf>,
-    /// Display name for untitled documents (e.g., "Untitled", "Untitled-2")
+    /// Display name for untitled documents (e.g., "Untitled-1", "Untitled-2")
     pub untitled_name: Option<String>,
     /// Whether the buffer has unsaved changes
     pub is_modified: bool,
+    /// The file was deleted (or moved somewhere we could not follow) while
+    /// open; saving asks whether to recreate it
+    pub deleted_on_disk: bool,
+    /// `replay::content_hash` of the contents last loaded from or written to
+    /// the file, to tell our own saves apart from changes made by others
+    pub disk_hash: Option<String>,
+    /// A scratchpad from the config dir: saved automatically, never prompted
+    /// for when closed
+    pub is_scratchpad: bool,
     /// Undo stack
     pub undo_stack: Vec<EditOperation>,
     /// Redo stack
@@ -64,10 +231,16 @@ pub struct Document {
     /// document has never been saved (or the file doesn't exist yet), so
     /// undo/redo can never clear the dirty flag on its own.
     pub saved_revision: Option<usize>,
+    /// When the last edit of the undo step still being typed was made;
+    /// `None` once that step is closed and the next edit starts a new one
+    pub undo_group_at: Option<Instant>,
 
     // === Syntax Highlighting ===
     /// Detected language for syntax highlighting
     pub language: LanguageId,
+    /// The language was picked by hand, so it no longer follows the file
+    /// extension
+    pub language_overridden: bool,
     /// Current syntax highlights (updated asynchronously)
     pub syntax_highlights: Option<SyntaxHighlights>,
     /// Parsed outline data (functions, structs, etc.)
@@ -75,6 +248,31 @@ pub struct Document {
     /// Document revision counter (incremented on each edit)
     /// Used for staleness checking in async parsing
     pub revision: u64,
+    /// Edits since the last parse request, for incremental parsing
+    pub syntax_edits: EditLog,
+    /// Virtual text attached by other features, drawn but never edited
+    pub annotations: Annotations,
+    /// Line tints and gutter icons attached by other features
+    pub decorations: LineDecorations,
+
+    // === Git Gutter ===
+    /// HEAD version of the file, which `git_diff` is made against
+    pub git_head: HeadVersion,
+    /// Changes against HEAD; lags behind `revision` while the next diff is
+    /// computed
+    pub git_diff: Option<GitDiff>,
+    /// Revision of the diff being computed in the background
+    pub git_diff_pending: Option<u64>,
+    /// Last commit of each line, while blame annotations are shown
+    pub git_blame: Option<GitBlame>,
+    /// Revision of the blame being computed in the background
+    pub git_blame_pending: Option<u64>,
+    /// Opened in large file mode: no syntax highlighting, git gutter, blame
+    /// or undo grouping (see [`crate::util::LARGE_FILE_SIZE`])
+    pub large_file: bool,
+    /// Set while a large file is still streaming in; the buffer stays empty
+    /// and can't be edited or saved until it has loaded
+    pub loading: Option<LoadProgress>,
 }
 
 impl Document {
@@ -86,13 +284,28 @@ impl Document {
             file_path: None,
             untitled_name: None,
             is_modified: false,
+            deleted_on_disk: false,
+            disk_hash: None,
+            is_scratchpad: false,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             saved_revision: Some(0),
+            undo_group_at: None,
             language: LanguageId::PlainText,
+            language_overridden: false,
             syntax_highlights: None,
             outline: None,
             revision: 0,
+            syntax_edits: EditLog::default(),
+            annotations: Annotations::default(),
+            decorations: LineDecorations::default(),
+            git_head: HeadVersion::Unknown,
+            git_diff: None,
+            git_diff_pending: None,
+            git_blame: None,
+            git_blame_pending: None,
+            large_file: false,
+            loading: None,
         }
     }
 
@@ -109,7 +322,9 @@ impl Document {
         let content = std::fs::read_to_string(&path)?;
         let language = LanguageId::from_path(&path);
         Ok(Self {
+            disk_hash: Some(crate::replay::content_hash(&content)),
             buffer: Rope::from(content),
+            is_scratchpad: crate::config_paths::is_scratchpad_path(&path),
             file_path: Some(path),
             language,
             ..Self::new()
@@ -133,6 +348,13 @@ impl Document {
         }
     }
 
+    /// Read the HEAD version again for the next git gutter diff: the file was
+    /// saved, reloaded or moved, and may have been committed meanwhile
+    pub fn forget_git_head(&mut self) {
+        self.git_head = HeadVersion::Unknown;
+        self.git_blame = None;
+    }
+
     /// Get the display name for this document.
     /// Returns the filename if saved, the untitled name if set, or "Untitled" as fallback.
     pub fn display_name(&self) -> String {
@@ -242,6 +464,19 @@ impl Document {
         }
     }
 
+    /// Visual column of `column` on `line`: tabs expanded to fixed stops
+    /// and wide characters counted as two columns
+    pub fn visual_column(&self, line: usize, column: usize) -> usize {
+        self.get_line_cow(line)
+            .map_or(column, |text| char_col_to_visual_col(&text, column))
+    }
+
+    /// Column of `line` at `visual_col`, the inverse of [`Self::visual_column`]
+    pub fn column_at_visual(&self, line: usize, visual_col: usize) -> usize {
+        self.get_line_cow(line)
+            .map_or(0, |text| visual_col_to_char_col(&text, visual_col))
+    }
+
     /// Convert a (line, column) position to a buffer offset
     /// Uses ropey's O(log n) line_to_char method instead of O(n) iteration
     pub fn cursor_to_offset(&self, line: usize, column: usize) -> usize {
@@ -272,6 +507,30 @@ impl Document {
             .count()
     }
 
+    /// Open a file: read it right away, or for a large file return an empty
+    /// placeholder that is filled in once the file has streamed in
+    pub fn open(path: PathBuf) -> Result<Self, std::io::Error> {
+        if !crate::util::is_large_file(&path) {
+            return Self::from_file(path);
+        }
+        let total = std::fs::metadata(&path)?.len();
+        Ok(Self {
+            language: LanguageId::from_path(&path),
+            file_path: Some(path),
+            large_file: true,
+            loading: Some(LoadProgress {
+                total,
+                ..Default::default()
+            }),
+            ..Self::new()
+        })
+    }
+
+    /// Whether the document is a large file still streaming in
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
     /// Get the column after the last non-whitespace character on a line
     pub fn last_non_whitespace_column(&self, line_idx: usize) -> usize {
         if line_idx >= self.buffer.len_lines() {
@@ -285,15 +544,52 @@ impl Document {
 
     /// Push an edit operation onto the undo stack and clear redo stack
     pub fn push_edit(&mut self, op: EditOperation) {
+        self.bump_revision(op.text_edits());
         self.undo_stack.push(op);
         self.redo_stack.clear();
         self.is_modified = true;
-        self.revision = self.revision.wrapping_add(1);
         // Keep existing syntax highlights until new ones arrive.
         // This prevents "flash of unstyled text" during the debounce window.
         // The revision check in ParseCompleted ensures only matching highlights are applied.
     }
 
+    /// Move to the next revision after changing the text with `edits`
+    pub fn bump_revision(&mut self, edits: Vec<TextEdit>) {
+        let from = self.revision;
+        self.revision = self.revision.wrapping_add(1);
+        self.syntax_edits.record(from, self.revision, edits);
+    }
+
+    /// Fold the edit just pushed into the undo step before it, if that step
+    /// is still being typed and the edit continues it (see
+    /// [`EditOperation::merge`]). Never merges across the saved state, so
+    /// undoing back to it still clears the modified flag.
+    pub fn group_last_edit(&mut self, rules: &crate::config::UndoConfig, now: Instant) {
+        let open = self.undo_group_at.is_some_and(|at| {
+            now.saturating_duration_since(at) <= Duration::from_millis(rules.group_timeout_ms)
+        });
+        if !rules.group_typing || self.large_file {
+            return;
+        }
+        self.undo_group_at = Some(now);
+
+        let depth = self.undo_stack.len();
+        if !open || depth < 2 || self.saved_revision == Some(depth - 1) {
+            return;
+        }
+        if let Some(merged) =
+            self.undo_stack[depth - 2].merge(&self.undo_stack[depth - 1], rules.group_by_word)
+        {
+            self.undo_stack.truncate(depth - 2);
+            self.undo_stack.push(merged);
+        }
+    }
+
+    /// Close the undo step being typed, so the next edit starts a new one
+    pub fn end_undo_group(&mut self) {
+        self.undo_group_at = None;
+    }
+
     /// Get highlight tokens for a specific line
     pub fn get_line_highlights(&self, line: usize) -> &[crate::syntax::HighlightToken] {
         self.syntax_highlights
@@ -731,6 +1027,92 @@ mod tests {
         assert!(doc.is_modified);
     }
 
+    fn insert(position: usize, text: &str) -> EditOperation {
+        EditOperation::Insert {
+            position,
+            text: text.to_string(),
+            cursor_before: Cursor::at(0, position),
+            cursor_after: Cursor::at(0, position + text.chars().count()),
+        }
+    }
+
+    fn delete(position: usize, text: &str) -> EditOperation {
+        EditOperation::Delete {
+            position,
+            text: text.to_string(),
+            cursor_before: Cursor::default(),
+            cursor_after: Cursor::default(),
+        }
+    }
+
+    #[test]
+    fn test_merge_contiguous_inserts() {
+        let Some(EditOperation::Insert {
+            position,
+            text,
+            cursor_before,
+            cursor_after,
+        }) = insert(3, "ab").merge(&insert(5, "c"), true)
+        else {
+            panic!("expected a merged insert");
+        };
+        assert_eq!((position, text.as_str()), (3, "abc"));
+        assert_eq!(cursor_before, Cursor::at(0, 3));
+        assert_eq!(cursor_after, Cursor::at(0, 6));
+
+        assert!(insert(3, "ab").merge(&insert(4, "c"), true).is_none());
+    }
+
+    #[test]
+    fn test_merge_splits_words_after_whitespace() {
+        assert!(insert(0, "let ").merge(&insert(4, "x"), true).is_none());
+        assert!(insert(0, "let ").merge(&insert(4, "x"), false).is_some());
+        // Trailing whitespace stays with the word before it
+        assert!(insert(0, "let").merge(&insert(3, " "), true).is_some());
+    }
+
+    #[test]
+    fn test_merge_backspace_and_delete_runs() {
+        let Some(EditOperation::Delete { position, text, .. }) =
+            delete(4, "o").merge(&delete(3, "l"), true)
+        else {
+            panic!("expected a merged backspace");
+        };
+        assert_eq!((position, text.as_str()), (3, "lo"));
+
+        let Some(EditOperation::Delete { position, text, .. }) =
+            delete(1, "e").merge(&delete(1, "l"), true)
+        else {
+            panic!("expected a merged forward delete");
+        };
+        assert_eq!((position, text.as_str()), (1, "el"));
+
+        assert!(delete(4, "o").merge(&insert(4, "x"), true).is_none());
+    }
+
+    #[test]
+    fn test_group_last_edit_respects_timeout_and_saved_state() {
+        let rules = crate::config::UndoConfig::default();
+        let start = Instant::now();
+        let mut doc = Document::new();
+        doc.push_edit(insert(0, "a"));
+        doc.group_last_edit(&rules, start);
+        doc.push_edit(insert(1, "b"));
+        doc.group_last_edit(&rules, start + Duration::from_millis(10));
+        assert_eq!(doc.undo_stack.len(), 1);
+
+        // A long pause starts a new step
+        doc.push_edit(insert(2, "c"));
+        doc.group_last_edit(&rules, start + Duration::from_secs(5));
+        assert_eq!(doc.undo_stack.len(), 2);
+
+        // Never merge into the saved state
+        doc.saved_revision = Some(2);
+        doc.push_edit(insert(3, "d"));
+        doc.group_last_edit(&rules, start + Duration::from_secs(5));
+        assert_eq!(doc.undo_stack.len(), 3);
+    }
+
     #[test]
     fn test_push_edit_clears_redo_stack() {
         let mut doc = Document::with_text("hello");
@@ -1,7 +1,10 @@
 //! Editor state - cursor, viewport, selections, and view-specific state
 
+use std::time::Instant;
+
 use super::document::Document;
 use super::editor_area::{DocumentId, EditorId};
+use super::folding::{self, FoldState};
 use crate::csv::CsvState;
 use crate::util::{char_type, CharType};
 
@@ -134,13 +137,14 @@ impl Selection {
 }
 
 /// Cursor position in the document
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct Cursor {
     /// Line number (0-indexed)
     pub line: usize,
     /// Column number (0-indexed)
     pub column: usize,
-    /// Desired column for vertical movement (preserves position when moving through short lines)
+    /// Desired visual column for vertical movement (preserves position when
+    /// moving through short lines and lines with tabs or wide characters)
     pub desired_column: Option<usize>,
 }
 
@@ -180,6 +184,13 @@ impl Cursor {
             self.desired_column = Some(self.column);
         }
     }
+
+    /// Visual column vertical movement aims for: the desired column, or
+    /// where the cursor is drawn on its line
+    pub fn desired_visual_column(&self, document: &Document) -> usize {
+        self.desired_column
+            .unwrap_or_else(|| document.visual_column(self.line, self.column))
+    }
 }
 
 /// Viewport state - what portion of the document is visible
@@ -217,18 +228,22 @@ impl Default for Viewport {
 ///
 /// This is the current shared seam for both rendering and editor-state logic
 /// that needs to answer "which logical line/column does this visible row or
-/// pixel map to?". Soft wrap and folding can later replace or extend this
-/// mapping without forcing each caller to open-code `top_line + visual_row`.
+/// pixel map to?". Folded lines are skipped: rows and scroll arithmetic work
+/// on visual lines (see [`folding`](super::folding)), while `top_line` and
+/// every line passed in or returned are document lines. Soft wrap can later
+/// extend this mapping without forcing each caller to open-code
+/// `top_line + visual_row`.
 #[derive(Debug, Clone, Copy)]
-pub struct TextViewportMap {
+pub struct TextViewportMap<'a> {
     top_line: usize,
     left_column: usize,
     visible_lines: usize,
     visible_columns: usize,
     line_count: usize,
+    hidden: &'a [(usize, usize)],
 }
 
-impl TextViewportMap {
+impl<'a> TextViewportMap<'a> {
     pub fn new(viewport: &Viewport, line_count: usize) -> Self {
         Self {
             top_line: viewport.top_line,
@@ -236,9 +251,41 @@ impl TextViewportMap {
             visible_lines: viewport.visible_lines,
             visible_columns: viewport.visible_columns,
             line_count,
+            hidden: &[],
         }
     }
 
+    /// Skip the given (sorted, merged, inclusive) ranges of folded lines
+    pub fn with_hidden_lines(mut self, hidden: &'a [(usize, usize)]) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    #[inline]
+    fn visual(&self, doc_line: usize) -> usize {
+        folding::visual_line(self.hidden, doc_line)
+    }
+
+    #[inline]
+    fn doc(&self, visual_line: usize) -> usize {
+        folding::doc_line(self.hidden, visual_line).min(self.line_count)
+    }
+
+    /// Number of lines left once folded ones are hidden
+    #[inline]
+    fn visual_line_count(&self) -> usize {
+        if self.line_count == 0 {
+            0
+        } else {
+            self.visual(self.line_count - 1) + 1
+        }
+    }
+
+    #[inline]
+    fn max_visual_top(&self) -> usize {
+        self.visual_line_count().saturating_sub(self.visible_lines)
+    }
+
     #[inline]
     pub fn top_line(&self) -> usize {
         self.top_line
@@ -256,12 +303,13 @@ impl TextViewportMap {
 
     #[inline]
     pub fn last_line(&self) -> usize {
-        self.line_count.saturating_sub(1)
+        self.doc(self.visual_line_count().saturating_sub(1))
+            .min(self.line_count.saturating_sub(1))
     }
 
     #[inline]
     pub fn max_top_line(&self) -> usize {
-        self.line_count.saturating_sub(self.visible_lines)
+        self.doc(self.max_visual_top())
     }
 
     #[inline]
@@ -269,37 +317,43 @@ impl TextViewportMap {
         if !self.has_vertical_scroll() {
             0
         } else {
-            top_line.min(self.max_top_line())
+            self.doc(self.visual(top_line).min(self.max_visual_top()))
         }
     }
 
     #[inline]
     pub fn end_line(&self) -> usize {
-        self.top_line
-            .saturating_add(self.visible_lines)
-            .min(self.line_count)
+        self.doc(
+            self.visual(self.top_line)
+                .saturating_add(self.visible_lines),
+        )
     }
 
     #[inline]
     pub fn bottom_line(&self) -> usize {
-        self.top_line
-            .saturating_add(self.visible_lines.saturating_sub(1))
+        let bottom = self
+            .visual(self.top_line)
+            .saturating_add(self.visible_lines.saturating_sub(1));
+        self.doc(bottom.min(self.visual_line_count().saturating_sub(1)))
             .min(self.last_line())
     }
 
     #[inline]
     pub fn doc_line_for_visible_row(&self, visible_row: usize) -> Option<usize> {
-        let doc_line = self.top_line.saturating_add(visible_row);
-        (doc_line < self.line_count).then_some(doc_line)
+        let visual_line = self.visual(self.top_line).saturating_add(visible_row);
+        (visual_line < self.visual_line_count()).then(|| self.doc(visual_line))
     }
 
     #[inline]
     pub fn visible_row_for_doc_line(&self, doc_line: usize) -> Option<usize> {
-        if doc_line < self.top_line || doc_line >= self.line_count {
+        if doc_line < self.top_line
+            || doc_line >= self.line_count
+            || folding::is_hidden(self.hidden, doc_line)
+        {
             return None;
         }
 
-        let visible_row = doc_line - self.top_line;
+        let visible_row = self.visual(doc_line) - self.visual(self.top_line);
         (visible_row < self.visible_lines).then_some(visible_row)
     }
 
@@ -330,7 +384,7 @@ impl TextViewportMap {
 
     #[inline]
     fn has_vertical_scroll(&self) -> bool {
-        self.visible_lines > 0 && self.line_count > self.visible_lines
+        self.visible_lines > 0 && self.visual_line_count() > self.visible_lines
     }
 
     pub fn reveal_line_no_padding(&self, line: usize) -> usize {
@@ -338,15 +392,19 @@ impl TextViewportMap {
             return 0;
         }
 
-        if line < self.top_line {
-            line.min(self.max_top_line())
-        } else if line > self.bottom_line() {
+        let line = self.visual(line);
+        let top = self.visual(self.top_line);
+        let bottom = top.saturating_add(self.visible_lines.saturating_sub(1));
+        let new_top = if line < top {
+            line.min(self.max_visual_top())
+        } else if line > bottom {
             (line + 1)
                 .saturating_sub(self.visible_lines)
-                .min(self.max_top_line())
+                .min(self.max_visual_top())
         } else {
-            self.top_line
-        }
+            top
+        };
+        self.doc(new_top)
     }
 
     pub fn reveal_line_with_mode(
@@ -359,10 +417,11 @@ impl TextViewportMap {
             return 0;
         }
 
-        let safe_top = self.top_line + padding;
-        let safe_bottom = self
-            .top_line
-            .saturating_add(self.visible_lines.saturating_sub(padding).saturating_sub(1));
+        let line = self.visual(line);
+        let top = self.visual(self.top_line);
+        let safe_top = top + padding;
+        let safe_bottom =
+            top.saturating_add(self.visible_lines.saturating_sub(padding).saturating_sub(1));
         let off_above = line < safe_top;
         let off_below = line > safe_bottom;
 
@@ -370,7 +429,7 @@ impl TextViewportMap {
             return self.top_line;
         }
 
-        match mode {
+        let new_top = match mode {
             ScrollRevealMode::Minimal => {
                 if off_above {
                     line.saturating_sub(padding)
@@ -384,7 +443,8 @@ impl TextViewportMap {
             }
             ScrollRevealMode::Centered => line.saturating_sub(self.visible_lines / 2),
         }
-        .min(self.max_top_line())
+        .min(self.max_visual_top());
+        self.doc(new_top)
     }
 
     pub fn reveal_column(&self, column: usize, margin: usize) -> usize {
@@ -407,18 +467,29 @@ impl TextViewportMap {
     }
 
     pub fn scroll_vertical_by(&self, delta: isize) -> usize {
+        let top = self.visual(self.top_line);
         if delta > 0 {
-            self.clamp_top_line(self.top_line.saturating_add(delta as usize))
+            self.clamp_top_line(self.doc(top.saturating_add(delta as usize)))
         } else if delta < 0 {
-            self.top_line.saturating_sub(delta.unsigned_abs())
+            self.doc(top.saturating_sub(delta.unsigned_abs()))
         } else {
             self.top_line
         }
     }
 }
 
-/// State for an in-progress rectangle selection (middle mouse drag)
-/// Uses VISUAL columns (screen position) rather than character columns
+/// State for dragging the primary selection to a new position (left mouse).
+/// The selection itself is left untouched until the text is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextDragState {
+    /// Where the mouse was pressed inside the selection
+    pub origin: Position,
+    /// Where the text would be dropped; `None` until the drag threshold is crossed
+    pub drop: Option<Position>,
+}
+
+/// State for an in-progress rectangle selection (middle mouse drag, or
+/// Shift+Alt+arrows). Uses VISUAL columns (screen position) rather than character columns
 /// so rectangle selection works consistently across lines of different lengths.
 #[derive(Debug, Clone, Default)]
 pub struct RectangleSelectionState {
@@ -434,6 +505,9 @@ pub struct RectangleSelectionState {
     pub current_visual_col: usize,
     /// Preview cursor positions (computed during drag, shown before commit)
     pub preview_cursors: Vec<Position>,
+    /// Grown with Shift+Alt+arrows rather than the mouse; committed when the
+    /// modifiers are released or an edit comes in
+    pub from_keyboard: bool,
 }
 
 impl RectangleSelectionState {
@@ -469,6 +543,30 @@ pub struct OccurrenceState {
     pub last_search_offset: usize,
 }
 
+/// Other occurrences of the word the cursor rests in, found in the visible
+/// lines once the cursor has stayed in that word for a moment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WordHighlights {
+    /// What the occurrences are (or will be) for
+    pub key: Option<WordHighlightKey>,
+    /// When to look for the occurrences, while they are being waited for
+    pub due: Option<Instant>,
+    /// Occurrences as (line, start column, end column), in document order
+    pub ranges: Vec<(usize, usize, usize)>,
+}
+
+/// The word, text and visible lines [`WordHighlights`] were found for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordHighlightKey {
+    /// Start of the word under the cursor
+    pub word_start: Position,
+    /// Document revision searched
+    pub revision: u64,
+    /// First and last line searched
+    pub top_line: usize,
+    pub end_line: usize,
+}
+
 /// What kind of content this tab displays
 #[derive(Debug, Clone, Default)]
 pub enum TabContent {
@@ -477,6 +575,8 @@ pub enum TabContent {
     Text,
     /// Placeholder for unsupported binary files
     BinaryPlaceholder(BinaryPlaceholderState),
+    /// Start screen shown on launch when no file was opened
+    Welcome(WelcomeState),
 }
 
 /// State for a binary file placeholder tab
@@ -488,6 +588,29 @@ pub struct BinaryPlaceholderState {
     pub size_bytes: u64,
 }
 
+/// State for the welcome tab
+#[derive(Debug, Clone, Default)]
+pub struct WelcomeState {
+    /// Selectable entries (built once, when the tab is created)
+    pub items: Vec<crate::welcome::WelcomeItem>,
+    /// Index of the keyboard-selected entry
+    pub selected_index: usize,
+}
+
+impl WelcomeState {
+    pub fn new(items: Vec<crate::welcome::WelcomeItem>) -> Self {
+        Self {
+            items,
+            selected_index: 0,
+        }
+    }
+
+    /// The keyboard-selected entry
+    pub fn selected_item(&self) -> Option<&crate::welcome::WelcomeItem> {
+        self.items.get(self.selected_index)
+    }
+}
+
 /// Allows switching between normal text editing and specialized views
 /// like CSV grid mode. The underlying Document is shared.
 #[derive(Debug, Clone, Default)]
@@ -499,6 +622,8 @@ pub enum ViewMode {
     Csv(Box<CsvState>),
     /// Image viewer mode
     Image(Box<crate::image::ImageState>),
+    /// Side-by-side diff view mode
+    Diff(Box<crate::diff::DiffState>),
 }
 
 impl ViewMode {
@@ -543,6 +668,27 @@ impl ViewMode {
             _ => None,
         }
     }
+
+    /// Check if in diff mode
+    pub fn is_diff(&self) -> bool {
+        matches!(self, ViewMode::Diff(_))
+    }
+
+    /// Get diff state if in diff mode
+    pub fn as_diff(&self) -> Option<&crate::diff::DiffState> {
+        match self {
+            ViewMode::Diff(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    /// Get mutable diff state if in diff mode
+    pub fn as_diff_mut(&mut self) -> Option<&mut crate::diff::DiffState> {
+        match self {
+            ViewMode::Diff(state) => Some(state),
+            _ => None,
+        }
+    }
 }
 
 /// Editor state - view-specific state for editing a document
@@ -571,6 +717,8 @@ pub struct EditorState {
     pub scroll_padding: usize,
     /// Rectangle selection state (for middle mouse drag)
     pub rectangle_selection: RectangleSelectionState,
+    /// Selection drag-and-drop state (left mouse drag starting inside the selection)
+    pub text_drag: Option<TextDragState>,
     /// Occurrence selection state (for Cmd+J "select next occurrence")
     pub occurrence_state: Option<OccurrenceState>,
     /// Selection history stack for expand/shrink selection (Option+Up/Down)
@@ -582,6 +730,10 @@ pub struct EditorState {
     pub tab_content: TabContent,
     /// Matching bracket pair positions (if cursor is adjacent to a bracket)
     pub matched_brackets: Option<(Position, Position)>,
+    /// Other occurrences of the word under the cursor
+    pub word_highlights: WordHighlights,
+    /// Folded line ranges in this view
+    pub folds: FoldState,
 }
 
 impl EditorState {
@@ -598,11 +750,14 @@ impl EditorState {
             viewport: Viewport::default(),
             scroll_padding: 1,
             rectangle_selection: RectangleSelectionState::default(),
+            text_drag: None,
             occurrence_state: None,
             selection_history: Vec::new(),
             view_mode: ViewMode::default(),
             tab_content: TabContent::default(),
             matched_brackets: None,
+            word_highlights: WordHighlights::default(),
+            folds: FoldState::default(),
         }
     }
 
@@ -887,6 +1042,17 @@ impl EditorState {
     /// - All selections are canonical (forward: anchor <= head)
     /// - `active_cursor_index` points to the merged selection containing the original active cursor
     pub fn merge_overlapping_selections(&mut self) {
+        self.merge_selections(true);
+    }
+
+    /// Like [`merge_overlapping_selections`](Self::merge_overlapping_selections),
+    /// but selections that only touch (one ends where the next starts) stay
+    /// separate, so an edit can still treat them one by one
+    pub fn merge_intersecting_selections(&mut self) {
+        self.merge_selections(false);
+    }
+
+    fn merge_selections(&mut self, merge_touching: bool) {
         if self.selections.len() <= 1 {
             return;
         }
@@ -907,8 +1073,8 @@ impl EditorState {
         let mut merged: Vec<(Position, Position, Vec<usize>)> = Vec::new();
         for (start, end, orig_idx) in indexed {
             if let Some((_, last_end, orig_indices)) = merged.last_mut() {
-                // Overlapping or touching: next.start <= current.end
-                if start <= *last_end {
+                // Overlapping (or touching, when those merge too)
+                if start < *last_end || (merge_touching && start == *last_end) {
                     // Extend the current merged range if this one goes further
                     if end > *last_end {
                         *last_end = end;
@@ -946,8 +1112,9 @@ impl EditorState {
     }
 
     /// Build the current no-wrap text viewport map for this editor/document pair.
-    pub fn viewport_map(&self, document: &Document) -> TextViewportMap {
+    pub fn viewport_map(&self, document: &Document) -> TextViewportMap<'_> {
         TextViewportMap::new(&self.viewport, document.line_count())
+            .with_hidden_lines(self.folds.hidden_ranges())
     }
 
     /// Clamp the viewport's top line against the current document.
@@ -966,11 +1133,11 @@ impl EditorState {
         changed
     }
 
-    /// Return the longest logical line visible in the current viewport window.
+    /// Return the visual width of the widest line in the current viewport window.
     pub fn max_visible_line_length(&self, document: &Document) -> usize {
         let viewport = self.viewport_map(document);
         (viewport.top_line()..viewport.end_line())
-            .map(|line| document.line_length(line))
+            .map(|line| document.visual_column(line, document.line_length(line)))
             .max()
             .unwrap_or(0)
     }
@@ -1025,13 +1192,17 @@ impl EditorState {
     /// Only scrolls if the cursor is completely outside the viewport bounds.
     pub fn ensure_cursor_visible_no_padding(&mut self, document: &Document) {
         let cursor = &self.cursors[self.active_cursor_index];
+        self.folds.reveal(cursor.line);
         let viewport = self.viewport_map(document);
 
-        self.viewport.top_line = viewport.reveal_line_no_padding(cursor.line);
+        let top_line = viewport.reveal_line_no_padding(cursor.line);
 
         // Horizontal scrolling (same as normal - always check)
         const HORIZONTAL_MARGIN: usize = 4;
-        self.viewport.left_column = viewport.reveal_column(cursor.column, HORIZONTAL_MARGIN);
+        let visual_col = document.visual_column(cursor.line, cursor.column);
+        let left_column = viewport.reveal_column(visual_col, HORIZONTAL_MARGIN);
+        self.viewport.top_line = top_line;
+        self.viewport.left_column = left_column;
     }
 
     /// Ensure the active cursor is visible using the specified reveal strategy
@@ -1043,13 +1214,18 @@ impl EditorState {
     pub fn ensure_cursor_visible_with_mode(&mut self, document: &Document, mode: ScrollRevealMode) {
         let cursor = &self.cursors[self.active_cursor_index];
         let padding = self.scroll_padding;
+        // A cursor inside a fold (after a jump or search) opens it
+        self.folds.reveal(cursor.line);
         let viewport = self.viewport_map(document);
 
-        self.viewport.top_line = viewport.reveal_line_with_mode(cursor.line, padding, mode);
+        let top_line = viewport.reveal_line_with_mode(cursor.line, padding, mode);
 
         // Horizontal scrolling (always check, independent of vertical)
         const HORIZONTAL_MARGIN: usize = 4;
-        self.viewport.left_column = viewport.reveal_column(cursor.column, HORIZONTAL_MARGIN);
+        let visual_col = document.visual_column(cursor.line, cursor.column);
+        let left_column = viewport.reveal_column(visual_col, HORIZONTAL_MARGIN);
+        self.viewport.top_line = top_line;
+        self.viewport.left_column = left_column;
     }
 
     /// Set primary cursor position from buffer offset (clears selection)
@@ -1183,6 +1359,29 @@ impl EditorState {
         );
     }
 
+    /// Assert invariants, plus that a viewport-only operation (scroll, resize,
+    /// theme change) left cursors, selections and the active cursor as they
+    /// were in `before`
+    #[cfg(debug_assertions)]
+    pub fn assert_view_only_invariants_with_context(&self, before: &EditorState, context: &str) {
+        self.assert_invariants_with_context(context);
+        debug_assert_eq!(
+            self.cursors, before.cursors,
+            "[{}] Viewport-only operation changed cursors (desired_column included)",
+            context
+        );
+        debug_assert_eq!(
+            self.selections, before.selections,
+            "[{}] Viewport-only operation changed selections",
+            context
+        );
+        debug_assert_eq!(
+            self.active_cursor_index, before.active_cursor_index,
+            "[{}] Viewport-only operation changed the active cursor",
+            context
+        );
+    }
+
     /// No-op in release builds
     #[cfg(not(debug_assertions))]
     #[inline]
@@ -1193,6 +1392,11 @@ impl EditorState {
     #[inline]
     pub fn assert_invariants_with_context(&self, _context: &str) {}
 
+    /// No-op in release builds
+    #[cfg(not(debug_assertions))]
+    #[inline]
+    pub fn assert_view_only_invariants_with_context(&self, _before: &EditorState, _context: &str) {}
+
     // =========================================================================
     // Per-cursor movement primitives (Phase 0)
     // =========================================================================
@@ -1203,8 +1407,8 @@ impl EditorState {
         if cursor.column > 0 {
             cursor.column -= 1;
             cursor.desired_column = None;
-        } else if cursor.line > 0 {
-            cursor.line -= 1;
+        } else if let Some(line) = self.folds.visible_line_above(cursor.line) {
+            cursor.line = line;
             cursor.column = doc.line_length(cursor.line);
             cursor.desired_column = None;
         }
@@ -1217,8 +1421,8 @@ impl EditorState {
         if cursor.column < line_len {
             cursor.column += 1;
             cursor.desired_column = None;
-        } else if cursor.line < doc.line_count().saturating_sub(1) {
-            cursor.line += 1;
+        } else if let Some(line) = self.folds.visible_line_below(cursor.line, doc.line_count()) {
+            cursor.line = line;
             cursor.column = 0;
             cursor.desired_column = None;
         }
@@ -1227,11 +1431,10 @@ impl EditorState {
     /// Move a single cursor up by one line
     pub fn move_cursor_up_at(&mut self, doc: &Document, idx: usize) {
         let cursor = &mut self.cursors[idx];
-        if cursor.line > 0 {
-            cursor.line -= 1;
-            let desired = cursor.desired_column.unwrap_or(cursor.column);
-            let line_len = doc.line_length(cursor.line);
-            cursor.column = desired.min(line_len);
+        if let Some(line) = self.folds.visible_line_above(cursor.line) {
+            let desired = cursor.desired_visual_column(doc);
+            cursor.line = line;
+            cursor.column = doc.column_at_visual(line, desired);
             cursor.desired_column = Some(desired);
         }
     }
@@ -1239,11 +1442,10 @@ impl EditorState {
     /// Move a single cursor down by one line
     pub fn move_cursor_down_at(&mut self, doc: &Document, idx: usize) {
         let cursor = &mut self.cursors[idx];
-        if cursor.line < doc.line_count().saturating_sub(1) {
-            cursor.line += 1;
-            let desired = cursor.desired_column.unwrap_or(cursor.column);
-            let line_len = doc.line_length(cursor.line);
-            cursor.column = desired.min(line_len);
+        if let Some(line) = self.folds.visible_line_below(cursor.line, doc.line_count()) {
+            let desired = cursor.desired_visual_column(doc);
+            cursor.line = line;
+            cursor.column = doc.column_at_visual(line, desired);
             cursor.desired_column = Some(desired);
         }
     }
@@ -1292,21 +1494,22 @@ impl EditorState {
     /// Move a single cursor up by `jump` lines (for page up)
     pub fn page_up_at(&mut self, doc: &Document, jump: usize, idx: usize) {
         let cursor = &mut self.cursors[idx];
-        cursor.line = cursor.line.saturating_sub(jump);
-        let desired = cursor.desired_column.unwrap_or(cursor.column);
-        let line_len = doc.line_length(cursor.line);
-        cursor.column = desired.min(line_len);
+        let desired = cursor.desired_visual_column(doc);
+        cursor.line =
+            self.folds
+                .offset_visible_line(cursor.line, -(jump as isize), doc.line_count());
+        cursor.column = doc.column_at_visual(cursor.line, desired);
         cursor.desired_column = Some(desired);
     }
 
     /// Move a single cursor down by `jump` lines (for page down)
     pub fn page_down_at(&mut self, doc: &Document, jump: usize, idx: usize) {
         let cursor = &mut self.cursors[idx];
-        let max_line = doc.line_count().saturating_sub(1);
-        cursor.line = (cursor.line + jump).min(max_line);
-        let desired = cursor.desired_column.unwrap_or(cursor.column);
-        let line_len = doc.line_length(cursor.line);
-        cursor.column = desired.min(line_len);
+        let desired = cursor.desired_visual_column(doc);
+        cursor.line = self
+            .folds
+            .offset_visible_line(cursor.line, jump as isize, doc.line_count());
+        cursor.column = doc.column_at_visual(cursor.line, desired);
         cursor.desired_column = Some(desired);
     }
 
@@ -1702,4 +1905,18 @@ mod tests {
         assert!(editor.scroll_horizontal_visible_window_by(&document, -2));
         assert_eq!(editor.viewport.left_column, 0);
     }
+
+    #[test]
+    fn vertical_movement_keeps_the_visual_column_across_wide_characters() {
+        let mut editor = EditorState::with_viewport(10, 40);
+        let document = Document::with_text("abcdef\n中文字\nabcdef\n");
+        editor.cursors[0].column = 4;
+
+        editor.move_cursor_down_at(&document, 0);
+        assert_eq!(editor.cursors[0].column, 2);
+        assert_eq!(editor.cursors[0].desired_column, Some(4));
+
+        editor.move_cursor_down_at(&document, 0);
+        assert_eq!(editor.cursors[0].column, 4);
+    }
 }
@@ -5,9 +5,16 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use super::document::Document;
-use super::editor::{EditorState, ScrollRevealMode};
+use super::editor::{Cursor, EditorState, ScrollRevealMode, Selection, ViewMode};
 use crate::markdown::PreviewPane;
+use crate::session::{EditorSession, GroupSession, LayoutSession, TabSession};
+use crate::theme::Color;
+use crate::util::{
+    is_likely_binary, is_supported_image, normalize_path, validate_file_for_opening,
+};
 
 // ============================================================================
 // Identifiers
@@ -61,17 +68,66 @@ impl Rect {
 // Tabs
 // ============================================================================
 
+/// Color label on a tab, shown as an accent strip in the tab bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl TabColor {
+    /// Every label, in the order tabs are grouped by
+    pub const ALL: [TabColor; 6] = [
+        TabColor::Red,
+        TabColor::Orange,
+        TabColor::Yellow,
+        TabColor::Green,
+        TabColor::Blue,
+        TabColor::Purple,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TabColor::Red => "Red",
+            TabColor::Orange => "Orange",
+            TabColor::Yellow => "Yellow",
+            TabColor::Green => "Green",
+            TabColor::Blue => "Blue",
+            TabColor::Purple => "Purple",
+        }
+    }
+
+    /// Accent color; saturated enough to read on light and dark themes
+    pub fn color(self) -> Color {
+        match self {
+            TabColor::Red => Color::rgb(0xE5, 0x48, 0x4D),
+            TabColor::Orange => Color::rgb(0xF0, 0x8C, 0x2E),
+            TabColor::Yellow => Color::rgb(0xE5, 0xC0, 0x2A),
+            TabColor::Green => Color::rgb(0x3F, 0xB9, 0x50),
+            TabColor::Blue => Color::rgb(0x3B, 0x82, 0xF6),
+            TabColor::Purple => Color::rgb(0xA3, 0x71, 0xF7),
+        }
+    }
+}
+
 /// A tab represents an open editor in a group
 #[derive(Debug, Clone)]
 pub struct Tab {
     pub id: TabId,
     pub editor_id: EditorId,
-    // TODO: is_pinned is currently unused. Implement pinned tab functionality
-    // to prevent accidental closure of important tabs.
+    /// Kept before the unpinned tabs, drawn compact, and left open by
+    /// Close Others / Close All
     pub is_pinned: bool,
-    // TODO: is_preview is currently unused. Implement preview tab behavior
-    // where opening a new file replaces the preview tab instead of creating a new one.
+    /// Opened by a single click in the file tree: replaced by the next file
+    /// opened that way until it is edited or kept open
     pub is_preview: bool,
+    /// Color label, persisted with the session by file path
+    pub color: Option<TabColor>,
 }
 
 // ============================================================================
@@ -90,6 +146,19 @@ pub struct EditorGroup {
     pub attached_preview: Option<PreviewId>,
     /// Horizontal tab bar scroll offset in pixels (0 = leftmost tab visible)
     pub tab_scroll: usize,
+    /// Set while this group's scrolling is locked to other groups showing
+    /// the same document
+    pub scroll_lock: Option<ScrollLock>,
+}
+
+/// Scroll lock state of one group. Locked groups showing the same document
+/// keep the distance between their top lines that they had when locked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollLock {
+    /// Top line when the lock was made
+    pub anchor_line: usize,
+    /// Top line after the last sync, to tell which group scrolled
+    pub synced_line: usize,
 }
 
 impl EditorGroup {
@@ -102,6 +171,11 @@ impl EditorGroup {
     pub fn active_editor_id(&self) -> Option<EditorId> {
         self.active_tab().map(|t| t.editor_id)
     }
+
+    /// Number of pinned tabs, which come first
+    pub fn pinned_count(&self) -> usize {
+        self.tabs.iter().filter(|tab| tab.is_pinned).count()
+    }
 }
 
 // ============================================================================
@@ -109,7 +183,8 @@ impl EditorGroup {
 // ============================================================================
 
 /// Direction for splitting editor groups
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SplitDirection {
     /// Children arranged left-to-right
     Horizontal,
@@ -117,6 +192,65 @@ pub enum SplitDirection {
     Vertical,
 }
 
+/// Edge of a group that a dragged tab can be dropped on, to move it into a
+/// new group split off that side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl DropEdge {
+    /// Share of a group's width or height, from each side, that drops on
+    /// that edge
+    pub const ZONE_FRACTION: f32 = 0.25;
+
+    /// The edge zone of `rect` containing the point, the nearest edge when
+    /// zones overlap in a corner. `None` in the middle or outside.
+    pub fn at(rect: Rect, x: f32, y: f32) -> Option<Self> {
+        if !rect.contains(x, y) {
+            return None;
+        }
+        let (fx, fy) = ((x - rect.x) / rect.width, (y - rect.y) / rect.height);
+        [
+            (DropEdge::Left, fx),
+            (DropEdge::Right, 1.0 - fx),
+            (DropEdge::Top, fy),
+            (DropEdge::Bottom, 1.0 - fy),
+        ]
+        .into_iter()
+        .filter(|(_, distance)| *distance < Self::ZONE_FRACTION)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(edge, _)| edge)
+    }
+
+    /// Direction of the split a drop on this edge makes
+    pub fn direction(self) -> SplitDirection {
+        match self {
+            DropEdge::Left | DropEdge::Right => SplitDirection::Horizontal,
+            DropEdge::Top | DropEdge::Bottom => SplitDirection::Vertical,
+        }
+    }
+
+    /// Whether the new group goes before (left of or above) the group
+    pub fn is_before(self) -> bool {
+        matches!(self, DropEdge::Left | DropEdge::Top)
+    }
+
+    /// The part of `rect` the new group would take
+    pub fn preview_rect(self, rect: Rect) -> Rect {
+        let (half_width, half_height) = (rect.width / 2.0, rect.height / 2.0);
+        match self {
+            DropEdge::Left => Rect::new(rect.x, rect.y, half_width, rect.height),
+            DropEdge::Right => Rect::new(rect.x + half_width, rect.y, half_width, rect.height),
+            DropEdge::Top => Rect::new(rect.x, rect.y, rect.width, half_height),
+            DropEdge::Bottom => Rect::new(rect.x, rect.y + half_height, rect.width, half_height),
+        }
+    }
+}
+
 /// A container that splits space between children
 #[derive(Debug, Clone)]
 pub struct SplitContainer {
@@ -210,6 +344,7 @@ impl EditorArea {
             editor_id,
             is_pinned: false,
             is_preview: false,
+            color: None,
         };
 
         groups.insert(
@@ -221,6 +356,7 @@ impl EditorArea {
                 rect: Rect::default(),
                 attached_preview: None,
                 tab_scroll: 0,
+                scroll_lock: None,
             },
         );
 
@@ -361,15 +497,11 @@ impl EditorArea {
         id
     }
 
-    /// Generate the next untitled document name (e.g., "Untitled", "Untitled-2", etc.)
+    /// Generate the next untitled document name ("Untitled-1", "Untitled-2", etc.)
     pub fn next_untitled_name(&mut self) -> String {
         let n = self.next_untitled_number;
         self.next_untitled_number += 1;
-        if n == 1 {
-            "Untitled".to_string()
-        } else {
-            format!("Untitled-{}", n)
-        }
+        format!("Untitled-{}", n)
     }
 
     /// Get all editor IDs that are viewing a specific document
@@ -381,31 +513,49 @@ impl EditorArea {
             .collect()
     }
 
+    /// Find the document for a file path, comparing normalized paths so
+    /// relative, `..` and symlinked aliases of an open file match it
+    pub fn find_document_by_path(&self, path: &std::path::Path) -> Option<DocumentId> {
+        let target = normalize_path(path);
+        self.documents
+            .iter()
+            .find(|(_, doc)| {
+                doc.file_path
+                    .as_deref()
+                    .is_some_and(|doc_path| normalize_path(doc_path) == target)
+            })
+            .map(|(doc_id, _)| *doc_id)
+    }
+
+    /// Find a tab showing a document, preferring one in `preferred_group`
+    pub fn find_tab_for_document(
+        &self,
+        doc_id: DocumentId,
+        preferred_group: GroupId,
+    ) -> Option<(GroupId, usize)> {
+        let tab_in_group = |group: &EditorGroup| {
+            group.tabs.iter().position(|tab| {
+                self.editors
+                    .get(&tab.editor_id)
+                    .is_some_and(|editor| editor.document_id == Some(doc_id))
+            })
+        };
+
+        if let Some(index) = self.groups.get(&preferred_group).and_then(tab_in_group) {
+            return Some((preferred_group, index));
+        }
+        self.groups
+            .iter()
+            .find_map(|(group_id, group)| tab_in_group(group).map(|index| (*group_id, index)))
+    }
+
     /// Find if a file is already open by its path
-    /// Returns the document ID and group/tab info if found
+    /// Returns the document ID and group/tab info if found, preferring a tab
+    /// in the focused group
     pub fn find_open_file(&self, path: &std::path::Path) -> Option<(DocumentId, GroupId, usize)> {
-        // Canonicalize the input path for comparison
-        let canonical_path = path.canonicalize().ok()?;
-
-        for (doc_id, doc) in &self.documents {
-            if let Some(ref doc_path) = doc.file_path {
-                if let Ok(doc_canonical) = doc_path.canonicalize() {
-                    if doc_canonical == canonical_path {
-                        // Find which group/tab has this document
-                        for (group_id, group) in &self.groups {
-                            for (tab_idx, tab) in group.tabs.iter().enumerate() {
-                                if let Some(editor) = self.editors.get(&tab.editor_id) {
-                                    if editor.document_id == Some(*doc_id) {
-                                        return Some((*doc_id, *group_id, tab_idx));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        None
+        let doc_id = self.find_document_by_path(path)?;
+        let (group_id, tab_idx) = self.find_tab_for_document(doc_id, self.focused_group_id)?;
+        Some((doc_id, group_id, tab_idx))
     }
 
     /// Check if a file is already open (quick check without returning details)
@@ -713,7 +863,7 @@ impl EditorArea {
                 let width = group.rect.width as u32;
                 let height = group.rect.height as u32;
                 let content_height =
-                    (height as usize).saturating_sub(metrics.tab_bar_height) as u32;
+                    (height as usize).saturating_sub(metrics.header_height()) as u32;
                 (editor_ids, width, height, content_height)
             })
             .collect();
@@ -724,10 +874,10 @@ impl EditorArea {
                 continue;
             }
 
-            // Subtract tab bar height because group rect includes the tab bar area,
+            // Subtract the header height because group rect includes the tab bar area,
             // but visible_lines should only count the text content area.
             let visible_lines = (height as usize)
-                .saturating_sub(metrics.tab_bar_height)
+                .saturating_sub(metrics.header_height())
                 .checked_div(line_height)
                 .unwrap_or(0);
 
@@ -1098,6 +1248,274 @@ pub struct SplitterBar {
     pub index: usize,
 }
 
+// ============================================================================
+// Session Persistence
+// ============================================================================
+
+impl EditorArea {
+    /// Tabs and splits showing files, for saving with the session. Untitled
+    /// documents, files deleted on disk and preview panes are left out.
+    pub fn session(&self) -> EditorSession {
+        let mut groups = Vec::new();
+        let layout = self
+            .layout_session(&self.layout, &mut groups)
+            .unwrap_or_else(|| {
+                LayoutSession::Group(GroupSession {
+                    tabs: Vec::new(),
+                    active_tab: 0,
+                })
+            });
+        EditorSession {
+            version: EditorSession::CURRENT_VERSION,
+            layout,
+            focused_group: groups
+                .iter()
+                .position(|id| *id == self.focused_group_id)
+                .unwrap_or(0),
+        }
+    }
+
+    /// Saved form of `node`, `None` when it shows no files. `groups` collects
+    /// the saved groups depth-first.
+    fn layout_session(
+        &self,
+        node: &LayoutNode,
+        groups: &mut Vec<GroupId>,
+    ) -> Option<LayoutSession> {
+        match node {
+            LayoutNode::Group(group_id) => {
+                let group = self.groups.get(group_id)?;
+                let mut tabs = Vec::new();
+                let mut active_tab = 0;
+                for (index, tab) in group.tabs.iter().enumerate() {
+                    let Some(tab) = self.tab_session(tab) else {
+                        continue;
+                    };
+                    // The active tab, or the closest saved one before it
+                    if index <= group.active_tab_index {
+                        active_tab = tabs.len();
+                    }
+                    tabs.push(tab);
+                }
+                if tabs.is_empty() {
+                    return None;
+                }
+                groups.push(*group_id);
+                Some(LayoutSession::Group(GroupSession { tabs, active_tab }))
+            }
+            LayoutNode::Split(split) => {
+                let mut children = Vec::new();
+                let mut ratios = Vec::new();
+                for (child, ratio) in split.children.iter().zip(&split.ratios) {
+                    if let Some(child) = self.layout_session(child, groups) {
+                        children.push(child);
+                        ratios.push(*ratio);
+                    }
+                }
+                if children.len() < 2 {
+                    return children.pop();
+                }
+                let total: f32 = ratios.iter().sum();
+                Some(LayoutSession::Split {
+                    direction: split.direction,
+                    children,
+                    ratios: ratios.into_iter().map(|ratio| ratio / total).collect(),
+                })
+            }
+            LayoutNode::Preview(_) | LayoutNode::Empty => None,
+        }
+    }
+
+    fn tab_session(&self, tab: &Tab) -> Option<TabSession> {
+        let editor = self.editors.get(&tab.editor_id)?;
+        let document = self.documents.get(&editor.document_id?)?;
+        if document.deleted_on_disk {
+            return None;
+        }
+        Some(TabSession {
+            path: document.file_path.clone()?,
+            cursor: (editor.cursors[0].line, editor.cursors[0].column),
+            top_line: editor.viewport.top_line,
+            left_column: editor.viewport.left_column,
+            pinned: tab.is_pinned,
+        })
+    }
+
+    /// Rebuild the tabs and splits of a saved session, with editors made by
+    /// `new_editor`. Files that can no longer be opened are skipped; `None`
+    /// when none of them could be.
+    pub fn from_session(
+        session: &EditorSession,
+        new_editor: impl Fn() -> EditorState,
+    ) -> Option<Self> {
+        let mut area = Self {
+            documents: HashMap::new(),
+            editors: HashMap::new(),
+            groups: HashMap::new(),
+            previews: HashMap::new(),
+            layout: LayoutNode::Empty,
+            focused_group_id: GroupId(1),
+            next_document_id: 1,
+            next_editor_id: 1,
+            next_group_id: 1,
+            next_tab_id: 1,
+            next_preview_id: 1,
+            next_untitled_number: 1,
+            last_layout_rect: None,
+        };
+        let mut groups = Vec::new();
+        area.layout = area.restore_layout(&session.layout, &new_editor, &mut groups)?;
+        area.focused_group_id = groups
+            .get(session.focused_group)
+            .or(groups.first())
+            .copied()?;
+        Some(area)
+    }
+
+    fn restore_layout(
+        &mut self,
+        node: &LayoutSession,
+        new_editor: &impl Fn() -> EditorState,
+        groups: &mut Vec<GroupId>,
+    ) -> Option<LayoutNode> {
+        match node {
+            LayoutSession::Group(saved) => {
+                let mut tabs = Vec::new();
+                let mut active_tab_index = 0;
+                for (index, saved_tab) in saved.tabs.iter().enumerate() {
+                    let Some(editor_id) = self.restore_editor(saved_tab, new_editor) else {
+                        continue;
+                    };
+                    if index <= saved.active_tab {
+                        active_tab_index = tabs.len();
+                    }
+                    tabs.push(Tab {
+                        id: self.next_tab_id(),
+                        editor_id,
+                        is_pinned: saved_tab.pinned,
+                        is_preview: false,
+                        color: None,
+                    });
+                }
+                if tabs.is_empty() {
+                    return None;
+                }
+                let group_id = self.next_group_id();
+                self.groups.insert(
+                    group_id,
+                    EditorGroup {
+                        id: group_id,
+                        tabs,
+                        active_tab_index,
+                        rect: Rect::default(),
+                        attached_preview: None,
+                        tab_scroll: 0,
+                        scroll_lock: None,
+                    },
+                );
+                groups.push(group_id);
+                Some(LayoutNode::Group(group_id))
+            }
+            LayoutSession::Split {
+                direction,
+                children: saved_children,
+                ratios: saved_ratios,
+            } => {
+                let mut children = Vec::new();
+                let mut ratios = Vec::new();
+                for (child, ratio) in saved_children.iter().zip(saved_ratios) {
+                    if let Some(child) = self.restore_layout(child, new_editor, groups) {
+                        children.push(child);
+                        ratios.push(ratio.max(0.05));
+                    }
+                }
+                if children.len() < 2 {
+                    return children.pop();
+                }
+                let total: f32 = ratios.iter().sum();
+                Some(LayoutNode::Split(SplitContainer {
+                    direction: *direction,
+                    min_sizes: vec![100.0; children.len()],
+                    children,
+                    ratios: ratios.into_iter().map(|ratio| ratio / total).collect(),
+                }))
+            }
+        }
+    }
+
+    /// Open the file of a saved tab (sharing the document with other tabs
+    /// on it) in a new editor placed where the tab's view was
+    fn restore_editor(
+        &mut self,
+        saved: &TabSession,
+        new_editor: &impl Fn() -> EditorState,
+    ) -> Option<EditorId> {
+        if is_supported_image(&saved.path) {
+            return self.restore_image_editor(saved, new_editor);
+        }
+
+        let document_id = match self.find_document_by_path(&saved.path) {
+            Some(id) => id,
+            None => {
+                if validate_file_for_opening(&saved.path).is_err() || is_likely_binary(&saved.path)
+                {
+                    return None;
+                }
+                let mut document = Document::open(saved.path.clone())
+                    .map_err(|e| tracing::warn!("Failed to open {}: {}", saved.path.display(), e))
+                    .ok()?;
+                let id = self.next_document_id();
+                document.id = Some(id);
+                self.documents.insert(id, document);
+                id
+            }
+        };
+
+        let document = &self.documents[&document_id];
+        let last_line = document.line_count().saturating_sub(1);
+        let line = saved.cursor.0.min(last_line);
+        let column = saved.cursor.1.min(document.line_length(line));
+
+        let editor_id = self.next_editor_id();
+        let mut editor = new_editor();
+        editor.id = Some(editor_id);
+        editor.document_id = Some(document_id);
+        editor.cursors[0] = Cursor::at(line, column);
+        editor.selections[0] = Selection::new(editor.cursors[0].to_position());
+        editor.viewport.top_line = saved.top_line.min(last_line);
+        editor.viewport.left_column = saved.left_column;
+        self.editors.insert(editor_id, editor);
+        Some(editor_id)
+    }
+
+    /// Open the image of a saved tab in the image viewer. It's fitted to its
+    /// group on the next layout pass.
+    fn restore_image_editor(
+        &mut self,
+        saved: &TabSession,
+        new_editor: &impl Fn() -> EditorState,
+    ) -> Option<EditorId> {
+        validate_file_for_opening(&saved.path).ok()?;
+        let image = crate::image::load_image(&saved.path, 800, 600)?;
+        let document_id = self.find_document_by_path(&saved.path).unwrap_or_else(|| {
+            let id = self.next_document_id();
+            let mut document = Document::new();
+            document.id = Some(id);
+            document.file_path = Some(saved.path.clone());
+            self.documents.insert(id, document);
+            id
+        });
+
+        let editor_id = self.next_editor_id();
+        let mut editor = new_editor();
+        editor.id = Some(editor_id);
+        editor.document_id = Some(document_id);
+        editor.view_mode = ViewMode::Image(Box::new(image));
+        self.editors.insert(editor_id, editor);
+        Some(editor_id)
+    }
+}
+
 // ============================================================================
 // Debug Invariant Validation
 // ============================================================================
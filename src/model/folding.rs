@@ -0,0 +1,402 @@
+//! Folded line ranges for an editor view
+//!
+//! A fold keeps its first line (the header) visible and hides the lines after
+//! it up to and including `end`. Folds may nest; the hidden lines are the
+//! union of all fold bodies. Rendering, hit-testing and scrolling go through
+//! [`TextViewportMap`](super::TextViewportMap), which maps between document
+//! lines and visual lines (document lines with the hidden ones left out).
+//!
+//! Foldable regions of code come from indentation: a line followed by deeper
+//! indented lines heads a region that ends at the last of them.
+
+use ropey::Rope;
+
+use super::Document;
+use crate::syntax::LanguageId;
+use crate::util::text::TABULATOR_WIDTH;
+
+/// A folded region: `start` stays visible, `start + 1..=end` are hidden
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fold {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Folds of one editor view, at most one per header line
+#[derive(Debug, Clone, Default)]
+pub struct FoldState {
+    /// Sorted by `start`
+    folds: Vec<Fold>,
+    /// Merged, sorted, inclusive ranges of hidden lines
+    hidden: Vec<(usize, usize)>,
+    /// Document line count the folds were made against
+    line_count: usize,
+}
+
+impl FoldState {
+    pub fn is_empty(&self) -> bool {
+        self.folds.is_empty()
+    }
+
+    pub fn folds(&self) -> &[Fold] {
+        &self.folds
+    }
+
+    /// Document line count the folds refer to
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Fold whose header is `start`
+    pub fn fold_at(&self, start: usize) -> Option<Fold> {
+        self.folds
+            .binary_search_by_key(&start, |fold| fold.start)
+            .ok()
+            .map(|index| self.folds[index])
+    }
+
+    /// Add a fold (replacing any fold with the same header); empty folds are
+    /// ignored
+    pub fn add(&mut self, fold: Fold, line_count: usize) {
+        self.line_count = line_count;
+        if fold.end <= fold.start || fold.end >= line_count {
+            return;
+        }
+        match self.folds.binary_search_by_key(&fold.start, |f| f.start) {
+            Ok(index) => self.folds[index] = fold,
+            Err(index) => self.folds.insert(index, fold),
+        }
+        self.rebuild_hidden();
+    }
+
+    /// Remove the fold whose header is `start`
+    pub fn remove_at(&mut self, start: usize) -> bool {
+        let before = self.folds.len();
+        self.folds.retain(|fold| fold.start != start);
+        self.rebuild_hidden();
+        self.folds.len() != before
+    }
+
+    /// Replace all folds
+    pub fn set(&mut self, folds: impl IntoIterator<Item = Fold>, line_count: usize) {
+        self.folds.clear();
+        self.line_count = line_count;
+        for fold in folds {
+            self.add(fold, line_count);
+        }
+        self.rebuild_hidden();
+    }
+
+    pub fn clear(&mut self) {
+        self.folds.clear();
+        self.hidden.clear();
+    }
+
+    /// Hidden line ranges for [`TextViewportMap::with_hidden_lines`](super::TextViewportMap::with_hidden_lines)
+    pub fn hidden_ranges(&self) -> &[(usize, usize)] {
+        &self.hidden
+    }
+
+    pub fn is_hidden(&self, line: usize) -> bool {
+        is_hidden(&self.hidden, line)
+    }
+
+    /// Unfold every fold hiding `line`; returns whether anything changed
+    pub fn reveal(&mut self, line: usize) -> bool {
+        if !self.is_hidden(line) {
+            return false;
+        }
+        self.folds
+            .retain(|fold| !(fold.start < line && line <= fold.end));
+        self.rebuild_hidden();
+        true
+    }
+
+    /// Nearest visible line above `line`
+    pub fn visible_line_above(&self, line: usize) -> Option<usize> {
+        let mut above = line.checked_sub(1)?;
+        while let Some(&(start, _)) = self.range_containing(above) {
+            above = start.checked_sub(1)?;
+        }
+        Some(above)
+    }
+
+    /// Nearest visible line below `line`, if the document has one
+    pub fn visible_line_below(&self, line: usize, line_count: usize) -> Option<usize> {
+        let mut below = line + 1;
+        while let Some(&(_, end)) = self.range_containing(below) {
+            below = end + 1;
+        }
+        (below < line_count).then_some(below)
+    }
+
+    /// Move `line` by `delta` visual lines, staying within the document
+    pub fn offset_visible_line(&self, line: usize, delta: isize, line_count: usize) -> usize {
+        let last = visual_line(&self.hidden, line_count.saturating_sub(1));
+        let visual = visual_line(&self.hidden, line).saturating_add_signed(delta);
+        doc_line(&self.hidden, visual.min(last))
+    }
+
+    /// Keep folds aligned with the text after an edit at `edit_line` that
+    /// changed the line count from `old_line_count` to `new_line_count`.
+    ///
+    /// Folds below the edit move with it; folds the edit touched are opened.
+    pub fn shift_for_edit(
+        &mut self,
+        edit_line: usize,
+        old_line_count: usize,
+        new_line_count: usize,
+    ) {
+        if self.folds.is_empty() || self.line_count != old_line_count {
+            self.line_count = new_line_count;
+            return;
+        }
+        self.line_count = new_line_count;
+        let delta = new_line_count as isize - old_line_count as isize;
+        if delta == 0 {
+            return;
+        }
+        let deleted_lines = if delta < 0 { delta.unsigned_abs() } else { 0 };
+        self.folds.retain_mut(|fold| {
+            if fold.end < edit_line {
+                true
+            } else if fold.start > edit_line + deleted_lines {
+                fold.start = fold.start.saturating_add_signed(delta);
+                fold.end = fold.end.saturating_add_signed(delta);
+                true
+            } else {
+                false
+            }
+        });
+        self.rebuild_hidden();
+    }
+
+    fn range_containing(&self, line: usize) -> Option<&(usize, usize)> {
+        self.hidden
+            .iter()
+            .find(|&&(start, end)| start <= line && line <= end)
+    }
+
+    fn rebuild_hidden(&mut self) {
+        self.hidden.clear();
+        for fold in &self.folds {
+            let (start, end) = (fold.start + 1, fold.end);
+            match self.hidden.last_mut() {
+                Some((_, last_end)) if start <= *last_end + 1 => *last_end = (*last_end).max(end),
+                _ => self.hidden.push((start, end)),
+            }
+        }
+    }
+}
+
+/// Whether `line` falls in one of the `hidden` ranges
+pub fn is_hidden(hidden: &[(usize, usize)], line: usize) -> bool {
+    hidden
+        .iter()
+        .take_while(|&&(start, _)| start <= line)
+        .any(|&(_, end)| line <= end)
+}
+
+/// Visual line of a document line; hidden lines map to the visible line
+/// above them
+pub fn visual_line(hidden: &[(usize, usize)], line: usize) -> usize {
+    let mut hidden_before = 0;
+    for &(start, end) in hidden {
+        if line < start {
+            break;
+        }
+        if line <= end {
+            return (start - 1) - hidden_before;
+        }
+        hidden_before += end - start + 1;
+    }
+    line - hidden_before
+}
+
+/// Document line shown at a visual line
+pub fn doc_line(hidden: &[(usize, usize)], visual: usize) -> usize {
+    let mut line = visual;
+    for &(start, end) in hidden {
+        if start > line {
+            break;
+        }
+        line += end - start + 1;
+    }
+    line
+}
+
+/// Indentation of a line in columns (tabs advance to the next tab stop), or
+/// `None` for a blank line
+fn indent_of(buffer: &Rope, line: usize) -> Option<usize> {
+    let mut width = 0;
+    for ch in buffer.line(line).chars() {
+        match ch {
+            ' ' => width += 1,
+            '\t' => width += TABULATOR_WIDTH - width % TABULATOR_WIDTH,
+            '\n' | '\r' => return None,
+            _ => return Some(width),
+        }
+    }
+    None
+}
+
+/// Whether the next non-blank line after `line` is indented deeper than it
+pub fn starts_indent_fold(buffer: &Rope, line: usize) -> bool {
+    let Some(indent) = (line < buffer.len_lines())
+        .then(|| indent_of(buffer, line))
+        .flatten()
+    else {
+        return false;
+    };
+    (line + 1..buffer.len_lines())
+        .find_map(|next| indent_of(buffer, next))
+        .is_some_and(|next| next > indent)
+}
+
+/// Whether a fold region starts at `line`: a Markdown heading with lines
+/// after it, or a line followed by deeper indented ones
+pub fn can_fold_at(document: &Document, line: usize) -> bool {
+    if document.language == LanguageId::Markdown {
+        line + 1 < document.line_count()
+            && document
+                .get_line_cow(line)
+                .is_some_and(|text| crate::markdown::atx_heading_level(&text).is_some())
+    } else {
+        starts_indent_fold(&document.buffer, line)
+    }
+}
+
+/// The region under `line` by indentation: the lines after it up to the last
+/// non-blank line indented deeper than it
+pub fn indent_fold_at(buffer: &Rope, line: usize) -> Option<Fold> {
+    if !starts_indent_fold(buffer, line) {
+        return None;
+    }
+    let indent = indent_of(buffer, line)?;
+    let mut end = line;
+    for next in line + 1..buffer.len_lines() {
+        match indent_of(buffer, next) {
+            Some(next_indent) if next_indent > indent => end = next,
+            Some(_) => break,
+            None => {}
+        }
+    }
+    Some(Fold { start: line, end })
+}
+
+/// The innermost indentation region containing `line` below its header
+pub fn enclosing_indent_fold(buffer: &Rope, line: usize) -> Option<Fold> {
+    let indent = (line..buffer.len_lines()).find_map(|next| indent_of(buffer, next))?;
+    (0..line.min(buffer.len_lines()))
+        .rev()
+        .find(|&above| indent_of(buffer, above).is_some_and(|above| above < indent))
+        .and_then(|header| indent_fold_at(buffer, header))
+        .filter(|fold| fold.end >= line)
+}
+
+/// All indentation regions of a document, sorted by header line
+pub fn indent_fold_regions(buffer: &Rope) -> Vec<Fold> {
+    let mut folds = Vec::new();
+    // Headers of the regions still open, with their indentation
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut previous: Option<(usize, usize)> = None;
+    for line in 0..buffer.len_lines() {
+        let Some(indent) = indent_of(buffer, line) else {
+            continue;
+        };
+        let last = previous.map_or(0, |(last, _)| last);
+        while open.last().is_some_and(|&(_, header)| indent <= header) {
+            let (start, _) = open.pop().unwrap_or_default();
+            folds.push(Fold { start, end: last });
+        }
+        if let Some((last, last_indent)) = previous.filter(|&(_, i)| indent > i) {
+            open.push((last, last_indent));
+        }
+        previous = Some((line, indent));
+    }
+    if let Some((last, _)) = previous {
+        folds.extend(open.into_iter().map(|(start, _)| Fold { start, end: last }));
+    }
+    folds.sort_by_key(|fold| fold.start);
+    folds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folded(folds: &[(usize, usize)], line_count: usize) -> FoldState {
+        let mut state = FoldState::default();
+        state.set(
+            folds.iter().map(|&(start, end)| Fold { start, end }),
+            line_count,
+        );
+        state
+    }
+
+    #[test]
+    fn nested_folds_hide_the_union_and_map_visual_lines() {
+        // 0 h1, 1-5 body with 2 h2 (3-4 folded), 6 visible
+        let state = folded(&[(2, 4), (0, 5)], 10);
+        assert_eq!(state.hidden_ranges(), &[(1, 5)]);
+
+        let state = folded(&[(2, 4), (6, 7)], 10);
+        assert_eq!(state.hidden_ranges(), &[(3, 4), (7, 7)]);
+        let hidden = state.hidden_ranges();
+        assert_eq!(visual_line(hidden, 2), 2);
+        assert_eq!(visual_line(hidden, 4), 2);
+        assert_eq!(visual_line(hidden, 5), 3);
+        assert_eq!(visual_line(hidden, 8), 5);
+        assert_eq!(doc_line(hidden, 3), 5);
+        assert_eq!(doc_line(hidden, 5), 8);
+
+        assert_eq!(state.visible_line_below(2, 10), Some(5));
+        assert_eq!(state.visible_line_above(5), Some(2));
+        assert_eq!(state.visible_line_below(6, 8), None);
+        assert_eq!(state.offset_visible_line(0, 4, 10), 6);
+    }
+
+    #[test]
+    fn edits_shift_folds_below_and_open_touched_ones() {
+        let mut state = folded(&[(2, 4), (6, 8)], 10);
+        state.shift_for_edit(5, 10, 12);
+        assert_eq!(
+            state.folds(),
+            &[Fold { start: 2, end: 4 }, Fold { start: 8, end: 10 }]
+        );
+
+        // Enter at the end of a fold header opens it
+        state.shift_for_edit(2, 12, 13);
+        assert_eq!(state.folds(), &[Fold { start: 9, end: 11 }]);
+
+        let mut state = folded(&[(3, 5)], 10);
+        assert!(state.reveal(4));
+        assert!(state.is_empty());
+    }
+
+    #[test]
+    fn indentation_regions_nest_and_skip_blank_lines() {
+        let text =
+            Rope::from("fn a() {\n    if x {\n        y();\n\n    }\n    z();\n}\n\nfn b() {}\n");
+        let regions: Vec<(usize, usize)> = indent_fold_regions(&text)
+            .iter()
+            .map(|fold| (fold.start, fold.end))
+            .collect();
+        assert_eq!(regions, vec![(0, 5), (1, 2)]);
+
+        assert_eq!(indent_fold_at(&text, 1), Some(Fold { start: 1, end: 2 }));
+        assert!(starts_indent_fold(&text, 0));
+        assert!(!starts_indent_fold(&text, 2));
+        assert_eq!(indent_fold_at(&text, 8), None);
+        // Inside a region: its header's region, from a blank line too
+        assert_eq!(
+            enclosing_indent_fold(&text, 5),
+            Some(Fold { start: 0, end: 5 })
+        );
+        assert_eq!(
+            enclosing_indent_fold(&text, 3),
+            Some(Fold { start: 0, end: 5 })
+        );
+        assert_eq!(enclosing_indent_fold(&text, 8), None);
+    }
+}
@@ -2,43 +2,60 @@
 //!
 //! This module contains all the state types following the Elm Architecture pattern.
 
+pub mod annotations;
+pub mod decorations;
 pub mod document;
 pub mod editor;
 pub mod editor_area;
+pub mod folding;
 pub mod status_bar;
 pub mod ui;
 pub mod workspace;
 
-pub use document::{Document, EditOperation};
+pub use annotations::{Annotation, AnnotationPlacement, AnnotationSource, Annotations};
+pub use decorations::{
+    DecorationKind, DecorationSource, GutterIcon, LineDecoration, LineDecorations, LineStyle,
+};
+pub use document::{Document, EditOperation, LoadProgress};
 pub use editor::{
     BinaryPlaceholderState, Cursor, EditorState, OccurrenceState, Position,
-    RectangleSelectionState, ScrollRevealMode, Selection, TabContent, TextViewportMap, ViewMode,
-    Viewport,
+    RectangleSelectionState, ScrollRevealMode, Selection, TabContent, TextDragState,
+    TextViewportMap, ViewMode, Viewport, WelcomeState, WordHighlightKey, WordHighlights,
 };
 pub use editor_area::{
-    DocumentId, EditorArea, EditorGroup, EditorId, GroupId, LayoutNode, Rect, SplitContainer,
-    SplitDirection, SplitterBar, Tab, TabId, SPLITTER_WIDTH,
+    DocumentId, DropEdge, EditorArea, EditorGroup, EditorId, GroupId, LayoutNode, Rect, ScrollLock,
+    SplitContainer, SplitDirection, SplitterBar, Tab, TabColor, TabId, SPLITTER_WIDTH,
 };
+pub use folding::{Fold, FoldState};
 pub use status_bar::{
     sync_status_bar, RenderedSegment, SegmentContent, SegmentId, SegmentPosition, StatusBar,
     StatusBarLayout, StatusSegment, TransientMessage,
 };
 pub use ui::{
-    CommandPaletteState, DropState, FileFinderState, FileMatch, FindReplaceField, FindReplaceState,
-    FocusTarget, GotoLineState, HoverRegion, ModalId, ModalState, OutlinePanelState,
-    RecentFilesState, ScrollbarDragAxis, ScrollbarDragState, SidebarResizeState, ThemePickerState,
-    UiState,
+    BacklinksState, BreadcrumbSymbol, BreadcrumbSymbolsState, CommandPaletteState, CompletionState,
+    ContextMenuState, CreateFileState, DeletedFileChoice, DeletedFileState, DropState,
+    FileChangedChoice, FileChangedState, FileMatch, FindMatchCounts, FindReplaceField,
+    FindReplaceState, FocusTarget, GotoLineState, GotoSymbolEntry, GotoSymbolState, HoverRegion,
+    KeymapDiagnosticsState, LanguagePickerState, LogPanelState, ModalId, ModalState,
+    OutlinePanelState, PastedImage, QuickOpenState, RecentFilesState, RenameFileState,
+    SaveAllProgress, ScrollbarDragAxis, ScrollbarDragState, SearchHighlight, SearchPanelState,
+    SearchRegex, SearchScope, SidebarResizeState, ThemePickerState, TodoPanelState, UiState,
+    UnsavedChangesChoice, UnsavedChangesState, WorkspaceTrustChoice, WorkspaceTrustState,
 };
-pub use workspace::{FileExtension, FileNode, FileTree, Workspace};
+pub use workspace::{FileExtension, FileNode, FileTree, NameEdit, NameEditKind, Workspace};
 
 use crate::config::EditorConfig;
 use crate::config_paths;
 #[cfg(debug_assertions)]
 use crate::debug_overlay::DebugOverlay;
 use crate::recent_files::RecentFiles;
+use crate::session::{EditorSession, Session};
 use crate::theme::{load_theme, Theme};
-use crate::util::{is_likely_binary, validate_file_for_opening, FileOpenError};
-use std::path::PathBuf;
+use crate::util::{
+    is_likely_binary, is_supported_image, normalize_path, validate_file_for_opening, FileOpenError,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 // ============================================================================
 // Viewport Geometry - pure calculations for dimensions
@@ -136,19 +153,47 @@ fn load_config_and_theme() -> (EditorConfig, Theme) {
     (config, theme)
 }
 
+/// Load an image file for the image viewer, fitted to the window until the
+/// first layout pass fits it to its group
+fn load_image_view(path: &Path, geom: &ViewportGeometry) -> Option<ViewMode> {
+    let image = crate::image::load_image(path, geom.window_width, geom.window_height)?;
+    Some(ViewMode::Image(Box::new(image)))
+}
+
 /// Create initial session with documents and editor area
 fn create_initial_session(file_paths: Vec<PathBuf>, geom: &ViewportGeometry) -> InitialSession {
     // Load first file or create empty document
+    let mut first_view_mode = ViewMode::Text;
     let (first_document, status_message) = if let Some(first_path) = file_paths.first() {
         // Validate and load the first file
         match validate_file_for_opening(first_path) {
+            Ok(()) if is_supported_image(first_path) => {
+                // Image files open in the image viewer
+                match load_image_view(first_path, geom) {
+                    Some(view_mode) => {
+                        let msg = if file_paths.len() > 1 {
+                            format!("Opened {} files", file_paths.len())
+                        } else {
+                            format!("Opened image: {}", first_path.display())
+                        };
+                        first_view_mode = view_mode;
+                        let mut doc = Document::new();
+                        doc.file_path = Some(first_path.clone());
+                        (doc, msg)
+                    }
+                    None => {
+                        let msg = format!("Error opening image: {}", first_path.display());
+                        (Document::new(), msg)
+                    }
+                }
+            }
             Ok(()) => {
                 // File exists and is valid - check for binary
                 if is_likely_binary(first_path) {
                     let msg = format!("Cannot open binary file: {}", first_path.display());
                     (Document::new(), msg)
                 } else {
-                    match Document::from_file(first_path.clone()) {
+                    match Document::open(first_path.clone()) {
                         Ok(doc) => {
                             let msg = if file_paths.len() > 1 {
                                 format!("Opened {} files", file_paths.len())
@@ -181,22 +226,48 @@ fn create_initial_session(file_paths: Vec<PathBuf>, geom: &ViewportGeometry) ->
     };
 
     // Create editor state with viewport
-    let editor = EditorState::with_viewport(geom.visible_lines, geom.visible_columns);
+    let mut editor = EditorState::with_viewport(geom.visible_lines, geom.visible_columns);
+    editor.view_mode = first_view_mode;
 
     // Create editor area with first document
     let mut editor_area = EditorArea::single_document(first_document, editor);
 
-    // Open additional files as tabs
+    // Open additional files as tabs, skipping repeats of an already opened
+    // file (e.g. `token a.rs ./a.rs`)
+    let mut opened: Vec<PathBuf> = file_paths
+        .first()
+        .map(|p| normalize_path(p))
+        .into_iter()
+        .collect();
     for path in file_paths.into_iter().skip(1) {
+        let normalized = normalize_path(&path);
+        if opened.contains(&normalized) {
+            continue;
+        }
+        opened.push(normalized);
+
         // Validate before attempting to open
+        let mut view_mode = ViewMode::Text;
         let doc = match validate_file_for_opening(&path) {
+            Ok(()) if is_supported_image(&path) => match load_image_view(&path, geom) {
+                Some(image_view) => {
+                    view_mode = image_view;
+                    let mut doc = Document::new();
+                    doc.file_path = Some(path.clone());
+                    doc
+                }
+                None => {
+                    tracing::warn!("Failed to open image {}", path.display());
+                    continue;
+                }
+            },
             Ok(()) => {
                 // File exists - check for binary
                 if is_likely_binary(&path) {
                     tracing::warn!("Skipping binary file: {}", path.display());
                     continue;
                 }
-                match Document::from_file(path.clone()) {
+                match Document::open(path.clone()) {
                     Ok(doc) => doc,
                     Err(e) => {
                         tracing::warn!("Failed to open {}: {}", path.display(), e);
@@ -224,6 +295,7 @@ fn create_initial_session(file_paths: Vec<PathBuf>, geom: &ViewportGeometry) ->
         let mut editor = EditorState::with_viewport(geom.visible_lines, geom.visible_columns);
         editor.id = Some(editor_id);
         editor.document_id = Some(doc_id);
+        editor.view_mode = view_mode;
         editor_area.editors.insert(editor_id, editor);
 
         // Create tab in focused group
@@ -233,6 +305,7 @@ fn create_initial_session(file_paths: Vec<PathBuf>, geom: &ViewportGeometry) ->
             editor_id,
             is_pinned: false,
             is_preview: false,
+            color: None,
         };
 
         if let Some(group) = editor_area.groups.get_mut(&editor_area.focused_group_id) {
@@ -246,8 +319,9 @@ fn create_initial_session(file_paths: Vec<PathBuf>, geom: &ViewportGeometry) ->
     }
 }
 
-/// Layout constant - width of line number gutter in characters (e.g., " 123 ")
-pub const LINE_NUMBER_GUTTER_CHARS: usize = 5;
+/// Layout constant - width of line number gutter in characters (e.g., " 123 ▾"),
+/// including the fold marker column
+pub const LINE_NUMBER_GUTTER_CHARS: usize = 6;
 
 // ============================================================================
 // Scaled Metrics - UI layout constants scaled for display DPI
@@ -263,6 +337,8 @@ pub struct ScaledMetrics {
     pub scale_factor: f64,
     /// Tab bar height in physical pixels
     pub tab_bar_height: usize,
+    /// Breadcrumb strip height in physical pixels; 0 while it is hidden
+    pub breadcrumb_bar_height: usize,
     /// Splitter width in physical pixels
     pub splitter_width: f32,
     /// Gutter padding (after line numbers, before border) in physical pixels
@@ -291,6 +367,14 @@ pub struct ScaledMetrics {
     pub sidebar_max_width_logical: f32,
     /// Resize handle hit zone in physical pixels
     pub resize_handle_zone: usize,
+
+    // === Dock Metrics ===
+    /// Minimum dock size in logical pixels (width for side docks, height for bottom)
+    pub dock_min_size_logical: f32,
+    /// Maximum dock size as a fraction of the window dimension
+    pub dock_max_size_fraction: f32,
+    /// Thickness of a collapsed dock's icon strip in physical pixels
+    pub dock_icon_strip_size: usize,
     /// Scrollbar width in physical pixels
     pub scrollbar_width: usize,
 }
@@ -326,6 +410,14 @@ impl ScaledMetrics {
     const BASE_SIDEBAR_MAX_WIDTH: f32 = 500.0;
     /// Base resize handle zone at scale factor 1.0
     const BASE_RESIZE_HANDLE_ZONE: f64 = 4.0;
+
+    // === Dock Base Values ===
+    /// Minimum dock size in logical pixels (not scaled)
+    const BASE_DOCK_MIN_SIZE: f32 = 150.0;
+    /// Maximum dock size as a fraction of the window dimension
+    const BASE_DOCK_MAX_SIZE_FRACTION: f32 = 0.5;
+    /// Base collapsed dock icon strip thickness at scale factor 1.0
+    const BASE_DOCK_ICON_STRIP_SIZE: f64 = 28.0;
     /// Base scrollbar width at scale factor 1.0
     const BASE_SCROLLBAR_WIDTH: f64 = 12.0;
 
@@ -334,6 +426,7 @@ impl ScaledMetrics {
         Self {
             scale_factor,
             tab_bar_height: (Self::BASE_TAB_BAR_HEIGHT * scale_factor).round() as usize,
+            breadcrumb_bar_height: 0,
             splitter_width: (Self::BASE_SPLITTER_WIDTH * scale_factor) as f32,
             gutter_padding: (Self::BASE_GUTTER_PADDING * scale_factor) as f32,
             text_area_padding: (Self::BASE_TEXT_AREA_PADDING * scale_factor) as f32,
@@ -350,9 +443,18 @@ impl ScaledMetrics {
             resize_handle_zone: (Self::BASE_RESIZE_HANDLE_ZONE * scale_factor)
                 .round()
                 .max(2.0) as usize,
+            dock_min_size_logical: Self::BASE_DOCK_MIN_SIZE,
+            dock_max_size_fraction: Self::BASE_DOCK_MAX_SIZE_FRACTION,
+            dock_icon_strip_size: (Self::BASE_DOCK_ICON_STRIP_SIZE * scale_factor).round() as usize,
             scrollbar_width: (Self::BASE_SCROLLBAR_WIDTH * scale_factor).round() as usize,
         }
     }
+
+    /// Height above an editor group's content: the tab bar plus the
+    /// breadcrumb strip when it is shown
+    pub fn header_height(&self) -> usize {
+        self.tab_bar_height + self.breadcrumb_bar_height
+    }
 }
 
 impl Default for ScaledMetrics {
@@ -415,8 +517,22 @@ pub struct AppModel {
     pub terminal: crate::terminal::TerminalState,
     /// Outline panel UI state (expand/collapse, selection, scroll)
     pub outline_panel: crate::model::ui::OutlinePanelState,
+    /// TODO list panel state (scan results, selection, scroll)
+    pub todo_panel: crate::model::ui::TodoPanelState,
+    /// Workspace search panel state (query, streamed results, selection)
+    pub search_panel: crate::model::ui::SearchPanelState,
+    /// AI chat panel state (prompt input, transcript, in-flight request)
+    pub ai_chat: crate::ai::AiChatState,
+    /// Documents synced to language servers and the lookup in flight
+    pub lsp: crate::lsp::LspState,
     /// Recent files list (persistent across sessions)
     pub recent_files: RecentFiles,
+    /// Which workspaces may run commands (persistent across sessions)
+    pub workspace_trust: crate::workspace_trust::WorkspaceTrust,
+    /// File tree operations that can be undone (newest last)
+    pub file_ops: crate::file_ops::FileOpJournal,
+    /// Vim mode and partly typed command (used with `config.vim_mode`)
+    pub vim: crate::vim::VimState,
     /// Debug overlay state (debug builds only)
     #[cfg(debug_assertions)]
     pub debug_overlay: Option<DebugOverlay>,
@@ -448,7 +564,7 @@ impl AppModel {
             status_message,
         } = create_initial_session(file_paths, &geom);
 
-        Self {
+        let mut model = Self {
             editor_area,
             ui: UiState::with_status(status_message),
             theme,
@@ -462,9 +578,18 @@ impl AppModel {
             terminal: crate::terminal::TerminalState::default(),
             outline_panel: crate::model::ui::OutlinePanelState::default(),
             recent_files,
+            workspace_trust: crate::workspace_trust::WorkspaceTrust::load(),
+            file_ops: Default::default(),
+            vim: Default::default(),
+            ai_chat: crate::ai::AiChatState::default(),
+            lsp: crate::lsp::LspState::default(),
+            todo_panel: crate::model::TodoPanelState::default(),
+            search_panel: crate::model::SearchPanelState::default(),
             #[cfg(debug_assertions)]
             debug_overlay: Some(DebugOverlay::new()),
-        }
+        };
+        model.sync_breadcrumb_bar_height();
+        model
     }
 
     /// Open a directory as workspace
@@ -477,6 +602,10 @@ impl AppModel {
                 self.workspace = Some(workspace);
                 self.ui
                     .set_status(format!("Opened workspace: {}", root.display()));
+                if self.workspace_trust.decision(&root).is_none() {
+                    self.ui
+                        .open_modal(ModalState::WorkspaceTrust(WorkspaceTrustState::new(root)));
+                }
             }
             Err(e) => {
                 self.ui
@@ -485,12 +614,83 @@ impl AppModel {
         }
     }
 
+    /// Whether the open workspace may run commands. Without a workspace
+    /// nothing workspace-provided is loaded, so there is nothing to restrict.
+    pub fn workspace_trusted(&self) -> bool {
+        self.workspace
+            .as_ref()
+            .is_none_or(|workspace| self.workspace_trust.is_trusted(&workspace.root))
+    }
+
     /// Record that a file was opened (adds to recent files list)
     pub fn record_file_opened(&mut self, path: PathBuf) {
         let workspace = self.workspace.as_ref().map(|ws| ws.root.clone());
         self.recent_files.add(path, workspace);
     }
 
+    /// Snapshot of the UI state that is saved when the editor exits
+    pub fn session(&self) -> Session {
+        Session {
+            version: Session::CURRENT_VERSION,
+            docks: self.dock_layout.state(),
+            tab_colors: self.tab_colors(),
+        }
+    }
+
+    /// Color labels of tabs showing files, by path
+    fn tab_colors(&self) -> BTreeMap<PathBuf, TabColor> {
+        let area = &self.editor_area;
+        area.groups
+            .values()
+            .flat_map(|group| &group.tabs)
+            .filter_map(|tab| {
+                let color = tab.color?;
+                let document_id = area.editors.get(&tab.editor_id)?.document_id?;
+                let path = area.documents.get(&document_id)?.file_path.clone()?;
+                Some((path, color))
+            })
+            .collect()
+    }
+
+    /// Restore UI state saved by a previous run
+    pub fn restore_session(&mut self, session: &Session) {
+        self.dock_layout
+            .restore_state(&session.docks, self.metrics.dock_min_size_logical);
+        for group in self.editor_area.groups.values_mut() {
+            for tab in &mut group.tabs {
+                let path = self
+                    .editor_area
+                    .editors
+                    .get(&tab.editor_id)
+                    .and_then(|editor| editor.document_id)
+                    .and_then(|id| self.editor_area.documents.get(&id))
+                    .and_then(|doc| doc.file_path.as_ref());
+                if let Some(&color) = path.and_then(|path| session.tab_colors.get(path)) {
+                    tab.color = Some(color);
+                }
+            }
+        }
+        if let Some(workspace) = &mut self.workspace {
+            workspace.sidebar_visible = self.dock_layout.left.is_open;
+            workspace.sidebar_width_logical = self.dock_layout.left.size_logical;
+        }
+        self.recalculate_viewports();
+    }
+
+    /// Replace the open tabs with those of a saved editor session. Returns
+    /// false (and changes nothing) when none of its files could be opened.
+    pub fn restore_editor_session(&mut self, session: &EditorSession) -> bool {
+        let geom = ViewportGeometry::new(self.window_size.0, self.window_size.1);
+        let Some(editor_area) = EditorArea::from_session(session, || {
+            EditorState::with_viewport(geom.visible_lines, geom.visible_columns)
+        }) else {
+            return false;
+        };
+        self.editor_area = editor_area;
+        self.recalculate_viewports();
+        true
+    }
+
     /// Close the current workspace
     pub fn close_workspace(&mut self) {
         self.workspace = None;
@@ -521,6 +721,17 @@ impl AppModel {
         let padding = self.metrics.padding_medium;
 
         self.metrics.tab_bar_height = glyph_height + padding * 2;
+        self.sync_breadcrumb_bar_height();
+    }
+
+    /// Size the breadcrumb strip for the `breadcrumbs` setting: one line of
+    /// text with a little padding, or nothing when it is off
+    pub fn sync_breadcrumb_bar_height(&mut self) {
+        self.metrics.breadcrumb_bar_height = if self.config.breadcrumbs {
+            self.line_height + self.metrics.padding_small * 2
+        } else {
+            0
+        };
     }
 
     /// Get the focused editor (read-only), or None if no editor is focused
@@ -614,7 +825,7 @@ impl AppModel {
             .filter(|ws| ws.sidebar_visible)
             .map(|ws| ws.sidebar_width(self.metrics.scale_factor))
             .unwrap_or(0.0);
-        let right_dock_width = self.dock_layout.right.size(self.metrics.scale_factor);
+        let right_dock_width = self.dock_layout.right.size(&self.metrics);
 
         let text_x = text_start_x_scaled(self.char_width, &self.metrics).round();
         let effective_width = (width as f32) - sidebar_width - right_dock_width;
@@ -624,8 +835,8 @@ impl AppModel {
 
         // Subtract status bar, tab bar, and bottom dock from available height
         let status_bar_height = self.line_height;
-        let tab_bar_height = self.metrics.tab_bar_height;
-        let bottom_dock_height = self.dock_layout.bottom.size(self.metrics.scale_factor) as usize;
+        let tab_bar_height = self.metrics.header_height();
+        let bottom_dock_height = self.dock_layout.bottom.size(&self.metrics) as usize;
         let available_height = (height as usize)
             .saturating_sub(status_bar_height)
             .saturating_sub(tab_bar_height)
@@ -843,6 +1054,12 @@ impl AppModel {
         self.ui.reset_cursor_blink();
     }
 
+    /// Whether performance mode is on, and why. Costly visuals are off
+    /// while it is.
+    pub fn performance_mode(&self) -> Option<crate::performance::PerformanceReason> {
+        self.ui.performance.reason(self.config.performance_mode)
+    }
+
     /// Get a line from the document
     pub fn get_line(&self, line_idx: usize) -> Option<String> {
         self.document().get_line(line_idx)
@@ -879,6 +1096,16 @@ impl AppModel {
             })
             .unwrap_or(false)
     }
+
+    /// Check if typing into a CSV filter
+    ///
+    /// Used by keyboard routing to bypass keymap while a filter has focus.
+    pub fn is_csv_filtering(&self) -> bool {
+        self.editor_area
+            .focused_editor()
+            .and_then(|e| e.view_mode.as_csv())
+            .is_some_and(|csv| csv.filter_focus.is_some())
+    }
 }
 
 #[cfg(test)]
@@ -920,6 +1147,8 @@ mod tests {
         assert_eq!(metrics.file_tree_indent, 32.0); // 16 * 2
         assert_eq!(metrics.sidebar_default_width_logical, 250.0); // Not scaled
         assert_eq!(metrics.resize_handle_zone, 8); // 4 * 2
+        assert_eq!(metrics.dock_icon_strip_size, 56); // 28 * 2
+        assert_eq!(metrics.dock_min_size_logical, 150.0); // Not scaled
     }
 
     #[test]
@@ -7,6 +7,10 @@ use std::time::{Duration, Instant};
 /// Identifier for status bar segments
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SegmentId {
+    /// Vim mode and partly typed command (e.g., "NORMAL 2d")
+    VimMode,
+    /// Keystrokes of a chord waiting for its next key (e.g., "⌘K …")
+    KeyChord,
     /// File name display
     FileName,
     /// Modified indicator (e.g., "*")
@@ -21,6 +25,16 @@ pub enum SegmentId {
     StatusMessage,
     /// Caret count for multi-cursor (e.g., "4 carets")
     CaretCount,
+    /// Checked task list items in Markdown documents (e.g., "3/7 tasks")
+    TaskProgress,
+    /// Rows a CSV filter lets through (e.g., "12/340 rows")
+    CsvRows,
+    /// Shown while performance mode is on (e.g., "Performance (battery)")
+    PerformanceMode,
+    /// Shown while a large file streams in (e.g., "Loading 42%")
+    FileLoading,
+    /// Language of the focused document (e.g., "Rust"); click to change it
+    Language,
 }
 
 /// Position of a segment in the status bar
@@ -86,13 +100,20 @@ impl StatusSegment {
     pub fn new(id: SegmentId, content: SegmentContent) -> Self {
         // Determine default position based on segment type
         let position = match id {
-            SegmentId::FileName | SegmentId::ModifiedIndicator | SegmentId::StatusMessage => {
-                SegmentPosition::Left
-            }
+            SegmentId::VimMode
+            | SegmentId::KeyChord
+            | SegmentId::FileName
+            | SegmentId::ModifiedIndicator
+            | SegmentId::StatusMessage => SegmentPosition::Left,
             SegmentId::Selection
             | SegmentId::CursorPosition
             | SegmentId::LineCount
-            | SegmentId::CaretCount => SegmentPosition::Right,
+            | SegmentId::CaretCount
+            | SegmentId::TaskProgress
+            | SegmentId::CsvRows
+            | SegmentId::PerformanceMode
+            | SegmentId::FileLoading
+            | SegmentId::Language => SegmentPosition::Right,
         };
 
         Self {
@@ -134,6 +155,8 @@ impl StatusBar {
         Self {
             segments: vec![
                 // Left segments
+                StatusSegment::new(SegmentId::VimMode, SegmentContent::Empty).with_priority(95),
+                StatusSegment::new(SegmentId::KeyChord, SegmentContent::Empty).with_priority(96),
                 StatusSegment::new(
                     SegmentId::FileName,
                     SegmentContent::Text("[No Name]".into()),
@@ -144,6 +167,12 @@ impl StatusBar {
                 StatusSegment::new(SegmentId::StatusMessage, SegmentContent::Empty)
                     .with_priority(50),
                 // Right segments
+                StatusSegment::new(SegmentId::PerformanceMode, SegmentContent::Empty)
+                    .with_priority(30),
+                StatusSegment::new(SegmentId::FileLoading, SegmentContent::Empty).with_priority(85),
+                StatusSegment::new(SegmentId::TaskProgress, SegmentContent::Empty)
+                    .with_priority(35),
+                StatusSegment::new(SegmentId::CsvRows, SegmentContent::Empty).with_priority(36),
                 StatusSegment::new(SegmentId::CaretCount, SegmentContent::Empty).with_priority(45),
                 StatusSegment::new(SegmentId::Selection, SegmentContent::Empty).with_priority(40),
                 StatusSegment::new(
@@ -155,6 +184,11 @@ impl StatusBar {
                 StatusSegment::new(SegmentId::LineCount, SegmentContent::Text("1 Ln".into()))
                     .with_priority(60)
                     .with_min_width(6),
+                StatusSegment::new(
+                    SegmentId::Language,
+                    SegmentContent::Text("Plain Text".into()),
+                )
+                .with_priority(55),
             ],
             separator_spacing: 2,
             padding: 2,
@@ -351,6 +385,18 @@ impl StatusBar {
     }
 }
 
+impl StatusBarLayout {
+    /// The segment covering column `x` (in character units), if any
+    pub fn segment_at(&self, x: usize) -> Option<SegmentId> {
+        self.left
+            .iter()
+            .chain(&self.center)
+            .chain(&self.right)
+            .find(|seg| x >= seg.x && x < seg.x + seg.width)
+            .map(|seg| seg.id)
+    }
+}
+
 // =============================================================================
 // Sync Function
 // =============================================================================
@@ -359,6 +405,40 @@ use super::AppModel;
 
 /// Synchronize status bar segments with current document/editor state
 pub fn sync_status_bar(model: &mut AppModel) {
+    // PerformanceMode segment, whatever the focused editor shows
+    let performance = match model.performance_mode() {
+        Some(reason) => SegmentContent::Text(reason.label().to_string()),
+        None => SegmentContent::Empty,
+    };
+    model
+        .ui
+        .status_bar
+        .update_segment(SegmentId::PerformanceMode, performance);
+
+    // VimMode segment (only visible with Vim emulation on)
+    let vim = if model.config.vim_mode {
+        let pending = model.vim.pending();
+        let label = model.vim.mode.label();
+        if pending.is_empty() {
+            SegmentContent::Text(label.to_string())
+        } else {
+            SegmentContent::Text(format!("{} {}", label, pending))
+        }
+    } else {
+        SegmentContent::Empty
+    };
+    model.ui.status_bar.update_segment(SegmentId::VimMode, vim);
+
+    // KeyChord segment (only while a chord waits for its next key)
+    let chord = match &model.ui.pending_chord {
+        Some(keys) => SegmentContent::Text(format!("{} …", keys)),
+        None => SegmentContent::Empty,
+    };
+    model
+        .ui
+        .status_bar
+        .update_segment(SegmentId::KeyChord, chord);
+
     // Image mode: show image-specific info in status bar
     if let Some(image_state) = model
         .editor_area
@@ -390,6 +470,22 @@ pub fn sync_status_bar(model: &mut AppModel) {
             .ui
             .status_bar
             .update_segment(SegmentId::ModifiedIndicator, SegmentContent::Empty);
+        model
+            .ui
+            .status_bar
+            .update_segment(SegmentId::TaskProgress, SegmentContent::Empty);
+        model
+            .ui
+            .status_bar
+            .update_segment(SegmentId::CsvRows, SegmentContent::Empty);
+        model
+            .ui
+            .status_bar
+            .update_segment(SegmentId::FileLoading, SegmentContent::Empty);
+        model
+            .ui
+            .status_bar
+            .update_segment(SegmentId::Language, SegmentContent::Empty);
         return;
     }
 
@@ -451,6 +547,105 @@ pub fn sync_status_bar(model: &mut AppModel) {
         .ui
         .status_bar
         .update_segment(SegmentId::CaretCount, caret_content);
+
+    // TaskProgress segment (only visible in Markdown documents with tasks)
+    let task_content = calculate_task_progress(model);
+    model
+        .ui
+        .status_bar
+        .update_segment(SegmentId::TaskProgress, task_content);
+
+    // CsvRows segment (only while a CSV filter hides rows)
+    let csv_rows = calculate_csv_rows(model);
+    model
+        .ui
+        .status_bar
+        .update_segment(SegmentId::CsvRows, csv_rows);
+
+    // FileLoading segment (only while a large file streams in)
+    let loading = match &model.document().loading {
+        Some(progress) => SegmentContent::Text(format!("Loading {}%", progress.percent())),
+        None => SegmentContent::Empty,
+    };
+    model
+        .ui
+        .status_bar
+        .update_segment(SegmentId::FileLoading, loading);
+
+    // Language segment
+    let language = model.document().language.display_name().to_string();
+    model
+        .ui
+        .status_bar
+        .update_segment(SegmentId::Language, SegmentContent::Text(language));
+
+    // Diff mode: the change moved to and the line counts replace the cursor
+    if let Some(diff) = model
+        .editor_area
+        .focused_editor()
+        .and_then(|e| e.view_mode.as_diff())
+    {
+        let changes = diff.change_starts().len();
+        let position = match (changes, diff.current_change) {
+            (0, _) => "No changes".to_string(),
+            (n, Some(index)) => format!("Change {}/{}", index + 1, n),
+            (n, None) => format!("{} changes", n),
+        };
+        let (added, removed) = diff.line_counts();
+        let counts = format!("+{} −{}", added, removed);
+        model
+            .ui
+            .status_bar
+            .update_segment(SegmentId::CursorPosition, SegmentContent::Text(position));
+        model
+            .ui
+            .status_bar
+            .update_segment(SegmentId::Selection, SegmentContent::Text(counts));
+        model
+            .ui
+            .status_bar
+            .update_segment(SegmentId::CaretCount, SegmentContent::Empty);
+    }
+}
+
+/// Markdown documents larger than this don't show task progress, since it
+/// is recounted on every update
+const MAX_TASK_PROGRESS_BYTES: usize = 512 * 1024;
+
+/// Calculate the TaskProgress segment ("3/7 tasks")
+fn calculate_task_progress(model: &AppModel) -> SegmentContent {
+    let document = model.document();
+    if document.language != crate::syntax::LanguageId::Markdown
+        || document.buffer.len_bytes() > MAX_TASK_PROGRESS_BYTES
+    {
+        return SegmentContent::Empty;
+    }
+    let lines = document.buffer.lines().map(std::borrow::Cow::from);
+    match crate::notes::task_progress(lines) {
+        Some(progress) => {
+            SegmentContent::Text(format!("{}/{} tasks", progress.done, progress.total))
+        }
+        None => SegmentContent::Empty,
+    }
+}
+
+/// Calculate the CsvRows segment ("12/340 rows"), leaving out the header
+/// row
+fn calculate_csv_rows(model: &AppModel) -> SegmentContent {
+    let Some(csv) = model
+        .editor_area
+        .focused_editor()
+        .and_then(|editor| editor.view_mode.as_csv())
+        .filter(|csv| csv.is_filtered())
+    else {
+        return SegmentContent::Empty;
+    };
+    let header = usize::from(csv.has_header_row && csv.data.row_count() > 0);
+    SegmentContent::Text(format!(
+        "{}/{} rows",
+        csv.visible_row_count().saturating_sub(header),
+        csv.data.row_count() - header
+    ))
 }
 
 /// Calculate selection info for the Selection segment
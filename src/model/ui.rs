@@ -1,10 +1,16 @@
 //! UI state - status bar, cursor blink, modals, and other UI concerns
 
-use super::editor_area::{GroupId, SplitDirection};
+use super::editor::Position;
+use super::editor_area::{DocumentId, EditorId, GroupId, SplitDirection, TabId};
 use super::status_bar::{StatusBar, TransientMessage};
+use crate::commands::CommandId;
+use crate::edit_review::EditReviewState;
 use crate::editable::{EditConstraints, EditableState, StringBuffer};
+use crate::menu::MenuItem;
 use crate::panel::DockPosition;
+use crate::syntax::LanguageId;
 use crate::theme::{list_available_themes, ThemeInfo};
+use crate::util::{char_type, CharType};
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
@@ -79,10 +85,39 @@ pub enum ModalId {
     FindReplace,
     /// Theme picker
     ThemePicker,
-    /// File finder (Shift+Cmd+O) - fuzzy search files in workspace
-    FileFinder,
+    /// Quick Open (Cmd+P) - fuzzy search files in workspace
+    QuickOpen,
     /// Recent files list (Cmd+E)
     RecentFiles,
+    /// Diff review of a pending automated edit (opened by the edit itself)
+    EditReview,
+    /// Save / Don't Save / Cancel prompt (opened by closing a modified tab)
+    UnsavedChanges,
+    /// Recreate / Save As / Cancel prompt (opened by saving a file that was
+    /// deleted on disk)
+    DeletedFile,
+    /// Reload / Keep prompt (opened by another program changing the file of
+    /// a modified document)
+    FileChanged,
+    /// New name for a file, renamed in place
+    RenameFile,
+    /// Path of a file to create (opened by following a link to a missing note)
+    CreateFile,
+    /// Notes linking to the focused document
+    Backlinks,
+    /// Conflicting keybindings and keymap load errors
+    KeymapDiagnostics,
+    /// Language of the focused document ("Change Language Mode")
+    LanguagePicker,
+    /// Symbols next to one in the breadcrumb strip (opened by clicking it)
+    BreadcrumbSymbols,
+    /// Symbols of the focused document ("Go to Symbol")
+    GotoSymbol,
+    /// Trust / Restricted Mode prompt (opened by opening an undecided
+    /// workspace)
+    WorkspaceTrust,
+    /// Menu of commands at the mouse (opened by right-clicking a tab)
+    ContextMenu,
 }
 
 /// State for the command palette modal
@@ -142,6 +177,158 @@ impl GotoLineState {
     }
 }
 
+/// Search term whose matches are highlighted in the editor, and which F3 /
+/// Shift+F3 continue searching for after the find modal is closed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHighlight {
+    pub query: String,
+    pub case_sensitive: bool,
+    /// Only match the query as a whole word (set by searching for the word
+    /// under the cursor)
+    pub whole_word: bool,
+    /// The query compiled as a regular expression, in regex mode
+    pub regex: Option<SearchRegex>,
+    /// Only match within this part of a document ("find in selection")
+    pub scope: Option<SearchScope>,
+}
+
+impl SearchHighlight {
+    /// Matches on line `line` of document `document_id`, leaving out those
+    /// outside the search scope
+    pub fn matches_on_line(
+        &self,
+        document_id: Option<DocumentId>,
+        line: usize,
+        text: &str,
+    ) -> Vec<(usize, usize)> {
+        let mut matches = self.matches_in_line(text);
+        if let Some(scope) = self
+            .scope
+            .filter(|scope| Some(scope.document_id) == document_id)
+        {
+            matches.retain(|&(start, end)| {
+                scope.contains(Position::new(line, start), Position::new(line, end))
+            });
+        }
+        matches
+    }
+
+    /// Matches of the query within one line, as `(start_col, end_col)` char
+    /// columns. Matches don't overlap.
+    pub fn matches_in_line(&self, line: &str) -> Vec<(usize, usize)> {
+        if let Some(regex) = &self.regex {
+            return regex.matches_in_line(line);
+        }
+        let needle: Vec<char> = self.query.chars().collect();
+        let chars: Vec<char> = line.trim_end_matches(['\n', '\r']).chars().collect();
+        if needle.is_empty() || needle.len() > chars.len() {
+            return Vec::new();
+        }
+
+        let chars_equal = |a: char, b: char| {
+            a == b || (!self.case_sensitive && a.to_lowercase().eq(b.to_lowercase()))
+        };
+        let is_word = |col: usize| {
+            chars
+                .get(col)
+                .is_some_and(|&ch| char_type(ch) == CharType::WordChar)
+        };
+
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while start + needle.len() <= chars.len() {
+            let end = start + needle.len();
+            let found = chars[start..end]
+                .iter()
+                .zip(&needle)
+                .all(|(&a, &b)| chars_equal(a, b))
+                && (!self.whole_word || ((start == 0 || !is_word(start - 1)) && !is_word(end)));
+            if found {
+                matches.push((start, end));
+                start = end;
+            } else {
+                start += 1;
+            }
+        }
+        matches
+    }
+}
+
+/// A find query compiled as a regular expression. Matches are found within
+/// single lines, and replacements can refer to capture groups (`$1`,
+/// `${name}`).
+#[derive(Debug, Clone)]
+pub struct SearchRegex(regex::Regex);
+
+impl SearchRegex {
+    pub fn new(pattern: &str, case_sensitive: bool) -> Result<Self, regex::Error> {
+        regex::RegexBuilder::new(pattern)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map(Self)
+    }
+
+    /// Non-empty matches within one line, as `(start_col, end_col)` char
+    /// columns. Empty matches (`^`, `a*`) have nothing to highlight or
+    /// replace and are left out.
+    pub fn matches_in_line(&self, line: &str) -> Vec<(usize, usize)> {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let mut matches = Vec::new();
+        // Byte offsets turn into char columns counting from the last match
+        let (mut byte, mut col) = (0, 0);
+        for found in self.0.find_iter(line) {
+            if found.is_empty() {
+                continue;
+            }
+            col += line[byte..found.start()].chars().count();
+            let start_col = col;
+            col += found.as_str().chars().count();
+            byte = found.end();
+            matches.push((start_col, col));
+        }
+        matches
+    }
+
+    /// `replacement` for the match starting at char column `start_col` of
+    /// `line`, with capture group references expanded
+    pub fn expand(&self, line: &str, start_col: usize, replacement: &str) -> String {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let start = line
+            .char_indices()
+            .nth(start_col)
+            .map_or(line.len(), |(byte, _)| byte);
+        let mut expanded = String::new();
+        if let Some(captures) = self.0.captures_at(line, start) {
+            captures.expand(replacement, &mut expanded);
+        }
+        expanded
+    }
+}
+
+/// Compared by pattern; case sensitivity is compared by `SearchHighlight`
+impl PartialEq for SearchRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for SearchRegex {}
+
+/// Part of a document that find/replace is limited to ("find in selection")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchScope {
+    pub document_id: DocumentId,
+    pub start: Position,
+    pub end: Position,
+}
+
+impl SearchScope {
+    /// Whether a match from `start` to `end` lies within the scope
+    pub fn contains(&self, start: Position, end: Position) -> bool {
+        self.start <= start && end <= self.end
+    }
+}
+
 /// Which field is focused in find/replace
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum FindReplaceField {
@@ -150,6 +337,19 @@ pub enum FindReplaceField {
     Replace,
 }
 
+/// The gist of a regex error, for showing under a search input
+fn regex_error_message(error: &regex::Error) -> String {
+    match error {
+        regex::Error::Syntax(message) => message
+            .lines()
+            .last()
+            .unwrap_or("invalid pattern")
+            .trim_start_matches("error: ")
+            .to_string(),
+        _ => "pattern too large".to_string(),
+    }
+}
+
 /// State for the find/replace modal
 #[derive(Debug, Clone)]
 pub struct FindReplaceState {
@@ -163,6 +363,35 @@ pub struct FindReplaceState {
     pub replace_mode: bool,
     /// Case-sensitive search
     pub case_sensitive: bool,
+    /// The query is a regular expression, and the replacement can refer to
+    /// its capture groups
+    pub regex: bool,
+    /// Limit matching and replace-all to `scope`
+    pub in_selection: bool,
+    /// The selection when the modal was opened
+    pub scope: Option<SearchScope>,
+    /// Matches of the current query, shown under the inputs
+    pub match_counts: FindMatchCounts,
+    /// Matches of the query in `matches_document`, in document order. Kept
+    /// up to date as the query changes, for highlighting while typing.
+    pub matches: Vec<(Position, Position)>,
+    pub matches_document: Option<DocumentId>,
+    /// Index into `matches` of the match the primary selection is on
+    pub current_match: Option<usize>,
+    /// Where the cursor was when the modal opened: typing in the query
+    /// selects the first match from here, and going back to no matches
+    /// returns the cursor here
+    pub origin: Option<Position>,
+}
+
+/// How often the find query matches in the open documents
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FindMatchCounts {
+    /// Matches in the focused document, within the scope
+    pub current: usize,
+    /// `(name, matches)` of the other open documents that have any, most
+    /// matches first
+    pub other_files: Vec<(String, usize)>,
 }
 
 impl Default for FindReplaceState {
@@ -176,11 +405,71 @@ impl Default for FindReplaceState {
             focused_field: FindReplaceField::Query,
             replace_mode: false,
             case_sensitive: false,
+            regex: false,
+            in_selection: false,
+            scope: None,
+            match_counts: FindMatchCounts::default(),
+            matches: Vec::new(),
+            matches_document: None,
+            current_match: None,
+            origin: None,
         }
     }
 }
 
 impl FindReplaceState {
+    /// The query and options as a search term. An invalid regular
+    /// expression gives an empty query, which matches nothing.
+    pub fn search_highlight(&self) -> SearchHighlight {
+        let mut query = self.query();
+        let regex = match self
+            .regex
+            .then(|| SearchRegex::new(&query, self.case_sensitive))
+        {
+            Some(Ok(regex)) => Some(regex),
+            Some(Err(_)) => {
+                query.clear();
+                None
+            }
+            None => None,
+        };
+        SearchHighlight {
+            query,
+            case_sensitive: self.case_sensitive,
+            whole_word: false,
+            regex,
+            scope: self.active_scope(),
+        }
+    }
+
+    /// Why the query isn't a valid regular expression, in regex mode
+    pub fn regex_error(&self) -> Option<String> {
+        if !self.regex {
+            return None;
+        }
+        let error = SearchRegex::new(&self.query(), self.case_sensitive).err()?;
+        Some(regex_error_message(&error))
+    }
+
+    /// Cached matches on line `line` of document `document_id`
+    pub fn matches_on_line(
+        &self,
+        document_id: Option<DocumentId>,
+        line: usize,
+    ) -> &[(Position, Position)] {
+        if document_id.is_none() || document_id != self.matches_document {
+            return &[];
+        }
+        let first = self.matches.partition_point(|(start, _)| start.line < line);
+        let end = first + self.matches[first..].partition_point(|(start, _)| start.line == line);
+        &self.matches[first..end]
+    }
+
+    /// The scope searches are limited to, when "in selection" is on
+    pub fn active_scope(&self) -> Option<SearchScope> {
+        self.scope.filter(|_| self.in_selection)
+    }
+
     /// Get the query text (convenience accessor)
     pub fn query(&self) -> String {
         self.query_editable.text()
@@ -293,31 +582,40 @@ impl FileMatch {
     }
 }
 
-/// State for the file finder modal (fuzzy file search)
+/// Lines of the highlighted file shown beside the Quick Open results
+pub const QUICK_OPEN_PREVIEW_LINES: usize = 12;
+
+/// State for the Quick Open modal (fuzzy file search)
 #[derive(Debug, Clone)]
-pub struct FileFinderState {
+pub struct QuickOpenState {
     /// Editable state for the search input field
     pub editable: EditableState<StringBuffer>,
     /// Index of selected file in filtered results
     pub selected_index: usize,
     /// Filtered and ranked file results
     pub results: Vec<FileMatch>,
-    /// All files in workspace (cached when modal opens)
-    pub all_files: Vec<PathBuf>,
-    /// Workspace root path (for computing relative paths)
-    pub workspace_root: PathBuf,
+    /// Workspace files (indexed when the modal opens)
+    pub index: crate::quick_open::FileIndex,
+    /// Recently opened files, most recent first (for ranking)
+    pub recent: Vec<PathBuf>,
+    /// Leading lines of the highlighted file, `None` for binary or
+    /// unreadable files
+    pub preview: Option<Vec<String>>,
 }
 
-impl FileFinderState {
-    /// Create a new file finder state with the given files
-    pub fn new(all_files: Vec<PathBuf>, workspace_root: PathBuf) -> Self {
-        Self {
+impl QuickOpenState {
+    /// Create a new Quick Open state over the given index
+    pub fn new(index: crate::quick_open::FileIndex, recent: Vec<PathBuf>) -> Self {
+        let mut state = Self {
             editable: EditableState::new(StringBuffer::new(), EditConstraints::single_line()),
             selected_index: 0,
             results: Vec::new(),
-            all_files,
-            workspace_root,
-        }
+            index,
+            recent,
+            preview: None,
+        };
+        state.update_results();
+        state
     }
 
     /// Get the search input text
@@ -329,6 +627,31 @@ impl FileFinderState {
     pub fn set_input(&mut self, text: &str) {
         self.editable.set_content(text);
     }
+
+    /// Re-rank the results for the current query and highlight the first
+    pub fn update_results(&mut self) {
+        self.results = self.index.search(&self.input(), &self.recent);
+        self.select(0);
+    }
+
+    /// Lines the modal reserves for the preview. Kept while the highlighted
+    /// file has no preview so the modal doesn't jump in size.
+    pub fn preview_rows(&self) -> usize {
+        if self.results.is_empty() {
+            0
+        } else {
+            QUICK_OPEN_PREVIEW_LINES
+        }
+    }
+
+    /// Highlight the result at `index` and load its preview
+    pub fn select(&mut self, index: usize) {
+        self.selected_index = index.min(self.results.len().saturating_sub(1));
+        self.preview = self
+            .results
+            .get(self.selected_index)
+            .and_then(|file| crate::quick_open::read_preview(&file.path, QUICK_OPEN_PREVIEW_LINES));
+    }
 }
 
 /// State for the recent files modal
@@ -391,15 +714,679 @@ impl RecentFilesState {
     }
 }
 
+/// Answer to the unsaved changes prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsavedChangesChoice {
+    Save,
+    DontSave,
+    Cancel,
+}
+
+impl UnsavedChangesChoice {
+    /// Choices in button order
+    pub const ALL: [UnsavedChangesChoice; 3] = [
+        UnsavedChangesChoice::Save,
+        UnsavedChangesChoice::DontSave,
+        UnsavedChangesChoice::Cancel,
+    ];
+
+    /// Button label
+    pub fn label(&self) -> &'static str {
+        match self {
+            UnsavedChangesChoice::Save => "Save",
+            UnsavedChangesChoice::DontSave => "Don't Save",
+            UnsavedChangesChoice::Cancel => "Cancel",
+        }
+    }
+
+    /// Neighbouring choice, clamped to the first/last button
+    pub fn step(self, forward: bool) -> Self {
+        step_choice(&Self::ALL, self, forward)
+    }
+}
+
+/// Neighbour of `current` in a row of prompt buttons, clamped to the ends
+fn step_choice<T: Copy + PartialEq>(all: &[T], current: T, forward: bool) -> T {
+    let index = all.iter().position(|c| *c == current).unwrap_or(0);
+    let index = if forward {
+        (index + 1).min(all.len() - 1)
+    } else {
+        index.saturating_sub(1)
+    };
+    all[index]
+}
+
+/// State for the unsaved changes prompt shown before closing a modified tab
+#[derive(Debug, Clone)]
+pub struct UnsavedChangesState {
+    /// Tab whose close is waiting on the answer
+    pub tab_id: TabId,
+    /// Display name of the modified document
+    pub document_name: String,
+    /// Highlighted button (confirmed with Enter)
+    pub selected: UnsavedChangesChoice,
+}
+
+impl UnsavedChangesState {
+    pub fn new(tab_id: TabId, document_name: String) -> Self {
+        Self {
+            tab_id,
+            document_name,
+            selected: UnsavedChangesChoice::Save,
+        }
+    }
+}
+
+/// Answer to the prompt shown when saving a file that was deleted on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeletedFileChoice {
+    Recreate,
+    SaveAs,
+    Cancel,
+}
+
+impl DeletedFileChoice {
+    /// Choices in button order
+    pub const ALL: [DeletedFileChoice; 3] = [
+        DeletedFileChoice::Recreate,
+        DeletedFileChoice::SaveAs,
+        DeletedFileChoice::Cancel,
+    ];
+
+    /// Button label
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeletedFileChoice::Recreate => "Recreate",
+            DeletedFileChoice::SaveAs => "Save As…",
+            DeletedFileChoice::Cancel => "Cancel",
+        }
+    }
+
+    /// Neighbouring choice, clamped to the first/last button
+    pub fn step(self, forward: bool) -> Self {
+        step_choice(&Self::ALL, self, forward)
+    }
+}
+
+/// State for the prompt shown when saving a file that was deleted on disk
+#[derive(Debug, Clone)]
+pub struct DeletedFileState {
+    /// Document being saved
+    pub document_id: DocumentId,
+    /// Where the file used to be
+    pub path: PathBuf,
+    /// Highlighted button (confirmed with Enter)
+    pub selected: DeletedFileChoice,
+}
+
+impl DeletedFileState {
+    pub fn new(document_id: DocumentId, path: PathBuf) -> Self {
+        Self {
+            document_id,
+            path,
+            selected: DeletedFileChoice::Recreate,
+        }
+    }
+}
+
+/// Answer to the prompt shown when the file of a modified document was
+/// changed by another program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangedChoice {
+    Reload,
+    Keep,
+}
+
+impl FileChangedChoice {
+    /// Choices in button order
+    pub const ALL: [FileChangedChoice; 2] = [FileChangedChoice::Reload, FileChangedChoice::Keep];
+
+    /// Button label
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileChangedChoice::Reload => "Reload",
+            FileChangedChoice::Keep => "Keep My Changes",
+        }
+    }
+
+    /// Neighbouring choice, clamped to the first/last button
+    pub fn step(self, forward: bool) -> Self {
+        step_choice(&Self::ALL, self, forward)
+    }
+}
+
+/// State for the prompt shown when the file of a modified document was
+/// changed by another program
+#[derive(Debug, Clone)]
+pub struct FileChangedState {
+    /// Document whose file changed
+    pub document_id: DocumentId,
+    /// The changed file
+    pub path: PathBuf,
+    /// What the file holds now, loaded on Reload
+    pub content: String,
+    /// Highlighted button (confirmed with Enter)
+    pub selected: FileChangedChoice,
+}
+
+impl FileChangedState {
+    pub fn new(document_id: DocumentId, path: PathBuf, content: String) -> Self {
+        Self {
+            document_id,
+            path,
+            content,
+            selected: FileChangedChoice::Keep,
+        }
+    }
+}
+
+/// Answer to the prompt shown when opening a workspace for the first time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceTrustChoice {
+    Trust,
+    Restrict,
+}
+
+impl WorkspaceTrustChoice {
+    /// Choices in button order
+    pub const ALL: [WorkspaceTrustChoice; 2] =
+        [WorkspaceTrustChoice::Trust, WorkspaceTrustChoice::Restrict];
+
+    /// Button label
+    pub fn label(&self) -> &'static str {
+        match self {
+            WorkspaceTrustChoice::Trust => "Trust",
+            WorkspaceTrustChoice::Restrict => "Restricted Mode",
+        }
+    }
+
+    /// Neighbouring choice, clamped to the first/last button
+    pub fn step(self, forward: bool) -> Self {
+        step_choice(&Self::ALL, self, forward)
+    }
+}
+
+/// State for the prompt asking whether a workspace may run commands
+#[derive(Debug, Clone)]
+pub struct WorkspaceTrustState {
+    /// Workspace the decision is for
+    pub root: PathBuf,
+    /// Highlighted button (confirmed with Enter)
+    pub selected: WorkspaceTrustChoice,
+}
+
+impl WorkspaceTrustState {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            // Safe default: Enter alone doesn't grant trust
+            selected: WorkspaceTrustChoice::Restrict,
+        }
+    }
+}
+
+/// State for the modal that renames a file in place
+#[derive(Debug, Clone)]
+pub struct RenameFileState {
+    /// File being renamed
+    pub path: PathBuf,
+    /// Editable state for the new file name
+    pub editable: EditableState<StringBuffer>,
+}
+
+impl RenameFileState {
+    /// Start from the current file name with everything before the
+    /// extension selected
+    pub fn new(path: PathBuf) -> Self {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let stem_len = std::path::Path::new(&name)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().chars().count())
+            .unwrap_or(0);
+
+        let mut editable = EditableState::new(StringBuffer::new(), EditConstraints::single_line());
+        editable.set_content(&name);
+        *editable.selection_mut() = crate::editable::Selection::new(
+            crate::editable::Position::zero(),
+            crate::editable::Position::new(0, stem_len),
+        );
+        editable.cursor_mut().column = stem_len;
+        Self { path, editable }
+    }
+
+    /// Get the input text (convenience accessor)
+    pub fn input(&self) -> String {
+        self.editable.text()
+    }
+
+    /// Where the file should be renamed to, or `None` if the name is empty,
+    /// unchanged, or tries to leave the folder
+    pub fn target(&self) -> Option<PathBuf> {
+        let name = self.input();
+        let name = name.trim();
+        if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+            return None;
+        }
+        let target = self.path.with_file_name(name);
+        (target != self.path).then_some(target)
+    }
+}
+
+/// State for the modal that creates a new, empty file
+#[derive(Debug, Clone)]
+pub struct CreateFileState {
+    /// Folder the entered path is relative to
+    pub dir: PathBuf,
+    /// Editable state for the path of the new file
+    pub editable: EditableState<StringBuffer>,
+}
+
+impl CreateFileState {
+    /// Start with `relative_path` filled in, cursor at the end
+    pub fn new(dir: PathBuf, relative_path: &str) -> Self {
+        let mut editable = EditableState::new(StringBuffer::new(), EditConstraints::single_line());
+        editable.set_content(relative_path);
+        editable.cursor_mut().column = relative_path.chars().count();
+        Self { dir, editable }
+    }
+
+    /// Get the input text (convenience accessor)
+    pub fn input(&self) -> String {
+        self.editable.text()
+    }
+
+    /// The file to create, or `None` if no path was entered
+    pub fn target(&self) -> Option<PathBuf> {
+        let input = self.input();
+        let input = input.trim();
+        if input.is_empty() || input.ends_with(['/', '\\']) {
+            return None;
+        }
+        Some(self.dir.join(input))
+    }
+}
+
+/// State for the list of notes linking to the focused document
+#[derive(Debug, Clone)]
+pub struct BacklinksState {
+    /// File name of the linked-to note (for the title)
+    pub target_name: String,
+    pub entries: Vec<crate::notes::Backlink>,
+    /// Index of selected entry in the filtered list
+    pub selected_index: usize,
+    /// Editable state for optional filter input
+    pub editable: EditableState<StringBuffer>,
+}
+
+impl BacklinksState {
+    pub fn new(target_name: String, entries: Vec<crate::notes::Backlink>) -> Self {
+        Self {
+            target_name,
+            entries,
+            selected_index: 0,
+            editable: EditableState::new(StringBuffer::new(), EditConstraints::single_line()),
+        }
+    }
+
+    /// Get the filter text
+    pub fn input(&self) -> String {
+        self.editable.text()
+    }
+
+    /// Entries whose path or line contains the filter text
+    pub fn filtered_entries(&self) -> Vec<&crate::notes::Backlink> {
+        let filter = self.input().to_lowercase();
+        self.entries
+            .iter()
+            .filter(|entry| {
+                filter.is_empty()
+                    || entry.display_name.to_lowercase().contains(&filter)
+                    || entry.text.to_lowercase().contains(&filter)
+            })
+            .collect()
+    }
+}
+
+/// State for the list of keymap problems
+#[derive(Debug, Clone)]
+pub struct KeymapDiagnosticsState {
+    pub entries: Vec<crate::keymap::KeymapDiagnostic>,
+    /// Index of selected entry in the filtered list
+    pub selected_index: usize,
+    /// Editable state for optional filter input
+    pub editable: EditableState<StringBuffer>,
+}
+
+impl KeymapDiagnosticsState {
+    pub fn new(entries: Vec<crate::keymap::KeymapDiagnostic>) -> Self {
+        Self {
+            entries,
+            selected_index: 0,
+            editable: EditableState::new(StringBuffer::new(), EditConstraints::single_line()),
+        }
+    }
+
+    /// Get the filter text
+    pub fn input(&self) -> String {
+        self.editable.text()
+    }
+
+    /// Entries whose message contains the filter text
+    pub fn filtered_entries(&self) -> Vec<&crate::keymap::KeymapDiagnostic> {
+        let filter = self.input().to_lowercase();
+        self.entries
+            .iter()
+            .filter(|entry| filter.is_empty() || entry.message.to_lowercase().contains(&filter))
+            .collect()
+    }
+}
+
+/// State for the language picker modal
+#[derive(Debug, Clone)]
+pub struct LanguagePickerState {
+    /// Language of the document when the picker opened
+    pub current: LanguageId,
+    /// Index of selected language in the filtered list
+    pub selected_index: usize,
+    /// Editable state for optional filter input
+    pub editable: EditableState<StringBuffer>,
+}
+
+impl LanguagePickerState {
+    /// Create with the document's current language selected
+    pub fn new(current: LanguageId) -> Self {
+        Self {
+            current,
+            selected_index: LanguageId::ALL
+                .iter()
+                .position(|&lang| lang == current)
+                .unwrap_or(0),
+            editable: EditableState::new(StringBuffer::new(), EditConstraints::single_line()),
+        }
+    }
+
+    /// Get the filter text
+    pub fn input(&self) -> String {
+        self.editable.text()
+    }
+
+    /// Languages whose name contains the filter text
+    pub fn filtered_languages(&self) -> Vec<LanguageId> {
+        let filter = self.input().to_lowercase();
+        LanguageId::ALL
+            .iter()
+            .copied()
+            .filter(|lang| {
+                filter.is_empty() || lang.display_name().to_lowercase().contains(&filter)
+            })
+            .collect()
+    }
+}
+
+/// A symbol listed by the breadcrumb dropdown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BreadcrumbSymbol {
+    /// Kind label and name, as the breadcrumb shows it
+    pub label: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// State for the dropdown of symbols next to a breadcrumb
+#[derive(Debug, Clone)]
+pub struct BreadcrumbSymbolsState {
+    /// Name of the symbol containing the listed ones, or the file name for
+    /// top-level symbols (for the title)
+    pub parent: String,
+    pub entries: Vec<BreadcrumbSymbol>,
+    /// Index of selected entry in the filtered list
+    pub selected_index: usize,
+    /// Editable state for optional filter input
+    pub editable: EditableState<StringBuffer>,
+}
+
+impl BreadcrumbSymbolsState {
+    /// Create with the entry at `current` (the clicked crumb) selected
+    pub fn new(parent: String, entries: Vec<BreadcrumbSymbol>, current: usize) -> Self {
+        Self {
+            parent,
+            entries,
+            selected_index: current,
+            editable: EditableState::new(StringBuffer::new(), EditConstraints::single_line()),
+        }
+    }
+
+    /// Get the filter text
+    pub fn input(&self) -> String {
+        self.editable.text()
+    }
+
+    /// Entries whose label contains the filter text
+    pub fn filtered_entries(&self) -> Vec<&BreadcrumbSymbol> {
+        let filter = self.input().to_lowercase();
+        self.entries
+            .iter()
+            .filter(|entry| filter.is_empty() || entry.label.to_lowercase().contains(&filter))
+            .collect()
+    }
+}
+
+/// A symbol listed by the Go to Symbol modal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GotoSymbolEntry {
+    /// Kind label and name, e.g. `fn render`
+    pub label: String,
+    /// Symbol name, matched against the query
+    pub name: String,
+    /// Name of the enclosing symbol, if any
+    pub container: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// State for the Go to Symbol modal
+#[derive(Debug, Clone)]
+pub struct GotoSymbolState {
+    /// Every symbol of the document, in document order
+    pub entries: Vec<GotoSymbolEntry>,
+    /// Index of selected entry in the filtered list
+    pub selected_index: usize,
+    /// Editable state for the query input
+    pub editable: EditableState<StringBuffer>,
+}
+
+impl GotoSymbolState {
+    /// List every symbol of `outline`, parents before their children
+    pub fn new(outline: &crate::outline::OutlineData) -> Self {
+        fn collect(
+            nodes: &[crate::outline::OutlineNode],
+            container: Option<&str>,
+            entries: &mut Vec<GotoSymbolEntry>,
+        ) {
+            for node in nodes {
+                entries.push(GotoSymbolEntry {
+                    label: format!("{} {}", node.kind.label(), node.name),
+                    name: node.name.clone(),
+                    container: container.map(str::to_string),
+                    line: node.range.start_line,
+                    column: node.range.start_col,
+                });
+                collect(&node.children, Some(&node.name), entries);
+            }
+        }
+
+        let mut entries = Vec::new();
+        collect(&outline.roots, None, &mut entries);
+        Self {
+            entries,
+            selected_index: 0,
+            editable: EditableState::new(StringBuffer::new(), EditConstraints::single_line()),
+        }
+    }
+
+    /// Get the query text
+    pub fn input(&self) -> String {
+        self.editable.text()
+    }
+
+    /// Entries whose name fuzzy-matches the query, best match first. Equal
+    /// scores keep document order; an empty query lists every symbol.
+    pub fn filtered_entries(&self) -> Vec<&GotoSymbolEntry> {
+        use nucleo_matcher::{Config, Matcher, Utf32Str};
+
+        let query = self.input();
+        if query.is_empty() {
+            return self.entries.iter().collect();
+        }
+
+        let mut matcher = Matcher::new(Config::DEFAULT);
+        let mut query_buf = Vec::new();
+        let needle = Utf32Str::new(&query, &mut query_buf);
+        let mut haystack_buf = Vec::new();
+        let mut matches: Vec<(&GotoSymbolEntry, u16)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let haystack = Utf32Str::new(&entry.name, &mut haystack_buf);
+                Some((entry, matcher.fuzzy_match(haystack, needle)?))
+            })
+            .collect();
+        matches.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        matches.into_iter().map(|(entry, _)| entry).collect()
+    }
+}
+
+/// Completion popup shown under the cursor of an editor
+#[derive(Debug, Clone)]
+pub struct CompletionState {
+    /// Editor the popup belongs to
+    pub editor_id: EditorId,
+    /// Line of the text being completed
+    pub line: usize,
+    /// Char column where the text replaced on accept starts
+    pub start_column: usize,
+    /// Text from `start_column` to the cursor the items were made for
+    pub query: String,
+    pub items: Vec<crate::completion::CompletionItem>,
+    /// Index of the highlighted item
+    pub selected_index: usize,
+    /// First item shown (the list scrolls to keep the selection visible)
+    pub scroll_offset: usize,
+}
+
+impl CompletionState {
+    /// Most items shown at once
+    pub const MAX_VISIBLE_ITEMS: usize = 8;
+
+    pub fn selected_item(&self) -> Option<&crate::completion::CompletionItem> {
+        self.items.get(self.selected_index)
+    }
+
+    /// Move the selection by `delta`, wrapping around the ends
+    pub fn select_offset(&mut self, delta: isize) {
+        if self.items.is_empty() {
+            return;
+        }
+        let len = self.items.len() as isize;
+        self.selected_index = (self.selected_index as isize + delta).rem_euclid(len) as usize;
+        if self.selected_index < self.scroll_offset {
+            self.scroll_offset = self.selected_index;
+        } else if self.selected_index >= self.scroll_offset + Self::MAX_VISIBLE_ITEMS {
+            self.scroll_offset = self.selected_index + 1 - Self::MAX_VISIBLE_ITEMS;
+        }
+    }
+}
+
+/// Image most recently pasted into a Markdown document, which "Rename
+/// Pasted Image" renames (updating the link to it)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PastedImage {
+    pub document_id: DocumentId,
+    pub path: PathBuf,
+}
+
+/// State for a context menu: palette commands listed at the mouse
+#[derive(Debug, Clone)]
+pub struct ContextMenuState {
+    /// Window position of the menu's top-left corner (the click)
+    pub position: (usize, usize),
+    pub items: Vec<MenuItem>,
+    /// Index into `items` of the highlighted command (never a separator)
+    pub selected_index: usize,
+}
+
+impl ContextMenuState {
+    /// Open at `position` with the first command highlighted
+    pub fn new(position: (usize, usize), items: Vec<MenuItem>) -> Self {
+        let selected_index = items
+            .iter()
+            .position(|item| matches!(item, MenuItem::Command { .. }))
+            .unwrap_or(0);
+        Self {
+            position,
+            items,
+            selected_index,
+        }
+    }
+
+    /// Highlight the next (or previous) command, skipping separators and
+    /// stopping at the ends
+    pub fn step(&mut self, forward: bool) {
+        let next = if forward {
+            (self.selected_index + 1..self.items.len()).find(|&i| self.is_command(i))
+        } else {
+            (0..self.selected_index).rev().find(|&i| self.is_command(i))
+        };
+        if let Some(index) = next {
+            self.selected_index = index;
+        }
+    }
+
+    /// Highlight the item at `index` if it is a command
+    pub fn select(&mut self, index: usize) {
+        if self.is_command(index) {
+            self.selected_index = index;
+        }
+    }
+
+    /// The highlighted command
+    pub fn selected_command(&self) -> Option<CommandId> {
+        match self.items.get(self.selected_index)? {
+            MenuItem::Command { id, .. } => Some(*id),
+            MenuItem::Standard(_) | MenuItem::Separator => None,
+        }
+    }
+
+    fn is_command(&self, index: usize) -> bool {
+        matches!(self.items.get(index), Some(MenuItem::Command { .. }))
+    }
+}
+
 /// Union of all modal states
 #[derive(Debug, Clone)]
 pub enum ModalState {
     CommandPalette(CommandPaletteState),
     GotoLine(GotoLineState),
-    FindReplace(FindReplaceState),
+    FindReplace(Box<FindReplaceState>),
     ThemePicker(ThemePickerState),
-    FileFinder(FileFinderState),
+    QuickOpen(QuickOpenState),
     RecentFiles(RecentFilesState),
+    EditReview(EditReviewState),
+    UnsavedChanges(UnsavedChangesState),
+    DeletedFile(DeletedFileState),
+    FileChanged(FileChangedState),
+    RenameFile(RenameFileState),
+    CreateFile(CreateFileState),
+    Backlinks(BacklinksState),
+    KeymapDiagnostics(KeymapDiagnosticsState),
+    LanguagePicker(LanguagePickerState),
+    BreadcrumbSymbols(BreadcrumbSymbolsState),
+    GotoSymbol(GotoSymbolState),
+    WorkspaceTrust(WorkspaceTrustState),
+    ContextMenu(ContextMenuState),
 }
 
 impl ModalState {
@@ -410,8 +1397,21 @@ impl ModalState {
             ModalState::GotoLine(_) => ModalId::GotoLine,
             ModalState::FindReplace(_) => ModalId::FindReplace,
             ModalState::ThemePicker(_) => ModalId::ThemePicker,
-            ModalState::FileFinder(_) => ModalId::FileFinder,
+            ModalState::QuickOpen(_) => ModalId::QuickOpen,
             ModalState::RecentFiles(_) => ModalId::RecentFiles,
+            ModalState::EditReview(_) => ModalId::EditReview,
+            ModalState::UnsavedChanges(_) => ModalId::UnsavedChanges,
+            ModalState::DeletedFile(_) => ModalId::DeletedFile,
+            ModalState::FileChanged(_) => ModalId::FileChanged,
+            ModalState::RenameFile(_) => ModalId::RenameFile,
+            ModalState::CreateFile(_) => ModalId::CreateFile,
+            ModalState::Backlinks(_) => ModalId::Backlinks,
+            ModalState::KeymapDiagnostics(_) => ModalId::KeymapDiagnostics,
+            ModalState::LanguagePicker(_) => ModalId::LanguagePicker,
+            ModalState::BreadcrumbSymbols(_) => ModalId::BreadcrumbSymbols,
+            ModalState::GotoSymbol(_) => ModalId::GotoSymbol,
+            ModalState::WorkspaceTrust(_) => ModalId::WorkspaceTrust,
+            ModalState::ContextMenu(_) => ModalId::ContextMenu,
         }
     }
 }
@@ -427,6 +1427,8 @@ pub struct DropState {
     pub hovered_files: Vec<PathBuf>,
     /// Whether files are currently being dragged over the window
     pub is_hovering: bool,
+    /// Files of the current drop received so far (one event arrives per file)
+    pub dropped_files: Vec<PathBuf>,
 }
 
 impl DropState {
@@ -444,18 +1446,42 @@ impl DropState {
         self.is_hovering = false;
     }
 
+    /// Record a dropped file. Returns the whole drop once every hovered file
+    /// has arrived (right away for a file that was never hovered).
+    pub fn take_drop(&mut self, path: PathBuf) -> Option<Vec<PathBuf>> {
+        let was_hovered = self.hovered_files.contains(&path);
+        self.hovered_files.retain(|hovered| *hovered != path);
+        self.dropped_files.push(path);
+        if was_hovered && !self.hovered_files.is_empty() {
+            return None;
+        }
+        self.cancel_hover();
+        Some(std::mem::take(&mut self.dropped_files))
+    }
+
     /// Get display text for the hover overlay
     pub fn display_text(&self) -> String {
-        match self.hovered_files.len() {
-            0 => String::new(),
-            1 => {
-                let filename = self.hovered_files[0]
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_else(|| "file".to_string());
-                format!("Drop to open: {}", filename)
-            }
-            n => format!("Drop to open {} files", n),
+        let folders = self
+            .hovered_files
+            .iter()
+            .filter(|path| path.is_dir())
+            .count();
+        let files = self.hovered_files.len() - folders;
+        let first_name = || {
+            self.hovered_files
+                .iter()
+                .find(|path| path.is_dir() == (folders > 0))
+                .and_then(|path| path.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "file".to_string())
+        };
+        match (folders, files) {
+            (0, 0) => String::new(),
+            (0, 1) => format!("Drop to open: {}", first_name()),
+            (0, n) => format!("Drop to open {} files", n),
+            (_, 0) => format!("Drop to open folder: {}", first_name()),
+            (_, 1) => format!("Drop to open folder {} and 1 file", first_name()),
+            (_, n) => format!("Drop to open folder {} and {} files", first_name(), n),
         }
     }
 }
@@ -524,8 +1550,9 @@ pub enum ScrollbarDragAxis {
     Horizontal,
 }
 
-/// State for dragging an editor tab (reorder within a group, or move to
-/// another group by dropping on its tab bar).
+/// State for dragging an editor tab (reorder within a group, move to
+/// another group by dropping on its tab bar, or split a group by dropping on
+/// its edge).
 #[derive(Debug, Clone, Copy)]
 pub struct TabDragState {
     /// The tab being dragged
@@ -536,6 +1563,12 @@ pub struct TabDragState {
     pub current: (f64, f64),
     /// Whether the drag threshold has been exceeded
     pub active: bool,
+    /// Group edge under the cursor; dropping there moves the tab into a new
+    /// split on that side
+    pub split_target: Option<(
+        crate::model::editor_area::GroupId,
+        crate::model::editor_area::DropEdge,
+    )>,
 }
 
 /// State for scrollbar thumb dragging
@@ -578,6 +1611,9 @@ pub struct OutlinePanelState {
     /// Collapsed node keys: (kind, range) for unique identification
     pub collapsed:
         std::collections::HashSet<(crate::outline::OutlineKind, crate::outline::OutlineRange)>,
+    /// Document, cursor line and outline revision the selection last
+    /// followed, so it only moves when one of them changes
+    pub followed: Option<(DocumentId, usize, u64)>,
 }
 
 impl OutlinePanelState {
@@ -602,6 +1638,175 @@ impl OutlinePanelState {
     }
 }
 
+/// UI state for the TODO list panel
+#[derive(Debug, Clone, Default)]
+pub struct TodoPanelState {
+    /// Scan results grouped by file
+    pub files: Vec<crate::todo::TodoFile>,
+    /// Index of selected row (file headers and items, flattened)
+    pub selected_index: Option<usize>,
+    /// Scroll offset (in rows)
+    pub scroll_offset: usize,
+    /// Incremented for every scan request so stale results can be dropped
+    pub generation: u64,
+    /// Whether a scan is in flight
+    pub is_scanning: bool,
+    /// Whether at least one scan has completed
+    pub has_scanned: bool,
+}
+
+impl TodoPanelState {
+    /// Total number of rows (file headers plus items)
+    pub fn row_count(&self) -> usize {
+        crate::todo::row_count(&self.files)
+    }
+
+    /// Resolve a flattened row index
+    pub fn row(&self, index: usize) -> Option<crate::todo::TodoRow<'_>> {
+        crate::todo::row_at(&self.files, index)
+    }
+}
+
+/// UI state for the workspace search panel
+#[derive(Debug, Clone)]
+pub struct SearchPanelState {
+    /// Query input field
+    pub input: EditableState<StringBuffer>,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// The query is a regular expression
+    pub regex: bool,
+    /// Results of the last search, grouped by file in path order
+    pub files: Vec<crate::project_search::SearchFileResult>,
+    /// Query of the last search, shown in the summary
+    pub searched_query: Option<String>,
+    /// How the last search went, once it finished
+    pub summary: Option<crate::project_search::SearchSummary>,
+    /// Why the last query couldn't be searched (invalid regex)
+    pub error: Option<String>,
+    /// Index of selected row (file headers and matches, flattened)
+    pub selected_index: Option<usize>,
+    /// Scroll offset (in rows)
+    pub scroll_offset: usize,
+    /// Incremented for every search so stale results can be dropped
+    pub generation: u64,
+    /// Whether a search is in flight
+    pub is_searching: bool,
+    /// Cancellation flag shared with the in-flight search thread
+    cancel: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
+}
+
+impl Default for SearchPanelState {
+    fn default() -> Self {
+        Self {
+            input: EditableState::new(StringBuffer::new(), EditConstraints::single_line()),
+            case_sensitive: false,
+            whole_word: false,
+            regex: false,
+            files: Vec::new(),
+            searched_query: None,
+            summary: None,
+            error: None,
+            selected_index: None,
+            scroll_offset: 0,
+            generation: 0,
+            is_searching: false,
+            cancel: None,
+        }
+    }
+}
+
+impl SearchPanelState {
+    /// Clear the results and start a new search, cancelling any search
+    /// still in flight. Returns the new generation and its cancellation flag.
+    pub fn begin_search(
+        &mut self,
+        query: String,
+    ) -> (u64, std::sync::Arc<std::sync::atomic::AtomicBool>) {
+        self.cancel_search();
+        self.generation = self.generation.wrapping_add(1);
+        self.is_searching = true;
+        self.files.clear();
+        self.searched_query = Some(query);
+        self.summary = None;
+        self.error = None;
+        self.selected_index = None;
+        self.scroll_offset = 0;
+        let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        self.cancel = Some(cancel.clone());
+        (self.generation, cancel)
+    }
+
+    /// Signal the in-flight search (if any) to stop
+    pub fn cancel_search(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+        self.is_searching = false;
+    }
+
+    /// The query and options as a search (without a scope). An invalid
+    /// regex is returned as the error message.
+    pub fn search(&self) -> Result<SearchHighlight, String> {
+        let query = self.input.text();
+        let regex = if self.regex {
+            let pattern = if self.whole_word {
+                format!(r"\b(?:{})\b", query)
+            } else {
+                query.clone()
+            };
+            Some(
+                SearchRegex::new(&pattern, self.case_sensitive)
+                    .map_err(|e| regex_error_message(&e))?,
+            )
+        } else {
+            None
+        };
+        Ok(SearchHighlight {
+            query,
+            case_sensitive: self.case_sensitive,
+            whole_word: self.whole_word,
+            regex,
+            scope: None,
+        })
+    }
+
+    /// Total number of rows (file headers plus matches)
+    pub fn row_count(&self) -> usize {
+        crate::project_search::row_count(&self.files)
+    }
+
+    /// Resolve a flattened row index
+    pub fn row(&self, index: usize) -> Option<crate::project_search::SearchRow<'_>> {
+        crate::project_search::row_at(&self.files, index)
+    }
+}
+
+/// UI state for the log viewer panel
+#[derive(Debug, Clone, Default)]
+pub struct LogPanelState {
+    /// Copy of the log buffer, oldest first
+    pub entries: Vec<crate::tracing::LogEntry>,
+    /// `crate::tracing::log_generation()` when `entries` was copied
+    pub generation: u64,
+    /// Rows scrolled up from the newest entry (0 follows the tail)
+    pub scroll_offset: usize,
+}
+
+/// Files still being written by "Save All", for the summary shown once
+/// they are all done
+#[derive(Debug, Clone, Default)]
+pub struct SaveAllProgress {
+    /// Documents whose save hasn't completed yet, with their names
+    pub pending: Vec<(DocumentId, String)>,
+    /// Number of files saved so far
+    pub saved: usize,
+    /// Files that failed to save, with the error
+    pub failed: Vec<String>,
+    /// Modified documents left alone (untitled or deleted on disk)
+    pub skipped: usize,
+}
+
 /// UI state - status messages and cursor animation
 #[derive(Debug, Clone)]
 pub struct UiState {
@@ -611,20 +1816,47 @@ pub struct UiState {
     pub status_bar: StatusBar,
     /// Transient message with auto-expiry
     pub transient_message: Option<TransientMessage>,
+    /// Keystrokes of a chord waiting for its next key, for the status bar
+    pub pending_chord: Option<String>,
     /// Whether the cursor is currently visible (for blinking)
     pub cursor_visible: bool,
     /// Timestamp of last cursor blink state change
     pub last_cursor_blink: Instant,
+    /// Whether the window has keyboard focus
+    pub window_focused: bool,
+    /// Whether the window is hidden (minimized or fully covered)
+    pub window_occluded: bool,
     /// Whether a file is currently being loaded
     pub is_loading: bool,
     /// Whether a file is currently being saved
     pub is_saving: bool,
+    /// Tab to close once the save started from the unsaved changes prompt
+    /// completes
+    pub close_after_save: Option<TabId>,
+    /// "Save All" waiting for its saves to complete
+    pub save_all: Option<SaveAllProgress>,
     /// Currently active modal (if any)
     pub active_modal: Option<ModalState>,
     /// Last command palette state (persisted for quick re-execution)
     pub last_command_palette: Option<CommandPaletteState>,
     /// Last find/replace state (persisted for quick re-use)
     pub last_find_replace: Option<FindReplaceState>,
+    /// Current search term, highlighted in the editor
+    pub search_highlight: Option<SearchHighlight>,
+    /// Trigram index of the open documents, used by find
+    pub search_index: crate::search_index::SearchIndex,
+    /// Power source and render speed, for `performance_mode: auto`
+    pub performance: crate::performance::PerformanceState,
+    /// Last image pasted into a Markdown document
+    pub last_pasted_image: Option<PastedImage>,
+    /// Files open when the previous run crashed, until restored
+    pub crashed_session: Option<crate::crash::EditorSummary>,
+    /// Log viewer panel
+    pub log_panel: LogPanelState,
+    /// Newer release found by the update checker
+    pub available_update: Option<crate::update_check::Release>,
+    /// Conflicts and load errors found in the keymap files
+    pub keymap_diagnostics: Vec<crate::keymap::KeymapDiagnostic>,
     /// File drag-and-drop state
     pub drop_state: DropState,
     /// Splitter (resize handle) drag state
@@ -637,6 +1869,10 @@ pub struct UiState {
     pub scrollbar_drag: Option<ScrollbarDragState>,
     /// Editor tab drag state (reorder within a group / move between groups)
     pub tab_drag: Option<TabDragState>,
+    /// Completion popup in the focused editor
+    pub completion: Option<CompletionState>,
+    /// Whether git blame annotations are shown
+    pub show_blame: bool,
     /// Which UI region has keyboard focus
     pub focus: FocusTarget,
     /// Which UI region the mouse is currently hovering over
@@ -653,19 +1889,34 @@ impl UiState {
             status_message: String::new(),
             status_bar: StatusBar::new(),
             transient_message: None,
+            pending_chord: None,
             cursor_visible: true,
             last_cursor_blink: Instant::now(),
+            window_focused: true,
+            window_occluded: false,
             is_loading: false,
             is_saving: false,
+            close_after_save: None,
+            save_all: None,
             active_modal: None,
             last_command_palette: None,
             last_find_replace: None,
+            search_highlight: None,
+            search_index: Default::default(),
+            performance: Default::default(),
+            last_pasted_image: None,
+            crashed_session: None,
+            log_panel: LogPanelState::default(),
+            available_update: None,
+            keymap_diagnostics: Vec::new(),
             drop_state: DropState::default(),
             splitter_drag: None,
             sidebar_resize: None,
             dock_resize: None,
             scrollbar_drag: None,
             tab_drag: None,
+            completion: None,
+            show_blame: false,
             focus: FocusTarget::Editor,
             hover: HoverRegion::None,
             previous_cursor_lines: Vec::new(),
@@ -749,6 +2000,12 @@ impl UiState {
         self.last_cursor_blink = Instant::now();
     }
 
+    /// Whether the window is focused and visible. An inactive window doesn't
+    /// blink the cursor or schedule timer wake-ups.
+    pub fn is_window_active(&self) -> bool {
+        self.window_focused && !self.window_occluded
+    }
+
     /// Update cursor blink state based on elapsed time
     /// Returns true if the state changed (needs redraw)
     pub fn update_cursor_blink(&mut self, blink_interval: Duration) -> bool {
@@ -766,6 +2023,15 @@ impl UiState {
         self.status_message = message.into();
     }
 
+    /// Scope of the current search: the open find modal's, or else that of
+    /// the highlighted search term
+    pub fn search_scope(&self) -> Option<SearchScope> {
+        match &self.active_modal {
+            Some(ModalState::FindReplace(state)) => state.active_scope(),
+            _ => self.search_highlight.as_ref()?.scope,
+        }
+    }
+
     /// Check if the UI is busy (loading or saving)
     pub fn is_busy(&self) -> bool {
         self.is_loading || self.is_saving
@@ -783,6 +2049,60 @@ mod tests {
     use super::*;
     use crate::recent_files::{RecentEntry, RecentFiles};
 
+    #[test]
+    fn search_highlight_matches_whole_words_and_case() {
+        let word = SearchHighlight {
+            query: "foo".to_string(),
+            case_sensitive: true,
+            whole_word: true,
+            regex: None,
+            scope: None,
+        };
+        assert_eq!(
+            word.matches_in_line("foo foobar (foo) Foo\n"),
+            vec![(0, 3), (12, 15)]
+        );
+
+        let text = SearchHighlight {
+            query: "aa".to_string(),
+            case_sensitive: false,
+            whole_word: false,
+            regex: None,
+            scope: None,
+        };
+        assert_eq!(text.matches_in_line("AAAa"), vec![(0, 2), (2, 4)]);
+    }
+
+    #[test]
+    fn search_regex_matches_in_chars_and_expands_captures() {
+        let regex = SearchRegex::new(r"(\w+)=(\d+)", true).unwrap();
+        let line = "é a=1 bb=22\n";
+        assert_eq!(regex.matches_in_line(line), vec![(2, 5), (6, 11)]);
+        assert_eq!(regex.expand(line, 6, "$2=$1"), "22=bb");
+
+        // Empty matches are never reported
+        let empty = SearchRegex::new("x*", true).unwrap();
+        assert_eq!(empty.matches_in_line("abxxc"), vec![(2, 4)]);
+
+        let insensitive = SearchRegex::new("foo", false).unwrap();
+        assert_eq!(insensitive.matches_in_line("FOO"), vec![(0, 3)]);
+    }
+
+    #[test]
+    fn find_replace_state_reports_invalid_regex() {
+        let mut state = FindReplaceState {
+            regex: true,
+            ..Default::default()
+        };
+        state.query_editable.set_content("(unclosed");
+        assert!(state.regex_error().is_some());
+        assert!(state.search_highlight().query.is_empty());
+
+        state.query_editable.set_content("(closed)");
+        assert_eq!(state.regex_error(), None);
+        assert!(state.search_highlight().regex.is_some());
+    }
+
     fn make_entry(path: &str, workspace: Option<&str>) -> RecentEntry {
         RecentEntry {
             path: PathBuf::from(path),
@@ -8,6 +8,8 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+use crate::config::WorkspaceConfig;
+use crate::editable::{Cursor, EditConstraints, EditableState, Position, Selection, StringBuffer};
 use crate::util::{visible_tree_count, visible_tree_row_at_index, visible_tree_row_matching};
 
 use super::ScaledMetrics;
@@ -289,7 +291,7 @@ impl FileTree {
     }
 
     /// Check if a path should be ignored
-    fn should_ignore(path: &Path) -> bool {
+    pub(crate) fn should_ignore(path: &Path) -> bool {
         let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
         // Check exact matches
@@ -495,6 +497,80 @@ pub struct Workspace {
 
     /// Scroll offset in the file tree (in items)
     pub scroll_offset: usize,
+
+    /// Overrides of the user's config from the workspace's config file
+    pub config: WorkspaceConfig,
+
+    /// Name being typed in the tree for a new or renamed entry
+    pub name_edit: Option<NameEdit>,
+}
+
+/// What a name typed in the file tree is for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameEditKind {
+    NewFile { parent: PathBuf },
+    NewFolder { parent: PathBuf },
+    Rename { path: PathBuf },
+}
+
+/// A file or folder name edited inline in the file tree
+#[derive(Debug, Clone)]
+pub struct NameEdit {
+    pub kind: NameEditKind,
+    pub input: EditableState<StringBuffer>,
+}
+
+impl NameEdit {
+    /// Start with an empty name for a new entry, or with the current name
+    /// for a rename, its stem selected so typing keeps the extension
+    pub fn new(kind: NameEditKind) -> Self {
+        let mut input = EditableState::new(StringBuffer::new(), EditConstraints::single_line());
+        if let NameEditKind::Rename { path } = &kind {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            input.set_content(&name);
+            let stem = match name.rfind('.') {
+                Some(dot) if dot > 0 => name[..dot].chars().count(),
+                _ => name.chars().count(),
+            };
+            *input.cursor_mut() = Cursor::new(0, stem);
+            *input.selection_mut() = Selection::new(Position::new(0, 0), Position::new(0, stem));
+        }
+        Self { kind, input }
+    }
+
+    /// Folder the entry is (or will be) in
+    pub fn parent(&self) -> &Path {
+        match &self.kind {
+            NameEditKind::NewFile { parent } | NameEditKind::NewFolder { parent } => parent,
+            NameEditKind::Rename { path } => path.parent().unwrap_or(path),
+        }
+    }
+
+    /// Path for the typed name, or `None` if it is empty, unchanged or leaves
+    /// the folder. New entries may name subfolders (`dir/file.rs`).
+    pub fn target(&self) -> Option<PathBuf> {
+        let name = self.input.text();
+        let name = name.trim();
+        let nested = !matches!(self.kind, NameEditKind::Rename { .. });
+        let valid = !name.is_empty()
+            && !name.starts_with(['/', '\\'])
+            && !name.ends_with(['/', '\\'])
+            && (nested || !name.contains(['/', '\\']))
+            && name
+                .split(['/', '\\'])
+                .all(|part| part != "." && part != "..");
+        if !valid {
+            return None;
+        }
+        let target = self.parent().join(name);
+        match &self.kind {
+            NameEditKind::Rename { path } => (&target != path).then_some(target),
+            _ => Some(target),
+        }
+    }
 }
 
 impl Workspace {
@@ -510,6 +586,8 @@ impl Workspace {
         let mut expanded_folders = HashSet::new();
         expanded_folders.insert(root.clone());
 
+        let config = WorkspaceConfig::load(&root);
+
         Ok(Self {
             root,
             expanded_folders,
@@ -518,6 +596,8 @@ impl Workspace {
             sidebar_visible: true,
             sidebar_width_logical: metrics.sidebar_default_width_logical,
             scroll_offset: 0,
+            config,
+            name_edit: None,
         })
     }
 
@@ -576,6 +656,23 @@ impl Workspace {
         self.file_tree.count_visible(&self.expanded_folders)
     }
 
+    /// Visible row of the input for a new entry's name: right below its
+    /// folder, pushing the rows after it down
+    pub fn new_entry_row(&self) -> Option<usize> {
+        let edit = self.name_edit.as_ref()?;
+        if matches!(edit.kind, NameEditKind::Rename { .. }) {
+            return None;
+        }
+        let parent = edit.parent();
+        let index = visible_tree_row_matching(
+            &self.file_tree.roots,
+            |node| node.is_dir && self.is_expanded(&node.path),
+            |node| node.path == parent,
+        )?
+        .index;
+        Some(index + 1)
+    }
+
     /// Get the depth of a path relative to workspace root
     pub fn depth(&self, path: &Path) -> usize {
         path.strip_prefix(&self.root)
@@ -673,6 +770,8 @@ mod tests {
             sidebar_visible: true,
             sidebar_width_logical: metrics.sidebar_default_width_logical,
             scroll_offset: 0,
+            config: WorkspaceConfig::default(),
+            name_edit: None,
         };
 
         let folder = Path::new("/test/src");
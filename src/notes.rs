@@ -0,0 +1,459 @@
+//! Links between Markdown notes
+//!
+//! Two link forms are recognized: regular Markdown links with a relative
+//! target (`[text](notes/idea.md)`) and wiki-links (`[[idea]]`,
+//! `[[idea|shown text]]`, `[[idea#heading]]`). Markdown links resolve
+//! against the folder of the document they appear in; wiki-links name a
+//! file anywhere in the workspace, falling back to the document's folder.
+//!
+//! Backlinks are found by scanning workspace Markdown files on a background
+//! thread (see `Cmd::FindBacklinks`). Open documents are scanned from an
+//! in-memory snapshot so unsaved edits show up.
+//!
+//! Task list items (`- [ ] todo`, `- [x] done`) are counted for the status
+//! bar's task progress segment.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::util::normalize_path;
+
+/// Workspace files larger than this are skipped
+const MAX_SCAN_FILE_BYTES: u64 = 1024 * 1024;
+
+/// A link to another note, as written in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteLink {
+    /// Target of `[text](target)`, without the optional title
+    Markdown(String),
+    /// Page name of `[[name]]`, without alias or heading
+    Wiki(String),
+}
+
+/// A link on a line: char columns `start..end` cover the whole link syntax
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkSpan {
+    pub start: usize,
+    pub end: usize,
+    pub link: NoteLink,
+}
+
+/// All links on a line, in order
+pub fn links_in_line(line: &str) -> Vec<LinkSpan> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '[' {
+            i += 1;
+            continue;
+        }
+        let parsed = if chars.get(i + 1) == Some(&'[') {
+            parse_wiki_link(&chars, i)
+        } else {
+            parse_markdown_link(&chars, i)
+        };
+        match parsed {
+            Some(span) => {
+                i = span.end;
+                spans.push(span);
+            }
+            None => i += 1,
+        }
+    }
+    spans
+}
+
+/// The link under char `column`, counting the position just after a link
+/// as on it (where the cursor ends up after typing one)
+pub fn link_at(line: &str, column: usize) -> Option<NoteLink> {
+    links_in_line(line)
+        .into_iter()
+        .find(|span| span.start <= column && column <= span.end)
+        .map(|span| span.link)
+}
+
+/// `[[name]]`, `[[name|alias]]` or `[[name#heading]]` starting at `start`
+fn parse_wiki_link(chars: &[char], start: usize) -> Option<LinkSpan> {
+    let inner_start = start + 2;
+    let close = (inner_start..chars.len().saturating_sub(1))
+        .find(|&i| chars[i] == ']' && chars[i + 1] == ']')?;
+    let inner: String = chars[inner_start..close].iter().collect();
+    let name = inner.split(['|', '#']).next().unwrap_or_default().trim();
+    if name.is_empty() || inner.contains('[') {
+        return None;
+    }
+    Some(LinkSpan {
+        start,
+        end: close + 2,
+        link: NoteLink::Wiki(name.to_string()),
+    })
+}
+
+/// `[text](target)` or `[text](<target> "title")` starting at `start`
+fn parse_markdown_link(chars: &[char], start: usize) -> Option<LinkSpan> {
+    let mut depth = 0;
+    let mut text_end = None;
+    for (i, &ch) in chars.iter().enumerate().skip(start) {
+        match ch {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    text_end = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let text_end = text_end?;
+    if chars.get(text_end + 1) != Some(&'(') {
+        return None;
+    }
+    let close = (text_end + 2..chars.len()).find(|&i| chars[i] == ')')?;
+    let destination: String = chars[text_end + 2..close].iter().collect();
+    let destination = destination.trim();
+    let target = match destination.strip_prefix('<') {
+        Some(rest) => rest.split('>').next().unwrap_or_default(),
+        None => destination.split_whitespace().next().unwrap_or_default(),
+    };
+    if target.is_empty() {
+        return None;
+    }
+    Some(LinkSpan {
+        start,
+        end: close + 1,
+        link: NoteLink::Markdown(target.to_string()),
+    })
+}
+
+/// Whether `path` is a Markdown file
+pub fn is_markdown_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+/// The file a link in the document at `document_path` points to, or `None`
+/// for links that are not to a local file (URLs, in-page anchors). The file
+/// does not need to exist.
+pub fn resolve_link(
+    link: &NoteLink,
+    document_path: &Path,
+    workspace_files: &[PathBuf],
+) -> Option<PathBuf> {
+    let dir = document_path.parent().unwrap_or(Path::new(""));
+    match link {
+        NoteLink::Markdown(target) => {
+            if target.starts_with('#') || target.contains("://") || target.starts_with("mailto:") {
+                return None;
+            }
+            let path = target.split(['#', '?']).next().unwrap_or_default();
+            let path = percent_decode(path);
+            if path.is_empty() {
+                return None;
+            }
+            Some(normalize_path(&dir.join(path)))
+        }
+        NoteLink::Wiki(name) => {
+            let file_name = if Path::new(name).extension().is_some() {
+                name.clone()
+            } else {
+                format!("{}.md", name)
+            };
+            let wanted = Path::new(&file_name);
+            let matches_name = |path: &&PathBuf| {
+                path.to_string_lossy()
+                    .to_lowercase()
+                    .ends_with(&format!("/{}", file_name.to_lowercase()))
+            };
+            // Prefer a note next to the document, then the shortest path
+            let found = workspace_files
+                .iter()
+                .filter(matches_name)
+                .min_by_key(|path| (path.parent() != Some(dir), path.components().count()));
+            Some(normalize_path(
+                &found.cloned().unwrap_or_else(|| dir.join(wanted)),
+            ))
+        }
+    }
+}
+
+/// Decode `%XX` escapes (e.g. `%20` for spaces in link targets)
+pub fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// A link to the target note found in another file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backlink {
+    pub path: PathBuf,
+    /// Workspace-relative path shown in the list
+    pub display_name: String,
+    /// 0-indexed line of the link
+    pub line: usize,
+    /// 0-indexed char column where the link starts
+    pub column: usize,
+    /// The line the link is on, trimmed
+    pub text: String,
+}
+
+/// Everything a background backlink scan needs
+#[derive(Debug, Clone, Default)]
+pub struct BacklinkScanRequest {
+    /// Note to find links to
+    pub target: PathBuf,
+    /// Workspace root, used for relative display names
+    pub workspace_root: Option<PathBuf>,
+    /// Open Markdown documents as (path, text), scanned from memory
+    pub open_documents: Vec<(PathBuf, String)>,
+    /// All workspace files (Markdown ones not open are read from disk;
+    /// the full list resolves wiki-links)
+    pub workspace_files: Vec<PathBuf>,
+}
+
+/// Find links to `request.target` in other Markdown files, open documents
+/// first, then workspace files sorted by path
+pub fn find_backlinks(request: BacklinkScanRequest) -> Vec<Backlink> {
+    let target = normalize_path(&request.target);
+    let root = request.workspace_root.as_deref();
+    let mut seen = HashSet::new();
+    let mut backlinks = Vec::new();
+
+    let mut scan = |path: &Path, text: &str, backlinks: &mut Vec<Backlink>| {
+        let normalized = normalize_path(path);
+        if normalized == target || !seen.insert(normalized) {
+            return;
+        }
+        for (line, content) in text.lines().enumerate() {
+            for span in links_in_line(content) {
+                let resolved = resolve_link(&span.link, path, &request.workspace_files);
+                if resolved.as_deref() == Some(target.as_path()) {
+                    backlinks.push(Backlink {
+                        path: path.to_path_buf(),
+                        display_name: display_name_for(path, root),
+                        line,
+                        column: span.start,
+                        text: content.trim().to_string(),
+                    });
+                }
+            }
+        }
+    };
+
+    for (path, text) in &request.open_documents {
+        scan(path, text, &mut backlinks);
+    }
+
+    let mut workspace_files: Vec<&PathBuf> = request
+        .workspace_files
+        .iter()
+        .filter(|path| is_markdown_path(path))
+        .collect();
+    workspace_files.sort();
+    for path in workspace_files {
+        let Some(text) = read_scannable_file(path) else {
+            continue;
+        };
+        scan(path, &text, &mut backlinks);
+    }
+
+    backlinks
+}
+
+fn display_name_for(path: &Path, root: Option<&Path>) -> String {
+    root.and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn read_scannable_file(path: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_SCAN_FILE_BYTES {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
+/// Checked and total task list items in a note
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Whether `line` is a task list item, and if so whether it is checked
+pub fn task_state(line: &str) -> Option<bool> {
+    let rest = line.trim_start();
+    let marker_len = if rest.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let delimiter = rest[digits..].chars().next();
+        if digits == 0 || !matches!(delimiter, Some('.' | ')')) {
+            return None;
+        }
+        digits + 1
+    };
+    let item = &rest[marker_len..];
+    let content = item.trim_start_matches(' ');
+    if content.len() == item.len() {
+        return None;
+    }
+    let checked = match content.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    content[3..]
+        .chars()
+        .next()
+        .is_none_or(char::is_whitespace)
+        .then_some(checked)
+}
+
+/// Count task list items outside fenced code blocks; `None` when there are
+/// none
+pub fn task_progress<'a>(
+    lines: impl Iterator<Item = std::borrow::Cow<'a, str>>,
+) -> Option<TaskProgress> {
+    let mut progress = TaskProgress { done: 0, total: 0 };
+    let mut in_fence = false;
+    for line in lines {
+        if crate::markdown::is_fence(&line) {
+            in_fence = !in_fence;
+        } else if let Some(checked) = task_state(&line).filter(|_| !in_fence) {
+            progress.total += 1;
+            progress.done += checked as usize;
+        }
+    }
+    (progress.total > 0).then_some(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_markdown_and_wiki_links_on_a_line() {
+        let spans = links_in_line("See [the plan](plans/q3.md \"Q3\") and [[Ideas|ideas]]!");
+        assert_eq!(
+            spans,
+            vec![
+                LinkSpan {
+                    start: 4,
+                    end: 32,
+                    link: NoteLink::Markdown("plans/q3.md".into()),
+                },
+                LinkSpan {
+                    start: 37,
+                    end: 52,
+                    link: NoteLink::Wiki("Ideas".into()),
+                },
+            ]
+        );
+        assert_eq!(
+            link_at("[a](b.md) x", 9),
+            Some(NoteLink::Markdown("b.md".into()))
+        );
+        assert_eq!(link_at("[a](b.md) x", 10), None);
+        assert!(links_in_line("[not a link] (x)").is_empty());
+    }
+
+    #[test]
+    fn resolves_links_relative_to_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("notes/deep")).unwrap();
+        let doc = root.join("notes/today.md");
+        let deep = root.join("notes/deep/Ideas.md");
+        std::fs::write(&deep, "").unwrap();
+        let files = vec![doc.clone(), deep.clone()];
+
+        let link = NoteLink::Markdown("../My%20Plan.md#goals".into());
+        assert_eq!(
+            resolve_link(&link, &doc, &files),
+            Some(root.join("My Plan.md"))
+        );
+        let url = NoteLink::Markdown("https://example.com/a.md".into());
+        assert_eq!(resolve_link(&url, &doc, &files), None);
+
+        let wiki = NoteLink::Wiki("ideas".into());
+        assert_eq!(resolve_link(&wiki, &doc, &files), Some(deep));
+        let missing = NoteLink::Wiki("New Note".into());
+        assert_eq!(
+            resolve_link(&missing, &doc, &files),
+            Some(root.join("notes/New Note.md"))
+        );
+    }
+
+    #[test]
+    fn backlinks_come_from_other_markdown_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let target = root.join("target.md");
+        let on_disk = root.join("b.md");
+        let open = root.join("a.md");
+        std::fs::write(&target, "[[target]] links to itself").unwrap();
+        std::fs::write(&on_disk, "intro\n- see [t](./target.md)\n").unwrap();
+        std::fs::write(&open, "stale on disk").unwrap();
+        std::fs::write(root.join("c.txt"), "[[target]]").unwrap();
+
+        let backlinks = find_backlinks(BacklinkScanRequest {
+            target: target.clone(),
+            workspace_root: Some(root.clone()),
+            open_documents: vec![(open.clone(), "[[Target]]".into())],
+            workspace_files: vec![target, on_disk.clone(), open.clone(), root.join("c.txt")],
+        });
+
+        let found: Vec<_> = backlinks
+            .iter()
+            .map(|b| (b.display_name.as_str(), b.line, b.text.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("a.md", 0, "[[Target]]"),
+                ("b.md", 1, "- see [t](./target.md)")
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_task_items_outside_code_blocks() {
+        assert_eq!(task_state("- [ ] write"), Some(false));
+        assert_eq!(task_state("  * [x] done"), Some(true));
+        assert_eq!(task_state("3. [X] numbered"), Some(true));
+        assert_eq!(task_state("- [ ]"), Some(false));
+        assert_eq!(task_state("-[ ] no space"), None);
+        assert_eq!(task_state("- [link](x.md)"), None);
+        assert_eq!(task_state("[ ] not a list item"), None);
+
+        let text = "# Plan\n- [x] a\n- [ ] b\n```\n- [ ] sample\n```\n1) [x] c\n";
+        let lines = text.lines().map(std::borrow::Cow::Borrowed);
+        assert_eq!(
+            task_progress(lines),
+            Some(TaskProgress { done: 2, total: 3 })
+        );
+        assert_eq!(task_progress(["plain"].into_iter().map(Into::into)), None);
+    }
+}
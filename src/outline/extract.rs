@@ -51,6 +51,18 @@ pub fn extract_outline(
             let flat = extract_yaml_symbols(root, source);
             build_tree_by_containment(flat)
         }
+        LanguageId::Json => {
+            let flat = extract_json_symbols(root, source);
+            build_tree_by_containment(flat)
+        }
+        LanguageId::Toml => {
+            let flat = extract_toml_symbols(root, source);
+            build_tree_by_containment(flat)
+        }
+        LanguageId::Css => {
+            let flat = extract_css_symbols(root, source);
+            build_tree_by_containment(flat)
+        }
         LanguageId::Html => {
             let flat = extract_html_symbols(root, source);
             build_tree_by_containment(flat)
@@ -880,6 +892,118 @@ fn classify_yaml_node<'tree>(
     None
 }
 
+// =============================================================================
+// JSON symbol extraction
+// =============================================================================
+
+fn extract_json_symbols(root: Node, source: &str) -> Vec<FlatSymbol> {
+    let mut symbols = Vec::new();
+    walk_and_collect(root, source, &mut symbols, &classify_json_node);
+    symbols
+}
+
+fn classify_json_node<'tree>(
+    node: Node<'tree>,
+    source: &str,
+    symbols: &mut Vec<FlatSymbol>,
+) -> Option<Vec<Node<'tree>>> {
+    if node.kind() == "pair" {
+        if let Some(key_text) = child_by_field(&node, "key").and_then(|k| node_name(&k, source)) {
+            let name = key_text.trim_matches('"');
+            if !name.is_empty() {
+                symbols.push(flat_sym(OutlineKind::Property, name, &node));
+            }
+        }
+    }
+
+    None
+}
+
+// =============================================================================
+// TOML symbol extraction
+// =============================================================================
+
+fn extract_toml_symbols(root: Node, source: &str) -> Vec<FlatSymbol> {
+    let mut symbols = Vec::new();
+    walk_and_collect(root, source, &mut symbols, &classify_toml_node);
+    symbols
+}
+
+fn classify_toml_node<'tree>(
+    node: Node<'tree>,
+    source: &str,
+    symbols: &mut Vec<FlatSymbol>,
+) -> Option<Vec<Node<'tree>>> {
+    let kind = match node.kind() {
+        "table" | "table_array_element" => OutlineKind::Section,
+        "pair" => OutlineKind::Property,
+        _ => return None,
+    };
+
+    // The key is the first named child: a table header or the left side of a pair
+    let key = node
+        .named_child(0)
+        .filter(|k| matches!(k.kind(), "bare_key" | "quoted_key" | "dotted_key"));
+    if let Some(key_text) = key.and_then(|k| node_name(&k, source)) {
+        let name = key_text.trim();
+        if !name.is_empty() {
+            symbols.push(flat_sym(kind, name, &node));
+        }
+    }
+
+    None
+}
+
+// =============================================================================
+// CSS symbol extraction
+// =============================================================================
+
+fn extract_css_symbols(root: Node, source: &str) -> Vec<FlatSymbol> {
+    let mut symbols = Vec::new();
+    walk_and_collect(root, source, &mut symbols, &classify_css_node);
+    symbols
+}
+
+fn classify_css_node<'tree>(
+    node: Node<'tree>,
+    source: &str,
+    symbols: &mut Vec<FlatSymbol>,
+) -> Option<Vec<Node<'tree>>> {
+    match node.kind() {
+        "rule_set" => {
+            let selectors = node
+                .named_children(&mut node.walk())
+                .find(|child| child.kind() == "selectors");
+            if let Some(text) = selectors.and_then(|s| node_name(&s, source)) {
+                let name = collapse_whitespace(text);
+                if !name.is_empty() {
+                    symbols.push(flat_sym(OutlineKind::Element, &name, &node));
+                }
+            }
+        }
+        "media_statement" | "supports_statement" | "keyframes_statement" | "at_rule" => {
+            // Label at-rules with their prelude, e.g. `@media (max-width: 600px)`
+            let block = node
+                .named_children(&mut node.walk())
+                .find(|child| matches!(child.kind(), "block" | "keyframe_block_list"));
+            let end = block.map_or(node.end_byte(), |b| b.start_byte());
+            if let Some(prelude) = source.get(node.start_byte()..end) {
+                let name = collapse_whitespace(prelude.trim_end_matches(';'));
+                if !name.is_empty() {
+                    symbols.push(flat_sym(OutlineKind::Section, &name, &node));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    None
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 // =============================================================================
 // HTML symbol extraction
 // =============================================================================
@@ -1356,4 +1480,65 @@ mod tests {
         // Empty after @
         assert_eq!(parse_directive_ident("@"), None);
     }
+
+    fn outline(language: tree_sitter::Language, id: LanguageId, source: &str) -> Vec<OutlineNode> {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        extract_outline(&tree, source, id, 0).roots
+    }
+
+    fn names(nodes: &[OutlineNode]) -> Vec<&str> {
+        nodes.iter().map(|n| n.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_json_outline_nests_object_keys() {
+        let roots = outline(
+            tree_sitter_json::LANGUAGE.into(),
+            LanguageId::Json,
+            r#"{"name": "token", "scripts": {"build": "cargo build", "test": "cargo test"}}"#,
+        );
+
+        assert_eq!(names(&roots), ["name", "scripts"]);
+        assert_eq!(roots[1].kind, OutlineKind::Property);
+        assert_eq!(names(&roots[1].children), ["build", "test"]);
+    }
+
+    #[test]
+    fn test_toml_outline_groups_keys_under_tables() {
+        let source = "title = \"x\"\n\n[package]\nname = \"token\"\nedition = \"2021\"\n\n[[bin]]\nname = \"token\"\n\n[profile.release]\nlto = true\n";
+        let roots = outline(
+            tree_sitter_toml_ng::LANGUAGE.into(),
+            LanguageId::Toml,
+            source,
+        );
+
+        assert_eq!(
+            names(&roots),
+            ["title", "package", "bin", "profile.release"]
+        );
+        assert_eq!(roots[1].kind, OutlineKind::Section);
+        assert_eq!(names(&roots[1].children), ["name", "edition"]);
+        assert_eq!(names(&roots[2].children), ["name"]);
+        assert_eq!(names(&roots[3].children), ["lto"]);
+    }
+
+    #[test]
+    fn test_css_outline_lists_rules_and_at_rules() {
+        let source = "body,\n  .app > main { margin: 0; }\n\n@media (max-width: 600px) {\n  .sidebar { display: none; }\n}\n\n@keyframes spin { from { opacity: 0; } }\n";
+        let roots = outline(tree_sitter_css::LANGUAGE.into(), LanguageId::Css, source);
+
+        assert_eq!(
+            names(&roots),
+            [
+                "body, .app > main",
+                "@media (max-width: 600px)",
+                "@keyframes spin"
+            ]
+        );
+        assert_eq!(roots[0].kind, OutlineKind::Element);
+        assert_eq!(roots[1].kind, OutlineKind::Section);
+        assert_eq!(names(&roots[1].children), [".sidebar"]);
+    }
 }
@@ -85,6 +85,16 @@ impl OutlineNode {
     pub fn is_collapsible(&self) -> bool {
         !self.children.is_empty()
     }
+
+    /// Whether `line` is inside this symbol. A heading's range is only its
+    /// own line, so it covers everything below it; a later heading at the
+    /// same level takes over from there.
+    pub fn covers_line(&self, line: usize) -> bool {
+        match self.kind {
+            OutlineKind::Heading { .. } => self.range.start_line <= line,
+            _ => (self.range.start_line..=self.range.end_line).contains(&line),
+        }
+    }
 }
 
 /// Complete outline for a document
@@ -107,4 +117,87 @@ impl OutlineData {
     pub fn is_empty(&self) -> bool {
         self.roots.is_empty()
     }
+
+    /// The symbols `line` is in, outermost first
+    pub fn symbol_path_at(&self, line: usize) -> Vec<&OutlineNode> {
+        let mut path = Vec::new();
+        let mut nodes = &self.roots;
+        // Siblings come in document order, so the last one covering the
+        // line is the right one for headings
+        while let Some(node) = nodes.iter().rev().find(|node| node.covers_line(line)) {
+            path.push(node);
+            nodes = &node.children;
+        }
+        path
+    }
+
+    /// The symbols next to the one at `depth` in `path`, itself included
+    pub fn siblings_at<'a>(&'a self, path: &[&'a OutlineNode], depth: usize) -> &'a [OutlineNode] {
+        match depth.checked_sub(1).and_then(|parent| path.get(parent)) {
+            Some(parent) => &parent.children,
+            None => &self.roots,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(kind: OutlineKind, name: &str, lines: (usize, usize)) -> OutlineNode {
+        OutlineNode {
+            kind,
+            name: name.to_string(),
+            range: OutlineRange {
+                start_line: lines.0,
+                start_col: 0,
+                end_line: lines.1,
+                end_col: 0,
+            },
+            children: Vec::new(),
+        }
+    }
+
+    fn names(path: &[&OutlineNode]) -> Vec<String> {
+        path.iter().map(|node| node.name.clone()).collect()
+    }
+
+    #[test]
+    fn symbol_path_descends_into_the_innermost_symbol() {
+        let mut renderer = node(OutlineKind::Impl, "Renderer", (0, 10));
+        renderer.children = vec![
+            node(OutlineKind::Method, "new", (1, 3)),
+            node(OutlineKind::Method, "render_text_area", (5, 9)),
+        ];
+        let outline = OutlineData {
+            revision: 0,
+            roots: vec![renderer, node(OutlineKind::Function, "main", (12, 14))],
+        };
+
+        let path = outline.symbol_path_at(6);
+        assert_eq!(names(&path), ["Renderer", "render_text_area"]);
+        assert_eq!(outline.siblings_at(&path, 1).len(), 2);
+        assert_eq!(outline.siblings_at(&path, 0).len(), 2);
+
+        // Between the methods only the impl contains the line
+        assert_eq!(names(&outline.symbol_path_at(4)), ["Renderer"]);
+        assert!(outline.symbol_path_at(11).is_empty());
+    }
+
+    #[test]
+    fn headings_cover_the_lines_below_them() {
+        let mut intro = node(OutlineKind::Heading { level: 1 }, "Intro", (0, 0));
+        intro.children = vec![node(OutlineKind::Heading { level: 2 }, "Setup", (3, 3))];
+        let outline = OutlineData {
+            revision: 0,
+            roots: vec![
+                intro,
+                node(OutlineKind::Heading { level: 1 }, "Usage", (8, 8)),
+            ],
+        };
+
+        assert_eq!(names(&outline.symbol_path_at(1)), ["Intro"]);
+        assert_eq!(names(&outline.symbol_path_at(5)), ["Intro", "Setup"]);
+        assert_eq!(names(&outline.symbol_path_at(9)), ["Usage"]);
+    }
 }
@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::model::ScaledMetrics;
+
 /// Position where a dock can be placed
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DockPosition {
@@ -57,6 +59,8 @@ pub enum PanelId {
     TaskRunner,
     AiChat,
     TodoList,
+    SearchResults,
+    Logs,
 }
 
 impl PanelId {
@@ -66,6 +70,8 @@ impl PanelId {
     pub const TASK_RUNNER: PanelId = PanelId::TaskRunner;
     pub const AI_CHAT: PanelId = PanelId::AiChat;
     pub const TODO_LIST: PanelId = PanelId::TodoList;
+    pub const SEARCH_RESULTS: PanelId = PanelId::SearchResults;
+    pub const LOGS: PanelId = PanelId::Logs;
 
     /// Get the display name for this panel
     pub fn display_name(&self) -> &'static str {
@@ -76,6 +82,8 @@ impl PanelId {
             PanelId::TaskRunner => "Tasks",
             PanelId::AiChat => "Chat",
             PanelId::TodoList => "TODOs",
+            PanelId::SearchResults => "Search",
+            PanelId::Logs => "Log",
         }
     }
 
@@ -84,7 +92,11 @@ impl PanelId {
         match self {
             PanelId::FileExplorer => DockPosition::Left,
             PanelId::Outline => DockPosition::Right,
-            PanelId::Terminal | PanelId::TaskRunner | PanelId::TodoList => DockPosition::Bottom,
+            PanelId::Terminal
+            | PanelId::TaskRunner
+            | PanelId::TodoList
+            | PanelId::SearchResults
+            | PanelId::Logs => DockPosition::Bottom,
             PanelId::AiChat => DockPosition::Right,
         }
     }
@@ -107,6 +119,11 @@ pub struct Dock {
 
     /// Size in logical pixels (width for left/right, height for bottom)
     pub size_logical: f32,
+
+    /// Whether the open dock is collapsed to a thin strip of panel icons.
+    /// `size_logical` is kept so restoring returns to the previous size.
+    #[serde(default)]
+    pub is_collapsed: bool,
 }
 
 impl Dock {
@@ -121,6 +138,7 @@ impl Dock {
                 DockPosition::Left | DockPosition::Right => 250.0,
                 DockPosition::Bottom => 200.0,
             },
+            is_collapsed: false,
         }
     }
 
@@ -141,11 +159,32 @@ impl Dock {
         }
     }
 
-    /// Activate a panel by ID, opening the dock if closed
+    /// Activate a panel by ID, opening (and expanding) the dock if needed
     pub fn activate(&mut self, panel_id: PanelId) {
         if let Some(index) = self.panel_ids.iter().position(|id| *id == panel_id) {
             self.active_index = Some(index);
             self.is_open = true;
+            self.is_collapsed = false;
+        }
+    }
+
+    /// Whether `panel_id` is the active panel of an open, expanded dock
+    pub fn is_panel_visible(&self, panel_id: PanelId) -> bool {
+        self.is_open && !self.is_collapsed && self.active_panel() == Some(panel_id)
+    }
+
+    /// Whether this dock can collapse to an icon strip.
+    ///
+    /// The left dock hosts the file explorer sidebar, which is sized by the
+    /// workspace and has no header, so only the right and bottom docks collapse.
+    pub fn can_collapse(&self) -> bool {
+        self.position != DockPosition::Left
+    }
+
+    /// Collapse an expanded dock to its icon strip, or expand a collapsed one
+    pub fn toggle_collapsed(&mut self) {
+        if self.is_open && self.can_collapse() && self.has_panels() {
+            self.is_collapsed = !self.is_collapsed;
         }
     }
 
@@ -159,12 +198,15 @@ impl Dock {
         self.is_open = !self.is_open;
     }
 
-    /// Physical size accounting for scale factor
-    pub fn size(&self, scale_factor: f64) -> f32 {
-        if self.is_open {
-            self.size_logical * scale_factor as f32
-        } else {
+    /// Physical size accounting for scale factor (the icon strip
+    /// thickness when collapsed)
+    pub fn size(&self, metrics: &ScaledMetrics) -> f32 {
+        if !self.is_open {
             0.0
+        } else if self.is_collapsed {
+            metrics.dock_icon_strip_size as f32
+        } else {
+            self.size_logical * metrics.scale_factor as f32
         }
     }
 
@@ -173,15 +215,8 @@ impl Dock {
         self.size_logical = physical_size / scale_factor as f32;
     }
 
-    /// Minimum size in logical pixels
-    pub fn min_size(&self) -> f32 {
-        150.0
-    }
-
-    /// Maximum size as fraction of window dimension
-    pub fn max_size_fraction(&self) -> f32 {
-        0.5
-    }
+    /// Logical pixels a dock grows or shrinks per keyboard resize step
+    pub const KEYBOARD_RESIZE_STEP: f32 = 20.0;
 
     /// Cycle to next panel in this dock
     pub fn next_panel(&mut self) {
@@ -206,6 +241,56 @@ impl Dock {
     pub fn has_panels(&self) -> bool {
         !self.panel_ids.is_empty()
     }
+
+    /// Snapshot of the user-adjustable state, for session persistence
+    pub fn state(&self) -> DockState {
+        DockState {
+            is_open: self.is_open,
+            size_logical: self.size_logical,
+            active_panel: self.active_panel(),
+            is_collapsed: self.is_collapsed,
+        }
+    }
+
+    /// Restore a saved snapshot. Panels that are no longer registered to
+    /// this dock are ignored, and the size is kept above `min_size`.
+    pub fn restore_state(&mut self, state: &DockState, min_size: f32) {
+        self.is_open = state.is_open && self.has_panels();
+        self.is_collapsed = state.is_collapsed && self.can_collapse();
+        self.size_logical = state.size_logical.max(min_size);
+        if let Some(index) = state
+            .active_panel
+            .and_then(|id| self.panel_ids.iter().position(|p| *p == id))
+        {
+            self.active_index = Some(index);
+        }
+    }
+}
+
+/// Persisted state of a single dock
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DockState {
+    pub is_open: bool,
+    pub size_logical: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_panel: Option<PanelId>,
+    #[serde(default)]
+    pub is_collapsed: bool,
+}
+
+/// Persisted state of all docks
+///
+/// Only visibility, size, and the active tab are saved; panel registration
+/// always comes from `DockLayout::default()` so new panels show up after an
+/// upgrade.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DockLayoutState {
+    #[serde(default)]
+    pub left: Option<DockState>,
+    #[serde(default)]
+    pub right: Option<DockState>,
+    #[serde(default)]
+    pub bottom: Option<DockState>,
 }
 
 /// Complete dock layout state
@@ -230,7 +315,11 @@ impl Default for DockLayout {
         // Register default panels
         layout.left.register_panel(PanelId::FILE_EXPLORER);
         layout.right.register_panel(PanelId::OUTLINE);
+        layout.right.register_panel(PanelId::AI_CHAT);
         layout.bottom.register_panel(PanelId::TERMINAL);
+        layout.bottom.register_panel(PanelId::TODO_LIST);
+        layout.bottom.register_panel(PanelId::SEARCH_RESULTS);
+        layout.bottom.register_panel(PanelId::LOGS);
 
         // Left dock (file explorer) is open by default
         layout.left.is_open = true;
@@ -320,13 +409,35 @@ impl DockLayout {
     }
 
     /// Get the total width consumed by side docks (for editor area calculation)
-    pub fn side_docks_width(&self, scale_factor: f64) -> f32 {
-        self.left.size(scale_factor) + self.right.size(scale_factor)
+    pub fn side_docks_width(&self, metrics: &ScaledMetrics) -> f32 {
+        self.left.size(metrics) + self.right.size(metrics)
     }
 
     /// Get the height consumed by bottom dock
-    pub fn bottom_dock_height(&self, scale_factor: f64) -> f32 {
-        self.bottom.size(scale_factor)
+    pub fn bottom_dock_height(&self, metrics: &ScaledMetrics) -> f32 {
+        self.bottom.size(metrics)
+    }
+
+    /// Snapshot visibility, sizes, and active tabs of all docks
+    pub fn state(&self) -> DockLayoutState {
+        DockLayoutState {
+            left: Some(self.left.state()),
+            right: Some(self.right.state()),
+            bottom: Some(self.bottom.state()),
+        }
+    }
+
+    /// Restore a snapshot taken with `state()`; missing docks keep their defaults
+    pub fn restore_state(&mut self, state: &DockLayoutState, min_size: f32) {
+        for (position, dock_state) in [
+            (DockPosition::Left, &state.left),
+            (DockPosition::Right, &state.right),
+            (DockPosition::Bottom, &state.bottom),
+        ] {
+            if let Some(dock_state) = dock_state {
+                self.dock_mut(position).restore_state(dock_state, min_size);
+            }
+        }
     }
 }
 
@@ -393,12 +504,36 @@ mod tests {
 
         // Closed dock has zero size
         dock.is_open = false;
-        assert_eq!(dock.size(1.0), 0.0);
+        assert_eq!(dock.size(&ScaledMetrics::new(1.0)), 0.0);
 
         // Open dock has its size
         dock.is_open = true;
-        assert_eq!(dock.size(1.0), 250.0);
-        assert_eq!(dock.size(2.0), 500.0);
+        assert_eq!(dock.size(&ScaledMetrics::new(1.0)), 250.0);
+        assert_eq!(dock.size(&ScaledMetrics::new(2.0)), 500.0);
+    }
+
+    #[test]
+    fn test_collapsed_dock_uses_icon_strip_size() {
+        let metrics = ScaledMetrics::new(2.0);
+        let mut layout = DockLayout::default();
+        layout.right.activate(PanelId::OUTLINE);
+
+        layout.right.toggle_collapsed();
+        assert!(layout.right.is_collapsed);
+        assert!(!layout.right.is_panel_visible(PanelId::OUTLINE));
+        assert_eq!(
+            layout.right.size(&metrics),
+            metrics.dock_icon_strip_size as f32
+        );
+
+        // Activating a panel restores the previous size
+        layout.right.activate(PanelId::AI_CHAT);
+        assert!(!layout.right.is_collapsed);
+        assert_eq!(layout.right.size(&metrics), 500.0);
+
+        // The file explorer sidebar never collapses
+        layout.left.toggle_collapsed();
+        assert!(!layout.left.is_collapsed);
     }
 
     #[test]
@@ -431,7 +566,11 @@ mod tests {
             layout.find_panel(PanelId::TERMINAL),
             Some(DockPosition::Bottom)
         );
-        assert_eq!(layout.find_panel(PanelId::AI_CHAT), None);
+        assert_eq!(
+            layout.find_panel(PanelId::AI_CHAT),
+            Some(DockPosition::Right)
+        );
+        assert_eq!(layout.find_panel(PanelId::TASK_RUNNER), None);
     }
 
     #[test]
@@ -451,4 +590,44 @@ mod tests {
         assert_eq!(pos, None);
         assert!(!layout.left.is_open);
     }
+
+    #[test]
+    fn test_dock_layout_state_round_trip() {
+        let mut layout = DockLayout::default();
+        layout.left.close();
+        layout.right.activate(PanelId::AI_CHAT);
+        layout.bottom.size_logical = 320.0;
+
+        let json = serde_json::to_string(&layout.state()).unwrap();
+        let state: DockLayoutState = serde_json::from_str(&json).unwrap();
+        let mut restored = DockLayout::default();
+        restored.restore_state(&state, 150.0);
+
+        assert!(!restored.left.is_open);
+        assert!(restored.right.is_open);
+        assert_eq!(restored.right.active_panel(), Some(PanelId::AI_CHAT));
+        assert_eq!(restored.bottom.size_logical, 320.0);
+        assert!(!restored.bottom.is_open);
+    }
+
+    #[test]
+    fn test_restore_state_clamps_size_and_ignores_unknown_panel() {
+        let mut layout = DockLayout::default();
+        layout.restore_state(
+            &DockLayoutState {
+                right: Some(DockState {
+                    is_open: true,
+                    size_logical: 10.0,
+                    active_panel: Some(PanelId::TERMINAL),
+                    is_collapsed: false,
+                }),
+                ..Default::default()
+            },
+            150.0,
+        );
+
+        assert_eq!(layout.right.size_logical, 150.0);
+        assert_eq!(layout.right.active_panel(), Some(PanelId::OUTLINE));
+        assert!(layout.left.is_open);
+    }
 }
@@ -21,4 +21,4 @@
 
 mod dock;
 
-pub use dock::{Dock, DockLayout, DockPosition, PanelId};
+pub use dock::{Dock, DockLayout, DockLayoutState, DockPosition, DockState, PanelId};
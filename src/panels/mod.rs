@@ -26,11 +26,13 @@ pub fn panel_title(panel_id: PanelId) -> &'static str {
 /// Get an icon for a panel (Nerd Font icon)
 pub fn panel_icon(panel_id: PanelId) -> &'static str {
     match panel_id {
-        PanelId::FileExplorer => "󰙅", // file tree
-        PanelId::Outline => "",       // list/outline
-        PanelId::Terminal => "",      // terminal
-        PanelId::TaskRunner => "",    // tasks/play
-        PanelId::AiChat => "",        // chat/comment
-        PanelId::TodoList => "",      // checklist
+        PanelId::FileExplorer => "󰙅",  // file tree
+        PanelId::Outline => "",        // list/outline
+        PanelId::Terminal => "",       // terminal
+        PanelId::TaskRunner => "",     // tasks/play
+        PanelId::AiChat => "",         // chat/comment
+        PanelId::TodoList => "",       // checklist
+        PanelId::SearchResults => "", // magnifier
+        PanelId::Logs => "",          // output
     }
 }
@@ -29,6 +29,8 @@ impl PlaceholderPanel {
             PanelId::TaskRunner => "Task runner coming soon...",
             PanelId::AiChat => "AI chat coming soon...",
             PanelId::TodoList => "TODO list coming soon...",
+            PanelId::SearchResults => "Workspace search coming soon...",
+            PanelId::Logs => "Log viewer coming soon...",
             PanelId::FileExplorer => "File explorer",
         }
     }
@@ -0,0 +1,141 @@
+//! Saving images pasted from the clipboard next to a Markdown document
+//!
+//! The file name comes from the `paste_image_path` setting, a path relative
+//! to the document's folder with `{doc}` and `{timestamp}` placeholders.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::util::UtcDateTime;
+
+/// RGBA pixels read from the clipboard
+#[derive(Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major RGBA, 4 bytes per pixel
+    pub rgba: Vec<u8>,
+}
+
+// Pixel data is far too large to appear in message logs
+impl fmt::Debug for ClipboardImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ClipboardImage({}x{})", self.width, self.height)
+    }
+}
+
+/// Where to save an image pasted into the document at `document_path`,
+/// following `pattern`
+pub fn image_path_for(pattern: &str, document_path: &Path, now: UtcDateTime) -> PathBuf {
+    let doc = document_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "image".to_string());
+    let relative = pattern
+        .replace("{doc}", &doc)
+        .replace("{timestamp}", &now.to_compact());
+    let dir = document_path.parent().unwrap_or(Path::new(""));
+    dir.join(relative)
+}
+
+/// Link target for `image_path` from a document in `document_dir`: relative
+/// when possible, with `/` separators and spaces escaped
+pub fn link_target(document_dir: &Path, image_path: &Path) -> String {
+    let relative = image_path.strip_prefix(document_dir).unwrap_or(image_path);
+    let parts: Vec<String> = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            Component::ParentDir => Some("..".to_string()),
+            Component::RootDir => Some(String::new()),
+            Component::CurDir | Component::Prefix(_) => None,
+        })
+        .collect();
+    parts.join("/").replace(' ', "%20")
+}
+
+/// Encode `image` as PNG at `path`, or at `name-2.png`, `name-3.png`, …
+/// when `path` is taken. Returns the path written.
+pub fn save_png(image: &ClipboardImage, path: &Path) -> io::Result<PathBuf> {
+    let buffer =
+        ::image::RgbaImage::from_raw(image.width as u32, image.height as u32, image.rgba.clone())
+            .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "clipboard image data is truncated",
+            )
+        })?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let path = unique_path(path);
+    buffer
+        .save_with_format(&path, ::image::ImageFormat::Png)
+        .map_err(io::Error::other)?;
+    Ok(path)
+}
+
+fn unique_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut n = 2;
+    loop {
+        let candidate = path.with_file_name(format!("{}-{}{}", stem, n, extension));
+        if !candidate.exists() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_path_fills_placeholders_relative_to_document() {
+        let now = UtcDateTime::from_unix_secs(1_709_214_330);
+        let path = image_path_for(
+            "assets/{doc}-{timestamp}.png",
+            Path::new("/notes/My Day.md"),
+            now,
+        );
+        assert_eq!(path, Path::new("/notes/assets/My Day-20240229-134530.png"));
+        assert_eq!(
+            link_target(Path::new("/notes"), &path),
+            "assets/My%20Day-20240229-134530.png"
+        );
+
+        let up = image_path_for("../img/{doc}.png", Path::new("/notes/a.md"), now);
+        assert_eq!(link_target(Path::new("/notes"), &up), "../img/a.png");
+    }
+
+    #[test]
+    fn save_png_never_overwrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = ClipboardImage {
+            width: 1,
+            height: 1,
+            rgba: vec![255, 0, 0, 255],
+        };
+        let path = dir.path().join("assets/shot.png");
+
+        let first = save_png(&image, &path).unwrap();
+        let second = save_png(&image, &path).unwrap();
+        assert_eq!(first, path);
+        assert_eq!(second, dir.path().join("assets/shot-2.png"));
+        assert!(::image::open(&second).is_ok());
+    }
+}
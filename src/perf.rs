@@ -11,9 +11,7 @@ use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
 #[cfg(debug_assertions)]
-use crate::overlay::{
-    render_overlay_background, render_overlay_border, OverlayAnchor, OverlayConfig,
-};
+use crate::overlay::{OverlayAnchor, OverlayConfig};
 #[cfg(debug_assertions)]
 use crate::theme::Theme;
 #[cfg(debug_assertions)]
@@ -37,7 +35,9 @@ pub enum PerfStage {
     Scrollbars,
     Csv,
     Image,
+    Diff,
     BinaryPlaceholder,
+    Welcome,
     PreviewPane,
     Splitters,
     Sidebar,
@@ -79,7 +79,9 @@ impl PerfStage {
             Self::Scrollbars => "scrollbars",
             Self::Csv => "csv",
             Self::Image => "image",
+            Self::Diff => "diff",
             Self::BinaryPlaceholder => "binary_placeholder",
+            Self::Welcome => "welcome",
             Self::PreviewPane => "preview_pane",
             Self::Splitters => "splitters",
             Self::Sidebar => "sidebar",
@@ -100,7 +102,7 @@ impl PerfStage {
 }
 
 impl PerfStage {
-    pub const ALL: [Self; 28] = [
+    pub const ALL: [Self; 30] = [
         Self::BuildPlan,
         Self::Clear,
         Self::CursorFastPath,
@@ -113,7 +115,9 @@ impl PerfStage {
         Self::Scrollbars,
         Self::Csv,
         Self::Image,
+        Self::Diff,
         Self::BinaryPlaceholder,
+        Self::Welcome,
         Self::PreviewPane,
         Self::Splitters,
         Self::Sidebar,
@@ -202,11 +206,21 @@ impl PerfStage {
                 short_label: "Image",
                 color: 0xFFF7768E,
             },
+            Self::Diff => PerfStageSpec {
+                label: "Diff",
+                short_label: "Diff",
+                color: 0xFF73DACA,
+            },
             Self::BinaryPlaceholder => PerfStageSpec {
                 label: "Binary",
                 short_label: "Binary",
                 color: 0xFFFF9E64,
             },
+            Self::Welcome => PerfStageSpec {
+                label: "Welcome",
+                short_label: "Welcome",
+                color: 0xFFE0AF68,
+            },
             Self::PreviewPane => PerfStageSpec {
                 label: "Preview Pane",
                 short_label: "Preview",
@@ -624,22 +638,22 @@ pub fn render_perf_overlay(
         .with_background(theme.overlay.background.to_argb_u32());
 
     let bounds = config.compute_bounds(width_usize, height_usize);
-    render_overlay_background(
-        frame.buffer_mut(),
-        &bounds,
-        config.background,
-        width_usize,
-        height_usize,
-    );
-
-    if let Some(border_color) = &theme.overlay.border {
-        render_overlay_border(
-            frame.buffer_mut(),
-            &bounds,
+    match &theme.overlay.border {
+        Some(border_color) => frame.draw_bordered_rect(
+            bounds.x,
+            bounds.y,
+            bounds.width,
+            bounds.height,
+            config.background,
             border_color.to_argb_u32(),
-            width_usize,
-            height_usize,
-        );
+        ),
+        None => frame.blend_rect_px(
+            bounds.x,
+            bounds.y,
+            bounds.width,
+            bounds.height,
+            config.background,
+        ),
     }
 
     let text_color = theme.overlay.foreground.to_argb_u32();
@@ -0,0 +1,229 @@
+//! Performance mode: one switch that turns off costly visuals
+//!
+//! With `performance_mode: auto` (the default) the mode turns itself on
+//! while the machine runs on battery, where that can be detected, and once
+//! rendering has been slow for a while. Visual features that cost frame time
+//! or wake-ups check [`AppModel::performance_mode`](crate::model::AppModel)
+//! and stay off while it is on; the cursor, for one, stops blinking.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// `performance_mode` in config.yaml
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PerformanceMode {
+    /// On while on battery or after slow frames
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+/// Why performance mode is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerformanceReason {
+    /// `performance_mode: on`, or toggled on
+    Manual,
+    Battery,
+    SlowFrames,
+}
+
+impl PerformanceReason {
+    /// Status bar text
+    pub fn label(self) -> &'static str {
+        match self {
+            PerformanceReason::Manual => "Performance",
+            PerformanceReason::Battery => "Performance (battery)",
+            PerformanceReason::SlowFrames => "Performance (slow frames)",
+        }
+    }
+}
+
+/// What `auto` mode goes by, reported by the runtime
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerformanceState {
+    /// The machine runs on battery
+    pub on_battery: bool,
+    /// Rendering was slow (see [`FrameMonitor`]); stays set for the session
+    pub slow_frames: bool,
+}
+
+impl PerformanceState {
+    /// Whether performance mode is on under `mode`, and why
+    pub fn reason(&self, mode: PerformanceMode) -> Option<PerformanceReason> {
+        match mode {
+            PerformanceMode::On => Some(PerformanceReason::Manual),
+            PerformanceMode::Off => None,
+            PerformanceMode::Auto if self.slow_frames => Some(PerformanceReason::SlowFrames),
+            PerformanceMode::Auto if self.on_battery => Some(PerformanceReason::Battery),
+            PerformanceMode::Auto => None,
+        }
+    }
+}
+
+/// Frames averaged before judging render speed
+const FRAME_WINDOW: usize = 30;
+
+/// Average frame time over which rendering counts as slow (under 30 fps)
+pub const SLOW_FRAME_TIME: Duration = Duration::from_millis(33);
+
+/// Watches render times for a stretch of slow frames
+#[derive(Debug, Default)]
+pub struct FrameMonitor {
+    frame_times: VecDeque<Duration>,
+    total: Duration,
+    reported: bool,
+}
+
+impl FrameMonitor {
+    /// Record a frame's render time. True the first time the last
+    /// `FRAME_WINDOW` frames average slower than [`SLOW_FRAME_TIME`]; only
+    /// reported once.
+    pub fn record(&mut self, frame_time: Duration) -> bool {
+        if self.reported {
+            return false;
+        }
+        self.frame_times.push_back(frame_time);
+        self.total += frame_time;
+        if self.frame_times.len() > FRAME_WINDOW {
+            if let Some(oldest) = self.frame_times.pop_front() {
+                self.total -= oldest;
+            }
+        }
+        if self.frame_times.len() == FRAME_WINDOW
+            && self.total / FRAME_WINDOW as u32 > SLOW_FRAME_TIME
+        {
+            self.reported = true;
+        }
+        self.reported
+    }
+}
+
+/// How often the runtime checks the power source
+pub const POWER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Whether the machine runs on battery; `None` where that can't be told
+/// (desktops, unsupported platforms). Blocks briefly; called from a
+/// background thread.
+pub fn on_battery() -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        linux_on_battery(std::path::Path::new("/sys/class/power_supply"))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .ok()?;
+        parse_pmset(&String::from_utf8_lossy(&output.stdout))
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// On battery if a battery discharges and no mains adapter is online
+#[cfg(target_os = "linux")]
+fn linux_on_battery(power_supply: &std::path::Path) -> Option<bool> {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok();
+    let mut battery = None;
+    for entry in std::fs::read_dir(power_supply).ok()?.flatten() {
+        let path = entry.path();
+        match read(path.join("type")).as_deref().map(str::trim) {
+            Some("Mains") if read(path.join("online")).as_deref().map(str::trim) == Some("1") => {
+                return Some(false);
+            }
+            Some("Battery") => {
+                let status = read(path.join("status")).unwrap_or_default();
+                battery = Some(battery.unwrap_or(false) || status.trim() == "Discharging");
+            }
+            _ => {}
+        }
+    }
+    battery
+}
+
+/// `pmset -g batt` starts with "Now drawing from 'Battery Power'"
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset(output: &str) -> Option<bool> {
+    let line = output.lines().next()?;
+    if line.contains("'Battery Power'") {
+        Some(true)
+    } else if line.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_frames_are_reported_once() {
+        let mut monitor = FrameMonitor::default();
+        for _ in 0..100 {
+            assert!(!monitor.record(Duration::from_millis(10)));
+        }
+        // One slow frame doesn't tip the average
+        assert!(!monitor.record(Duration::from_millis(500)));
+        let reported = (0..FRAME_WINDOW)
+            .filter(|_| monitor.record(Duration::from_millis(40)))
+            .count();
+        assert_eq!(reported, 1);
+        assert!(!monitor.record(Duration::from_millis(40)));
+    }
+
+    #[test]
+    fn auto_mode_follows_battery_and_frame_times() {
+        let mut state = PerformanceState::default();
+        assert_eq!(state.reason(PerformanceMode::Auto), None);
+        assert_eq!(
+            state.reason(PerformanceMode::On),
+            Some(PerformanceReason::Manual)
+        );
+
+        state.on_battery = true;
+        assert_eq!(
+            state.reason(PerformanceMode::Auto),
+            Some(PerformanceReason::Battery)
+        );
+        assert_eq!(state.reason(PerformanceMode::Off), None);
+
+        assert_eq!(
+            parse_pmset("Now drawing from 'AC Power'\n -InternalBattery-0 100%"),
+            Some(false)
+        );
+        assert_eq!(parse_pmset("Now drawing from 'Battery Power'"), Some(true));
+        assert_eq!(parse_pmset(""), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn linux_power_supplies_are_read_from_sysfs() {
+        let dir = tempfile::tempdir().unwrap();
+        let supply = |name: &str, files: &[(&str, &str)]| {
+            let path = dir.path().join(name);
+            std::fs::create_dir(&path).unwrap();
+            for (file, content) in files {
+                std::fs::write(path.join(file), content).unwrap();
+            }
+        };
+        assert_eq!(linux_on_battery(dir.path()), None, "no battery");
+
+        supply(
+            "BAT0",
+            &[("type", "Battery\n"), ("status", "Discharging\n")],
+        );
+        assert_eq!(linux_on_battery(dir.path()), Some(true));
+
+        supply("AC", &[("type", "Mains\n"), ("online", "1\n")]);
+        assert_eq!(linux_on_battery(dir.path()), Some(false));
+    }
+}
@@ -0,0 +1,602 @@
+//! Search across the files of the workspace for the search panel
+//!
+//! Searching runs on a background thread (see `Cmd::SearchProject`) and
+//! streams results back in batches as files are searched. Files are found by
+//! walking the workspace root, skipping what the file explorer skips and
+//! what `.gitignore` files (and `.git/info/exclude`) ignore. Open documents
+//! are searched from an in-memory snapshot so unsaved edits show up.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::model::editor_area::DocumentId;
+use crate::model::ui::SearchHighlight;
+use crate::model::FileTree;
+
+/// Files larger than this are skipped
+const MAX_SEARCH_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Searching stops after this many matches
+pub const MAX_MATCHES: usize = 10_000;
+
+/// Directory depth the walk descends to, as in the file explorer
+const MAX_DEPTH: usize = 20;
+
+/// Results are sent at least this often while files are being searched
+const BATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Preview text is cut to this many chars
+const MAX_PREVIEW_CHARS: usize = 200;
+
+/// A match starting further into the line than this gets the start of its
+/// preview cut off, so the match stays visible
+const MAX_PREVIEW_LEAD: usize = 40;
+
+/// One match, with the line it is on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// 0-indexed line
+    pub line: usize,
+    /// 0-indexed char column of the match start
+    pub column: usize,
+    /// Char column just past the match end
+    pub end_column: usize,
+    /// The line, without leading whitespace and cut to a readable length
+    pub preview: String,
+    /// Char range of the match within `preview`
+    pub preview_range: (usize, usize),
+}
+
+/// All matches in one file
+#[derive(Debug, Clone)]
+pub struct SearchFileResult {
+    pub path: PathBuf,
+    /// Open document the matches came from, if any
+    pub document_id: Option<DocumentId>,
+    /// Workspace-relative path shown in the panel
+    pub display_name: String,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Snapshot of an open document to search instead of its file on disk
+#[derive(Debug, Clone)]
+pub struct SearchSource {
+    pub document_id: DocumentId,
+    pub path: PathBuf,
+    pub text: String,
+}
+
+/// Everything a background search needs
+#[derive(Debug, Clone)]
+pub struct ProjectSearchRequest {
+    /// Workspace root to walk
+    pub root: PathBuf,
+    /// What to look for (the scope is ignored)
+    pub search: SearchHighlight,
+    /// Open documents with a file path
+    pub open_documents: Vec<SearchSource>,
+}
+
+/// How a finished search went
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchSummary {
+    /// Number of files searched
+    pub files_searched: usize,
+    /// Whether searching stopped at the match limit
+    pub truncated: bool,
+}
+
+/// A flattened panel row: a file header followed by its matches
+#[derive(Debug, Clone, Copy)]
+pub enum SearchRow<'a> {
+    File(&'a SearchFileResult),
+    Match(&'a SearchFileResult, &'a SearchMatch),
+}
+
+/// Total number of panel rows (file headers plus matches)
+pub fn row_count(files: &[SearchFileResult]) -> usize {
+    files.iter().map(|file| file.matches.len() + 1).sum()
+}
+
+/// Resolve a flattened row index
+pub fn row_at(files: &[SearchFileResult], index: usize) -> Option<SearchRow<'_>> {
+    let mut remaining = index;
+    for file in files {
+        if remaining == 0 {
+            return Some(SearchRow::File(file));
+        }
+        remaining -= 1;
+        if remaining < file.matches.len() {
+            return Some(SearchRow::Match(file, &file.matches[remaining]));
+        }
+        remaining -= file.matches.len();
+    }
+    None
+}
+
+/// Total number of matches across files
+pub fn match_count(files: &[SearchFileResult]) -> usize {
+    files.iter().map(|file| file.matches.len()).sum()
+}
+
+// =============================================================================
+// .gitignore
+// =============================================================================
+
+/// One pattern line of an ignore file
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    pattern: Vec<char>,
+    /// `!pattern`: re-include what an earlier pattern ignored
+    negated: bool,
+    /// `pattern/`: only matches directories
+    dir_only: bool,
+    /// A pattern containing a `/` matches the path relative to the ignore
+    /// file's folder; one without matches the name at any depth
+    anchored: bool,
+}
+
+/// The patterns of one ignore file, relative to the folder it applies to
+#[derive(Debug, Clone)]
+struct IgnoreFile {
+    base: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreFile {
+    fn parse(base: &Path, content: &str) -> Self {
+        let rules = content
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                let line = line.strip_prefix('/').unwrap_or(line);
+                (!line.is_empty()).then(|| IgnoreRule {
+                    pattern: line.chars().collect(),
+                    negated,
+                    dir_only,
+                    anchored,
+                })
+            })
+            .collect();
+        Self {
+            base: base.to_path_buf(),
+            rules,
+        }
+    }
+
+    fn read(base: &Path, path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        Some(Self::parse(base, &content)).filter(|file| !file.rules.is_empty())
+    }
+
+    /// Whether the last rule matching `path` ignores it (`Some(true)`),
+    /// re-includes it (`Some(false)`), or no rule matches (`None`)
+    fn matches(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base).ok()?;
+        let relative: Vec<char> = relative
+            .to_string_lossy()
+            .replace(std::path::MAIN_SEPARATOR, "/")
+            .chars()
+            .collect();
+        let name_start = relative
+            .iter()
+            .rposition(|&c| c == '/')
+            .map_or(0, |i| i + 1);
+        let name = &relative[name_start..];
+
+        self.rules.iter().rev().find_map(|rule| {
+            if rule.dir_only && !is_dir {
+                return None;
+            }
+            let target = if rule.anchored { &relative[..] } else { name };
+            glob_match(&rule.pattern, target).then_some(!rule.negated)
+        })
+    }
+}
+
+/// Whether the ignore files in effect (outermost first) ignore `path`.
+/// Rules in deeper ignore files win over those further up.
+fn is_ignored(ignore_files: &[IgnoreFile], path: &Path, is_dir: bool) -> bool {
+    ignore_files
+        .iter()
+        .rev()
+        .find_map(|file| file.matches(path, is_dir))
+        .unwrap_or(false)
+}
+
+/// Match `text` against a gitignore-style glob: `*` and `?` stay within one
+/// path component, `**` spans components, and `[a-z]` / `[!a]` are classes
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) if rest.first() == Some(&'*') => {
+            // `**/` matches zero or more folders; a trailing `**` everything
+            let rest = &rest[1..];
+            let rest = rest.strip_prefix(&['/']).unwrap_or(rest);
+            if rest.is_empty() {
+                return true;
+            }
+            (0..=text.len())
+                .filter(|&i| i == 0 || text[i - 1] == '/')
+                .any(|i| glob_match(rest, &text[i..]))
+        }
+        Some(('*', rest)) => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| glob_match(rest, &text[i..])),
+        Some(('?', rest)) => match text.split_first() {
+            Some((&c, text_rest)) if c != '/' => glob_match(rest, text_rest),
+            _ => false,
+        },
+        Some(('[', rest)) => {
+            let Some((&c, text_rest)) = text.split_first() else {
+                return false;
+            };
+            match match_class(rest, c) {
+                Some((true, after)) => c != '/' && glob_match(after, text_rest),
+                Some((false, _)) => false,
+                // No closing `]`: a literal `[`
+                None => c == '[' && glob_match(rest, text_rest),
+            }
+        }
+        Some(('\\', rest)) if !rest.is_empty() => {
+            text.first() == Some(&rest[0]) && glob_match(&rest[1..], &text[1..])
+        }
+        Some((&p, rest)) => text.first() == Some(&p) && glob_match(rest, &text[1..]),
+    }
+}
+
+/// Match `c` against the class after a `[`. Returns whether it matched and
+/// the pattern after the closing `]`, or None if the class isn't closed.
+fn match_class(pattern: &[char], c: char) -> Option<(bool, &[char])> {
+    let (negated, mut rest) = match pattern.first() {
+        Some('!' | '^') => (true, &pattern[1..]),
+        _ => (false, pattern),
+    };
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        match rest {
+            [] => return None,
+            [']', after @ ..] if !first => return Some((matched != negated, after)),
+            [lo, '-', hi, after @ ..] if *hi != ']' => {
+                matched |= (*lo..=*hi).contains(&c);
+                rest = after;
+            }
+            [ch, after @ ..] => {
+                matched |= *ch == c;
+                rest = after;
+            }
+        }
+        first = false;
+    }
+}
+
+/// Files under `root` that aren't ignored, sorted by path
+pub fn workspace_files(root: &Path) -> Vec<PathBuf> {
+    let mut ignore_files: Vec<IgnoreFile> =
+        IgnoreFile::read(root, &root.join(".git").join("info").join("exclude"))
+            .into_iter()
+            .collect();
+    let mut files = Vec::new();
+    walk(root, 0, &mut ignore_files, &mut files);
+    files.sort();
+    files
+}
+
+fn walk(dir: &Path, depth: usize, ignore_files: &mut Vec<IgnoreFile>, files: &mut Vec<PathBuf>) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    let pushed = match IgnoreFile::read(dir, &dir.join(".gitignore")) {
+        Some(file) => {
+            ignore_files.push(file);
+            true
+        }
+        None => false,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        // Symlinked folders are not followed, so the walk can't loop
+        let is_dir = file_type.is_dir();
+        if FileTree::should_ignore(&path) || is_ignored(ignore_files, &path, is_dir) {
+            continue;
+        }
+        if is_dir {
+            walk(&path, depth + 1, ignore_files, files);
+        } else {
+            files.push(path);
+        }
+    }
+
+    if pushed {
+        ignore_files.pop();
+    }
+}
+
+// =============================================================================
+// Searching
+// =============================================================================
+
+fn read_searchable_file(path: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_SEARCH_FILE_BYTES {
+        return None;
+    }
+    // Non-UTF-8 (binary) files fail here and are skipped
+    std::fs::read_to_string(path).ok()
+}
+
+/// The preview of a line and where the match is in it
+fn preview(line: &str, start: usize, end: usize) -> (String, (usize, usize)) {
+    let line = line.trim_end_matches(['\n', '\r']);
+    let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+    let skip = if start.saturating_sub(indent) > MAX_PREVIEW_LEAD {
+        start - MAX_PREVIEW_LEAD / 2
+    } else {
+        indent.min(start)
+    };
+    let mut preview: String = line.chars().skip(skip).take(MAX_PREVIEW_CHARS).collect();
+    let mut range = (start - skip, end - skip);
+    if skip > indent {
+        preview.insert(0, '\u{2026}');
+        range = (range.0 + 1, range.1 + 1);
+    }
+    let len = preview.chars().count();
+    (preview, (range.0.min(len), range.1.min(len)))
+}
+
+/// Matches of `search` in `text`, at most `limit`
+fn search_text(search: &SearchHighlight, text: &str, limit: usize) -> Vec<SearchMatch> {
+    let mut matches = Vec::new();
+    for (line, content) in text.lines().enumerate() {
+        for (column, end_column) in search.matches_in_line(content) {
+            if matches.len() == limit {
+                return matches;
+            }
+            let (preview, preview_range) = preview(content, column, end_column);
+            matches.push(SearchMatch {
+                line,
+                column,
+                end_column,
+                preview,
+                preview_range,
+            });
+        }
+    }
+    matches
+}
+
+fn display_name_for(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Search the workspace, calling `on_results` with batches of files that
+/// have matches, in path order. Stops early when `cancel` is set.
+pub fn search(
+    request: ProjectSearchRequest,
+    cancel: &AtomicBool,
+    mut on_results: impl FnMut(Vec<SearchFileResult>),
+) -> SearchSummary {
+    let mut summary = SearchSummary::default();
+    if request.search.query.is_empty() {
+        return summary;
+    }
+
+    let mut batch = Vec::new();
+    let mut last_sent = Instant::now();
+    let mut total_matches = 0;
+    for path in workspace_files(&request.root) {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let open = request
+            .open_documents
+            .iter()
+            .find(|source| source.path == path);
+        let (document_id, matches) = match open {
+            Some(source) => (
+                Some(source.document_id),
+                search_text(&request.search, &source.text, MAX_MATCHES - total_matches),
+            ),
+            None => {
+                let Some(text) = read_searchable_file(&path) else {
+                    continue;
+                };
+                let matches = search_text(&request.search, &text, MAX_MATCHES - total_matches);
+                (None, matches)
+            }
+        };
+        summary.files_searched += 1;
+
+        if !matches.is_empty() {
+            total_matches += matches.len();
+            batch.push(SearchFileResult {
+                display_name: display_name_for(&path, &request.root),
+                path,
+                document_id,
+                matches,
+            });
+        }
+        if total_matches >= MAX_MATCHES {
+            summary.truncated = true;
+            break;
+        }
+        if !batch.is_empty() && last_sent.elapsed() >= BATCH_INTERVAL {
+            on_results(std::mem::take(&mut batch));
+            last_sent = Instant::now();
+        }
+    }
+
+    if !batch.is_empty() {
+        on_results(batch);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(query: &str) -> SearchHighlight {
+        SearchHighlight {
+            query: query.to_string(),
+            case_sensitive: false,
+            whole_word: false,
+            regex: None,
+            scope: None,
+        }
+    }
+
+    fn glob(pattern: &str, text: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        glob_match(&pattern, &text)
+    }
+
+    #[test]
+    fn globs_follow_gitignore_rules() {
+        assert!(glob("*.log", "debug.log"));
+        assert!(!glob("*.log", "logs/debug.log"));
+        assert!(glob("debug?.txt", "debug1.txt"));
+        assert!(glob("[a-c]at", "bat"));
+        assert!(!glob("[!a-c]at", "bat"));
+        assert!(glob("**/build", "build"));
+        assert!(glob("**/build", "a/b/build"));
+        assert!(glob("a/**/b", "a/b"));
+        assert!(glob("a/**/b", "a/x/y/b"));
+        assert!(glob("out/**", "out/x/y"));
+        assert!(glob("\\*star", "*star"));
+        assert!(!glob("\\*star", "xstar"));
+    }
+
+    #[test]
+    fn walk_respects_nested_gitignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join(".gitignore"), "*.log\n/build/\n!keep.log\n").unwrap();
+        std::fs::create_dir_all(root.join("build")).unwrap();
+        std::fs::create_dir_all(root.join("src/build")).unwrap();
+        std::fs::create_dir_all(root.join("src/gen")).unwrap();
+        std::fs::write(root.join("src/.gitignore"), "gen\n").unwrap();
+        for file in [
+            "a.rs",
+            "debug.log",
+            "keep.log",
+            "build/out.rs",
+            "src/build/b.rs",
+            "src/gen/g.rs",
+            "src/lib.rs",
+        ] {
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+
+        let files: Vec<String> = workspace_files(root)
+            .iter()
+            .map(|path| display_name_for(path, root).replace('\\', "/"))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ".gitignore",
+                "a.rs",
+                "keep.log",
+                "src/.gitignore",
+                "src/build/b.rs",
+                "src/lib.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn search_prefers_open_documents_and_streams_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.txt"), "needle on disk\n").unwrap();
+        std::fs::write(root.join("b.txt"), "no match\n  two needle needle\n").unwrap();
+
+        let mut batches = Vec::new();
+        let summary = search(
+            ProjectSearchRequest {
+                root: root.to_path_buf(),
+                search: literal("NEEDLE"),
+                open_documents: vec![SearchSource {
+                    document_id: DocumentId(1),
+                    path: root.join("a.txt"),
+                    text: "unsaved\nneedle".to_string(),
+                }],
+            },
+            &AtomicBool::new(false),
+            |files| batches.push(files),
+        );
+
+        let files: Vec<SearchFileResult> = batches.into_iter().flatten().collect();
+        assert_eq!(summary.files_searched, 2);
+        assert!(!summary.truncated);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].document_id, Some(DocumentId(1)));
+        assert_eq!(files[0].matches[0].line, 1);
+        assert_eq!(files[1].display_name, "b.txt");
+        let second = &files[1].matches[1];
+        assert_eq!((second.line, second.column, second.end_column), (1, 13, 19));
+        assert_eq!(second.preview, "two needle needle");
+        assert_eq!(second.preview_range, (11, 17));
+        assert_eq!(row_count(&files), 5);
+        assert_eq!(match_count(&files), 3);
+        assert!(matches!(row_at(&files, 2), Some(SearchRow::File(f)) if f.display_name == "b.txt"));
+    }
+
+    #[test]
+    fn previews_keep_far_matches_visible() {
+        let line = format!("{}match", "x".repeat(100));
+        let (preview, range) = preview(&line, 100, 105);
+        assert!(preview.starts_with('\u{2026}'));
+        let matched: String = preview
+            .chars()
+            .skip(range.0)
+            .take(range.1 - range.0)
+            .collect();
+        assert_eq!(matched, "match");
+    }
+
+    #[test]
+    fn cancelled_search_stops() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "needle").unwrap();
+        let mut called = false;
+        let summary = search(
+            ProjectSearchRequest {
+                root: dir.path().to_path_buf(),
+                search: literal("needle"),
+                open_documents: Vec::new(),
+            },
+            &AtomicBool::new(true),
+            |_| called = true,
+        );
+        assert!(!called);
+        assert_eq!(summary.files_searched, 0);
+    }
+}
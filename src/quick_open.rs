@@ -0,0 +1,252 @@
+//! Quick Open file index and ranking
+//!
+//! Flattens the workspace `FileTree` into a list of files that the Quick Open
+//! modal fuzzy-matches against. Matches score on the whole relative path, get
+//! a bonus when the filename itself matches, and recently opened files are
+//! boosted so the usual suspects float to the top.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+
+use crate::model::{FileMatch, FileTree};
+use crate::util::is_likely_binary;
+
+/// Maximum number of ranked results for a non-empty query
+const MAX_RESULTS: usize = 50;
+
+/// Maximum number of files listed for an empty query
+const MAX_UNFILTERED: usize = 100;
+
+/// How many of the most recent files get a boost
+const RECENT_LIMIT: usize = 20;
+
+/// Boost for the most recently opened file, roughly four matched characters.
+/// Later entries get a linearly smaller share.
+const RECENT_BOOST: u32 = 64;
+
+/// Maximum bytes read for the preview of the highlighted file
+const PREVIEW_MAX_BYTES: usize = 16 * 1024;
+
+/// A file in the index
+#[derive(Debug, Clone)]
+pub struct IndexedFile {
+    pub path: PathBuf,
+    /// Path relative to the workspace root, matched against the query
+    pub relative_path: String,
+    pub filename: String,
+}
+
+/// The workspace files searched by Quick Open
+#[derive(Debug, Clone, Default)]
+pub struct FileIndex {
+    files: Vec<IndexedFile>,
+}
+
+impl FileIndex {
+    /// Index every file in `tree`, in tree order
+    pub fn from_tree(tree: &FileTree, root: &Path) -> Self {
+        Self::from_paths(tree.get_all_file_paths(), root)
+    }
+
+    /// Index the given files, with paths shown relative to `root`
+    pub fn from_paths(paths: Vec<PathBuf>, root: &Path) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let filename = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let relative_path = path
+                    .strip_prefix(root)
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|_| path.to_string_lossy().to_string());
+                IndexedFile {
+                    path,
+                    relative_path,
+                    filename,
+                }
+            })
+            .collect();
+        Self { files }
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Rank the indexed files against `query`. `recent` lists recently
+    /// opened files, most recent first.
+    ///
+    /// An empty query lists the recent files first, then the rest in tree
+    /// order.
+    pub fn search(&self, query: &str, recent: &[PathBuf]) -> Vec<FileMatch> {
+        let recent_rank: HashMap<&Path, usize> = recent
+            .iter()
+            .take(RECENT_LIMIT)
+            .enumerate()
+            .map(|(rank, path)| (path.as_path(), rank))
+            .collect();
+
+        if query.is_empty() {
+            let mut files: Vec<(Option<usize>, &IndexedFile)> = self
+                .files
+                .iter()
+                .map(|file| (recent_rank.get(file.path.as_path()).copied(), file))
+                .collect();
+            // Stable, so files that aren't recent keep their tree order
+            files.sort_by_key(|(rank, _)| rank.unwrap_or(usize::MAX));
+            return files
+                .into_iter()
+                .take(MAX_UNFILTERED)
+                .map(|(_, file)| to_match(file, 0, vec![]))
+                .collect();
+        }
+
+        let mut matcher = Matcher::new(Config::DEFAULT.match_paths());
+        let mut query_buf = Vec::new();
+        let needle = Utf32Str::new(query, &mut query_buf);
+        let mut haystack_buf = Vec::new();
+
+        let mut results: Vec<FileMatch> = self
+            .files
+            .iter()
+            .filter_map(|file| {
+                let haystack = Utf32Str::new(&file.relative_path, &mut haystack_buf);
+                let path_score = matcher.fuzzy_match(haystack, needle)? as u32;
+
+                // Matching within the filename counts for more than matching
+                // across directory names
+                let haystack = Utf32Str::new(&file.filename, &mut haystack_buf);
+                let mut indices = Vec::new();
+                let name_score = matcher
+                    .fuzzy_indices(haystack, needle, &mut indices)
+                    .map_or(0, u32::from);
+
+                let boost = recent_rank.get(file.path.as_path()).map_or(0, |rank| {
+                    RECENT_BOOST * (RECENT_LIMIT - rank) as u32 / RECENT_LIMIT as u32
+                });
+
+                Some(to_match(file, path_score + name_score + boost, indices))
+            })
+            .collect();
+
+        // Best score first; shorter paths win ties
+        results.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.relative_path.len().cmp(&b.relative_path.len()))
+                .then_with(|| a.relative_path.cmp(&b.relative_path))
+        });
+        results.truncate(MAX_RESULTS);
+        results
+    }
+}
+
+fn to_match(file: &IndexedFile, score: u32, indices: Vec<u32>) -> FileMatch {
+    FileMatch {
+        path: file.path.clone(),
+        filename: file.filename.clone(),
+        relative_path: file.relative_path.clone(),
+        score,
+        indices,
+    }
+}
+
+/// Read the first `max_lines` lines of `path` for the preview pane. Binary
+/// and unreadable files have no preview.
+pub fn read_preview(path: &Path, max_lines: usize) -> Option<Vec<String>> {
+    if is_likely_binary(path) {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(PREVIEW_MAX_BYTES as u64)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    let text = String::from_utf8_lossy(&bytes);
+    Some(
+        text.lines()
+            .take(max_lines)
+            .map(|line| line.replace('\t', "    "))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(paths: &[&str]) -> FileIndex {
+        let root = PathBuf::from("/ws");
+        FileIndex::from_paths(paths.iter().map(|p| root.join(p)).collect(), &root)
+    }
+
+    fn ranked(results: &[FileMatch]) -> Vec<&str> {
+        results.iter().map(|m| m.relative_path.as_str()).collect()
+    }
+
+    #[test]
+    fn matches_across_directories() {
+        let index = index(&["src/view/modal.rs", "src/model/ui.rs", "README.md"]);
+        let results = index.search("viewmod", &[]);
+        assert_eq!(ranked(&results), vec!["src/view/modal.rs"]);
+    }
+
+    #[test]
+    fn filename_matches_rank_above_directory_matches() {
+        let index = index(&["config/main.rs", "src/config.rs"]);
+        let results = index.search("config", &[]);
+        assert_eq!(ranked(&results), vec!["src/config.rs", "config/main.rs"]);
+        assert!(results[1].indices.is_empty());
+    }
+
+    #[test]
+    fn recent_files_are_boosted() {
+        let index = index(&["src/a/lib.rs", "src/b/lib.rs"]);
+        assert_eq!(
+            ranked(&index.search("lib", &[])),
+            vec!["src/a/lib.rs", "src/b/lib.rs"]
+        );
+
+        let recent = vec![PathBuf::from("/ws/src/b/lib.rs")];
+        assert_eq!(
+            ranked(&index.search("lib", &recent)),
+            vec!["src/b/lib.rs", "src/a/lib.rs"]
+        );
+    }
+
+    #[test]
+    fn empty_query_lists_recent_files_first() {
+        let index = index(&["a.rs", "b.rs", "c.rs"]);
+        let recent = vec![PathBuf::from("/ws/c.rs"), PathBuf::from("/elsewhere/x.rs")];
+        assert_eq!(
+            ranked(&index.search("", &recent)),
+            vec!["c.rs", "a.rs", "b.rs"]
+        );
+    }
+
+    #[test]
+    fn preview_reads_leading_lines_of_text_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = dir.path().join("notes.txt");
+        std::fs::write(&text, "one\n\ttwo\nthree\n").unwrap();
+        assert_eq!(
+            read_preview(&text, 2),
+            Some(vec!["one".to_string(), "    two".to_string()])
+        );
+
+        let binary = dir.path().join("blob.bin");
+        std::fs::write(&binary, [0u8, 1, 2]).unwrap();
+        assert_eq!(read_preview(&binary, 2), None);
+    }
+}
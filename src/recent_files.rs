@@ -3,6 +3,7 @@
 //! Tracks files opened in the editor and persists them to disk.
 //! Files are stored in MRU (most recently used) order with a capacity limit.
 
+use crate::util::{normalize_path, rebase_path};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
@@ -161,6 +162,27 @@ impl RecentFiles {
         self.entries.retain(|e| e.path != canonical);
     }
 
+    /// Point entries at their new location after a file or directory was
+    /// renamed or moved. Returns whether any entry changed.
+    pub fn rename(&mut self, from: &Path, to: &Path) -> bool {
+        let to = normalize_path(to);
+        let mut changed = false;
+        for entry in &mut self.entries {
+            if let Some(new_path) = rebase_path(&entry.path, from, &to) {
+                entry.path = new_path;
+                changed = true;
+            }
+        }
+        // Renaming onto a path that was already listed leaves a duplicate
+        let mut seen = Vec::new();
+        self.entries.retain(|entry| {
+            let first = !seen.contains(&entry.path);
+            seen.push(entry.path.clone());
+            first
+        });
+        changed
+    }
+
     /// Clear all recent files
     pub fn clear(&mut self) {
         self.entries.clear();
@@ -199,6 +221,26 @@ mod tests {
         assert_eq!(recent.entries[0].path, path);
     }
 
+    #[test]
+    fn test_rename_follows_file_and_directory_moves() {
+        let mut recent = RecentFiles::default();
+        recent.add(PathBuf::from("/project/src/a.rs"), None);
+        recent.add(PathBuf::from("/project/src/nested/b.rs"), None);
+        recent.add(PathBuf::from("/project/other.rs"), None);
+
+        assert!(recent.rename(Path::new("/project/src"), Path::new("/project/lib")));
+        let paths: Vec<_> = recent.entries.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/project/other.rs"),
+                PathBuf::from("/project/lib/nested/b.rs"),
+                PathBuf::from("/project/lib/a.rs"),
+            ]
+        );
+        assert!(!recent.rename(Path::new("/missing"), Path::new("/elsewhere")));
+    }
+
     #[test]
     fn test_reopening_moves_to_front() {
         let mut recent = RecentFiles::default();
@@ -0,0 +1,361 @@
+//! Recording and replaying editing sessions
+//!
+//! A recording is a JSON Lines file: a [`RecordingHeader`] describing the
+//! open files (with content hashes, so a replay can tell when a file has
+//! changed since), followed by one [`RecordedEntry`] per message. Editor and
+//! document messages are recorded; they are what cursor, selection and undo
+//! bugs are made of, and they replay without the runtime. Messages produced
+//! by the runtime in response (clipboard reads, for instance) are recorded
+//! as they arrive, so a replay never needs the clipboard or the window.
+//!
+//! Start recording with `--record <file>` or "Toggle Session Recording";
+//! replay with `--replay <file>`.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::messages::{DocumentMsg, EditorMsg, Msg};
+use crate::model::{AppModel, Position};
+
+/// Recording format version, bumped on incompatible changes
+pub const RECORDING_VERSION: u32 = 1;
+
+/// First line of a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    pub version: u32,
+    pub app_version: String,
+    /// Open files, focused file first
+    pub files: Vec<RecordedFile>,
+    /// Primary cursor of the focused editor when recording started
+    pub cursor: (usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFile {
+    pub path: PathBuf,
+    /// [`content_hash`] of the buffer when recording started
+    pub hash: String,
+}
+
+/// A recorded message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEntry {
+    /// Milliseconds since recording started
+    pub at_ms: u64,
+    pub msg: RecordedMsg,
+}
+
+/// The recordable subset of [`Msg`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RecordedMsg {
+    Editor(EditorMsg),
+    Document(DocumentMsg),
+}
+
+impl RecordedMsg {
+    pub fn from_msg(msg: &Msg) -> Option<Self> {
+        match msg {
+            Msg::Editor(m) => Some(RecordedMsg::Editor(m.clone())),
+            Msg::Document(m) => Some(RecordedMsg::Document(m.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn into_msg(self) -> Msg {
+        match self {
+            RecordedMsg::Editor(m) => Msg::Editor(m),
+            RecordedMsg::Document(m) => Msg::Document(m),
+        }
+    }
+}
+
+/// FNV-1a hash of `text` as hex; stable across builds and platforms
+pub fn content_hash(text: &str) -> String {
+    let mut hasher = ContentHasher::new();
+    hasher.update(text.as_bytes());
+    hasher.finish()
+}
+
+/// [`content_hash`] of text that arrives in pieces
+#[derive(Debug, Clone, Copy)]
+pub struct ContentHasher(u64);
+
+impl ContentHasher {
+    pub fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.0 = bytes.iter().fold(self.0, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    }
+
+    pub fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes a recording
+pub struct SessionRecorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    started: Instant,
+}
+
+impl SessionRecorder {
+    /// Create `path` and write the header for the model's open files
+    pub fn create(path: &Path, model: &AppModel) -> Result<Self, String> {
+        let focused = model.editor_area.focused_document_id();
+        let mut docs: Vec<_> = model.editor_area.documents.iter().collect();
+        // Focused document first, so a replay opens and focuses it first
+        docs.sort_by_key(|(id, _)| (Some(**id) != focused, id.0));
+        let files = docs
+            .into_iter()
+            .filter_map(|(_, doc)| {
+                Some(RecordedFile {
+                    path: doc.file_path.clone()?,
+                    hash: content_hash(&doc.buffer.to_string()),
+                })
+            })
+            .collect();
+        let cursor = model
+            .focused_editor()
+            .map(|editor| {
+                let cursor = editor.primary_cursor();
+                (cursor.line, cursor.column)
+            })
+            .unwrap_or_default();
+        let header = RecordingHeader {
+            version: RECORDING_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            files,
+            cursor,
+        };
+
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut recorder = Self {
+            path: path.to_path_buf(),
+            writer: BufWriter::new(file),
+            started: Instant::now(),
+        };
+        recorder.write_line(&header)?;
+        Ok(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `msg` if it is recordable
+    pub fn record(&mut self, msg: &Msg) -> Result<(), String> {
+        let Some(msg) = RecordedMsg::from_msg(msg) else {
+            return Ok(());
+        };
+        let entry = RecordedEntry {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            msg,
+        };
+        self.write_line(&entry)
+    }
+
+    fn write_line(&mut self, value: &impl Serialize) -> Result<(), String> {
+        serde_json::to_writer(&mut self.writer, value).map_err(|e| e.to_string())?;
+        // Flushed per line so a crash keeps everything up to it
+        self.writer
+            .write_all(b"\n")
+            .and_then(|()| self.writer.flush())
+            .map_err(|e| e.to_string())
+    }
+}
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+static RECORDER: Mutex<Option<SessionRecorder>> = Mutex::new(None);
+
+/// Start recording every message passed to `update` into `path`
+pub fn start_recording(path: &Path, model: &AppModel) -> Result<(), String> {
+    let recorder = SessionRecorder::create(path, model)?;
+    let mut slot = RECORDER.lock().map_err(|e| e.to_string())?;
+    *slot = Some(recorder);
+    RECORDING.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Stop recording, returning the recording's path
+pub fn stop_recording() -> Option<PathBuf> {
+    RECORDING.store(false, Ordering::Relaxed);
+    let recorder = RECORDER.lock().ok()?.take()?;
+    Some(recorder.path)
+}
+
+pub fn is_recording() -> bool {
+    RECORDING.load(Ordering::Relaxed)
+}
+
+/// Called by `update` for every message
+pub(crate) fn record(msg: &Msg) {
+    if !is_recording() {
+        return;
+    }
+    if let Ok(mut slot) = RECORDER.lock() {
+        if let Some(recorder) = slot.as_mut() {
+            if let Err(e) = recorder.record(msg) {
+                tracing::warn!("Stopped recording {}: {}", recorder.path.display(), e);
+                *slot = None;
+                RECORDING.store(false, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// A loaded recording
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub header: RecordingHeader,
+    pub entries: Vec<RecordedEntry>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file =
+            File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let mut lines = BufReader::new(file).lines();
+        let header_line = lines
+            .next()
+            .ok_or("Recording is empty")?
+            .map_err(|e| e.to_string())?;
+        let header: RecordingHeader = serde_json::from_str(&header_line)
+            .map_err(|e| format!("Invalid recording header: {}", e))?;
+        if header.version != RECORDING_VERSION {
+            return Err(format!(
+                "Unsupported recording version {} (expected {})",
+                header.version, RECORDING_VERSION
+            ));
+        }
+
+        let mut entries = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let line = line.map_err(|e| e.to_string())?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line)
+                .map_err(|e| format!("Invalid entry on line {}: {}", index + 2, e))?;
+            entries.push(entry);
+        }
+        Ok(Self { header, entries })
+    }
+
+    /// Files to open for the replay, focused file first
+    pub fn file_paths(&self) -> Vec<PathBuf> {
+        self.header.files.iter().map(|f| f.path.clone()).collect()
+    }
+
+    /// Restore the recorded starting cursor and describe open files whose
+    /// contents differ from when the recording was made
+    pub fn prepare(&self, model: &mut AppModel) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for file in &self.header.files {
+            match model.editor_area.find_document_by_path(&file.path) {
+                Some(doc_id) => {
+                    let text = model.editor_area.documents[&doc_id].buffer.to_string();
+                    if content_hash(&text) != file.hash {
+                        warnings.push(format!("{} has changed", file.path.display()));
+                    }
+                }
+                None => warnings.push(format!("{} is not open", file.path.display())),
+            }
+        }
+
+        if model.focused_editor().is_some() {
+            let (line, column) = self.header.cursor;
+            let document = model.document();
+            let line = line.min(document.line_count().saturating_sub(1));
+            let column = column.min(document.line_length(line));
+            let editor = model.editor_mut();
+            editor.collapse_to_primary();
+            editor.cursors[0].line = line;
+            editor.cursors[0].column = column;
+            editor.cursors[0].desired_column = None;
+            editor.selections[0].anchor = Position::new(line, column);
+            editor.selections[0].head = Position::new(line, column);
+        }
+        warnings
+    }
+
+    /// Feed every recorded message through `update`, ignoring the commands
+    /// they return
+    pub fn apply(&self, model: &mut AppModel) {
+        for entry in &self.entries {
+            crate::update::update(model, entry.msg.clone().into_msg());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::Direction;
+    use crate::update::update;
+
+    #[test]
+    fn replay_reproduces_recorded_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "hello\nworld\n").unwrap();
+        let recording = dir.path().join("session.jsonl");
+
+        let mut model = AppModel::new(800, 600, 1.0, vec![file.clone()]);
+        update(
+            &mut model,
+            Msg::Editor(EditorMsg::MoveCursor(Direction::Down)),
+        );
+        let mut recorder = SessionRecorder::create(&recording, &model).unwrap();
+        for msg in [
+            Msg::Editor(EditorMsg::MoveCursorLineEnd),
+            Msg::Document(DocumentMsg::InsertText("!!".to_string())),
+            Msg::Editor(EditorMsg::MoveCursor(Direction::Up)),
+            Msg::Document(DocumentMsg::DeleteBackward),
+            Msg::Document(DocumentMsg::Undo),
+            Msg::Document(DocumentMsg::InsertChar('?')),
+        ] {
+            recorder.record(&msg).unwrap();
+            update(&mut model, msg);
+        }
+        drop(recorder);
+
+        let replay = Replay::load(&recording).unwrap();
+        assert_eq!(replay.entries.len(), 6);
+        let mut replayed = AppModel::new(800, 600, 1.0, replay.file_paths());
+        assert!(replay.prepare(&mut replayed).is_empty());
+        replay.apply(&mut replayed);
+
+        assert_eq!(
+            replayed.document().buffer.to_string(),
+            model.document().buffer.to_string()
+        );
+        let cursor = |model: &AppModel| {
+            let cursor = model.editor().primary_cursor();
+            (cursor.line, cursor.column)
+        };
+        assert_eq!(cursor(&replayed), cursor(&model));
+
+        std::fs::write(&file, "changed\n").unwrap();
+        let mut changed = AppModel::new(800, 600, 1.0, replay.file_paths());
+        assert_eq!(replay.prepare(&mut changed).len(), 1);
+    }
+}
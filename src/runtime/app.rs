@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::time::{Duration, Instant};
@@ -16,25 +17,29 @@ use winit::window::{CursorIcon, Icon, Window};
 
 use token::cli::StartupConfig;
 use token::commands::{Cmd, Damage};
-use token::fs_watcher::{FileSystemEvent, FileSystemWatcher};
+use token::file_ops::Trash;
+use token::fs_watcher::{FileSystemEvent, FileSystemWatcher, OpenFileWatcher};
 use token::keymap::{
-    keystroke_from_winit, load_default_keymap, Command, KeyAction, KeyContext, Keymap,
+    keystroke_from_winit, load_keymap, Command, KeyAction, KeyContext, Keymap, KeymapWatcher,
 };
 use token::messages::{
-    AppMsg, EditorMsg, ImageMsg, LayoutMsg, Msg, SyntaxMsg, UiMsg, WorkspaceMsg,
+    AiChatMsg, AppMsg, CsvMsg, DocumentMsg, EditorMsg, GitMsg, ImageMsg, LayoutMsg, ModalMsg, Msg,
+    NotesMsg, SearchMsg, SyntaxMsg, TodoMsg, UiMsg, WelcomeMsg, WorkspaceMsg,
 };
 use token::model::editor::Position;
 use token::model::AppModel;
-use token::syntax::{LanguageId, ParserState};
-use token::update::update;
+use token::session::{EditorSession, Session};
+use token::syntax::{LanguageId, ParserState, SyntaxEdits};
+use token::update::{autosave_interval, update, word_highlight_deadline};
 
-use super::input::{handle_key, KeyModifiers, OptionKeyGesture};
+use super::clipboard::ClipboardHandle;
+use super::input::{completion_key_msg, handle_key, vim_key_msg, KeyModifiers, OptionKeyGesture};
 use super::mouse::{
     end_tab_drag, handle_mouse_press, handle_mouse_wheel, make_mouse_event, update_tab_drag,
     ClickTracker, DragState,
 };
 use super::webview::WebviewManager;
-use token::view::Renderer;
+use token::view::{EditorFonts, Renderer};
 
 use super::perf::{PerfStage, PerfStats};
 
@@ -46,6 +51,7 @@ struct SyntaxParseRequest {
     revision: u64,
     source: String,
     language: LanguageId,
+    edits: Option<SyntaxEdits>,
 }
 
 enum SyntaxWorkerRequest {
@@ -62,6 +68,8 @@ pub struct App {
     window: Option<Rc<Window>>,
     context: Option<Context<Rc<Window>>>,
     last_tick: Instant,
+    /// When the interval autosave last ran
+    last_autosave: Instant,
     modifiers: ModifiersState,
     mouse_position: Option<(f64, f64)>,
     /// Carries sub-line trackpad scroll remainders between wheel events.
@@ -70,11 +78,22 @@ pub struct App {
     drag: DragState,
     msg_tx: Sender<Msg>,
     msg_rx: Receiver<Msg>,
+    /// System clipboard, kept open so copied text stays available
+    clipboard: ClipboardHandle,
     perf: PerfStats,
+    /// Render times, for turning on performance mode when rendering is slow
+    frame_monitor: token::performance::FrameMonitor,
     /// Channel to send parse requests to syntax worker
     syntax_tx: Sender<SyntaxWorkerRequest>,
     /// File system watcher for workspace directory (if workspace is open)
     fs_watcher: Option<FileSystemWatcher>,
+    /// Workspace root `fs_watcher` was last started for, so a watcher that
+    /// failed to start is not retried on every poll
+    fs_watcher_root: Option<std::path::PathBuf>,
+    /// Watcher for the folders of open files outside the workspace
+    open_file_watcher: Option<OpenFileWatcher>,
+    /// Watcher for the user keymap, to reload bindings when it is saved
+    keymap_watcher: Option<KeymapWatcher>,
     /// Pending damage for the next render (accumulated from commands)
     pending_damage: Damage,
     /// Flag to request application exit (set by Cmd::Quit)
@@ -90,12 +109,21 @@ pub struct App {
     /// Receiver for background PTY spawn completion. Spawned asynchronously
     /// because `portable_pty` startup can block on shell initialization.
     terminal_spawn_rx: Option<(usize, TerminalSpawnReceiver)>,
+    /// Running language servers, started by the first `Cmd::Lsp` for each
+    lsp: token::lsp::LspManager,
+    /// Wakes the event loop for menu bar commands; the menu bar is
+    /// installed from it once the app has launched
+    #[cfg(target_os = "macos")]
+    menu_proxy: Option<winit::event_loop::EventLoopProxy<()>>,
+    #[cfg(target_os = "macos")]
+    menu_bar: Option<super::menu::MenuBar>,
 }
 
 impl App {
     pub fn new(window_width: u32, window_height: u32, startup_config: StartupConfig) -> Self {
         let (msg_tx, msg_rx) = mpsc::channel();
-        let keymap = Keymap::with_bindings(load_default_keymap());
+        let loaded_keymap = load_keymap();
+        let keymap = Keymap::with_bindings(loaded_keymap.bindings);
 
         // Spawn syntax highlighting worker thread
         let (syntax_tx, syntax_rx) = mpsc::channel::<SyntaxWorkerRequest>();
@@ -108,10 +136,12 @@ impl App {
         let mut file_paths = startup_config.file_paths();
         let workspace_root = startup_config.workspace_root().cloned();
         let initial_position = startup_config.initial_position;
+        // Recording and replaying need every file loaded before the first message
+        let load_all_now = startup_config.record.is_some() || startup_config.replay.is_some();
 
         // Load the first file synchronously (needed for immediate editing).
         // Defer additional files to background loads so startup isn't blocked.
-        let pending_file_loads = if file_paths.len() > 1 {
+        let pending_file_loads = if file_paths.len() > 1 && !load_all_now {
             file_paths.split_off(1)
         } else {
             Vec::new()
@@ -126,6 +156,7 @@ impl App {
             .values()
             .filter_map(|doc| doc.file_path.clone())
             .collect();
+        let cli_paths_empty = cli_paths.is_empty();
         for path in cli_paths {
             model.record_file_opened(path);
         }
@@ -145,6 +176,34 @@ impl App {
             None
         };
 
+        token::tracing::set_verbose_targets(&model.config.verbose_logging);
+
+        // Launched without files: reopen the tabs of the workspace's last
+        // session, or show the start screen instead of an empty buffer
+        let restored = cli_paths_empty
+            && !load_all_now
+            && model.config.restore_session
+            && EditorSession::load(model.workspace_root().map(PathBuf::as_path))
+                .is_some_and(|session| model.restore_editor_session(&session));
+
+        // Restore dock visibility and sizes from the previous run
+        model.restore_session(&Session::load());
+
+        if cli_paths_empty && !restored {
+            update(&mut model, Msg::Welcome(WelcomeMsg::Show));
+        }
+
+        // The previous run crashed: offer to reopen its files
+        if let Some((report, crashed)) = token::crash::take_pending_report() {
+            update(
+                &mut model,
+                Msg::App(AppMsg::CrashReportFound {
+                    report,
+                    session: crashed.editor,
+                }),
+            );
+        }
+
         // Apply initial cursor position if specified (--line/--column)
         if let Some((line, column)) = initial_position {
             let editor = model.editor_mut();
@@ -155,6 +214,9 @@ impl App {
             model.ensure_cursor_visible();
         }
 
+        model.ui.keymap_diagnostics = loaded_keymap.diagnostics;
+
+        let lsp = token::lsp::LspManager::new(msg_tx.clone());
         let mut app = Self {
             model,
             keymap,
@@ -162,6 +224,7 @@ impl App {
             window: None,
             context: None,
             last_tick: Instant::now(),
+            last_autosave: Instant::now(),
             modifiers: ModifiersState::empty(),
             mouse_position: None,
             scroll_accumulator: ScrollAccumulator::default(),
@@ -169,9 +232,20 @@ impl App {
             drag: DragState::default(),
             msg_tx,
             msg_rx,
+            clipboard: ClipboardHandle::default(),
             perf: PerfStats::default(),
+            frame_monitor: Default::default(),
             syntax_tx,
+            fs_watcher_root: fs_watcher.as_ref().map(|w| w.root().clone()),
             fs_watcher,
+            open_file_watcher: OpenFileWatcher::new()
+                .map_err(|e| tracing::warn!("Failed to start open file watcher: {}", e))
+                .ok(),
+            keymap_watcher: token::config_paths::keymap_file().and_then(|path| {
+                KeymapWatcher::new(path)
+                    .map_err(|e| tracing::debug!("Not watching the keymap: {}", e))
+                    .ok()
+            }),
             pending_damage: Damage::Full, // Start with full render
             should_quit: false,
             webview_manager: WebviewManager::new(),
@@ -179,14 +253,64 @@ impl App {
             syntax_deadlines: HashMap::new(),
             pending_file_loads,
             terminal_spawn_rx: None,
+            lsp,
+            #[cfg(target_os = "macos")]
+            menu_proxy: None,
+            #[cfg(target_os = "macos")]
+            menu_bar: None,
         };
 
         // Trigger initial syntax parsing for all loaded documents
         app.trigger_initial_syntax_parsing();
+        // Stream in large files opened at startup
+        if let Some(cmd) = token::update::sync_large_file_loads(&mut app.model) {
+            app.process_cmd(cmd);
+        }
+
+        if let Some(path) = startup_config.record {
+            match token::replay::start_recording(&path, &app.model) {
+                Ok(()) => app
+                    .model
+                    .ui
+                    .set_status(format!("Recording session to {}", path.display())),
+                Err(e) => app.model.ui.set_status(e),
+            }
+        }
+        if let Some(replay) = startup_config.replay {
+            app.start_replay(replay);
+        }
+
+        app.watch_power_source();
+
+        if app.model.config.check_for_updates {
+            app.process_cmd(Cmd::CheckForUpdates { manual: false });
+        }
 
         app
     }
 
+    /// Poll the power source on a thread, reporting each change of it (for
+    /// `performance_mode: auto`). Stops once the app is gone or the power
+    /// source can't be told.
+    fn watch_power_source(&self) {
+        let tx = self.msg_tx.clone();
+        std::thread::spawn(move || {
+            let mut last = None;
+            while let Some(on_battery) = token::performance::on_battery() {
+                if last != Some(on_battery) {
+                    last = Some(on_battery);
+                    if tx
+                        .send(Msg::Ui(UiMsg::PowerSourceChanged(on_battery)))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                std::thread::sleep(token::performance::POWER_POLL_INTERVAL);
+            }
+        });
+    }
+
     /// Trigger syntax parsing for all documents loaded at startup
     fn trigger_initial_syntax_parsing(&mut self) {
         // Collect document info first to avoid borrow issues
@@ -195,7 +319,7 @@ impl App {
             .editor_area
             .documents
             .iter()
-            .filter(|(_, doc)| doc.language.has_highlighting())
+            .filter(|(_, doc)| doc.language.has_highlighting() && !doc.large_file)
             .map(|(&id, doc)| (id, doc.revision, doc.buffer.to_string(), doc.language))
             .collect();
 
@@ -208,6 +332,7 @@ impl App {
                     revision,
                     source,
                     language,
+                    edits: None,
                 }))
             {
                 tracing::warn!("Failed to send initial syntax parse request: {}", e);
@@ -236,28 +361,90 @@ impl App {
             modal_active: self.model.ui.has_modal(),
             editor_focused: matches!(focus, FocusTarget::Editor),
             sidebar_focused: matches!(focus, FocusTarget::Sidebar),
+            column_selection: self.model.editor().rectangle_selection.from_keyboard,
+            vim_mode: self.model.config.vim_mode.then_some(self.model.vim.mode),
+            csv_mode: self
+                .model
+                .editor_area
+                .focused_editor()
+                .and_then(|editor| editor.view_mode.as_csv())
+                .is_some_and(|csv| !csv.is_editing()),
+            diff_mode: self
+                .model
+                .editor_area
+                .focused_editor()
+                .is_some_and(|editor| editor.view_mode.is_diff()),
+            image_mode: self
+                .model
+                .editor_area
+                .focused_editor()
+                .is_some_and(|editor| editor.view_mode.is_image()),
         }
     }
 
     fn init_renderer(&mut self, window: Rc<Window>, context: &Context<Rc<Window>>) -> Result<()> {
-        let renderer = Renderer::new(Rc::clone(&window), context)?;
+        let renderer = Renderer::new(
+            Rc::clone(&window),
+            context,
+            self.editor_fonts(),
+            self.model.config.font_size,
+            self.model.config.renderer,
+        )?;
+        self.model.set_scale_factor(renderer.scale_factor());
+        let size = window.inner_size();
+        self.use_renderer(renderer, size.width, size.height);
+        Ok(())
+    }
+
+    /// The configured fonts, reusing the current renderer's when the font
+    /// settings haven't changed (loading a font parses all of its glyphs)
+    fn editor_fonts(&self) -> Rc<EditorFonts> {
+        let config = &self.model.config;
+        match &self.renderer {
+            Some(renderer)
+                if renderer
+                    .fonts()
+                    .is_for(&config.font_family, &config.font_fallbacks) =>
+            {
+                Rc::clone(renderer.fonts())
+            }
+            _ => Rc::new(EditorFonts::load(
+                &config.font_family,
+                &config.font_fallbacks,
+            )),
+        }
+    }
 
+    /// Take on `renderer`'s font metrics for a window of the given size
+    fn use_renderer(&mut self, renderer: Renderer, width: u32, height: u32) {
         self.model.set_char_width(renderer.char_width());
-        self.model.set_scale_factor(renderer.scale_factor());
         self.model.line_height = renderer.line_height();
 
         // Derive tab bar height from glyph metrics instead of hardcoded value
         self.model.recompute_tab_bar_height_from_line_height();
 
         // Recompute viewport geometry with new metrics
-        let size = window.inner_size();
-        self.model.resize(size.width, size.height);
+        self.model.resize(width, height);
 
         self.renderer = Some(renderer);
-        Ok(())
     }
 
     fn reinit_renderer(&mut self, scale_factor: f64) -> Result<()> {
+        // Headless: there is no window, only a buffer the size of the model's
+        #[cfg(feature = "headless")]
+        if self.window.is_none() && self.renderer.is_some() {
+            let (width, height) = self.model.window_size;
+            let renderer = Renderer::headless(
+                width,
+                height,
+                scale_factor,
+                self.editor_fonts(),
+                self.model.config.font_size,
+            )?;
+            self.use_renderer(renderer, width, height);
+            return Ok(());
+        }
+
         let Some(window) = &self.window else {
             return Ok(());
         };
@@ -265,19 +452,19 @@ impl App {
             return Ok(());
         };
 
-        let renderer = Renderer::with_scale_factor(Rc::clone(window), context, scale_factor)?;
-
-        self.model.set_char_width(renderer.char_width());
-        self.model.line_height = renderer.line_height();
-
-        // Recompute tab bar height from new font metrics
-        self.model.recompute_tab_bar_height_from_line_height();
-
-        // Recompute viewport geometry for new char_width/line_height
+        let fonts = self.editor_fonts();
+        // Only one renderer may draw to the window at a time
+        self.renderer = None;
+        let renderer = Renderer::with_scale_factor(
+            Rc::clone(window),
+            context,
+            scale_factor,
+            fonts,
+            self.model.config.font_size,
+            self.model.config.renderer,
+        )?;
         let size = window.inner_size();
-        self.model.resize(size.width, size.height);
-
-        self.renderer = Some(renderer);
+        self.use_renderer(renderer, size.width, size.height);
         Ok(())
     }
 
@@ -297,15 +484,28 @@ impl App {
         use token::model::HoverRegion;
         use token::view::hit_test::{hit_test_ui, Point};
 
-        let Some(window) = &self.window else { return };
         let Some(renderer) = &self.renderer else {
             return;
         };
+        // Hover is tracked without a window too (headless runs)
+        let window = self.window.as_ref();
+        let set_cursor = |icon: CursorIcon| {
+            if let Some(window) = window {
+                window.set_cursor(icon);
+            }
+        };
 
         // In-progress sidebar resize overrides all hit-testing
         if self.model.ui.sidebar_resize.is_some() {
             self.model.ui.hover = HoverRegion::SidebarResize;
-            window.set_cursor(CursorIcon::ColResize);
+            set_cursor(CursorIcon::ColResize);
+            return;
+        }
+
+        // So does dragging a CSV column's edge
+        if self.is_csv_column_resizing() {
+            self.model.ui.hover = HoverRegion::EditorText;
+            set_cursor(CursorIcon::ColResize);
             return;
         }
 
@@ -316,167 +516,274 @@ impl App {
                 token::model::ui::DockResizeAxis::Horizontal => CursorIcon::ColResize,
                 token::model::ui::DockResizeAxis::Vertical => CursorIcon::RowResize,
             };
-            window.set_cursor(icon);
+            set_cursor(icon);
             return;
         }
 
         let pt = Point::new(x, y);
         if let Some(target) = hit_test_ui(&self.model, pt, renderer.char_width()) {
-            window.set_cursor(target.cursor_icon());
+            set_cursor(target.cursor_icon());
             self.model.ui.hover = target.hover_region();
         } else {
             self.model.ui.hover = HoverRegion::None;
-            window.set_cursor(CursorIcon::Default);
+            set_cursor(CursorIcon::Default);
         }
     }
 
-    fn handle_event(&mut self, event: &WindowEvent) -> Option<Cmd> {
-        match event {
-            WindowEvent::Resized(size) => update(
-                &mut self.model,
-                Msg::App(AppMsg::Resize(size.width, size.height)),
-            ),
-            WindowEvent::ScaleFactorChanged { scale_factor, .. } => update(
-                &mut self.model,
-                Msg::App(AppMsg::ScaleFactorChanged(*scale_factor)),
-            ),
-            WindowEvent::ModifiersChanged(mods) => {
-                self.modifiers = mods.state();
-                None
+    /// Whether a CSV column's edge is being dragged in the focused editor
+    fn is_csv_column_resizing(&self) -> bool {
+        self.model
+            .editor_area
+            .focused_editor()
+            .and_then(|editor| editor.view_mode.as_csv())
+            .is_some_and(|csv| csv.column_resize.is_some())
+    }
+
+    /// Handle a key press or release. Split out of [`App::handle_event`]
+    /// because winit's `KeyEvent` can't be built outside winit, so the
+    /// headless harness feeds keys in here.
+    fn handle_keyboard_input(
+        &mut self,
+        logical_key: &Key,
+        physical_key: PhysicalKey,
+        state: ElementState,
+        repeat: bool,
+    ) -> Option<Cmd> {
+        let is_option_key = matches!(
+            physical_key,
+            PhysicalKey::Code(KeyCode::AltLeft) | PhysicalKey::Code(KeyCode::AltRight)
+        );
+
+        if is_option_key {
+            if state == ElementState::Pressed && !repeat {
+                self.option_gesture.on_press();
+            } else if state == ElementState::Released {
+                self.option_gesture.on_release();
             }
-            WindowEvent::KeyboardInput { event, .. } => {
-                let is_option_key = matches!(
-                    event.physical_key,
-                    PhysicalKey::Code(KeyCode::AltLeft) | PhysicalKey::Code(KeyCode::AltRight)
-                );
+        }
 
-                if is_option_key {
-                    if event.state == ElementState::Pressed && !event.repeat {
-                        self.option_gesture.on_press();
-                    } else if event.state == ElementState::Released {
-                        self.option_gesture.on_release();
-                    }
+        if state == ElementState::Pressed {
+            // F2 renames in the file tree
+            #[cfg(debug_assertions)]
+            if *logical_key == Key::Named(NamedKey::F2)
+                && self.model.ui.focus != token::model::FocusTarget::Sidebar
+            {
+                self.perf.show_overlay = !self.perf.show_overlay;
+                return Some(Cmd::Redraw);
+            }
+
+            #[cfg(debug_assertions)]
+            if *logical_key == Key::Named(NamedKey::F7) {
+                let dump = crate::debug_dump::StateDump::from_model(&self.model);
+                match dump.save_to_file() {
+                    Ok(filename) => eprintln!("[DEBUG] State dumped to: {}", filename),
+                    Err(e) => eprintln!("[DEBUG] Failed to dump state: {}", e),
                 }
+                return Some(Cmd::Redraw);
+            }
 
-                if event.state == ElementState::Pressed {
-                    #[cfg(debug_assertions)]
-                    if event.logical_key == Key::Named(NamedKey::F2) {
-                        self.perf.show_overlay = !self.perf.show_overlay;
-                        return Some(Cmd::Redraw);
+            #[cfg(debug_assertions)]
+            if *logical_key == Key::Named(NamedKey::F8) {
+                if let Some(ref mut overlay) = self.model.debug_overlay {
+                    if self.modifiers.shift_key() {
+                        overlay.toggle_interactive();
+                    } else {
+                        overlay.toggle();
                     }
+                }
+                return Some(Cmd::Redraw);
+            }
 
-                    #[cfg(debug_assertions)]
-                    if event.logical_key == Key::Named(NamedKey::F7) {
-                        let dump = crate::debug_dump::StateDump::from_model(&self.model);
-                        match dump.save_to_file() {
-                            Ok(filename) => eprintln!("[DEBUG] State dumped to: {}", filename),
-                            Err(e) => eprintln!("[DEBUG] Failed to dump state: {}", e),
-                        }
-                        return Some(Cmd::Redraw);
-                    }
+            #[cfg(debug_assertions)]
+            if let Some(cmd) = self.handle_debug_overlay_key(logical_key) {
+                return Some(cmd);
+            }
 
-                    #[cfg(debug_assertions)]
-                    if event.logical_key == Key::Named(NamedKey::F8) {
-                        if let Some(ref mut overlay) = self.model.debug_overlay {
-                            overlay.toggle();
-                        }
-                        return Some(Cmd::Redraw);
-                    }
+            let ctrl = self.modifiers.control_key();
+            let shift = self.modifiers.shift_key();
+            let alt = self.modifiers.alt_key();
+            let logo = self.modifiers.super_key();
+            let modifiers = KeyModifiers {
+                ctrl,
+                shift,
+                alt,
+                logo,
+            };
 
-                    let ctrl = self.modifiers.control_key();
-                    let shift = self.modifiers.shift_key();
-                    let alt = self.modifiers.alt_key();
-                    let logo = self.modifiers.super_key();
-                    let modifiers = KeyModifiers {
-                        ctrl,
-                        shift,
-                        alt,
-                        logo,
-                    };
+            // Convert the raw winit event to our Keystroke type once. This is a
+            // pure conversion of the event + modifiers and doesn't depend on
+            // keymap state.
+            let keystroke = keystroke_from_winit(logical_key, physical_key, ctrl, shift, alt, logo);
+
+            // Non-global commands and new chords come from the keymap only when:
+            // - No modal is active (modals handled by handle_modal_key in input.rs)
+            // - Not in option double-tap mode with alt pressed (multi-cursor gesture)
+            // - Sidebar is not focused (sidebar keys handled by handle_sidebar_key in input.rs)
+            // - The TODO panel is not focused (handled by handle_todo_dock_key)
+            // - The AI chat prompt is not focused (handled by handle_ai_chat_dock_key)
+            // - The search panel is not focused (handled by handle_search_dock_key)
+            // - Not editing a CSV cell (CSV cell editor handled by handle_csv_edit_key in input.rs)
+            let sidebar_focused = matches!(self.model.ui.focus, token::model::FocusTarget::Sidebar);
+            let terminal_focused = self.model.ui.focused_dock()
+                == Some(token::panel::DockPosition::Bottom)
+                && self.model.dock_layout.bottom.is_open
+                && self.model.dock_layout.bottom.active_panel()
+                    == Some(token::panel::PanelId::TERMINAL);
+            let todo_focused = self.model.ui.focused_dock().is_some_and(|position| {
+                let dock = self.model.dock_layout.dock(position);
+                dock.is_open && dock.active_panel() == Some(token::panel::PanelId::TodoList)
+            });
+            let ai_chat_focused = self.model.ui.focused_dock().is_some_and(|position| {
+                let dock = self.model.dock_layout.dock(position);
+                dock.is_open && dock.active_panel() == Some(token::panel::PanelId::AI_CHAT)
+            });
+            let search_focused = self.model.ui.focused_dock().is_some_and(|position| {
+                let dock = self.model.dock_layout.dock(position);
+                dock.is_open && dock.active_panel() == Some(token::panel::PanelId::SEARCH_RESULTS)
+            });
+            let skip_keymap = self.model.ui.has_modal()
+                || (self.option_gesture.double_tapped && alt)
+                || sidebar_focused
+                || terminal_focused
+                || todo_focused
+                || ai_chat_focused
+                || search_focused
+                || self.model.is_csv_editing()
+                || self.model.is_csv_filtering();
+
+            // Look the keystroke up once. A chord in progress takes every key
+            // until it completes or is cancelled.
+            let chord_prefix = self.keymap.pending_chord_display();
+            let context = self.get_key_context();
+            let action = keystroke.map(|keystroke| {
+                self.keymap
+                    .handle_keystroke_with_context(keystroke, Some(&context))
+            });
+            if let (Some(prefix), Some(keystroke)) = (chord_prefix, keystroke) {
+                return match action {
+                    Some(KeyAction::AwaitMore) => update(
+                        &mut self.model,
+                        Msg::Ui(UiMsg::ChordPending(self.keymap.pending_chord_display())),
+                    ),
+                    Some(KeyAction::Execute(command)) => {
+                        let cleared = update(&mut self.model, Msg::Ui(UiMsg::ChordPending(None)));
+                        self.dispatch_command(command).or(cleared)
+                    }
+                    _ => update(
+                        &mut self.model,
+                        Msg::Ui(UiMsg::ChordNotBound(format!(
+                            "{} {}",
+                            prefix,
+                            keystroke.display_string()
+                        ))),
+                    ),
+                };
+            }
 
-                    // Convert the raw winit event to our Keystroke type once. This is a
-                    // pure conversion of the event + modifiers and doesn't depend on
-                    // keymap state, so the same value can be reused for both the
-                    // global-command check below and the non-global check further down
-                    // (previously this called keystroke_from_winit twice with identical
-                    // arguments).
-                    let keystroke = keystroke_from_winit(
-                        &event.logical_key,
-                        event.physical_key,
-                        ctrl,
-                        shift,
-                        alt,
-                        logo,
+            // Global commands (command palette, save, quit, etc.) work
+            // regardless of focus state
+            let action = match action {
+                Some(KeyAction::AwaitMore) if skip_keymap => {
+                    // Chords only start where the keymap applies; the key
+                    // keeps its single-keystroke binding elsewhere
+                    self.keymap.reset();
+                    keystroke
+                        .and_then(|k| self.keymap.lookup_with_context(&k, Some(&context)))
+                        .map(KeyAction::Execute)
+                }
+                action => action,
+            };
+            match action {
+                Some(KeyAction::Execute(command)) if command.is_global() => {
+                    return self.dispatch_command(command);
+                }
+                // The filter toggle also closes the filter row being typed in
+                Some(KeyAction::Execute(Command::CsvToggleFilter)) => {
+                    return self.dispatch_command(Command::CsvToggleFilter);
+                }
+                Some(KeyAction::AwaitMore) => {
+                    // Chord started - don't fall through to handle_key
+                    return update(
+                        &mut self.model,
+                        Msg::Ui(UiMsg::ChordPending(self.keymap.pending_chord_display())),
                     );
+                }
+                _ => {}
+            }
 
-                    // Check for global commands first (work regardless of focus state)
-                    // These include command palette, save, quit, etc.
-                    if let Some(keystroke) = keystroke {
-                        let context = self.get_key_context();
-                        if let KeyAction::Execute(command) = self
-                            .keymap
-                            .handle_keystroke_with_context(keystroke, Some(&context))
-                        {
-                            if command.is_global() {
-                                return self.dispatch_command(command);
-                            }
-                        }
-                        // Reset keymap state after global check (we'll re-check below if needed)
-                        self.keymap.reset();
-                    }
+            // The completion popup takes its navigation keys before the keymap
+            if let Some(msg) = completion_key_msg(&self.model, logical_key, modifiers) {
+                return update(&mut self.model, Msg::Completion(msg));
+            }
 
-                    // Try keymap for non-global commands, but only when:
-                    // - No modal is active (modals handled by handle_modal_key in input.rs)
-                    // - Not in option double-tap mode with alt pressed (multi-cursor gesture)
-                    // - Sidebar is not focused (sidebar keys handled by handle_sidebar_key in input.rs)
-                    // - Not editing a CSV cell (CSV cell editor handled by handle_csv_edit_key in input.rs)
-                    let sidebar_focused =
-                        matches!(self.model.ui.focus, token::model::FocusTarget::Sidebar);
-                    let terminal_focused = self.model.ui.focused_dock()
-                        == Some(token::panel::DockPosition::Bottom)
-                        && self.model.dock_layout.bottom.is_open
-                        && self.model.dock_layout.bottom.active_panel()
-                            == Some(token::panel::PanelId::TERMINAL);
-                    let skip_keymap = self.model.ui.has_modal()
-                        || (self.option_gesture.double_tapped && alt)
-                        || sidebar_focused
-                        || terminal_focused
-                        || self.model.is_csv_editing();
-
-                    if !skip_keymap {
-                        if let Some(keystroke) = keystroke {
-                            let context = self.get_key_context();
-                            match self
-                                .keymap
-                                .handle_keystroke_with_context(keystroke, Some(&context))
-                            {
-                                KeyAction::Execute(command) if command.is_simple() => {
-                                    return self.dispatch_command(command);
-                                }
-                                KeyAction::AwaitMore => {
-                                    // Chord in progress - don't fall through to handle_key
-                                    return Some(Cmd::Redraw);
-                                }
-                                _ => {
-                                    // NoMatch or complex command - fall through to handle_key
-                                }
-                            }
-                        }
+            if !skip_keymap {
+                if let Some(msg) = vim_key_msg(&self.model, logical_key, modifiers) {
+                    return update(&mut self.model, Msg::Vim(msg));
+                }
+                if let Some(KeyAction::Execute(command)) = action {
+                    if command.is_simple() {
+                        return self.dispatch_command(command);
                     }
+                    // Complex command - fall through to handle_key
+                }
+            }
+
+            // Fall back to legacy handle_key for complex/context-dependent behavior
+            handle_key(
+                &mut self.model,
+                logical_key.clone(),
+                physical_key,
+                modifiers,
+                self.option_gesture.double_tapped,
+            )
+        } else {
+            None
+        }
+    }
 
-                    // Fall back to legacy handle_key for complex/context-dependent behavior
-                    handle_key(
+    fn handle_event(&mut self, event: &WindowEvent) -> Option<Cmd> {
+        match event {
+            WindowEvent::Resized(size) => update(
+                &mut self.model,
+                Msg::App(AppMsg::Resize(size.width, size.height)),
+            ),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => update(
+                &mut self.model,
+                Msg::App(AppMsg::ScaleFactorChanged(*scale_factor)),
+            ),
+            WindowEvent::Focused(focused) => {
+                update(&mut self.model, Msg::Ui(UiMsg::WindowFocused(*focused)))
+            }
+            WindowEvent::Occluded(occluded) => {
+                update(&mut self.model, Msg::Ui(UiMsg::WindowOccluded(*occluded)))
+            }
+            WindowEvent::ModifiersChanged(mods) => {
+                self.modifiers = mods.state();
+                // Releasing Shift+Alt commits a column selection grown with
+                // the arrow keys
+                let column_selection = self.model.editor().rectangle_selection.from_keyboard;
+                if column_selection && !(self.modifiers.shift_key() && self.modifiers.alt_key()) {
+                    return update(
                         &mut self.model,
-                        event.logical_key.clone(),
-                        event.physical_key,
-                        modifiers,
-                        self.option_gesture.double_tapped,
-                    )
-                } else {
-                    None
+                        Msg::Editor(EditorMsg::FinishRectangleSelection),
+                    );
                 }
+                None
             }
+            WindowEvent::KeyboardInput { event, .. } => self.handle_keyboard_input(
+                &event.logical_key,
+                event.physical_key,
+                event.state,
+                event.repeat,
+            ),
             WindowEvent::RedrawRequested => {
+                // Frame-step mode: draw only the requested frames
+                #[cfg(debug_assertions)]
+                if let Some(ref mut overlay) = self.model.debug_overlay {
+                    if !overlay.take_frame_permit() {
+                        return None;
+                    }
+                }
                 if let Err(e) = self.render() {
                     eprintln!("Render error: {}", e);
                 }
@@ -529,11 +836,28 @@ impl App {
                     );
                 }
 
+                // Highlight the context menu item under the pointer
+                let pt = token::view::hit_test::Point::new(position.x, position.y);
+                if let Some(index) = token::view::hit_test::context_menu_item_at(&self.model, pt) {
+                    return update(
+                        &mut self.model,
+                        Msg::Ui(UiMsg::Modal(ModalMsg::SelectContextMenuItem(index))),
+                    );
+                }
+
                 // Handle tab drag (armed on tab press, activates past threshold)
                 if self.model.ui.tab_drag.is_some() {
                     return update_tab_drag(&mut self.model, position.x, position.y);
                 }
 
+                // Handle CSV column edge drag
+                if self.is_csv_column_resizing() {
+                    return update(
+                        &mut self.model,
+                        Msg::Csv(CsvMsg::UpdateColumnResize { x: position.x }),
+                    );
+                }
+
                 // Handle image panning and mouse tracking
                 if let Some(editor) = self.model.editor_area.focused_editor() {
                     if editor.view_mode.is_image() {
@@ -565,6 +889,29 @@ impl App {
                     }
                 }
 
+                // Dragging across a CSV grid grows a range of cells
+                let is_csv = self
+                    .model
+                    .editor_area
+                    .focused_editor()
+                    .is_some_and(|editor| editor.view_mode.is_csv());
+                if is_csv && self.drag.is_down() {
+                    self.drag.check_threshold(position.x, position.y);
+                    if !self.drag.is_active() {
+                        return None;
+                    }
+                    let cell = self.renderer.as_ref().and_then(|renderer| {
+                        renderer.pixel_to_csv_cell(position.x, position.y, &self.model)
+                    })?;
+                    return update(
+                        &mut self.model,
+                        Msg::Csv(CsvMsg::ExtendToCell {
+                            row: cell.row,
+                            col: cell.col,
+                        }),
+                    );
+                }
+
                 if self.model.editor().rectangle_selection.active {
                     if let Some(renderer) = &mut self.renderer {
                         // Use visual column (screen position) for rectangle selection
@@ -581,9 +928,24 @@ impl App {
                 } else if self.drag.is_down() {
                     if let Some(renderer) = &mut self.renderer {
                         // Check if drag threshold was just crossed
-                        if let Some((start_x, start_y)) =
-                            self.drag.check_threshold(position.x, position.y)
-                        {
+                        let threshold_crossed = self.drag.check_threshold(position.x, position.y);
+
+                        // Dragging the selection itself: track the drop position
+                        if self.model.editor().text_drag.is_some() {
+                            if !self.drag.is_active() {
+                                return None;
+                            }
+                            let (line, column) =
+                                renderer.pixel_to_cursor(position.x, position.y, &self.model);
+                            update(
+                                &mut self.model,
+                                Msg::Editor(EditorMsg::UpdateTextDrag { line, column }),
+                            );
+                            self.try_auto_scroll_for_drag(position.y);
+                            return Some(Cmd::Redraw);
+                        }
+
+                        if let Some((start_x, start_y)) = threshold_crossed {
                             let (start_line, start_col) =
                                 renderer.pixel_to_cursor(start_x, start_y, &self.model);
                             self.model.editor_mut().primary_selection_mut().anchor =
@@ -646,6 +1008,18 @@ impl App {
             } => {
                 self.drag.end();
 
+                // Drop dragged text (Alt copies instead of moving)
+                if let Some(text_drag) = self.model.editor().text_drag {
+                    if text_drag.drop.is_some() {
+                        let copy = self.modifiers.alt_key();
+                        return update(
+                            &mut self.model,
+                            Msg::Document(DocumentMsg::DropDraggedText { copy }),
+                        );
+                    }
+                    return update(&mut self.model, Msg::Editor(EditorMsg::CancelTextDrag));
+                }
+
                 // Finish tab drag if one is armed/active
                 if self.model.ui.tab_drag.is_some() {
                     if let Some(cmd) = end_tab_drag(&mut self.model) {
@@ -663,6 +1037,11 @@ impl App {
                     return update(&mut self.model, Msg::Ui(UiMsg::ScrollbarDragEnd));
                 }
 
+                // End CSV column edge drag if active
+                if self.is_csv_column_resizing() {
+                    return update(&mut self.model, Msg::Csv(CsvMsg::EndColumnResize));
+                }
+
                 // End sidebar resize drag if active
                 if self.model.ui.sidebar_resize.is_some() {
                     return update(
@@ -718,6 +1097,32 @@ impl App {
                 }
                 None
             }
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Right,
+                ..
+            } => {
+                if let Some((x, y)) = self.mouse_position {
+                    if let Some(renderer) = &mut self.renderer {
+                        let event = make_mouse_event(
+                            x,
+                            y,
+                            MouseButton::Right,
+                            ElementState::Pressed,
+                            1,
+                            self.modifiers,
+                        );
+                        let result = handle_mouse_press(
+                            &mut self.model,
+                            renderer,
+                            event,
+                            &mut self.click_tracker,
+                        );
+                        return result.cmd;
+                    }
+                }
+                None
+            }
             WindowEvent::MouseInput {
                 state: ElementState::Released,
                 button: MouseButton::Middle,
@@ -741,12 +1146,7 @@ impl App {
                 handle_mouse_wheel(&mut self.model, self.mouse_position, h_delta, v_delta)
             }
             WindowEvent::DroppedFile(path) => {
-                // Clear hover state first
-                self.model.ui.drop_state.cancel_hover();
-                update(
-                    &mut self.model,
-                    Msg::Layout(LayoutMsg::OpenFileInNewTab(path.clone())),
-                )
+                update(&mut self.model, Msg::Ui(UiMsg::FileDropped(path.clone())))
             }
             WindowEvent::HoveredFile(path) => {
                 update(&mut self.model, Msg::Ui(UiMsg::FileHovered(path.clone())))
@@ -764,7 +1164,11 @@ impl App {
         if let Some(renderer) = &mut self.renderer {
             // Take pending damage and reset to empty for next frame
             let damage = std::mem::take(&mut self.pending_damage);
+            let start = Instant::now();
             renderer.render(&mut self.model, &mut self.perf, &damage)?;
+            if self.frame_monitor.record(start.elapsed()) {
+                let _ = self.msg_tx.send(Msg::Ui(UiMsg::SlowFrames));
+            }
         }
 
         // Sync webviews with preview panes.
@@ -793,7 +1197,7 @@ impl App {
     /// Creates, updates, or destroys webviews as needed.
     fn sync_webviews(&mut self) {
         use super::webview::PreviewContent;
-        use token::markdown::{content_to_preview_html, PreviewTheme};
+        use token::markdown::{content_to_preview_html, scroll_to_line_on_load, PreviewTheme};
         use token::model::editor_area::PreviewId;
         use token::syntax::LanguageId;
         use token::view::geometry::PreviewPaneLayout;
@@ -835,6 +1239,9 @@ impl App {
             doc_revision: u64,
             needs_content_update: bool,
             needs_create: bool,
+            /// Source line the webview should be scrolled to
+            target_line: usize,
+            needs_scroll: bool,
         }
 
         let updates: Vec<PreviewUpdate> = self
@@ -846,6 +1253,8 @@ impl App {
                 let document = self.model.editor_area.documents.get(&preview.document_id)?;
                 let needs_create = !self.webview_manager.has_webview(preview_id);
                 let needs_content_update = preview.needs_refresh(document.revision);
+                let target_line = preview.webview_target_line();
+                let needs_scroll = preview.webview_line != Some(target_line);
 
                 let webview_rect =
                     PreviewPaneLayout::new(preview.rect, metrics).hosted_content_rect();
@@ -854,6 +1263,8 @@ impl App {
                 let content = if needs_create || needs_content_update {
                     let buffer_content = document.buffer.to_string();
                     let html = content_to_preview_html(&buffer_content, document.language, &theme)?;
+                    // A (re)load starts at the top; open where the reader is
+                    let html = scroll_to_line_on_load(html, target_line);
 
                     // For HTML files with a file path, enable local resource loading
                     Some(if document.language == LanguageId::Html {
@@ -883,6 +1294,8 @@ impl App {
                     doc_revision: document.revision,
                     needs_content_update,
                     needs_create,
+                    target_line,
+                    needs_scroll,
                 })
             })
             .collect();
@@ -909,6 +1322,7 @@ impl App {
                         window,
                         update.rect,
                         content,
+                        self.msg_tx.clone(),
                     ) {
                         tracing::error!("Failed to create webview for preview: {}", e);
                         continue;
@@ -917,6 +1331,7 @@ impl App {
                 // Update last_revision after successful creation
                 if let Some(preview) = self.model.editor_area.preview_mut(update.preview_id) {
                     preview.last_revision = update.doc_revision;
+                    preview.webview_line = Some(update.target_line);
                 }
             } else {
                 // Update existing webview bounds
@@ -937,6 +1352,14 @@ impl App {
                     // Update last_revision after content update
                     if let Some(preview) = self.model.editor_area.preview_mut(update.preview_id) {
                         preview.last_revision = update.doc_revision;
+                        preview.webview_line = Some(update.target_line);
+                    }
+                } else if update.needs_scroll {
+                    // Follow the editor's scrolling
+                    self.webview_manager
+                        .scroll_to_line(update.preview_id, update.target_line);
+                    if let Some(preview) = self.model.editor_area.preview_mut(update.preview_id) {
+                        preview.webview_line = Some(update.target_line);
                     }
                 }
             }
@@ -947,6 +1370,76 @@ impl App {
         update(&mut self.model, Msg::Ui(UiMsg::BlinkCursor))
     }
 
+    /// Replay a recording: restore its starting state, then feed its
+    /// messages in on a thread, keeping their original pacing (pauses are
+    /// capped at a second)
+    fn start_replay(&mut self, replay: token::replay::Replay) {
+        let warnings = replay.prepare(&mut self.model);
+        for warning in &warnings {
+            tracing::warn!("Replay: {}", warning);
+        }
+        let count = replay.entries.len();
+        self.model.ui.set_status(if warnings.is_empty() {
+            format!("Replaying {} messages", count)
+        } else {
+            format!("Replaying {} messages ({})", count, warnings.join(", "))
+        });
+
+        let tx = self.msg_tx.clone();
+        std::thread::spawn(move || {
+            let mut last_ms = 0;
+            for entry in replay.entries {
+                let pause = entry.at_ms.saturating_sub(last_ms).min(1000);
+                std::thread::sleep(Duration::from_millis(pause));
+                last_ms = entry.at_ms;
+                if tx.send(entry.msg.into_msg()).is_err() {
+                    return;
+                }
+            }
+            let _ = tx.send(Msg::Ui(UiMsg::SetStatus(format!(
+                "Replay finished ({} messages)",
+                count
+            ))));
+        });
+    }
+
+    /// Debug overlay keys: F9/F10 frame stepping, and the inspector's keys
+    /// while it is interactive (these don't reach the editor)
+    #[cfg(debug_assertions)]
+    fn handle_debug_overlay_key(&mut self, key: &Key) -> Option<Cmd> {
+        let mut overlay = self.model.debug_overlay.take()?;
+        let handled = match key {
+            Key::Named(NamedKey::F9) => {
+                overlay.toggle_frame_step();
+                true
+            }
+            Key::Named(NamedKey::F10) if overlay.frame_step => {
+                overlay.request_step();
+                true
+            }
+            _ if !overlay.interactive => false,
+            Key::Named(NamedKey::ArrowUp) => {
+                overlay.select_older();
+                true
+            }
+            Key::Named(NamedKey::ArrowDown) => {
+                overlay.select_newer();
+                true
+            }
+            Key::Named(NamedKey::Escape) => {
+                overlay.interactive = false;
+                true
+            }
+            Key::Character(c) if c.eq_ignore_ascii_case("p") => {
+                overlay.toggle_pin(self.model.focused_editor());
+                true
+            }
+            _ => false,
+        };
+        self.model.debug_overlay = Some(overlay);
+        handled.then_some(Cmd::Redraw)
+    }
+
     fn process_cmd(&mut self, cmd: Cmd) {
         match cmd {
             Cmd::None => {}
@@ -957,6 +1450,10 @@ impl App {
                 if let Err(e) = self.reinit_renderer(scale_factor) {
                     tracing::error!("Failed to reinitialize renderer: {}", e);
                 }
+                // New font metrics resize the terminal grid and CSV viewport
+                // as a window resize would
+                let (width, height) = self.model.window_size;
+                let _ = self.msg_tx.send(Msg::App(AppMsg::Resize(width, height)));
             }
             Cmd::SaveFile { path, content } => {
                 let tx = self.msg_tx.clone();
@@ -967,6 +1464,94 @@ impl App {
                     }
                 });
             }
+            Cmd::RunExternalTool { name, command, cwd } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = token::external_tools::run(&command, cwd.as_deref());
+                    let msg = Msg::App(AppMsg::ExternalToolFinished { name, result });
+                    if let Err(e) = tx.send(msg) {
+                        tracing::warn!("Failed to send tool result to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::AutosaveFile {
+                document_id,
+                path,
+                content,
+                saved_revision,
+            } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = std::fs::write(&path, content).map_err(|e| e.to_string());
+                    let msg = Msg::App(AppMsg::AutosaveCompleted {
+                        document_id,
+                        saved_revision,
+                        result,
+                    });
+                    if let Err(e) = tx.send(msg) {
+                        tracing::warn!("Failed to send autosave completion to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::TrashPath { path } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = match Trash::system() {
+                        Some(trash) => trash.trash(&path).map_err(|e| e.to_string()),
+                        None => Err("no trash folder available".to_string()),
+                    };
+                    let msg = Msg::Workspace(WorkspaceMsg::FileOperationCompleted(result));
+                    if let Err(e) = tx.send(msg) {
+                        tracing::warn!("Failed to send trash result to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::RenamePath { from, to } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = match Trash::system() {
+                        Some(trash) => trash.rename(&from, &to).map_err(|e| e.to_string()),
+                        None => Err("no trash folder available".to_string()),
+                    };
+                    let msg = Msg::Workspace(WorkspaceMsg::FileOperationCompleted(result));
+                    if let Err(e) = tx.send(msg) {
+                        tracing::warn!("Failed to send rename result to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::RevertFileOperation(operation) => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = operation.revert().map_err(|e| e.to_string());
+                    let msg =
+                        Msg::Workspace(WorkspaceMsg::FileOperationReverted { operation, result });
+                    if let Err(e) = tx.send(msg) {
+                        tracing::warn!("Failed to send undo result to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::CreateFile { path } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = token::file_ops::create_file(&path)
+                        .map(|()| path)
+                        .map_err(|e| e.to_string());
+                    if let Err(e) = tx.send(Msg::Workspace(WorkspaceMsg::FileCreated(result))) {
+                        tracing::warn!("Failed to send create result to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::CreateFolder { path } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = token::file_ops::create_folder(&path)
+                        .map(|()| path)
+                        .map_err(|e| e.to_string());
+                    if let Err(e) = tx.send(Msg::Workspace(WorkspaceMsg::FolderCreated(result))) {
+                        tracing::warn!("Failed to send create result to main thread: {}", e);
+                    }
+                });
+            }
             Cmd::LoadFile { path } => {
                 let tx = self.msg_tx.clone();
                 std::thread::spawn(move || {
@@ -976,6 +1561,47 @@ impl App {
                     }
                 });
             }
+            Cmd::LoadLargeFile { document_id, path } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    // Report each whole percent read, not every chunk
+                    let mut reported = None;
+                    let result = token::large_file::stream_file(&path, |loaded, total| {
+                        let percent = (loaded * 100).checked_div(total);
+                        if percent != reported {
+                            reported = percent;
+                            let msg = Msg::App(AppMsg::LargeFileProgress {
+                                document_id,
+                                loaded,
+                                total,
+                            });
+                            let _ = tx.send(msg);
+                        }
+                    });
+                    let msg = Msg::App(AppMsg::LargeFileLoaded {
+                        document_id,
+                        result,
+                    });
+                    if let Err(e) = tx.send(msg) {
+                        tracing::warn!("Failed to send large file to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::ReadChangedFile { document_id, path } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || match std::fs::read_to_string(&path) {
+                    Ok(content) => {
+                        let msg = Msg::App(AppMsg::FileChangedOnDisk {
+                            document_id,
+                            content,
+                        });
+                        if let Err(e) = tx.send(msg) {
+                            tracing::warn!("Failed to send changed file to main thread: {}", e);
+                        }
+                    }
+                    Err(e) => tracing::debug!("Failed to read {}: {}", path.display(), e),
+                });
+            }
             Cmd::OpenInExplorer { path } => {
                 #[cfg(target_os = "macos")]
                 {
@@ -996,6 +1622,20 @@ impl App {
                     }
                 }
             }
+            Cmd::OpenUrl { url } => {
+                if let Err(e) = open::that(&url) {
+                    tracing::warn!("Failed to open {}: {}", url, e);
+                }
+            }
+            Cmd::CheckForUpdates { manual } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = token::update_check::fetch_latest_release(
+                        token::update_check::RELEASE_FEED_URL,
+                    );
+                    let _ = tx.send(Msg::App(AppMsg::UpdateCheckCompleted { manual, result }));
+                });
+            }
             Cmd::RevealFileInFinder { path } => {
                 #[cfg(target_os = "macos")]
                 {
@@ -1042,34 +1682,33 @@ impl App {
                     }
                 });
             }
-            Cmd::CopyToClipboard(text) => {
+            Cmd::SaveWorkspaceTrust { trust } => {
                 std::thread::spawn(move || {
-                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                        if let Err(e) = clipboard.set_text(&text) {
-                            tracing::warn!("Failed to copy to clipboard: {}", e);
-                        }
-                    } else {
-                        tracing::warn!("Failed to initialize clipboard");
+                    if let Err(e) = trust.save() {
+                        tracing::warn!("Failed to save workspace trust: {}", e);
                     }
                 });
             }
-            Cmd::RequestClipboardPaste => {
+            Cmd::CopyToClipboard(text) => self.clipboard.copy(text),
+            Cmd::RequestClipboardPaste => self.clipboard.request_paste(self.msg_tx.clone()),
+            Cmd::SavePastedImage {
+                document_id,
+                path,
+                image,
+            } => {
                 let tx = self.msg_tx.clone();
                 std::thread::spawn(move || {
-                    let clipboard_text = if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                        clipboard.get_text().unwrap_or_default()
-                    } else {
-                        tracing::warn!("Failed to open clipboard for pasting");
-                        String::new()
-                    };
-                    if let Err(e) = tx.send(Msg::App(AppMsg::PasteFromClipboard(clipboard_text))) {
-                        tracing::warn!(
-                            "Failed to send clipboard paste message to main thread: {}",
-                            e
-                        );
+                    let result = token::paste_image::save_png(&image, &path)
+                        .map_err(|e| format!("{}: {}", path.display(), e));
+                    if let Err(e) = tx.send(Msg::App(AppMsg::PastedImageSaved {
+                        document_id,
+                        result,
+                    })) {
+                        tracing::warn!("Failed to send pasted image result to main thread: {}", e);
                     }
                 });
             }
+            Cmd::ReloadKeymap => self.reload_keymap(),
             Cmd::CreateDefaultKeymapFile { path } => {
                 let tx = self.msg_tx.clone();
                 std::thread::spawn(move || {
@@ -1123,6 +1762,100 @@ impl App {
                     }
                 });
             }
+            Cmd::ScanTodos {
+                generation,
+                request,
+            } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let files = token::todo::scan(request);
+                    if let Err(e) = tx.send(Msg::Todo(TodoMsg::ScanCompleted { generation, files }))
+                    {
+                        tracing::warn!("Failed to send TODO scan result to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::SearchProject {
+                generation,
+                request,
+                cancel,
+            } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let summary = token::project_search::search(request, &cancel, |files| {
+                        let msg = Msg::Search(SearchMsg::ResultsFound { generation, files });
+                        if let Err(e) = tx.send(msg) {
+                            tracing::warn!("Failed to send search results to main thread: {}", e);
+                        }
+                    });
+                    if let Err(e) = tx.send(Msg::Search(SearchMsg::Finished {
+                        generation,
+                        summary,
+                    })) {
+                        tracing::warn!("Failed to send search summary to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::ComputeGitDiff(request) => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = token::git::compute_diff(request);
+                    if let Err(e) = tx.send(Msg::Git(GitMsg::DiffComputed(result))) {
+                        tracing::warn!("Failed to send git diff to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::ComputeGitBlame(request) => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = token::git::compute_blame(request);
+                    if let Err(e) = tx.send(Msg::Git(GitMsg::BlameComputed(result))) {
+                        tracing::warn!("Failed to send git blame to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::FindBacklinks { request } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let target = request.target.clone();
+                    let backlinks = token::notes::find_backlinks(request);
+                    let msg = Msg::Notes(NotesMsg::BacklinksFound { target, backlinks });
+                    if let Err(e) = tx.send(msg) {
+                        tracing::warn!("Failed to send backlinks to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::AiChatRequest {
+                request_id,
+                config,
+                messages,
+                cancel,
+            } => {
+                let tx = self.msg_tx.clone();
+                std::thread::spawn(move || {
+                    let result = token::ai::create_provider(&config).and_then(|provider| {
+                        provider.stream_chat(&messages, &mut |text| {
+                            !cancel.load(std::sync::atomic::Ordering::Relaxed)
+                                && tx
+                                    .send(Msg::AiChat(AiChatMsg::StreamDelta {
+                                        request_id,
+                                        text: text.to_string(),
+                                    }))
+                                    .is_ok()
+                        })
+                    });
+                    let error = result.err();
+                    if let Some(e) = &error {
+                        tracing::warn!("AI chat request failed: {}", e);
+                    }
+                    if let Err(e) =
+                        tx.send(Msg::AiChat(AiChatMsg::StreamFinished { request_id, error }))
+                    {
+                        tracing::warn!("Failed to send AI chat result to main thread: {}", e);
+                    }
+                });
+            }
+            Cmd::Lsp(request) => self.lsp.handle(request),
             Cmd::Batch(cmds) => {
                 for cmd in cmds {
                     self.process_cmd(cmd);
@@ -1158,7 +1891,10 @@ impl App {
                 });
             }
 
-            Cmd::ShowSaveFileDialog { suggested_path } => {
+            Cmd::ShowSaveFileDialog {
+                document_id,
+                suggested_path,
+            } => {
                 let tx = self.msg_tx.clone();
                 std::thread::spawn(move || {
                     let mut dlg = rfd::FileDialog::new();
@@ -1172,7 +1908,8 @@ impl App {
                     }
 
                     let path = dlg.save_file();
-                    if let Err(e) = tx.send(Msg::App(AppMsg::SaveFileAsDialogResult { path })) {
+                    let msg = AppMsg::SaveFileAsDialogResult { document_id, path };
+                    if let Err(e) = tx.send(Msg::App(msg)) {
                         tracing::warn!(
                             "Failed to send save file dialog result to main thread: {}",
                             e
@@ -1227,6 +1964,7 @@ impl App {
                 revision,
                 source,
                 language,
+                edits,
             } => {
                 tracing::debug!(
                     "RunSyntaxParse: doc={} rev={} lang={:?} len={}",
@@ -1241,6 +1979,7 @@ impl App {
                     revision,
                     source,
                     language,
+                    edits,
                 })) {
                     tracing::warn!("Failed to send syntax parse request: {}", e);
                 }
@@ -1274,6 +2013,68 @@ impl App {
         }
     }
 
+    /// Wake the event loop as soon as a background thread sends a message,
+    /// instead of when it next wakes up on its own. Lets an inactive window
+    /// wait for events without missing file loads, saves or terminal output.
+    pub fn wake_on_messages(&mut self, proxy: winit::event_loop::EventLoopProxy<()>) {
+        let (forward_tx, forward_rx) = mpsc::channel();
+        let background_rx = std::mem::replace(&mut self.msg_rx, forward_rx);
+        std::thread::spawn(move || {
+            for msg in background_rx {
+                if forward_tx.send(msg).is_err() || proxy.send_event(()).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Give the app the proxy used by the native menu bar
+    #[cfg(target_os = "macos")]
+    pub fn set_menu_proxy(&mut self, proxy: winit::event_loop::EventLoopProxy<()>) {
+        self.menu_proxy = Some(proxy);
+    }
+
+    #[cfg(target_os = "macos")]
+    fn install_menu_bar(&mut self) {
+        let Some(proxy) = self.menu_proxy.take() else {
+            return;
+        };
+        match super::menu::MenuBar::install(self.msg_tx.clone(), proxy) {
+            Ok(menu_bar) => self.menu_bar = Some(menu_bar),
+            Err(e) => tracing::warn!("Failed to create menu bar: {}", e),
+        }
+    }
+
+    /// Save state that outlives the process, then stop the event loop
+    fn exit(&mut self, event_loop: &ActiveEventLoop) {
+        token::update::save_scratchpads(&mut self.model);
+        if let Some(path) = token::replay::stop_recording() {
+            tracing::info!("Session recorded to {}", path.display());
+        }
+        if let Err(e) = self.model.session().save() {
+            tracing::warn!("Failed to save session: {}", e);
+        }
+        if self.model.config.restore_session {
+            let root = self.model.workspace_root().map(PathBuf::as_path);
+            if let Err(e) = self.model.editor_area.session().save(root) {
+                tracing::warn!("Failed to save editor session: {}", e);
+            }
+        }
+        event_loop.exit();
+    }
+
+    /// Run the command an event produced, accumulating its damage. Returns
+    /// whether a redraw is needed.
+    fn apply_cmd(&mut self, cmd: Option<Cmd>) -> bool {
+        let Some(cmd) = cmd else {
+            return false;
+        };
+        let needs_redraw = cmd.needs_redraw();
+        self.pending_damage.merge(cmd.damage());
+        self.process_cmd(cmd);
+        needs_redraw
+    }
+
     fn process_async_messages(&mut self) -> bool {
         let mut needs_redraw = self.process_terminal_spawn_results();
         while let Ok(msg) = self.msg_rx.try_recv() {
@@ -1410,6 +2211,9 @@ impl ApplicationHandler for App {
             self.window = Some(window);
             self.context = Some(context);
 
+            #[cfg(target_os = "macos")]
+            self.install_menu_bar();
+
             // Dispatch background loads for any additional startup files
             for path in std::mem::take(&mut self.pending_file_loads) {
                 self.process_cmd(Cmd::OpenFileInEditor { path });
@@ -1426,15 +2230,8 @@ impl ApplicationHandler for App {
         let should_exit = matches!(event, WindowEvent::CloseRequested);
         let should_redraw = if let Some(window) = &self.window {
             if window_id == window.id() && !should_exit {
-                if let Some(cmd) = self.handle_event(&event) {
-                    let needs_redraw = cmd.needs_redraw();
-                    // Accumulate damage from command
-                    self.pending_damage.merge(cmd.damage());
-                    self.process_cmd(cmd);
-                    needs_redraw
-                } else {
-                    false
-                }
+                let cmd = self.handle_event(&event);
+                self.apply_cmd(cmd)
             } else {
                 false
             }
@@ -1443,7 +2240,7 @@ impl ApplicationHandler for App {
         };
 
         if should_exit || self.should_quit {
-            event_loop.exit();
+            self.exit(event_loop);
         } else if should_redraw {
             if let Some(window) = &self.window {
                 window.request_redraw();
@@ -1457,16 +2254,46 @@ impl ApplicationHandler for App {
         if self.process_async_messages() {
             needs_redraw = true;
         }
+        // Quit chosen from the menu bar arrives as an async message
+        if self.should_quit {
+            self.exit(event_loop);
+            return;
+        }
+        token::crash::record_state(&self.model);
 
         // Poll file system watcher for changes
         if self.poll_fs_watcher() {
             needs_redraw = true;
         }
+        if self
+            .keymap_watcher
+            .as_ref()
+            .is_some_and(|watcher| watcher.poll_changed())
+        {
+            self.reload_keymap();
+            needs_redraw = true;
+        }
+        if let Some(interval) = autosave_interval(&self.model) {
+            if self.last_autosave.elapsed() >= interval {
+                self.last_autosave = Instant::now();
+                let cmd = update(&mut self.model, Msg::App(AppMsg::AutosaveInterval));
+                if self.apply_cmd(cmd) {
+                    needs_redraw = true;
+                }
+            }
+        }
+        if self.keymap.expire_chord(Instant::now()) {
+            update(&mut self.model, Msg::Ui(UiMsg::ChordPending(None)));
+            needs_redraw = true;
+        }
 
         // Check syntax debounce deadlines
         if self.check_syntax_deadlines() {
             needs_redraw = true;
         }
+        if self.check_word_highlight_deadline() {
+            needs_redraw = true;
+        }
 
         if needs_redraw {
             if let Some(window) = &self.window {
@@ -1474,6 +2301,13 @@ impl ApplicationHandler for App {
             }
         }
 
+        // Unfocused or hidden: no blinking and no timer wake-ups. Input,
+        // focus changes and background messages still wake the loop.
+        if !self.model.ui.is_window_active() {
+            event_loop.set_control_flow(self.inactive_control_flow());
+            return;
+        }
+
         // Check if cursor blink timer has elapsed
         let now = Instant::now();
         let time_since_tick = now.duration_since(self.last_tick);
@@ -1498,11 +2332,30 @@ impl ApplicationHandler for App {
         if let Some(earliest_deadline) = self.syntax_deadlines.values().map(|(d, _)| *d).min() {
             next_wake = next_wake.min(earliest_deadline);
         }
+        if let Some(interval) = autosave_interval(&self.model) {
+            next_wake = next_wake.min(self.last_autosave + interval);
+        }
+        if let Some(chord_deadline) = self.keymap.chord_deadline() {
+            next_wake = next_wake.min(chord_deadline);
+        }
+        if let Some(deadline) = word_highlight_deadline(&self.model) {
+            next_wake = next_wake.min(deadline);
+        }
         event_loop.set_control_flow(ControlFlow::WaitUntil(next_wake));
     }
 }
 
 impl App {
+    /// Control flow while the window is inactive: wait for events (see
+    /// [`App::wake_on_messages`]), waking early only for a pending syntax
+    /// deadline. File system changes are picked up on the next wake-up.
+    fn inactive_control_flow(&self) -> ControlFlow {
+        match self.syntax_deadlines.values().map(|(d, _)| *d).min() {
+            Some(deadline) => ControlFlow::WaitUntil(deadline),
+            None => ControlFlow::Wait,
+        }
+    }
+
     /// Check syntax debounce deadlines and fire ParseReady for expired ones
     fn check_syntax_deadlines(&mut self) -> bool {
         if self.syntax_deadlines.is_empty() {
@@ -1545,20 +2398,28 @@ impl App {
         needs_redraw
     }
 
-    /// Poll file system watcher and dispatch events
-    /// Returns true if any events were processed
-    fn poll_fs_watcher(&mut self) -> bool {
-        let Some(watcher) = &self.fs_watcher else {
-            return false;
-        };
-
-        let events = watcher.poll_events();
-        if events.is_empty() {
+    /// Highlight the other occurrences of the word under the cursor once the
+    /// cursor has rested in it long enough
+    fn check_word_highlight_deadline(&mut self) -> bool {
+        if !word_highlight_deadline(&self.model).is_some_and(|deadline| Instant::now() >= deadline)
+        {
             return false;
         }
+        let cmd = update(&mut self.model, Msg::Ui(UiMsg::HighlightWordOccurrences));
+        self.apply_cmd(cmd)
+    }
+
+    /// Poll the file system watchers and dispatch events
+    /// Returns true if any events were processed
+    fn poll_fs_watcher(&mut self) -> bool {
+        self.sync_fs_watchers();
 
         // Extract changed paths from events for incremental update
-        let paths: Vec<_> = events
+        let mut paths: Vec<_> = self
+            .fs_watcher
+            .as_ref()
+            .map(|watcher| watcher.poll_events())
+            .unwrap_or_default()
             .into_iter()
             .map(|e| match e {
                 FileSystemEvent::Created(p)
@@ -1567,6 +2428,12 @@ impl App {
                 | FileSystemEvent::Changed(p) => p,
             })
             .collect();
+        if let Some(watcher) = &self.open_file_watcher {
+            paths.extend(watcher.poll_paths());
+        }
+        if paths.is_empty() {
+            return false;
+        }
 
         // Dispatch FileSystemChange with the changed paths for incremental update
         if let Some(cmd) = update(
@@ -1582,6 +2449,132 @@ impl App {
 
         true
     }
+
+    /// Load the keymap files again and swap in the new bindings. A user
+    /// keymap that fails to load keeps the current bindings.
+    fn reload_keymap(&mut self) {
+        let loaded = load_keymap();
+        let applied = !loaded.user_keymap_failed;
+        if applied {
+            self.keymap = Keymap::with_bindings(loaded.bindings);
+            update(&mut self.model, Msg::Ui(UiMsg::ChordPending(None)));
+        }
+        let msg = AppMsg::KeymapReloaded {
+            diagnostics: loaded.diagnostics,
+            applied,
+        };
+        if let Some(cmd) = update(&mut self.model, Msg::App(msg)) {
+            self.pending_damage.merge(cmd.damage());
+        }
+    }
+
+    /// Follow the workspace opened (or closed) since the last poll, and
+    /// watch the folders of open files outside it
+    fn sync_fs_watchers(&mut self) {
+        let root = self.model.workspace.as_ref().map(|ws| ws.root.clone());
+        if self.fs_watcher_root != root {
+            self.fs_watcher = root.clone().and_then(|root| {
+                FileSystemWatcher::new(root)
+                    .map_err(|e| tracing::warn!("Failed to start file system watcher: {}", e))
+                    .ok()
+            });
+            self.fs_watcher_root = root;
+        }
+
+        let Some(open_files) = &mut self.open_file_watcher else {
+            return;
+        };
+        let root = self.fs_watcher.as_ref().map(|w| w.root().as_path());
+        open_files.sync(
+            self.model
+                .editor_area
+                .documents
+                .values()
+                .filter(|doc| !doc.deleted_on_disk)
+                .filter_map(|doc| doc.file_path.as_deref())
+                .filter(|path| root.is_none_or(|root| !path.starts_with(root))),
+        );
+    }
+}
+
+/// Driving the app without a window or event loop (see
+/// [`super::headless`]). Events go through the same handlers as in a real
+/// window; frames are rendered into the renderer's back buffer.
+#[cfg(all(test, feature = "headless"))]
+impl App {
+    pub fn headless(
+        window_width: u32,
+        window_height: u32,
+        scale_factor: f64,
+        startup_config: StartupConfig,
+    ) -> Result<Self> {
+        let mut app = Self::new(window_width, window_height, startup_config);
+        let renderer = Renderer::headless(
+            window_width,
+            window_height,
+            scale_factor,
+            app.editor_fonts(),
+            app.model.config.font_size,
+        )?;
+        app.model.set_scale_factor(scale_factor);
+        app.use_renderer(renderer, window_width, window_height);
+        for path in std::mem::take(&mut app.pending_file_loads) {
+            app.process_cmd(Cmd::OpenFileInEditor { path });
+        }
+        Ok(app)
+    }
+
+    /// Handle `event` as if the window had received it. Returns whether a
+    /// redraw was requested.
+    pub fn inject_event(&mut self, event: &WindowEvent) -> bool {
+        let cmd = self.handle_event(event);
+        self.apply_cmd(cmd)
+    }
+
+    /// Handle a key press or release (winit's `KeyEvent` can't be built
+    /// outside winit). Returns whether a redraw was requested.
+    pub fn inject_key(
+        &mut self,
+        logical_key: &Key,
+        physical_key: PhysicalKey,
+        state: ElementState,
+    ) -> bool {
+        let cmd = self.handle_keyboard_input(logical_key, physical_key, state, false);
+        self.apply_cmd(cmd)
+    }
+
+    /// Handle `msg` as if it came from the event loop (for events that
+    /// can't be built outside winit). Returns whether a redraw was requested.
+    pub fn inject_msg(&mut self, msg: Msg) -> bool {
+        let cmd = update(&mut self.model, msg);
+        self.apply_cmd(cmd)
+    }
+
+    /// What the event loop does between events: handle background messages
+    /// and due syntax parses and word highlights. Returns whether anything
+    /// changed.
+    pub fn run_pending(&mut self) -> bool {
+        let messages = self.process_async_messages();
+        let syntax = self.check_syntax_deadlines();
+        let word_highlights = self.check_word_highlight_deadline();
+        messages || syntax || word_highlights
+    }
+
+    /// Render the accumulated damage, as on `RedrawRequested`
+    pub fn render_frame(&mut self) -> Result<()> {
+        self.render()
+    }
+
+    pub fn model(&self) -> &AppModel {
+        &self.model
+    }
+
+    /// Last rendered frame, `window_size.0` pixels per row
+    pub fn frame_buffer(&self) -> &[u32] {
+        self.renderer
+            .as_ref()
+            .map_or(&[], |renderer| renderer.frame_buffer())
+    }
 }
 
 #[cfg(test)]
@@ -1594,6 +2587,8 @@ mod tests {
             mode: StartupMode::Empty,
             initial_position: None,
             wait_mode: false,
+            record: None,
+            replay: None,
         }
     }
 
@@ -1725,11 +2720,12 @@ fn syntax_worker_loop(rx: Receiver<SyntaxWorkerRequest>, msg_tx: Sender<Msg>) {
                 req.language
             );
 
-            let highlights = parser_state.parse_and_highlight(
+            let highlights = parser_state.parse_and_highlight_edited(
                 &req.source,
                 req.language,
                 req.document_id,
                 req.revision,
+                req.edits.as_ref(),
             );
 
             // Extract outline from the cached tree (just parsed above)
@@ -1776,6 +2772,14 @@ fn handle_syntax_worker_request(
                 req.revision,
                 req.language
             );
+            // A request replacing one that was never parsed takes over its
+            // edits too
+            let mut req = req;
+            if let Some(previous) = pending.remove(&req.document_id) {
+                req.edits = previous
+                    .edits
+                    .and_then(|edits| edits.followed_by(previous.revision, req.edits.take()));
+            }
             pending.insert(req.document_id, req);
         }
         SyntaxWorkerRequest::ClearDocument(document_id) => {
@@ -0,0 +1,123 @@
+//! Persistent system clipboard handle
+//!
+//! On X11 (and XWayland) the copying application owns the clipboard contents
+//! and serves them on request, so a transient `arboard::Clipboard` per copy
+//! meant large copies could vanish once the handle was dropped, especially
+//! without a clipboard manager. The runtime keeps one handle for the whole
+//! session instead; dropping it on exit hands the contents over to the
+//! clipboard manager when one is running.
+
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use token::messages::{AppMsg, Msg};
+use token::paste_image::ClipboardImage;
+
+/// Shared clipboard handle, created lazily on first use
+#[derive(Default)]
+pub struct ClipboardHandle {
+    clipboard: Arc<Mutex<Option<arboard::Clipboard>>>,
+}
+
+impl ClipboardHandle {
+    /// Place `text` on the clipboard in the background
+    pub fn copy(&self, text: String) {
+        let clipboard = Arc::clone(&self.clipboard);
+        std::thread::spawn(move || {
+            with_clipboard(&clipboard, |clipboard| {
+                if let Err(e) = clipboard.set_text(text.as_str()) {
+                    tracing::warn!("Failed to copy to clipboard: {}", e);
+                }
+            });
+        });
+    }
+
+    /// Read the clipboard in the background and send the result back as
+    /// `AppMsg::PasteFromClipboard` (or `AppMsg::PasteImageFromClipboard`
+    /// when it holds only an image)
+    pub fn request_paste(&self, tx: Sender<Msg>) {
+        let clipboard = Arc::clone(&self.clipboard);
+        std::thread::spawn(move || {
+            let msg = with_clipboard(&clipboard, read_paste)
+                .unwrap_or_else(|| AppMsg::PasteFromClipboard(String::new()));
+            if let Err(e) = tx.send(Msg::App(msg)) {
+                tracing::warn!(
+                    "Failed to send clipboard paste message to main thread: {}",
+                    e
+                );
+            }
+        });
+    }
+}
+
+/// Run `f` with the shared clipboard, opening it if this is the first use
+/// (or an earlier attempt to open it failed)
+fn with_clipboard<T>(
+    clipboard: &Mutex<Option<arboard::Clipboard>>,
+    f: impl FnOnce(&mut arboard::Clipboard) -> T,
+) -> Option<T> {
+    let mut guard = clipboard.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.is_none() {
+        match arboard::Clipboard::new() {
+            Ok(opened) => *guard = Some(opened),
+            Err(e) => {
+                tracing::warn!("Failed to initialize clipboard: {}", e);
+                return None;
+            }
+        }
+    }
+    guard.as_mut().map(f)
+}
+
+/// What to paste for the current clipboard contents.
+///
+/// Files copied in a file manager are offered as a list of paths
+/// (`text/uri-list` on Linux), which is preferred over the plain-text
+/// alternative so they paste as full paths rather than bare file names.
+/// An image is only used when there is no text at all.
+fn read_paste(clipboard: &mut arboard::Clipboard) -> AppMsg {
+    if let Ok(paths) = clipboard.get().file_list() {
+        if !paths.is_empty() {
+            return AppMsg::PasteFromClipboard(paths_to_paste_text(&paths));
+        }
+    }
+    let text = clipboard.get_text().unwrap_or_default();
+    if text.is_empty() {
+        if let Ok(image) = clipboard.get_image() {
+            return AppMsg::PasteImageFromClipboard(ClipboardImage {
+                width: image.width,
+                height: image.height,
+                rgba: image.bytes.into_owned(),
+            });
+        }
+    }
+    AppMsg::PasteFromClipboard(text)
+}
+
+/// One path per line
+fn paths_to_paste_text(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pasted_file_list_is_one_path_per_line() {
+        let paths = vec![
+            PathBuf::from("/home/me/notes.md"),
+            PathBuf::from("/home/me/My Files/a.rs"),
+        ];
+        assert_eq!(
+            paths_to_paste_text(&paths),
+            "/home/me/notes.md\n/home/me/My Files/a.rs"
+        );
+        assert_eq!(paths_to_paste_text(&paths[..1]), "/home/me/notes.md");
+    }
+}
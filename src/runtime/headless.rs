@@ -0,0 +1,450 @@
+//! Headless harness for end-to-end tests
+//!
+//! [`HeadlessApp`] runs the real [`App`] without a window or event loop.
+//! Synthetic winit events go through the same handlers as in a window
+//! (`input`, `mouse`, hit testing), and every event that asks for a redraw
+//! is rendered into a buffer the test can inspect. Only built with the
+//! `headless` feature: `cargo test --features headless`.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{
+    DeviceId, ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+};
+use winit::keyboard::{Key, KeyCode, ModifiersState, NamedKey, NativeKeyCode, PhysicalKey};
+
+use token::cli::{StartupConfig, StartupMode};
+use token::messages::{AppMsg, Msg};
+use token::model::AppModel;
+
+use super::App;
+
+/// Background work (file loads, syntax parses) stops arriving after this long
+const SETTLE_QUIET: Duration = Duration::from_millis(150);
+/// Give up waiting for background work after this long
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The "command" modifier of the default keymap: Cmd on macOS, Ctrl elsewhere
+pub const PRIMARY: ModifiersState = if cfg!(target_os = "macos") {
+    ModifiersState::SUPER
+} else {
+    ModifiersState::CONTROL
+};
+
+pub struct HeadlessApp {
+    app: App,
+    modifiers: ModifiersState,
+}
+
+impl HeadlessApp {
+    /// An 800x600 window at scale 1 opened on `mode`
+    pub fn new(mode: StartupMode) -> Self {
+        Self::with_size(mode, 800, 600, 1.0)
+    }
+
+    /// The editor opened on `path`, with background loading and parsing done
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let mut app = Self::new(StartupMode::SingleFile(path.into()));
+        app.settle();
+        app
+    }
+
+    pub fn with_size(mode: StartupMode, width: u32, height: u32, scale_factor: f64) -> Self {
+        let startup_config = StartupConfig {
+            mode,
+            initial_position: None,
+            wait_mode: false,
+            record: None,
+            replay: None,
+        };
+        let app =
+            App::headless(width, height, scale_factor, startup_config).expect("headless renderer");
+        let mut app = Self {
+            app,
+            modifiers: ModifiersState::empty(),
+        };
+        app.render();
+        app
+    }
+
+    pub fn model(&self) -> &AppModel {
+        self.app.model()
+    }
+
+    // === Window ===
+
+    /// Handle `event` as the window would, rendering if it asks for a redraw
+    pub fn send(&mut self, event: WindowEvent) {
+        if self.app.inject_event(&event) {
+            self.render();
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.send(WindowEvent::Resized(PhysicalSize::new(width, height)));
+    }
+
+    /// Move the window to a display with another scale factor. winit's
+    /// `ScaleFactorChanged` can't be built outside winit; the app handles
+    /// it by sending this message.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        if self
+            .app
+            .inject_msg(Msg::App(AppMsg::ScaleFactorChanged(scale_factor)))
+        {
+            self.render();
+        }
+    }
+
+    // === Keyboard ===
+
+    /// Hold down `modifiers` (replacing those held before)
+    pub fn set_modifiers(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+        self.send(WindowEvent::ModifiersChanged(modifiers.into()));
+    }
+
+    /// Press and release `key` with the current modifiers
+    pub fn press(&mut self, key: Key) {
+        let physical_key = physical_key_for(&key);
+        if self
+            .app
+            .inject_key(&key, physical_key, ElementState::Pressed)
+        {
+            self.render();
+        }
+        if self
+            .app
+            .inject_key(&key, physical_key, ElementState::Released)
+        {
+            self.render();
+        }
+    }
+
+    pub fn press_named(&mut self, key: NamedKey) {
+        self.press(Key::Named(key));
+    }
+
+    /// Press `key` while holding `modifiers`, then let go of them
+    pub fn chord(&mut self, modifiers: ModifiersState, key: Key) {
+        let held = self.modifiers;
+        self.set_modifiers(modifiers);
+        self.press(key);
+        self.set_modifiers(held);
+    }
+
+    /// Type `text` one key per character
+    pub fn type_text(&mut self, text: &str) {
+        for ch in text.chars() {
+            match ch {
+                '\n' => self.press_named(NamedKey::Enter),
+                '\t' => self.press_named(NamedKey::Tab),
+                ' ' => self.press_named(NamedKey::Space),
+                ch => self.press(Key::Character(ch.to_string().into())),
+            }
+        }
+    }
+
+    // === Mouse ===
+
+    pub fn move_mouse(&mut self, x: f64, y: f64) {
+        self.send(WindowEvent::CursorMoved {
+            device_id: DeviceId::dummy(),
+            position: PhysicalPosition::new(x, y),
+        });
+    }
+
+    pub fn mouse_button(&mut self, button: MouseButton, state: ElementState) {
+        self.send(WindowEvent::MouseInput {
+            device_id: DeviceId::dummy(),
+            state,
+            button,
+        });
+    }
+
+    /// Left click at `(x, y)`
+    pub fn click(&mut self, x: f64, y: f64) {
+        self.move_mouse(x, y);
+        self.mouse_button(MouseButton::Left, ElementState::Pressed);
+        self.mouse_button(MouseButton::Left, ElementState::Released);
+    }
+
+    /// Press at `from`, move to `to` in a few steps, release
+    pub fn drag(&mut self, from: (f64, f64), to: (f64, f64)) {
+        const STEPS: usize = 4;
+        self.move_mouse(from.0, from.1);
+        self.mouse_button(MouseButton::Left, ElementState::Pressed);
+        for step in 1..=STEPS {
+            let t = step as f64 / STEPS as f64;
+            self.move_mouse(from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        }
+        self.mouse_button(MouseButton::Left, ElementState::Released);
+    }
+
+    /// Scroll by whole lines; positive `lines` scrolls towards the top, as a
+    /// wheel turned away from the user does
+    pub fn scroll_lines(&mut self, columns: f32, lines: f32) {
+        self.send(WindowEvent::MouseWheel {
+            device_id: DeviceId::dummy(),
+            delta: MouseScrollDelta::LineDelta(columns, lines),
+            phase: TouchPhase::Moved,
+        });
+    }
+
+    // === Event loop ===
+
+    /// Run background work (file loads, syntax parses, saves) to completion,
+    /// as the event loop would between input events, then render
+    pub fn settle(&mut self) {
+        let start = Instant::now();
+        let mut last_change = Instant::now();
+        while start.elapsed() < SETTLE_TIMEOUT && last_change.elapsed() < SETTLE_QUIET {
+            if self.app.run_pending() {
+                last_change = Instant::now();
+            } else {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        }
+        self.render();
+    }
+
+    /// Render the damage accumulated so far
+    pub fn render(&mut self) {
+        self.app.render_frame().expect("headless render");
+    }
+
+    // === Frame ===
+
+    /// The last rendered frame, `window_size.0` pixels per row
+    pub fn frame(&self) -> &[u32] {
+        self.app.frame_buffer()
+    }
+
+    /// Pixel at `(x, y)` of the last rendered frame
+    pub fn pixel(&self, x: usize, y: usize) -> u32 {
+        let width = self.model().window_size.0 as usize;
+        self.frame()[y * width + x]
+    }
+}
+
+/// Physical key a US keyboard would report for `key`, for code that looks
+/// at the physical key (e.g. Option+letter on macOS)
+fn physical_key_for(key: &Key) -> PhysicalKey {
+    let code = match key {
+        Key::Named(named) => match named {
+            NamedKey::Enter => Some(KeyCode::Enter),
+            NamedKey::Escape => Some(KeyCode::Escape),
+            NamedKey::Tab => Some(KeyCode::Tab),
+            NamedKey::Backspace => Some(KeyCode::Backspace),
+            NamedKey::Delete => Some(KeyCode::Delete),
+            NamedKey::Space => Some(KeyCode::Space),
+            NamedKey::ArrowUp => Some(KeyCode::ArrowUp),
+            NamedKey::ArrowDown => Some(KeyCode::ArrowDown),
+            NamedKey::ArrowLeft => Some(KeyCode::ArrowLeft),
+            NamedKey::ArrowRight => Some(KeyCode::ArrowRight),
+            NamedKey::Home => Some(KeyCode::Home),
+            NamedKey::End => Some(KeyCode::End),
+            NamedKey::PageUp => Some(KeyCode::PageUp),
+            NamedKey::PageDown => Some(KeyCode::PageDown),
+            _ => None,
+        },
+        Key::Character(text) => match text.chars().next().map(|ch| ch.to_ascii_lowercase()) {
+            Some(ch @ 'a'..='z') => Some(LETTERS[(ch as u8 - b'a') as usize]),
+            Some(ch @ '0'..='9') => Some(DIGITS[(ch as u8 - b'0') as usize]),
+            _ => None,
+        },
+        _ => None,
+    };
+    code.map_or(
+        PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+        PhysicalKey::Code,
+    )
+}
+
+const LETTERS: [KeyCode; 26] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+];
+
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use token::model::{HoverRegion, ModalState};
+    use token::view::geometry::GroupLayout;
+
+    fn open_text(text: &str) -> (HeadlessApp, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, text).unwrap();
+        (HeadlessApp::open(path), dir)
+    }
+
+    /// Window coordinates just inside `(line, column)` in the focused group
+    /// (clicks land on the nearest character boundary)
+    fn text_position(app: &HeadlessApp, line: usize, column: usize) -> (f64, f64) {
+        let model = app.model();
+        let group = model.editor_area.focused_group().unwrap();
+        let layout = GroupLayout::new(group, model, model.char_width);
+        let x = layout.text_start_x as f64 + (column as f64 + 0.25) * model.char_width as f64;
+        let y = layout.content_rect.y as f64 + (line as f64 + 0.5) * model.line_height as f64;
+        (x, y)
+    }
+
+    #[test]
+    fn typing_edits_the_document_and_the_frame() {
+        let (mut app, _dir) = open_text("hello\n");
+        assert_eq!(app.model().document().buffer.to_string(), "hello\n");
+        let before = app.frame().to_vec();
+
+        app.press_named(NamedKey::End);
+        app.type_text(" world");
+        assert_eq!(app.model().document().buffer.to_string(), "hello world\n");
+        assert_ne!(app.frame(), &before[..]);
+
+        // The cursor line is painted with something other than the background
+        let (x, y) = text_position(&app, 0, 0);
+        let background = app.pixel(app.model().window_size.0 as usize - 2, y as usize);
+        assert!((x as usize..x as usize + 40).any(|x| app.pixel(x, y as usize) != background));
+
+        app.chord(PRIMARY, Key::Character("z".into()));
+        assert_ne!(app.model().document().buffer.to_string(), "hello world\n");
+    }
+
+    #[test]
+    fn clicking_in_the_text_moves_the_cursor() {
+        let (mut app, _dir) = open_text("first line\nsecond line\nthird line\n");
+        let (x, y) = text_position(&app, 1, 3);
+        app.click(x, y);
+
+        let cursor = app.model().editor().active_cursor();
+        assert_eq!((cursor.line, cursor.column), (1, 3));
+    }
+
+    #[test]
+    fn dragging_selects_text() {
+        let (mut app, _dir) = open_text("first line\nsecond line\n");
+        let from = text_position(&app, 0, 0);
+        let to = text_position(&app, 0, 5);
+        app.drag(from, to);
+
+        let selection = app.model().editor().active_selection();
+        assert_eq!((selection.start().column, selection.end().column), (0, 5));
+    }
+
+    #[test]
+    fn resize_and_scale_factor_change_relayout() {
+        let (mut app, _dir) = open_text("text\n");
+        app.resize(1024, 700);
+        assert_eq!(app.model().window_size, (1024, 700));
+        assert_eq!(app.frame().len(), 1024 * 700);
+
+        let line_height = app.model().line_height;
+        app.set_scale_factor(2.0);
+        assert!(app.model().line_height > line_height);
+        assert_eq!(app.frame().len(), 1024 * 700);
+    }
+
+    #[test]
+    fn command_palette_opens_and_closes_from_the_keyboard() {
+        let mut app = HeadlessApp::new(StartupMode::Empty);
+        app.chord(PRIMARY | ModifiersState::SHIFT, Key::Character("a".into()));
+        assert!(matches!(
+            app.model().ui.active_modal,
+            Some(ModalState::CommandPalette(_))
+        ));
+
+        app.press_named(NamedKey::Escape);
+        assert!(app.model().ui.active_modal.is_none());
+    }
+
+    #[test]
+    fn find_searches_while_typing_and_f3_moves_between_matches() {
+        let (mut app, _dir) = open_text("alpha beta alpha\n");
+        let selected = |app: &HeadlessApp| {
+            let selection = app.model().editor().active_selection();
+            (selection.start().column, selection.end().column)
+        };
+
+        app.chord(PRIMARY, Key::Character("f".into()));
+        app.type_text("alpha");
+        assert_eq!(selected(&app), (0, 5));
+        let first = app.frame().to_vec();
+
+        app.press_named(NamedKey::F3);
+        assert_eq!(selected(&app), (11, 16));
+        assert_ne!(app.frame(), &first[..], "current match moved");
+
+        app.chord(ModifiersState::SHIFT, Key::Named(NamedKey::F3));
+        assert_eq!(selected(&app), (0, 5));
+        assert!(matches!(
+            app.model().ui.active_modal,
+            Some(ModalState::FindReplace(_))
+        ));
+    }
+
+    #[test]
+    fn hovering_the_status_bar_tracks_the_region() {
+        let (mut app, _dir) = open_text("text\n");
+        let (width, height) = app.model().window_size;
+        let line_height = app.model().line_height;
+        app.move_mouse(
+            width as f64 / 2.0,
+            (height as usize - line_height / 2) as f64,
+        );
+        assert_eq!(app.model().ui.hover, HoverRegion::StatusBar);
+    }
+
+    #[test]
+    fn scrolling_moves_the_viewport() {
+        let text: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        let (mut app, _dir) = open_text(&text);
+        let (x, y) = text_position(&app, 2, 2);
+        app.move_mouse(x, y);
+
+        app.scroll_lines(0.0, -5.0);
+        let top_line = app.model().editor().viewport.top_line;
+        assert!(top_line > 0);
+
+        app.scroll_lines(0.0, 5.0);
+        assert!(app.model().editor().viewport.top_line < top_line);
+    }
+}
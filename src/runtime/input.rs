@@ -6,6 +6,7 @@
 //! Most keybindings are handled by the keymap system in `src/keymap/`.
 //! This file handles:
 //! - Modal input routing (when a modal dialog is active)
+//! - Completion popup keys (selection, accept, dismiss)
 //! - CSV cell editing input routing
 //! - Option double-tap for multi-cursor creation
 //! - Navigation with selection collapse (moving clears selection first)
@@ -16,14 +17,19 @@ use std::time::{Duration, Instant};
 use winit::keyboard::{Key, NamedKey};
 
 use token::commands::Cmd;
+use token::editable::{MoveTarget, TextEditMsg};
 use token::messages::{
-    CsvMsg, Direction, DocumentMsg, EditorMsg, LayoutMsg, ModalMsg, Msg, OutlineMsg, TerminalMsg,
-    UiMsg, WorkspaceMsg,
+    AiChatMsg, CompletionMsg, CsvMsg, Direction, DocumentMsg, EditorMsg, LayoutMsg, ModalMsg, Msg,
+    OutlineMsg, SearchMsg, TerminalMsg, TodoMsg, UiMsg, VimMsg, WorkspaceMsg,
+};
+use token::model::{
+    AppModel, DeletedFileChoice, FileChangedChoice, FocusTarget, ModalState, UnsavedChangesChoice,
+    WorkspaceTrustChoice,
 };
-use token::model::AppModel;
 use token::panel::{DockPosition, PanelId};
 use token::terminal::{translate_key, TerminalKeyModifiers};
 use token::update::update;
+use token::vim::VimMode;
 
 /// Bundles the four keyboard modifier flags (Ctrl, Shift, Alt, Logo/Cmd) that
 /// are threaded through nearly every keyboard-handling function in this module.
@@ -66,6 +72,60 @@ impl OptionKeyGesture {
     }
 }
 
+/// The popup message for a key pressed while the completion popup is open:
+/// Up/Down move the selection, Enter/Tab accept it and Escape closes the
+/// popup. Other keys reach the editor as usual (typing re-filters the list).
+pub fn completion_key_msg(
+    model: &AppModel,
+    key: &Key,
+    modifiers: KeyModifiers,
+) -> Option<CompletionMsg> {
+    let KeyModifiers {
+        ctrl,
+        shift,
+        alt,
+        logo,
+    } = modifiers;
+    if model.ui.completion.is_none() || model.ui.has_modal() || ctrl || shift || alt || logo {
+        return None;
+    }
+    match key {
+        Key::Named(NamedKey::ArrowUp) => Some(CompletionMsg::SelectPrevious),
+        Key::Named(NamedKey::ArrowDown) => Some(CompletionMsg::SelectNext),
+        Key::Named(NamedKey::Enter | NamedKey::Tab) => Some(CompletionMsg::Accept),
+        Key::Named(NamedKey::Escape) => Some(CompletionMsg::Dismiss),
+        _ => None,
+    }
+}
+
+/// The Vim message for a key pressed in Vim normal or visual mode: typed
+/// characters run commands instead of inserting text. Space, Backspace and
+/// Enter move like `l`, `h` and `j`; Escape only cancels a partly typed
+/// command here (leaving insert and visual mode is a keybinding).
+pub fn vim_key_msg(model: &AppModel, key: &Key, modifiers: KeyModifiers) -> Option<VimMsg> {
+    let KeyModifiers {
+        ctrl, alt, logo, ..
+    } = modifiers;
+    if !model.config.vim_mode || model.vim.mode == VimMode::Insert || ctrl || alt || logo {
+        return None;
+    }
+    if model.ui.has_modal() || model.ui.focus != FocusTarget::Editor {
+        return None;
+    }
+    if !model.editor_area.focused_editor()?.is_plain_text_mode() {
+        return None;
+    }
+    match key {
+        Key::Character(text) => text.chars().next().map(VimMsg::Key),
+        Key::Named(NamedKey::Space) => Some(VimMsg::Key('l')),
+        Key::Named(NamedKey::Backspace) => Some(VimMsg::Key('h')),
+        Key::Named(NamedKey::Enter) => Some(VimMsg::Key('j')),
+        Key::Named(NamedKey::Delete) => Some(VimMsg::Key('x')),
+        Key::Named(NamedKey::Escape) if !model.vim.pending().is_empty() => Some(VimMsg::NormalMode),
+        _ => None,
+    }
+}
+
 /// Handle keyboard input for special cases not covered by keymap
 ///
 /// Called as a fallback when:
@@ -76,7 +136,7 @@ impl OptionKeyGesture {
 pub fn handle_key(
     model: &mut AppModel,
     key: Key,
-    _physical_key: winit::keyboard::PhysicalKey,
+    physical_key: winit::keyboard::PhysicalKey,
     modifiers: KeyModifiers,
     option_double_tapped: bool,
 ) -> Option<Cmd> {
@@ -96,6 +156,9 @@ pub fn handle_key(
 
     // Focus capture: route keys to modal when active
     if model.ui.has_modal() {
+        if let Some(msg) = find_replace_key_msg(model, &key, physical_key, modifiers) {
+            return update(model, Msg::Ui(UiMsg::Modal(msg)));
+        }
         return handle_modal_key(model, key, modifiers);
     }
 
@@ -104,10 +167,18 @@ pub fn handle_key(
         return handle_csv_edit_key(model, key, modifiers);
     }
 
+    // Focus capture: route keys to the CSV filter row while typing a filter
+    if model.is_csv_filtering() {
+        return handle_csv_filter_key(model, key, modifiers);
+    }
+
     // Focus capture: route keys exclusively to sidebar when it has focus
     // Keys that sidebar doesn't handle are consumed (not passed to editor)
     if is_sidebar_focused(model) {
-        return handle_sidebar_key(model, &key, ctrl).or(Some(Cmd::Redraw));
+        if is_naming_in_sidebar(model) {
+            return handle_sidebar_name_key(model, &key, modifiers).or(Some(Cmd::Redraw));
+        }
+        return handle_sidebar_key(model, &key, ctrl, logo).or(Some(Cmd::Redraw));
     }
 
     // Focus capture: route keys to outline panel when right dock outline has focus
@@ -115,6 +186,21 @@ pub fn handle_key(
         return handle_outline_dock_key(model, &key).or(Some(Cmd::Redraw));
     }
 
+    // Focus capture: route keys to the TODO panel when it has focus
+    if is_todo_dock_focused(model) {
+        return handle_todo_dock_key(model, &key, ctrl).or(Some(Cmd::Redraw));
+    }
+
+    // Focus capture: route keys to the search panel when it has focus
+    if is_search_dock_focused(model) {
+        return handle_search_dock_key(model, &key, physical_key, modifiers).or(Some(Cmd::Redraw));
+    }
+
+    // Focus capture: route keys to the AI chat prompt when it has focus
+    if is_ai_chat_dock_focused(model) {
+        return handle_ai_chat_dock_key(model, &key, modifiers).or(Some(Cmd::Redraw));
+    }
+
     // Focus capture: route keys to terminal panel when bottom dock terminal has focus
     if is_terminal_dock_focused(model) {
         return handle_terminal_dock_key(model, &key, modifiers).or(Some(Cmd::Redraw));
@@ -469,6 +555,16 @@ fn dispatch_csv_text_edit(model: &mut AppModel, action: TextEditingKeyAction) ->
 fn handle_modal_key(model: &mut AppModel, key: Key, modifiers: KeyModifiers) -> Option<Cmd> {
     let KeyModifiers { shift, alt, .. } = modifiers;
 
+    // The edit review modal has no text input: letters are hunk commands
+    if matches!(model.ui.active_modal, Some(ModalState::EditReview(_))) {
+        if let Some(msg) = edit_review_key_msg(&key, modifiers) {
+            return update(model, Msg::Ui(UiMsg::Modal(msg)));
+        }
+    }
+    if let Some(msg) = button_prompt_key_msg(model.ui.active_modal.as_ref(), &key, modifiers) {
+        return update(model, Msg::Ui(UiMsg::Modal(msg)));
+    }
+
     match key {
         // Escape: close modal
         Key::Named(NamedKey::Escape) => update(model, Msg::Ui(UiMsg::Modal(ModalMsg::Close))),
@@ -493,6 +589,124 @@ fn handle_modal_key(model: &mut AppModel, key: Key, modifiers: KeyModifiers) ->
     }
 }
 
+/// Keys of the find modal: F3 / Shift+F3 go to the next / previous match,
+/// Tab switches between the query and replacement fields, Cmd+Alt+Enter
+/// replaces all matches, and Alt+L / Alt+C / Alt+R toggle searching in the
+/// selection, case sensitivity and regex mode. The Alt toggles are matched
+/// on the physical key, since Option+letter types a character on macOS.
+fn find_replace_key_msg(
+    model: &AppModel,
+    key: &Key,
+    physical_key: winit::keyboard::PhysicalKey,
+    modifiers: KeyModifiers,
+) -> Option<ModalMsg> {
+    use winit::keyboard::{KeyCode, PhysicalKey};
+
+    if !matches!(model.ui.active_modal, Some(ModalState::FindReplace(_))) {
+        return None;
+    }
+    let KeyModifiers {
+        ctrl,
+        shift,
+        alt,
+        logo,
+    } = modifiers;
+    match key {
+        Key::Named(NamedKey::F3) if !ctrl && !alt && !logo => Some(if shift {
+            ModalMsg::FindPrevious
+        } else {
+            ModalMsg::FindNext
+        }),
+        Key::Named(NamedKey::Tab) if !ctrl && !alt && !logo => {
+            Some(ModalMsg::ToggleFindReplaceField)
+        }
+        Key::Named(NamedKey::Enter) if (ctrl || logo) && alt && !shift => {
+            Some(ModalMsg::ReplaceAll)
+        }
+        _ if alt && !ctrl && !logo && !shift => match physical_key {
+            PhysicalKey::Code(KeyCode::KeyL) => Some(ModalMsg::ToggleFindInSelection),
+            PhysicalKey::Code(KeyCode::KeyC) => Some(ModalMsg::ToggleFindReplaceCaseSensitive),
+            PhysicalKey::Code(KeyCode::KeyR) => Some(ModalMsg::ToggleFindRegex),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Hunk commands in the edit review modal (Space / A / R)
+fn edit_review_key_msg(key: &Key, modifiers: KeyModifiers) -> Option<ModalMsg> {
+    if modifiers.ctrl || modifiers.logo || modifiers.alt {
+        return None;
+    }
+    match key {
+        Key::Named(NamedKey::Space) => Some(ModalMsg::ToggleReviewHunk),
+        Key::Character(s) if s.eq_ignore_ascii_case("a") => Some(ModalMsg::SetAllReviewHunks(true)),
+        Key::Character(s) if s.eq_ignore_ascii_case("r") => {
+            Some(ModalMsg::SetAllReviewHunks(false))
+        }
+        _ => None,
+    }
+}
+
+/// Button navigation (Left / Right / Tab) and letter shortcuts in the
+/// button prompts: S save / D don't save, R recreate / A save as, R reload /
+/// K keep
+fn button_prompt_key_msg(
+    modal: Option<&ModalState>,
+    key: &Key,
+    modifiers: KeyModifiers,
+) -> Option<ModalMsg> {
+    if modifiers.ctrl || modifiers.logo || modifiers.alt {
+        return None;
+    }
+    if !matches!(
+        modal,
+        Some(
+            ModalState::UnsavedChanges(_)
+                | ModalState::DeletedFile(_)
+                | ModalState::FileChanged(_)
+                | ModalState::WorkspaceTrust(_)
+        )
+    ) {
+        return None;
+    }
+    let shortcut =
+        |letter: &str| matches!(key, Key::Character(s) if s.eq_ignore_ascii_case(letter));
+    match key {
+        Key::Named(NamedKey::ArrowLeft) => return Some(ModalMsg::SelectPrevious),
+        Key::Named(NamedKey::Tab) if modifiers.shift => return Some(ModalMsg::SelectPrevious),
+        Key::Named(NamedKey::ArrowRight | NamedKey::Tab) => return Some(ModalMsg::SelectNext),
+        _ => {}
+    }
+    match modal {
+        Some(ModalState::UnsavedChanges(_)) if shortcut("s") => {
+            Some(ModalMsg::ResolveUnsavedChanges(UnsavedChangesChoice::Save))
+        }
+        Some(ModalState::UnsavedChanges(_)) if shortcut("d") => Some(
+            ModalMsg::ResolveUnsavedChanges(UnsavedChangesChoice::DontSave),
+        ),
+        Some(ModalState::DeletedFile(_)) if shortcut("r") => {
+            Some(ModalMsg::ResolveDeletedFile(DeletedFileChoice::Recreate))
+        }
+        Some(ModalState::DeletedFile(_)) if shortcut("a") => {
+            Some(ModalMsg::ResolveDeletedFile(DeletedFileChoice::SaveAs))
+        }
+        Some(ModalState::FileChanged(_)) if shortcut("r") => {
+            Some(ModalMsg::ResolveFileChanged(FileChangedChoice::Reload))
+        }
+        Some(ModalState::FileChanged(_)) if shortcut("k") => {
+            Some(ModalMsg::ResolveFileChanged(FileChangedChoice::Keep))
+        }
+        Some(ModalState::WorkspaceTrust(_)) if shortcut("t") => {
+            Some(ModalMsg::ResolveWorkspaceTrust(WorkspaceTrustChoice::Trust))
+        }
+        Some(ModalState::WorkspaceTrust(_)) if shortcut("r") => Some(
+            ModalMsg::ResolveWorkspaceTrust(WorkspaceTrustChoice::Restrict),
+        ),
+        _ => None,
+    }
+}
+
 /// Handle keyboard input when editing a CSV cell
 ///
 /// This captures focus and routes keys to the cell editor instead of the normal editor.
@@ -564,6 +778,36 @@ fn handle_csv_edit_key(model: &mut AppModel, key: Key, modifiers: KeyModifiers)
     }
 }
 
+/// Handle keyboard input while typing into a CSV column filter
+fn handle_csv_filter_key(model: &mut AppModel, key: Key, modifiers: KeyModifiers) -> Option<Cmd> {
+    match key {
+        // Escape/Enter/Down: back to the grid, keeping the filters
+        Key::Named(NamedKey::Escape | NamedKey::Enter | NamedKey::ArrowDown) => {
+            update(model, Msg::Csv(CsvMsg::LeaveFilter))
+        }
+
+        // Tab/Shift+Tab: filter the next or previous column
+        Key::Named(NamedKey::Tab) if modifiers.shift => {
+            update(model, Msg::Csv(CsvMsg::FilterPrevColumn))
+        }
+        Key::Named(NamedKey::Tab) => update(model, Msg::Csv(CsvMsg::FilterNextColumn)),
+
+        _ => match classify_text_editing_key(&key, modifiers) {
+            Some(TextEditingKeyAction::InsertText(text)) => {
+                for ch in text.chars() {
+                    update(model, Msg::Csv(CsvMsg::FilterInsertChar(ch)));
+                }
+                Some(Cmd::Redraw)
+            }
+            Some(TextEditingKeyAction::DeleteBackward) => {
+                update(model, Msg::Csv(CsvMsg::FilterDeleteBackward))
+            }
+            // Block all other keys while filtering (consume but don't act)
+            _ => Some(Cmd::Redraw),
+        },
+    }
+}
+
 // =============================================================================
 // Sidebar Focus Handling
 // =============================================================================
@@ -575,7 +819,7 @@ fn is_sidebar_focused(model: &AppModel) -> bool {
 }
 
 /// Handle keyboard input when sidebar file tree is focused
-fn handle_sidebar_key(model: &mut AppModel, key: &Key, ctrl: bool) -> Option<Cmd> {
+fn handle_sidebar_key(model: &mut AppModel, key: &Key, ctrl: bool, logo: bool) -> Option<Cmd> {
     match key {
         // Arrow Up/Down: navigate file tree
         Key::Named(NamedKey::ArrowUp) => {
@@ -648,11 +892,90 @@ fn handle_sidebar_key(model: &mut AppModel, key: &Key, ctrl: bool) -> Option<Cmd
             update(model, Msg::Workspace(WorkspaceMsg::Refresh))
         }
 
+        // F2: rename the selected item in place
+        Key::Named(NamedKey::F2) => update(model, Msg::Workspace(WorkspaceMsg::StartRename)),
+
+        // Delete / Cmd+Backspace: move the selected item to the trash
+        Key::Named(NamedKey::Delete) => delete_selected_item(model),
+        Key::Named(NamedKey::Backspace) if ctrl || logo => delete_selected_item(model),
+
+        // Cmd+Z / Ctrl+Z: undo the last delete or rename
+        Key::Character(ref s) if (ctrl || logo) && s == "z" => {
+            update(model, Msg::Workspace(WorkspaceMsg::UndoFileOperation))
+        }
+
         // Don't consume other keys - let them fall through to normal handling
         _ => None,
     }
 }
 
+/// Whether a file or folder name is being typed in the file tree
+fn is_naming_in_sidebar(model: &AppModel) -> bool {
+    model
+        .workspace
+        .as_ref()
+        .is_some_and(|workspace| workspace.name_edit.is_some())
+}
+
+/// Dispatch a `TextEditingKeyAction` to the name being typed in the file tree.
+fn dispatch_sidebar_name_edit(model: &mut AppModel, action: TextEditingKeyAction) -> Option<Cmd> {
+    use TextEditingKeyAction::*;
+
+    let movement = |target: MoveTarget, extend: bool| {
+        if extend {
+            TextEditMsg::MoveWithSelection(target)
+        } else {
+            TextEditMsg::Move(target)
+        }
+    };
+    let edit = match action {
+        MoveLeft { extend } => movement(MoveTarget::Left, extend),
+        MoveRight { extend } => movement(MoveTarget::Right, extend),
+        MoveWordLeft { extend } => movement(MoveTarget::WordLeft, extend),
+        MoveWordRight { extend } => movement(MoveTarget::WordRight, extend),
+        MoveHome { extend } => movement(MoveTarget::LineStart, extend),
+        MoveEnd { extend } => movement(MoveTarget::LineEnd, extend),
+        SelectAll => TextEditMsg::SelectAll,
+        Copy => TextEditMsg::Copy,
+        Cut => TextEditMsg::Cut,
+        Paste => return Some(Cmd::RequestClipboardPaste),
+        DeleteWordBackward => TextEditMsg::DeleteWordBackward,
+        DeleteBackward => TextEditMsg::DeleteBackward,
+        DeleteForward => TextEditMsg::DeleteForward,
+        InsertText(s) => TextEditMsg::InsertText(s),
+    };
+    update(model, Msg::Workspace(WorkspaceMsg::NameInput(edit)))
+}
+
+/// Handle keyboard input while a name is typed in the file tree: Enter
+/// creates or renames, Escape cancels, everything else edits the name
+fn handle_sidebar_name_key(
+    model: &mut AppModel,
+    key: &Key,
+    modifiers: KeyModifiers,
+) -> Option<Cmd> {
+    match key {
+        Key::Named(NamedKey::Enter) => update(model, Msg::Workspace(WorkspaceMsg::ConfirmNameEdit)),
+        Key::Named(NamedKey::Escape) => update(model, Msg::Workspace(WorkspaceMsg::CancelNameEdit)),
+        Key::Character(s) if (modifiers.logo || modifiers.ctrl) && s.eq_ignore_ascii_case("z") => {
+            let edit = if modifiers.shift {
+                TextEditMsg::Redo
+            } else {
+                TextEditMsg::Undo
+            };
+            update(model, Msg::Workspace(WorkspaceMsg::NameInput(edit)))
+        }
+        _ => classify_text_editing_key(key, modifiers)
+            .and_then(|action| dispatch_sidebar_name_edit(model, action)),
+    }
+}
+
+/// Trash the item selected in the file tree
+fn delete_selected_item(model: &mut AppModel) -> Option<Cmd> {
+    let path = model.workspace.as_ref()?.selected_item.clone()?;
+    update(model, Msg::Workspace(WorkspaceMsg::DeletePath(path)))
+}
+
 /// Check if the outline panel (right dock) has keyboard focus
 fn is_outline_dock_focused(model: &AppModel) -> bool {
     if model.ui.focused_dock() != Some(DockPosition::Right) {
@@ -681,6 +1004,222 @@ fn handle_outline_dock_key(model: &mut AppModel, key: &Key) -> Option<Cmd> {
     }
 }
 
+/// Check if the TODO panel has keyboard focus
+fn is_todo_dock_focused(model: &AppModel) -> bool {
+    let Some(position) = model.ui.focused_dock() else {
+        return false;
+    };
+
+    let dock = model.dock_layout.dock(position);
+    dock.is_open && dock.active_panel() == Some(PanelId::TodoList)
+}
+
+/// Handle keyboard input when the TODO panel is focused
+fn handle_todo_dock_key(model: &mut AppModel, key: &Key, ctrl: bool) -> Option<Cmd> {
+    match key {
+        Key::Named(NamedKey::ArrowUp) => update(model, Msg::Todo(TodoMsg::SelectPrevious)),
+        Key::Named(NamedKey::ArrowDown) => update(model, Msg::Todo(TodoMsg::SelectNext)),
+        Key::Named(NamedKey::Enter) => update(model, Msg::Todo(TodoMsg::OpenSelected)),
+        Key::Named(NamedKey::Escape) => {
+            model.ui.focus_editor();
+            Some(Cmd::Redraw)
+        }
+        // Cmd+R / Ctrl+R: rescan
+        Key::Character(ref s) if (ctrl || cfg!(target_os = "macos")) && s == "r" => {
+            update(model, Msg::Todo(TodoMsg::Refresh))
+        }
+        _ => None,
+    }
+}
+
+/// Check if the AI chat panel has keyboard focus
+fn is_ai_chat_dock_focused(model: &AppModel) -> bool {
+    let Some(position) = model.ui.focused_dock() else {
+        return false;
+    };
+
+    let dock = model.dock_layout.dock(position);
+    dock.is_open && dock.active_panel() == Some(PanelId::AiChat)
+}
+
+/// Dispatch a `TextEditingKeyAction` to the AI chat prompt input.
+fn dispatch_ai_chat_text_edit(model: &mut AppModel, action: TextEditingKeyAction) -> Option<Cmd> {
+    use TextEditingKeyAction::*;
+
+    let movement = |target: MoveTarget, extend: bool| {
+        if extend {
+            TextEditMsg::MoveWithSelection(target)
+        } else {
+            TextEditMsg::Move(target)
+        }
+    };
+    let edit = match action {
+        MoveLeft { extend } => movement(MoveTarget::Left, extend),
+        MoveRight { extend } => movement(MoveTarget::Right, extend),
+        MoveWordLeft { extend } => movement(MoveTarget::WordLeft, extend),
+        MoveWordRight { extend } => movement(MoveTarget::WordRight, extend),
+        MoveHome { extend } => movement(MoveTarget::LineStart, extend),
+        MoveEnd { extend } => movement(MoveTarget::LineEnd, extend),
+        SelectAll => TextEditMsg::SelectAll,
+        Copy => TextEditMsg::Copy,
+        Cut => TextEditMsg::Cut,
+        Paste => return Some(Cmd::RequestClipboardPaste),
+        DeleteWordBackward => TextEditMsg::DeleteWordBackward,
+        DeleteBackward => TextEditMsg::DeleteBackward,
+        DeleteForward => TextEditMsg::DeleteForward,
+        InsertText(s) => TextEditMsg::InsertText(s),
+    };
+    update(model, Msg::AiChat(AiChatMsg::Input(edit)))
+}
+
+/// Handle keyboard input when the AI chat panel is focused
+fn handle_ai_chat_dock_key(
+    model: &mut AppModel,
+    key: &Key,
+    modifiers: KeyModifiers,
+) -> Option<Cmd> {
+    let KeyModifiers {
+        ctrl, shift, logo, ..
+    } = modifiers;
+
+    match key {
+        // Escape: stop a streaming answer, otherwise return focus to the editor
+        Key::Named(NamedKey::Escape) => {
+            if model.ai_chat.is_streaming {
+                update(model, Msg::AiChat(AiChatMsg::Cancel))
+            } else {
+                model.ui.focus_editor();
+                Some(Cmd::Redraw)
+            }
+        }
+        Key::Named(NamedKey::Enter) => update(model, Msg::AiChat(AiChatMsg::Submit)),
+
+        // Up/Down scroll the transcript (the prompt is a single line)
+        Key::Named(NamedKey::ArrowUp) => update(model, Msg::AiChat(AiChatMsg::Scroll { lines: 1 })),
+        Key::Named(NamedKey::ArrowDown) => {
+            update(model, Msg::AiChat(AiChatMsg::Scroll { lines: -1 }))
+        }
+
+        // Undo/redo within the prompt (Cmd+Z / Cmd+Shift+Z)
+        Key::Character(s) if (logo || ctrl) && s.eq_ignore_ascii_case("z") => {
+            let edit = if shift {
+                TextEditMsg::Redo
+            } else {
+                TextEditMsg::Undo
+            };
+            update(model, Msg::AiChat(AiChatMsg::Input(edit)))
+        }
+
+        _ => classify_text_editing_key(key, modifiers)
+            .and_then(|action| dispatch_ai_chat_text_edit(model, action)),
+    }
+}
+
+/// Check if the search panel has keyboard focus
+fn is_search_dock_focused(model: &AppModel) -> bool {
+    model.ui.focused_dock().is_some_and(|position| {
+        let dock = model.dock_layout.dock(position);
+        dock.is_open && dock.active_panel() == Some(PanelId::SEARCH_RESULTS)
+    })
+}
+
+/// Dispatch a `TextEditingKeyAction` to the search panel's query input.
+fn dispatch_search_text_edit(model: &mut AppModel, action: TextEditingKeyAction) -> Option<Cmd> {
+    use TextEditingKeyAction::*;
+
+    let movement = |target: MoveTarget, extend: bool| {
+        if extend {
+            TextEditMsg::MoveWithSelection(target)
+        } else {
+            TextEditMsg::Move(target)
+        }
+    };
+    let edit = match action {
+        MoveLeft { extend } => movement(MoveTarget::Left, extend),
+        MoveRight { extend } => movement(MoveTarget::Right, extend),
+        MoveWordLeft { extend } => movement(MoveTarget::WordLeft, extend),
+        MoveWordRight { extend } => movement(MoveTarget::WordRight, extend),
+        MoveHome { extend } => movement(MoveTarget::LineStart, extend),
+        MoveEnd { extend } => movement(MoveTarget::LineEnd, extend),
+        SelectAll => TextEditMsg::SelectAll,
+        Copy => TextEditMsg::Copy,
+        Cut => TextEditMsg::Cut,
+        Paste => return Some(Cmd::RequestClipboardPaste),
+        DeleteWordBackward => TextEditMsg::DeleteWordBackward,
+        DeleteBackward => TextEditMsg::DeleteBackward,
+        DeleteForward => TextEditMsg::DeleteForward,
+        InsertText(s) => TextEditMsg::InsertText(s),
+    };
+    update(model, Msg::Search(SearchMsg::Input(edit)))
+}
+
+/// Handle keyboard input when the search panel is focused: typing edits
+/// the query, Enter searches (or opens the result picked with Up/Down),
+/// and Alt+C / Alt+W / Alt+R toggle case, whole word and regex. The Alt
+/// toggles are matched on the physical key, as in the find modal.
+fn handle_search_dock_key(
+    model: &mut AppModel,
+    key: &Key,
+    physical_key: winit::keyboard::PhysicalKey,
+    modifiers: KeyModifiers,
+) -> Option<Cmd> {
+    use winit::keyboard::{KeyCode, PhysicalKey};
+
+    let KeyModifiers {
+        ctrl,
+        shift,
+        alt,
+        logo,
+    } = modifiers;
+
+    if alt && !ctrl && !logo && !shift {
+        let toggle = match physical_key {
+            PhysicalKey::Code(KeyCode::KeyC) => Some(SearchMsg::ToggleCaseSensitive),
+            PhysicalKey::Code(KeyCode::KeyW) => Some(SearchMsg::ToggleWholeWord),
+            PhysicalKey::Code(KeyCode::KeyR) => Some(SearchMsg::ToggleRegex),
+            _ => None,
+        };
+        if let Some(msg) = toggle {
+            return update(model, Msg::Search(msg));
+        }
+    }
+
+    match key {
+        // Escape: stop a running search, otherwise return focus to the editor
+        Key::Named(NamedKey::Escape) => {
+            if model.search_panel.is_searching {
+                update(model, Msg::Search(SearchMsg::Cancel))
+            } else {
+                model.ui.focus_editor();
+                Some(Cmd::Redraw)
+            }
+        }
+        Key::Named(NamedKey::Enter) => {
+            let msg = if model.search_panel.selected_index.is_some() {
+                SearchMsg::OpenSelected
+            } else {
+                SearchMsg::Submit
+            };
+            update(model, Msg::Search(msg))
+        }
+        Key::Named(NamedKey::ArrowUp) => update(model, Msg::Search(SearchMsg::SelectPrevious)),
+        Key::Named(NamedKey::ArrowDown) => update(model, Msg::Search(SearchMsg::SelectNext)),
+
+        // Undo/redo within the query (Cmd+Z / Cmd+Shift+Z)
+        Key::Character(s) if (logo || ctrl) && s.eq_ignore_ascii_case("z") => {
+            let edit = if shift {
+                TextEditMsg::Redo
+            } else {
+                TextEditMsg::Undo
+            };
+            update(model, Msg::Search(SearchMsg::Input(edit)))
+        }
+
+        _ => classify_text_editing_key(key, modifiers)
+            .and_then(|action| dispatch_search_text_edit(model, action)),
+    }
+}
+
 /// Check if the terminal panel (bottom dock) has keyboard focus.
 fn is_terminal_dock_focused(model: &AppModel) -> bool {
     if model.ui.focused_dock() != Some(DockPosition::Bottom) {
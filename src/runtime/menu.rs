@@ -0,0 +1,92 @@
+//! Native menu bar (macOS)
+//!
+//! Built from [`token::menu::menu_bar`]. Choosing an item sends
+//! `AppMsg::RunCommand` through the app's message channel and wakes the
+//! event loop, so it runs exactly like the same command from the palette.
+
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+
+use muda::{AboutMetadata, Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem, Submenu};
+use winit::event_loop::EventLoopProxy;
+
+use token::commands::CommandId;
+use token::menu::{self, MenuRole, StandardItem};
+use token::messages::{AppMsg, Msg};
+
+/// The installed menu bar; kept alive for as long as the app runs
+pub struct MenuBar {
+    _menu: Menu,
+}
+
+impl MenuBar {
+    /// Build the menu bar and make it the application's main menu. Call
+    /// once the app has finished launching, or the system menu replaces it.
+    pub fn install(msg_tx: Sender<Msg>, proxy: EventLoopProxy<()>) -> Result<Self, muda::Error> {
+        let menu = Menu::new();
+        let mut commands: HashMap<MenuId, CommandId> = HashMap::new();
+        let mut special = Vec::new();
+
+        for spec in menu::menu_bar() {
+            let submenu = Submenu::new(spec.title, true);
+            for item in spec.items {
+                match item {
+                    menu::MenuItem::Command { id, label } => {
+                        let native = MenuItem::new(label, true, None);
+                        commands.insert(native.id().clone(), id);
+                        submenu.append(&native)?;
+                    }
+                    menu::MenuItem::Standard(item) => submenu.append(&standard_item(item))?,
+                    menu::MenuItem::Separator => submenu.append(&PredefinedMenuItem::separator())?,
+                }
+            }
+            menu.append(&submenu)?;
+            if matches!(spec.role, MenuRole::Window | MenuRole::Help) {
+                special.push((spec.role, submenu));
+            }
+        }
+
+        menu.init_for_nsapp();
+        for (role, submenu) in special {
+            match role {
+                MenuRole::Window => submenu.set_as_windows_menu_for_nsapp(),
+                MenuRole::Help => submenu.set_as_help_menu_for_nsapp(),
+                MenuRole::App | MenuRole::Other => {}
+            }
+        }
+
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            let Some(&id) = commands.get(&event.id) else {
+                return;
+            };
+            if msg_tx.send(Msg::App(AppMsg::RunCommand(id))).is_ok() {
+                // Wake the event loop so the message is processed right away
+                let _ = proxy.send_event(());
+            }
+        }));
+
+        Ok(Self { _menu: menu })
+    }
+}
+
+fn standard_item(item: StandardItem) -> PredefinedMenuItem {
+    match item {
+        StandardItem::About => PredefinedMenuItem::about(
+            Some("About Token"),
+            Some(AboutMetadata {
+                name: Some("Token".to_string()),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                website: Some(env!("CARGO_PKG_HOMEPAGE").to_string()),
+                ..Default::default()
+            }),
+        ),
+        StandardItem::Services => PredefinedMenuItem::services(None),
+        StandardItem::Hide => PredefinedMenuItem::hide(None),
+        StandardItem::HideOthers => PredefinedMenuItem::hide_others(None),
+        StandardItem::ShowAll => PredefinedMenuItem::show_all(None),
+        StandardItem::Minimize => PredefinedMenuItem::minimize(None),
+        StandardItem::Zoom => PredefinedMenuItem::maximize(None),
+        StandardItem::Fullscreen => PredefinedMenuItem::fullscreen(None),
+        StandardItem::BringAllToFront => PredefinedMenuItem::bring_all_to_front(None),
+    }
+}
@@ -2,13 +2,21 @@
 //!
 //! This module contains platform-specific code for running the editor:
 //! - `app` - ApplicationHandler and window management
+//! - `clipboard` - Persistent system clipboard handle
+//! - `headless` - Window-less harness for end-to-end tests (`headless` feature)
 //! - `input` - Keyboard/mouse event to message mapping
+//! - `menu` - Native menu bar (macOS only)
 //! - `mouse` - Unified mouse event handling with hit-testing
 //! - `perf` - Performance overlay (debug builds only)
 //! - `webview` - Webview management for markdown preview
 
 pub mod app;
+pub mod clipboard;
+#[cfg(all(test, feature = "headless"))]
+pub mod headless;
 pub mod input;
+#[cfg(target_os = "macos")]
+pub mod menu;
 pub mod mouse;
 pub mod perf;
 pub mod webview;
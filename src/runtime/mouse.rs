@@ -13,16 +13,21 @@ use winit::keyboard::ModifiersState;
 
 use token::commands::Cmd;
 use token::messages::{
-    CsvMsg, EditorMsg, ImageMsg, LayoutMsg, ModalMsg, Msg, OutlineMsg, PreviewMsg, TerminalMsg,
-    UiMsg, WorkspaceMsg,
+    CsvMsg, EditorMsg, ImageMsg, LayoutMsg, ModalMsg, Msg, NotesMsg, OutlineMsg, PreviewMsg,
+    TerminalMsg, UiMsg, WorkspaceMsg,
 };
-use token::model::AppModel;
+use token::model::{AppModel, DropEdge, ModalId, ModalState, Rect, SegmentId};
 use token::update::update;
 use token::util::visible_tree_row_at_index;
 
 use token::model::editor_area::GroupId;
-use token::view::geometry::{DockHeaderLayout, OutlinePanelLayout, TabBarLayout, WindowLayout};
-use token::view::hit_test::{hit_test_ui, EventResult, HitTarget, MouseEvent};
+use token::view::geometry::{
+    DockHeaderLayout, OutlinePanelLayout, SearchPanelLayout, TabBarLayout, TodoPanelLayout,
+    WindowLayout,
+};
+use token::view::hit_test::{
+    context_menu_item_at, hit_test_ui, EventResult, HitTarget, MouseEvent,
+};
 use token::view::Renderer;
 
 /// Identifies what was clicked, so rapid clicks on unrelated targets
@@ -37,12 +42,25 @@ pub enum ClickRegion {
     Sidebar {
         row: usize,
     },
+    Tab {
+        tab: token::model::editor_area::TabId,
+    },
     Outline {
         row: usize,
     },
     BinaryPlaceholder {
         group: token::model::editor_area::GroupId,
     },
+    DockHeader {
+        position: token::panel::DockPosition,
+    },
+    DockIconStrip {
+        position: token::panel::DockPosition,
+    },
+    CsvColumnBorder {
+        group: token::model::editor_area::GroupId,
+        col: usize,
+    },
 }
 
 /// Click tracking state for double/triple click detection
@@ -265,6 +283,30 @@ fn tab_bar_target_at(model: &AppModel, x: f64, y: f64) -> Option<(GroupId, usize
     })
 }
 
+/// Find the group whose content area has an edge zone under the point, and
+/// that edge. The group the tab comes from only counts while it has other
+/// tabs to keep.
+fn split_target_at(
+    model: &AppModel,
+    x: f64,
+    y: f64,
+    owning_group: GroupId,
+) -> Option<(GroupId, DropEdge)> {
+    let tab_bar_height = model.metrics.tab_bar_height as f32;
+    model.editor_area.groups.values().find_map(|group| {
+        if group.id == owning_group && group.tabs.len() <= 1 {
+            return None;
+        }
+        let content = Rect::new(
+            group.rect.x,
+            group.rect.y + tab_bar_height,
+            group.rect.width,
+            group.rect.height - tab_bar_height,
+        );
+        DropEdge::at(content, x as f32, y as f32).map(|edge| (group.id, edge))
+    })
+}
+
 /// Find the group that currently owns a tab.
 fn tab_owning_group(model: &AppModel, tab_id: token::model::editor_area::TabId) -> Option<GroupId> {
     model
@@ -278,8 +320,9 @@ fn tab_owning_group(model: &AppModel, tab_id: token::model::editor_area::TabId)
 ///
 /// The drag is fully live: hovering a tab bar reorders the tab into that
 /// slot (moving it between groups first if needed), and hovering another
-/// pane's content area moves the tab into that pane. Releasing simply drops
-/// the tab where it already is (`end_tab_drag`).
+/// pane's content area moves the tab into that pane. Hovering the edge of a
+/// pane only marks where a new split would go, which is made on release
+/// (`end_tab_drag`).
 pub fn update_tab_drag(model: &mut AppModel, x: f64, y: f64) -> Option<Cmd> {
     let drag = model.ui.tab_drag.as_mut()?;
     drag.current = (x, y);
@@ -296,10 +339,22 @@ pub fn update_tab_drag(model: &mut AppModel, x: f64, y: f64) -> Option<Cmd> {
     let tab_id = drag.tab_id;
     let owning_group = tab_owning_group(model, tab_id)?;
 
-    // Tab bars take priority; a pane's content area targets that pane's
-    // tab end, but only for *other* panes (dragging into your own pane's
-    // text area must not reorder anything).
-    let target = tab_bar_target_at(model, x, y).or_else(|| {
+    // Tab bars take priority, then pane edges; the middle of a pane's
+    // content area targets that pane's tab end, but only for *other* panes
+    // (dragging into your own pane's text area must not reorder anything).
+    let tab_bar_target = tab_bar_target_at(model, x, y);
+    let split_target = tab_bar_target
+        .is_none()
+        .then(|| split_target_at(model, x, y, owning_group))
+        .flatten();
+    if let Some(drag) = model.ui.tab_drag.as_mut() {
+        drag.split_target = split_target;
+    }
+    if split_target.is_some() {
+        return Some(Cmd::Redraw);
+    }
+
+    let target = tab_bar_target.or_else(|| {
         model.editor_area.groups.values().find_map(|g| {
             (g.id != owning_group && g.rect.contains(x as f32, y as f32))
                 .then_some((g.id, usize::MAX))
@@ -333,13 +388,24 @@ pub fn update_tab_drag(model: &mut AppModel, x: f64, y: f64) -> Option<Cmd> {
 
 /// Finish a tab drag on mouse release.
 ///
-/// Moves/reorders happen live during the drag, so this only clears the
-/// drag state and repaints to remove the ghost.
+/// Moves/reorders happen live during the drag, so unless the tab is
+/// dropped on a pane edge this only clears the drag state and repaints to
+/// remove the ghost.
 pub fn end_tab_drag(model: &mut AppModel) -> Option<Cmd> {
     let drag = model.ui.tab_drag.take()?;
     if !drag.active {
         return None; // plain click, no drag happened
     }
+    if let Some((group_id, edge)) = drag.split_target {
+        update(
+            model,
+            Msg::Layout(LayoutMsg::MoveTabToNewSplit {
+                tab_id: drag.tab_id,
+                group_id,
+                edge,
+            }),
+        );
+    }
     Some(Cmd::Redraw)
 }
 
@@ -400,6 +466,17 @@ pub fn handle_mouse_press(
     // Dispatch based on target and button
     let result = dispatch_mouse_press(model, renderer, &target, &event, click_tracker);
 
+    // Dragging over CSV cells (not their column headers or filters) selects
+    // a range.
+    // Checked after the press, which focuses the clicked group.
+    let is_csv_cell = matches!(target, HitTarget::CsvCell { .. })
+        && renderer
+            .pixel_to_csv_column_header(event.pos.x, event.pos.y, model)
+            .is_none()
+        && renderer
+            .pixel_to_csv_filter(event.pos.x, event.pos.y, model)
+            .is_none();
+
     // Apply focus changes
     if let EventResult::Consumed {
         focus: Some(focus_target),
@@ -424,7 +501,7 @@ pub fn handle_mouse_press(
 
     MousePressResult {
         cmd,
-        start_drag_tracking: is_editor_content && is_left_click,
+        start_drag_tracking: (is_editor_content || is_csv_cell) && is_left_click,
     }
 }
 
@@ -458,8 +535,21 @@ fn handle_left_click(
         // Modal handling
         HitTarget::Modal { inside } => {
             if *inside {
-                // Click inside modal - consume but don't close
-                // Future: could handle clicking on list items
+                // Click inside modal - consume but don't close; a context
+                // menu runs the clicked command
+                if let Some(index) = context_menu_item_at(model, event.pos) {
+                    update(
+                        model,
+                        Msg::Ui(UiMsg::Modal(ModalMsg::SelectContextMenuItem(index))),
+                    );
+                    let selected = matches!(
+                        &model.ui.active_modal,
+                        Some(ModalState::ContextMenu(state)) if state.selected_index == index
+                    );
+                    if selected {
+                        update(model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+                    }
+                }
                 EventResult::consumed_redraw()
             } else {
                 // Click outside modal - close it
@@ -468,8 +558,14 @@ fn handle_left_click(
             }
         }
 
-        // Status bar - consume but do nothing
-        HitTarget::StatusBar => EventResult::consumed_no_redraw(),
+        // Status bar - the language segment opens the language picker
+        HitTarget::StatusBar {
+            segment: Some(SegmentId::Language),
+        } => {
+            update(model, Msg::Ui(UiMsg::ToggleModal(ModalId::LanguagePicker)));
+            EventResult::consumed_redraw()
+        }
+        HitTarget::StatusBar { .. } => EventResult::consumed_no_redraw(),
 
         // Sidebar resize handle
         HitTarget::SidebarResize => {
@@ -510,7 +606,18 @@ fn handle_left_click(
                 return EventResult::consumed_with_focus(FocusTarget::Sidebar);
             }
 
-            // Double-click opens file or toggles folder
+            // Single click previews a file; double-click keeps it open or
+            // toggles a folder
+            if click_count == 1 && !*is_dir {
+                let cmd = update(
+                    model,
+                    Msg::Workspace(WorkspaceMsg::OpenFile {
+                        path: path.clone(),
+                        preview: true,
+                    }),
+                );
+                return EventResult::consumed_with_cmd(cmd, FocusTarget::Sidebar);
+            }
             if click_count >= 2 {
                 let cmd = if *is_dir {
                     update(
@@ -568,12 +675,17 @@ fn handle_left_click(
                 update(model, Msg::Layout(LayoutMsg::FocusGroup(*group_id)));
             }
             update(model, Msg::Layout(LayoutMsg::SwitchToTab(*tab_index)));
+            // Double-click keeps a preview tab open
+            if click_tracker.track_click(ClickRegion::Tab { tab: *tab_id }) == 2 {
+                update(model, Msg::Layout(LayoutMsg::KeepTabOpen(*tab_id)));
+            }
             // Arm a potential tab drag (activates past the move threshold)
             model.ui.tab_drag = Some(token::model::ui::TabDragState {
                 tab_id: *tab_id,
                 press: (event.pos.x, event.pos.y),
                 current: (event.pos.x, event.pos.y),
                 active: false,
+                split_target: None,
             });
             EventResult::consumed_with_focus(FocusTarget::Editor)
         }
@@ -586,13 +698,35 @@ fn handle_left_click(
             EventResult::consumed_with_focus(FocusTarget::Editor)
         }
 
-        // Editor gutter (line numbers) - could be used for line selection
-        HitTarget::EditorGutter { group_id, .. } => {
+        // A symbol in the breadcrumb strip lists the symbols next to it
+        HitTarget::GroupBreadcrumb { group_id, depth } => {
             if *group_id != model.editor_area.focused_group_id {
                 update(model, Msg::Layout(LayoutMsg::FocusGroup(*group_id)));
             }
-            // For now, treat like editor content click
-            // Future: could select entire line
+            if let Some(depth) = *depth {
+                update(
+                    model,
+                    Msg::Outline(OutlineMsg::ShowBreadcrumbSymbols { depth }),
+                );
+                return EventResult::consumed_redraw();
+            }
+            EventResult::consumed_with_focus(FocusTarget::Editor)
+        }
+
+        // Editor gutter (line numbers): a click on a fold marker toggles it
+        HitTarget::EditorGutter { group_id, line, .. } => {
+            if *group_id != model.editor_area.focused_group_id {
+                update(model, Msg::Layout(LayoutMsg::FocusGroup(*group_id)));
+            }
+            let foldable = model.editor().folds.fold_at(*line).is_some()
+                || token::model::folding::can_fold_at(model.document(), *line);
+            if foldable {
+                let cmd = update(
+                    model,
+                    Msg::Editor(token::messages::EditorMsg::ToggleFoldAt { line: *line }),
+                );
+                return EventResult::consumed_with_cmd(cmd, FocusTarget::Editor);
+            }
             EventResult::consumed_with_focus(FocusTarget::Editor)
         }
 
@@ -609,19 +743,61 @@ fn handle_left_click(
                 update(model, Msg::Layout(LayoutMsg::FocusGroup(*group_id)));
             }
 
+            // A column header click sorts by that column, toggling the direction
+            if let Some(col) = renderer.pixel_to_csv_column_header(event.pos.x, event.pos.y, model)
+            {
+                let ascending = model
+                    .editor_area
+                    .focused_editor()
+                    .and_then(|editor| editor.view_mode.as_csv())
+                    .is_none_or(|csv| csv.header_click_ascending(col));
+                let cmd = update(model, Msg::Csv(CsvMsg::SortByColumn { col, ascending }));
+                return EventResult::consumed_with_cmd(cmd, FocusTarget::Editor);
+            }
+
+            // A filter row click types into that column's filter
+            if let Some(col) = renderer.pixel_to_csv_filter(event.pos.x, event.pos.y, model) {
+                let cmd = update(model, Msg::Csv(CsvMsg::FocusFilter(col)));
+                return EventResult::consumed_with_cmd(cmd, FocusTarget::Editor);
+            }
+
             // Use renderer to find the actual cell at this position
+            // Shift+click grows the range selection to the clicked cell
             if let Some(cell) = renderer.pixel_to_csv_cell(event.pos.x, event.pos.y, model) {
-                update(
-                    model,
-                    Msg::Csv(CsvMsg::SelectCell {
-                        row: cell.row,
-                        col: cell.col,
-                    }),
-                );
+                let (row, col) = (cell.row, cell.col);
+                let msg = if event.shift() {
+                    CsvMsg::ExtendToCell { row, col }
+                } else {
+                    CsvMsg::SelectCell { row, col }
+                };
+                update(model, Msg::Csv(msg));
             }
             EventResult::consumed_with_focus(FocusTarget::Editor)
         }
 
+        // CSV column edge - drag to resize, double-click to fit the content
+        HitTarget::CsvColumnBorder { group_id, col, .. } => {
+            use token::messages::CsvMsg;
+
+            if *group_id != model.editor_area.focused_group_id {
+                update(model, Msg::Layout(LayoutMsg::FocusGroup(*group_id)));
+            }
+            let click_count = click_tracker.track_click(ClickRegion::CsvColumnBorder {
+                group: *group_id,
+                col: *col,
+            });
+            let msg = if click_count >= 2 {
+                CsvMsg::AutoFitColumn(*col)
+            } else {
+                CsvMsg::StartColumnResize {
+                    col: *col,
+                    x: event.pos.x,
+                }
+            };
+            let cmd = update(model, Msg::Csv(msg));
+            EventResult::consumed_with_cmd(cmd, FocusTarget::Editor)
+        }
+
         // Image content - start panning
         HitTarget::ImageContent { group_id, .. } => {
             if *group_id != model.editor_area.focused_group_id {
@@ -751,6 +927,17 @@ fn handle_left_click(
             EventResult::consumed_with_focus(FocusTarget::Editor)
         }
 
+        HitTarget::WelcomeItem { group_id, index } => {
+            if *group_id != model.editor_area.focused_group_id {
+                update(model, Msg::Layout(LayoutMsg::FocusGroup(*group_id)));
+            }
+            let cmd = update(
+                model,
+                Msg::Welcome(token::messages::WelcomeMsg::Activate { index: *index }),
+            );
+            EventResult::consumed_with_cmd(cmd, FocusTarget::Editor)
+        }
+
         // Dock resize handle
         HitTarget::DockResize { position } => {
             let initial_coord = match position {
@@ -767,25 +954,62 @@ fn handle_left_click(
             EventResult::consumed_with_focus(FocusTarget::Editor)
         }
 
-        // Dock tab click - activate panel (never toggles the dock closed)
-        HitTarget::DockTab { panel_id, .. } => {
+        // Collapsed dock icon strip: clicking an icon restores the dock with
+        // that panel active, clicking elsewhere restores it as it was
+        HitTarget::DockTab { position, panel_id }
+            if model.dock_layout.dock(*position).is_collapsed =>
+        {
+            click_tracker.track_click(ClickRegion::DockIconStrip {
+                position: *position,
+            });
             update(
                 model,
                 Msg::Dock(token::messages::DockMsg::ActivatePanel(*panel_id)),
             );
             EventResult::consumed_redraw()
         }
-
-        // Dock tab bar empty area
-        HitTarget::DockTabBarEmpty { position } => {
-            // Focus the dock
+        HitTarget::DockTabBarEmpty { position }
+            if model.dock_layout.dock(*position).is_collapsed =>
+        {
+            click_tracker.track_click(ClickRegion::DockIconStrip {
+                position: *position,
+            });
             update(
                 model,
-                Msg::Dock(token::messages::DockMsg::FocusDock(*position)),
+                Msg::Dock(token::messages::DockMsg::ToggleCollapsed(*position)),
             );
             EventResult::consumed_redraw()
         }
 
+        // Dock tab click - activate panel (never toggles the dock closed);
+        // double-click collapses the dock to its icon strip
+        HitTarget::DockTab { position, panel_id } => {
+            let click_count = click_tracker.track_click(ClickRegion::DockHeader {
+                position: *position,
+            });
+            let msg = if click_count >= 2 {
+                token::messages::DockMsg::ToggleCollapsed(*position)
+            } else {
+                token::messages::DockMsg::ActivatePanel(*panel_id)
+            };
+            update(model, Msg::Dock(msg));
+            EventResult::consumed_redraw()
+        }
+
+        // Dock tab bar empty area - focus the dock; double-click collapses it
+        HitTarget::DockTabBarEmpty { position } => {
+            let click_count = click_tracker.track_click(ClickRegion::DockHeader {
+                position: *position,
+            });
+            let msg = if click_count >= 2 {
+                token::messages::DockMsg::ToggleCollapsed(*position)
+            } else {
+                token::messages::DockMsg::FocusDock(*position)
+            };
+            update(model, Msg::Dock(msg));
+            EventResult::consumed_redraw()
+        }
+
         // Dock content area - handle panel-specific interactions
         HitTarget::DockContent {
             position,
@@ -853,6 +1077,67 @@ fn handle_left_click(
                 return EventResult::consumed_with_focus(FocusTarget::Dock(*position));
             }
 
+            // Handle TODO panel clicks
+            if *active_panel_id == token::panel::PanelId::TodoList {
+                let window_layout = WindowLayout::compute(model, model.line_height);
+                let dock_rect = match position {
+                    token::panel::DockPosition::Right => window_layout.right_dock_rect,
+                    token::panel::DockPosition::Bottom => window_layout.bottom_dock_rect,
+                    token::panel::DockPosition::Left => None,
+                };
+                let mut cmd = None;
+                if let Some(dock_rect) = dock_rect {
+                    let dock = model.dock_layout.dock(*position);
+                    let dock_layout =
+                        DockHeaderLayout::new(dock, dock_rect, &model.metrics, model.char_width);
+                    let todo_layout =
+                        TodoPanelLayout::new(dock_layout.content_rect, &model.metrics);
+                    if let Some(index) = todo_layout
+                        .row_index_at_y(event.pos.y as f32, model.todo_panel.scroll_offset)
+                    {
+                        // Jumping to an item may open a file, which returns
+                        // syntax-parse and recent-files commands to run
+                        cmd = update(
+                            model,
+                            Msg::Todo(token::messages::TodoMsg::ClickRow { index }),
+                        );
+                    }
+                }
+
+                return EventResult::consumed_with_cmd(cmd, model.ui.focus);
+            }
+
+            // Handle search panel clicks
+            if *active_panel_id == token::panel::PanelId::SearchResults {
+                let window_layout = WindowLayout::compute(model, model.line_height);
+                let dock_rect = match position {
+                    token::panel::DockPosition::Right => window_layout.right_dock_rect,
+                    token::panel::DockPosition::Bottom => window_layout.bottom_dock_rect,
+                    token::panel::DockPosition::Left => None,
+                };
+                let mut cmd = None;
+                if let Some(dock_rect) = dock_rect {
+                    let dock = model.dock_layout.dock(*position);
+                    let dock_layout =
+                        DockHeaderLayout::new(dock, dock_rect, &model.metrics, model.char_width);
+                    let search_layout = SearchPanelLayout::new(
+                        dock_layout.content_rect,
+                        &model.metrics,
+                        model.char_width,
+                    );
+                    if let Some(index) = search_layout
+                        .row_index_at_y(event.pos.y as f32, model.search_panel.scroll_offset)
+                    {
+                        cmd = update(
+                            model,
+                            Msg::Search(token::messages::SearchMsg::ClickRow { index }),
+                        );
+                    }
+                }
+
+                return EventResult::consumed_with_cmd(cmd, model.ui.focus);
+            }
+
             // For left dock (file explorer), return sidebar focus
             match position {
                 token::panel::DockPosition::Left => {
@@ -873,7 +1158,7 @@ fn handle_editor_content_click(
     click_tracker: &mut ClickTracker,
 ) -> EventResult {
     use token::messages::EditorMsg;
-    use token::model::FocusTarget;
+    use token::model::{FocusTarget, Position};
 
     // Focus group if needed
     if group_id != model.editor_area.focused_group_id {
@@ -892,6 +1177,9 @@ fn handle_editor_content_click(
                 }
                 return EventResult::consumed_with_focus(FocusTarget::Editor);
             }
+            token::model::TabContent::Welcome(_) => {
+                return EventResult::consumed_with_focus(FocusTarget::Editor);
+            }
             token::model::TabContent::Text => {}
         }
     }
@@ -907,6 +1195,13 @@ fn handle_editor_content_click(
     });
 
     // Handle modifiers
+    if event.command() && click_count == 1 {
+        // Cmd+Click on a note link opens it; elsewhere it's a plain click
+        if let Some(cmd) = update(model, Msg::Notes(NotesMsg::FollowLinkAt { line, column })) {
+            return EventResult::consumed_with_cmd(Some(cmd), FocusTarget::Editor);
+        }
+    }
+
     if event.shift() {
         update(
             model,
@@ -940,11 +1235,25 @@ fn handle_editor_content_click(
             update(model, Msg::Editor(EditorMsg::SelectLine));
         }
         _ => {
-            model.editor_mut().clear_selection();
-            update(
-                model,
-                Msg::Editor(EditorMsg::SetCursorPosition { line, column }),
-            );
+            // Pressing inside the selection may start dragging it; the cursor
+            // is only placed on release if the mouse didn't move
+            let editor = model.editor();
+            if !editor.has_multiple_cursors()
+                && editor
+                    .primary_selection()
+                    .contains(Position::new(line, column))
+            {
+                update(
+                    model,
+                    Msg::Editor(EditorMsg::StartTextDrag { line, column }),
+                );
+            } else {
+                model.editor_mut().clear_selection();
+                update(
+                    model,
+                    Msg::Editor(EditorMsg::SetCursorPosition { line, column }),
+                );
+            }
         }
     }
 
@@ -960,7 +1269,7 @@ fn handle_middle_click(
 ) -> EventResult {
     match target {
         // Status bar - ignore
-        HitTarget::StatusBar => EventResult::consumed_no_redraw(),
+        HitTarget::StatusBar { .. } => EventResult::consumed_no_redraw(),
 
         // Preview header - middle click closes preview
         HitTarget::PreviewHeader { .. } => {
@@ -983,7 +1292,9 @@ fn handle_middle_click(
         }
 
         // Empty tab bar area - consume but no action
-        HitTarget::GroupTabBarEmpty { .. } => EventResult::consumed_no_redraw(),
+        HitTarget::GroupTabBarEmpty { .. } | HitTarget::GroupBreadcrumb { .. } => {
+            EventResult::consumed_no_redraw()
+        }
 
         // Editor gutter - treat like editor content for rectangle selection
         HitTarget::EditorGutter { group_id, .. } => {
@@ -1020,7 +1331,9 @@ fn handle_middle_click(
         }
 
         // CSV cell - no middle-click behavior
-        HitTarget::CsvCell { .. } => EventResult::consumed_no_redraw(),
+        HitTarget::CsvCell { .. } | HitTarget::CsvColumnBorder { .. } => {
+            EventResult::consumed_no_redraw()
+        }
 
         // Modal - consume, no action
         HitTarget::Modal { .. } => EventResult::consumed_no_redraw(),
@@ -1039,8 +1352,10 @@ fn handle_middle_click(
         | HitTarget::DockTabBarEmpty { .. }
         | HitTarget::DockContent { .. } => EventResult::consumed_no_redraw(),
 
-        // Binary placeholder button - no middle-click action
-        HitTarget::BinaryPlaceholderButton { .. } => EventResult::consumed_no_redraw(),
+        // Binary placeholder button and welcome entries - no middle-click action
+        HitTarget::BinaryPlaceholderButton { .. } | HitTarget::WelcomeItem { .. } => {
+            EventResult::consumed_no_redraw()
+        }
 
         // Image content and scrollbars - no middle-click action
         HitTarget::ImageContent { .. }
@@ -1051,14 +1366,29 @@ fn handle_middle_click(
     }
 }
 
-/// Handle right mouse button clicks (context menus - future)
-fn handle_right_click(
-    _model: &mut AppModel,
-    _target: &HitTarget,
-    _event: &MouseEvent,
-) -> EventResult {
-    // Future: show context menus based on target
-    EventResult::Bubble
+/// Handle right mouse button clicks: context menus
+fn handle_right_click(model: &mut AppModel, target: &HitTarget, event: &MouseEvent) -> EventResult {
+    match target {
+        HitTarget::Modal { inside: true } => EventResult::consumed_no_redraw(),
+        HitTarget::Modal { inside: false } => {
+            update(model, Msg::Ui(UiMsg::Modal(ModalMsg::Close)));
+            EventResult::consumed_redraw()
+        }
+
+        // Tab - the tab's context menu, at the pointer
+        HitTarget::GroupTab { tab_id, .. } => {
+            update(
+                model,
+                Msg::Layout(LayoutMsg::ShowTabContextMenu {
+                    tab_id: *tab_id,
+                    position: (event.pos.x as usize, event.pos.y as usize),
+                }),
+            );
+            EventResult::consumed_redraw()
+        }
+
+        _ => EventResult::Bubble,
+    }
 }
 
 /// Horizontal `delta_px` for scrolling the editor tab strip from a wheel event,
@@ -1111,6 +1441,27 @@ pub fn handle_mouse_wheel(
             };
             if active_panel == Some(token::panel::PanelId::Outline) && v_delta != 0 {
                 update(model, Msg::Outline(OutlineMsg::Scroll { lines: v_delta }))
+            } else if active_panel == Some(token::panel::PanelId::TODO_LIST) && v_delta != 0 {
+                update(
+                    model,
+                    Msg::Todo(token::messages::TodoMsg::Scroll { lines: v_delta }),
+                )
+            } else if active_panel == Some(token::panel::PanelId::SEARCH_RESULTS) && v_delta != 0 {
+                update(
+                    model,
+                    Msg::Search(token::messages::SearchMsg::Scroll { lines: v_delta }),
+                )
+            } else if active_panel == Some(token::panel::PanelId::LOGS) && v_delta != 0 {
+                update(
+                    model,
+                    Msg::Ui(token::messages::UiMsg::ScrollLogViewer { lines: v_delta }),
+                )
+            } else if active_panel == Some(token::panel::PanelId::AI_CHAT) && v_delta != 0 {
+                // The transcript offset counts up from the bottom
+                update(
+                    model,
+                    Msg::AiChat(token::messages::AiChatMsg::Scroll { lines: -v_delta }),
+                )
             } else if active_panel == Some(token::panel::PanelId::TERMINAL) && v_delta != 0 {
                 let lines = v_delta.unsigned_abs() as usize;
                 let msg = if v_delta < 0 {
@@ -7,11 +7,13 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::mpsc::Sender;
 use std::sync::{Arc, RwLock};
 
 use winit::window::Window;
 use wry::{Rect, WebView, WebViewBuilder};
 
+use token::messages::{Msg, PreviewMsg};
 use token::model::editor_area::PreviewId;
 
 /// Content source for a preview - either generated HTML or a file with base directory
@@ -54,13 +56,15 @@ impl WebviewManager {
         }
     }
 
-    /// Create a new webview for a preview pane with custom protocol support
+    /// Create a new webview for a preview pane with custom protocol support.
+    /// Scrolling it sends `PreviewMsg::SyncFromPreview` on `msg_tx`.
     pub fn create_webview(
         &mut self,
         preview_id: PreviewId,
         window: &Rc<Window>,
         bounds: token::model::editor_area::Rect,
         content: PreviewContent,
+        msg_tx: Sender<Msg>,
     ) -> Result<(), wry::Error> {
         // Don't create duplicate
         if self.webviews.contains_key(&preview_id) {
@@ -84,6 +88,15 @@ impl WebviewManager {
             .with_url(format!("token://preview-{}/index.html", preview_id.0))
             .with_bounds(to_wry_rect(bounds, scale_factor, window_height))
             .with_transparent(false)
+            .with_ipc_handler(move |request| {
+                // The page posts the 1-based source line at its top
+                if let Ok(line) = request.body().trim().parse::<usize>() {
+                    let _ = msg_tx.send(Msg::Preview(PreviewMsg::SyncFromPreview {
+                        preview_id: pid,
+                        line: line.saturating_sub(1),
+                    }));
+                }
+            })
             .with_navigation_handler(|url| {
                 // Open external links in the default browser
                 if url.starts_with("http://") || url.starts_with("https://") {
@@ -128,11 +141,10 @@ impl WebviewManager {
         }
     }
 
-    /// Scroll webview to a specific line (for scroll sync)
-    #[allow(dead_code)]
+    /// Scroll webview to a specific (0-based) source line (for scroll sync)
     pub fn scroll_to_line(&self, preview_id: PreviewId, line: usize) {
         if let Some(webview) = self.webviews.get(&preview_id) {
-            let js = format!("if(window.scrollToLine) window.scrollToLine({});", line);
+            let js = format!("if(window.scrollToLine) window.scrollToLine({});", line + 1);
             let _ = webview.evaluate_script(&js);
         }
     }
@@ -0,0 +1,229 @@
+//! Trigram index over open documents, for searching without rescanning
+//!
+//! Every line of a document is broken into its three-character sequences
+//! (trigrams, compared case-insensitively), and each trigram maps to the
+//! lines it occurs on. A query can only match lines that contain all of its
+//! trigrams, so only those lines are checked against the query. Indexes are
+//! stamped with the document revision they were built from and rebuilt once
+//! the document changes: after the edit debounce, or when a search finds
+//! them stale. Queries shorter than a trigram fall back to scanning.
+
+use std::collections::HashMap;
+
+use crate::model::editor::Position;
+use crate::model::editor_area::DocumentId;
+use crate::model::{Document, SearchHighlight};
+
+/// Shortest query the index can narrow down; shorter ones are scanned for
+pub const MIN_QUERY_CHARS: usize = 3;
+
+/// Documents larger than this are scanned instead of indexed
+const MAX_INDEXED_CHARS: usize = 16 * 1024 * 1024;
+
+/// Three characters packed into one key (a char fits in 21 bits)
+type Trigram = u64;
+
+#[derive(Debug, Clone)]
+struct DocumentIndex {
+    /// Document revision the index was built from
+    revision: u64,
+    /// Lines each trigram occurs on, ascending
+    postings: HashMap<Trigram, Vec<u32>>,
+}
+
+/// Trigram indexes of the open documents
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    documents: HashMap<DocumentId, DocumentIndex>,
+}
+
+impl SearchIndex {
+    /// Index `document` unless its index is current. Documents without an
+    /// id or too large to index are left out.
+    pub fn refresh(&mut self, document: &Document) {
+        let Some(id) = document.id else {
+            return;
+        };
+        if self.is_current(document) {
+            return;
+        }
+        if document.buffer.len_chars() > MAX_INDEXED_CHARS {
+            self.documents.remove(&id);
+            return;
+        }
+
+        let mut postings: HashMap<Trigram, Vec<u32>> = HashMap::new();
+        let mut folded = Vec::new();
+        for (line, text) in document.buffer.lines().enumerate() {
+            folded.clear();
+            folded.extend(
+                text.chars()
+                    .filter(|&ch| ch != '\n' && ch != '\r')
+                    .map(fold),
+            );
+            for window in folded.windows(3) {
+                let lines = postings.entry(trigram(window)).or_default();
+                if lines.last() != Some(&(line as u32)) {
+                    lines.push(line as u32);
+                }
+            }
+        }
+        self.documents.insert(
+            id,
+            DocumentIndex {
+                revision: document.revision,
+                postings,
+            },
+        );
+    }
+
+    /// Drop the indexes of documents `keep` rejects (closed documents)
+    pub fn retain(&mut self, mut keep: impl FnMut(DocumentId) -> bool) {
+        self.documents.retain(|id, _| keep(*id));
+    }
+
+    /// Whether `document` has an index built from its current revision
+    pub fn is_current(&self, document: &Document) -> bool {
+        document
+            .id
+            .and_then(|id| self.documents.get(&id))
+            .is_some_and(|index| index.revision == document.revision)
+    }
+
+    /// Lines of `document` that may contain `query`, ascending. `None` when
+    /// the index can't narrow it down: the query is shorter than a trigram
+    /// or the document's index isn't current.
+    pub fn candidate_lines(&self, document: &Document, query: &str) -> Option<Vec<usize>> {
+        let index = self.documents.get(&document.id?)?;
+        if index.revision != document.revision {
+            return None;
+        }
+        let folded: Vec<char> = query.chars().map(fold).collect();
+        if folded.len() < MIN_QUERY_CHARS {
+            return None;
+        }
+
+        let mut lists = Vec::new();
+        for window in folded.windows(3) {
+            match index.postings.get(&trigram(window)) {
+                Some(lines) => lists.push(lines),
+                None => return Some(Vec::new()),
+            }
+        }
+        // Narrow down from the rarest trigram
+        lists.sort_by_key(|lines| lines.len());
+        let mut candidates = lists[0].clone();
+        for lines in &lists[1..] {
+            candidates.retain(|line| lines.binary_search(line).is_ok());
+            if candidates.is_empty() {
+                break;
+            }
+        }
+        Some(candidates.into_iter().map(|line| line as usize).collect())
+    }
+
+    /// All matches of `highlight` in `document`, within its scope, indexing
+    /// the document first if needed
+    pub fn matches(
+        &mut self,
+        document: &Document,
+        highlight: &SearchHighlight,
+    ) -> Vec<(Position, Position)> {
+        self.refresh(document);
+        // A regex's literal characters aren't its trigrams
+        let candidates = match highlight.regex {
+            Some(_) => None,
+            None => self.candidate_lines(document, &highlight.query),
+        };
+        let lines = candidates.unwrap_or_else(|| (0..document.line_count()).collect());
+
+        let mut matches = Vec::new();
+        for line in lines {
+            let Some(text) = document.get_line_cow(line) else {
+                continue;
+            };
+            matches.extend(
+                highlight
+                    .matches_on_line(document.id, line, &text)
+                    .into_iter()
+                    .map(|(start, end)| (Position::new(line, start), Position::new(line, end))),
+            );
+        }
+        matches
+    }
+}
+
+/// Case-folded character. Characters whose lowercase form is more than one
+/// character are kept as they are, which only they can match.
+fn fold(ch: char) -> char {
+    let mut lower = ch.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => ch,
+    }
+}
+
+fn trigram(chars: &[char]) -> Trigram {
+    ((chars[0] as u64) << 42) | ((chars[1] as u64) << 21) | chars[2] as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(text: &str) -> Document {
+        let mut document = Document::with_text(text);
+        document.id = Some(DocumentId(1));
+        document
+    }
+
+    fn highlight(query: &str, case_sensitive: bool) -> SearchHighlight {
+        SearchHighlight {
+            query: query.to_string(),
+            case_sensitive,
+            whole_word: false,
+            regex: None,
+            scope: None,
+        }
+    }
+
+    #[test]
+    fn candidates_contain_every_trigram_of_the_query() {
+        let document = document("let value = 1;\nfn main() {}\nVALUE + values\n");
+        let mut index = SearchIndex::default();
+        index.refresh(&document);
+
+        assert_eq!(index.candidate_lines(&document, "value"), Some(vec![0, 2]));
+        assert_eq!(index.candidate_lines(&document, "main("), Some(vec![1]));
+        assert_eq!(index.candidate_lines(&document, "nothing"), Some(vec![]));
+        // Too short to narrow down
+        assert_eq!(index.candidate_lines(&document, "va"), None);
+
+        let matches = index.matches(&document, &highlight("VALUE", true));
+        assert_eq!(matches, [(Position::new(2, 0), Position::new(2, 5))]);
+        assert_eq!(
+            index.matches(&document, &highlight("value", false)).len(),
+            3
+        );
+        assert_eq!(index.matches(&document, &highlight("e", false)).len(), 4);
+    }
+
+    #[test]
+    fn stale_indexes_are_rebuilt_before_searching() {
+        let mut document = document("alpha\nbeta\n");
+        let mut index = SearchIndex::default();
+        index.refresh(&document);
+
+        document.buffer = ropey::Rope::from_str("beta\nalpha\n");
+        document.revision += 1;
+        assert!(!index.is_current(&document));
+        assert_eq!(index.candidate_lines(&document, "alpha"), None);
+
+        let matches = index.matches(&document, &highlight("alpha", false));
+        assert_eq!(matches, [(Position::new(1, 0), Position::new(1, 5))]);
+        assert!(index.is_current(&document));
+
+        index.retain(|_| false);
+        assert!(!index.is_current(&document));
+    }
+}
@@ -0,0 +1,141 @@
+//! Persistent UI session state
+//!
+//! Saved to `session.json` in the config directory when the editor exits and
+//! restored on the next launch. Currently holds the dock layout (visibility,
+//! sizes, and active panels) and the color labels of open tabs.
+//!
+//! The open tabs and splits are kept per workspace in
+//! `sessions/<hash>.json` (see [`EditorSession`]).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{SplitDirection, TabColor};
+use crate::panel::DockLayoutState;
+
+/// UI state restored across launches
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Schema version for forward compatibility
+    #[serde(default)]
+    pub version: u32,
+    /// Dock visibility, sizes, and active panels
+    #[serde(default)]
+    pub docks: DockLayoutState,
+    /// Color labels of tabs, by the file they show
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tab_colors: BTreeMap<PathBuf, TabColor>,
+}
+
+impl Session {
+    pub const CURRENT_VERSION: u32 = 1;
+
+    /// Load the session from disk, or an empty session if none was saved
+    pub fn load() -> Self {
+        let Some(path) = crate::config_paths::session_file() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid session file {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Save the session to disk
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = crate::config_paths::session_file() else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "No config directory available",
+            ));
+        };
+        crate::config_paths::ensure_all_config_dirs();
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)
+    }
+}
+
+/// Open tabs and splits of one workspace (or of the editor without one)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditorSession {
+    /// Schema version for forward compatibility
+    #[serde(default)]
+    pub version: u32,
+    /// Split layout with the tabs of each group
+    pub layout: LayoutSession,
+    /// Focused group, counted depth-first through `layout`
+    #[serde(default)]
+    pub focused_group: usize,
+}
+
+/// A node of the saved split layout (mirrors `LayoutNode`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LayoutSession {
+    Group(GroupSession),
+    Split {
+        direction: SplitDirection,
+        children: Vec<LayoutSession>,
+        ratios: Vec<f32>,
+    },
+}
+
+/// Tabs of an editor group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupSession {
+    pub tabs: Vec<TabSession>,
+    #[serde(default)]
+    pub active_tab: usize,
+}
+
+/// A tab showing a file, with where its view was
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TabSession {
+    pub path: PathBuf,
+    /// Primary cursor line and column, zero-based
+    #[serde(default)]
+    pub cursor: (usize, usize),
+    /// First visible line
+    #[serde(default)]
+    pub top_line: usize,
+    /// First visible column
+    #[serde(default)]
+    pub left_column: usize,
+    /// Pinned to the start of its tab bar
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl EditorSession {
+    pub const CURRENT_VERSION: u32 = 1;
+
+    /// Load the session saved for the workspace at `root` (or for no
+    /// workspace), if there is one
+    pub fn load(root: Option<&Path>) -> Option<Self> {
+        let path = crate::config_paths::editor_session_file(root)?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&contents)
+            .map_err(|e| {
+                tracing::warn!("Ignoring invalid session file {}: {}", path.display(), e);
+            })
+            .ok()
+    }
+
+    /// Save the session for the workspace at `root` (or for no workspace)
+    pub fn save(&self, root: Option<&Path>) -> std::io::Result<()> {
+        let Some(path) = crate::config_paths::editor_session_file(root) else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "No config directory available",
+            ));
+        };
+        crate::config_paths::ensure_sessions_dir().map_err(std::io::Error::other)?;
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)
+    }
+}
@@ -0,0 +1,173 @@
+//! Text edits handed to the syntax worker for incremental parsing
+//!
+//! Documents log every change they make to their text. A parse request
+//! carries the changes since the previous request, which the worker turns
+//! into tree-sitter `InputEdit`s for the tree it cached. When the log can't
+//! be trusted (the text was replaced wholesale, or a request was missed) the
+//! worker diffs the old and new text instead.
+
+/// A change to a document's text, in chars
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Char offset where the edit starts
+    pub start: usize,
+    /// Number of chars removed at `start`
+    pub deleted: usize,
+    /// Text inserted at `start` after the removal
+    pub inserted: String,
+}
+
+impl TextEdit {
+    pub fn insert(start: usize, text: &str) -> Self {
+        Self {
+            start,
+            deleted: 0,
+            inserted: text.to_string(),
+        }
+    }
+
+    pub fn delete(start: usize, text: &str) -> Self {
+        Self {
+            start,
+            deleted: text.chars().count(),
+            inserted: String::new(),
+        }
+    }
+
+    pub fn replace(start: usize, deleted: &str, inserted: &str) -> Self {
+        Self {
+            start,
+            deleted: deleted.chars().count(),
+            inserted: inserted.to_string(),
+        }
+    }
+}
+
+/// The edits that turn a document's text at `base_revision` into the text
+/// of the parse request carrying them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyntaxEdits {
+    pub base_revision: u64,
+    pub edits: Vec<TextEdit>,
+}
+
+impl SyntaxEdits {
+    /// Combine with the edits of the next request, when `next` starts where
+    /// these end (at `revision`)
+    pub fn followed_by(self, revision: u64, next: Option<SyntaxEdits>) -> Option<SyntaxEdits> {
+        let next = next.filter(|next| next.base_revision == revision)?;
+        let mut edits = self.edits;
+        edits.extend(next.edits);
+        Some(SyntaxEdits {
+            base_revision: self.base_revision,
+            edits,
+        })
+    }
+}
+
+/// Edits made to a document since its last parse request
+#[derive(Debug, Clone, Default)]
+pub struct EditLog {
+    base_revision: u64,
+    /// Revision the logged edits bring the text to; `None` when the log
+    /// doesn't describe the text anymore
+    revision: Option<u64>,
+    edits: Vec<TextEdit>,
+}
+
+impl EditLog {
+    /// Log the edits that took the text from `from` to revision `to`. A
+    /// change the log missed since it last caught up starts it over at
+    /// `from`.
+    pub fn record(&mut self, from: u64, to: u64, edits: impl IntoIterator<Item = TextEdit>) {
+        if self.revision != Some(from) {
+            self.base_revision = from;
+            self.edits.clear();
+        }
+        self.edits.extend(edits);
+        self.revision = Some(to);
+    }
+
+    /// Forget the logged edits: the text changed in a way they don't cover
+    pub fn invalidate(&mut self) {
+        self.revision = None;
+        self.edits.clear();
+    }
+
+    /// The edits since the last request, if they lead to the text at
+    /// `revision`; the log starts over from there
+    pub fn take(&mut self, revision: u64) -> Option<SyntaxEdits> {
+        let edits = std::mem::take(&mut self.edits);
+        let base_revision = self.base_revision;
+        let current = self.revision == Some(revision);
+        self.base_revision = revision;
+        self.revision = Some(revision);
+        current.then_some(SyntaxEdits {
+            base_revision,
+            edits,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_hands_over_edits_since_the_last_request() {
+        let mut log = EditLog::default();
+        log.record(3, 4, [TextEdit::insert(0, "a")]);
+        log.record(4, 5, [TextEdit::delete(0, "a")]);
+
+        let taken = log.take(5).unwrap();
+        assert_eq!(taken.base_revision, 3);
+        assert_eq!(taken.edits.len(), 2);
+
+        // Nothing changed since
+        assert_eq!(
+            log.take(5),
+            Some(SyntaxEdits {
+                base_revision: 5,
+                edits: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn missed_changes_make_the_log_start_over() {
+        let mut log = EditLog::default();
+        log.record(1, 2, [TextEdit::insert(0, "a")]);
+        // Revision 3 came from a change that wasn't logged
+        log.record(3, 4, [TextEdit::insert(0, "b")]);
+        assert_eq!(log.take(4).unwrap().base_revision, 3);
+
+        log.record(4, 5, [TextEdit::insert(0, "c")]);
+        log.invalidate();
+        assert_eq!(log.take(6), None);
+        // The log is current again after a request
+        log.record(6, 7, [TextEdit::insert(0, "d")]);
+        assert_eq!(log.take(7).unwrap().base_revision, 6);
+    }
+
+    #[test]
+    fn coalesced_requests_chain_their_edits() {
+        let first = SyntaxEdits {
+            base_revision: 1,
+            edits: vec![TextEdit::insert(0, "a")],
+        };
+        let second = SyntaxEdits {
+            base_revision: 2,
+            edits: vec![TextEdit::insert(1, "b")],
+        };
+        let chained = first.clone().followed_by(2, Some(second)).unwrap();
+        assert_eq!(chained.base_revision, 1);
+        assert_eq!(chained.edits.len(), 2);
+
+        // A gap between the two requests
+        let third = SyntaxEdits {
+            base_revision: 5,
+            edits: vec![],
+        };
+        assert_eq!(first.followed_by(2, Some(third)), None);
+    }
+}
@@ -46,6 +46,36 @@ pub enum LanguageId {
 }
 
 impl LanguageId {
+    /// Every language, in the order the language picker lists them
+    pub const ALL: &'static [LanguageId] = &[
+        LanguageId::PlainText,
+        LanguageId::Bash,
+        LanguageId::Blade,
+        LanguageId::C,
+        LanguageId::Cpp,
+        LanguageId::Css,
+        LanguageId::Go,
+        LanguageId::Html,
+        LanguageId::Ini,
+        LanguageId::Java,
+        LanguageId::JavaScript,
+        LanguageId::Json,
+        LanguageId::Jsx,
+        LanguageId::Just,
+        LanguageId::Markdown,
+        LanguageId::Php,
+        LanguageId::Python,
+        LanguageId::Rust,
+        LanguageId::Scheme,
+        LanguageId::Sema,
+        LanguageId::Toml,
+        LanguageId::Tsx,
+        LanguageId::TypeScript,
+        LanguageId::Vue,
+        LanguageId::Xml,
+        LanguageId::Yaml,
+    ];
+
     /// Detect language from file extension
     ///
     /// Note: `.blade.php` files are detected in `from_path()` before this is called,
@@ -165,6 +195,27 @@ impl LanguageId {
         matches!(self, LanguageId::Markdown | LanguageId::Html)
     }
 
+    /// Check if this language delimits blocks with `{ }`, so typing `}` can
+    /// re-indent the block it closes
+    pub fn has_brace_blocks(&self) -> bool {
+        matches!(
+            self,
+            LanguageId::Rust
+                | LanguageId::Css
+                | LanguageId::JavaScript
+                | LanguageId::TypeScript
+                | LanguageId::Tsx
+                | LanguageId::Jsx
+                | LanguageId::Json
+                | LanguageId::Go
+                | LanguageId::Php
+                | LanguageId::C
+                | LanguageId::Cpp
+                | LanguageId::Java
+                | LanguageId::Bash
+        )
+    }
+
     /// Detect language from fenced code block info string (e.g., "rust", "python", "js")
     /// Used for language injection in markdown code blocks.
     pub fn from_code_fence_info(info: &str) -> Option<Self> {
@@ -348,6 +399,19 @@ mod tests {
         assert_eq!(LanguageId::from_path(Path::new("app.php")), LanguageId::Php);
     }
 
+    #[test]
+    fn test_all_languages_sorted_by_name() {
+        let names: Vec<String> = LanguageId::ALL[1..]
+            .iter()
+            .map(|lang| lang.display_name().to_lowercase())
+            .collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(names, sorted);
+        assert_eq!(LanguageId::ALL[0], LanguageId::PlainText);
+    }
+
     #[test]
     fn test_display_names() {
         assert_eq!(LanguageId::TypeScript.display_name(), "TypeScript");
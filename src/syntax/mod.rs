@@ -13,16 +13,23 @@
 //!              → (worker thread) → Msg::SyntaxUpdated → Cmd::Redraw
 //! ```
 //!
+//! Parse requests carry the edits made since the previous one (see
+//! [`EditLog`]), so the worker reparses incrementally and only extracts
+//! highlights again for the lines that changed.
+//!
 //! ## Supported Languages (Phase 1)
 //!
 //! - YAML
 //! - Markdown
 //! - Rust
 
+mod edits;
 mod highlights;
 mod languages;
 mod parser;
+pub mod tags;
 
+pub use edits::{EditLog, SyntaxEdits, TextEdit};
 pub use highlights::{
     highlight_id_for_name, HighlightId, HighlightToken, LineHighlights, SyntaxHighlights,
     HIGHLIGHT_NAMES,
@@ -1,13 +1,17 @@
 //! Tree-sitter parser state and highlighting extraction
 //!
 //! Manages parsers, trees, and queries for syntax highlighting.
-//! Supports incremental parsing by caching trees and computing edits.
+//! Supports incremental parsing by caching trees and applying the edits made
+//! since the last parse, re-extracting highlights only for changed lines.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
 
+use ropey::Rope;
 use streaming_iterator::StreamingIterator;
 use tree_sitter::{InputEdit, Parser, Point, Query, QueryCursor, Tree, TreeCursor};
 
+use super::edits::{SyntaxEdits, TextEdit};
 use super::highlights::{highlight_id_for_name, HighlightToken, SyntaxHighlights};
 use super::languages::LanguageId;
 use crate::model::editor_area::DocumentId;
@@ -19,7 +23,23 @@ struct DocParseState {
     /// The parsed tree
     tree: Tree,
     /// The source text that was parsed (needed for computing edits)
-    source: String,
+    source: Rope,
+    /// Document revision of `source`
+    revision: u64,
+    /// Whether `source` only breaks lines with `\n` or `\r\n`, so its rope
+    /// lines are the rows tree-sitter counts
+    plain_line_breaks: bool,
+    /// Highlights extracted from `tree`, updated after the next incremental
+    /// parse (plain grammars only, not those with injected languages)
+    highlights: Option<SyntaxHighlights>,
+}
+
+/// How a document's previous tree was reused for a new parse
+struct Reparse {
+    /// The previous tree, edited to line up with the new text
+    old_tree: Tree,
+    /// The edits made to it, in order
+    edits: Vec<InputEdit>,
 }
 
 /// Convert byte column to character column (handles UTF-8 multi-byte chars)
@@ -42,18 +62,80 @@ fn byte_to_char_col(text: &str, byte_col: usize) -> usize {
 /// take effect; keeping the first would paint everything with the fallback.
 fn finalize_line_tokens(highlights: &mut SyntaxHighlights) {
     for line_highlights in highlights.lines.values_mut() {
-        let tokens = &mut line_highlights.tokens;
-        // Stable sort preserves capture order within identical spans
-        tokens.sort_by_key(|t| (t.start_col, t.end_col));
-        // Collapse runs of identical spans, keeping the LAST capture
-        tokens.dedup_by(|next, kept| {
-            if next.start_col == kept.start_col && next.end_col == kept.end_col {
-                kept.highlight = next.highlight;
-                true
+        finalize_tokens(&mut line_highlights.tokens);
+    }
+}
+
+/// Sort and deduplicate one line's tokens (see [`finalize_line_tokens`])
+fn finalize_tokens(tokens: &mut Vec<HighlightToken>) {
+    // Stable sort preserves capture order within identical spans
+    tokens.sort_by_key(|t| (t.start_col, t.end_col));
+    // Collapse runs of identical spans, keeping the LAST capture
+    tokens.dedup_by(|next, kept| {
+        if next.start_col == kept.start_col && next.end_col == kept.end_col {
+            kept.highlight = next.highlight;
+            true
+        } else {
+            false
+        }
+    });
+}
+
+/// Run a highlight query over `tree` and add its captures to `highlights`
+/// as per-line tokens, only for the lines in `rows` if given. `line` looks
+/// up the text of a line, without its line break.
+fn collect_highlights<'a>(
+    query: &Query,
+    source: &str,
+    tree: &Tree,
+    rows: Option<Range<usize>>,
+    line: impl Fn(usize) -> &'a str,
+    highlights: &mut SyntaxHighlights,
+) {
+    let mut cursor = QueryCursor::new();
+    if let Some(rows) = &rows {
+        cursor.set_point_range(Point::new(rows.start, 0)..Point::new(rows.end, 0));
+    }
+    let rows = rows.unwrap_or(0..usize::MAX);
+
+    // Run query and collect captures using StreamingIterator
+    let mut captures = cursor.captures(query, tree.root_node(), source.as_bytes());
+    while let Some((query_match, capture_idx)) = captures.next() {
+        let capture = &query_match.captures[*capture_idx];
+        let capture_name = &query.capture_names()[capture.index as usize];
+
+        // Map capture name to highlight ID
+        let highlight_id = match highlight_id_for_name(capture_name) {
+            Some(id) => id,
+            None => continue, // Skip unknown captures
+        };
+
+        let start = capture.node.start_position();
+        let end = capture.node.end_position();
+
+        // Multi-line tokens are split across lines
+        for row in start.row.max(rows.start)..=end.row.min(rows.end - 1) {
+            let line = line(row);
+            let start_char = if row == start.row {
+                byte_to_char_col(line, start.column)
             } else {
-                false
+                0
+            };
+            let end_char = if row == end.row {
+                byte_to_char_col(line, end.column)
+            } else {
+                line.chars().count()
+            };
+
+            if start_char < end_char {
+                let line_highlights = highlights.lines.entry(row).or_default();
+                line_highlights.tokens.push(HighlightToken {
+                    start_col: start_char,
+                    end_col: end_char,
+                    highlight: highlight_id,
+                });
             }
-        });
+        }
     }
 }
 
@@ -115,6 +197,66 @@ fn compute_incremental_edit(old_src: &str, new_src: &str) -> Option<InputEdit> {
     })
 }
 
+/// Whether `text` breaks lines with anything but `\n` or `\r\n`: ropey
+/// also counts a lone `\r`, vertical tab, form feed, NEL and the Unicode
+/// line and paragraph separators as line breaks, tree-sitter doesn't
+fn has_other_line_breaks(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.iter().enumerate().any(|(i, &byte)| match byte {
+        b'\r' => bytes.get(i + 1) != Some(&b'\n'),
+        0x0B | 0x0C => true,
+        // U+0085 is encoded as C2 85, U+2028 and U+2029 as E2 80 A8/A9
+        0x85 => i >= 1 && bytes[i - 1] == 0xC2,
+        0xA8 | 0xA9 => i >= 2 && bytes[i - 2] == 0xE2 && bytes[i - 1] == 0x80,
+        _ => false,
+    })
+}
+
+/// Tree-sitter position of a byte offset in a rope with plain line breaks
+fn rope_point(rope: &Rope, byte: usize) -> Point {
+    let row = rope.byte_to_line(byte);
+    Point::new(row, byte - rope.line_to_byte(row))
+}
+
+/// Apply logged edits to a copy of the old text, returning the matching
+/// tree-sitter edits and the new text if it comes out as `new_src`. Gives
+/// up on edits that would make rope lines and tree-sitter rows disagree.
+fn apply_text_edits(
+    old_src: &Rope,
+    edits: &[TextEdit],
+    new_src: &str,
+) -> Option<(Vec<InputEdit>, Rope)> {
+    let mut rope = old_src.clone();
+    let mut input_edits = Vec::with_capacity(edits.len());
+    for edit in edits {
+        let end = edit.start.checked_add(edit.deleted)?;
+        // Joining text to a `\r` could leave it without its `\n`
+        let after_cr = edit.start > 0 && rope.get_char(edit.start - 1) == Some('\r');
+        if end > rope.len_chars() || after_cr || has_other_line_breaks(&edit.inserted) {
+            return None;
+        }
+
+        let start_byte = rope.char_to_byte(edit.start);
+        let old_end_byte = rope.char_to_byte(end);
+        let start_position = rope_point(&rope, start_byte);
+        let old_end_position = rope_point(&rope, old_end_byte);
+        rope.remove(edit.start..end);
+        rope.insert(edit.start, &edit.inserted);
+        let new_end_byte = start_byte + edit.inserted.len();
+
+        input_edits.push(InputEdit {
+            start_byte,
+            old_end_byte,
+            new_end_byte,
+            start_position,
+            old_end_position,
+            new_end_position: rope_point(&rope, new_end_byte),
+        });
+    }
+    // A change the log missed shows up as a different text
+    (rope == new_src).then_some((input_edits, rope))
+}
+
 // Embedded query files
 // Phase 1 languages
 const YAML_HIGHLIGHTS: &str = include_str!("../../queries/yaml/highlights.scm");
@@ -360,6 +502,20 @@ impl ParserState {
         language: LanguageId,
         doc_id: DocumentId,
         revision: u64,
+    ) -> SyntaxHighlights {
+        self.parse_and_highlight_edited(source, language, doc_id, revision, None)
+    }
+
+    /// Parse document and extract highlights, given the `edits` made to it
+    /// since the previous parse. After an incremental parse only the lines
+    /// that changed have their highlights extracted again.
+    pub fn parse_and_highlight_edited(
+        &mut self,
+        source: &str,
+        language: LanguageId,
+        doc_id: DocumentId,
+        revision: u64,
+        edits: Option<&SyntaxEdits>,
     ) -> SyntaxHighlights {
         // Skip plain text
         if language == LanguageId::PlainText {
@@ -368,131 +524,146 @@ impl ParserState {
 
         // Use specialized two-pass parsing for markdown (block + inline)
         if language == LanguageId::Markdown {
-            return self.parse_and_highlight_markdown(source, doc_id, revision);
+            return self.parse_and_highlight_markdown(source, doc_id, revision, edits);
         }
 
         // Use specialized parsing with language injection for HTML
         if language == LanguageId::Html {
-            return self.parse_and_highlight_html(source, doc_id, revision);
+            return self.parse_and_highlight_html(source, doc_id, revision, edits);
         }
 
         // Use specialized parsing with language injection for Vue SFC
         if language == LanguageId::Vue {
-            return self.parse_and_highlight_vue(source, doc_id, revision);
+            return self.parse_and_highlight_vue(source, doc_id, revision, edits);
         }
 
-        let parser = match self.parsers.get_mut(&language) {
-            Some(p) => p,
-            None => {
-                tracing::warn!("No parser for language {:?}", language);
-                return SyntaxHighlights::new(language, revision);
+        let Some((tree, reparse)) = self.parse_document(source, language, doc_id, revision, edits)
+        else {
+            return SyntaxHighlights::new(language, revision);
+        };
+
+        // Reuse the previous highlights for the lines that didn't change
+        let Some(cached) = self.doc_cache.get_mut(&doc_id) else {
+            return SyntaxHighlights::new(language, revision);
+        };
+        let previous = cached.highlights.take();
+        let rope = cached.plain_line_breaks.then(|| cached.source.clone());
+        let highlights = match (reparse, previous, rope) {
+            (Some(reparse), Some(previous), Some(rope)) => {
+                self.extract_changed_highlights(source, &rope, &tree, &reparse, previous, revision)
             }
+            _ => self.extract_highlights(source, &tree, language, revision),
         };
 
-        // Try incremental parsing if we have a cached tree for this document
-        let tree = if let Some(cached) = self.doc_cache.get_mut(&doc_id) {
-            if cached.language == language {
-                // Same language, try incremental parse
-                if let Some(edit) = compute_incremental_edit(&cached.source, source) {
-                    // Apply the edit to the cached tree
-                    cached.tree.edit(&edit);
-
-                    tracing::trace!(
-                        "Incremental parse: edit at byte {}..{} -> {}..{}",
-                        edit.start_byte,
-                        edit.old_end_byte,
-                        edit.start_byte,
-                        edit.new_end_byte
-                    );
+        if let Some(cached) = self.doc_cache.get_mut(&doc_id) {
+            cached.highlights = Some(highlights.clone());
+        }
+        highlights
+    }
 
-                    // Parse with the edited old tree for incremental reuse
-                    match parser.parse(source, Some(&cached.tree)) {
-                        Some(new_tree) => {
-                            // Update cache with new tree and source
-                            cached.tree = new_tree.clone();
-                            cached.source = source.to_owned();
-                            new_tree
-                        }
-                        None => {
-                            // Incremental parse failed, fall back to full parse
-                            tracing::warn!(
-                                "Incremental parse failed for {:?}, falling back to full parse",
-                                language
-                            );
-                            self.doc_cache.remove(&doc_id);
-                            match parser.parse(source, None) {
-                                Some(t) => {
-                                    self.doc_cache.insert(
-                                        doc_id,
-                                        DocParseState {
-                                            language,
-                                            tree: t.clone(),
-                                            source: source.to_owned(),
-                                        },
-                                    );
-                                    t
-                                }
-                                None => {
-                                    tracing::error!("Full parse also failed for {:?}", language);
-                                    return SyntaxHighlights::new(language, revision);
-                                }
-                            }
-                        }
-                    }
-                } else {
-                    // No edit (source unchanged), reuse cached tree
-                    tracing::trace!("Source unchanged, reusing cached tree");
-                    cached.tree.clone()
-                }
-            } else {
-                // Language changed, do full parse
+    /// Parse a document, reusing its cached tree if it was parsed as the same
+    /// language before. The old tree is edited with `edits` when they lead
+    /// from the cached text to `source`, or else with the difference between
+    /// the two.
+    fn parse_document(
+        &mut self,
+        source: &str,
+        language: LanguageId,
+        doc_id: DocumentId,
+        revision: u64,
+        edits: Option<&SyntaxEdits>,
+    ) -> Option<(Tree, Option<Reparse>)> {
+        let Some(parser) = self.parsers.get_mut(&language) else {
+            tracing::warn!("No parser for language {:?}", language);
+            return None;
+        };
+
+        let cached = self.doc_cache.remove(&doc_id).filter(|cached| {
+            if cached.language != language {
                 tracing::debug!(
                     "Language changed from {:?} to {:?}, doing full parse",
                     cached.language,
                     language
                 );
-                self.doc_cache.remove(&doc_id);
-                match parser.parse(source, None) {
-                    Some(t) => {
-                        self.doc_cache.insert(
-                            doc_id,
-                            DocParseState {
-                                language,
-                                tree: t.clone(),
-                                source: source.to_owned(),
-                            },
-                        );
-                        t
-                    }
-                    None => {
-                        tracing::error!("Parse failed for {:?}", language);
-                        return SyntaxHighlights::new(language, revision);
-                    }
+            }
+            cached.language == language
+        });
+
+        if let Some(cached) = cached {
+            let logged = edits
+                .filter(|edits| edits.base_revision == cached.revision && cached.plain_line_breaks)
+                .and_then(|edits| apply_text_edits(&cached.source, &edits.edits, source));
+            let (input_edits, rope, plain_line_breaks) = match logged {
+                Some((input_edits, rope)) => (input_edits, rope, true),
+                None => {
+                    let old_source = cached.source.to_string();
+                    let input_edits = compute_incremental_edit(&old_source, source)
+                        .into_iter()
+                        .collect();
+                    (
+                        input_edits,
+                        Rope::from_str(source),
+                        !has_other_line_breaks(source),
+                    )
                 }
+            };
+
+            let mut old_tree = cached.tree;
+            for edit in &input_edits {
+                old_tree.edit(edit);
             }
-        } else {
-            // No cached tree, do full parse
-            match parser.parse(source, None) {
-                Some(t) => {
+            tracing::trace!("Incremental parse with {} edits", input_edits.len());
+
+            // Parse with the edited old tree for incremental reuse
+            let tree = if input_edits.is_empty() {
+                Some(old_tree.clone())
+            } else {
+                parser.parse(source, Some(&old_tree))
+            };
+            match tree {
+                Some(tree) => {
                     self.doc_cache.insert(
                         doc_id,
                         DocParseState {
                             language,
-                            tree: t.clone(),
-                            source: source.to_owned(),
+                            tree: tree.clone(),
+                            source: rope,
+                            revision,
+                            plain_line_breaks,
+                            highlights: cached.highlights,
                         },
                     );
-                    t
-                }
-                None => {
-                    tracing::error!("Parse failed for {:?}", language);
-                    return SyntaxHighlights::new(language, revision);
+                    return Some((
+                        tree,
+                        Some(Reparse {
+                            old_tree,
+                            edits: input_edits,
+                        }),
+                    ));
                 }
+                None => tracing::warn!(
+                    "Incremental parse failed for {:?}, falling back to full parse",
+                    language
+                ),
             }
-        };
+        }
 
-        // Extract highlights
-        self.extract_highlights(source, &tree, language, revision)
+        let Some(tree) = parser.parse(source, None) else {
+            tracing::error!("Parse failed for {:?}", language);
+            return None;
+        };
+        self.doc_cache.insert(
+            doc_id,
+            DocParseState {
+                language,
+                tree: tree.clone(),
+                source: Rope::from_str(source),
+                revision,
+                plain_line_breaks: !has_other_line_breaks(source),
+                highlights: None,
+            },
+        );
+        Some((tree, None))
     }
 
     /// Remove cached parse state for a document (call when document is closed)
@@ -508,82 +679,101 @@ impl ParserState {
         language: LanguageId,
         revision: u64,
     ) -> SyntaxHighlights {
-        let query = match self.queries.get(&language) {
-            Some(q) => q,
-            None => return SyntaxHighlights::new(language, revision),
-        };
-
         let mut highlights = SyntaxHighlights::new(language, revision);
-        let mut cursor = QueryCursor::new();
-        let source_bytes = source.as_bytes();
+        let Some(query) = self.queries.get(&language) else {
+            return highlights;
+        };
 
         // Pre-split into lines for byte→char column conversion
         let lines: Vec<&str> = source.lines().collect();
+        collect_highlights(
+            query,
+            source,
+            tree,
+            None,
+            |row| lines.get(row).copied().unwrap_or(""),
+            &mut highlights,
+        );
 
-        // Run query and collect captures using StreamingIterator
-        let mut captures = cursor.captures(query, tree.root_node(), source_bytes);
-        while let Some((query_match, capture_idx)) = captures.next() {
-            let capture = &query_match.captures[*capture_idx];
-            let capture_name = &query.capture_names()[capture.index as usize];
-
-            // Map capture name to highlight ID
-            let highlight_id = match highlight_id_for_name(capture_name) {
-                Some(id) => id,
-                None => continue, // Skip unknown captures
-            };
+        finalize_line_tokens(&mut highlights);
 
-            let node = capture.node;
-            let start = node.start_position();
-            let end = node.end_position();
+        highlights
+    }
 
-            // Handle multi-line nodes
-            if start.row == end.row {
-                // Single line token
-                let row = start.row;
-                let line = lines.get(row).copied().unwrap_or("");
-                let start_char = byte_to_char_col(line, start.column);
-                let end_char = byte_to_char_col(line, end.column);
-
-                if start_char < end_char {
-                    let line_highlights = highlights.lines.entry(row).or_default();
-                    line_highlights.tokens.push(HighlightToken {
-                        start_col: start_char,
-                        end_col: end_char,
-                        highlight: highlight_id,
-                    });
-                }
-            } else {
-                // Multi-line token: split across lines
-                for row in start.row..=end.row {
-                    let line = lines.get(row).copied().unwrap_or("");
-                    let line_char_len = line.chars().count();
+    /// Bring the highlights of the previous parse up to date after an
+    /// incremental one: move the lines after each edit, then extract the
+    /// edited lines and those whose syntax changed again. `rope` holds
+    /// `source`, for looking up lines.
+    fn extract_changed_highlights(
+        &self,
+        source: &str,
+        rope: &Rope,
+        tree: &Tree,
+        reparse: &Reparse,
+        mut highlights: SyntaxHighlights,
+        revision: u64,
+    ) -> SyntaxHighlights {
+        highlights.revision = revision;
+        let Some(query) = self.queries.get(&highlights.language) else {
+            highlights.lines.clear();
+            return highlights;
+        };
 
-                    let (start_char, end_char) = if row == start.row {
-                        // First line: from start to end of line
-                        let start_char = byte_to_char_col(line, start.column);
-                        (start_char, line_char_len)
-                    } else if row == end.row {
-                        // Last line: from start of line to end position
-                        let end_char = byte_to_char_col(line, end.column);
-                        (0, end_char)
+        let mut changed = BTreeSet::new();
+        for edit in &reparse.edits {
+            let start = edit.start_position.row;
+            let old_end = edit.old_end_position.row;
+            let new_end = edit.new_end_position.row;
+            if old_end != new_end {
+                let moved = |row: usize| {
+                    if row < start {
+                        Some(row)
+                    } else if row > old_end {
+                        Some(row - old_end + new_end)
                     } else {
-                        // Middle lines: entire line
-                        (0, line_char_len)
-                    };
-
-                    if start_char < end_char {
-                        let line_highlights = highlights.lines.entry(row).or_default();
-                        line_highlights.tokens.push(HighlightToken {
-                            start_col: start_char,
-                            end_col: end_char,
-                            highlight: highlight_id,
-                        });
+                        None
                     }
-                }
+                };
+                highlights.lines = std::mem::take(&mut highlights.lines)
+                    .into_iter()
+                    .filter_map(|(row, line)| Some((moved(row)?, line)))
+                    .collect();
+                changed = changed.into_iter().filter_map(moved).collect();
             }
+            changed.extend(start..=new_end);
+        }
+        for range in reparse.old_tree.changed_ranges(tree) {
+            changed.extend(range.start_point.row..=range.end_point.row);
         }
 
-        finalize_line_tokens(&mut highlights);
+        let line_count = rope.len_lines();
+        highlights.lines.retain(|&row, _| row < line_count);
+        let line = |row: usize| {
+            let text = &source[rope.line_to_byte(row)..rope.line_to_byte(row + 1)];
+            let text = text.strip_suffix('\n').unwrap_or(text);
+            text.strip_suffix('\r').unwrap_or(text)
+        };
+
+        // Extract each run of consecutive changed lines in one query
+        let mut rows = changed
+            .into_iter()
+            .filter(|&row| row < line_count)
+            .peekable();
+        while let Some(first) = rows.next() {
+            let mut end = first + 1;
+            while rows.next_if_eq(&end).is_some() {
+                end += 1;
+            }
+            for row in first..end {
+                highlights.lines.remove(&row);
+            }
+            collect_highlights(query, source, tree, Some(first..end), line, &mut highlights);
+            for row in first..end {
+                if let Some(line_highlights) = highlights.lines.get_mut(&row) {
+                    finalize_tokens(&mut line_highlights.tokens);
+                }
+            }
+        }
 
         highlights
     }
@@ -594,82 +784,14 @@ impl ParserState {
         source: &str,
         doc_id: DocumentId,
         revision: u64,
+        edits: Option<&SyntaxEdits>,
     ) -> SyntaxHighlights {
         let language = LanguageId::Markdown;
 
         // Step 1: Parse block structure with existing block parser
-        let parser = match self.parsers.get_mut(&language) {
-            Some(p) => p,
-            None => {
-                tracing::warn!("No parser for markdown");
-                return SyntaxHighlights::new(language, revision);
-            }
-        };
-
-        // Try incremental parsing if we have a cached tree
-        let block_tree = if let Some(cached) = self.doc_cache.get_mut(&doc_id) {
-            if cached.language == language {
-                if let Some(edit) = compute_incremental_edit(&cached.source, source) {
-                    cached.tree.edit(&edit);
-                    match parser.parse(source, Some(&cached.tree)) {
-                        Some(new_tree) => {
-                            cached.tree = new_tree.clone();
-                            cached.source = source.to_owned();
-                            new_tree
-                        }
-                        None => {
-                            self.doc_cache.remove(&doc_id);
-                            match parser.parse(source, None) {
-                                Some(t) => {
-                                    self.doc_cache.insert(
-                                        doc_id,
-                                        DocParseState {
-                                            language,
-                                            tree: t.clone(),
-                                            source: source.to_owned(),
-                                        },
-                                    );
-                                    t
-                                }
-                                None => return SyntaxHighlights::new(language, revision),
-                            }
-                        }
-                    }
-                } else {
-                    cached.tree.clone()
-                }
-            } else {
-                self.doc_cache.remove(&doc_id);
-                match parser.parse(source, None) {
-                    Some(t) => {
-                        self.doc_cache.insert(
-                            doc_id,
-                            DocParseState {
-                                language,
-                                tree: t.clone(),
-                                source: source.to_owned(),
-                            },
-                        );
-                        t
-                    }
-                    None => return SyntaxHighlights::new(language, revision),
-                }
-            }
-        } else {
-            match parser.parse(source, None) {
-                Some(t) => {
-                    self.doc_cache.insert(
-                        doc_id,
-                        DocParseState {
-                            language,
-                            tree: t.clone(),
-                            source: source.to_owned(),
-                        },
-                    );
-                    t
-                }
-                None => return SyntaxHighlights::new(language, revision),
-            }
+        let Some((block_tree, _)) = self.parse_document(source, language, doc_id, revision, edits)
+        else {
+            return SyntaxHighlights::new(language, revision);
         };
 
         // Step 2: Extract block-level highlights
@@ -1056,82 +1178,14 @@ impl ParserState {
         source: &str,
         doc_id: DocumentId,
         revision: u64,
+        edits: Option<&SyntaxEdits>,
     ) -> SyntaxHighlights {
         let language = LanguageId::Html;
 
         // Step 1: Parse HTML structure
-        let parser = match self.parsers.get_mut(&language) {
-            Some(p) => p,
-            None => {
-                tracing::warn!("No parser for HTML");
-                return SyntaxHighlights::new(language, revision);
-            }
-        };
-
-        // Try incremental parsing if cached
-        let html_tree = if let Some(cached) = self.doc_cache.get_mut(&doc_id) {
-            if cached.language == language {
-                if let Some(edit) = compute_incremental_edit(&cached.source, source) {
-                    cached.tree.edit(&edit);
-                    match parser.parse(source, Some(&cached.tree)) {
-                        Some(new_tree) => {
-                            cached.tree = new_tree.clone();
-                            cached.source = source.to_owned();
-                            new_tree
-                        }
-                        None => {
-                            self.doc_cache.remove(&doc_id);
-                            match parser.parse(source, None) {
-                                Some(t) => {
-                                    self.doc_cache.insert(
-                                        doc_id,
-                                        DocParseState {
-                                            language,
-                                            tree: t.clone(),
-                                            source: source.to_owned(),
-                                        },
-                                    );
-                                    t
-                                }
-                                None => return SyntaxHighlights::new(language, revision),
-                            }
-                        }
-                    }
-                } else {
-                    cached.tree.clone()
-                }
-            } else {
-                self.doc_cache.remove(&doc_id);
-                match parser.parse(source, None) {
-                    Some(t) => {
-                        self.doc_cache.insert(
-                            doc_id,
-                            DocParseState {
-                                language,
-                                tree: t.clone(),
-                                source: source.to_owned(),
-                            },
-                        );
-                        t
-                    }
-                    None => return SyntaxHighlights::new(language, revision),
-                }
-            }
-        } else {
-            match parser.parse(source, None) {
-                Some(t) => {
-                    self.doc_cache.insert(
-                        doc_id,
-                        DocParseState {
-                            language,
-                            tree: t.clone(),
-                            source: source.to_owned(),
-                        },
-                    );
-                    t
-                }
-                None => return SyntaxHighlights::new(language, revision),
-            }
+        let Some((html_tree, _)) = self.parse_document(source, language, doc_id, revision, edits)
+        else {
+            return SyntaxHighlights::new(language, revision);
         };
 
         // Step 2: Extract HTML-level highlights
@@ -1257,82 +1311,13 @@ impl ParserState {
         source: &str,
         doc_id: DocumentId,
         revision: u64,
+        edits: Option<&SyntaxEdits>,
     ) -> SyntaxHighlights {
         let language = LanguageId::Vue;
 
         // Step 1: Parse with HTML grammar (Vue SFC is structurally valid HTML)
-        let parser = match self.parsers.get_mut(&language) {
-            Some(p) => p,
-            None => {
-                tracing::warn!("No parser for Vue");
-                return SyntaxHighlights::new(language, revision);
-            }
-        };
-
-        // Try incremental parsing if cached
-        let tree = if let Some(cached) = self.doc_cache.get_mut(&doc_id) {
-            if cached.language == language {
-                if let Some(edit) = compute_incremental_edit(&cached.source, source) {
-                    cached.tree.edit(&edit);
-                    match parser.parse(source, Some(&cached.tree)) {
-                        Some(new_tree) => {
-                            cached.tree = new_tree.clone();
-                            cached.source = source.to_owned();
-                            new_tree
-                        }
-                        None => {
-                            self.doc_cache.remove(&doc_id);
-                            match parser.parse(source, None) {
-                                Some(t) => {
-                                    self.doc_cache.insert(
-                                        doc_id,
-                                        DocParseState {
-                                            language,
-                                            tree: t.clone(),
-                                            source: source.to_owned(),
-                                        },
-                                    );
-                                    t
-                                }
-                                None => return SyntaxHighlights::new(language, revision),
-                            }
-                        }
-                    }
-                } else {
-                    cached.tree.clone()
-                }
-            } else {
-                self.doc_cache.remove(&doc_id);
-                match parser.parse(source, None) {
-                    Some(t) => {
-                        self.doc_cache.insert(
-                            doc_id,
-                            DocParseState {
-                                language,
-                                tree: t.clone(),
-                                source: source.to_owned(),
-                            },
-                        );
-                        t
-                    }
-                    None => return SyntaxHighlights::new(language, revision),
-                }
-            }
-        } else {
-            match parser.parse(source, None) {
-                Some(t) => {
-                    self.doc_cache.insert(
-                        doc_id,
-                        DocParseState {
-                            language,
-                            tree: t.clone(),
-                            source: source.to_owned(),
-                        },
-                    );
-                    t
-                }
-                None => return SyntaxHighlights::new(language, revision),
-            }
+        let Some((tree, _)) = self.parse_document(source, language, doc_id, revision, edits) else {
+            return SyntaxHighlights::new(language, revision);
         };
 
         // Step 2: Extract HTML-level highlights (Vue uses same query as HTML)
@@ -2591,6 +2576,130 @@ test:
         assert_eq!(h1.lines.len(), h2.lines.len());
     }
 
+    /// Parse `source` after applying `edits` to it, both incrementally with
+    /// the logged edits and from scratch, and check both give the same
+    /// highlights
+    fn assert_incremental_matches_full(
+        state: &mut ParserState,
+        doc_id: DocumentId,
+        language: LanguageId,
+        source: &mut String,
+        revision: &mut u64,
+        edits: Vec<TextEdit>,
+    ) {
+        let mut rope = Rope::from_str(source);
+        for edit in &edits {
+            rope.remove(edit.start..edit.start + edit.deleted);
+            rope.insert(edit.start, &edit.inserted);
+        }
+        *source = rope.to_string();
+        let logged = SyntaxEdits {
+            base_revision: *revision,
+            edits,
+        };
+        *revision += 1;
+        // The logged edits are used, not a diff
+        let cached = &state.doc_cache[&doc_id].source;
+        assert!(apply_text_edits(cached, &logged.edits, source).is_some());
+
+        let incremental =
+            state.parse_and_highlight_edited(source, language, doc_id, *revision, Some(&logged));
+        let full = ParserState::new().parse_and_highlight(source, language, doc_id, *revision);
+
+        let mut rows: Vec<_> = full.lines.keys().chain(incremental.lines.keys()).collect();
+        rows.sort();
+        rows.dedup();
+        for row in rows {
+            assert_eq!(
+                incremental.get_line(*row).map(|l| &l.tokens),
+                full.get_line(*row).map(|l| &l.tokens),
+                "line {} differs after editing to:\n{}",
+                row,
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_incremental_highlights_match_full_parse() {
+        let mut state = ParserState::new();
+        let doc_id = DocumentId(106);
+        let language = LanguageId::Rust;
+        let mut source =
+            "fn main() {\n    let x = 1;\n}\n\nstruct Point {\n    x: i32,\n}\n".to_string();
+        let mut revision = 1;
+        state.parse_and_highlight(&source, language, doc_id, revision);
+
+        let steps = vec![
+            // Typing on one line
+            vec![TextEdit::insert(24, "2")],
+            // A new line in the middle moves the lines below it
+            vec![TextEdit::insert(27, "\n    let y = \"s\";")],
+            // Opening a block comment changes everything after it
+            vec![TextEdit::insert(0, "/* ")],
+            // Closing it again, then joining two lines
+            vec![TextEdit::delete(0, "/* "), TextEdit::delete(11, "\n")],
+            // Several edits at once, like a multi-cursor edit
+            vec![
+                TextEdit::replace(3, "main", "start"),
+                TextEdit::insert(0, "// header\n"),
+            ],
+        ];
+        for edits in steps {
+            assert_incremental_matches_full(
+                &mut state,
+                doc_id,
+                language,
+                &mut source,
+                &mut revision,
+                edits,
+            );
+        }
+
+        // Without the log the texts are diffed instead
+        source.insert_str(0, "use std::fmt;\n");
+        revision += 1;
+        let diffed = state.parse_and_highlight(&source, language, doc_id, revision);
+        let full = ParserState::new().parse_and_highlight(&source, language, doc_id, revision);
+        assert_eq!(diffed.lines.len(), full.lines.len());
+        for (row, line) in &full.lines {
+            assert_eq!(diffed.get_line(*row).map(|l| &l.tokens), Some(&line.tokens));
+        }
+    }
+
+    #[test]
+    fn test_apply_text_edits_checks_the_result() {
+        let old = Rope::from_str("let a = 1;\nlet b = 2;\n");
+        let edits = vec![TextEdit::replace(15, "b", "bb"), TextEdit::insert(0, "\n")];
+
+        let (input_edits, rope) =
+            apply_text_edits(&old, &edits, "\nlet a = 1;\nlet bb = 2;\n").unwrap();
+        assert_eq!(rope.to_string(), "\nlet a = 1;\nlet bb = 2;\n");
+        assert_eq!(input_edits[0].start_byte, 15);
+        assert_eq!(input_edits[0].start_position, Point::new(1, 4));
+        assert_eq!(input_edits[0].new_end_position, Point::new(1, 6));
+        assert_eq!(input_edits[1].new_end_position, Point::new(1, 0));
+
+        // The log missed a change
+        assert!(apply_text_edits(&old, &edits, "let a = 1;\nlet bb = 3;\n").is_none());
+        // Out of range
+        assert!(apply_text_edits(&old, &[TextEdit::delete(20, "xxxxx")], "").is_none());
+        // Splitting a \r\n
+        let crlf = Rope::from_str("a\r\nb");
+        assert!(apply_text_edits(&crlf, &[TextEdit::insert(2, "x")], "a\rx\nb").is_none());
+    }
+
+    #[test]
+    fn test_has_other_line_breaks() {
+        assert!(!has_other_line_breaks("a\nb\r\nc é"));
+        assert!(has_other_line_breaks("a\rb"));
+        assert!(has_other_line_breaks("a\r"));
+        assert!(has_other_line_breaks("a\u{c}b"));
+        assert!(has_other_line_breaks("a\u{85}b"));
+        assert!(has_other_line_breaks("a\u{2028}b"));
+        assert!(!has_other_line_breaks("\u{2020}\u{a8}"));
+    }
+
     #[test]
     fn test_clear_doc_cache() {
         let mut state = ParserState::new();
@@ -0,0 +1,288 @@
+//! Markup tag structure for auto-closing and linked tag editing
+//!
+//! Parses HTML/XML/JSX sources with tree-sitter on demand and pairs opening
+//! and closing tag names with a simple stack. The syntax worker keeps its
+//! trees off the main thread, so these helpers run their own short-lived
+//! parser; they are only invoked when the cursor is already next to a `<`
+//! or inside a tag name, never on every keystroke.
+
+use std::ops::Range;
+
+use tree_sitter::{Node, Parser};
+
+use super::languages::LanguageId;
+
+/// HTML elements that never have a closing tag
+const HTML_VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Node kinds describing tags in a particular grammar
+struct TagGrammar {
+    open_kinds: &'static [&'static str],
+    close_kinds: &'static [&'static str],
+    /// Child kinds holding the tag name (used when the grammar has no `name` field)
+    name_kinds: &'static [&'static str],
+    has_void_elements: bool,
+}
+
+const HTML_TAGS: TagGrammar = TagGrammar {
+    open_kinds: &["start_tag"],
+    close_kinds: &["end_tag"],
+    name_kinds: &["tag_name"],
+    has_void_elements: true,
+};
+
+const XML_TAGS: TagGrammar = TagGrammar {
+    open_kinds: &["STag"],
+    close_kinds: &["ETag"],
+    name_kinds: &["Name"],
+    has_void_elements: false,
+};
+
+const JSX_TAGS: TagGrammar = TagGrammar {
+    open_kinds: &["jsx_opening_element"],
+    close_kinds: &["jsx_closing_element"],
+    name_kinds: &[],
+    has_void_elements: false,
+};
+
+fn tag_grammar(lang: LanguageId) -> Option<(tree_sitter::Language, &'static TagGrammar)> {
+    Some(match lang {
+        LanguageId::Html | LanguageId::Vue => (tree_sitter_html::LANGUAGE.into(), &HTML_TAGS),
+        LanguageId::Xml => (tree_sitter_xml::LANGUAGE_XML.into(), &XML_TAGS),
+        LanguageId::Jsx | LanguageId::JavaScript => {
+            (tree_sitter_javascript::LANGUAGE.into(), &JSX_TAGS)
+        }
+        LanguageId::Tsx => (tree_sitter_typescript::LANGUAGE_TSX.into(), &JSX_TAGS),
+        _ => return None,
+    })
+}
+
+/// Whether `lang` supports tag auto-closing and linked tag editing
+pub fn supports_tags(lang: LanguageId) -> bool {
+    matches!(
+        lang,
+        LanguageId::Html
+            | LanguageId::Vue
+            | LanguageId::Xml
+            | LanguageId::Jsx
+            | LanguageId::JavaScript
+            | LanguageId::Tsx
+    )
+}
+
+/// Whether `ch` can appear in a tag name (`div`, `my-widget`, `svg:rect`, `Foo.Bar`)
+pub fn is_tag_name_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '-' | '_' | ':' | '.')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagSide {
+    Open,
+    Close,
+}
+
+/// A tag name occurrence, as a byte range into the source
+#[derive(Debug, Clone)]
+struct TagName {
+    side: TagSide,
+    range: Range<usize>,
+}
+
+fn tag_name_node<'a>(node: Node<'a>, grammar: &TagGrammar) -> Option<Node<'a>> {
+    if let Some(name) = node.child_by_field_name("name") {
+        return Some(name);
+    }
+    let mut cursor = node.walk();
+    let found = node
+        .named_children(&mut cursor)
+        .find(|child| grammar.name_kinds.contains(&child.kind()));
+    found
+}
+
+/// Collect tag names in document order
+fn collect_tags(lang: LanguageId, source: &str) -> Vec<TagName> {
+    let Some((ts_lang, grammar)) = tag_grammar(lang) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&ts_lang).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let mut tags = Vec::new();
+    let mut cursor = tree.walk();
+    let mut visited_children = false;
+    loop {
+        if !visited_children {
+            let node = cursor.node();
+            let side = if grammar.open_kinds.contains(&node.kind()) {
+                Some(TagSide::Open)
+            } else if grammar.close_kinds.contains(&node.kind()) {
+                Some(TagSide::Close)
+            } else {
+                None
+            };
+            if let Some(side) = side {
+                if let Some(name) = tag_name_node(node, grammar) {
+                    let range = name.byte_range();
+                    let is_void = grammar.has_void_elements
+                        && side == TagSide::Open
+                        && HTML_VOID_ELEMENTS
+                            .iter()
+                            .any(|v| v.eq_ignore_ascii_case(&source[range.clone()]));
+                    if !is_void && !range.is_empty() {
+                        tags.push(TagName { side, range });
+                    }
+                }
+            }
+        }
+
+        if !visited_children && cursor.goto_first_child() {
+            continue;
+        }
+        if cursor.goto_next_sibling() {
+            visited_children = false;
+            continue;
+        }
+        if !cursor.goto_parent() {
+            break;
+        }
+        visited_children = true;
+    }
+    tags
+}
+
+/// Pair opening and closing tags with a stack.
+///
+/// Returns `(pairs, unclosed)` where `pairs` holds `(open_index, close_index)`
+/// and `unclosed` is the stack of still-open tag indices, innermost last.
+fn pair_tags(source: &str, tags: &[TagName]) -> (Vec<(usize, usize)>, Vec<usize>) {
+    let mut stack: Vec<usize> = Vec::new();
+    let mut pairs = Vec::new();
+    for (idx, tag) in tags.iter().enumerate() {
+        match tag.side {
+            TagSide::Open => stack.push(idx),
+            TagSide::Close => {
+                let name = &source[tag.range.clone()];
+                if let Some(depth) = stack
+                    .iter()
+                    .rposition(|&open| &source[tags[open].range.clone()] == name)
+                {
+                    pairs.push((stack[depth], idx));
+                    stack.truncate(depth);
+                }
+            }
+        }
+    }
+    (pairs, stack)
+}
+
+/// Name of the innermost element left open before `byte_offset`.
+///
+/// Used when the user types `</` at `byte_offset` (the offset of the `<`).
+pub fn closing_tag_name(lang: LanguageId, source: &str, byte_offset: usize) -> Option<String> {
+    let prefix = source.get(..byte_offset)?;
+    let tags = collect_tags(lang, prefix);
+    let (_, unclosed) = pair_tags(prefix, &tags);
+    unclosed
+        .last()
+        .map(|&idx| prefix[tags[idx].range.clone()].to_string())
+}
+
+/// Byte ranges of the tag name touching `byte_offset` and of its paired tag name.
+///
+/// Returns `(own, partner)`. The offset may sit anywhere inside the name,
+/// including directly after its last character.
+pub fn linked_tag_ranges(
+    lang: LanguageId,
+    source: &str,
+    byte_offset: usize,
+) -> Option<(Range<usize>, Range<usize>)> {
+    let tags = collect_tags(lang, source);
+    let (pairs, _) = pair_tags(source, &tags);
+    let touches = |idx: usize| {
+        let range = &tags[idx].range;
+        range.start <= byte_offset && byte_offset <= range.end
+    };
+    pairs.iter().find_map(|&(open, close)| {
+        if touches(open) {
+            Some((tags[open].range.clone(), tags[close].range.clone()))
+        } else if touches(close) {
+            Some((tags[close].range.clone(), tags[open].range.clone()))
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closes_innermost_open_html_element() {
+        let src = "<div><span>text<";
+        assert_eq!(
+            closing_tag_name(LanguageId::Html, src, src.len() - 1),
+            Some("span".to_string())
+        );
+    }
+
+    #[test]
+    fn skips_closed_and_void_html_elements() {
+        let src = "<section><p>a</p><br><img src=\"x\"><";
+        assert_eq!(
+            closing_tag_name(LanguageId::Html, src, src.len() - 1),
+            Some("section".to_string())
+        );
+    }
+
+    #[test]
+    fn nothing_to_close_when_all_elements_closed() {
+        let src = "<p>a</p><";
+        assert_eq!(closing_tag_name(LanguageId::Html, src, src.len() - 1), None);
+    }
+
+    #[test]
+    fn closes_jsx_element() {
+        let src = "const a = <Foo.Bar><div>x<";
+        assert_eq!(
+            closing_tag_name(LanguageId::Jsx, src, src.len() - 1),
+            Some("div".to_string())
+        );
+    }
+
+    #[test]
+    fn links_open_tag_name_to_close_tag_name() {
+        let src = "<div><span>a</span></div>";
+        let (own, partner) = linked_tag_ranges(LanguageId::Html, src, 1).unwrap();
+        assert_eq!(&src[own], "div");
+        assert_eq!(partner, 21..24);
+    }
+
+    #[test]
+    fn links_close_tag_name_back_to_open_tag_name() {
+        let src = "<div><span>a</span></div>";
+        let (own, partner) = linked_tag_ranges(LanguageId::Html, src, 16).unwrap();
+        assert_eq!(own, 14..18);
+        assert_eq!(partner, 6..10);
+    }
+
+    #[test]
+    fn no_link_outside_tag_names() {
+        let src = "<div>text</div>";
+        assert!(linked_tag_ranges(LanguageId::Html, src, 7).is_none());
+    }
+
+    #[test]
+    fn unsupported_language_has_no_tags() {
+        assert!(!supports_tags(LanguageId::Rust));
+        assert_eq!(closing_tag_name(LanguageId::Rust, "<a><", 3), None);
+    }
+}
@@ -301,6 +301,22 @@ pub struct EditorThemeData {
     pub secondary_cursor_color: Option<String>,
     #[serde(default)]
     pub bracket_match_background: Option<String>,
+    #[serde(default)]
+    pub search_match_background: Option<String>,
+    #[serde(default)]
+    pub search_match_current_background: Option<String>,
+    #[serde(default)]
+    pub whitespace_foreground: Option<String>,
+    #[serde(default)]
+    pub invisible_character_foreground: Option<String>,
+    #[serde(default)]
+    pub indent_guide_foreground: Option<String>,
+    #[serde(default)]
+    pub indent_guide_active_foreground: Option<String>,
+    #[serde(default)]
+    pub ruler_foreground: Option<String>,
+    #[serde(default)]
+    pub word_highlight_background: Option<String>,
 }
 
 /// Gutter (line numbers) colors
@@ -523,6 +539,22 @@ pub struct EditorTheme {
     pub secondary_cursor_color: Color,
     /// Background color for matching bracket highlight
     pub bracket_match_background: Color,
+    /// Background color for matches of the current search term
+    pub search_match_background: Color,
+    /// Background color for the match the find modal is on
+    pub search_match_current_background: Color,
+    /// Marks drawn for spaces, tabs and line ends when rendering whitespace
+    pub whitespace_foreground: Color,
+    /// Marks drawn for non-breaking spaces and byte order marks
+    pub invisible_character_foreground: Color,
+    /// Vertical lines at each indentation level
+    pub indent_guide_foreground: Color,
+    /// Indent guide of the block the cursor is in
+    pub indent_guide_active_foreground: Color,
+    /// Vertical rulers at the configured columns
+    pub ruler_foreground: Color,
+    /// Background of the other occurrences of the word under the cursor
+    pub word_highlight_background: Color,
 }
 
 /// Gutter colors (resolved)
@@ -975,6 +1007,70 @@ impl Theme {
                 .map(|s| Color::from_hex(s))
                 .transpose()?
                 .unwrap_or(Color::rgba(0x58, 0xA6, 0xFF, 0x40)),
+            search_match_background: data
+                .ui
+                .editor
+                .search_match_background
+                .as_ref()
+                .map(|s| Color::from_hex(s))
+                .transpose()?
+                .unwrap_or(Color::rgba(0xE5, 0xC0, 0x7B, 0x50)),
+            search_match_current_background: data
+                .ui
+                .editor
+                .search_match_current_background
+                .as_ref()
+                .map(|s| Color::from_hex(s))
+                .transpose()?
+                .unwrap_or(Color::rgba(0xE5, 0xC0, 0x7B, 0xA0)),
+            whitespace_foreground: data
+                .ui
+                .editor
+                .whitespace_foreground
+                .as_ref()
+                .map(|s| Color::from_hex(s))
+                .transpose()?
+                .unwrap_or(Color::rgba(0x80, 0x80, 0x80, 0x70)),
+            invisible_character_foreground: data
+                .ui
+                .editor
+                .invisible_character_foreground
+                .as_ref()
+                .map(|s| Color::from_hex(s))
+                .transpose()?
+                .unwrap_or(Color::rgba(0xE5, 0xC0, 0x7B, 0xC0)),
+            indent_guide_foreground: data
+                .ui
+                .editor
+                .indent_guide_foreground
+                .as_ref()
+                .map(|s| Color::from_hex(s))
+                .transpose()?
+                .unwrap_or(Color::rgba(0x80, 0x80, 0x80, 0x40)),
+            indent_guide_active_foreground: data
+                .ui
+                .editor
+                .indent_guide_active_foreground
+                .as_ref()
+                .map(|s| Color::from_hex(s))
+                .transpose()?
+                .unwrap_or(Color::rgba(0x80, 0x80, 0x80, 0xA0)),
+            ruler_foreground: data
+                .ui
+                .editor
+                .ruler_foreground
+                .as_ref()
+                .map(|s| Color::from_hex(s))
+                .transpose()?
+                .unwrap_or(Color::rgba(0x80, 0x80, 0x80, 0x50)),
+            word_highlight_background: data
+                .ui
+                .editor
+                .word_highlight_background
+                .as_ref()
+                .map(|s| Color::from_hex(s))
+                .transpose()?
+                .unwrap_or(Color::rgba(0x80, 0x80, 0x80, 0x40)),
         };
 
         let gutter = GutterTheme {
@@ -1497,6 +1593,14 @@ impl Theme {
                         selection_background: Color::rgb(0x26, 0x4F, 0x78),
                         secondary_cursor_color: Color::rgba(0xFF, 0xFF, 0xFF, 0x80),
                         bracket_match_background: Color::rgba(0x58, 0xA6, 0xFF, 0x40),
+                        search_match_background: Color::rgba(0xE5, 0xC0, 0x7B, 0x50),
+                        search_match_current_background: Color::rgba(0xE5, 0xC0, 0x7B, 0xA0),
+                        whitespace_foreground: Color::rgba(0x80, 0x80, 0x80, 0x70),
+                        invisible_character_foreground: Color::rgba(0xE5, 0xC0, 0x7B, 0xC0),
+                        indent_guide_foreground: Color::rgba(0x80, 0x80, 0x80, 0x40),
+                        indent_guide_active_foreground: Color::rgba(0x80, 0x80, 0x80, 0xA0),
+                        ruler_foreground: Color::rgba(0x80, 0x80, 0x80, 0x50),
+                        word_highlight_background: Color::rgba(0x80, 0x80, 0x80, 0x40),
                     },
                     gutter: GutterTheme {
                         background: Color::rgb(0x1E, 0x1E, 0x1E),
@@ -0,0 +1,338 @@
+//! TODO/FIXME/HACK comment scanning for the TODO list panel
+//!
+//! Scanning runs on a background thread (see `Cmd::ScanTodos`). Open
+//! documents are scanned from an in-memory snapshot so unsaved edits show
+//! up; workspace files that are not open are read from disk.
+
+use std::path::{Path, PathBuf};
+
+use crate::model::editor_area::DocumentId;
+
+/// Workspace files larger than this are skipped
+const MAX_SCAN_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Upper bound on workspace files read per scan
+const MAX_WORKSPACE_FILES: usize = 5000;
+
+/// Comment tag recognized by the scanner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TodoTag {
+    Todo,
+    Fixme,
+    Hack,
+}
+
+impl TodoTag {
+    /// All tags, in display order
+    pub const ALL: [TodoTag; 3] = [TodoTag::Todo, TodoTag::Fixme, TodoTag::Hack];
+
+    /// Tag keyword as written in source
+    pub fn label(&self) -> &'static str {
+        match self {
+            TodoTag::Todo => "TODO",
+            TodoTag::Fixme => "FIXME",
+            TodoTag::Hack => "HACK",
+        }
+    }
+}
+
+/// A single tagged comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoItem {
+    /// 0-indexed line
+    pub line: usize,
+    /// 0-indexed char column of the tag keyword
+    pub column: usize,
+    pub tag: TodoTag,
+    /// Comment text following the tag
+    pub text: String,
+}
+
+/// All tagged comments found in one file or open document
+#[derive(Debug, Clone)]
+pub struct TodoFile {
+    /// Path on disk (None for untitled documents)
+    pub path: Option<PathBuf>,
+    /// Open document the items came from, if any
+    pub document_id: Option<DocumentId>,
+    /// Label shown in the panel (workspace-relative path or untitled name)
+    pub display_name: String,
+    pub items: Vec<TodoItem>,
+}
+
+/// Snapshot of an open document to scan
+#[derive(Debug, Clone)]
+pub struct TodoScanSource {
+    pub document_id: DocumentId,
+    pub path: Option<PathBuf>,
+    pub display_name: String,
+    pub text: String,
+}
+
+/// Everything a background scan needs
+#[derive(Debug, Clone, Default)]
+pub struct TodoScanRequest {
+    /// Open documents (scanned from memory)
+    pub open_documents: Vec<TodoScanSource>,
+    /// Workspace root, used for relative display names
+    pub workspace_root: Option<PathBuf>,
+    /// Workspace files to read from disk (open documents are skipped)
+    pub workspace_files: Vec<PathBuf>,
+}
+
+/// A flattened panel row: a file header followed by its items
+#[derive(Debug, Clone, Copy)]
+pub enum TodoRow<'a> {
+    File(&'a TodoFile),
+    Item(&'a TodoFile, &'a TodoItem),
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Find the first tag keyword on a line, returning (char column, tag, rest)
+fn find_tag(line: &str) -> Option<(usize, TodoTag, &str)> {
+    let mut prev: Option<char> = None;
+    for (byte_idx, ch) in line.char_indices() {
+        if prev.is_some_and(is_word_char) || !ch.is_ascii_uppercase() {
+            prev = Some(ch);
+            continue;
+        }
+        prev = Some(ch);
+        let rest = &line[byte_idx..];
+        for tag in TodoTag::ALL {
+            let Some(after) = rest.strip_prefix(tag.label()) else {
+                continue;
+            };
+            // Must be a whole word: `TODO:`, `TODO(name)`, `TODO fix` - not `TODOS`
+            if after.chars().next().is_some_and(is_word_char) {
+                continue;
+            }
+            let column = line[..byte_idx].chars().count();
+            return Some((column, tag, after));
+        }
+    }
+    None
+}
+
+/// Clean up the text following a tag keyword
+fn item_text(after: &str) -> String {
+    let mut text = after.trim_start();
+    if text.starts_with('(') {
+        if let Some(end) = text.find(')') {
+            text = &text[end + 1..];
+        }
+    }
+    let text = text.trim_start_matches([':', '-', '!']).trim();
+    let text = text
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim_end_matches("#}")
+        .trim_end_matches("--}}")
+        .trim_end();
+    text.to_string()
+}
+
+/// Scan text for tagged comments
+pub fn scan_text(text: &str) -> Vec<TodoItem> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(line, content)| {
+            find_tag(content).map(|(column, tag, after)| TodoItem {
+                line,
+                column,
+                tag,
+                text: item_text(after),
+            })
+        })
+        .collect()
+}
+
+fn display_name_for(path: &Path, root: Option<&Path>) -> String {
+    root.and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn read_scannable_file(path: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_SCAN_FILE_BYTES {
+        return None;
+    }
+    // Non-UTF-8 (binary) files fail here and are skipped
+    std::fs::read_to_string(path).ok()
+}
+
+/// Run a full scan. Files without items are omitted; open documents come
+/// first, followed by workspace files sorted by path.
+pub fn scan(request: TodoScanRequest) -> Vec<TodoFile> {
+    let root = request.workspace_root.as_deref();
+    let mut files = Vec::new();
+    let mut open_paths = std::collections::HashSet::new();
+
+    for source in request.open_documents {
+        if let Some(path) = &source.path {
+            open_paths.insert(path.clone());
+        }
+        let items = scan_text(&source.text);
+        if items.is_empty() {
+            continue;
+        }
+        let display_name = match &source.path {
+            Some(path) => display_name_for(path, root),
+            None => source.display_name,
+        };
+        files.push(TodoFile {
+            path: source.path,
+            document_id: Some(source.document_id),
+            display_name,
+            items,
+        });
+    }
+
+    let mut workspace_files: Vec<PathBuf> = request
+        .workspace_files
+        .into_iter()
+        .filter(|path| !open_paths.contains(path))
+        .take(MAX_WORKSPACE_FILES)
+        .collect();
+    workspace_files.sort();
+
+    for path in workspace_files {
+        let Some(text) = read_scannable_file(&path) else {
+            continue;
+        };
+        let items = scan_text(&text);
+        if items.is_empty() {
+            continue;
+        }
+        files.push(TodoFile {
+            display_name: display_name_for(&path, root),
+            path: Some(path),
+            document_id: None,
+            items,
+        });
+    }
+
+    files
+}
+
+/// Number of items per tag across all files, in `TodoTag::ALL` order
+pub fn tag_counts(files: &[TodoFile]) -> [(TodoTag, usize); 3] {
+    TodoTag::ALL.map(|tag| {
+        let count = files
+            .iter()
+            .flat_map(|file| &file.items)
+            .filter(|item| item.tag == tag)
+            .count();
+        (tag, count)
+    })
+}
+
+/// Total number of panel rows (file headers plus items)
+pub fn row_count(files: &[TodoFile]) -> usize {
+    files.iter().map(|file| file.items.len() + 1).sum()
+}
+
+/// Resolve a flattened row index
+pub fn row_at(files: &[TodoFile], index: usize) -> Option<TodoRow<'_>> {
+    let mut remaining = index;
+    for file in files {
+        if remaining == 0 {
+            return Some(TodoRow::File(file));
+        }
+        remaining -= 1;
+        if remaining < file.items.len() {
+            return Some(TodoRow::Item(file, &file.items[remaining]));
+        }
+        remaining -= file.items.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_tags_with_common_punctuation() {
+        let items = scan_text(
+            "// TODO: write docs\n# FIXME(helge) broken on windows\n/* HACK - temporary */\n",
+        );
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].tag, TodoTag::Todo);
+        assert_eq!(items[0].text, "write docs");
+        assert_eq!(items[0].column, 3);
+        assert_eq!(items[1].tag, TodoTag::Fixme);
+        assert_eq!(items[1].text, "broken on windows");
+        assert_eq!(items[2].tag, TodoTag::Hack);
+        assert_eq!(items[2].text, "temporary");
+    }
+
+    #[test]
+    fn ignores_partial_and_lowercase_words() {
+        let items = scan_text("let todos = TODOS;\nlet x = MYTODO;\n// todo: lowercase\n");
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn columns_count_chars_not_bytes() {
+        let items = scan_text("// é TODO: x");
+        assert_eq!(items[0].column, 5);
+    }
+
+    #[test]
+    fn counts_and_rows_flatten_files() {
+        let files = vec![
+            TodoFile {
+                path: None,
+                document_id: None,
+                display_name: "a".into(),
+                items: scan_text("TODO a\nFIXME b"),
+            },
+            TodoFile {
+                path: None,
+                document_id: None,
+                display_name: "b".into(),
+                items: scan_text("TODO c"),
+            },
+        ];
+        assert_eq!(
+            tag_counts(&files),
+            [(TodoTag::Todo, 2), (TodoTag::Fixme, 1), (TodoTag::Hack, 0)]
+        );
+        assert_eq!(row_count(&files), 5);
+        assert!(matches!(row_at(&files, 0), Some(TodoRow::File(f)) if f.display_name == "a"));
+        assert!(matches!(row_at(&files, 2), Some(TodoRow::Item(_, i)) if i.text == "b"));
+        assert!(matches!(row_at(&files, 3), Some(TodoRow::File(f)) if f.display_name == "b"));
+        assert!(row_at(&files, 5).is_none());
+    }
+
+    #[test]
+    fn open_documents_shadow_workspace_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let on_disk = dir.path().join("a.rs");
+        let other = dir.path().join("b.rs");
+        std::fs::write(&on_disk, "// TODO: stale").unwrap();
+        std::fs::write(&other, "// FIXME: disk").unwrap();
+
+        let files = scan(TodoScanRequest {
+            open_documents: vec![TodoScanSource {
+                document_id: DocumentId(1),
+                path: Some(on_disk.clone()),
+                display_name: "a.rs".into(),
+                text: "// TODO: fresh".into(),
+            }],
+            workspace_root: Some(dir.path().to_path_buf()),
+            workspace_files: vec![on_disk, other],
+        });
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].items[0].text, "fresh");
+        assert_eq!(files[0].document_id, Some(DocumentId(1)));
+        assert_eq!(files[1].display_name, "b.rs");
+        assert_eq!(files[1].items[0].tag, TodoTag::Fixme);
+    }
+}
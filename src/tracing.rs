@@ -14,11 +14,253 @@
 //!
 //! Logs are written to `~/.config/token-editor/logs/token.log` with daily rotation.
 //! File logging uses debug level by default for more verbose troubleshooting.
+//!
+//! # Runtime Verbosity
+//!
+//! The [`LogTarget`] areas (input, render, syntax, io) can be made verbose
+//! while the editor runs, from the command palette or `verbose_logging` in
+//! the config. Their debug events then go to the log file and to the
+//! in-app log viewer, which shows the most recent events kept in memory.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::filter::FilterExt;
+use tracing_subscriber::layer::{Context, Filter};
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
 use crate::model::EditorState;
 
+/// Number of events kept for the log viewer
+const LOG_BUFFER_SIZE: usize = 1000;
+
+/// Areas whose debug logging can be switched on at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTarget {
+    /// Keyboard and mouse handling, keymap
+    Input,
+    /// Rendering and frame timing
+    Render,
+    /// Parsing and highlighting
+    Syntax,
+    /// Files, config, session, file system watcher
+    Io,
+}
+
+/// Bit per [`LogTarget`] that is currently verbose
+static VERBOSE_TARGETS: AtomicU8 = AtomicU8::new(0);
+
+impl LogTarget {
+    pub const ALL: [LogTarget; 4] = [
+        LogTarget::Input,
+        LogTarget::Render,
+        LogTarget::Syntax,
+        LogTarget::Io,
+    ];
+
+    /// Name used in the config and as an explicit `target:` in log macros
+    pub fn name(self) -> &'static str {
+        match self {
+            LogTarget::Input => "input",
+            LogTarget::Render => "render",
+            LogTarget::Syntax => "syntax",
+            LogTarget::Io => "io",
+        }
+    }
+
+    /// Module paths whose events belong to this target
+    fn modules(self) -> &'static [&'static str] {
+        match self {
+            LogTarget::Input => &[
+                "token::runtime::input",
+                "token::runtime::mouse",
+                "token::keymap",
+            ],
+            LogTarget::Render => &["token::view", "token::runtime::perf", "token::perf"],
+            LogTarget::Syntax => &["token::syntax", "token::update::syntax"],
+            LogTarget::Io => &[
+                "token::config",
+                "token::config_paths",
+                "token::fs_watcher",
+                "token::session",
+                "token::recent_files",
+                "token::update::workspace",
+            ],
+        }
+    }
+
+    /// The target an event with `target` (a module path or explicit target)
+    /// belongs to
+    pub fn of(target: &str) -> Option<LogTarget> {
+        Self::ALL.into_iter().find(|log_target| {
+            target == log_target.name()
+                || log_target.modules().iter().any(|module| {
+                    target
+                        .strip_prefix(module)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+                })
+        })
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    pub fn is_verbose(self) -> bool {
+        VERBOSE_TARGETS.load(Ordering::Relaxed) & self.bit() != 0
+    }
+
+    pub fn set_verbose(self, verbose: bool) {
+        if verbose {
+            VERBOSE_TARGETS.fetch_or(self.bit(), Ordering::Relaxed);
+        } else {
+            VERBOSE_TARGETS.fetch_and(!self.bit(), Ordering::Relaxed);
+        }
+    }
+}
+
+/// Make exactly `targets` verbose
+pub fn set_verbose_targets(targets: &[LogTarget]) {
+    for target in LogTarget::ALL {
+        target.set_verbose(targets.contains(&target));
+    }
+}
+
+/// The currently verbose targets
+pub fn verbose_targets() -> Vec<LogTarget> {
+    LogTarget::ALL
+        .into_iter()
+        .filter(|target| target.is_verbose())
+        .collect()
+}
+
+/// Passes debug (and more severe) events of the verbose [`LogTarget`]s
+struct VerboseTargets;
+
+impl<S> Filter<S> for VerboseTargets {
+    fn enabled(&self, meta: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        *meta.level() <= Level::DEBUG
+            && LogTarget::of(meta.target()).is_some_and(LogTarget::is_verbose)
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        // Asked again for every event, so toggling takes effect immediately
+        if LogTarget::of(meta.target()).is_some() {
+            Interest::sometimes()
+        } else {
+            Interest::never()
+        }
+    }
+}
+
+/// An event kept for the log viewer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Time since logging started
+    pub elapsed: Duration,
+    pub level: Level,
+    pub target: String,
+    /// The message followed by any other fields as `name=value`
+    pub message: String,
+}
+
+impl LogEntry {
+    /// One line for display: `   12.345 WARN  token::config: message`
+    pub fn display_line(&self) -> String {
+        format!(
+            "{:>9.3} {:<5} {}: {}",
+            self.elapsed.as_secs_f64(),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+struct LogBuffer {
+    entries: VecDeque<LogEntry>,
+    /// Bumped for every event, so readers can tell when to refresh
+    generation: u64,
+}
+
+static LOG_BUFFER: Mutex<LogBuffer> = Mutex::new(LogBuffer {
+    entries: VecDeque::new(),
+    generation: 0,
+});
+
+static LOG_START: OnceLock<Instant> = OnceLock::new();
+
+/// Changes whenever an event is added to the log viewer's buffer
+pub fn log_generation() -> u64 {
+    LOG_BUFFER
+        .lock()
+        .map(|buffer| buffer.generation)
+        .unwrap_or(0)
+}
+
+/// The events kept for the log viewer, oldest first
+pub fn log_entries() -> Vec<LogEntry> {
+    LOG_BUFFER
+        .lock()
+        .map(|buffer| buffer.entries.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Keeps the most recent events in [`LOG_BUFFER`]
+struct LogBufferLayer;
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let entry = LogEntry {
+            elapsed: LOG_START.get_or_init(Instant::now).elapsed(),
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            message: visitor.message,
+        };
+        if let Ok(mut buffer) = LOG_BUFFER.lock() {
+            if buffer.entries.len() >= LOG_BUFFER_SIZE {
+                buffer.entries.pop_front();
+            }
+            buffer.entries.push_back(entry);
+            buffer.generation += 1;
+        }
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        use std::fmt::Write;
+
+        if field.name() == "message" {
+            let fields = std::mem::take(&mut self.message);
+            let _ = write!(self.message, "{:?}", value);
+            if !fields.is_empty() {
+                self.message.push(' ');
+                self.message.push_str(&fields);
+            }
+        } else {
+            if !self.message.is_empty() {
+                self.message.push(' ');
+            }
+            let _ = write!(self.message, "{}={:?}", field.name(), value);
+        }
+    }
+}
+
 /// Guard that must be held alive for the duration of profiling.
 /// When dropped, flushes and closes any active trace output.
 pub struct TraceGuard {
@@ -37,6 +279,7 @@ pub struct TraceGuard {
 ///
 /// Returns a guard that must be held alive for trace output to flush on exit.
 pub fn init() -> TraceGuard {
+    LOG_START.get_or_init(Instant::now);
     let console_filter = default_filter_from_env("RUST_LOG");
 
     // Console layer - respects RUST_LOG
@@ -57,7 +300,7 @@ pub fn init() -> TraceGuard {
                     .with_ansi(false)
                     .with_target(true)
                     .with_line_number(true)
-                    .with_filter(file_filter),
+                    .with_filter(file_filter.or(VerboseTargets)),
             )
         }
         Err(e) => {
@@ -66,6 +309,10 @@ pub fn init() -> TraceGuard {
         }
     };
 
+    // Log viewer - info and up, plus the verbose targets
+    let buffer_layer =
+        LogBufferLayer.with_filter(EnvFilter::new("warn,token=info").or(VerboseTargets));
+
     #[cfg(feature = "profile-chrome")]
     let (chrome_layer, chrome_guard) = {
         let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
@@ -80,6 +327,7 @@ pub fn init() -> TraceGuard {
         tracing_subscriber::registry()
             .with(console_layer)
             .with(file_layer)
+            .with(buffer_layer)
             .with(chrome_layer)
             .init();
     }
@@ -88,6 +336,7 @@ pub fn init() -> TraceGuard {
         tracing_subscriber::registry()
             .with(console_layer)
             .with(file_layer)
+            .with(buffer_layer)
             .init();
     }
 
@@ -179,3 +428,39 @@ impl CursorSnapshot {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_cover_their_modules() {
+        assert_eq!(LogTarget::of("syntax"), Some(LogTarget::Syntax));
+        assert_eq!(
+            LogTarget::of("token::syntax::parser"),
+            Some(LogTarget::Syntax)
+        );
+        assert_eq!(
+            LogTarget::of("token::runtime::input"),
+            Some(LogTarget::Input)
+        );
+        assert_eq!(LogTarget::of("token::config"), Some(LogTarget::Io));
+        assert_eq!(LogTarget::of("token::config_paths"), Some(LogTarget::Io));
+        assert_eq!(LogTarget::of("token::configurable"), None);
+        assert_eq!(LogTarget::of("token::update::app"), None);
+    }
+
+    #[test]
+    fn message_visitor_puts_message_first() {
+        let subscriber = tracing_subscriber::registry().with(LogBufferLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "log_viewer_test", path = "a.txt", "Failed to open");
+        });
+        let entry = log_entries()
+            .into_iter()
+            .rfind(|entry| entry.target == "log_viewer_test")
+            .unwrap();
+        assert_eq!(entry.level, Level::WARN);
+        assert_eq!(entry.message, "Failed to open path=\"a.txt\"");
+    }
+}
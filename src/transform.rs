@@ -0,0 +1,139 @@
+//! Text transforms: small string-to-string conversions shared by the
+//! commands that rewrite or copy text
+//!
+//! Each transform is a [`TextTransform`] variant with a label and an
+//! [`apply`](TextTransform::apply), listed in [`TextTransform::ALL`]; the
+//! "Copy as…" palette commands each run one. Transforms take whole text and
+//! return whole text, so they compose by chaining `apply` calls.
+
+/// A text transform in the registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextTransform {
+    /// A JSON string literal, quotes included
+    JsonString,
+    /// A Rust raw string literal with as many `#`s as the text needs
+    RustRawString,
+    /// `&`, `<`, `>`, `"` and `'` escaped as HTML entities
+    HtmlEscape,
+    /// Each line prefixed with its right-aligned line number
+    LineNumbers,
+}
+
+impl TextTransform {
+    /// Every transform, in menu order
+    pub const ALL: [TextTransform; 4] = [
+        TextTransform::JsonString,
+        TextTransform::RustRawString,
+        TextTransform::HtmlEscape,
+        TextTransform::LineNumbers,
+    ];
+
+    /// Display name, e.g. for "Copy as JSON String"
+    pub fn label(self) -> &'static str {
+        match self {
+            TextTransform::JsonString => "JSON String",
+            TextTransform::RustRawString => "Rust Raw String",
+            TextTransform::HtmlEscape => "HTML-Escaped Text",
+            TextTransform::LineNumbers => "Text with Line Numbers",
+        }
+    }
+
+    /// Transform `text`. `first_line` is the 1-based number of its first
+    /// line, used by transforms that number lines.
+    pub fn apply(self, text: &str, first_line: usize) -> String {
+        match self {
+            TextTransform::JsonString => json_string(text),
+            TextTransform::RustRawString => rust_raw_string(text),
+            TextTransform::HtmlEscape => html_escape(text),
+            TextTransform::LineNumbers => with_line_numbers(text, first_line),
+        }
+    }
+}
+
+fn json_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+fn rust_raw_string(text: &str) -> String {
+    // One more `#` than the longest run following a quote in the text
+    let mut hashes = if text.contains('"') { 1 } else { 0 };
+    let mut rest = text;
+    while let Some(quote) = rest.find('"') {
+        rest = &rest[quote + 1..];
+        let run = rest.chars().take_while(|&c| c == '#').count();
+        hashes = hashes.max(run + 1);
+    }
+    let fence = "#".repeat(hashes);
+    format!("r{fence}\"{text}\"{fence}")
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn with_line_numbers(text: &str, first_line: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let last_line = first_line + lines.len().saturating_sub(1);
+    let width = last_line.to_string().len();
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let numbered = format!("{:>width$} | {}", first_line + i, line);
+            numbered.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_literals_escape_what_they_must() {
+        let text = "say \"hi\"\n\tand \\ bye";
+        assert_eq!(
+            TextTransform::JsonString.apply(text, 1),
+            r#""say \"hi\"\n\tand \\ bye""#
+        );
+        assert_eq!(TextTransform::RustRawString.apply("plain", 1), "r\"plain\"");
+        assert_eq!(
+            TextTransform::RustRawString.apply(text, 1),
+            "r#\"say \"hi\"\n\tand \\ bye\"#"
+        );
+        assert_eq!(
+            TextTransform::RustRawString.apply("a \"# b", 1),
+            "r##\"a \"# b\"##"
+        );
+        assert_eq!(
+            TextTransform::HtmlEscape.apply("<a href=\"x\">Tom & Jerry's</a>", 1),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn line_numbers_start_at_first_line_and_align() {
+        assert_eq!(
+            TextTransform::LineNumbers.apply("a\n\nb\n", 9),
+            " 9 | a\n10 |\n11 | b"
+        );
+        // Transforms chain
+        let numbered = TextTransform::LineNumbers.apply("<b>", 1);
+        assert_eq!(
+            TextTransform::HtmlEscape.apply(&numbered, 1),
+            "1 | &lt;b&gt;"
+        );
+    }
+}
@@ -0,0 +1,350 @@
+//! AI chat panel update handlers
+
+use crate::ai::{answer_insert_text, explain_selection_prompt, ChatMessage, ChatRole};
+use crate::commands::Cmd;
+use crate::edit_review::EditReviewSource;
+use crate::editable::TextEditMsg;
+use crate::messages::{AiChatMsg, DockMsg};
+use crate::model::AppModel;
+use crate::panel::{DockPosition, PanelId};
+use crate::view::geometry::{AiChatPanelLayout, DockHeaderLayout, WindowLayout};
+
+use super::dock::update_dock;
+use super::edit_review::open_edit_review;
+use super::text_edit::apply_text_edit_msg;
+
+/// Append `prompt` as a user message and start streaming the answer
+fn send_prompt(model: &mut AppModel, prompt: String) -> Option<Cmd> {
+    if prompt.trim().is_empty() {
+        return None;
+    }
+
+    let chat = &mut model.ai_chat;
+    chat.messages.push(ChatMessage::new(ChatRole::User, prompt));
+    let messages = chat.request_messages(&model.config.ai.system_prompt);
+    // Placeholder the streamed deltas are appended to
+    chat.messages
+        .push(ChatMessage::new(ChatRole::Assistant, ""));
+    let (request_id, cancel) = chat.begin_request();
+
+    Some(Cmd::Batch(vec![
+        Cmd::AiChatRequest {
+            request_id,
+            config: model.config.ai.clone(),
+            messages,
+            cancel,
+        },
+        Cmd::Redraw,
+    ]))
+}
+
+/// Text of the editor's primary selection, if non-empty
+fn primary_selection_text(model: &AppModel) -> Option<String> {
+    let text = model
+        .editor()
+        .primary_selection()
+        .get_text(model.document());
+    (!text.is_empty()).then_some(text)
+}
+
+/// The last answer, reduced to the text that should go into the buffer
+fn answer_for_buffer(model: &mut AppModel) -> Option<String> {
+    let Some(answer) = model.ai_chat.last_answer() else {
+        model.ui.set_status("No AI answer to insert yet");
+        return None;
+    };
+    Some(answer_insert_text(answer).to_string())
+}
+
+/// Number of transcript rows hidden above the panel when scrolled to the top
+fn max_scroll_offset(model: &AppModel) -> usize {
+    let Some(position) = model.dock_layout.find_panel(PanelId::AI_CHAT) else {
+        return 0;
+    };
+    let window_layout = WindowLayout::compute(model, model.line_height);
+    let dock_rect = match position {
+        DockPosition::Right => window_layout.right_dock_rect,
+        DockPosition::Bottom => window_layout.bottom_dock_rect,
+        DockPosition::Left => None,
+    };
+    let Some(dock_rect) = dock_rect else {
+        return 0;
+    };
+    let dock_layout = DockHeaderLayout::new(
+        model.dock_layout.dock(position),
+        dock_rect,
+        &model.metrics,
+        model.char_width,
+    );
+    let layout = AiChatPanelLayout::new(dock_layout.content_rect, &model.metrics);
+    let rows = model
+        .ai_chat
+        .display_rows(layout.max_cols(model.char_width))
+        .len();
+    rows.saturating_sub(layout.visible_rows())
+}
+
+/// Handle AI chat panel messages
+pub fn update_ai_chat(model: &mut AppModel, msg: AiChatMsg) -> Option<Cmd> {
+    match msg {
+        AiChatMsg::Input(edit) => {
+            let chat = &mut model.ai_chat;
+            let copied = match edit {
+                TextEditMsg::Copy | TextEditMsg::Cut => Some(chat.input.selected_text()),
+                _ => None,
+            }
+            .filter(|text| !text.is_empty());
+
+            let edit = match edit {
+                // Single-line input: flatten pasted newlines into spaces
+                TextEditMsg::Paste(text) | TextEditMsg::InsertText(text) => {
+                    TextEditMsg::InsertText(text.replace("\r\n", " ").replace(['\n', '\r'], " "))
+                }
+                other => other,
+            };
+            let modified = apply_text_edit_msg(&mut chat.input, &edit);
+
+            match copied {
+                Some(text) => Some(Cmd::Batch(vec![Cmd::Redraw, Cmd::CopyToClipboard(text)])),
+                None if modified => Some(Cmd::Redraw),
+                None => None,
+            }
+        }
+
+        AiChatMsg::Submit => {
+            let prompt = model.ai_chat.input.text();
+            if prompt.trim().is_empty() {
+                return None;
+            }
+            model.ai_chat.input.set_content("");
+            send_prompt(model, prompt)
+        }
+
+        AiChatMsg::Cancel => {
+            if !model.ai_chat.is_streaming {
+                return None;
+            }
+            model.ai_chat.cancel_request();
+            if model
+                .ai_chat
+                .messages
+                .last()
+                .is_some_and(|msg| msg.role == ChatRole::Assistant && msg.content.is_empty())
+            {
+                model.ai_chat.messages.pop();
+            }
+            Some(Cmd::Redraw)
+        }
+
+        AiChatMsg::Clear => {
+            model.ai_chat.cancel_request();
+            model.ai_chat.messages.clear();
+            model.ai_chat.error = None;
+            model.ai_chat.scroll_offset = 0;
+            Some(Cmd::Redraw)
+        }
+
+        AiChatMsg::StreamDelta { request_id, text } => {
+            let chat = &mut model.ai_chat;
+            if request_id != chat.request_id || !chat.is_streaming {
+                return None;
+            }
+            match chat.messages.last_mut() {
+                Some(last) if last.role == ChatRole::Assistant => last.content.push_str(&text),
+                _ => chat
+                    .messages
+                    .push(ChatMessage::new(ChatRole::Assistant, text)),
+            }
+            Some(Cmd::Redraw)
+        }
+
+        AiChatMsg::StreamFinished { request_id, error } => {
+            let chat = &mut model.ai_chat;
+            if request_id != chat.request_id {
+                return None;
+            }
+            chat.cancel_request();
+            if chat
+                .messages
+                .last()
+                .is_some_and(|msg| msg.role == ChatRole::Assistant && msg.content.is_empty())
+            {
+                chat.messages.pop();
+            }
+            if let Some(error) = error {
+                model.ui.set_status(format!("AI request failed: {}", error));
+                model.ai_chat.error = Some(error);
+            }
+            Some(Cmd::Redraw)
+        }
+
+        AiChatMsg::ExplainSelection => {
+            let Some(code) = primary_selection_text(model) else {
+                model.ui.set_status("Select some code to explain");
+                return Some(Cmd::Redraw);
+            };
+            let doc = model.document();
+            let prompt =
+                explain_selection_prompt(&code, doc.language.display_name(), &doc.display_name());
+
+            let dock_cmd = update_dock(model, DockMsg::ActivatePanel(PanelId::AI_CHAT));
+            let send_cmd = send_prompt(model, prompt);
+            Some(Cmd::Batch(dock_cmd.into_iter().chain(send_cmd).collect()))
+        }
+
+        AiChatMsg::InsertAnswerAtCursor => {
+            let text = answer_for_buffer(model)?;
+            let cursor = *model.editor().primary_cursor();
+            let doc = model.document();
+            let offset = doc.cursor_to_offset(cursor.line, cursor.column);
+            let mut proposed = doc.buffer.clone();
+            proposed.insert(offset, &text);
+            model.ui.focus_editor();
+            open_edit_review(model, EditReviewSource::AiAnswer, &proposed.to_string())
+        }
+
+        AiChatMsg::ReplaceSelectionWithAnswer => {
+            let selection = *model.editor().primary_selection();
+            if selection.is_empty() {
+                model.ui.set_status("No selection to replace");
+                return Some(Cmd::Redraw);
+            }
+            let text = answer_for_buffer(model)?;
+            let doc = model.document();
+            let (start, end) = (selection.start(), selection.end());
+            let start = doc.cursor_to_offset(start.line, start.column);
+            let end = doc.cursor_to_offset(end.line, end.column);
+            let mut proposed = doc.buffer.clone();
+            proposed.remove(start..end);
+            proposed.insert(start, &text);
+            model.ui.focus_editor();
+            open_edit_review(model, EditReviewSource::AiAnswer, &proposed.to_string())
+        }
+
+        AiChatMsg::Scroll { lines } => {
+            let max_offset = max_scroll_offset(model) as i64;
+            let offset = model.ai_chat.scroll_offset as i64 + lines as i64;
+            model.ai_chat.scroll_offset = offset.clamp(0, max_offset) as usize;
+            Some(Cmd::Redraw)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{DocumentMsg, ModalMsg, UiMsg};
+    use crate::model::editor::{Position, Selection};
+    use crate::update::{update_document, update_ui};
+
+    fn test_model(text: &str) -> AppModel {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.document_mut().buffer = ropey::Rope::from(text);
+        model
+    }
+
+    fn answer(model: &mut AppModel, text: &str) {
+        model
+            .ai_chat
+            .messages
+            .push(ChatMessage::new(ChatRole::Assistant, text));
+    }
+
+    #[test]
+    fn submit_sends_conversation_and_streams_into_placeholder() {
+        let mut model = test_model("");
+        for ch in "hi".chars() {
+            update_ai_chat(&mut model, AiChatMsg::Input(TextEditMsg::InsertChar(ch)));
+        }
+
+        let Some(Cmd::Batch(cmds)) = update_ai_chat(&mut model, AiChatMsg::Submit) else {
+            panic!("expected batched request");
+        };
+        let Some(Cmd::AiChatRequest {
+            request_id,
+            messages,
+            ..
+        }) = cmds.into_iter().next()
+        else {
+            panic!("expected AiChatRequest");
+        };
+        assert_eq!(
+            messages.last(),
+            Some(&ChatMessage::new(ChatRole::User, "hi"))
+        );
+        assert_eq!(messages[0].role, ChatRole::System);
+        assert!(model.ai_chat.input.text().is_empty());
+
+        update_ai_chat(
+            &mut model,
+            AiChatMsg::StreamDelta {
+                request_id,
+                text: "Hel".into(),
+            },
+        );
+        update_ai_chat(
+            &mut model,
+            AiChatMsg::StreamDelta {
+                request_id,
+                text: "lo".into(),
+            },
+        );
+        update_ai_chat(
+            &mut model,
+            AiChatMsg::StreamFinished {
+                request_id,
+                error: None,
+            },
+        );
+
+        assert_eq!(model.ai_chat.last_answer(), Some("Hello"));
+        assert!(!model.ai_chat.is_streaming);
+    }
+
+    #[test]
+    fn deltas_from_cancelled_request_are_ignored() {
+        let mut model = test_model("");
+        send_prompt(&mut model, "q".into());
+        let request_id = model.ai_chat.request_id;
+        update_ai_chat(&mut model, AiChatMsg::Cancel);
+
+        let cmd = update_ai_chat(
+            &mut model,
+            AiChatMsg::StreamDelta {
+                request_id,
+                text: "late".into(),
+            },
+        );
+        assert!(cmd.is_none());
+        assert_eq!(model.ai_chat.last_answer(), None);
+    }
+
+    #[test]
+    fn replace_selection_is_reviewed_then_a_single_undoable_edit() {
+        let mut model = test_model("let x = old();\n");
+        answer(&mut model, "Try:\n```rust\nnew()\n```");
+        model.editor_mut().selections[0] =
+            Selection::from_positions(Position::new(0, 8), Position::new(0, 13));
+        model.editor_mut().cursors[0].column = 13;
+
+        update_ai_chat(&mut model, AiChatMsg::ReplaceSelectionWithAnswer);
+        assert_eq!(model.document().buffer.to_string(), "let x = old();\n");
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::Confirm));
+        assert_eq!(model.document().buffer.to_string(), "let x = new();\n");
+
+        update_document(&mut model, DocumentMsg::Undo);
+        assert_eq!(model.document().buffer.to_string(), "let x = old();\n");
+    }
+
+    #[test]
+    fn insert_answer_at_cursor_keeps_selection_text() {
+        let mut model = test_model("ab");
+        answer(&mut model, "X");
+        model.editor_mut().cursors[0].column = 1;
+        model.editor_mut().clear_selection();
+
+        update_ai_chat(&mut model, AiChatMsg::InsertAnswerAtCursor);
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::Confirm));
+        assert_eq!(model.document().buffer.to_string(), "aXb");
+    }
+}
@@ -1,18 +1,41 @@
 //! App message handlers (file operations, window events)
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use crate::calc;
 use crate::commands::{Cmd, CommandId};
 use crate::config::EditorConfig;
 use crate::config_paths;
+use crate::git::{Host, RemoteRepo, Repository};
 use crate::keymap::get_default_keymap_yaml;
-use crate::messages::{AppMsg, DockMsg, DocumentMsg, LayoutMsg, TerminalMsg, UiMsg};
-use crate::model::{AppModel, ModalId, SplitDirection};
+use crate::messages::{
+    AiChatMsg, AppMsg, CompletionMsg, DiffMsg, DockMsg, DocumentMsg, EditorMsg, GitMsg, LayoutMsg,
+    LspMsg, NotesMsg, SearchMsg, TerminalMsg, UiMsg, WelcomeMsg, WorkspaceMsg,
+};
+use crate::model::editor_area::DocumentId;
+use crate::model::status_bar::TransientMessage;
+use crate::model::{
+    AppModel, Cursor, KeymapDiagnosticsState, ModalId, Position, Selection, SplitDirection,
+};
 use crate::panel::{DockPosition, PanelId};
+use crate::replay::content_hash;
 use crate::syntax::LanguageId;
 use crate::theme::{load_theme, Theme};
+use crate::tracing::LogTarget;
+use crate::transform::TextTransform;
+use crate::update_check;
+use crate::workspace_trust::TrustDecision;
 
-use super::{update_document, update_layout, update_ui, SYNTAX_DEBOUNCE_MS};
+use super::crash_recovery;
+use super::file_moves::prompt_deleted_file;
+use super::unsaved_changes::close_after_save;
+use super::workspace_trust;
+use super::{
+    schedule_syntax_parse, update_completion, update_diff, update_document, update_editor,
+    update_layout, update_lsp, update_notes, update_ui, update_welcome, update_workspace,
+    SYNTAX_DEBOUNCE_MS,
+};
 
 /// Handle app messages (file operations, window events)
 pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
@@ -24,7 +47,7 @@ pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
             if let Some(editor) = model.editor_area.focused_editor_mut() {
                 if let Some(csv) = editor.view_mode.as_csv_mut() {
                     let line_height = model.line_height.max(1);
-                    let tab_bar_height = model.metrics.tab_bar_height;
+                    let tab_bar_height = model.metrics.header_height();
                     let status_bar_height = line_height;
                     let col_header_height = line_height;
                     let content_height = (height as usize)
@@ -49,18 +72,23 @@ pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
         }
 
         AppMsg::SaveFile => {
+            if let Some(cmd) = super::large_file::block_while_loading(model) {
+                return Some(cmd);
+            }
+            if let Some(cmd) = prompt_deleted_file(model) {
+                return Some(cmd);
+            }
             let file_path = model.document().file_path.clone();
             match file_path {
                 Some(path) => {
                     let content = model.document().buffer.to_string();
+                    model.document_mut().disk_hash = Some(content_hash(&content));
                     model.ui.is_saving = true;
                     model.ui.set_status("Saving...");
                     Some(Cmd::SaveFile { path, content })
                 }
-                None => {
-                    model.ui.set_status("No file path - cannot save");
-                    Some(Cmd::redraw_status_bar())
-                }
+                // Untitled documents are saved through Save As
+                None => update_app(model, AppMsg::SaveFileAs),
             }
         }
 
@@ -71,18 +99,20 @@ pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
         }
 
         AppMsg::NewFile => {
-            // TODO: Implement new file
-            model.ui.set_status("New file not yet implemented");
-            Some(Cmd::redraw_status_bar())
+            let cmd = update_layout(model, LayoutMsg::NewTab);
+            model.ui.focus_editor();
+            cmd
         }
 
         AppMsg::SaveCompleted(result) => {
             model.ui.is_saving = false;
+            let saved = result.is_ok();
             match result {
                 Ok(_) => {
                     let doc = model.document_mut();
                     doc.is_modified = false;
                     doc.saved_revision = Some(doc.undo_stack.len());
+                    doc.forget_git_head();
                     if let Some(path) = &model.document().file_path {
                         model.ui.set_status(format!("Saved: {}", path.display()));
                     }
@@ -91,9 +121,49 @@ pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
                     model.ui.set_status(format!("Error: {}", e));
                 }
             }
-            Some(Cmd::redraw_status_bar())
+            if let Some(close_cmd) = close_after_save(model) {
+                return Some(close_cmd);
+            }
+            let mut cmds = vec![Cmd::redraw_status_bar()];
+            if super::todo::is_todo_panel_visible(model) {
+                cmds.push(super::todo::refresh_todos(model));
+            }
+            if saved {
+                let document_id = model.editor_area.focused_document_id();
+                cmds.extend(document_id.and_then(|id| super::lsp::did_save(model, id)));
+            }
+            Some(if cmds.len() == 1 {
+                cmds.remove(0)
+            } else {
+                Cmd::Batch(cmds)
+            })
+        }
+
+        AppMsg::ExternalToolFinished { name, result } => {
+            super::external_tools::tool_finished(model, name, result)
         }
 
+        AppMsg::SaveAllFiles => super::autosave::save_all(model),
+
+        AppMsg::AutosaveInterval => super::autosave::on_interval(model),
+
+        AppMsg::AutosaveCompleted {
+            document_id,
+            saved_revision,
+            result,
+        } => super::autosave::completed(model, document_id, saved_revision, result),
+
+        AppMsg::LargeFileProgress {
+            document_id,
+            loaded,
+            total,
+        } => super::large_file::progress(model, document_id, loaded, total),
+
+        AppMsg::LargeFileLoaded {
+            document_id,
+            result,
+        } => super::large_file::loaded(model, document_id, result),
+
         AppMsg::KeymapCreated { path, result } => match result {
             Ok(_) => Some(Cmd::OpenFileInEditor { path }),
             Err(e) => {
@@ -104,21 +174,49 @@ pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
             }
         },
 
+        AppMsg::KeymapReloaded {
+            diagnostics,
+            applied,
+        } => {
+            let status = match (applied, diagnostics.len()) {
+                (false, _) => "Keymap not reloaded: run \"Keymap: Show Diagnostics\"".to_string(),
+                (true, 0) => "Keymap reloaded".to_string(),
+                (true, 1) => "Keymap reloaded with 1 conflict".to_string(),
+                (true, n) => format!("Keymap reloaded with {} conflicts", n),
+            };
+            model.ui.keymap_diagnostics = diagnostics;
+            model.ui.set_status(status);
+            Some(Cmd::redraw_status_bar())
+        }
+
         AppMsg::FileLoaded { path, result } => {
             model.ui.is_loading = false;
+
+            // Already open in another tab: show that document (and any unsaved
+            // edits in it) rather than loading a second copy over this one
+            if let Some(doc_id) = model.editor_area.find_document_by_path(&path) {
+                if model.editor_area.focused_document_id() != Some(doc_id) {
+                    return update_layout(model, LayoutMsg::OpenFileInNewTab(path));
+                }
+            }
+
             match result {
                 Ok(content) => {
                     // Detect language from file extension
                     let language = LanguageId::from_path(&path);
 
                     let doc = model.document_mut();
+                    doc.disk_hash = Some(content_hash(&content));
                     doc.buffer = ropey::Rope::from(content);
                     doc.file_path = Some(path.clone());
+                    doc.forget_git_head();
+                    doc.git_diff = None;
                     doc.is_modified = false;
                     doc.undo_stack.clear();
                     doc.redo_stack.clear();
                     doc.saved_revision = Some(0);
                     doc.language = language;
+                    doc.language_overridden = false;
                     doc.syntax_highlights = None;
                     doc.revision = doc.revision.wrapping_add(1);
 
@@ -167,15 +265,62 @@ pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
             }
         }
 
+        AppMsg::FileChangedOnDisk {
+            document_id,
+            content,
+        } => super::file_changes::file_changed_on_disk(model, document_id, content),
+
         AppMsg::Quit => Some(Cmd::Quit),
 
+        AppMsg::RunCommand(id) => execute_command(model, id),
+
+        AppMsg::CrashReportFound { report, session } => {
+            crash_recovery::offer_restore(model, &report, session)
+        }
+
+        AppMsg::UpdateCheckCompleted { manual, result } => match result {
+            Ok(release) if update_check::is_newer(&release.version, env!("CARGO_PKG_VERSION")) => {
+                model.ui.set_status(format!(
+                    "Token {} is available - run \"View Release Notes\"",
+                    release.version
+                ));
+                model.ui.available_update = Some(release);
+                Some(Cmd::redraw_status_bar())
+            }
+            Ok(_) if manual => {
+                model.ui.transient_message = Some(TransientMessage::new(
+                    format!("Token {} is up to date", env!("CARGO_PKG_VERSION")),
+                    Duration::from_secs(3),
+                ));
+                Some(Cmd::redraw_status_bar())
+            }
+            Err(e) if manual => {
+                model.ui.set_status(format!("Update check failed: {}", e));
+                Some(Cmd::redraw_status_bar())
+            }
+            Ok(_) => None,
+            Err(e) => {
+                tracing::debug!("Update check failed: {}", e);
+                None
+            }
+        },
+
         AppMsg::ReloadConfiguration => {
             use crate::config::ReloadResult;
 
             let (new_config, result) = EditorConfig::reload();
             let new_theme = load_theme(&new_config.theme).unwrap_or_else(|_| Theme::default());
+            crate::tracing::set_verbose_targets(&new_config.verbose_logging);
+            let fonts_changed = new_config.font_size != model.config.font_size
+                || new_config.font_family != model.config.font_family
+                || new_config.font_fallbacks != model.config.font_fallbacks;
             model.config = new_config;
             model.theme = new_theme;
+            model.sync_breadcrumb_bar_height();
+            model.recalculate_viewports();
+            if let Some(workspace) = &mut model.workspace {
+                workspace.config = crate::config::WorkspaceConfig::load(&workspace.root);
+            }
 
             let msg = match result {
                 ReloadResult::Loaded => "Configuration reloaded",
@@ -194,27 +339,75 @@ pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
             // A theme/config reload can change colors across the whole
             // window, not just the status bar, so it needs a full redraw to
             // actually appear before the next unrelated event triggers one.
-            Some(Cmd::Redraw)
+            let mut cmds = vec![Cmd::Redraw, Cmd::ReloadKeymap];
+            if fonts_changed {
+                cmds.push(Cmd::ReinitializeRenderer);
+            }
+            Some(Cmd::Batch(cmds))
         }
 
         // =====================================================================
         // File Dialog Messages
         // =====================================================================
         AppMsg::SaveFileAs => {
-            let suggested = model.document().file_path.clone();
+            if let Some(cmd) = super::large_file::block_while_loading(model) {
+                return Some(cmd);
+            }
+            let doc = model.document();
+            // Untitled documents suggest their tab name in the workspace root
+            let suggested = doc.file_path.clone().or_else(|| {
+                let name = doc.untitled_name.clone()?;
+                Some(model.workspace_root()?.join(name))
+            });
             Some(Cmd::ShowSaveFileDialog {
+                document_id: doc.id,
                 suggested_path: suggested,
             })
         }
 
-        AppMsg::SaveFileAsDialogResult { path } => {
+        AppMsg::SaveFileAsDialogResult { document_id, path } => {
+            // The dialog doesn't block the editor: save the document it was
+            // opened for, even if another tab was focused meanwhile
+            let refocused = document_id.is_none_or(|id| focus_document(model, id));
+            if path.is_some() && !refocused {
+                model.ui.close_after_save = None;
+                model
+                    .ui
+                    .set_status("Save cancelled: the document was closed");
+                return Some(Cmd::redraw_status_bar());
+            }
             if let Some(path) = path {
-                model.document_mut().file_path = Some(path.clone());
+                let doc = model.document_mut();
+                doc.file_path = Some(path.clone());
+                doc.untitled_name = None;
+                doc.forget_git_head();
+                doc.deleted_on_disk = false;
+                // Infer the language on first save unless it was set manually
+                let parse_cmd = if doc.language == LanguageId::PlainText && !doc.language_overridden
+                {
+                    doc.language = LanguageId::from_path(&path);
+                    doc.id
+                        .and_then(|doc_id| schedule_syntax_parse(model, doc_id))
+                } else {
+                    None
+                };
+                model.record_file_opened(path.clone());
+
                 let content = model.document().buffer.to_string();
+                model.document_mut().disk_hash = Some(content_hash(&content));
                 model.ui.is_saving = true;
                 model.ui.set_status("Saving...");
-                Some(Cmd::SaveFile { path, content })
+                let mut cmds = vec![
+                    Cmd::SaveFile { path, content },
+                    Cmd::SaveRecentFiles {
+                        recent: model.recent_files.clone(),
+                    },
+                    Cmd::Redraw,
+                ];
+                cmds.extend(parse_cmd);
+                Some(Cmd::Batch(cmds))
             } else {
+                model.ui.close_after_save = None;
                 model.ui.set_status("Save cancelled");
                 Some(Cmd::redraw_status_bar())
             }
@@ -273,6 +466,13 @@ pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
             Some(Cmd::redraw_status_bar())
         }
 
+        AppMsg::PasteImageFromClipboard(image) => super::paste_image::paste_image(model, image),
+
+        AppMsg::PastedImageSaved {
+            document_id,
+            result,
+        } => super::paste_image::pasted_image_saved(model, document_id, result),
+
         AppMsg::PasteFromClipboard(text) => {
             if model.ui.active_modal.is_some() {
                 return super::ui::update_ui(
@@ -293,6 +493,32 @@ pub fn update_app(model: &mut AppModel, msg: AppMsg) -> Option<Cmd> {
                 return super::terminal::update_terminal(model, TerminalMsg::Paste(text));
             }
 
+            if is_ai_chat_dock_focused(model) {
+                return super::ai_chat::update_ai_chat(
+                    model,
+                    AiChatMsg::Input(crate::editable::TextEditMsg::Paste(text)),
+                );
+            }
+
+            if is_search_dock_focused(model) {
+                return super::project_search::update_project_search(
+                    model,
+                    SearchMsg::Input(crate::editable::TextEditMsg::Paste(text)),
+                );
+            }
+
+            let naming_in_sidebar = model.ui.focus == crate::model::FocusTarget::Sidebar
+                && model
+                    .workspace
+                    .as_ref()
+                    .is_some_and(|workspace| workspace.name_edit.is_some());
+            if naming_in_sidebar {
+                return update_workspace(
+                    model,
+                    WorkspaceMsg::NameInput(crate::editable::TextEditMsg::Paste(text)),
+                );
+            }
+
             super::document::update_document(model, crate::messages::DocumentMsg::PasteText(text))
         }
     }
@@ -307,39 +533,130 @@ fn is_terminal_dock_focused(model: &AppModel) -> bool {
     bottom_dock.is_open && bottom_dock.active_panel() == Some(PanelId::TERMINAL)
 }
 
+fn is_ai_chat_dock_focused(model: &AppModel) -> bool {
+    model.ui.focused_dock().is_some_and(|position| {
+        let dock = model.dock_layout.dock(position);
+        dock.is_open && dock.active_panel() == Some(PanelId::AI_CHAT)
+    })
+}
+
+fn is_search_dock_focused(model: &AppModel) -> bool {
+    model.ui.focused_dock().is_some_and(|position| {
+        let dock = model.dock_layout.dock(position);
+        dock.is_open && dock.active_panel() == Some(PanelId::SEARCH_RESULTS)
+    })
+}
+
+/// Start recording editing messages to the recordings dir, or stop and
+/// report where the recording was saved
+fn toggle_session_recording(model: &mut AppModel) -> Option<Cmd> {
+    if let Some(path) = crate::replay::stop_recording() {
+        model
+            .ui
+            .set_status(format!("Session recorded to {}", path.display()));
+        return Some(Cmd::redraw_status_bar());
+    }
+
+    let started = config_paths::ensure_recordings_dir().and_then(|dir| {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = dir.join(format!("session-{}.jsonl", timestamp));
+        crate::replay::start_recording(&path, model).map(|()| path)
+    });
+    match started {
+        Ok(path) => model.ui.set_status(format!(
+            "Recording session to {} (replay with --replay)",
+            path.display()
+        )),
+        Err(e) => model.ui.set_status(e),
+    }
+    Some(Cmd::redraw_status_bar())
+}
+
+/// Focus a tab showing the document. Returns false if no tab shows it.
+fn focus_document(model: &mut AppModel, document_id: DocumentId) -> bool {
+    if model.editor_area.focused_document_id() == Some(document_id) {
+        return true;
+    }
+    let focused_group_id = model.editor_area.focused_group_id;
+    let Some((group_id, tab_idx)) = model
+        .editor_area
+        .find_tab_for_document(document_id, focused_group_id)
+    else {
+        return false;
+    };
+    model.editor_area.focused_group_id = group_id;
+    if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
+        group.active_tab_index = tab_idx;
+    }
+    model.editor_area.on_group_active_tab_changed(group_id);
+    true
+}
+
 /// Execute a command from the command palette
 pub fn execute_command(model: &mut AppModel, cmd_id: CommandId) -> Option<Cmd> {
     match cmd_id {
-        CommandId::NewFile => update_layout(model, LayoutMsg::NewTab),
+        CommandId::NewFile => update_app(model, AppMsg::NewFile),
         CommandId::OpenFile => update_app(model, AppMsg::OpenFileDialog),
         CommandId::FuzzyFileFinder => update_ui(model, UiMsg::OpenFuzzyFileFinder),
         CommandId::SaveFile => update_app(model, AppMsg::SaveFile),
         CommandId::SaveFileAs => update_app(model, AppMsg::SaveFileAs),
+        CommandId::SaveAllFiles => update_app(model, AppMsg::SaveAllFiles),
         CommandId::Undo => update_document(model, DocumentMsg::Undo),
         CommandId::Redo => update_document(model, DocumentMsg::Redo),
         CommandId::Cut => update_document(model, DocumentMsg::Cut),
         CommandId::Copy => update_document(model, DocumentMsg::Copy),
+        CommandId::CopyAs(transform) => copy_as(model, transform),
         CommandId::Paste => update_document(model, DocumentMsg::Paste),
         CommandId::SelectAll => {
             // SelectAll is an EditorMsg, so we need to dispatch through update
             crate::update::update_editor(model, crate::messages::EditorMsg::SelectAll)
         }
         CommandId::GotoLine => update_ui(model, UiMsg::ToggleModal(ModalId::GotoLine)),
+        CommandId::GotoSymbol => update_ui(model, UiMsg::ToggleModal(ModalId::GotoSymbol)),
         CommandId::SplitHorizontal => {
             update_layout(model, LayoutMsg::SplitFocused(SplitDirection::Horizontal))
         }
         CommandId::SplitVertical => {
             update_layout(model, LayoutMsg::SplitFocused(SplitDirection::Vertical))
         }
+        CommandId::ScrollOtherGroupUp => update_layout(model, LayoutMsg::ScrollOtherGroup(-1)),
+        CommandId::ScrollOtherGroupDown => update_layout(model, LayoutMsg::ScrollOtherGroup(1)),
+        CommandId::ToggleScrollLock => update_layout(model, LayoutMsg::ToggleScrollLock),
         CommandId::CloseGroup => update_layout(model, LayoutMsg::CloseFocusedGroup),
         CommandId::NextTab => update_layout(model, LayoutMsg::NextTab),
         CommandId::PrevTab => update_layout(model, LayoutMsg::PrevTab),
         CommandId::CloseTab => update_layout(model, LayoutMsg::CloseFocusedTab),
+        CommandId::CloseOtherTabs => update_layout(model, LayoutMsg::CloseOtherTabs),
+        CommandId::CloseTabsToRight => update_layout(model, LayoutMsg::CloseTabsToRight),
+        CommandId::CloseAllTabs => update_layout(model, LayoutMsg::CloseAllTabs),
+        CommandId::TogglePinTab => update_layout(model, LayoutMsg::TogglePinTab),
+        CommandId::SetTabColor(color) => update_layout(model, LayoutMsg::SetTabColor(color)),
+        CommandId::GroupTabsByColor => update_layout(model, LayoutMsg::GroupTabsByColor),
         CommandId::Find => update_ui(model, UiMsg::ToggleModal(ModalId::FindReplace)),
+        CommandId::SearchInWorkspace => {
+            super::project_search::update_project_search(model, SearchMsg::Open)
+        }
+        CommandId::FindNextMatch => update_editor(model, EditorMsg::FindNextMatch),
+        CommandId::FindPreviousMatch => update_editor(model, EditorMsg::FindPreviousMatch),
+        CommandId::SearchWordUnderCursor => {
+            update_editor(model, EditorMsg::SearchWordUnderCursor { forward: true })
+        }
+        CommandId::SearchWordUnderCursorBackward => {
+            update_editor(model, EditorMsg::SearchWordUnderCursor { forward: false })
+        }
+        CommandId::ClearSearchHighlight => update_editor(model, EditorMsg::ClearSearchHighlight),
+        CommandId::GoToDefinition => update_lsp(model, LspMsg::GoToDefinition),
+        CommandId::ShowHover => update_lsp(model, LspMsg::Hover),
         CommandId::ShowCommandPalette => {
             update_ui(model, UiMsg::ToggleModal(ModalId::CommandPalette))
         }
         CommandId::SwitchTheme => update_ui(model, UiMsg::ToggleModal(ModalId::ThemePicker)),
+        CommandId::ChangeLanguageMode => {
+            update_ui(model, UiMsg::ToggleModal(ModalId::LanguagePicker))
+        }
         CommandId::OpenConfigDirectory => {
             if let Some(config_dir) = config_paths::config_dir() {
                 config_paths::ensure_all_config_dirs();
@@ -349,6 +666,52 @@ pub fn execute_command(model: &mut AppModel, cmd_id: CommandId) -> Option<Cmd> {
                 Some(Cmd::redraw_status_bar())
             }
         }
+        CommandId::OpenSettings => {
+            let Some(config_path) = config_paths::config_file() else {
+                model.ui.set_status("Could not determine config path");
+                return Some(Cmd::Redraw);
+            };
+            // Write out the current settings so there is something to edit
+            if !config_path.exists() {
+                if let Err(e) = model.config.save() {
+                    model.ui.set_status(e);
+                    return Some(Cmd::Redraw);
+                }
+            }
+            Some(Cmd::OpenFileInEditor { path: config_path })
+        }
+        CommandId::ToggleLogViewer => super::logs::toggle_log_viewer(model),
+        CommandId::ToggleInputLogging => {
+            super::logs::toggle_verbose_logging(model, LogTarget::Input)
+        }
+        CommandId::ToggleRenderLogging => {
+            super::logs::toggle_verbose_logging(model, LogTarget::Render)
+        }
+        CommandId::ToggleSyntaxLogging => {
+            super::logs::toggle_verbose_logging(model, LogTarget::Syntax)
+        }
+        CommandId::ToggleIoLogging => super::logs::toggle_verbose_logging(model, LogTarget::Io),
+        CommandId::ToggleSessionRecording => toggle_session_recording(model),
+        CommandId::RestoreCrashedSession => crash_recovery::restore_crashed_session(model),
+        CommandId::TrustWorkspace => {
+            workspace_trust::set_workspace_trust(model, TrustDecision::Trusted)
+        }
+        CommandId::RestrictWorkspace => {
+            workspace_trust::set_workspace_trust(model, TrustDecision::Restricted)
+        }
+        CommandId::CheckForUpdates => Some(Cmd::CheckForUpdates { manual: true }),
+        CommandId::ViewReleaseNotes => match &model.ui.available_update {
+            Some(release) => Some(Cmd::OpenUrl {
+                url: release.url.clone(),
+            }),
+            None => {
+                model.ui.transient_message = Some(TransientMessage::new(
+                    "No update available - run \"Check for Updates\" first".to_string(),
+                    Duration::from_secs(3),
+                ));
+                Some(Cmd::redraw_status_bar())
+            }
+        },
         CommandId::OpenKeybindings => {
             if let Some(keymap_path) = config_paths::keymap_file() {
                 config_paths::ensure_all_config_dirs();
@@ -363,9 +726,50 @@ pub fn execute_command(model: &mut AppModel, cmd_id: CommandId) -> Option<Cmd> {
             }
         }
         CommandId::ToggleCsvView => super::csv::update_csv(model, crate::messages::CsvMsg::Toggle),
+        CommandId::CsvSortAscending => super::csv::sort_selected_column(model, true),
+        CommandId::CsvSortDescending => super::csv::sort_selected_column(model, false),
+        CommandId::CsvToggleHeaderRow => {
+            super::csv::update_csv(model, crate::messages::CsvMsg::ToggleHeaderRow)
+        }
+        CommandId::CsvInsertRowAbove => {
+            super::csv::update_csv(model, crate::messages::CsvMsg::InsertRowAbove)
+        }
+        CommandId::CsvInsertRowBelow => {
+            super::csv::update_csv(model, crate::messages::CsvMsg::InsertRowBelow)
+        }
+        CommandId::CsvDeleteRow => {
+            super::csv::update_csv(model, crate::messages::CsvMsg::DeleteRow)
+        }
+        CommandId::CsvInsertColumnLeft => {
+            super::csv::update_csv(model, crate::messages::CsvMsg::InsertColumnLeft)
+        }
+        CommandId::CsvInsertColumnRight => {
+            super::csv::update_csv(model, crate::messages::CsvMsg::InsertColumnRight)
+        }
+        CommandId::CsvDeleteColumn => {
+            super::csv::update_csv(model, crate::messages::CsvMsg::DeleteColumn)
+        }
+        CommandId::CsvToggleFilter => {
+            super::csv::update_csv(model, crate::messages::CsvMsg::ToggleFilterRow)
+        }
+        CommandId::DiffWithSaved => update_diff(model, DiffMsg::CompareWithSaved),
+        CommandId::DiffWithHead => update_diff(model, DiffMsg::CompareWithHead),
+        CommandId::DiffWithOtherSplit => update_diff(model, DiffMsg::CompareWithOtherSplit),
+        CommandId::DiffNextChange => update_diff(model, DiffMsg::NextChange),
+        CommandId::DiffPrevChange => update_diff(model, DiffMsg::PrevChange),
+        CommandId::DiffClose => update_diff(model, DiffMsg::Close),
+        CommandId::ToggleFold => update_editor(model, EditorMsg::ToggleFold),
+        CommandId::FoldAll => update_editor(model, EditorMsg::FoldAll),
+        CommandId::UnfoldAll => update_editor(model, EditorMsg::UnfoldAll),
         CommandId::ToggleMarkdownPreview => {
             super::preview::update_preview(model, crate::messages::PreviewMsg::Toggle)
         }
+        CommandId::ToggleMarkdownHybrid => {
+            super::preview::update_preview(model, crate::messages::PreviewMsg::ToggleHybrid)
+        }
+        CommandId::CycleHeadingFold => update_editor(model, EditorMsg::CycleHeadingFold),
+        CommandId::CycleAllHeadingFolds => update_editor(model, EditorMsg::CycleAllHeadingFolds),
+        CommandId::TogglePerformanceMode => update_ui(model, UiMsg::TogglePerformanceMode),
         CommandId::OpenLogFile => {
             if let Some(log_path) = config_paths::log_file() {
                 // Ensure logs dir exists
@@ -377,6 +781,17 @@ pub fn execute_command(model: &mut AppModel, cmd_id: CommandId) -> Option<Cmd> {
             }
         }
         CommandId::ReloadConfiguration => update_app(model, AppMsg::ReloadConfiguration),
+        CommandId::ShowKeymapDiagnostics => {
+            if model.ui.keymap_diagnostics.is_empty() {
+                model.ui.set_status("No keymap conflicts or errors");
+                return Some(Cmd::redraw_status_bar());
+            }
+            let state = KeymapDiagnosticsState::new(model.ui.keymap_diagnostics.clone());
+            model
+                .ui
+                .open_modal(crate::model::ModalState::KeymapDiagnostics(state));
+            Some(Cmd::Redraw)
+        }
         CommandId::OpenFolder => update_app(model, AppMsg::OpenFolderDialog),
         CommandId::ToggleFileExplorer => {
             // Command palette uses focus-agnostic toggle (pure open/close)
@@ -390,7 +805,56 @@ pub fn execute_command(model: &mut AppModel, cmd_id: CommandId) -> Option<Cmd> {
             // Command palette uses focus-agnostic toggle (pure open/close)
             super::dock::update_dock(model, DockMsg::TogglePanel(PanelId::OUTLINE))
         }
+        CommandId::ToggleBreadcrumbs => update_ui(model, UiMsg::ToggleBreadcrumbs),
+        CommandId::ToggleRenderWhitespace => update_ui(model, UiMsg::ToggleRenderWhitespace),
+        CommandId::ZoomIn => update_ui(model, UiMsg::ZoomIn),
+        CommandId::ZoomOut => update_ui(model, UiMsg::ZoomOut),
+        CommandId::ResetZoom => update_ui(model, UiMsg::ResetZoom),
+        CommandId::ToggleTodoList => {
+            // Command palette uses focus-agnostic toggle (pure open/close)
+            super::dock::update_dock(model, DockMsg::TogglePanel(PanelId::TODO_LIST))
+        }
+        CommandId::ToggleAiChat => {
+            // Command palette uses focus-agnostic toggle (pure open/close)
+            super::dock::update_dock(model, DockMsg::TogglePanel(PanelId::AI_CHAT))
+        }
+        CommandId::AiExplainSelection => {
+            super::ai_chat::update_ai_chat(model, AiChatMsg::ExplainSelection)
+        }
+        CommandId::AiInsertAnswer => {
+            super::ai_chat::update_ai_chat(model, AiChatMsg::InsertAnswerAtCursor)
+        }
+        CommandId::AiReplaceSelection => {
+            super::ai_chat::update_ai_chat(model, AiChatMsg::ReplaceSelectionWithAnswer)
+        }
+        CommandId::AiClearChat => super::ai_chat::update_ai_chat(model, AiChatMsg::Clear),
         CommandId::CloseFocusedDock => super::dock::update_dock(model, DockMsg::CloseFocusedDock),
+        CommandId::ToggleLeftDock => {
+            super::dock::update_dock(model, DockMsg::ToggleDock(DockPosition::Left))
+        }
+        CommandId::ToggleRightDock => {
+            super::dock::update_dock(model, DockMsg::ToggleDock(DockPosition::Right))
+        }
+        CommandId::ToggleBottomDock => {
+            super::dock::update_dock(model, DockMsg::ToggleDock(DockPosition::Bottom))
+        }
+        CommandId::FocusFileExplorer => {
+            super::dock::update_dock(model, DockMsg::ActivatePanel(PanelId::FILE_EXPLORER))
+        }
+        CommandId::FocusTerminal => {
+            super::dock::update_dock(model, DockMsg::ActivatePanel(PanelId::TERMINAL))
+        }
+        CommandId::FocusOutline => {
+            super::dock::update_dock(model, DockMsg::ActivatePanel(PanelId::OUTLINE))
+        }
+        CommandId::FocusTodoList => {
+            super::dock::update_dock(model, DockMsg::ActivatePanel(PanelId::TODO_LIST))
+        }
+        CommandId::FocusAiChat => {
+            super::dock::update_dock(model, DockMsg::ActivatePanel(PanelId::AI_CHAT))
+        }
+        CommandId::FocusNextArea => super::dock::update_dock(model, DockMsg::FocusNextArea),
+        CommandId::FocusPreviousArea => super::dock::update_dock(model, DockMsg::FocusPreviousArea),
         CommandId::RevealInFinder => {
             if let Some(path) = model.document().file_path.clone() {
                 Some(Cmd::Batch(vec![
@@ -428,7 +892,27 @@ pub fn execute_command(model: &mut AppModel, cmd_id: CommandId) -> Option<Cmd> {
                 Some(Cmd::Redraw)
             }
         }
+        CommandId::CopyPermalink => copy_permalink(model),
+        CommandId::ToggleGitBlame => super::git::update_git(model, GitMsg::ToggleBlame),
+        CommandId::EvaluateSelection => evaluate_selection(model, EvaluateOutput::Message),
+        CommandId::EvaluateAndReplace => evaluate_selection(model, EvaluateOutput::Replace),
+        CommandId::EvaluateAndAppend => evaluate_selection(model, EvaluateOutput::Append),
+        CommandId::UndoFileOperation => update_workspace(model, WorkspaceMsg::UndoFileOperation),
+        CommandId::ExplorerNewFile => update_workspace(model, WorkspaceMsg::StartNewFile),
+        CommandId::ExplorerNewFolder => update_workspace(model, WorkspaceMsg::StartNewFolder),
+        CommandId::ExplorerRename => update_workspace(model, WorkspaceMsg::StartRename),
+        CommandId::ExplorerDelete => {
+            let path = model.workspace.as_ref()?.selected_item.clone()?;
+            update_workspace(model, WorkspaceMsg::DeletePath(path))
+        }
+        CommandId::RenamePastedImage => super::paste_image::rename_pasted_image(model),
+        CommandId::CompletePath => update_completion(model, CompletionMsg::TriggerPath),
+        CommandId::FollowLink => update_notes(model, NotesMsg::FollowLinkAtCursor),
+        CommandId::ShowBacklinks => update_notes(model, NotesMsg::ShowBacklinks),
         CommandId::OpenRecentFiles => update_ui(model, UiMsg::ToggleModal(ModalId::RecentFiles)),
+        CommandId::OpenScratchpad => super::scratchpad::open_scratchpad(model, false),
+        CommandId::OpenLanguageScratchpad => super::scratchpad::open_scratchpad(model, true),
+        CommandId::ShowWelcome => update_welcome(model, WelcomeMsg::Show),
         CommandId::Quit => update_app(model, AppMsg::Quit),
         #[cfg(debug_assertions)]
         CommandId::TogglePerfOverlay => Some(Cmd::TogglePerfOverlay),
@@ -442,6 +926,142 @@ pub fn execute_command(model: &mut AppModel, cmd_id: CommandId) -> Option<Cmd> {
     }
 }
 
+/// Copy the selection, or the whole buffer without one, through a transform
+fn copy_as(model: &mut AppModel, transform: TextTransform) -> Option<Cmd> {
+    let selection = *model.editor().primary_selection();
+    let doc = model.document();
+    let (text, first_line) = if selection.is_empty() {
+        (doc.buffer.to_string(), 1)
+    } else {
+        let (start, end) = (selection.start(), selection.end());
+        let start_offset = doc.cursor_to_offset(start.line, start.column);
+        let end_offset = doc.cursor_to_offset(end.line, end.column);
+        let text = doc.buffer.slice(start_offset..end_offset).to_string();
+        (text, start.line + 1)
+    };
+
+    let text = transform.apply(&text, first_line);
+    model
+        .ui
+        .set_status(format!("Copied as {}", transform.label()));
+    Some(Cmd::Batch(vec![Cmd::CopyToClipboard(text), Cmd::Redraw]))
+}
+
+/// Copy a GitHub/GitLab link to the selected lines at the checked-out commit
+fn copy_permalink(model: &mut AppModel) -> Option<Cmd> {
+    let Some(path) = model.document().file_path.clone() else {
+        model.ui.set_status("No file path (unsaved)");
+        return Some(Cmd::Redraw);
+    };
+    match permalink_for(model, &path) {
+        Ok((host, url)) => {
+            let note = if model.document().is_modified {
+                " (unsaved changes, lines may differ)"
+            } else {
+                ""
+            };
+            model.ui.set_status(format!(
+                "Copied {} permalink: {}{}",
+                host.display_name(),
+                url,
+                note
+            ));
+            Some(Cmd::Batch(vec![Cmd::CopyToClipboard(url), Cmd::Redraw]))
+        }
+        Err(message) => {
+            model.ui.set_status(message);
+            Some(Cmd::Redraw)
+        }
+    }
+}
+
+/// Permalink to the primary selection's lines of the file at `path`
+fn permalink_for(model: &AppModel, path: &Path) -> Result<(Host, String), &'static str> {
+    let repo = Repository::discover(path).ok_or("Not in a git repository")?;
+    let commit = repo.head_commit().ok_or("No commit checked out")?;
+    let remote = repo
+        .remote_url()
+        .and_then(|url| RemoteRepo::parse(&url))
+        .ok_or("No GitHub or GitLab remote")?;
+    let relative = path
+        .strip_prefix(&repo.root)
+        .map_err(|_| "File is outside the repository")?;
+    let relative: Vec<_> = relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect();
+
+    let selection = model.editor().primary_selection();
+    let (start, end) = (selection.start(), selection.end());
+    // A selection ending at the start of a line doesn't include that line
+    let end_line = if end.line > start.line && end.column == 0 {
+        end.line - 1
+    } else {
+        end.line
+    };
+    let url = remote.permalink(&commit, &relative.join("/"), start.line + 1, end_line + 1);
+    Ok((remote.host, url))
+}
+
+/// Where "Evaluate Selection" puts the result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EvaluateOutput {
+    Message,
+    Replace,
+    Append,
+}
+
+/// Evaluate the primary selection (or the cursor line when nothing is
+/// selected) as a calculator expression
+fn evaluate_selection(model: &mut AppModel, output: EvaluateOutput) -> Option<Cmd> {
+    let selection = *model.editor().primary_selection();
+    let (start, end) = if selection.is_empty() {
+        let line = selection.head.line;
+        (
+            Position::new(line, 0),
+            Position::new(line, model.document().line_length(line)),
+        )
+    } else {
+        (selection.start(), selection.end())
+    };
+    let doc = model.document();
+    let start_offset = doc.cursor_to_offset(start.line, start.column);
+    let end_offset = doc.cursor_to_offset(end.line, end.column);
+    let text = doc.buffer.slice(start_offset..end_offset).to_string();
+
+    let result = match calc::evaluate(&text) {
+        Ok(result) => result,
+        Err(e) => {
+            model.ui.transient_message = Some(TransientMessage::new(
+                format!("Can't evaluate: {}", e),
+                Duration::from_secs(3),
+            ));
+            return Some(Cmd::redraw_status_bar());
+        }
+    };
+    let (range, insert) = match output {
+        EvaluateOutput::Message => {
+            model.ui.set_status(format!("{} = {}", text.trim(), result));
+            return Some(Cmd::redraw_status_bar());
+        }
+        EvaluateOutput::Replace => ((start, end), result),
+        EvaluateOutput::Append => {
+            let separator = match text.trim_end().ends_with('=') {
+                true if text.ends_with(char::is_whitespace) => "",
+                true => " ",
+                false => " = ",
+            };
+            ((end, end), format!("{}{}", separator, result))
+        }
+    };
+
+    let editor = model.editor_mut();
+    editor.collapse_to_primary();
+    editor.cursors[0] = Cursor::from_position(range.1);
+    editor.selections[0] = Selection::from_anchor_head(range.0, range.1);
+    update_document(model, DocumentMsg::InsertText(insert))
+}
+
 pub fn create_default_keymap_file(path: &std::path::Path) -> Result<(), String> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)
@@ -532,4 +1152,30 @@ mod tests {
         assert_eq!(model.document().buffer.to_string(), document_before);
         assert_eq!(pty_rx.try_recv().unwrap(), b"terminal paste".to_vec());
     }
+
+    #[test]
+    fn copy_as_transforms_selection_or_whole_buffer() {
+        let mut model = test_model();
+        model.document_mut().buffer = ropey::Rope::from("one\ntwo \"2\"\nthree\n");
+
+        let copied = |cmd: Option<Cmd>| match cmd {
+            Some(Cmd::Batch(cmds)) => cmds.into_iter().find_map(|cmd| match cmd {
+                Cmd::CopyToClipboard(text) => Some(text),
+                _ => None,
+            }),
+            _ => None,
+        };
+
+        // No selection: the whole buffer
+        let cmd = execute_command(&mut model, CommandId::CopyAs(TextTransform::JsonString));
+        assert_eq!(copied(cmd).as_deref(), Some(r#""one\ntwo \"2\"\nthree\n""#));
+
+        // A selection keeps its document line numbers
+        model.editor_mut().cursors[0] = Cursor::at(2, 5);
+        model.editor_mut().selections[0] =
+            Selection::from_positions(Position::new(1, 0), Position::new(2, 5));
+        let cmd = execute_command(&mut model, CommandId::CopyAs(TextTransform::LineNumbers));
+        assert_eq!(copied(cmd).as_deref(), Some("2 | two \"2\"\n3 | three"));
+        assert_eq!(model.ui.status_message, "Copied as Text with Line Numbers");
+    }
 }
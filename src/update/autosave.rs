@@ -0,0 +1,303 @@
+//! Saving modified files without being asked: when the window loses focus,
+//! when switching away from a file's tab, or every so often (the `autosave`
+//! config section, which a workspace can override). "Save All" shares the
+//! same background saves.
+
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::config::AutosaveConfig;
+use crate::model::editor_area::DocumentId;
+use crate::model::{AppModel, Document, SaveAllProgress};
+use crate::replay::content_hash;
+
+/// Autosave settings in effect: the workspace's, else the user's
+pub(super) fn settings(model: &AppModel) -> AutosaveConfig {
+    model
+        .workspace
+        .as_ref()
+        .and_then(|workspace| workspace.config.autosave)
+        .unwrap_or(model.config.autosave)
+}
+
+/// How often modified files are saved, if autosaving on an interval
+pub fn autosave_interval(model: &AppModel) -> Option<Duration> {
+    settings(model)
+        .interval_ms
+        .filter(|&ms| ms > 0)
+        .map(Duration::from_millis)
+}
+
+/// Save every modified file, if the window losing focus should
+pub(super) fn on_window_blur(model: &mut AppModel) -> Option<Cmd> {
+    if !settings(model).on_window_blur {
+        return None;
+    }
+    let ids: Vec<DocumentId> = model.editor_area.documents.keys().copied().collect();
+    save_documents(model, ids)
+}
+
+/// Save every modified file, if autosaving on an interval
+pub(super) fn on_interval(model: &mut AppModel) -> Option<Cmd> {
+    autosave_interval(model)?;
+    let ids: Vec<DocumentId> = model.editor_area.documents.keys().copied().collect();
+    save_documents(model, ids)
+}
+
+/// Save every modified file. The status bar sums up what was saved once
+/// every save has completed.
+pub(super) fn save_all(model: &mut AppModel) -> Option<Cmd> {
+    let mut progress = SaveAllProgress::default();
+    let mut ids = Vec::new();
+    for (&document_id, doc) in &model.editor_area.documents {
+        if !doc.is_modified {
+            continue;
+        }
+        if doc.file_path.is_none() || doc.deleted_on_disk {
+            progress.skipped += 1;
+        } else {
+            ids.push(document_id);
+            progress.pending.push((document_id, doc.display_name()));
+        }
+    }
+
+    if ids.is_empty() {
+        model.ui.set_status(if progress.skipped == 0 {
+            "No unsaved changes".to_string()
+        } else {
+            save_all_summary(&progress)
+        });
+        return Some(Cmd::redraw_status_bar());
+    }
+    model
+        .ui
+        .set_status(format!("Saving {}...", files(progress.pending.len())));
+    model.ui.save_all = Some(progress);
+    save_documents(model, ids)
+}
+
+/// "Saved 3 files, failed: a.rs (denied), 1 untitled or deleted file not saved"
+fn save_all_summary(progress: &SaveAllProgress) -> String {
+    let mut parts = vec![format!("Saved {}", files(progress.saved))];
+    if !progress.failed.is_empty() {
+        parts.push(format!("failed: {}", progress.failed.join(", ")));
+    }
+    if progress.skipped > 0 {
+        parts.push(format!(
+            "{} untitled or deleted {} not saved",
+            progress.skipped,
+            if progress.skipped == 1 {
+                "file"
+            } else {
+                "files"
+            }
+        ));
+    }
+    parts.join(", ")
+}
+
+fn files(count: usize) -> String {
+    match count {
+        1 => "1 file".to_string(),
+        n => format!("{} files", n),
+    }
+}
+
+/// Save the file that was focused before an update, if the update switched
+/// away from it and switching tabs should save
+pub(super) fn on_focus_change(model: &mut AppModel, previous: Option<DocumentId>) -> Option<Cmd> {
+    let previous = previous?;
+    if model.editor_area.focused_document_id() == Some(previous) || !settings(model).on_tab_switch {
+        return None;
+    }
+    save_documents(model, vec![previous])
+}
+
+/// Save those of `ids` that are modified files. Untitled documents and
+/// files deleted on disk are left for the user to save.
+fn save_documents(model: &mut AppModel, ids: Vec<DocumentId>) -> Option<Cmd> {
+    let cmds: Vec<Cmd> = ids
+        .into_iter()
+        .filter_map(|document_id| {
+            let doc = model.editor_area.documents.get_mut(&document_id)?;
+            if !doc.is_modified || doc.deleted_on_disk {
+                return None;
+            }
+            let path = doc.file_path.clone()?;
+            let content = doc.buffer.to_string();
+            doc.disk_hash = Some(content_hash(&content));
+            Some(Cmd::AutosaveFile {
+                document_id,
+                path,
+                content,
+                saved_revision: doc.undo_stack.len(),
+            })
+        })
+        .collect();
+    (!cmds.is_empty()).then(|| Cmd::batch(cmds))
+}
+
+/// Record a finished automatic save. The document counts as saved at the
+/// undo stack length it was saved at, so edits made meanwhile stay modified.
+pub(super) fn completed(
+    model: &mut AppModel,
+    document_id: DocumentId,
+    saved_revision: usize,
+    result: Result<(), String>,
+) -> Option<Cmd> {
+    if let Some(progress) = model.ui.save_all.as_mut() {
+        if let Some(index) = progress
+            .pending
+            .iter()
+            .position(|(id, _)| *id == document_id)
+        {
+            let (_, name) = progress.pending.remove(index);
+            match &result {
+                Ok(()) => progress.saved += 1,
+                Err(e) => progress.failed.push(format!("{} ({})", name, e)),
+            }
+            if progress.pending.is_empty() {
+                let summary = save_all_summary(progress);
+                model.ui.save_all = None;
+                model.ui.set_status(summary);
+            }
+            if let Some(doc) = model.editor_area.documents.get_mut(&document_id) {
+                if result.is_ok() {
+                    mark_saved(doc, saved_revision);
+                }
+            }
+            return Some(Cmd::Redraw);
+        }
+    }
+
+    let doc = model.editor_area.documents.get_mut(&document_id)?;
+    match result {
+        Ok(()) => {
+            mark_saved(doc, saved_revision);
+            Some(Cmd::Redraw)
+        }
+        Err(e) => {
+            let message = format!("Autosave of {} failed: {}", doc.display_name(), e);
+            model.ui.set_status(message);
+            Some(Cmd::redraw_status_bar())
+        }
+    }
+}
+
+fn mark_saved(doc: &mut Document, saved_revision: usize) {
+    doc.saved_revision = Some(saved_revision);
+    doc.is_modified = doc.undo_stack.len() != saved_revision;
+    doc.forget_git_head();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+    use crate::messages::{AppMsg, DocumentMsg, LayoutMsg, Msg, UiMsg};
+    use crate::update::update;
+
+    fn open_modified(path: &Path) -> AppModel {
+        std::fs::write(path, "a\n").unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![path.to_path_buf()]);
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+        model
+    }
+
+    fn autosaved(cmd: Option<Cmd>) -> Vec<PathBuf> {
+        match cmd {
+            Some(Cmd::AutosaveFile { path, .. }) => vec![path],
+            Some(Cmd::Batch(cmds)) => cmds.into_iter().flat_map(|c| autosaved(Some(c))).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn window_blur_saves_modified_files_but_not_untitled_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        let mut model = open_modified(&path);
+        model.config.autosave.on_window_blur = true;
+        let document_id = model.document().id.unwrap();
+
+        // Switching tabs doesn't save unless asked to
+        let cmd = update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+        assert!(autosaved(cmd).is_empty());
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('y')));
+
+        let cmd = update(&mut model, Msg::Ui(UiMsg::WindowFocused(false)));
+        assert_eq!(autosaved(cmd), [path]);
+
+        completed(&mut model, document_id, 1, Ok(()));
+        assert!(!model.editor_area.documents[&document_id].is_modified);
+        assert!(model.document().is_modified, "untitled stays modified");
+    }
+
+    #[test]
+    fn workspace_config_overrides_autosave_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".token")).unwrap();
+        std::fs::write(
+            dir.path().join(".token").join("config.yaml"),
+            "autosave:\n  on_tab_switch: true\n",
+        )
+        .unwrap();
+        let path = dir.path().join("a.txt");
+        let mut model = open_modified(&path);
+        model.config.autosave.on_window_blur = true;
+        model.open_workspace(dir.path().to_path_buf());
+
+        let cmd = update(&mut model, Msg::Ui(UiMsg::WindowFocused(false)));
+        assert!(autosaved(cmd).is_empty());
+        let cmd = update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+        assert_eq!(autosaved(cmd), [path]);
+    }
+
+    #[test]
+    fn interval_saves_only_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        let mut model = open_modified(&path);
+
+        let cmd = update(&mut model, Msg::App(AppMsg::AutosaveInterval));
+        assert!(autosaved(cmd).is_empty());
+
+        model.config.autosave.interval_ms = Some(0);
+        assert_eq!(autosave_interval(&model), None, "zero turns it off");
+        model.config.autosave.interval_ms = Some(5_000);
+        assert_eq!(autosave_interval(&model), Some(Duration::from_secs(5)));
+        let cmd = update(&mut model, Msg::App(AppMsg::AutosaveInterval));
+        assert_eq!(autosaved(cmd), [path]);
+    }
+
+    #[test]
+    fn save_all_reports_a_summary_when_done() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        let mut model = open_modified(&path);
+        let document_id = model.document().id.unwrap();
+        update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('y')));
+
+        let cmd = update(&mut model, Msg::App(AppMsg::SaveAllFiles));
+        assert_eq!(autosaved(cmd), [path]);
+        assert_eq!(model.ui.status_message, "Saving 1 file...");
+
+        completed(&mut model, document_id, 1, Err("denied".to_string()));
+        assert_eq!(
+            model.ui.status_message,
+            "Saved 0 files, failed: a.txt (denied), 1 untitled or deleted file not saved"
+        );
+        assert!(model.ui.save_all.is_none());
+        assert!(model.editor_area.documents[&document_id].is_modified);
+
+        update(&mut model, Msg::App(AppMsg::SaveAllFiles));
+        completed(&mut model, document_id, 1, Ok(()));
+        assert!(model
+            .ui
+            .status_message
+            .starts_with("Saved 1 file, 1 untitled"));
+        assert!(!model.editor_area.documents[&document_id].is_modified);
+    }
+}
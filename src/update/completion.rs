@@ -0,0 +1,268 @@
+//! Completion popup: file path completions for the string or Markdown link
+//! at the cursor, opened by typing `/` or by "Complete Path"
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::completion::{path_completions, path_query_at, CompletionItem, PathQuery};
+use crate::messages::{CompletionMsg, DocumentMsg};
+use crate::model::status_bar::TransientMessage;
+use crate::model::{AppModel, CompletionState, FocusTarget, Position, Selection};
+use crate::syntax::LanguageId;
+use crate::util::normalize_path;
+
+use super::document::update_document;
+
+/// Handle completion popup messages
+pub fn update_completion(model: &mut AppModel, msg: CompletionMsg) -> Option<Cmd> {
+    match msg {
+        CompletionMsg::TriggerPath => {
+            if open_path_completion(model, false) {
+                Some(Cmd::Redraw)
+            } else {
+                show_message(model, "No file paths to complete here")
+            }
+        }
+        CompletionMsg::SelectPrevious => {
+            model.ui.completion.as_mut()?.select_offset(-1);
+            Some(Cmd::Redraw)
+        }
+        CompletionMsg::SelectNext => {
+            model.ui.completion.as_mut()?.select_offset(1);
+            Some(Cmd::Redraw)
+        }
+        CompletionMsg::Accept => accept(model),
+        CompletionMsg::Dismiss => {
+            model.ui.completion.take()?;
+            Some(Cmd::Redraw)
+        }
+    }
+}
+
+/// Open path completions after `/` was typed into a path
+pub(super) fn open_after_slash(model: &mut AppModel) -> bool {
+    open_path_completion(model, true)
+}
+
+/// Keep the popup in step with the text after any message: re-filter when
+/// the query changed, close it when the cursor left the path or the editor
+/// lost focus. Returns whether the popup changed (and needs a redraw).
+pub(super) fn sync_completion(model: &mut AppModel) -> bool {
+    let Some(state) = &model.ui.completion else {
+        return false;
+    };
+    let focused = model.ui.focus == FocusTarget::Editor
+        && !model.ui.has_modal()
+        && model.editor_area.focused_editor_id() == Some(state.editor_id);
+    let query = focused.then(|| path_query(model)).flatten();
+    let line = model.editor().active_cursor().line;
+    let Some(query) = query.filter(|query| line == state.line && query.start == state.start_column)
+    else {
+        model.ui.completion = None;
+        return true;
+    };
+    if query.text == state.query {
+        return false;
+    }
+
+    let items = items_for(model, &query);
+    match model.ui.completion.as_mut() {
+        Some(state) if !items.is_empty() => {
+            state.query = query.text;
+            state.items = items;
+            state.selected_index = 0;
+            state.scroll_offset = 0;
+        }
+        _ => model.ui.completion = None,
+    }
+    true
+}
+
+/// Open the popup for the path at the cursor. When `typed`, only plain
+/// paths open it (not wiki-links or strings with spaces).
+fn open_path_completion(model: &mut AppModel, typed: bool) -> bool {
+    model.ui.completion = None;
+    let Some(query) = path_query(model).filter(|query| !typed || query.is_path_like()) else {
+        return false;
+    };
+    let items = items_for(model, &query);
+    let Some(editor_id) = model.editor_area.focused_editor_id() else {
+        return false;
+    };
+    if items.is_empty() {
+        return false;
+    }
+    model.ui.completion = Some(CompletionState {
+        editor_id,
+        line: model.editor().active_cursor().line,
+        start_column: query.start,
+        query: query.text,
+        items,
+        selected_index: 0,
+        scroll_offset: 0,
+    });
+    true
+}
+
+/// Replace the query with the selected item; folders open the popup again
+/// for their contents
+fn accept(model: &mut AppModel) -> Option<Cmd> {
+    let state = model.ui.completion.take()?;
+    let item = state.selected_item()?.clone();
+    let cursor = *model.editor().active_cursor();
+    if cursor.line != state.line {
+        return Some(Cmd::Redraw);
+    }
+
+    model.editor_mut().selections[0] = Selection::from_anchor_head(
+        Position::new(state.line, state.start_column),
+        Position::new(cursor.line, cursor.column),
+    );
+    let cmd = update_document(model, DocumentMsg::InsertText(item.insert_text));
+    if item.retrigger {
+        open_path_completion(model, false);
+    }
+    Some(match cmd {
+        Some(cmd) => Cmd::batch(vec![cmd, Cmd::Redraw]),
+        None => Cmd::Redraw,
+    })
+}
+
+/// The path query at the primary cursor of the focused text editor
+fn path_query(model: &AppModel) -> Option<PathQuery> {
+    let editor = model.editor_area.focused_editor()?;
+    if !editor.is_plain_text_mode()
+        || editor.has_multiple_cursors()
+        || !editor.primary_selection().is_empty()
+    {
+        return None;
+    }
+    let document = model.editor_area.focused_document()?;
+    let cursor = editor.active_cursor();
+    let line = document.get_line(cursor.line)?;
+    path_query_at(
+        &line,
+        cursor.column,
+        document.language == LanguageId::Markdown,
+    )
+}
+
+/// Candidates from the workspace file index, resolved against the focused
+/// document's folder (the workspace root for unsaved documents)
+fn items_for(model: &AppModel, query: &PathQuery) -> Vec<CompletionItem> {
+    let Some(workspace) = model.workspace.as_ref() else {
+        return Vec::new();
+    };
+    let document_dir: PathBuf = model
+        .editor_area
+        .focused_document()
+        .and_then(|doc| doc.file_path.as_deref())
+        .and_then(Path::parent)
+        .map(normalize_path)
+        .unwrap_or_else(|| workspace.root.clone());
+    path_completions(
+        query,
+        &document_dir,
+        Some(&workspace.root),
+        &workspace.file_tree.get_all_file_paths(),
+    )
+}
+
+fn show_message(model: &mut AppModel, message: &str) -> Option<Cmd> {
+    model.ui.transient_message = Some(TransientMessage::new(
+        message.to_string(),
+        Duration::from_secs(3),
+    ));
+    Some(Cmd::redraw_status_bar())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{Direction, EditorMsg, Msg};
+    use crate::update::update;
+
+    fn labels(model: &AppModel) -> Vec<String> {
+        model
+            .ui
+            .completion
+            .as_ref()
+            .map(|state| state.items.iter().map(|item| item.label.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    fn type_text(model: &mut AppModel, text: &str) {
+        for ch in text.chars() {
+            update(model, Msg::Document(DocumentMsg::InsertChar(ch)));
+        }
+    }
+
+    #[test]
+    fn slash_in_link_lists_folder_and_accepting_a_folder_continues() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("notes/img")).unwrap();
+        std::fs::write(root.join("notes/idea.md"), "").unwrap();
+        std::fs::write(root.join("notes/img/diagram.png"), "").unwrap();
+        let path = root.join("index.md");
+        std::fs::write(&path, "See [x](notes\n").unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![path]);
+        model.open_workspace(root);
+        // Dismiss the workspace trust prompt
+        model.ui.close_modal();
+        model.editor_mut().active_cursor_mut().column = 13;
+
+        type_text(&mut model, "/");
+        assert_eq!(labels(&model), vec!["img/", "idea.md"]);
+
+        // Typing filters, accepting a folder lists its contents
+        type_text(&mut model, "i");
+        assert_eq!(labels(&model), vec!["img/", "idea.md"]);
+        type_text(&mut model, "m");
+        assert_eq!(labels(&model), vec!["img/"]);
+        update(&mut model, Msg::Completion(CompletionMsg::Accept));
+        assert_eq!(
+            model.document().get_line(0).unwrap(),
+            "See [x](notes/img/\n"
+        );
+        assert_eq!(labels(&model), vec!["diagram.png"]);
+
+        update(&mut model, Msg::Completion(CompletionMsg::Accept));
+        assert_eq!(
+            model.document().get_line(0).unwrap(),
+            "See [x](notes/img/diagram.png\n"
+        );
+        assert!(model.ui.completion.is_none());
+    }
+
+    #[test]
+    fn popup_closes_when_the_cursor_leaves_the_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+        let path = root.join("build.rs");
+        std::fs::write(&path, "let p = \"src\";\n").unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![path]);
+        model.open_workspace(root);
+        model.editor_mut().active_cursor_mut().column = 12;
+
+        type_text(&mut model, "/");
+        assert_eq!(labels(&model), vec!["main.rs"]);
+        update(
+            &mut model,
+            Msg::Editor(EditorMsg::MoveCursor(Direction::Right)),
+        );
+        update(
+            &mut model,
+            Msg::Editor(EditorMsg::MoveCursor(Direction::Right)),
+        );
+        assert!(model.ui.completion.is_none());
+
+        // No path under the cursor: the explicit trigger says so
+        update(&mut model, Msg::Completion(CompletionMsg::TriggerPath));
+        assert!(model.ui.completion.is_none());
+        assert!(model.ui.transient_message.is_some());
+    }
+}
@@ -0,0 +1,157 @@
+//! Reopening the files of a run that crashed (see [`crate::crash`])
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::crash::EditorSummary;
+use crate::messages::LayoutMsg;
+use crate::model::status_bar::TransientMessage;
+use crate::model::{AppModel, Position};
+
+use super::layout::update_layout;
+
+/// Remember the crashed run's files and tell the user how to get them back
+pub(super) fn offer_restore(
+    model: &mut AppModel,
+    report: &Path,
+    session: EditorSummary,
+) -> Option<Cmd> {
+    tracing::warn!("Previous run crashed, see {}", report.display());
+    if session.open_files.is_empty() && session.workspace.is_none() {
+        model.ui.set_status(format!(
+            "Token quit unexpectedly - report saved to {}",
+            report.display()
+        ));
+    } else {
+        model.ui.set_status(format!(
+            "Token quit unexpectedly - run \"Restore Crashed Session\" to reopen {} file(s)",
+            session.open_files.len()
+        ));
+        model.ui.crashed_session = Some(session);
+    }
+    Some(Cmd::redraw_status_bar())
+}
+
+/// Reopen the workspace and files of the crashed run, restoring cursors
+pub(super) fn restore_crashed_session(model: &mut AppModel) -> Option<Cmd> {
+    let Some(session) = model.ui.crashed_session.take() else {
+        model.ui.transient_message = Some(TransientMessage::new(
+            "No crashed session to restore".to_string(),
+            Duration::from_secs(3),
+        ));
+        return Some(Cmd::redraw_status_bar());
+    };
+
+    if model.workspace.is_none() {
+        if let Some(root) = session.workspace.filter(|root| root.is_dir()) {
+            model.open_workspace(root);
+        }
+    }
+
+    let mut cmds = vec![Cmd::Redraw];
+    let mut reopened = 0;
+    for file in &session.open_files {
+        if !file.path.is_file() {
+            continue;
+        }
+        cmds.extend(update_layout(
+            model,
+            LayoutMsg::OpenFileInNewTab(file.path.clone()),
+        ));
+        if let Some(&(line, column)) = file.cursors.first() {
+            place_cursor(model, line, column);
+        }
+        reopened += 1;
+    }
+    if let Some(focused) = session.focused_file.filter(|path| path.is_file()) {
+        cmds.extend(update_layout(model, LayoutMsg::OpenFileInNewTab(focused)));
+    }
+
+    let lost = session.open_files.iter().filter(|f| f.modified).count();
+    let mut message = format!("Reopened {} file(s)", reopened);
+    if lost > 0 {
+        message.push_str(&format!(", {} had unsaved changes that were lost", lost));
+    }
+    model.ui.set_status(message);
+    Some(Cmd::Batch(cmds))
+}
+
+/// Move the focused editor's cursor, clamped to the document
+fn place_cursor(model: &mut AppModel, line: usize, column: usize) {
+    let document = model.document();
+    let line = line.min(document.line_count().saturating_sub(1));
+    let column = column.min(document.line_length(line));
+    let editor = model.editor_mut();
+    editor.collapse_to_primary();
+    editor.cursors[0].line = line;
+    editor.cursors[0].column = column;
+    editor.cursors[0].desired_column = None;
+    editor.selections[0].anchor = Position::new(line, column);
+    editor.selections[0].head = Position::new(line, column);
+    model.ensure_cursor_visible();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::CommandId;
+    use crate::crash::OpenFile;
+    use crate::messages::{AppMsg, Msg};
+    use crate::update::update;
+
+    #[test]
+    fn restores_files_and_cursors_of_crashed_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        std::fs::write(&a, "one\ntwo\nthree\n").unwrap();
+        std::fs::write(&b, "hello\n").unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+
+        let session = EditorSummary {
+            workspace: None,
+            focused_file: Some(a.clone()),
+            open_files: vec![
+                OpenFile {
+                    path: a.clone(),
+                    modified: false,
+                    cursors: vec![(1, 2)],
+                },
+                OpenFile {
+                    path: b.clone(),
+                    modified: true,
+                    cursors: vec![(40, 40)],
+                },
+                OpenFile {
+                    path: dir.path().join("gone.txt"),
+                    modified: false,
+                    cursors: vec![],
+                },
+            ],
+        };
+        update(
+            &mut model,
+            Msg::App(AppMsg::CrashReportFound {
+                report: dir.path().join("crash-1.json"),
+                session,
+            }),
+        );
+        assert!(model.ui.crashed_session.is_some());
+
+        update(
+            &mut model,
+            Msg::App(AppMsg::RunCommand(CommandId::RestoreCrashedSession)),
+        );
+        assert!(model.ui.crashed_session.is_none());
+        assert!(model.editor_area.find_document_by_path(&b).is_some());
+        assert_eq!(model.document().file_path.as_deref(), Some(a.as_path()));
+        assert_eq!(
+            (
+                model.editor().cursors[0].line,
+                model.editor().cursors[0].column
+            ),
+            (1, 2)
+        );
+    }
+}
@@ -3,9 +3,16 @@
 //! Handles CsvMsg messages for CSV view mode operations.
 
 use crate::commands::Cmd;
-use crate::csv::{detect_delimiter, escape_csv_value, parse_csv, CellEdit, CsvState, Delimiter};
+use crate::csv::{
+    cell_write, detect_delimiter, parse_csv, reorder_records, replace_in_cell, sorted_row_order,
+    CellEdit, CellPosition, CsvState, Delimiter,
+};
 use crate::messages::CsvMsg;
-use crate::model::{AppModel, ViewMode};
+use crate::model::{AppModel, Cursor, EditOperation, Position, Selection, ViewMode};
+
+use super::editor::{
+    sync_other_editor_cursors_for_deleted_text, sync_other_editor_cursors_for_text,
+};
 
 /// Handle CSV mode messages
 pub fn update_csv(model: &mut AppModel, msg: CsvMsg) -> Option<Cmd> {
@@ -25,8 +32,33 @@ pub fn update_csv(model: &mut AppModel, msg: CsvMsg) -> Option<Cmd> {
         CsvMsg::PageUp => page_up(model),
         CsvMsg::PageDown => page_down(model),
         CsvMsg::SelectCell { row, col } => select_cell(model, row, col),
+        CsvMsg::ExtendUp => extend_selection(model, -1, 0),
+        CsvMsg::ExtendDown => extend_selection(model, 1, 0),
+        CsvMsg::ExtendLeft => extend_selection(model, 0, -1),
+        CsvMsg::ExtendRight => extend_selection(model, 0, 1),
+        CsvMsg::ExtendToCell { row, col } => extend_selection_to(model, row, col),
+        CsvMsg::CopySelection => copy_selection(model),
+        CsvMsg::StartColumnResize { col, x } => start_column_resize(model, col, x),
+        CsvMsg::UpdateColumnResize { x } => update_column_resize(model, x),
+        CsvMsg::EndColumnResize => end_column_resize(model),
+        CsvMsg::AutoFitColumn(col) => auto_fit_column(model, col),
         CsvMsg::ScrollVertical(delta) => scroll_vertical(model, delta),
         CsvMsg::ScrollHorizontal(delta) => scroll_horizontal(model, delta),
+        CsvMsg::SortByColumn { col, ascending } => sort_by_column(model, col, ascending),
+        CsvMsg::InsertRowAbove => insert_row(model, false),
+        CsvMsg::InsertRowBelow => insert_row(model, true),
+        CsvMsg::DeleteRow => delete_row(model),
+        CsvMsg::InsertColumnLeft => insert_column(model, false),
+        CsvMsg::InsertColumnRight => insert_column(model, true),
+        CsvMsg::DeleteColumn => delete_column(model),
+        CsvMsg::ToggleFilterRow => toggle_filter_row(model),
+        CsvMsg::FocusFilter(col) => focus_filter(model, col),
+        CsvMsg::FilterInsertChar(ch) => edit_filter(model, |csv| csv.filter_insert_char(ch)),
+        CsvMsg::FilterDeleteBackward => edit_filter(model, CsvState::filter_delete_backward),
+        CsvMsg::FilterNextColumn => edit_filter(model, |csv| csv.move_filter_focus(1)),
+        CsvMsg::FilterPrevColumn => edit_filter(model, |csv| csv.move_filter_focus(-1)),
+        CsvMsg::LeaveFilter => edit_filter(model, |csv| csv.filter_focus = None),
+        CsvMsg::ToggleHeaderRow => toggle_header_row(model),
 
         // Cell editing messages
         CsvMsg::StartEditing => start_editing(model),
@@ -102,7 +134,7 @@ fn toggle_csv_mode(model: &mut AppModel) -> Option<Cmd> {
 
             // Calculate visible rows based on window dimensions
             let line_height = model.line_height.max(1);
-            let tab_bar_height = model.metrics.tab_bar_height;
+            let tab_bar_height = model.metrics.header_height();
             let status_bar_height = line_height;
             let col_header_height = line_height;
             let content_height = (model.window_size.1 as usize)
@@ -244,6 +276,7 @@ fn page_down(model: &mut AppModel) -> Option<Cmd> {
 fn select_cell(model: &mut AppModel, row: usize, col: usize) -> Option<Cmd> {
     let editor = model.editor_area.focused_editor_mut()?;
     if let Some(csv) = editor.view_mode.as_csv_mut() {
+        csv.filter_focus = None;
         csv.select_cell(row, col);
         Some(Cmd::redraw_editor())
     } else {
@@ -251,6 +284,109 @@ fn select_cell(model: &mut AppModel, row: usize, col: usize) -> Option<Cmd> {
     }
 }
 
+/// Grow the range selection by moving its head cell
+fn extend_selection(model: &mut AppModel, delta_row: i32, delta_col: i32) -> Option<Cmd> {
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    if csv.is_editing() {
+        return None;
+    }
+    csv.extend_selection(delta_row, delta_col);
+    Some(Cmd::redraw_editor())
+}
+
+/// Grow the range selection to a cell (Shift+click, mouse drag)
+fn extend_selection_to(model: &mut AppModel, row: usize, col: usize) -> Option<Cmd> {
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    if csv.is_editing() {
+        return None;
+    }
+    csv.filter_focus = None;
+    if csv.selected_cell == CellPosition::new(row, col) {
+        return Some(Cmd::redraw_editor());
+    }
+    csv.extend_selection_to(row, col);
+    Some(Cmd::redraw_editor())
+}
+
+/// Copy the selected cells as tab-separated values
+fn copy_selection(model: &mut AppModel) -> Option<Cmd> {
+    let csv = model.editor_area.focused_editor()?.view_mode.as_csv()?;
+    let (start, end) = csv.selection_range();
+    let tsv = csv.selection_as_tsv();
+    let count = csv.selected_rows().count() * (end.col - start.col + 1);
+    model.ui.set_status(if count == 1 {
+        "Copied 1 cell".to_string()
+    } else {
+        format!("Copied {count} cells")
+    });
+    Some(Cmd::Batch(vec![
+        Cmd::CopyToClipboard(tsv),
+        Cmd::redraw_status_bar(),
+    ]))
+}
+
+/// Start dragging a column's right edge
+fn start_column_resize(model: &mut AppModel, col: usize, x: f64) -> Option<Cmd> {
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    if csv.is_editing() {
+        return None;
+    }
+    csv.start_column_resize(col, x);
+    None
+}
+
+/// Resize the dragged column to follow the mouse
+fn update_column_resize(model: &mut AppModel, x: f64) -> Option<Cmd> {
+    let char_width = model.char_width;
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    csv.update_column_resize(x, char_width)
+        .then(Cmd::redraw_editor)
+}
+
+/// Finish dragging a column's edge
+fn end_column_resize(model: &mut AppModel) -> Option<Cmd> {
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    csv.column_resize.take()?;
+    Some(Cmd::redraw_editor())
+}
+
+/// Fit a column to its widest value
+fn auto_fit_column(model: &mut AppModel, col: usize) -> Option<Cmd> {
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    if csv.is_editing() {
+        return None;
+    }
+    csv.column_resize = None;
+    let width = csv.fit_column_width(col);
+    csv.set_column_width(col, width);
+    csv.ensure_selection_visible();
+    Some(Cmd::redraw_editor())
+}
+
 /// Scroll viewport vertically (from mouse wheel)
 fn scroll_vertical(model: &mut AppModel, delta: i32) -> Option<Cmd> {
     let editor = model.editor_area.focused_editor_mut()?;
@@ -796,165 +932,411 @@ pub(crate) fn edit_paste_text(model: &mut AppModel, text: String) -> Option<Cmd>
     }
 }
 
-// === Document Sync ===
+// === Sorting ===
 
-use crate::model::Document;
+/// Sort the rows by the values in `col`, leaving the header row in place,
+/// and move the records in the document to match as one undoable edit.
+/// The selection stays on the row it was on.
+fn sort_by_column(model: &mut AppModel, col: usize, ascending: bool) -> Option<Cmd> {
+    let csv = model.editor_area.focused_editor()?.view_mode.as_csv()?;
+    if csv.is_editing() || col >= csv.data.column_count() {
+        return None;
+    }
+    let order = sorted_row_order(&csv.data, col, ascending, csv.has_header_row);
+    if order.iter().enumerate().any(|(to, &from)| to != from) {
+        let content = model.document().buffer.to_string();
+        let Some(new_text) = reorder_records(&content, &order, csv.delimiter) else {
+            tracing::warn!("CSV rows don't match the document, not sorting");
+            return None;
+        };
+        replace_document_text(model, &new_text);
+    }
 
-/// Sync a cell edit back to the document text buffer
-fn sync_cell_edit_to_document(doc: &mut Document, edit: &CellEdit, delimiter: Delimiter) {
-    let content = doc.buffer.to_string();
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    let selected = csv.selected_cell;
+    csv.data.reorder_rows(&order);
+    csv.apply_filters();
+    let row = order
+        .iter()
+        .position(|&from| from == selected.row)
+        .unwrap_or(selected.row);
+    csv.select_cell(row, selected.col);
+    csv.sorted_by = Some((col, ascending));
+
+    model.ui.set_status(format!(
+        "Sorted by {} ({})",
+        csv.column_name(col),
+        if ascending { "ascending" } else { "descending" }
+    ));
+    Some(Cmd::redraw_editor())
+}
 
-    let row_range = match find_row_byte_range(&content, edit.position.row) {
-        Some(r) => r,
-        None => {
-            tracing::warn!("Could not find row {} in document", edit.position.row);
-            return;
-        }
-    };
+/// Sort by the column of the selected cell (palette commands)
+pub(super) fn sort_selected_column(model: &mut AppModel, ascending: bool) -> Option<Cmd> {
+    let col = model
+        .editor_area
+        .focused_editor()?
+        .view_mode
+        .as_csv()?
+        .selected_cell
+        .col;
+    sort_by_column(model, col, ascending)
+}
 
-    let row_content = &content[row_range.clone()];
+// === Header Row ===
 
-    let cell_range = match find_field_byte_range(row_content, edit.position.col, delimiter) {
-        Some(r) => r,
-        None => {
-            tracing::warn!(
-                "Could not find field {} in row {}",
-                edit.position.col,
-                edit.position.row
-            );
-            return;
-        }
-    };
+/// Treat the first row as the header, or as an ordinary row
+fn toggle_header_row(model: &mut AppModel) -> Option<Cmd> {
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    if csv.is_editing() {
+        return None;
+    }
+    csv.toggle_header_row();
+    model.ui.set_status(if csv.has_header_row {
+        "First row is the header"
+    } else {
+        "First row is data"
+    });
+    Some(Cmd::Batch(vec![
+        Cmd::redraw_editor(),
+        Cmd::redraw_status_bar(),
+    ]))
+}
 
-    let abs_start_byte = row_range.start + cell_range.start;
-    let abs_end_byte = row_range.start + cell_range.end;
+// === Filtering ===
+
+/// Show the filter row and type into the selected column's filter, or hide
+/// it and show every row again
+fn toggle_filter_row(model: &mut AppModel) -> Option<Cmd> {
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    if csv.is_editing() {
+        return None;
+    }
+    if csv.show_filter_row {
+        csv.close_filter_row();
+    } else {
+        csv.selection_anchor = None;
+        csv.focus_filter(csv.selected_cell.col);
+    }
+    Some(Cmd::Batch(vec![
+        Cmd::redraw_editor(),
+        Cmd::redraw_status_bar(),
+    ]))
+}
 
-    // `find_row_byte_range`/`find_field_byte_range` operate on `str::char_indices`,
-    // which yields byte offsets. `Rope::remove`/`Rope::insert` expect char offsets,
-    // so convert here before touching the rope. Without this, any multi-byte UTF-8
-    // content (accents, CJK, emoji) at or before the edited cell causes the wrong
-    // range to be mutated, or a panic when the byte offset exceeds `len_chars()`.
-    let abs_start = doc.buffer.byte_to_char(abs_start_byte);
-    let abs_end = doc.buffer.byte_to_char(abs_end_byte);
+/// Type into a column's filter (click on the filter row)
+fn focus_filter(model: &mut AppModel, col: usize) -> Option<Cmd> {
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    if csv.is_editing() {
+        return None;
+    }
+    csv.selection_anchor = None;
+    csv.focus_filter(col);
+    csv.selected_cell.col = csv.filter_focus?;
+    csv.ensure_selection_visible();
+    Some(Cmd::redraw_editor())
+}
 
-    let escaped = escape_csv_value(&edit.new_value, delimiter);
+/// Change the focused filter (typing, Tab, leaving it)
+fn edit_filter(model: &mut AppModel, edit: impl FnOnce(&mut CsvState)) -> Option<Cmd> {
+    let csv = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_csv_mut()?;
+    csv.filter_focus?;
+    edit(csv);
+    Some(Cmd::Batch(vec![
+        Cmd::redraw_editor(),
+        Cmd::redraw_status_bar(),
+    ]))
+}
 
-    doc.buffer.remove(abs_start..abs_end);
-    doc.buffer.insert(abs_start, &escaped);
+// === Rows and Columns ===
 
-    doc.is_modified = true;
-    doc.revision = doc.revision.wrapping_add(1);
+/// Insert an empty row above or below the selected cell and select the
+/// cell in it
+fn insert_row(model: &mut AppModel, below: bool) -> Option<Cmd> {
+    let csv = model.editor_area.focused_editor()?.view_mode.as_csv()?;
+    if csv.is_editing() {
+        return None;
+    }
+    let selected = csv.selected_cell;
+    let at = selected.row + usize::from(below);
+    let content = model.document().buffer.to_string();
+    let new_text = crate::csv::insert_row(&content, at, csv.data.column_count(), csv.delimiter)?;
+    let filtered = csv.is_filtered();
+    let cmd = apply_structure_change(model, &new_text, CellPosition::new(at, selected.col));
+    if filtered {
+        model.ui.set_status("Inserted a row the filter hides");
+        return Some(Cmd::Batch(vec![cmd, Cmd::redraw_status_bar()]));
+    }
+    Some(cmd)
 }
 
-/// Find byte range of a row in the document (excluding newline)
-fn find_row_byte_range(content: &str, row_idx: usize) -> Option<std::ops::Range<usize>> {
-    let mut current_row = 0;
-    let mut row_start = 0;
-
-    for (i, ch) in content.char_indices() {
-        if ch == '\n' {
-            if current_row == row_idx {
-                return Some(row_start..i);
-            }
-            current_row += 1;
-            row_start = i + 1;
-        }
+/// Delete the selected cell's row. The last row left can't be deleted.
+fn delete_row(model: &mut AppModel) -> Option<Cmd> {
+    let csv = model.editor_area.focused_editor()?.view_mode.as_csv()?;
+    if csv.is_editing() {
+        return None;
+    }
+    if csv.data.row_count() <= 1 {
+        model.ui.set_status("Can't delete the only row");
+        return Some(Cmd::redraw_status_bar());
     }
+    let selected = csv.selected_cell;
+    let content = model.document().buffer.to_string();
+    let new_text = crate::csv::delete_row(&content, selected.row, csv.delimiter)?;
+    Some(apply_structure_change(model, &new_text, selected))
+}
 
-    if current_row == row_idx {
-        return Some(row_start..content.len());
+/// Insert an empty column left or right of the selected cell and select
+/// the cell in it
+fn insert_column(model: &mut AppModel, right: bool) -> Option<Cmd> {
+    let csv = model.editor_area.focused_editor()?.view_mode.as_csv()?;
+    if csv.is_editing() {
+        return None;
+    }
+    let selected = csv.selected_cell;
+    let at = selected.col + usize::from(right);
+    let content = model.document().buffer.to_string();
+    let new_text = crate::csv::insert_column(&content, at, csv.delimiter);
+    if let Some(csv) = model.editor_mut().view_mode.as_csv_mut() {
+        csv.shift_manual_widths(at, true);
     }
+    Some(apply_structure_change(
+        model,
+        &new_text,
+        CellPosition::new(selected.row, at),
+    ))
+}
 
-    None
+/// Delete the selected cell's column. The last column left can't be
+/// deleted.
+fn delete_column(model: &mut AppModel) -> Option<Cmd> {
+    let csv = model.editor_area.focused_editor()?.view_mode.as_csv()?;
+    if csv.is_editing() {
+        return None;
+    }
+    if csv.data.column_count() <= 1 {
+        model.ui.set_status("Can't delete the only column");
+        return Some(Cmd::redraw_status_bar());
+    }
+    let selected = csv.selected_cell;
+    let content = model.document().buffer.to_string();
+    let new_text = crate::csv::delete_column(&content, selected.col, csv.delimiter);
+    if let Some(csv) = model.editor_mut().view_mode.as_csv_mut() {
+        csv.shift_manual_widths(selected.col, false);
+    }
+    Some(apply_structure_change(model, &new_text, selected))
 }
 
-/// Find byte range of a field within a CSV row (handles quoted fields)
-fn find_field_byte_range(
-    row: &str,
-    field_idx: usize,
-    delimiter: Delimiter,
-) -> Option<std::ops::Range<usize>> {
-    let delim = delimiter.char();
-    let mut field_start = 0;
-    let mut current_field = 0;
-    let mut in_quotes = false;
-
-    for (i, ch) in row.char_indices() {
-        if ch == '"' {
-            in_quotes = !in_quotes;
-        } else if ch == delim && !in_quotes {
-            if current_field == field_idx {
-                return Some(field_start..i);
+/// Write a row or column change to the document as one undoable edit, then
+/// re-read the grid and select `select` (clamped to the new grid)
+fn apply_structure_change(model: &mut AppModel, new_text: &str, select: CellPosition) -> Cmd {
+    replace_document_text(model, new_text);
+    if let Some(csv) = model.editor_mut().view_mode.as_csv_mut() {
+        match parse_csv(new_text, csv.delimiter) {
+            Ok(data) => {
+                csv.replace_data(data);
+                csv.select_cell(select.row, select.col);
             }
-            current_field += 1;
-            field_start = i + ch.len_utf8();
+            Err(e) => tracing::warn!("Failed to re-parse CSV: {}", e),
         }
     }
+    Cmd::redraw_editor()
+}
 
-    if current_field == field_idx {
-        return Some(field_start..row.len());
+// === Document Sync ===
+
+/// Replace the focused document's text with `new_text` as one undoable
+/// edit covering just the span that differs. The text cursor moves to the
+/// start of that span; other editors on the document keep theirs in step.
+fn replace_document_text(model: &mut AppModel, new_text: &str) {
+    let old: Vec<char> = model.document().buffer.chars().collect();
+    let new: Vec<char> = new_text.chars().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let deleted_text: String = old[prefix..old.len() - suffix].iter().collect();
+    let inserted_text: String = new[prefix..new.len() - suffix].iter().collect();
+    if deleted_text.is_empty() && inserted_text.is_empty() {
+        return;
     }
 
-    None
+    let cursor_before = model.editor().cursors[0];
+    let doc = model.document_mut();
+    let (line, column) = doc.offset_to_cursor(prefix);
+    doc.buffer.remove(prefix..old.len() - suffix);
+    doc.buffer.insert(prefix, &inserted_text);
+    sync_other_editor_cursors_for_deleted_text(model, line, column, &deleted_text);
+    sync_other_editor_cursors_for_text(model, line, column, &inserted_text);
+
+    let editor = model.editor_mut();
+    editor.collapse_to_primary();
+    editor.cursors[0] = Cursor::at(line, column);
+    editor.selections[0] = Selection::new(Position::new(line, column));
+    model.document_mut().push_edit(EditOperation::Replace {
+        position: prefix,
+        deleted_text,
+        inserted_text,
+        cursor_before,
+        cursor_after: Cursor::at(line, column),
+    });
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::csv::CellPosition;
+use crate::model::Document;
 
-    #[test]
-    fn test_find_row_byte_range() {
-        let content = "a,b,c\n1,2,3\nx,y,z";
+/// Sync a cell edit back to the document text buffer
+fn sync_cell_edit_to_document(doc: &mut Document, edit: &CellEdit, delimiter: Delimiter) {
+    let content = doc.buffer.to_string();
 
-        assert_eq!(find_row_byte_range(content, 0), Some(0..5));
-        assert_eq!(find_row_byte_range(content, 1), Some(6..11));
-        assert_eq!(find_row_byte_range(content, 2), Some(12..17));
-        assert_eq!(find_row_byte_range(content, 3), None);
-    }
+    let Some((range, escaped)) = cell_write(&content, edit.position, &edit.new_value, delimiter)
+    else {
+        tracing::warn!(
+            "Could not find cell ({}, {}) in document",
+            edit.position.row,
+            edit.position.col
+        );
+        return;
+    };
 
-    #[test]
-    fn test_find_field_byte_range() {
-        let row = "alice,30,engineer";
+    // `cell_write` returns byte offsets; `Rope::remove`/`Rope::insert` expect
+    // char offsets, so convert here before touching the rope. Without this,
+    // any multi-byte UTF-8 content (accents, CJK, emoji) at or before the
+    // edited cell causes the wrong range to be mutated, or a panic when the
+    // byte offset exceeds `len_chars()`.
+    let abs_start = doc.buffer.byte_to_char(range.start);
+    let abs_end = doc.buffer.byte_to_char(range.end);
+    let deleted_text = doc.buffer.slice(abs_start..abs_end).to_string();
+
+    doc.buffer.remove(abs_start..abs_end);
+    doc.buffer.insert(abs_start, &escaped);
 
-        assert_eq!(find_field_byte_range(row, 0, Delimiter::Comma), Some(0..5));
-        assert_eq!(find_field_byte_range(row, 1, Delimiter::Comma), Some(6..8));
-        assert_eq!(find_field_byte_range(row, 2, Delimiter::Comma), Some(9..17));
-        assert_eq!(find_field_byte_range(row, 3, Delimiter::Comma), None);
+    // Recorded like a text edit, so undo in CSV mode steps back through
+    // cell edits and sorts alike
+    let (line, column) = doc.offset_to_cursor(abs_start);
+    doc.push_edit(EditOperation::Replace {
+        position: abs_start,
+        deleted_text,
+        inserted_text: escaped,
+        cursor_before: Cursor::at(line, column),
+        cursor_after: Cursor::at(line, column),
+    });
+}
+
+// ===== Find/Replace in Cells =====
+
+/// Every cell whose value contains `query`, with the value after replacing
+/// all matches in it, in row-major order
+pub(super) fn cell_replacements(
+    csv: &CsvState,
+    query: &str,
+    replacement: &str,
+    case_sensitive: bool,
+) -> Vec<(CellPosition, String)> {
+    let mut edits = Vec::new();
+    for row in 0..csv.data.row_count() {
+        for (col, value) in csv.data.row_cells(row).enumerate() {
+            if let Some(new_value) = replace_in_cell(value, query, replacement, case_sensitive) {
+                edits.push((CellPosition::new(row, col), new_value));
+            }
+        }
     }
+    edits
+}
 
-    #[test]
-    fn test_find_field_byte_range_quoted() {
-        let row = "\"hello, world\",test,123";
-        // Field 0: "hello, world" (positions 0..14, the comma inside is at index 6)
-        // Delimiter at position 14
-        // Field 1: test (positions 15..19)
-        // Delimiter at position 19
-        // Field 2: 123 (positions 20..23)
-
-        assert_eq!(find_field_byte_range(row, 0, Delimiter::Comma), Some(0..14));
-        assert_eq!(
-            find_field_byte_range(row, 1, Delimiter::Comma),
-            Some(15..19)
-        );
-        assert_eq!(
-            find_field_byte_range(row, 2, Delimiter::Comma),
-            Some(20..23)
-        );
+/// Replace in CSV mode: replace the matches in the selected cell and write it
+/// back like a cell edit, then select the next cell with a match. `false`
+/// when no cell matches.
+pub(super) fn replace_in_cell_and_find_next(
+    model: &mut AppModel,
+    query: &str,
+    replacement: &str,
+    case_sensitive: bool,
+) -> bool {
+    let Some(editor) = model.editor_area.focused_editor_mut() else {
+        return false;
+    };
+    let Some(csv) = editor.view_mode.as_csv_mut() else {
+        return false;
+    };
+    let document_id = editor.document_id;
+    let delimiter = csv.delimiter;
+
+    let selected = csv.selected_cell;
+    let old_value = csv.data.get(selected.row, selected.col).to_string();
+    let edit = replace_in_cell(&old_value, query, replacement, case_sensitive).map(|new_value| {
+        csv.data.set(selected.row, selected.col, &new_value);
+        CellEdit {
+            position: selected,
+            old_value,
+            new_value,
+        }
+    });
+
+    // Next matching cell after the selected one, wrapping around
+    let matches = cell_replacements(csv, query, replacement, case_sensitive);
+    let next = matches
+        .iter()
+        .map(|(position, _)| *position)
+        .find(|position| (position.row, position.col) > (selected.row, selected.col))
+        .or_else(|| matches.first().map(|(position, _)| *position));
+    if let Some(next) = next {
+        csv.select_cell(next.row, next.col);
     }
 
-    #[test]
-    fn test_find_field_byte_range_tab_delimiter() {
-        let row = "a\tb\tc";
+    if let Some(edit) = &edit {
+        if let Some(doc) = document_id.and_then(|id| model.editor_area.documents.get_mut(&id)) {
+            sync_cell_edit_to_document(doc, edit, delimiter);
+        }
+    }
+    edit.is_some() || next.is_some()
+}
 
-        assert_eq!(find_field_byte_range(row, 0, Delimiter::Tab), Some(0..1));
-        assert_eq!(find_field_byte_range(row, 1, Delimiter::Tab), Some(2..3));
-        assert_eq!(find_field_byte_range(row, 2, Delimiter::Tab), Some(4..5));
+/// Re-read the grid after the document changed as text (an applied edit
+/// review), keeping the selected cell
+pub(super) fn refresh_csv_data(model: &mut AppModel) {
+    if !model.editor().view_mode.is_csv() {
+        return;
     }
+    let content = model.document().buffer.to_string();
+    let Some(csv) = model.editor_mut().view_mode.as_csv_mut() else {
+        return;
+    };
+    match parse_csv(&content, csv.delimiter) {
+        Ok(data) => csv.replace_data(data),
+        Err(e) => tracing::warn!("Failed to re-parse CSV: {}", e),
+    }
+}
 
-    /// Regression test: `find_row_byte_range`/`find_field_byte_range` return BYTE
-    /// offsets (from `str::char_indices`), but `Rope::remove`/`Rope::insert` expect
-    /// CHAR offsets. When a row before the edited cell contains multi-byte UTF-8
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Regression test: `cell_write` returns BYTE offsets (from
+    /// `str::char_indices`), but `Rope::remove`/`Rope::insert` expect CHAR
+    /// offsets. When a row before the edited cell contains multi-byte UTF-8
     /// (like "café", where 'é' is 2 bytes but 1 char), byte offsets and char offsets
     /// diverge, and passing raw byte offsets into the rope mutates the wrong range.
     ///
@@ -997,4 +1379,331 @@ mod tests {
         assert_eq!(doc.buffer.to_string(), "name,age\ncafé,31\nbob,25");
         assert!(doc.is_modified);
     }
+
+    fn csv_model(text: &str) -> AppModel {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("people.csv");
+        std::fs::write(&path, text).unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![path]);
+        update_csv(&mut model, CsvMsg::Toggle);
+        model
+    }
+
+    #[test]
+    fn test_sort_by_column_reorders_document_and_undoes_in_one_step() {
+        use crate::messages::{DocumentMsg, Msg};
+
+        let text = "name,age\nann,30\n\"bob, jr\",9\ncid,100\n";
+        let mut model = csv_model(text);
+        update_csv(&mut model, CsvMsg::SelectCell { row: 1, col: 0 });
+
+        update_csv(
+            &mut model,
+            CsvMsg::SortByColumn {
+                col: 1,
+                ascending: false,
+            },
+        );
+        assert_eq!(
+            model.document().buffer.to_string(),
+            "name,age\ncid,100\nann,30\n\"bob, jr\",9\n"
+        );
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.data.get(1, 0), "cid");
+        assert_eq!(csv.sorted_by, Some((1, false)));
+        // The selection follows ann's row
+        assert_eq!(csv.selected_cell, CellPosition::new(2, 0));
+
+        crate::update::update(&mut model, Msg::Document(DocumentMsg::Undo));
+        assert_eq!(model.document().buffer.to_string(), text);
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.data.get(1, 0), "ann");
+        assert_eq!(csv.sorted_by, None);
+    }
+
+    #[test]
+    fn test_undo_in_csv_mode_reverts_cell_edit() {
+        use crate::messages::{DocumentMsg, Msg};
+
+        let mut model = csv_model("a,b\n1,2\n");
+        update_csv(&mut model, CsvMsg::SelectCell { row: 1, col: 1 });
+        update_csv(&mut model, CsvMsg::StartEditingWithChar('7'));
+        update_csv(&mut model, CsvMsg::ConfirmEdit);
+        assert_eq!(model.document().buffer.to_string(), "a,b\n1,7\n");
+
+        crate::update::update(&mut model, Msg::Document(DocumentMsg::Undo));
+        assert_eq!(model.document().buffer.to_string(), "a,b\n1,2\n");
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.data.get(1, 1), "2");
+    }
+
+    #[test]
+    fn test_insert_row_below_selects_it_and_undoes() {
+        use crate::messages::{DocumentMsg, Msg};
+
+        let mut model = csv_model("a,b,c\n1,2,3\n4,5,6\n");
+        update_csv(&mut model, CsvMsg::SelectCell { row: 1, col: 2 });
+        update_csv(&mut model, CsvMsg::InsertRowBelow);
+        assert_eq!(
+            model.document().buffer.to_string(),
+            "a,b,c\n1,2,3\n,,\n4,5,6\n"
+        );
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.data.row_count(), 4);
+        assert_eq!(csv.selected_cell, CellPosition::new(2, 2));
+
+        crate::update::update(&mut model, Msg::Document(DocumentMsg::Undo));
+        assert_eq!(model.document().buffer.to_string(), "a,b,c\n1,2,3\n4,5,6\n");
+        assert_eq!(
+            model.editor().view_mode.as_csv().unwrap().data.row_count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_insert_and_delete_columns() {
+        let mut model = csv_model("a,b\n1,\"x,y\"\n");
+        update_csv(&mut model, CsvMsg::SelectCell { row: 1, col: 1 });
+
+        update_csv(&mut model, CsvMsg::InsertColumnLeft);
+        assert_eq!(model.document().buffer.to_string(), "a,,b\n1,,\"x,y\"\n");
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.data.column_count(), 3);
+        assert_eq!(csv.selected_cell, CellPosition::new(1, 1));
+
+        update_csv(&mut model, CsvMsg::MoveRight);
+        update_csv(&mut model, CsvMsg::DeleteColumn);
+        assert_eq!(model.document().buffer.to_string(), "a,\n1,\n");
+        assert_eq!(model.document().undo_stack.len(), 2);
+    }
+
+    #[test]
+    fn test_shift_arrows_select_a_range_copied_as_tsv() {
+        use crate::messages::{Direction, DocumentMsg, EditorMsg, Msg};
+
+        let mut model = csv_model("a,b,c\n1,2,3\n4,5,6\n");
+        update_csv(&mut model, CsvMsg::SelectCell { row: 1, col: 1 });
+        for direction in [Direction::Down, Direction::Right] {
+            crate::update::update(
+                &mut model,
+                Msg::Editor(EditorMsg::MoveCursorWithSelection(direction)),
+            );
+        }
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(
+            csv.selection_range(),
+            (CellPosition::new(1, 1), CellPosition::new(2, 2))
+        );
+
+        let cmd = crate::update::update(&mut model, Msg::Document(DocumentMsg::Copy));
+        let Some(Cmd::Batch(cmds)) = cmd else {
+            panic!("expected a clipboard command");
+        };
+        assert!(matches!(&cmds[0], Cmd::CopyToClipboard(text) if text == "2\t3\n5\t6"));
+        assert_eq!(model.ui.status_message, "Copied 4 cells");
+        // The document is untouched
+        assert_eq!(model.document().buffer.to_string(), "a,b,c\n1,2,3\n4,5,6\n");
+
+        update_csv(&mut model, CsvMsg::MoveUp);
+        assert!(!model.editor().view_mode.as_csv().unwrap().has_range());
+    }
+
+    #[test]
+    fn test_column_resize_and_auto_fit_keep_widths_after_edits() {
+        let mut model = csv_model("id,description\n1,a fairly long description here\n");
+        model.char_width = 10.0;
+        let widths = |model: &AppModel| {
+            model
+                .editor()
+                .view_mode
+                .as_csv()
+                .unwrap()
+                .column_widths
+                .clone()
+        };
+        assert_eq!(widths(&model), vec![4, 30]);
+
+        update_csv(&mut model, CsvMsg::StartColumnResize { col: 1, x: 500.0 });
+        update_csv(&mut model, CsvMsg::UpdateColumnResize { x: 400.0 });
+        update_csv(&mut model, CsvMsg::EndColumnResize);
+        assert_eq!(widths(&model), vec![4, 20]);
+        assert!(model
+            .editor()
+            .view_mode
+            .as_csv()
+            .unwrap()
+            .column_resize
+            .is_none());
+
+        // Undoing an edit re-reads the grid, keeping the dragged width
+        update_csv(&mut model, CsvMsg::SelectCell { row: 1, col: 0 });
+        update_csv(&mut model, CsvMsg::StartEditingWithChar('2'));
+        update_csv(&mut model, CsvMsg::ConfirmEdit);
+        crate::update::update(
+            &mut model,
+            crate::messages::Msg::Document(crate::messages::DocumentMsg::Undo),
+        );
+        assert_eq!(
+            model.document().buffer.to_string().lines().nth(1).unwrap(),
+            "1,a fairly long description here"
+        );
+        assert_eq!(widths(&model)[1], 20);
+
+        update_csv(&mut model, CsvMsg::AutoFitColumn(1));
+        assert_eq!(widths(&model), vec![4, 30]);
+    }
+
+    #[test]
+    fn test_filtered_rows_write_back_to_their_records() {
+        use crate::messages::Msg;
+        use crate::model::{SegmentContent, SegmentId};
+
+        let mut model = csv_model("name,city\nann,Oslo\nbob,Bergen\ncid,oslo\n");
+        update_csv(&mut model, CsvMsg::SelectCell { row: 1, col: 1 });
+        crate::update::update(&mut model, Msg::Csv(CsvMsg::ToggleFilterRow));
+        assert!(model.is_csv_filtering());
+        for ch in "OSLO".chars() {
+            crate::update::update(&mut model, Msg::Csv(CsvMsg::FilterInsertChar(ch)));
+        }
+        let rows = model.ui.status_bar.get_segment(SegmentId::CsvRows);
+        assert_eq!(
+            rows.map(|segment| &segment.content),
+            Some(&SegmentContent::Text("2/3 rows".into()))
+        );
+
+        // Moving down from ann skips bob's hidden row, and the edit lands on cid
+        update_csv(&mut model, CsvMsg::LeaveFilter);
+        update_csv(&mut model, CsvMsg::MoveDown);
+        update_csv(&mut model, CsvMsg::StartEditingWithChar('X'));
+        update_csv(&mut model, CsvMsg::ConfirmEdit);
+        assert_eq!(
+            model.document().buffer.to_string(),
+            "name,city\nann,Oslo\nbob,Bergen\ncid,X\n"
+        );
+
+        crate::update::update(&mut model, Msg::Csv(CsvMsg::ToggleFilterRow));
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert!(!csv.show_filter_row);
+        assert_eq!(csv.visible_row_count(), 4);
+        let rows = model.ui.status_bar.get_segment(SegmentId::CsvRows);
+        assert_eq!(
+            rows.map(|segment| &segment.content),
+            Some(&SegmentContent::Empty)
+        );
+    }
+
+    #[test]
+    fn test_header_row_toggle_lets_first_row_sort_and_filter() {
+        let mut model = csv_model("b\nc\na\n");
+        update_csv(
+            &mut model,
+            CsvMsg::SortByColumn {
+                col: 0,
+                ascending: true,
+            },
+        );
+        assert_eq!(model.document().buffer.to_string(), "b\na\nc\n");
+        assert_eq!(model.ui.status_message, "Sorted by b (ascending)");
+
+        update_csv(&mut model, CsvMsg::ToggleHeaderRow);
+        assert_eq!(model.ui.status_message, "First row is data");
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.column_name(0), "A");
+        assert_eq!(csv.sorted_by, None);
+
+        update_csv(
+            &mut model,
+            CsvMsg::SortByColumn {
+                col: 0,
+                ascending: true,
+            },
+        );
+        assert_eq!(model.document().buffer.to_string(), "a\nb\nc\n");
+
+        update_csv(&mut model, CsvMsg::ToggleFilterRow);
+        update_csv(&mut model, CsvMsg::FilterInsertChar('c'));
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.row_map, Some(vec![2]));
+    }
+
+    #[test]
+    fn test_delete_row_refuses_the_last_one() {
+        let mut model = csv_model("only,row\n");
+        update_csv(&mut model, CsvMsg::DeleteRow);
+        assert_eq!(model.document().buffer.to_string(), "only,row\n");
+        assert_eq!(model.ui.status_message, "Can't delete the only row");
+    }
+
+    #[test]
+    fn test_header_click_direction_toggles() {
+        let mut model = csv_model("a,b\n2,x\n1,y\n");
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert!(csv.header_click_ascending(0));
+
+        update_csv(
+            &mut model,
+            CsvMsg::SortByColumn {
+                col: 0,
+                ascending: true,
+            },
+        );
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert!(!csv.header_click_ascending(0));
+        assert!(csv.header_click_ascending(1));
+        assert_eq!(model.document().buffer.to_string(), "a,b\n1,y\n2,x\n");
+    }
+
+    fn open_replace(model: &mut AppModel, query: &str, replacement: &str) {
+        let mut state = crate::model::FindReplaceState::default();
+        state.set_query(query);
+        state.set_replacement(replacement);
+        model
+            .ui
+            .open_modal(crate::model::ModalState::FindReplace(Box::new(state)));
+    }
+
+    #[test]
+    fn test_replace_all_in_cells_requotes_changed_cells() {
+        use crate::messages::{ModalMsg, UiMsg};
+        use crate::update::update_ui;
+
+        let mut model = csv_model("name,city\nann,\"Oslo\"\n\"bob, jr\",Bergen\n");
+        open_replace(&mut model, "o", "o, \"O\"");
+        if let Some(crate::model::ModalState::FindReplace(state)) = &mut model.ui.active_modal {
+            state.case_sensitive = true;
+        }
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::ReplaceAll));
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::Confirm));
+
+        // Each changed cell is escaped on its own; the header is untouched
+        assert_eq!(
+            model.document().buffer.to_string(),
+            "name,city\nann,\"Oslo, \"\"O\"\"\"\n\"bo, \"\"O\"\"b, jr\",Bergen\n"
+        );
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.data.get(1, 1), "Oslo, \"O\"");
+        assert_eq!(csv.data.get(2, 0), "bo, \"O\"b, jr");
+        assert_eq!(csv.data.column_count(), 2);
+    }
+
+    #[test]
+    fn test_replace_in_selected_cell_then_select_next_match() {
+        use crate::messages::{ModalMsg, UiMsg};
+        use crate::update::update_ui;
+
+        let mut model = csv_model("a,b\nx,1\ny,x\n");
+        open_replace(&mut model, "x", "p,q");
+
+        // The selected header cell doesn't match: only moves to the next match
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::ReplaceAndFindNext));
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.selected_cell, CellPosition::new(1, 0));
+        assert_eq!(model.document().buffer.to_string(), "a,b\nx,1\ny,x\n");
+
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::ReplaceAndFindNext));
+        let csv = model.editor().view_mode.as_csv().unwrap();
+        assert_eq!(csv.selected_cell, CellPosition::new(2, 1));
+        assert_eq!(csv.data.get(1, 0), "p,q");
+        assert_eq!(model.document().buffer.to_string(), "a,b\n\"p,q\",1\ny,x\n");
+    }
 }
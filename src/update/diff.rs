@@ -0,0 +1,288 @@
+//! Diff view update handlers
+//!
+//! Opens, scrolls and closes the side-by-side diff view, and keeps open
+//! diff views in step with edits to their documents.
+
+use crate::commands::Cmd;
+use crate::diff::{DiffBase, DiffState};
+use crate::git::{HeadVersion, Repository};
+use crate::messages::DiffMsg;
+use crate::model::editor::TabContent;
+use crate::model::{AppModel, ViewMode};
+
+pub fn update_diff(model: &mut AppModel, msg: DiffMsg) -> Option<Cmd> {
+    match msg {
+        DiffMsg::CompareWithSaved => compare_with_saved(model),
+        DiffMsg::CompareWithHead => compare_with_head(model),
+        DiffMsg::CompareWithOtherSplit => compare_with_other_split(model),
+        DiffMsg::NextChange => move_to_change(model, true),
+        DiffMsg::PrevChange => move_to_change(model, false),
+        DiffMsg::Scroll(delta) => scroll(model, |diff, page| diff.scroll(delta, page)),
+        DiffMsg::ScrollHorizontal(delta) => scroll(model, |diff, _| diff.scroll_horizontal(delta)),
+        DiffMsg::PageUp => scroll(model, |diff, page| diff.scroll(-(page as i32), page)),
+        DiffMsg::PageDown => scroll(model, |diff, page| diff.scroll(page as i32, page)),
+        DiffMsg::Top => scroll(model, |diff, page| diff.scroll(i32::MIN, page)),
+        DiffMsg::Bottom => scroll(model, |diff, page| diff.scroll(i32::MAX, page)),
+        DiffMsg::Close => close(model),
+    }
+}
+
+/// Diff the focused document against its file as it is on disk now
+fn compare_with_saved(model: &mut AppModel) -> Option<Cmd> {
+    if !can_diff(model) {
+        return Some(Cmd::redraw_status_bar());
+    }
+    let Some(path) = model.document().file_path.clone() else {
+        model.ui.set_status("No saved version to compare with");
+        return Some(Cmd::redraw_status_bar());
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(saved) => open_diff(model, DiffBase::Saved, "Saved".to_string(), saved),
+        Err(e) => {
+            model
+                .ui
+                .set_status(format!("Error reading {}: {}", path.display(), e));
+            Some(Cmd::redraw_status_bar())
+        }
+    }
+}
+
+/// Diff the focused document against its HEAD version, reading that if the
+/// gutter diff hasn't yet
+fn compare_with_head(model: &mut AppModel) -> Option<Cmd> {
+    if !can_diff(model) {
+        return Some(Cmd::redraw_status_bar());
+    }
+    // Reading HEAD runs git, which an untrusted workspace can configure
+    if !model.workspace_trusted() {
+        model
+            .ui
+            .set_status("Comparing with HEAD needs a trusted workspace");
+        return Some(Cmd::redraw_status_bar());
+    }
+    let document = model.document();
+    let head = match (&document.git_head, &document.file_path) {
+        (HeadVersion::Loaded(head), _) => Some(head.to_string()),
+        (HeadVersion::Unknown, Some(path)) => {
+            Repository::discover(path).and_then(|repo| repo.head_file(path))
+        }
+        _ => None,
+    };
+    match head {
+        Some(head) => open_diff(model, DiffBase::Head, "HEAD".to_string(), head),
+        None => {
+            let name = model.document().display_name();
+            model.ui.set_status(format!("{} is not committed", name));
+            Some(Cmd::redraw_status_bar())
+        }
+    }
+}
+
+/// Diff the focused document against the one shown in the split before it
+fn compare_with_other_split(model: &mut AppModel) -> Option<Cmd> {
+    if !can_diff(model) {
+        return Some(Cmd::redraw_status_bar());
+    }
+    let other = super::layout::adjacent_group_id(model, false)
+        .and_then(|group_id| model.editor_area.groups.get(&group_id))
+        .and_then(|group| group.active_editor_id())
+        .and_then(|editor_id| model.editor_area.editors.get(&editor_id))
+        .filter(|editor| matches!(editor.tab_content, TabContent::Text))
+        .and_then(|editor| editor.document_id);
+    let Some(other) = other else {
+        model
+            .ui
+            .set_status("No document in another split to compare with");
+        return Some(Cmd::redraw_status_bar());
+    };
+    if model.editor_area.focused_document_id() == Some(other) {
+        model
+            .ui
+            .set_status("The other split shows the same document");
+        return Some(Cmd::redraw_status_bar());
+    }
+    let document = model.editor_area.documents.get(&other)?;
+    let (title, text) = (document.display_name(), document.buffer.to_string());
+    open_diff(model, DiffBase::Document(other), title, text)
+}
+
+/// Whether the focused tab shows a text document the diff view can show;
+/// sets the status when it can't
+fn can_diff(model: &mut AppModel) -> bool {
+    let shows_text = model.editor_area.focused_editor().is_some_and(|editor| {
+        matches!(editor.tab_content, TabContent::Text)
+            && matches!(editor.view_mode, ViewMode::Text | ViewMode::Diff(_))
+    });
+    if !shows_text {
+        model.ui.set_status("Only text documents can be compared");
+        return false;
+    }
+    // Diffing a large file on every edit would stall the editor
+    if model.document().large_file {
+        model.ui.set_status("Large files can't be compared");
+        return false;
+    }
+    true
+}
+
+/// Show the focused document in a diff view against `base_text`, scrolled
+/// to the first change
+fn open_diff(
+    model: &mut AppModel,
+    base: DiffBase,
+    base_title: String,
+    base_text: String,
+) -> Option<Cmd> {
+    let document = model.editor_area.focused_document()?;
+    let mut diff = DiffState::new(
+        base,
+        base_title,
+        document.display_name(),
+        base_text,
+        &document.buffer.to_string(),
+        document.revision,
+    );
+    if let DiffBase::Document(id) = base {
+        diff.base_revision = model.editor_area.documents.get(&id).map(|doc| doc.revision);
+    }
+    let status = if diff.move_to_change(true) {
+        let (added, removed) = diff.line_counts();
+        format!(
+            "Comparing with {}: +{} −{} lines",
+            diff.base_title, added, removed
+        )
+    } else {
+        format!("No differences from {}", diff.base_title)
+    };
+    model.editor_area.focused_editor_mut()?.view_mode = ViewMode::Diff(Box::new(diff));
+    model.ui.set_status(status);
+    Some(Cmd::Redraw)
+}
+
+/// Scroll to the next or previous change
+fn move_to_change(model: &mut AppModel, forward: bool) -> Option<Cmd> {
+    let diff = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_diff_mut()?;
+    if !diff.move_to_change(forward) {
+        let status = if diff.change_starts().is_empty() {
+            "No changes"
+        } else if forward {
+            "No more changes below"
+        } else {
+            "No more changes above"
+        };
+        model.ui.set_status(status);
+        return Some(Cmd::redraw_status_bar());
+    }
+    Some(Cmd::Batch(vec![
+        Cmd::redraw_editor(),
+        Cmd::redraw_status_bar(),
+    ]))
+}
+
+fn scroll(model: &mut AppModel, apply: impl FnOnce(&mut DiffState, usize)) -> Option<Cmd> {
+    let page = visible_rows(model);
+    let diff = model
+        .editor_area
+        .focused_editor_mut()?
+        .view_mode
+        .as_diff_mut()?;
+    apply(diff, page);
+    Some(Cmd::redraw_editor())
+}
+
+/// Rows that fit below the title strip of the focused group's diff view
+fn visible_rows(model: &AppModel) -> usize {
+    let line_height = model.line_height.max(1);
+    let height = model
+        .editor_area
+        .focused_group()
+        .map_or(0, |group| group.rect.height as usize);
+    (height.saturating_sub(model.metrics.header_height() + line_height) / line_height).max(1)
+}
+
+/// Back to the text view
+fn close(model: &mut AppModel) -> Option<Cmd> {
+    let editor = model.editor_area.focused_editor_mut()?;
+    if !editor.view_mode.is_diff() {
+        return None;
+    }
+    editor.view_mode = ViewMode::Text;
+    Some(Cmd::Redraw)
+}
+
+/// Diff again wherever a diff view's document, or the document it is
+/// compared with, changed since its rows were made. Returns whether any
+/// view changed.
+pub(super) fn sync_diff_views(model: &mut AppModel) -> bool {
+    let documents = &model.editor_area.documents;
+    let mut changed = false;
+    for editor in model.editor_area.editors.values_mut() {
+        let Some(diff) = editor.view_mode.as_diff_mut() else {
+            continue;
+        };
+        let Some(document) = editor.document_id.and_then(|id| documents.get(&id)) else {
+            continue;
+        };
+        // A closed base document leaves its last text behind
+        let base = match diff.base {
+            DiffBase::Document(id) => documents.get(&id),
+            DiffBase::Saved | DiffBase::Head => None,
+        };
+        let base_revision = base.map(|doc| doc.revision);
+        if diff.revision == document.revision && diff.base_revision == base_revision {
+            continue;
+        }
+        if let Some(base) = base {
+            diff.base_text = base.buffer.to_string();
+        }
+        diff.base_revision = base_revision;
+        diff.refresh(&document.buffer.to_string(), document.revision);
+        changed = true;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::messages::{DiffMsg, DocumentMsg, EditorMsg, Msg};
+    use crate::model::AppModel;
+    use crate::update::update;
+
+    fn model_with_file(saved: &str, text: &str) -> (tempfile::TempDir, AppModel) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, saved).unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![path]);
+        model.document_mut().buffer = ropey::Rope::from(text);
+        model.document_mut().revision += 1;
+        (dir, model)
+    }
+
+    #[test]
+    fn test_compare_with_saved_follows_edits_and_closes() {
+        let (_dir, mut model) = model_with_file("one\ntwo\nthree\n", "one\n2\nthree\n");
+        update(&mut model, Msg::Diff(DiffMsg::CompareWithSaved));
+        let diff = model.editor().view_mode.as_diff().unwrap();
+        assert_eq!(diff.change_starts(), [1]);
+        assert_eq!(diff.current_change, Some(0));
+
+        // Typing is blocked in the diff view; the arrows scroll it
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+        assert_eq!(model.document().buffer.to_string(), "one\n2\nthree\n");
+
+        // An edit made elsewhere shows up in the diff
+        model.document_mut().buffer = ropey::Rope::from("one\ntwo\nthree\nfour\n");
+        model.document_mut().revision += 1;
+        update(&mut model, Msg::Editor(EditorMsg::Scroll(1)));
+        let diff = model.editor().view_mode.as_diff().unwrap();
+        assert_eq!(diff.change_starts(), [3]);
+        assert_eq!(diff.line_counts(), (1, 0));
+
+        update(&mut model, Msg::Diff(DiffMsg::Close));
+        assert!(!model.editor().view_mode.is_diff());
+    }
+}
@@ -29,8 +29,7 @@ fn next_terminal_session_id(model: &AppModel) -> usize {
 }
 
 fn is_terminal_panel_open(model: &AppModel) -> bool {
-    let dock = &model.dock_layout.bottom;
-    dock.is_open && dock.active_panel() == Some(PanelId::TERMINAL)
+    model.dock_layout.bottom.is_panel_visible(PanelId::TERMINAL)
 }
 
 fn terminal_grid_size_for_model(model: &AppModel) -> Option<TerminalGridSize> {
@@ -115,8 +114,82 @@ pub(super) fn with_terminal_sync(model: &mut AppModel, cmd: Cmd) -> Cmd {
     Cmd::Batch(cmds)
 }
 
+/// Kick off the first TODO scan once the TODO panel becomes visible
+fn with_todo_scan(model: &mut AppModel, cmd: Option<Cmd>) -> Option<Cmd> {
+    if !super::todo::is_todo_panel_visible(model)
+        || model.todo_panel.has_scanned
+        || model.todo_panel.is_scanning
+    {
+        return cmd;
+    }
+
+    let scan = super::todo::refresh_todos(model);
+    Some(match cmd {
+        Some(cmd) => {
+            let mut cmds = Vec::new();
+            push_command(&mut cmds, cmd);
+            cmds.push(scan);
+            Cmd::Batch(cmds)
+        }
+        None => scan,
+    })
+}
+
+/// Focusable areas in cycling order: the editor, then every open, expanded
+/// dock with panels (left, bottom, right)
+fn focus_areas(model: &AppModel) -> Vec<FocusTarget> {
+    let mut areas = vec![FocusTarget::Editor];
+    for position in [
+        DockPosition::Left,
+        DockPosition::Bottom,
+        DockPosition::Right,
+    ] {
+        let dock = model.dock_layout.dock(position);
+        if dock.is_open && !dock.is_collapsed && dock.has_panels() {
+            areas.push(match position {
+                DockPosition::Left => FocusTarget::Sidebar,
+                _ => FocusTarget::Dock(position),
+            });
+        }
+    }
+    areas
+}
+
+/// Move focus `step` areas forward (or backward if negative)
+fn cycle_focus_area(model: &mut AppModel, step: isize) -> Option<Cmd> {
+    let areas = focus_areas(model);
+    let current = areas
+        .iter()
+        .position(|area| *area == model.ui.focus)
+        .unwrap_or(0);
+    let next = (current as isize + step).rem_euclid(areas.len() as isize) as usize;
+    model.ui.focus = areas[next];
+    Some(Cmd::Redraw)
+}
+
+/// Largest size (logical px) a dock may take in the current window
+fn max_dock_size(model: &AppModel, position: DockPosition) -> f32 {
+    let dimension = match position {
+        DockPosition::Left | DockPosition::Right => model.window_size.0,
+        DockPosition::Bottom => model.window_size.1,
+    };
+    physical_dimension_to_logical(dimension, model.metrics.scale_factor)
+        * model.metrics.dock_max_size_fraction
+}
+
+/// Clamp a dock size (logical px) to the min/max from `ScaledMetrics`
+fn clamp_dock_size(model: &AppModel, position: DockPosition, size: f32) -> f32 {
+    let min_size = model.metrics.dock_min_size_logical;
+    size.clamp(min_size, max_dock_size(model, position).max(min_size))
+}
+
 /// Update function for dock messages
 pub fn update_dock(model: &mut AppModel, msg: DockMsg) -> Option<Cmd> {
+    let cmd = update_dock_inner(model, msg);
+    with_todo_scan(model, cmd)
+}
+
+fn update_dock_inner(model: &mut AppModel, msg: DockMsg) -> Option<Cmd> {
     match msg {
         DockMsg::FocusOrTogglePanel(panel_id) => {
             let is_dock_focused = |pos: DockPosition| -> bool {
@@ -238,6 +311,12 @@ pub fn update_dock(model: &mut AppModel, msg: DockMsg) -> Option<Cmd> {
             if position == DockPosition::Left {
                 sync_workspace_with_dock(model);
             }
+            // Don't leave keyboard focus in a dock that was just hidden
+            if !model.dock_layout.dock(position).is_open
+                && model.ui.focused_dock() == Some(position)
+            {
+                model.ui.focus = FocusTarget::Editor;
+            }
             model.recalculate_viewports();
             Some(with_terminal_sync(model, Cmd::Redraw))
         }
@@ -307,53 +386,72 @@ pub fn update_dock(model: &mut AppModel, msg: DockMsg) -> Option<Cmd> {
                     coord - resize_state.start_x,
                     model.metrics.scale_factor,
                 );
-                let new_width = (resize_state.original_width as f64 + delta)
-                    .max(model.dock_layout.left.min_size() as f64)
-                    as f32;
-                let max_width =
-                    physical_dimension_to_logical(model.window_size.0, model.metrics.scale_factor)
-                        * model.dock_layout.left.max_size_fraction();
-                model.dock_layout.left.size_logical = new_width.min(max_width);
+                let new_width = (resize_state.original_width as f64 + delta) as f32;
+                model.dock_layout.left.size_logical =
+                    clamp_dock_size(model, DockPosition::Left, new_width);
                 sync_workspace_with_dock(model);
                 model.recalculate_viewports();
             }
 
             if let Some(ref resize_state) = model.ui.dock_resize {
-                if resize_state.position != DockPosition::Left {
-                    let dock = model.dock_layout.dock_mut(resize_state.position);
-                    let delta = match resize_state.position {
+                let position = resize_state.position;
+                if position != DockPosition::Left {
+                    let delta = match position {
                         DockPosition::Right | DockPosition::Bottom => {
                             resize_state.start_coord - coord
                         }
                         DockPosition::Left => coord - resize_state.start_coord,
                     };
                     let delta = physical_delta_to_logical(delta, model.metrics.scale_factor);
-                    let min_size = dock.min_size();
-                    let max_size = match resize_state.position {
-                        DockPosition::Right => {
-                            physical_dimension_to_logical(
-                                model.window_size.0,
-                                model.metrics.scale_factor,
-                            ) * dock.max_size_fraction()
-                        }
-                        DockPosition::Bottom => {
-                            physical_dimension_to_logical(
-                                model.window_size.1,
-                                model.metrics.scale_factor,
-                            ) * dock.max_size_fraction()
-                        }
-                        DockPosition::Left => dock.size_logical,
-                    };
-                    let new_size = (resize_state.original_size as f64 + delta)
-                        .clamp(min_size as f64, max_size as f64)
-                        as f32;
-                    dock.size_logical = new_size;
+                    let new_size = (resize_state.original_size as f64 + delta) as f32;
+                    let new_size = clamp_dock_size(model, position, new_size);
+                    model.dock_layout.dock_mut(position).size_logical = new_size;
                     model.recalculate_viewports();
                 }
             }
             Some(with_terminal_sync(model, Cmd::Redraw))
         }
 
+        DockMsg::ResizeFocusedDock { delta } => {
+            let Some(position) = model.ui.focused_dock() else {
+                model.ui.set_status("No panel focused to resize");
+                return Some(Cmd::Redraw);
+            };
+            let dock = model.dock_layout.dock(position);
+            if !dock.is_open || dock.is_collapsed {
+                return None;
+            }
+            let new_size = clamp_dock_size(model, position, dock.size_logical + delta);
+            model.dock_layout.dock_mut(position).size_logical = new_size;
+            if position == DockPosition::Left {
+                sync_workspace_with_dock(model);
+            }
+            model.recalculate_viewports();
+            Some(with_terminal_sync(model, Cmd::Redraw))
+        }
+
+        DockMsg::ToggleCollapsed(position) => {
+            let dock = model.dock_layout.dock_mut(position);
+            if !dock.can_collapse() || !dock.is_open {
+                return None;
+            }
+            dock.toggle_collapsed();
+            if dock.is_collapsed {
+                // The icon strip has no keyboard interaction
+                if model.ui.focused_dock() == Some(position) {
+                    model.ui.focus = FocusTarget::Editor;
+                }
+            } else {
+                model.ui.focus = FocusTarget::Dock(position);
+            }
+            model.recalculate_viewports();
+            Some(with_terminal_sync(model, Cmd::Redraw))
+        }
+
+        DockMsg::FocusNextArea => cycle_focus_area(model, 1),
+
+        DockMsg::FocusPreviousArea => cycle_focus_area(model, -1),
+
         DockMsg::EndResize => {
             model.ui.sidebar_resize = None;
             model.ui.dock_resize = None;
@@ -563,4 +661,83 @@ mod tests {
         assert!(model.ui.sidebar_resize.is_none());
         assert!(model.ui.dock_resize.is_none());
     }
+
+    #[test]
+    fn resizing_focused_dock_from_keyboard_is_clamped() {
+        let mut model = test_model();
+        update_dock(&mut model, DockMsg::ActivatePanel(PanelId::OUTLINE));
+        let original_size = model.dock_layout.right.size_logical;
+
+        update_dock(&mut model, DockMsg::ResizeFocusedDock { delta: 20.0 });
+        assert_eq!(model.dock_layout.right.size_logical, original_size + 20.0);
+
+        update_dock(&mut model, DockMsg::ResizeFocusedDock { delta: 10_000.0 });
+        assert_eq!(model.dock_layout.right.size_logical, 400.0);
+
+        update_dock(&mut model, DockMsg::ResizeFocusedDock { delta: -10_000.0 });
+        assert_eq!(
+            model.dock_layout.right.size_logical,
+            model.metrics.dock_min_size_logical
+        );
+    }
+
+    #[test]
+    fn focus_cycles_through_editor_and_open_docks() {
+        let mut model = test_model();
+        model.dock_layout.bottom.activate(PanelId::TERMINAL);
+        assert_eq!(model.ui.focus, FocusTarget::Editor);
+
+        update_dock(&mut model, DockMsg::FocusNextArea);
+        assert_eq!(model.ui.focus, FocusTarget::Sidebar);
+        update_dock(&mut model, DockMsg::FocusNextArea);
+        assert_eq!(model.ui.focus, FocusTarget::Dock(DockPosition::Bottom));
+        // Right dock is closed, so focus wraps back to the editor
+        update_dock(&mut model, DockMsg::FocusNextArea);
+        assert_eq!(model.ui.focus, FocusTarget::Editor);
+
+        update_dock(&mut model, DockMsg::FocusPreviousArea);
+        assert_eq!(model.ui.focus, FocusTarget::Dock(DockPosition::Bottom));
+    }
+
+    #[test]
+    fn toggling_focused_dock_closed_returns_focus_to_editor() {
+        let mut model = test_model();
+        update_dock(&mut model, DockMsg::ActivatePanel(PanelId::OUTLINE));
+        assert_eq!(model.ui.focus, FocusTarget::Dock(DockPosition::Right));
+
+        update_dock(&mut model, DockMsg::ToggleDock(DockPosition::Right));
+        assert!(!model.dock_layout.right.is_open);
+        assert_eq!(model.ui.focus, FocusTarget::Editor);
+    }
+
+    #[test]
+    fn collapsing_dock_keeps_size_and_moves_focus_to_editor() {
+        let mut model = test_model();
+        update_dock(&mut model, DockMsg::ActivatePanel(PanelId::OUTLINE));
+        let size = model.dock_layout.right.size_logical;
+
+        update_dock(&mut model, DockMsg::ToggleCollapsed(DockPosition::Right));
+        assert!(model.dock_layout.right.is_collapsed);
+        assert_eq!(model.ui.focus, FocusTarget::Editor);
+        let layout = WindowLayout::compute(&model, model.line_height);
+        assert_eq!(
+            layout.right_dock_rect.unwrap().width,
+            model.metrics.dock_icon_strip_size as f32
+        );
+
+        // Clicking a strip icon restores the dock at its previous size
+        update_dock(&mut model, DockMsg::ActivatePanel(PanelId::AI_CHAT));
+        assert!(!model.dock_layout.right.is_collapsed);
+        assert_eq!(model.dock_layout.right.size_logical, size);
+        assert_eq!(model.ui.focus, FocusTarget::Dock(DockPosition::Right));
+    }
+
+    #[test]
+    fn collapsed_terminal_dock_does_not_resize_terminal() {
+        let mut model = test_model();
+        update_dock(&mut model, DockMsg::TogglePanel(PanelId::TERMINAL));
+
+        let cmd = update_dock(&mut model, DockMsg::ToggleCollapsed(DockPosition::Bottom));
+        assert!(matches!(cmd, Some(Cmd::Redraw)));
+    }
 }
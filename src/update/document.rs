@@ -1,8 +1,15 @@
 //! Document update functions for text editing and undo/redo
 
+use std::ops::Range;
+
 use crate::commands::Cmd;
+use crate::config::IndentStyle;
+use crate::indent;
 use crate::messages::DocumentMsg;
-use crate::model::{AppModel, Cursor, EditOperation, Position, Selection};
+use crate::model::{
+    AppModel, Cursor, EditOperation, Position, SearchHighlight, SearchRegex, Selection,
+};
+use crate::syntax::{tags, HIGHLIGHT_NAMES};
 use crate::util::char_type;
 
 use super::editor::{
@@ -26,6 +33,236 @@ fn surround_pair(open: char) -> Option<char> {
     })
 }
 
+/// Name of the tag to complete when the user types `/` right after a `<`.
+///
+/// Only applies to a single cursor in a markup-capable language.
+fn auto_close_tag_name(model: &AppModel) -> Option<String> {
+    if !model.config.auto_close_tags || model.editor().has_multiple_cursors() {
+        return None;
+    }
+    let doc = model.document();
+    if !tags::supports_tags(doc.language) {
+        return None;
+    }
+    let pos = model.cursor_buffer_position();
+    if pos == 0 || doc.buffer.char(pos - 1) != '<' {
+        return None;
+    }
+    let source = doc.buffer.to_string();
+    tags::closing_tag_name(doc.language, &source, doc.buffer.char_to_byte(pos - 1))
+}
+
+/// Re-indent the block closed by the `}` just typed before the cursor.
+///
+/// Recorded as its own undo step: undo keeps the `}` and restores the
+/// original indentation.
+fn reindent_closed_block(model: &mut AppModel) {
+    let doc = model.document();
+    if !model.config.reindent_on_close_brace || !doc.language.has_brace_blocks() {
+        return;
+    }
+    let close_offset = model.cursor_buffer_position() - 1;
+    let unit = indent::detect_indent_unit(&doc.buffer);
+    let highlights = doc.syntax_highlights.as_ref();
+    let changes = indent::reindent_closed_block(&doc.buffer, close_offset, &unit, |line, col| {
+        highlights
+            .and_then(|highlights| highlights.get_line(line))
+            .and_then(|line| line.highlight_at(col))
+            .and_then(|id| HIGHLIGHT_NAMES.get(id as usize))
+            .is_some_and(|name| name.starts_with("string") || *name == "comment")
+    });
+    if changes.is_empty() {
+        return;
+    }
+
+    let cursor_before = *model.editor().primary_cursor();
+    let mut operations = Vec::new();
+    let mut line_deltas = std::collections::HashMap::new();
+    for change in changes.iter().rev() {
+        let start = model.document().buffer.line_to_char(change.line);
+        let deleted_text: String = model
+            .document()
+            .buffer
+            .slice(start..start + change.old_len)
+            .chars()
+            .collect();
+        let buffer = &mut model.document_mut().buffer;
+        buffer.remove(start..start + change.old_len);
+        buffer.insert(start, &change.indent);
+
+        let new_len = change.indent.chars().count();
+        line_deltas.insert(change.line, new_len as isize - change.old_len as isize);
+        operations.push(EditOperation::Replace {
+            position: start,
+            deleted_text,
+            inserted_text: change.indent.clone(),
+            cursor_before: Cursor::at(change.line, change.old_len),
+            cursor_after: Cursor::at(change.line, new_len),
+        });
+    }
+
+    let delta = line_deltas.get(&cursor_before.line).copied().unwrap_or(0);
+    let column = cursor_before.column.saturating_add_signed(delta);
+    let pos = model
+        .document()
+        .cursor_to_offset(cursor_before.line, column);
+    model.set_cursor_from_position(pos);
+    model.ensure_cursor_visible();
+    let cursor_after = *model.editor().primary_cursor();
+    model.document_mut().push_edit(EditOperation::Batch {
+        operations,
+        cursors_before: vec![cursor_before],
+        cursors_after: vec![cursor_after],
+    });
+    super::editor::sync_other_editor_cursors_for_line_shifts(model, &line_deltas);
+}
+
+/// A paired tag name captured before an edit, so the edit can be mirrored
+/// onto the partner tag afterwards (linked tag editing).
+struct LinkedTagEdit {
+    /// Char range of the tag name being edited
+    own: Range<usize>,
+    /// Char range of the paired tag name
+    partner: Range<usize>,
+    /// Buffer length in chars before the edit
+    len_before: usize,
+    /// Undo stack depth before the edit
+    undo_depth: usize,
+}
+
+/// Capture the tag pair under the cursor if `msg` edits a tag name.
+///
+/// A cheap text check (cursor inside `<name` or `</name`) runs first so the
+/// tree-sitter parse only happens when the cursor is actually in a tag name.
+fn linked_tag_edit_target(model: &AppModel, msg: &DocumentMsg) -> Option<LinkedTagEdit> {
+    if !model.config.linked_tag_editing
+        || model.editor().has_multiple_cursors()
+        || !model.editor().primary_selection().is_empty()
+    {
+        return None;
+    }
+    let doc = model.document();
+    if !tags::supports_tags(doc.language) {
+        return None;
+    }
+
+    let pos = model.cursor_buffer_position();
+    let buffer = &doc.buffer;
+    let probe = match msg {
+        DocumentMsg::InsertChar(ch) if tags::is_tag_name_char(*ch) => pos,
+        DocumentMsg::DeleteBackward if pos > 0 => pos - 1,
+        DocumentMsg::DeleteForward if pos < buffer.len_chars() => pos,
+        _ => return None,
+    };
+    if !matches!(msg, DocumentMsg::InsertChar(_)) && !tags::is_tag_name_char(buffer.char(probe)) {
+        return None;
+    }
+
+    let mut start = pos;
+    while start > 0 && tags::is_tag_name_char(buffer.char(start - 1)) {
+        start -= 1;
+    }
+    let opens_tag = match start {
+        0 => false,
+        1 => buffer.char(0) == '<',
+        _ => {
+            buffer.char(start - 1) == '<'
+                || (buffer.char(start - 1) == '/' && buffer.char(start - 2) == '<')
+        }
+    };
+    if !opens_tag {
+        return None;
+    }
+
+    let source = buffer.to_string();
+    let (own, partner) = tags::linked_tag_ranges(doc.language, &source, buffer.char_to_byte(pos))?;
+    let own = buffer.byte_to_char(own.start)..buffer.byte_to_char(own.end);
+    let partner = buffer.byte_to_char(partner.start)..buffer.byte_to_char(partner.end);
+
+    // The edit must land inside the tag name, not just next to it
+    let inside = match msg {
+        DocumentMsg::DeleteBackward => probe >= own.start,
+        DocumentMsg::DeleteForward => probe < own.end,
+        _ => true,
+    };
+    if !inside {
+        return None;
+    }
+
+    Some(LinkedTagEdit {
+        own,
+        partner,
+        len_before: buffer.len_chars(),
+        undo_depth: doc.undo_stack.len(),
+    })
+}
+
+/// Apply the same rename to the partner tag and fold both edits into a single
+/// undo step.
+fn mirror_linked_tag_edit(model: &mut AppModel, link: LinkedTagEdit) {
+    let doc = model.document();
+    if doc.undo_stack.len() != link.undo_depth + 1 {
+        return;
+    }
+    let delta = doc.buffer.len_chars() as isize - link.len_before as isize;
+    let own_end = (link.own.end as isize + delta) as usize;
+    if own_end <= link.own.start || own_end > doc.buffer.len_chars() {
+        return;
+    }
+    let new_name: String = doc.buffer.slice(link.own.start..own_end).chars().collect();
+    if !new_name.chars().all(tags::is_tag_name_char) {
+        return;
+    }
+
+    let partner = if link.partner.start > link.own.start {
+        let start = (link.partner.start as isize + delta) as usize;
+        start..start + link.partner.len()
+    } else {
+        link.partner.clone()
+    };
+    let old_name: String = doc.buffer.slice(partner.clone()).chars().collect();
+
+    let cursor_offset = model.cursor_buffer_position();
+    let cursor_before_mirror = *model.editor().primary_cursor();
+    let Some(user_edit) = model.document_mut().undo_stack.pop() else {
+        return;
+    };
+    // The user's edit is logged already and is about to be pushed again
+    model.document_mut().syntax_edits.invalidate();
+    let Some(cursor_before) = edit_cursor_before(&user_edit) else {
+        model.document_mut().undo_stack.push(user_edit);
+        return;
+    };
+
+    model.document_mut().buffer.remove(partner.clone());
+    model.document_mut().buffer.insert(partner.start, &new_name);
+
+    let mirror_delta = new_name.chars().count() as isize - old_name.chars().count() as isize;
+    if partner.start < link.own.start {
+        model.set_cursor_from_position((cursor_offset as isize + mirror_delta) as usize);
+    }
+    let cursor_after = *model.editor().primary_cursor();
+
+    let partner_line = model.document().buffer.char_to_line(partner.start);
+    let partner_column = partner.start - model.document().buffer.line_to_char(partner_line);
+    sync_other_editor_cursors(model, partner_line, partner_column, 0, mirror_delta);
+
+    model.document_mut().push_edit(EditOperation::Batch {
+        operations: vec![
+            user_edit,
+            EditOperation::Replace {
+                position: partner.start,
+                deleted_text: old_name,
+                inserted_text: new_name,
+                cursor_before: cursor_before_mirror,
+                cursor_after,
+            },
+        ],
+        cursors_before: vec![cursor_before],
+        cursors_after: vec![cursor_after],
+    });
+}
+
 /// Returns a Cmd that redraws and schedules syntax parsing for the current document
 fn redraw_with_syntax_parse(model: &mut AppModel) -> Cmd {
     redraw_with_syntax_parse_shift(model, None)
@@ -44,6 +281,11 @@ fn redraw_with_syntax_parse_shift(
                     highlights.shift_for_edit(edit_line, old_count, new_count);
                 }
             }
+            for editor in model.editor_area.editors.values_mut() {
+                if editor.document_id == Some(doc_id) {
+                    editor.folds.shift_for_edit(edit_line, old_count, new_count);
+                }
+            }
         }
         if let Some(parse_cmd) = schedule_syntax_parse(model, doc_id) {
             return Cmd::Batch(vec![Cmd::redraw_editor(), parse_cmd]);
@@ -107,9 +349,49 @@ fn word_end_after(buffer: &ropey::Rope, offset: usize) -> usize {
     pos
 }
 
+/// Spaces from the active cursor to the next multiple of `indent_width`
+fn spaces_to_indent_stop(model: &AppModel) -> String {
+    let width = model.config.indent_width.max(1);
+    let cursor = model.editor().active_cursor();
+    let line = model.document().get_line(cursor.line).unwrap_or_default();
+    let visual_col = crate::util::text::char_col_to_visual_col(&line, cursor.column);
+    " ".repeat(width - visual_col % width)
+}
+
 /// Handle document messages (text editing, undo/redo)
 pub fn update_document(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd> {
+    if let Some(cmd) = super::large_file::block_while_loading(model) {
+        return Some(cmd);
+    }
+
+    // Typed and deleted characters may join the undo step before them
+    let groups_with_previous = matches!(
+        msg,
+        DocumentMsg::InsertChar(_) | DocumentMsg::DeleteBackward | DocumentMsg::DeleteForward
+    );
+    let undo_depth = model.document().undo_stack.len();
+
+    // Indenting with spaces: Tab fills up to the next indent stop
+    let msg = match msg {
+        DocumentMsg::InsertChar('\t') if model.config.indent_style == IndentStyle::Spaces => {
+            DocumentMsg::InsertText(spaces_to_indent_stop(model))
+        }
+        msg => msg,
+    };
+
+    let linked_tag = linked_tag_edit_target(model, &msg);
     let result = update_document_inner(model, msg);
+    if let Some(link) = linked_tag {
+        mirror_linked_tag_edit(model, link);
+    }
+
+    let rules = model.config.undo;
+    let doc = model.document_mut();
+    if groups_with_previous && doc.undo_stack.len() == undo_depth + 1 {
+        doc.group_last_edit(&rules, std::time::Instant::now());
+    } else if doc.undo_stack.len() != undo_depth {
+        doc.end_undo_group();
+    }
     if model.editor().is_plain_text_mode() {
         super::editor::compute_matched_brackets(model);
     }
@@ -132,89 +414,101 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
         DocumentMsg::InsertChar(ch) => {
             let cursor_before = *model.editor().primary_cursor();
 
-            // Multi-cursor: process all cursors in reverse document order
+            // Multi-cursor: each selection is wrapped on its own when a
+            // surround char is typed, other cursors get the char inserted
             if model.editor().has_multiple_cursors() {
-                let close = surround_pair(ch);
                 let any_has_selection = model.editor().selections.iter().any(|s| !s.is_empty());
-                let do_surround =
-                    model.config.auto_surround && close.is_some() && any_has_selection;
+                let surround =
+                    surround_pair(ch).filter(|_| model.config.auto_surround && any_has_selection);
 
                 // Overlapping selections (e.g. from SelectAllOccurrences, which only
                 // deduplicates cursors but does not merge overlapping ranges) would
                 // corrupt the buffer when processed in reverse order below, since each
                 // selection's offset is recomputed against an already-mutated buffer.
-                // Merging first guarantees non-overlapping ranges, making reverse-order
-                // processing safe.
-                if do_surround {
-                    model.editor_mut().merge_overlapping_selections();
+                // Merging first guarantees non-overlapping ranges (a nested selection
+                // joins the one around it); selections that only touch are still
+                // wrapped one by one.
+                if surround.is_some() {
+                    model.editor_mut().merge_intersecting_selections();
                 }
 
                 let cursors_before: Vec<Cursor> = model.editor().cursors.clone();
-                let indices = cursors_in_reverse_order(model);
-                let mut operations = Vec::new();
-
-                for idx in indices {
-                    let cursor = model.editor().cursors[idx];
-                    let selection = model.editor().selections[idx];
 
-                    if do_surround && !selection.is_empty() {
-                        let Some(close) = close else { continue };
-                        let sel_start = selection.start();
-                        let sel_end = selection.end();
-                        let start_offset = model
-                            .document()
-                            .cursor_to_offset(sel_start.line, sel_start.column);
-                        let end_offset = model
-                            .document()
-                            .cursor_to_offset(sel_end.line, sel_end.column);
-                        let selected_text: String = model
-                            .document()
-                            .buffer
-                            .slice(start_offset..end_offset)
-                            .chars()
-                            .collect();
-
-                        model.document_mut().buffer.remove(start_offset..end_offset);
-                        let wrapped = format!("{ch}{selected_text}{close}");
-                        model.document_mut().buffer.insert(start_offset, &wrapped);
-
-                        let new_offset = start_offset + wrapped.len();
-                        let (new_line, new_col) = model.document().offset_to_cursor(new_offset);
-
-                        operations.push(EditOperation::Replace {
-                            position: start_offset,
-                            deleted_text: selected_text,
-                            inserted_text: wrapped,
-                            cursor_before: cursor,
-                            cursor_after: Cursor::at(new_line, new_col),
-                        });
-
-                        model.editor_mut().cursors[idx] = Cursor::at(new_line, new_col);
-                        model.editor_mut().cursors[idx].desired_column = None;
-                        let new_pos = Position::new(new_line, new_col);
-                        model.editor_mut().selections[idx] = Selection::new(new_pos);
-                    } else {
-                        let pos = model
-                            .document()
-                            .cursor_to_offset(cursor.line, cursor.column);
-
-                        model.document_mut().buffer.insert_char(pos, ch);
-
-                        operations.push(EditOperation::Insert {
-                            position: pos,
-                            text: ch.to_string(),
-                            cursor_before: cursor,
-                            cursor_after: Cursor::at(cursor.line, cursor.column + 1),
-                        });
-
-                        model.editor_mut().cursors[idx].column += 1;
-                        model.editor_mut().cursors[idx].desired_column = None;
+                // Each cursor's edit as (cursor index, start, end, inserted text)
+                let doc = model.document();
+                let editor = model.editor();
+                let mut edits: Vec<(usize, usize, usize, String)> = editor
+                    .cursors
+                    .iter()
+                    .zip(&editor.selections)
+                    .enumerate()
+                    .map(|(idx, (cursor, selection))| match surround {
+                        Some(close) if !selection.is_empty() => {
+                            let (start, end) = (selection.start(), selection.end());
+                            let start = doc.cursor_to_offset(start.line, start.column);
+                            let end = doc.cursor_to_offset(end.line, end.column);
+                            let text: String = doc.buffer.slice(start..end).chars().collect();
+                            (idx, start, end, format!("{ch}{text}{close}"))
+                        }
+                        _ => {
+                            let pos = doc.cursor_to_offset(cursor.line, cursor.column);
+                            (idx, pos, pos, ch.to_string())
+                        }
+                    })
+                    .collect();
+                edits.sort_by_key(|&(_, start, end, _)| (start, end));
+
+                // Apply back to front so earlier offsets stay valid
+                let mut deleted_texts = vec![String::new(); edits.len()];
+                for (edit, deleted) in edits.iter().zip(deleted_texts.iter_mut()).rev() {
+                    let (_, start, end, ref text) = *edit;
+                    let buffer = &mut model.document_mut().buffer;
+                    *deleted = buffer.slice(start..end).chars().collect();
+                    buffer.remove(start..end);
+                    buffer.insert(start, text);
+                }
 
-                        let new_pos = model.editor().cursors[idx].to_position();
-                        model.editor_mut().selections[idx] = Selection::new(new_pos);
-                    }
+                // Each cursor ends up after its edit, shifted by the edits before it
+                let mut shift = 0isize;
+                let mut cursors_after_edit = Vec::with_capacity(edits.len());
+                for (&(idx, start, _, ref text), deleted) in edits.iter().zip(&deleted_texts) {
+                    let inserted = text.chars().count();
+                    let offset = (start as isize + shift) as usize + inserted;
+                    shift += inserted as isize - deleted.chars().count() as isize;
+                    let (line, column) = model.document().offset_to_cursor(offset);
+                    cursors_after_edit.push(Cursor::at(line, column));
+                    model.editor_mut().cursors[idx] = Cursor::at(line, column);
+                    model.editor_mut().selections[idx] =
+                        Selection::new(Position::new(line, column));
                 }
 
+                // Operations in application order (back to front)
+                let operations = edits
+                    .into_iter()
+                    .zip(deleted_texts)
+                    .zip(cursors_after_edit)
+                    .rev()
+                    .map(|(((idx, start, _, text), deleted_text), cursor_after)| {
+                        let cursor_before = cursors_before[idx];
+                        if deleted_text.is_empty() {
+                            EditOperation::Insert {
+                                position: start,
+                                text,
+                                cursor_before,
+                                cursor_after,
+                            }
+                        } else {
+                            EditOperation::Replace {
+                                position: start,
+                                deleted_text,
+                                inserted_text: text,
+                                cursor_before,
+                                cursor_after,
+                            }
+                        }
+                    })
+                    .collect();
+
                 // Record batch for proper multi-cursor undo
                 let cursors_after: Vec<Cursor> = model.editor().cursors.clone();
                 model.document_mut().push_edit(EditOperation::Batch {
@@ -255,7 +549,7 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
                     model.document_mut().buffer.insert(start_offset, &wrapped);
 
                     // Position cursor after the closing char
-                    let new_offset = start_offset + wrapped.len();
+                    let new_offset = start_offset + wrapped.chars().count();
                     model.set_cursor_from_position(new_offset);
                     model.ensure_cursor_visible();
 
@@ -273,6 +567,36 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
                 }
             }
 
+            // Typing `</` in markup completes the innermost open tag
+            if ch == '/' && selection.is_empty() {
+                if let Some(name) = auto_close_tag_name(model) {
+                    let text = format!("/{name}>");
+                    let text_len = text.chars().count();
+                    let pos = model.cursor_buffer_position();
+                    model.document_mut().buffer.insert(pos, &text);
+                    model.set_cursor_from_position(pos + text_len);
+                    model.ensure_cursor_visible();
+
+                    let cursor_after = *model.editor().primary_cursor();
+                    model.document_mut().push_edit(EditOperation::Insert {
+                        position: pos,
+                        text,
+                        cursor_before,
+                        cursor_after,
+                    });
+
+                    sync_other_editor_cursors(
+                        model,
+                        cursor_before.line,
+                        cursor_before.column,
+                        0,
+                        text_len as isize,
+                    );
+                    model.reset_cursor_blink();
+                    return Some(redraw_with_syntax_parse(model));
+                }
+            }
+
             // If there's a selection (non-surround char), delete it first and use Replace for atomic undo
             if let Some((pos, deleted_text)) = delete_selection(model) {
                 // Insert at selection start
@@ -308,6 +632,10 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
 
                 // Sync cursors in other views
                 sync_other_editor_cursors(model, edit_line, edit_column, 0, 1);
+
+                if ch == '}' {
+                    reindent_closed_block(model);
+                }
             }
 
             model.reset_cursor_blink();
@@ -1296,11 +1624,14 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
             if let Some(edit) = model.document_mut().undo_stack.pop() {
                 apply_undo_operation(model, &edit);
                 let doc = model.document_mut();
+                doc.bump_revision(edit.undo_text_edits());
                 doc.redo_stack.push(edit);
                 doc.is_modified = doc.saved_revision != Some(doc.undo_stack.len());
                 model.editor_mut().collapse_selections_to_cursors();
                 model.ensure_cursor_visible();
                 model.reset_cursor_blink();
+                // Re-read a CSV grid from the restored text
+                super::csv::refresh_csv_data(model);
             }
             Some(redraw_with_syntax_parse(model))
         }
@@ -1309,15 +1640,22 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
             if let Some(edit) = model.document_mut().redo_stack.pop() {
                 apply_redo_operation(model, &edit);
                 let doc = model.document_mut();
+                doc.bump_revision(edit.text_edits());
                 doc.undo_stack.push(edit);
                 doc.is_modified = doc.saved_revision != Some(doc.undo_stack.len());
                 model.editor_mut().collapse_selections_to_cursors();
                 model.ensure_cursor_visible();
                 model.reset_cursor_blink();
+                super::csv::refresh_csv_data(model);
             }
             Some(redraw_with_syntax_parse(model))
         }
 
+        DocumentMsg::UndoGroupBoundary => {
+            model.document_mut().end_undo_group();
+            None
+        }
+
         DocumentMsg::Copy => {
             let mut text_to_copy = String::new();
 
@@ -1914,7 +2252,10 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
             Some(redraw_with_syntax_parse(model))
         }
 
-        DocumentMsg::IndentLines => {
+        DocumentMsg::MoveLinesUp => move_lines(model, -1),
+        DocumentMsg::MoveLinesDown => move_lines(model, 1),
+
+        DocumentMsg::IndentSelection => {
             // Multi-cursor: collect unique lines from all cursors/selections
             let covered_lines = lines_covered_by_all_cursors(model);
 
@@ -1924,33 +2265,35 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
 
             let cursors_before: Vec<Cursor> = model.editor().cursors.clone();
             let mut operations = Vec::new();
+            let unit = model.config.indent_unit();
+            let unit_len = unit.chars().count();
 
-            // Insert tabs in reverse document order (highest line first) to preserve offsets
+            // Insert in reverse document order (highest line first) to preserve offsets
             for &line in &covered_lines {
                 let offset = model.document().cursor_to_offset(line, 0);
-                model.document_mut().buffer.insert_char(offset, '\t');
+                model.document_mut().buffer.insert(offset, &unit);
 
                 operations.push(EditOperation::Insert {
                     position: offset,
-                    text: "\t".to_string(),
+                    text: unit.clone(),
                     cursor_before: Cursor::at(line, 0),
-                    cursor_after: Cursor::at(line, 1),
+                    cursor_after: Cursor::at(line, unit_len),
                 });
             }
 
-            // Adjust all cursors and selections: bump column +1 for each that's on an indented line
+            // Adjust all cursors and selections on an indented line by the unit's length
             let indented_lines: std::collections::HashSet<usize> =
                 covered_lines.iter().copied().collect();
             let editor = model.editor_mut();
             for (cursor, selection) in editor.cursors.iter_mut().zip(editor.selections.iter_mut()) {
                 if indented_lines.contains(&cursor.line) {
-                    cursor.column += 1;
+                    cursor.column += unit_len;
                 }
                 if indented_lines.contains(&selection.anchor.line) {
-                    selection.anchor.column += 1;
+                    selection.anchor.column += unit_len;
                 }
                 if indented_lines.contains(&selection.head.line) {
-                    selection.head.column += 1;
+                    selection.head.column += unit_len;
                 }
             }
 
@@ -1962,9 +2305,11 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
             });
 
             // Sync peer editors on the same document: every indented line
-            // shifted its own columns right by 1.
-            let line_deltas: std::collections::HashMap<usize, isize> =
-                indented_lines.iter().map(|&line| (line, 1)).collect();
+            // shifted its own columns right by the unit's length.
+            let line_deltas: std::collections::HashMap<usize, isize> = indented_lines
+                .iter()
+                .map(|&line| (line, unit_len as isize))
+                .collect();
             super::editor::sync_other_editor_cursors_for_line_shifts(model, &line_deltas);
 
             model.document_mut().is_modified = true;
@@ -1973,7 +2318,7 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
             Some(redraw_with_syntax_parse(model))
         }
 
-        DocumentMsg::UnindentLines => {
+        DocumentMsg::DedentSelection => {
             // Multi-cursor: collect unique lines from all cursors/selections
             let covered_lines = lines_covered_by_all_cursors(model);
 
@@ -1985,6 +2330,7 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
             let mut operations = Vec::new();
             let mut removed_per_line: std::collections::HashMap<usize, usize> =
                 std::collections::HashMap::new();
+            let width = model.config.indent_width.max(1);
 
             // Process in reverse document order (highest line first) to preserve offsets
             for &line in &covered_lines {
@@ -1994,8 +2340,12 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
                 let chars_to_remove = if line_text.starts_with('\t') {
                     1
                 } else {
-                    // Count leading spaces (up to 4)
-                    line_text.chars().take_while(|c| *c == ' ').count().min(4)
+                    // Leading spaces, up to one indent level
+                    line_text
+                        .chars()
+                        .take_while(|c| *c == ' ')
+                        .count()
+                        .min(width)
                 };
 
                 if chars_to_remove > 0 {
@@ -2052,9 +2402,295 @@ fn update_document_inner(model: &mut AppModel, msg: DocumentMsg) -> Option<Cmd>
             model.reset_cursor_blink();
             Some(redraw_with_syntax_parse(model))
         }
+
+        DocumentMsg::DropDraggedText { copy } => {
+            let drag = model.editor_mut().text_drag.take()?;
+            let drop = drag.drop?;
+            let selection = *model.editor().primary_selection();
+            if selection.is_empty() {
+                return Some(Cmd::redraw_editor());
+            }
+
+            let doc = model.document();
+            let text = selection.get_text(doc);
+            let start = doc.cursor_to_offset(selection.start().line, selection.start().column);
+            let end = doc.cursor_to_offset(selection.end().line, selection.end().column);
+            let drop_offset = doc.cursor_to_offset(drop.line, drop.column);
+            // Moving text onto itself changes nothing
+            if !copy && (start..=end).contains(&drop_offset) {
+                return Some(Cmd::redraw_editor());
+            }
+
+            let cursors_before = model.editor().cursors.clone();
+            let text_len = text.chars().count();
+            let mut operations = Vec::new();
+
+            let insert_at = if copy {
+                drop_offset
+            } else {
+                let (start_line, start_column) = model.document().offset_to_cursor(start);
+                model.document_mut().buffer.remove(start..end);
+                operations.push(EditOperation::Delete {
+                    position: start,
+                    text: text.clone(),
+                    cursor_before: cursors_before[0],
+                    cursor_after: Cursor::at(start_line, start_column),
+                });
+                sync_other_editor_cursors_for_deleted_text(model, start_line, start_column, &text);
+                if drop_offset > end {
+                    drop_offset - text_len
+                } else {
+                    drop_offset
+                }
+            };
+
+            let (insert_line, insert_column) = model.document().offset_to_cursor(insert_at);
+            model.document_mut().buffer.insert(insert_at, &text);
+            sync_other_editor_cursors_for_text(model, insert_line, insert_column, &text);
+
+            // Leave the dropped text selected
+            let (end_line, end_column) = model.document().offset_to_cursor(insert_at + text_len);
+            let editor = model.editor_mut();
+            editor.collapse_to_primary();
+            editor.cursors[0] = Cursor::at(end_line, end_column);
+            editor.selections[0] = Selection::from_anchor_head(
+                Position::new(insert_line, insert_column),
+                Position::new(end_line, end_column),
+            );
+            let cursor_after = editor.cursors[0];
+
+            operations.push(EditOperation::Insert {
+                position: insert_at,
+                text,
+                cursor_before: Cursor::at(insert_line, insert_column),
+                cursor_after,
+            });
+            model.document_mut().push_edit(EditOperation::Batch {
+                operations,
+                cursors_before,
+                cursors_after: vec![cursor_after],
+            });
+
+            model.document_mut().is_modified = true;
+            model.ensure_cursor_visible();
+            model.reset_cursor_blink();
+            Some(redraw_with_syntax_parse(model))
+        }
+
+        DocumentMsg::ReplaceAll {
+            query,
+            replacement,
+            regex,
+            case_sensitive,
+        } => {
+            let regex = match regex.then(|| SearchRegex::new(&query, case_sensitive)) {
+                Some(Ok(regex)) => Some(regex),
+                Some(Err(_)) => {
+                    model
+                        .ui
+                        .set_status("Replace All: invalid regular expression");
+                    return Some(Cmd::redraw_status_bar());
+                }
+                None => None,
+            };
+            let search = SearchHighlight {
+                query: query.clone(),
+                case_sensitive,
+                whole_word: false,
+                regex,
+                scope: None,
+            };
+            let replaced = replace_all_matches(model, &search, &replacement);
+            if replaced == 0 {
+                model.ui.set_status(format!("No matches for \"{}\"", query));
+                return Some(Cmd::redraw_status_bar());
+            }
+            model.ui.set_status(match replaced {
+                1 => "Replaced 1 occurrence".to_string(),
+                n => format!("Replaced {} occurrences", n),
+            });
+            Some(redraw_with_syntax_parse(model))
+        }
     }
 }
 
+/// Replace the matches of `search` in the focused document, recorded as one
+/// `Batch` so a single undo restores them all. The cursor ends after the
+/// first replacement. Returns how many matches were replaced.
+fn replace_all_matches(model: &mut AppModel, search: &SearchHighlight, replacement: &str) -> usize {
+    let replacements = super::search::replacements(model, search, replacement);
+    let Some((first_start, _, first_replacement)) = replacements.first().cloned() else {
+        return 0;
+    };
+
+    let cursors_before: Vec<Cursor> = model.editor().cursors.clone();
+    let mut operations = Vec::with_capacity(replacements.len());
+    // Back to front, so the positions of earlier matches stay valid
+    for (start, end, inserted_text) in replacements.iter().rev() {
+        let doc = model.document();
+        let position = doc.cursor_to_offset(start.line, start.column);
+        let end_offset = doc.cursor_to_offset(end.line, end.column);
+        let deleted_text = doc.buffer.slice(position..end_offset).to_string();
+
+        let doc = model.document_mut();
+        doc.buffer.remove(position..end_offset);
+        doc.buffer.insert(position, inserted_text);
+        let (line, column) = doc.offset_to_cursor(position + inserted_text.chars().count());
+
+        sync_other_editor_cursors_for_deleted_text(model, start.line, start.column, &deleted_text);
+        sync_other_editor_cursors_for_text(model, start.line, start.column, inserted_text);
+        operations.push(EditOperation::Replace {
+            position,
+            deleted_text,
+            inserted_text: inserted_text.clone(),
+            cursor_before: Cursor::at(end.line, end.column),
+            cursor_after: Cursor::at(line, column),
+        });
+    }
+
+    let doc = model.document();
+    let first_end = doc.cursor_to_offset(first_start.line, first_start.column)
+        + first_replacement.chars().count();
+    let (line, column) = doc.offset_to_cursor(first_end);
+    let editor = model.editor_mut();
+    editor.collapse_to_primary();
+    editor.cursors[0] = Cursor::at(line, column);
+    editor.selections[0] = Selection::new(Position::new(line, column));
+
+    let cursors_after = vec![editor.cursors[0]];
+    model.document_mut().push_edit(EditOperation::Batch {
+        operations,
+        cursors_before,
+        cursors_after,
+    });
+    model.ensure_cursor_visible();
+    model.reset_cursor_blink();
+    replacements.len()
+}
+
+/// Move the lines touched by each cursor or selection one line up
+/// (`delta == -1`) or down (`delta == 1`), past the line next to them.
+/// Cursors keep their columns; nothing moves if any block is already at the
+/// top or bottom of the document.
+fn move_lines(model: &mut AppModel, delta: isize) -> Option<Cmd> {
+    let doc = model.document();
+    // A trailing newline leaves an empty last line that isn't moved
+    let mut last_line = doc.line_count().saturating_sub(1);
+    if last_line > 0 && doc.line_length(last_line) == 0 {
+        last_line -= 1;
+    }
+
+    // Blocks of touched lines; a selection ending at column 0 doesn't touch
+    // its last line. Overlapping and adjacent blocks move together.
+    let mut blocks: Vec<(usize, usize)> = model
+        .editor()
+        .selections
+        .iter()
+        .map(|selection| {
+            let (start, end) = (selection.start(), selection.end());
+            let end_line = if end.column == 0 && end.line > start.line {
+                end.line - 1
+            } else {
+                end.line
+            };
+            (start.line, end_line)
+        })
+        .collect();
+    blocks.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(blocks.len());
+    for (start, end) in blocks {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let blocked = merged.iter().any(|&(start, end)| {
+        end > last_line || (delta < 0 && start == 0) || (delta > 0 && end == last_line)
+    });
+    if merged.is_empty() || blocked {
+        return None;
+    }
+
+    let cursors_before: Vec<Cursor> = model.editor().cursors.clone();
+    let mut operations = Vec::with_capacity(merged.len());
+
+    // Back to front; the swapped text keeps its length, so offsets stay valid
+    for &(start, end) in merged.iter().rev() {
+        let (first, last) = if delta < 0 {
+            (start - 1, end)
+        } else {
+            (start, end + 1)
+        };
+        let doc = model.document();
+        let region_start = doc.buffer.line_to_char(first);
+        let region_end = if last + 1 < doc.line_count() {
+            doc.buffer.line_to_char(last + 1)
+        } else {
+            doc.buffer.len_chars()
+        };
+        let deleted_text: String = doc.buffer.slice(region_start..region_end).chars().collect();
+        let trailing_newline = deleted_text.ends_with('\n');
+        let mut lines: Vec<&str> = deleted_text
+            .strip_suffix('\n')
+            .unwrap_or(&deleted_text)
+            .split('\n')
+            .collect();
+        if delta < 0 {
+            lines.rotate_left(1);
+        } else {
+            lines.rotate_right(1);
+        }
+        let mut inserted_text = lines.join("\n");
+        if trailing_newline {
+            inserted_text.push('\n');
+        }
+
+        let buffer = &mut model.document_mut().buffer;
+        buffer.remove(region_start..region_end);
+        buffer.insert(region_start, &inserted_text);
+        operations.push(EditOperation::Replace {
+            position: region_start,
+            deleted_text,
+            inserted_text,
+            cursor_before: Cursor::at(start, 0),
+            cursor_after: Cursor::at((start as isize + delta) as usize, 0),
+        });
+    }
+
+    // Every cursor is in a moved block and its line keeps its text, so
+    // cursors and selections move with it, columns unchanged. A selection
+    // ending past the last line ends at the end of the document instead.
+    let last = model.document().line_count() - 1;
+    let last_len = model.document().line_length(last);
+    let shift = |position: &mut Position| {
+        let line = (position.line as isize + delta) as usize;
+        *position = if line > last {
+            Position::new(last, last_len)
+        } else {
+            Position::new(line, position.column)
+        };
+    };
+    let editor = model.editor_mut();
+    for (cursor, selection) in editor.cursors.iter_mut().zip(editor.selections.iter_mut()) {
+        shift(&mut selection.anchor);
+        shift(&mut selection.head);
+        cursor.line = selection.head.line;
+        cursor.column = selection.head.column;
+    }
+
+    let cursors_after: Vec<Cursor> = model.editor().cursors.clone();
+    model.document_mut().push_edit(EditOperation::Batch {
+        operations,
+        cursors_before,
+        cursors_after,
+    });
+
+    model.ensure_cursor_visible();
+    model.reset_cursor_blink();
+    Some(redraw_with_syntax_parse(model))
+}
+
 /// Extract the single-cursor `cursor_before` field from a non-batch
 /// `EditOperation`. Returns `None` for `Batch`, which restores its whole
 /// `cursors_before` vector instead (see `apply_undo_operation`).
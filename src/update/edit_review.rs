@@ -0,0 +1,182 @@
+//! Pending edit review: opening the review modal and applying accepted hunks
+
+use crate::commands::Cmd;
+use crate::edit_review::{EditReviewSource, EditReviewState};
+use crate::messages::DocumentMsg;
+use crate::model::editor::{Cursor, Position, Selection};
+use crate::model::{AppModel, ModalState};
+use crate::view::geometry::EDIT_REVIEW_MAX_VISIBLE_ROWS;
+
+use super::document::update_document;
+
+/// Show `proposed` (the full new contents of the focused document) in the
+/// edit review modal instead of applying it directly.
+pub(super) fn open_edit_review(
+    model: &mut AppModel,
+    source: EditReviewSource,
+    proposed: &str,
+) -> Option<Cmd> {
+    let document_id = model.editor_area.focused_document_id()?;
+    let doc = model.document();
+    let Some(state) = EditReviewState::new(
+        document_id,
+        doc.revision,
+        source,
+        doc.buffer.to_string(),
+        proposed,
+    ) else {
+        model
+            .ui
+            .set_status(format!("{}: no changes", source.label()));
+        return Some(Cmd::Redraw);
+    };
+    model.ui.open_modal(ModalState::EditReview(state));
+    Some(Cmd::Redraw)
+}
+
+/// Move the hunk selection by `delta`, keeping it scrolled into view
+pub(super) fn move_review_selection(state: &mut EditReviewState, delta: isize) {
+    let max_index = state.hunks.len().saturating_sub(1);
+    state.selected_index = state
+        .selected_index
+        .saturating_add_signed(delta)
+        .min(max_index);
+    state.scroll_to_selected(EDIT_REVIEW_MAX_VISIBLE_ROWS);
+}
+
+/// Number of leading chars shared by `a` and `b`
+fn common_prefix_chars(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count()
+}
+
+/// Number of trailing chars shared by `a` and `b`
+fn common_suffix_chars(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+/// Write the accepted hunks of a review into its document as one undoable edit
+pub(super) fn apply_edit_review(model: &mut AppModel, state: EditReviewState) -> Option<Cmd> {
+    let label = state.source.label();
+    if model.editor_area.focused_document_id() != Some(state.document_id)
+        || model.document().revision != state.base_revision
+    {
+        model
+            .ui
+            .set_status(format!("{}: document changed, edit discarded", label));
+        return Some(Cmd::Redraw);
+    }
+
+    let accepted = state.accepted_count();
+    let total = state.hunks.len();
+    if accepted == 0 {
+        model
+            .ui
+            .set_status(format!("{}: all changes rejected", label));
+        return Some(Cmd::Redraw);
+    }
+
+    // Collapse the accepted hunks into the single char range that differs, so
+    // the buffer gets one Replace edit (and one undo step)
+    let proposed = state.result_text();
+    let original = &state.original;
+    let prefix = common_prefix_chars(original, &proposed);
+    let original_len = original.chars().count();
+    let proposed_len = proposed.chars().count();
+    let suffix = common_suffix_chars(original, &proposed)
+        .min(original_len - prefix)
+        .min(proposed_len - prefix);
+    let replacement: String = proposed
+        .chars()
+        .skip(prefix)
+        .take(proposed_len - prefix - suffix)
+        .collect();
+
+    let doc = model.document();
+    let (start_line, start_col) = doc.offset_to_cursor(prefix);
+    let (end_line, end_col) = doc.offset_to_cursor(original_len - suffix);
+    let editor = model.editor_mut();
+    editor.collapse_to_primary();
+    editor.cursors[0] = Cursor::at(end_line, end_col);
+    editor.selections[0] = Selection::from_positions(
+        Position::new(start_line, start_col),
+        Position::new(end_line, end_col),
+    );
+
+    let cmd = if replacement.is_empty() {
+        update_document(model, DocumentMsg::DeleteBackward)
+    } else {
+        update_document(model, DocumentMsg::PasteText(replacement))
+    };
+    // A CSV grid shows the new cell values
+    super::csv::refresh_csv_data(model);
+    model.ui.set_status(format!(
+        "{}: applied {} of {} changes",
+        label, accepted, total
+    ));
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{ModalMsg, UiMsg};
+    use crate::update::update_ui;
+
+    fn test_model(text: &str) -> AppModel {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.document_mut().buffer = ropey::Rope::from(text);
+        model
+    }
+
+    #[test]
+    fn review_applies_only_accepted_hunks_as_one_undo_step() {
+        let mut model = test_model("a\nb\nc\nd\n");
+        open_edit_review(&mut model, EditReviewSource::ReplaceAll, "A\nb\nc\nD\n");
+        assert_eq!(model.document().buffer.to_string(), "a\nb\nc\nd\n");
+
+        // Reject the first hunk, keep the second
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::ToggleReviewHunk));
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::Confirm));
+
+        assert!(model.ui.active_modal.is_none());
+        assert_eq!(model.document().buffer.to_string(), "a\nb\nc\nD\n");
+
+        update_document(&mut model, DocumentMsg::Undo);
+        assert_eq!(model.document().buffer.to_string(), "a\nb\nc\nd\n");
+    }
+
+    #[test]
+    fn closing_review_leaves_buffer_untouched() {
+        let mut model = test_model("x\n");
+        open_edit_review(&mut model, EditReviewSource::AiAnswer, "y\n");
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::Close));
+
+        assert!(model.ui.active_modal.is_none());
+        assert_eq!(model.document().buffer.to_string(), "x\n");
+    }
+
+    #[test]
+    fn review_is_discarded_if_document_changed() {
+        let mut model = test_model("x\n");
+        open_edit_review(&mut model, EditReviewSource::AiAnswer, "y\n");
+        model.document_mut().revision += 1;
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::Confirm));
+
+        assert_eq!(model.document().buffer.to_string(), "x\n");
+    }
+
+    #[test]
+    fn rejecting_everything_makes_no_edit() {
+        let mut model = test_model("a\nb\n");
+        open_edit_review(&mut model, EditReviewSource::ReplaceAll, "a\n");
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::SetAllReviewHunks(false)));
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::Confirm));
+
+        assert_eq!(model.document().buffer.to_string(), "a\nb\n");
+        assert!(model.document().undo_stack.is_empty());
+    }
+}
@@ -1,14 +1,16 @@
 //! Editor update functions for cursor movement, selection, and viewport scrolling.
 
-use std::time::Duration;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
 
 use crate::commands::Cmd;
 use crate::messages::{Direction, EditorMsg};
 use crate::model::{
-    AppModel, Cursor, EditorState, OccurrenceState, Position, SegmentContent, SegmentId, Selection,
-    TransientMessage,
+    AppModel, Cursor, Document, EditorState, OccurrenceState, Position, SegmentContent, SegmentId,
+    Selection, TextDragState, TransientMessage, WordHighlightKey, WordHighlights,
 };
 use crate::util::{char_type, CharType};
+use crate::view::line_layout::LineLayout;
 
 /// Handle editor messages (cursor movement, viewport scrolling)
 pub fn update_editor(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
@@ -36,7 +38,10 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
         | EditorMsg::PageDown
         | EditorMsg::SetCursorPosition { .. }
         | EditorMsg::ClearSelection
-        | EditorMsg::CollapseToSingleCursor => {
+        | EditorMsg::CollapseToSingleCursor
+        | EditorMsg::SearchWordUnderCursor { .. }
+        | EditorMsg::FindNextMatch
+        | EditorMsg::FindPreviousMatch => {
             model.editor_mut().occurrence_state = None;
             model.editor_mut().clear_selection_history();
         }
@@ -54,6 +59,7 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
         | EditorMsg::SelectLine
         | EditorMsg::ExtendSelectionToPosition { .. }
         | EditorMsg::SelectNextOccurrence
+        | EditorMsg::SkipOccurrence
         | EditorMsg::SelectAllOccurrences => {
             model.editor_mut().clear_selection_history();
         }
@@ -61,6 +67,16 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
     }
 
     match msg {
+        EditorMsg::SearchWordUnderCursor { forward } => {
+            super::search::search_word_under_cursor(model, forward)
+        }
+        EditorMsg::FindNextMatch => super::search::find_match(model, true),
+        EditorMsg::FindPreviousMatch => super::search::find_match(model, false),
+        EditorMsg::ClearSearchHighlight => {
+            model.ui.search_highlight = None;
+            Some(Cmd::redraw_editor())
+        }
+
         EditorMsg::MoveCursor(direction) => {
             {
                 let doc = model.document().clone();
@@ -215,6 +231,14 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
             .scroll_focused_editor_horizontal_by(delta as isize)
             .then_some(Cmd::redraw_editor()),
 
+        EditorMsg::ScrollLines(delta) => {
+            if !model.scroll_focused_editor_vertical_by(delta as isize) {
+                return None;
+            }
+            pull_cursor_within_a_page(model);
+            Some(Cmd::redraw_editor())
+        }
+
         // === Selection Movement (Shift+key) ===
         EditorMsg::MoveCursorWithSelection(direction) => {
             {
@@ -606,6 +630,8 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
                     let cursor_idx = model.editor().cursors.len();
                     model.editor_mut().cursors.push(new_cursor);
                     model.editor_mut().selections.push(new_selection);
+                    // The new occurrence drives scrolling, so it's revealed
+                    model.editor_mut().active_cursor_index = cursor_idx;
 
                     // Update occurrence state
                     if let Some(ref mut state) = model.editor_mut().occurrence_state {
@@ -677,6 +703,14 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
                     editor.cursors.remove(idx);
                     editor.selections.remove(idx);
                 }
+                // Scroll back to the previously added occurrence
+                editor.active_cursor_index = editor
+                    .occurrence_state
+                    .as_ref()
+                    .and_then(|state| state.added_cursor_indices.last().copied())
+                    .filter(|&i| i < editor.cursors.len())
+                    .unwrap_or(0);
+                model.ensure_cursor_visible();
             }
 
             // Clear occurrence state if needed
@@ -688,6 +722,75 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
             Some(Cmd::redraw_editor())
         }
 
+        EditorMsg::SkipOccurrence => {
+            // The occurrence being skipped: the last one added, or the lone
+            // selection before any were added
+            let skip_idx = {
+                let editor = model.editor();
+                editor
+                    .occurrence_state
+                    .as_ref()
+                    .and_then(|state| state.added_cursor_indices.last().copied())
+                    .or_else(|| {
+                        (editor.cursors.len() == 1 && !editor.primary_selection().is_empty())
+                            .then_some(0)
+                    })
+                    .filter(|&i| i < editor.cursors.len())
+            };
+            let Some(skip_idx) = skip_idx else {
+                // Nothing selected yet: select the word under the cursor
+                return update_editor(model, EditorMsg::SelectNextOccurrence);
+            };
+
+            // Continue the search after the skipped occurrence
+            let (search_text, skipped_end) = {
+                let selection = model.editor().selections[skip_idx];
+                let end = selection.end();
+                (
+                    selection.get_text(model.document()),
+                    model.document().cursor_to_offset(end.line, end.column),
+                )
+            };
+            if search_text.is_empty() {
+                return Some(Cmd::redraw_editor());
+            }
+            let added_cursor_indices = model
+                .editor()
+                .occurrence_state
+                .as_ref()
+                .map(|state| state.added_cursor_indices.clone())
+                .unwrap_or_default();
+            model.editor_mut().occurrence_state = Some(OccurrenceState {
+                search_text,
+                added_cursor_indices,
+                last_search_offset: skipped_end,
+            });
+
+            let cursor_count = model.editor().cursors.len();
+            update_editor(model, EditorMsg::SelectNextOccurrence);
+            if model.editor().cursors.len() == cursor_count {
+                // No other occurrence to move to; keep the skipped one
+                return Some(Cmd::redraw_editor());
+            }
+
+            let editor = model.editor_mut();
+            editor.cursors.remove(skip_idx);
+            editor.selections.remove(skip_idx);
+            if let Some(ref mut state) = editor.occurrence_state {
+                state.added_cursor_indices.retain(|&i| i != skip_idx);
+                for i in &mut state.added_cursor_indices {
+                    if *i > skip_idx {
+                        *i -= 1;
+                    }
+                }
+            }
+            // The occurrence just added was pushed last
+            editor.active_cursor_index = editor.cursors.len() - 1;
+            model.ensure_cursor_visible();
+            model.reset_cursor_blink();
+            Some(Cmd::redraw_editor())
+        }
+
         EditorMsg::SelectAllOccurrences => {
             // Get search text from current selection or word under cursor
             let search_text = {
@@ -804,14 +907,18 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
                     .rectangle_selection
                     .preview_cursors
                     .clear();
+                let tabs = crate::view::geometry::elastic_tabs(
+                    model,
+                    model.document(),
+                    top_line,
+                    bottom_line,
+                );
                 for preview_line in top_line..=bottom_line {
                     let line_text = model.document().get_line(preview_line).unwrap_or_default();
                     let line_text_trimmed = line_text.trim_end_matches('\n');
                     // Convert visual column to char column, clamped to line length
-                    let char_col = crate::util::text::visual_col_to_char_col(
-                        line_text_trimmed,
-                        current_visual_col,
-                    );
+                    let char_col = LineLayout::for_line(model.document(), &tabs, preview_line)
+                        .char_col(line_text_trimmed, current_visual_col);
                     let line_len = model.document().line_length(preview_line);
                     let clamped_col = char_col.min(line_len);
                     model
@@ -835,6 +942,9 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
             let right_visual_col = model.editor().rectangle_selection.right_visual_col();
             let current_visual_col = model.editor().rectangle_selection.current_visual_col;
 
+            let tabs =
+                crate::view::geometry::elastic_tabs(model, model.document(), top_line, bottom_line);
+
             // Clear existing cursors and selections
             model.editor_mut().cursors.clear();
             model.editor_mut().selections.clear();
@@ -844,19 +954,14 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
                 let line_text = model.document().get_line(line).unwrap_or_default();
                 let line_text_trimmed = line_text.trim_end_matches('\n');
                 let line_len = model.document().line_length(line);
+                let layout = LineLayout::for_line(model.document(), &tabs, line);
 
                 // Convert visual columns to char columns for this line
-                let start_char_col =
-                    crate::util::text::visual_col_to_char_col(line_text_trimmed, left_visual_col)
-                        .min(line_len);
-                let end_char_col =
-                    crate::util::text::visual_col_to_char_col(line_text_trimmed, right_visual_col)
-                        .min(line_len);
-                let cursor_char_col = crate::util::text::visual_col_to_char_col(
-                    line_text_trimmed,
-                    current_visual_col,
-                )
-                .min(line_len);
+                let to_char_col =
+                    |visual_col| layout.char_col(line_text_trimmed, visual_col).min(line_len);
+                let start_char_col = to_char_col(left_visual_col);
+                let end_char_col = to_char_col(right_visual_col);
+                let cursor_char_col = to_char_col(current_visual_col);
 
                 // Create cursor at the dragged-to position (clamped to line length)
                 let cursor = Cursor::at(line, cursor_char_col);
@@ -884,6 +989,7 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
 
             // Deactivate rectangle selection mode and clear preview
             model.editor_mut().rectangle_selection.active = false;
+            model.editor_mut().rectangle_selection.from_keyboard = false;
             model
                 .editor_mut()
                 .rectangle_selection
@@ -894,8 +1000,57 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
             Some(Cmd::redraw_editor())
         }
 
+        EditorMsg::ExtendRectangleSelection(direction) => {
+            let state = &model.editor().rectangle_selection;
+            if state.active && !state.from_keyboard {
+                // A middle mouse drag is in progress
+                return None;
+            }
+            let (line, visual_col) = if state.active {
+                (state.current_line, state.current_visual_col)
+            } else {
+                let cursor = *model.editor().active_cursor();
+                let visual_col = caret_visual_col(model, cursor.line, Some(cursor.column));
+                let state = &mut model.editor_mut().rectangle_selection;
+                state.active = true;
+                state.from_keyboard = true;
+                state.start_line = cursor.line;
+                state.start_visual_col = visual_col;
+                (cursor.line, visual_col)
+            };
+
+            let last_line = model.document().line_count().saturating_sub(1);
+            let (line, visual_col) = match direction {
+                Direction::Up => (line.saturating_sub(1), visual_col),
+                Direction::Down => ((line + 1).min(last_line), visual_col),
+                Direction::Left => (line, visual_col.saturating_sub(1)),
+                Direction::Right => {
+                    // Not past the end of the longest line spanned
+                    let start_line = model.editor().rectangle_selection.start_line;
+                    let widest = (start_line.min(line)..=start_line.max(line))
+                        .map(|line| caret_visual_col(model, line, None))
+                        .max()
+                        .unwrap_or(0);
+                    (line, (visual_col + 1).min(widest.max(visual_col)))
+                }
+            };
+
+            // Keep the moving corner in view
+            let viewport = &mut model.editor_mut().viewport;
+            if line < viewport.top_line {
+                viewport.top_line = line;
+            } else if line >= viewport.top_line + viewport.visible_lines {
+                viewport.top_line = line + 1 - viewport.visible_lines.max(1);
+            }
+            update_editor(
+                model,
+                EditorMsg::UpdateRectangleSelection { line, visual_col },
+            )
+        }
+
         EditorMsg::CancelRectangleSelection => {
             model.editor_mut().rectangle_selection.active = false;
+            model.editor_mut().rectangle_selection.from_keyboard = false;
             model
                 .editor_mut()
                 .rectangle_selection
@@ -903,6 +1058,42 @@ fn update_editor_inner(model: &mut AppModel, msg: EditorMsg) -> Option<Cmd> {
                 .clear();
             Some(Cmd::redraw_editor())
         }
+
+        // === Selection Drag-and-Drop ===
+        EditorMsg::StartTextDrag { line, column } => {
+            model.editor_mut().text_drag = Some(TextDragState {
+                origin: Position::new(line, column),
+                drop: None,
+            });
+            Some(Cmd::redraw_editor())
+        }
+
+        EditorMsg::UpdateTextDrag { line, column } => {
+            if let Some(drag) = model.editor_mut().text_drag.as_mut() {
+                drag.drop = Some(Position::new(line, column));
+            }
+            Some(Cmd::redraw_editor())
+        }
+
+        EditorMsg::CancelTextDrag => {
+            let drag = model.editor_mut().text_drag.take()?;
+            // The press never turned into a drag: behave like a plain click
+            model.editor_mut().clear_selection();
+            update_editor(
+                model,
+                EditorMsg::SetCursorPosition {
+                    line: drag.origin.line,
+                    column: drag.origin.column,
+                },
+            )
+        }
+
+        EditorMsg::CycleHeadingFold => super::folding::cycle_heading_fold(model),
+        EditorMsg::CycleAllHeadingFolds => super::folding::cycle_all_heading_folds(model),
+        EditorMsg::ToggleFold => super::folding::toggle_fold(model, None),
+        EditorMsg::ToggleFoldAt { line } => super::folding::toggle_fold(model, Some(line)),
+        EditorMsg::FoldAll => super::folding::fold_all(model),
+        EditorMsg::UnfoldAll => super::folding::unfold_all(model),
     }
 }
 
@@ -953,6 +1144,16 @@ pub(crate) fn delete_selection(model: &mut AppModel) -> Option<(usize, String)>
 
 /// Expand selection to next semantic level: cursor → word → line → all
 /// Supports multiple cursors - each cursor/selection expands independently
+/// Visual column of a cursor at `column` on `line`, or at its end for `None`
+fn caret_visual_col(model: &AppModel, line: usize, column: Option<usize>) -> usize {
+    let document = model.document();
+    let text = document.get_line(line).unwrap_or_default();
+    let text = text.trim_end_matches('\n');
+    let column = column.unwrap_or_else(|| document.line_length(line));
+    let tabs = crate::view::geometry::elastic_tabs(model, document, line, line);
+    LineLayout::for_line(document, &tabs, line).caret_col(text, column)
+}
+
 fn expand_selection(model: &mut AppModel) {
     // For multi-cursor, we need to expand each selection independently
     // Exception: if ANY selection would expand to "all", we expand all to "all"
@@ -1544,3 +1745,129 @@ fn find_matching_backward(
     }
     None
 }
+
+/// After scrolling without moving the cursor, bring it back to the nearest
+/// visible line once it is more than a page outside the viewport
+fn pull_cursor_within_a_page(model: &mut AppModel) {
+    let viewport = &model.editor().viewport;
+    let top_line = viewport.top_line;
+    let page = viewport.visible_lines.max(1);
+    let cursor_line = model.editor().active_cursor().line;
+
+    let target_line = if cursor_line.saturating_add(page) < top_line {
+        top_line
+    } else if cursor_line >= top_line.saturating_add(2 * page) {
+        top_line + page - 1
+    } else {
+        return;
+    };
+    let target_line = target_line.min(model.document().line_count().saturating_sub(1));
+    let desired = model.editor().cursors[0].desired_visual_column(model.document());
+    let column = model.document().column_at_visual(target_line, desired);
+
+    let editor = model.editor_mut();
+    editor.collapse_to_primary();
+    let cursor = &mut editor.cursors[0];
+    cursor.desired_column = Some(desired);
+    cursor.line = target_line;
+    cursor.column = column;
+    editor.clear_selection();
+}
+
+/// How long the cursor rests in a word before the word's other occurrences
+/// are highlighted
+pub const WORD_HIGHLIGHT_DEBOUNCE_MS: u64 = 250;
+
+/// Keep the focused editor's word highlights in step with the word under
+/// its cursor, the text and the visible lines. When any of them changes the
+/// highlights are dropped, and looked for again once the cursor has rested
+/// for [`WORD_HIGHLIGHT_DEBOUNCE_MS`].
+pub(crate) fn sync_word_highlights(model: &mut AppModel) {
+    let (Some(editor), Some(document)) = (
+        model.editor_area.focused_editor(),
+        model.editor_area.focused_document(),
+    ) else {
+        return;
+    };
+    let single_caret =
+        editor.cursors.len() == 1 && editor.selections.iter().all(Selection::is_empty);
+    let key = (model.config.highlight_occurrences && editor.is_plain_text_mode() && single_caret)
+        .then(|| editor.word_under_cursor(document))
+        .flatten()
+        .map(|(_, word_start, _)| {
+            let viewport = editor.viewport_map(document);
+            WordHighlightKey {
+                word_start,
+                revision: document.revision,
+                top_line: viewport.top_line(),
+                end_line: viewport.end_line(),
+            }
+        });
+    if editor.word_highlights.key == key {
+        return;
+    }
+    model.editor_mut().word_highlights = WordHighlights {
+        key,
+        due: key.map(|_| Instant::now() + Duration::from_millis(WORD_HIGHLIGHT_DEBOUNCE_MS)),
+        ranges: Vec::new(),
+    };
+}
+
+/// When the focused editor's word highlights are due to be looked for
+pub fn word_highlight_deadline(model: &AppModel) -> Option<Instant> {
+    model.editor_area.focused_editor()?.word_highlights.due
+}
+
+/// Find the other occurrences of the word under the cursor in the lines that
+/// were visible when the cursor came to rest
+pub(crate) fn highlight_word_occurrences(model: &mut AppModel) -> Option<Cmd> {
+    let editor = model.editor_area.focused_editor()?;
+    let key = editor.word_highlights.key?;
+    editor.word_highlights.due?;
+    let document = model.editor_area.focused_document()?;
+    let (word, word_start, _) = editor.word_under_cursor(document)?;
+    let ranges = if word_start == key.word_start && document.revision == key.revision {
+        word_occurrences(document, &word, key.top_line..=key.end_line, word_start)
+    } else {
+        Vec::new()
+    };
+
+    let found = !ranges.is_empty();
+    let highlights = &mut model.editor_mut().word_highlights;
+    highlights.due = None;
+    highlights.ranges = ranges;
+    found.then(Cmd::redraw_editor)
+}
+
+/// Whole-word occurrences of `word` on `lines`, other than the one starting
+/// at `skip`, as (line, start column, end column)
+fn word_occurrences(
+    document: &Document,
+    word: &str,
+    lines: RangeInclusive<usize>,
+    skip: Position,
+) -> Vec<(usize, usize, usize)> {
+    let word: Vec<char> = word.chars().collect();
+    let mut ranges = Vec::new();
+    for line in lines {
+        let Some(text) = document.get_line_cow(line) else {
+            break;
+        };
+        let chars: Vec<char> = text.chars().collect();
+        let mut column = 0;
+        while column < chars.len() {
+            if char_type(chars[column]) != CharType::WordChar {
+                column += 1;
+                continue;
+            }
+            let start = column;
+            while column < chars.len() && char_type(chars[column]) == CharType::WordChar {
+                column += 1;
+            }
+            if chars[start..column] == word[..] && Position::new(line, start) != skip {
+                ranges.push((line, start, column));
+            }
+        }
+    }
+    ranges
+}
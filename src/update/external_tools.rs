@@ -0,0 +1,80 @@
+//! Running external tools from the command palette
+
+use std::path::Path;
+
+use crate::commands::Cmd;
+use crate::external_tools::{ToolContext, ToolOutput};
+use crate::model::AppModel;
+
+/// Run the external tool at `index` of the config's `external_tools` on the
+/// focused file
+pub(super) fn run_tool(model: &mut AppModel, index: usize) -> Option<Cmd> {
+    let tool = model.config.external_tools.get(index)?.clone();
+    if !model.workspace_trusted() {
+        model.ui.set_status(format!(
+            "{} not run: workspace is in restricted mode (run \"Trust Workspace\")",
+            tool.name
+        ));
+        return Some(Cmd::redraw_status_bar());
+    }
+    let cursor = *model.editor().primary_cursor();
+    let context = ToolContext {
+        file: model.document().file_path.clone(),
+        line: cursor.line,
+        column: cursor.column,
+        workspace: model.workspace.as_ref().map(|w| w.root.clone()),
+    };
+
+    match tool.expand(&context) {
+        Ok(command) => {
+            tracing::info!("Running external tool {}: {}", tool.name, command);
+            model.ui.set_status(format!("Running {}...", tool.name));
+            Some(Cmd::batch(vec![
+                Cmd::RunExternalTool {
+                    name: tool.name,
+                    command,
+                    cwd: context.dir().map(Path::to_path_buf),
+                },
+                Cmd::redraw_status_bar(),
+            ]))
+        }
+        Err(placeholder) => {
+            model.ui.set_status(format!(
+                "{}: nothing to use for {}; save the file first",
+                tool.name, placeholder
+            ));
+            Some(Cmd::redraw_status_bar())
+        }
+    }
+}
+
+/// Report a finished tool in the status bar; its full output goes to the log
+pub(super) fn tool_finished(
+    model: &mut AppModel,
+    name: String,
+    result: Result<ToolOutput, String>,
+) -> Option<Cmd> {
+    let message = match result {
+        Ok(output) => {
+            if !output.stdout.is_empty() {
+                tracing::info!("{} output:\n{}", name, output.stdout.trim_end());
+            }
+            if !output.stderr.is_empty() {
+                tracing::warn!("{} errors:\n{}", name, output.stderr.trim_end());
+            }
+            match (output.success, output.summary(), output.code) {
+                (true, Some(line), _) => format!("{}: {}", name, line),
+                (true, None, _) => format!("{} finished", name),
+                (false, Some(line), Some(code)) => {
+                    format!("{} failed (exit {}): {}", name, code, line)
+                }
+                (false, Some(line), None) => format!("{} failed: {}", name, line),
+                (false, None, Some(code)) => format!("{} failed (exit {})", name, code),
+                (false, None, None) => format!("{} was stopped", name),
+            }
+        }
+        Err(e) => format!("{} could not run: {}", name, e),
+    };
+    model.ui.set_status(message);
+    Some(Cmd::redraw_status_bar())
+}
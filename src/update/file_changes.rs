@@ -0,0 +1,220 @@
+//! Keeping open documents in step with files edited by other programs
+
+use std::path::PathBuf;
+
+use crate::commands::Cmd;
+use crate::model::editor_area::DocumentId;
+use crate::model::{AppModel, Cursor, FileChangedChoice, FileChangedState, ModalState};
+use crate::replay::content_hash;
+use crate::util::{filename_for_display, normalize_path};
+
+use super::syntax::schedule_syntax_parse;
+
+/// Read the files of open documents among the watcher's `changed` paths, to
+/// find out whether they were edited on disk
+pub(super) fn read_changed_files(model: &AppModel, changed: &[PathBuf]) -> Option<Cmd> {
+    let changed: Vec<PathBuf> = changed.iter().map(|path| normalize_path(path)).collect();
+    let cmds: Vec<Cmd> = model
+        .editor_area
+        .documents
+        .iter()
+        // A large file still streaming in reads whatever is on disk now
+        .filter(|(_, doc)| !doc.deleted_on_disk && !doc.is_loading())
+        .filter_map(|(document_id, doc)| {
+            let path = doc.file_path.as_deref()?;
+            (path.is_file() && changed.contains(&normalize_path(path))).then(|| {
+                Cmd::ReadChangedFile {
+                    document_id: *document_id,
+                    path: path.to_path_buf(),
+                }
+            })
+        })
+        .collect();
+    (!cmds.is_empty()).then(|| Cmd::batch(cmds))
+}
+
+/// Handle the new contents of a document's file. Our own saves and files
+/// that already match the buffer are ignored, unmodified documents are
+/// reloaded, and modified ones ask whether to reload or keep the edits.
+pub(super) fn file_changed_on_disk(
+    model: &mut AppModel,
+    document_id: DocumentId,
+    content: String,
+) -> Option<Cmd> {
+    let hash = content_hash(&content);
+    let doc = model.editor_area.documents.get_mut(&document_id)?;
+    if doc.disk_hash.as_deref() == Some(hash.as_str()) {
+        return None;
+    }
+    if doc.buffer == content.as_str() {
+        doc.disk_hash = Some(hash);
+        return None;
+    }
+    let path = doc.file_path.clone()?;
+    if !doc.is_modified {
+        let cmd = reload_document(model, document_id, content);
+        model.ui.set_status(format!(
+            "Reloaded {}: changed on disk",
+            filename_for_display(&path)
+        ));
+        return cmd;
+    }
+
+    // Not asked again about this version of the file
+    doc.disk_hash = Some(hash);
+    model
+        .ui
+        .open_modal(ModalState::FileChanged(FileChangedState::new(
+            document_id,
+            path,
+            content,
+        )));
+    Some(Cmd::Redraw)
+}
+
+/// Act on the answer to the changed file prompt (the modal is closed)
+pub(super) fn resolve_file_changed(
+    model: &mut AppModel,
+    state: FileChangedState,
+    choice: FileChangedChoice,
+) -> Option<Cmd> {
+    match choice {
+        FileChangedChoice::Reload => {
+            let name = filename_for_display(&state.path);
+            let cmd = reload_document(model, state.document_id, state.content);
+            model.ui.set_status(format!("Reloaded {}", name));
+            cmd
+        }
+        // The buffer stays modified, so the next save overwrites the file
+        FileChangedChoice::Keep => Some(Cmd::Redraw),
+    }
+}
+
+/// Replace a document's contents with `content` from its file. The undo
+/// history is dropped and cursors past the new end are pulled back in.
+fn reload_document(model: &mut AppModel, document_id: DocumentId, content: String) -> Option<Cmd> {
+    let doc = model.editor_area.documents.get_mut(&document_id)?;
+    doc.disk_hash = Some(content_hash(&content));
+    doc.buffer = ropey::Rope::from(content);
+    doc.is_modified = false;
+    doc.undo_stack.clear();
+    doc.redo_stack.clear();
+    doc.undo_group_at = None;
+    doc.saved_revision = Some(0);
+    doc.revision = doc.revision.wrapping_add(1);
+    doc.forget_git_head();
+
+    let editor_ids = model.editor_area.editors_for_document(document_id);
+    let doc = &model.editor_area.documents[&document_id];
+    let last_line = doc.line_count().saturating_sub(1);
+    for editor_id in editor_ids {
+        let Some(editor) = model.editor_area.editors.get_mut(&editor_id) else {
+            continue;
+        };
+        let line = editor.cursors[0].line.min(last_line);
+        let column = editor.cursors[0].column.min(doc.line_length(line));
+        editor.cursors[0] = Cursor::at(line, column);
+        editor.collapse_to_primary();
+    }
+
+    let mut cmds = vec![Cmd::Redraw];
+    cmds.extend(schedule_syntax_parse(model, document_id));
+    Some(Cmd::batch(cmds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{AppMsg, ModalMsg, UiMsg};
+    use crate::update::{update_app, update_ui};
+
+    fn open_model(path: &std::path::Path) -> AppModel {
+        let mut model = AppModel::new(800, 600, 1.0, vec![path.to_path_buf()]);
+        model.recent_files.entries.clear();
+        model
+    }
+
+    fn changed_on_disk(model: &mut AppModel, content: &str) -> Option<Cmd> {
+        let document_id = model.document().id.unwrap();
+        update_app(
+            model,
+            AppMsg::FileChangedOnDisk {
+                document_id,
+                content: content.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn unmodified_document_is_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        let mut model = open_model(&path);
+        model.editor_mut().cursors[0] = Cursor::at(2, 3);
+
+        std::fs::write(&path, "one\n").unwrap();
+        let cmd = read_changed_files(&model, std::slice::from_ref(&path));
+        assert!(matches!(
+            cmd,
+            Some(Cmd::Batch(ref cmds)) if matches!(cmds[..], [Cmd::ReadChangedFile { .. }])
+        ));
+        changed_on_disk(&mut model, "one\n");
+
+        assert_eq!(model.document().buffer.to_string(), "one\n");
+        assert!(!model.document().is_modified);
+        assert!(model.document().undo_stack.is_empty());
+        assert_eq!(model.editor().cursors[0], Cursor::at(1, 0));
+        assert!(model.ui.active_modal.is_none());
+    }
+
+    #[test]
+    fn own_save_is_not_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "a\n").unwrap();
+        let mut model = open_model(&path);
+        model.document_mut().buffer = ropey::Rope::from("b\n");
+        model.document_mut().is_modified = true;
+
+        update_app(&mut model, AppMsg::SaveFile);
+        // Typed on before the watcher caught up with the save
+        model.document_mut().buffer = ropey::Rope::from("bc\n");
+        assert!(changed_on_disk(&mut model, "b\n").is_none());
+        assert!(model.ui.active_modal.is_none());
+        assert_eq!(model.document().buffer.to_string(), "bc\n");
+    }
+
+    #[test]
+    fn modified_document_asks_before_reloading() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "a\n").unwrap();
+        let mut model = open_model(&path);
+        model.document_mut().buffer = ropey::Rope::from("mine\n");
+        model.document_mut().is_modified = true;
+
+        changed_on_disk(&mut model, "theirs\n");
+        assert!(matches!(
+            model.ui.active_modal,
+            Some(ModalState::FileChanged(_))
+        ));
+        update_ui(
+            &mut model,
+            UiMsg::Modal(ModalMsg::ResolveFileChanged(FileChangedChoice::Keep)),
+        );
+        assert_eq!(model.document().buffer.to_string(), "mine\n");
+        assert!(model.document().is_modified);
+        // The same version of the file is not asked about again
+        assert!(changed_on_disk(&mut model, "theirs\n").is_none());
+
+        changed_on_disk(&mut model, "theirs again\n");
+        update_ui(
+            &mut model,
+            UiMsg::Modal(ModalMsg::ResolveFileChanged(FileChangedChoice::Reload)),
+        );
+        assert_eq!(model.document().buffer.to_string(), "theirs again\n");
+        assert!(!model.document().is_modified);
+        assert!(model.ui.active_modal.is_none());
+    }
+}
@@ -0,0 +1,438 @@
+//! Keeping open documents in step with files renamed, moved or deleted on disk
+
+use std::path::{Path, PathBuf};
+
+use crate::commands::Cmd;
+use crate::file_ops::FileOperation;
+use crate::messages::AppMsg;
+use crate::model::editor_area::DocumentId;
+use crate::model::{AppModel, DeletedFileChoice, DeletedFileState, ModalState};
+use crate::syntax::LanguageId;
+use crate::util::{filename_for_display, normalize_path, rebase_path};
+
+use super::app::update_app;
+use super::syntax::schedule_syntax_parse;
+
+/// Retarget open documents and recent files after `from` (a file or a
+/// directory) was renamed to `to`
+pub(super) fn apply_rename(model: &mut AppModel, from: &Path, to: &Path) -> Option<Cmd> {
+    let mut reparse = Vec::new();
+    let mut renamed = 0;
+    for (doc_id, doc) in model.editor_area.documents.iter_mut() {
+        // A document whose file was deleted keeps its path; the path now
+        // belongs to whatever replaced the file
+        if doc.deleted_on_disk {
+            continue;
+        }
+        let Some(old_path) = doc.file_path.clone() else {
+            continue;
+        };
+        let Some(new_path) = rebase_path(&old_path, from, to) else {
+            continue;
+        };
+
+        // Follow an extension change, unless the language was picked by hand
+        let old_language = LanguageId::from_path(&old_path);
+        let new_language = LanguageId::from_path(&new_path);
+        if !doc.language_overridden && doc.language == old_language && new_language != old_language
+        {
+            doc.language = new_language;
+            doc.syntax_highlights = None;
+            reparse.push(*doc_id);
+        }
+        doc.file_path = Some(new_path);
+        doc.forget_git_head();
+        renamed += 1;
+    }
+
+    let recent_changed = model.recent_files.rename(from, to);
+    if renamed == 0 && !recent_changed {
+        return None;
+    }
+
+    if renamed > 0 {
+        model.ui.set_status(format!(
+            "Renamed: {} → {}",
+            filename_for_display(from),
+            filename_for_display(to)
+        ));
+    }
+    let mut cmds = vec![Cmd::Redraw];
+    for doc_id in reparse {
+        cmds.extend(schedule_syntax_parse(model, doc_id));
+    }
+    if recent_changed {
+        cmds.push(Cmd::SaveRecentFiles {
+            recent: model.recent_files.clone(),
+        });
+    }
+    Some(Cmd::Batch(cmds))
+}
+
+/// Whether the file at `candidate` looks like where the open document at
+/// `path` was moved: unmodified buffers must match the file's contents
+fn is_moved_copy(model: &AppModel, doc_id: DocumentId, candidate: &Path) -> bool {
+    let Some(doc) = model.editor_area.documents.get(&doc_id) else {
+        return false;
+    };
+    if doc.is_modified {
+        return true;
+    }
+    std::fs::read_to_string(candidate).is_ok_and(|content| doc.buffer == content.as_str())
+}
+
+/// Update open documents for a batch of watcher changes.
+///
+/// The watcher only reports which paths changed, so a rename shows up as
+/// the old path vanishing and a new one appearing. An open file whose path
+/// vanished is matched against the paths that appeared: a single sibling
+/// (renamed) or a single namesake elsewhere (moved) is taken as its new
+/// location. Files that can't be matched are marked as deleted on disk.
+pub(super) fn reconcile_open_documents(model: &mut AppModel, changed: &[PathBuf]) -> Option<Cmd> {
+    let appeared: Vec<&PathBuf> = changed
+        .iter()
+        .filter(|path| path.exists() && model.editor_area.find_document_by_path(path).is_none())
+        .collect();
+
+    let mut moves: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let mut deleted: Vec<DocumentId> = Vec::new();
+    for (doc_id, doc) in &model.editor_area.documents {
+        let Some(path) = doc.file_path.as_deref() else {
+            continue;
+        };
+        if doc.deleted_on_disk || path.exists() {
+            continue;
+        }
+        // The changed path that took this file with it: the file itself or
+        // one of its parent directories
+        let Some(moved) = changed
+            .iter()
+            .find(|c| !c.exists() && rebase_path(path, c, c).is_some())
+        else {
+            continue;
+        };
+        let moved_is_file = normalize_path(moved) == normalize_path(path);
+
+        let candidates: Vec<&PathBuf> = appeared
+            .iter()
+            .copied()
+            .filter(|candidate| {
+                candidate.is_file() == moved_is_file
+                    && (candidate.file_name() == moved.file_name()
+                        || candidate.parent() == moved.parent())
+            })
+            .collect();
+        match candidates.as_slice() {
+            [to] if !moved_is_file || is_moved_copy(model, *doc_id, to) => {
+                if !moves.iter().any(|(from, _)| from == moved) {
+                    moves.push((moved.clone(), (*to).clone()));
+                }
+            }
+            _ => deleted.push(*doc_id),
+        }
+    }
+
+    let mut cmds = Vec::new();
+    for (from, to) in moves {
+        cmds.extend(apply_rename(model, &from, &to));
+    }
+
+    for doc_id in deleted {
+        if let Some(doc) = model.editor_area.documents.get_mut(&doc_id) {
+            doc.deleted_on_disk = true;
+            model
+                .ui
+                .set_status(format!("{} was deleted on disk", doc.display_name()));
+            cmds.push(Cmd::Redraw);
+        }
+    }
+
+    // Files that were deleted and have come back
+    for doc in model.editor_area.documents.values_mut() {
+        if doc.deleted_on_disk && doc.file_path.as_deref().is_some_and(Path::exists) {
+            doc.deleted_on_disk = false;
+            cmds.push(Cmd::Redraw);
+        }
+    }
+
+    match cmds.len() {
+        0 => None,
+        1 => cmds.pop(),
+        _ => Some(Cmd::Batch(cmds)),
+    }
+}
+
+/// Set the deleted-on-disk flag of open documents at or under `path`.
+/// Returns whether any document changed.
+fn mark_deleted_under(model: &mut AppModel, path: &Path, deleted: bool) -> bool {
+    let mut changed = false;
+    for doc in model.editor_area.documents.values_mut() {
+        let under = doc
+            .file_path
+            .as_deref()
+            .is_some_and(|doc_path| rebase_path(doc_path, path, path).is_some());
+        if under && doc.deleted_on_disk != deleted {
+            doc.deleted_on_disk = deleted;
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Refresh the file tree entries for `paths`
+fn refresh_tree_paths(model: &mut AppModel, paths: &[PathBuf]) {
+    if let Some(workspace) = &mut model.workspace {
+        if let Err(e) = workspace.update_paths(paths) {
+            tracing::warn!("Failed to update file tree: {}", e);
+        }
+    }
+}
+
+/// Record a delete or rename made from the editor and bring the file tree
+/// and open documents up to date
+pub(super) fn file_operation_completed(
+    model: &mut AppModel,
+    operation: FileOperation,
+) -> Option<Cmd> {
+    let mut cmds = vec![Cmd::Redraw];
+    match &operation {
+        FileOperation::Trashed { original, .. } => {
+            refresh_tree_paths(model, std::slice::from_ref(original));
+            mark_deleted_under(model, original, true);
+            if let Some(workspace) = &mut model.workspace {
+                if workspace
+                    .selected_item
+                    .as_deref()
+                    .is_some_and(|selected| rebase_path(selected, original, original).is_some())
+                {
+                    workspace.selected_item = original.parent().map(Path::to_path_buf);
+                }
+            }
+        }
+        FileOperation::Renamed { from, to, .. } => {
+            refresh_tree_paths(model, &[from.clone(), to.clone()]);
+            // Documents open on a file that was replaced lose their file
+            mark_deleted_under(model, to, true);
+            cmds.extend(apply_rename(model, from, to));
+            cmds.extend(super::paste_image::relink_pasted_image(model, from, to));
+            if let Some(workspace) = &mut model.workspace {
+                workspace.selected_item = Some(to.clone());
+            }
+        }
+    }
+
+    let undo_key = if cfg!(target_os = "macos") {
+        "⌘Z"
+    } else {
+        "Ctrl+Z"
+    };
+    model.ui.set_status(format!(
+        "{} — Undo with {} in the file tree",
+        operation.describe(),
+        undo_key
+    ));
+    model.file_ops.push(operation);
+    Some(Cmd::Batch(cmds))
+}
+
+/// Bring the file tree and open documents up to date after undoing
+/// `operation`
+pub(super) fn file_operation_reverted(
+    model: &mut AppModel,
+    operation: FileOperation,
+) -> Option<Cmd> {
+    let mut cmds = vec![Cmd::Redraw];
+    let mut restored = Some(&operation);
+    while let Some(op) = restored.take() {
+        match op {
+            FileOperation::Trashed { original, .. } => {
+                refresh_tree_paths(model, std::slice::from_ref(original));
+                mark_deleted_under(model, original, false);
+            }
+            FileOperation::Renamed {
+                from,
+                to,
+                overwritten,
+            } => {
+                refresh_tree_paths(model, &[from.clone(), to.clone()]);
+                cmds.extend(apply_rename(model, to, from));
+                restored = overwritten.as_deref();
+            }
+        }
+    }
+
+    model
+        .ui
+        .set_status(format!("Undone: {}", operation.describe()));
+    Some(Cmd::Batch(cmds))
+}
+
+/// Ask what to do before saving a document whose file was deleted on disk.
+/// Returns `None` when the save can go ahead as usual.
+pub(super) fn prompt_deleted_file(model: &mut AppModel) -> Option<Cmd> {
+    let doc = model.document();
+    let path = doc.file_path.clone()?;
+    if !doc.deleted_on_disk || path.exists() {
+        return None;
+    }
+    let doc_id = doc.id?;
+    model
+        .ui
+        .open_modal(ModalState::DeletedFile(DeletedFileState::new(doc_id, path)));
+    Some(Cmd::Redraw)
+}
+
+/// Act on the answer to the deleted file prompt (the modal is closed)
+pub(super) fn resolve_deleted_file(
+    model: &mut AppModel,
+    state: &DeletedFileState,
+    choice: DeletedFileChoice,
+) -> Option<Cmd> {
+    if model.editor_area.focused_document_id() != Some(state.document_id) {
+        model.ui.close_after_save = None;
+        return Some(Cmd::Redraw);
+    }
+    match choice {
+        DeletedFileChoice::Recreate => {
+            model.document_mut().deleted_on_disk = false;
+            update_app(model, AppMsg::SaveFile)
+        }
+        DeletedFileChoice::SaveAs => update_app(model, AppMsg::SaveFileAs),
+        DeletedFileChoice::Cancel => {
+            model.ui.close_after_save = None;
+            Some(Cmd::Redraw)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{ModalMsg, UiMsg, WorkspaceMsg};
+    use crate::update::{update_ui, update_workspace};
+
+    fn open_model(path: &Path) -> AppModel {
+        let mut model = AppModel::new(800, 600, 1.0, vec![path.to_path_buf()]);
+        model.recent_files.entries.clear();
+        model
+    }
+
+    #[test]
+    fn rename_message_retargets_document_and_language() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("notes.txt");
+        std::fs::write(&from, "fn main() {}\n").unwrap();
+        let mut model = open_model(&from);
+        model.record_file_opened(from.clone());
+
+        let to = dir.path().join("main.rs");
+        std::fs::rename(&from, &to).unwrap();
+        update_workspace(
+            &mut model,
+            WorkspaceMsg::PathRenamed {
+                from: from.clone(),
+                to: to.clone(),
+            },
+        );
+
+        assert_eq!(model.document().file_path.as_deref(), Some(to.as_path()));
+        assert_eq!(model.document().display_name(), "main.rs");
+        assert_eq!(model.document().language, LanguageId::Rust);
+        assert_eq!(
+            model.recent_files.entries[0].path,
+            to.canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn rename_keeps_language_picked_by_hand() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("notes.txt");
+        std::fs::write(&from, "a: 1\n").unwrap();
+        let mut model = open_model(&from);
+        model.document_mut().language_overridden = true;
+
+        let to = dir.path().join("notes.md");
+        std::fs::rename(&from, &to).unwrap();
+        update_workspace(&mut model, WorkspaceMsg::PathRenamed { from, to });
+
+        assert_eq!(model.document().language, LanguageId::PlainText);
+    }
+
+    #[test]
+    fn watcher_rename_within_directory_is_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let from = dir.path().join("src").join("a.rs");
+        std::fs::write(&from, "a\n").unwrap();
+        let mut model = open_model(&from);
+
+        let moved_dir = dir.path().join("lib");
+        std::fs::rename(dir.path().join("src"), &moved_dir).unwrap();
+        reconcile_open_documents(&mut model, &[dir.path().join("src"), moved_dir.clone()]);
+
+        assert_eq!(
+            model.document().file_path.as_deref(),
+            Some(moved_dir.join("a.rs").as_path())
+        );
+        assert!(!model.document().deleted_on_disk);
+    }
+
+    #[test]
+    fn deleted_file_prompts_before_saving() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gone.txt");
+        std::fs::write(&path, "x").unwrap();
+        let mut model = open_model(&path);
+
+        std::fs::remove_file(&path).unwrap();
+        reconcile_open_documents(&mut model, std::slice::from_ref(&path));
+        assert!(model.document().deleted_on_disk);
+
+        update_app(&mut model, AppMsg::SaveFile);
+        assert!(matches!(
+            model.ui.active_modal,
+            Some(ModalState::DeletedFile(_))
+        ));
+
+        let cmd = update_ui(
+            &mut model,
+            UiMsg::Modal(ModalMsg::ResolveDeletedFile(DeletedFileChoice::Recreate)),
+        );
+        assert!(matches!(cmd, Some(Cmd::SaveFile { ref path, .. }) if path.ends_with("gone.txt")));
+        assert!(!model.document().deleted_on_disk);
+    }
+
+    #[test]
+    fn trashed_file_can_be_undone() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = crate::file_ops::Trash::freedesktop(dir.path().join("Trash"));
+        let path = dir.path().join("foo.rs");
+        std::fs::write(&path, "fn foo() {}\n").unwrap();
+        let mut model = open_model(&path);
+
+        let cmd = update_workspace(&mut model, WorkspaceMsg::DeletePath(path.clone()));
+        assert!(matches!(cmd, Some(Cmd::TrashPath { .. })));
+        let result = trash.trash(&path).map_err(|e| e.to_string());
+        update_workspace(&mut model, WorkspaceMsg::FileOperationCompleted(result));
+
+        assert!(!path.exists());
+        assert!(model.document().deleted_on_disk);
+        assert!(model.ui.status_message.starts_with("Deleted foo.rs — Undo"));
+
+        let Some(Cmd::RevertFileOperation(operation)) =
+            update_workspace(&mut model, WorkspaceMsg::UndoFileOperation)
+        else {
+            panic!("expected a revert command");
+        };
+        assert!(model.file_ops.is_empty());
+        let result = operation.revert().map_err(|e| e.to_string());
+        update_workspace(
+            &mut model,
+            WorkspaceMsg::FileOperationReverted { operation, result },
+        );
+
+        assert!(path.exists());
+        assert!(!model.document().deleted_on_disk);
+    }
+}
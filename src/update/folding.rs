@@ -0,0 +1,556 @@
+//! Folding: toggling code regions and Org-style outline cycling of Markdown
+//! headings
+//!
+//! Toggle Fold folds the region headed by the cursor line (or the innermost
+//! one around it); regions are heading sections in Markdown and indentation
+//! blocks elsewhere.
+//!
+//! Tab on a heading cycles that heading between folded, children (direct
+//! sub-headings visible but folded) and subtree (everything shown);
+//! Shift+Tab on a heading cycles the whole document between overview (only
+//! top-level headings), contents (all headings, no body text) and show all.
+
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::messages::DocumentMsg;
+use crate::model::folding::{enclosing_indent_fold, indent_fold_at, indent_fold_regions};
+use crate::model::status_bar::TransientMessage;
+use crate::model::{AppModel, Document, Fold, Selection};
+use crate::syntax::LanguageId;
+
+/// A Markdown heading and the lines of its section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Heading {
+    line: usize,
+    level: u8,
+    /// Last line of the section (before the next heading of the same or a
+    /// higher level), trailing blank lines excluded
+    end: usize,
+}
+
+impl Heading {
+    fn subtree(self) -> Option<Fold> {
+        (self.end > self.line).then_some(Fold {
+            start: self.line,
+            end: self.end,
+        })
+    }
+}
+
+/// Route Tab / Shift+Tab on a Markdown heading line to outline cycling
+pub(super) fn heading_cycle_for(
+    model: &AppModel,
+    msg: &DocumentMsg,
+) -> Option<crate::messages::EditorMsg> {
+    use crate::messages::EditorMsg;
+
+    let cycle = match msg {
+        DocumentMsg::InsertChar('\t') => EditorMsg::CycleHeadingFold,
+        DocumentMsg::DedentSelection => EditorMsg::CycleAllHeadingFolds,
+        _ => return None,
+    };
+    let editor = model.editor_area.focused_editor()?;
+    let document = model.editor_area.focused_document()?;
+    if document.language != LanguageId::Markdown
+        || editor.has_multiple_cursors()
+        || !editor.primary_selection().is_empty()
+    {
+        return None;
+    }
+    let headings = markdown_headings(document);
+    let heading = heading_at(&headings, editor.active_cursor().line)?;
+    // Tab on a heading with nothing under it still inserts a tab
+    if matches!(cycle, EditorMsg::CycleHeadingFold) && heading.subtree().is_none() {
+        return None;
+    }
+    Some(cycle)
+}
+
+/// Cycle the heading under the cursor: folded → children → subtree
+pub(super) fn cycle_heading_fold(model: &mut AppModel) -> Option<Cmd> {
+    let document = model.editor_area.focused_document()?;
+    let line_count = document.line_count();
+    let headings = markdown_headings(document);
+    let line = model.editor().active_cursor().line;
+    let Some(heading) = heading_at(&headings, line) else {
+        return show_message(model, "Not on a Markdown heading");
+    };
+    let subtree = heading.subtree()?;
+    let children: Vec<Fold> = direct_children(&headings, heading)
+        .filter_map(Heading::subtree)
+        .collect();
+
+    let folds = &model.editor().folds;
+    let is_folded = folds
+        .fold_at(heading.line)
+        .is_some_and(|f| f.end >= subtree.end);
+    let shows_children = !children.is_empty()
+        && folds.fold_at(heading.line).is_none()
+        && children
+            .iter()
+            .all(|child| folds.fold_at(child.start) == Some(*child));
+
+    if is_folded && !children.is_empty() {
+        clear_folds_within(model, subtree);
+        let folds = &mut model.editor_mut().folds;
+        for child in children {
+            folds.add(child, line_count);
+        }
+    } else if is_folded || shows_children {
+        clear_folds_within(model, subtree);
+    } else {
+        model.editor_mut().folds.add(subtree, line_count);
+    }
+    Some(after_folding(model))
+}
+
+/// Cycle all headings: overview → contents → show all
+pub(super) fn cycle_all_heading_folds(model: &mut AppModel) -> Option<Cmd> {
+    let document = model.editor_area.focused_document()?;
+    if document.language != LanguageId::Markdown {
+        return show_message(model, "Outline cycling works in Markdown documents");
+    }
+    let line_count = document.line_count();
+    let headings = markdown_headings(document);
+    let overview: Vec<Fold> = top_level(&headings).filter_map(Heading::subtree).collect();
+    let contents: Vec<Fold> = headings
+        .iter()
+        .enumerate()
+        .filter_map(|(index, heading)| {
+            // The body up to the first sub-heading
+            let body_end = headings
+                .get(index + 1)
+                .map_or(heading.end, |next| next.line.saturating_sub(1))
+                .min(heading.end);
+            let end = last_non_blank(document, heading.line, body_end);
+            (end > heading.line).then_some(Fold {
+                start: heading.line,
+                end,
+            })
+        })
+        .collect();
+
+    let current = model.editor().folds.folds().to_vec();
+    let (folds, label) = if !overview.is_empty() && current == overview {
+        (contents, "Contents")
+    } else if current.is_empty() || current != contents {
+        (overview, "Overview")
+    } else {
+        (Vec::new(), "Show all")
+    };
+    model.editor_mut().folds.set(folds, line_count);
+    model.ui.transient_message = Some(TransientMessage::new(
+        label.to_string(),
+        Duration::from_secs(2),
+    ));
+    Some(after_folding(model))
+}
+
+/// Fold the region headed by `line` (the cursor line when `None`), or unfold
+/// it if it is folded
+pub(super) fn toggle_fold(model: &mut AppModel, line: Option<usize>) -> Option<Cmd> {
+    let document = model.editor_area.focused_document()?;
+    let line_count = document.line_count();
+    let at_cursor = line.is_none();
+    let line = line.unwrap_or_else(|| model.editor().active_cursor().line);
+    if model.editor().folds.fold_at(line).is_some() {
+        model.editor_mut().folds.remove_at(line);
+        return Some(after_folding(model));
+    }
+
+    // From the cursor, a line inside a region folds that region
+    let region = region_at(document, line).or_else(|| {
+        at_cursor
+            .then(|| enclosing_region(document, line))
+            .flatten()
+    });
+    let Some(region) = region else {
+        return show_message(model, "Nothing to fold here");
+    };
+    model.editor_mut().folds.add(region, line_count);
+    Some(after_folding(model))
+}
+
+/// Fold every region of the document
+pub(super) fn fold_all(model: &mut AppModel) -> Option<Cmd> {
+    let document = model.editor_area.focused_document()?;
+    let line_count = document.line_count();
+    let regions = fold_regions(document);
+    if regions.is_empty() {
+        return show_message(model, "Nothing to fold");
+    }
+    model.editor_mut().folds.set(regions, line_count);
+    Some(after_folding(model))
+}
+
+pub(super) fn unfold_all(model: &mut AppModel) -> Option<Cmd> {
+    if model.editor().folds.is_empty() {
+        return None;
+    }
+    model.editor_mut().folds.clear();
+    Some(after_folding(model))
+}
+
+/// Drop folds made against a different line count than the document now
+/// has: an edit changed the text without shifting them
+pub(super) fn drop_stale_folds(model: &mut AppModel) {
+    let editor_area = &mut model.editor_area;
+    for editor in editor_area.editors.values_mut() {
+        if editor.folds.is_empty() {
+            continue;
+        }
+        let line_count = editor
+            .document_id
+            .and_then(|id| editor_area.documents.get(&id))
+            .map(Document::line_count);
+        if line_count != Some(editor.folds.line_count()) {
+            editor.folds.clear();
+        }
+    }
+}
+
+/// Markdown headings with their section ends, from the syntax tree's outline
+/// when it is current, otherwise from the ATX headings in the text
+fn markdown_headings(document: &Document) -> Vec<Heading> {
+    let mut starts: Vec<(usize, u8)> = Vec::new();
+    match &document.outline {
+        Some(outline) if outline.revision == document.revision => {
+            collect_outline_headings(&outline.roots, &mut starts);
+            starts.sort_unstable();
+        }
+        _ => {
+            let mut in_fence = false;
+            for (line, text) in document.buffer.lines().enumerate() {
+                let text = std::borrow::Cow::from(text);
+                if crate::markdown::is_fence(&text) {
+                    in_fence = !in_fence;
+                } else if let Some(level) =
+                    crate::markdown::atx_heading_level(&text).filter(|_| !in_fence)
+                {
+                    starts.push((line, level));
+                }
+            }
+        }
+    }
+
+    let last_line = document.line_count().saturating_sub(1);
+    starts
+        .iter()
+        .enumerate()
+        .map(|(index, &(line, level))| {
+            let section_end = starts[index + 1..]
+                .iter()
+                .find(|(_, next_level)| *next_level <= level)
+                .map_or(last_line, |(next_line, _)| next_line.saturating_sub(1));
+            Heading {
+                line,
+                level,
+                end: last_non_blank(document, line, section_end),
+            }
+        })
+        .collect()
+}
+
+/// Foldable regions: heading sections in Markdown, indentation blocks
+/// elsewhere
+fn fold_regions(document: &Document) -> Vec<Fold> {
+    if document.language == LanguageId::Markdown {
+        markdown_headings(document)
+            .into_iter()
+            .filter_map(Heading::subtree)
+            .collect()
+    } else {
+        indent_fold_regions(&document.buffer)
+    }
+}
+
+/// The region headed by `line`
+fn region_at(document: &Document, line: usize) -> Option<Fold> {
+    if document.language == LanguageId::Markdown {
+        heading_at(&markdown_headings(document), line).and_then(Heading::subtree)
+    } else {
+        indent_fold_at(&document.buffer, line)
+    }
+}
+
+/// The innermost region containing `line` below its header
+fn enclosing_region(document: &Document, line: usize) -> Option<Fold> {
+    if document.language == LanguageId::Markdown {
+        markdown_headings(document)
+            .into_iter()
+            .rev()
+            .find(|heading| heading.line < line && line <= heading.end)
+            .and_then(Heading::subtree)
+    } else {
+        enclosing_indent_fold(&document.buffer, line)
+    }
+}
+
+fn collect_outline_headings(nodes: &[crate::outline::OutlineNode], out: &mut Vec<(usize, u8)>) {
+    for node in nodes {
+        if let crate::outline::OutlineKind::Heading { level } = node.kind {
+            out.push((node.range.start_line, level));
+        }
+        collect_outline_headings(&node.children, out);
+    }
+}
+
+/// `end`, moved up past blank lines but not above `start`
+fn last_non_blank(document: &Document, start: usize, mut end: usize) -> usize {
+    while end > start
+        && document
+            .get_line_cow(end)
+            .is_some_and(|line| line.trim().is_empty())
+    {
+        end -= 1;
+    }
+    end
+}
+
+fn heading_at(headings: &[Heading], line: usize) -> Option<Heading> {
+    headings
+        .iter()
+        .find(|heading| heading.line == line)
+        .copied()
+}
+
+/// Headings that aren't inside another heading's section
+fn top_level(headings: &[Heading]) -> impl Iterator<Item = Heading> + '_ {
+    outermost(headings.iter().copied())
+}
+
+/// Direct sub-headings of `parent`
+fn direct_children(headings: &[Heading], parent: Heading) -> impl Iterator<Item = Heading> + '_ {
+    outermost(
+        headings
+            .iter()
+            .copied()
+            .filter(move |h| h.line > parent.line && h.line <= parent.end),
+    )
+}
+
+/// Headings whose level is no deeper than any heading before them
+fn outermost(headings: impl Iterator<Item = Heading>) -> impl Iterator<Item = Heading> {
+    let mut min_level = u8::MAX;
+    headings.filter(move |heading| {
+        let outer = heading.level <= min_level;
+        min_level = min_level.min(heading.level);
+        outer
+    })
+}
+
+fn clear_folds_within(model: &mut AppModel, range: Fold) {
+    let inside: Vec<usize> = model
+        .editor()
+        .folds
+        .folds()
+        .iter()
+        .filter(|fold| fold.start >= range.start && fold.start <= range.end)
+        .map(|fold| fold.start)
+        .collect();
+    for start in inside {
+        model.editor_mut().folds.remove_at(start);
+    }
+}
+
+/// Move cursors out of newly hidden lines and keep the viewport valid
+fn after_folding(model: &mut AppModel) -> Cmd {
+    let document = model.document().clone();
+    let editor = model.editor_mut();
+    // Only cursors that were moved lose their selection; the rest keep
+    // their selection direction and desired column
+    for (cursor, selection) in editor.cursors.iter_mut().zip(&mut editor.selections) {
+        if editor.folds.is_hidden(cursor.line) {
+            let line = editor.folds.visible_line_above(cursor.line).unwrap_or(0);
+            cursor.line = line;
+            cursor.column = document.line_length(line);
+            cursor.desired_column = None;
+            *selection = Selection::new(cursor.to_position());
+        }
+    }
+    editor.deduplicate_cursors();
+    let top_line = editor.viewport.top_line;
+    editor.set_top_line_clamped(&document, top_line);
+    model.ensure_cursor_visible();
+    Cmd::redraw_editor()
+}
+
+fn show_message(model: &mut AppModel, message: &str) -> Option<Cmd> {
+    model.ui.transient_message = Some(TransientMessage::new(
+        message.to_string(),
+        Duration::from_secs(3),
+    ));
+    Some(Cmd::redraw_status_bar())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{EditorMsg, Msg};
+    use crate::update::update;
+
+    const NOTES: &str = "\
+# Plan
+intro
+## Design
+design notes
+
+## Tasks
+- a
+# Later
+someday
+";
+
+    fn markdown_model(text: &str, line: usize) -> AppModel {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        std::fs::write(&path, text).unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![path]);
+        model.editor_mut().cursors[0].line = line;
+        model.editor_mut().selections[0] = Selection::new(crate::model::Position::new(line, 0));
+        model
+    }
+
+    fn folds(model: &AppModel) -> Vec<(usize, usize)> {
+        model
+            .editor()
+            .folds
+            .folds()
+            .iter()
+            .map(|fold| (fold.start, fold.end))
+            .collect()
+    }
+
+    #[test]
+    fn tab_on_heading_cycles_folded_children_subtree() {
+        let mut model = markdown_model(NOTES, 0);
+
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('\t')));
+        assert_eq!(folds(&model), vec![(0, 6)]);
+        assert_eq!(model.document().buffer.to_string(), NOTES);
+
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('\t')));
+        assert_eq!(folds(&model), vec![(2, 3), (5, 6)]);
+
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('\t')));
+        assert!(folds(&model).is_empty());
+
+        // Not on a heading: Tab still inserts
+        model.editor_mut().cursors[0].line = 1;
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('\t')));
+        assert!(model.document().buffer.to_string().contains("\tintro"));
+    }
+
+    #[test]
+    fn global_cycle_goes_overview_contents_show_all() {
+        let mut model = markdown_model(NOTES, 2);
+
+        update(&mut model, Msg::Editor(EditorMsg::CycleAllHeadingFolds));
+        assert_eq!(folds(&model), vec![(0, 6), (7, 8)]);
+        // The cursor was inside the folded section: it moves to the heading
+        assert_eq!(model.editor().active_cursor().line, 0);
+
+        update(&mut model, Msg::Editor(EditorMsg::CycleAllHeadingFolds));
+        assert_eq!(folds(&model), vec![(0, 1), (2, 3), (5, 6), (7, 8)]);
+
+        update(&mut model, Msg::Editor(EditorMsg::CycleAllHeadingFolds));
+        assert!(folds(&model).is_empty());
+    }
+
+    #[test]
+    fn moving_down_skips_folded_lines_and_edits_shift_folds() {
+        let mut model = markdown_model(NOTES, 2);
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('\t')));
+        assert_eq!(folds(&model), vec![(2, 3)]);
+
+        update(
+            &mut model,
+            Msg::Editor(EditorMsg::MoveCursor(crate::messages::Direction::Down)),
+        );
+        assert_eq!(model.editor().active_cursor().line, 4);
+
+        // A new line above the fold moves it down
+        model.editor_mut().cursors[0].line = 0;
+        model.editor_mut().cursors[0].column = 6;
+        update(&mut model, Msg::Document(DocumentMsg::InsertNewline));
+        assert_eq!(folds(&model), vec![(3, 4)]);
+    }
+
+    #[test]
+    fn folding_keeps_selections_of_cursors_that_stay_visible() {
+        use crate::model::{Cursor, Position};
+
+        let mut model = markdown_model(NOTES, 2);
+        let editor = model.editor_mut();
+        editor.cursors.push(Cursor {
+            line: 7,
+            column: 2,
+            desired_column: Some(5),
+        });
+        editor.selections.push(Selection {
+            anchor: Position::new(7, 7),
+            head: Position::new(7, 2),
+        });
+        editor.active_cursor_index = 1;
+
+        update(&mut model, Msg::Editor(EditorMsg::CycleAllHeadingFolds));
+        let editor = model.editor();
+        // The hidden cursor moved to its heading
+        assert_eq!(editor.cursors[0].line, 0);
+        // The visible one kept its backward selection and desired column
+        assert_eq!(editor.cursors[1].desired_column, Some(5));
+        assert_eq!(editor.selections[1].anchor, Position::new(7, 7));
+        assert_eq!(editor.selections[1].head, Position::new(7, 2));
+        assert_eq!(editor.active_cursor_index, 1);
+    }
+
+    const CODE: &str = "\
+fn main() {
+    if ready {
+        go();
+    }
+    done();
+}
+";
+
+    fn code_model(line: usize) -> AppModel {
+        let mut model = markdown_model(CODE, line);
+        model.document_mut().language = LanguageId::Rust;
+        model
+    }
+
+    #[test]
+    fn toggle_fold_folds_the_block_at_or_around_the_cursor() {
+        let mut model = code_model(1);
+        update(&mut model, Msg::Editor(EditorMsg::ToggleFold));
+        assert_eq!(folds(&model), vec![(1, 2)]);
+        update(&mut model, Msg::Editor(EditorMsg::ToggleFold));
+        assert!(folds(&model).is_empty());
+
+        // Inside a block: the enclosing one folds, the cursor moves to it
+        model.editor_mut().cursors[0].line = 4;
+        update(&mut model, Msg::Editor(EditorMsg::ToggleFold));
+        assert_eq!(folds(&model), vec![(0, 4)]);
+        assert_eq!(model.editor().active_cursor().line, 0);
+
+        update(&mut model, Msg::Editor(EditorMsg::ToggleFoldAt { line: 0 }));
+        assert!(folds(&model).is_empty());
+    }
+
+    #[test]
+    fn fold_all_and_unfold_all() {
+        let mut model = code_model(0);
+        update(&mut model, Msg::Editor(EditorMsg::FoldAll));
+        assert_eq!(folds(&model), vec![(0, 4), (1, 2)]);
+        assert_eq!(model.document().buffer.to_string(), CODE);
+
+        update(&mut model, Msg::Editor(EditorMsg::UnfoldAll));
+        assert!(folds(&model).is_empty());
+
+        // Markdown folds heading sections
+        let mut model = markdown_model(NOTES, 0);
+        update(&mut model, Msg::Editor(EditorMsg::FoldAll));
+        assert_eq!(folds(&model), vec![(0, 6), (2, 3), (5, 6), (7, 8)]);
+    }
+}
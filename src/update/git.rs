@@ -0,0 +1,378 @@
+//! Git gutter and blame: diffs of open documents against their HEAD
+//! version, and the last commit of each line
+//!
+//! After every update, documents whose diff is older than their text (or
+//! whose HEAD version was forgotten on save) get a new diff computed in the
+//! background, one at a time per document: edits made meanwhile are picked
+//! up when the running diff comes back. Blame works the same way while it
+//! is shown, and becomes end-of-line annotations.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::git::{GitBlameRequest, GitDiffRequest, HeadVersion, Repository};
+use crate::messages::GitMsg;
+use crate::model::{
+    Annotation, AnnotationSource, AppModel, Document, DocumentId, EditorId, EditorState,
+    TabContent, TransientMessage,
+};
+
+/// Handle git gutter and blame messages
+pub fn update_git(model: &mut AppModel, msg: GitMsg) -> Option<Cmd> {
+    match msg {
+        GitMsg::DiffComputed(result) => {
+            let doc = model.editor_area.documents.get_mut(&result.document_id)?;
+            doc.git_diff_pending = None;
+            doc.git_head = result.head;
+            doc.git_diff = result.diff;
+            Some(Cmd::redraw_editor())
+        }
+        GitMsg::ToggleBlame => {
+            model.ui.show_blame = !model.ui.show_blame;
+            let message = if !model.ui.show_blame {
+                for doc in model.editor_area.documents.values_mut() {
+                    doc.annotations.clear(AnnotationSource::GitBlame);
+                    doc.git_blame = None;
+                    doc.git_blame_pending = None;
+                }
+                "Blame hidden"
+            } else if !model.workspace_trusted() {
+                "Blame needs a trusted workspace"
+            } else if model
+                .try_document()
+                .is_some_and(|doc| doc.git_head == HeadVersion::Untracked)
+            {
+                "Blame shown (this file is not in git)"
+            } else {
+                "Blame shown"
+            };
+            model.ui.transient_message = Some(TransientMessage::new(
+                message.to_string(),
+                Duration::from_secs(2),
+            ));
+            Some(Cmd::redraw_editor())
+        }
+        GitMsg::BlameComputed(result) => {
+            if !model.ui.show_blame {
+                return None;
+            }
+            let line_only = model.config.blame.current_line_only;
+            let cursor_line = focused_cursor_line(model, result.document_id);
+            let doc = model.editor_area.documents.get_mut(&result.document_id)?;
+            doc.git_blame_pending = None;
+            doc.git_blame = Some(result.blame);
+            annotate_blame(doc, line_only, cursor_line);
+            Some(Cmd::redraw_editor())
+        }
+    }
+}
+
+/// Request diffs for text documents whose git gutter is out of date
+pub(super) fn sync_git_diffs(model: &mut AppModel) -> Option<Cmd> {
+    if !model.workspace_trusted() {
+        return None;
+    }
+    let editor_area = &mut model.editor_area;
+    let mut cmds = Vec::new();
+    for (id, doc) in editor_area.documents.iter_mut() {
+        // Diffing a large file on every edit would stall the editor
+        if doc.large_file {
+            continue;
+        }
+        let up_to_date = doc
+            .git_diff
+            .as_ref()
+            .is_some_and(|diff| diff.revision == doc.revision);
+        let head = match &doc.git_head {
+            HeadVersion::Untracked => continue,
+            HeadVersion::Loaded(_) if up_to_date => continue,
+            HeadVersion::Loaded(head) => Some(head.clone()),
+            HeadVersion::Unknown => None,
+        };
+        if doc.git_diff_pending.is_some() {
+            continue;
+        }
+        let Some(path) = &doc.file_path else {
+            continue;
+        };
+        // Images and binary files have no text to diff
+        if !shown_as_text(&editor_area.editors, *id) {
+            continue;
+        }
+        if head.is_none() && Repository::discover(path).is_none() {
+            doc.git_head = HeadVersion::Untracked;
+            doc.git_diff = None;
+            continue;
+        }
+
+        doc.git_diff_pending = Some(doc.revision);
+        cmds.push(Cmd::ComputeGitDiff(GitDiffRequest {
+            document_id: *id,
+            revision: doc.revision,
+            path: path.clone(),
+            text: doc.buffer.to_string(),
+            head,
+        }));
+    }
+    (!cmds.is_empty()).then(|| Cmd::batch(cmds))
+}
+
+/// While blame is shown, request blame for text documents whose blame is
+/// out of date, and move the annotation along with the cursor when only
+/// its line is annotated
+pub(super) fn sync_git_blame(model: &mut AppModel) -> Option<Cmd> {
+    if !model.ui.show_blame || !model.workspace_trusted() {
+        return None;
+    }
+    let line_only = model.config.blame.current_line_only;
+    let focused = model
+        .editor_area
+        .focused_editor()
+        .and_then(|editor| Some((editor.document_id?, editor.active_cursor().line)));
+    let editor_area = &mut model.editor_area;
+    let mut cmds = Vec::new();
+    let mut moved = false;
+    for (id, doc) in editor_area.documents.iter_mut() {
+        if let Some(blame) = &doc.git_blame {
+            if blame.revision == doc.revision {
+                let cursor_line = focused
+                    .filter(|(doc_id, _)| doc_id == id)
+                    .map(|(_, line)| line);
+                if line_only && blame.annotated_line != cursor_line {
+                    annotate_blame(doc, line_only, cursor_line);
+                    moved = true;
+                }
+                continue;
+            }
+        }
+        if doc.git_blame_pending.is_some()
+            || doc.git_head == HeadVersion::Untracked
+            || doc.large_file
+        {
+            continue;
+        }
+        let Some(path) = &doc.file_path else {
+            continue;
+        };
+        if !shown_as_text(&editor_area.editors, *id) {
+            continue;
+        }
+
+        doc.git_blame_pending = Some(doc.revision);
+        cmds.push(Cmd::ComputeGitBlame(GitBlameRequest {
+            document_id: *id,
+            revision: doc.revision,
+            path: path.clone(),
+            text: doc.buffer.to_string(),
+        }));
+    }
+    if moved {
+        cmds.push(Cmd::redraw_editor());
+    }
+    (!cmds.is_empty()).then(|| Cmd::batch(cmds))
+}
+
+/// Whether an editor shows `document_id` as text (not as an image or a
+/// binary file placeholder)
+fn shown_as_text(editors: &HashMap<EditorId, EditorState>, document_id: DocumentId) -> bool {
+    editors.values().any(|editor| {
+        editor.document_id == Some(document_id)
+            && matches!(editor.tab_content, TabContent::Text)
+            && !editor.view_mode.is_image()
+    })
+}
+
+/// Cursor line of the focused editor, if it shows `document_id`
+fn focused_cursor_line(model: &AppModel, document_id: DocumentId) -> Option<usize> {
+    let editor = model.editor_area.focused_editor()?;
+    (editor.document_id == Some(document_id)).then(|| editor.active_cursor().line)
+}
+
+/// Turn a document's blame into annotations: on every line, or only on
+/// `cursor_line` when `line_only`
+fn annotate_blame(doc: &mut Document, line_only: bool, cursor_line: Option<usize>) {
+    let Some(blame) = &mut doc.git_blame else {
+        return;
+    };
+    blame.annotated_line = if line_only { cursor_line } else { None };
+    let annotations: Vec<Annotation> = blame
+        .lines
+        .iter()
+        .enumerate()
+        .filter(|(line, _)| !line_only || Some(*line) == cursor_line)
+        .filter_map(|(line, commit)| Some(Annotation::end_of_line(line, commit.as_ref()?.label())))
+        .collect();
+    doc.annotations
+        .set(AnnotationSource::GitBlame, blame.revision, annotations);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::git::{GitDiff, GitDiffResult, HunkKind};
+    use crate::messages::{DocumentMsg, Msg};
+    use crate::update::update;
+
+    /// A model with a file in a (bare-bones) git repository
+    fn repo_model() -> (tempfile::TempDir, AppModel) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one\ntwo\n").unwrap();
+        let model = AppModel::new(800, 600, 1.0, vec![path]);
+        (dir, model)
+    }
+
+    fn requests(cmd: Option<Cmd>) -> Vec<GitDiffRequest> {
+        match cmd {
+            Some(Cmd::ComputeGitDiff(request)) => vec![request],
+            Some(Cmd::Batch(cmds)) => cmds
+                .into_iter()
+                .flat_map(|cmd| requests(Some(cmd)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn blame_requests(cmd: Option<Cmd>) -> Vec<GitBlameRequest> {
+        match cmd {
+            Some(Cmd::ComputeGitBlame(request)) => vec![request],
+            Some(Cmd::Batch(cmds)) => cmds
+                .into_iter()
+                .flat_map(|cmd| blame_requests(Some(cmd)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn edits_request_one_diff_at_a_time_reusing_the_head_version() {
+        let (_dir, mut model) = repo_model();
+        let first = requests(sync_git_diffs(&mut model));
+        assert_eq!(first.len(), 1);
+        assert!(first[0].head.is_none());
+        // Still computing: no second request
+        assert!(requests(sync_git_diffs(&mut model)).is_empty());
+
+        // An edit lands while the diff runs
+        let cmd = update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+        assert!(requests(cmd).is_empty());
+
+        let document_id = first[0].document_id;
+        let cmd = update(
+            &mut model,
+            Msg::Git(GitMsg::DiffComputed(GitDiffResult {
+                document_id,
+                head: HeadVersion::Loaded(Arc::from("one\ntwo\n")),
+                diff: Some(GitDiff {
+                    revision: first[0].revision,
+                    hunks: Vec::new(),
+                }),
+            })),
+        );
+        // The stale diff is shown and the edit is diffed next
+        let next = requests(cmd);
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].revision, model.document().revision);
+        assert_eq!(next[0].head.as_deref(), Some("one\ntwo\n"));
+        assert!(next[0].text.starts_with("xone"));
+        assert!(model.document().git_diff.is_some());
+    }
+
+    #[test]
+    fn saving_forgets_the_head_version() {
+        let (_dir, mut model) = repo_model();
+        let request = requests(sync_git_diffs(&mut model)).remove(0);
+        update_git(
+            &mut model,
+            GitMsg::DiffComputed(GitDiffResult {
+                document_id: request.document_id,
+                head: HeadVersion::Loaded(Arc::from("one\n")),
+                diff: Some(GitDiff {
+                    revision: request.revision,
+                    hunks: crate::git::diff_lines("one\n", &request.text),
+                }),
+            }),
+        );
+        assert_eq!(
+            model.document().git_diff.as_ref().unwrap().hunks[0].kind,
+            HunkKind::Added
+        );
+        assert!(requests(sync_git_diffs(&mut model)).is_empty());
+
+        let cmd = update(
+            &mut model,
+            Msg::App(crate::messages::AppMsg::SaveCompleted(Ok(()))),
+        );
+        let after_save = requests(cmd);
+        assert_eq!(after_save.len(), 1);
+        assert!(after_save[0].head.is_none());
+    }
+
+    #[test]
+    fn files_outside_a_repository_are_not_diffed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one\n").unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![path]);
+
+        assert!(requests(sync_git_diffs(&mut model)).is_empty());
+        assert_eq!(model.document().git_head, HeadVersion::Untracked);
+    }
+
+    #[test]
+    fn blame_annotates_lines_and_follows_the_cursor_in_line_only_mode() {
+        use crate::git::{BlameLine, GitBlame, GitBlameResult};
+        use crate::messages::{Direction, EditorMsg};
+
+        let (_dir, mut model) = repo_model();
+        model.config.blame.current_line_only = true;
+        let cmd = update(&mut model, Msg::Git(GitMsg::ToggleBlame));
+        let request = blame_requests(cmd).remove(0);
+
+        let commit = Arc::new(BlameLine {
+            author: "Jane Doe".to_string(),
+            time: 0,
+            summary: "Initial commit".to_string(),
+            uncommitted: false,
+        });
+        update(
+            &mut model,
+            Msg::Git(GitMsg::BlameComputed(GitBlameResult {
+                document_id: request.document_id,
+                blame: GitBlame {
+                    revision: request.revision,
+                    lines: vec![Some(commit.clone()), Some(commit)],
+                    annotated_line: None,
+                },
+            })),
+        );
+        let revision = model.document().revision;
+        let texts = |model: &AppModel, line| -> Vec<String> {
+            model
+                .document()
+                .annotations
+                .on_line(line, revision)
+                .iter()
+                .map(|a| a.text.clone())
+                .collect()
+        };
+        assert_eq!(texts(&model, 0), ["Jane Doe, 1970-01-01 • Initial commit"]);
+        assert!(texts(&model, 1).is_empty());
+
+        update(
+            &mut model,
+            Msg::Editor(EditorMsg::MoveCursor(Direction::Down)),
+        );
+        assert!(texts(&model, 0).is_empty());
+        assert_eq!(texts(&model, 1).len(), 1);
+
+        update(&mut model, Msg::Git(GitMsg::ToggleBlame));
+        assert!(model.document().annotations.is_empty());
+        assert!(model.document().git_blame.is_none());
+    }
+}
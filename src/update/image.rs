@@ -26,10 +26,10 @@ pub fn update_image(model: &mut AppModel, msg: ImageMsg) -> Option<Cmd> {
             let group_id = model.editor_area.focused_group_id;
             let group = model.editor_area.groups.get(&group_id)?;
             let area_x = group.rect.x as f64;
-            let area_y = group.rect.y as f64 + model.metrics.tab_bar_height as f64;
+            let area_y = group.rect.y as f64 + model.metrics.header_height() as f64;
 
             let area_w = group.rect.width as f64;
-            let area_h = group.rect.height as f64 - model.metrics.tab_bar_height as f64;
+            let area_h = group.rect.height as f64 - model.metrics.header_height() as f64;
 
             let editor = model.editor_area.editors.get_mut(&editor_id)?;
             let state = editor.view_mode.as_image_mut()?;
@@ -138,7 +138,7 @@ pub fn update_image(model: &mut AppModel, msg: ImageMsg) -> Option<Cmd> {
             let group = model.editor_area.groups.get(&group_id)?;
             let vw = group.rect.width as u32;
             let vh =
-                (group.rect.height as usize).saturating_sub(model.metrics.tab_bar_height) as u32;
+                (group.rect.height as usize).saturating_sub(model.metrics.header_height()) as u32;
 
             let editor = model.editor_area.editors.get_mut(&editor_id)?;
             let state = editor.view_mode.as_image_mut()?;
@@ -0,0 +1,104 @@
+//! Streaming large files into the placeholder documents they open as (see
+//! `crate::large_file`)
+
+use crate::commands::Cmd;
+use crate::large_file::LoadedFile;
+use crate::model::editor_area::DocumentId;
+use crate::model::AppModel;
+use crate::util::filename_for_display;
+
+use super::layout::{discard_and_close_tab, is_last_tab};
+
+/// Start reading the large files of documents opened since the last update
+pub fn sync_large_file_loads(model: &mut AppModel) -> Option<Cmd> {
+    let mut cmds = Vec::new();
+    for (&document_id, doc) in model.editor_area.documents.iter_mut() {
+        let Some(progress) = doc.loading.as_mut().filter(|progress| !progress.requested) else {
+            continue;
+        };
+        let Some(path) = doc.file_path.clone() else {
+            continue;
+        };
+        progress.requested = true;
+        cmds.push(Cmd::LoadLargeFile { document_id, path });
+    }
+    (!cmds.is_empty()).then(|| Cmd::batch(cmds))
+}
+
+/// Refuse to edit or save the focused document while it streams in
+pub(super) fn block_while_loading(model: &mut AppModel) -> Option<Cmd> {
+    let doc = model.editor_area.focused_document()?;
+    if !doc.is_loading() {
+        return None;
+    }
+    let name = doc.display_name();
+    model.ui.set_status(format!("{} is still loading", name));
+    Some(Cmd::redraw_status_bar())
+}
+
+pub(super) fn progress(
+    model: &mut AppModel,
+    document_id: DocumentId,
+    loaded: u64,
+    total: u64,
+) -> Option<Cmd> {
+    let progress = model
+        .editor_area
+        .documents
+        .get_mut(&document_id)?
+        .loading
+        .as_mut()?;
+    progress.loaded = loaded;
+    progress.total = total;
+    Some(Cmd::redraw_status_bar())
+}
+
+pub(super) fn loaded(
+    model: &mut AppModel,
+    document_id: DocumentId,
+    result: Result<LoadedFile, String>,
+) -> Option<Cmd> {
+    let doc = model.editor_area.documents.get_mut(&document_id)?;
+    doc.loading.as_ref()?;
+    doc.loading = None;
+    let name = doc
+        .file_path
+        .as_deref()
+        .map(filename_for_display)
+        .unwrap_or_else(|| doc.display_name());
+
+    match result {
+        Ok(file) => {
+            doc.buffer = file.text;
+            doc.disk_hash = Some(file.disk_hash);
+            doc.revision = doc.revision.wrapping_add(1);
+            model.ui.set_status(format!(
+                "Opened {} in large file mode: no syntax highlighting or git gutter",
+                name
+            ));
+            Some(Cmd::Redraw)
+        }
+        Err(e) => {
+            // Never save the empty placeholder over the file
+            doc.file_path = None;
+            doc.large_file = false;
+            model
+                .ui
+                .set_status(format!("Failed to load {}: {}", name, e));
+            let mut cmds = Vec::new();
+            let focused_group = model.editor_area.focused_group_id;
+            while let Some((group_id, index)) = model
+                .editor_area
+                .find_tab_for_document(document_id, focused_group)
+            {
+                let tab_id = model.editor_area.groups[&group_id].tabs[index].id;
+                if is_last_tab(model, tab_id) {
+                    break;
+                }
+                cmds.push(discard_and_close_tab(model, tab_id));
+            }
+            cmds.push(Cmd::Redraw);
+            Some(Cmd::batch(cmds))
+        }
+    }
+}
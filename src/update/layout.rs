@@ -7,8 +7,8 @@ use crate::messages::LayoutMsg;
 use crate::model::editor::{BinaryPlaceholderState, TabContent, ViewMode};
 use crate::model::ui::SplitterDragState;
 use crate::model::{
-    AppModel, Document, EditorGroup, EditorState, GroupId, LayoutNode, Rect, SplitContainer,
-    SplitDirection, Tab, TabId,
+    AppModel, ContextMenuState, Document, DocumentId, DropEdge, EditorGroup, EditorState, GroupId,
+    LayoutNode, ModalState, Rect, ScrollLock, SplitContainer, SplitDirection, Tab, TabId,
 };
 use crate::util::{
     filename_for_display, is_likely_binary, is_supported_image, validate_file_for_opening,
@@ -16,6 +16,7 @@ use crate::util::{
 };
 
 use super::syntax::schedule_syntax_parse;
+use super::unsaved_changes::{prompt_unsaved_changes, unsaved_changes_prompt};
 
 /// Drag threshold in pixels before drag becomes active
 const DRAG_THRESHOLD_PIXELS: f32 = 4.0;
@@ -39,6 +40,13 @@ pub fn update_layout(model: &mut AppModel, msg: LayoutMsg) -> Option<Cmd> {
             cmd
         }
 
+        LayoutMsg::OpenFileInPreviewTab(path) => {
+            let cmd = open_file_in_preview_tab(model, path);
+            sync_viewports(model);
+            ensure_focused_tab_visible(model);
+            cmd
+        }
+
         LayoutMsg::OpenWithDefaultApp(path) => Some(Cmd::OpenInExplorer { path }),
 
         LayoutMsg::SplitFocused(direction) => {
@@ -106,18 +114,52 @@ pub fn update_layout(model: &mut AppModel, msg: LayoutMsg) -> Option<Cmd> {
             Some(Cmd::redraw_editor())
         }
 
+        LayoutMsg::MoveTabToNewSplit {
+            tab_id,
+            group_id,
+            edge,
+        } => {
+            move_tab_to_new_split(model, tab_id, group_id, edge);
+            sync_viewports(model);
+            Some(Cmd::Redraw)
+        }
+
+        LayoutMsg::SetTabColor(color) => {
+            let tab = model
+                .editor_area
+                .focused_group_mut()
+                .and_then(|group| group.tabs.get_mut(group.active_tab_index))?;
+            tab.color = color;
+            Some(Cmd::redraw_editor())
+        }
+
+        LayoutMsg::GroupTabsByColor => {
+            group_tabs_by_color(model);
+            Some(Cmd::redraw_editor())
+        }
+
         LayoutMsg::ScrollTabBar { group_id, delta_px } => {
             scroll_tab_bar(model, group_id, delta_px);
             Some(Cmd::redraw_editor())
         }
 
-        LayoutMsg::CloseTab(tab_id) => {
-            let released_documents = close_tab(model, tab_id);
-            ensure_focused_tab_visible(model);
-            Some(with_released_documents(
+        LayoutMsg::ScrollOtherGroup(delta) => {
+            scroll_other_group(model, delta as isize).then_some(Cmd::redraw_editor())
+        }
+
+        LayoutMsg::ToggleScrollLock => {
+            toggle_scroll_lock(model);
+            Some(Cmd::Batch(vec![
                 Cmd::redraw_editor(),
-                released_documents,
-            ))
+                Cmd::redraw_status_bar(),
+            ]))
+        }
+
+        LayoutMsg::CloseTab(tab_id) => {
+            if prompt_unsaved_changes(model, tab_id) {
+                return Some(Cmd::Redraw);
+            }
+            Some(discard_and_close_tab(model, tab_id))
         }
 
         LayoutMsg::CloseFocusedTab => {
@@ -127,16 +169,62 @@ pub fn update_layout(model: &mut AppModel, msg: LayoutMsg) -> Option<Cmd> {
                 .and_then(|g| g.active_tab())
             {
                 let tab_id = tab.id;
-                let released_documents = close_tab(model, tab_id);
-                ensure_focused_tab_visible(model);
-                return Some(with_released_documents(
-                    Cmd::redraw_editor(),
-                    released_documents,
-                ));
+                if prompt_unsaved_changes(model, tab_id) {
+                    return Some(Cmd::Redraw);
+                }
+                return Some(discard_and_close_tab(model, tab_id));
             }
             Some(Cmd::redraw_editor())
         }
 
+        LayoutMsg::CloseOtherTabs => close_unpinned_tabs(model, TabsToClose::Others),
+
+        LayoutMsg::CloseTabsToRight => close_unpinned_tabs(model, TabsToClose::ToTheRight),
+
+        LayoutMsg::CloseAllTabs => close_unpinned_tabs(model, TabsToClose::All),
+
+        LayoutMsg::TogglePinTab => {
+            let group = model.editor_area.focused_group_mut()?;
+            let tab = group.tabs.get_mut(group.active_tab_index)?;
+            tab.is_pinned = !tab.is_pinned;
+            tab.is_preview = false;
+            let (tab_id, pinned) = (tab.id, tab.is_pinned);
+            // Pinned tabs come first: move it to the end of the pinned run
+            let boundary = group.pinned_count() - pinned as usize;
+            reorder_tab(model, tab_id, boundary);
+            model
+                .ui
+                .set_status(if pinned { "Tab pinned" } else { "Tab unpinned" });
+            Some(Cmd::Batch(vec![
+                Cmd::redraw_editor(),
+                Cmd::redraw_status_bar(),
+            ]))
+        }
+
+        LayoutMsg::KeepTabOpen(tab_id) => {
+            let tab = model
+                .editor_area
+                .groups
+                .values_mut()
+                .flat_map(|group| group.tabs.iter_mut())
+                .find(|tab| tab.id == tab_id && tab.is_preview)?;
+            tab.is_preview = false;
+            Some(Cmd::redraw_editor())
+        }
+
+        LayoutMsg::ShowTabContextMenu { tab_id, position } => {
+            let (group_id, index) = model.editor_area.groups.iter().find_map(|(id, group)| {
+                let index = group.tabs.iter().position(|tab| tab.id == tab_id)?;
+                Some((*id, index))
+            })?;
+            model.editor_area.focused_group_id = group_id;
+            update_layout(model, LayoutMsg::SwitchToTab(index));
+            let pinned = model.editor_area.groups[&group_id].tabs[index].is_pinned;
+            let menu = ContextMenuState::new(position, crate::menu::tab_context_menu(pinned));
+            model.ui.open_modal(ModalState::ContextMenu(menu));
+            Some(Cmd::Redraw)
+        }
+
         LayoutMsg::NextTab => {
             if let Some(group) = model.editor_area.focused_group_mut() {
                 if !group.tabs.is_empty() {
@@ -265,7 +353,7 @@ fn scroll_tab_bar(model: &mut AppModel, group_id: GroupId, delta_px: i32) {
 }
 
 /// Move a tab to a new index within its owning group (drag reorder),
-/// keeping the same tab active.
+/// keeping the same tab active. Pinned tabs stay before the others.
 fn reorder_tab(model: &mut AppModel, tab_id: TabId, to_index: usize) {
     let Some((group_id, from_index)) = model
         .editor_area
@@ -279,7 +367,12 @@ fn reorder_tab(model: &mut AppModel, tab_id: TabId, to_index: usize) {
     let Some(group) = model.editor_area.groups.get_mut(&group_id) else {
         return;
     };
-    let to_index = to_index.min(group.tabs.len().saturating_sub(1));
+    let pinned = group.pinned_count();
+    let to_index = if group.tabs[from_index].is_pinned {
+        to_index.min(pinned.saturating_sub(1))
+    } else {
+        to_index.clamp(pinned, group.tabs.len().saturating_sub(1))
+    };
     if from_index == to_index {
         return;
     }
@@ -299,6 +392,24 @@ fn reorder_tab(model: &mut AppModel, tab_id: TabId, to_index: usize) {
     ensure_active_tab_visible(model, group_id);
 }
 
+/// Stable-sort the focused group's tabs by color label, in `TabColor::ALL`
+/// order with unlabeled tabs last, keeping the same tab active. Pinned tabs
+/// are sorted among themselves and stay first.
+fn group_tabs_by_color(model: &mut AppModel) {
+    let group_id = model.editor_area.focused_group_id;
+    let Some(group) = model.editor_area.groups.get_mut(&group_id) else {
+        return;
+    };
+    let active_id = group.active_tab().map(|tab| tab.id);
+    group
+        .tabs
+        .sort_by_key(|tab| (!tab.is_pinned, tab.color.is_none(), tab.color));
+    if let Some(index) = active_id.and_then(|id| group.tabs.iter().position(|t| t.id == id)) {
+        group.active_tab_index = index;
+    }
+    ensure_active_tab_visible(model, group_id);
+}
+
 // ============================================================================
 // Layout Helper Functions
 // ============================================================================
@@ -329,6 +440,7 @@ fn new_tab_in_focused_group(model: &mut AppModel) {
         editor_id,
         is_pinned: false,
         is_preview: false,
+        color: None,
     };
 
     if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
@@ -337,17 +449,88 @@ fn new_tab_in_focused_group(model: &mut AppModel) {
     }
 }
 
+/// Show a document that is already open instead of loading it a second time.
+///
+/// Focuses its tab in the focused group if there is one. A plain text
+/// document open only in other groups gets another editor view in the
+/// focused group, sharing the same `Document` so edits stay in sync; other
+/// tab kinds (images, CSV, binary) switch to their existing tab.
+fn show_open_document(
+    model: &mut AppModel,
+    doc_id: crate::model::editor_area::DocumentId,
+    filename: &str,
+) {
+    let focused_group_id = model.editor_area.focused_group_id;
+    let existing = model
+        .editor_area
+        .find_tab_for_document(doc_id, focused_group_id);
+
+    let shares_view = |model: &AppModel, group_id: GroupId, tab_idx: usize| {
+        model.editor_area.groups[&group_id]
+            .tabs
+            .get(tab_idx)
+            .and_then(|tab| model.editor_area.editors.get(&tab.editor_id))
+            .is_some_and(|editor| {
+                matches!(editor.tab_content, TabContent::Text)
+                    && matches!(editor.view_mode, ViewMode::Text)
+            })
+    };
+
+    match existing {
+        Some((group_id, tab_idx))
+            if group_id == focused_group_id || !shares_view(model, group_id, tab_idx) =>
+        {
+            model.editor_area.focused_group_id = group_id;
+            if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
+                group.active_tab_index = tab_idx;
+            }
+            model.editor_area.on_group_active_tab_changed(group_id);
+            model.ui.set_status(format!("Switched to: {}", filename));
+        }
+        _ => {
+            let editor_id = model.editor_area.next_editor_id();
+            let mut editor = EditorState::new();
+            editor.id = Some(editor_id);
+            editor.document_id = Some(doc_id);
+            model.editor_area.editors.insert(editor_id, editor);
+
+            let tab_id = model.editor_area.next_tab_id();
+            let tab = Tab {
+                id: tab_id,
+                editor_id,
+                is_pinned: false,
+                is_preview: false,
+                color: None,
+            };
+            if let Some(group) = model.editor_area.groups.get_mut(&focused_group_id) {
+                group.tabs.push(tab);
+                group.active_tab_index = group.tabs.len() - 1;
+            }
+            model
+                .editor_area
+                .on_group_active_tab_changed(focused_group_id);
+            model
+                .ui
+                .set_status(format!("Opened another view: {}", filename));
+        }
+    }
+}
+
 /// Open a file in a new tab in the focused group
 fn open_file_in_new_tab(model: &mut AppModel, path: PathBuf) -> Option<Cmd> {
     let filename = filename_for_display(&path);
 
-    // 0. Check if file is already open - if so, focus it instead
-    if let Some((_doc_id, group_id, tab_idx)) = model.editor_area.find_open_file(&path) {
-        model.editor_area.focused_group_id = group_id;
-        if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
-            group.active_tab_index = tab_idx;
+    // 0. Check if file is already open - if so, show that document instead,
+    //    keeping a preview tab of it open
+    if let Some(doc_id) = model.editor_area.find_document_by_path(&path) {
+        show_open_document(model, doc_id, &filename);
+        if let Some(tab) = model
+            .editor_area
+            .focused_group_mut()
+            .and_then(|group| group.tabs.get_mut(group.active_tab_index))
+        {
+            tab.is_preview = false;
         }
-        model.ui.set_status(format!("Switched to: {}", filename));
         return Some(Cmd::Redraw);
     }
 
@@ -363,7 +546,8 @@ fn open_file_in_new_tab(model: &mut AppModel, path: PathBuf) -> Option<Cmd> {
                 let vw = group.map(|g| g.rect.width as u32).unwrap_or(800);
                 let vh = group
                     .map(|g| {
-                        (g.rect.height as usize).saturating_sub(model.metrics.tab_bar_height) as u32
+                        (g.rect.height as usize).saturating_sub(model.metrics.header_height())
+                            as u32
                     })
                     .unwrap_or(600);
 
@@ -391,6 +575,7 @@ fn open_file_in_new_tab(model: &mut AppModel, path: PathBuf) -> Option<Cmd> {
                             editor_id,
                             is_pinned: false,
                             is_preview: false,
+                            color: None,
                         };
                         if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
                             group.tabs.push(tab);
@@ -440,6 +625,7 @@ fn open_file_in_new_tab(model: &mut AppModel, path: PathBuf) -> Option<Cmd> {
                     editor_id,
                     is_pinned: false,
                     is_preview: false,
+                    color: None,
                 };
                 if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
                     group.tabs.push(tab);
@@ -458,7 +644,7 @@ fn open_file_in_new_tab(model: &mut AppModel, path: PathBuf) -> Option<Cmd> {
             }
 
             // Load text document from file
-            match Document::from_file(path.clone()) {
+            match Document::open(path.clone()) {
                 Ok(mut doc) => {
                     doc.id = Some(doc_id);
                     model.ui.set_status(format!("Opened: {}", path.display()));
@@ -503,6 +689,7 @@ fn open_file_in_new_tab(model: &mut AppModel, path: PathBuf) -> Option<Cmd> {
         editor_id,
         is_pinned: false,
         is_preview: false,
+        color: None,
     };
 
     if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
@@ -523,6 +710,42 @@ fn open_file_in_new_tab(model: &mut AppModel, path: PathBuf) -> Option<Cmd> {
     Some(Cmd::Batch(cmds))
 }
 
+/// Open a file in the focused group's preview tab. The file previewed
+/// before is closed and the new one takes its place in the tab bar; a file
+/// that is already open is shown where it is.
+fn open_file_in_preview_tab(model: &mut AppModel, path: PathBuf) -> Option<Cmd> {
+    if let Some(doc_id) = model.editor_area.find_document_by_path(&path) {
+        show_open_document(model, doc_id, &filename_for_display(&path));
+        return Some(Cmd::Redraw);
+    }
+
+    let group_id = model.editor_area.focused_group_id;
+    let group = model.editor_area.groups.get(&group_id)?;
+    let tab_count = group.tabs.len();
+    let previous = group
+        .tabs
+        .iter()
+        .position(|tab| tab.is_preview)
+        .map(|index| (index, group.tabs[index].id));
+
+    let cmd = open_file_in_new_tab(model, path);
+    let group = model.editor_area.groups.get_mut(&group_id)?;
+    if group.tabs.len() == tab_count {
+        // Not opened (the status says why)
+        return cmd;
+    }
+    let tab = group.tabs.last_mut()?;
+    tab.is_preview = true;
+    let tab_id = tab.id;
+
+    let Some((index, previous_id)) = previous else {
+        return cmd;
+    };
+    let close = discard_and_close_tab(model, previous_id);
+    reorder_tab(model, tab_id, index);
+    Some(Cmd::batch(cmd.into_iter().chain([close]).collect()))
+}
+
 /// Split the focused group in the given direction
 fn split_focused_group(model: &mut AppModel, direction: SplitDirection) {
     let group_id = model.editor_area.focused_group_id;
@@ -532,18 +755,19 @@ fn split_focused_group(model: &mut AppModel, direction: SplitDirection) {
 /// Split a specific group in the given direction
 fn split_group(model: &mut AppModel, group_id: GroupId, direction: SplitDirection) {
     // Get the document ID from the active tab in the group to split
-    let doc_id = {
+    let (doc_id, color) = {
         let group = match model.editor_area.groups.get(&group_id) {
             Some(g) => g,
             None => return,
         };
+        let color = group.active_tab().and_then(|tab| tab.color);
         let editor_id = match group.active_editor_id() {
             Some(id) => id,
             None => return,
         };
         match model.editor_area.editors.get(&editor_id) {
             Some(e) => match e.document_id {
-                Some(id) => id,
+                Some(id) => (id, color),
                 None => return,
             },
             None => return,
@@ -567,6 +791,7 @@ fn split_group(model: &mut AppModel, group_id: GroupId, direction: SplitDirectio
         editor_id: new_editor_id,
         is_pinned: false,
         is_preview: false,
+        color,
     };
 
     // Create a new group with the new tab
@@ -578,6 +803,7 @@ fn split_group(model: &mut AppModel, group_id: GroupId, direction: SplitDirectio
         rect: Default::default(),
         attached_preview: None,
         tab_scroll: 0,
+        scroll_lock: None,
     };
     model.editor_area.groups.insert(new_group_id, new_group);
 
@@ -587,29 +813,36 @@ fn split_group(model: &mut AppModel, group_id: GroupId, direction: SplitDirectio
         group_id,
         new_group_id,
         direction,
+        false,
     );
 
     // Focus the new group
     model.editor_area.focused_group_id = new_group_id;
 }
 
-/// Insert a split into the layout tree, replacing the target group with a split container
+/// Insert a split into the layout tree, replacing the target group with a split container.
+/// The new group goes after the target, or before it with `new_first`.
 fn insert_split_in_layout(
     layout: &mut LayoutNode,
     target_group: GroupId,
     new_group: GroupId,
     direction: SplitDirection,
+    new_first: bool,
 ) {
     match layout {
         LayoutNode::Empty => {}
         LayoutNode::Group(id) if *id == target_group => {
             // Replace this group with a split containing both groups
+            let mut children = vec![
+                LayoutNode::Group(target_group),
+                LayoutNode::Group(new_group),
+            ];
+            if new_first {
+                children.reverse();
+            }
             *layout = LayoutNode::Split(SplitContainer {
                 direction,
-                children: vec![
-                    LayoutNode::Group(target_group),
-                    LayoutNode::Group(new_group),
-                ],
+                children,
                 ratios: vec![0.5, 0.5],
                 min_sizes: vec![100.0, 100.0],
             });
@@ -623,7 +856,7 @@ fn insert_split_in_layout(
         LayoutNode::Split(container) => {
             // Recursively search children
             for child in &mut container.children {
-                insert_split_in_layout(child, target_group, new_group, direction);
+                insert_split_in_layout(child, target_group, new_group, direction, new_first);
             }
         }
     }
@@ -765,9 +998,17 @@ fn collect_group_ids(layout: &LayoutNode) -> Vec<GroupId> {
 
 /// Focus the next or previous group
 fn focus_adjacent_group(model: &mut AppModel, next: bool) {
+    if let Some(group_id) = adjacent_group_id(model, next) {
+        model.editor_area.focused_group_id = group_id;
+    }
+}
+
+/// The group after (or before) the focused one in layout order, wrapping
+/// around. `None` when there is only one group.
+pub(super) fn adjacent_group_id(model: &AppModel, next: bool) -> Option<GroupId> {
     let group_ids = collect_group_ids(&model.editor_area.layout);
     if group_ids.len() <= 1 {
-        return;
+        return None;
     }
 
     let current_idx = group_ids
@@ -783,7 +1024,187 @@ fn focus_adjacent_group(model: &mut AppModel, next: bool) {
         current_idx - 1
     };
 
-    model.editor_area.focused_group_id = group_ids[new_idx];
+    Some(group_ids[new_idx])
+}
+
+/// Scroll the active editor of the next group without focusing it, e.g. to
+/// read a reference file while typing in the focused group
+fn scroll_other_group(model: &mut AppModel, delta: isize) -> bool {
+    let Some(group_id) = adjacent_group_id(model, true) else {
+        return false;
+    };
+    let Some(editor_id) = model
+        .editor_area
+        .groups
+        .get(&group_id)
+        .and_then(|group| group.active_tab())
+        .map(|tab| tab.editor_id)
+    else {
+        return false;
+    };
+    let editor_area = &mut model.editor_area;
+    let Some(editor) = editor_area.editors.get_mut(&editor_id) else {
+        return false;
+    };
+    let Some(document) = editor
+        .document_id
+        .and_then(|doc_id| editor_area.documents.get(&doc_id))
+    else {
+        return false;
+    };
+    editor.scroll_vertical_by(document, delta)
+}
+
+/// Groups whose active tab shows `document_id`, with that tab's top line
+fn groups_showing(model: &AppModel, document_id: DocumentId) -> Vec<(GroupId, usize)> {
+    let editor_area = &model.editor_area;
+    collect_group_ids(&editor_area.layout)
+        .into_iter()
+        .filter_map(|group_id| {
+            let editor_id = editor_area.groups.get(&group_id)?.active_editor_id()?;
+            let editor = editor_area.editors.get(&editor_id)?;
+            (editor.document_id == Some(document_id))
+                .then_some((group_id, editor.viewport.top_line))
+        })
+        .collect()
+}
+
+/// Lock the focused group's scrolling to the other groups showing its
+/// document, keeping the distance between their top lines, or unlock it
+fn toggle_scroll_lock(model: &mut AppModel) {
+    let Some(document_id) = model.editor_area.focused_document_id() else {
+        return;
+    };
+    let focused_id = model.editor_area.focused_group_id;
+    let groups = groups_showing(model, document_id);
+    let is_locked = |model: &AppModel, group_id: &GroupId| {
+        model
+            .editor_area
+            .groups
+            .get(group_id)
+            .is_some_and(|group| group.scroll_lock.is_some())
+    };
+
+    if is_locked(model, &focused_id) {
+        let mut unlock = vec![focused_id];
+        // A group left locked on its own has nothing to follow
+        let others: Vec<GroupId> = groups
+            .iter()
+            .map(|&(group_id, _)| group_id)
+            .filter(|group_id| *group_id != focused_id && is_locked(model, group_id))
+            .collect();
+        if others.len() == 1 {
+            unlock.extend(others);
+        }
+        for group_id in unlock {
+            if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
+                group.scroll_lock = None;
+            }
+        }
+        model.ui.set_status("Scroll lock off");
+        return;
+    }
+
+    if groups.len() < 2 {
+        model
+            .ui
+            .set_status("Open this document in another split to lock scrolling");
+        return;
+    }
+    for &(group_id, top_line) in &groups {
+        if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
+            group.scroll_lock = Some(ScrollLock {
+                anchor_line: top_line,
+                synced_line: top_line,
+            });
+        }
+    }
+    model
+        .ui
+        .set_status(format!("Scroll lock on for {} splits", groups.len()));
+}
+
+/// Keep preview tabs whose documents were edited open, so the next
+/// previewed file doesn't replace them. Returns whether any was kept.
+pub(super) fn sync_preview_tabs(model: &mut AppModel) -> bool {
+    let editor_area = &mut model.editor_area;
+    let mut changed = false;
+    for tab in editor_area
+        .groups
+        .values_mut()
+        .flat_map(|group| group.tabs.iter_mut())
+        .filter(|tab| tab.is_preview)
+    {
+        let modified = editor_area
+            .editors
+            .get(&tab.editor_id)
+            .and_then(|editor| editor.document_id)
+            .and_then(|id| editor_area.documents.get(&id))
+            .is_some_and(|document| document.is_modified);
+        if modified {
+            tab.is_preview = false;
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Scroll the locked partners of a scroll-locked group that scrolled since
+/// the last sync, whatever scrolled it. Returns whether any partner moved.
+pub(super) fn sync_scroll_locks(model: &mut AppModel) -> bool {
+    let editor_area = &mut model.editor_area;
+    let locked: Vec<(GroupId, DocumentId, usize, ScrollLock)> = editor_area
+        .groups
+        .values()
+        .filter_map(|group| {
+            let lock = group.scroll_lock?;
+            let editor = editor_area.editors.get(&group.active_editor_id()?)?;
+            Some((
+                group.id,
+                editor.document_id?,
+                editor.viewport.top_line,
+                lock,
+            ))
+        })
+        .collect();
+    // When several scrolled at once the focused group leads
+    let focused_id = editor_area.focused_group_id;
+    let Some(&(leader_id, document_id, top_line, lock)) = locked
+        .iter()
+        .filter(|(_, _, top_line, lock)| *top_line != lock.synced_line)
+        .max_by_key(|(group_id, ..)| *group_id == focused_id)
+    else {
+        return false;
+    };
+    let offset = top_line as isize - lock.anchor_line as isize;
+
+    let mut changed = false;
+    for &(group_id, partner_document_id, _, partner_lock) in &locked {
+        if partner_document_id != document_id {
+            continue;
+        }
+        let Some(group) = editor_area.groups.get_mut(&group_id) else {
+            continue;
+        };
+        let Some(editor) = group
+            .active_editor_id()
+            .and_then(|editor_id| editor_area.editors.get_mut(&editor_id))
+        else {
+            continue;
+        };
+        if group_id != leader_id {
+            let Some(document) = editor_area.documents.get(&document_id) else {
+                continue;
+            };
+            let target = partner_lock.anchor_line.saturating_add_signed(offset);
+            changed |= editor.set_top_line_clamped(document, target);
+        }
+        group.scroll_lock = Some(ScrollLock {
+            synced_line: editor.viewport.top_line,
+            ..partner_lock
+        });
+    }
+    changed
 }
 
 /// Move a tab to a different group
@@ -831,10 +1252,15 @@ fn move_tab(model: &mut AppModel, tab_id: TabId, to_group: GroupId) {
         }
     }
 
-    // Add the tab to the target group
+    // Add the tab to the target group, a pinned one after its pinned tabs
     if let Some(target_group) = model.editor_area.groups.get_mut(&to_group) {
-        target_group.tabs.push(tab);
-        target_group.active_tab_index = target_group.tabs.len() - 1;
+        let index = if tab.is_pinned {
+            target_group.pinned_count()
+        } else {
+            target_group.tabs.len()
+        };
+        target_group.tabs.insert(index, tab);
+        target_group.active_tab_index = index;
     }
 
     // If source group is now empty, close it (unless it's the last group)
@@ -849,7 +1275,130 @@ fn move_tab(model: &mut AppModel, tab_id: TabId, to_group: GroupId) {
     }
 }
 
+/// Which of the focused group's tabs `close_unpinned_tabs` closes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TabsToClose {
+    /// All but the active tab
+    Others,
+    /// Those right of the active tab
+    ToTheRight,
+    All,
+}
+
+/// Close some of the focused group's unpinned tabs. Tabs with unsaved
+/// changes are kept open, and the first of them asks whether to save.
+fn close_unpinned_tabs(model: &mut AppModel, which: TabsToClose) -> Option<Cmd> {
+    let group = model.editor_area.focused_group()?;
+    let active = group.active_tab_index;
+    let targets: Vec<TabId> = group
+        .tabs
+        .iter()
+        .enumerate()
+        .filter(|(index, tab)| {
+            !tab.is_pinned
+                && match which {
+                    TabsToClose::Others => *index != active,
+                    TabsToClose::ToTheRight => *index > active,
+                    TabsToClose::All => true,
+                }
+        })
+        .map(|(_, tab)| tab.id)
+        .collect();
+
+    let mut cmds = Vec::new();
+    let mut prompt = None;
+    for tab_id in targets {
+        match unsaved_changes_prompt(model, tab_id) {
+            Some(state) => {
+                prompt.get_or_insert(state);
+            }
+            None => cmds.push(discard_and_close_tab(model, tab_id)),
+        }
+    }
+    if let Some(state) = prompt {
+        model.ui.open_modal(ModalState::UnsavedChanges(state));
+    }
+    cmds.push(Cmd::Redraw);
+    Some(Cmd::Batch(cmds))
+}
+
+/// Move a tab into a new group split off `edge` of `group_id`, focusing it.
+/// A group's only tab can't be split off the group itself.
+fn move_tab_to_new_split(model: &mut AppModel, tab_id: TabId, group_id: GroupId, edge: DropEdge) {
+    if !model.editor_area.groups.contains_key(&group_id) {
+        return;
+    }
+    let Some((source_group_id, tab_idx)) =
+        model.editor_area.groups.iter().find_map(|(gid, group)| {
+            let idx = group.tabs.iter().position(|t| t.id == tab_id)?;
+            Some((*gid, idx))
+        })
+    else {
+        return;
+    };
+    if source_group_id == group_id && model.editor_area.groups[&group_id].tabs.len() == 1 {
+        return;
+    }
+
+    let Some(source) = model.editor_area.groups.get_mut(&source_group_id) else {
+        return;
+    };
+    let tab = source.tabs.remove(tab_idx);
+    if source.active_tab_index >= source.tabs.len() && !source.tabs.is_empty() {
+        source.active_tab_index = source.tabs.len() - 1;
+    }
+
+    let new_group_id = model.editor_area.next_group_id();
+    model.editor_area.groups.insert(
+        new_group_id,
+        EditorGroup {
+            id: new_group_id,
+            tabs: vec![tab],
+            active_tab_index: 0,
+            rect: Default::default(),
+            attached_preview: None,
+            tab_scroll: 0,
+            scroll_lock: None,
+        },
+    );
+    insert_split_in_layout(
+        &mut model.editor_area.layout,
+        group_id,
+        new_group_id,
+        edge.direction(),
+        edge.is_before(),
+    );
+    model.editor_area.focused_group_id = new_group_id;
+
+    // A group emptied by the move closes, as with MoveTab
+    if model
+        .editor_area
+        .groups
+        .get(&source_group_id)
+        .is_some_and(|g| g.tabs.is_empty())
+    {
+        close_group(model, source_group_id);
+    }
+}
+
 /// Close a specific tab
+/// Close a tab without asking about unsaved changes
+pub(super) fn discard_and_close_tab(model: &mut AppModel, tab_id: TabId) -> Cmd {
+    let released_documents = close_tab(model, tab_id);
+    ensure_focused_tab_visible(model);
+    with_released_documents(Cmd::redraw_editor(), released_documents)
+}
+
+/// Whether `close_tab` would refuse to close this tab (the very last one)
+pub(super) fn is_last_tab(model: &AppModel, tab_id: TabId) -> bool {
+    model.editor_area.groups.len() == 1
+        && model
+            .editor_area
+            .groups
+            .values()
+            .all(|group| group.tabs.len() == 1 && group.tabs[0].id == tab_id)
+}
+
 fn close_tab(model: &mut AppModel, tab_id: TabId) -> Vec<crate::model::editor_area::DocumentId> {
     // Find the tab and its group
     let mut found = None;
@@ -887,6 +1436,10 @@ fn close_tab(model: &mut AppModel, tab_id: TabId) -> Vec<crate::model::editor_ar
     // Remove the tab
     if let Some(group) = model.editor_area.groups.get_mut(&group_id) {
         group.tabs.remove(tab_idx);
+        // Closing a tab left of the active one keeps the same tab active
+        if tab_idx < group.active_tab_index {
+            group.active_tab_index -= 1;
+        }
         if group.active_tab_index >= group.tabs.len() && !group.tabs.is_empty() {
             group.active_tab_index = group.tabs.len() - 1;
         }
@@ -0,0 +1,112 @@
+//! Log viewer panel and runtime log verbosity (see [`crate::tracing`])
+
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::messages::DockMsg;
+use crate::model::status_bar::TransientMessage;
+use crate::model::AppModel;
+use crate::panel::PanelId;
+use crate::tracing::LogTarget;
+
+use super::dock::update_dock;
+
+/// Whether the log viewer is the active panel of an open dock
+pub(super) fn is_log_viewer_visible(model: &AppModel) -> bool {
+    model
+        .dock_layout
+        .find_panel(PanelId::Logs)
+        .is_some_and(|position| {
+            model
+                .dock_layout
+                .dock(position)
+                .is_panel_visible(PanelId::Logs)
+        })
+}
+
+/// Copy new log events into the panel while it is visible. Returns whether
+/// anything changed.
+pub(super) fn sync_log_viewer(model: &mut AppModel) -> bool {
+    if !is_log_viewer_visible(model) {
+        return false;
+    }
+    let generation = crate::tracing::log_generation();
+    let panel = &mut model.ui.log_panel;
+    if generation == panel.generation && !panel.entries.is_empty() {
+        return false;
+    }
+    // Keep a scrolled-up view on the same entries as new ones arrive
+    if panel.scroll_offset > 0 {
+        panel.scroll_offset += generation.saturating_sub(panel.generation) as usize;
+    }
+    panel.entries = crate::tracing::log_entries();
+    panel.generation = generation;
+    if panel.scroll_offset > 0 {
+        panel.scroll_offset = panel
+            .scroll_offset
+            .min(panel.entries.len().saturating_sub(1));
+    }
+    true
+}
+
+pub(super) fn toggle_log_viewer(model: &mut AppModel) -> Option<Cmd> {
+    let cmd = update_dock(model, DockMsg::TogglePanel(PanelId::LOGS));
+    sync_log_viewer(model);
+    cmd
+}
+
+pub(super) fn scroll_log_viewer(model: &mut AppModel, lines: i32) -> Option<Cmd> {
+    let panel = &mut model.ui.log_panel;
+    let max_offset = panel.entries.len().saturating_sub(1);
+    panel.scroll_offset = if lines < 0 {
+        (panel.scroll_offset + lines.unsigned_abs() as usize).min(max_offset)
+    } else {
+        panel.scroll_offset.saturating_sub(lines as usize)
+    };
+    Some(Cmd::Redraw)
+}
+
+/// Switch debug logging for `target` on or off
+pub(super) fn toggle_verbose_logging(model: &mut AppModel, target: LogTarget) -> Option<Cmd> {
+    let verbose = !target.is_verbose();
+    target.set_verbose(verbose);
+    let message = format!(
+        "Verbose {} logging {}",
+        target.name(),
+        if verbose { "on" } else { "off" }
+    );
+    tracing::info!("{}", message);
+    model.ui.transient_message = Some(TransientMessage::new(message, Duration::from_secs(3)));
+    sync_log_viewer(model);
+    Some(Cmd::Redraw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{Msg, UiMsg};
+    use crate::tracing::LogEntry;
+    use crate::update::update;
+
+    fn entry(message: &str) -> LogEntry {
+        LogEntry {
+            elapsed: Duration::ZERO,
+            level: tracing::Level::INFO,
+            target: "token".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn scrolling_stays_within_the_log() {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.ui.log_panel.entries = vec![entry("a"), entry("b"), entry("c")];
+
+        update(&mut model, Msg::Ui(UiMsg::ScrollLogViewer { lines: -10 }));
+        assert_eq!(model.ui.log_panel.scroll_offset, 2);
+        update(&mut model, Msg::Ui(UiMsg::ScrollLogViewer { lines: 1 }));
+        assert_eq!(model.ui.log_panel.scroll_offset, 1);
+        update(&mut model, Msg::Ui(UiMsg::ScrollLogViewer { lines: 5 }));
+        assert_eq!(model.ui.log_panel.scroll_offset, 0);
+    }
+}
@@ -0,0 +1,513 @@
+//! Language server update handlers
+//!
+//! Keeps servers in sync with open documents, turns published diagnostics
+//! into line decorations and end-of-line annotations, and answers hover and
+//! go-to-definition.
+
+use std::cmp::Reverse;
+use std::path::Path;
+
+use crate::commands::Cmd;
+use crate::lsp::{
+    self, Diagnostic, DiagnosticSeverity, Location, LspAction, LspPosition, LspRequest,
+    LspServerSpec, OpenDocument, PendingLookup,
+};
+use crate::messages::LspMsg;
+use crate::model::editor_area::DocumentId;
+use crate::model::{
+    Annotation, AnnotationSource, AppModel, DecorationKind, DecorationSource, GutterIcon,
+    LineDecoration,
+};
+use crate::syntax::LanguageId;
+
+/// Handle language server messages
+pub fn update_lsp(model: &mut AppModel, msg: LspMsg) -> Option<Cmd> {
+    match msg {
+        LspMsg::Hover => lookup(model, PendingLookup::Hover),
+        LspMsg::GoToDefinition => lookup(model, PendingLookup::Definition),
+
+        LspMsg::Diagnostics {
+            path,
+            version,
+            diagnostics,
+        } => {
+            apply_diagnostics(model, &path, version, &diagnostics);
+            Some(Cmd::redraw_editor())
+        }
+
+        LspMsg::HoverResult {
+            request_id,
+            contents,
+        } => {
+            if model.lsp.pending != Some(PendingLookup::Hover(request_id)) {
+                return None;
+            }
+            model.lsp.pending = None;
+            let summary = contents.as_deref().and_then(hover_summary);
+            model
+                .ui
+                .set_status(summary.unwrap_or_else(|| "No hover information".to_string()));
+            Some(Cmd::redraw_status_bar())
+        }
+
+        LspMsg::DefinitionResult {
+            request_id,
+            location,
+        } => {
+            if model.lsp.pending != Some(PendingLookup::Definition(request_id)) {
+                return None;
+            }
+            model.lsp.pending = None;
+            match location {
+                Some(location) => open_location(model, location),
+                None => {
+                    model.ui.set_status("No definition found");
+                    Some(Cmd::redraw_status_bar())
+                }
+            }
+        }
+
+        LspMsg::ServerStopped { server, error } => {
+            model
+                .lsp
+                .open_documents
+                .retain(|_, open| open.server.name() != server);
+            model
+                .ui
+                .set_status(format!("Language server {server} stopped: {error}"));
+            model.lsp.failed_servers.insert(server);
+            Some(Cmd::redraw_status_bar())
+        }
+    }
+}
+
+/// Server for a document at `path`, with its LSP language id. `None` when
+/// language servers are off, the workspace is restricted, none is
+/// configured, or it has stopped.
+fn server_for(
+    model: &AppModel,
+    path: &Path,
+    language: LanguageId,
+) -> Option<(LspServerSpec, &'static str)> {
+    if !model.config.lsp.enabled || !model.workspace_trusted() {
+        return None;
+    }
+    let language_id = lsp::language_id(language)?;
+    let config = model.config.lsp.servers.get(language_id)?.clone();
+    let root = model
+        .workspace
+        .as_ref()
+        .map(|workspace| workspace.root.clone())
+        .filter(|root| path.starts_with(root))
+        .or_else(|| path.parent().map(Path::to_path_buf))?;
+    let server = LspServerSpec { config, root };
+    let stopped = model.lsp.failed_servers.contains(&server.name());
+    (!stopped).then_some((server, language_id))
+}
+
+/// Bring language servers up to date with `document_id`'s text, and tell
+/// them about documents closed since the last sync. Called once edits have
+/// settled.
+pub(super) fn sync_document(model: &mut AppModel, document_id: DocumentId) -> Option<Cmd> {
+    let mut cmds = close_removed_documents(model);
+    cmds.extend(sync(model, document_id));
+    batch(cmds)
+}
+
+/// Tell the server a synced document was saved
+pub(super) fn did_save(model: &AppModel, document_id: DocumentId) -> Option<Cmd> {
+    let open = model.lsp.open_documents.get(&document_id)?;
+    Some(Cmd::Lsp(LspRequest {
+        server: open.server.clone(),
+        action: LspAction::DidSave {
+            path: open.path.clone(),
+        },
+    }))
+}
+
+fn sync(model: &mut AppModel, document_id: DocumentId) -> Option<Cmd> {
+    let doc = model.editor_area.documents.get(&document_id)?;
+    let path = doc.file_path.clone()?;
+    let (server, language_id) = server_for(model, &path, doc.language)?;
+    let version = doc.revision;
+
+    let mut cmds = Vec::new();
+    let action = match model.lsp.open_documents.get(&document_id) {
+        Some(open) if open.server == server && open.path == path => {
+            if open.version == version {
+                return None;
+            }
+            LspAction::DidChange {
+                path: path.clone(),
+                version,
+                text: doc.buffer.to_string(),
+            }
+        }
+        reopen => {
+            // Renamed, or its language changed: close it where it was
+            if let Some(open) = reopen {
+                cmds.push(Cmd::Lsp(LspRequest {
+                    server: open.server.clone(),
+                    action: LspAction::DidClose {
+                        path: open.path.clone(),
+                    },
+                }));
+            }
+            LspAction::DidOpen {
+                path: path.clone(),
+                language_id,
+                version,
+                text: doc.buffer.to_string(),
+            }
+        }
+    };
+    cmds.push(Cmd::Lsp(LspRequest {
+        server: server.clone(),
+        action,
+    }));
+    model.lsp.open_documents.insert(
+        document_id,
+        OpenDocument {
+            server,
+            path,
+            version,
+        },
+    );
+    batch(cmds)
+}
+
+fn close_removed_documents(model: &mut AppModel) -> Vec<Cmd> {
+    let documents = &model.editor_area.documents;
+    let closed: Vec<DocumentId> = model
+        .lsp
+        .open_documents
+        .keys()
+        .filter(|id| !documents.contains_key(id))
+        .copied()
+        .collect();
+    close_documents(model, closed)
+}
+
+/// Tell servers every synced document was closed, e.g. when the workspace
+/// is put in restricted mode
+pub(super) fn close_all_documents(model: &mut AppModel) -> Vec<Cmd> {
+    let all = model.lsp.open_documents.keys().copied().collect();
+    close_documents(model, all)
+}
+
+fn close_documents(model: &mut AppModel, ids: Vec<DocumentId>) -> Vec<Cmd> {
+    ids.into_iter()
+        .filter_map(|id| model.lsp.open_documents.remove(&id))
+        .map(|open| {
+            Cmd::Lsp(LspRequest {
+                server: open.server,
+                action: LspAction::DidClose { path: open.path },
+            })
+        })
+        .collect()
+}
+
+fn batch(mut cmds: Vec<Cmd>) -> Option<Cmd> {
+    match cmds.len() {
+        0 => None,
+        1 => cmds.pop(),
+        _ => Some(Cmd::Batch(cmds)),
+    }
+}
+
+/// Ask the server about the symbol at the primary cursor
+fn lookup(model: &mut AppModel, pending: fn(u64) -> PendingLookup) -> Option<Cmd> {
+    let document_id = model.editor_area.focused_document_id()?;
+    let doc = model.document();
+    let Some(path) = doc.file_path.clone() else {
+        model
+            .ui
+            .set_status("Save the file to use its language server");
+        return Some(Cmd::redraw_status_bar());
+    };
+    if server_for(model, &path, doc.language).is_none() {
+        let status = if !model.config.lsp.enabled {
+            "Language servers are off (set lsp.enabled in config.yaml)".to_string()
+        } else if !model.workspace_trusted() {
+            "Language servers don't run in restricted mode (run \"Trust Workspace\")".to_string()
+        } else {
+            format!("No language server for {}", doc.language.display_name())
+        };
+        model.ui.set_status(status);
+        return Some(Cmd::redraw_status_bar());
+    }
+
+    let cursor = model.editor().active_cursor();
+    let line = doc.get_line_cow(cursor.line).unwrap_or_default();
+    let position = LspPosition {
+        line: cursor.line as u32,
+        character: lsp::utf16_column(&line, cursor.column),
+    };
+
+    // The server must see the text the cursor is in
+    let mut cmds: Vec<Cmd> = sync(model, document_id).into_iter().collect();
+    let server = model.lsp.open_documents.get(&document_id)?.server.clone();
+    let request_id = model.lsp.next_request_id();
+    let lookup = pending(request_id);
+    let action = match lookup {
+        PendingLookup::Hover(request_id) => LspAction::Hover {
+            request_id,
+            path,
+            position,
+        },
+        PendingLookup::Definition(request_id) => LspAction::Definition {
+            request_id,
+            path,
+            position,
+        },
+    };
+    cmds.push(Cmd::Lsp(LspRequest { server, action }));
+    model.lsp.pending = Some(lookup);
+    batch(cmds)
+}
+
+/// First lines of a hover answer that fit the status bar, skipping code
+/// fences and rules
+fn hover_summary(contents: &str) -> Option<String> {
+    let lines: Vec<&str> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("```") && *line != "---")
+        .take(2)
+        .collect();
+    (!lines.is_empty()).then(|| lines.join(" — "))
+}
+
+fn open_location(model: &mut AppModel, location: Location) -> Option<Cmd> {
+    let line = location.position.line as usize;
+    let open = model
+        .editor_area
+        .documents
+        .iter()
+        .find(|(_, doc)| doc.file_path.as_deref() == Some(location.path.as_path()));
+    // A file that isn't open yet is taken to count columns in chars
+    let (document_id, column) = match open {
+        Some((id, doc)) => {
+            let text = doc.get_line_cow(line).unwrap_or_default();
+            (
+                Some(*id),
+                lsp::char_column(&text, location.position.character),
+            )
+        }
+        None => (None, location.position.character as usize),
+    };
+    super::todo::open_item(model, Some(location.path), document_id, line, column)
+}
+
+fn decoration_kind(severity: DiagnosticSeverity) -> Option<DecorationKind> {
+    match severity {
+        DiagnosticSeverity::Error => Some(DecorationKind::Error),
+        DiagnosticSeverity::Warning => Some(DecorationKind::Warning),
+        DiagnosticSeverity::Information => Some(DecorationKind::Highlight),
+        // Hints (unused code, style nits) would drown out the rest
+        DiagnosticSeverity::Hint => None,
+    }
+}
+
+/// Show `diagnostics` on the documents open at `path`. They are stamped with
+/// the version they were computed for, so they hide once the text changes.
+fn apply_diagnostics(
+    model: &mut AppModel,
+    path: &Path,
+    version: Option<u64>,
+    diagnostics: &[Diagnostic],
+) {
+    let mut shown: Vec<&Diagnostic> = diagnostics
+        .iter()
+        .filter(|diagnostic| decoration_kind(diagnostic.severity).is_some())
+        .collect();
+    // Most severe first on each line
+    shown.sort_by_key(|diagnostic| (diagnostic.start.line, Reverse(diagnostic.severity)));
+
+    let open_documents = &model.lsp.open_documents;
+    for (id, doc) in model.editor_area.documents.iter_mut() {
+        if doc.file_path.as_deref() != Some(path) {
+            continue;
+        }
+        let revision = version
+            .or_else(|| open_documents.get(id).map(|open| open.version))
+            .unwrap_or(doc.revision);
+
+        let decorations = shown.iter().filter_map(|diagnostic| {
+            let kind = decoration_kind(diagnostic.severity)?;
+            let line = diagnostic.start.line as usize;
+            let decoration = if kind == DecorationKind::Error {
+                LineDecoration::tint(line, kind).with_icon(GutterIcon::Dot)
+            } else {
+                LineDecoration::icon(line, kind, GutterIcon::Dot)
+            };
+            Some(decoration.with_priority(diagnostic.severity as i32))
+        });
+        doc.decorations
+            .set(DecorationSource::Diagnostics, revision, decorations);
+
+        let mut annotations: Vec<Annotation> = Vec::new();
+        for diagnostic in &shown {
+            let line = diagnostic.start.line as usize;
+            if annotations.last().is_some_and(|last| last.line == line) {
+                continue;
+            }
+            let message = diagnostic.message.lines().next().unwrap_or_default();
+            annotations.push(Annotation::end_of_line(line, message));
+        }
+        doc.annotations
+            .set(AnnotationSource::Diagnostics, revision, annotations);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A model with `fn main() {}` open as `/project/main.rs` and language
+    /// servers turned on
+    fn rust_model() -> (AppModel, DocumentId) {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.config.lsp.enabled = true;
+        let document_id = model.editor_area.focused_document_id().unwrap();
+        let doc = model.document_mut();
+        doc.file_path = Some("/project/main.rs".into());
+        doc.language = LanguageId::Rust;
+        doc.buffer = ropey::Rope::from_str("fn main() {\n    let x = 1;\n}\n");
+        (model, document_id)
+    }
+
+    fn actions(cmd: Option<Cmd>) -> Vec<LspAction> {
+        match cmd {
+            Some(Cmd::Lsp(request)) => vec![request.action],
+            Some(Cmd::Batch(cmds)) => cmds.into_iter().flat_map(|c| actions(Some(c))).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn documents_open_then_change_then_close() {
+        let (mut model, document_id) = rust_model();
+
+        let opened = actions(sync_document(&mut model, document_id));
+        assert!(matches!(
+            opened.as_slice(),
+            [LspAction::DidOpen {
+                language_id: "rust",
+                version: 0,
+                ..
+            }]
+        ));
+        assert!(sync_document(&mut model, document_id).is_none());
+
+        model.document_mut().revision = 1;
+        let changed = actions(sync_document(&mut model, document_id));
+        assert!(matches!(
+            changed.as_slice(),
+            [LspAction::DidChange { version: 1, .. }]
+        ));
+
+        model.editor_area.documents.remove(&document_id);
+        let other = DocumentId(document_id.0 + 100);
+        let closed = actions(sync_document(&mut model, other));
+        assert_eq!(
+            closed,
+            vec![LspAction::DidClose {
+                path: "/project/main.rs".into()
+            }]
+        );
+        assert!(model.lsp.open_documents.is_empty());
+    }
+
+    #[test]
+    fn nothing_is_sent_when_off_or_stopped() {
+        let (mut model, document_id) = rust_model();
+        model.config.lsp.enabled = false;
+        assert!(sync_document(&mut model, document_id).is_none());
+
+        model.config.lsp.enabled = true;
+        update_lsp(
+            &mut model,
+            LspMsg::ServerStopped {
+                server: "rust-analyzer".to_string(),
+                error: "not found".to_string(),
+            },
+        );
+        assert!(sync_document(&mut model, document_id).is_none());
+        assert!(model.ui.status_message.contains("rust-analyzer"));
+    }
+
+    #[test]
+    fn diagnostics_decorate_lines_for_their_version() {
+        let (mut model, document_id) = rust_model();
+        sync_document(&mut model, document_id);
+        let at = |line, character| LspPosition { line, character };
+        let diagnostic = |line, severity, message: &str| Diagnostic {
+            start: at(line, 4),
+            end: at(line, 5),
+            severity,
+            message: message.to_string(),
+            source: None,
+        };
+
+        update_lsp(
+            &mut model,
+            LspMsg::Diagnostics {
+                path: "/project/main.rs".into(),
+                version: None,
+                diagnostics: vec![
+                    diagnostic(1, DiagnosticSeverity::Warning, "unused variable\nnote"),
+                    diagnostic(1, DiagnosticSeverity::Error, "mismatched types"),
+                    diagnostic(0, DiagnosticSeverity::Hint, "hint"),
+                ],
+            },
+        );
+
+        let doc = model.document();
+        let style = doc.decorations.style(1, 0);
+        assert_eq!(style.tint, Some(DecorationKind::Error));
+        assert_eq!(style.icon, Some((GutterIcon::Dot, DecorationKind::Error)));
+        assert!(doc.decorations.style(0, 0).is_plain());
+        let annotations = doc.annotations.on_line(1, 0);
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0].text, "mismatched types");
+
+        // Stale once the text moves on
+        assert!(doc.decorations.style(1, 1).is_plain());
+    }
+
+    #[test]
+    fn only_the_latest_lookup_is_answered() {
+        let (mut model, _) = rust_model();
+        let sent = actions(update_lsp(&mut model, LspMsg::Hover));
+        let [LspAction::DidOpen { .. }, LspAction::Hover {
+            request_id,
+            position,
+            ..
+        }] = sent.as_slice()
+        else {
+            panic!("expected didOpen then hover");
+        };
+        assert_eq!(*position, LspPosition::default());
+        let request_id = *request_id;
+
+        update_lsp(
+            &mut model,
+            LspMsg::HoverResult {
+                request_id: request_id + 1,
+                contents: Some("stale".to_string()),
+            },
+        );
+        assert!(!model.ui.status_message.contains("stale"));
+
+        update_lsp(
+            &mut model,
+            LspMsg::HoverResult {
+                request_id,
+                contents: Some("```rust\nfn main()\n```\n---\nThe entry point".to_string()),
+            },
+        );
+        assert_eq!(model.ui.status_message, "fn main() — The entry point");
+        assert_eq!(model.lsp.pending, None);
+    }
+}
@@ -2,23 +2,47 @@
 //!
 //! All state transformations flow through these functions.
 
+mod ai_chat;
 mod app;
+mod autosave;
+mod completion;
+mod crash_recovery;
 mod csv;
+mod diff;
 mod dock;
 mod document;
+mod edit_review;
 mod editor;
+mod external_tools;
+mod file_changes;
+mod file_moves;
+mod folding;
+mod git;
 mod image;
+mod large_file;
 pub mod layout;
+mod logs;
+mod lsp;
+mod notes;
 mod outline;
+mod paste_image;
 mod preview;
+mod project_search;
+mod scratchpad;
+mod search;
 mod syntax;
 mod terminal;
 mod text_edit;
+mod todo;
 mod ui;
+mod unsaved_changes;
+mod vim;
+mod welcome;
 mod workspace;
+mod workspace_trust;
 
 use crate::commands::Cmd;
-use crate::messages::{CsvMsg, Direction, DocumentMsg, EditorMsg, Msg};
+use crate::messages::{CsvMsg, DiffMsg, Direction, DocumentMsg, EditorMsg, Msg, WelcomeMsg};
 use crate::model::sync_status_bar;
 use crate::model::AppModel;
 
@@ -27,18 +51,30 @@ use crate::tracing::CursorSnapshot;
 #[cfg(debug_assertions)]
 use tracing::{debug, span, Level};
 
+pub use ai_chat::update_ai_chat;
 pub use app::{create_default_keymap_file, execute_command, update_app};
+pub use autosave::autosave_interval;
+pub use completion::update_completion;
 pub use csv::update_csv;
+pub use diff::update_diff;
 pub use dock::update_dock;
 pub use document::update_document;
-pub use editor::update_editor;
+pub use editor::{update_editor, word_highlight_deadline, WORD_HIGHLIGHT_DEBOUNCE_MS};
+pub use large_file::sync_large_file_loads;
 pub use layout::update_layout;
+pub use lsp::update_lsp;
+pub use notes::update_notes;
 pub use outline::update_outline;
 pub use preview::update_preview;
+pub use project_search::update_project_search;
+pub use scratchpad::save_scratchpads;
 pub use syntax::{schedule_syntax_parse, update_syntax, SYNTAX_DEBOUNCE_MS};
 pub use terminal::update_terminal;
 pub use text_edit::{apply_text_edit_msg, update_text_edit};
+pub use todo::update_todo;
 pub use ui::update_ui;
+pub use vim::update_vim;
+pub use welcome::update_welcome;
 pub use workspace::update_workspace;
 
 /// Main update function - dispatches to sub-handlers
@@ -47,6 +83,10 @@ pub use workspace::update_workspace;
 /// In release builds, it's a direct dispatch with zero overhead.
 #[inline]
 pub fn update(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
+    if !matches!(msg, Msg::Ui(crate::messages::UiMsg::BlinkCursor)) {
+        crate::crash::record_message(&msg);
+    }
+    crate::replay::record(&msg);
     #[cfg(debug_assertions)]
     {
         update_traced(model, msg)
@@ -59,8 +99,15 @@ pub fn update(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
 
 /// Inner update logic (no tracing)
 fn update_inner(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
+    let focused_document = model.editor_area.focused_document_id();
     let result = match msg {
         Msg::Editor(m) => {
+            // On the welcome tab, navigation moves the entry selection
+            if is_welcome_focused(model) {
+                return map_editor_to_welcome(&m)
+                    .and_then(|welcome_msg| welcome::update_welcome(model, welcome_msg));
+            }
+
             // Block editor messages in image mode and binary placeholder mode
             let is_non_text = model.editor_area.focused_editor().is_some_and(|e| {
                 e.view_mode.is_image()
@@ -73,6 +120,21 @@ fn update_inner(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
                 return None;
             }
 
+            // In the diff view, movement scrolls both halves
+            if model
+                .editor_area
+                .focused_editor()
+                .is_some_and(|e| e.view_mode.is_diff())
+            {
+                return map_editor_to_diff(&m)
+                    .and_then(|diff_msg| diff::update_diff(model, diff_msg));
+            }
+
+            // Moving the cursor ends the undo step being typed
+            if !matches!(m, EditorMsg::Scroll(_) | EditorMsg::ScrollHorizontal(_)) {
+                model.document_mut().end_undo_group();
+            }
+
             // When in CSV mode, intercept navigation messages and route to CSV
             let csv_info = model
                 .editor_area
@@ -88,9 +150,21 @@ fn update_inner(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
             editor::update_editor(model, m)
         }
         Msg::Document(m) => {
-            // Block document messages in image mode and binary placeholder mode
+            // On the welcome tab, Enter runs the selected entry
+            if is_welcome_focused(model) {
+                return match m {
+                    DocumentMsg::InsertNewline => {
+                        welcome::update_welcome(model, WelcomeMsg::ActivateSelected)
+                    }
+                    _ => None,
+                };
+            }
+
+            // Block document messages in image mode, the diff view and
+            // binary placeholder mode
             let is_non_text = model.editor_area.focused_editor().is_some_and(|e| {
                 e.view_mode.is_image()
+                    || e.view_mode.is_diff()
                     || matches!(
                         e.tab_content,
                         crate::model::editor::TabContent::BinaryPlaceholder(_)
@@ -110,9 +184,32 @@ fn update_inner(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
                 if let Some(csv_msg) = map_document_to_csv(&m, is_editing) {
                     return csv::update_csv(model, csv_msg);
                 }
-                return None;
+                // Grid changes (like sorting) are undone like text edits
+                if is_editing || !matches!(m, DocumentMsg::Undo | DocumentMsg::Redo) {
+                    return None;
+                }
             }
-            document::update_document(model, m)
+
+            // An edit while Shift+Alt still grows a column selection types
+            // at its cursors
+            if model.editor().rectangle_selection.from_keyboard {
+                editor::update_editor(model, EditorMsg::FinishRectangleSelection);
+            }
+
+            // In Markdown, Tab / Shift+Tab on a heading cycle its folds
+            if let Some(cycle) = folding::heading_cycle_for(model, &m) {
+                return editor::update_editor(model, cycle);
+            }
+            let typed_slash = matches!(m, DocumentMsg::InsertChar('/'));
+            let cmd = document::update_document(model, m);
+            // A `/` typed into a string or link offers path completions
+            if typed_slash && completion::open_after_slash(model) {
+                return Some(match cmd {
+                    Some(cmd) => Cmd::batch(vec![cmd, Cmd::Redraw]),
+                    None => Cmd::Redraw,
+                });
+            }
+            cmd
         }
         Msg::Ui(m) => ui::update_ui(model, m),
         Msg::Layout(m) => layout::update_layout(model, m),
@@ -120,21 +217,89 @@ fn update_inner(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
         Msg::Syntax(m) => syntax::update_syntax(model, m),
         Msg::Csv(m) => csv::update_csv(model, m),
         Msg::Image(m) => image::update_image(model, m),
+        Msg::Diff(m) => diff::update_diff(model, m),
         Msg::Preview(m) => preview::update_preview(model, m),
         Msg::Workspace(m) => workspace::update_workspace(model, m),
         Msg::Dock(m) => dock::update_dock(model, m),
         Msg::Outline(m) => outline::update_outline(model, m),
+        Msg::Todo(m) => todo::update_todo(model, m),
+        Msg::Search(m) => project_search::update_project_search(model, m),
+        Msg::AiChat(m) => ai_chat::update_ai_chat(model, m),
+        Msg::Lsp(m) => lsp::update_lsp(model, m),
+        Msg::Welcome(m) => welcome::update_welcome(model, m),
+        Msg::Notes(m) => notes::update_notes(model, m),
+        Msg::Completion(m) => completion::update_completion(model, m),
+        Msg::Vim(m) => vim::update_vim(model, m),
         Msg::TextEdit(context, m) => text_edit::update_text_edit(model, context, m),
         Msg::Terminal(m) => terminal::update_terminal(model, m),
+        Msg::Git(m) => git::update_git(model, m),
     };
 
+    folding::drop_stale_folds(model);
+    editor::sync_word_highlights(model);
+    let result = match autosave::on_focus_change(model, focused_document) {
+        Some(save) => Some(Cmd::batch(result.into_iter().chain([save]).collect())),
+        None => result,
+    };
+    let result = match large_file::sync_large_file_loads(model) {
+        Some(load) => Some(Cmd::batch(result.into_iter().chain([load]).collect())),
+        None => result,
+    };
+    let result = match git::sync_git_diffs(model) {
+        Some(diff) => Some(Cmd::batch(result.into_iter().chain([diff]).collect())),
+        None => result,
+    };
+    let result = match git::sync_git_blame(model) {
+        Some(blame) => Some(Cmd::batch(result.into_iter().chain([blame]).collect())),
+        None => result,
+    };
+    let result = if diff::sync_diff_views(model) {
+        Some(match result {
+            Some(cmd) => Cmd::batch(vec![cmd, Cmd::Redraw]),
+            None => Cmd::Redraw,
+        })
+    } else {
+        result
+    };
+    let result = if layout::sync_scroll_locks(model) {
+        Some(match result {
+            Some(cmd) => Cmd::batch(vec![cmd, Cmd::redraw_editor()]),
+            None => Cmd::redraw_editor(),
+        })
+    } else {
+        result
+    };
+    let result = if layout::sync_preview_tabs(model) {
+        Some(match result {
+            Some(cmd) => Cmd::batch(vec![cmd, Cmd::redraw_editor()]),
+            None => Cmd::redraw_editor(),
+        })
+    } else {
+        result
+    };
+    let result = if outline::sync_outline_selection(model) {
+        Some(match result {
+            Some(cmd) => Cmd::batch(vec![cmd, Cmd::Redraw]),
+            None => Cmd::Redraw,
+        })
+    } else {
+        result
+    };
+    let result = if completion::sync_completion(model) {
+        Some(match result {
+            Some(cmd) => Cmd::batch(vec![cmd, Cmd::Redraw]),
+            None => Cmd::Redraw,
+        })
+    } else {
+        result
+    };
     sync_status_bar(model);
     result
 }
 
 /// Map text editor movement messages to CSV navigation messages
 ///
-/// When not editing: arrows move cell selection
+/// When not editing: arrows move cell selection, Shift+arrows grow a range
 /// When editing: left/right move cursor in cell, up/down confirm and navigate
 fn map_editor_to_csv(editor_msg: &EditorMsg, is_editing: bool) -> Option<CsvMsg> {
     match (editor_msg, is_editing) {
@@ -153,6 +318,10 @@ fn map_editor_to_csv(editor_msg: &EditorMsg, is_editing: bool) -> Option<CsvMsg>
         (EditorMsg::MoveCursor(Direction::Down), false) => Some(CsvMsg::MoveDown),
         (EditorMsg::MoveCursor(Direction::Left), false) => Some(CsvMsg::MoveLeft),
         (EditorMsg::MoveCursor(Direction::Right), false) => Some(CsvMsg::MoveRight),
+        (EditorMsg::MoveCursorWithSelection(Direction::Up), false) => Some(CsvMsg::ExtendUp),
+        (EditorMsg::MoveCursorWithSelection(Direction::Down), false) => Some(CsvMsg::ExtendDown),
+        (EditorMsg::MoveCursorWithSelection(Direction::Left), false) => Some(CsvMsg::ExtendLeft),
+        (EditorMsg::MoveCursorWithSelection(Direction::Right), false) => Some(CsvMsg::ExtendRight),
         (EditorMsg::MoveCursorLineStart, false) => Some(CsvMsg::RowStart),
         (EditorMsg::MoveCursorLineEnd, false) => Some(CsvMsg::RowEnd),
         (EditorMsg::MoveCursorDocumentStart, _) => Some(CsvMsg::FirstCell),
@@ -163,9 +332,46 @@ fn map_editor_to_csv(editor_msg: &EditorMsg, is_editing: bool) -> Option<CsvMsg>
     }
 }
 
+/// Map text editor movement messages to diff view scrolling
+fn map_editor_to_diff(editor_msg: &EditorMsg) -> Option<DiffMsg> {
+    match editor_msg {
+        EditorMsg::MoveCursor(Direction::Up) => Some(DiffMsg::Scroll(-1)),
+        EditorMsg::MoveCursor(Direction::Down) => Some(DiffMsg::Scroll(1)),
+        EditorMsg::MoveCursor(Direction::Left) => Some(DiffMsg::ScrollHorizontal(-1)),
+        EditorMsg::MoveCursor(Direction::Right) => Some(DiffMsg::ScrollHorizontal(1)),
+        EditorMsg::Scroll(delta) => Some(DiffMsg::Scroll(*delta)),
+        EditorMsg::ScrollHorizontal(delta) => Some(DiffMsg::ScrollHorizontal(*delta)),
+        EditorMsg::PageUp => Some(DiffMsg::PageUp),
+        EditorMsg::PageDown => Some(DiffMsg::PageDown),
+        EditorMsg::MoveCursorDocumentStart => Some(DiffMsg::Top),
+        EditorMsg::MoveCursorDocumentEnd => Some(DiffMsg::Bottom),
+        _ => None,
+    }
+}
+
+/// Whether the focused tab is the welcome tab
+fn is_welcome_focused(model: &AppModel) -> bool {
+    model
+        .editor_area
+        .focused_editor()
+        .is_some_and(|e| matches!(e.tab_content, crate::model::editor::TabContent::Welcome(_)))
+}
+
+/// Map text editor movement messages to welcome tab navigation
+fn map_editor_to_welcome(editor_msg: &EditorMsg) -> Option<WelcomeMsg> {
+    match editor_msg {
+        EditorMsg::MoveCursor(Direction::Up) => Some(WelcomeMsg::SelectPrevious),
+        EditorMsg::MoveCursor(Direction::Down) => Some(WelcomeMsg::SelectNext),
+        EditorMsg::MoveCursorDocumentStart | EditorMsg::PageUp => Some(WelcomeMsg::SelectFirst),
+        EditorMsg::MoveCursorDocumentEnd | EditorMsg::PageDown => Some(WelcomeMsg::SelectLast),
+        _ => None,
+    }
+}
+
 /// Map document messages to CSV cell editing messages
 ///
-/// When not editing: InsertNewline starts editing, InsertChar starts with that char
+/// When not editing: InsertNewline starts editing, InsertChar starts with that char,
+/// Copy copies the selected cells
 /// When editing: InsertNewline confirms edit, InsertChar inserts into buffer
 fn map_document_to_csv(doc_msg: &DocumentMsg, is_editing: bool) -> Option<CsvMsg> {
     match (doc_msg, is_editing) {
@@ -175,6 +381,7 @@ fn map_document_to_csv(doc_msg: &DocumentMsg, is_editing: bool) -> Option<CsvMsg
         (DocumentMsg::InsertChar(ch), true) => Some(CsvMsg::EditInsertChar(*ch)),
         (DocumentMsg::DeleteBackward, true) => Some(CsvMsg::EditDeleteBackward),
         (DocumentMsg::DeleteForward, true) => Some(CsvMsg::EditDeleteForward),
+        (DocumentMsg::Copy, false) => Some(CsvMsg::CopySelection),
         _ => None,
     }
 }
@@ -198,6 +405,9 @@ fn update_traced(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
     };
 
     let before = model.focused_editor().map(CursorSnapshot::from_editor);
+    // Viewport-only messages must leave every editor's cursors alone, not
+    // just the focused one's (split views scroll and resize together)
+    let view_only_before = is_viewport_only(&msg).then(|| model.editor_area.editors.clone());
 
     if !is_noisy {
         debug!(target: "message", msg = %msg_name, "processing");
@@ -219,6 +429,13 @@ fn update_traced(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
     if let Some(editor) = model.focused_editor() {
         editor.assert_invariants_with_context(&msg_name);
     }
+    if let Some(before) = view_only_before {
+        for (id, editor) in &model.editor_area.editors {
+            if let Some(before) = before.get(id) {
+                editor.assert_view_only_invariants_with_context(before, &msg_name);
+            }
+        }
+    }
 
     if !is_noisy {
         if let Some(ref mut overlay) = model.debug_overlay {
@@ -229,6 +446,31 @@ fn update_traced(model: &mut AppModel, msg: Msg) -> Option<Cmd> {
     result
 }
 
+/// Messages that only move or repaint the view: scrolling, scrollbars,
+/// window resize and scale, and configuration (theme) reloads. They must not
+/// touch cursors, selections or `desired_column`.
+#[cfg(debug_assertions)]
+fn is_viewport_only(msg: &Msg) -> bool {
+    use crate::messages::{AppMsg, LayoutMsg, UiMsg};
+
+    matches!(
+        msg,
+        Msg::Editor(EditorMsg::Scroll(_) | EditorMsg::ScrollHorizontal(_))
+            | Msg::Layout(LayoutMsg::ScrollOtherGroup(_) | LayoutMsg::ScrollTabBar { .. })
+            | Msg::App(
+                AppMsg::Resize(..) | AppMsg::ScaleFactorChanged(_) | AppMsg::ReloadConfiguration
+            )
+            | Msg::Ui(
+                UiMsg::ScrollbarTrackClickedVertical { .. }
+                    | UiMsg::ScrollbarTrackClickedHorizontal { .. }
+                    | UiMsg::ScrollbarThumbPressedVertical { .. }
+                    | UiMsg::ScrollbarThumbPressedHorizontal { .. }
+                    | UiMsg::ScrollbarDragUpdate { .. }
+                    | UiMsg::ScrollbarDragEnd
+            )
+    )
+}
+
 /// Get a display name for a message type
 ///
 /// Uses Debug formatting to include variant names and arguments.
@@ -247,11 +489,21 @@ fn msg_type_name(msg: &Msg) -> String {
         Msg::Syntax(m) => format!("Syntax::{:?}", m),
         Msg::Csv(m) => format!("Csv::{:?}", m),
         Msg::Image(m) => format!("Image::{:?}", m),
+        Msg::Diff(m) => format!("Diff::{:?}", m),
         Msg::Preview(m) => format!("Preview::{:?}", m),
         Msg::Workspace(m) => format!("Workspace::{:?}", m),
         Msg::Dock(m) => format!("Dock::{:?}", m),
         Msg::Outline(m) => format!("Outline::{:?}", m),
+        Msg::Todo(m) => format!("Todo::{:?}", m),
+        Msg::Search(m) => format!("Search::{:?}", m),
+        Msg::AiChat(m) => format!("AiChat::{:?}", m),
+        Msg::Lsp(m) => format!("Lsp::{:?}", m),
+        Msg::Welcome(m) => format!("Welcome::{:?}", m),
+        Msg::Notes(m) => format!("Notes::{:?}", m),
+        Msg::Completion(m) => format!("Completion::{:?}", m),
+        Msg::Vim(m) => format!("Vim::{:?}", m),
         Msg::TextEdit(ctx, m) => format!("TextEdit::{:?}::{:?}", ctx, m),
         Msg::Terminal(m) => format!("Terminal::{:?}", m),
+        Msg::Git(m) => format!("Git::{:?}", m),
     }
 }
@@ -0,0 +1,249 @@
+//! Markdown note links: following `[text](path.md)` and `[[wiki-links]]`,
+//! and listing the notes that link to the current one
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::messages::{LayoutMsg, NotesMsg};
+use crate::model::status_bar::TransientMessage;
+use crate::model::{AppModel, BacklinksState, CreateFileState, ModalState};
+use crate::notes::{is_markdown_path, link_at, resolve_link, BacklinkScanRequest};
+use crate::syntax::LanguageId;
+use crate::util::filename_for_display;
+
+use super::layout::update_layout;
+
+/// Handle note link messages
+pub fn update_notes(model: &mut AppModel, msg: NotesMsg) -> Option<Cmd> {
+    match msg {
+        NotesMsg::FollowLinkAt { line, column } => follow_link(model, line, column),
+
+        NotesMsg::FollowLinkAtCursor => {
+            let cursor = model.editor().active_cursor();
+            let (line, column) = (cursor.line, cursor.column);
+            follow_link(model, line, column)
+                .or_else(|| show_message(model, "No link under the cursor"))
+        }
+
+        NotesMsg::ShowBacklinks => {
+            let Some(target) = model
+                .editor_area
+                .focused_document()
+                .and_then(|doc| doc.file_path.clone())
+            else {
+                return show_message(model, "Save the document to find links to it");
+            };
+            model.ui.set_status(format!(
+                "Finding backlinks to {}…",
+                filename_for_display(&target)
+            ));
+            Some(Cmd::Batch(vec![
+                Cmd::FindBacklinks {
+                    request: backlink_request(model, target),
+                },
+                Cmd::redraw_status_bar(),
+            ]))
+        }
+
+        NotesMsg::BacklinksFound { target, backlinks } => {
+            let name = filename_for_display(&target);
+            if backlinks.is_empty() {
+                model.ui.set_status(format!("No notes link to {}", name));
+                return Some(Cmd::redraw_status_bar());
+            }
+            model.ui.set_status(format!(
+                "{} backlink{} to {}",
+                backlinks.len(),
+                if backlinks.len() == 1 { "" } else { "s" },
+                name
+            ));
+            // Don't replace whatever the user opened while the scan ran
+            if model.ui.active_modal.is_none() {
+                model
+                    .ui
+                    .open_modal(ModalState::Backlinks(BacklinksState::new(name, backlinks)));
+            }
+            Some(Cmd::Redraw)
+        }
+    }
+}
+
+/// Open the target of the link at `line`/`column` of the focused Markdown
+/// document, or prompt to create it when it doesn't exist. `None` when
+/// there is no link to a local file there.
+fn follow_link(model: &mut AppModel, line: usize, column: usize) -> Option<Cmd> {
+    let doc = model.editor_area.focused_document()?;
+    if doc.language != LanguageId::Markdown {
+        return None;
+    }
+    let document_path = doc.file_path.clone()?;
+    let link = link_at(&doc.get_line_cow(line)?, column)?;
+    let workspace_files = workspace_files(model);
+    let target = resolve_link(&link, &document_path, &workspace_files)?;
+
+    if target.is_dir() {
+        model
+            .ui
+            .set_status(format!("{} is a folder", filename_for_display(&target)));
+        return Some(Cmd::redraw_status_bar());
+    }
+    if target.exists() {
+        return update_layout(model, LayoutMsg::OpenFileInNewTab(target));
+    }
+
+    let dir = document_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let dir = crate::util::normalize_path(&dir);
+    let relative = target
+        .strip_prefix(&dir)
+        .unwrap_or(&target)
+        .to_string_lossy()
+        .to_string();
+    model
+        .ui
+        .open_modal(ModalState::CreateFile(CreateFileState::new(dir, &relative)));
+    Some(Cmd::Redraw)
+}
+
+fn workspace_files(model: &AppModel) -> Vec<PathBuf> {
+    model
+        .workspace
+        .as_ref()
+        .map(|workspace| workspace.file_tree.get_all_file_paths())
+        .unwrap_or_default()
+}
+
+/// Snapshot open Markdown documents and the workspace file list for a
+/// background scan
+fn backlink_request(model: &AppModel, target: PathBuf) -> BacklinkScanRequest {
+    let mut open_documents: Vec<(PathBuf, String)> = model
+        .editor_area
+        .documents
+        .values()
+        .filter_map(|doc| {
+            let path = doc
+                .file_path
+                .as_ref()
+                .filter(|path| is_markdown_path(path))?;
+            Some((path.clone(), doc.buffer.to_string()))
+        })
+        .collect();
+    open_documents.sort();
+
+    BacklinkScanRequest {
+        target,
+        workspace_root: model.workspace.as_ref().map(|w| w.root.clone()),
+        open_documents,
+        workspace_files: workspace_files(model),
+    }
+}
+
+fn show_message(model: &mut AppModel, message: &str) -> Option<Cmd> {
+    model.ui.transient_message = Some(TransientMessage::new(
+        message.to_string(),
+        Duration::from_secs(3),
+    ));
+    Some(Cmd::redraw_status_bar())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{ModalMsg, Msg, UiMsg, WorkspaceMsg};
+    use crate::update::update;
+
+    fn note_model(dir: &Path, text: &str) -> AppModel {
+        let path = dir.join("index.md");
+        std::fs::write(&path, text).unwrap();
+        AppModel::new(800, 600, 1.0, vec![path])
+    }
+
+    fn focused_path(model: &AppModel) -> Option<PathBuf> {
+        model.document().file_path.clone()
+    }
+
+    #[test]
+    fn following_link_opens_existing_note() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("todo.md"), "# Todo\n").unwrap();
+        let mut model = note_model(&root, "See [the list](todo.md).\n");
+
+        update(
+            &mut model,
+            Msg::Notes(NotesMsg::FollowLinkAt { line: 0, column: 6 }),
+        );
+        assert_eq!(focused_path(&model), Some(root.join("todo.md")));
+    }
+
+    #[test]
+    fn following_link_outside_any_link_does_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut model = note_model(dir.path(), "plain [[text\n");
+
+        let cmd = update(
+            &mut model,
+            Msg::Notes(NotesMsg::FollowLinkAt { line: 0, column: 2 }),
+        );
+        assert!(cmd.is_none());
+        assert!(model.ui.active_modal.is_none());
+    }
+
+    #[test]
+    fn missing_wiki_link_target_is_created_on_confirm() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let mut model = note_model(&root, "[[Ideas/Later]]\n");
+
+        update(&mut model, Msg::Notes(NotesMsg::FollowLinkAtCursor));
+        let Some(ModalState::CreateFile(state)) = &model.ui.active_modal else {
+            panic!("expected the create file prompt");
+        };
+        assert_eq!(state.input(), "Ideas/Later.md");
+        let target = state.target().unwrap();
+        assert_eq!(target, root.join("Ideas/Later.md"));
+
+        let cmd = update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+        assert!(matches!(cmd, Some(Cmd::CreateFile { ref path }) if *path == target));
+        crate::file_ops::create_file(&target).unwrap();
+        update(
+            &mut model,
+            Msg::Workspace(WorkspaceMsg::FileCreated(Ok(target.clone()))),
+        );
+        assert_eq!(focused_path(&model), Some(target));
+    }
+
+    #[test]
+    fn backlinks_open_at_the_linking_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let mut model = note_model(&root, "# Index\n");
+        let other = root.join("journal.md");
+        std::fs::write(&other, "monday\nback to [[index]]\n").unwrap();
+
+        let Some(Cmd::Batch(cmds)) = update(&mut model, Msg::Notes(NotesMsg::ShowBacklinks)) else {
+            panic!("expected a backlink scan");
+        };
+        let Some(Cmd::FindBacklinks { mut request }) = cmds.into_iter().next() else {
+            panic!("expected a backlink scan");
+        };
+        request.workspace_files.push(other.clone());
+        let target = request.target.clone();
+        let backlinks = crate::notes::find_backlinks(request);
+        update(
+            &mut model,
+            Msg::Notes(NotesMsg::BacklinksFound { target, backlinks }),
+        );
+        assert!(matches!(
+            model.ui.active_modal,
+            Some(ModalState::Backlinks(ref state)) if state.entries.len() == 1
+        ));
+
+        update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+        assert_eq!(focused_path(&model), Some(other));
+        assert_eq!(model.editor().active_cursor().line, 1);
+    }
+}
@@ -2,8 +2,9 @@
 
 use crate::commands::Cmd;
 use crate::messages::OutlineMsg;
-use crate::model::AppModel;
+use crate::model::{AppModel, BreadcrumbSymbol, BreadcrumbSymbolsState, ModalState};
 use crate::outline::OutlineNode;
+use crate::panel::PanelId;
 use crate::util::{visible_tree_count, visible_tree_row_at_index};
 use crate::view::geometry::{DockHeaderLayout, OutlinePanelLayout, WindowLayout};
 
@@ -35,6 +36,85 @@ fn visible_node_at_index<'a>(
     .map(|row| row.node)
 }
 
+/// Whether the outline panel is the active panel of an open dock
+fn is_outline_panel_visible(model: &AppModel) -> bool {
+    model
+        .dock_layout
+        .find_panel(PanelId::Outline)
+        .is_some_and(|position| {
+            model
+                .dock_layout
+                .dock(position)
+                .is_panel_visible(PanelId::Outline)
+        })
+}
+
+/// Visible row of the symbol `line` is in: the innermost one covering it
+fn symbol_row_at_line(
+    nodes: &[OutlineNode],
+    panel: &crate::model::OutlinePanelState,
+    line: usize,
+) -> Option<usize> {
+    fn walk(
+        nodes: &[OutlineNode],
+        panel: &crate::model::OutlinePanelState,
+        line: usize,
+        index: &mut usize,
+        found: &mut Option<usize>,
+    ) {
+        for node in nodes {
+            // Rows come in document order, parents first, so the last
+            // match is the innermost
+            if node.covers_line(line) {
+                *found = Some(*index);
+            }
+            *index += 1;
+            if node.is_collapsible() && !panel.is_collapsed(node) {
+                walk(&node.children, panel, line, index, found);
+            }
+        }
+    }
+
+    let mut found = None;
+    walk(nodes, panel, line, &mut 0, &mut found);
+    found
+}
+
+/// Select the symbol under the cursor in the outline panel when the cursor
+/// moves to another line or the outline is rebuilt. Only while the editor
+/// has focus, so it doesn't fight keyboard navigation in the panel. Returns
+/// whether the selection changed.
+pub fn sync_outline_selection(model: &mut AppModel) -> bool {
+    if model.ui.focus != crate::model::FocusTarget::Editor || !is_outline_panel_visible(model) {
+        return false;
+    }
+    let Some(document_id) = model.editor_area.focused_document_id() else {
+        return false;
+    };
+    let Some(outline) = model
+        .editor_area
+        .documents
+        .get(&document_id)
+        .and_then(|doc| doc.outline.as_ref())
+    else {
+        return false;
+    };
+    let line = model.editor().active_cursor().line;
+    let followed = Some((document_id, line, outline.revision));
+    if model.outline_panel.followed == followed {
+        return false;
+    }
+    model.outline_panel.followed = followed;
+
+    match symbol_row_at_line(&outline.roots, &model.outline_panel, line) {
+        Some(index) if model.outline_panel.selected_index != Some(index) => {
+            model.outline_panel.selected_index = Some(index);
+            true
+        }
+        _ => false,
+    }
+}
+
 /// Handle outline panel messages
 pub fn update_outline(model: &mut AppModel, msg: OutlineMsg) -> Option<Cmd> {
     match msg {
@@ -194,6 +274,44 @@ pub fn update_outline(model: &mut AppModel, msg: OutlineMsg) -> Option<Cmd> {
             Some(Cmd::Redraw)
         }
 
+        OutlineMsg::ShowBreadcrumbSymbols { depth } => {
+            let line = model.editor().active_cursor().line;
+            let Some(document) = model.editor_area.focused_document() else {
+                return None;
+            };
+            let Some(outline) = document.outline.as_ref() else {
+                return None;
+            };
+            let path = outline.symbol_path_at(line);
+            let Some(current) = path.get(depth) else {
+                return None;
+            };
+
+            let siblings = outline.siblings_at(&path, depth);
+            let entries = siblings
+                .iter()
+                .map(|node| BreadcrumbSymbol {
+                    label: format!("{} {}", node.kind.label(), node.name),
+                    line: node.range.start_line,
+                    column: node.range.start_col,
+                })
+                .collect();
+            let selected = siblings
+                .iter()
+                .position(|node| std::ptr::eq(node, *current))
+                .unwrap_or(0);
+            let parent = match depth.checked_sub(1) {
+                Some(parent) => path[parent].name.clone(),
+                None => document.display_name(),
+            };
+            model
+                .ui
+                .open_modal(ModalState::BreadcrumbSymbols(BreadcrumbSymbolsState::new(
+                    parent, entries, selected,
+                )));
+            Some(Cmd::Redraw)
+        }
+
         OutlineMsg::Scroll { lines } => {
             let offset = model.outline_panel.scroll_offset;
             if lines < 0 {
@@ -273,3 +391,128 @@ pub fn update_outline(model: &mut AppModel, msg: OutlineMsg) -> Option<Cmd> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{EditorMsg, Msg};
+    use crate::model::Document;
+    use crate::outline::{OutlineData, OutlineKind, OutlineRange};
+    use crate::update::update;
+
+    fn node(kind: OutlineKind, name: &str, lines: (usize, usize)) -> OutlineNode {
+        OutlineNode {
+            kind,
+            name: name.to_string(),
+            range: OutlineRange {
+                start_line: lines.0,
+                start_col: 0,
+                end_line: lines.1,
+                end_col: 0,
+            },
+            children: Vec::new(),
+        }
+    }
+
+    /// `struct A` (lines 0-3, with a field on line 1) and `fn b` (lines 5-7)
+    fn model_with_outline() -> AppModel {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        *model.document_mut() = Document::with_text("a\nb\nc\nd\ne\nf\ng\nh\n");
+        let mut strukt = node(OutlineKind::Struct, "A", (0, 3));
+        strukt.children = vec![node(OutlineKind::Field, "x", (1, 1))];
+        model.document_mut().outline = Some(OutlineData {
+            revision: 0,
+            roots: vec![strukt, node(OutlineKind::Function, "b", (5, 7))],
+        });
+        model.dock_layout.right.activate(PanelId::OUTLINE);
+        model.ui.focus_editor();
+        model
+    }
+
+    fn move_to_line(model: &mut AppModel, line: usize) {
+        update(
+            model,
+            Msg::Editor(EditorMsg::SetCursorPosition { line, column: 0 }),
+        );
+    }
+
+    #[test]
+    fn selection_follows_the_cursor_to_the_innermost_symbol() {
+        let mut model = model_with_outline();
+
+        move_to_line(&mut model, 1);
+        assert_eq!(model.outline_panel.selected_index, Some(1));
+        move_to_line(&mut model, 2);
+        assert_eq!(model.outline_panel.selected_index, Some(0));
+        move_to_line(&mut model, 6);
+        assert_eq!(model.outline_panel.selected_index, Some(2));
+
+        // Between symbols the selection stays where it was
+        move_to_line(&mut model, 4);
+        assert_eq!(model.outline_panel.selected_index, Some(2));
+    }
+
+    #[test]
+    fn collapsed_parent_is_selected_for_hidden_children() {
+        let mut model = model_with_outline();
+        let strukt = model.document().outline.as_ref().unwrap().roots[0].clone();
+        model.outline_panel.toggle_collapsed(&strukt);
+
+        move_to_line(&mut model, 1);
+        assert_eq!(model.outline_panel.selected_index, Some(0));
+        move_to_line(&mut model, 6);
+        // `fn b` is the second visible row now
+        assert_eq!(model.outline_panel.selected_index, Some(1));
+    }
+
+    #[test]
+    fn heading_covers_its_section() {
+        let roots = vec![
+            node(OutlineKind::Heading { level: 1 }, "Intro", (0, 0)),
+            node(OutlineKind::Heading { level: 1 }, "Usage", (4, 4)),
+        ];
+        let panel = crate::model::OutlinePanelState::default();
+        assert_eq!(symbol_row_at_line(&roots, &panel, 2), Some(0));
+        assert_eq!(symbol_row_at_line(&roots, &panel, 9), Some(1));
+    }
+
+    #[test]
+    fn selection_is_left_alone_while_the_panel_has_focus() {
+        let mut model = model_with_outline();
+        model.ui.focus = crate::model::FocusTarget::Dock(crate::panel::DockPosition::Right);
+
+        move_to_line(&mut model, 6);
+        assert_eq!(model.outline_panel.selected_index, None);
+    }
+
+    #[test]
+    fn breadcrumb_dropdown_lists_siblings_and_jumps_to_one() {
+        use crate::messages::{ModalMsg, UiMsg};
+
+        let mut model = model_with_outline();
+        move_to_line(&mut model, 1);
+
+        update(
+            &mut model,
+            Msg::Outline(OutlineMsg::ShowBreadcrumbSymbols { depth: 0 }),
+        );
+        let Some(ModalState::BreadcrumbSymbols(state)) = &model.ui.active_modal else {
+            panic!("expected the breadcrumb dropdown");
+        };
+        let labels: Vec<_> = state.entries.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, ["struct A", "fn b"]);
+        assert_eq!(state.selected_index, 0);
+
+        update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::SelectNext)));
+        update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+        assert!(model.ui.active_modal.is_none());
+        assert_eq!(model.editor().active_cursor().line, 5);
+
+        // Nothing to list below the innermost symbol
+        update(
+            &mut model,
+            Msg::Outline(OutlineMsg::ShowBreadcrumbSymbols { depth: 1 }),
+        );
+        assert!(model.ui.active_modal.is_none());
+    }
+}
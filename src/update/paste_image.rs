@@ -0,0 +1,259 @@
+//! Pasting clipboard images into Markdown documents: the image is saved next
+//! to the document and a `![](…)` link to it inserted at the cursor
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::messages::DocumentMsg;
+use crate::model::editor_area::DocumentId;
+use crate::model::status_bar::TransientMessage;
+use crate::model::{AppModel, ModalState, PastedImage, RenameFileState, Selection};
+use crate::paste_image::{image_path_for, link_target, ClipboardImage};
+use crate::syntax::LanguageId;
+use crate::util::{filename_for_display, UtcDateTime};
+
+use super::document::update_document;
+
+/// Save a pasted clipboard image for the focused Markdown document
+pub(super) fn paste_image(model: &mut AppModel, image: ClipboardImage) -> Option<Cmd> {
+    if model.ui.active_modal.is_some() {
+        return None;
+    }
+    let doc = model.editor_area.focused_document()?;
+    if doc.language != LanguageId::Markdown {
+        return show_message(model, "Images can only be pasted into Markdown documents");
+    }
+    let (Some(document_id), Some(file_path)) = (doc.id, doc.file_path.clone()) else {
+        return show_message(model, "Save the document before pasting images into it");
+    };
+
+    let path = image_path_for(
+        &model.config.paste_image_path,
+        &file_path,
+        UtcDateTime::now(),
+    );
+    Some(Cmd::SavePastedImage {
+        document_id,
+        path,
+        image,
+    })
+}
+
+/// Insert the link to a saved image, if its document is still focused
+pub(super) fn pasted_image_saved(
+    model: &mut AppModel,
+    document_id: DocumentId,
+    result: Result<PathBuf, String>,
+) -> Option<Cmd> {
+    let path = match result {
+        Ok(path) => path,
+        Err(e) => {
+            model
+                .ui
+                .set_status(format!("Failed to save pasted image: {}", e));
+            return Some(Cmd::redraw_status_bar());
+        }
+    };
+
+    let doc_dir = document_dir(model, document_id);
+    let Some(doc_dir) = doc_dir.filter(|_| {
+        model.editor_area.focused_document_id() == Some(document_id)
+            && model.ui.active_modal.is_none()
+    }) else {
+        model.ui.set_status(format!(
+            "Saved pasted image to {}",
+            filename_for_display(&path)
+        ));
+        return Some(Cmd::redraw_status_bar());
+    };
+
+    let target = link_target(&doc_dir, &path);
+    model.editor_mut().collapse_to_primary();
+    let cmd = update_document(model, DocumentMsg::InsertText(format!("![]({})", target)));
+
+    model.ui.last_pasted_image = Some(PastedImage { document_id, path });
+    model.ui.set_status(format!(
+        "Saved image as {} — run \"Rename Pasted Image\" to rename it",
+        target
+    ));
+    cmd
+}
+
+/// Open the rename modal for the most recently pasted image
+pub(super) fn rename_pasted_image(model: &mut AppModel) -> Option<Cmd> {
+    let Some(path) = model
+        .ui
+        .last_pasted_image
+        .as_ref()
+        .map(|pasted| pasted.path.clone())
+        .filter(|path| path.exists())
+    else {
+        return show_message(model, "No pasted image to rename");
+    };
+    model
+        .ui
+        .open_modal(ModalState::RenameFile(RenameFileState::new(path)));
+    Some(Cmd::Redraw)
+}
+
+/// After the pasted image was renamed from `from` to `to`, point its link
+/// in the (focused) document at the new name
+pub(super) fn relink_pasted_image(model: &mut AppModel, from: &Path, to: &Path) -> Option<Cmd> {
+    let pasted = model
+        .ui
+        .last_pasted_image
+        .as_mut()
+        .filter(|pasted| pasted.path == from)?;
+    pasted.path = to.to_path_buf();
+    let document_id = pasted.document_id;
+    if model.editor_area.focused_document_id() != Some(document_id) {
+        return None;
+    }
+    let doc_dir = document_dir(model, document_id)?;
+    let old_target = link_target(&doc_dir, from);
+    let new_target = link_target(&doc_dir, to);
+
+    let text = model.document().buffer.to_string();
+    let link_start = text.find(&format!("]({})", old_target))?;
+    let start = text[..link_start].chars().count() + 2;
+    let end = start + old_target.chars().count();
+
+    // Replace the link target as an undoable edit, then put the cursor back
+    let cursor_offset = model.cursor_buffer_position();
+    let (start_line, start_column) = model.document().offset_to_cursor(start);
+    let (end_line, end_column) = model.document().offset_to_cursor(end);
+    let editor = model.editor_mut();
+    editor.collapse_to_primary();
+    editor.cursors[0].line = end_line;
+    editor.cursors[0].column = end_column;
+    editor.selections[0] = Selection::from_anchor_head(
+        crate::model::Position::new(start_line, start_column),
+        crate::model::Position::new(end_line, end_column),
+    );
+    let cmd = update_document(model, DocumentMsg::InsertText(new_target.clone()));
+
+    let delta = new_target.chars().count() as isize - old_target.chars().count() as isize;
+    let cursor_offset = if cursor_offset >= end {
+        (cursor_offset as isize + delta) as usize
+    } else {
+        cursor_offset.min(start)
+    };
+    model.set_cursor_from_position(cursor_offset);
+    model.editor_mut().clear_selection();
+    cmd
+}
+
+fn document_dir(model: &AppModel, document_id: DocumentId) -> Option<PathBuf> {
+    model
+        .editor_area
+        .documents
+        .get(&document_id)?
+        .file_path
+        .as_ref()?
+        .parent()
+        .map(Path::to_path_buf)
+}
+
+fn show_message(model: &mut AppModel, message: &str) -> Option<Cmd> {
+    model.ui.transient_message = Some(TransientMessage::new(
+        message.to_string(),
+        Duration::from_secs(3),
+    ));
+    Some(Cmd::redraw_status_bar())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_ops::FileOperation;
+    use crate::messages::{AppMsg, Msg, WorkspaceMsg};
+    use crate::update::update;
+
+    fn markdown_model(dir: &Path, text: &str) -> AppModel {
+        let path = dir.join("notes.md");
+        std::fs::write(&path, text).unwrap();
+        AppModel::new(800, 600, 1.0, vec![path])
+    }
+
+    fn red_pixel() -> ClipboardImage {
+        ClipboardImage {
+            width: 1,
+            height: 1,
+            rgba: vec![255, 0, 0, 255],
+        }
+    }
+
+    #[test]
+    fn pasting_image_saves_relative_to_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut model = markdown_model(dir.path(), "# Notes\n");
+
+        let cmd = update(
+            &mut model,
+            Msg::App(AppMsg::PasteImageFromClipboard(red_pixel())),
+        );
+        let Some(Cmd::SavePastedImage { path, .. }) = cmd else {
+            panic!("expected SavePastedImage, got {:?}", cmd);
+        };
+        assert!(path.starts_with(dir.path().join("assets")));
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("notes-") && name.ends_with(".png"));
+    }
+
+    #[test]
+    fn pasting_image_into_plain_text_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "").unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![path]);
+
+        let cmd = update(
+            &mut model,
+            Msg::App(AppMsg::PasteImageFromClipboard(red_pixel())),
+        );
+        assert!(!matches!(cmd, Some(Cmd::SavePastedImage { .. })));
+    }
+
+    #[test]
+    fn saved_image_is_linked_and_rename_updates_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut model = markdown_model(dir.path(), "");
+        let document_id = model.document().id.unwrap();
+        let image = dir.path().join("assets/shot.png");
+        std::fs::create_dir_all(image.parent().unwrap()).unwrap();
+        std::fs::write(&image, "png").unwrap();
+
+        update(
+            &mut model,
+            Msg::App(AppMsg::PastedImageSaved {
+                document_id,
+                result: Ok(image.clone()),
+            }),
+        );
+        assert_eq!(model.document().buffer.to_string(), "![](assets/shot.png)");
+
+        update(
+            &mut model,
+            Msg::Document(DocumentMsg::InsertText(" done".into())),
+        );
+        let renamed = dir.path().join("assets/chart.png");
+        update(
+            &mut model,
+            Msg::Workspace(WorkspaceMsg::FileOperationCompleted(Ok(
+                FileOperation::Renamed {
+                    from: image,
+                    to: renamed.clone(),
+                    overwritten: None,
+                },
+            ))),
+        );
+
+        assert_eq!(
+            model.document().buffer.to_string(),
+            "![](assets/chart.png) done"
+        );
+        assert_eq!(model.cursor_buffer_position(), 26);
+        assert_eq!(model.ui.last_pasted_image.as_ref().unwrap().path, renamed);
+    }
+}
@@ -40,19 +40,34 @@ pub fn update_preview(model: &mut AppModel, msg: PreviewMsg) -> Option<Cmd> {
             }
             None
         }
-        PreviewMsg::SyncFromPreview(line) => {
-            if let Some(preview_id) = model.editor_area.find_preview_for_group(group_id) {
-                if let Some(preview) = model.editor_area.preview(preview_id) {
-                    if preview.scroll_sync_enabled {
-                        if let Some(editor_id) = model.editor_area.focused_editor_id() {
-                            return model
-                                .set_editor_vertical_scroll(editor_id, line)
-                                .then_some(Cmd::Redraw);
-                        }
-                    }
-                }
+        PreviewMsg::SyncFromPreview { preview_id, line } => {
+            let preview = model.editor_area.preview_mut(preview_id)?;
+            preview.webview_line = Some(line);
+            if !preview.scroll_sync_enabled {
+                return None;
             }
-            None
+
+            // Scroll the editor the preview sits next to, if it still shows
+            // the previewed document
+            let (group_id, document_id) = (preview.group_id, preview.document_id);
+            let editor_id = model
+                .editor_area
+                .groups
+                .get(&group_id)?
+                .active_editor_id()?;
+            let editor = model.editor_area.editors.get(&editor_id)?;
+            if editor.document_id != Some(document_id) {
+                return None;
+            }
+            let changed = model.set_editor_vertical_scroll(editor_id, line);
+
+            // The webview is already where the user scrolled it; don't
+            // scroll it again to the editor's (clamped) top line
+            let top_line = model.editor_area.editors.get(&editor_id)?.viewport.top_line;
+            let preview = model.editor_area.preview_mut(preview_id)?;
+            preview.scroll_offset = top_line;
+            preview.webview_line = Some(top_line);
+            changed.then_some(Cmd::Redraw)
         }
         PreviewMsg::ToggleSync => {
             if let Some(preview) = model.editor_area.preview_for_group_mut(group_id) {
@@ -69,5 +84,68 @@ pub fn update_preview(model: &mut AppModel, msg: PreviewMsg) -> Option<Cmd> {
             }
             None
         }
+        PreviewMsg::ToggleHybrid => {
+            model.config.markdown_hybrid = !model.config.markdown_hybrid;
+            model.ui.set_status(if model.config.markdown_hybrid {
+                "Markdown hybrid view on"
+            } else {
+                "Markdown hybrid view off"
+            });
+            Some(Cmd::Redraw)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ropey::Rope;
+
+    fn model_with_preview() -> (AppModel, crate::model::editor_area::PreviewId) {
+        let mut model = AppModel::new(120, 80, 1.0, vec![]);
+        model.document_mut().buffer = Rope::from("a\nb\nc\nd\ne\nf\n");
+        model.editor_mut().resize_viewport(2, 20);
+        let preview_id = model.editor_area.open_preview_for_focused_group().unwrap();
+        (model, preview_id)
+    }
+
+    #[test]
+    fn scrolling_the_webview_scrolls_the_editor() {
+        let (mut model, preview_id) = model_with_preview();
+
+        let cmd = update_preview(
+            &mut model,
+            PreviewMsg::SyncFromPreview {
+                preview_id,
+                line: 3,
+            },
+        );
+
+        assert!(cmd.is_some());
+        assert_eq!(model.editor().viewport.top_line, 3);
+        let preview = model.editor_area.preview(preview_id).unwrap();
+        assert_eq!(preview.scroll_offset, 3);
+        // Already there, so the webview isn't scrolled again
+        assert_eq!(preview.webview_line, Some(preview.webview_target_line()));
+    }
+
+    #[test]
+    fn webview_keeps_its_own_position_without_scroll_sync() {
+        let (mut model, preview_id) = model_with_preview();
+        update_preview(&mut model, PreviewMsg::ToggleSync);
+
+        let cmd = update_preview(
+            &mut model,
+            PreviewMsg::SyncFromPreview {
+                preview_id,
+                line: 3,
+            },
+        );
+
+        assert!(cmd.is_none());
+        assert_eq!(model.editor().viewport.top_line, 0);
+        let preview = model.editor_area.preview(preview_id).unwrap();
+        assert_eq!(preview.webview_line, Some(3));
+        assert_eq!(preview.webview_target_line(), 3);
     }
 }
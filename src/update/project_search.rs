@@ -0,0 +1,392 @@
+//! Workspace search panel update handlers
+
+use crate::commands::Cmd;
+use crate::editable::TextEditMsg;
+use crate::messages::{DockMsg, SearchMsg};
+use crate::model::{AppModel, Position, Selection};
+use crate::panel::PanelId;
+use crate::project_search::{ProjectSearchRequest, SearchRow, SearchSource};
+
+use super::dock::update_dock;
+use super::text_edit::apply_text_edit_msg;
+use super::todo::open_item;
+
+/// Search the workspace for the panel's query, superseding any search
+/// still in flight
+fn submit(model: &mut AppModel) -> Option<Cmd> {
+    let query = model.search_panel.input.text();
+    if query.is_empty() {
+        return None;
+    }
+    let Some(root) = model.workspace_root().cloned() else {
+        model.ui.set_status("Open a folder to search in it");
+        return Some(Cmd::redraw_status_bar());
+    };
+
+    let search = model.search_panel.search();
+    let (generation, cancel) = model.search_panel.begin_search(query);
+    let search = match search {
+        Ok(search) => search,
+        Err(error) => {
+            model.search_panel.cancel_search();
+            model.search_panel.error = Some(error);
+            return Some(Cmd::Redraw);
+        }
+    };
+
+    // Open documents are searched as they are, unsaved edits included
+    let open_documents = model
+        .editor_area
+        .documents
+        .iter()
+        .filter_map(|(id, doc)| {
+            let path = doc
+                .file_path
+                .clone()
+                .filter(|path| path.starts_with(&root))?;
+            Some(SearchSource {
+                document_id: *id,
+                path,
+                text: doc.buffer.to_string(),
+            })
+        })
+        .collect();
+
+    Some(Cmd::Batch(vec![
+        Cmd::SearchProject {
+            generation,
+            request: ProjectSearchRequest {
+                root,
+                search,
+                open_documents,
+            },
+            cancel,
+        },
+        Cmd::Redraw,
+    ]))
+}
+
+/// Toggle a search option, searching again if there was a search
+fn toggle_option(
+    model: &mut AppModel,
+    toggle: fn(&mut crate::model::SearchPanelState),
+) -> Option<Cmd> {
+    toggle(&mut model.search_panel);
+    if model.search_panel.searched_query.is_some() {
+        if let Some(cmd) = submit(model) {
+            return Some(cmd);
+        }
+    }
+    Some(Cmd::Redraw)
+}
+
+/// Open the file of a match and select the match
+fn open_row(model: &mut AppModel, index: usize) -> Option<Cmd> {
+    let Some(SearchRow::Match(file, found)) = model.search_panel.row(index) else {
+        return None;
+    };
+    let path = file.path.clone();
+    let document_id = file.document_id;
+    let (line, column, end_column) = (found.line, found.column, found.end_column);
+
+    let cmd = open_item(model, Some(path.clone()), document_id, line, column);
+    let opened = model
+        .editor_area
+        .focused_document()
+        .is_some_and(|doc| doc.file_path.as_deref() == Some(path.as_path()));
+    if opened && model.document().line_length(line) >= end_column {
+        let editor = model.editor_mut();
+        editor.cursors[0].column = end_column;
+        editor.selections[0] = Selection::from_anchor_head(
+            Position::new(line, column),
+            Position::new(line, end_column),
+        );
+    }
+    cmd
+}
+
+/// Handle workspace search panel messages
+pub fn update_project_search(model: &mut AppModel, msg: SearchMsg) -> Option<Cmd> {
+    match msg {
+        SearchMsg::Open => {
+            // A selection within one line becomes the query
+            let selection = *model.editor().primary_selection();
+            if !selection.is_empty() && selection.start().line == selection.end().line {
+                let text = selection.get_text(model.document());
+                model.search_panel.input.set_content(&text);
+            }
+            apply_text_edit_msg(&mut model.search_panel.input, &TextEditMsg::SelectAll);
+            update_dock(model, DockMsg::ActivatePanel(PanelId::SEARCH_RESULTS))
+        }
+
+        SearchMsg::Input(edit) => {
+            let panel = &mut model.search_panel;
+            let copied = match edit {
+                TextEditMsg::Copy | TextEditMsg::Cut => Some(panel.input.selected_text()),
+                _ => None,
+            }
+            .filter(|text| !text.is_empty());
+
+            let edit = match edit {
+                // Single-line input: flatten pasted newlines into spaces
+                TextEditMsg::Paste(text) | TextEditMsg::InsertText(text) => {
+                    TextEditMsg::InsertText(text.replace("\r\n", " ").replace(['\n', '\r'], " "))
+                }
+                other => other,
+            };
+            let modified = apply_text_edit_msg(&mut panel.input, &edit);
+            if modified {
+                // Enter searches again rather than opening the old selection
+                panel.selected_index = None;
+            }
+
+            match copied {
+                Some(text) => Some(Cmd::Batch(vec![Cmd::Redraw, Cmd::CopyToClipboard(text)])),
+                None if modified => Some(Cmd::Redraw),
+                None => None,
+            }
+        }
+
+        SearchMsg::Submit => submit(model),
+
+        SearchMsg::Cancel => {
+            model.search_panel.cancel_search();
+            Some(Cmd::Redraw)
+        }
+
+        SearchMsg::ToggleCaseSensitive => {
+            toggle_option(model, |panel| panel.case_sensitive = !panel.case_sensitive)
+        }
+        SearchMsg::ToggleWholeWord => {
+            toggle_option(model, |panel| panel.whole_word = !panel.whole_word)
+        }
+        SearchMsg::ToggleRegex => toggle_option(model, |panel| panel.regex = !panel.regex),
+
+        SearchMsg::ResultsFound { generation, files } => {
+            if generation != model.search_panel.generation {
+                return None;
+            }
+            model.search_panel.files.extend(files);
+            Some(Cmd::Redraw)
+        }
+
+        SearchMsg::Finished {
+            generation,
+            summary,
+        } => {
+            if generation != model.search_panel.generation {
+                return None;
+            }
+            model.search_panel.cancel_search();
+            model.search_panel.summary = Some(summary);
+            Some(Cmd::Redraw)
+        }
+
+        SearchMsg::ClickRow { index } => {
+            if index >= model.search_panel.row_count() {
+                return None;
+            }
+            model.search_panel.selected_index = Some(index);
+            match model.search_panel.row(index) {
+                Some(SearchRow::Match(..)) => open_row(model, index),
+                _ => Some(Cmd::Redraw),
+            }
+        }
+
+        SearchMsg::SelectPrevious => {
+            model.search_panel.selected_index = Some(
+                model
+                    .search_panel
+                    .selected_index
+                    .map_or(0, |idx| idx.saturating_sub(1)),
+            );
+            Some(Cmd::Redraw)
+        }
+
+        SearchMsg::SelectNext => {
+            let rows = model.search_panel.row_count();
+            if rows > 0 {
+                model.search_panel.selected_index = Some(
+                    model
+                        .search_panel
+                        .selected_index
+                        .map_or(0, |idx| (idx + 1).min(rows - 1)),
+                );
+            }
+            Some(Cmd::Redraw)
+        }
+
+        SearchMsg::OpenSelected => {
+            let index = model.search_panel.selected_index?;
+            open_row(model, index)
+        }
+
+        SearchMsg::Scroll { lines } => {
+            let max_offset = model.search_panel.row_count().saturating_sub(1);
+            let offset = model.search_panel.scroll_offset as i64 + lines as i64;
+            model.search_panel.scroll_offset = offset.clamp(0, max_offset as i64) as usize;
+            Some(Cmd::Redraw)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_search::{SearchFileResult, SearchMatch, SearchSummary};
+
+    fn test_model() -> AppModel {
+        AppModel::new(800, 600, 1.0, vec![])
+    }
+
+    fn type_query(model: &mut AppModel, query: &str) {
+        update_project_search(
+            model,
+            SearchMsg::Input(TextEditMsg::InsertText(query.to_string())),
+        );
+    }
+
+    fn found(line: usize, column: usize, end_column: usize) -> SearchMatch {
+        SearchMatch {
+            line,
+            column,
+            end_column,
+            preview: String::new(),
+            preview_range: (0, 0),
+        }
+    }
+
+    #[test]
+    fn submit_snapshots_open_documents_in_the_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut model = test_model();
+        model.open_workspace(dir.path().to_path_buf());
+        model.document_mut().file_path = Some(dir.path().join("a.rs"));
+        model.document_mut().buffer = ropey::Rope::from("unsaved needle\n");
+        type_query(&mut model, "needle");
+
+        let Some(Cmd::Batch(cmds)) = update_project_search(&mut model, SearchMsg::Submit) else {
+            panic!("expected batched search + redraw");
+        };
+        let Some(Cmd::SearchProject {
+            generation,
+            request,
+            ..
+        }) = cmds.into_iter().next()
+        else {
+            panic!("expected SearchProject");
+        };
+        assert_eq!(generation, 1);
+        assert!(model.search_panel.is_searching);
+        assert_eq!(request.search.query, "needle");
+        assert_eq!(request.open_documents.len(), 1);
+        assert_eq!(request.open_documents[0].text, "unsaved needle\n");
+    }
+
+    #[test]
+    fn invalid_regex_is_reported_instead_of_searching() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut model = test_model();
+        model.open_workspace(dir.path().to_path_buf());
+        type_query(&mut model, "(");
+        update_project_search(&mut model, SearchMsg::ToggleRegex);
+
+        let cmd = update_project_search(&mut model, SearchMsg::Submit);
+        assert!(matches!(cmd, Some(Cmd::Redraw)));
+        assert!(!model.search_panel.is_searching);
+        assert!(model.search_panel.error.is_some());
+    }
+
+    #[test]
+    fn streamed_results_accumulate_and_stale_ones_are_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut model = test_model();
+        model.open_workspace(dir.path().to_path_buf());
+        type_query(&mut model, "x");
+        update_project_search(&mut model, SearchMsg::Submit);
+        update_project_search(&mut model, SearchMsg::Submit);
+
+        let file = |name: &str| SearchFileResult {
+            path: dir.path().join(name),
+            document_id: None,
+            display_name: name.into(),
+            matches: vec![found(0, 0, 1)],
+        };
+        let stale = update_project_search(
+            &mut model,
+            SearchMsg::ResultsFound {
+                generation: 1,
+                files: vec![file("old")],
+            },
+        );
+        assert!(stale.is_none());
+        for name in ["a", "b"] {
+            update_project_search(
+                &mut model,
+                SearchMsg::ResultsFound {
+                    generation: 2,
+                    files: vec![file(name)],
+                },
+            );
+        }
+        update_project_search(
+            &mut model,
+            SearchMsg::Finished {
+                generation: 2,
+                summary: SearchSummary {
+                    files_searched: 3,
+                    truncated: false,
+                },
+            },
+        );
+
+        let names: Vec<&str> = model
+            .search_panel
+            .files
+            .iter()
+            .map(|file| file.display_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert!(!model.search_panel.is_searching);
+        assert_eq!(model.search_panel.summary.unwrap().files_searched, 3);
+    }
+
+    #[test]
+    fn clicking_a_match_selects_it_in_the_open_document() {
+        let mut model = test_model();
+        let path = std::path::PathBuf::from("/tmp/token-search-test.rs");
+        model.document_mut().file_path = Some(path.clone());
+        model.document_mut().buffer = ropey::Rope::from("a\n  let needle = 1;\n");
+        let doc_id = model.document().id;
+        model.search_panel.files = vec![SearchFileResult {
+            path,
+            document_id: doc_id,
+            display_name: "token-search-test.rs".into(),
+            matches: vec![found(1, 6, 12)],
+        }];
+
+        update_project_search(&mut model, SearchMsg::ClickRow { index: 1 });
+
+        let selection = model.editor().primary_selection();
+        assert_eq!(selection.start(), Position::new(1, 6));
+        assert_eq!(selection.end(), Position::new(1, 12));
+        assert_eq!(model.editor().primary_cursor().column, 12);
+        assert_eq!(model.search_panel.selected_index, Some(1));
+    }
+
+    #[test]
+    fn open_takes_the_query_from_a_selection() {
+        let mut model = test_model();
+        model.document_mut().buffer = ropey::Rope::from("find me\n");
+        model.editor_mut().selections[0] =
+            Selection::from_anchor_head(Position::new(0, 0), Position::new(0, 4));
+
+        update_project_search(&mut model, SearchMsg::Open);
+
+        assert_eq!(model.search_panel.input.text(), "find");
+        assert_eq!(
+            model.dock_layout.bottom.active_panel(),
+            Some(PanelId::SEARCH_RESULTS)
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Scratchpads: persistent documents in the config dir for temporary
+//! snippets, saved automatically and closed without prompting
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::config_paths;
+use crate::messages::LayoutMsg;
+use crate::model::status_bar::TransientMessage;
+use crate::model::AppModel;
+
+use super::layout::update_layout;
+
+/// Open the scratchpad, creating it when missing. With `per_language`, the
+/// scratchpad for the focused document's file type (`scratch.rs`, …) is
+/// opened instead of the shared Markdown one.
+pub(super) fn open_scratchpad(model: &mut AppModel, per_language: bool) -> Option<Cmd> {
+    let extension = if per_language {
+        let extension = model
+            .editor_area
+            .focused_document()
+            .and_then(|doc| doc.file_path.as_deref())
+            .and_then(Path::extension)
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase);
+        match extension {
+            Some(extension) => extension,
+            None => return show_message(model, "The current document has no file type"),
+        }
+    } else {
+        "md".to_string()
+    };
+
+    let dir = match config_paths::ensure_scratch_dir() {
+        Ok(dir) => dir,
+        Err(e) => return show_message(model, &e),
+    };
+    let path = dir.join(format!("scratch.{}", extension));
+    if !path.exists() {
+        if let Err(e) = std::fs::write(&path, "") {
+            return show_message(model, &format!("Failed to create scratchpad: {}", e));
+        }
+    }
+    update_layout(model, LayoutMsg::OpenFileInNewTab(path))
+}
+
+/// Write every modified scratchpad to disk. Returns whether any was saved.
+pub fn save_scratchpads(model: &mut AppModel) -> bool {
+    let mut saved = false;
+    for doc in model.editor_area.documents.values_mut() {
+        if !doc.is_scratchpad || !doc.is_modified {
+            continue;
+        }
+        let Some(path) = &doc.file_path else {
+            continue;
+        };
+        match std::fs::write(path, doc.buffer.to_string()) {
+            Ok(()) => {
+                doc.is_modified = false;
+                doc.saved_revision = Some(doc.undo_stack.len());
+                saved = true;
+            }
+            Err(e) => tracing::warn!("Failed to save scratchpad {}: {}", path.display(), e),
+        }
+    }
+    saved
+}
+
+fn show_message(model: &mut AppModel, message: &str) -> Option<Cmd> {
+    model.ui.transient_message = Some(TransientMessage::new(
+        message.to_string(),
+        Duration::from_secs(3),
+    ));
+    Some(Cmd::redraw_status_bar())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{DocumentMsg, LayoutMsg, Msg, UiMsg};
+    use crate::update::update;
+
+    #[test]
+    fn scratchpad_is_saved_on_tick_and_closes_without_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let other = dir.path().join("other.txt");
+        let scratch = dir.path().join("scratch.md");
+        std::fs::write(&other, "").unwrap();
+        std::fs::write(&scratch, "").unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![scratch.clone(), other]);
+        model.document_mut().is_scratchpad = true;
+
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+        assert!(model.document().is_modified);
+        update(&mut model, Msg::Ui(UiMsg::BlinkCursor));
+        assert!(!model.document().is_modified);
+        assert_eq!(std::fs::read_to_string(&scratch).unwrap(), "x");
+
+        // Closing saves pending edits instead of asking
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('y')));
+        update(&mut model, Msg::Layout(LayoutMsg::CloseFocusedTab));
+        assert!(model.ui.active_modal.is_none());
+        assert_eq!(std::fs::read_to_string(&scratch).unwrap(), "xy");
+        assert_eq!(model.editor_area.documents.len(), 1);
+    }
+}
@@ -0,0 +1,314 @@
+//! Searching the document outside the find modal: the word under the
+//! cursor, and F3 / Shift+F3 for the current search term. Also counts the
+//! find modal's matches across the open documents.
+
+use std::time::Duration;
+
+use crate::commands::Cmd;
+use crate::model::editor::{Position, Selection};
+use crate::model::status_bar::TransientMessage;
+use crate::model::{AppModel, FindMatchCounts, ModalState, SearchHighlight};
+use crate::search_index::MIN_QUERY_CHARS;
+
+/// Search for the word under the primary cursor (or the selected text) and
+/// jump to its next or previous occurrence. The term becomes the current
+/// search, so F3 and the find modal pick it up.
+pub(super) fn search_word_under_cursor(model: &mut AppModel, forward: bool) -> Option<Cmd> {
+    let editor = model.editor();
+    let selection = editor.selections[0];
+    let (highlight, origin) =
+        if !selection.is_empty() && selection.start().line == selection.end().line {
+            let doc = model.document();
+            let start = selection.start();
+            let end = selection.end();
+            let query: String = doc
+                .get_line_cow(start.line)?
+                .chars()
+                .skip(start.column)
+                .take(end.column - start.column)
+                .collect();
+            let highlight = SearchHighlight {
+                query,
+                case_sensitive: true,
+                whole_word: false,
+                regex: None,
+                scope: None,
+            };
+            (highlight, start)
+        } else {
+            let Some((word, start, _)) = editor.word_under_cursor(model.document()) else {
+                model.ui.transient_message = Some(TransientMessage::new(
+                    "No word under cursor".to_string(),
+                    Duration::from_secs(2),
+                ));
+                return Some(Cmd::redraw_status_bar());
+            };
+            let highlight = SearchHighlight {
+                query: word,
+                case_sensitive: true,
+                whole_word: true,
+                regex: None,
+                scope: None,
+            };
+            (highlight, start)
+        };
+
+    set_search_highlight(model, highlight);
+    jump_to_match(model, origin, forward)
+}
+
+/// Jump to the next or previous match of the current search term
+pub(super) fn find_match(model: &mut AppModel, forward: bool) -> Option<Cmd> {
+    if model.ui.search_highlight.is_none() {
+        // Fall back to the last query typed into the find modal
+        let state = model.ui.last_find_replace.as_ref()?;
+        let highlight = state.search_highlight();
+        if highlight.query.is_empty() {
+            return None;
+        }
+        model.ui.search_highlight = Some(highlight);
+    }
+    let origin = model.editor().selections[0].start();
+    jump_to_match(model, origin, forward)
+}
+
+/// Make `highlight` the current search term, and mirror it into the find
+/// modal so reopening it continues the same search
+pub(super) fn set_search_highlight(model: &mut AppModel, highlight: SearchHighlight) {
+    let mut state = model.ui.last_find_replace.clone().unwrap_or_default();
+    state.set_query(&highlight.query);
+    state.case_sensitive = highlight.case_sensitive;
+    state.regex = highlight.regex.is_some();
+    state.in_selection = highlight.scope.is_some();
+    model.ui.last_find_replace = Some(state);
+    model.ui.search_highlight = Some(highlight);
+}
+
+/// Count the find modal's query in the open documents, and cache its
+/// matches in the focused document. Other documents are only counted for
+/// queries the search index can narrow down (not regexes), so typing stays
+/// instant.
+pub(super) fn count_find_matches(model: &mut AppModel) {
+    let Some(ModalState::FindReplace(state)) = &model.ui.active_modal else {
+        return;
+    };
+    let highlight = state.search_highlight();
+
+    let mut counts = FindMatchCounts::default();
+    let mut matches = Vec::new();
+    let focused = model.editor_area.focused_document_id();
+    if !highlight.query.is_empty() {
+        let count_others =
+            highlight.regex.is_none() && highlight.query.chars().count() >= MIN_QUERY_CHARS;
+        let documents = &model.editor_area.documents;
+        let index = &mut model.ui.search_index;
+        index.retain(|id| documents.contains_key(&id));
+        for (&id, doc) in documents {
+            if Some(id) == focused {
+                matches = index.matches(doc, &highlight);
+                counts.current = matches.len();
+            } else if count_others {
+                let matches = index.matches(doc, &highlight).len();
+                if matches > 0 {
+                    counts.other_files.push((doc.display_name(), matches));
+                }
+            }
+        }
+        counts
+            .other_files
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    }
+
+    let selection = model.editor_area.focused_editor().map(|editor| {
+        let selection = editor.selections[0];
+        (selection.start(), selection.end())
+    });
+    if let Some(ModalState::FindReplace(state)) = &mut model.ui.active_modal {
+        state.match_counts = counts;
+        state.current_match =
+            selection.and_then(|selected| matches.iter().position(|m| *m == selected));
+        state.matches = matches;
+        state.matches_document = focused;
+    }
+}
+
+/// Incremental search: after the find modal's query or options change,
+/// highlight the new matches and select the first one from where the
+/// search started (wrapping around). Without matches the cursor goes back
+/// to where it was. Expects `count_find_matches` to have run.
+pub(super) fn incremental_find(model: &mut AppModel) -> Option<Cmd> {
+    let Some(ModalState::FindReplace(state)) = &model.ui.active_modal else {
+        return None;
+    };
+    let highlight = state.search_highlight();
+    let origin = state
+        .origin
+        .unwrap_or_else(|| model.editor().selections[0].start());
+    let next = state
+        .matches
+        .iter()
+        .find(|(start, _)| *start >= origin)
+        .or(state.matches.first())
+        .copied();
+
+    model.ui.search_highlight = (!highlight.query.is_empty()).then_some(highlight);
+    let editor = model.editor_mut();
+    editor.collapse_to_primary();
+    let (start, end) = next.unwrap_or((origin, origin));
+    editor.cursors[0].line = end.line;
+    editor.cursors[0].column = end.column;
+    editor.cursors[0].desired_column = None;
+    editor.selections[0] = Selection::from_anchor_head(start, end);
+
+    if let Some(ModalState::FindReplace(state)) = &mut model.ui.active_modal {
+        state.current_match = next.and_then(|next| state.matches.iter().position(|m| *m == next));
+    }
+    model.ensure_cursor_visible();
+    model.reset_cursor_blink();
+    Some(Cmd::Redraw)
+}
+
+/// Matches of `highlight` in the focused document with what replaces each:
+/// `replacement`, with capture groups expanded for a regex. Expanded
+/// against the unchanged text, so apply them back to front.
+pub(super) fn replacements(
+    model: &mut AppModel,
+    highlight: &SearchHighlight,
+    replacement: &str,
+) -> Vec<(Position, Position, String)> {
+    let matches = document_matches(model, highlight);
+    let Some(regex) = &highlight.regex else {
+        return matches
+            .into_iter()
+            .map(|(start, end)| (start, end, replacement.to_string()))
+            .collect();
+    };
+    let doc = model.document();
+    matches
+        .into_iter()
+        .map(|(start, end)| {
+            let line = doc.get_line_cow(start.line).unwrap_or_default();
+            (start, end, regex.expand(&line, start.column, replacement))
+        })
+        .collect()
+}
+
+/// All matches of the current search term in the focused document, within
+/// its scope
+fn document_matches(
+    model: &mut AppModel,
+    highlight: &SearchHighlight,
+) -> Vec<(Position, Position)> {
+    match model.editor_area.focused_document() {
+        Some(doc) => model.ui.search_index.matches(doc, highlight),
+        None => Vec::new(),
+    }
+}
+
+/// Select the first match after (or the last match before) `origin`,
+/// wrapping around the document
+fn jump_to_match(model: &mut AppModel, origin: Position, forward: bool) -> Option<Cmd> {
+    let highlight = model.ui.search_highlight.clone()?;
+    let matches = document_matches(model, &highlight);
+
+    let index = if forward {
+        matches
+            .iter()
+            .position(|(start, _)| *start > origin)
+            .unwrap_or(0)
+    } else {
+        matches
+            .iter()
+            .rposition(|(start, _)| *start < origin)
+            .unwrap_or(matches.len().saturating_sub(1))
+    };
+    let Some(&(start, end)) = matches.get(index) else {
+        model.ui.transient_message = Some(TransientMessage::new(
+            format!("No matches for \"{}\"", highlight.query),
+            Duration::from_secs(2),
+        ));
+        return Some(Cmd::redraw_editor());
+    };
+
+    let editor = model.editor_mut();
+    editor.collapse_to_primary();
+    editor.cursors[0].line = end.line;
+    editor.cursors[0].column = end.column;
+    editor.cursors[0].desired_column = None;
+    editor.selections[0] = Selection::from_anchor_head(start, end);
+
+    model.ui.transient_message = Some(TransientMessage::new(
+        format!("{} of {} matches", index + 1, matches.len()),
+        Duration::from_secs(2),
+    ));
+    model.ensure_cursor_visible();
+    model.reset_cursor_blink();
+    Some(Cmd::redraw_editor())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{EditorMsg, Msg};
+    use crate::update::update;
+
+    fn model_with(text: &str) -> AppModel {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.document_mut().buffer = ropey::Rope::from(text);
+        model
+    }
+
+    fn selected_range(model: &AppModel) -> (Position, Position) {
+        let selection = &model.editor().selections[0];
+        (selection.start(), selection.end())
+    }
+
+    #[test]
+    fn word_search_matches_whole_words_and_wraps() {
+        let mut model = model_with("foo foobar\nbar foo\nfoo");
+        update(
+            &mut model,
+            Msg::Editor(EditorMsg::SearchWordUnderCursor { forward: true }),
+        );
+        assert_eq!(
+            selected_range(&model),
+            (Position::new(1, 4), Position::new(1, 7))
+        );
+
+        update(&mut model, Msg::Editor(EditorMsg::FindNextMatch));
+        assert_eq!(selected_range(&model).0, Position::new(2, 0));
+        update(&mut model, Msg::Editor(EditorMsg::FindNextMatch));
+        assert_eq!(selected_range(&model).0, Position::new(0, 0), "wraps");
+
+        update(&mut model, Msg::Editor(EditorMsg::FindPreviousMatch));
+        assert_eq!(selected_range(&model).0, Position::new(2, 0));
+    }
+
+    #[test]
+    fn backward_word_search_sets_find_query() {
+        let mut model = model_with("let x = 1;\nx + x");
+        model.editor_mut().cursors[0] = crate::model::Cursor::at(1, 4);
+        update(
+            &mut model,
+            Msg::Editor(EditorMsg::SearchWordUnderCursor { forward: false }),
+        );
+
+        assert_eq!(selected_range(&model).0, Position::new(1, 0));
+        assert_eq!(model.ui.last_find_replace.as_ref().unwrap().query(), "x");
+        assert!(model.ui.search_highlight.as_ref().unwrap().whole_word);
+    }
+
+    #[test]
+    fn find_next_without_highlight_uses_last_find_query() {
+        let mut model = model_with("a b a");
+        let mut state = crate::model::FindReplaceState::default();
+        state.set_query("a");
+        model.ui.last_find_replace = Some(state);
+
+        update(&mut model, Msg::Editor(EditorMsg::FindNextMatch));
+        assert_eq!(
+            selected_range(&model),
+            (Position::new(0, 4), Position::new(0, 5))
+        );
+    }
+}
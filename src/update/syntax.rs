@@ -58,9 +58,23 @@ pub fn update_syntax(model: &mut AppModel, msg: SyntaxMsg) -> Option<Cmd> {
                 return None;
             }
 
+            // Edits have settled, so bring the search index up to date too
+            let documents = &model.editor_area.documents;
+            model
+                .ui
+                .search_index
+                .retain(|id| documents.contains_key(&id));
+            model.ui.search_index.refresh(doc);
+
             // Snapshot the document content for parsing
             let source = doc.buffer.to_string();
             let language = doc.language;
+            let edits = model
+                .editor_area
+                .documents
+                .get_mut(&document_id)?
+                .syntax_edits
+                .take(revision);
 
             #[cfg(debug_assertions)]
             if let Some(ref mut overlay) = model.debug_overlay {
@@ -77,6 +91,7 @@ pub fn update_syntax(model: &mut AppModel, msg: SyntaxMsg) -> Option<Cmd> {
                 revision,
                 source,
                 language,
+                edits,
             })
         }
 
@@ -151,7 +166,11 @@ pub fn update_syntax(model: &mut AppModel, msg: SyntaxMsg) -> Option<Cmd> {
                 );
             }
 
-            Some(Cmd::redraw_editor())
+            // The text has settled, so language servers can have it too
+            match super::lsp::sync_document(model, document_id) {
+                Some(lsp) => Some(Cmd::Batch(vec![Cmd::redraw_editor(), lsp])),
+                None => Some(Cmd::redraw_editor()),
+            }
         }
 
         SyntaxMsg::LanguageChanged {
@@ -163,6 +182,9 @@ pub fn update_syntax(model: &mut AppModel, msg: SyntaxMsg) -> Option<Cmd> {
             // Update language and clear old highlights
             doc.language = language;
             doc.syntax_highlights = None;
+            if doc.large_file {
+                return Some(Cmd::redraw_editor());
+            }
 
             // Trigger a new parse
             let revision = doc.revision;
@@ -186,6 +208,27 @@ pub fn update_syntax(model: &mut AppModel, msg: SyntaxMsg) -> Option<Cmd> {
     }
 }
 
+/// Switch the focused document to a language picked by hand. The document
+/// keeps it for the rest of the session, whatever its file extension says.
+pub fn override_language(model: &mut AppModel, language: crate::syntax::LanguageId) -> Option<Cmd> {
+    let document_id = model.editor_area.focused_document_id()?;
+    let doc = model.editor_area.documents.get_mut(&document_id)?;
+    doc.language_overridden = true;
+    model
+        .ui
+        .set_status(format!("Language: {}", language.display_name()));
+
+    let mut cmds = vec![Cmd::Redraw];
+    cmds.extend(update_syntax(
+        model,
+        SyntaxMsg::LanguageChanged {
+            document_id,
+            language,
+        },
+    ));
+    Some(Cmd::Batch(cmds))
+}
+
 /// Schedule a syntax parse for a document (call after document edits)
 ///
 /// This returns a `Cmd::DebouncedSyntaxParse` that should be included
@@ -196,8 +239,8 @@ pub fn schedule_syntax_parse(
 ) -> Option<Cmd> {
     let doc = model.editor_area.documents.get(&document_id)?;
 
-    // Skip plain text documents
-    if !doc.language.has_highlighting() {
+    // Skip plain text documents and large files
+    if !doc.language.has_highlighting() || doc.large_file {
         return None;
     }
 
@@ -291,6 +334,7 @@ mod tests {
             revision,
             source,
             language,
+            ..
         }) = cmd
         {
             assert_eq!(document_id, doc_id);
@@ -572,4 +616,54 @@ mod tests {
             "Final highlights should have tokens on line 1"
         );
     }
+
+    #[test]
+    fn test_parse_request_carries_edits_since_the_last_one() {
+        use crate::messages::{DocumentMsg, Msg};
+        use crate::syntax::{SyntaxEdits, TextEdit};
+        use crate::update::update;
+
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        let doc_id = model.document().id.expect("Document should have an ID");
+        model.document_mut().language = LanguageId::Rust;
+
+        let run_parse = |model: &mut AppModel| {
+            let revision = model.document().revision;
+            match update_syntax(
+                model,
+                SyntaxMsg::ParseReady {
+                    document_id: doc_id,
+                    revision,
+                },
+            ) {
+                Some(Cmd::RunSyntaxParse { edits, .. }) => edits,
+                other => panic!("Expected RunSyntaxParse, got {:?}", other),
+            }
+        };
+        run_parse(&mut model);
+
+        let base_revision = model.document().revision;
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('a')));
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('b')));
+        assert_eq!(
+            run_parse(&mut model),
+            Some(SyntaxEdits {
+                base_revision,
+                edits: vec![TextEdit::insert(0, "a"), TextEdit::insert(1, "b")],
+            })
+        );
+
+        let base_revision = model.document().revision;
+        update(&mut model, Msg::Document(DocumentMsg::Undo));
+        let edits = run_parse(&mut model).expect("undo is logged");
+        assert_eq!(edits.base_revision, base_revision);
+        assert_eq!(model.document().buffer.to_string(), "");
+        assert_eq!(edits.edits.last(), Some(&TextEdit::delete(0, "ab")));
+
+        // Replacing the text wholesale leaves the worker to diff it
+        let doc = model.document_mut();
+        doc.buffer = ropey::Rope::from("fn main() {}");
+        doc.revision += 1;
+        assert_eq!(run_parse(&mut model), None);
+    }
 }
@@ -63,6 +63,7 @@ pub fn update_text_edit(
                 state.focused_field = FindReplaceField::Query;
                 let modified = apply_text_edit_msg(&mut state.query_editable, &msg);
                 if modified {
+                    super::search::count_find_matches(model);
                     Some(Cmd::Redraw)
                 } else {
                     None
@@ -303,8 +304,8 @@ fn bridge_text_edit_to_editor(model: &mut AppModel, msg: TextEditMsg) -> Option<
         TextEditMsg::Redo => update_document(model, DocumentMsg::Redo),
 
         // === Line Operations ===
-        TextEditMsg::Indent => update_document(model, DocumentMsg::IndentLines),
-        TextEditMsg::Unindent => update_document(model, DocumentMsg::UnindentLines),
+        TextEditMsg::Indent => update_document(model, DocumentMsg::IndentSelection),
+        TextEditMsg::Unindent => update_document(model, DocumentMsg::DedentSelection),
         TextEditMsg::Duplicate => update_document(model, DocumentMsg::Duplicate),
         TextEditMsg::MoveLineUp | TextEditMsg::MoveLineDown => {
             // Not implemented in legacy editor
@@ -0,0 +1,305 @@
+//! TODO list panel update handlers
+
+use std::path::PathBuf;
+
+use crate::commands::Cmd;
+use crate::messages::{LayoutMsg, TodoMsg};
+use crate::model::editor_area::DocumentId;
+use crate::model::AppModel;
+use crate::panel::PanelId;
+use crate::todo::{TodoRow, TodoScanRequest, TodoScanSource};
+
+use super::layout::update_layout;
+
+/// Whether the TODO panel is the active panel of an open dock
+pub(super) fn is_todo_panel_visible(model: &AppModel) -> bool {
+    model
+        .dock_layout
+        .find_panel(PanelId::TodoList)
+        .is_some_and(|position| {
+            model
+                .dock_layout
+                .dock(position)
+                .is_panel_visible(PanelId::TodoList)
+        })
+}
+
+/// Snapshot open documents (and workspace files) for a background scan
+fn build_scan_request(model: &AppModel) -> TodoScanRequest {
+    let mut open_documents: Vec<TodoScanSource> = model
+        .editor_area
+        .documents
+        .iter()
+        .map(|(id, doc)| TodoScanSource {
+            document_id: *id,
+            path: doc.file_path.clone(),
+            display_name: doc.display_name(),
+            text: doc.buffer.to_string(),
+        })
+        .collect();
+    open_documents.sort_by_key(|source| source.document_id.0);
+
+    let (workspace_root, workspace_files) = match &model.workspace {
+        Some(workspace) => (
+            Some(workspace.root.clone()),
+            workspace.file_tree.get_all_file_paths(),
+        ),
+        None => (None, Vec::new()),
+    };
+
+    TodoScanRequest {
+        open_documents,
+        workspace_root,
+        workspace_files,
+    }
+}
+
+/// Start a new scan, superseding any scan still in flight
+pub(super) fn refresh_todos(model: &mut AppModel) -> Cmd {
+    model.todo_panel.generation = model.todo_panel.generation.wrapping_add(1);
+    model.todo_panel.is_scanning = true;
+    Cmd::ScanTodos {
+        generation: model.todo_panel.generation,
+        request: build_scan_request(model),
+    }
+}
+
+/// Focus the document an item came from and move the cursor to it
+pub(super) fn open_item(
+    model: &mut AppModel,
+    path: Option<PathBuf>,
+    document_id: Option<DocumentId>,
+    line: usize,
+    column: usize,
+) -> Option<Cmd> {
+    let open_tab = document_id.and_then(|doc_id| {
+        model
+            .editor_area
+            .groups
+            .iter()
+            .find_map(|(group_id, group)| {
+                group
+                    .tabs
+                    .iter()
+                    .position(|tab| {
+                        model
+                            .editor_area
+                            .editors
+                            .get(&tab.editor_id)
+                            .is_some_and(|editor| editor.document_id == Some(doc_id))
+                    })
+                    .map(|tab_idx| (*group_id, tab_idx))
+            })
+    });
+
+    let mut open_cmd = None;
+    let target_doc = if let Some((group_id, tab_idx)) = open_tab {
+        model.editor_area.focused_group_id = group_id;
+        update_layout(model, LayoutMsg::SwitchToTab(tab_idx));
+        document_id
+    } else if let Some(path) = path {
+        open_cmd = update_layout(model, LayoutMsg::OpenFileInNewTab(path.clone()));
+        let opened = model
+            .editor_area
+            .focused_document()
+            .is_some_and(|doc| doc.file_path.as_deref() == Some(path.as_path()));
+        if !opened {
+            return open_cmd;
+        }
+        model.editor_area.focused_document_id()
+    } else {
+        None
+    };
+
+    if target_doc.is_none() || target_doc != model.editor_area.focused_document_id() {
+        return Some(Cmd::Redraw);
+    }
+
+    let last_line = model.document().line_count().saturating_sub(1);
+    let line = line.min(last_line);
+    let column = column.min(model.document().line_length(line));
+    let editor = model.editor_mut();
+    editor.cursors[0].line = line;
+    editor.cursors[0].column = column;
+    editor.cursors[0].desired_column = None;
+    editor.clear_selection();
+    model.ensure_cursor_visible_centered();
+    model.ui.focus_editor();
+    Some(match open_cmd {
+        Some(cmd) => Cmd::Batch(vec![cmd, Cmd::Redraw]),
+        None => Cmd::Redraw,
+    })
+}
+
+fn open_row(model: &mut AppModel, index: usize) -> Option<Cmd> {
+    let (path, document_id, line, column) = match model.todo_panel.row(index)? {
+        TodoRow::File(file) => (file.path.clone(), file.document_id, 0, 0),
+        TodoRow::Item(file, item) => (file.path.clone(), file.document_id, item.line, item.column),
+    };
+    open_item(model, path, document_id, line, column)
+}
+
+/// Handle TODO panel messages
+pub fn update_todo(model: &mut AppModel, msg: TodoMsg) -> Option<Cmd> {
+    match msg {
+        TodoMsg::Refresh => Some(Cmd::Batch(vec![refresh_todos(model), Cmd::Redraw])),
+
+        TodoMsg::ScanCompleted { generation, files } => {
+            if generation != model.todo_panel.generation {
+                return None;
+            }
+            model.todo_panel.files = files;
+            model.todo_panel.is_scanning = false;
+            model.todo_panel.has_scanned = true;
+
+            let rows = model.todo_panel.row_count();
+            model.todo_panel.selected_index = model
+                .todo_panel
+                .selected_index
+                .filter(|_| rows > 0)
+                .map(|idx| idx.min(rows - 1));
+            model.todo_panel.scroll_offset =
+                model.todo_panel.scroll_offset.min(rows.saturating_sub(1));
+            Some(Cmd::Redraw)
+        }
+
+        TodoMsg::ClickRow { index } => {
+            if index >= model.todo_panel.row_count() {
+                return None;
+            }
+            model.todo_panel.selected_index = Some(index);
+            match model.todo_panel.row(index) {
+                Some(TodoRow::Item(..)) => open_row(model, index),
+                _ => Some(Cmd::Redraw),
+            }
+        }
+
+        TodoMsg::SelectPrevious => {
+            model.todo_panel.selected_index = Some(
+                model
+                    .todo_panel
+                    .selected_index
+                    .map_or(0, |idx| idx.saturating_sub(1)),
+            );
+            Some(Cmd::Redraw)
+        }
+
+        TodoMsg::SelectNext => {
+            let rows = model.todo_panel.row_count();
+            if rows > 0 {
+                model.todo_panel.selected_index = Some(
+                    model
+                        .todo_panel
+                        .selected_index
+                        .map_or(0, |idx| (idx + 1).min(rows - 1)),
+                );
+            }
+            Some(Cmd::Redraw)
+        }
+
+        TodoMsg::OpenSelected => {
+            let index = model.todo_panel.selected_index?;
+            open_row(model, index)
+        }
+
+        TodoMsg::Scroll { lines } => {
+            let max_offset = model.todo_panel.row_count().saturating_sub(1);
+            let offset = model.todo_panel.scroll_offset as i64 + lines as i64;
+            model.todo_panel.scroll_offset = offset.clamp(0, max_offset as i64) as usize;
+            Some(Cmd::Redraw)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::{TodoFile, TodoItem, TodoTag};
+
+    fn test_model() -> AppModel {
+        AppModel::new(800, 600, 1.0, vec![])
+    }
+
+    fn item(line: usize) -> TodoItem {
+        TodoItem {
+            line,
+            column: 3,
+            tag: TodoTag::Todo,
+            text: "x".into(),
+        }
+    }
+
+    #[test]
+    fn refresh_snapshots_open_documents() {
+        let mut model = test_model();
+        model.document_mut().buffer = ropey::Rope::from("// TODO: a\n");
+
+        let cmd = update_todo(&mut model, TodoMsg::Refresh);
+        let Some(Cmd::Batch(cmds)) = cmd else {
+            panic!("expected batched scan + redraw");
+        };
+        let Some(Cmd::ScanTodos {
+            generation,
+            request,
+        }) = cmds.into_iter().next()
+        else {
+            panic!("expected ScanTodos");
+        };
+        assert_eq!(generation, 1);
+        assert!(model.todo_panel.is_scanning);
+        assert_eq!(request.open_documents.len(), 1);
+        assert_eq!(request.open_documents[0].text, "// TODO: a\n");
+    }
+
+    #[test]
+    fn stale_scan_results_are_dropped() {
+        let mut model = test_model();
+        update_todo(&mut model, TodoMsg::Refresh);
+        update_todo(&mut model, TodoMsg::Refresh);
+
+        let files = vec![TodoFile {
+            path: None,
+            document_id: None,
+            display_name: "a".into(),
+            items: vec![item(0)],
+        }];
+        let cmd = update_todo(
+            &mut model,
+            TodoMsg::ScanCompleted {
+                generation: 1,
+                files: files.clone(),
+            },
+        );
+        assert!(cmd.is_none());
+        assert!(model.todo_panel.files.is_empty());
+
+        update_todo(
+            &mut model,
+            TodoMsg::ScanCompleted {
+                generation: 2,
+                files,
+            },
+        );
+        assert_eq!(model.todo_panel.files.len(), 1);
+        assert!(!model.todo_panel.is_scanning);
+    }
+
+    #[test]
+    fn clicking_item_jumps_to_line_in_open_document() {
+        let mut model = test_model();
+        model.document_mut().buffer = ropey::Rope::from("a\nb\n  // TODO: c\n");
+        let doc_id = model.document().id;
+        model.todo_panel.files = vec![TodoFile {
+            path: None,
+            document_id: doc_id,
+            display_name: "Untitled".into(),
+            items: vec![item(2)],
+        }];
+
+        update_todo(&mut model, TodoMsg::ClickRow { index: 1 });
+
+        assert_eq!(model.editor().primary_cursor().line, 2);
+        assert_eq!(model.editor().primary_cursor().column, 3);
+        assert_eq!(model.todo_panel.selected_index, Some(1));
+    }
+}
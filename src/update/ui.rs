@@ -1,19 +1,32 @@
 //! UI message handlers (status bar, cursor blink, transient messages, modals)
 
+use std::path::PathBuf;
 use std::time::Duration;
 
-use crate::commands::{filter_commands, Cmd};
+use crate::commands::{palette_entries, Cmd, PaletteAction};
+use crate::config::{RenderWhitespace, DEFAULT_FONT_SIZE};
+use crate::csv::write_cells;
+use crate::edit_review::EditReviewSource;
 use crate::editable::{EditableState, StringBuffer};
 use crate::messages::LayoutMsg;
-use crate::messages::{ModalMsg, UiMsg};
+use crate::messages::{ModalMsg, OutlineMsg, UiMsg, WorkspaceMsg};
 use crate::model::{
-    AppModel, FileFinderState, GotoLineState, ModalId, ModalState, RecentFilesState,
-    SegmentContent, SegmentId, ThemePickerState, TransientMessage,
+    AppModel, Cursor, EditOperation, FindReplaceField, FindReplaceState, GotoLineState,
+    GotoSymbolState, LanguagePickerState, ModalId, ModalState, QuickOpenState, RecentFilesState,
+    SearchHighlight, SearchScope, SegmentContent, SegmentId, ThemePickerState, TransientMessage,
 };
+use crate::performance::PerformanceMode;
 use crate::theme::load_theme;
 use crate::update::layout::update_layout;
 
 use super::app::execute_command;
+use super::edit_review::{apply_edit_review, move_review_selection, open_edit_review};
+use super::file_changes::resolve_file_changed;
+use super::file_moves::resolve_deleted_file;
+use super::todo::open_item;
+use super::unsaved_changes::resolve_unsaved_changes;
+use super::workspace::update_workspace;
+use super::workspace_trust::resolve_workspace_trust;
 
 /// Handle UI messages (status bar, cursor blink, modals)
 pub fn update_ui(model: &mut AppModel, msg: UiMsg) -> Option<Cmd> {
@@ -28,7 +41,27 @@ pub fn update_ui(model: &mut AppModel, msg: UiMsg) -> Option<Cmd> {
             Some(Cmd::redraw_status_bar())
         }
 
+        UiMsg::ScrollLogViewer { lines } => super::logs::scroll_log_viewer(model, lines),
+
         UiMsg::BlinkCursor => {
+            // Scratchpads are saved on the tick rather than on every keystroke
+            if super::scratchpad::save_scratchpads(model) {
+                return Some(Cmd::Redraw);
+            }
+            if super::logs::sync_log_viewer(model) {
+                return Some(Cmd::Redraw);
+            }
+            if !model.ui.is_window_active() {
+                return None;
+            }
+            if model.performance_mode().is_some() {
+                // No blinking; bring back a cursor hidden mid-blink
+                if model.ui.cursor_visible {
+                    return None;
+                }
+                model.ui.reset_cursor_blink();
+                return Some(Cmd::Redraw);
+            }
             if model
                 .ui
                 .update_cursor_blink(Duration::from_millis(model.config.cursor_blink_ms))
@@ -57,6 +90,91 @@ pub fn update_ui(model: &mut AppModel, msg: UiMsg) -> Option<Cmd> {
             }
         }
 
+        UiMsg::HighlightWordOccurrences => super::editor::highlight_word_occurrences(model),
+
+        UiMsg::WindowFocused(focused) => {
+            model.ui.window_focused = focused;
+            let cmd = window_activity_changed(model);
+            let save = if focused {
+                None
+            } else {
+                super::autosave::on_window_blur(model)
+            };
+            match save {
+                Some(save) => Some(Cmd::batch(cmd.into_iter().chain([save]).collect())),
+                None => cmd,
+            }
+        }
+
+        UiMsg::WindowOccluded(occluded) => {
+            model.ui.window_occluded = occluded;
+            window_activity_changed(model)
+        }
+
+        UiMsg::TogglePerformanceMode => {
+            let (mode, status) = if model.performance_mode().is_some() {
+                (PerformanceMode::Off, "Performance mode off")
+            } else {
+                (PerformanceMode::On, "Performance mode on")
+            };
+            model.config.performance_mode = mode;
+            model.ui.set_status(status);
+            model.ui.reset_cursor_blink();
+            Some(Cmd::Redraw)
+        }
+
+        UiMsg::ToggleBreadcrumbs => {
+            model.config.breadcrumbs = !model.config.breadcrumbs;
+            model.sync_breadcrumb_bar_height();
+            model.recalculate_viewports();
+            model.ui.set_status(if model.config.breadcrumbs {
+                "Breadcrumbs shown"
+            } else {
+                "Breadcrumbs hidden"
+            });
+            Some(Cmd::Redraw)
+        }
+
+        UiMsg::ToggleRenderWhitespace => {
+            model.config.render_whitespace = match model.config.render_whitespace {
+                RenderWhitespace::None => RenderWhitespace::All,
+                RenderWhitespace::Selection | RenderWhitespace::All => RenderWhitespace::None,
+            };
+            model.ui.set_status(
+                if model.config.render_whitespace == RenderWhitespace::None {
+                    "Whitespace hidden"
+                } else {
+                    "Whitespace shown"
+                },
+            );
+            Some(Cmd::Redraw)
+        }
+
+        UiMsg::ZoomIn => {
+            let font_size = model.config.zoomed_font_size(1);
+            set_font_size(model, font_size)
+        }
+        UiMsg::ZoomOut => {
+            let font_size = model.config.zoomed_font_size(-1);
+            set_font_size(model, font_size)
+        }
+        UiMsg::ResetZoom => set_font_size(model, DEFAULT_FONT_SIZE),
+
+        UiMsg::PowerSourceChanged(on_battery) => {
+            model.ui.performance.on_battery = on_battery;
+            model.ui.reset_cursor_blink();
+            Some(Cmd::Redraw)
+        }
+
+        UiMsg::SlowFrames => {
+            model.ui.performance.slow_frames = true;
+            if model.config.performance_mode == PerformanceMode::Auto {
+                tracing::info!("Rendering is slow; performance mode on");
+            }
+            model.ui.reset_cursor_blink();
+            Some(Cmd::Redraw)
+        }
+
         UiMsg::UpdateSegment { id, content } => {
             model.ui.status_bar.update_segment(id, content);
             Some(Cmd::redraw_status_bar())
@@ -82,12 +200,40 @@ pub fn update_ui(model: &mut AppModel, msg: UiMsg) -> Option<Cmd> {
             Some(Cmd::redraw_status_bar())
         }
 
-        UiMsg::Modal(modal_msg) => update_modal(model, modal_msg),
+        UiMsg::ChordPending(keys) => {
+            model.ui.pending_chord = keys;
+            Some(Cmd::redraw_status_bar())
+        }
+
+        UiMsg::ChordNotBound(keys) => {
+            model.ui.pending_chord = None;
+            update_ui(
+                model,
+                UiMsg::SetTransientMessage {
+                    text: format!("{} is not bound", keys),
+                    duration_ms: 2000,
+                },
+            )
+        }
+
+        UiMsg::Modal(modal_msg) => {
+            let search = find_search(model);
+            let cmd = update_modal(model, modal_msg);
+            super::search::count_find_matches(model);
+            // Search as the query is typed (and as options are toggled)
+            match (search, find_search(model)) {
+                (Some(before), Some(after)) if before != after => {
+                    super::search::incremental_find(model).or(cmd)
+                }
+                _ => cmd,
+            }
+        }
 
         UiMsg::ToggleModal(modal_id) => {
             if let Some(ref active) = model.ui.active_modal {
                 if active.id() == modal_id {
                     // Close if same modal
+                    remember_find_query(model);
                     model.ui.close_modal();
                     return Some(Cmd::Redraw);
                 }
@@ -100,26 +246,48 @@ pub fn update_ui(model: &mut AppModel, msg: UiMsg) -> Option<Cmd> {
                 }
                 ModalId::GotoLine => ModalState::GotoLine(GotoLineState::default()),
                 ModalId::FindReplace => {
-                    let state = model.ui.last_find_replace.clone().unwrap_or_default();
-                    ModalState::FindReplace(state)
+                    ModalState::FindReplace(Box::new(find_replace_state(model)))
                 }
                 ModalId::ThemePicker => {
                     ModalState::ThemePicker(ThemePickerState::new(model.config.theme.clone()))
                 }
-                ModalId::FileFinder => {
-                    // Get files from workspace (if open)
-                    if let Some(ref workspace) = model.workspace {
-                        let all_files = workspace.file_tree.get_all_file_paths();
-                        let workspace_root = workspace.root.clone();
-                        let mut state = FileFinderState::new(all_files, workspace_root);
-                        // Initialize results with all files (empty query shows all)
-                        update_file_finder_results(&mut state);
-                        ModalState::FileFinder(state)
-                    } else {
+                ModalId::LanguagePicker => {
+                    ModalState::LanguagePicker(LanguagePickerState::new(model.document().language))
+                }
+                ModalId::GotoSymbol => {
+                    let outline = model
+                        .editor_area
+                        .focused_document()
+                        .and_then(|doc| doc.outline.as_ref())
+                        .filter(|outline| !outline.is_empty());
+                    match outline {
+                        Some(outline) => ModalState::GotoSymbol(GotoSymbolState::new(outline)),
+                        None => {
+                            model.ui.set_status("No symbols in this document");
+                            return Some(Cmd::Redraw);
+                        }
+                    }
+                }
+                ModalId::QuickOpen => match quick_open_state(model) {
+                    Some(state) => ModalState::QuickOpen(state),
+                    None => {
                         model.ui.set_status("No workspace open");
                         return Some(Cmd::Redraw);
                     }
-                }
+                },
+                // Only opened by the edit being reviewed / the tab being
+                // closed; nothing to toggle on
+                ModalId::EditReview
+                | ModalId::UnsavedChanges
+                | ModalId::DeletedFile
+                | ModalId::FileChanged
+                | ModalId::RenameFile
+                | ModalId::CreateFile
+                | ModalId::Backlinks
+                | ModalId::KeymapDiagnostics
+                | ModalId::BreadcrumbSymbols
+                | ModalId::WorkspaceTrust
+                | ModalId::ContextMenu => return Some(Cmd::Redraw),
                 ModalId::RecentFiles => {
                     let current_file = model
                         .editor_area
@@ -132,32 +300,18 @@ pub fn update_ui(model: &mut AppModel, msg: UiMsg) -> Option<Cmd> {
                 }
             };
             model.ui.open_modal(state);
+            super::search::count_find_matches(model);
             Some(Cmd::Redraw)
         }
 
         UiMsg::OpenFuzzyFileFinder => {
-            // Check if workspace is open
-            if model.workspace.is_none() {
+            let Some(state) = quick_open_state(model) else {
                 model
                     .ui
                     .set_status("No workspace open - use Cmd+O to open a file");
                 return Some(Cmd::Redraw);
-            }
-
-            // Get files from workspace
-            let (all_files, workspace_root) = if let Some(ref workspace) = model.workspace {
-                (
-                    workspace.file_tree.get_all_file_paths(),
-                    workspace.root.clone(),
-                )
-            } else {
-                return Some(Cmd::Redraw);
             };
-
-            let mut state = FileFinderState::new(all_files, workspace_root);
-            // Initialize results with all files (empty query shows all)
-            update_file_finder_results(&mut state);
-            model.ui.open_modal(ModalState::FileFinder(state));
+            model.ui.open_modal(ModalState::QuickOpen(state));
             Some(Cmd::Redraw)
         }
 
@@ -172,6 +326,11 @@ pub fn update_ui(model: &mut AppModel, msg: UiMsg) -> Option<Cmd> {
             Some(Cmd::Redraw)
         }
 
+        UiMsg::FileDropped(path) => {
+            let paths = model.ui.drop_state.take_drop(path)?;
+            Some(open_dropped_paths(model, paths))
+        }
+
         // === Scrollbar interaction ===
         UiMsg::ScrollbarTrackClickedVertical {
             editor_id,
@@ -256,6 +415,20 @@ pub fn update_ui(model: &mut AppModel, msg: UiMsg) -> Option<Cmd> {
     }
 }
 
+/// Change the editor font size and remember it in the config. The renderer
+/// is rebuilt for the new size, which re-measures every viewport.
+fn set_font_size(model: &mut AppModel, font_size: f32) -> Option<Cmd> {
+    model.ui.set_status(format!("Font size {}", font_size));
+    if font_size == model.config.font_size {
+        return Some(Cmd::redraw_status_bar());
+    }
+    model.config.font_size = font_size;
+    if let Err(e) = model.config.save() {
+        tracing::warn!("Failed to save font size: {}", e);
+    }
+    Some(Cmd::Batch(vec![Cmd::ReinitializeRenderer, Cmd::Redraw]))
+}
+
 /// Get mutable access to the "active" editable text field for a modal state,
 /// if it has one. This is the single field that plain text-editing
 /// `ModalMsg` variants (insert/delete/move/select/copy/cut) operate on.
@@ -268,23 +441,51 @@ fn modal_editable_mut(modal: &mut ModalState) -> Option<&mut EditableState<Strin
         ModalState::CommandPalette(state) => Some(&mut state.editable),
         ModalState::GotoLine(state) => Some(&mut state.editable),
         ModalState::FindReplace(state) => Some(state.focused_editable_mut()),
-        ModalState::ThemePicker(_) => None,
-        ModalState::FileFinder(state) => Some(&mut state.editable),
+        ModalState::ThemePicker(_)
+        | ModalState::EditReview(_)
+        | ModalState::UnsavedChanges(_)
+        | ModalState::DeletedFile(_)
+        | ModalState::FileChanged(_)
+        | ModalState::WorkspaceTrust(_)
+        | ModalState::ContextMenu(_) => None,
+        ModalState::QuickOpen(state) => Some(&mut state.editable),
         ModalState::RecentFiles(state) => Some(&mut state.editable),
+        ModalState::RenameFile(state) => Some(&mut state.editable),
+        ModalState::CreateFile(state) => Some(&mut state.editable),
+        ModalState::Backlinks(state) => Some(&mut state.editable),
+        ModalState::KeymapDiagnostics(state) => Some(&mut state.editable),
+        ModalState::LanguagePicker(state) => Some(&mut state.editable),
+        ModalState::BreadcrumbSymbols(state) => Some(&mut state.editable),
+        ModalState::GotoSymbol(state) => Some(&mut state.editable),
     }
 }
 
 /// Run the modal-specific side effect that should happen whenever a modal's
 /// text input changes (insert/delete/cut/paste). `CommandPalette` and
 /// `RecentFiles` reset their selected index back to the top of the list;
-/// `FileFinder` refreshes its fuzzy-matched results. Other modal types have
+/// `QuickOpen` refreshes its fuzzy-matched results. Other modal types have
 /// no such side effect.
 fn on_modal_input_changed(modal: &mut ModalState) {
     match modal {
         ModalState::CommandPalette(state) => state.selected_index = 0,
-        ModalState::FileFinder(state) => update_file_finder_results(state),
+        ModalState::QuickOpen(state) => state.update_results(),
         ModalState::RecentFiles(state) => state.selected_index = 0,
-        ModalState::GotoLine(_) | ModalState::FindReplace(_) | ModalState::ThemePicker(_) => {}
+        ModalState::Backlinks(state) => state.selected_index = 0,
+        ModalState::KeymapDiagnostics(state) => state.selected_index = 0,
+        ModalState::LanguagePicker(state) => state.selected_index = 0,
+        ModalState::BreadcrumbSymbols(state) => state.selected_index = 0,
+        ModalState::GotoSymbol(state) => state.selected_index = 0,
+        ModalState::GotoLine(_)
+        | ModalState::FindReplace(_)
+        | ModalState::ThemePicker(_)
+        | ModalState::EditReview(_)
+        | ModalState::UnsavedChanges(_)
+        | ModalState::DeletedFile(_)
+        | ModalState::FileChanged(_)
+        | ModalState::WorkspaceTrust(_)
+        | ModalState::ContextMenu(_)
+        | ModalState::RenameFile(_)
+        | ModalState::CreateFile(_) => {}
     }
 }
 
@@ -305,8 +506,10 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
         }
 
         ModalMsg::OpenFindReplace => {
-            let state = model.ui.last_find_replace.clone().unwrap_or_default();
-            model.ui.open_modal(ModalState::FindReplace(state));
+            let state = find_replace_state(model);
+            model
+                .ui
+                .open_modal(ModalState::FindReplace(Box::new(state)));
             Some(Cmd::Redraw)
         }
 
@@ -317,6 +520,11 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                     model.theme = theme;
                 }
             }
+            if let Some(ModalState::EditReview(state)) = &model.ui.active_modal {
+                let status = format!("{}: changes discarded", state.source.label());
+                model.ui.set_status(status);
+            }
+            remember_find_query(model);
             model.ui.close_modal();
             Some(Cmd::Redraw)
         }
@@ -327,15 +535,45 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                     ModalState::CommandPalette(state) => state.set_input(&text),
                     ModalState::GotoLine(state) => state.set_input(&text),
                     ModalState::FindReplace(state) => state.set_query(&text),
-                    ModalState::ThemePicker(_) => {} // No text input for theme picker
-                    ModalState::FileFinder(state) => {
+                    // No text input for theme picker, edit review or the
+                    // unsaved changes prompt
+                    ModalState::ThemePicker(_)
+                    | ModalState::EditReview(_)
+                    | ModalState::UnsavedChanges(_)
+                    | ModalState::DeletedFile(_)
+                    | ModalState::FileChanged(_)
+                    | ModalState::WorkspaceTrust(_)
+                    | ModalState::ContextMenu(_) => {}
+                    ModalState::QuickOpen(state) => {
                         state.set_input(&text);
-                        update_file_finder_results(state);
+                        state.update_results();
                     }
                     ModalState::RecentFiles(state) => {
                         state.editable.set_content(&text);
                         state.selected_index = 0;
                     }
+                    ModalState::RenameFile(state) => state.editable.set_content(&text),
+                    ModalState::CreateFile(state) => state.editable.set_content(&text),
+                    ModalState::Backlinks(state) => {
+                        state.editable.set_content(&text);
+                        state.selected_index = 0;
+                    }
+                    ModalState::KeymapDiagnostics(state) => {
+                        state.editable.set_content(&text);
+                        state.selected_index = 0;
+                    }
+                    ModalState::LanguagePicker(state) => {
+                        state.editable.set_content(&text);
+                        state.selected_index = 0;
+                    }
+                    ModalState::BreadcrumbSymbols(state) => {
+                        state.editable.set_content(&text);
+                        state.selected_index = 0;
+                    }
+                    ModalState::GotoSymbol(state) => {
+                        state.editable.set_content(&text);
+                        state.selected_index = 0;
+                    }
                 }
                 Some(Cmd::Redraw)
             } else {
@@ -572,14 +810,58 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                         state.selected_index = state.selected_index.saturating_sub(1);
                         state.themes.get(state.selected_index).map(|t| t.id.clone())
                     }
-                    ModalState::FileFinder(state) => {
-                        state.selected_index = state.selected_index.saturating_sub(1);
+                    ModalState::QuickOpen(state) => {
+                        state.select(state.selected_index.saturating_sub(1));
                         None
                     }
                     ModalState::RecentFiles(state) => {
                         state.selected_index = state.selected_index.saturating_sub(1);
                         None
                     }
+                    ModalState::Backlinks(state) => {
+                        state.selected_index = state.selected_index.saturating_sub(1);
+                        None
+                    }
+                    ModalState::KeymapDiagnostics(state) => {
+                        state.selected_index = state.selected_index.saturating_sub(1);
+                        None
+                    }
+                    ModalState::LanguagePicker(state) => {
+                        state.selected_index = state.selected_index.saturating_sub(1);
+                        None
+                    }
+                    ModalState::BreadcrumbSymbols(state) => {
+                        state.selected_index = state.selected_index.saturating_sub(1);
+                        None
+                    }
+                    ModalState::GotoSymbol(state) => {
+                        state.selected_index = state.selected_index.saturating_sub(1);
+                        None
+                    }
+                    ModalState::EditReview(state) => {
+                        move_review_selection(state, -1);
+                        None
+                    }
+                    ModalState::UnsavedChanges(state) => {
+                        state.selected = state.selected.step(false);
+                        None
+                    }
+                    ModalState::DeletedFile(state) => {
+                        state.selected = state.selected.step(false);
+                        None
+                    }
+                    ModalState::FileChanged(state) => {
+                        state.selected = state.selected.step(false);
+                        None
+                    }
+                    ModalState::WorkspaceTrust(state) => {
+                        state.selected = state.selected.step(false);
+                        None
+                    }
+                    ModalState::ContextMenu(state) => {
+                        state.step(false);
+                        None
+                    }
                     _ => None,
                 };
                 // Apply preview theme for instant preview
@@ -599,7 +881,7 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                 let preview_theme_id = match modal {
                     ModalState::CommandPalette(state) => {
                         let input_text = state.input();
-                        let filtered = filter_commands(&input_text);
+                        let filtered = palette_entries(&input_text, &model.config.external_tools);
                         let max_index = filtered.len().saturating_sub(1);
                         state.selected_index =
                             state.selected_index.saturating_add(1).min(max_index);
@@ -611,10 +893,8 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                             state.selected_index.saturating_add(1).min(max_index);
                         state.themes.get(state.selected_index).map(|t| t.id.clone())
                     }
-                    ModalState::FileFinder(state) => {
-                        let max_index = state.results.len().saturating_sub(1);
-                        state.selected_index =
-                            state.selected_index.saturating_add(1).min(max_index);
+                    ModalState::QuickOpen(state) => {
+                        state.select(state.selected_index.saturating_add(1));
                         None
                     }
                     ModalState::RecentFiles(state) => {
@@ -624,6 +904,60 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                             state.selected_index.saturating_add(1).min(max_index);
                         None
                     }
+                    ModalState::Backlinks(state) => {
+                        let max_index = state.filtered_entries().len().saturating_sub(1);
+                        state.selected_index =
+                            state.selected_index.saturating_add(1).min(max_index);
+                        None
+                    }
+                    ModalState::KeymapDiagnostics(state) => {
+                        let max_index = state.filtered_entries().len().saturating_sub(1);
+                        state.selected_index =
+                            state.selected_index.saturating_add(1).min(max_index);
+                        None
+                    }
+                    ModalState::LanguagePicker(state) => {
+                        let max_index = state.filtered_languages().len().saturating_sub(1);
+                        state.selected_index =
+                            state.selected_index.saturating_add(1).min(max_index);
+                        None
+                    }
+                    ModalState::BreadcrumbSymbols(state) => {
+                        let max_index = state.filtered_entries().len().saturating_sub(1);
+                        state.selected_index =
+                            state.selected_index.saturating_add(1).min(max_index);
+                        None
+                    }
+                    ModalState::GotoSymbol(state) => {
+                        let max_index = state.filtered_entries().len().saturating_sub(1);
+                        state.selected_index =
+                            state.selected_index.saturating_add(1).min(max_index);
+                        None
+                    }
+                    ModalState::EditReview(state) => {
+                        move_review_selection(state, 1);
+                        None
+                    }
+                    ModalState::UnsavedChanges(state) => {
+                        state.selected = state.selected.step(true);
+                        None
+                    }
+                    ModalState::DeletedFile(state) => {
+                        state.selected = state.selected.step(true);
+                        None
+                    }
+                    ModalState::FileChanged(state) => {
+                        state.selected = state.selected.step(true);
+                        None
+                    }
+                    ModalState::WorkspaceTrust(state) => {
+                        state.selected = state.selected.step(true);
+                        None
+                    }
+                    ModalState::ContextMenu(state) => {
+                        state.step(true);
+                        None
+                    }
                     _ => None,
                 };
                 // Apply preview theme for instant preview
@@ -647,16 +981,20 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                     ModalState::CommandPalette(state) => {
                         // Get the selected command
                         let input_text = state.input();
-                        let filtered = filter_commands(&input_text);
+                        let filtered = palette_entries(&input_text, &model.config.external_tools);
                         let selected_index =
                             state.selected_index.min(filtered.len().saturating_sub(1));
 
-                        if let Some(cmd_def) = filtered.get(selected_index) {
-                            let cmd_id = cmd_def.id;
+                        if let Some(action) = filtered.get(selected_index).map(|e| e.action()) {
                             // Save state for next time (only on successful execution)
                             model.ui.last_command_palette = Some(state);
                             model.ui.close_modal();
-                            return execute_command(model, cmd_id);
+                            return match action {
+                                PaletteAction::Command(cmd_id) => execute_command(model, cmd_id),
+                                PaletteAction::Tool(index) => {
+                                    super::external_tools::run_tool(model, index)
+                                }
+                            };
                         }
                         model.ui.close_modal();
                         Some(Cmd::Redraw)
@@ -699,15 +1037,24 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                         Some(Cmd::Redraw)
                     }
                     ModalState::FindReplace(state) => {
-                        // For Confirm, treat it as FindNext
-                        let query = state.query();
-                        if !query.is_empty() {
-                            let case_sensitive = state.case_sensitive;
-                            model.ui.last_find_replace = Some(state);
-                            return find_next_in_document(model, &query, case_sensitive);
+                        // For Confirm, treat it as FindNext (or, in the
+                        // replacement field, ReplaceAndFindNext)
+                        if state.query().is_empty() {
+                            model.ui.close_modal();
+                            return Some(Cmd::Redraw);
                         }
-                        model.ui.close_modal();
-                        Some(Cmd::Redraw)
+                        let search = state.search_highlight();
+                        if search.query.is_empty() {
+                            // Invalid regex: the modal shows why
+                            return Some(Cmd::Redraw);
+                        }
+                        let replacement = state.replacement();
+                        let replacing = state.focused_field == FindReplaceField::Replace;
+                        model.ui.last_find_replace = Some(*state);
+                        if replacing {
+                            return replace_and_find_next(model, search, &replacement);
+                        }
+                        find_next_in_document(model, search)
                     }
                     ModalState::ThemePicker(state) => {
                         // Apply selected theme and save config
@@ -724,7 +1071,7 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                         model.ui.close_modal();
                         Some(Cmd::Redraw)
                     }
-                    ModalState::FileFinder(state) => {
+                    ModalState::QuickOpen(state) => {
                         // Open selected file
                         if let Some(file_match) = state.results.get(state.selected_index) {
                             let path = file_match.path.clone();
@@ -744,15 +1091,188 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
                         model.ui.close_modal();
                         Some(Cmd::Redraw)
                     }
+                    ModalState::EditReview(state) => {
+                        model.ui.close_modal();
+                        apply_edit_review(model, state)
+                    }
+                    ModalState::UnsavedChanges(state) => {
+                        model.ui.close_modal();
+                        resolve_unsaved_changes(model, state.tab_id, state.selected)
+                    }
+                    ModalState::DeletedFile(state) => {
+                        model.ui.close_modal();
+                        resolve_deleted_file(model, &state, state.selected)
+                    }
+                    ModalState::FileChanged(state) => {
+                        model.ui.close_modal();
+                        let choice = state.selected;
+                        resolve_file_changed(model, state, choice)
+                    }
+                    ModalState::WorkspaceTrust(state) => {
+                        model.ui.close_modal();
+                        resolve_workspace_trust(model, &state.root, state.selected)
+                    }
+                    ModalState::ContextMenu(state) => {
+                        model.ui.close_modal();
+                        match state.selected_command() {
+                            Some(id) => execute_command(model, id),
+                            None => Some(Cmd::Redraw),
+                        }
+                    }
+                    ModalState::RenameFile(state) => {
+                        model.ui.close_modal();
+                        match state.target() {
+                            Some(to) => update_workspace(
+                                model,
+                                WorkspaceMsg::RenamePath {
+                                    from: state.path,
+                                    to,
+                                },
+                            ),
+                            None => Some(Cmd::Redraw),
+                        }
+                    }
+                    ModalState::CreateFile(state) => {
+                        model.ui.close_modal();
+                        match state.target() {
+                            Some(path) => update_workspace(model, WorkspaceMsg::CreateFile(path)),
+                            None => Some(Cmd::Redraw),
+                        }
+                    }
+                    ModalState::Backlinks(state) => {
+                        model.ui.close_modal();
+                        let entry = state.filtered_entries().get(state.selected_index).cloned();
+                        match entry {
+                            Some(entry) => open_item(
+                                model,
+                                Some(entry.path.clone()),
+                                None,
+                                entry.line,
+                                entry.column,
+                            ),
+                            None => Some(Cmd::Redraw),
+                        }
+                    }
+                    ModalState::KeymapDiagnostics(state) => {
+                        model.ui.close_modal();
+                        let entry = state.filtered_entries().get(state.selected_index).cloned();
+                        match entry.and_then(|entry| Some((entry.path.clone()?, entry.line))) {
+                            Some((path, line)) => {
+                                open_item(model, Some(path), None, line.unwrap_or(0), 0)
+                            }
+                            // The embedded defaults have no file to open
+                            None => Some(Cmd::Redraw),
+                        }
+                    }
+                    ModalState::LanguagePicker(state) => {
+                        model.ui.close_modal();
+                        match state.filtered_languages().get(state.selected_index) {
+                            Some(&language) => super::syntax::override_language(model, language),
+                            None => Some(Cmd::Redraw),
+                        }
+                    }
+                    ModalState::BreadcrumbSymbols(state) => {
+                        model.ui.close_modal();
+                        match state.filtered_entries().get(state.selected_index) {
+                            Some(entry) => super::outline::update_outline(
+                                model,
+                                OutlineMsg::JumpToSymbol {
+                                    line: entry.line,
+                                    col: entry.column,
+                                },
+                            ),
+                            None => Some(Cmd::Redraw),
+                        }
+                    }
+                    ModalState::GotoSymbol(state) => {
+                        model.ui.close_modal();
+                        match state.filtered_entries().get(state.selected_index) {
+                            Some(entry) => super::outline::update_outline(
+                                model,
+                                OutlineMsg::JumpToSymbol {
+                                    line: entry.line,
+                                    col: entry.column,
+                                },
+                            ),
+                            None => Some(Cmd::Redraw),
+                        }
+                    }
                 }
             } else {
                 None
             }
         }
 
+        ModalMsg::ToggleReviewHunk => {
+            if let Some(ModalState::EditReview(ref mut state)) = model.ui.active_modal {
+                state.toggle_selected();
+                Some(Cmd::Redraw)
+            } else {
+                None
+            }
+        }
+
+        ModalMsg::SetAllReviewHunks(accepted) => {
+            if let Some(ModalState::EditReview(ref mut state)) = model.ui.active_modal {
+                state.set_all(accepted);
+                Some(Cmd::Redraw)
+            } else {
+                None
+            }
+        }
+
+        ModalMsg::ResolveUnsavedChanges(choice) => {
+            if let Some(ModalState::UnsavedChanges(ref state)) = model.ui.active_modal {
+                let tab_id = state.tab_id;
+                model.ui.close_modal();
+                resolve_unsaved_changes(model, tab_id, choice)
+            } else {
+                None
+            }
+        }
+
+        ModalMsg::ResolveDeletedFile(choice) => {
+            if let Some(ModalState::DeletedFile(state)) = model.ui.active_modal.clone() {
+                model.ui.close_modal();
+                resolve_deleted_file(model, &state, choice)
+            } else {
+                None
+            }
+        }
+
+        ModalMsg::ResolveFileChanged(choice) => {
+            if let Some(ModalState::FileChanged(state)) = model.ui.active_modal.clone() {
+                model.ui.close_modal();
+                resolve_file_changed(model, state, choice)
+            } else {
+                None
+            }
+        }
+
+        ModalMsg::ResolveWorkspaceTrust(choice) => {
+            if let Some(ModalState::WorkspaceTrust(state)) = model.ui.active_modal.clone() {
+                model.ui.close_modal();
+                resolve_workspace_trust(model, &state.root, choice)
+            } else {
+                None
+            }
+        }
+
+        ModalMsg::SelectContextMenuItem(index) => {
+            if let Some(ModalState::ContextMenu(ref mut state)) = model.ui.active_modal {
+                let previous = state.selected_index;
+                state.select(index);
+                (state.selected_index != previous).then_some(Cmd::Redraw)
+            } else {
+                None
+            }
+        }
+
         ModalMsg::ToggleFindReplaceField => {
             if let Some(ModalState::FindReplace(ref mut state)) = model.ui.active_modal {
                 state.toggle_field();
+                // Going to the replacement shows it
+                state.replace_mode |= state.focused_field == FindReplaceField::Replace;
                 Some(Cmd::Redraw)
             } else {
                 None
@@ -768,19 +1288,41 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
             }
         }
 
+        ModalMsg::ToggleFindRegex => {
+            if let Some(ModalState::FindReplace(ref mut state)) = model.ui.active_modal {
+                state.regex = !state.regex;
+                Some(Cmd::Redraw)
+            } else {
+                None
+            }
+        }
+
+        ModalMsg::ToggleFindInSelection => {
+            if let Some(ModalState::FindReplace(ref mut state)) = model.ui.active_modal {
+                if state.scope.is_none() {
+                    model.ui.transient_message = Some(TransientMessage::new(
+                        "Select text before opening Find to search in it".to_string(),
+                        Duration::from_secs(2),
+                    ));
+                    return Some(Cmd::Redraw);
+                }
+                state.in_selection = !state.in_selection;
+                let scope = state.active_scope();
+                if let Some(highlight) = &mut model.ui.search_highlight {
+                    highlight.scope = scope;
+                }
+                Some(Cmd::Redraw)
+            } else {
+                None
+            }
+        }
+
         ModalMsg::FindNext => {
             if let Some(ModalState::FindReplace(ref state)) = model.ui.active_modal {
-                let query = state.query();
-                let case_sensitive = state.case_sensitive;
-                if !query.is_empty() {
-                    model.ui.last_find_replace = model.ui.active_modal.clone().and_then(|m| {
-                        if let ModalState::FindReplace(s) = m {
-                            Some(s)
-                        } else {
-                            None
-                        }
-                    });
-                    return find_next_in_document(model, &query, case_sensitive);
+                let search = state.search_highlight();
+                if !search.query.is_empty() {
+                    model.ui.last_find_replace = Some((**state).clone());
+                    return find_next_in_document(model, search);
                 }
             }
             Some(Cmd::Redraw)
@@ -788,17 +1330,10 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
 
         ModalMsg::FindPrevious => {
             if let Some(ModalState::FindReplace(ref state)) = model.ui.active_modal {
-                let query = state.query();
-                let case_sensitive = state.case_sensitive;
-                if !query.is_empty() {
-                    model.ui.last_find_replace = model.ui.active_modal.clone().and_then(|m| {
-                        if let ModalState::FindReplace(s) = m {
-                            Some(s)
-                        } else {
-                            None
-                        }
-                    });
-                    return find_prev_in_document(model, &query, case_sensitive);
+                let search = state.search_highlight();
+                if !search.query.is_empty() {
+                    model.ui.last_find_replace = Some((**state).clone());
+                    return find_prev_in_document(model, search);
                 }
             }
             Some(Cmd::Redraw)
@@ -806,18 +1341,11 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
 
         ModalMsg::ReplaceAndFindNext => {
             if let Some(ModalState::FindReplace(ref state)) = model.ui.active_modal {
-                let query = state.query();
+                let search = state.search_highlight();
                 let replacement = state.replacement();
-                let case_sensitive = state.case_sensitive;
-                if !query.is_empty() {
-                    model.ui.last_find_replace = model.ui.active_modal.clone().and_then(|m| {
-                        if let ModalState::FindReplace(s) = m {
-                            Some(s)
-                        } else {
-                            None
-                        }
-                    });
-                    return replace_and_find_next(model, &query, &replacement, case_sensitive);
+                if !search.query.is_empty() {
+                    model.ui.last_find_replace = Some((**state).clone());
+                    return replace_and_find_next(model, search, &replacement);
                 }
             }
             Some(Cmd::Redraw)
@@ -825,18 +1353,11 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
 
         ModalMsg::ReplaceAll => {
             if let Some(ModalState::FindReplace(ref state)) = model.ui.active_modal {
-                let query = state.query();
+                let search = state.search_highlight();
                 let replacement = state.replacement();
-                let case_sensitive = state.case_sensitive;
-                if !query.is_empty() {
-                    model.ui.last_find_replace = model.ui.active_modal.clone().and_then(|m| {
-                        if let ModalState::FindReplace(s) = m {
-                            Some(s)
-                        } else {
-                            None
-                        }
-                    });
-                    return replace_all(model, &query, &replacement, case_sensitive);
+                if !search.query.is_empty() {
+                    model.ui.last_find_replace = Some((**state).clone());
+                    return replace_all(model, &search, &replacement);
                 }
             }
             Some(Cmd::Redraw)
@@ -844,8 +1365,68 @@ fn update_modal(model: &mut AppModel, msg: ModalMsg) -> Option<Cmd> {
     }
 }
 
+/// Keep the query typed into the open find modal, so F3 and reopening the
+/// modal continue that search
+fn remember_find_query(model: &mut AppModel) {
+    if let Some(ModalState::FindReplace(state)) = &model.ui.active_modal {
+        if !state.query().is_empty() {
+            model.ui.last_find_replace = Some((**state).clone());
+        }
+    }
+}
+
+/// The open find modal's query and options
+fn find_search(model: &AppModel) -> Option<SearchHighlight> {
+    match &model.ui.active_modal {
+        Some(ModalState::FindReplace(state)) => Some(state.search_highlight()),
+        _ => None,
+    }
+}
+
+/// Find/replace state for opening the modal: the last search, scoped to
+/// the selection if there is one. A selection spanning lines turns "in
+/// selection" on; a shorter one is only remembered for the toggle.
+fn find_replace_state(model: &AppModel) -> FindReplaceState {
+    let mut state = model.ui.last_find_replace.clone().unwrap_or_default();
+    let selection = *model.editor().primary_selection();
+    state.origin = Some(selection.start());
+    state.scope = match model.document().id {
+        Some(document_id) if !selection.is_empty() => Some(SearchScope {
+            document_id,
+            start: selection.start(),
+            end: selection.end(),
+        }),
+        _ => None,
+    };
+    state.in_selection = state.scope.is_some() && selection.start().line != selection.end().line;
+    state
+}
+
+/// Occurrences of `search` in the focused document as char offsets, limited
+/// to its scope when that belongs to the document
+fn occurrences_in_scope(model: &mut AppModel, search: &SearchHighlight) -> Vec<(usize, usize)> {
+    let Some(doc) = model.editor_area.focused_document() else {
+        return Vec::new();
+    };
+    model
+        .ui
+        .search_index
+        .matches(doc, search)
+        .into_iter()
+        .map(|(start, end)| {
+            (
+                doc.cursor_to_offset(start.line, start.column),
+                doc.cursor_to_offset(end.line, end.column),
+            )
+        })
+        .collect()
+}
+
 /// Find next occurrence in the document and select it
-fn find_next_in_document(model: &mut AppModel, query: &str, case_sensitive: bool) -> Option<Cmd> {
+fn find_next_in_document(model: &mut AppModel, search: SearchHighlight) -> Option<Cmd> {
+    let scope = search.scope;
+    let occurrences = occurrences_in_scope(model, &search);
+    model.ui.search_highlight = Some(search);
     let editor = model.editor();
     let doc = model.document();
 
@@ -858,9 +1439,13 @@ fn find_next_in_document(model: &mut AppModel, query: &str, case_sensitive: bool
         doc.cursor_to_offset(editor.cursors[0].line, editor.cursors[0].column)
     };
 
-    if let Some((start, end)) =
-        doc.find_next_occurrence_with_options(query, start_offset, case_sensitive)
-    {
+    // First occurrence after the start point, wrapping around
+    let next = occurrences
+        .iter()
+        .find(|(start, _)| *start > start_offset)
+        .or(occurrences.first());
+    if let Some(&(start, end)) = next {
+        let doc = model.document();
         let (start_line, start_col) = doc.offset_to_cursor(start);
         let (end_line, end_col) = doc.offset_to_cursor(end);
 
@@ -881,7 +1466,7 @@ fn find_next_in_document(model: &mut AppModel, query: &str, case_sensitive: bool
     } else {
         // No match found - show transient message
         model.ui.transient_message = Some(TransientMessage::new(
-            "No matches found".to_string(),
+            no_matches_message(scope),
             Duration::from_secs(2),
         ));
         Some(Cmd::redraw_editor())
@@ -889,7 +1474,10 @@ fn find_next_in_document(model: &mut AppModel, query: &str, case_sensitive: bool
 }
 
 /// Find previous occurrence in the document and select it
-fn find_prev_in_document(model: &mut AppModel, query: &str, case_sensitive: bool) -> Option<Cmd> {
+fn find_prev_in_document(model: &mut AppModel, search: SearchHighlight) -> Option<Cmd> {
+    let scope = search.scope;
+    let occurrences = occurrences_in_scope(model, &search);
+    model.ui.search_highlight = Some(search);
     let editor = model.editor();
     let doc = model.document();
 
@@ -902,9 +1490,14 @@ fn find_prev_in_document(model: &mut AppModel, query: &str, case_sensitive: bool
         doc.cursor_to_offset(editor.cursors[0].line, editor.cursors[0].column)
     };
 
-    if let Some((start, end)) =
-        doc.find_prev_occurrence_with_options(query, start_offset, case_sensitive)
-    {
+    // Last occurrence before the start point, wrapping around
+    let previous = occurrences
+        .iter()
+        .rev()
+        .find(|(start, _)| *start < start_offset)
+        .or(occurrences.last());
+    if let Some(&(start, end)) = previous {
+        let doc = model.document();
         let (start_line, start_col) = doc.offset_to_cursor(start);
         let (end_line, end_col) = doc.offset_to_cursor(end);
 
@@ -914,70 +1507,99 @@ fn find_prev_in_document(model: &mut AppModel, query: &str, case_sensitive: bool
         editor.cursors[0].column = start_col;
         editor.cursors[0].desired_column = None;
 
-        // Set selection to cover the match
+        // Set selection to cover the match, with its head at the cursor
         editor.selections[0] = crate::model::Selection::from_anchor_head(
-            crate::model::Position::new(start_line, start_col),
             crate::model::Position::new(end_line, end_col),
+            crate::model::Position::new(start_line, start_col),
         );
 
         model.ensure_cursor_visible();
         Some(Cmd::redraw_editor())
     } else {
         model.ui.transient_message = Some(TransientMessage::new(
-            "No matches found".to_string(),
+            no_matches_message(scope),
             Duration::from_secs(2),
         ));
         Some(Cmd::redraw_editor())
     }
 }
 
+fn no_matches_message(scope: Option<SearchScope>) -> String {
+    if scope.is_some() {
+        "No matches in selection".to_string()
+    } else {
+        "No matches found".to_string()
+    }
+}
+
 /// Replace current selection if it matches, then find next
 fn replace_and_find_next(
     model: &mut AppModel,
-    query: &str,
+    search: SearchHighlight,
     replacement: &str,
-    case_sensitive: bool,
 ) -> Option<Cmd> {
-    // First, gather all the info we need without holding borrows
-    let should_replace = {
-        let editor = model.editor();
-        let doc = model.document();
-
-        if editor.selections[0].is_empty() {
-            None
-        } else {
-            let sel = &editor.selections[0];
-            let start = sel.start();
-            let end = sel.end();
-            let start_offset = doc.cursor_to_offset(start.line, start.column);
-            let end_offset = doc.cursor_to_offset(end.line, end.column);
-
-            let selected_text = doc.buffer.slice(start_offset..end_offset).to_string();
-            let matches = if case_sensitive {
-                selected_text == query
-            } else {
-                selected_text.to_lowercase() == query.to_lowercase()
-            };
-
-            if matches {
-                Some((start_offset, end_offset))
-            } else {
-                None
-            }
+    if model.editor().view_mode.is_csv() {
+        if search.regex.is_some() {
+            return regex_unsupported_in_csv(model);
+        }
+        if !super::csv::replace_in_cell_and_find_next(
+            model,
+            &search.query,
+            replacement,
+            search.case_sensitive,
+        ) {
+            model.ui.transient_message = Some(TransientMessage::new(
+                no_matches_message(None),
+                Duration::from_secs(2),
+            ));
         }
+        return Some(Cmd::Redraw);
+    }
+
+    // The selection is replaced if it is one of the matches (which also
+    // keeps it within the scope)
+    let selection = model.editor().selections[0];
+    let should_replace = if selection.is_empty() {
+        None
+    } else {
+        super::search::replacements(model, &search, replacement)
+            .into_iter()
+            .find(|&(start, end, _)| (start, end) == (selection.start(), selection.end()))
     };
 
     // Now do the replacement if needed
-    if let Some((start_offset, end_offset)) = should_replace {
+    let mut search = search;
+    if let Some((start, end, replacement)) = should_replace {
+        let doc = model.document();
+        let start_offset = doc.cursor_to_offset(start.line, start.column);
+        let end_offset = doc.cursor_to_offset(end.line, end.column);
+        // The scope's end moves with the replaced text
+        let scope_end = search
+            .scope
+            .map(|scope| doc.cursor_to_offset(scope.end.line, scope.end.column));
+        let deleted_text = doc.buffer.slice(start_offset..end_offset).to_string();
+        let cursor_before = model.editor().cursors[0];
+
         let doc = model.document_mut();
         doc.buffer.remove(start_offset..end_offset);
-        doc.buffer.insert(start_offset, replacement);
-        doc.is_modified = true;
-        doc.revision += 1;
+        doc.buffer.insert(start_offset, &replacement);
 
         // Update cursor position
         let new_offset = start_offset + replacement.chars().count();
         let (new_line, new_col) = doc.offset_to_cursor(new_offset);
+        doc.push_edit(EditOperation::Replace {
+            position: start_offset,
+            deleted_text,
+            inserted_text: replacement,
+            cursor_before,
+            cursor_after: Cursor::at(new_line, new_col),
+        });
+        if let (Some(scope), Some(scope_end)) = (search.scope.as_mut(), scope_end) {
+            let scope_end = (scope_end + new_offset).saturating_sub(end_offset);
+            let (line, column) = doc.offset_to_cursor(scope_end);
+            scope.end = crate::model::Position::new(line, column);
+            set_find_scope(model, *scope);
+        }
 
         let editor = model.editor_mut();
         editor.cursors[0].line = new_line;
@@ -986,54 +1608,104 @@ fn replace_and_find_next(
     }
 
     // Now find next
-    find_next_in_document(model, query, case_sensitive)
+    find_next_in_document(model, search)
 }
 
-/// Replace all occurrences
-fn replace_all(
-    model: &mut AppModel,
-    query: &str,
-    replacement: &str,
-    case_sensitive: bool,
-) -> Option<Cmd> {
-    let doc = model.document();
-    let occurrences = doc.find_all_occurrences_with_options(query, case_sensitive);
+/// CSV mode searches cell values as plain text
+fn regex_unsupported_in_csv(model: &mut AppModel) -> Option<Cmd> {
+    model.ui.transient_message = Some(TransientMessage::new(
+        "Regex replace isn't available in CSV mode".to_string(),
+        Duration::from_secs(2),
+    ));
+    Some(Cmd::Redraw)
+}
+
+/// Update the scope of the open find modal and of the remembered search
+fn set_find_scope(model: &mut AppModel, scope: SearchScope) {
+    if let Some(ModalState::FindReplace(state)) = &mut model.ui.active_modal {
+        state.scope = Some(scope);
+    }
+    if let Some(state) = &mut model.ui.last_find_replace {
+        state.scope = Some(scope);
+    }
+}
 
-    if occurrences.is_empty() {
+/// Replace all occurrences, via the edit review modal
+fn replace_all(model: &mut AppModel, search: &SearchHighlight, replacement: &str) -> Option<Cmd> {
+    // In CSV mode matches are searched in cell values, and each changed cell
+    // is written back re-escaped so the file stays valid CSV
+    let proposed = if let Some(csv) = model.editor().view_mode.as_csv() {
+        if search.regex.is_some() {
+            return regex_unsupported_in_csv(model);
+        }
+        let edits =
+            super::csv::cell_replacements(csv, &search.query, replacement, search.case_sensitive);
+        let content = model.document().buffer.to_string();
+        (!edits.is_empty()).then(|| write_cells(&content, &edits, csv.delimiter))
+    } else {
+        let replacements = super::search::replacements(model, search, replacement);
+
+        // Build the proposed text from end to start to preserve offsets;
+        // nothing touches the buffer until the review is confirmed
+        (!replacements.is_empty()).then(|| {
+            let doc = model.document();
+            let mut proposed = doc.buffer.clone();
+            for (start, end, replacement) in replacements.into_iter().rev() {
+                let start = doc.cursor_to_offset(start.line, start.column);
+                let end = doc.cursor_to_offset(end.line, end.column);
+                proposed.remove(start..end);
+                proposed.insert(start, &replacement);
+            }
+            proposed.to_string()
+        })
+    };
+
+    let Some(proposed) = proposed else {
         model.ui.transient_message = Some(TransientMessage::new(
-            "No matches found".to_string(),
+            no_matches_message(search.scope),
             Duration::from_secs(2),
         ));
         return Some(Cmd::Redraw);
-    }
+    };
 
-    let count = occurrences.len();
+    open_edit_review(model, EditReviewSource::ReplaceAll, &proposed)
+}
 
-    // Replace from end to start to preserve offsets
-    let doc = model.document_mut();
-    let replacement_char_len = replacement.chars().count();
-    for (start, end) in occurrences.into_iter().rev() {
-        doc.buffer.remove(start..end);
-        doc.buffer.insert(start, replacement);
+/// Get the line numbers of all cursors in the focused editor
+/// Returns empty vec if no focused editor exists
+/// Open what was dropped onto the window: the first folder becomes the
+/// workspace, files open in tabs, with a summary when there were several
+fn open_dropped_paths(model: &mut AppModel, paths: Vec<PathBuf>) -> Cmd {
+    let (folders, files): (Vec<PathBuf>, Vec<PathBuf>) =
+        paths.into_iter().partition(|path| path.is_dir());
+    if let Some(folder) = folders.first() {
+        model.open_workspace(folder.clone());
     }
-    doc.is_modified = true;
-    doc.revision += 1;
 
-    // Position cursor at end of last replacement (which is now first in document)
-    let editor = model.editor_mut();
-    editor.cursors[0].line = 0;
-    editor.cursors[0].column = replacement_char_len;
-    editor.clear_selection();
+    let mut cmds = vec![Cmd::Redraw];
+    let file_count = files.len();
+    for path in files {
+        cmds.extend(update_layout(model, LayoutMsg::OpenFileInNewTab(path)));
+    }
 
-    model.ui.transient_message = Some(TransientMessage::new(
-        format!("Replaced {} occurrences", count),
-        Duration::from_secs(2),
-    ));
-    Some(Cmd::redraw_editor())
+    let mut summary = Vec::new();
+    if file_count > 1 {
+        summary.push(format!("Opened {} files", file_count));
+    }
+    if folders.len() > 1 {
+        let only = if summary.is_empty() { "Only" } else { "only" };
+        summary.push(format!(
+            "{} the first folder was opened as the workspace",
+            only
+        ));
+    }
+    if !summary.is_empty() {
+        let message = summary.join("; ");
+        model.ui.transient_message = Some(TransientMessage::new(message, Duration::from_secs(3)));
+    }
+    Cmd::batch(cmds)
 }
 
-/// Get the line numbers of all cursors in the focused editor
-/// Returns empty vec if no focused editor exists
 fn get_current_cursor_lines(model: &AppModel) -> Vec<usize> {
     // Get the focused editor's cursors
     if let Some(editor) = model.focused_editor() {
@@ -1048,76 +1720,39 @@ fn get_current_cursor_lines(model: &AppModel) -> Vec<usize> {
 }
 
 // ============================================================================
-// Fuzzy File Finder
+// Quick Open
 // ============================================================================
 
-use crate::model::FileMatch;
-use nucleo_matcher::{Config, Matcher, Utf32Str};
-use std::path::Path;
-
-/// Update file finder results based on current query
-pub fn update_file_finder_results(state: &mut FileFinderState) {
-    let query = state.input();
-    state.results = fuzzy_match_files(&state.all_files, &query, &state.workspace_root);
-    // Reset selection to first item
-    state.selected_index = 0;
-}
-
-/// Perform fuzzy matching on file paths
-fn fuzzy_match_files(
-    files: &[std::path::PathBuf],
-    query: &str,
-    workspace_root: &Path,
-) -> Vec<FileMatch> {
-    if query.is_empty() {
-        // Show all files sorted alphabetically when no query (limit to first 100)
-        return files
-            .iter()
-            .take(100)
-            .map(|p| FileMatch::from_path(p, workspace_root, 0, vec![]))
-            .collect();
-    }
-
-    let mut matcher = Matcher::new(Config::DEFAULT);
-    let mut query_buf = Vec::new();
-    let needle = Utf32Str::new(query, &mut query_buf);
-
-    let mut results: Vec<FileMatch> = files
+/// Quick Open state over the workspace's files, with recently opened files
+/// boosted. `None` without a workspace.
+fn quick_open_state(model: &AppModel) -> Option<QuickOpenState> {
+    let workspace = model.workspace.as_ref()?;
+    let index = crate::quick_open::FileIndex::from_tree(&workspace.file_tree, &workspace.root);
+    let recent = model
+        .recent_files
+        .entries
         .iter()
-        .filter_map(|path| {
-            let filename = path.file_name()?.to_str()?;
-            let mut filename_buf = Vec::new();
-            let haystack = Utf32Str::new(filename, &mut filename_buf);
-
-            // Get fuzzy match score
-            let score = matcher.fuzzy_match(haystack, needle)?;
-
-            // Get match indices for highlighting
-            let mut indices = vec![];
-            matcher.fuzzy_indices(haystack, needle, &mut indices);
-            let indices = indices.to_vec();
-
-            Some(FileMatch::from_path(
-                path,
-                workspace_root,
-                score as u32,
-                indices,
-            ))
-        })
+        .map(|entry| entry.path.clone())
         .collect();
+    Some(QuickOpenState::new(index, recent))
+}
 
-    // Sort by score descending
-    results.sort_by_key(|a| std::cmp::Reverse(a.score));
-
-    // Limit results
-    results.truncate(50);
-    results
+/// Pause or resume the cursor blink when the window becomes inactive or
+/// active again. The cursor stays visible while paused.
+fn window_activity_changed(model: &mut AppModel) -> Option<Cmd> {
+    if !model.ui.is_window_active() {
+        // The runtime stops ticking, so save scratchpads now
+        super::scratchpad::save_scratchpads(model);
+    }
+    model.ui.reset_cursor_blink();
+    Some(Cmd::Redraw)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{get_current_cursor_lines, update_ui};
     use crate::commands::{Cmd, DamageArea};
+    use crate::config::RenderWhitespace;
     use crate::image::ImageState;
     use crate::messages::UiMsg;
     use crate::model::{AppModel, ViewMode};
@@ -1181,4 +1816,86 @@ mod tests {
         updated_previous.sort_unstable();
         assert_eq!(updated_previous, vec![3, 5]);
     }
+
+    #[test]
+    fn cursor_blink_pauses_while_window_is_inactive() {
+        let mut model = AppModel::new(80, 60, 1.0, vec![]);
+        model.config.cursor_blink_ms = 0;
+
+        update_ui(&mut model, UiMsg::WindowFocused(false));
+        assert!(!model.ui.is_window_active());
+        assert!(update_ui(&mut model, UiMsg::BlinkCursor).is_none());
+        assert!(model.ui.cursor_visible, "cursor stays visible while paused");
+
+        update_ui(&mut model, UiMsg::WindowFocused(true));
+        update_ui(&mut model, UiMsg::WindowOccluded(true));
+        assert!(update_ui(&mut model, UiMsg::BlinkCursor).is_none());
+
+        update_ui(&mut model, UiMsg::WindowOccluded(false));
+        assert!(model.ui.is_window_active());
+        assert!(update_ui(&mut model, UiMsg::BlinkCursor).is_some());
+        assert!(!model.ui.cursor_visible);
+    }
+
+    #[test]
+    fn performance_mode_stops_cursor_blink() {
+        use crate::model::status_bar::sync_status_bar;
+        use crate::model::{SegmentContent, SegmentId};
+        use crate::performance::PerformanceMode;
+
+        let mut model = AppModel::new(80, 60, 1.0, vec![]);
+        model.config.cursor_blink_ms = 0;
+        update_ui(&mut model, UiMsg::BlinkCursor);
+        assert!(!model.ui.cursor_visible);
+
+        // Auto mode turns on on battery, showing a cursor hidden mid-blink
+        update_ui(&mut model, UiMsg::PowerSourceChanged(true));
+        assert!(model.ui.cursor_visible);
+        assert!(update_ui(&mut model, UiMsg::BlinkCursor).is_none());
+        sync_status_bar(&mut model);
+        let segment = model.ui.status_bar.get_segment(SegmentId::PerformanceMode);
+        assert_eq!(
+            segment.map(|segment| &segment.content),
+            Some(&SegmentContent::Text("Performance (battery)".into()))
+        );
+
+        update_ui(&mut model, UiMsg::TogglePerformanceMode);
+        assert_eq!(model.config.performance_mode, PerformanceMode::Off);
+        assert!(update_ui(&mut model, UiMsg::BlinkCursor).is_some());
+
+        update_ui(&mut model, UiMsg::TogglePerformanceMode);
+        assert_eq!(model.config.performance_mode, PerformanceMode::On);
+        assert!(update_ui(&mut model, UiMsg::BlinkCursor).is_none());
+        assert!(model.ui.cursor_visible);
+    }
+
+    #[test]
+    fn breadcrumbs_take_lines_from_the_editor_while_shown() {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.recalculate_viewports();
+        let lines = model.editor().viewport.visible_lines;
+        assert_eq!(model.metrics.header_height(), model.metrics.tab_bar_height);
+
+        update_ui(&mut model, UiMsg::ToggleBreadcrumbs);
+        assert!(model.config.breadcrumbs);
+        assert!(model.metrics.breadcrumb_bar_height > 0);
+        assert!(model.editor().viewport.visible_lines < lines);
+
+        update_ui(&mut model, UiMsg::ToggleBreadcrumbs);
+        assert_eq!(model.metrics.breadcrumb_bar_height, 0);
+        assert_eq!(model.editor().viewport.visible_lines, lines);
+    }
+
+    #[test]
+    fn toggling_render_whitespace_turns_it_off_from_any_mode() {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        update_ui(&mut model, UiMsg::ToggleRenderWhitespace);
+        assert_eq!(model.config.render_whitespace, RenderWhitespace::All);
+        update_ui(&mut model, UiMsg::ToggleRenderWhitespace);
+        assert_eq!(model.config.render_whitespace, RenderWhitespace::None);
+
+        model.config.render_whitespace = RenderWhitespace::Selection;
+        update_ui(&mut model, UiMsg::ToggleRenderWhitespace);
+        assert_eq!(model.config.render_whitespace, RenderWhitespace::None);
+    }
 }
@@ -0,0 +1,205 @@
+//! Unsaved changes prompt: asking before a modified tab is closed
+
+use crate::commands::Cmd;
+use crate::messages::{AppMsg, LayoutMsg};
+use crate::model::{AppModel, ModalState, TabId, UnsavedChangesChoice, UnsavedChangesState};
+
+use super::app::update_app;
+use super::layout::{discard_and_close_tab, is_last_tab, update_layout};
+
+/// Group and index of a tab
+fn find_tab(model: &AppModel, tab_id: TabId) -> Option<(crate::model::GroupId, usize)> {
+    model
+        .editor_area
+        .groups
+        .iter()
+        .find_map(|(group_id, group)| {
+            group
+                .tabs
+                .iter()
+                .position(|tab| tab.id == tab_id)
+                .map(|index| (*group_id, index))
+        })
+}
+
+/// Open the unsaved changes prompt if closing `tab_id` would lose edits.
+///
+/// Returns `true` when the prompt was opened and the close must wait for
+/// the answer. Documents still shown in another tab are not prompted for,
+/// since closing one view keeps them open, and scratchpads are saved
+/// instead.
+pub(super) fn prompt_unsaved_changes(model: &mut AppModel, tab_id: TabId) -> bool {
+    match unsaved_changes_prompt(model, tab_id) {
+        Some(state) => {
+            model.ui.open_modal(ModalState::UnsavedChanges(state));
+            true
+        }
+        None => false,
+    }
+}
+
+/// The prompt closing `tab_id` needs, without opening it (see
+/// [`prompt_unsaved_changes`])
+pub(super) fn unsaved_changes_prompt(
+    model: &mut AppModel,
+    tab_id: TabId,
+) -> Option<UnsavedChangesState> {
+    if is_last_tab(model, tab_id) {
+        return None;
+    }
+    let (group_id, index) = find_tab(model, tab_id)?;
+    let editor_id = model.editor_area.groups[&group_id].tabs[index].editor_id;
+    let doc_id = model
+        .editor_area
+        .editors
+        .get(&editor_id)
+        .and_then(|editor| editor.document_id)?;
+    let doc = model.editor_area.documents.get(&doc_id)?;
+    if doc.is_scratchpad {
+        super::scratchpad::save_scratchpads(model);
+        return None;
+    }
+    if !doc.is_modified || model.editor_area.editors_for_document(doc_id).len() > 1 {
+        return None;
+    }
+    Some(UnsavedChangesState::new(tab_id, doc.display_name()))
+}
+
+/// Act on the answer to the unsaved changes prompt (the modal is closed)
+pub(super) fn resolve_unsaved_changes(
+    model: &mut AppModel,
+    tab_id: TabId,
+    choice: UnsavedChangesChoice,
+) -> Option<Cmd> {
+    match choice {
+        UnsavedChangesChoice::Cancel => Some(Cmd::Redraw),
+        UnsavedChangesChoice::DontSave => Some(discard_and_close_tab(model, tab_id)),
+        UnsavedChangesChoice::Save => {
+            // Saving works on the focused document, so bring the tab forward
+            let (group_id, index) = find_tab(model, tab_id)?;
+            model.editor_area.focused_group_id = group_id;
+            update_layout(model, LayoutMsg::SwitchToTab(index));
+
+            model.ui.close_after_save = Some(tab_id);
+            update_app(model, AppMsg::SaveFile)
+        }
+    }
+}
+
+/// Close the tab waiting on a save started from the prompt, once the save
+/// succeeded. A failed save keeps the tab open.
+pub(super) fn close_after_save(model: &mut AppModel) -> Option<Cmd> {
+    let tab_id = model.ui.close_after_save.take()?;
+    if model.document().is_modified {
+        return None;
+    }
+    Some(discard_and_close_tab(model, tab_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{ModalMsg, UiMsg};
+    use crate::update::update_ui;
+
+    /// Model with an untouched first tab and a modified second tab
+    fn modified_tab_model() -> (AppModel, TabId) {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        update_app(&mut model, AppMsg::NewFile);
+        model.document_mut().buffer = ropey::Rope::from("draft");
+        model.document_mut().is_modified = true;
+        let tab_id = model
+            .editor_area
+            .focused_group()
+            .and_then(|group| group.active_tab())
+            .unwrap()
+            .id;
+        (model, tab_id)
+    }
+
+    fn tab_count(model: &AppModel) -> usize {
+        model.editor_area.focused_group().unwrap().tabs.len()
+    }
+
+    #[test]
+    fn closing_modified_tab_asks_first() {
+        let (mut model, tab_id) = modified_tab_model();
+        update_layout(&mut model, LayoutMsg::CloseTab(tab_id));
+
+        assert_eq!(tab_count(&model), 2);
+        assert!(matches!(
+            model.ui.active_modal,
+            Some(ModalState::UnsavedChanges(ref state)) if state.tab_id == tab_id
+        ));
+
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::Close));
+        assert_eq!(tab_count(&model), 2);
+    }
+
+    #[test]
+    fn dont_save_closes_without_saving() {
+        let (mut model, _) = modified_tab_model();
+        update_layout(&mut model, LayoutMsg::CloseFocusedTab);
+        update_ui(
+            &mut model,
+            UiMsg::Modal(ModalMsg::ResolveUnsavedChanges(
+                UnsavedChangesChoice::DontSave,
+            )),
+        );
+
+        assert!(model.ui.active_modal.is_none());
+        assert_eq!(tab_count(&model), 1);
+    }
+
+    #[test]
+    fn save_on_untitled_tab_goes_through_save_as_and_closes_after() {
+        let (mut model, _) = modified_tab_model();
+        update_layout(&mut model, LayoutMsg::CloseFocusedTab);
+        let cmd = update_ui(&mut model, UiMsg::Modal(ModalMsg::Confirm));
+        assert!(matches!(cmd, Some(Cmd::ShowSaveFileDialog { .. })));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("draft.rs");
+        update_app(
+            &mut model,
+            AppMsg::SaveFileAsDialogResult {
+                document_id: None,
+                path: Some(path.clone()),
+            },
+        );
+        assert_eq!(model.document().file_path.as_deref(), Some(path.as_path()));
+        assert_eq!(model.document().language, crate::syntax::LanguageId::Rust);
+        assert_eq!(tab_count(&model), 2, "tab stays open until the save lands");
+
+        update_app(&mut model, AppMsg::SaveCompleted(Ok(())));
+        assert_eq!(tab_count(&model), 1);
+        assert!(model.ui.close_after_save.is_none());
+    }
+
+    #[test]
+    fn cancelled_save_as_keeps_tab_open() {
+        let (mut model, _) = modified_tab_model();
+        update_layout(&mut model, LayoutMsg::CloseFocusedTab);
+        update_ui(&mut model, UiMsg::Modal(ModalMsg::Confirm));
+        update_app(
+            &mut model,
+            AppMsg::SaveFileAsDialogResult {
+                document_id: None,
+                path: None,
+            },
+        );
+
+        assert_eq!(tab_count(&model), 2);
+        assert!(model.ui.close_after_save.is_none());
+    }
+
+    #[test]
+    fn unmodified_tab_closes_immediately() {
+        let (mut model, _) = modified_tab_model();
+        model.document_mut().is_modified = false;
+        update_layout(&mut model, LayoutMsg::CloseFocusedTab);
+
+        assert!(model.ui.active_modal.is_none());
+        assert_eq!(tab_count(&model), 1);
+    }
+}
@@ -0,0 +1,351 @@
+//! Vim emulation: applies normal and visual mode commands to the focused
+//! editor. Key parsing lives in `crate::vim`; edits go through the regular
+//! document messages so undo and the clipboard behave as usual.
+
+use crate::commands::Cmd;
+use crate::messages::{DocumentMsg, VimMsg};
+use crate::model::{AppModel, Position, Selection};
+use crate::util::{char_type, CharType};
+use crate::vim::{motion_target, InsertAt, Motion, Operator, VimAction, VimMode};
+
+use super::document::update_document;
+
+/// Handle Vim messages (ignored unless `vim_mode` is on)
+pub fn update_vim(model: &mut AppModel, msg: VimMsg) -> Option<Cmd> {
+    if !model.config.vim_mode {
+        return None;
+    }
+    match msg {
+        VimMsg::Key(ch) => match model.vim.key(ch) {
+            Some(action) => apply(model, action),
+            // Partly typed command: redraw the status bar
+            None => Some(Cmd::Redraw),
+        },
+        VimMsg::NormalMode => {
+            model.vim.reset();
+            let mode = std::mem::replace(&mut model.vim.mode, VimMode::Normal);
+            let cursor = *model.editor().active_cursor();
+            model.editor_mut().collapse_to_primary();
+            match mode {
+                // Leaving insert mode steps back onto the last typed character
+                VimMode::Insert => {
+                    model.document_mut().end_undo_group();
+                    let offset = model
+                        .document()
+                        .cursor_to_offset(cursor.line, cursor.column);
+                    if cursor.column > 0 {
+                        model.set_cursor_from_position(offset - 1);
+                    } else {
+                        model.set_cursor_from_position(offset);
+                    }
+                }
+                VimMode::Visual => {
+                    let head = visual_head(model);
+                    model.set_cursor_from_position(head);
+                }
+                VimMode::Normal => {
+                    let offset = model
+                        .document()
+                        .cursor_to_offset(cursor.line, cursor.column);
+                    model.set_cursor_from_position(offset);
+                }
+            }
+            clamp_to_last_char(model);
+            Some(Cmd::Redraw)
+        }
+    }
+}
+
+fn apply(model: &mut AppModel, action: VimAction) -> Option<Cmd> {
+    match action {
+        VimAction::Move { motion, count } => {
+            if model.vim.mode == VimMode::Visual {
+                let (line, column) = model.document().offset_to_cursor(visual_head(model));
+                let (line, column) = motion_target(model.document(), line, column, motion, count);
+                let head = model.document().cursor_to_offset(line, column);
+                select_visual(model, head);
+            } else {
+                let cursor = *model.editor().active_cursor();
+                let (line, column) =
+                    motion_target(model.document(), cursor.line, cursor.column, motion, count);
+                let offset = model.document().cursor_to_offset(line, column);
+                model.editor_mut().collapse_to_primary();
+                model.set_cursor_from_position(offset);
+                // Up/Down keep aiming for the column they started from
+                if matches!(motion, Motion::Up | Motion::Down) {
+                    let desired = cursor.desired_visual_column(model.document());
+                    let column = model.document().column_at_visual(line, desired);
+                    let editor = model.editor_mut();
+                    editor.cursors[0].column = column;
+                    editor.cursors[0].desired_column = Some(desired);
+                    editor.clear_selection();
+                }
+                clamp_to_last_char(model);
+            }
+            model.ensure_cursor_visible();
+            Some(Cmd::Redraw)
+        }
+        VimAction::Operate {
+            operator,
+            motion,
+            count,
+        } => {
+            let cursor = *model.editor().active_cursor();
+            let doc = model.document();
+            let (line, column) = motion_target(doc, cursor.line, cursor.column, motion, count);
+            if motion.is_linewise() {
+                let (first, last) = (cursor.line.min(line), cursor.line.max(line));
+                return operate_lines(model, operator, first, last);
+            }
+            let from = doc.cursor_to_offset(cursor.line, cursor.column);
+            let mut to = doc.cursor_to_offset(line, column);
+            if motion == Motion::WordForward {
+                // `dw` on the last word of a line stops at the line end
+                if line > cursor.line && cursor.column < doc.line_length(cursor.line) {
+                    to = doc.cursor_to_offset(cursor.line, doc.line_length(cursor.line));
+                }
+                // `cw` leaves the whitespace after the word alone
+                if operator == Operator::Change && from < to {
+                    let buffer = &doc.buffer;
+                    if char_type(buffer.char(from)) != CharType::Whitespace {
+                        while to > from && char_type(buffer.char(to - 1)) == CharType::Whitespace {
+                            to -= 1;
+                        }
+                    }
+                }
+            }
+            operate(model, operator, from.min(to), from.max(to))
+        }
+        VimAction::OperateLines { operator, count } => {
+            let line = model.editor().active_cursor().line;
+            let last = (line + count - 1).min(model.document().line_count().saturating_sub(1));
+            operate_lines(model, operator, line, last)
+        }
+        VimAction::OperateSelection(operator) => {
+            let selection = *model.editor().primary_selection();
+            let doc = model.document();
+            let start = doc.cursor_to_offset(selection.start().line, selection.start().column);
+            let end = doc.cursor_to_offset(selection.end().line, selection.end().column);
+            model.vim.mode = VimMode::Normal;
+            operate(model, operator, start, end)
+        }
+        VimAction::Insert(at) => {
+            let cursor = *model.editor().active_cursor();
+            let doc = model.document();
+            let line_length = doc.line_length(cursor.line);
+            let column = match at {
+                InsertAt::Cursor => cursor.column,
+                InsertAt::AfterCursor => (cursor.column + 1).min(line_length),
+                InsertAt::LineStart => doc.first_non_whitespace_column(cursor.line),
+                InsertAt::LineEnd | InsertAt::LineBelow => line_length,
+                InsertAt::LineAbove => 0,
+            };
+            let offset = doc.cursor_to_offset(cursor.line, column);
+            model.editor_mut().collapse_to_primary();
+            model.set_cursor_from_position(offset);
+            model.vim.mode = VimMode::Insert;
+            match at {
+                InsertAt::LineBelow => update_document(model, DocumentMsg::InsertNewline),
+                InsertAt::LineAbove => {
+                    let cmd = update_document(model, DocumentMsg::InsertNewline);
+                    let offset = model.document().cursor_to_offset(cursor.line, 0);
+                    model.set_cursor_from_position(offset);
+                    model.ensure_cursor_visible();
+                    cmd
+                }
+                _ => {
+                    model.ensure_cursor_visible();
+                    Some(Cmd::Redraw)
+                }
+            }
+        }
+        VimAction::DeleteChar { count } => {
+            let cursor = *model.editor().active_cursor();
+            let doc = model.document();
+            let end_column = (cursor.column + count).min(doc.line_length(cursor.line));
+            let from = doc.cursor_to_offset(cursor.line, cursor.column);
+            let to = doc.cursor_to_offset(cursor.line, end_column);
+            operate(model, Operator::Delete, from, to)
+        }
+        VimAction::ToggleVisual => {
+            if model.vim.mode == VimMode::Visual {
+                let head = visual_head(model);
+                model.vim.mode = VimMode::Normal;
+                model.set_cursor_from_position(head);
+            } else {
+                let cursor = *model.editor().active_cursor();
+                let offset = model
+                    .document()
+                    .cursor_to_offset(cursor.line, cursor.column);
+                model.editor_mut().collapse_to_primary();
+                model.vim.mode = VimMode::Visual;
+                model.vim.visual_anchor = offset;
+                select_visual(model, offset);
+            }
+            Some(Cmd::Redraw)
+        }
+        VimAction::Paste => {
+            if model.vim.mode == VimMode::Visual {
+                // Replaces the selection
+                model.vim.mode = VimMode::Normal;
+            } else {
+                let cursor = *model.editor().active_cursor();
+                let line_length = model.document().line_length(cursor.line);
+                if cursor.column < line_length {
+                    let offset = model
+                        .document()
+                        .cursor_to_offset(cursor.line, cursor.column);
+                    model.editor_mut().collapse_to_primary();
+                    model.set_cursor_from_position(offset + 1);
+                }
+            }
+            update_document(model, DocumentMsg::Paste)
+        }
+        VimAction::Undo => {
+            let cmd = update_document(model, DocumentMsg::Undo);
+            clamp_to_last_char(model);
+            cmd
+        }
+    }
+}
+
+/// Apply `operator` to whole lines `first..=last`
+fn operate_lines(
+    model: &mut AppModel,
+    operator: Operator,
+    first: usize,
+    last: usize,
+) -> Option<Cmd> {
+    let doc = model.document();
+    let last_line = doc.line_count().saturating_sub(1);
+    let mut from = doc.cursor_to_offset(first, 0);
+    let to = if operator == Operator::Change {
+        // `cc` keeps an empty line to type on
+        doc.cursor_to_offset(last, doc.line_length(last))
+    } else if last < last_line {
+        doc.cursor_to_offset(last + 1, 0)
+    } else {
+        // No newline after the last line: take the one before the first
+        if first > 0 {
+            from = doc.cursor_to_offset(first - 1, doc.line_length(first - 1));
+        }
+        doc.buffer.len_chars()
+    };
+
+    let cursor = *model.editor().active_cursor();
+    let cmd = operate(model, operator, from, to);
+    match operator {
+        // `yy` leaves the cursor where it was
+        Operator::Yank => {
+            let offset = model
+                .document()
+                .cursor_to_offset(cursor.line, cursor.column);
+            model.set_cursor_from_position(offset);
+        }
+        Operator::Delete => {
+            let line = first.min(model.document().line_count().saturating_sub(1));
+            let column = model.document().first_non_whitespace_column(line);
+            let offset = model.document().cursor_to_offset(line, column);
+            model.set_cursor_from_position(offset);
+            clamp_to_last_char(model);
+        }
+        Operator::Change => {}
+    }
+    cmd
+}
+
+/// Apply `operator` to the characters `from..to` through the clipboard
+/// commands, so the text can be pasted with `p`
+fn operate(model: &mut AppModel, operator: Operator, from: usize, to: usize) -> Option<Cmd> {
+    let (start_line, start_column) = model.document().offset_to_cursor(from);
+    let (end_line, end_column) = model.document().offset_to_cursor(to);
+    let start = Position::new(start_line, start_column);
+
+    model.editor_mut().collapse_to_primary();
+    {
+        let editor = model.editor_mut();
+        editor.cursors[0].line = end_line;
+        editor.cursors[0].column = end_column;
+        editor.cursors[0].desired_column = None;
+        editor.selections[0] =
+            Selection::from_anchor_head(start, Position::new(end_line, end_column));
+    }
+
+    let cmd = match operator {
+        Operator::Yank => {
+            let cmd = if from < to {
+                update_document(model, DocumentMsg::Copy)
+            } else {
+                Some(Cmd::Redraw)
+            };
+            model.set_cursor_from_position(from);
+            cmd
+        }
+        Operator::Delete | Operator::Change if from < to => {
+            update_document(model, DocumentMsg::Cut)
+        }
+        Operator::Delete | Operator::Change => {
+            model.set_cursor_from_position(from);
+            Some(Cmd::Redraw)
+        }
+    };
+
+    if operator == Operator::Change {
+        model.vim.mode = VimMode::Insert;
+    } else {
+        model.vim.mode = VimMode::Normal;
+        clamp_to_last_char(model);
+    }
+    model.ensure_cursor_visible();
+    cmd
+}
+
+/// The character the cursor is on in visual mode. The selection includes
+/// it, so when the head is after the anchor the cursor sits one past it.
+fn visual_head(model: &AppModel) -> usize {
+    let cursor = model.editor().active_cursor();
+    let offset = model
+        .document()
+        .cursor_to_offset(cursor.line, cursor.column);
+    if offset > model.vim.visual_anchor {
+        offset - 1
+    } else {
+        offset
+    }
+}
+
+/// Select from the visual anchor to `head`, including both characters
+fn select_visual(model: &mut AppModel, head: usize) {
+    let len = model.document().buffer.len_chars();
+    let anchor = model.vim.visual_anchor.min(len);
+    let (anchor, head) = if head >= anchor {
+        (anchor, (head + 1).min(len))
+    } else {
+        ((anchor + 1).min(len), head)
+    };
+    let (anchor_line, anchor_column) = model.document().offset_to_cursor(anchor);
+    let (head_line, head_column) = model.document().offset_to_cursor(head);
+    let editor = model.editor_mut();
+    editor.cursors[0].line = head_line;
+    editor.cursors[0].column = head_column;
+    editor.cursors[0].desired_column = None;
+    editor.selections[0] = Selection::from_anchor_head(
+        Position::new(anchor_line, anchor_column),
+        Position::new(head_line, head_column),
+    );
+    model.ensure_cursor_visible();
+}
+
+/// In normal mode the cursor sits on a character, never past the line end
+fn clamp_to_last_char(model: &mut AppModel) {
+    if model.vim.mode != VimMode::Normal {
+        return;
+    }
+    let cursor = *model.editor().active_cursor();
+    let last_column = model.document().line_length(cursor.line).saturating_sub(1);
+    if cursor.column > last_column {
+        let editor = model.editor_mut();
+        editor.cursors[0].column = last_column;
+        editor.clear_selection();
+    }
+}
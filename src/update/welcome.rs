@@ -0,0 +1,208 @@
+//! Welcome tab update handlers
+
+use crate::commands::Cmd;
+use crate::messages::{AppMsg, LayoutMsg, WelcomeMsg};
+use crate::model::editor::{TabContent, ViewMode, WelcomeState};
+use crate::model::AppModel;
+use crate::welcome::{welcome_items, WelcomeAction};
+
+use super::app::update_app;
+use super::layout::update_layout;
+
+/// State of the focused tab, if it is the welcome tab
+fn focused_welcome_mut(model: &mut AppModel) -> Option<&mut WelcomeState> {
+    match &mut model.editor_area.focused_editor_mut()?.tab_content {
+        TabContent::Welcome(state) => Some(state),
+        _ => None,
+    }
+}
+
+/// Whether the focused tab is an empty, unsaved, unmodified text buffer
+fn is_focused_tab_pristine(model: &AppModel) -> bool {
+    let Some(editor) = model.editor_area.focused_editor() else {
+        return false;
+    };
+    if !matches!(editor.tab_content, TabContent::Text)
+        || !matches!(editor.view_mode, ViewMode::Text)
+    {
+        return false;
+    }
+    let doc = model.document();
+    doc.file_path.is_none() && !doc.is_modified && doc.buffer.len_chars() == 0
+}
+
+/// Show the welcome tab: reuse an open one, replace an empty untitled buffer,
+/// or open it in a new tab
+fn show_welcome(model: &mut AppModel) -> Option<Cmd> {
+    let welcome_tab_index = model.editor_area.focused_group().and_then(|group| {
+        group.tabs.iter().position(|tab| {
+            model
+                .editor_area
+                .editors
+                .get(&tab.editor_id)
+                .is_some_and(|e| matches!(e.tab_content, TabContent::Welcome(_)))
+        })
+    });
+    if let Some(index) = welcome_tab_index {
+        if let Some(group) = model.editor_area.focused_group_mut() {
+            group.active_tab_index = index;
+        }
+        return Some(Cmd::redraw_editor());
+    }
+
+    if !is_focused_tab_pristine(model) {
+        update_layout(model, LayoutMsg::NewTab);
+    }
+    let state = WelcomeState::new(welcome_items(&model.recent_files));
+    model.editor_mut().tab_content = TabContent::Welcome(state);
+    model.ui.focus_editor();
+    Some(Cmd::redraw_editor())
+}
+
+/// Run a welcome entry from the focused welcome tab
+fn activate(model: &mut AppModel, index: usize) -> Option<Cmd> {
+    let state = focused_welcome_mut(model)?;
+    let action = state.items.get(index)?.action.clone();
+    state.selected_index = index;
+
+    match action {
+        WelcomeAction::NewFile => {
+            // The welcome tab already wraps an empty untitled document
+            model.editor_mut().tab_content = TabContent::Text;
+            Some(Cmd::redraw_editor())
+        }
+        WelcomeAction::OpenFileDialog => update_app(model, AppMsg::OpenFileDialog),
+        WelcomeAction::OpenFolderDialog => update_app(model, AppMsg::OpenFolderDialog),
+        WelcomeAction::OpenRecentWorkspace(folder) => update_app(
+            model,
+            AppMsg::OpenFolderDialogResult {
+                folder: Some(folder),
+            },
+        ),
+        WelcomeAction::OpenRecentFile(path) => {
+            let welcome_tab_id = model
+                .editor_area
+                .focused_group()
+                .and_then(|group| group.active_tab())
+                .map(|tab| tab.id);
+            let open_cmd = update_layout(model, LayoutMsg::OpenFileInNewTab(path));
+
+            // The opened file takes the welcome tab's place
+            let close_cmd = welcome_tab_id
+                .filter(|_| !matches!(model.editor().tab_content, TabContent::Welcome(_)))
+                .and_then(|tab_id| update_layout(model, LayoutMsg::CloseTab(tab_id)));
+            Some(Cmd::Batch(open_cmd.into_iter().chain(close_cmd).collect()))
+        }
+    }
+}
+
+/// Handle welcome tab messages
+pub fn update_welcome(model: &mut AppModel, msg: WelcomeMsg) -> Option<Cmd> {
+    match msg {
+        WelcomeMsg::Show => show_welcome(model),
+        WelcomeMsg::ActivateSelected => {
+            let index = focused_welcome_mut(model)?.selected_index;
+            activate(model, index)
+        }
+        WelcomeMsg::Activate { index } => activate(model, index),
+        WelcomeMsg::SelectPrevious
+        | WelcomeMsg::SelectNext
+        | WelcomeMsg::SelectFirst
+        | WelcomeMsg::SelectLast => {
+            let state = focused_welcome_mut(model)?;
+            let last_index = state.items.len().saturating_sub(1);
+            state.selected_index = match msg {
+                WelcomeMsg::SelectPrevious => state.selected_index.saturating_sub(1),
+                WelcomeMsg::SelectNext => (state.selected_index + 1).min(last_index),
+                WelcomeMsg::SelectFirst => 0,
+                _ => last_index,
+            };
+            Some(Cmd::redraw_editor())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{Direction, DocumentMsg, EditorMsg, Msg};
+    use crate::update::update;
+
+    fn welcome_model() -> AppModel {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.recent_files.entries.clear();
+        update_welcome(&mut model, WelcomeMsg::Show);
+        model
+    }
+
+    fn selected_index(model: &AppModel) -> usize {
+        match &model.editor().tab_content {
+            TabContent::Welcome(state) => state.selected_index,
+            other => panic!("expected welcome tab, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn show_replaces_pristine_untitled_buffer() {
+        let model = welcome_model();
+        assert_eq!(model.editor_area.focused_group().unwrap().tabs.len(), 1);
+        assert_eq!(selected_index(&model), 0);
+    }
+
+    #[test]
+    fn show_opens_new_tab_when_buffer_has_content() {
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.document_mut().buffer = ropey::Rope::from("keep me");
+        update_welcome(&mut model, WelcomeMsg::Show);
+
+        let group = model.editor_area.focused_group().unwrap();
+        assert_eq!(group.tabs.len(), 2);
+        assert_eq!(group.active_tab_index, 1);
+        assert!(matches!(model.editor().tab_content, TabContent::Welcome(_)));
+    }
+
+    #[test]
+    fn arrow_keys_move_selection_and_enter_activates() {
+        let mut model = welcome_model();
+        let down = || Msg::Editor(EditorMsg::MoveCursor(Direction::Down));
+        update(&mut model, down());
+        update(&mut model, down());
+        update(&mut model, down());
+        assert_eq!(
+            selected_index(&model),
+            2,
+            "selection stops at the last entry"
+        );
+
+        // Typing does not edit the hidden buffer
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+        assert_eq!(model.document().buffer.len_chars(), 0);
+
+        update(&mut model, Msg::Editor(EditorMsg::MoveCursorDocumentStart));
+        assert_eq!(selected_index(&model), 0);
+
+        // Enter on "New File" turns the welcome tab into an editable buffer
+        update(&mut model, Msg::Document(DocumentMsg::InsertNewline));
+        assert!(matches!(model.editor().tab_content, TabContent::Text));
+        update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+        assert_eq!(model.document().buffer.to_string(), "x");
+    }
+
+    #[test]
+    fn recent_file_replaces_welcome_tab() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "hello").unwrap();
+
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.recent_files.entries.clear();
+        model.recent_files.add(path.clone(), None);
+        update_welcome(&mut model, WelcomeMsg::Show);
+        update_welcome(&mut model, WelcomeMsg::Activate { index: 3 });
+
+        let group = model.editor_area.focused_group().unwrap();
+        assert_eq!(group.tabs.len(), 1);
+        assert_eq!(model.document().file_path.as_deref(), Some(path.as_path()));
+        assert_eq!(model.document().buffer.to_string(), "hello");
+    }
+}
@@ -1,12 +1,22 @@
 //! Workspace message handlers (file tree sidebar)
 
+use std::path::PathBuf;
+
 use crate::commands::Cmd;
-use crate::messages::{LayoutMsg, WorkspaceMsg};
-use crate::model::AppModel;
-use crate::util::visible_tree_index_of;
+use crate::editable::TextEditMsg;
+use crate::messages::{DockMsg, LayoutMsg, WorkspaceMsg};
+use crate::model::{AppModel, NameEdit, NameEditKind};
+use crate::panel::PanelId;
+use crate::util::{same_file_path, visible_tree_index_of};
 use crate::view::geometry::status_bar_height;
 
+use super::dock::update_dock;
+use super::file_changes::read_changed_files;
+use super::file_moves::{
+    apply_rename, file_operation_completed, file_operation_reverted, reconcile_open_documents,
+};
 use super::layout::update_layout;
+use super::text_edit::apply_text_edit_msg;
 
 /// Handle workspace messages (file tree, sidebar)
 pub fn update_workspace(model: &mut AppModel, msg: WorkspaceMsg) -> Option<Cmd> {
@@ -57,6 +67,7 @@ pub fn update_workspace(model: &mut AppModel, msg: WorkspaceMsg) -> Option<Cmd>
         WorkspaceMsg::SelectItem(path) => {
             if let Some(workspace) = &mut model.workspace {
                 workspace.selected_item = Some(path);
+                workspace.name_edit = None;
             }
             Some(Cmd::redraw_editor())
         }
@@ -79,10 +90,12 @@ pub fn update_workspace(model: &mut AppModel, msg: WorkspaceMsg) -> Option<Cmd>
             Some(Cmd::redraw_editor())
         }
 
-        WorkspaceMsg::OpenFile { path, preview: _ } => {
-            // For now, always open as a permanent tab
-            // TODO: Implement preview tab behavior
-            update_layout(model, LayoutMsg::OpenFileInNewTab(path))
+        WorkspaceMsg::OpenFile { path, preview } => {
+            if preview {
+                update_layout(model, LayoutMsg::OpenFileInPreviewTab(path))
+            } else {
+                update_layout(model, LayoutMsg::OpenFileInNewTab(path))
+            }
         }
 
         WorkspaceMsg::OpenOrToggle => {
@@ -206,20 +219,229 @@ pub fn update_workspace(model: &mut AppModel, msg: WorkspaceMsg) -> Option<Cmd>
                         tracing::debug!("File tree fully refreshed");
                     }
                 } else {
-                    // Incremental update for specific changed paths
-                    if let Err(e) = workspace.update_paths(&paths) {
+                    // Incremental update for specific changed paths. Paths
+                    // outside the workspace come from watching open files
+                    let tree_paths: Vec<PathBuf> = paths
+                        .iter()
+                        .filter(|path| path.starts_with(&workspace.root))
+                        .cloned()
+                        .collect();
+                    if tree_paths.is_empty() {
+                        tracing::debug!("No file tree paths among {} changes", paths.len());
+                    } else if let Err(e) = workspace.update_paths(&tree_paths) {
                         tracing::warn!("Failed to update file tree: {}", e);
                     } else {
                         tracing::debug!(
                             "File tree incrementally updated for {} paths",
-                            paths.len()
+                            tree_paths.len()
                         );
                     }
                 }
             }
+            let mut cmds = vec![Cmd::redraw_editor()];
+            cmds.extend(reconcile_open_documents(model, &paths));
+            cmds.extend(read_changed_files(model, &paths));
+            Some(Cmd::batch(cmds))
+        }
+
+        WorkspaceMsg::PathRenamed { from, to } => {
+            if let Some(workspace) = &mut model.workspace {
+                if let Err(e) = workspace.update_paths(&[from.clone(), to.clone()]) {
+                    tracing::warn!("Failed to update file tree: {}", e);
+                }
+            }
+            apply_rename(model, &from, &to).or(Some(Cmd::redraw_editor()))
+        }
+
+        WorkspaceMsg::DeletePath(path) => {
+            if model
+                .workspace
+                .as_ref()
+                .is_some_and(|workspace| same_file_path(&workspace.root, &path))
+            {
+                model.ui.set_status("Can't delete the workspace folder");
+                return Some(Cmd::redraw_status_bar());
+            }
+            Some(Cmd::TrashPath { path })
+        }
+
+        WorkspaceMsg::RenamePath { from, to } => Some(Cmd::RenamePath { from, to }),
+
+        WorkspaceMsg::UndoFileOperation => match model.file_ops.pop() {
+            Some(operation) => Some(Cmd::RevertFileOperation(operation)),
+            None => {
+                model.ui.set_status("No file operation to undo");
+                Some(Cmd::redraw_status_bar())
+            }
+        },
+
+        WorkspaceMsg::CreateFile(path) => Some(Cmd::CreateFile { path }),
+
+        WorkspaceMsg::FileCreated(result) => match result {
+            Ok(path) => {
+                if let Some(workspace) = &mut model.workspace {
+                    if let Err(e) = workspace.update_paths(std::slice::from_ref(&path)) {
+                        tracing::warn!("Failed to update file tree: {}", e);
+                    }
+                }
+                update_layout(model, LayoutMsg::OpenFileInNewTab(path))
+            }
+            Err(e) => {
+                model.ui.set_status(format!("Could not create file: {}", e));
+                Some(Cmd::redraw_status_bar())
+            }
+        },
+
+        WorkspaceMsg::CreateFolder(path) => Some(Cmd::CreateFolder { path }),
+
+        WorkspaceMsg::FolderCreated(result) => match result {
+            Ok(path) => {
+                if let Some(workspace) = &mut model.workspace {
+                    if let Err(e) = workspace.update_paths(std::slice::from_ref(&path)) {
+                        tracing::warn!("Failed to update file tree: {}", e);
+                    }
+                    workspace.reveal_file(&path);
+                }
+                ensure_selection_visible(model);
+                Some(Cmd::redraw_editor())
+            }
+            Err(e) => {
+                model
+                    .ui
+                    .set_status(format!("Could not create folder: {}", e));
+                Some(Cmd::redraw_status_bar())
+            }
+        },
+
+        WorkspaceMsg::StartNewFile => {
+            start_name_edit(model, |parent| NameEditKind::NewFile { parent })
+        }
+
+        WorkspaceMsg::StartNewFolder => {
+            start_name_edit(model, |parent| NameEditKind::NewFolder { parent })
+        }
+
+        WorkspaceMsg::StartRename => {
+            let workspace = model.workspace.as_ref()?;
+            let path = workspace.selected_item.clone()?;
+            if same_file_path(&workspace.root, &path) {
+                model.ui.set_status("Can't rename the workspace folder");
+                return Some(Cmd::redraw_status_bar());
+            }
+            show_file_explorer(model);
+            if let Some(workspace) = &mut model.workspace {
+                workspace.name_edit = Some(NameEdit::new(NameEditKind::Rename { path }));
+            }
+            ensure_selection_visible(model);
+            Some(Cmd::redraw_editor())
+        }
+
+        WorkspaceMsg::NameInput(edit) => {
+            let input = &mut model.workspace.as_mut()?.name_edit.as_mut()?.input;
+            let copied = match edit {
+                TextEditMsg::Copy | TextEditMsg::Cut => Some(input.selected_text()),
+                _ => None,
+            }
+            .filter(|text| !text.is_empty());
+
+            let edit = match edit {
+                // Single-line input: drop pasted line breaks
+                TextEditMsg::Paste(text) | TextEditMsg::InsertText(text) => {
+                    TextEditMsg::InsertText(text.replace(['\n', '\r'], ""))
+                }
+                other => other,
+            };
+            let modified = apply_text_edit_msg(input, &edit);
+
+            match copied {
+                Some(text) => Some(Cmd::Batch(vec![
+                    Cmd::redraw_editor(),
+                    Cmd::CopyToClipboard(text),
+                ])),
+                None if modified => Some(Cmd::redraw_editor()),
+                None => None,
+            }
+        }
+
+        WorkspaceMsg::ConfirmNameEdit => {
+            let edit = model.workspace.as_mut()?.name_edit.take()?;
+            let Some(target) = edit.target() else {
+                return Some(Cmd::redraw_editor());
+            };
+            match edit.kind {
+                NameEditKind::NewFile { .. } => {
+                    update_workspace(model, WorkspaceMsg::CreateFile(target))
+                }
+                NameEditKind::NewFolder { .. } => {
+                    update_workspace(model, WorkspaceMsg::CreateFolder(target))
+                }
+                NameEditKind::Rename { path } => update_workspace(
+                    model,
+                    WorkspaceMsg::RenamePath {
+                        from: path,
+                        to: target,
+                    },
+                ),
+            }
+        }
+
+        WorkspaceMsg::CancelNameEdit => {
+            model.workspace.as_mut()?.name_edit = None;
             Some(Cmd::redraw_editor())
         }
+
+        WorkspaceMsg::FileOperationCompleted(result) => match result {
+            Ok(operation) => file_operation_completed(model, operation),
+            Err(e) => {
+                model.ui.set_status(format!("File operation failed: {}", e));
+                Some(Cmd::redraw_status_bar())
+            }
+        },
+
+        WorkspaceMsg::FileOperationReverted { operation, result } => match result {
+            Ok(()) => file_operation_reverted(model, operation),
+            Err(e) => {
+                // Keep it in the journal so the undo can be retried
+                model.file_ops.push(operation);
+                model.ui.set_status(format!("Undo failed: {}", e));
+                Some(Cmd::redraw_status_bar())
+            }
+        },
+    }
+}
+
+/// Show the file tree with an input for a new entry's name, in the selected
+/// folder, the selected file's folder, or the workspace root
+fn start_name_edit(
+    model: &mut AppModel,
+    kind: impl FnOnce(std::path::PathBuf) -> NameEditKind,
+) -> Option<Cmd> {
+    let workspace = model.workspace.as_ref()?;
+    let parent = match &workspace.selected_item {
+        Some(path) if path.is_dir() => path.clone(),
+        Some(path) => path
+            .parent()
+            .filter(|parent| parent.starts_with(&workspace.root))
+            .map_or_else(|| workspace.root.clone(), |parent| parent.to_path_buf()),
+        None => workspace.root.clone(),
+    };
+    show_file_explorer(model);
+    if let Some(workspace) = &mut model.workspace {
+        for folder in parent.ancestors() {
+            if !folder.starts_with(&workspace.root) {
+                break;
+            }
+            workspace.expand_folder(folder);
+        }
+        workspace.name_edit = Some(NameEdit::new(kind(parent)));
     }
+    ensure_selection_visible(model);
+    Some(Cmd::redraw_editor())
+}
+
+/// Open the file explorer and give it keyboard focus, so a name can be typed
+fn show_file_explorer(model: &mut AppModel) {
+    update_dock(model, DockMsg::ActivatePanel(PanelId::FILE_EXPLORER));
 }
 
 /// Number of rows that fit in the sidebar viewport.
@@ -426,6 +648,8 @@ mod tests {
             sidebar_visible: true,
             sidebar_width_logical: metrics.sidebar_default_width_logical,
             scroll_offset: 0,
+            config: Default::default(),
+            name_edit: None,
         }
     }
 
@@ -455,4 +679,78 @@ mod tests {
         update_workspace(&mut model, WorkspaceMsg::ToggleFolder(folder.clone()));
         assert!(!model.workspace.as_ref().unwrap().is_expanded(&folder));
     }
+
+    /// A model with a workspace holding `src/main.rs`, which is selected
+    fn named_entries_model() -> (tempfile::TempDir, AppModel, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.open_workspace(dir.path().to_path_buf());
+        let src = model.workspace.as_ref().unwrap().root.join("src");
+        update_workspace(&mut model, WorkspaceMsg::SelectItem(src.join("main.rs")));
+        (dir, model, src)
+    }
+
+    fn type_name(model: &mut AppModel, text: &str) {
+        update_workspace(
+            model,
+            WorkspaceMsg::NameInput(TextEditMsg::InsertText(text.to_string())),
+        );
+    }
+
+    #[test]
+    fn new_file_is_named_inline_in_the_selected_files_folder() {
+        let (_dir, mut model, src) = named_entries_model();
+
+        update_workspace(&mut model, WorkspaceMsg::StartNewFile);
+        let workspace = model.workspace.as_ref().unwrap();
+        assert_eq!(workspace.name_edit.as_ref().unwrap().parent(), src);
+        // Root, then src, then the input
+        assert_eq!(workspace.new_entry_row(), Some(2));
+        assert_eq!(model.ui.focus, crate::model::FocusTarget::Sidebar);
+
+        type_name(&mut model, "lib.rs");
+        let cmd = update_workspace(&mut model, WorkspaceMsg::ConfirmNameEdit);
+        assert!(matches!(cmd, Some(Cmd::CreateFile { ref path }) if *path == src.join("lib.rs")));
+        assert!(model.workspace.as_ref().unwrap().name_edit.is_none());
+    }
+
+    #[test]
+    fn rename_replaces_the_stem_and_keeps_the_extension() {
+        let (_dir, mut model, src) = named_entries_model();
+
+        update_workspace(&mut model, WorkspaceMsg::StartRename);
+        let edit = model
+            .workspace
+            .as_ref()
+            .unwrap()
+            .name_edit
+            .as_ref()
+            .unwrap();
+        assert_eq!(edit.input.selected_text(), "main");
+        assert_eq!(model.workspace.as_ref().unwrap().new_entry_row(), None);
+
+        type_name(&mut model, "app");
+        let cmd = update_workspace(&mut model, WorkspaceMsg::ConfirmNameEdit);
+        assert!(matches!(
+            cmd,
+            Some(Cmd::RenamePath { ref from, ref to })
+                if *from == src.join("main.rs") && *to == src.join("app.rs")
+        ));
+    }
+
+    #[test]
+    fn names_leaving_the_folder_are_ignored() {
+        let (_dir, mut model, _src) = named_entries_model();
+
+        update_workspace(&mut model, WorkspaceMsg::StartNewFolder);
+        type_name(&mut model, "../outside");
+        let cmd = update_workspace(&mut model, WorkspaceMsg::ConfirmNameEdit);
+        assert!(!matches!(cmd, Some(Cmd::CreateFolder { .. })));
+
+        update_workspace(&mut model, WorkspaceMsg::StartNewFolder);
+        update_workspace(&mut model, WorkspaceMsg::CancelNameEdit);
+        assert!(model.workspace.as_ref().unwrap().name_edit.is_none());
+    }
 }
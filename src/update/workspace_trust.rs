@@ -0,0 +1,139 @@
+//! Answering the workspace trust prompt and changing the decision later
+
+use std::path::Path;
+
+use crate::commands::Cmd;
+use crate::model::{AppModel, WorkspaceTrustChoice};
+use crate::workspace_trust::TrustDecision;
+
+use super::lsp;
+
+/// Act on the answer to the trust prompt for `root` (the modal is closed)
+pub(super) fn resolve_workspace_trust(
+    model: &mut AppModel,
+    root: &Path,
+    choice: WorkspaceTrustChoice,
+) -> Option<Cmd> {
+    let decision = match choice {
+        WorkspaceTrustChoice::Trust => TrustDecision::Trusted,
+        WorkspaceTrustChoice::Restrict => TrustDecision::Restricted,
+    };
+    decide(model, root, decision)
+}
+
+/// "Trust Workspace" / "Restrict Workspace" from the command palette
+pub(super) fn set_workspace_trust(model: &mut AppModel, decision: TrustDecision) -> Option<Cmd> {
+    let Some(root) = model.workspace.as_ref().map(|ws| ws.root.clone()) else {
+        model.ui.set_status("No workspace open");
+        return Some(Cmd::redraw_status_bar());
+    };
+    decide(model, &root, decision)
+}
+
+fn decide(model: &mut AppModel, root: &Path, decision: TrustDecision) -> Option<Cmd> {
+    model.workspace_trust.set(root, decision);
+    let name = crate::util::filename_for_display(root);
+
+    let mut cmds = vec![
+        Cmd::SaveWorkspaceTrust {
+            trust: model.workspace_trust.clone(),
+        },
+        Cmd::Redraw,
+    ];
+    match decision {
+        TrustDecision::Trusted => {
+            model.ui.set_status(format!("Trusted workspace {}", name));
+            // Start language servers for the file in front of the user now
+            // rather than after the next edit
+            if let Some(document_id) = model.editor_area.focused_document_id() {
+                cmds.extend(lsp::sync_document(model, document_id));
+            }
+        }
+        TrustDecision::Restricted => {
+            model.ui.set_status(format!(
+                "Restricted mode: {} can't run commands (run \"Trust Workspace\" to allow)",
+                name
+            ));
+            cmds.extend(lsp::close_all_documents(model));
+        }
+    }
+    Some(Cmd::Batch(cmds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{ModalMsg, UiMsg};
+    use crate::model::ModalState;
+    use crate::update::external_tools::run_tool;
+    use crate::update::update_ui;
+
+    fn open_workspace() -> (AppModel, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut model = AppModel::new(800, 600, 1.0, vec![]);
+        model.workspace_trust = Default::default();
+        model.open_workspace(dir.path().to_path_buf());
+        (model, dir)
+    }
+
+    fn saved_trust(cmd: &Option<Cmd>) -> bool {
+        matches!(cmd, Some(Cmd::Batch(cmds)) if cmds.iter().any(|c| matches!(c, Cmd::SaveWorkspaceTrust { .. })))
+    }
+
+    #[test]
+    fn new_workspace_asks_and_stays_restricted_until_trusted() {
+        let (mut model, dir) = open_workspace();
+        assert!(matches!(
+            model.ui.active_modal,
+            Some(ModalState::WorkspaceTrust(_))
+        ));
+        assert!(!model.workspace_trusted());
+
+        let cmd = update_ui(
+            &mut model,
+            UiMsg::Modal(ModalMsg::ResolveWorkspaceTrust(WorkspaceTrustChoice::Trust)),
+        );
+        assert!(saved_trust(&cmd));
+        assert!(model.ui.active_modal.is_none());
+        assert!(model.workspace_trusted());
+
+        // Decided workspaces aren't asked about again
+        model.open_workspace(dir.path().to_path_buf());
+        assert!(model.ui.active_modal.is_none());
+    }
+
+    #[test]
+    fn enter_on_the_prompt_keeps_restricted_mode() {
+        let (mut model, _dir) = open_workspace();
+        let cmd = update_ui(&mut model, UiMsg::Modal(ModalMsg::Confirm));
+        assert!(saved_trust(&cmd));
+        assert!(!model.workspace_trusted());
+        assert_eq!(
+            model
+                .workspace_trust
+                .decision(&model.workspace.as_ref().unwrap().root),
+            Some(TrustDecision::Restricted)
+        );
+    }
+
+    #[test]
+    fn external_tools_refuse_to_run_in_restricted_mode() {
+        let (mut model, _dir) = open_workspace();
+        model.ui.close_modal();
+        model.config.external_tools = vec![crate::external_tools::ExternalTool {
+            name: "Format".to_string(),
+            command: "fmt {file}".to_string(),
+        }];
+        model.document_mut().file_path = Some("/tmp/a.txt".into());
+
+        let cmd = run_tool(&mut model, 0);
+        assert!(matches!(cmd, Some(Cmd::RedrawAreas(_))));
+        assert!(model.ui.status_message.contains("restricted mode"));
+
+        set_workspace_trust(&mut model, TrustDecision::Trusted);
+        let cmd = run_tool(&mut model, 0);
+        assert!(
+            matches!(cmd, Some(Cmd::Batch(ref cmds)) if matches!(cmds[0], Cmd::RunExternalTool { .. }))
+        );
+    }
+}
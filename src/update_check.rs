@@ -0,0 +1,124 @@
+//! Update checker
+//!
+//! Asks the GitHub releases API for the latest release and compares its
+//! version with the running one. Only runs when `check_for_updates` is
+//! enabled (or on "Check for Updates"); the request is a plain GET made
+//! with the system `curl`, sending nothing but the user agent.
+
+use std::cmp::Ordering;
+use std::process::Command;
+
+/// Latest release of the editor
+pub const RELEASE_FEED_URL: &str = "https://api.github.com/repos/HelgeSverre/token/releases/latest";
+
+/// A published release
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// Version without a leading `v`, e.g. `0.5.0`
+    pub version: String,
+    /// Release notes page
+    pub url: String,
+}
+
+/// Fetch the latest release from `feed_url` (blocking)
+pub fn fetch_latest_release(feed_url: &str) -> Result<Release, String> {
+    let output = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--location",
+            "--max-time",
+            "10",
+            "--header",
+            "Accept: application/vnd.github+json",
+            "--user-agent",
+            concat!("token-editor/", env!("CARGO_PKG_VERSION")),
+            feed_url,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("Request failed ({})", output.status)
+        } else {
+            stderr
+        });
+    }
+    parse_release(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse a GitHub release JSON object (`tag_name`, `html_url`)
+pub fn parse_release(json: &str) -> Result<Release, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid release feed: {}", e))?;
+    let tag = value
+        .get("tag_name")
+        .and_then(|tag| tag.as_str())
+        .ok_or("Release feed has no tag_name")?;
+    let url = value
+        .get("html_url")
+        .and_then(|url| url.as_str())
+        .ok_or("Release feed has no html_url")?;
+    Ok(Release {
+        version: tag.trim_start_matches(['v', 'V']).to_string(),
+        url: url.to_string(),
+    })
+}
+
+/// Whether `candidate` is a newer version than `current`. Versions compare
+/// by their numeric `major.minor.patch` parts; a pre-release (`1.0.0-rc.1`)
+/// is older than the release itself. Unparseable versions are never newer.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    match (parse_version(candidate), parse_version(current)) {
+        (Some(candidate), Some(current)) => candidate.cmp(&current) == Ordering::Greater,
+        _ => false,
+    }
+}
+
+/// `([major, minor, patch], is_release)`, ordered so releases sort after
+/// their pre-releases
+fn parse_version(version: &str) -> Option<([u64; 3], bool)> {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    let version = version.split('+').next()?;
+    let (numbers, pre_release) = match version.split_once('-') {
+        Some((numbers, _)) => (numbers, true),
+        None => (version, false),
+    };
+    let mut parts = [0u64; 3];
+    for (index, part) in numbers.split('.').enumerate() {
+        *parts.get_mut(index)? = part.parse().ok()?;
+    }
+    Some((parts, !pre_release))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions() {
+        assert!(is_newer("0.5.0", "0.4.1"));
+        assert!(is_newer("v0.4.10", "0.4.9"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(is_newer("1.0.0", "1.0.0-rc.1"));
+        assert!(!is_newer("1.0.0-rc.1", "1.0.0"));
+        assert!(!is_newer("0.4.1", "0.4.1"));
+        assert!(!is_newer("0.3.9", "0.4.1"));
+        assert!(!is_newer("nightly", "0.4.1"));
+    }
+
+    #[test]
+    fn parses_github_release() {
+        let json = r#"{"tag_name": "v0.5.0", "html_url": "https://github.com/o/r/releases/tag/v0.5.0", "draft": false}"#;
+        assert_eq!(
+            parse_release(json).unwrap(),
+            Release {
+                version: "0.5.0".to_string(),
+                url: "https://github.com/o/r/releases/tag/v0.5.0".to_string(),
+            }
+        );
+        assert!(parse_release(r#"{"message": "Not Found"}"#).is_err());
+    }
+}
@@ -9,8 +9,12 @@ use std::fs::{self, File};
 use std::io::Read;
 use std::path::Path;
 
-/// Maximum file size in bytes (50 MB)
-pub const MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
+/// Maximum file size in bytes (512 MB)
+pub const MAX_FILE_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Text files larger than this (10 MB) open in large file mode: streamed in
+/// on a background thread, without syntax highlighting or undo grouping
+pub const LARGE_FILE_SIZE: u64 = 10 * 1024 * 1024;
 
 /// Errors that can occur when validating a file for opening
 #[derive(Debug, Clone)]
@@ -94,6 +98,11 @@ pub fn validate_file_for_opening(path: &Path) -> Result<(), FileOpenError> {
     Ok(())
 }
 
+/// Check if a file should open in large file mode (see [`LARGE_FILE_SIZE`])
+pub fn is_large_file(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.len() > LARGE_FILE_SIZE)
+}
+
 /// Check if a file path has a supported image extension
 pub fn is_supported_image(path: &Path) -> bool {
     let ext = path
@@ -1,7 +1,9 @@
 //! Utility modules
 
 pub mod file_validation;
+pub mod path;
 pub mod text;
+pub mod time;
 pub mod tree;
 
 // Re-export text utilities at the util level for backward compatibility
@@ -9,10 +11,16 @@ pub use text::{char_type, is_punctuation, is_word_boundary, CharType};
 
 // Re-export file validation utilities
 pub use file_validation::{
-    filename_for_display, is_likely_binary, is_supported_image, validate_file_for_opening,
-    FileOpenError, MAX_FILE_SIZE,
+    filename_for_display, is_large_file, is_likely_binary, is_supported_image,
+    validate_file_for_opening, FileOpenError, LARGE_FILE_SIZE, MAX_FILE_SIZE,
 };
 
+// Re-export calendar time
+pub use time::UtcDateTime;
+
+// Re-export path normalization utilities
+pub use path::{normalize_path, rebase_path, same_file_path};
+
 // Re-export tree traversal utilities
 pub use tree::{
     visible_tree_count, visible_tree_index_of, visible_tree_row_at_index,
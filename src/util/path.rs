@@ -0,0 +1,107 @@
+//! Path normalization for comparing file identities
+//!
+//! The same file can be reached through relative paths, `..` segments or
+//! symlinks. Comparing normalized paths keeps such aliases from being opened
+//! as separate documents.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Normalize a path so that aliases of the same file compare equal.
+///
+/// Existing paths are canonicalized (absolute, symlinks resolved). Paths that
+/// do not exist yet (e.g. a new file given on the command line) are made
+/// absolute, `.` and `..` are removed lexically, and the parent directory is
+/// canonicalized if it exists.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        match std::env::current_dir() {
+            Ok(cwd) => cwd.join(path),
+            Err(_) => path.to_path_buf(),
+        }
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+
+    match (normalized.parent(), normalized.file_name()) {
+        (Some(parent), Some(name)) => match parent.canonicalize() {
+            Ok(parent) => parent.join(name),
+            Err(_) => normalized,
+        },
+        _ => normalized,
+    }
+}
+
+/// Where `path` ends up when `from` is renamed to `to`: `to` itself if
+/// `path` is `from`, the same relative location under `to` if `path` is
+/// inside a moved `from` directory, and `None` if it is unaffected
+pub fn rebase_path(path: &Path, from: &Path, to: &Path) -> Option<PathBuf> {
+    let normalized = normalize_path(path);
+    let rest = normalized.strip_prefix(normalize_path(from)).ok()?;
+    if rest.as_os_str().is_empty() {
+        Some(to.to_path_buf())
+    } else {
+        Some(to.join(rest))
+    }
+}
+
+/// Whether two paths refer to the same file after normalization
+pub fn same_file_path(a: &Path, b: &Path) -> bool {
+    a == b || normalize_path(a) == normalize_path(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_segments_resolve_to_the_same_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let file = dir.path().join("src").join("main.rs");
+        std::fs::write(&file, "").unwrap();
+
+        let alias = dir.path().join("src/../src/./main.rs");
+        assert!(same_file_path(&file, &alias));
+        assert_eq!(normalize_path(&alias), file.canonicalize().unwrap());
+    }
+
+    #[test]
+    fn missing_files_are_normalized_lexically() {
+        let dir = tempfile::tempdir().unwrap();
+        let new_file = dir.path().join("sub/../new.txt");
+
+        assert_eq!(
+            normalize_path(&new_file),
+            dir.path().canonicalize().unwrap().join("new.txt")
+        );
+        assert!(!same_file_path(&new_file, &dir.path().join("other.txt")));
+    }
+
+    #[test]
+    fn rebase_follows_file_and_directory_moves() {
+        let from = Path::new("/project/src");
+        let to = Path::new("/project/lib");
+
+        assert_eq!(
+            rebase_path(Path::new("/project/src/a/b.rs"), from, to),
+            Some(PathBuf::from("/project/lib/a/b.rs"))
+        );
+        assert_eq!(rebase_path(from, from, to), Some(to.to_path_buf()));
+        assert_eq!(rebase_path(Path::new("/project/srcs/x.rs"), from, to), None);
+    }
+}
@@ -65,26 +65,134 @@ pub fn is_word_boundary(ch: char) -> bool {
     ch.is_whitespace() || is_punctuation(ch)
 }
 
+/// Columns `ch` takes up outside of tabs: 2 for wide characters (CJK,
+/// emoji), 0 for combining marks and other zero-width characters, 1 for
+/// everything else, control characters included. A byte order mark gets a
+/// column so that it can be shown.
+pub fn char_display_width(ch: char) -> usize {
+    match ch {
+        BYTE_ORDER_MARK => 1,
+        _ => unicode_width::UnicodeWidthChar::width(ch).unwrap_or(1),
+    }
+}
+
+/// U+FEFF, left at the start of a file saved with a byte order mark
+pub const BYTE_ORDER_MARK: char = '\u{FEFF}';
+
+/// Columns `text` takes up, counting tabs as one column
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_display_width).sum()
+}
+
+/// Push the part of `text` in visual columns `first..first + columns` to
+/// `out`. A wide character cut in half by the left edge leaves spaces in
+/// its place; one that doesn't fit at the right edge is left out.
+pub fn push_columns(out: &mut String, text: &str, first: usize, columns: usize) {
+    let last = first.saturating_add(columns);
+    let mut col = 0;
+    for ch in text.chars() {
+        let start = col;
+        col += char_display_width(ch);
+        if col > last {
+            break;
+        }
+        if col <= first {
+            continue;
+        }
+        if start < first {
+            out.extend(std::iter::repeat_n(' ', col - first));
+        } else {
+            out.push(ch);
+        }
+    }
+}
+
 /// Tab width for visual column calculations
 pub const TABULATOR_WIDTH: usize = 4;
 
+/// Extra columns after the widest cell of an elastic tabstop column
+pub const ELASTIC_TAB_PADDING: usize = 2;
+
+/// Lines scanned above and below the requested range for the rest of an
+/// elastic tabstop block
+const ELASTIC_BLOCK_SCAN_LIMIT: usize = 500;
+
+/// How the tabs on a line expand
+#[derive(Debug, Clone, Copy, Default)]
+pub enum TabLayout<'a> {
+    /// Tabs advance to the next multiple of [`TABULATOR_WIDTH`]
+    #[default]
+    Fixed,
+    /// Elastic tabstops: the cell ended by tab `i` is `widths[i]` columns
+    /// wide (see [`ElasticTabs`])
+    Elastic(&'a [usize]),
+}
+
+/// Visual width of each character of a line, in order
+#[derive(Debug, Clone)]
+pub struct TabExpander<'a> {
+    layout: TabLayout<'a>,
+    visual_col: usize,
+    tab_index: usize,
+    cell_start: usize,
+}
+
+impl<'a> TabExpander<'a> {
+    pub fn new(layout: TabLayout<'a>) -> Self {
+        Self {
+            layout,
+            visual_col: 0,
+            tab_index: 0,
+            cell_start: 0,
+        }
+    }
+
+    /// Visual column of the next character
+    pub fn visual_col(&self) -> usize {
+        self.visual_col
+    }
+
+    /// Width of `ch`, the next character of the line
+    pub fn advance(&mut self, ch: char) -> usize {
+        let width = if ch != '\t' {
+            char_display_width(ch)
+        } else {
+            let cell = match self.layout {
+                TabLayout::Elastic(widths) => widths.get(self.tab_index).copied(),
+                TabLayout::Fixed => None,
+            };
+            self.tab_index += 1;
+            match cell {
+                Some(cell) => cell
+                    .saturating_sub(self.visual_col - self.cell_start)
+                    .max(1),
+                None => TABULATOR_WIDTH - (self.visual_col % TABULATOR_WIDTH),
+            }
+        };
+        self.visual_col += width;
+        if ch == '\t' {
+            self.cell_start = self.visual_col;
+        }
+        width
+    }
+}
+
 /// Convert a visual column (screen position) to character column.
 /// Accounts for tab expansion when converting screen position to character index.
 pub fn visual_col_to_char_col(text: &str, visual_col: usize) -> usize {
-    let mut current_visual = 0;
+    visual_col_to_char_col_with(text, visual_col, TabLayout::Fixed)
+}
+
+/// [`visual_col_to_char_col`] with tabs expanded by `layout`
+pub fn visual_col_to_char_col_with(text: &str, visual_col: usize, layout: TabLayout) -> usize {
+    let mut expander = TabExpander::new(layout);
     let mut char_col = 0;
 
     for ch in text.chars() {
-        if current_visual >= visual_col {
+        if expander.visual_col() >= visual_col {
             return char_col;
         }
-
-        if ch == '\t' {
-            let tab_width = TABULATOR_WIDTH - (current_visual % TABULATOR_WIDTH);
-            current_visual += tab_width;
-        } else {
-            current_visual += 1;
-        }
+        expander.advance(ch);
         char_col += 1;
     }
 
@@ -94,20 +202,216 @@ pub fn visual_col_to_char_col(text: &str, visual_col: usize) -> usize {
 /// Convert a character column to visual column (screen position).
 /// Accounts for tab expansion when converting character index to screen position.
 pub fn char_col_to_visual_col(text: &str, char_col: usize) -> usize {
-    let mut visual_col = 0;
+    char_col_to_visual_col_with(text, char_col, TabLayout::Fixed)
+}
 
-    for (i, ch) in text.chars().enumerate() {
-        if i >= char_col {
-            break;
+/// [`char_col_to_visual_col`] with tabs expanded by `layout`
+pub fn char_col_to_visual_col_with(text: &str, char_col: usize, layout: TabLayout) -> usize {
+    let mut expander = TabExpander::new(layout);
+    for ch in text.chars().take(char_col) {
+        expander.advance(ch);
+    }
+    expander.visual_col()
+}
+
+/// Elastic tabstop cell widths for a range of lines.
+///
+/// With elastic tabstops, a tab ends a cell rather than advancing to a fixed
+/// stop. Cells at the same index on adjacent lines form a column block, and
+/// every cell in a block is as wide as the block's widest text plus
+/// [`ELASTIC_TAB_PADDING`] (at least [`TABULATOR_WIDTH`], so indentation
+/// keeps its width). A line without tabs ends every block. Only display
+/// geometry changes; the buffer keeps its tabs.
+#[derive(Debug, Clone, Default)]
+pub struct ElasticTabs {
+    first_line: usize,
+    /// Cell widths per line, from `first_line`
+    widths: Vec<Vec<usize>>,
+}
+
+impl ElasticTabs {
+    /// Widths for lines `first..=last`, taking in the rest of the blocks they
+    /// are part of. `line_text` returns a line without its line ending.
+    pub fn compute<S: AsRef<str>>(
+        first: usize,
+        last: usize,
+        line_count: usize,
+        line_text: impl Fn(usize) -> Option<S>,
+    ) -> Self {
+        if line_count == 0 {
+            return Self::default();
         }
+        let last = last.min(line_count - 1);
+        let has_tab = |line: usize| line_text(line).is_some_and(|t| t.as_ref().contains('\t'));
 
-        if ch == '\t' {
-            let tab_width = TABULATOR_WIDTH - (visual_col % TABULATOR_WIDTH);
-            visual_col += tab_width;
-        } else {
-            visual_col += 1;
+        let mut block_first = first.min(last);
+        while block_first > 0
+            && first - block_first < ELASTIC_BLOCK_SCAN_LIMIT
+            && has_tab(block_first - 1)
+        {
+            block_first -= 1;
+        }
+        let mut block_last = last;
+        while block_last + 1 < line_count
+            && block_last - last < ELASTIC_BLOCK_SCAN_LIMIT
+            && has_tab(block_last + 1)
+        {
+            block_last += 1;
+        }
+
+        // Text width of every tab-terminated cell
+        let cells: Vec<Vec<usize>> = (block_first..=block_last)
+            .map(|line| {
+                let Some(text) = line_text(line) else {
+                    return Vec::new();
+                };
+                let text = text.as_ref();
+                let mut cells: Vec<usize> = text.split('\t').map(display_width).collect();
+                // The text after the last tab isn't a cell
+                cells.pop();
+                cells
+            })
+            .collect();
+
+        let mut widths: Vec<Vec<usize>> = cells.iter().map(|c| vec![0; c.len()]).collect();
+        let max_cells = cells.iter().map(Vec::len).max().unwrap_or(0);
+        for column in 0..max_cells {
+            let mut row = 0;
+            while row < cells.len() {
+                if cells[row].len() <= column {
+                    row += 1;
+                    continue;
+                }
+                let start = row;
+                while row < cells.len() && cells[row].len() > column {
+                    row += 1;
+                }
+                let widest = cells[start..row]
+                    .iter()
+                    .map(|c| c[column])
+                    .max()
+                    .unwrap_or(0);
+                let width = (widest + ELASTIC_TAB_PADDING).max(TABULATOR_WIDTH);
+                for line_widths in &mut widths[start..row] {
+                    line_widths[column] = width;
+                }
+            }
         }
+
+        Self {
+            first_line: block_first,
+            widths,
+        }
+    }
+
+    /// How tabs expand on `line`; fixed stops outside the computed range
+    pub fn layout(&self, line: usize) -> TabLayout<'_> {
+        line.checked_sub(self.first_line)
+            .and_then(|index| self.widths.get(index))
+            .filter(|widths| !widths.is_empty())
+            .map_or(TabLayout::Fixed, |widths| TabLayout::Elastic(widths))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elastic(lines: &[&str]) -> ElasticTabs {
+        ElasticTabs::compute(0, lines.len() - 1, lines.len(), |line| lines.get(line))
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        let text = "a中b😀\tc";
+        assert_eq!(display_width("中文"), 4);
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("\u{FEFF}a"), 2);
+        assert_eq!(char_col_to_visual_col(text, 2), 3);
+        assert_eq!(char_col_to_visual_col(text, 4), 6);
+        // The tab after the emoji runs to the next stop at 8
+        assert_eq!(char_col_to_visual_col(text, 5), 8);
+
+        assert_eq!(visual_col_to_char_col(text, 1), 1);
+        assert_eq!(visual_col_to_char_col(text, 3), 2);
+        assert_eq!(visual_col_to_char_col(text, 8), 5);
+    }
+
+    #[test]
+    fn columns_cut_through_wide_characters_are_padded_or_dropped() {
+        let columns = |first, count| {
+            let mut out = String::new();
+            push_columns(&mut out, "a中b文", first, count);
+            out
+        };
+        assert_eq!(columns(0, 6), "a中b文");
+        assert_eq!(columns(2, 3), " b");
+        assert_eq!(columns(0, 5), "a中b");
+        assert_eq!(columns(1, 2), "中");
     }
 
-    visual_col
+    #[test]
+    fn elastic_tabs_measure_cells_in_columns() {
+        let lines = ["名前\tx", "ab\ty"];
+        let tabs = elastic(&lines);
+        assert_eq!(
+            char_col_to_visual_col_with(lines[0], 3, tabs.layout(0)),
+            char_col_to_visual_col_with(lines[1], 3, tabs.layout(1))
+        );
+    }
+
+    #[test]
+    fn elastic_tabs_align_columns_to_the_widest_cell() {
+        let lines = [
+            "id\tname\tx",
+            "1\tAda Lovelace\ty",
+            "no tabs",
+            "longer id\tz",
+        ];
+        let tabs = elastic(&lines);
+
+        // "name" and "Ada Lovelace" are one block; the line without tabs
+        // ends it, so "longer id" sizes its own
+        let name_col = char_col_to_visual_col_with(lines[0], 3, tabs.layout(0));
+        assert_eq!(name_col, 4);
+        assert_eq!(
+            char_col_to_visual_col_with(lines[1], 2, tabs.layout(1)),
+            name_col
+        );
+        let x0 = char_col_to_visual_col_with(lines[0], 8, tabs.layout(0));
+        let y1 = char_col_to_visual_col_with(lines[1], 15, tabs.layout(1));
+        assert_eq!(x0, y1);
+        assert_eq!(x0, 4 + "Ada Lovelace".len() + ELASTIC_TAB_PADDING);
+        assert_eq!(
+            char_col_to_visual_col_with(lines[3], 10, tabs.layout(3)),
+            "longer id".len() + ELASTIC_TAB_PADDING
+        );
+        assert!(matches!(tabs.layout(2), TabLayout::Fixed));
+
+        // Visual to char column is the inverse, inside the tab included
+        assert_eq!(
+            visual_col_to_char_col_with(lines[0], name_col, tabs.layout(0)),
+            3
+        );
+        assert_eq!(visual_col_to_char_col_with(lines[0], x0, tabs.layout(0)), 8);
+    }
+
+    #[test]
+    fn elastic_tabs_keep_indentation_width() {
+        let lines = ["\tfoo();", "\t\tbar();"];
+        let tabs = elastic(&lines);
+        assert_eq!(char_col_to_visual_col_with(lines[0], 1, tabs.layout(0)), 4);
+        assert_eq!(char_col_to_visual_col_with(lines[1], 2, tabs.layout(1)), 8);
+    }
+
+    #[test]
+    fn elastic_tabs_include_the_rest_of_the_block() {
+        let lines = ["a\tb", "wide cell\tc", "x\ty"];
+        // Only the last line is asked for, but it aligns with the lines above
+        let tabs = ElasticTabs::compute(2, 2, lines.len(), |line| lines.get(line));
+        assert_eq!(
+            char_col_to_visual_col_with(lines[2], 2, tabs.layout(2)),
+            "wide cell".len() + ELASTIC_TAB_PADDING
+        );
+    }
 }
@@ -0,0 +1,88 @@
+//! Calendar time without a date-time dependency
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A UTC date and time, to the second
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtcDateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl UtcDateTime {
+    /// The current time
+    pub fn now() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self::from_unix_secs(secs)
+    }
+
+    /// Convert seconds since the Unix epoch
+    pub fn from_unix_secs(secs: u64) -> Self {
+        let (days, secs_today) = ((secs / 86400) as i64, secs % 86400);
+
+        // Civil date from days since the epoch (Howard Hinnant's algorithm)
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let doe = days.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month: month as u32,
+            day: day as u32,
+            hour: (secs_today / 3600) as u32,
+            minute: ((secs_today % 3600) / 60) as u32,
+            second: (secs_today % 60) as u32,
+        }
+    }
+
+    /// `YYYY-MM-DDThh:mm:ss`
+    pub fn to_iso8601(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+
+    /// `YYYY-MM-DD`
+    pub fn to_date(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    /// `YYYYMMDD-hhmmss`, for use in file names
+    pub fn to_compact(&self) -> String {
+        format!(
+            "{:04}{:02}{:02}-{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_unix_seconds_to_calendar_time() {
+        assert_eq!(
+            UtcDateTime::from_unix_secs(0).to_iso8601(),
+            "1970-01-01T00:00:00"
+        );
+        // 2024-02-29 (leap day) 13:45:30 UTC
+        let leap_day = UtcDateTime::from_unix_secs(1_709_214_330);
+        assert_eq!(leap_day.to_iso8601(), "2024-02-29T13:45:30");
+        assert_eq!(leap_day.to_compact(), "20240229-134530");
+    }
+}
@@ -0,0 +1,148 @@
+//! Completion popup, drawn under (or above) the text being completed
+
+use crate::model::{AppModel, CompletionState};
+
+use super::frame::{Frame, TextPainter};
+use super::geometry::{self, GroupLayout};
+
+/// Popup width limits, in characters
+const MIN_WIDTH_CHARS: usize = 20;
+const MAX_WIDTH_CHARS: usize = 60;
+
+/// Draw the completion popup of the focused editor, if any
+pub fn render_completion_popup(frame: &mut Frame, painter: &mut TextPainter, model: &AppModel) {
+    let Some(state) = &model.ui.completion else {
+        return;
+    };
+    let Some(group) = model
+        .editor_area
+        .groups
+        .values()
+        .find(|group| group.active_editor_id() == Some(state.editor_id))
+    else {
+        return;
+    };
+    let Some((editor, document)) =
+        model
+            .editor_area
+            .editors
+            .get(&state.editor_id)
+            .and_then(|editor| {
+                let document = model.editor_area.documents.get(&editor.document_id?)?;
+                Some((editor, document))
+            })
+    else {
+        return;
+    };
+
+    let char_width = painter.char_width();
+    let line_height = painter.line_height();
+    let layout = GroupLayout::new(group, model, char_width);
+    let Some(row) = editor
+        .viewport_map(document)
+        .visible_row_for_doc_line(state.line)
+    else {
+        return;
+    };
+    let line_text = document.get_line_cow(state.line).unwrap_or_default();
+    let tabs = geometry::elastic_tabs(model, document, state.line, state.line);
+    let visual_col = super::line_layout::LineLayout::for_line(document, &tabs, state.line)
+        .caret_col(&line_text, state.start_column);
+    let anchor_x = geometry::column_to_pixel_x(
+        visual_col,
+        editor.viewport.left_column,
+        layout.text_start_x,
+        char_width,
+    );
+    let line_y = layout.content_y() + row * line_height;
+
+    let (width, height) = popup_size(state, char_width, line_height);
+    let content_bottom = layout.content_y() + layout.content_h();
+    // Below the line when it fits, otherwise above it
+    let y = if line_y + line_height + height <= content_bottom || line_y < height {
+        line_y + line_height
+    } else {
+        line_y - height
+    };
+    let group_right = layout.rect_x() + layout.rect_w();
+    let x = anchor_x
+        .saturating_sub(4)
+        .min(group_right.saturating_sub(width))
+        .max(layout.rect_x());
+
+    let overlay = &model.theme.overlay;
+    let fg = overlay.foreground.to_argb_u32();
+    let dim = overlay.foreground.with_alpha(128).to_argb_u32();
+    let border = overlay
+        .border
+        .map(|c| c.to_argb_u32())
+        .unwrap_or(0xFF444444);
+    frame.draw_bordered_rect(
+        x,
+        y,
+        width,
+        height,
+        overlay.background.to_argb_u32() | 0xFF00_0000,
+        border,
+    );
+
+    let text_chars = (width.saturating_sub(16) as f32 / char_width) as usize;
+    let visible = state
+        .items
+        .iter()
+        .enumerate()
+        .skip(state.scroll_offset)
+        .take(CompletionState::MAX_VISIBLE_ITEMS);
+    for (row, (index, item)) in visible.enumerate() {
+        let row_y = y + 2 + row * line_height;
+        if index == state.selected_index {
+            frame.fill_rect_px(
+                x + 1,
+                row_y,
+                width.saturating_sub(2),
+                line_height,
+                overlay.selection_background.to_argb_u32(),
+            );
+        }
+        let label: String = item.label.chars().take(text_chars).collect();
+        painter.draw(frame, x + 8, row_y, &label, fg);
+
+        let label_chars = label.chars().count();
+        let room = text_chars.saturating_sub(label_chars + 2);
+        if !item.detail.is_empty() && room > 1 {
+            let detail = truncate_start(&item.detail, room);
+            let detail_x = x + 8 + ((label_chars + 2) as f32 * char_width).round() as usize;
+            painter.draw(frame, detail_x, row_y, &detail, dim);
+        }
+    }
+}
+
+/// Popup size in pixels: wide enough for the longest label and detail,
+/// within limits
+fn popup_size(state: &CompletionState, char_width: f32, line_height: usize) -> (usize, usize) {
+    let widest = state
+        .items
+        .iter()
+        .map(|item| {
+            let detail = item.detail.chars().count();
+            item.label.chars().count() + if detail > 0 { detail + 2 } else { 0 }
+        })
+        .max()
+        .unwrap_or(0)
+        .clamp(MIN_WIDTH_CHARS, MAX_WIDTH_CHARS);
+    let rows = state.items.len().min(CompletionState::MAX_VISIBLE_ITEMS);
+    (
+        (widest as f32 * char_width).round() as usize + 16,
+        rows * line_height + 4,
+    )
+}
+
+/// The last `max_chars` characters of `text`, with an ellipsis when cut
+fn truncate_start(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(count + 1 - max_chars).collect();
+    format!("…{}", tail)
+}
@@ -1,9 +1,12 @@
 //! Non-text editor tab rendering.
 
-use crate::model::editor::BinaryPlaceholderState;
-use crate::model::AppModel;
+use crate::diff::{DiffState, RowKind};
+use crate::model::editor::{BinaryPlaceholderState, WelcomeState};
+use crate::model::{AppModel, Rect};
+use crate::util::text::push_columns;
 
 use super::frame::{Frame, TextPainter};
+use super::geometry::{char_col_to_visual_col, expand_tabs_for_display};
 use super::{button, geometry};
 
 /// Render an image viewer tab.
@@ -25,6 +28,139 @@ pub fn render_image_tab(
     );
 }
 
+/// Render a diff view: a title strip over two halves, the base on the left
+/// and the document on the right, scrolled together row by row.
+///
+/// Changed rows are tinted (removed lines on the left, added lines on the
+/// right) with the differing characters tinted more strongly; a row a half
+/// has no line for is filled like the gutter.
+pub fn render_diff_view(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &DiffState,
+    layout: &geometry::GroupLayout,
+) {
+    let content_rect = layout.content_rect;
+    let theme = &model.theme;
+    let fg = theme.editor.foreground.to_argb_u32();
+    let dim_fg = theme.gutter.foreground.to_argb_u32();
+    let gutter_bg = theme.gutter.background.to_argb_u32();
+    let border = theme.gutter.border_color.to_argb_u32();
+    let marker = theme.editor.cursor_color.to_argb_u32();
+    frame.fill_rect(content_rect, theme.editor.background.to_argb_u32());
+    frame.set_clip(content_rect);
+
+    let char_width = painter.char_width();
+    let line_height = painter.line_height().max(1);
+    let padding = model.metrics.padding_medium;
+    let (x, y) = (content_rect.x as usize, content_rect.y as usize);
+    let (width, height) = (content_rect.width as usize, content_rect.height as usize);
+    let half_width = width / 2;
+
+    frame.fill_rect_px(x, y, width, line_height, gutter_bg);
+    painter.draw(frame, x + padding, y, &state.base_title, dim_fg);
+    painter.draw(frame, x + half_width + padding, y, &state.title, dim_fg);
+
+    // One gutter width for both halves, fitting the longest line number
+    let max_line = state
+        .rows
+        .iter()
+        .flat_map(|row| [&row.left, &row.right])
+        .flatten()
+        .map(|line| line.line + 1)
+        .max()
+        .unwrap_or(1);
+    let gutter_width =
+        (max_line.to_string().len() as f32 * char_width).ceil() as usize + padding * 2;
+
+    // Rows of the change last moved to get a marker
+    let starts = state.change_starts();
+    let current = state.current_change.and_then(|index| {
+        let start = *starts.get(index)?;
+        let len = state.rows[start..]
+            .iter()
+            .take_while(|row| row.kind != RowKind::Unchanged)
+            .count();
+        Some(start..start + len)
+    });
+
+    let halves = [
+        (x, half_width, theme.overlay.error),
+        (x + half_width, width - half_width, theme.overlay.highlight),
+    ];
+    let rows_y = y + line_height;
+    let visible_rows = height.saturating_sub(line_height).div_ceil(line_height);
+    for (offset, row) in state
+        .rows
+        .iter()
+        .skip(state.top_row)
+        .take(visible_rows)
+        .enumerate()
+    {
+        let row_y = rows_y + offset * line_height;
+        for ((half_x, half_w, color), line) in halves.iter().zip([&row.left, &row.right]) {
+            let Some(line) = line else {
+                frame.fill_rect_px(*half_x, row_y, *half_w, line_height, gutter_bg);
+                continue;
+            };
+            let text_x = half_x + gutter_width;
+            if row.kind != RowKind::Unchanged {
+                frame.blend_rect_px(
+                    *half_x,
+                    row_y,
+                    *half_w,
+                    line_height,
+                    color.with_alpha(0x2A).to_argb_u32(),
+                );
+            }
+
+            let number = (line.line + 1).to_string();
+            let number_x = (text_x - padding)
+                .saturating_sub((number.len() as f32 * char_width).ceil() as usize);
+            painter.draw(frame, number_x, row_y, &number, dim_fg);
+
+            frame.set_clip(Rect::new(
+                text_x as f32,
+                row_y as f32,
+                half_w.saturating_sub(gutter_width) as f32,
+                line_height as f32,
+            ));
+            let col_x = |col: usize| {
+                let visual = char_col_to_visual_col(&line.text, col);
+                text_x + (visual.saturating_sub(state.left_column) as f32 * char_width) as usize
+            };
+            if let Some(changed) = &line.changed {
+                let (start, end) = (col_x(changed.start), col_x(changed.end));
+                frame.blend_rect_px(
+                    start,
+                    row_y,
+                    end - start,
+                    line_height,
+                    color.with_alpha(0x66).to_argb_u32(),
+                );
+            }
+            let text = expand_tabs_for_display(&line.text);
+            let mut shown = String::new();
+            push_columns(&mut shown, &text, state.left_column, usize::MAX);
+            painter.draw(frame, text_x, row_y, &shown, fg);
+            frame.set_clip(content_rect);
+        }
+
+        if current
+            .as_ref()
+            .is_some_and(|rows| rows.contains(&(state.top_row + offset)))
+        {
+            let bar_width = model.metrics.padding_small;
+            frame.fill_rect_px(x, row_y, bar_width, line_height, marker);
+            frame.fill_rect_px(x + half_width + 1, row_y, bar_width, line_height, marker);
+        }
+    }
+
+    frame.fill_rect_px(x + half_width, y, 1, height, border);
+    frame.clear_clip();
+}
+
 /// Render a binary file placeholder tab.
 ///
 /// `group_id` identifies which editor group this placeholder belongs to
@@ -92,6 +228,89 @@ pub fn render_binary_placeholder(
     );
 }
 
+/// Render the welcome tab: title, entry sections, and keybinding cheatsheet.
+///
+/// The selected entry is highlighted; `focused` dims the highlight when the
+/// group does not have focus.
+pub fn render_welcome(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &WelcomeState,
+    layout: &geometry::GroupLayout,
+    focused: bool,
+) {
+    let content_rect = layout.content_rect;
+    let theme = &model.theme;
+    let bg = theme.editor.background.to_argb_u32();
+    let fg = theme.editor.foreground.to_argb_u32();
+    let dim_fg = theme.gutter.foreground.to_argb_u32();
+    let selected_bg = if focused {
+        theme.editor.selection_background.to_argb_u32()
+    } else {
+        theme.editor.current_line_background.to_argb_u32()
+    };
+    frame.fill_rect(content_rect, bg);
+    frame.set_clip(content_rect);
+
+    let char_width = painter.char_width();
+    let line_height = painter.line_height();
+    let welcome = geometry::WelcomeLayout::new(
+        content_rect,
+        &state.items,
+        line_height,
+        char_width,
+        &model.metrics,
+    );
+    let padding = model.metrics.padding_medium;
+    let text_x = welcome.column_x + padding;
+    let text_offset = (welcome.row_height - line_height) / 2;
+    let right_edge = welcome.column_x + welcome.column_width.saturating_sub(padding);
+    let text_width = |text: &str| (text.chars().count() as f32 * char_width).ceil() as usize;
+
+    painter.draw(frame, text_x, welcome.title_y, geometry::WELCOME_TITLE, fg);
+    for (title, y) in &welcome.headings {
+        painter.draw(frame, text_x, y + text_offset, title, dim_fg);
+    }
+
+    for (index, (item, rect)) in state.items.iter().zip(&welcome.item_rects).enumerate() {
+        if index == state.selected_index {
+            frame.fill_rect(*rect, selected_bg);
+        }
+        let y = rect.y as usize + text_offset;
+        let label_x = text_x + padding;
+        painter.draw(frame, label_x, y, &item.label, fg);
+
+        // Right-align the detail, or skip it if it would overlap the label
+        let detail_x = right_edge.saturating_sub(text_width(&item.detail));
+        if !item.detail.is_empty() && detail_x > label_x + text_width(&item.label) + padding {
+            painter.draw(frame, detail_x, y, &item.detail, dim_fg);
+        }
+    }
+
+    painter.draw(
+        frame,
+        text_x,
+        welcome.cheatsheet_y + text_offset,
+        geometry::WELCOME_CHEATSHEET_TITLE,
+        dim_fg,
+    );
+    let mut y = welcome.cheatsheet_y + welcome.row_height + text_offset;
+    for (label, keybinding) in crate::welcome::cheatsheet() {
+        painter.draw(frame, text_x + padding, y, label, fg);
+        painter.draw(
+            frame,
+            right_edge.saturating_sub(text_width(keybinding)),
+            y,
+            keybinding,
+            dim_fg,
+        );
+        y += welcome.row_height;
+    }
+
+    frame.clear_clip();
+}
+
 fn format_file_size(bytes: u64) -> String {
     if bytes < 1024 {
         format!("{} B", bytes)
@@ -106,7 +325,7 @@ fn format_file_size(bytes: u64) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{format_file_size, render_binary_placeholder, render_image_tab};
+    use super::{format_file_size, render_binary_placeholder, render_diff_view, render_image_tab};
     use crate::commands::Cmd;
     use crate::image::ImageState;
     use crate::messages::{ImageMsg, Msg};
@@ -333,4 +552,68 @@ mod tests {
             "hovering this group's own button must change its rendered pixels"
         );
     }
+
+    #[test]
+    fn diff_view_tints_both_halves_of_changed_rows_only() {
+        use crate::diff::{DiffBase, DiffState};
+        use crate::view::{GlyphCache, TextPainter};
+        use fontdue::{Font, FontSettings};
+
+        let font = Font::from_bytes(
+            include_bytes!("../../assets/JetBrainsMono.ttf") as &[u8],
+            FontSettings::default(),
+        )
+        .expect("test font should load");
+        let font_size = 14.0;
+        let line_metrics = font
+            .horizontal_line_metrics(font_size)
+            .expect("font should expose horizontal metrics");
+        let (metrics, _) = font.rasterize('M', font_size);
+        let char_width = metrics.advance_width;
+        let line_height = line_metrics.new_line_size.ceil() as usize;
+
+        let mut model = AppModel::new(200, 120, 1.0, vec![]);
+        let group_id = model.editor_area.focused_group_id;
+        let tab_bar_height = model.metrics.tab_bar_height as f32;
+        model.editor_area.groups.get_mut(&group_id).unwrap().rect =
+            Rect::new(0.0, 0.0, 200.0, 120.0 + tab_bar_height);
+        let diff = DiffState::new(
+            DiffBase::Saved,
+            "Saved".to_string(),
+            "notes.txt".to_string(),
+            "one\ntwo\n".to_string(),
+            "ONE\ntwo\n",
+            0,
+        );
+
+        let height = (120.0 + tab_bar_height) as usize;
+        let mut buffer = vec![0; 200 * height];
+        let mut frame = Frame::new(&mut buffer, 200, height);
+        let mut glyph_cache = GlyphCache::default();
+        let mut painter = TextPainter::new(
+            &font,
+            &mut glyph_cache,
+            font_size,
+            line_metrics.ascent,
+            char_width,
+            line_height,
+        );
+        let group = model.editor_area.groups.get(&group_id).unwrap();
+        let layout = GroupLayout::new(group, &model, char_width);
+        render_diff_view(&mut frame, &mut painter, &model, &diff, &layout);
+
+        // Sample past the end of the text, at the right edge of each half;
+        // rows start below the title strip
+        let content = layout.content_rect;
+        let background = model.theme.editor.background.to_argb_u32();
+        let pixel = |x: usize, row: usize| {
+            let y = content.y as usize + (row + 1) * line_height + 1;
+            buffer[y * 200 + content.x as usize + x]
+        };
+        let (left, right) = (content.width as usize / 2 - 2, content.width as usize - 2);
+        assert_ne!(pixel(left, 0), background);
+        assert_ne!(pixel(right, 0), background);
+        assert_eq!(pixel(left, 1), background);
+        assert_eq!(pixel(right, 1), background);
+    }
 }
@@ -3,17 +3,30 @@
 #[cfg(debug_assertions)]
 use std::time::{Duration, Instant};
 
+use crate::config::RenderWhitespace;
+use crate::git::HunkKind;
 use crate::model::editor::Selection;
-use crate::model::{AppModel, Document, EditorState, TextViewportMap};
+use crate::model::folding::can_fold_at;
+use crate::model::{
+    AppModel, DecorationKind, Document, EditorState, LineStyle, ModalState, TextViewportMap,
+};
 use crate::perf::{PerfStage, PerfStats};
+use crate::util::text::{display_width, push_columns, BYTE_ORDER_MARK};
 
-use super::frame::{Frame, TextPainter};
-use super::geometry::{self, char_col_to_visual_col, column_to_pixel_x, expand_tabs_for_display};
+use super::frame::{blend_colors, Frame, GlyphStyle, TextPainter};
+use super::geometry::{self, column_to_pixel_x, ElasticTabs, TabExpander};
+use super::indent_guides::IndentGuides;
+use super::line_layout::LineLayout;
 
 /// Cursor width in pixels.
 const CURSOR_WIDTH: usize = 2;
 /// Cursor inset from top of line in pixels.
 const CURSOR_INSET: usize = 1;
+/// Columns taken by the chip after a folded line, including its leading gap.
+const FOLD_MARKER_WIDTH: usize = 4;
+/// Columns between the end of a line and its end-of-line annotations, and
+/// between those annotations.
+const ANNOTATION_GAP: usize = 2;
 
 /// Shared theme colors for text editor rendering.
 #[derive(Debug, Clone, Copy)]
@@ -22,13 +35,24 @@ struct EditorPalette {
     current_line: u32,
     selection: u32,
     bracket_match: u32,
+    search_match: u32,
+    search_match_current: u32,
+    search_scope: u32,
+    word_highlight: u32,
     text: u32,
+    whitespace: u32,
+    invisible_character: u32,
+    indent_guide: u32,
+    indent_guide_active: u32,
+    ruler: u32,
     gutter_background: u32,
     gutter_border: u32,
     line_number: u32,
     active_line_number: u32,
     primary_cursor: u32,
     secondary_cursor: u32,
+    /// Line decoration colors: highlight, success, warning, error
+    decorations: [u32; 4],
 }
 
 impl EditorPalette {
@@ -38,20 +62,66 @@ impl EditorPalette {
             current_line: model.theme.editor.current_line_background.to_argb_u32(),
             selection: model.theme.editor.selection_background.to_argb_u32(),
             bracket_match: model.theme.editor.bracket_match_background.to_argb_u32(),
+            search_match: model.theme.editor.search_match_background.to_argb_u32(),
+            search_match_current: model
+                .theme
+                .editor
+                .search_match_current_background
+                .to_argb_u32(),
+            search_scope: model
+                .theme
+                .editor
+                .selection_background
+                .with_alpha(48)
+                .to_argb_u32(),
+            word_highlight: model.theme.editor.word_highlight_background.to_argb_u32(),
             text: model.theme.editor.foreground.to_argb_u32(),
+            whitespace: model.theme.editor.whitespace_foreground.to_argb_u32(),
+            invisible_character: model
+                .theme
+                .editor
+                .invisible_character_foreground
+                .to_argb_u32(),
+            indent_guide: model.theme.editor.indent_guide_foreground.to_argb_u32(),
+            indent_guide_active: model
+                .theme
+                .editor
+                .indent_guide_active_foreground
+                .to_argb_u32(),
+            ruler: model.theme.editor.ruler_foreground.to_argb_u32(),
             gutter_background: model.theme.gutter.background.to_argb_u32(),
             gutter_border: model.theme.gutter.border_color.to_argb_u32(),
             line_number: model.theme.gutter.foreground.to_argb_u32(),
             active_line_number: model.theme.gutter.foreground_active.to_argb_u32(),
             primary_cursor: model.theme.editor.cursor_color.to_argb_u32(),
             secondary_cursor: model.theme.editor.secondary_cursor_color.to_argb_u32(),
+            decorations: [
+                model.theme.editor.cursor_color.to_argb_u32(),
+                model.theme.overlay.highlight.to_argb_u32(),
+                model.theme.overlay.warning.to_argb_u32(),
+                model.theme.overlay.error.to_argb_u32(),
+            ],
         }
     }
+
+    fn decoration(&self, kind: DecorationKind) -> u32 {
+        self.decorations[match kind {
+            DecorationKind::Highlight => 0,
+            DecorationKind::Success => 1,
+            DecorationKind::Warning => 2,
+            DecorationKind::Error => 3,
+        }]
+    }
+
+    /// Translucent line background for a decoration
+    fn decoration_tint(&self, kind: DecorationKind) -> u32 {
+        (self.decoration(kind) & 0x00FF_FFFF) | 0x2A00_0000
+    }
 }
 
 /// Shared layout-derived values for editor text rendering.
-struct EditorRenderContext {
-    viewport: TextViewportMap,
+struct EditorRenderContext<'a> {
+    viewport: TextViewportMap<'a>,
     char_width: f32,
     line_height: usize,
     rect_x: usize,
@@ -63,17 +133,20 @@ struct EditorRenderContext {
     text_start_x: usize,
     visible_lines: usize,
     visible_columns: usize,
+    document: &'a Document,
+    /// Elastic tabstop widths of the visible lines (fixed stops when off)
+    tabs: ElasticTabs,
 }
 
-impl EditorRenderContext {
+impl<'a> EditorRenderContext<'a> {
     fn new(
         layout: &geometry::GroupLayout,
-        editor: &EditorState,
-        document: &Document,
+        editor: &'a EditorState,
+        document: &'a Document,
         char_width: f32,
         line_height: usize,
     ) -> Self {
-        let viewport = TextViewportMap::new(&editor.viewport, document.line_count());
+        let viewport = editor.viewport_map(document);
         let visible_lines = layout.visible_lines(line_height);
         let visible_columns = layout.visible_columns(char_width);
 
@@ -90,6 +163,8 @@ impl EditorRenderContext {
             text_start_x: layout.text_start_x,
             visible_lines,
             visible_columns,
+            document,
+            tabs: ElasticTabs::default(),
         }
     }
 
@@ -100,6 +175,12 @@ impl EditorRenderContext {
             .map(|visible_row| self.content_y + visible_row * self.line_height)
     }
 
+    /// Tab stops and annotations of `doc_line`
+    #[inline]
+    fn line_layout(&self, doc_line: usize) -> LineLayout<'_> {
+        LineLayout::for_line(self.document, &self.tabs, doc_line)
+    }
+
     #[inline]
     fn text_right_x(&self) -> usize {
         self.rect_x + self.rect_w
@@ -141,6 +222,10 @@ struct EditorTextBuffers {
     adjusted_tokens: Vec<crate::syntax::HighlightToken>,
     display_text: String,
     selection_spans: Vec<(usize, usize)>,
+    search_match_spans: Vec<(usize, usize)>,
+    word_highlight_spans: Vec<(usize, usize)>,
+    current_search_match_span: Option<(usize, usize)>,
+    search_scope_span: Option<(usize, usize)>,
     bracket_visual_cols: [Option<usize>; 2],
 }
 
@@ -150,6 +235,10 @@ impl EditorTextBuffers {
             adjusted_tokens: Vec::with_capacity(32),
             display_text: String::with_capacity(max_chars + 16),
             selection_spans: Vec::with_capacity(8),
+            search_match_spans: Vec::with_capacity(8),
+            word_highlight_spans: Vec::with_capacity(8),
+            current_search_match_span: None,
+            search_scope_span: None,
             bracket_visual_cols: [None, None],
         }
     }
@@ -164,6 +253,8 @@ struct VisibleTextLine {
     y: usize,
     height: usize,
     is_active_line: bool,
+    /// Tint and gutter icon from the document's line decorations
+    decoration: LineStyle,
 }
 
 /// Stateful text editor renderer.
@@ -175,9 +266,14 @@ struct TextEditorRenderer<'a> {
     model: &'a AppModel,
     editor: &'a EditorState,
     document: &'a Document,
-    ctx: EditorRenderContext,
+    ctx: EditorRenderContext<'a>,
     palette: EditorPalette,
     text_buffers: EditorTextBuffers,
+    /// Hybrid Markdown view: first visible line and, from there on, which
+    /// lines are inside fenced code blocks (`None` when the view is off)
+    markdown_fences: Option<(usize, Vec<bool>)>,
+    /// Indent guides of the visible lines (`None` when they are off)
+    indent_guides: Option<IndentGuides>,
 }
 
 impl<'a> TextEditorRenderer<'a> {
@@ -189,9 +285,32 @@ impl<'a> TextEditorRenderer<'a> {
         char_width: f32,
         line_height: usize,
     ) -> Self {
-        let ctx = EditorRenderContext::new(layout, editor, document, char_width, line_height);
+        let mut ctx = EditorRenderContext::new(layout, editor, document, char_width, line_height);
+        ctx.tabs = geometry::elastic_tabs(
+            model,
+            document,
+            ctx.viewport.top_line(),
+            ctx.viewport.end_line(),
+        );
         let palette = EditorPalette::from_model(model);
         let text_buffers = EditorTextBuffers::new(ctx.visible_columns);
+        let markdown_fences = (model.config.markdown_hybrid
+            && document.language == crate::syntax::LanguageId::Markdown)
+            .then(|| {
+                let first = ctx.viewport.top_line();
+                let count = ctx.viewport.end_line().saturating_sub(first) + 1;
+                let lines = (0..).map_while(|i| document.get_line_cow(i));
+                (first, crate::markdown::fenced_lines(lines, first, count))
+            });
+        let indent_guides = model.config.show_indent_guides.then(|| {
+            IndentGuides::compute(
+                document,
+                &ctx.tabs,
+                ctx.viewport.top_line(),
+                ctx.viewport.end_line(),
+                editor.active_cursor().line,
+            )
+        });
 
         Self {
             model,
@@ -200,6 +319,8 @@ impl<'a> TextEditorRenderer<'a> {
             ctx,
             palette,
             text_buffers,
+            markdown_fences,
+            indent_guides,
         }
     }
 
@@ -243,8 +364,9 @@ impl<'a> TextEditorRenderer<'a> {
             line_len
         };
 
-        let visual_start_col = char_col_to_visual_col(line_text, start_col);
-        let visual_end_col = char_col_to_visual_col(line_text, end_col);
+        let layout = ctx.line_layout(doc_line);
+        let visual_start_col = layout.visual_col(line_text, start_col);
+        let visual_end_col = layout.caret_col(line_text, end_col);
         Some(ctx.clipped_span_x(visual_start_col, visual_end_col, viewport_left))
     }
 
@@ -262,7 +384,7 @@ impl<'a> TextEditorRenderer<'a> {
         let left_visual_col = rect_sel.left_visual_col();
         let right_visual_col = rect_sel.right_visual_col();
 
-        let line_visual_len = char_col_to_visual_col(line_text, line_text.chars().count());
+        let line_visual_len = ctx.line_layout(doc_line).line_end(line_text);
 
         // A line has nothing to draw only when it's fully to the left of the
         // whole rectangle. Don't bail based on the live drag column (just
@@ -296,8 +418,12 @@ impl<'a> TextEditorRenderer<'a> {
             self.palette.gutter_background,
         );
 
-        let text_area_x = self.ctx.gutter_right_x + 1;
-        let text_area_w = self.ctx.rect_w.saturating_sub(self.ctx.gutter_width + 1);
+        let border_width = self.model.metrics.border_width;
+        let text_area_x = self.ctx.gutter_right_x + border_width;
+        let text_area_w = self
+            .ctx
+            .rect_w
+            .saturating_sub(self.ctx.gutter_width + border_width);
         let bg = if is_cursor_line {
             self.palette.current_line
         } else {
@@ -315,6 +441,10 @@ impl<'a> TextEditorRenderer<'a> {
             y,
             height,
             is_active_line: doc_line == self.editor.active_cursor().line,
+            decoration: self
+                .document
+                .decorations
+                .style(doc_line, self.document.revision),
         }
     }
 
@@ -344,6 +474,18 @@ impl<'a> TextEditorRenderer<'a> {
         );
     }
 
+    /// Vertical rulers at the configured columns, from `y` down `height` pixels
+    fn render_rulers_stage(&self, frame: &mut Frame, y: usize, height: usize) {
+        let viewport_left = self.viewport_left();
+        for &column in &self.model.config.rulers {
+            if !self.ctx.contains_visual_col(column, viewport_left) {
+                continue;
+            }
+            let x = self.ctx.pixel_x(column, viewport_left);
+            frame.blend_rect_px(x, y, 1, height, self.palette.ruler);
+        }
+    }
+
     fn collect_line_decorations(&mut self, line: &VisibleTextLine) {
         let document = self.document;
         let ctx = &self.ctx;
@@ -352,10 +494,16 @@ impl<'a> TextEditorRenderer<'a> {
 
         let mut selection_spans = std::mem::take(&mut self.text_buffers.selection_spans);
         selection_spans.clear();
+        let mut search_match_spans = std::mem::take(&mut self.text_buffers.search_match_spans);
+        search_match_spans.clear();
+        self.text_buffers.word_highlight_spans.clear();
         let mut bracket_visual_cols = [None, None];
+        self.text_buffers.search_scope_span = None;
+        self.text_buffers.current_search_match_span = None;
 
         let Some(line_text) = document.get_line_cow(line.doc_line) else {
             self.text_buffers.selection_spans = selection_spans;
+            self.text_buffers.search_match_spans = search_match_spans;
             self.text_buffers.bracket_visual_cols = bracket_visual_cols;
             return;
         };
@@ -389,13 +537,85 @@ impl<'a> TextEditorRenderer<'a> {
             }
         }
 
+        if let Some(scope) = self
+            .model
+            .ui
+            .search_scope()
+            .filter(|scope| Some(scope.document_id) == document.id)
+        {
+            self.text_buffers.search_scope_span = Self::selection_span_for_line(
+                document,
+                ctx,
+                viewport_left,
+                &Selection::from_anchor_head(scope.start, scope.end),
+                line.doc_line,
+                &line_text,
+            )
+            .filter(|(x_start, x_end)| x_end > x_start);
+        }
+
+        let layout = ctx.line_layout(line.doc_line);
+        let match_span = |start_col: usize, end_col: usize| {
+            let (x_start, x_end) = ctx.clipped_span_x(
+                layout.visual_col(&line_text, start_col),
+                layout.caret_col(&line_text, end_col),
+                viewport_left,
+            );
+            (x_end > x_start).then_some((x_start, x_end))
+        };
+        let find_state = match &self.model.ui.active_modal {
+            Some(ModalState::FindReplace(state))
+                if document.id.is_some() && state.matches_document == document.id =>
+            {
+                Some(state)
+            }
+            _ => None,
+        };
+        if let Some(state) = find_state {
+            // The find modal keeps this document's matches up to date as
+            // the query is typed, and marks the one it's on
+            let current = state.current_match.and_then(|i| state.matches.get(i));
+            for found in state.matches_on_line(document.id, line.doc_line) {
+                let Some(span) = match_span(found.0.column, found.1.column) else {
+                    continue;
+                };
+                if Some(found) == current {
+                    self.text_buffers.current_search_match_span = Some(span);
+                } else {
+                    search_match_spans.push(span);
+                }
+            }
+        } else if let Some(highlight) = &self.model.ui.search_highlight {
+            search_match_spans.extend(
+                highlight
+                    .matches_on_line(document.id, line.doc_line, &line_text)
+                    .into_iter()
+                    .filter_map(|(start_col, end_col)| match_span(start_col, end_col)),
+            );
+        }
+
+        // Found for an earlier revision, the ranges may be off
+        let word_highlights = &self.editor.word_highlights;
+        if word_highlights
+            .key
+            .is_some_and(|key| key.revision == document.revision)
+        {
+            self.text_buffers.word_highlight_spans.extend(
+                word_highlights
+                    .ranges
+                    .iter()
+                    .filter(|(doc_line, _, _)| *doc_line == line.doc_line)
+                    .filter_map(|&(_, start_col, end_col)| match_span(start_col, end_col)),
+            );
+        }
+
         if let Some((pos_a, pos_b)) = self.editor.matched_brackets {
             for (slot, pos) in [pos_a, pos_b].into_iter().enumerate() {
                 if pos.line != line.doc_line {
                     continue;
                 }
 
-                let visual_col = char_col_to_visual_col(&line_text, pos.column);
+                let visual_col = layout.visual_col(&line_text, pos.column);
                 if ctx.contains_visual_col(visual_col, viewport_left) {
                     bracket_visual_cols[slot] = Some(visual_col);
                 }
@@ -403,10 +623,54 @@ impl<'a> TextEditorRenderer<'a> {
         }
 
         self.text_buffers.selection_spans = selection_spans;
+        self.text_buffers.search_match_spans = search_match_spans;
         self.text_buffers.bracket_visual_cols = bracket_visual_cols;
     }
 
     fn render_line_decoration_stage(&self, frame: &mut Frame, line: &VisibleTextLine) {
+        if let Some(kind) = line.decoration.tint {
+            let border_width = self.model.metrics.border_width;
+            frame.blend_rect_px(
+                self.ctx.gutter_right_x + border_width,
+                line.y,
+                self.ctx
+                    .rect_w
+                    .saturating_sub(self.ctx.gutter_width + border_width),
+                line.height,
+                self.palette.decoration_tint(kind),
+            );
+        }
+
+        if let Some((x_start, x_end)) = self.text_buffers.search_scope_span {
+            frame.blend_rect_px(
+                x_start,
+                line.y,
+                x_end - x_start,
+                line.height,
+                self.palette.search_scope,
+            );
+        }
+
+        for &(x_start, x_end) in &self.text_buffers.word_highlight_spans {
+            frame.blend_rect_px(
+                x_start,
+                line.y,
+                x_end - x_start,
+                line.height,
+                self.palette.word_highlight,
+            );
+        }
+
+        for &(x_start, x_end) in &self.text_buffers.search_match_spans {
+            frame.blend_rect_px(
+                x_start,
+                line.y,
+                x_end.saturating_sub(x_start),
+                line.height,
+                self.palette.search_match,
+            );
+        }
+
         for &(x_start, x_end) in &self.text_buffers.selection_spans {
             frame.fill_rect_px(
                 x_start,
@@ -417,17 +681,44 @@ impl<'a> TextEditorRenderer<'a> {
             );
         }
 
-        let bracket_width = self.ctx.char_width.round() as usize;
+        // Over the selection, which covers the match the find modal selected
+        if let Some((x_start, x_end)) = self.text_buffers.current_search_match_span {
+            frame.blend_rect_px(
+                x_start,
+                line.y,
+                x_end - x_start,
+                line.height,
+                self.palette.search_match_current,
+            );
+        }
+
         for visual_col in self.text_buffers.bracket_visual_cols.into_iter().flatten() {
             let x = self.ctx.pixel_x(visual_col, self.viewport_left());
+            let end_x = self.ctx.pixel_x(visual_col + 1, self.viewport_left());
             frame.blend_rect_px(
                 x,
                 line.y,
-                bracket_width,
+                end_x - x,
                 line.height,
                 self.palette.bracket_match,
             );
         }
+
+        if let Some(guides) = &self.indent_guides {
+            let viewport_left = self.viewport_left();
+            for visual_col in guides.columns(line.doc_line) {
+                if !self.ctx.contains_visual_col(visual_col, viewport_left) {
+                    continue;
+                }
+                let color = if guides.is_active(line.doc_line, visual_col) {
+                    self.palette.indent_guide_active
+                } else {
+                    self.palette.indent_guide
+                };
+                let x = self.ctx.pixel_x(visual_col, viewport_left);
+                frame.blend_rect_px(x, line.y, 1, line.height, color);
+            }
+        }
     }
 
     fn render_line_text_stage(
@@ -447,18 +738,22 @@ impl<'a> TextEditorRenderer<'a> {
         };
 
         let max_chars = ctx.visible_columns;
-        let expanded_text = expand_tabs_for_display(&line_text);
+        let layout = ctx.line_layout(line.doc_line);
+        let expanded_text = layout.display_text(&line_text);
 
         text_buffers.display_text.clear();
-        for ch in expanded_text.chars().skip(viewport_left).take(max_chars) {
-            text_buffers.display_text.push(ch);
-        }
+        push_columns(
+            &mut text_buffers.display_text,
+            &expanded_text,
+            viewport_left,
+            max_chars,
+        );
 
         let line_tokens = document.get_line_highlights(line.doc_line);
         text_buffers.adjusted_tokens.clear();
         for t in line_tokens.iter() {
-            let visual_start = char_col_to_visual_col(&line_text, t.start_col);
-            let visual_end = char_col_to_visual_col(&line_text, t.end_col);
+            let visual_start = layout.visual_col(&line_text, t.start_col);
+            let visual_end = layout.caret_col(&line_text, t.end_col);
             let start = visual_start.saturating_sub(viewport_left);
             let end = visual_end.saturating_sub(viewport_left);
 
@@ -473,7 +768,18 @@ impl<'a> TextEditorRenderer<'a> {
             }
         }
 
-        if text_buffers.adjusted_tokens.is_empty() {
+        let hybrid = self
+            .markdown_fences
+            .as_ref()
+            .is_some_and(|(first, fenced)| {
+                line.doc_line
+                    .checked_sub(*first)
+                    .and_then(|i| fenced.get(i))
+                    .is_some_and(|in_fence| !in_fence)
+            });
+        if hybrid {
+            self.render_hybrid_line_text(frame, painter, line, &line_text);
+        } else if text_buffers.adjusted_tokens.is_empty() {
             painter.draw(
                 frame,
                 ctx.text_start_x,
@@ -492,6 +798,252 @@ impl<'a> TextEditorRenderer<'a> {
                 self.palette.text,
             );
         }
+
+        let layout = self.ctx.line_layout(line.doc_line);
+        let mut line_end = layout.line_end(&line_text);
+        self.render_whitespace_marks(frame, painter, line, &line_text, &layout, line_end);
+        if self.editor.folds.fold_at(line.doc_line).is_some() {
+            self.render_fold_marker(frame, painter, line, line_end);
+            line_end += FOLD_MARKER_WIDTH;
+        }
+        self.render_annotations(frame, painter, line, &line_text, &layout, line_end);
+    }
+
+    /// Mark the whitespace of a line as `render_whitespace` asks: a dot per
+    /// space, an arrow per tab and a pilcrow at the line break. Non-breaking
+    /// spaces and byte order marks are marked whatever the setting, as they
+    /// are otherwise indistinguishable from spaces or not seen at all.
+    fn render_whitespace_marks(
+        &self,
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        line: &VisibleTextLine,
+        line_text: &str,
+        layout: &LineLayout,
+        line_end: usize,
+    ) {
+        let mode = self.model.config.render_whitespace;
+        if mode == RenderWhitespace::None
+            && !line_text.contains(['\u{A0}', '\u{202F}', BYTE_ORDER_MARK])
+        {
+            return;
+        }
+        let viewport_left = self.viewport_left();
+        let shown = |visual_col: usize| match mode {
+            RenderWhitespace::None => false,
+            RenderWhitespace::All => true,
+            RenderWhitespace::Selection => {
+                let x = self.ctx.pixel_x(visual_col, viewport_left);
+                self.text_buffers
+                    .selection_spans
+                    .iter()
+                    .any(|&(x_start, x_end)| (x_start..x_end).contains(&x))
+            }
+        };
+        let mut draw = |visual_col: usize, mark: &str, color: u32| {
+            if self.ctx.contains_visual_col(visual_col, viewport_left) {
+                let x = self.ctx.pixel_x(visual_col, viewport_left);
+                painter.draw(frame, x, line.y, mark, color);
+            }
+        };
+
+        let mut tabs = TabExpander::new(layout.tabs());
+        // Columns of the inline annotations so far
+        let mut shift = 0;
+        for (char_idx, ch) in line_text.chars().enumerate() {
+            shift += layout.inline_width_at(char_idx);
+            let visual_col = tabs.visual_col() + shift;
+            tabs.advance(ch);
+            match ch {
+                ' ' if shown(visual_col) => draw(visual_col, "·", self.palette.whitespace),
+                '\t' if shown(visual_col) => draw(visual_col, "→", self.palette.whitespace),
+                '\u{A0}' | '\u{202F}' => draw(visual_col, "␣", self.palette.invisible_character),
+                BYTE_ORDER_MARK => draw(visual_col, "◆", self.palette.invisible_character),
+                _ => {}
+            }
+        }
+
+        let line_break = line.doc_line + 1 < self.document.line_count();
+        let break_shown = match mode {
+            RenderWhitespace::None => false,
+            RenderWhitespace::All => true,
+            RenderWhitespace::Selection => self.editor.selections.iter().any(|selection| {
+                !selection.is_empty()
+                    && selection.start().line <= line.doc_line
+                    && line.doc_line < selection.end().line
+            }),
+        };
+        if line_break && break_shown {
+            draw(line_end, "¶", self.palette.whitespace);
+        }
+    }
+
+    /// Draw a line's annotations, dimmed: inline ones over the columns left
+    /// free for them, end-of-line ones after `line_end`
+    fn render_annotations(
+        &self,
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        line: &VisibleTextLine,
+        line_text: &str,
+        layout: &LineLayout,
+        line_end: usize,
+    ) {
+        let color = blend_colors(self.palette.background, self.palette.text, 0.5);
+        let mut start = line_end + ANNOTATION_GAP;
+        let end_of_line = layout.end_of_line_annotations().iter().map(|annotation| {
+            let col = start;
+            start += annotation.width() + ANNOTATION_GAP;
+            (col, *annotation)
+        });
+        for (col, annotation) in layout.inline_annotations(line_text).chain(end_of_line) {
+            self.draw_clipped_text(frame, painter, line.y, col, &annotation.text, color);
+        }
+    }
+
+    /// Draw `text` starting at visual column `col`, clipped to the columns in
+    /// view
+    fn draw_clipped_text(
+        &self,
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        y: usize,
+        col: usize,
+        text: &str,
+        color: u32,
+    ) {
+        let viewport_left = self.viewport_left();
+        let skip = viewport_left.saturating_sub(col);
+        let first = col.max(viewport_left);
+        let room = (viewport_left + self.ctx.visible_columns).saturating_sub(first);
+        let mut visible = String::new();
+        push_columns(&mut visible, text, skip, room);
+        if !visible.is_empty() {
+            painter.draw(
+                frame,
+                self.ctx.pixel_x(first, viewport_left),
+                y,
+                &visible,
+                color,
+            );
+        }
+    }
+
+    /// "…" chip after the header line of a fold, in place of the hidden lines
+    fn render_fold_marker(
+        &self,
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        line: &VisibleTextLine,
+        line_end_visual: usize,
+    ) {
+        let viewport_left = self.viewport_left();
+        let start = line_end_visual + 1;
+        if !self.ctx.contains_visual_col(start, viewport_left) {
+            return;
+        }
+        let x = self.ctx.pixel_x(start, viewport_left);
+        let end_x = self.ctx.pixel_x(start + 3, viewport_left);
+        let chip = (self.palette.text & 0x00FF_FFFF) | 0x2F00_0000;
+        frame.blend_rect_px(x, line.y, end_x - x, line.height, chip);
+        let color = blend_colors(self.palette.background, self.palette.text, 0.6);
+        painter.draw(
+            frame,
+            self.ctx.pixel_x(start + 1, viewport_left),
+            line.y,
+            "…",
+            color,
+        );
+    }
+
+    /// Draw a line of Markdown source with the hybrid view's inline styles,
+    /// on top of the syntax colors already computed for it
+    fn render_hybrid_line_text(
+        &mut self,
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        line: &VisibleTextLine,
+        line_text: &str,
+    ) {
+        let hybrid = crate::markdown::style_line(line_text);
+        let viewport_left = self.viewport_left();
+        let syntax = &self.model.theme.syntax;
+        let title = hybrid
+            .heading_level
+            .and_then(|_| crate::syntax::highlight_id_for_name("text.title"))
+            .map(|id| syntax.color_for_highlight(id).to_argb_u32());
+        let text_buffers = &self.text_buffers;
+
+        // Per display column: base color from the syntax tokens, then the
+        // style of the source char the column belongs to (tabs span several)
+        let display_len = display_width(&text_buffers.display_text);
+        let mut colors = Vec::with_capacity(display_len);
+        let mut styles = Vec::with_capacity(display_len);
+        let mut code_spans: Vec<(usize, usize)> = Vec::new();
+        let mut token_idx = 0;
+        let layout = self.ctx.line_layout(line.doc_line);
+        let mut tabs = TabExpander::new(layout.tabs());
+        // Columns of the inline annotations so far
+        let mut shift = 0;
+        for (char_idx, ch) in line_text.chars().enumerate() {
+            let annotation_start = tabs.visual_col() + shift;
+            let visual_col = annotation_start + layout.inline_width_at(char_idx);
+            shift = visual_col - tabs.visual_col();
+            let width = tabs.advance(ch);
+            let char_style = hybrid.styles.get(char_idx).copied().unwrap_or_default();
+            for col in annotation_start..visual_col + width {
+                // Annotations are drawn later, over plain columns
+                let style = if col < visual_col {
+                    Default::default()
+                } else {
+                    char_style
+                };
+                let Some(col) = col.checked_sub(viewport_left) else {
+                    continue;
+                };
+                if col >= display_len {
+                    break;
+                }
+                let tokens = &text_buffers.adjusted_tokens;
+                while token_idx < tokens.len() && tokens[token_idx].end_col <= col {
+                    token_idx += 1;
+                }
+                let token_color = tokens
+                    .get(token_idx)
+                    .filter(|t| col >= t.start_col)
+                    .map(|t| syntax.color_for_highlight(t.highlight).to_argb_u32());
+                let color = title.or(token_color).unwrap_or(self.palette.text);
+                colors.push(if style.dim {
+                    blend_colors(self.palette.background, color, 0.5)
+                } else {
+                    color
+                });
+                styles.push(GlyphStyle {
+                    bold: style.bold,
+                    italic: style.italic,
+                });
+                match code_spans.last_mut() {
+                    Some((_, end)) if style.code && *end == col => *end = col + 1,
+                    _ if style.code => code_spans.push((col, col + 1)),
+                    _ => {}
+                }
+            }
+        }
+
+        let chip = (self.palette.text & 0x00FF_FFFF) | 0x1F00_0000;
+        for &(start, end) in &code_spans {
+            let x = self.ctx.pixel_x(start + viewport_left, viewport_left);
+            let end_x = self.ctx.pixel_x(end + viewport_left, viewport_left);
+            frame.blend_rect_px(x, line.y, end_x - x, line.height, chip);
+        }
+        painter.draw_styled(
+            frame,
+            self.ctx.text_start_x,
+            line.y,
+            &text_buffers.display_text,
+            &colors,
+            &styles,
+        );
     }
 
     fn render_gutter_line_number(
@@ -501,7 +1053,9 @@ impl<'a> TextEditorRenderer<'a> {
         line: &VisibleTextLine,
     ) {
         let line_num_str = format!("{}", line.doc_line + 1);
-        let text_width_px = (line_num_str.len() as f32 * self.ctx.char_width).round() as usize;
+        // The last column of the gutter holds fold markers
+        let text_width_px =
+            ((line_num_str.len() + 1) as f32 * self.ctx.char_width).round() as usize;
         let text_x = self
             .ctx
             .gutter_right_x
@@ -514,6 +1068,93 @@ impl<'a> TextEditorRenderer<'a> {
         painter.draw(frame, text_x, line.y, &line_num_str, line_color);
     }
 
+    /// Fold marker in the last gutter column: ▸ on a folded line, ▾ where a
+    /// region starts
+    fn render_gutter_fold_marker(
+        &self,
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        line: &VisibleTextLine,
+    ) {
+        let (marker, color) = if self.editor.folds.fold_at(line.doc_line).is_some() {
+            ("▸", self.palette.active_line_number)
+        } else if can_fold_at(self.document, line.doc_line) {
+            ("▾", self.palette.line_number)
+        } else {
+            return;
+        };
+        let char_width = self.ctx.char_width.round() as usize;
+        let x = self
+            .ctx
+            .gutter_right_x
+            .saturating_sub(self.model.metrics.padding_medium + char_width);
+        painter.draw(frame, x, line.y, marker, color);
+    }
+
+    /// Git change marker against the gutter border: a bar beside added and
+    /// modified lines, a wider tick where lines were deleted
+    fn render_gutter_git_marker(&self, frame: &mut Frame, line: &VisibleTextLine) {
+        let Some(diff) = &self.document.git_diff else {
+            return;
+        };
+        let bar_width = self.model.metrics.padding_small + 1;
+        let x = self.ctx.gutter_right_x.saturating_sub(bar_width);
+        let tick_height = self.model.metrics.padding_small;
+        let last_line = self.document.line_count().saturating_sub(1);
+        if line.doc_line == last_line && diff.deleted_at_end(last_line) {
+            frame.fill_rect_px(
+                x.saturating_sub(bar_width),
+                line.y + line.height.saturating_sub(tick_height),
+                bar_width * 2,
+                tick_height,
+                self.palette.decoration(DecorationKind::Error),
+            );
+        }
+        match diff.change_at(line.doc_line) {
+            Some(HunkKind::Added) => frame.fill_rect_px(
+                x,
+                line.y,
+                bar_width,
+                line.height,
+                self.palette.decoration(DecorationKind::Success),
+            ),
+            Some(HunkKind::Modified) => frame.fill_rect_px(
+                x,
+                line.y,
+                bar_width,
+                line.height,
+                self.palette.decoration(DecorationKind::Highlight),
+            ),
+            Some(HunkKind::Deleted) => frame.fill_rect_px(
+                x.saturating_sub(bar_width),
+                line.y,
+                bar_width * 2,
+                tick_height,
+                self.palette.decoration(DecorationKind::Error),
+            ),
+            None => {}
+        }
+    }
+
+    /// Icon from the line's decorations, at the left edge of the gutter
+    fn render_gutter_icon(
+        &self,
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        line: &VisibleTextLine,
+    ) {
+        let Some((icon, kind)) = line.decoration.icon else {
+            return;
+        };
+        painter.draw(
+            frame,
+            self.ctx.rect_x + self.model.metrics.padding_small,
+            line.y,
+            icon.glyph(),
+            self.palette.decoration(kind),
+        );
+    }
+
     fn render_cursor_at(
         &self,
         frame: &mut Frame,
@@ -523,7 +1164,7 @@ impl<'a> TextEditorRenderer<'a> {
         color: u32,
     ) {
         let line_text = self.document.get_line_cow(line).unwrap_or_default();
-        let visual_cursor_col = char_col_to_visual_col(&line_text, column);
+        let visual_cursor_col = self.ctx.line_layout(line).caret_col(&line_text, column);
 
         if !self
             .ctx
@@ -601,6 +1242,24 @@ impl<'a> TextEditorRenderer<'a> {
         }
     }
 
+    /// Caret showing where dragged text will be dropped
+    fn render_drop_caret(&self, frame: &mut Frame) {
+        let Some(drop) = self.editor.text_drag.and_then(|drag| drag.drop) else {
+            return;
+        };
+        let Some(screen_line) = self.ctx.viewport.visible_row_for_doc_line(drop.line) else {
+            return;
+        };
+        let y = self.ctx.content_y + screen_line * self.ctx.line_height;
+        self.render_cursor_at(
+            frame,
+            drop.line,
+            drop.column,
+            y,
+            self.palette.secondary_cursor,
+        );
+    }
+
     fn render_line_content_stages(
         &mut self,
         frame: &mut Frame,
@@ -633,7 +1292,11 @@ impl<'a> TextEditorRenderer<'a> {
 
             let line = self.prepare_visible_line(doc_line, y);
             self.render_line_background_stage(frame, &line);
+            self.render_rulers_stage(frame, line.y, line.height);
             self.render_gutter_line_number(frame, painter, &line);
+            self.render_gutter_fold_marker(frame, painter, &line);
+            self.render_gutter_git_marker(frame, &line);
+            self.render_gutter_icon(frame, painter, &line);
             self.render_line_content_stages(frame, painter, &line);
             self.render_dirty_line_cursor_stage(frame, &line);
         }
@@ -659,10 +1322,14 @@ impl<'a> TextEditorRenderer<'a> {
         {
             let start = Instant::now();
             self.render_current_line_background_stage(frame);
+            self.render_rulers_stage(frame, self.ctx.content_y, self.ctx.content_h);
             background_time += start.elapsed();
         }
         #[cfg(not(debug_assertions))]
-        self.render_current_line_background_stage(frame);
+        {
+            self.render_current_line_background_stage(frame);
+            self.render_rulers_stage(frame, self.ctx.content_y, self.ctx.content_h);
+        }
 
         for screen_line in 0..self.ctx.visible_lines {
             let Some(doc_line) = self.ctx.viewport.doc_line_for_visible_row(screen_line) else {
@@ -695,12 +1362,14 @@ impl<'a> TextEditorRenderer<'a> {
                 let start = Instant::now();
                 self.render_visible_cursors(frame);
                 self.render_preview_cursors(frame);
+                self.render_drop_caret(frame);
                 cursor_time += start.elapsed();
             }
             #[cfg(not(debug_assertions))]
             {
                 self.render_visible_cursors(frame);
                 self.render_preview_cursors(frame);
+                self.render_drop_caret(frame);
             }
         }
 
@@ -733,12 +1402,13 @@ impl<'a> TextEditorRenderer<'a> {
 
             let line = self.prepare_visible_line(doc_line, y);
             self.render_gutter_line_number(frame, painter, &line);
+            self.render_gutter_icon(frame, painter, &line);
         }
 
         frame.fill_rect_px(
             self.ctx.gutter_right_x,
             self.ctx.content_y,
-            1,
+            self.model.metrics.border_width,
             self.ctx.content_h,
             self.palette.gutter_border,
         );
@@ -835,9 +1505,11 @@ pub fn render_gutter(
 mod tests {
     use super::render_cursor_lines_only;
     use super::{EditorRenderContext, TextEditorRenderer};
+    use crate::config::RenderWhitespace;
     use crate::model::editor::RectangleSelectionState;
     use crate::model::{AppModel, Cursor, Position, Rect, Selection};
     use crate::view::geometry::GroupLayout;
+    use crate::view::scene::{Primitive, Scene};
     use crate::view::{Frame, GlyphCache, Renderer, TextPainter};
     use fontdue::{Font, FontSettings};
     use ropey::Rope;
@@ -914,6 +1586,7 @@ mod tests {
             current_line: 2,
             current_visual_col: 10,
             preview_cursors: Vec::new(),
+            from_keyboard: false,
         };
 
         // "alpha" is only 5 chars long — shorter than the drag's right edge
@@ -937,6 +1610,7 @@ mod tests {
                 current_line: 2,
                 current_visual_col: 30,
                 preview_cursors: Vec::new(),
+                from_keyboard: false,
             },
             0,
             "alpha",
@@ -1027,6 +1701,141 @@ mod tests {
         band
     }
 
+    #[test]
+    fn line_decorations_tint_their_line_and_draw_gutter_icons() {
+        use crate::model::{DecorationKind, DecorationSource, GutterIcon, LineDecoration};
+
+        let mut model = make_text_model();
+        let plain = render_full_editor_group(&model);
+        let document = model.document_mut();
+        let revision = document.revision;
+        document.decorations.set(
+            DecorationSource::Diagnostics,
+            revision,
+            [LineDecoration::tint(2, DecorationKind::Error).with_icon(GutterIcon::Dot)],
+        );
+        let decorated = render_full_editor_group(&model);
+
+        let width = model.window_size.0 as usize;
+        let (_, _, _, _, line_height) = load_test_font();
+        let row = |buffer: &[u32], line: usize| {
+            let y = model.metrics.tab_bar_height + line * line_height + line_height / 2;
+            buffer[y * width..(y + 1) * width].to_vec()
+        };
+        assert_eq!(row(&plain, 0), row(&decorated, 0));
+        let (before, after) = (row(&plain, 2), row(&decorated, 2));
+        // Tinted across the text area, with an icon in the gutter
+        assert_ne!(before[width - 30], after[width - 30]);
+        let gutter = model.metrics.padding_small..model.metrics.padding_small + 8;
+        assert_ne!(before[gutter.clone()], after[gutter]);
+    }
+
+    /// Characters of the glyphs drawn for the focused group, in order
+    fn rendered_glyphs(model: &AppModel) -> Vec<char> {
+        let mut scene = Scene::new(
+            model.window_size.0 as usize,
+            model.window_size.1 as usize,
+            Default::default(),
+        );
+        let mut frame = Frame::recording(&mut scene);
+        let (font, font_size, ascent, char_width, line_height) = load_test_font();
+        let mut glyph_cache = GlyphCache::default();
+        let mut painter = TextPainter::new(
+            &font,
+            &mut glyph_cache,
+            font_size,
+            ascent,
+            char_width,
+            line_height,
+        );
+        let group = model
+            .editor_area
+            .groups
+            .get(&model.editor_area.focused_group_id)
+            .unwrap();
+        let mut perf = crate::perf::PerfStats::default();
+        Renderer::render_editor_group(
+            &mut frame,
+            &mut painter,
+            model,
+            group.id,
+            group.rect,
+            true,
+            &mut perf,
+        );
+        scene
+            .primitives
+            .iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Glyph { key, .. } => Some(key.0),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn whitespace_marks_follow_render_whitespace() {
+        let mut model = make_text_model();
+        let marks = |model: &AppModel| {
+            let glyphs = rendered_glyphs(model);
+            let count = |mark: char| glyphs.iter().filter(|&&glyph| glyph == mark).count();
+            (count('→'), count('¶'))
+        };
+        assert_eq!(marks(&model), (0, 0));
+
+        model.config.render_whitespace = RenderWhitespace::All;
+        assert_eq!(marks(&model), (1, 3));
+
+        // The selection covers "beta" but neither the tab nor a line break
+        model.config.render_whitespace = RenderWhitespace::Selection;
+        assert_eq!(marks(&model), (0, 0));
+        model.editor_mut().selections = vec![Selection::from_positions(
+            Position::new(0, 2),
+            Position::new(1, 1),
+        )];
+        assert_eq!(marks(&model), (1, 1));
+
+        // Non-breaking spaces are marked with whitespace rendering off
+        model.config.render_whitespace = RenderWhitespace::None;
+        model.document_mut().buffer = Rope::from("a\u{A0}b\n");
+        model.editor_mut().selections = vec![Selection::new(Position::new(0, 0))];
+        model.editor_mut().cursors = vec![Cursor::at(0, 0)];
+        model.editor_mut().matched_brackets = None;
+        assert!(rendered_glyphs(&model).contains(&'␣'));
+    }
+
+    #[test]
+    fn rulers_run_down_the_whole_text_area() {
+        let mut model = make_text_model();
+        let plain = render_full_editor_group(&model);
+        model.config.rulers = vec![3];
+        let ruled = render_full_editor_group(&model);
+
+        let width = model.window_size.0 as usize;
+        let (_, _, _, char_width, line_height) = load_test_font();
+        let group = model
+            .editor_area
+            .groups
+            .get(&model.editor_area.focused_group_id)
+            .unwrap();
+        let layout = GroupLayout::new(group, &model, char_width);
+        let x = layout.text_start_x + crate::view::geometry::column_offset_px(3, char_width);
+        // Through a tab and below the end of the document
+        for line in [1, 4] {
+            let y = layout.content_y() + line * line_height + line_height / 2;
+            assert_ne!(plain[y * width + x], ruled[y * width + x]);
+            assert_eq!(plain[y * width + x + 2], ruled[y * width + x + 2]);
+        }
+
+        // The cursor-line fast path redraws the ruler over the line background
+        let mut redrawn = ruled.clone();
+        rerender_cursor_lines(&model, &mut redrawn, &[1]);
+        assert_eq!(
+            extract_active_line_band(&model, &redrawn),
+            extract_active_line_band(&model, &ruled)
+        );
+    }
+
     #[test]
     fn cursor_line_fast_path_matches_full_render_after_cursor_visibility_change() {
         let mut model = make_text_model();
@@ -0,0 +1,190 @@
+//! Editor fonts: the configured family and its fallback chain
+//!
+//! Text is drawn with the primary font; a character it has no glyph for
+//! (CJK, symbols) is drawn with the first fallback font that has one.
+//! Fonts are found by family name among the installed system fonts, and
+//! JetBrains Mono is embedded so there is always a font to draw with.
+//!
+//! Fallback fonts are loaded the first time a glyph is looked up in them:
+//! CJK fonts are large, and most files never need them.
+
+use std::cell::OnceCell;
+use std::sync::OnceLock;
+
+use fontdue::{Font, FontSettings};
+
+/// Family name of the embedded font
+pub const EMBEDDED_FAMILY: &str = "JetBrains Mono";
+
+/// The primary font and the fallbacks tried, in order, for glyphs it lacks
+pub struct EditorFonts {
+    pub primary: Font,
+    pub fallbacks: Vec<FallbackFont>,
+    /// Family asked for, which `primary` may not be when it isn't installed
+    family: String,
+    fallback_families: Vec<String>,
+}
+
+impl EditorFonts {
+    /// `family`, or the embedded font when it isn't installed, followed by
+    /// `fallback_families` and the embedded font
+    pub fn load(family: &str, fallback_families: &[String]) -> Self {
+        let primary = if is_embedded(family) {
+            None
+        } else {
+            let font = load_system_font(family);
+            if font.is_none() {
+                tracing::warn!(
+                    "Font family \"{}\" not found, using {}",
+                    family,
+                    EMBEDDED_FAMILY
+                );
+            }
+            font
+        };
+        let mut fallbacks: Vec<FallbackFont> = fallback_families
+            .iter()
+            .filter(|name| !name.eq_ignore_ascii_case(family) && !is_embedded(name))
+            .map(|name| FallbackFont::new(name))
+            .collect();
+        // The embedded font still has glyphs a system font may lack
+        if primary.is_some() {
+            fallbacks.push(FallbackFont::new(EMBEDDED_FAMILY));
+        }
+        Self {
+            primary: primary.unwrap_or_else(embedded_font),
+            fallbacks,
+            family: family.to_string(),
+            fallback_families: fallback_families.to_vec(),
+        }
+    }
+
+    /// Only the embedded font
+    pub fn embedded() -> Self {
+        Self {
+            primary: embedded_font(),
+            fallbacks: Vec::new(),
+            family: EMBEDDED_FAMILY.to_string(),
+            fallback_families: Vec::new(),
+        }
+    }
+
+    /// Whether these fonts were loaded for `family` and `fallback_families`
+    pub fn is_for(&self, family: &str, fallback_families: &[String]) -> bool {
+        self.family == family && self.fallback_families == fallback_families
+    }
+}
+
+/// A font of the fallback chain, loaded on first use
+pub struct FallbackFont {
+    family: String,
+    font: OnceCell<Option<Font>>,
+}
+
+impl FallbackFont {
+    fn new(family: &str) -> Self {
+        Self {
+            family: family.to_string(),
+            font: OnceCell::new(),
+        }
+    }
+
+    /// The font, or `None` when no installed font has this family
+    pub fn font(&self) -> Option<&Font> {
+        self.font
+            .get_or_init(|| {
+                if is_embedded(&self.family) {
+                    return Some(embedded_font());
+                }
+                let font = load_system_font(&self.family);
+                match font {
+                    Some(_) => tracing::info!("Loaded fallback font \"{}\"", self.family),
+                    None => tracing::debug!("Fallback font \"{}\" not installed", self.family),
+                }
+                font
+            })
+            .as_ref()
+    }
+}
+
+/// The font to draw `ch` with: `font` if it has a glyph for it, else the
+/// first of `fallbacks` that does, else `font` (drawing its missing-glyph
+/// box)
+pub fn font_for<'a>(font: &'a Font, fallbacks: &'a [FallbackFont], ch: char) -> &'a Font {
+    if font.has_glyph(ch) {
+        return font;
+    }
+    fallbacks
+        .iter()
+        .filter_map(FallbackFont::font)
+        .find(|fallback| fallback.has_glyph(ch))
+        .unwrap_or(font)
+}
+
+fn is_embedded(family: &str) -> bool {
+    family.eq_ignore_ascii_case(EMBEDDED_FAMILY)
+}
+
+fn embedded_font() -> Font {
+    Font::from_bytes(
+        include_bytes!("../../assets/JetBrainsMono.ttf") as &[u8],
+        FontSettings::default(),
+    )
+    .expect("Embedded font is valid")
+}
+
+/// Installed fonts, scanned the first time a system font is asked for
+fn system_fonts() -> &'static fontdb::Database {
+    static DATABASE: OnceLock<fontdb::Database> = OnceLock::new();
+    DATABASE.get_or_init(|| {
+        let mut database = fontdb::Database::new();
+        database.load_system_fonts();
+        tracing::debug!("Found {} system font faces", database.len());
+        database
+    })
+}
+
+/// The regular face of the installed `family`
+fn load_system_font(family: &str) -> Option<Font> {
+    let database = system_fonts();
+    let id = database.query(&fontdb::Query {
+        families: &[fontdb::Family::Name(family)],
+        ..fontdb::Query::default()
+    })?;
+    database
+        .with_face_data(id, |data, index| {
+            let settings = FontSettings {
+                collection_index: index,
+                ..FontSettings::default()
+            };
+            Font::from_bytes(data, settings)
+                .map_err(|e| tracing::warn!("Failed to load font \"{}\": {}", family, e))
+                .ok()
+        })
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_family_needs_no_system_fonts() {
+        let fonts = EditorFonts::load("jetbrains mono", &[]);
+        assert!(fonts.fallbacks.is_empty());
+        assert!(fonts.is_for("jetbrains mono", &[]));
+        assert!(!fonts.is_for(EMBEDDED_FAMILY, &["Menlo".to_string()]));
+    }
+
+    #[test]
+    fn glyphs_missing_everywhere_use_the_primary_font() {
+        let fonts = EditorFonts::embedded();
+        let fallbacks = [FallbackFont::new(EMBEDDED_FAMILY)];
+        let font = font_for(&fonts.primary, &fallbacks, 'a');
+        assert!(std::ptr::eq(font, &fonts.primary));
+        // A private use character no font here has
+        let font = font_for(&fonts.primary, &fallbacks, '\u{10FFFD}');
+        assert!(std::ptr::eq(font, &fonts.primary));
+        assert!(fallbacks[0].font().is_some());
+    }
+}
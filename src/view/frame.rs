@@ -4,9 +4,11 @@
 //! direct buffer indexing scattered throughout rendering code.
 
 use crate::model::editor_area::Rect;
-use fontdue::Font;
+use fontdue::{Font, Metrics};
 
-use super::GlyphCache;
+use super::fonts::{font_for, FallbackFont};
+use super::scene::{intersect, Primitive, Scene};
+use super::{GlyphCache, GlyphCacheKey};
 
 /// Blend a foreground color onto a background color using alpha compositing.
 ///
@@ -43,11 +45,14 @@ struct ClipRect {
 /// A frame buffer wrapper providing safe drawing primitives.
 ///
 /// All coordinates are in pixels. Out-of-bounds operations are safely clipped.
+/// A frame created with [`Frame::recording`] records the primitives into a
+/// [`Scene`] for the GPU renderer instead of writing pixels.
 pub struct Frame<'a> {
     buffer: &'a mut [u32],
     width: usize,
     height: usize,
     clip: Option<ClipRect>,
+    scene: Option<&'a mut Scene>,
 }
 
 impl<'a> Frame<'a> {
@@ -72,9 +77,28 @@ impl<'a> Frame<'a> {
             width,
             height,
             clip: None,
+            scene: None,
         }
     }
 
+    /// Create a frame that records into `scene` (sized to the scene)
+    pub fn recording(scene: &'a mut Scene) -> Self {
+        Self {
+            buffer: &mut [],
+            width: scene.width,
+            height: scene.height,
+            clip: None,
+            scene: Some(scene),
+        }
+    }
+
+    /// Whether drawing is recorded for the GPU renderer rather than written
+    /// to pixels
+    #[inline]
+    pub fn is_recording(&self) -> bool {
+        self.scene.is_some()
+    }
+
     /// Get the frame width in pixels
     #[inline]
     #[allow(dead_code)]
@@ -92,7 +116,8 @@ impl<'a> Frame<'a> {
     /// Get mutable access to the underlying pixel buffer
     ///
     /// Use this for low-level operations that need direct buffer access.
-    /// Prefer using Frame's drawing methods when possible.
+    /// Prefer using Frame's drawing methods when possible: a recording frame
+    /// has no pixels, and returns an empty buffer.
     #[inline]
     #[allow(dead_code)]
     pub fn buffer_mut(&mut self) -> &mut [u32] {
@@ -145,9 +170,39 @@ impl<'a> Frame<'a> {
         self.clip.map_or(0, |c| c.y0)
     }
 
+    /// The clip rect, or the whole frame
+    fn clip_rect(&self) -> Rect {
+        let (x0, y0) = (self.min_x(), self.min_y());
+        Rect::new(
+            x0 as f32,
+            y0 as f32,
+            self.max_x().saturating_sub(x0) as f32,
+            self.max_y().saturating_sub(y0) as f32,
+        )
+    }
+
+    /// When recording, record the already clipped span `x0..x1` by `y0..y1`
+    /// as a rectangle and return true
+    #[inline]
+    fn record_span(&mut self, x0: usize, y0: usize, x1: usize, y1: usize, color: u32) -> bool {
+        let Some(scene) = self.scene.as_deref_mut() else {
+            return false;
+        };
+        if x1 > x0 && y1 > y0 {
+            scene.push(Primitive::Rect {
+                rect: Rect::new(x0 as f32, y0 as f32, (x1 - x0) as f32, (y1 - y0) as f32),
+                color,
+            });
+        }
+        true
+    }
+
     /// Clear the entire buffer with a solid color
     #[inline]
     pub fn clear(&mut self, color: u32) {
+        if let Some(scene) = self.scene.as_deref_mut() {
+            return scene.clear(color | 0xFF000000);
+        }
         self.buffer.fill(color);
     }
 
@@ -159,6 +214,9 @@ impl<'a> Frame<'a> {
             .max(self.min_y());
         let x1 = ((rect.x + rect.width) as usize).min(self.max_x());
         let y1 = ((rect.y + rect.height) as usize).min(self.max_y());
+        if self.record_span(x0, y0, x1, y1, color | 0xFF000000) {
+            return;
+        }
 
         for y in y0..y1 {
             let row_start = y * self.width;
@@ -174,6 +232,9 @@ impl<'a> Frame<'a> {
         let y0 = y.min(self.height).max(self.min_y());
         let x1 = (x + w).min(self.max_x());
         let y1 = (y + h).min(self.max_y());
+        if self.record_span(x0, y0, x1, y1, color | 0xFF000000) {
+            return;
+        }
 
         for py in y0..y1 {
             let row_start = py * self.width;
@@ -198,6 +259,9 @@ impl<'a> Frame<'a> {
         let y0 = y.min(self.height).max(self.min_y());
         let x1 = (x + w).min(self.max_x());
         let y1 = (y + h).min(self.max_y());
+        if self.record_span(x0, y0, x1, y1, color) {
+            return;
+        }
 
         for py in y0..y1 {
             let row_start = py * self.width;
@@ -224,6 +288,9 @@ impl<'a> Frame<'a> {
             .max(self.min_y());
         let x1 = ((rect.x + rect.width) as usize).min(self.max_x());
         let y1 = ((rect.y + rect.height) as usize).min(self.max_y());
+        if self.record_span(x0, y0, x1, y1, color) {
+            return;
+        }
 
         for y in y0..y1 {
             let row_start = y * self.width;
@@ -239,16 +306,20 @@ impl<'a> Frame<'a> {
     #[allow(dead_code)]
     pub fn set_pixel(&mut self, x: usize, y: usize, color: u32) {
         if x >= self.min_x() && x < self.max_x() && y >= self.min_y() && y < self.max_y() {
+            if self.record_span(x, y, x + 1, y + 1, color | 0xFF000000) {
+                return;
+            }
             self.buffer[y * self.width + x] = color;
         }
     }
 
-    /// Get a single pixel (bounds-checked, returns 0 if out of bounds)
+    /// Get a single pixel (bounds-checked, returns 0 if out of bounds or
+    /// recording)
     #[inline]
     #[allow(dead_code)]
     pub fn get_pixel(&self, x: usize, y: usize) -> u32 {
         if x < self.width && y < self.height {
-            self.buffer[y * self.width + x]
+            self.buffer.get(y * self.width + x).copied().unwrap_or(0)
         } else {
             0
         }
@@ -263,7 +334,7 @@ impl<'a> Frame<'a> {
 
         let idx = y * self.width + x;
         let alpha = ((color >> 24) & 0xFF) as f32 / 255.0;
-        if alpha <= 0.0 {
+        if alpha <= 0.0 || self.record_span(x, y, x + 1, y + 1, color) {
             return;
         }
         if alpha >= 1.0 {
@@ -281,10 +352,30 @@ impl<'a> Frame<'a> {
         if x < self.min_x() || x >= self.max_x() || y < self.min_y() || y >= self.max_y() {
             return;
         }
+        if self.is_recording() {
+            let alpha = ((alpha.clamp(0.0, 1.0) * 255.0).round() as u32) << 24;
+            self.record_span(x, y, x + 1, y + 1, (color & 0x00FFFFFF) | alpha);
+            return;
+        }
         let idx = y * self.width + x;
         self.buffer[idx] = blend_colors(self.buffer[idx], color, alpha);
     }
 
+    /// Blend a pixel with separate red, green and blue coverage (subpixel
+    /// text), in linear light, respecting clip rect
+    #[inline]
+    pub fn blend_subpixel_text_pixel(&mut self, x: usize, y: usize, color: u32, coverage: [u8; 3]) {
+        if self.is_recording() {
+            let average = coverage.iter().map(|&c| c as f32).sum::<f32>() / (3.0 * 255.0);
+            return self.blend_text_pixel(x, y, color, average);
+        }
+        if x < self.min_x() || x >= self.max_x() || y < self.min_y() || y >= self.max_y() {
+            return;
+        }
+        let idx = y * self.width + x;
+        self.buffer[idx] = crate::rendering::blend_subpixel_u8(self.buffer[idx], color, coverage);
+    }
+
     /// Fill a rectangle with alpha blending
     pub fn blend_rect(&mut self, rect: Rect, color: u32) {
         // `color`'s alpha is loop-invariant across the whole rect; extract
@@ -314,6 +405,9 @@ impl<'a> Frame<'a> {
             .max(self.min_y());
         let x1 = ((rect.x + rect.width) as usize).min(self.max_x());
         let y1 = ((rect.y + rect.height) as usize).min(self.max_y());
+        if self.record_span(x0, y0, x1, y1, color) {
+            return;
+        }
 
         for y in y0..y1 {
             for x in x0..x1 {
@@ -352,6 +446,16 @@ impl<'a> Frame<'a> {
         let offset_x = dest_x + (dest_w.saturating_sub(scaled_w)) / 2;
         let offset_y = dest_y + (dest_h.saturating_sub(scaled_h)) / 2;
 
+        if self.is_recording() {
+            let dest = Rect::new(
+                offset_x as f32,
+                offset_y as f32,
+                scaled_w as f32,
+                scaled_h as f32,
+            );
+            return self.record_image(pixels, img_width, img_height, dest, dest);
+        }
+
         for dy in 0..scaled_h {
             let py = offset_y + dy;
             if py >= self.height {
@@ -408,13 +512,57 @@ impl<'a> Frame<'a> {
                 dim_color | 0xFF000000,
             );
         }
-        for y in self.min_y()..self.max_y() {
-            for x in self.min_x()..self.max_x() {
+        let (x0, y0, x1, y1) = (self.min_x(), self.min_y(), self.max_x(), self.max_y());
+        if self.record_span(x0, y0, x1, y1, dim_color) {
+            return;
+        }
+        for y in y0..y1 {
+            for x in x0..x1 {
                 self.blend_text_pixel(x, y, dim_color, a);
             }
         }
     }
 
+    /// Record an RGBA8 image scaled to `dest`, showing only the part inside
+    /// `visible` and the clip rect. Only used when recording; the software
+    /// path draws images pixel by pixel.
+    pub fn record_image(
+        &mut self,
+        pixels: &[u8],
+        width: u32,
+        height: u32,
+        dest: Rect,
+        visible: Rect,
+    ) {
+        let Some(clip) = intersect(visible, self.clip_rect()) else {
+            return;
+        };
+        let Some(scene) = self.scene.as_deref_mut() else {
+            return;
+        };
+        if width == 0 || height == 0 || intersect(dest, clip).is_none() {
+            return;
+        }
+        let image = scene.add_image(pixels, width, height);
+        scene.push(Primitive::Image { image, dest, clip });
+    }
+
+    /// Record a checkerboard of `cell`-sized squares filling `rect`. Only
+    /// used when recording.
+    pub fn record_checkerboard(&mut self, rect: Rect, cell: usize, light: u32, dark: u32) {
+        let Some(rect) = intersect(rect, self.clip_rect()) else {
+            return;
+        };
+        if let Some(scene) = self.scene.as_deref_mut() {
+            scene.push(Primitive::Checkerboard {
+                rect,
+                cell: cell.max(1) as f32,
+                light,
+                dark,
+            });
+        }
+    }
+
     /// Draw a rectangle with a 1px border
     pub fn draw_bordered_rect(
         &mut self,
@@ -484,12 +632,13 @@ impl<'a> Frame<'a> {
             let bar_height = ((normalized * chart_height as f32).round() as usize)
                 .max(1)
                 .min(chart_height);
-            let px = x + column;
-
-            for dy in 0..bar_height {
-                let py = y + chart_height - dy - 1;
-                self.set_pixel(px, py, bar_color);
-            }
+            self.fill_rect_px(
+                x + column,
+                y + chart_height - bar_height,
+                1,
+                bar_height,
+                bar_color,
+            );
         }
     }
 }
@@ -503,16 +652,32 @@ pub struct CacheStats {
     pub misses: usize,
 }
 
+/// Synthetic font style for a glyph (the editor font has a single face)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GlyphStyle {
+    /// Drawn twice, one pixel apart
+    pub bold: bool,
+    /// Rows sheared to the right above the baseline
+    pub italic: bool,
+}
+
+/// Horizontal shift per pixel above the baseline for synthetic italics
+const ITALIC_SHEAR: f32 = 0.2;
+
 /// Text rendering context wrapping font and glyph cache.
 ///
 /// Provides methods for drawing text with proper font metrics and glyph caching.
 pub struct TextPainter<'a> {
     font: &'a Font,
+    /// Fonts for glyphs `font` lacks (see [`TextPainter::with_fallbacks`])
+    fallbacks: &'a [FallbackFont],
     glyph_cache: &'a mut GlyphCache,
     font_size: f32,
     ascent: f32,
     char_width: f32,
     line_height: usize,
+    /// Rasterize with per-channel coverage (see [`TextPainter::with_subpixel`])
+    subpixel: bool,
     #[cfg(debug_assertions)]
     #[allow(dead_code)]
     cache_stats: CacheStats,
@@ -530,16 +695,34 @@ impl<'a> TextPainter<'a> {
     ) -> Self {
         Self {
             font,
+            fallbacks: &[],
             glyph_cache,
             font_size,
             ascent,
             char_width,
             line_height,
+            subpixel: false,
             #[cfg(debug_assertions)]
             cache_stats: CacheStats::default(),
         }
     }
 
+    /// Use subpixel (LCD) antialiasing: glyphs get red, green and blue
+    /// coverage and are blended in linear light. The glyph cache must only
+    /// hold glyphs rasterized the same way.
+    pub fn with_subpixel(mut self, subpixel: bool) -> Self {
+        self.subpixel = subpixel;
+        self
+    }
+
+    /// Draw characters the font has no glyph for with the first of
+    /// `fallbacks` that has one. Each character resolves to one font, so the
+    /// glyph cache stays keyed by character and size.
+    pub fn with_fallbacks(mut self, fallbacks: &'a [FallbackFont]) -> Self {
+        self.fallbacks = fallbacks;
+        self
+    }
+
     /// Get the cache statistics (hits and misses)
     #[cfg(debug_assertions)]
     #[inline]
@@ -569,7 +752,7 @@ impl<'a> TextPainter<'a> {
 
     /// Draw text at the specified position
     pub fn draw(&mut self, frame: &mut Frame, x: usize, y: usize, text: &str, color: u32) {
-        let mut current_x = x as f32;
+        let mut pen = Pen::new(x, self.char_width);
         let baseline = y as f32 + self.ascent;
 
         for ch in text.chars() {
@@ -585,52 +768,80 @@ impl<'a> TextPainter<'a> {
                 self.cache_stats.misses += 1;
             }
 
-            let (metrics, bitmap) = self
-                .glyph_cache
-                .entry(key)
-                .or_insert_with(|| self.font.rasterize(ch, self.font_size));
-
-            let glyph_top = baseline - metrics.height as f32 - metrics.ymin as f32;
-
-            for bitmap_y in 0..metrics.height {
-                for bitmap_x in 0..metrics.width {
-                    let bitmap_idx = bitmap_y * metrics.width + bitmap_x;
-                    if bitmap_idx < bitmap.len() {
-                        let alpha = bitmap[bitmap_idx];
-                        if alpha > 0 {
-                            let px = current_x as isize + bitmap_x as isize + metrics.xmin as isize;
-                            let py = (glyph_top + bitmap_y as f32) as isize;
-
-                            if px >= 0 && py >= 0 {
-                                frame.blend_text_pixel(
-                                    px as usize,
-                                    py as usize,
-                                    color,
-                                    alpha as f32 / 255.0,
-                                );
-                            }
-                        }
-                    }
-                }
-            }
+            let (metrics, bitmap) = self.glyph_cache.entry(key).or_insert_with(|| {
+                rasterize(
+                    font_for(self.font, self.fallbacks, ch),
+                    ch,
+                    self.font_size,
+                    self.subpixel,
+                )
+            });
+
+            draw_glyph(
+                frame,
+                key,
+                metrics,
+                bitmap,
+                pen.x(),
+                baseline,
+                color,
+                GlyphStyle::default(),
+                self.subpixel,
+            );
 
-            current_x += metrics.advance_width;
+            pen.advance(ch);
         }
     }
 
-    /// Measure text width in pixels
-    #[allow(dead_code)]
-    pub fn measure_width(&mut self, text: &str) -> f32 {
-        let mut width = 0.0;
+    /// Draw text with a color and synthetic style per visual column (hybrid
+    /// Markdown view). Columns beyond `colors`/`styles` use the last entry.
+    pub fn draw_styled(
+        &mut self,
+        frame: &mut Frame,
+        x: usize,
+        y: usize,
+        text: &str,
+        colors: &[u32],
+        styles: &[GlyphStyle],
+    ) {
+        let mut pen = Pen::new(x, self.char_width);
+        let baseline = y as f32 + self.ascent;
+
         for ch in text.chars() {
+            let col = pen.column();
+            let color = colors.get(col).or(colors.last()).copied().unwrap_or(0);
+            let style = styles.get(col).copied().unwrap_or_default();
             let key = (ch, self.font_size.to_bits());
-            let (metrics, _) = self
-                .glyph_cache
-                .entry(key)
-                .or_insert_with(|| self.font.rasterize(ch, self.font_size));
-            width += metrics.advance_width;
+            let (metrics, bitmap) = self.glyph_cache.entry(key).or_insert_with(|| {
+                rasterize(
+                    font_for(self.font, self.fallbacks, ch),
+                    ch,
+                    self.font_size,
+                    self.subpixel,
+                )
+            });
+
+            draw_glyph(
+                frame,
+                key,
+                metrics,
+                bitmap,
+                pen.x(),
+                baseline,
+                color,
+                style,
+                self.subpixel,
+            );
+
+            pen.advance(ch);
         }
-        width
+    }
+
+    /// Measure text width in pixels: the cells it takes up, as drawn
+    #[allow(dead_code)]
+    pub fn measure_width(&self, text: &str) -> f32 {
+        let columns = crate::util::text::display_width(text);
+        super::geometry::column_offset_px(columns, self.char_width) as f32
     }
 
     /// Draw text with syntax highlighting
@@ -654,12 +865,13 @@ impl<'a> TextPainter<'a> {
             return;
         }
 
-        let mut current_x = x as f32;
+        let mut pen = Pen::new(x, self.char_width);
         let baseline = y as f32 + self.ascent;
 
         let mut token_idx = 0;
 
-        for (col, ch) in text.chars().enumerate() {
+        for ch in text.chars() {
+            let col = pen.column();
             // Advance token_idx past any tokens that end before or at this column
             while token_idx < tokens.len() && tokens[token_idx].end_col <= col {
                 token_idx += 1;
@@ -690,36 +902,181 @@ impl<'a> TextPainter<'a> {
                 self.cache_stats.misses += 1;
             }
 
-            let (metrics, bitmap) = self
-                .glyph_cache
-                .entry(key)
-                .or_insert_with(|| self.font.rasterize(ch, self.font_size));
-
-            let glyph_top = baseline - metrics.height as f32 - metrics.ymin as f32;
-
-            for bitmap_y in 0..metrics.height {
-                for bitmap_x in 0..metrics.width {
-                    let bitmap_idx = bitmap_y * metrics.width + bitmap_x;
-                    if bitmap_idx < bitmap.len() {
-                        let alpha = bitmap[bitmap_idx];
-                        if alpha > 0 {
-                            let px = current_x as isize + bitmap_x as isize + metrics.xmin as isize;
-                            let py = (glyph_top + bitmap_y as f32) as isize;
-
-                            if px >= 0 && py >= 0 {
-                                frame.blend_text_pixel(
-                                    px as usize,
-                                    py as usize,
-                                    color,
-                                    alpha as f32 / 255.0,
-                                );
-                            }
-                        }
+            let (metrics, bitmap) = self.glyph_cache.entry(key).or_insert_with(|| {
+                rasterize(
+                    font_for(self.font, self.fallbacks, ch),
+                    ch,
+                    self.font_size,
+                    self.subpixel,
+                )
+            });
+
+            draw_glyph(
+                frame,
+                key,
+                metrics,
+                bitmap,
+                pen.x(),
+                baseline,
+                color,
+                GlyphStyle::default(),
+                self.subpixel,
+            );
+
+            pen.advance(ch);
+        }
+    }
+}
+
+/// Pen position along a line of text, in cells: one per character, two
+/// for wide characters (CJK, emoji), none for combining marks. Glyphs start
+/// on the pixels of [`column_offset_px`](super::geometry::column_offset_px)
+/// like cursors and selections do, whatever the advance of the font they
+/// came from.
+struct Pen {
+    x: usize,
+    columns: usize,
+    char_width: f32,
+}
+
+impl Pen {
+    fn new(x: usize, char_width: f32) -> Self {
+        Self {
+            x,
+            columns: 0,
+            char_width,
+        }
+    }
+
+    /// Visual column of the next glyph
+    fn column(&self) -> usize {
+        self.columns
+    }
+
+    /// x of the next glyph, rounded by [`draw_glyph`]
+    fn x(&self) -> f32 {
+        let columns = super::geometry::column_offset_px(self.columns, self.char_width);
+        (self.x + columns) as f32
+    }
+
+    fn advance(&mut self, ch: char) {
+        self.columns += crate::util::text::char_display_width(ch);
+    }
+}
+
+/// Rasterize `ch`: one coverage value per pixel, or with `subpixel` three
+/// (red, green, blue), filtered across neighbouring subpixels to soften
+/// color fringes
+fn rasterize(font: &Font, ch: char, font_size: f32, subpixel: bool) -> (Metrics, Vec<u8>) {
+    if !subpixel {
+        return font.rasterize(ch, font_size);
+    }
+    let (metrics, mut bitmap) = font.rasterize_subpixel(ch, font_size);
+    let row_len = metrics.width * 3;
+    if row_len == 0 {
+        return (metrics, bitmap);
+    }
+    let mut source = vec![0u8; row_len];
+    for row in bitmap.chunks_mut(row_len) {
+        source[..row.len()].copy_from_slice(row);
+        for (i, value) in row.iter_mut().enumerate() {
+            let left = if i > 0 { source[i - 1] as u32 } else { 0 };
+            let right = source.get(i + 1).copied().unwrap_or(0) as u32;
+            *value = ((left + source[i] as u32 + right + 1) / 3) as u8;
+        }
+    }
+    (metrics, bitmap)
+}
+
+/// Draw one cached glyph at pen position `pen_x` on `baseline`. With
+/// `subpixel`, `bitmap` holds red, green and blue coverage per pixel.
+#[allow(clippy::too_many_arguments)]
+fn draw_glyph(
+    frame: &mut Frame,
+    key: GlyphCacheKey,
+    metrics: &Metrics,
+    bitmap: &[u8],
+    pen_x: f32,
+    baseline: f32,
+    color: u32,
+    style: GlyphStyle,
+    subpixel: bool,
+) {
+    let glyph_top = baseline - metrics.height as f32 - metrics.ymin as f32;
+    let passes = if style.bold { 2 } else { 1 };
+
+    if frame.is_recording() {
+        if metrics.width == 0 || metrics.height == 0 {
+            return;
+        }
+        let clip = frame.clip_rect();
+        let Some(scene) = frame.scene.as_deref_mut() else {
+            return;
+        };
+        // Same pixel grid as the software path below
+        let x = (pen_x.round() as isize + metrics.xmin as isize) as f32;
+        let y = (glyph_top as isize) as f32;
+        for pass in 0..passes {
+            scene.push(Primitive::Glyph {
+                key,
+                x: x + pass as f32,
+                y,
+                width: metrics.width as f32,
+                height: metrics.height as f32,
+                color,
+                shear: if style.italic { ITALIC_SHEAR } else { 0.0 },
+                baseline,
+                clip,
+            });
+        }
+        return;
+    }
+
+    for bitmap_y in 0..metrics.height {
+        let py = (glyph_top + bitmap_y as f32) as isize;
+        let shear = if style.italic {
+            ((baseline - py as f32) * ITALIC_SHEAR).round() as isize
+        } else {
+            0
+        };
+        for bitmap_x in 0..metrics.width {
+            if subpixel {
+                let offset = (bitmap_y * metrics.width + bitmap_x) * 3;
+                let Some(&[r, g, b]) = bitmap.get(offset..offset + 3) else {
+                    continue;
+                };
+                if r == 0 && g == 0 && b == 0 {
+                    continue;
+                }
+                for pass in 0..passes {
+                    let px = pen_x.round() as isize
+                        + bitmap_x as isize
+                        + metrics.xmin as isize
+                        + shear
+                        + pass;
+                    if px >= 0 && py >= 0 {
+                        frame.blend_subpixel_text_pixel(px as usize, py as usize, color, [r, g, b]);
                     }
                 }
+                continue;
+            }
+            let alpha = bitmap
+                .get(bitmap_y * metrics.width + bitmap_x)
+                .copied()
+                .unwrap_or(0);
+            if alpha == 0 {
+                continue;
+            }
+            for pass in 0..passes {
+                let px = pen_x.round() as isize
+                    + bitmap_x as isize
+                    + metrics.xmin as isize
+                    + shear
+                    + pass;
+                if px >= 0 && py >= 0 {
+                    frame.blend_text_pixel(px as usize, py as usize, color, alpha as f32 / 255.0);
+                }
             }
-
-            current_x += metrics.advance_width;
         }
     }
 }
@@ -966,4 +1323,108 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn recording_frame_records_clipped_primitives_instead_of_pixels() {
+        let font = Font::from_bytes(
+            include_bytes!("../../assets/JetBrainsMono.ttf") as &[u8],
+            fontdue::FontSettings::default(),
+        )
+        .expect("test font should load");
+        let mut glyph_cache = GlyphCache::default();
+        let mut painter = TextPainter::new(&font, &mut glyph_cache, 14.0, 11.0, 8.0, 17);
+        let mut scene = Scene::new(100, 50, Default::default());
+        let mut frame = Frame::recording(&mut scene);
+
+        frame.clear(0xFF101010);
+        frame.set_clip(Rect::new(10.0, 10.0, 50.0, 20.0));
+        frame.fill_rect(Rect::new(0.0, 0.0, 100.0, 50.0), 0xFFFF0000);
+        painter.draw(&mut frame, 12, 12, "A", 0xFFFFFFFF);
+        assert!(frame.is_recording());
+        assert_eq!(frame.get_pixel(20, 20), 0);
+
+        assert_eq!(scene.clear_color, 0xFF101010);
+        assert_eq!(scene.primitives.len(), 2);
+        match &scene.primitives[0] {
+            Primitive::Rect { rect, color } => {
+                assert_eq!(
+                    (rect.x, rect.y, rect.width, rect.height),
+                    (10.0, 10.0, 50.0, 20.0)
+                );
+                assert_eq!(*color, 0xFFFF0000);
+            }
+            other => panic!("expected a rect, got {:?}", other),
+        }
+        match &scene.primitives[1] {
+            Primitive::Glyph { key, clip, .. } => {
+                assert_eq!(key.0, 'A');
+                assert_eq!((clip.x, clip.width), (10.0, 50.0));
+            }
+            other => panic!("expected a glyph, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn glyphs_start_on_cursor_columns_at_fractional_scales() {
+        let font = Font::from_bytes(
+            include_bytes!("../../assets/JetBrainsMono.ttf") as &[u8],
+            fontdue::FontSettings::default(),
+        )
+        .expect("test font should load");
+        let text = "fn main() {}";
+        for scale in [1.0, 1.25, 1.5, 1.75] {
+            let font_size = 14.0 * scale;
+            let char_width = font.rasterize('M', font_size).0.advance_width;
+            let mut glyph_cache = GlyphCache::default();
+            let mut painter =
+                TextPainter::new(&font, &mut glyph_cache, font_size, 11.0, char_width, 17);
+            let mut scene = Scene::new(400, 40, Default::default());
+            let mut frame = Frame::recording(&mut scene);
+            painter.draw(&mut frame, 33, 4, text, 0xFFFFFFFF);
+
+            let glyph_xs: Vec<(char, f32)> = scene
+                .primitives
+                .iter()
+                .filter_map(|primitive| match primitive {
+                    Primitive::Glyph { key, x, .. } => Some((key.0, *x)),
+                    _ => None,
+                })
+                .collect();
+            let columns = text.chars().enumerate().filter(|(_, ch)| *ch != ' ');
+            for ((col, ch), (glyph, x)) in columns.zip(glyph_xs) {
+                assert_eq!(ch, glyph);
+                let xmin = glyph_cache[&(ch, font_size.to_bits())].0.xmin as f32;
+                let cursor_x = super::super::geometry::column_to_pixel_x(col, 0, 33, char_width);
+                assert_eq!(x - xmin, cursor_x as f32, "scale {scale}, column {col}");
+            }
+        }
+    }
+
+    #[test]
+    fn wide_characters_take_two_cells() {
+        let font = Font::from_bytes(
+            include_bytes!("../../assets/JetBrainsMono.ttf") as &[u8],
+            fontdue::FontSettings::default(),
+        )
+        .expect("test font should load");
+        let mut glyph_cache = GlyphCache::default();
+        let mut painter = TextPainter::new(&font, &mut glyph_cache, 14.0, 11.0, 8.0, 17);
+        let mut scene = Scene::new(400, 40, Default::default());
+        let mut frame = Frame::recording(&mut scene);
+        painter.draw(&mut frame, 0, 0, "a中b", 0xFFFFFFFF);
+        assert_eq!(painter.measure_width("a中b"), 32.0);
+
+        let origins: Vec<(char, f32)> = scene
+            .primitives
+            .iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Glyph { key, x, .. } if key.0.is_ascii() => {
+                    Some((key.0, *x - glyph_cache[key].0.xmin as f32))
+                }
+                _ => None,
+            })
+            .collect();
+        // 中 (a missing-glyph box here) takes columns 1 and 2
+        assert_eq!(origins, [('a', 0.0), ('b', 24.0)]);
+    }
 }
@@ -7,8 +7,9 @@
 //! All functions here are pure (no I/O, no side effects) and can be
 //! tested independently of the rendering infrastructure.
 
-use crate::model::editor_area::{EditorGroup, Rect, TabId};
-use crate::model::{AppModel, Document, EditorState, ScaledMetrics, TextViewportMap};
+use crate::menu::MenuItem;
+use crate::model::editor_area::{EditorGroup, Rect, Tab, TabId};
+use crate::model::{AppModel, ContextMenuState, Document, EditorState, ScaledMetrics};
 
 // ============================================================================
 // Layout Constants
@@ -16,6 +17,7 @@ use crate::model::{AppModel, Document, EditorState, ScaledMetrics, TextViewportM
 
 // Re-export TABULATOR_WIDTH from util::text for single source of truth
 pub use crate::util::text::TABULATOR_WIDTH;
+pub use crate::util::text::{ElasticTabs, TabExpander, TabLayout};
 
 // ============================================================================
 // Viewport Sizing Helpers
@@ -49,6 +51,12 @@ use std::borrow::Cow;
 /// assert_eq!(&*expanded, "a   b");  // Tab becomes 3 spaces (to reach column 4)
 /// ```
 pub fn expand_tabs_for_display(text: &str) -> Cow<'_, str> {
+    expand_tabs_with(text, TabLayout::Fixed)
+}
+
+/// [`expand_tabs_for_display`] with tabs expanded by `layout` (elastic
+/// tabstops)
+pub fn expand_tabs_with<'t>(text: &'t str, layout: TabLayout) -> Cow<'t, str> {
     // Fast path: if no tabs, return borrowed reference (no allocation)
     if !text.contains('\t') {
         return Cow::Borrowed(text);
@@ -56,18 +64,16 @@ pub fn expand_tabs_for_display(text: &str) -> Cow<'_, str> {
 
     // Slow path: expand tabs
     let mut result = String::with_capacity(text.len() * 2);
-    let mut visual_col = 0;
+    let mut expander = TabExpander::new(layout);
 
     for ch in text.chars() {
+        let width = expander.advance(ch);
         if ch == '\t' {
-            let spaces = TABULATOR_WIDTH - (visual_col % TABULATOR_WIDTH);
-            for _ in 0..spaces {
+            for _ in 0..width {
                 result.push(' ');
             }
-            visual_col += spaces;
         } else {
             result.push(ch);
-            visual_col += 1;
         }
     }
 
@@ -87,18 +93,7 @@ pub fn expand_tabs_for_display(text: &str) -> Cow<'_, str> {
 /// # Returns
 /// The visual column (screen position) for the given character index.
 pub fn char_col_to_visual_col(text: &str, char_col: usize) -> usize {
-    let mut visual_col = 0;
-    for (i, ch) in text.chars().enumerate() {
-        if i >= char_col {
-            break;
-        }
-        if ch == '\t' {
-            visual_col += TABULATOR_WIDTH - (visual_col % TABULATOR_WIDTH);
-        } else {
-            visual_col += 1;
-        }
-    }
-    visual_col
+    crate::util::text::char_col_to_visual_col(text, char_col)
 }
 
 /// Convert a visual (screen) column position to a character column index.
@@ -115,24 +110,34 @@ pub fn char_col_to_visual_col(text: &str, char_col: usize) -> usize {
 /// The character index corresponding to the given visual column.
 /// If the visual column is past the end of the line, returns the line length.
 pub fn visual_col_to_char_col(text: &str, visual_col: usize) -> usize {
-    let mut current_visual = 0;
-    let mut char_col = 0;
-
-    for ch in text.chars() {
-        if current_visual >= visual_col {
-            return char_col;
-        }
+    crate::util::text::visual_col_to_char_col(text, visual_col)
+}
 
-        if ch == '\t' {
-            let tab_width = TABULATOR_WIDTH - (current_visual % TABULATOR_WIDTH);
-            current_visual += tab_width;
-        } else {
-            current_visual += 1;
-        }
-        char_col += 1;
-    }
+/// Elastic tabstop widths for `first..=last` of `document` when
+/// `elastic_tabstops` is on, otherwise none (fixed tab stops everywhere)
+pub fn elastic_tabs(
+    model: &AppModel,
+    document: &Document,
+    first: usize,
+    last: usize,
+) -> ElasticTabs {
+    if !model.config.elastic_tabstops {
+        return ElasticTabs::default();
+    }
+    ElasticTabs::compute(first, last, document.line_count(), |line| {
+        document.get_line_cow(line)
+    })
+}
 
-    char_col
+/// Pixel offset of the left edge of column `col` in a run of monospace text.
+///
+/// Glyph origins ([`TextPainter`](super::TextPainter)), cursors and selection
+/// edges are all rounded here, once, so at fractional char widths (1.25x and
+/// 1.5x scale) they land on the same pixels. Widths of column spans should be
+/// the difference of two offsets rather than a rounded product.
+#[inline]
+pub fn column_offset_px(col: usize, char_width: f32) -> usize {
+    (col as f32 * char_width).round() as usize
 }
 
 /// Convert a visual column into a viewport-relative pixel x-coordinate.
@@ -147,7 +152,7 @@ pub fn column_to_pixel_x(
     char_width: f32,
 ) -> usize {
     let visible_col = visual_col.saturating_sub(viewport_left);
-    text_start_x + (visible_col as f32 * char_width).round() as usize
+    text_start_x + column_offset_px(visible_col, char_width)
 }
 
 // ============================================================================
@@ -194,6 +199,26 @@ pub struct TabBarTab {
     pub text_x: usize,
     pub text_y: usize,
     pub is_active: bool,
+    pub color: Option<crate::model::TabColor>,
+    /// Drawn in italics
+    pub is_preview: bool,
+}
+
+/// Characters of the file name a pinned tab shows
+pub const PINNED_TAB_TITLE_CHARS: usize = 4;
+
+/// Title drawn on a tab: pinned tabs are compact, showing the start of the
+/// file name without its extension
+fn tab_title(model: &AppModel, tab: &Tab) -> String {
+    let name = get_tab_display_name(model, tab);
+    if !tab.is_pinned {
+        return name;
+    }
+    let stem = match name.rfind('.') {
+        Some(dot) if dot > 0 => &name[..dot],
+        _ => &name,
+    };
+    stem.chars().take(PINNED_TAB_TITLE_CHARS).collect()
 }
 
 #[derive(Debug, Clone)]
@@ -222,7 +247,7 @@ impl TabBarLayout {
         let mut x = metrics.padding_medium;
 
         for tab in &group.tabs {
-            let title_chars = get_tab_display_name(model, tab).chars().count();
+            let title_chars = tab_title(model, tab).chars().count();
             let ideal_width =
                 (title_chars as f32 * char_width).round() as usize + metrics.padding_large * 2;
             spans.push((x, x + ideal_width));
@@ -276,7 +301,7 @@ impl TabBarLayout {
             tabs.push(TabBarTab {
                 index,
                 tab_id: tab.id,
-                title: get_tab_display_name(model, tab),
+                title: tab_title(model, tab),
                 x: visible_x,
                 y: tab_y,
                 width: visible_right.saturating_sub(visible_x),
@@ -284,6 +309,8 @@ impl TabBarLayout {
                 text_x: (tab_x + metrics.padding_large as isize).max(0) as usize,
                 text_y: tab_y + metrics.padding_medium,
                 is_active: index == group.active_tab_index,
+                color: tab.color,
+                is_preview: tab.is_preview,
             });
         }
 
@@ -319,6 +346,99 @@ impl TabBarLayout {
     }
 }
 
+/// One entry of the breadcrumb strip
+#[derive(Debug, Clone)]
+pub struct Breadcrumb {
+    /// Depth of the symbol in the path at the cursor; `None` for the file
+    pub depth: Option<usize>,
+    pub text: String,
+    pub x: usize,
+    pub width: usize,
+}
+
+/// The breadcrumb strip under a group's tab bar: the file name followed by
+/// the symbols the cursor of the group's active editor is in
+#[derive(Debug, Clone)]
+pub struct BreadcrumbLayout {
+    pub rect_x: usize,
+    pub rect_y: usize,
+    pub rect_w: usize,
+    pub rect_h: usize,
+    pub text_y: usize,
+    pub crumbs: Vec<Breadcrumb>,
+    /// X positions of the separators drawn between crumbs
+    pub separators: Vec<usize>,
+}
+
+impl BreadcrumbLayout {
+    pub const SEPARATOR: &'static str = "›";
+
+    /// `None` while breadcrumbs are hidden or the group has no active tab
+    pub fn new(group: &EditorGroup, model: &AppModel, char_width: f32) -> Option<Self> {
+        let metrics = &model.metrics;
+        if metrics.breadcrumb_bar_height == 0 {
+            return None;
+        }
+        let tab = group.active_tab()?;
+        let editor = model.editor_area.editors.get(&tab.editor_id)?;
+
+        let mut texts = vec![(None, get_tab_display_name(model, tab))];
+        let outline = editor
+            .document_id
+            .and_then(|id| model.editor_area.documents.get(&id))
+            .and_then(|doc| doc.outline.as_ref());
+        if let Some(outline) = outline {
+            let path = outline.symbol_path_at(editor.active_cursor().line);
+            texts.extend(path.iter().enumerate().map(|(depth, node)| {
+                (Some(depth), format!("{} {}", node.kind.label(), node.name))
+            }));
+        }
+
+        let rect_x = group.rect.x.round() as usize;
+        let rect_y = group.rect.y.round() as usize + metrics.tab_bar_height;
+        let rect_h = metrics.breadcrumb_bar_height;
+        let text_width = |text: &str| (text.chars().count() as f32 * char_width).round() as usize;
+        let separator_width = text_width(Self::SEPARATOR) + metrics.padding_medium * 2;
+
+        let mut crumbs = Vec::with_capacity(texts.len());
+        let mut separators = Vec::with_capacity(texts.len().saturating_sub(1));
+        let mut x = rect_x + metrics.padding_large;
+        for (depth, text) in texts {
+            if !crumbs.is_empty() {
+                separators.push(x + metrics.padding_medium);
+                x += separator_width;
+            }
+            let width = text_width(&text);
+            crumbs.push(Breadcrumb {
+                depth,
+                text,
+                x,
+                width,
+            });
+            x += width;
+        }
+
+        Some(Self {
+            rect_x,
+            rect_y,
+            rect_w: group.rect.width.round() as usize,
+            rect_h,
+            text_y: rect_y + metrics.padding_small,
+            crumbs,
+            separators,
+        })
+    }
+
+    pub fn crumb_at(&self, x: f64, y: f64) -> Option<&Breadcrumb> {
+        if y < self.rect_y as f64 || y >= (self.rect_y + self.rect_h) as f64 {
+            return None;
+        }
+        self.crumbs
+            .iter()
+            .find(|crumb| x >= crumb.x as f64 && x < (crumb.x + crumb.width) as f64)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct DockHeaderTab {
     pub panel_id: crate::panel::PanelId,
@@ -429,6 +549,69 @@ impl DockHeaderLayout {
     }
 }
 
+/// One panel icon in a collapsed dock's icon strip
+#[derive(Debug, Clone, Copy)]
+pub struct DockStripIcon {
+    pub panel_id: crate::panel::PanelId,
+    pub x: usize,
+    pub y: usize,
+    pub size: usize,
+    pub is_active: bool,
+}
+
+/// Geometry of a collapsed dock: a thin strip with one square icon cell per
+/// panel, stacked vertically for side docks and horizontally for the bottom
+/// dock.
+#[derive(Debug, Clone)]
+pub struct DockIconStripLayout {
+    pub rect: Rect,
+    pub icons: Vec<DockStripIcon>,
+}
+
+impl DockIconStripLayout {
+    pub fn new(dock: &crate::panel::Dock, rect: Rect, metrics: &ScaledMetrics) -> Self {
+        let size = metrics.dock_icon_strip_size;
+        let rect_x = rect.x.round() as usize;
+        let rect_y = rect.y.round() as usize;
+        let (limit, vertical) = match dock.position {
+            crate::panel::DockPosition::Bottom => ((rect.x + rect.width) as usize, false),
+            _ => ((rect.y + rect.height) as usize, true),
+        };
+
+        let mut icons = Vec::with_capacity(dock.panel_ids.len());
+        for (index, panel_id) in dock.panel_ids.iter().copied().enumerate() {
+            let offset = metrics.padding_small + index * size;
+            let (x, y) = if vertical {
+                (rect_x, rect_y + offset)
+            } else {
+                (rect_x + offset, rect_y)
+            };
+            let end = if vertical { y + size } else { x + size };
+            if end > limit {
+                break;
+            }
+            icons.push(DockStripIcon {
+                panel_id,
+                x,
+                y,
+                size,
+                is_active: dock.active_index == Some(index),
+            });
+        }
+
+        Self { rect, icons }
+    }
+
+    pub fn icon_at(&self, x: f64, y: f64) -> Option<&DockStripIcon> {
+        self.icons.iter().find(|icon| {
+            x >= icon.x as f64
+                && x < (icon.x + icon.size) as f64
+                && y >= icon.y as f64
+                && y < (icon.y + icon.size) as f64
+        })
+    }
+}
+
 /// Convert pixel coordinates to document line and column for the focused editor.
 ///
 /// Takes into account the group's position (including sidebar offset), tab bar,
@@ -506,17 +689,16 @@ pub fn pixel_to_line_and_visual_column_in_group(
     editor: &EditorState,
     document: &Document,
 ) -> (usize, usize) {
-    let viewport = TextViewportMap::new(&editor.viewport, document.line_count());
-    let local_x = x - group_rect.x as f64;
+    let viewport = editor.viewport_map(document);
     let local_y = y - group_rect.y as f64;
 
-    let text_x = crate::model::text_start_x_scaled(char_width, &model.metrics).round() as f64;
+    let text_x = text_area_x(group_rect, char_width, model) as f64;
 
-    let text_start_y = model.metrics.tab_bar_height as f64;
+    let text_start_y = model.metrics.header_height() as f64;
     let adjusted_y = (local_y - text_start_y).max(0.0);
     let line = viewport.doc_line_for_pixel_y(adjusted_y, line_height);
 
-    let x_offset = local_x - text_x;
+    let x_offset = x - text_x;
     let visual_column = viewport.visual_column_for_x_offset(x_offset, char_width);
 
     (line, visual_column)
@@ -538,21 +720,22 @@ pub fn pixel_to_cursor_in_group(
     editor: &EditorState,
     document: &Document,
 ) -> (usize, usize) {
-    let viewport = TextViewportMap::new(&editor.viewport, document.line_count());
-    let local_x = x - group_rect.x as f64;
+    let viewport = editor.viewport_map(document);
     let local_y = y - group_rect.y as f64;
 
-    let text_x = crate::model::text_start_x_scaled(char_width, &model.metrics).round() as f64;
-    let text_start_y = model.metrics.tab_bar_height as f64;
+    let text_x = text_area_x(group_rect, char_width, model) as f64;
+    let text_start_y = model.metrics.header_height() as f64;
     let adjusted_y = (local_y - text_start_y).max(0.0);
     let line = viewport.doc_line_for_pixel_y(adjusted_y, line_height);
 
-    let x_offset = local_x - text_x;
+    let x_offset = x - text_x;
     let visual_column = viewport.visual_column_for_x_offset(x_offset, char_width);
 
     let line_text = document.get_line(line).unwrap_or_default();
     let line_text_trimmed = super::helpers::trim_line_ending(&line_text);
-    let column = visual_col_to_char_col(line_text_trimmed, visual_column);
+    let tabs = elastic_tabs(model, document, line, line);
+    let column = super::line_layout::LineLayout::for_line(document, &tabs, line)
+        .char_col(line_text_trimmed, visual_column);
 
     let line_len = document.line_length(line);
     let column = column.min(line_len);
@@ -560,6 +743,15 @@ pub fn pixel_to_cursor_in_group(
     (line, column)
 }
 
+/// Window x where the text area of the group at `group_rect` starts (the
+/// same value as [`GroupLayout::text_start_x`])
+#[inline]
+fn text_area_x(group_rect: &Rect, char_width: f32, model: &AppModel) -> usize {
+    (group_rect.x + crate::model::text_start_x_scaled(char_width, &model.metrics))
+        .round()
+        .max(0.0) as usize
+}
+
 // ============================================================================
 // GroupLayout - Unified Layout Computation
 // ============================================================================
@@ -579,10 +771,12 @@ pub fn pixel_to_cursor_in_group(
 pub struct GroupLayout {
     /// The group's rect in window coordinates (from compute_layout_scaled)
     pub group_rect: Rect,
-    /// Content area (excludes tab bar), in window coordinates
+    /// Content area (excludes tab bar and breadcrumbs), in window coordinates
     pub content_rect: Rect,
     /// Tab bar height (scaled for DPI)
     pub tab_bar_height: usize,
+    /// Breadcrumb strip under the tab bar, when breadcrumbs are shown
+    pub breadcrumb_rect: Option<Rect>,
     /// Gutter border X position (absolute window coordinate)
     pub gutter_right_x: usize,
     /// X coordinate where text content starts (absolute window coordinate)
@@ -599,23 +793,36 @@ impl GroupLayout {
         let metrics = &model.metrics;
 
         let tab_bar_height = metrics.tab_bar_height;
+        let header_height = metrics.header_height();
         let content_rect = Rect::new(
             group_rect.x,
-            group_rect.y + tab_bar_height as f32,
+            group_rect.y + header_height as f32,
             group_rect.width,
-            (group_rect.height - tab_bar_height as f32).max(0.0),
+            (group_rect.height - header_height as f32).max(0.0),
         );
+        let breadcrumb_rect = (metrics.breadcrumb_bar_height > 0).then(|| {
+            Rect::new(
+                group_rect.x,
+                group_rect.y + tab_bar_height as f32,
+                group_rect.width,
+                metrics.breadcrumb_bar_height as f32,
+            )
+        });
 
-        let rect_x = group_rect.x.round() as usize;
-        let gutter_right_x =
-            rect_x + crate::model::gutter_border_x_scaled(char_width, metrics).round() as usize;
-        let text_start_x =
-            rect_x + crate::model::text_start_x_scaled(char_width, metrics).round() as usize;
+        // Offsets are added to the unrounded group x and rounded once;
+        // rounding both separately puts the gutter border and text a pixel
+        // off in groups at fractional positions
+        let gutter_right_x = (group_rect.x
+            + crate::model::gutter_border_x_scaled(char_width, metrics))
+        .round()
+        .max(0.0) as usize;
+        let text_start_x = text_area_x(&group_rect, char_width, model);
 
         Self {
             group_rect,
             content_rect,
             tab_bar_height,
+            breadcrumb_rect,
             gutter_right_x,
             text_start_x,
         }
@@ -1282,13 +1489,16 @@ pub struct FindReplaceWidgets {
     pub find_input: usize,
     pub replace_label: Option<usize>,
     pub replace_input: Option<usize>,
+    /// Match counts, under the inputs
+    pub matches: usize,
 }
 
 /// Compute layout for the Find/Replace modal.
 ///
 /// Height is derived automatically from the content. In find-only mode,
 /// no "Find:" label is shown (the title says "Find"). In replace mode,
-/// both "Find:" and "Replace:" labels are shown.
+/// both "Find:" and "Replace:" labels are shown. A row of match counts
+/// follows the inputs.
 pub fn find_replace_layout(
     window_width: usize,
     window_height: usize,
@@ -1323,6 +1533,9 @@ pub fn find_replace_layout(
         replace_input = None;
     }
 
+    v.gap(ModalSpacing::GAP_SM);
+    let matches = v.push(line_height);
+
     let layout = ModalLayout::build(v, modal_width, window_width, window_height);
     let widgets = FindReplaceWidgets {
         title,
@@ -1330,6 +1543,7 @@ pub fn find_replace_layout(
         find_input,
         replace_label,
         replace_input,
+        matches,
     };
 
     (layout, widgets)
@@ -1401,23 +1615,29 @@ pub fn command_palette_layout(
     (layout, CommandPaletteWidgets { title, input, list })
 }
 
-/// Layout indices for FileFinder modal widgets
+/// Layout indices for file list modal widgets (Quick Open, recent files,
+/// backlinks)
 pub struct FileFinderWidgets {
     pub title: usize,
     pub input: usize,
     pub list: Option<usize>,
+    /// File preview below the list (Quick Open only)
+    pub preview: Option<usize>,
 }
 
-/// Compute layout for the File Finder modal.
+/// Compute layout for the file list modals.
 ///
 /// `has_query` should be true when the input is non-empty, so we reserve
 /// space for the "No files match" message even when `list_items` is 0.
+/// `preview_rows` reserves lines for a preview of the selected file; pass 0
+/// for none.
 pub fn file_finder_layout(
     window_width: usize,
     window_height: usize,
     line_height: usize,
     list_items: usize,
     has_query: bool,
+    preview_rows: usize,
 ) -> (ModalLayout, FileFinderWidgets) {
     let modal_width = (window_width as f32 * 0.7).clamp(500.0, 900.0) as usize;
     let pad = ModalSpacing::PAD;
@@ -1445,9 +1665,23 @@ pub fn file_finder_layout(
     } else {
         None
     };
+    let preview = if preview_rows > 0 {
+        v.gap(ModalSpacing::GAP_MD);
+        Some(v.push(preview_rows * line_height))
+    } else {
+        None
+    };
 
     let layout = ModalLayout::build(v, modal_width, window_width, window_height);
-    (layout, FileFinderWidgets { title, input, list })
+    (
+        layout,
+        FileFinderWidgets {
+            title,
+            input,
+            list,
+            preview,
+        },
+    )
 }
 
 /// Layout indices for ThemePicker modal widgets
@@ -1512,6 +1746,148 @@ pub fn theme_picker_layout(
     (layout, ThemePickerWidgets { title, list })
 }
 
+/// Layout indices for EditReview modal widgets
+pub struct EditReviewWidgets {
+    pub title: usize,
+    pub list: usize,
+    pub hint: usize,
+}
+
+/// Cap on diff rows shown at once in the edit review modal. Shared between
+/// rendering, hit-testing and the update code that scrolls the selected
+/// hunk into view.
+pub const EDIT_REVIEW_MAX_VISIBLE_ROWS: usize = 18;
+
+/// Compute layout for the Edit Review modal.
+///
+/// `total_rows` is the number of diff rows (hunk headers, context, removed
+/// and added lines); the list is capped at `EDIT_REVIEW_MAX_VISIBLE_ROWS`.
+pub fn edit_review_layout(
+    window_width: usize,
+    window_height: usize,
+    line_height: usize,
+    total_rows: usize,
+) -> (ModalLayout, EditReviewWidgets) {
+    let modal_width = (window_width as f32 * 0.8).clamp(500.0, 1000.0) as usize;
+    let pad = ModalSpacing::PAD;
+    let content_width = modal_width.saturating_sub(pad * 2);
+
+    let mut v = VStack::new(content_width);
+    let title = v.push(line_height);
+    v.gap(ModalSpacing::GAP_MD);
+    let list = v.push(total_rows.clamp(1, EDIT_REVIEW_MAX_VISIBLE_ROWS) * line_height);
+    v.gap(ModalSpacing::GAP_MD);
+    let hint = v.push(line_height);
+
+    let layout = ModalLayout::build(v, modal_width, window_width, window_height);
+    (layout, EditReviewWidgets { title, list, hint })
+}
+
+/// Layout indices for button prompt modal widgets (UnsavedChanges,
+/// DeletedFile, FileChanged, WorkspaceTrust)
+pub struct ButtonPromptWidgets {
+    pub title: usize,
+    pub message: usize,
+    pub buttons: usize,
+}
+
+/// Horizontal padding inside each prompt button, in characters
+pub const PROMPT_BUTTON_PAD_CHARS: usize = 2;
+
+/// Compute layout for a prompt with a title, a message line and a row of
+/// buttons.
+pub fn button_prompt_layout(
+    window_width: usize,
+    window_height: usize,
+    line_height: usize,
+) -> (ModalLayout, ButtonPromptWidgets) {
+    let modal_width = (window_width as f32 * 0.5).clamp(340.0, 460.0) as usize;
+    let pad = ModalSpacing::PAD;
+    let content_width = modal_width.saturating_sub(pad * 2);
+
+    let mut v = VStack::new(content_width);
+    let title = v.push(line_height);
+    v.gap(ModalSpacing::GAP_SM);
+    let message = v.push(line_height);
+    v.gap(ModalSpacing::GAP_MD);
+    let buttons = v.push(ModalLayout::input_height(line_height));
+
+    let layout = ModalLayout::build(v, modal_width, window_width, window_height);
+    (
+        layout,
+        ButtonPromptWidgets {
+            title,
+            message,
+            buttons,
+        },
+    )
+}
+
+/// Label of a context menu row, `None` for separators
+pub fn context_menu_label(item: &MenuItem) -> Option<&'static str> {
+    match item {
+        MenuItem::Command { label, .. } => Some(label),
+        MenuItem::Standard(_) | MenuItem::Separator => None,
+    }
+}
+
+/// Compute layout for a context menu: one widget per item, with the menu's
+/// top-left corner at the click, moved back inside the window where it
+/// would overflow. Item widgets span the menu's width; labels are inset by
+/// `ModalSpacing::INPUT_PAD_X`.
+pub fn context_menu_layout(
+    state: &ContextMenuState,
+    window_width: usize,
+    window_height: usize,
+    line_height: usize,
+    char_width: f32,
+) -> ModalLayout {
+    let pad_y = ModalSpacing::GAP_SM;
+    let row_height = line_height + ModalSpacing::GAP_SM;
+    let separator_height = ModalSpacing::GAP_MD + 1;
+
+    let label_chars = state
+        .items
+        .iter()
+        .filter_map(context_menu_label)
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let item_width =
+        (label_chars as f32 * char_width).round() as usize + ModalSpacing::INPUT_PAD_X * 4;
+    let w = item_width + 2;
+    let height = |item: &MenuItem| match context_menu_label(item) {
+        Some(_) => row_height,
+        None => separator_height,
+    };
+    let h = state.items.iter().map(height).sum::<usize>() + pad_y * 2;
+
+    let x = state.position.0.min(window_width.saturating_sub(w));
+    let y = state.position.1.min(window_height.saturating_sub(h));
+    let mut item_y = y + pad_y;
+    let widgets = state
+        .items
+        .iter()
+        .map(|item| {
+            let rect = WidgetRect {
+                x: x + 1,
+                y: item_y,
+                w: item_width,
+                h: height(item),
+            };
+            item_y += rect.h;
+            rect
+        })
+        .collect();
+    ModalLayout {
+        x,
+        y,
+        w,
+        h,
+        widgets,
+    }
+}
+
 // ============================================================================
 // Dock Geometry
 // ============================================================================
@@ -1547,8 +1923,8 @@ impl WindowLayout {
             .filter(|ws| ws.sidebar_visible)
             .map(|ws| ws.sidebar_width(model.metrics.scale_factor))
             .unwrap_or(0.0);
-        let right_dock_width = model.dock_layout.right.size(model.metrics.scale_factor);
-        let bottom_dock_height = model.dock_layout.bottom.size(model.metrics.scale_factor);
+        let right_dock_width = model.dock_layout.right.size(&model.metrics);
+        let bottom_dock_height = model.dock_layout.bottom.size(&model.metrics);
         let side_panel_height = (content_height - bottom_dock_height).max(0.0);
 
         let content_rect = Rect::new(0.0, 0.0, window_width, content_height);
@@ -1656,6 +2032,94 @@ pub fn binary_placeholder_layout(
     }
 }
 
+// ============================================================================
+// Welcome Layout
+// ============================================================================
+
+/// Title shown at the top of the welcome tab.
+pub const WELCOME_TITLE: &str = "Welcome to Token";
+
+/// Heading of the keybinding cheatsheet on the welcome tab.
+pub const WELCOME_CHEATSHEET_TITLE: &str = "Keyboard Shortcuts";
+
+/// Maximum width of the welcome tab's centered column, in characters.
+const WELCOME_COLUMN_CHARS: f32 = 64.0;
+
+/// Positions of everything on the welcome tab.
+///
+/// Entries are grouped by section, each group preceded by its heading; the
+/// cheatsheet follows the last group. Shared by rendering and hit-testing.
+#[derive(Debug, Clone)]
+pub struct WelcomeLayout {
+    /// Left edge and width of the centered column
+    pub column_x: usize,
+    pub column_width: usize,
+    /// Baseline row of the title
+    pub title_y: usize,
+    /// Section headings as `(title, y)`
+    pub headings: Vec<(&'static str, usize)>,
+    /// Row rect of each entry, in `WelcomeState::items` order
+    pub item_rects: Vec<Rect>,
+    /// Y of the cheatsheet heading
+    pub cheatsheet_y: usize,
+    /// Height of entry and cheatsheet rows
+    pub row_height: usize,
+}
+
+impl WelcomeLayout {
+    pub fn new(
+        content_rect: Rect,
+        items: &[crate::welcome::WelcomeItem],
+        line_height: usize,
+        char_width: f32,
+        metrics: &ScaledMetrics,
+    ) -> Self {
+        let max_width = (content_rect.width as usize).saturating_sub(metrics.padding_large * 2);
+        let column_width = ((WELCOME_COLUMN_CHARS * char_width) as usize).min(max_width);
+        let column_x = content_rect.x as usize + (content_rect.width as usize - column_width) / 2;
+        let row_height = line_height + metrics.padding_medium;
+        let section_gap = line_height;
+
+        let title_y = content_rect.y as usize + line_height * 2;
+        let mut y = title_y + line_height + section_gap;
+        let mut headings = Vec::new();
+        let mut item_rects = Vec::with_capacity(items.len());
+        let mut section = None;
+        for item in items {
+            if section != Some(item.section) {
+                if section.is_some() {
+                    y += section_gap;
+                }
+                section = Some(item.section);
+                headings.push((item.section.title(), y));
+                y += row_height;
+            }
+            item_rects.push(Rect::new(
+                column_x as f32,
+                y as f32,
+                column_width as f32,
+                row_height as f32,
+            ));
+            y += row_height;
+        }
+
+        Self {
+            column_x,
+            column_width,
+            title_y,
+            headings,
+            item_rects,
+            cheatsheet_y: y + section_gap,
+            row_height,
+        }
+    }
+
+    /// Index of the entry under a point
+    pub fn item_at(&self, x: f32, y: f32) -> Option<usize> {
+        self.item_rects.iter().position(|rect| rect.contains(x, y))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1680,6 +2144,7 @@ mod tests {
             group_rect: Rect::new(0.0, 0.0, 200.0, 120.0),
             content_rect: Rect::new(0.0, 24.0, 200.0, 96.0),
             tab_bar_height: 24,
+            breadcrumb_rect: None,
             gutter_right_x: 48,
             text_start_x: 60,
         };
@@ -1745,6 +2210,47 @@ mod tests {
         assert_eq!(layout.tabs[0].x + layout.tabs[0].width, right_edge);
     }
 
+    #[test]
+    fn test_welcome_layout_groups_items_under_headings() {
+        use crate::welcome::{WelcomeAction, WelcomeItem, WelcomeSection};
+
+        let item = |section, label: &str| WelcomeItem {
+            section,
+            label: label.to_string(),
+            detail: String::new(),
+            action: WelcomeAction::NewFile,
+        };
+        let items = vec![
+            item(WelcomeSection::Start, "New File"),
+            item(WelcomeSection::Start, "Open File…"),
+            item(WelcomeSection::RecentFiles, "main.rs"),
+        ];
+        let metrics = ScaledMetrics::new(1.0);
+        let layout = WelcomeLayout::new(
+            Rect::new(0.0, 30.0, 800.0, 500.0),
+            &items,
+            20,
+            8.0,
+            &metrics,
+        );
+
+        assert_eq!(layout.headings.len(), 2);
+        assert_eq!(layout.item_rects.len(), 3);
+        // Column is centered and capped in width
+        assert_eq!(layout.column_width, 512);
+        assert_eq!(layout.column_x, 144);
+        // The recent files heading sits between the two sections
+        let (_, recent_y) = layout.headings[1];
+        assert!(recent_y as f32 > layout.item_rects[1].y);
+        assert!((recent_y as f32) < layout.item_rects[2].y);
+        assert!(layout.cheatsheet_y as f32 >= layout.item_rects[2].y + 20.0);
+
+        let row = layout.item_rects[2];
+        assert_eq!(layout.item_at(row.x + 5.0, row.y + 1.0), Some(2));
+        assert_eq!(layout.item_at(row.x - 5.0, row.y + 1.0), None);
+        assert_eq!(layout.item_at(row.x + 5.0, recent_y as f32 + 1.0), None);
+    }
+
     #[test]
     fn test_dock_header_layout_splits_header_and_content() {
         let metrics = ScaledMetrics::new(1.0);
@@ -1788,6 +2294,29 @@ mod tests {
         assert!(!layout.is_in_content(10.0, layout.rect_y as f64 + 2.0));
     }
 
+    #[test]
+    fn test_dock_icon_strip_stacks_icons_along_the_dock() {
+        let metrics = ScaledMetrics::new(1.0);
+        let mut dock = crate::panel::Dock::new(crate::panel::DockPosition::Right);
+        dock.register_panel(crate::panel::PanelId::OUTLINE);
+        dock.register_panel(crate::panel::PanelId::AI_CHAT);
+        dock.active_index = Some(1);
+
+        let layout = DockIconStripLayout::new(&dock, Rect::new(772.0, 0.0, 28.0, 500.0), &metrics);
+        assert_eq!(layout.icons.len(), 2);
+        assert_eq!(layout.icons[0].x, 772);
+        assert_eq!(layout.icons[1].y, layout.icons[0].y + 28);
+        assert!(layout.icons[1].is_active);
+
+        let second = layout.icons[1];
+        let hit = layout.icon_at(second.x as f64 + 5.0, second.y as f64 + 5.0);
+        assert_eq!(
+            hit.map(|icon| icon.panel_id),
+            Some(crate::panel::PanelId::AI_CHAT)
+        );
+        assert!(layout.icon_at(780.0, 400.0).is_none());
+    }
+
     #[test]
     fn test_window_layout_editor_area_accounts_for_docks() {
         use crate::panel::DockPosition;
@@ -1825,6 +2354,22 @@ mod tests {
         assert_eq!(visual_col_to_char_col("a\tb", 4), 2); // visual 4 is 'b' which is char 2
     }
 
+    #[test]
+    fn test_elastic_tabs_follow_config() {
+        let mut model = crate::model::AppModel::new(400, 300, 1.0, vec![]);
+        let document = Document::with_text("a\tb\nlonger\tc\n\nx\ty");
+
+        let fixed = elastic_tabs(&model, &document, 0, 3);
+        assert_eq!(expand_tabs_with("a\tb", fixed.layout(0)), "a   b");
+
+        model.config.elastic_tabstops = true;
+        let tabs = elastic_tabs(&model, &document, 0, 3);
+        assert_eq!(expand_tabs_with("a\tb", tabs.layout(0)), "a       b");
+        assert_eq!(expand_tabs_with("longer\tc", tabs.layout(1)), "longer  c");
+        // A line without tabs ends the block
+        assert_eq!(expand_tabs_with("x\ty", tabs.layout(3)), "x   y");
+    }
+
     #[test]
     fn test_crlf_cursor_column_math_matches_rendered_text() {
         // Regression test for a CRLF cursor-placement bug: `get_line_cow`
@@ -1862,6 +2407,90 @@ mod tests {
         assert_eq!(column_to_pixel_x(3, 1, 100, 7.5), 115);
     }
 
+    /// Scale factors with fractional char widths (8.4px at 1x)
+    const FRACTIONAL_SCALES: [f64; 5] = [1.0, 1.25, 1.5, 1.75, 2.0];
+
+    #[test]
+    fn column_spans_tile_at_fractional_scales() {
+        for scale in FRACTIONAL_SCALES {
+            let char_width = 8.4 * scale as f32;
+            for col in 0..200 {
+                let left = column_to_pixel_x(col, 0, 37, char_width);
+                let right = column_to_pixel_x(col + 1, 0, 37, char_width);
+                let width = (right - left) as f32;
+                assert!(
+                    width == char_width.floor() || width == char_width.ceil(),
+                    "scale {scale}: column {col} is {width}px wide"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn group_layout_rounds_offsets_once_at_fractional_scales() {
+        for scale in FRACTIONAL_SCALES {
+            let mut model = crate::model::AppModel::new(800, 600, scale, vec![]);
+            let group_id = model.editor_area.focused_group_id;
+            let group_rect = Rect::new(300.4, 20.0, 400.0, 300.0);
+            model.editor_area.groups.get_mut(&group_id).unwrap().rect = group_rect;
+            let char_width = 8.4 * scale as f32;
+
+            let group = model.editor_area.groups.get(&group_id).unwrap();
+            let layout = GroupLayout::new(group, &model, char_width);
+            let text_start =
+                group_rect.x + crate::model::text_start_x_scaled(char_width, &model.metrics);
+            let gutter_border =
+                group_rect.x + crate::model::gutter_border_x_scaled(char_width, &model.metrics);
+            assert_eq!(layout.text_start_x, text_start.round() as usize, "{scale}");
+            assert_eq!(
+                layout.gutter_right_x,
+                gutter_border.round() as usize,
+                "{scale}"
+            );
+            assert!(
+                layout.text_start_x >= layout.gutter_right_x + model.metrics.border_width,
+                "scale {scale}: text overlaps the gutter border"
+            );
+        }
+    }
+
+    #[test]
+    fn clicks_land_in_the_clicked_column_at_fractional_scales() {
+        for scale in FRACTIONAL_SCALES {
+            let mut model = crate::model::AppModel::new(800, 600, scale, vec![]);
+            *model.document_mut() = crate::model::Document::with_text("abcdefghijklmnop\n");
+            let group_id = model.editor_area.focused_group_id;
+            let group_rect = Rect::new(150.6, 0.0, 600.0, 400.0);
+            model.editor_area.groups.get_mut(&group_id).unwrap().rect = group_rect;
+            let char_width = 8.4 * scale as f32;
+
+            let group = model.editor_area.groups.get(&group_id).unwrap();
+            let layout = GroupLayout::new(group, &model, char_width);
+            let y = layout.content_y() as f64 + 2.0;
+            for col in 0..16 {
+                let left = column_to_pixel_x(col, 0, layout.text_start_x, char_width) as f64;
+                let right = column_to_pixel_x(col + 1, 0, layout.text_start_x, char_width) as f64;
+                let hit = |x: f64| {
+                    pixel_to_cursor_in_group(
+                        x,
+                        y,
+                        char_width,
+                        model.line_height as f64,
+                        &group_rect,
+                        &model,
+                        model.editor(),
+                        model.document(),
+                    )
+                    .1
+                };
+                // Left half of a character puts the cursor before it, the
+                // right half after it
+                assert_eq!(hit(left + 1.0), col, "scale {scale}, column {col}");
+                assert_eq!(hit(right - 1.0), col + 1, "scale {scale}, column {col}");
+            }
+        }
+    }
+
     // ====================================================================
     // VStack / ModalLayout tests
     // ====================================================================
@@ -1967,11 +2596,11 @@ mod tests {
         let lh = 20;
         let (layout, w) = find_replace_layout(1000, 800, lh, false);
 
-        // Find-only: title + find_input (no labels)
+        // Find-only: title + find_input + matches (no labels)
         assert!(w.find_label.is_none());
         assert!(w.replace_label.is_none());
         assert!(w.replace_input.is_none());
-        assert_eq!(layout.widgets.len(), 2);
+        assert_eq!(layout.widgets.len(), 3);
     }
 
     #[test]
@@ -1979,16 +2608,18 @@ mod tests {
         let lh = 20;
         let (layout, w) = find_replace_layout(1000, 800, lh, true);
 
-        // Replace mode: title + find_label + find_input + replace_label + replace_input
+        // Replace mode: title + find_label + find_input + replace_label +
+        // replace_input + matches
         assert!(w.find_label.is_some());
         assert!(w.replace_label.is_some());
         assert!(w.replace_input.is_some());
-        assert_eq!(layout.widgets.len(), 5);
+        assert_eq!(layout.widgets.len(), 6);
 
-        // Replace input is below find input
+        // Replace input is below find input, match counts below both
         let find_input = layout.widget(w.find_input);
         let repl_input = layout.widget(w.replace_input.unwrap());
         assert!(repl_input.y > find_input.y + find_input.h);
+        assert!(layout.widget(w.matches).y > repl_input.y + repl_input.h);
     }
 
     #[test]
@@ -2026,7 +2657,7 @@ mod tests {
     #[test]
     fn test_file_finder_layout_empty_no_query() {
         let lh = 20;
-        let (_, w) = file_finder_layout(1000, 800, lh, 0, false);
+        let (_, w) = file_finder_layout(1000, 800, lh, 0, false, 0);
 
         // No query, no results -> no list area
         assert!(w.list.is_none());
@@ -2035,7 +2666,7 @@ mod tests {
     #[test]
     fn test_file_finder_layout_empty_with_query() {
         let lh = 20;
-        let (layout, w) = file_finder_layout(1000, 800, lh, 0, true);
+        let (layout, w) = file_finder_layout(1000, 800, lh, 0, true, 0);
 
         // Has query but no results -> 1 row for "No files match" message
         assert!(w.list.is_some());
@@ -2115,6 +2746,37 @@ mod tests {
         assert!(!layout.is_on_chevron(0, 725.0));
     }
 
+    #[test]
+    fn test_todo_panel_layout_reserves_summary_row() {
+        let metrics = ScaledMetrics::new(1.0);
+        let layout = TodoPanelLayout::new(Rect::new(0.0, 400.0, 800.0, 176.0), &metrics);
+        let row = metrics.file_tree_row_height as f32;
+
+        assert_eq!(layout.summary_rect.height, row);
+        assert_eq!(layout.list_rect.y, 400.0 + row);
+        assert_eq!(layout.row_index_at_y(400.0, 0), None);
+        assert_eq!(layout.row_index_at_y(400.0 + row, 2), Some(2));
+        assert_eq!(layout.row_index_at_y(400.0 + row * 2.0, 2), Some(3));
+    }
+
+    #[test]
+    fn test_ai_chat_panel_layout_puts_input_below_transcript() {
+        let metrics = ScaledMetrics::new(1.0);
+        let content = Rect::new(600.0, 40.0, 200.0, 300.0);
+        let layout = AiChatPanelLayout::new(content, &metrics);
+
+        let input_bottom = layout.input_rect.y + layout.input_rect.h;
+        assert!(input_bottom <= 340);
+        assert!(
+            layout.input_rect.y as f32 >= layout.transcript_rect.y + layout.transcript_rect.height
+        );
+        assert_eq!(
+            layout.visible_rows(),
+            (layout.transcript_rect.height / metrics.file_tree_row_height as f32) as usize
+        );
+        assert_eq!(layout.max_cols(10.0), layout.text_width / 10);
+    }
+
     #[test]
     fn test_preview_pane_layout_splits_header_and_hosted_content() {
         let metrics = ScaledMetrics::new(1.0);
@@ -2222,6 +2884,283 @@ impl OutlinePanelLayout {
     }
 }
 
+/// Shared layout for the TODO list panel content area.
+///
+/// The first row holds per-tag counts; the remaining rows list file headers
+/// and their items.
+#[derive(Debug, Clone, Copy)]
+pub struct TodoPanelLayout {
+    /// Summary row with per-tag counts.
+    pub summary_rect: Rect,
+    /// Scrollable list area below the summary row.
+    pub list_rect: Rect,
+    /// Row height in pixels.
+    pub row_height: usize,
+    /// Left padding for row text.
+    pub text_x: usize,
+    /// Extra indent for item rows under a file header.
+    pub item_indent: usize,
+    /// Vertical text padding inside a row.
+    pub text_top_padding: usize,
+}
+
+impl TodoPanelLayout {
+    /// Build TODO panel geometry from the dock content rectangle and scaled metrics.
+    pub fn new(content_rect: Rect, metrics: &ScaledMetrics) -> Self {
+        let row_height = metrics.file_tree_row_height;
+        let summary_height = (row_height as f32).min(content_rect.height);
+        Self {
+            summary_rect: Rect::new(
+                content_rect.x,
+                content_rect.y,
+                content_rect.width,
+                summary_height,
+            ),
+            list_rect: Rect::new(
+                content_rect.x,
+                content_rect.y + summary_height,
+                content_rect.width,
+                content_rect.height - summary_height,
+            ),
+            row_height,
+            text_x: content_rect.x as usize + metrics.padding_large,
+            item_indent: metrics.file_tree_indent as usize,
+            text_top_padding: metrics.padding_small,
+        }
+    }
+
+    /// Number of whole list rows that fit below the summary row.
+    #[inline]
+    pub fn visible_capacity(&self) -> usize {
+        if self.row_height == 0 {
+            0
+        } else {
+            (self.list_rect.height / self.row_height as f32).max(0.0) as usize
+        }
+    }
+
+    /// Resolve a mouse y-coordinate to a flattened row index.
+    #[inline]
+    pub fn row_index_at_y(&self, y: f32, scroll_offset: usize) -> Option<usize> {
+        if self.row_height == 0
+            || y < self.list_rect.y
+            || y >= self.list_rect.y + self.list_rect.height
+        {
+            return None;
+        }
+
+        let visual_row = ((y - self.list_rect.y) / self.row_height as f32) as usize;
+        Some(scroll_offset.saturating_add(visual_row))
+    }
+}
+
+/// Shared layout for the workspace search panel: the query input with its
+/// option toggles, a summary row, then results grouped by file.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchPanelLayout {
+    /// Query input box.
+    pub input_rect: WidgetRect,
+    /// Left edge of the option toggles to the right of the input.
+    pub options_x: usize,
+    /// Summary row with the result counts.
+    pub summary_rect: Rect,
+    /// Scrollable result list below the summary row.
+    pub list_rect: Rect,
+    /// Row height in pixels.
+    pub row_height: usize,
+    /// Left padding for row text.
+    pub text_x: usize,
+    /// Extra indent for match rows under a file header.
+    pub item_indent: usize,
+    /// Vertical text padding inside a row.
+    pub text_top_padding: usize,
+}
+
+impl SearchPanelLayout {
+    /// Characters taken by the option toggles ("Aa ab .*").
+    pub const OPTIONS_CHARS: usize = 8;
+
+    /// Build search panel geometry from the dock content rectangle and scaled metrics.
+    pub fn new(content_rect: Rect, metrics: &ScaledMetrics, char_width: f32) -> Self {
+        let row_height = metrics.file_tree_row_height;
+        let margin = metrics.padding_medium;
+        let input_h = row_height + metrics.padding_small * 2;
+        let input_area_h = ((input_h + margin * 2) as f32).min(content_rect.height);
+        let options_w = (Self::OPTIONS_CHARS as f32 * char_width) as usize + margin;
+        let summary_height = (row_height as f32).min((content_rect.height - input_area_h).max(0.0));
+        let list_y = content_rect.y + input_area_h + summary_height;
+
+        Self {
+            input_rect: WidgetRect {
+                x: content_rect.x as usize + margin,
+                y: content_rect.y as usize + margin,
+                w: (content_rect.width as usize).saturating_sub(margin * 2 + options_w),
+                h: input_h,
+            },
+            options_x: (content_rect.x + content_rect.width) as usize
+                - options_w.min(content_rect.width as usize),
+            summary_rect: Rect::new(
+                content_rect.x,
+                content_rect.y + input_area_h,
+                content_rect.width,
+                summary_height,
+            ),
+            list_rect: Rect::new(
+                content_rect.x,
+                list_y,
+                content_rect.width,
+                (content_rect.y + content_rect.height - list_y).max(0.0),
+            ),
+            row_height,
+            text_x: content_rect.x as usize + metrics.padding_large,
+            item_indent: metrics.file_tree_indent as usize,
+            text_top_padding: metrics.padding_small,
+        }
+    }
+
+    /// Number of whole result rows that fit below the summary row.
+    #[inline]
+    pub fn visible_capacity(&self) -> usize {
+        if self.row_height == 0 {
+            0
+        } else {
+            (self.list_rect.height / self.row_height as f32).max(0.0) as usize
+        }
+    }
+
+    /// Resolve a mouse y-coordinate to a flattened row index.
+    #[inline]
+    pub fn row_index_at_y(&self, y: f32, scroll_offset: usize) -> Option<usize> {
+        if self.row_height == 0
+            || y < self.list_rect.y
+            || y >= self.list_rect.y + self.list_rect.height
+        {
+            return None;
+        }
+
+        let visual_row = ((y - self.list_rect.y) / self.row_height as f32) as usize;
+        Some(scroll_offset.saturating_add(visual_row))
+    }
+}
+
+/// Shared layout for the log viewer panel.
+///
+/// The first row lists the verbose log targets; the remaining rows show the
+/// tail of the log, newest at the bottom.
+#[derive(Debug, Clone, Copy)]
+pub struct LogPanelLayout {
+    /// Row listing the verbose targets.
+    pub summary_rect: Rect,
+    /// Log rows below the summary row.
+    pub list_rect: Rect,
+    /// Row height in pixels.
+    pub row_height: usize,
+    /// Left padding for row text.
+    pub text_x: usize,
+    /// Vertical text padding inside a row.
+    pub text_top_padding: usize,
+}
+
+impl LogPanelLayout {
+    /// Build log panel geometry from the dock content rectangle and scaled metrics.
+    pub fn new(content_rect: Rect, metrics: &ScaledMetrics) -> Self {
+        let row_height = metrics.file_tree_row_height;
+        let summary_height = (row_height as f32).min(content_rect.height);
+        Self {
+            summary_rect: Rect::new(
+                content_rect.x,
+                content_rect.y,
+                content_rect.width,
+                summary_height,
+            ),
+            list_rect: Rect::new(
+                content_rect.x,
+                content_rect.y + summary_height,
+                content_rect.width,
+                content_rect.height - summary_height,
+            ),
+            row_height,
+            text_x: content_rect.x as usize + metrics.padding_large,
+            text_top_padding: metrics.padding_small,
+        }
+    }
+
+    /// Number of whole log rows that fit below the summary row.
+    #[inline]
+    pub fn visible_capacity(&self) -> usize {
+        if self.row_height == 0 {
+            0
+        } else {
+            (self.list_rect.height / self.row_height as f32).max(0.0) as usize
+        }
+    }
+}
+
+/// Shared layout for the AI chat panel: a scrollable transcript above a
+/// single-line prompt input.
+#[derive(Debug, Clone, Copy)]
+pub struct AiChatPanelLayout {
+    /// Transcript area above the input.
+    pub transcript_rect: Rect,
+    /// Prompt input box.
+    pub input_rect: WidgetRect,
+    /// Transcript row height in pixels.
+    pub row_height: usize,
+    /// Left edge of transcript text.
+    pub text_x: usize,
+    /// Width available for transcript text in pixels.
+    pub text_width: usize,
+    /// Vertical text padding inside a row.
+    pub text_top_padding: usize,
+}
+
+impl AiChatPanelLayout {
+    /// Build AI chat panel geometry from the dock content rectangle and scaled metrics.
+    pub fn new(content_rect: Rect, metrics: &ScaledMetrics) -> Self {
+        let row_height = metrics.file_tree_row_height;
+        let margin = metrics.padding_medium;
+        let input_h = row_height + metrics.padding_small * 2;
+        let input_area_h = ((input_h + margin * 2) as f32).min(content_rect.height);
+        let input_area_y = content_rect.y + content_rect.height - input_area_h;
+        let text_x = content_rect.x as usize + metrics.padding_large;
+
+        Self {
+            transcript_rect: Rect::new(
+                content_rect.x,
+                content_rect.y,
+                content_rect.width,
+                content_rect.height - input_area_h,
+            ),
+            input_rect: WidgetRect {
+                x: content_rect.x as usize + margin,
+                y: input_area_y as usize + margin,
+                w: (content_rect.width as usize).saturating_sub(margin * 2),
+                h: input_h,
+            },
+            row_height,
+            text_x,
+            text_width: (content_rect.width as usize).saturating_sub(metrics.padding_large * 2),
+            text_top_padding: metrics.padding_small,
+        }
+    }
+
+    /// Number of whole transcript rows that fit above the input.
+    #[inline]
+    pub fn visible_rows(&self) -> usize {
+        if self.row_height == 0 {
+            0
+        } else {
+            (self.transcript_rect.height / self.row_height as f32).max(0.0) as usize
+        }
+    }
+
+    /// Characters per wrapped transcript row.
+    #[inline]
+    pub fn max_cols(&self, char_width: f32) -> usize {
+        ((self.text_width as f32 / char_width.max(1.0)) as usize).max(1)
+    }
+}
+
 /// Computed positions for a single tree node at a given depth and y.
 #[derive(Debug, Clone, Copy)]
 pub struct TreeNodePosition {
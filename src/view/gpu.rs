@@ -0,0 +1,864 @@
+//! GPU renderer (wgpu)
+//!
+//! Draws a [`Scene`] recorded by the view code as quads with a single
+//! pipeline: solid rectangles, glyphs sampled from a glyph atlas (coverage
+//! masks uploaded from the glyph cache), images from per-image textures and
+//! checkerboards computed in the shader. Blending happens on the GPU, so
+//! full-window redraws cost the same at any resolution.
+//!
+//! Built with the `gpu` feature and selected with `renderer` in
+//! `config.yaml`; the software renderer is the fallback.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use anyhow::{anyhow, Result};
+use winit::window::Window;
+
+use super::scene::{intersect, Primitive, Scene};
+use super::{GlyphCache, GlyphCacheKey};
+use crate::model::editor_area::Rect;
+
+const SHADER: &str = r#"
+struct Globals {
+    size: vec2<f32>,
+    _padding: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> globals: Globals;
+@group(0) @binding(1) var glyph_atlas: texture_2d<f32>;
+@group(0) @binding(2) var image: texture_2d<f32>;
+@group(0) @binding(3) var nearest: sampler;
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) color2: vec4<f32>,
+    @location(4) params: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) color2: vec4<f32>,
+    @location(3) params: vec4<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    let ndc = vec2<f32>(
+        in.position.x / globals.size.x * 2.0 - 1.0,
+        1.0 - in.position.y / globals.size.y * 2.0,
+    );
+    out.position = vec4<f32>(ndc, 0.0, 1.0);
+    out.uv = in.uv;
+    out.color = in.color;
+    out.color2 = in.color2;
+    out.params = in.params;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Sampled up front: sampling must happen in uniform control flow
+    let coverage = textureSample(glyph_atlas, nearest, in.uv).r;
+    let texel = textureSample(image, nearest, in.uv);
+    let mode = u32(in.params.x + 0.5);
+    if mode == 1u {
+        return vec4<f32>(in.color.rgb, in.color.a * coverage);
+    }
+    if mode == 2u {
+        return vec4<f32>(texel.rgb, texel.a * in.color.a);
+    }
+    if mode == 3u {
+        let cell = floor((in.position.xy - in.params.zw) / in.params.y);
+        if ((i32(cell.x) + i32(cell.y)) & 1) == 0 {
+            return in.color;
+        }
+        return in.color2;
+    }
+    return in.color;
+}
+"#;
+
+/// How a quad is shaded (`params.x` in the shader)
+#[derive(Clone, Copy)]
+enum Mode {
+    Solid = 0,
+    Glyph = 1,
+    Image = 2,
+    Checkerboard = 3,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Vertex {
+    position: [f32; 2],
+    uv: [f32; 2],
+    color: [f32; 4],
+    /// Dark checkerboard cells
+    color2: [f32; 4],
+    /// Mode, checkerboard cell size, checkerboard origin
+    params: [f32; 4],
+}
+
+impl Vertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        0 => Float32x2,
+        1 => Float32x2,
+        2 => Float32x4,
+        3 => Float32x4,
+        4 => Float32x4,
+    ];
+}
+
+/// Initial glyph atlas size; doubled when one frame's glyphs don't fit
+const ATLAS_SIZE: u32 = 1024;
+
+/// Glyph coverage masks packed in rows
+struct GlyphAtlas {
+    view: wgpu::TextureView,
+    texture: wgpu::Texture,
+    size: u32,
+    /// `[x, y, width, height]` in texels
+    slots: HashMap<GlyphCacheKey, [u32; 4]>,
+    cursor_x: u32,
+    cursor_y: u32,
+    row_height: u32,
+}
+
+impl GlyphAtlas {
+    fn new(device: &wgpu::Device, size: u32) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("glyph atlas"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        Self {
+            view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            texture,
+            size,
+            slots: HashMap::new(),
+            cursor_x: 0,
+            cursor_y: 0,
+            row_height: 0,
+        }
+    }
+
+    /// Forget all glyphs, keeping the texture
+    fn reset(&mut self) {
+        self.slots.clear();
+        self.cursor_x = 0;
+        self.cursor_y = 0;
+        self.row_height = 0;
+    }
+
+    /// Where `key` is in the atlas, uploading it first if needed. `None`
+    /// when the atlas is full.
+    fn slot(
+        &mut self,
+        queue: &wgpu::Queue,
+        key: GlyphCacheKey,
+        glyph_cache: &GlyphCache,
+    ) -> Option<[u32; 4]> {
+        if let Some(slot) = self.slots.get(&key) {
+            return Some(*slot);
+        }
+        let (metrics, bitmap) = glyph_cache.get(&key)?;
+        let (width, height) = (metrics.width as u32, metrics.height as u32);
+        if bitmap.len() < (width * height) as usize {
+            return None;
+        }
+        // One texel of padding so neighbours never bleed in
+        if self.cursor_x + width + 1 > self.size {
+            self.cursor_x = 0;
+            self.cursor_y += self.row_height + 1;
+            self.row_height = 0;
+        }
+        if self.cursor_y + height + 1 > self.size || width + 1 > self.size {
+            return None;
+        }
+        let slot = [self.cursor_x, self.cursor_y, width, height];
+        if width > 0 && height > 0 {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: slot[0],
+                        y: slot[1],
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &bitmap[..(width * height) as usize],
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        self.cursor_x += width + 1;
+        self.row_height = self.row_height.max(height);
+        self.slots.insert(key, slot);
+        Some(slot)
+    }
+}
+
+/// An uploaded image
+struct GpuImage {
+    view: wgpu::TextureView,
+    /// Only kept alive for the view
+    _texture: wgpu::Texture,
+}
+
+/// A run of vertices drawn with one bind group
+struct Batch {
+    /// Scene image index, or `None` for the default bind group
+    image: Option<usize>,
+    vertices: std::ops::Range<u32>,
+}
+
+pub struct GpuRenderer {
+    // Dropped before `window`, which it draws to
+    surface: wgpu::Surface<'static>,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    globals: wgpu::Buffer,
+    vertex_buffer: wgpu::Buffer,
+    atlas: GlyphAtlas,
+    /// 1x1 stand-in bound when a quad has no image
+    blank_image: GpuImage,
+    default_bind_group: wgpu::BindGroup,
+    images: HashMap<u64, GpuImage>,
+    adapter_name: String,
+    _window: Rc<Window>,
+}
+
+impl GpuRenderer {
+    /// Set up wgpu for `window`. Fails when no adapter is available, or when
+    /// the only one is a software rasterizer and `allow_software_adapter` is
+    /// false (it would be slower than the software renderer).
+    pub fn new(window: Rc<Window>, allow_software_adapter: bool) -> Result<Self> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        // SAFETY: the window is kept alive in `_window` and outlives the
+        // surface, which is declared (and so dropped) first
+        let surface = unsafe {
+            instance.create_surface_unsafe(wgpu::SurfaceTargetUnsafe::from_window(&*window)?)
+        }?;
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::LowPower,
+            force_fallback_adapter: false,
+            compatible_surface: Some(&surface),
+        }))?;
+        let info = adapter.get_info();
+        if info.device_type == wgpu::DeviceType::Cpu && !allow_software_adapter {
+            return Err(anyhow!(
+                "only a software adapter is available ({})",
+                info.name
+            ));
+        }
+
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+                label: Some("token"),
+                required_features: wgpu::Features::empty(),
+                required_limits:
+                    wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter.limits()),
+                memory_hints: wgpu::MemoryHints::MemoryUsage,
+                trace: wgpu::Trace::Off,
+            }))?;
+
+        let size = window.inner_size();
+        let capabilities = surface.get_capabilities(&adapter);
+        // Blend in the same (non-linear) space as the software renderer, so
+        // both look the same
+        let format = capabilities
+            .formats
+            .iter()
+            .copied()
+            .find(|format| !format.is_srgb())
+            .or_else(|| capabilities.formats.first().copied())
+            .ok_or_else(|| anyhow!("surface is not supported by {}", info.name))?;
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: capabilities.alpha_modes[0],
+            view_formats: vec![],
+        };
+        surface.configure(&device, &config);
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("quads"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("quads"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(1),
+                texture_entry(2),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("quads"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("quads"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &Vertex::ATTRIBUTES,
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        // Nearest sampling, like the software renderer: glyphs are drawn on
+        // the pixel grid and images are scaled without smoothing
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("nearest"),
+            ..Default::default()
+        });
+        let globals = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("globals"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let vertex_buffer = create_vertex_buffer(&device, 4096);
+        let atlas = GlyphAtlas::new(&device, ATLAS_SIZE);
+        let blank_image = upload_image(&device, &queue, &[0, 0, 0, 0], 1, 1);
+        let default_bind_group = create_bind_group(
+            &device,
+            &bind_group_layout,
+            &globals,
+            &atlas.view,
+            &blank_image.view,
+            &sampler,
+        );
+
+        Ok(Self {
+            surface,
+            device,
+            queue,
+            config,
+            pipeline,
+            bind_group_layout,
+            sampler,
+            globals,
+            vertex_buffer,
+            atlas,
+            blank_image,
+            default_bind_group,
+            images: HashMap::new(),
+            adapter_name: format!("{} ({:?})", info.name, info.backend),
+            _window: window,
+        })
+    }
+
+    /// Adapter name and graphics API, for logging
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.config.width = width.max(1);
+        self.config.height = height.max(1);
+        self.surface.configure(&self.device, &self.config);
+    }
+
+    /// Images with textures already, so a [`Scene`] needn't copy them
+    pub fn resident_images(&self) -> HashSet<u64> {
+        self.images.keys().copied().collect()
+    }
+
+    /// Draw `scene` and present it. Glyph bitmaps come from `glyph_cache`.
+    pub fn render(&mut self, scene: &Scene, glyph_cache: &GlyphCache) -> Result<()> {
+        self.upload_images(scene);
+        self.prepare_glyphs(scene, glyph_cache);
+        let (vertices, batches) = self.build_vertices(scene);
+
+        let frame = match self.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                // Skip this frame; the next one uses the new configuration
+                self.surface.configure(&self.device, &self.config);
+                return Ok(());
+            }
+            Err(wgpu::SurfaceError::Timeout) => return Ok(()),
+            Err(e) => return Err(anyhow!("Failed to acquire surface texture: {}", e)),
+        };
+
+        self.queue.write_buffer(
+            &self.globals,
+            0,
+            bytemuck::cast_slice(&[
+                self.config.width as f32,
+                self.config.height as f32,
+                0.0,
+                0.0,
+            ]),
+        );
+        let bytes: &[u8] = bytemuck::cast_slice(&vertices);
+        if bytes.len() as u64 > self.vertex_buffer.size() {
+            self.vertex_buffer =
+                create_vertex_buffer(&self.device, (bytes.len() as u64).next_power_of_two());
+        }
+        if !bytes.is_empty() {
+            self.queue.write_buffer(&self.vertex_buffer, 0, bytes);
+        }
+
+        let image_bind_groups: HashMap<usize, wgpu::BindGroup> = batches
+            .iter()
+            .filter_map(|batch| batch.image)
+            .filter_map(|index| {
+                let image = self.images.get(&scene.images[index].key)?;
+                let bind_group = create_bind_group(
+                    &self.device,
+                    &self.bind_group_layout,
+                    &self.globals,
+                    &self.atlas.view,
+                    &image.view,
+                    &self.sampler,
+                );
+                Some((index, bind_group))
+            })
+            .collect();
+
+        let view = frame
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("frame"),
+            });
+        {
+            let [r, g, b, _] = unpack_color(scene.clear_color);
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("frame"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: r as f64,
+                            g: g as f64,
+                            b: b as f64,
+                            a: 1.0,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            for batch in &batches {
+                let bind_group = match batch.image {
+                    Some(index) => match image_bind_groups.get(&index) {
+                        Some(bind_group) => bind_group,
+                        None => continue,
+                    },
+                    None => &self.default_bind_group,
+                };
+                pass.set_bind_group(0, bind_group, &[]);
+                pass.draw(batch.vertices.clone(), 0..1);
+            }
+        }
+        self.queue.submit([encoder.finish()]);
+        frame.present();
+        Ok(())
+    }
+
+    /// Upload the scene's new images and drop textures it no longer uses
+    fn upload_images(&mut self, scene: &Scene) {
+        let max_dimension = self.device.limits().max_texture_dimension_2d;
+        for image in &scene.images {
+            if self.images.contains_key(&image.key) {
+                continue;
+            }
+            let Some(pixels) = &image.pixels else {
+                continue;
+            };
+            if pixels.len() < (image.width as usize) * (image.height as usize) * 4 {
+                continue;
+            }
+            let uploaded = if image.width > max_dimension || image.height > max_dimension {
+                let (pixels, width, height) =
+                    downscale(pixels, image.width, image.height, max_dimension);
+                upload_image(&self.device, &self.queue, &pixels, width, height)
+            } else {
+                upload_image(&self.device, &self.queue, pixels, image.width, image.height)
+            };
+            self.images.insert(image.key, uploaded);
+        }
+        self.images
+            .retain(|key, _| scene.images.iter().any(|image| image.key == *key));
+    }
+
+    /// Make sure every glyph in the scene is in the atlas: first by
+    /// evicting glyphs from earlier frames, then by growing the atlas
+    fn prepare_glyphs(&mut self, scene: &Scene, glyph_cache: &GlyphCache) {
+        let max_size = self.device.limits().max_texture_dimension_2d;
+        let mut evicted = false;
+        'retry: loop {
+            for primitive in &scene.primitives {
+                let Primitive::Glyph { key, .. } = primitive else {
+                    continue;
+                };
+                if self.atlas.slot(&self.queue, *key, glyph_cache).is_some()
+                    || !glyph_cache.contains_key(key)
+                {
+                    continue;
+                }
+                if !evicted {
+                    self.atlas.reset();
+                    evicted = true;
+                } else if self.atlas.size * 2 <= max_size {
+                    self.atlas = GlyphAtlas::new(&self.device, self.atlas.size * 2);
+                    self.default_bind_group = create_bind_group(
+                        &self.device,
+                        &self.bind_group_layout,
+                        &self.globals,
+                        &self.atlas.view,
+                        &self.blank_image.view,
+                        &self.sampler,
+                    );
+                } else {
+                    tracing::warn!("Glyph atlas is full; some glyphs are not drawn");
+                    return;
+                }
+                continue 'retry;
+            }
+            return;
+        }
+    }
+
+    /// Quads for the scene, in order, split where the bound image changes
+    fn build_vertices(&self, scene: &Scene) -> (Vec<Vertex>, Vec<Batch>) {
+        let mut vertices = Vec::with_capacity(scene.primitives.len() * 6);
+        let mut batches: Vec<Batch> = Vec::new();
+        let atlas_size = self.atlas.size as f32;
+
+        for primitive in &scene.primitives {
+            let start = vertices.len() as u32;
+            let image = match primitive {
+                Primitive::Rect { rect, color } => {
+                    push_quad(
+                        &mut vertices,
+                        Quad::solid(*rect, *color, Mode::Solid),
+                        *rect,
+                    );
+                    None
+                }
+                Primitive::Glyph {
+                    key,
+                    x,
+                    y,
+                    width,
+                    height,
+                    color,
+                    shear,
+                    baseline,
+                    clip,
+                } => {
+                    let Some([u, v, w, h]) = self.atlas.slots.get(key) else {
+                        continue;
+                    };
+                    let quad = Quad {
+                        rect: Rect::new(*x, *y, *width, *height),
+                        uv: [
+                            *u as f32 / atlas_size,
+                            *v as f32 / atlas_size,
+                            (*u + *w) as f32 / atlas_size,
+                            (*v + *h) as f32 / atlas_size,
+                        ],
+                        shear: (*shear, *baseline),
+                        ..Quad::solid(Rect::default(), *color, Mode::Glyph)
+                    };
+                    push_quad(&mut vertices, quad, *clip);
+                    None
+                }
+                Primitive::Image { image, dest, clip } => {
+                    if !self.images.contains_key(&scene.images[*image].key) {
+                        continue;
+                    }
+                    let quad = Quad {
+                        rect: *dest,
+                        uv: [0.0, 0.0, 1.0, 1.0],
+                        ..Quad::solid(Rect::default(), 0xFFFFFFFF, Mode::Image)
+                    };
+                    push_quad(&mut vertices, quad, *clip);
+                    Some(*image)
+                }
+                Primitive::Checkerboard {
+                    rect,
+                    cell,
+                    light,
+                    dark,
+                } => {
+                    let quad = Quad {
+                        color2: unpack_color(*dark),
+                        params: [Mode::Checkerboard as u32 as f32, *cell, rect.x, rect.y],
+                        ..Quad::solid(*rect, *light, Mode::Checkerboard)
+                    };
+                    push_quad(&mut vertices, quad, *rect);
+                    None
+                }
+            };
+            let end = vertices.len() as u32;
+            if end == start {
+                continue;
+            }
+            match batches.last_mut() {
+                Some(batch) if batch.image == image && image.is_none() => {
+                    batch.vertices.end = end;
+                }
+                _ => batches.push(Batch {
+                    image,
+                    vertices: start..end,
+                }),
+            }
+        }
+        (vertices, batches)
+    }
+}
+
+/// A quad before clipping
+struct Quad {
+    rect: Rect,
+    /// `[u0, v0, u1, v1]`
+    uv: [f32; 4],
+    color: [f32; 4],
+    color2: [f32; 4],
+    params: [f32; 4],
+    /// Shear per pixel above the baseline, and the baseline
+    shear: (f32, f32),
+}
+
+impl Quad {
+    fn solid(rect: Rect, color: u32, mode: Mode) -> Self {
+        Self {
+            rect,
+            uv: [0.0; 4],
+            color: unpack_color(color),
+            color2: [0.0; 4],
+            params: [mode as u32 as f32, 0.0, 0.0, 0.0],
+            shear: (0.0, 0.0),
+        }
+    }
+}
+
+/// Append two triangles for the part of `quad` inside `clip`
+fn push_quad(vertices: &mut Vec<Vertex>, quad: Quad, clip: Rect) {
+    let Some(visible) = intersect(quad.rect, clip) else {
+        return;
+    };
+    let rect = quad.rect;
+    let [u0, v0, u1, v1] = quad.uv;
+    let u = |x: f32| u0 + (x - rect.x) / rect.width * (u1 - u0);
+    let v = |y: f32| v0 + (y - rect.y) / rect.height * (v1 - v0);
+    let (x0, y0) = (visible.x, visible.y);
+    let (x1, y1) = (visible.x + visible.width, visible.y + visible.height);
+    let (shear, baseline) = quad.shear;
+    let vertex = |x: f32, y: f32| Vertex {
+        position: [x + (baseline - y) * shear, y],
+        uv: [u(x), v(y)],
+        color: quad.color,
+        color2: quad.color2,
+        params: quad.params,
+    };
+    let (top_left, top_right) = (vertex(x0, y0), vertex(x1, y0));
+    let (bottom_left, bottom_right) = (vertex(x0, y1), vertex(x1, y1));
+    vertices.extend_from_slice(&[
+        top_left,
+        bottom_left,
+        top_right,
+        top_right,
+        bottom_left,
+        bottom_right,
+    ]);
+}
+
+/// ARGB to normalized RGBA
+fn unpack_color(color: u32) -> [f32; 4] {
+    let channel = |shift: u32| ((color >> shift) & 0xFF) as f32 / 255.0;
+    [channel(16), channel(8), channel(0), channel(24)]
+}
+
+fn create_vertex_buffer(device: &wgpu::Device, size: u64) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("vertices"),
+        size,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    globals: &wgpu::Buffer,
+    atlas: &wgpu::TextureView,
+    image: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("quads"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: globals.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(atlas),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(image),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
+
+fn upload_image(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+) -> GpuImage {
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("image"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &pixels[..(width * height * 4) as usize],
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(width * 4),
+            rows_per_image: Some(height),
+        },
+        size,
+    );
+    GpuImage {
+        view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+        _texture: texture,
+    }
+}
+
+/// Nearest-neighbour downscale of an RGBA8 image to fit `max_dimension`
+fn downscale(pixels: &[u8], width: u32, height: u32, max_dimension: u32) -> (Vec<u8>, u32, u32) {
+    let scale = (max_dimension as f64 / width.max(height) as f64).min(1.0);
+    let new_width = ((width as f64 * scale) as u32).max(1);
+    let new_height = ((height as f64 * scale) as u32).max(1);
+    let mut scaled = Vec::with_capacity((new_width * new_height * 4) as usize);
+    for y in 0..new_height {
+        let src_y = ((y as f64 / scale) as u32).min(height - 1);
+        for x in 0..new_width {
+            let src_x = ((x as f64 / scale) as u32).min(width - 1);
+            let index = ((src_y * width + src_x) * 4) as usize;
+            scaled.extend_from_slice(&pixels[index..index + 4]);
+        }
+    }
+    (scaled, new_width, new_height)
+}
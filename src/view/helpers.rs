@@ -5,19 +5,27 @@
 
 use crate::model::editor_area::Tab;
 use crate::model::AppModel;
+use crate::model::TabContent;
 
 /// Get the display title for a tab.
 ///
 /// Centralizes the logic for determining what text to show in the tab bar.
 /// Returns "Untitled" if the editor or document cannot be found.
 pub fn get_tab_display_name(model: &AppModel, tab: &Tab) -> String {
-    model
-        .editor_area
-        .editors
-        .get(&tab.editor_id)
+    let editor = model.editor_area.editors.get(&tab.editor_id);
+    if let Some(TabContent::Welcome(_)) = editor.map(|e| &e.tab_content) {
+        return "Welcome".to_string();
+    }
+    editor
         .and_then(|e| e.document_id)
         .and_then(|doc_id| model.editor_area.documents.get(&doc_id))
-        .map(|d| d.display_name())
+        .map(|d| {
+            if d.deleted_on_disk {
+                format!("{} (deleted)", d.display_name())
+            } else {
+                d.display_name()
+            }
+        })
         .unwrap_or_else(|| "Untitled".to_string())
 }
 
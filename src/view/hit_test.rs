@@ -16,13 +16,13 @@ use std::path::PathBuf;
 use winit::event::{ElementState, MouseButton};
 use winit::keyboard::ModifiersState;
 
-use crate::commands::filter_commands;
+use crate::commands::palette_entries;
 use crate::model::editor_area::{DocumentId, EditorId, GroupId, PreviewId, Rect, TabId};
-use crate::model::{AppModel, FocusTarget, ModalState, TextViewportMap};
+use crate::model::{AppModel, FocusTarget, ModalState};
 
 use super::geometry::{
-    is_in_status_bar, DockHeaderLayout, PreviewPaneLayout, TabBarLayout, TreeListLayout,
-    WindowLayout,
+    is_in_status_bar, DockHeaderLayout, DockIconStripLayout, PreviewPaneLayout, TabBarLayout,
+    TreeListLayout, WindowLayout,
 };
 
 // ============================================================================
@@ -109,6 +109,16 @@ impl MouseEvent {
     pub fn alt(&self) -> bool {
         self.modifiers.alt_key()
     }
+
+    /// Check if the platform command key is held (Cmd on macOS, Ctrl elsewhere)
+    #[inline]
+    pub fn command(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.modifiers.super_key()
+        } else {
+            self.modifiers.control_key()
+        }
+    }
 }
 
 // ============================================================================
@@ -130,8 +140,11 @@ pub enum HitTarget {
     /// `inside` indicates whether the click was inside or outside the modal bounds
     Modal { inside: bool },
 
-    /// Status bar at the bottom of the window
-    StatusBar,
+    /// Status bar at the bottom of the window, and the segment under the
+    /// pointer if any
+    StatusBar {
+        segment: Option<crate::model::SegmentId>,
+    },
 
     /// Sidebar resize handle (the border between sidebar and editor area)
     SidebarResize,
@@ -169,6 +182,13 @@ pub enum HitTarget {
     /// Empty area of a group's tab bar (no specific tab)
     GroupTabBarEmpty { group_id: GroupId },
 
+    /// A group's breadcrumb strip; `depth` is the symbol under the pointer
+    /// (`None` on the file name or past the last crumb)
+    GroupBreadcrumb {
+        group_id: GroupId,
+        depth: Option<usize>,
+    },
+
     /// Editor gutter (line numbers)
     EditorGutter {
         group_id: GroupId,
@@ -191,6 +211,13 @@ pub enum HitTarget {
         col: usize,
     },
 
+    /// The grid line at a CSV column header's right edge (drag to resize)
+    CsvColumnBorder {
+        group_id: GroupId,
+        editor_id: EditorId,
+        col: usize,
+    },
+
     /// Dock resize handle (between dock and editor area)
     DockResize {
         position: crate::panel::DockPosition,
@@ -216,6 +243,9 @@ pub enum HitTarget {
     /// "Open with Default Application" button on binary placeholder tab
     BinaryPlaceholderButton { group_id: GroupId },
 
+    /// Entry on the welcome tab (index into `WelcomeState::items`)
+    WelcomeItem { group_id: GroupId, index: usize },
+
     /// Image content area (pan/zoom viewer)
     ImageContent {
         group_id: GroupId,
@@ -277,10 +307,13 @@ impl HitTarget {
         match self {
             HitTarget::GroupTab { group_id, .. }
             | HitTarget::GroupTabBarEmpty { group_id }
+            | HitTarget::GroupBreadcrumb { group_id, .. }
             | HitTarget::EditorGutter { group_id, .. }
             | HitTarget::EditorContent { group_id, .. }
             | HitTarget::CsvCell { group_id, .. }
+            | HitTarget::CsvColumnBorder { group_id, .. }
             | HitTarget::BinaryPlaceholderButton { group_id }
+            | HitTarget::WelcomeItem { group_id, .. }
             | HitTarget::ImageContent { group_id, .. }
             | HitTarget::ScrollbarThumbVertical { group_id, .. }
             | HitTarget::ScrollbarTrackVertical { group_id, .. }
@@ -298,10 +331,13 @@ impl HitTarget {
             HitTarget::SidebarEmpty | HitTarget::SidebarItem { .. } => Some(FocusTarget::Sidebar),
             HitTarget::GroupTab { .. }
             | HitTarget::GroupTabBarEmpty { .. }
+            | HitTarget::GroupBreadcrumb { .. }
             | HitTarget::EditorGutter { .. }
             | HitTarget::EditorContent { .. }
             | HitTarget::CsvCell { .. }
+            | HitTarget::CsvColumnBorder { .. }
             | HitTarget::BinaryPlaceholderButton { .. }
+            | HitTarget::WelcomeItem { .. }
             | HitTarget::ImageContent { .. }
             | HitTarget::ScrollbarThumbVertical { .. }
             | HitTarget::ScrollbarTrackVertical { .. }
@@ -318,7 +354,7 @@ impl HitTarget {
                 }
             }
             // These don't change focus
-            HitTarget::StatusBar
+            HitTarget::StatusBar { .. }
             | HitTarget::SidebarResize
             | HitTarget::DockResize { .. }
             | HitTarget::Splitter { .. }
@@ -334,8 +370,13 @@ impl HitTarget {
 
         match self {
             HitTarget::EditorContent { .. } | HitTarget::CsvCell { .. } => CursorIcon::Text,
-            HitTarget::BinaryPlaceholderButton { .. } => CursorIcon::Pointer,
-            HitTarget::SidebarResize => CursorIcon::ColResize,
+            HitTarget::BinaryPlaceholderButton { .. }
+            | HitTarget::WelcomeItem { .. }
+            | HitTarget::GroupBreadcrumb { depth: Some(_), .. }
+            | HitTarget::StatusBar {
+                segment: Some(crate::model::SegmentId::Language),
+            } => CursorIcon::Pointer,
+            HitTarget::SidebarResize | HitTarget::CsvColumnBorder { .. } => CursorIcon::ColResize,
             HitTarget::DockResize { position } => match position {
                 crate::panel::DockPosition::Right | crate::panel::DockPosition::Left => {
                     CursorIcon::ColResize
@@ -356,16 +397,16 @@ impl HitTarget {
 
         match self {
             HitTarget::Modal { .. } => HoverRegion::Modal,
-            HitTarget::StatusBar => HoverRegion::StatusBar,
+            HitTarget::StatusBar { .. } => HoverRegion::StatusBar,
             HitTarget::SidebarResize => HoverRegion::SidebarResize,
             HitTarget::SidebarEmpty | HitTarget::SidebarItem { .. } => HoverRegion::Sidebar,
             HitTarget::Splitter { .. } => HoverRegion::Splitter,
             HitTarget::PreviewHeader { .. } | HitTarget::PreviewContent { .. } => {
                 HoverRegion::Preview
             }
-            HitTarget::GroupTab { .. } | HitTarget::GroupTabBarEmpty { .. } => {
-                HoverRegion::EditorTabBar
-            }
+            HitTarget::GroupTab { .. }
+            | HitTarget::GroupTabBarEmpty { .. }
+            | HitTarget::GroupBreadcrumb { .. } => HoverRegion::EditorTabBar,
             HitTarget::DockResize { position } => HoverRegion::DockResize(*position),
             HitTarget::DockTab { position, .. }
             | HitTarget::DockTabBarEmpty { position }
@@ -374,8 +415,10 @@ impl HitTarget {
             // Editor content, gutter, image content, CSV cells, and scrollbars map to EditorText.
             HitTarget::EditorGutter { .. }
             | HitTarget::EditorContent { .. }
+            | HitTarget::WelcomeItem { .. }
             | HitTarget::ImageContent { .. }
             | HitTarget::CsvCell { .. }
+            | HitTarget::CsvColumnBorder { .. }
             | HitTarget::ScrollbarThumbVertical { .. }
             | HitTarget::ScrollbarTrackVertical { .. }
             | HitTarget::ScrollbarThumbHorizontal { .. }
@@ -488,17 +531,18 @@ pub fn hit_test_modal(model: &AppModel, pt: Point) -> Option<HitTarget> {
                 ww,
                 wh,
                 lh,
-                filter_commands(&input_text).len(),
+                palette_entries(&input_text, &model.config.external_tools).len(),
             );
             l
         }
-        Some(ModalState::FileFinder(state)) => {
+        Some(ModalState::QuickOpen(state)) => {
             let (l, _) = super::geometry::file_finder_layout(
                 ww,
                 wh,
                 lh,
                 state.results.len(),
                 !state.input().is_empty(),
+                state.preview_rows(),
             );
             l
         }
@@ -517,7 +561,9 @@ pub fn hit_test_modal(model: &AppModel, pt: Point) -> Option<HitTarget> {
             let (l, _) = super::geometry::theme_picker_layout(ww, wh, lh, visible_rows);
             l
         }
-        Some(ModalState::GotoLine(_)) => {
+        Some(ModalState::GotoLine(_))
+        | Some(ModalState::RenameFile(_))
+        | Some(ModalState::CreateFile(_)) => {
             let (l, _) = super::geometry::goto_line_layout(ww, wh, lh);
             l
         }
@@ -533,9 +579,79 @@ pub fn hit_test_modal(model: &AppModel, pt: Point) -> Option<HitTarget> {
                 lh,
                 filtered.len(),
                 !state.input().is_empty(),
+                0,
+            );
+            l
+        }
+        Some(ModalState::Backlinks(state)) => {
+            let (l, _) = super::geometry::file_finder_layout(
+                ww,
+                wh,
+                lh,
+                state.filtered_entries().len(),
+                !state.input().is_empty(),
+                0,
+            );
+            l
+        }
+        Some(ModalState::KeymapDiagnostics(state)) => {
+            let (l, _) = super::geometry::file_finder_layout(
+                ww,
+                wh,
+                lh,
+                state.filtered_entries().len(),
+                !state.input().is_empty(),
+                0,
+            );
+            l
+        }
+        Some(ModalState::LanguagePicker(state)) => {
+            let (l, _) = super::geometry::file_finder_layout(
+                ww,
+                wh,
+                lh,
+                state.filtered_languages().len(),
+                !state.input().is_empty(),
+                0,
+            );
+            l
+        }
+        Some(ModalState::BreadcrumbSymbols(state)) => {
+            let (l, _) = super::geometry::file_finder_layout(
+                ww,
+                wh,
+                lh,
+                state.filtered_entries().len(),
+                !state.input().is_empty(),
+                0,
+            );
+            l
+        }
+        Some(ModalState::GotoSymbol(state)) => {
+            let (l, _) = super::geometry::file_finder_layout(
+                ww,
+                wh,
+                lh,
+                state.filtered_entries().len(),
+                !state.input().is_empty(),
+                0,
             );
             l
         }
+        Some(ModalState::EditReview(state)) => {
+            let (l, _) = super::geometry::edit_review_layout(ww, wh, lh, state.rows().len());
+            l
+        }
+        Some(ModalState::UnsavedChanges(_))
+        | Some(ModalState::DeletedFile(_))
+        | Some(ModalState::FileChanged(_))
+        | Some(ModalState::WorkspaceTrust(_)) => {
+            let (l, _) = super::geometry::button_prompt_layout(ww, wh, lh);
+            l
+        }
+        Some(ModalState::ContextMenu(state)) => {
+            super::geometry::context_menu_layout(state, ww, wh, lh, model.char_width)
+        }
         None => return None,
     };
 
@@ -544,13 +660,40 @@ pub fn hit_test_modal(model: &AppModel, pt: Point) -> Option<HitTarget> {
     Some(HitTarget::Modal { inside })
 }
 
+/// Index of the context menu item under `pt`, if a context menu is open
+pub fn context_menu_item_at(model: &AppModel, pt: Point) -> Option<usize> {
+    let Some(ModalState::ContextMenu(state)) = &model.ui.active_modal else {
+        return None;
+    };
+    let layout = super::geometry::context_menu_layout(
+        state,
+        model.window_size.0 as usize,
+        model.window_size.1 as usize,
+        model.line_height,
+        model.char_width,
+    );
+    let (x, y) = (pt.x as usize, pt.y as usize);
+    layout
+        .widgets
+        .iter()
+        .position(|r| x >= r.x && x < r.x + r.w && y >= r.y && y < r.y + r.h)
+}
+
 /// Hit-test the status bar at the bottom of the window.
 pub fn hit_test_status_bar(model: &AppModel, pt: Point) -> Option<HitTarget> {
-    if is_in_status_bar(pt.y, model.window_size.1, model.line_height) {
-        Some(HitTarget::StatusBar)
-    } else {
-        None
+    if !is_in_status_bar(pt.y, model.window_size.1, model.line_height) {
+        return None;
     }
+    // Same layout as `render_status_bar`, in character columns
+    let char_width = model.char_width.max(1.0);
+    let available_chars = (model.window_size.0 as f32 / char_width).floor() as usize;
+    let column = (pt.x.max(0.0) as f32 / char_width).floor() as usize;
+    let segment = model
+        .ui
+        .status_bar
+        .layout(available_chars)
+        .segment_at(column);
+    Some(HitTarget::StatusBar { segment })
 }
 
 /// Hit-test the sidebar resize handle.
@@ -593,7 +736,16 @@ pub fn hit_test_sidebar(model: &AppModel, pt: Point) -> Option<HitTarget> {
 
     let row_height = model.metrics.file_tree_row_height as f64;
     let clicked_visual_row = (pt.y / row_height) as usize;
-    let clicked_row = workspace.scroll_offset.saturating_add(clicked_visual_row);
+    let mut clicked_row = workspace.scroll_offset.saturating_add(clicked_visual_row);
+
+    // The input for a new entry's name pushes the rows below it down
+    if let Some(new_row) = workspace.new_entry_row() {
+        match clicked_row.cmp(&new_row) {
+            std::cmp::Ordering::Less => {}
+            std::cmp::Ordering::Equal => return Some(HitTarget::SidebarEmpty),
+            std::cmp::Ordering::Greater => clicked_row -= 1,
+        }
+    }
 
     if let Some((node, depth)) = workspace
         .file_tree
@@ -681,6 +833,18 @@ pub fn hit_test_groups(model: &AppModel, pt: Point, char_width: f32) -> Option<H
         return Some(HitTarget::GroupTabBarEmpty { group_id });
     }
 
+    if let Some(breadcrumbs) = super::geometry::BreadcrumbLayout::new(group, model, char_width) {
+        if layout
+            .breadcrumb_rect
+            .is_some_and(|rect| rect.contains(pt.x as f32, pt.y as f32))
+        {
+            let depth = breadcrumbs
+                .crumb_at(pt.x, pt.y)
+                .and_then(|crumb| crumb.depth);
+            return Some(HitTarget::GroupBreadcrumb { group_id, depth });
+        }
+    }
+
     // Get the active editor for this group
     let editor_id = group.active_editor_id()?;
     let editor = model.editor_area.editors.get(&editor_id)?;
@@ -698,9 +862,9 @@ pub fn hit_test_groups(model: &AppModel, pt: Point, char_width: f32) -> Option<H
         let line_height = model.line_height;
         let content_rect = Rect::new(
             group.rect.x,
-            group.rect.y + model.metrics.tab_bar_height as f32,
+            group.rect.y + model.metrics.header_height() as f32,
             group.rect.width,
-            group.rect.height - model.metrics.tab_bar_height as f32,
+            group.rect.height - model.metrics.header_height() as f32,
         );
         let bp_layout = super::geometry::binary_placeholder_layout(
             content_rect,
@@ -716,6 +880,26 @@ pub fn hit_test_groups(model: &AppModel, pt: Point, char_width: f32) -> Option<H
         }
     }
 
+    // For the welcome tab, check the entry rows
+    if let crate::model::TabContent::Welcome(state) = &editor.tab_content {
+        let content_rect = Rect::new(
+            group.rect.x,
+            group.rect.y + model.metrics.header_height() as f32,
+            group.rect.width,
+            group.rect.height - model.metrics.header_height() as f32,
+        );
+        let welcome = super::geometry::WelcomeLayout::new(
+            content_rect,
+            &state.items,
+            model.line_height,
+            char_width,
+            &model.metrics,
+        );
+        if let Some(index) = welcome.item_at(pt.x as f32, pt.y as f32) {
+            return Some(HitTarget::WelcomeItem { group_id, index });
+        }
+    }
+
     let doc_id = editor.document_id?;
     let document = model.editor_area.documents.get(&doc_id)?;
 
@@ -803,7 +987,22 @@ pub fn hit_test_groups(model: &AppModel, pt: Point, char_width: f32) -> Option<H
     }
 
     // Check if in CSV mode
-    if editor.view_mode.is_csv() {
+    if let Some(csv) = editor.view_mode.as_csv() {
+        if let Some(col) = crate::csv::render::pixel_to_csv_column_border(
+            csv,
+            &group.rect,
+            pt.x,
+            pt.y,
+            model.line_height,
+            char_width,
+            model.metrics.header_height(),
+        ) {
+            return Some(HitTarget::CsvColumnBorder {
+                group_id,
+                editor_id,
+                col,
+            });
+        }
         // For CSV mode, we could compute the exact cell here
         // For now, return a placeholder that the caller can refine
         return Some(HitTarget::CsvCell {
@@ -821,7 +1020,7 @@ pub fn hit_test_groups(model: &AppModel, pt: Point, char_width: f32) -> Option<H
     if pt.x >= group.rect.x as f64 && pt.x < gutter_x_end && pt.y >= content_y_start {
         // Compute which line was clicked
         let local_y = pt.y - content_y_start;
-        let viewport = TextViewportMap::new(&editor.viewport, document.line_count());
+        let viewport = editor.viewport_map(document);
         let line = viewport.doc_line_for_pixel_y(local_y, model.line_height as f64);
         return Some(HitTarget::EditorGutter {
             group_id,
@@ -838,9 +1037,31 @@ pub fn hit_test_groups(model: &AppModel, pt: Point, char_width: f32) -> Option<H
     })
 }
 
+/// Hit-test a collapsed dock's icon strip: icons behave like dock tabs, and
+/// the rest of the strip like an empty tab bar.
+fn hit_test_icon_strip(
+    dock: &crate::panel::Dock,
+    rect: Rect,
+    model: &AppModel,
+    pt: Point,
+) -> HitTarget {
+    let layout = DockIconStripLayout::new(dock, rect, &model.metrics);
+    match layout.icon_at(pt.x, pt.y) {
+        Some(icon) => HitTarget::DockTab {
+            position: dock.position,
+            panel_id: icon.panel_id,
+        },
+        None => HitTarget::DockTabBarEmpty {
+            position: dock.position,
+        },
+    }
+}
+
 /// Hit-test dock panels (right and bottom docks).
 ///
 /// Computes dock rectangles and checks if the point falls within any open dock.
+/// Collapsed docks have no resize handle; their icon strip is hit-tested by
+/// `hit_test_icon_strip`.
 /// Returns `DockContent` with the active panel ID for content clicks, or
 /// `DockResize` if the point is over the resizable border between a dock and
 /// the editor area.
@@ -849,7 +1070,16 @@ pub fn hit_test_docks(model: &AppModel, pt: Point) -> Option<HitTarget> {
     let hit_zone = model.metrics.resize_handle_zone as f64;
 
     if let Some(right_rect) = window_layout.right_dock_rect {
-        if model.dock_layout.right.is_open {
+        if model.dock_layout.right.is_open && model.dock_layout.right.is_collapsed {
+            if right_rect.contains(pt.x as f32, pt.y as f32) {
+                return Some(hit_test_icon_strip(
+                    &model.dock_layout.right,
+                    right_rect,
+                    model,
+                    pt,
+                ));
+            }
+        } else if model.dock_layout.right.is_open {
             let resize_zone_start = right_rect.x as f64 - hit_zone;
             let resize_zone_end = right_rect.x as f64 + hit_zone;
             if pt.x >= resize_zone_start
@@ -893,7 +1123,16 @@ pub fn hit_test_docks(model: &AppModel, pt: Point) -> Option<HitTarget> {
     }
 
     if let Some(bottom_rect) = window_layout.bottom_dock_rect {
-        if model.dock_layout.bottom.is_open {
+        if model.dock_layout.bottom.is_open && model.dock_layout.bottom.is_collapsed {
+            if bottom_rect.contains(pt.x as f32, pt.y as f32) {
+                return Some(hit_test_icon_strip(
+                    &model.dock_layout.bottom,
+                    bottom_rect,
+                    model,
+                    pt,
+                ));
+            }
+        } else if model.dock_layout.bottom.is_open {
             let resize_zone_start = bottom_rect.y as f64 - hit_zone;
             let resize_zone_end = bottom_rect.y as f64 + hit_zone;
             if pt.y >= resize_zone_start
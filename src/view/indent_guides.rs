@@ -0,0 +1,169 @@
+//! Indent guides: a vertical line at each indentation level
+//!
+//! A line has a guide at every multiple of the document's indentation step
+//! left of its text. Blank lines take the indentation of the lines around
+//! them, so guides run unbroken through the blank lines of a block. The
+//! guide of the block the cursor is in is the active one.
+
+use std::ops::RangeInclusive;
+
+use crate::indent::detect_indent_unit;
+use crate::model::Document;
+use crate::util::text::{char_col_to_visual_col_with, ElasticTabs, TABULATOR_WIDTH};
+
+/// Lines searched above and below a blank line for the indentation around it
+const BLANK_LINE_SCAN_LIMIT: usize = 100;
+
+/// Guides of a range of lines
+#[derive(Debug, Clone, Default)]
+pub struct IndentGuides {
+    first_line: usize,
+    /// Indentation of each line from `first_line`, in visual columns
+    indents: Vec<usize>,
+    /// Columns between guides
+    step: usize,
+    /// Column of the cursor's guide and the lines it is active on
+    active: Option<(usize, RangeInclusive<usize>)>,
+}
+
+impl IndentGuides {
+    /// Guides for lines `first..=last` of `document`, with the one around
+    /// `cursor_line` active when the cursor is in that range
+    pub fn compute(
+        document: &Document,
+        tabs: &ElasticTabs,
+        first: usize,
+        last: usize,
+        cursor_line: usize,
+    ) -> Self {
+        let line_count = document.line_count();
+        if line_count == 0 {
+            return Self::default();
+        }
+        let last = last.min(line_count - 1);
+        let step = match detect_indent_unit(&document.buffer).as_str() {
+            "\t" => TABULATOR_WIDTH,
+            unit => unit.len().max(1),
+        };
+
+        // Indentation of a line with text on it
+        let text_indent = |line: usize| {
+            let text = document.get_line_cow(line)?;
+            let body = text.trim_start_matches([' ', '\t']);
+            if body.is_empty() {
+                return None;
+            }
+            let leading = text.len() - body.len();
+            Some(char_col_to_visual_col_with(
+                &text,
+                leading,
+                tabs.layout(line),
+            ))
+        };
+        let nearest = |lines: &mut dyn Iterator<Item = usize>| {
+            lines
+                .take(BLANK_LINE_SCAN_LIMIT)
+                .find_map(text_indent)
+                .unwrap_or(0)
+        };
+        let indent = |line: usize| {
+            text_indent(line).unwrap_or_else(|| {
+                let above = nearest(&mut (0..line).rev());
+                let below = nearest(&mut (line + 1..line_count));
+                above.min(below)
+            })
+        };
+        let first = first.min(last);
+        let indents: Vec<usize> = (first..=last).map(indent).collect();
+        let indent_at = |line: usize| indents[line - first];
+
+        let active = (first..=last).contains(&cursor_line).then(|| {
+            let next = nearest(&mut (cursor_line + 1..line_count));
+            let opens_block = text_indent(cursor_line).is_some_and(|own| next > own);
+            // A line opening a block activates the guide of the block
+            let (start, start_indent) = if opens_block {
+                (cursor_line + 1, next)
+            } else {
+                (cursor_line, indent_at(cursor_line))
+            };
+            let column = start_indent.checked_sub(1)? / step * step;
+            let mut top = start.min(last);
+            while top > first && indent_at(top - 1) > column {
+                top -= 1;
+            }
+            let mut bottom = start;
+            while bottom < last && indent_at(bottom + 1) > column {
+                bottom += 1;
+            }
+            (bottom >= top).then_some((column, top..=bottom))
+        });
+
+        Self {
+            first_line: first,
+            indents,
+            step,
+            active: active.flatten(),
+        }
+    }
+
+    /// Columns of the guides on `line`, left to right
+    pub fn columns(&self, line: usize) -> impl Iterator<Item = usize> {
+        let indent = line
+            .checked_sub(self.first_line)
+            .and_then(|index| self.indents.get(index))
+            .copied()
+            .unwrap_or(0);
+        (0..indent).step_by(self.step.max(1))
+    }
+
+    /// Whether the guide at `column` on `line` is the cursor's
+    pub fn is_active(&self, line: usize, column: usize) -> bool {
+        self.active
+            .as_ref()
+            .is_some_and(|(active, lines)| *active == column && lines.contains(&line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "\
+fn main() {
+    if ready {
+        go();
+
+        stop();
+    }
+}
+";
+
+    fn guides(cursor_line: usize) -> IndentGuides {
+        let document = Document::with_text(CODE);
+        IndentGuides::compute(&document, &ElasticTabs::default(), 0, 6, cursor_line)
+    }
+
+    #[test]
+    fn guides_sit_at_each_level_and_run_through_blank_lines() {
+        let guides = guides(0);
+        let columns = |line| guides.columns(line).collect::<Vec<_>>();
+        assert_eq!(columns(0), [] as [usize; 0]);
+        assert_eq!(columns(1), [0]);
+        assert_eq!(columns(2), [0, 4]);
+        assert_eq!(columns(3), [0, 4]);
+        assert_eq!(columns(5), [0]);
+    }
+
+    #[test]
+    fn the_guide_of_the_cursors_block_is_active() {
+        // Inside the `if`: its guide, blank line included
+        let guides = guides(4);
+        assert!(guides.is_active(2, 4) && guides.is_active(3, 4));
+        assert!(!guides.is_active(2, 0));
+
+        // On a line opening a block: the guide of that block
+        let guides = guides(0);
+        assert!((1..=5).all(|line| guides.is_active(line, 0)));
+        assert!(!guides.is_active(0, 0));
+    }
+}
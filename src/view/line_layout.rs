@@ -0,0 +1,215 @@
+//! Display layout of a document line
+//!
+//! A line's characters don't map one-to-one onto visual columns: tabs
+//! expand to the next (fixed or elastic) stop, and inline annotations take
+//! up columns in front of the character they are anchored to.
+//! [`LineLayout`] does that mapping for rendering, hit testing and
+//! rectangle selection, so glyphs, cursors and selections agree and
+//! annotations never count as text.
+
+use std::borrow::Cow;
+
+use crate::model::{Annotation, AnnotationPlacement, Document};
+use crate::util::text::{ElasticTabs, TabExpander, TabLayout};
+
+#[derive(Debug, Clone, Default)]
+pub struct LineLayout<'a> {
+    tabs: TabLayout<'a>,
+    /// Inline annotations as `(column, annotation)`, sorted by column
+    inline: Vec<(usize, &'a Annotation)>,
+    end_of_line: Vec<&'a Annotation>,
+}
+
+impl<'a> LineLayout<'a> {
+    /// Layout without annotations
+    pub fn new(tabs: TabLayout<'a>) -> Self {
+        Self {
+            tabs,
+            ..Self::default()
+        }
+    }
+
+    /// Layout of `line` of `document`, with its current annotations
+    pub fn for_line(document: &'a Document, tabs: &'a ElasticTabs, line: usize) -> Self {
+        let mut layout = Self::new(tabs.layout(line));
+        for annotation in document.annotations.on_line(line, document.revision) {
+            match annotation.placement {
+                AnnotationPlacement::Inline { column } => layout.inline.push((column, annotation)),
+                AnnotationPlacement::EndOfLine => layout.end_of_line.push(annotation),
+            }
+        }
+        layout
+    }
+
+    pub fn tabs(&self) -> TabLayout<'a> {
+        self.tabs
+    }
+
+    /// Columns taken by inline annotations at columns `..char_col`, plus
+    /// those at `char_col` itself if `inclusive`
+    fn inline_width(&self, char_col: usize, inclusive: bool) -> usize {
+        self.inline
+            .iter()
+            .take_while(|(column, _)| *column < char_col || (inclusive && *column == char_col))
+            .map(|(_, annotation)| annotation.width())
+            .sum()
+    }
+
+    /// Columns taken by the inline annotations anchored at `char_col`
+    pub fn inline_width_at(&self, char_col: usize) -> usize {
+        self.inline
+            .iter()
+            .filter(|(column, _)| *column == char_col)
+            .map(|(_, annotation)| annotation.width())
+            .sum()
+    }
+
+    /// Visual column the character at `char_col` is drawn at, after any
+    /// annotations in front of it
+    pub fn visual_col(&self, text: &str, char_col: usize) -> usize {
+        crate::util::text::char_col_to_visual_col_with(text, char_col, self.tabs)
+            + self.inline_width(char_col, true)
+    }
+
+    /// Visual column of a cursor at `char_col`: in front of any annotations
+    /// anchored there. Also the visual end of a span ending at `char_col`.
+    pub fn caret_col(&self, text: &str, char_col: usize) -> usize {
+        crate::util::text::char_col_to_visual_col_with(text, char_col, self.tabs)
+            + self.inline_width(char_col, false)
+    }
+
+    /// Char column at `visual_col`; a visual column inside an annotation maps
+    /// to the column the annotation is anchored to
+    pub fn char_col(&self, text: &str, visual_col: usize) -> usize {
+        let mut expander = TabExpander::new(self.tabs);
+        let mut inline = self.inline.iter().peekable();
+        let mut shift = 0;
+        let mut char_col = 0;
+        let mut chars = text.chars();
+        loop {
+            while let Some((_, annotation)) = inline.next_if(|(column, _)| *column <= char_col) {
+                shift += annotation.width();
+                if expander.visual_col() + shift > visual_col {
+                    return char_col;
+                }
+            }
+            if expander.visual_col() + shift >= visual_col {
+                return char_col;
+            }
+            let Some(ch) = chars.next() else {
+                return char_col;
+            };
+            expander.advance(ch);
+            char_col += 1;
+        }
+    }
+
+    /// `text` as drawn: tabs expanded and a space for every column an
+    /// inline annotation takes up, so the rest of the line lines up
+    pub fn display_text<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        if self.inline.is_empty() {
+            return super::geometry::expand_tabs_with(text, self.tabs);
+        }
+        let mut result = String::with_capacity(text.len() + self.inline_width(usize::MAX, true));
+        let mut expander = TabExpander::new(self.tabs);
+        let mut inline = self.inline.iter().peekable();
+        let mut chars = text.chars();
+        let mut char_col = 0;
+        loop {
+            while let Some((_, annotation)) = inline.next_if(|(column, _)| *column <= char_col) {
+                result.push_str(&" ".repeat(annotation.width()));
+            }
+            let Some(ch) = chars.next() else {
+                break;
+            };
+            let width = expander.advance(ch);
+            if ch == '\t' {
+                result.push_str(&" ".repeat(width));
+            } else {
+                result.push(ch);
+            }
+            char_col += 1;
+        }
+        Cow::Owned(result)
+    }
+
+    /// Inline annotations with the visual column each starts at. One past
+    /// the end of the line or beyond is drawn at the end.
+    pub fn inline_annotations<'s>(
+        &'s self,
+        text: &'s str,
+    ) -> impl Iterator<Item = (usize, &'a Annotation)> + 's {
+        let len = text.chars().count();
+        self.inline
+            .iter()
+            .map(move |&(column, annotation)| (self.caret_col(text, column.min(len)), annotation))
+    }
+
+    /// Annotations drawn after the end of the line, in order
+    pub fn end_of_line_annotations(&self) -> &[&'a Annotation] {
+        &self.end_of_line
+    }
+
+    /// Visual column just past the line's text and inline annotations
+    pub fn line_end(&self, text: &str) -> usize {
+        crate::util::text::char_col_to_visual_col_with(text, text.chars().count(), self.tabs)
+            + self.inline_width(usize::MAX, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::AnnotationSource;
+
+    fn document_with(annotations: Vec<Annotation>) -> Document {
+        let mut document = Document::with_text("f(a\tb)\n");
+        let revision = document.revision;
+        document
+            .annotations
+            .set(AnnotationSource::ParameterHints, revision, annotations);
+        document
+    }
+
+    #[test]
+    fn inline_annotations_shift_text_but_not_columns() {
+        let document = document_with(vec![
+            Annotation::inline(0, 2, "x: "),
+            Annotation::end_of_line(0, "blame"),
+        ]);
+        let tabs = ElasticTabs::default();
+        let layout = LineLayout::for_line(&document, &tabs, 0);
+        let text = "f(a\tb)";
+
+        // The annotation itself is drawn over the columns left free for it
+        assert_eq!(layout.display_text(text), "f(   a b)");
+        assert_eq!(layout.visual_col(text, 2), 5);
+        assert_eq!(layout.caret_col(text, 2), 2);
+        assert_eq!(layout.visual_col(text, 4), 7);
+        assert_eq!(layout.line_end(text), 9);
+        let inline: Vec<_> = layout
+            .inline_annotations(text)
+            .map(|(col, a)| (col, a.text.as_str()))
+            .collect();
+        assert_eq!(inline, [(2, "x: ")]);
+        assert_eq!(layout.end_of_line_annotations()[0].text, "blame");
+
+        // Clicks on the annotation land on its anchor, clicks after it on the
+        // text behind it
+        assert_eq!(layout.char_col(text, 1), 1);
+        assert_eq!(layout.char_col(text, 3), 2);
+        assert_eq!(layout.char_col(text, 5), 2);
+        assert_eq!(layout.char_col(text, 6), 3);
+        assert_eq!(layout.char_col(text, 7), 4);
+        assert_eq!(layout.char_col(text, 50), 6);
+    }
+
+    #[test]
+    fn stale_annotations_are_not_laid_out() {
+        let mut document = document_with(vec![Annotation::inline(0, 2, "x: ")]);
+        document.revision += 1;
+        let tabs = ElasticTabs::default();
+        let layout = LineLayout::for_line(&document, &tabs, 0);
+        assert_eq!(layout.display_text("f(a\tb)"), "f(a b)");
+    }
+}
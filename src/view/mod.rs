@@ -3,21 +3,29 @@
 //! Contains the Renderer struct and all rendering-related functionality.
 
 pub mod button;
+pub mod completion;
 pub mod editor_scrollbars;
 pub mod editor_special_tabs;
 pub mod editor_text;
+pub mod fonts;
 pub mod frame;
 pub mod geometry;
+#[cfg(feature = "gpu")]
+pub mod gpu;
 pub mod helpers;
 pub mod hit_test;
+pub mod indent_guides;
+pub mod line_layout;
 pub mod modal;
 pub mod panels;
+pub mod scene;
 pub mod scrollbar;
 pub mod selectable_list;
 pub mod text_field;
 pub mod tree_view;
 
 pub use button::{button_rect, render_button, ButtonState};
+pub use fonts::EditorFonts;
 pub use frame::{Frame, TextPainter};
 pub use helpers::get_tab_display_name;
 pub use text_field::{TextFieldContent, TextFieldOptions, TextFieldRenderer};
@@ -34,7 +42,8 @@ pub use hit_test::{
 };
 
 use anyhow::Result;
-use fontdue::{Font, FontSettings, LineMetrics, Metrics};
+use fontdue::{LineMetrics, Metrics};
+use serde::{Deserialize, Serialize};
 use softbuffer::Surface;
 use std::collections::HashMap;
 use std::num::NonZeroU32;
@@ -59,6 +68,47 @@ pub type GlyphCacheKey = (char, u32);
 
 pub type GlyphCache = HashMap<GlyphCacheKey, (Metrics, Vec<u8>)>;
 
+/// Which renderer draws the window (`renderer` in config.yaml)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RendererBackend {
+    /// GPU when built with it and a hardware adapter is available,
+    /// otherwise software
+    #[default]
+    Auto,
+    /// CPU rasterization presented with softbuffer
+    Software,
+    /// wgpu (needs the `gpu` build feature)
+    Gpu,
+}
+
+/// How text is antialiased (`text_antialiasing` in config.yaml)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextAntialiasing {
+    /// Subpixel on low-DPI landscape screens, grayscale otherwise
+    #[default]
+    Auto,
+    /// One coverage value per pixel
+    Grayscale,
+    /// Red, green and blue coverage per pixel, for RGB-striped LCDs
+    Subpixel,
+}
+
+impl TextAntialiasing {
+    /// Whether to render subpixel text at `scale_factor`. Subpixel
+    /// rendering only helps where pixels are big enough to see fringes
+    /// smoothed, and needs horizontal stripes in RGB order, which a rotated
+    /// (portrait) screen doesn't have.
+    pub fn use_subpixel(self, scale_factor: f64, portrait: bool) -> bool {
+        match self {
+            TextAntialiasing::Auto => scale_factor < 1.5 && !portrait,
+            TextAntialiasing::Grayscale => false,
+            TextAntialiasing::Subpixel => true,
+        }
+    }
+}
+
 /// Controls how preview panes render their content
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PreviewRenderMode {
@@ -80,6 +130,7 @@ struct RenderPlan {
     show_modal: bool,
     show_drop_overlay: bool,
     show_tab_drag_ghost: bool,
+    show_completion: bool,
     #[cfg(debug_assertions)]
     show_perf_overlay: bool,
     #[cfg(debug_assertions)]
@@ -103,33 +154,97 @@ struct RenderSession<'buffer, 'a> {
 impl<'buffer, 'a> RenderSession<'buffer, 'a> {
     #[allow(clippy::too_many_arguments)]
     fn new(
-        buffer: &'buffer mut [u32],
-        window_width: usize,
-        window_height: usize,
-        font: &'a Font,
+        frame: Frame<'buffer>,
+        fonts: &'a EditorFonts,
         glyph_cache: &'a mut GlyphCache,
         font_size: f32,
         ascent: f32,
         char_width: f32,
         line_height: usize,
+        subpixel: bool,
         model: &'a AppModel,
         plan: &'a RenderPlan,
     ) -> Self {
         Self {
-            frame: Frame::new(buffer, window_width, window_height),
+            frame,
             painter: TextPainter::new(
-                font,
+                &fonts.primary,
                 glyph_cache,
                 font_size,
                 ascent,
                 char_width,
                 line_height,
-            ),
+            )
+            .with_fallbacks(&fonts.fallbacks)
+            .with_subpixel(subpixel),
             model,
             plan,
         }
     }
 
+    /// Draw everything the plan asks for, in stacking order
+    fn render_phases(&mut self, perf: &mut crate::perf::PerfStats) {
+        if self.plan.render_editor {
+            self.render_editor_area_phase(perf);
+            if self.plan.window_layout.sidebar_rect.is_some() {
+                perf.measure_stage(crate::perf::PerfStage::Sidebar, || {
+                    self.render_sidebar_phase();
+                });
+            }
+            if self.plan.window_layout.right_dock_rect.is_some() {
+                perf.measure_stage(crate::perf::PerfStage::RightDock, || {
+                    self.render_right_dock_phase();
+                });
+            }
+            if self.plan.window_layout.bottom_dock_rect.is_some() {
+                perf.measure_stage(crate::perf::PerfStage::BottomDock, || {
+                    self.render_bottom_dock_phase();
+                });
+            }
+        }
+
+        if self.plan.render_status_bar {
+            perf.measure_stage(crate::perf::PerfStage::StatusBar, || {
+                self.render_status_bar_phase();
+            });
+        }
+
+        if self.plan.show_completion {
+            self.render_completion_phase();
+        }
+
+        if self.plan.show_modal {
+            perf.measure_stage(crate::perf::PerfStage::Modal, || {
+                self.render_modal_phase();
+            });
+        }
+        if self.plan.show_drop_overlay {
+            perf.measure_stage(crate::perf::PerfStage::DropOverlay, || {
+                self.render_drop_overlay_phase();
+            });
+        }
+        if self.plan.show_tab_drag_ghost {
+            self.render_tab_drag_ghost_phase();
+        }
+
+        #[cfg(debug_assertions)]
+        if self.plan.show_perf_overlay {
+            let start = std::time::Instant::now();
+            self.render_perf_overlay_phase(perf);
+            perf.record_stage_elapsed(crate::perf::PerfStage::PerfOverlay, start.elapsed());
+        }
+
+        #[cfg(debug_assertions)]
+        if self.plan.show_debug_overlay {
+            perf.measure_stage(crate::perf::PerfStage::DebugOverlay, || {
+                self.render_debug_overlay_phase();
+            });
+        }
+
+        #[cfg(debug_assertions)]
+        self.record_cache_stats(perf);
+    }
+
     fn render_editor_area_phase(&mut self, perf: &mut crate::perf::PerfStats) {
         Renderer::render_editor_area(
             &mut self.frame,
@@ -220,6 +335,14 @@ impl<'buffer, 'a> RenderSession<'buffer, 'a> {
         );
     }
 
+    fn render_completion_phase(&mut self) {
+        if !self.plan.show_completion {
+            return;
+        }
+
+        completion::render_completion_popup(&mut self.frame, &mut self.painter, self.model);
+    }
+
     fn render_tab_drag_ghost_phase(&mut self) {
         if !self.plan.show_tab_drag_ghost {
             return;
@@ -267,11 +390,13 @@ impl<'buffer, 'a> RenderSession<'buffer, 'a> {
         let bg_color = self.model.theme.overlay.background.to_argb_u32();
         let fg_color = self.model.theme.overlay.foreground.to_argb_u32();
 
-        for py in overlay_y..(overlay_y + overlay_height).min(self.plan.window_height) {
-            for px in overlay_x..(overlay_x + overlay_width).min(self.plan.window_width) {
-                self.frame.blend_pixel(px, py, bg_color);
-            }
-        }
+        self.frame.blend_rect_px(
+            overlay_x,
+            overlay_y,
+            overlay_width,
+            overlay_height,
+            bg_color,
+        );
 
         for (i, line) in lines.iter().enumerate() {
             let text_x = overlay_x + 10;
@@ -298,9 +423,15 @@ enum EditorContentKind<'a> {
     Image {
         state: &'a crate::image::ImageState,
     },
+    Diff {
+        state: &'a crate::diff::DiffState,
+    },
     BinaryPlaceholder {
         placeholder: &'a crate::model::editor::BinaryPlaceholderState,
     },
+    Welcome {
+        state: &'a crate::model::editor::WelcomeState,
+    },
 }
 
 struct EditorGroupScene<'a> {
@@ -329,8 +460,12 @@ impl<'a> EditorGroupScene<'a> {
             editor.tab_content
         {
             EditorContentKind::BinaryPlaceholder { placeholder }
+        } else if let crate::model::editor::TabContent::Welcome(ref state) = editor.tab_content {
+            EditorContentKind::Welcome { state }
         } else if let Some(state) = editor.view_mode.as_image() {
             EditorContentKind::Image { state }
+        } else if let Some(state) = editor.view_mode.as_diff() {
+            EditorContentKind::Diff { state }
         } else if let Some(state) = editor.view_mode.as_csv() {
             EditorContentKind::Csv { state }
         } else {
@@ -358,6 +493,7 @@ impl<'a> EditorGroupScene<'a> {
     ) {
         perf.measure_stage(crate::perf::PerfStage::TabBar, || {
             Renderer::render_tab_bar(frame, painter, model, self.group, &self.layout);
+            Renderer::render_breadcrumbs(frame, painter, model, self.group);
         });
         self.render_content(frame, painter, model, perf);
 
@@ -416,6 +552,11 @@ impl<'a> EditorGroupScene<'a> {
                     Renderer::render_image_tab(frame, model, state, &self.layout);
                 });
             }
+            EditorContentKind::Diff { state } => {
+                perf.measure_stage(crate::perf::PerfStage::Diff, || {
+                    Renderer::render_diff_view(frame, painter, model, state, &self.layout);
+                });
+            }
             EditorContentKind::BinaryPlaceholder { placeholder } => {
                 perf.measure_stage(crate::perf::PerfStage::BinaryPlaceholder, || {
                     Renderer::render_binary_placeholder(
@@ -429,6 +570,18 @@ impl<'a> EditorGroupScene<'a> {
                     );
                 });
             }
+            EditorContentKind::Welcome { state } => {
+                perf.measure_stage(crate::perf::PerfStage::Welcome, || {
+                    Renderer::render_welcome(
+                        frame,
+                        painter,
+                        model,
+                        state,
+                        &self.layout,
+                        self.is_focused,
+                    );
+                });
+            }
         }
     }
 
@@ -536,10 +689,20 @@ impl<'a> PreviewPaneScene<'a> {
     }
 }
 
+/// Where finished frames go
+enum RenderTarget {
+    Software(Surface<Rc<Window>, Rc<Window>>),
+    #[cfg(feature = "gpu")]
+    Gpu(Box<gpu::GpuRenderer>),
+    /// No window: frames stay in the back buffer (end-to-end tests)
+    #[cfg(feature = "headless")]
+    Headless,
+}
+
 pub struct Renderer {
-    font: Font,
-    surface: Surface<Rc<Window>, Rc<Window>>,
-    /// Persistent back buffer for partial rendering.
+    fonts: Rc<EditorFonts>,
+    target: RenderTarget,
+    /// Persistent back buffer for partial rendering (software only).
     /// Softbuffer doesn't guarantee buffer contents are preserved between frames,
     /// so we maintain our own buffer and copy to the surface on present.
     back_buffer: Vec<u32>,
@@ -550,60 +713,129 @@ pub struct Renderer {
     glyph_cache: GlyphCache,
     char_width: f32,
     scale_factor: f64,
+    /// Whether the glyph cache holds subpixel glyphs
+    subpixel_text: bool,
+    /// The window is on a rotated (portrait) monitor
+    portrait_monitor: bool,
 }
 
 impl Renderer {
     /// Create a new renderer, automatically detecting the window's scale factor
-    pub fn new(window: Rc<Window>, context: &softbuffer::Context<Rc<Window>>) -> Result<Self> {
+    pub fn new(
+        window: Rc<Window>,
+        context: &softbuffer::Context<Rc<Window>>,
+        fonts: Rc<EditorFonts>,
+        font_size: f32,
+        backend: RendererBackend,
+    ) -> Result<Self> {
         let scale_factor = window.scale_factor();
-        Self::with_scale_factor(window, context, scale_factor)
+        Self::with_scale_factor(window, context, scale_factor, fonts, font_size, backend)
     }
 
-    /// Create a new renderer with an explicit scale factor
+    /// Create a new renderer with an explicit scale factor. `font_size` is
+    /// in points, before scaling.
     pub fn with_scale_factor(
         window: Rc<Window>,
         context: &softbuffer::Context<Rc<Window>>,
         scale_factor: f64,
+        fonts: Rc<EditorFonts>,
+        font_size: f32,
+        backend: RendererBackend,
     ) -> Result<Self> {
         let (width, height) = {
             let size = window.inner_size();
             (size.width, size.height)
         };
 
-        let mut surface = Surface::new(context, Rc::clone(&window))
-            .map_err(|e| anyhow::anyhow!("Failed to create surface: {}", e))?;
+        let target = match Self::create_gpu_target(&window, backend) {
+            Some(target) => target,
+            None => {
+                let mut surface = Surface::new(context, Rc::clone(&window))
+                    .map_err(|e| anyhow::anyhow!("Failed to create surface: {}", e))?;
+
+                // Explicitly resize the surface to match window dimensions
+                // This is critical after DPI changes when the physical size changes
+                surface
+                    .resize(
+                        NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
+                        NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
+                    )
+                    .map_err(|e| anyhow::anyhow!("Failed to resize surface: {}", e))?;
+                tracing::info!("Using the software renderer");
+                RenderTarget::Software(surface)
+            }
+        };
 
-        // Explicitly resize the surface to match window dimensions
-        // This is critical after DPI changes when the physical size changes
-        surface
-            .resize(
-                NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
-                NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
-            )
-            .map_err(|e| anyhow::anyhow!("Failed to resize surface: {}", e))?;
+        let portrait_monitor = window.current_monitor().is_some_and(|monitor| {
+            let size = monitor.size();
+            size.height > size.width
+        });
 
-        let font = Font::from_bytes(
-            include_bytes!("../../assets/JetBrainsMono.ttf") as &[u8],
-            FontSettings::default(),
+        Self::with_target(
+            target,
+            width,
+            height,
+            scale_factor,
+            fonts,
+            font_size,
+            portrait_monitor,
         )
-        .map_err(|e| anyhow::anyhow!("Failed to load font: {}", e))?;
+    }
 
-        let font_size = 14.0 * scale_factor as f32;
+    /// Create a renderer that draws into its back buffer only, for driving
+    /// the runtime without a window
+    #[cfg(feature = "headless")]
+    pub fn headless(
+        width: u32,
+        height: u32,
+        scale_factor: f64,
+        fonts: Rc<EditorFonts>,
+        font_size: f32,
+    ) -> Result<Self> {
+        Self::with_target(
+            RenderTarget::Headless,
+            width,
+            height,
+            scale_factor,
+            fonts,
+            font_size,
+            false,
+        )
+    }
 
-        let line_metrics = font
+    fn with_target(
+        target: RenderTarget,
+        width: u32,
+        height: u32,
+        scale_factor: f64,
+        fonts: Rc<EditorFonts>,
+        font_size: f32,
+        portrait_monitor: bool,
+    ) -> Result<Self> {
+        // A hand-edited config can hold any size
+        let font_size = font_size.clamp(crate::config::MIN_FONT_SIZE, crate::config::MAX_FONT_SIZE)
+            * scale_factor as f32;
+
+        let line_metrics = fonts
+            .primary
             .horizontal_line_metrics(font_size)
-            .expect("Font missing horizontal line metrics");
+            .ok_or_else(|| anyhow::anyhow!("Font missing horizontal line metrics"))?;
 
-        let (metrics, _) = font.rasterize('M', font_size);
+        let (metrics, _) = fonts.primary.rasterize('M', font_size);
         let char_width = metrics.advance_width;
 
         // Initialize back buffer with enough space for the window
-        let buffer_size = (width as usize) * (height as usize);
-        let back_buffer = vec![0u32; buffer_size];
+        let back_buffer = match target {
+            RenderTarget::Software(_) => vec![0u32; (width as usize) * (height as usize)],
+            #[cfg(feature = "gpu")]
+            RenderTarget::Gpu(_) => Vec::new(),
+            #[cfg(feature = "headless")]
+            RenderTarget::Headless => vec![0u32; (width as usize) * (height as usize)],
+        };
 
         Ok(Self {
-            font,
-            surface,
+            fonts,
+            target,
             back_buffer,
             width,
             height,
@@ -612,9 +844,46 @@ impl Renderer {
             glyph_cache: HashMap::new(),
             char_width,
             scale_factor,
+            subpixel_text: false,
+            portrait_monitor,
         })
     }
 
+    /// The GPU renderer for `backend`, or `None` to render in software
+    #[cfg(feature = "gpu")]
+    fn create_gpu_target(window: &Rc<Window>, backend: RendererBackend) -> Option<RenderTarget> {
+        if backend == RendererBackend::Software {
+            return None;
+        }
+        // A software adapter is only worth it when asked for explicitly
+        let allow_software_adapter = backend == RendererBackend::Gpu;
+        match gpu::GpuRenderer::new(Rc::clone(window), allow_software_adapter) {
+            Ok(renderer) => {
+                tracing::info!("Using the GPU renderer on {}", renderer.adapter_name());
+                Some(RenderTarget::Gpu(Box::new(renderer)))
+            }
+            Err(e) => {
+                tracing::warn!("GPU renderer unavailable, using software: {}", e);
+                None
+            }
+        }
+    }
+
+    /// The GPU renderer for `backend`, or `None` to render in software
+    #[cfg(not(feature = "gpu"))]
+    fn create_gpu_target(_window: &Rc<Window>, backend: RendererBackend) -> Option<RenderTarget> {
+        if backend == RendererBackend::Gpu {
+            tracing::warn!("Built without the `gpu` feature, using the software renderer");
+        }
+        None
+    }
+
+    /// The fonts text is drawn with, to reuse in a renderer rebuilt for
+    /// another size or scale factor
+    pub fn fonts(&self) -> &Rc<EditorFonts> {
+        &self.fonts
+    }
+
     /// Get the current scale factor
     pub fn scale_factor(&self) -> f64 {
         self.scale_factor
@@ -632,6 +901,12 @@ impl Renderer {
         (self.width, self.height)
     }
 
+    /// Last rendered frame, `width * height` pixels row by row
+    #[cfg(feature = "headless")]
+    pub fn frame_buffer(&self) -> &[u32] {
+        &self.back_buffer
+    }
+
     // =========================================================================
     // Damage Tracking Helpers
     // =========================================================================
@@ -641,6 +916,7 @@ impl Renderer {
     /// Forces full redraw when:
     /// - Modal is active (background dim accumulation)
     /// - Drop overlay is showing
+    /// - Completion popup is open (it overlaps lines outside the damage)
     /// - Debug overlays are visible (debug builds only)
     fn compute_effective_damage(
         &self,
@@ -657,6 +933,10 @@ impl Renderer {
             return Damage::Full;
         }
 
+        if model.ui.completion.is_some() {
+            return Damage::Full;
+        }
+
         // Debug builds: force full for perf/debug overlays
         #[cfg(debug_assertions)]
         {
@@ -726,6 +1006,7 @@ impl Renderer {
             show_modal: model.ui.active_modal.is_some(),
             show_drop_overlay: model.ui.drop_state.is_hovering,
             show_tab_drag_ghost: model.ui.tab_drag.is_some_and(|d| d.active),
+            show_completion: model.ui.completion.is_some(),
             #[cfg(debug_assertions)]
             show_perf_overlay,
             #[cfg(debug_assertions)]
@@ -1137,6 +1418,16 @@ impl Renderer {
         editor_special_tabs::render_image_tab(frame, model, img_state, layout);
     }
 
+    fn render_diff_view(
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        model: &AppModel,
+        state: &crate::diff::DiffState,
+        layout: &geometry::GroupLayout,
+    ) {
+        editor_special_tabs::render_diff_view(frame, painter, model, state, layout);
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn render_binary_placeholder(
         frame: &mut Frame,
@@ -1158,6 +1449,17 @@ impl Renderer {
         );
     }
 
+    fn render_welcome(
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        model: &AppModel,
+        state: &crate::model::editor::WelcomeState,
+        layout: &geometry::GroupLayout,
+        focused: bool,
+    ) {
+        editor_special_tabs::render_welcome(frame, painter, model, state, layout, focused);
+    }
+
     fn render_tab_bar(
         frame: &mut Frame,
         painter: &mut TextPainter,
@@ -1200,6 +1502,15 @@ impl Renderer {
             };
 
             frame.fill_rect_px(tab.x, tab.y, tab.width, tab.height, bg_color);
+            if let Some(color) = tab.color {
+                frame.fill_rect_px(
+                    tab.x,
+                    tab.y,
+                    tab.width,
+                    metrics.border_width * 2,
+                    color.color().to_argb_u32(),
+                );
+            }
             // Clip the title to the tab rect: edge tabs are width-clamped to
             // the group, and unclipped text would bleed into the next pane.
             frame.set_clip(Rect::new(
@@ -1208,11 +1519,76 @@ impl Renderer {
                 tab.width as f32,
                 tab.height as f32,
             ));
-            painter.draw(frame, tab.text_x, tab.text_y, &tab.title, fg_color);
+            if tab.is_preview {
+                let italic = frame::GlyphStyle {
+                    italic: true,
+                    ..frame::GlyphStyle::default()
+                };
+                let styles = vec![italic; tab.title.chars().count()];
+                painter.draw_styled(
+                    frame,
+                    tab.text_x,
+                    tab.text_y,
+                    &tab.title,
+                    &[fg_color],
+                    &styles,
+                );
+            } else {
+                painter.draw(frame, tab.text_x, tab.text_y, &tab.title, fg_color);
+            }
             frame.clear_clip();
         }
     }
 
+    /// Draw the file name and symbol path of the active tab under the tab bar
+    fn render_breadcrumbs(
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        model: &AppModel,
+        group: &EditorGroup,
+    ) {
+        let Some(breadcrumbs) = geometry::BreadcrumbLayout::new(group, model, painter.char_width())
+        else {
+            return;
+        };
+
+        let theme = &model.theme.tab_bar;
+        frame.fill_rect_px(
+            breadcrumbs.rect_x,
+            breadcrumbs.rect_y,
+            breadcrumbs.rect_w,
+            breadcrumbs.rect_h,
+            theme.active_background.to_argb_u32(),
+        );
+
+        frame.set_clip(Rect::new(
+            breadcrumbs.rect_x as f32,
+            breadcrumbs.rect_y as f32,
+            breadcrumbs.rect_w as f32,
+            breadcrumbs.rect_h as f32,
+        ));
+        let dim = theme.inactive_foreground.to_argb_u32();
+        for &x in &breadcrumbs.separators {
+            painter.draw(
+                frame,
+                x,
+                breadcrumbs.text_y,
+                geometry::BreadcrumbLayout::SEPARATOR,
+                dim,
+            );
+        }
+        let last = breadcrumbs.crumbs.len() - 1;
+        for (i, crumb) in breadcrumbs.crumbs.iter().enumerate() {
+            let color = if i == last {
+                theme.active_foreground.to_argb_u32()
+            } else {
+                dim
+            };
+            painter.draw(frame, crumb.x, breadcrumbs.text_y, &crumb.text, color);
+        }
+        frame.clear_clip();
+    }
+
     pub fn render_splitters(frame: &mut Frame, splitters: &[SplitterBar], model: &AppModel) {
         let splitter_color = model.theme.splitter.background.to_argb_u32();
 
@@ -1246,6 +1622,7 @@ impl Renderer {
     /// Draws:
     /// - Row numbers column
     /// - Column headers (A, B, C, ...)
+    /// - Filter row (when shown)
     /// - Cell grid with data
     /// - Selected cell highlight
     fn render_csv_grid(
@@ -1256,7 +1633,7 @@ impl Renderer {
         layout: &geometry::GroupLayout,
         is_focused: bool,
     ) {
-        use crate::csv::render::{column_to_letters, truncate_text, CsvRenderLayout};
+        use crate::csv::render::{truncate_text, CsvRenderLayout};
 
         let char_width = painter.char_width();
         let line_height = painter.line_height();
@@ -1300,28 +1677,77 @@ impl Renderer {
             header_bg,
         );
 
-        // Draw column headers (A, B, C, ...)
+        // Draw column headers (the header row's names, or A, B, C, ...),
+        // with an arrow on the sorted one
         for (i, &(col_idx, col_x)) in layout.visible_columns.iter().enumerate() {
             let col_width_px = layout.column_widths_px.get(i).copied().unwrap_or(50);
-            let letter = column_to_letters(col_idx);
+            let col_width_chars = csv.column_widths.get(col_idx).copied().unwrap_or(10);
+            let arrow = match csv.sorted_by {
+                Some((col, true)) if col == col_idx => " ▲",
+                Some((col, false)) if col == col_idx => " ▼",
+                _ => "",
+            };
+            let mut label = truncate_text(
+                &csv.column_name(col_idx),
+                col_width_chars.saturating_sub(arrow.chars().count()).max(1),
+            );
+            label.push_str(arrow);
 
-            // Center the letter in the column
-            let text_width = (letter.len() as f32 * char_width).ceil() as usize;
+            // Center the label in the column
+            let text_width = (label.chars().count() as f32 * char_width).ceil() as usize;
             let text_x = layout.grid_x + col_x + (col_width_px.saturating_sub(text_width)) / 2;
 
-            painter.draw(frame, text_x, layout.col_header_y, &letter, header_fg);
+            painter.draw(frame, text_x, layout.col_header_y, &label, header_fg);
+        }
+
+        // Draw the filter row: each column's filter text, with a caret in
+        // the one being typed into
+        if csv.show_filter_row {
+            let y = layout.filter_row_y;
+            for (i, &(col_idx, col_x)) in layout.visible_columns.iter().enumerate() {
+                let col_width_px = layout.column_widths_px.get(i).copied().unwrap_or(50);
+                let col_width_chars = csv.column_widths.get(col_idx).copied().unwrap_or(10);
+                let cell_x = layout.grid_x + col_x;
+                let focused = is_focused && csv.filter_focus == Some(col_idx);
+                if focused {
+                    frame.fill_rect_px(
+                        cell_x + 1,
+                        y + 1,
+                        col_width_px.saturating_sub(2),
+                        layout.filter_row_height.saturating_sub(2),
+                        selection_bg,
+                    );
+                }
+
+                let text = truncate_text(csv.filter(col_idx), col_width_chars);
+                painter.draw(frame, cell_x + 4, y + 1, &text, fg_color);
+                if focused && model.ui.cursor_visible {
+                    let caret_x =
+                        cell_x + 4 + (text.chars().count() as f32 * char_width).round() as usize;
+                    let caret_color = theme.editor.cursor_color.to_argb_u32();
+                    frame.fill_rect_px(
+                        caret_x,
+                        y + 2,
+                        2,
+                        line_height.saturating_sub(4),
+                        caret_color,
+                    );
+                }
+            }
         }
 
-        // Calculate visible rows
-        let visible_rows = content_h.saturating_sub(layout.col_header_height) / line_height;
-        let end_row = (csv.viewport.top_row + visible_rows).min(csv.data.row_count());
+        // Calculate visible rows (grid positions, which skip rows a filter
+        // hides)
+        let visible_rows = content_h
+            .saturating_sub(layout.col_header_height + layout.filter_row_height)
+            / line_height;
+        let end_row = (csv.viewport.top_row + visible_rows).min(csv.visible_row_count());
 
-        // Draw row headers (1, 2, 3, ...)
+        // Draw row headers (1, 2, 3, ...), numbering rows as in the file
         for screen_row in 0..visible_rows {
-            let data_row = csv.viewport.top_row + screen_row;
-            if data_row >= csv.data.row_count() {
+            let Some(data_row) = csv.data_row(csv.viewport.top_row + screen_row) else {
                 break;
-            }
+            };
 
             let y = layout.data_y + screen_row * line_height;
             let row_label = format!("{}", data_row + 1);
@@ -1361,8 +1787,8 @@ impl Renderer {
         }
 
         // Pre-calculate selected cell geometry for background drawing
-        let selection_geom = if is_focused {
-            let sel_row = csv.selected_cell.row;
+        let selection_geom = if is_focused && csv.filter_focus.is_none() {
+            let sel_row = csv.view_row(csv.selected_cell.row);
             let sel_col = csv.selected_cell.col;
 
             if sel_row >= csv.viewport.top_row && sel_row < end_row {
@@ -1389,8 +1815,44 @@ impl Renderer {
             None
         };
 
+        // Draw a range selection as one rectangle over its visible cells
+        if is_focused && csv.has_range() {
+            let (start, end) = csv.selection_range();
+            let first_row = csv.view_row(start.row).max(csv.viewport.top_row);
+            let last_row = csv.view_row(end.row).min(end_row.saturating_sub(1));
+            let range_cols: Vec<(usize, usize)> = layout
+                .visible_columns
+                .iter()
+                .enumerate()
+                .filter(|(_, &(col_idx, _))| (start.col..=end.col).contains(&col_idx))
+                .map(|(screen_col, &(_, col_x))| {
+                    let col_width_px = layout
+                        .column_widths_px
+                        .get(screen_col)
+                        .copied()
+                        .unwrap_or(50);
+                    (col_x, col_width_px)
+                })
+                .collect();
+            if let (Some(&(first_x, _)), Some(&(last_x, last_w))) =
+                (range_cols.first(), range_cols.last())
+            {
+                if first_row <= last_row {
+                    let y = layout.data_y + (first_row - csv.viewport.top_row) * line_height;
+                    frame.fill_rect_px(
+                        layout.grid_x + first_x + 1,
+                        y + 1,
+                        (last_x + last_w).saturating_sub(first_x + 2),
+                        ((last_row - first_row + 1) * line_height).saturating_sub(2),
+                        selection_bg,
+                    );
+                }
+            }
+        }
+
         // Draw selection background BEFORE cells so text is visible on top
-        if let Some((cell_x, cell_y, col_width_px)) = selection_geom {
+        // (a range already covers the selected cell)
+        if let Some((cell_x, cell_y, col_width_px)) = selection_geom.filter(|_| !csv.has_range()) {
             frame.fill_rect_px(
                 cell_x + 1,
                 cell_y + 1,
@@ -1402,10 +1864,9 @@ impl Renderer {
 
         // Draw cells
         for screen_row in 0..visible_rows {
-            let data_row = csv.viewport.top_row + screen_row;
-            if data_row >= csv.data.row_count() {
+            let Some(data_row) = csv.data_row(csv.viewport.top_row + screen_row) else {
                 break;
-            }
+            };
 
             let y = layout.data_y + screen_row * line_height;
 
@@ -1485,10 +1946,11 @@ impl Renderer {
         let pos = &edit_state.position;
 
         // Check if cell is visible
-        if pos.row < csv.viewport.top_row {
+        let view_row = csv.view_row(pos.row);
+        if view_row < csv.viewport.top_row {
             return;
         }
-        let screen_row = pos.row - csv.viewport.top_row;
+        let screen_row = view_row - csv.viewport.top_row;
         if screen_row >= csv.viewport.visible_rows {
             return;
         }
@@ -1619,11 +2081,29 @@ impl Renderer {
     }
 
     /// Render a floating semi-transparent copy of the dragged tab at the
-    /// cursor position (drawn topmost, after all panes and overlays).
+    /// cursor position (drawn topmost, after all panes and overlays), over
+    /// the part of a pane a drop on its edge would split off.
     fn render_tab_drag_ghost(frame: &mut Frame, painter: &mut TextPainter, model: &AppModel) {
         let Some(drag) = model.ui.tab_drag.filter(|d| d.active) else {
             return;
         };
+        if let Some(area) = drag
+            .split_target
+            .and_then(|(group_id, edge)| Some((model.editor_area.groups.get(&group_id)?, edge)))
+            .map(|(group, edge)| edge.preview_rect(group.rect))
+        {
+            let highlight = model.theme.overlay.highlight;
+            frame.blend_rect(area, highlight.with_alpha(0x40).to_argb_u32());
+            let bw = model.metrics.border_width.max(1);
+            let (x, y) = (area.x.round() as usize, area.y.round() as usize);
+            let (w, h) = (area.width.round() as usize, area.height.round() as usize);
+            let border = highlight.to_argb_u32();
+            frame.fill_rect_px(x, y, w, bw, border);
+            frame.fill_rect_px(x, (y + h).saturating_sub(bw), w, bw, border);
+            frame.fill_rect_px(x, y, bw, h, border);
+            frame.fill_rect_px((x + w).saturating_sub(bw), y, bw, h, border);
+        }
+
         let Some(tab) = model
             .editor_area
             .groups
@@ -1694,15 +2174,44 @@ impl Renderer {
             self.width = model.window_size.0;
             self.height = model.window_size.1;
 
-            // Resize back buffer to match new window size
-            let new_size = (self.width as usize) * (self.height as usize);
-            self.back_buffer.resize(new_size, 0);
+            match &mut self.target {
+                RenderTarget::Software(surface) => {
+                    // Resize back buffer to match new window size
+                    let new_size = (self.width as usize) * (self.height as usize);
+                    self.back_buffer.resize(new_size, 0);
+
+                    let width = NonZeroU32::new(self.width.max(1)).unwrap();
+                    let height = NonZeroU32::new(self.height.max(1)).unwrap();
+                    surface
+                        .resize(width, height)
+                        .map_err(|e| anyhow::anyhow!("Failed to resize surface: {}", e))?;
+                }
+                #[cfg(feature = "gpu")]
+                RenderTarget::Gpu(gpu) => gpu.resize(self.width, self.height),
+                #[cfg(feature = "headless")]
+                RenderTarget::Headless => {
+                    let new_size = (self.width as usize) * (self.height as usize);
+                    self.back_buffer.resize(new_size, 0);
+                }
+            }
+        }
+
+        #[cfg(feature = "gpu")]
+        if matches!(self.target, RenderTarget::Gpu(_)) {
+            return self.render_gpu(model, perf);
+        }
 
-            let width = NonZeroU32::new(self.width.max(1)).unwrap();
-            let height = NonZeroU32::new(self.height.max(1)).unwrap();
-            self.surface
-                .resize(width, height)
-                .map_err(|e| anyhow::anyhow!("Failed to resize surface: {}", e))?;
+        let subpixel_text = model
+            .config
+            .text_antialiasing
+            .use_subpixel(self.scale_factor, self.portrait_monitor);
+        if subpixel_text != self.subpixel_text {
+            self.subpixel_text = subpixel_text;
+            self.glyph_cache.clear();
+            // Text everywhere changes, not just in the damaged areas
+            if !matches!(damage, Damage::Full) {
+                return self.render(model, perf, &Damage::Full);
+            }
         }
 
         let line_height = self.line_metrics.new_line_size.ceil() as usize;
@@ -1739,13 +2248,15 @@ impl Renderer {
         if let Some(ref dirty_lines) = plan.cursor_lines_only {
             let mut frame = Frame::new(&mut self.back_buffer, width_usize, height_usize);
             let mut painter = TextPainter::new(
-                &self.font,
+                &self.fonts.primary,
                 &mut self.glyph_cache,
                 font_size,
                 ascent,
                 char_width,
                 line_height,
-            );
+            )
+            .with_fallbacks(&self.fonts.fallbacks)
+            .with_subpixel(subpixel_text);
             perf.measure_stage(crate::perf::PerfStage::CursorFastPath, || {
                 editor_text::render_cursor_lines_only(&mut frame, &mut painter, model, dirty_lines);
             });
@@ -1768,74 +2279,19 @@ impl Renderer {
             }
         } else {
             let mut session = RenderSession::new(
-                &mut self.back_buffer,
-                width_usize,
-                height_usize,
-                &self.font,
+                Frame::new(&mut self.back_buffer, width_usize, height_usize),
+                &self.fonts,
                 &mut self.glyph_cache,
                 font_size,
                 ascent,
                 char_width,
                 line_height,
+                subpixel_text,
                 model,
                 &plan,
             );
 
-            if plan.render_editor {
-                session.render_editor_area_phase(perf);
-                if plan.window_layout.sidebar_rect.is_some() {
-                    perf.measure_stage(crate::perf::PerfStage::Sidebar, || {
-                        session.render_sidebar_phase();
-                    });
-                }
-                if plan.window_layout.right_dock_rect.is_some() {
-                    perf.measure_stage(crate::perf::PerfStage::RightDock, || {
-                        session.render_right_dock_phase();
-                    });
-                }
-                if plan.window_layout.bottom_dock_rect.is_some() {
-                    perf.measure_stage(crate::perf::PerfStage::BottomDock, || {
-                        session.render_bottom_dock_phase();
-                    });
-                }
-            }
-
-            if plan.render_status_bar {
-                perf.measure_stage(crate::perf::PerfStage::StatusBar, || {
-                    session.render_status_bar_phase();
-                });
-            }
-
-            if plan.show_modal {
-                perf.measure_stage(crate::perf::PerfStage::Modal, || {
-                    session.render_modal_phase();
-                });
-            }
-            if plan.show_drop_overlay {
-                perf.measure_stage(crate::perf::PerfStage::DropOverlay, || {
-                    session.render_drop_overlay_phase();
-                });
-            }
-            if plan.show_tab_drag_ghost {
-                session.render_tab_drag_ghost_phase();
-            }
-
-            #[cfg(debug_assertions)]
-            if plan.show_perf_overlay {
-                let start = std::time::Instant::now();
-                session.render_perf_overlay_phase(perf);
-                perf.record_stage_elapsed(crate::perf::PerfStage::PerfOverlay, start.elapsed());
-            }
-
-            #[cfg(debug_assertions)]
-            if plan.show_debug_overlay {
-                perf.measure_stage(crate::perf::PerfStage::DebugOverlay, || {
-                    session.render_debug_overlay_phase();
-                });
-            }
-
-            #[cfg(debug_assertions)]
-            session.record_cache_stats(perf);
+            session.render_phases(perf);
         }
 
         // Debug: visualize damage regions with colored outlines
@@ -1855,9 +2311,17 @@ impl Renderer {
         }
 
         // Copy back buffer to surface and present
+        #[allow(clippy::infallible_destructuring_match)] // without the gpu feature
+        let surface = match &mut self.target {
+            RenderTarget::Software(surface) => surface,
+            #[cfg(feature = "gpu")]
+            RenderTarget::Gpu(_) => return Ok(()),
+            #[cfg(feature = "headless")]
+            RenderTarget::Headless => return Ok(()),
+        };
         {
             let mut buffer = perf.measure_stage(crate::perf::PerfStage::SurfaceAcquire, || {
-                self.surface
+                surface
                     .buffer_mut()
                     .map_err(|e| anyhow::anyhow!("Failed to get surface buffer: {}", e))
             })?;
@@ -1874,6 +2338,64 @@ impl Renderer {
         Ok(())
     }
 
+    /// Record the whole window into a scene and draw it on the GPU. Every
+    /// frame is a full redraw: redrawing on the GPU is cheaper than tracking
+    /// what is still valid in a swapchain texture.
+    #[cfg(feature = "gpu")]
+    fn render_gpu(
+        &mut self,
+        model: &mut AppModel,
+        perf: &mut crate::perf::PerfStats,
+    ) -> Result<()> {
+        let RenderTarget::Gpu(gpu) = &self.target else {
+            return Ok(());
+        };
+        let line_height = self.line_metrics.new_line_size.ceil() as usize;
+        let char_width = self.char_width;
+        let width_usize = self.width as usize;
+        let height_usize = self.height as usize;
+
+        let show_perf_overlay = perf.should_show_overlay();
+        let plan = perf.measure_stage(crate::perf::PerfStage::BuildPlan, || {
+            self.build_render_plan(
+                model,
+                &Damage::Full,
+                width_usize,
+                height_usize,
+                line_height,
+                char_width,
+                show_perf_overlay,
+            )
+        });
+
+        let mut scene = scene::Scene::new(width_usize, height_usize, gpu.resident_images());
+        {
+            let mut frame = Frame::recording(&mut scene);
+            frame.clear(model.theme.editor.background.to_argb_u32());
+            let mut session = RenderSession::new(
+                frame,
+                &self.fonts,
+                &mut self.glyph_cache,
+                self.font_size,
+                self.line_metrics.ascent,
+                char_width,
+                line_height,
+                // The glyph atlas holds one coverage value per pixel
+                false,
+                model,
+                &plan,
+            );
+            session.render_phases(perf);
+        }
+
+        let RenderTarget::Gpu(gpu) = &mut self.target else {
+            return Ok(());
+        };
+        perf.measure_stage(crate::perf::PerfStage::SurfacePresent, || {
+            gpu.render(&scene, &self.glyph_cache)
+        })
+    }
+
     /// Render debug visualization of damage regions
     #[cfg(feature = "damage-debug")]
     #[allow(clippy::too_many_arguments)]
@@ -2048,7 +2570,47 @@ impl Renderer {
             y,
             line_height,
             self.char_width,
-            model.metrics.tab_bar_height,
+            model.metrics.header_height(),
+        )
+    }
+
+    /// The column whose header (A, B, C, ...) is at a pixel position in the
+    /// focused CSV grid
+    pub fn pixel_to_csv_column_header(&self, x: f64, y: f64, model: &AppModel) -> Option<usize> {
+        let group = model.editor_area.focused_group()?;
+        let editor = model.editor_area.focused_editor()?;
+        let csv = editor.view_mode.as_csv()?;
+
+        let line_height = self.line_metrics.new_line_size.ceil() as usize;
+
+        crate::csv::render::pixel_to_csv_column_header(
+            csv,
+            &group.rect,
+            x,
+            y,
+            line_height,
+            self.char_width,
+            model.metrics.header_height(),
+        )
+    }
+
+    /// The column whose filter is at a pixel position in the focused CSV
+    /// grid, when its filter row is shown
+    pub fn pixel_to_csv_filter(&self, x: f64, y: f64, model: &AppModel) -> Option<usize> {
+        let group = model.editor_area.focused_group()?;
+        let editor = model.editor_area.focused_editor()?;
+        let csv = editor.view_mode.as_csv()?;
+
+        let line_height = self.line_metrics.new_line_size.ceil() as usize;
+
+        crate::csv::render::pixel_to_csv_filter(
+            csv,
+            &group.rect,
+            x,
+            y,
+            line_height,
+            self.char_width,
+            model.metrics.header_height(),
         )
     }
 }
@@ -180,14 +180,14 @@ fn render_command_palette_modal(
     state: &crate::model::ui::CommandPaletteState,
     ctx: &ModalRenderCtx,
 ) {
-    use crate::commands::filter_commands;
+    use crate::commands::palette_entries;
 
     let colors = &ctx.colors;
     let line_height = ctx.line_height;
     let char_width = ctx.char_width;
 
     let input_text = state.input();
-    let filtered_commands = filter_commands(&input_text);
+    let filtered_commands = palette_entries(&input_text, &model.config.external_tools);
     let max_visible_items = 8;
 
     let (layout, w) = geometry::command_palette_layout(
@@ -237,9 +237,9 @@ fn render_command_palette_modal(
             &list_layout,
             &list_colors,
             |frame, cmd, _actual_index, item_y, _is_selected| {
-                painter.draw(frame, layout.x + 16, item_y, cmd.label, colors.fg);
+                painter.draw(frame, layout.x + 16, item_y, cmd.label(), colors.fg);
 
-                if let Some(kb) = cmd.keybinding {
+                if let Some(kb) = cmd.keybinding() {
                     let kb_width = (kb.chars().count() as f32 * char_width).round() as usize;
                     let kb_x = layout.x + layout.w - kb_width - 16;
                     painter.draw(frame, kb_x, item_y, kb, colors.dim);
@@ -289,6 +289,67 @@ fn render_goto_line_modal(
     );
 }
 
+/// Same shape as Go to Line: a title and one input
+fn render_input_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    title: &str,
+    input: &dyn TextFieldContent,
+    ctx: &ModalRenderCtx,
+) {
+    let colors = &ctx.colors;
+    let line_height = ctx.line_height;
+    let char_width = ctx.char_width;
+
+    let (layout, w) = geometry::goto_line_layout(ctx.window_width, ctx.window_height, line_height);
+
+    render_modal_shell(frame, &layout, colors);
+
+    let title_r = layout.widget(w.title);
+    painter.draw(frame, title_r.x, title_r.y, title, colors.fg);
+
+    let input_r = layout.widget(w.input);
+    TextFieldRenderer::render_modal_input(
+        frame,
+        painter,
+        input,
+        input_r,
+        line_height,
+        char_width,
+        colors.input_bg,
+        colors.fg,
+        colors.highlight,
+        colors.selection_bg,
+        model.ui.cursor_visible,
+    );
+}
+
+fn render_rename_file_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &crate::model::ui::RenameFileState,
+    ctx: &ModalRenderCtx,
+) {
+    let title = format!("Rename {}", crate::util::filename_for_display(&state.path));
+    render_input_modal(frame, painter, model, &title, &state.editable, ctx);
+}
+
+fn render_create_file_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &crate::model::CreateFileState,
+    ctx: &ModalRenderCtx,
+) {
+    let title = format!(
+        "Create file in {}",
+        crate::util::filename_for_display(&state.dir)
+    );
+    render_input_modal(frame, painter, model, &title, &state.editable, ctx);
+}
+
 fn render_find_replace_modal(
     frame: &mut Frame,
     painter: &mut TextPainter,
@@ -312,12 +373,32 @@ fn render_find_replace_modal(
     render_modal_shell(frame, &layout, colors);
 
     let title_r = layout.widget(w.title);
-    let title = if state.replace_mode {
-        "Find and Replace"
+    let title = match (state.replace_mode, state.in_selection) {
+        (true, true) => "Find and Replace in Selection",
+        (true, false) => "Find and Replace",
+        (false, true) => "Find in Selection",
+        (false, false) => "Find",
+    };
+    let options: Vec<&str> = [(state.regex, "regex"), (state.case_sensitive, "match case")]
+        .into_iter()
+        .filter_map(|(on, name)| on.then_some(name))
+        .collect();
+    let title = if options.is_empty() {
+        title.to_string()
     } else {
-        "Find"
+        format!("{title} ({})", options.join(", "))
     };
-    painter.draw(frame, title_r.x, title_r.y, title, colors.fg);
+    painter.draw(frame, title_r.x, title_r.y, &title, colors.fg);
+    if state.scope.is_some() {
+        let hint = if state.in_selection {
+            "Alt+L: whole file"
+        } else {
+            "Alt+L: in selection"
+        };
+        let hint_x = (title_r.x + title_r.w)
+            .saturating_sub((hint.chars().count() as f32 * char_width).round() as usize);
+        painter.draw(frame, hint_x, title_r.y, hint, colors.dim);
+    }
 
     if let Some(label_idx) = w.find_label {
         let label_r = layout.widget(label_idx);
@@ -372,6 +453,37 @@ fn render_find_replace_modal(
             replace_cursor_visible,
         );
     }
+
+    if !state.query().is_empty() {
+        let summary = match state.regex_error() {
+            Some(error) => format!("Invalid regex: {error}"),
+            None => match_summary(&state.match_counts, state.current_match),
+        };
+        let matches_r = layout.widget(w.matches);
+        let max_chars = (matches_r.w as f32 / char_width) as usize;
+        let summary = super::panels::truncate_with_ellipsis(&summary, max_chars);
+        painter.draw(frame, matches_r.x, matches_r.y, &summary, colors.dim);
+    }
+}
+
+/// Match counts line of the find modal
+fn match_summary(counts: &crate::model::FindMatchCounts, current: Option<usize>) -> String {
+    let mut summary = match (counts.current, current) {
+        (0, _) => "No matches".to_string(),
+        (n, Some(i)) => format!("{} of {n}", i + 1),
+        (1, None) => "1 match".to_string(),
+        (n, None) => format!("{n} matches"),
+    };
+    if !counts.other_files.is_empty() {
+        let files: Vec<String> = counts
+            .other_files
+            .iter()
+            .map(|(name, matches)| format!("{name} ({matches})"))
+            .collect();
+        summary.push_str(" · also in ");
+        summary.push_str(&files.join(", "));
+    }
+    summary
 }
 
 /// Shared shell for the two "search a list, filtered by a text input" modals
@@ -380,6 +492,8 @@ fn render_find_replace_modal(
 ///
 /// The two modals differ only in title, item type, per-row rendering, and
 /// empty-state copy, so those are the only things callers need to supply.
+/// `preview_rows` reserves a preview area below the list, which is returned
+/// for the caller to fill.
 #[allow(clippy::too_many_arguments)]
 fn render_search_list_modal<T>(
     frame: &mut Frame,
@@ -393,8 +507,9 @@ fn render_search_list_modal<T>(
     items: &[T],
     selected_index: usize,
     max_visible_items: usize,
+    preview_rows: usize,
     mut render_row: impl FnMut(&mut Frame, &mut TextPainter, &T, usize, usize, usize, f32, u32, u32),
-) {
+) -> Option<geometry::WidgetRect> {
     let colors = &ctx.colors;
     let line_height = ctx.line_height;
     let char_width = ctx.char_width;
@@ -405,6 +520,7 @@ fn render_search_list_modal<T>(
         line_height,
         items.len(),
         !input_is_empty,
+        preview_rows,
     );
 
     render_modal_shell(frame, &layout, colors);
@@ -460,17 +576,19 @@ fn render_search_list_modal<T>(
     if items.is_empty() && !input_is_empty {
         painter.draw(frame, layout.x + 12, results_y, empty_message, dim_color);
     }
+
+    w.preview.map(|idx| *layout.widget(idx))
 }
 
-fn render_file_finder_modal(
+fn render_quick_open_modal(
     frame: &mut Frame,
     painter: &mut TextPainter,
     model: &AppModel,
-    state: &crate::model::ui::FileFinderState,
+    state: &crate::model::ui::QuickOpenState,
     ctx: &ModalRenderCtx,
 ) {
     let input_text = state.input();
-    render_search_list_modal(
+    let preview_r = render_search_list_modal(
         frame,
         painter,
         model,
@@ -482,6 +600,7 @@ fn render_file_finder_modal(
         state.results.as_slice(),
         state.selected_index,
         10,
+        state.preview_rows(),
         |frame, painter, file_match, item_y, layout_x, layout_w, char_width, fg, dim| {
             let icon = crate::model::FileExtension::from_path(&file_match.path).icon();
             let icon_x = layout_x + 12;
@@ -510,6 +629,40 @@ fn render_file_finder_modal(
             }
         },
     );
+
+    // Leading lines of the highlighted file
+    let Some(preview_r) = preview_r else {
+        return;
+    };
+    let colors = &ctx.colors;
+    frame.fill_rect_px(
+        preview_r.x,
+        preview_r.y,
+        preview_r.w,
+        preview_r.h,
+        colors.input_bg,
+    );
+    let Some(lines) = &state.preview else {
+        painter.draw(
+            frame,
+            preview_r.x + 8,
+            preview_r.y,
+            "No preview available",
+            colors.dim,
+        );
+        return;
+    };
+    frame.set_clip(crate::model::Rect::new(
+        preview_r.x as f32,
+        preview_r.y as f32,
+        preview_r.w as f32,
+        preview_r.h as f32,
+    ));
+    for (i, line) in lines.iter().enumerate() {
+        let y = preview_r.y + i * ctx.line_height;
+        painter.draw(frame, preview_r.x + 8, y, line, colors.fg);
+    }
+    frame.clear_clip();
 }
 
 fn render_recent_files_modal(
@@ -533,6 +686,7 @@ fn render_recent_files_modal(
         filtered.as_slice(),
         state.selected_index,
         10,
+        0,
         |frame, painter, entry, item_y, layout_x, layout_w, char_width, fg, dim| {
             let icon = crate::model::FileExtension::from_path(&entry.path).icon();
             let icon_x = layout_x + 12;
@@ -550,6 +704,515 @@ fn render_recent_files_modal(
     );
 }
 
+fn render_backlinks_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &crate::model::BacklinksState,
+    ctx: &ModalRenderCtx,
+) {
+    let filtered = state.filtered_entries();
+    let input_text = state.input();
+    render_search_list_modal(
+        frame,
+        painter,
+        model,
+        ctx,
+        &format!("Backlinks to {}", state.target_name),
+        &state.editable,
+        input_text.is_empty(),
+        "No backlinks match your query",
+        filtered.as_slice(),
+        state.selected_index,
+        10,
+        0,
+        |frame, painter, entry, item_y, layout_x, layout_w, char_width, fg, dim| {
+            let icon = crate::model::FileExtension::from_path(&entry.path).icon();
+            painter.draw(frame, layout_x + 12, item_y, icon, fg);
+
+            let location = format!("{}:{}", entry.display_name, entry.line + 1);
+            let name_x = layout_x + 36;
+            painter.draw(frame, name_x, item_y, &location, fg);
+
+            let location_width = (location.chars().count() as f32 * char_width) as usize;
+            let text_x = name_x + location_width + (char_width as usize * 2);
+            let available_width = (layout_x + layout_w).saturating_sub(text_x + 16);
+            let max_chars = (available_width as f32 / char_width) as usize;
+            if max_chars > 5 {
+                let text = if entry.text.chars().count() > max_chars {
+                    let truncated: String = entry.text.chars().take(max_chars - 1).collect();
+                    format!("{}…", truncated)
+                } else {
+                    entry.text.clone()
+                };
+                painter.draw(frame, text_x, item_y, &text, dim);
+            }
+        },
+    );
+}
+
+fn render_keymap_diagnostics_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &crate::model::KeymapDiagnosticsState,
+    ctx: &ModalRenderCtx,
+) {
+    let filtered = state.filtered_entries();
+    let input_text = state.input();
+    render_search_list_modal(
+        frame,
+        painter,
+        model,
+        ctx,
+        "Keymap Diagnostics",
+        &state.editable,
+        input_text.is_empty(),
+        "No diagnostics match your query",
+        filtered.as_slice(),
+        state.selected_index,
+        10,
+        0,
+        |frame, painter, entry, item_y, layout_x, layout_w, char_width, fg, dim| {
+            let file_name = entry
+                .path
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "default keymap".to_string());
+            let location = match entry.line {
+                Some(line) => format!("{}:{}", file_name, line + 1),
+                None => file_name,
+            };
+            let name_x = layout_x + 12;
+            painter.draw(frame, name_x, item_y, &location, dim);
+
+            let location_width = (location.chars().count() as f32 * char_width) as usize;
+            let text_x = name_x + location_width + (char_width as usize * 2);
+            let available_width = (layout_x + layout_w).saturating_sub(text_x + 16);
+            let max_chars = (available_width as f32 / char_width) as usize;
+            if max_chars > 5 {
+                let text = if entry.message.chars().count() > max_chars {
+                    let truncated: String = entry.message.chars().take(max_chars - 1).collect();
+                    format!("{}…", truncated)
+                } else {
+                    entry.message.clone()
+                };
+                painter.draw(frame, text_x, item_y, &text, fg);
+            }
+        },
+    );
+}
+
+fn render_language_picker_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &crate::model::LanguagePickerState,
+    ctx: &ModalRenderCtx,
+) {
+    let filtered = state.filtered_languages();
+    let input_text = state.input();
+    render_search_list_modal(
+        frame,
+        painter,
+        model,
+        ctx,
+        "Select Language Mode",
+        &state.editable,
+        input_text.is_empty(),
+        "No languages match your query",
+        filtered.as_slice(),
+        state.selected_index,
+        10,
+        0,
+        |frame, painter, language, item_y, layout_x, layout_w, char_width, fg, dim| {
+            painter.draw(frame, layout_x + 12, item_y, language.display_name(), fg);
+
+            if *language == state.current {
+                let label = "current";
+                let label_width = (label.len() as f32 * char_width) as usize;
+                let label_x = (layout_x + layout_w).saturating_sub(label_width + 12);
+                painter.draw(frame, label_x, item_y, label, dim);
+            }
+        },
+    );
+}
+
+fn render_breadcrumb_symbols_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &crate::model::BreadcrumbSymbolsState,
+    ctx: &ModalRenderCtx,
+) {
+    let filtered = state.filtered_entries();
+    let input_text = state.input();
+    render_search_list_modal(
+        frame,
+        painter,
+        model,
+        ctx,
+        &format!("Symbols in {}", state.parent),
+        &state.editable,
+        input_text.is_empty(),
+        "No symbols match your query",
+        filtered.as_slice(),
+        state.selected_index,
+        10,
+        0,
+        |frame, painter, entry, item_y, layout_x, layout_w, char_width, fg, dim| {
+            painter.draw(frame, layout_x + 12, item_y, &entry.label, fg);
+
+            let label = format!("line {}", entry.line + 1);
+            let label_width = (label.len() as f32 * char_width) as usize;
+            let label_x = (layout_x + layout_w).saturating_sub(label_width + 12);
+            painter.draw(frame, label_x, item_y, &label, dim);
+        },
+    );
+}
+
+fn render_goto_symbol_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &crate::model::GotoSymbolState,
+    ctx: &ModalRenderCtx,
+) {
+    let filtered = state.filtered_entries();
+    let input_text = state.input();
+    render_search_list_modal(
+        frame,
+        painter,
+        model,
+        ctx,
+        "Go to Symbol",
+        &state.editable,
+        input_text.is_empty(),
+        "No symbols match your query",
+        filtered.as_slice(),
+        state.selected_index,
+        10,
+        0,
+        |frame, painter, entry, item_y, layout_x, layout_w, char_width, fg, dim| {
+            let label_x = layout_x + 12;
+            painter.draw(frame, label_x, item_y, &entry.label, fg);
+
+            if let Some(container) = &entry.container {
+                let container_x =
+                    label_x + ((entry.label.chars().count() + 2) as f32 * char_width) as usize;
+                painter.draw(frame, container_x, item_y, container, dim);
+            }
+
+            let line = format!("line {}", entry.line + 1);
+            let line_width = (line.len() as f32 * char_width) as usize;
+            let line_x = (layout_x + layout_w).saturating_sub(line_width + 12);
+            painter.draw(frame, line_x, item_y, &line, dim);
+        },
+    );
+}
+
+fn render_edit_review_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    state: &crate::edit_review::EditReviewState,
+    ctx: &ModalRenderCtx,
+) {
+    use crate::edit_review::ReviewRow;
+    use geometry::EDIT_REVIEW_MAX_VISIBLE_ROWS;
+
+    let colors = &ctx.colors;
+    let line_height = ctx.line_height;
+    let added_color = model.theme.overlay.highlight.to_argb_u32();
+    let removed_color = model.theme.overlay.error.to_argb_u32();
+
+    let rows = state.rows();
+    let (layout, w) =
+        geometry::edit_review_layout(ctx.window_width, ctx.window_height, line_height, rows.len());
+
+    render_modal_shell(frame, &layout, colors);
+
+    let title_r = layout.widget(w.title);
+    let title = format!(
+        "Review {}: {} of {} changes accepted",
+        state.source.label(),
+        state.accepted_count(),
+        state.hunks.len()
+    );
+    painter.draw(frame, title_r.x, title_r.y, &title, colors.fg);
+
+    let list_r = layout.widget(w.list);
+    frame.set_clip(crate::model::Rect::new(
+        list_r.x as f32,
+        list_r.y as f32,
+        list_r.w as f32,
+        list_r.h as f32,
+    ));
+
+    let scroll_offset = state
+        .scroll_offset
+        .min(rows.len().saturating_sub(EDIT_REVIEW_MAX_VISIBLE_ROWS));
+    let mut current_y = list_r.y;
+    for row in rows
+        .iter()
+        .skip(scroll_offset)
+        .take(EDIT_REVIEW_MAX_VISIBLE_ROWS)
+    {
+        match row {
+            ReviewRow::Header(idx) => {
+                let hunk = &state.hunks[*idx];
+                if *idx == state.selected_index {
+                    frame.fill_rect_px(
+                        layout.x + 4,
+                        current_y,
+                        layout.w - 8,
+                        line_height,
+                        colors.selection_bg,
+                    );
+                }
+                let mark = if hunk.accepted { "[x]" } else { "[ ]" };
+                let header = format!(
+                    "{} Line {}: -{} +{}",
+                    mark,
+                    hunk.old_start + 1,
+                    hunk.old_lines.len(),
+                    hunk.new_lines.len()
+                );
+                let color = if hunk.accepted { colors.fg } else { colors.dim };
+                painter.draw(frame, list_r.x, current_y, &header, color);
+            }
+            ReviewRow::Context(text) => {
+                painter.draw(
+                    frame,
+                    list_r.x,
+                    current_y,
+                    &format!("  {}", text),
+                    colors.dim,
+                );
+            }
+            ReviewRow::Removed(text) => {
+                painter.draw(
+                    frame,
+                    list_r.x,
+                    current_y,
+                    &format!("- {}", text),
+                    removed_color,
+                );
+            }
+            ReviewRow::Added(text) => {
+                painter.draw(
+                    frame,
+                    list_r.x,
+                    current_y,
+                    &format!("+ {}", text),
+                    added_color,
+                );
+            }
+        }
+        current_y += line_height;
+    }
+
+    frame.clear_clip();
+
+    let hint_r = layout.widget(w.hint);
+    painter.draw(
+        frame,
+        hint_r.x,
+        hint_r.y,
+        "↑↓ select   Space toggle   A accept all   R reject all   Enter apply   Esc discard",
+        colors.dim,
+    );
+}
+
+/// Render a prompt with a title, a message line and a row of buttons, the
+/// `selected` one highlighted
+fn render_button_prompt_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    title: &str,
+    message: &str,
+    buttons: &[&str],
+    selected: usize,
+    ctx: &ModalRenderCtx,
+) {
+    use geometry::PROMPT_BUTTON_PAD_CHARS;
+
+    let colors = &ctx.colors;
+    let line_height = ctx.line_height;
+    let char_width = ctx.char_width;
+
+    let (layout, w) =
+        geometry::button_prompt_layout(ctx.window_width, ctx.window_height, line_height);
+
+    render_modal_shell(frame, &layout, colors);
+
+    let title_r = layout.widget(w.title);
+    painter.draw(frame, title_r.x, title_r.y, title, colors.fg);
+
+    let message_r = layout.widget(w.message);
+    painter.draw(frame, message_r.x, message_r.y, message, colors.dim);
+
+    let buttons_r = layout.widget(w.buttons);
+    let text_y = buttons_r.y + buttons_r.h.saturating_sub(line_height) / 2;
+    let mut x = buttons_r.x;
+    for (index, label) in buttons.iter().enumerate() {
+        let chars = label.chars().count() + PROMPT_BUTTON_PAD_CHARS * 2;
+        let width = (chars as f32 * char_width).round() as usize;
+        let (bg, fg) = if index == selected {
+            (colors.selection_bg, colors.highlight)
+        } else {
+            (colors.input_bg, colors.fg)
+        };
+        frame.fill_rect_px(x, buttons_r.y, width, buttons_r.h, bg);
+        let text_x = x + (PROMPT_BUTTON_PAD_CHARS as f32 * char_width).round() as usize;
+        painter.draw(frame, text_x, text_y, label, fg);
+        x += width + char_width.round() as usize;
+    }
+}
+
+fn render_unsaved_changes_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    state: &crate::model::UnsavedChangesState,
+    ctx: &ModalRenderCtx,
+) {
+    use crate::model::UnsavedChangesChoice;
+
+    let buttons = UnsavedChangesChoice::ALL.map(|choice| choice.label());
+    let selected = UnsavedChangesChoice::ALL
+        .iter()
+        .position(|choice| *choice == state.selected)
+        .unwrap_or(0);
+    render_button_prompt_modal(
+        frame,
+        painter,
+        &format!("Save changes to {}?", state.document_name),
+        "Your changes will be lost if you don't save them.",
+        &buttons,
+        selected,
+        ctx,
+    );
+}
+
+fn render_deleted_file_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    state: &crate::model::DeletedFileState,
+    ctx: &ModalRenderCtx,
+) {
+    use crate::model::DeletedFileChoice;
+
+    let buttons = DeletedFileChoice::ALL.map(|choice| choice.label());
+    let selected = DeletedFileChoice::ALL
+        .iter()
+        .position(|choice| *choice == state.selected)
+        .unwrap_or(0);
+    let name = crate::util::filename_for_display(&state.path);
+    render_button_prompt_modal(
+        frame,
+        painter,
+        &format!("{} was deleted on disk", name),
+        "Recreate it at its old location, or save it somewhere else?",
+        &buttons,
+        selected,
+        ctx,
+    );
+}
+
+fn render_file_changed_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    state: &crate::model::FileChangedState,
+    ctx: &ModalRenderCtx,
+) {
+    use crate::model::FileChangedChoice;
+
+    let buttons = FileChangedChoice::ALL.map(|choice| choice.label());
+    let selected = FileChangedChoice::ALL
+        .iter()
+        .position(|choice| *choice == state.selected)
+        .unwrap_or(0);
+    let name = crate::util::filename_for_display(&state.path);
+    render_button_prompt_modal(
+        frame,
+        painter,
+        &format!("{} was changed on disk", name),
+        "Reload it and lose your changes, or keep editing your version?",
+        &buttons,
+        selected,
+        ctx,
+    );
+}
+
+fn render_workspace_trust_modal(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    state: &crate::model::WorkspaceTrustState,
+    ctx: &ModalRenderCtx,
+) {
+    use crate::model::WorkspaceTrustChoice;
+
+    let buttons = WorkspaceTrustChoice::ALL.map(|choice| choice.label());
+    let selected = WorkspaceTrustChoice::ALL
+        .iter()
+        .position(|choice| *choice == state.selected)
+        .unwrap_or(0);
+    let name = crate::util::filename_for_display(&state.root);
+    render_button_prompt_modal(
+        frame,
+        painter,
+        &format!("Trust the authors of {}?", name),
+        "Trusted workspaces can start language servers and tools.",
+        &buttons,
+        selected,
+        ctx,
+    );
+}
+
+fn render_context_menu(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    state: &crate::model::ContextMenuState,
+    ctx: &ModalRenderCtx,
+) {
+    let colors = &ctx.colors;
+    let line_height = ctx.line_height;
+
+    let layout = geometry::context_menu_layout(
+        state,
+        ctx.window_width,
+        ctx.window_height,
+        line_height,
+        ctx.char_width,
+    );
+    render_modal_shell(frame, &layout, colors);
+
+    for (index, (item, rect)) in state.items.iter().zip(&layout.widgets).enumerate() {
+        let Some(label) = geometry::context_menu_label(item) else {
+            // Separator: a rule across the middle of its row
+            let inset = geometry::ModalSpacing::INPUT_PAD_X;
+            frame.fill_rect_px(
+                rect.x + inset,
+                rect.y + rect.h / 2,
+                rect.w.saturating_sub(inset * 2),
+                1,
+                colors.border,
+            );
+            continue;
+        };
+        let fg = if index == state.selected_index {
+            frame.fill_rect_px(rect.x, rect.y, rect.w, rect.h, colors.selection_bg);
+            colors.highlight
+        } else {
+            colors.fg
+        };
+        let text_x = rect.x + geometry::ModalSpacing::INPUT_PAD_X * 2;
+        let text_y = rect.y + rect.h.saturating_sub(line_height) / 2;
+        painter.draw(frame, text_x, text_y, label, fg);
+    }
+}
+
 /// Render the active modal overlay.
 ///
 /// Draws:
@@ -569,8 +1232,11 @@ pub fn render_modals(
         return;
     };
 
-    // 1. Dim background (40% black overlay)
-    frame.dim(MODAL_DIM_ALPHA); // 102/255 ≈ 40% opacity
+    // 1. Dim background (40% black overlay); context menus leave the
+    //    window as it is
+    if !matches!(modal, ModalState::ContextMenu(_)) {
+        frame.dim(MODAL_DIM_ALPHA); // 102/255 ≈ 40% opacity
+    }
 
     let ctx = ModalRenderCtx {
         window_width,
@@ -592,12 +1258,41 @@ pub fn render_modals(
         ModalState::FindReplace(state) => {
             render_find_replace_modal(frame, painter, model, state, &ctx)
         }
-        ModalState::FileFinder(state) => {
-            render_file_finder_modal(frame, painter, model, state, &ctx)
-        }
+        ModalState::QuickOpen(state) => render_quick_open_modal(frame, painter, model, state, &ctx),
         ModalState::RecentFiles(state) => {
             render_recent_files_modal(frame, painter, model, state, &ctx)
         }
+        ModalState::EditReview(state) => {
+            render_edit_review_modal(frame, painter, model, state, &ctx)
+        }
+        ModalState::UnsavedChanges(state) => {
+            render_unsaved_changes_modal(frame, painter, state, &ctx)
+        }
+        ModalState::DeletedFile(state) => render_deleted_file_modal(frame, painter, state, &ctx),
+        ModalState::FileChanged(state) => render_file_changed_modal(frame, painter, state, &ctx),
+        ModalState::RenameFile(state) => {
+            render_rename_file_modal(frame, painter, model, state, &ctx)
+        }
+        ModalState::CreateFile(state) => {
+            render_create_file_modal(frame, painter, model, state, &ctx)
+        }
+        ModalState::Backlinks(state) => render_backlinks_modal(frame, painter, model, state, &ctx),
+        ModalState::KeymapDiagnostics(state) => {
+            render_keymap_diagnostics_modal(frame, painter, model, state, &ctx)
+        }
+        ModalState::LanguagePicker(state) => {
+            render_language_picker_modal(frame, painter, model, state, &ctx)
+        }
+        ModalState::BreadcrumbSymbols(state) => {
+            render_breadcrumb_symbols_modal(frame, painter, model, state, &ctx)
+        }
+        ModalState::GotoSymbol(state) => {
+            render_goto_symbol_modal(frame, painter, model, state, &ctx)
+        }
+        ModalState::WorkspaceTrust(state) => {
+            render_workspace_trust_modal(frame, painter, state, &ctx)
+        }
+        ModalState::ContextMenu(state) => render_context_menu(frame, painter, state, &ctx),
     }
 }
 
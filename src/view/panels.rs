@@ -1,15 +1,23 @@
-//! Panel rendering: sidebar file tree, dock panels, and outline panel
+//! Panel rendering: sidebar file tree, dock panels, outline, TODO, search and AI chat panels
 
 use crate::model::editor_area::Rect;
 use crate::model::AppModel;
 
 use super::frame::{Frame, TextPainter};
-use super::geometry::{DockHeaderLayout, OutlinePanelLayout, TreeListLayout};
+use super::geometry::{
+    AiChatPanelLayout, DockHeaderLayout, DockIconStripLayout, LogPanelLayout, OutlinePanelLayout,
+    SearchPanelLayout, TodoPanelLayout, TreeListLayout,
+};
+use super::text_field::{TextFieldOptions, TextFieldRenderer};
 use super::tree_view::{render_tree, TreeRenderLayout};
 
 enum DockContentKind {
     Outline,
     Terminal,
+    TodoList,
+    SearchResults,
+    Logs,
+    AiChat,
     Placeholder { message: &'static str },
 }
 
@@ -39,6 +47,10 @@ impl DockPaneScene {
         let content = match active_panel {
             crate::panel::PanelId::Outline => DockContentKind::Outline,
             crate::panel::PanelId::Terminal => DockContentKind::Terminal,
+            crate::panel::PanelId::TodoList => DockContentKind::TodoList,
+            crate::panel::PanelId::SearchResults => DockContentKind::SearchResults,
+            crate::panel::PanelId::Logs => DockContentKind::Logs,
+            crate::panel::PanelId::AiChat => DockContentKind::AiChat,
             _ => {
                 let placeholder = crate::panels::PlaceholderPanel::new(active_panel);
                 DockContentKind::Placeholder {
@@ -81,6 +93,42 @@ impl DockPaneScene {
                     self.layout.content_rect,
                 );
             }
+            DockContentKind::TodoList => {
+                render_todo_panel(
+                    frame,
+                    painter,
+                    model,
+                    self.layout.content_rect,
+                    self.text_color,
+                );
+            }
+            DockContentKind::SearchResults => {
+                render_search_panel(
+                    frame,
+                    painter,
+                    model,
+                    self.layout.content_rect,
+                    self.text_color,
+                );
+            }
+            DockContentKind::Logs => {
+                render_log_panel(
+                    frame,
+                    painter,
+                    model,
+                    self.layout.content_rect,
+                    self.text_color,
+                );
+            }
+            DockContentKind::AiChat => {
+                render_ai_chat_panel(
+                    frame,
+                    painter,
+                    model,
+                    self.layout.content_rect,
+                    self.text_color,
+                );
+            }
             DockContentKind::Placeholder { message } => {
                 self.render_placeholder_content(frame, painter, message);
             }
@@ -172,7 +220,7 @@ impl DockPaneScene {
 ///
 /// Operates on chars, not bytes, so multi-byte UTF-8 sequences are never cut
 /// mid-codepoint.
-fn truncate_with_ellipsis(name: &str, max_chars: usize) -> std::borrow::Cow<'_, str> {
+pub(super) fn truncate_with_ellipsis(name: &str, max_chars: usize) -> std::borrow::Cow<'_, str> {
     let char_count = name.chars().count();
     if char_count <= max_chars || max_chars == 0 {
         return std::borrow::Cow::Borrowed(name);
@@ -260,13 +308,31 @@ pub fn render_sidebar(
         folder_icon_color: theme.folder_icon.to_argb_u32(),
     };
 
+    // A new entry's name is typed in a row of its own, below its folder
+    let new_entry_row = workspace.new_entry_row();
+    let name_edit = workspace.name_edit.as_ref();
+    let name_input = NameInputStyle {
+        background: model.theme.overlay.input_background.to_argb_u32(),
+        text_color: ctx.text_color,
+        cursor_color: model.theme.editor.cursor_color.to_argb_u32(),
+        selection_color: ctx.selection_bg,
+        cursor_visible: matches!(model.ui.focus, crate::model::FocusTarget::Sidebar)
+            && model.ui.cursor_visible,
+        line_height: painter.line_height(),
+    };
+
     render_tree(
         &workspace.file_tree.roots,
         TreeRenderLayout::new(0, sidebar_height, ctx.row_height, workspace.scroll_offset),
         |node| node.is_dir && workspace.is_expanded(&node.path),
         |row| {
             let node = row.node;
-            let pos = ctx.tree.node_position(row.depth, row.row_y);
+            let row_y = if new_entry_row.is_some_and(|new_row| row.index >= new_row) {
+                row.row_y + ctx.row_height
+            } else {
+                row.row_y
+            };
+            let pos = ctx.tree.node_position(row.depth, row_y);
 
             let is_selected = workspace
                 .selected_item
@@ -278,7 +344,7 @@ pub fn render_sidebar(
                 frame.fill_rect_blended(
                     Rect::new(
                         0.0,
-                        row.row_y as f32,
+                        row_y as f32,
                         ctx.sidebar_width as f32,
                         ctx.row_height as f32,
                     ),
@@ -311,6 +377,15 @@ pub fn render_sidebar(
             };
 
             let available_width = ctx.tree.available_text_width(ctx.sidebar_width, text_x);
+
+            let renaming = name_edit.filter(|edit| {
+                matches!(&edit.kind, crate::model::NameEditKind::Rename { path } if *path == node.path)
+            });
+            if let Some(edit) = renaming {
+                name_input.render(frame, painter, &edit.input, text_x, row_y, &ctx);
+                return;
+            }
+
             let max_chars = available_width
                 .checked_div(ctx.char_width)
                 .unwrap_or(available_width / 8);
@@ -320,9 +395,68 @@ pub fn render_sidebar(
         },
     );
 
+    if let (Some(new_row), Some(edit)) = (new_entry_row, name_edit) {
+        if new_row >= workspace.scroll_offset {
+            let row_y = (new_row - workspace.scroll_offset) * ctx.row_height;
+            let depth = workspace.depth(edit.parent()) + 1;
+            let pos = ctx.tree.node_position(depth, row_y);
+            if matches!(edit.kind, crate::model::NameEditKind::NewFolder { .. }) {
+                painter.draw(frame, pos.icon_x, pos.text_y, "+", ctx.folder_icon_color);
+            }
+            name_input.render(frame, painter, &edit.input, pos.text_x, row_y, &ctx);
+        }
+    }
+
     frame.clear_clip();
 }
 
+/// Colors of the input for a name typed in the file tree
+struct NameInputStyle {
+    background: u32,
+    text_color: u32,
+    cursor_color: u32,
+    selection_color: u32,
+    cursor_visible: bool,
+    line_height: usize,
+}
+
+impl NameInputStyle {
+    /// Draw `input` over the rest of the tree row at `row_y`, from `text_x`
+    fn render(
+        &self,
+        frame: &mut Frame,
+        painter: &mut TextPainter,
+        input: &crate::editable::EditableState<crate::editable::StringBuffer>,
+        text_x: usize,
+        row_y: usize,
+        ctx: &SidebarRenderContext,
+    ) {
+        let x = text_x.saturating_sub(ctx.tree.left_padding / 2);
+        let width = ctx.sidebar_width.saturating_sub(x + ctx.tree.left_padding);
+        frame.fill_rect(
+            Rect::new(x as f32, row_y as f32, width as f32, ctx.row_height as f32),
+            self.background,
+        );
+
+        let text_width = ctx.tree.available_text_width(ctx.sidebar_width, text_x);
+        let char_width = painter.char_width();
+        let visible_chars = (text_width as f32 / char_width) as usize;
+        let opts = TextFieldOptions {
+            x: text_x,
+            y: row_y + ctx.tree.text_top_padding,
+            width: text_width,
+            height: self.line_height,
+            char_width,
+            text_color: self.text_color,
+            cursor_color: self.cursor_color,
+            selection_color: self.selection_color,
+            cursor_visible: self.cursor_visible,
+            scroll_x: TextFieldRenderer::calculate_scroll(input.cursor().column, 0, visible_chars),
+        };
+        TextFieldRenderer::render(frame, painter, input, &opts);
+    }
+}
+
 /// Render a dock panel (right or bottom dock with placeholder content)
 pub fn render_dock(
     frame: &mut Frame,
@@ -331,6 +465,12 @@ pub fn render_dock(
     position: crate::panel::DockPosition,
     rect: Rect,
 ) {
+    let dock = model.dock_layout.dock(position);
+    if dock.is_open && dock.is_collapsed {
+        render_dock_icon_strip(frame, painter, model, dock, rect);
+        return;
+    }
+
     let Some(scene) = DockPaneScene::resolve(model, position, rect) else {
         return;
     };
@@ -338,6 +478,52 @@ pub fn render_dock(
     scene.render(frame, painter, model);
 }
 
+/// Render a collapsed dock as a strip of panel icons
+fn render_dock_icon_strip(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    dock: &crate::panel::Dock,
+    rect: Rect,
+) {
+    let theme = &model.theme.sidebar;
+    let border_color = theme.border.to_argb_u32();
+    let text_color = theme.foreground.to_argb_u32();
+    let active_bg = theme.selection_background.to_argb_u32();
+    let active_fg = theme.selection_foreground.to_argb_u32();
+
+    frame.fill_rect(rect, theme.background.to_argb_u32());
+    // Border on the edge facing the editor
+    let border = match dock.position {
+        crate::panel::DockPosition::Bottom => Rect::new(rect.x, rect.y, rect.width, 1.0),
+        crate::panel::DockPosition::Right => Rect::new(rect.x, rect.y, 1.0, rect.height),
+        crate::panel::DockPosition::Left => {
+            Rect::new(rect.x + rect.width - 1.0, rect.y, 1.0, rect.height)
+        }
+    };
+    frame.fill_rect(border, border_color);
+
+    let layout = DockIconStripLayout::new(dock, rect, &model.metrics);
+    let char_width = painter.char_width().round() as usize;
+    let line_height = painter.line_height();
+    for icon in &layout.icons {
+        if icon.is_active {
+            frame.fill_rect_px(icon.x, icon.y, icon.size, icon.size, active_bg);
+        }
+        painter.draw(
+            frame,
+            icon.x + icon.size.saturating_sub(char_width) / 2,
+            icon.y + icon.size.saturating_sub(line_height) / 2,
+            crate::panels::panel_icon(icon.panel_id),
+            if icon.is_active {
+                active_fg
+            } else {
+                text_color
+            },
+        );
+    }
+}
+
 /// Compute the scroll offset that keeps `selected_index` visible within a
 /// window of `visible_capacity` rows, starting from the model's last-known
 /// `scroll_offset`.
@@ -498,6 +684,425 @@ pub fn render_outline_panel(
     );
 }
 
+/// Render the log viewer: verbose targets on top, then the tail of the log
+pub fn render_log_panel(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    rect: Rect,
+    text_color: u32,
+) {
+    let accent_color = model.theme.sidebar.folder_icon.to_argb_u32();
+    let warning_color = model.theme.overlay.warning.to_argb_u32();
+    let error_color = model.theme.overlay.error.to_argb_u32();
+
+    let layout = LogPanelLayout::new(rect, &model.metrics);
+    let panel = &model.ui.log_panel;
+    let char_width = painter.char_width();
+
+    frame.set_clip(rect);
+
+    let verbose = crate::tracing::verbose_targets();
+    let summary = if verbose.is_empty() {
+        "Verbose: none".to_string()
+    } else {
+        let names: Vec<&str> = verbose.iter().map(|target| target.name()).collect();
+        format!("Verbose: {}", names.join(", "))
+    };
+    painter.draw(
+        frame,
+        layout.text_x,
+        layout.summary_rect.y as usize + layout.text_top_padding,
+        &summary,
+        accent_color,
+    );
+
+    if panel.entries.is_empty() {
+        let msg = "No log entries";
+        let text_width = msg.len() as f32 * char_width;
+        let text_x = rect.x + (rect.width - text_width) / 2.0;
+        let text_y =
+            layout.list_rect.y + (layout.list_rect.height - painter.line_height() as f32) / 2.0;
+        painter.draw(frame, text_x as usize, text_y as usize, msg, text_color);
+        frame.clear_clip();
+        return;
+    }
+
+    // Newest entry (minus the scroll offset) sits on the bottom row
+    let capacity = layout.visible_capacity();
+    let end = panel.entries.len().saturating_sub(panel.scroll_offset);
+    let start = end.saturating_sub(capacity);
+    let max_chars =
+        ((rect.x + rect.width) as usize).saturating_sub(layout.text_x) as f32 / char_width.max(1.0);
+    for (visual_row, entry) in panel.entries[start..end].iter().enumerate() {
+        let text_y =
+            layout.list_rect.y as usize + visual_row * layout.row_height + layout.text_top_padding;
+        let color = match entry.level {
+            tracing::Level::ERROR => error_color,
+            tracing::Level::WARN => warning_color,
+            _ => text_color,
+        };
+        let line = entry.display_line();
+        let display = truncate_with_ellipsis(&line, max_chars as usize);
+        painter.draw(frame, layout.text_x, text_y, &display, color);
+    }
+
+    frame.clear_clip();
+}
+
+/// Render the TODO list panel: per-tag counts, then items grouped by file
+pub fn render_todo_panel(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    rect: Rect,
+    text_color: u32,
+) {
+    let theme = &model.theme.sidebar;
+    let selection_bg = theme.selection_background.to_argb_u32();
+    let selection_fg = theme.selection_foreground.to_argb_u32();
+    let accent_color = theme.folder_icon.to_argb_u32();
+
+    let layout = TodoPanelLayout::new(rect, &model.metrics);
+    let panel = &model.todo_panel;
+    let char_width = painter.char_width();
+
+    frame.set_clip(rect);
+
+    let summary = if panel.is_scanning && !panel.has_scanned {
+        "Scanning\u{2026}".to_string()
+    } else {
+        crate::todo::tag_counts(&panel.files)
+            .iter()
+            .map(|(tag, count)| format!("{} {}", tag.label(), count))
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+    painter.draw(
+        frame,
+        layout.text_x,
+        layout.summary_rect.y as usize + layout.text_top_padding,
+        &summary,
+        accent_color,
+    );
+
+    if panel.files.is_empty() {
+        if panel.has_scanned {
+            let msg = "No TODOs found";
+            let text_width = msg.len() as f32 * char_width;
+            let text_x = rect.x + (rect.width - text_width) / 2.0;
+            let text_y =
+                layout.list_rect.y + (layout.list_rect.height - painter.line_height() as f32) / 2.0;
+            painter.draw(frame, text_x as usize, text_y as usize, msg, text_color);
+        }
+        frame.clear_clip();
+        return;
+    }
+
+    let visible_capacity = layout.visible_capacity();
+    let scroll_offset =
+        resolve_outline_scroll_offset(panel.scroll_offset, panel.selected_index, visible_capacity);
+    let container_right = (rect.x + rect.width) as usize;
+
+    for visual_row in 0..visible_capacity {
+        let index = scroll_offset + visual_row;
+        let Some(row) = panel.row(index) else {
+            break;
+        };
+        let row_y = layout.list_rect.y as usize + visual_row * layout.row_height;
+        let text_y = row_y + layout.text_top_padding;
+        let is_selected = panel.selected_index == Some(index);
+
+        if is_selected {
+            frame.fill_rect_blended(
+                Rect::new(rect.x, row_y as f32, rect.width, layout.row_height as f32),
+                selection_bg,
+            );
+        }
+        let fg = if is_selected {
+            selection_fg
+        } else {
+            text_color
+        };
+
+        match row {
+            crate::todo::TodoRow::File(file) => {
+                let label = format!("{} ({})", file.display_name, file.items.len());
+                let max_chars = ((container_right.saturating_sub(layout.text_x)) as f32
+                    / char_width.max(1.0)) as usize;
+                let display = truncate_with_ellipsis(&label, max_chars);
+                painter.draw(frame, layout.text_x, text_y, &display, fg);
+            }
+            crate::todo::TodoRow::Item(_, item) => {
+                let line_x = layout.text_x + layout.item_indent;
+                let line_label = format!("{:>4}", item.line + 1);
+                painter.draw(frame, line_x, text_y, &line_label, fg);
+
+                let tag_x = line_x + ((line_label.len() + 1) as f32 * char_width) as usize;
+                let tag_color = if is_selected {
+                    selection_fg
+                } else {
+                    accent_color
+                };
+                painter.draw(frame, tag_x, text_y, item.tag.label(), tag_color);
+
+                let text_x = tag_x + ((item.tag.label().len() + 1) as f32 * char_width) as usize;
+                let max_chars = ((container_right.saturating_sub(text_x)) as f32
+                    / char_width.max(1.0)) as usize;
+                let display = truncate_with_ellipsis(&item.text, max_chars);
+                painter.draw(frame, text_x, text_y, &display, fg);
+            }
+        }
+    }
+
+    frame.clear_clip();
+}
+
+/// Render the workspace search panel: query input and option toggles, a
+/// summary row, then matches grouped by file
+pub fn render_search_panel(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    rect: Rect,
+    text_color: u32,
+) {
+    let theme = &model.theme.sidebar;
+    let selection_bg = theme.selection_background.to_argb_u32();
+    let selection_fg = theme.selection_foreground.to_argb_u32();
+    let accent_color = theme.folder_icon.to_argb_u32();
+    let error_color = model.theme.overlay.error.to_argb_u32();
+    let dim_color = model.theme.gutter.foreground.to_argb_u32();
+    let match_bg = model.theme.editor.search_match_background.to_argb_u32();
+
+    let char_width = painter.char_width();
+    let layout = SearchPanelLayout::new(rect, &model.metrics, char_width);
+    let panel = &model.search_panel;
+
+    frame.set_clip(rect);
+
+    let is_focused = model.ui.focused_dock().is_some_and(|position| {
+        model.dock_layout.dock(position).active_panel()
+            == Some(crate::panel::PanelId::SEARCH_RESULTS)
+    });
+    TextFieldRenderer::render_modal_input(
+        frame,
+        painter,
+        &panel.input,
+        &layout.input_rect,
+        painter.line_height(),
+        char_width,
+        model.theme.overlay.input_background.to_argb_u32(),
+        text_color,
+        model.theme.editor.cursor_color.to_argb_u32(),
+        selection_bg,
+        is_focused && model.ui.cursor_visible,
+    );
+
+    // Option toggles: lit when on
+    let options_y =
+        layout.input_rect.y + (layout.input_rect.h.saturating_sub(painter.line_height())) / 2;
+    let mut option_x = layout.options_x;
+    for (label, on) in [
+        ("Aa", panel.case_sensitive),
+        ("ab", panel.whole_word),
+        (".*", panel.regex),
+    ] {
+        let color = if on { accent_color } else { dim_color };
+        painter.draw(frame, option_x, options_y, label, color);
+        option_x += (3.0 * char_width) as usize;
+    }
+
+    let match_total = crate::project_search::match_count(&panel.files);
+    let (summary, summary_color) = if let Some(error) = &panel.error {
+        (error.clone(), error_color)
+    } else if panel.is_searching {
+        (
+            format!(
+                "Searching\u{2026} {} results in {} files",
+                match_total,
+                panel.files.len()
+            ),
+            dim_color,
+        )
+    } else if let Some(result) = panel.summary {
+        let mut text = match match_total {
+            0 => format!("No results in {} files", result.files_searched),
+            1 => "1 result".to_string(),
+            n => format!("{} results in {} files", n, panel.files.len()),
+        };
+        if result.truncated {
+            text.push_str(&format!(
+                " (limited to {})",
+                crate::project_search::MAX_MATCHES
+            ));
+        }
+        (text, accent_color)
+    } else {
+        (String::new(), dim_color)
+    };
+    painter.draw(
+        frame,
+        layout.text_x,
+        layout.summary_rect.y as usize + layout.text_top_padding,
+        &summary,
+        summary_color,
+    );
+
+    let visible_capacity = layout.visible_capacity();
+    let scroll_offset =
+        resolve_outline_scroll_offset(panel.scroll_offset, panel.selected_index, visible_capacity);
+    let container_right = (rect.x + rect.width) as usize;
+
+    frame.set_clip(layout.list_rect);
+    for visual_row in 0..visible_capacity {
+        let index = scroll_offset + visual_row;
+        let Some(row) = panel.row(index) else {
+            break;
+        };
+        let row_y = layout.list_rect.y as usize + visual_row * layout.row_height;
+        let text_y = row_y + layout.text_top_padding;
+        let is_selected = panel.selected_index == Some(index);
+
+        if is_selected {
+            frame.fill_rect_blended(
+                Rect::new(rect.x, row_y as f32, rect.width, layout.row_height as f32),
+                selection_bg,
+            );
+        }
+        let fg = if is_selected {
+            selection_fg
+        } else {
+            text_color
+        };
+
+        match row {
+            crate::project_search::SearchRow::File(file) => {
+                let label = format!("{} ({})", file.display_name, file.matches.len());
+                let max_chars = ((container_right.saturating_sub(layout.text_x)) as f32
+                    / char_width.max(1.0)) as usize;
+                let display = truncate_with_ellipsis(&label, max_chars);
+                painter.draw(frame, layout.text_x, text_y, &display, fg);
+            }
+            crate::project_search::SearchRow::Match(_, found) => {
+                let line_x = layout.text_x + layout.item_indent;
+                let line_label = format!("{:>4}", found.line + 1);
+                let line_color = if is_selected { selection_fg } else { dim_color };
+                painter.draw(frame, line_x, text_y, &line_label, line_color);
+
+                let text_x = line_x + ((line_label.len() + 1) as f32 * char_width) as usize;
+                let max_chars = ((container_right.saturating_sub(text_x)) as f32
+                    / char_width.max(1.0)) as usize;
+                let (start, end) = found.preview_range;
+                let end = end.min(max_chars);
+                if start < end {
+                    let x = text_x as f32 + start as f32 * char_width;
+                    frame.fill_rect_blended(
+                        Rect::new(
+                            x,
+                            row_y as f32 + layout.text_top_padding as f32 / 2.0,
+                            (end - start) as f32 * char_width,
+                            (layout.row_height - layout.text_top_padding) as f32,
+                        ),
+                        match_bg,
+                    );
+                }
+                let display = truncate_with_ellipsis(&found.preview, max_chars);
+                painter.draw(frame, text_x, text_y, &display, fg);
+            }
+        }
+    }
+
+    frame.clear_clip();
+}
+
+pub fn render_ai_chat_panel(
+    frame: &mut Frame,
+    painter: &mut TextPainter,
+    model: &AppModel,
+    rect: Rect,
+    text_color: u32,
+) {
+    let theme = &model.theme.sidebar;
+    let accent_color = theme.folder_icon.to_argb_u32();
+    let error_color = model.theme.overlay.error.to_argb_u32();
+
+    let layout = AiChatPanelLayout::new(rect, &model.metrics);
+    let chat = &model.ai_chat;
+    let char_width = painter.char_width();
+
+    frame.set_clip(rect);
+
+    let rows = chat.display_rows(layout.max_cols(char_width));
+    let visible_rows = layout.visible_rows();
+    if rows.is_empty() {
+        let msg = "Ask a question, or run \"AI: Explain Selection\"";
+        let max_chars = layout.max_cols(char_width);
+        let display = truncate_with_ellipsis(msg, max_chars);
+        let text_width = display.chars().count() as f32 * char_width;
+        let text_x = rect.x + (rect.width - text_width).max(0.0) / 2.0;
+        let text_y = layout.transcript_rect.y
+            + (layout.transcript_rect.height - painter.line_height() as f32).max(0.0) / 2.0;
+        painter.draw(
+            frame,
+            text_x as usize,
+            text_y as usize,
+            &display,
+            text_color,
+        );
+    } else {
+        // scroll_offset counts rows up from the bottom so new output stays visible
+        let max_offset = rows.len().saturating_sub(visible_rows);
+        let end = rows.len() - chat.scroll_offset.min(max_offset);
+        let start = end.saturating_sub(visible_rows);
+        let last_header = rows
+            .iter()
+            .rposition(|row| matches!(row, crate::ai::TranscriptRow::Header(_)));
+
+        for (visual_row, index) in (start..end).enumerate() {
+            let text_y = layout.transcript_rect.y as usize
+                + visual_row * layout.row_height
+                + layout.text_top_padding;
+            match &rows[index] {
+                crate::ai::TranscriptRow::Header(role) => {
+                    let label = if chat.is_streaming && Some(index) == last_header {
+                        format!("{} \u{2026}", role.label())
+                    } else {
+                        role.label().to_string()
+                    };
+                    painter.draw(frame, layout.text_x, text_y, &label, accent_color);
+                }
+                crate::ai::TranscriptRow::Text(text) => {
+                    painter.draw(frame, layout.text_x, text_y, text, text_color);
+                }
+                crate::ai::TranscriptRow::Error(text) => {
+                    painter.draw(frame, layout.text_x, text_y, text, error_color);
+                }
+                crate::ai::TranscriptRow::Spacer => {}
+            }
+        }
+    }
+
+    let is_focused = model.ui.focused_dock().is_some_and(|position| {
+        model.dock_layout.dock(position).active_panel() == Some(crate::panel::PanelId::AI_CHAT)
+    });
+    TextFieldRenderer::render_modal_input(
+        frame,
+        painter,
+        &chat.input,
+        &layout.input_rect,
+        painter.line_height(),
+        char_width,
+        model.theme.overlay.input_background.to_argb_u32(),
+        text_color,
+        model.theme.editor.cursor_color.to_argb_u32(),
+        theme.selection_background.to_argb_u32(),
+        is_focused && model.ui.cursor_visible,
+    );
+
+    frame.clear_clip();
+}
+
 #[cfg(test)]
 mod outline_scroll_tests {
     use super::resolve_outline_scroll_offset;
@@ -0,0 +1,137 @@
+//! Recorded drawing for the GPU renderer
+//!
+//! When rendering on the GPU, [`Frame`](super::frame::Frame) records its
+//! primitives into a [`Scene`] instead of writing pixels, and the GPU
+//! renderer draws them as textured quads. Rectangles are clipped as they are
+//! recorded; glyphs and images carry their clip rect, since their texture
+//! coordinates are only known to the renderer.
+
+use std::collections::HashSet;
+
+use crate::model::editor_area::Rect;
+
+use super::GlyphCacheKey;
+
+/// A recorded primitive, drawn in order
+#[derive(Debug, Clone)]
+pub enum Primitive {
+    /// Solid rectangle; the color's alpha blends it over what's below
+    Rect { rect: Rect, color: u32 },
+    /// Glyph bitmap from the glyph cache, used as coverage for `color`
+    Glyph {
+        key: GlyphCacheKey,
+        /// Top-left of the bitmap
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        color: u32,
+        /// Horizontal shift per pixel above `baseline` (synthetic italics)
+        shear: f32,
+        baseline: f32,
+        clip: Rect,
+    },
+    /// Image from [`Scene::images`], scaled to `dest`
+    Image {
+        image: usize,
+        dest: Rect,
+        clip: Rect,
+    },
+    /// Checkerboard (transparent image background), cells counted from the
+    /// top-left of `rect`
+    Checkerboard {
+        rect: Rect,
+        cell: f32,
+        light: u32,
+        dark: u32,
+    },
+}
+
+/// RGBA8 image referenced by [`Primitive::Image`]
+#[derive(Debug, Clone)]
+pub struct SceneImage {
+    /// Identifies the image across frames, for texture caching
+    pub key: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Pixel data, or `None` when the renderer already has the texture
+    pub pixels: Option<Vec<u8>>,
+}
+
+/// Everything drawn in one frame
+#[derive(Debug, Clone, Default)]
+pub struct Scene {
+    pub width: usize,
+    pub height: usize,
+    /// Color the frame starts from
+    pub clear_color: u32,
+    pub primitives: Vec<Primitive>,
+    pub images: Vec<SceneImage>,
+    /// Image keys the renderer has textures for; their pixels aren't copied
+    resident_images: HashSet<u64>,
+}
+
+impl Scene {
+    /// An empty scene. `resident_images` are images that needn't be
+    /// uploaded again.
+    pub fn new(width: usize, height: usize, resident_images: HashSet<u64>) -> Self {
+        Self {
+            width,
+            height,
+            clear_color: 0xFF000000,
+            primitives: Vec::new(),
+            images: Vec::new(),
+            resident_images,
+        }
+    }
+
+    /// Start over from a solid color
+    pub fn clear(&mut self, color: u32) {
+        self.clear_color = color;
+        self.primitives.clear();
+        self.images.clear();
+    }
+
+    pub fn push(&mut self, primitive: Primitive) {
+        self.primitives.push(primitive);
+    }
+
+    /// Add an image, returning its index for [`Primitive::Image`]
+    pub fn add_image(&mut self, pixels: &[u8], width: u32, height: u32) -> usize {
+        let key = image_key(pixels, width, height);
+        if let Some(index) = self.images.iter().position(|image| image.key == key) {
+            return index;
+        }
+        self.images.push(SceneImage {
+            key,
+            width,
+            height,
+            pixels: (!self.resident_images.contains(&key)).then(|| pixels.to_vec()),
+        });
+        self.images.len() - 1
+    }
+}
+
+/// Identity of an image's contents: its size and a sample of its pixels
+fn image_key(pixels: &[u8], width: u32, height: u32) -> u64 {
+    const SAMPLES: usize = 4096;
+    let step = (pixels.len() / SAMPLES).max(1);
+    let mut hash = 0xcbf29ce484222325u64;
+    let mut mix = |byte: u8| hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+    for byte in width.to_le_bytes().into_iter().chain(height.to_le_bytes()) {
+        mix(byte);
+    }
+    for byte in pixels.iter().step_by(step) {
+        mix(*byte);
+    }
+    hash
+}
+
+/// Intersection of two rects, or `None` if they don't overlap
+pub fn intersect(a: Rect, b: Rect) -> Option<Rect> {
+    let x0 = a.x.max(b.x);
+    let y0 = a.y.max(b.y);
+    let x1 = (a.x + a.width).min(b.x + b.width);
+    let y1 = (a.y + a.height).min(b.y + b.height);
+    (x1 > x0 && y1 > y0).then(|| Rect::new(x0, y0, x1 - x0, y1 - y0))
+}
@@ -7,7 +7,7 @@
 use crate::editable::{Cursor, EditableState, Position, Selection, StringBuffer};
 
 use super::frame::Frame;
-use super::geometry::{ModalSpacing, WidgetRect};
+use super::geometry::{column_offset_px, ModalSpacing, WidgetRect};
 use super::TextPainter;
 
 /// Options for rendering a text field.
@@ -120,9 +120,9 @@ impl TextFieldRenderer {
             let visible_end = end_col.saturating_sub(opts.scroll_x);
 
             if visible_end > visible_start {
-                let sel_x = opts.x + (visible_start as f32 * opts.char_width).round() as usize;
-                let sel_width =
-                    ((visible_end - visible_start) as f32 * opts.char_width).round() as usize;
+                let sel_x = opts.x + column_offset_px(visible_start, opts.char_width);
+                let sel_width = column_offset_px(visible_end, opts.char_width)
+                    - column_offset_px(visible_start, opts.char_width);
 
                 // Clamp to visible width
                 let clamped_width = sel_width.min(opts.width.saturating_sub(sel_x - opts.x));
@@ -149,7 +149,7 @@ impl TextFieldRenderer {
         if opts.cursor_visible {
             for (idx, cursor) in content.cursors().iter().enumerate() {
                 let col = cursor.column.saturating_sub(opts.scroll_x);
-                let cursor_x = opts.x + (col as f32 * opts.char_width).round() as usize;
+                let cursor_x = opts.x + column_offset_px(col, opts.char_width);
 
                 // Check if cursor is visible in viewport
                 if cursor_x >= opts.x && cursor_x < opts.x + opts.width {
@@ -240,7 +240,7 @@ impl TextFieldRenderer {
         // Render cursor
         if opts.cursor_visible {
             let col = cursor_col.saturating_sub(opts.scroll_x);
-            let cursor_x = opts.x + (col as f32 * opts.char_width).round() as usize;
+            let cursor_x = opts.x + column_offset_px(col, opts.char_width);
 
             if cursor_x >= opts.x && cursor_x < opts.x + opts.width {
                 frame.fill_rect_px(
@@ -0,0 +1,470 @@
+//! Vim emulation: modes, and parsing normal/visual mode keys into actions
+//!
+//! Keys typed in normal and visual mode are fed to [`VimState::key`], which
+//! collects counts, operators and the `g` prefix and returns a [`VimAction`]
+//! once a command is complete. Motions are resolved to positions with
+//! [`motion_target`]; applying actions to the editor lives in `update::vim`.
+
+use ropey::Rope;
+
+use crate::model::Document;
+use crate::util::{char_type, CharType};
+
+/// The editing mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VimMode {
+    /// Keys run commands
+    #[default]
+    Normal,
+    /// Keys type text
+    Insert,
+    /// Motions extend the selection
+    Visual,
+}
+
+impl VimMode {
+    /// Label for the status bar
+    pub fn label(self) -> &'static str {
+        match self {
+            VimMode::Normal => "NORMAL",
+            VimMode::Insert => "INSERT",
+            VimMode::Visual => "VISUAL",
+        }
+    }
+}
+
+/// Operators that act on the text a motion moves over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `d`
+    Delete,
+    /// `c`: delete, then insert
+    Change,
+    /// `y`
+    Yank,
+}
+
+/// Cursor motions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// `h`
+    Left,
+    /// `l`
+    Right,
+    /// `k`
+    Up,
+    /// `j`
+    Down,
+    /// `w`: start of the next word
+    WordForward,
+    /// `b`: start of the previous word
+    WordBackward,
+    /// `0`
+    LineStart,
+    /// `$`
+    LineEnd,
+    /// `gg`, or line N with a count
+    FirstLine,
+    /// `G`, or line N with a count
+    LastLine,
+}
+
+impl Motion {
+    /// Operators with these motions act on whole lines (`dj`, `dG`)
+    pub fn is_linewise(self) -> bool {
+        matches!(
+            self,
+            Motion::Up | Motion::Down | Motion::FirstLine | Motion::LastLine
+        )
+    }
+}
+
+/// Where insert mode starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertAt {
+    /// `i`
+    Cursor,
+    /// `a`
+    AfterCursor,
+    /// `I`: first non-blank of the line
+    LineStart,
+    /// `A`
+    LineEnd,
+    /// `o`
+    LineBelow,
+    /// `O`
+    LineAbove,
+}
+
+/// A complete normal or visual mode command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimAction {
+    /// Move the cursor (extend the selection in visual mode)
+    Move {
+        motion: Motion,
+        count: Option<usize>,
+    },
+    /// Apply an operator over a motion (`d2w`)
+    Operate {
+        operator: Operator,
+        motion: Motion,
+        count: Option<usize>,
+    },
+    /// Apply an operator to `count` whole lines (`dd`, `3yy`)
+    OperateLines { operator: Operator, count: usize },
+    /// Apply an operator to the visual selection
+    OperateSelection(Operator),
+    /// Enter insert mode
+    Insert(InsertAt),
+    /// `x`
+    DeleteChar { count: usize },
+    /// `v`
+    ToggleVisual,
+    /// `p`
+    Paste,
+    /// `u`
+    Undo,
+}
+
+/// Mode and the partly typed command
+#[derive(Debug, Clone, Default)]
+pub struct VimState {
+    pub mode: VimMode,
+    /// Character offset where visual mode started
+    pub visual_anchor: usize,
+    /// Count typed so far
+    count: Option<usize>,
+    /// Operator waiting for a motion, with the count typed before it
+    operator: Option<(Operator, Option<usize>)>,
+    /// `g` typed, waiting for the second `g`
+    pending_g: bool,
+}
+
+impl VimState {
+    /// Feed a typed character. Returns the action once a command is complete;
+    /// unknown keys cancel the partly typed command.
+    pub fn key(&mut self, ch: char) -> Option<VimAction> {
+        // Counts; a leading 0 is the line start motion
+        if let Some(digit) = ch.to_digit(10) {
+            if digit != 0 || self.count.is_some() {
+                let count = self.count.unwrap_or(0);
+                self.count = Some(count.saturating_mul(10).saturating_add(digit as usize));
+                return None;
+            }
+        }
+
+        if self.pending_g {
+            self.pending_g = false;
+            if ch == 'g' {
+                return self.motion(Motion::FirstLine);
+            }
+            self.reset();
+            return None;
+        }
+
+        let motion = match ch {
+            'h' => Some(Motion::Left),
+            'l' => Some(Motion::Right),
+            'k' => Some(Motion::Up),
+            'j' => Some(Motion::Down),
+            'w' => Some(Motion::WordForward),
+            'b' => Some(Motion::WordBackward),
+            '0' => Some(Motion::LineStart),
+            '$' => Some(Motion::LineEnd),
+            'G' => Some(Motion::LastLine),
+            _ => None,
+        };
+        if let Some(motion) = motion {
+            return self.motion(motion);
+        }
+        if ch == 'g' {
+            self.pending_g = true;
+            return None;
+        }
+
+        let operator = match ch {
+            'd' => Some(Operator::Delete),
+            'c' => Some(Operator::Change),
+            'y' => Some(Operator::Yank),
+            _ => None,
+        };
+        if let Some(operator) = operator {
+            if self.mode == VimMode::Visual {
+                self.reset();
+                return Some(VimAction::OperateSelection(operator));
+            }
+            return match self.operator.take() {
+                // Doubled operator: whole lines
+                Some((pending, count)) if pending == operator => {
+                    let count = multiply(count, self.count.take()).unwrap_or(1);
+                    self.reset();
+                    Some(VimAction::OperateLines { operator, count })
+                }
+                Some(_) => {
+                    self.reset();
+                    None
+                }
+                None => {
+                    self.operator = Some((operator, self.count.take()));
+                    None
+                }
+            };
+        }
+
+        // Anything else cancels a pending operator
+        if self.operator.is_some() {
+            self.reset();
+            return None;
+        }
+        let count = self.count.unwrap_or(1);
+        self.reset();
+        if self.mode == VimMode::Visual {
+            return match ch {
+                'x' => Some(VimAction::OperateSelection(Operator::Delete)),
+                'v' => Some(VimAction::ToggleVisual),
+                'p' => Some(VimAction::Paste),
+                _ => None,
+            };
+        }
+        match ch {
+            'i' => Some(VimAction::Insert(InsertAt::Cursor)),
+            'a' => Some(VimAction::Insert(InsertAt::AfterCursor)),
+            'I' => Some(VimAction::Insert(InsertAt::LineStart)),
+            'A' => Some(VimAction::Insert(InsertAt::LineEnd)),
+            'o' => Some(VimAction::Insert(InsertAt::LineBelow)),
+            'O' => Some(VimAction::Insert(InsertAt::LineAbove)),
+            'x' => Some(VimAction::DeleteChar { count }),
+            'v' => Some(VimAction::ToggleVisual),
+            'p' => Some(VimAction::Paste),
+            'u' => Some(VimAction::Undo),
+            _ => None,
+        }
+    }
+
+    /// Forget the partly typed command
+    pub fn reset(&mut self) {
+        self.count = None;
+        self.operator = None;
+        self.pending_g = false;
+    }
+
+    /// The partly typed command, for display (e.g. "2d")
+    pub fn pending(&self) -> String {
+        let mut pending = String::new();
+        if let Some((operator, count)) = self.operator {
+            if let Some(count) = count {
+                pending.push_str(&count.to_string());
+            }
+            pending.push(match operator {
+                Operator::Delete => 'd',
+                Operator::Change => 'c',
+                Operator::Yank => 'y',
+            });
+        }
+        if let Some(count) = self.count {
+            pending.push_str(&count.to_string());
+        }
+        if self.pending_g {
+            pending.push('g');
+        }
+        pending
+    }
+
+    fn motion(&mut self, motion: Motion) -> Option<VimAction> {
+        let action = match self.operator.take() {
+            Some((operator, count)) => VimAction::Operate {
+                operator,
+                motion,
+                count: multiply(count, self.count),
+            },
+            None => VimAction::Move {
+                motion,
+                count: self.count,
+            },
+        };
+        self.reset();
+        Some(action)
+    }
+}
+
+/// `2d3w` deletes six words
+fn multiply(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (None, None) => None,
+        _ => Some(a.unwrap_or(1).saturating_mul(b.unwrap_or(1))),
+    }
+}
+
+/// Where `motion`, repeated `count` times, moves a cursor at `line`/`column`
+pub fn motion_target(
+    doc: &Document,
+    line: usize,
+    column: usize,
+    motion: Motion,
+    count: Option<usize>,
+) -> (usize, usize) {
+    let n = count.unwrap_or(1);
+    let last_line = doc.line_count().saturating_sub(1);
+    let vertical = |line: usize| (line, column.min(doc.line_length(line)));
+    match motion {
+        Motion::Left => (line, column.saturating_sub(n)),
+        Motion::Right => (line, (column + n).min(doc.line_length(line))),
+        Motion::Up => vertical(line.saturating_sub(n)),
+        Motion::Down => vertical((line + n).min(last_line)),
+        Motion::LineStart => (line, 0),
+        Motion::LineEnd => {
+            let line = (line + n - 1).min(last_line);
+            (line, doc.line_length(line))
+        }
+        Motion::FirstLine | Motion::LastLine => {
+            let target = match (motion, count) {
+                (_, Some(count)) => count.saturating_sub(1).min(last_line),
+                (Motion::FirstLine, None) => 0,
+                _ => last_line,
+            };
+            (target, doc.first_non_whitespace_column(target))
+        }
+        Motion::WordForward | Motion::WordBackward => {
+            let mut offset = doc.cursor_to_offset(line, column);
+            for _ in 0..n {
+                offset = if motion == Motion::WordForward {
+                    next_word_start(&doc.buffer, offset)
+                } else {
+                    prev_word_start(&doc.buffer, offset)
+                };
+            }
+            doc.offset_to_cursor(offset)
+        }
+    }
+}
+
+/// Skip the rest of the word under `offset`, then the whitespace after it
+fn next_word_start(text: &Rope, mut offset: usize) -> usize {
+    let len = text.len_chars();
+    let kind = |offset: usize| char_type(text.char(offset));
+    if offset < len && kind(offset) != CharType::Whitespace {
+        let word = kind(offset);
+        while offset < len && kind(offset) == word {
+            offset += 1;
+        }
+    }
+    while offset < len && kind(offset) == CharType::Whitespace {
+        offset += 1;
+    }
+    offset
+}
+
+/// Skip the whitespace before `offset`, then back to the start of that word
+fn prev_word_start(text: &Rope, mut offset: usize) -> usize {
+    let kind = |offset: usize| char_type(text.char(offset));
+    while offset > 0 && kind(offset - 1) == CharType::Whitespace {
+        offset -= 1;
+    }
+    if offset > 0 {
+        let word = kind(offset - 1);
+        while offset > 0 && kind(offset - 1) == word {
+            offset -= 1;
+        }
+    }
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(state: &mut VimState, keys: &str) -> Option<VimAction> {
+        let mut last = None;
+        for ch in keys.chars() {
+            last = state.key(ch);
+        }
+        last
+    }
+
+    #[test]
+    fn counts_multiply_across_operator_and_motion() {
+        let mut state = VimState::default();
+        assert_eq!(
+            keys(&mut state, "2d3w"),
+            Some(VimAction::Operate {
+                operator: Operator::Delete,
+                motion: Motion::WordForward,
+                count: Some(6),
+            })
+        );
+        assert_eq!(
+            keys(&mut state, "3yy"),
+            Some(VimAction::OperateLines {
+                operator: Operator::Yank,
+                count: 3,
+            })
+        );
+        assert_eq!(state.pending(), "");
+    }
+
+    #[test]
+    fn zero_is_a_motion_unless_part_of_a_count() {
+        let mut state = VimState::default();
+        assert_eq!(
+            keys(&mut state, "0"),
+            Some(VimAction::Move {
+                motion: Motion::LineStart,
+                count: None,
+            })
+        );
+        assert_eq!(
+            keys(&mut state, "10G"),
+            Some(VimAction::Move {
+                motion: Motion::LastLine,
+                count: Some(10),
+            })
+        );
+    }
+
+    #[test]
+    fn pending_command_is_shown_and_cancelled_by_unknown_keys() {
+        let mut state = VimState::default();
+        assert_eq!(keys(&mut state, "2d"), None);
+        assert_eq!(state.pending(), "2d");
+        assert_eq!(keys(&mut state, "z"), None);
+        assert_eq!(state.pending(), "");
+        assert_eq!(keys(&mut state, "gg").map(|_| ()), Some(()));
+    }
+
+    #[test]
+    fn visual_mode_operators_act_on_the_selection() {
+        let mut state = VimState {
+            mode: VimMode::Visual,
+            ..VimState::default()
+        };
+        assert_eq!(
+            keys(&mut state, "y"),
+            Some(VimAction::OperateSelection(Operator::Yank))
+        );
+        assert_eq!(keys(&mut state, "i"), None);
+    }
+
+    #[test]
+    fn word_motions_stop_at_word_starts() {
+        let doc = Document::with_text("foo.bar  baz\nqux");
+        let forward = |column, count| motion_target(&doc, 0, column, Motion::WordForward, count);
+        assert_eq!(forward(0, None), (0, 3));
+        assert_eq!(forward(3, None), (0, 4));
+        assert_eq!(forward(4, None), (0, 9));
+        assert_eq!(forward(0, Some(4)), (1, 0));
+        assert_eq!(
+            motion_target(&doc, 1, 2, Motion::WordBackward, Some(3)),
+            (0, 4)
+        );
+    }
+
+    #[test]
+    fn line_motions_clamp_to_the_document() {
+        let doc = Document::with_text("  one\ntwo three\nx");
+        assert_eq!(motion_target(&doc, 1, 8, Motion::Down, None), (2, 1));
+        assert_eq!(motion_target(&doc, 1, 0, Motion::LineEnd, None), (1, 9));
+        assert_eq!(motion_target(&doc, 2, 0, Motion::FirstLine, None), (0, 2));
+        assert_eq!(motion_target(&doc, 0, 0, Motion::LastLine, Some(2)), (1, 0));
+        assert_eq!(motion_target(&doc, 0, 0, Motion::LastLine, None), (2, 0));
+    }
+}
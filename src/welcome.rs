@@ -0,0 +1,199 @@
+//! Start screen shown when the editor launches without a file
+//!
+//! The welcome tab (`TabContent::Welcome`) takes the place of the empty
+//! untitled buffer on startup. Its entries are built from the recent files
+//! list when the tab is created; activating one is handled in
+//! `update::welcome`.
+
+use std::path::PathBuf;
+
+use crate::commands::{keybinding_for_command_static, CommandId, COMMANDS};
+use crate::recent_files::RecentFiles;
+
+/// Maximum number of recent workspaces listed
+pub const MAX_RECENT_WORKSPACES: usize = 4;
+
+/// Maximum number of recent files listed
+pub const MAX_RECENT_FILES: usize = 8;
+
+/// Commands shown in the keybinding cheatsheet, in display order
+const CHEATSHEET_COMMANDS: &[CommandId] = &[
+    CommandId::ShowCommandPalette,
+    CommandId::FuzzyFileFinder,
+    CommandId::OpenRecentFiles,
+    CommandId::ToggleFileExplorer,
+    CommandId::Find,
+    CommandId::GotoLine,
+];
+
+/// Group an entry is listed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WelcomeSection {
+    Start,
+    RecentWorkspaces,
+    RecentFiles,
+}
+
+impl WelcomeSection {
+    /// Heading shown above the section's entries
+    pub fn title(&self) -> &'static str {
+        match self {
+            WelcomeSection::Start => "Start",
+            WelcomeSection::RecentWorkspaces => "Recent Folders",
+            WelcomeSection::RecentFiles => "Recent Files",
+        }
+    }
+}
+
+/// What activating an entry does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WelcomeAction {
+    /// Turn the welcome tab into an empty untitled buffer
+    NewFile,
+    /// Show the open file dialog
+    OpenFileDialog,
+    /// Show the open folder dialog
+    OpenFolderDialog,
+    /// Open a recently used folder as the workspace
+    OpenRecentWorkspace(PathBuf),
+    /// Open a recently used file
+    OpenRecentFile(PathBuf),
+}
+
+/// A selectable entry on the start screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WelcomeItem {
+    pub section: WelcomeSection,
+    /// Primary text (command name, file name, folder name)
+    pub label: String,
+    /// Secondary text shown dimmed after the label (keybinding or path)
+    pub detail: String,
+    pub action: WelcomeAction,
+}
+
+impl WelcomeItem {
+    fn command(label: &str, id: CommandId, action: WelcomeAction) -> Self {
+        Self {
+            section: WelcomeSection::Start,
+            label: label.to_string(),
+            detail: keybinding_for_command_static(id)
+                .unwrap_or_default()
+                .to_string(),
+            action,
+        }
+    }
+}
+
+/// Entries of the start screen: fixed actions, then recent folders and files
+pub fn welcome_items(recent: &RecentFiles) -> Vec<WelcomeItem> {
+    let mut items = vec![
+        WelcomeItem::command("New File", CommandId::NewFile, WelcomeAction::NewFile),
+        WelcomeItem::command(
+            "Open File…",
+            CommandId::OpenFile,
+            WelcomeAction::OpenFileDialog,
+        ),
+        WelcomeItem::command(
+            "Open Folder…",
+            CommandId::OpenFolder,
+            WelcomeAction::OpenFolderDialog,
+        ),
+    ];
+
+    let mut workspaces: Vec<&PathBuf> = Vec::new();
+    for workspace in recent.entries.iter().filter_map(|e| e.workspace.as_ref()) {
+        if workspaces.len() == MAX_RECENT_WORKSPACES {
+            break;
+        }
+        if !workspaces.contains(&workspace) && workspace.is_dir() {
+            workspaces.push(workspace);
+        }
+    }
+    items.extend(workspaces.into_iter().map(|path| WelcomeItem {
+        section: WelcomeSection::RecentWorkspaces,
+        label: file_name(path),
+        detail: path.display().to_string(),
+        action: WelcomeAction::OpenRecentWorkspace(path.clone()),
+    }));
+
+    items.extend(
+        recent
+            .entries
+            .iter()
+            .filter(|entry| entry.exists())
+            .take(MAX_RECENT_FILES)
+            .map(|entry| WelcomeItem {
+                section: WelcomeSection::RecentFiles,
+                label: file_name(&entry.path),
+                detail: entry.display_path(),
+                action: WelcomeAction::OpenRecentFile(entry.path.clone()),
+            }),
+    );
+    items
+}
+
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// `(label, keybinding)` rows of the keybinding cheatsheet
+pub fn cheatsheet() -> Vec<(&'static str, &'static str)> {
+    CHEATSHEET_COMMANDS
+        .iter()
+        .filter_map(|id| {
+            let def = COMMANDS.iter().find(|def| def.id == *id)?;
+            Some((def.label, def.keybinding?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recent_files::RecentEntry;
+
+    #[test]
+    fn items_list_existing_recent_files_and_distinct_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_a = dir.path().join("a.rs");
+        let file_b = dir.path().join("b.rs");
+        std::fs::write(&file_a, "").unwrap();
+        std::fs::write(&file_b, "").unwrap();
+
+        let workspace = Some(dir.path().to_path_buf());
+        let recent = RecentFiles {
+            version: 1,
+            entries: vec![
+                RecentEntry::new(file_a.clone(), workspace.clone()),
+                RecentEntry::new(dir.path().join("gone.rs"), workspace.clone()),
+                RecentEntry::new(file_b.clone(), workspace),
+            ],
+        };
+
+        let items = welcome_items(&recent);
+        let actions: Vec<_> = items.iter().map(|item| item.action.clone()).collect();
+        assert_eq!(
+            actions,
+            vec![
+                WelcomeAction::NewFile,
+                WelcomeAction::OpenFileDialog,
+                WelcomeAction::OpenFolderDialog,
+                WelcomeAction::OpenRecentWorkspace(dir.path().to_path_buf()),
+                WelcomeAction::OpenRecentFile(file_a),
+                WelcomeAction::OpenRecentFile(file_b),
+            ]
+        );
+        assert_eq!(items[4].detail, "a.rs");
+    }
+
+    #[test]
+    fn cheatsheet_uses_palette_keybindings() {
+        let rows = cheatsheet();
+        assert!(!rows.is_empty());
+        assert!(rows
+            .iter()
+            .all(|(label, key)| !label.is_empty() && !key.is_empty()));
+    }
+}
@@ -0,0 +1,117 @@
+//! Persistent workspace trust decisions
+//!
+//! A workspace's own files can point the editor at commands to run
+//! (language servers start project build scripts, tools run in the
+//! workspace), so each workspace root is asked about once: trusted, or kept
+//! in restricted mode, where nothing is executed on its behalf. Decisions
+//! are saved to `trust.json` in the config directory.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// What the user decided for a workspace root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustDecision {
+    /// Commands may run
+    Trusted,
+    /// Restricted mode: no commands run for this workspace
+    Restricted,
+}
+
+/// Trust decisions by workspace root
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceTrust {
+    /// Schema version for forward compatibility
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub workspaces: BTreeMap<PathBuf, TrustDecision>,
+}
+
+impl WorkspaceTrust {
+    pub const CURRENT_VERSION: u32 = 1;
+
+    /// Load decisions from disk, or none if nothing was saved
+    pub fn load() -> Self {
+        let Some(path) = crate::config_paths::trust_file() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid trust file {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Save decisions to disk
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = crate::config_paths::trust_file() else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "No config directory available",
+            ));
+        };
+        crate::config_paths::ensure_all_config_dirs();
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)
+    }
+
+    /// Decision for `root`, or `None` if the user hasn't been asked yet
+    pub fn decision(&self, root: &Path) -> Option<TrustDecision> {
+        self.workspaces.get(&canonical(root)).copied()
+    }
+
+    /// Whether commands may run for the workspace at `root`. Undecided
+    /// workspaces stay restricted until the user answers.
+    pub fn is_trusted(&self, root: &Path) -> bool {
+        self.decision(root) == Some(TrustDecision::Trusted)
+    }
+
+    pub fn set(&mut self, root: &Path, decision: TrustDecision) {
+        self.version = Self::CURRENT_VERSION;
+        self.workspaces.insert(canonical(root), decision);
+    }
+}
+
+/// The same folder reached through a symlink or `..` shares one decision
+fn canonical(root: &Path) -> PathBuf {
+    root.canonicalize().unwrap_or_else(|_| root.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undecided_workspaces_are_not_trusted() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut trust = WorkspaceTrust::default();
+        assert_eq!(trust.decision(dir.path()), None);
+        assert!(!trust.is_trusted(dir.path()));
+
+        trust.set(dir.path(), TrustDecision::Restricted);
+        assert!(!trust.is_trusted(dir.path()));
+
+        // Another spelling of the same folder replaces the decision
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        trust.set(&dir.path().join("sub").join(".."), TrustDecision::Trusted);
+        assert!(trust.is_trusted(dir.path()));
+        assert_eq!(trust.workspaces.len(), 1);
+    }
+
+    #[test]
+    fn decisions_round_trip_through_json() {
+        let mut trust = WorkspaceTrust::default();
+        trust.set(Path::new("/no/such/project"), TrustDecision::Trusted);
+        let json = serde_json::to_string(&trust).unwrap();
+        assert!(json.contains("\"trusted\""));
+        let loaded: WorkspaceTrust = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, trust);
+        assert!(loaded.is_trusted(Path::new("/no/such/project")));
+    }
+}
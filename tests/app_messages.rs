@@ -1,15 +1,20 @@
 //! Regression tests for AppMsg handlers in src/update/app.rs:
 //! - FileLoaded resetting view_mode/tab_content for non-text tabs
 //! - OpenFileDialogResult preserving per-file commands
-//! - ReloadConfiguration triggering a full redraw
+//! - ReloadConfiguration triggering a full redraw and a keymap reload
+//! - UpdateCheckCompleted remembering a newer release for "View Release Notes"
+//! - KeymapReloaded feeding "Keymap: Show Diagnostics"
+//! - SaveFileAsDialogResult saving the document the dialog was opened for
+//! - LargeFileProgress/LargeFileLoaded filling in a large file's placeholder
 
 mod common;
 
 use common::test_model;
-use token::commands::Cmd;
+use token::commands::{Cmd, CommandId};
 use token::messages::{AppMsg, Msg};
 use token::model::editor::{TabContent, ViewMode};
 use token::update::update;
+use token::update_check::Release;
 
 // ============================================================================
 // FileLoaded must not leave a stale non-text view_mode/tab_content behind
@@ -130,9 +135,317 @@ fn reload_configuration_returns_full_redraw() {
 
     let cmd = update(&mut model, Msg::App(AppMsg::ReloadConfiguration));
 
+    let Some(Cmd::Batch(cmds)) = &cmd else {
+        panic!("expected a batch, got: {:?}", cmd);
+    };
     assert!(
-        matches!(cmd, Some(Cmd::Redraw)),
+        cmds.iter().any(|cmd| matches!(cmd, Cmd::Redraw)),
         "ReloadConfiguration should return a full Cmd::Redraw so theme changes repaint immediately, got: {:?}",
         cmd
     );
+    assert!(
+        cmds.iter().any(|cmd| matches!(cmd, Cmd::ReloadKeymap)),
+        "ReloadConfiguration should reload the keymap too, got: {:?}",
+        cmd
+    );
+}
+
+// ============================================================================
+// UpdateCheckCompleted only announces releases newer than the running one
+// ============================================================================
+
+#[test]
+fn update_check_remembers_newer_release_for_release_notes() {
+    let mut model = test_model("hello\n", 0, 0);
+    let check = |version: &str| {
+        Msg::App(AppMsg::UpdateCheckCompleted {
+            manual: false,
+            result: Ok(Release {
+                version: version.to_string(),
+                url: format!("https://example.com/releases/{}", version),
+            }),
+        })
+    };
+
+    update(&mut model, check(env!("CARGO_PKG_VERSION")));
+    assert!(model.ui.available_update.is_none());
+
+    update(&mut model, check("999.0.0"));
+    assert_eq!(
+        model
+            .ui
+            .available_update
+            .as_ref()
+            .map(|r| r.version.as_str()),
+        Some("999.0.0")
+    );
+
+    let cmd = update(
+        &mut model,
+        Msg::App(AppMsg::RunCommand(CommandId::ViewReleaseNotes)),
+    );
+    match cmd {
+        Some(Cmd::OpenUrl { url }) => assert_eq!(url, "https://example.com/releases/999.0.0"),
+        other => panic!("expected Cmd::OpenUrl, got: {:?}", other),
+    }
+}
+
+// ============================================================================
+// KeymapReloaded keeps the diagnostics for "Keymap: Show Diagnostics"
+// ============================================================================
+
+#[test]
+fn keymap_diagnostics_are_listed_from_the_command_palette() {
+    use token::keymap::KeymapDiagnostic;
+    use token::model::ModalState;
+
+    let mut model = test_model("hello\n", 0, 0);
+    update(
+        &mut model,
+        Msg::App(AppMsg::RunCommand(CommandId::ShowKeymapDiagnostics)),
+    );
+    assert!(model.ui.active_modal.is_none(), "nothing to list yet");
+
+    let diagnostic = KeymapDiagnostic {
+        path: Some(std::path::PathBuf::from("/config/keymap.yaml")),
+        line: Some(7),
+        message: "cmd+d: Duplicate never runs".to_string(),
+    };
+    update(
+        &mut model,
+        Msg::App(AppMsg::KeymapReloaded {
+            diagnostics: vec![diagnostic.clone()],
+            applied: true,
+        }),
+    );
+    assert_eq!(model.ui.status_message, "Keymap reloaded with 1 conflict");
+
+    update(
+        &mut model,
+        Msg::App(AppMsg::RunCommand(CommandId::ShowKeymapDiagnostics)),
+    );
+    match &model.ui.active_modal {
+        Some(ModalState::KeymapDiagnostics(state)) => assert_eq!(state.entries, vec![diagnostic]),
+        other => panic!("expected the keymap diagnostics list, got {:?}", other),
+    }
+}
+
+// ============================================================================
+// Save As answers for the document that opened the dialog
+// ============================================================================
+
+/// Save an untitled document, returning the document id the dialog was
+/// opened for
+fn save_untitled(model: &mut token::model::AppModel) -> token::model::editor_area::DocumentId {
+    model.document_mut().file_path = None;
+    match update(model, Msg::App(AppMsg::SaveFile)) {
+        Some(Cmd::ShowSaveFileDialog {
+            document_id: Some(document_id),
+            ..
+        }) => document_id,
+        other => panic!("expected the save dialog, got {:?}", other),
+    }
+}
+
+#[test]
+fn save_as_result_saves_the_document_that_asked() {
+    use token::messages::LayoutMsg;
+
+    let mut model = test_model("fn main() {}\n", 0, 0);
+    let document_id = save_untitled(&mut model);
+
+    // Another tab is focused while the dialog is open
+    update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+    assert_ne!(model.document().id, Some(document_id));
+
+    let path = std::env::temp_dir().join("token-save-as").join("main.rs");
+    let cmd = update(
+        &mut model,
+        Msg::App(AppMsg::SaveFileAsDialogResult {
+            document_id: Some(document_id),
+            path: Some(path.clone()),
+        }),
+    );
+
+    assert_eq!(model.document().id, Some(document_id), "its tab is focused");
+    assert_eq!(model.document().file_path.as_deref(), Some(path.as_path()));
+    assert_eq!(model.document().display_name(), "main.rs");
+    assert_eq!(model.document().language, token::syntax::LanguageId::Rust);
+    let Some(Cmd::Batch(cmds)) = cmd else {
+        panic!("expected a batch, got {:?}", cmd);
+    };
+    assert!(cmds.iter().any(|cmd| matches!(
+        cmd,
+        Cmd::SaveFile { path: saved, content } if *saved == path && content == "fn main() {}\n"
+    )));
+}
+
+#[test]
+fn save_as_result_for_a_closed_document_is_dropped() {
+    use token::messages::LayoutMsg;
+
+    let mut model = test_model("draft\n", 0, 0);
+    let document_id = save_untitled(&mut model);
+    update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+    let tab_id = model.editor_area.focused_group().unwrap().tabs[0].id;
+    model
+        .editor_area
+        .documents
+        .get_mut(&document_id)
+        .unwrap()
+        .is_modified = false;
+    update(&mut model, Msg::Layout(LayoutMsg::CloseTab(tab_id)));
+    assert!(!model.editor_area.documents.contains_key(&document_id));
+
+    let cmd = update(
+        &mut model,
+        Msg::App(AppMsg::SaveFileAsDialogResult {
+            document_id: Some(document_id),
+            path: Some(std::env::temp_dir().join("draft.txt")),
+        }),
+    );
+    assert!(!matches!(cmd, Some(Cmd::Batch(_))));
+    assert_eq!(model.document().file_path, None);
+    assert_eq!(
+        model.ui.status_message,
+        "Save cancelled: the document was closed"
+    );
+}
+
+// ============================================================================
+// Large files stream into a placeholder document
+// ============================================================================
+
+/// Every command in `cmd`, with batches flattened
+fn flatten(cmd: Option<Cmd>) -> Vec<Cmd> {
+    match cmd {
+        Some(Cmd::Batch(cmds)) => cmds.into_iter().flat_map(|c| flatten(Some(c))).collect(),
+        Some(cmd) => vec![cmd],
+        None => vec![],
+    }
+}
+
+/// Open a file just over the large file threshold in a new tab
+fn open_large_file(
+    model: &mut token::model::AppModel,
+    dir: &tempfile::TempDir,
+) -> (std::path::PathBuf, Vec<Cmd>) {
+    let path = dir.path().join("big.rs");
+    let line = "fn main() {}\n";
+    let lines = token::large_file::LARGE_FILE_SIZE as usize / line.len() + 1;
+    std::fs::write(&path, line.repeat(lines)).unwrap();
+    let cmd = update(
+        model,
+        Msg::Layout(token::messages::LayoutMsg::OpenFileInNewTab(path.clone())),
+    );
+    (path, flatten(cmd))
+}
+
+fn segment(model: &token::model::AppModel, id: token::model::SegmentId) -> String {
+    model
+        .ui
+        .status_bar
+        .get_segment(id)
+        .unwrap()
+        .content
+        .display_text()
+        .to_string()
+}
+
+#[test]
+fn large_file_streams_into_its_placeholder() {
+    use token::model::SegmentId;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut model = test_model("", 0, 0);
+    let (path, cmds) = open_large_file(&mut model, &dir);
+    let document_id = model.editor_area.focused_document_id().unwrap();
+
+    // Opened empty at once, and read in the background
+    assert!(model.document().large_file);
+    assert!(model.document().is_loading());
+    assert_eq!(model.document().buffer.len_bytes(), 0);
+    let loads = cmds
+        .iter()
+        .filter(
+            |cmd| matches!(cmd, Cmd::LoadLargeFile { document_id: id, .. } if *id == document_id),
+        )
+        .count();
+    assert_eq!(loads, 1);
+
+    // Edits wait until the file is in
+    update(
+        &mut model,
+        Msg::Document(token::messages::DocumentMsg::InsertChar('x')),
+    );
+    assert_eq!(model.document().buffer.len_bytes(), 0);
+    assert_eq!(model.ui.status_message, "big.rs is still loading");
+
+    let total = std::fs::metadata(&path).unwrap().len();
+    update(
+        &mut model,
+        Msg::App(AppMsg::LargeFileProgress {
+            document_id,
+            loaded: total.div_ceil(2),
+            total,
+        }),
+    );
+    assert_eq!(segment(&model, SegmentId::FileLoading), "Loading 50%");
+
+    let result = token::large_file::stream_file(&path, |_, _| {});
+    let cmds = flatten(update(
+        &mut model,
+        Msg::App(AppMsg::LargeFileLoaded {
+            document_id,
+            result,
+        }),
+    ));
+    assert!(!model.document().is_loading());
+    assert_eq!(model.document().buffer.len_bytes() as u64, total);
+    assert_eq!(segment(&model, SegmentId::FileLoading), "");
+    assert!(!model.document().is_modified);
+
+    // No highlighting, and every keystroke is its own undo step
+    update(
+        &mut model,
+        Msg::Document(token::messages::DocumentMsg::InsertChar('x')),
+    );
+    let cmds: Vec<Cmd> = cmds
+        .into_iter()
+        .chain(flatten(update(
+            &mut model,
+            Msg::Document(token::messages::DocumentMsg::InsertChar('y')),
+        )))
+        .collect();
+    assert!(!cmds
+        .iter()
+        .any(|cmd| matches!(cmd, Cmd::DebouncedSyntaxParse { .. })));
+    assert_eq!(model.document().undo_stack.len(), 2);
+}
+
+#[test]
+fn large_file_that_fails_to_load_is_closed() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut model = test_model("", 0, 0);
+    open_large_file(&mut model, &dir);
+    let document_id = model.editor_area.focused_document_id().unwrap();
+    let tabs = model.editor_area.focused_group().unwrap().tabs.len();
+
+    update(
+        &mut model,
+        Msg::App(AppMsg::LargeFileLoaded {
+            document_id,
+            result: Err("stream did not contain valid UTF-8".to_string()),
+        }),
+    );
+
+    assert!(!model.editor_area.documents.contains_key(&document_id));
+    assert_eq!(
+        model.editor_area.focused_group().unwrap().tabs.len(),
+        tabs - 1
+    );
+    assert_eq!(
+        model.ui.status_message,
+        "Failed to load big.rs: stream did not contain valid UTF-8"
+    );
 }
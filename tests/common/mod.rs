@@ -6,8 +6,8 @@
 
 use token::config::EditorConfig;
 use token::model::{
-    AppModel, Cursor, Document, EditorArea, EditorState, Position, RectangleSelectionState,
-    Selection, TabContent, UiState, ViewMode, Viewport,
+    AppModel, Cursor, Document, EditorArea, EditorState, FoldState, Position,
+    RectangleSelectionState, Selection, TabContent, UiState, ViewMode, Viewport, WordHighlights,
 };
 use token::theme::Theme;
 
@@ -35,11 +35,14 @@ pub fn test_model(text: &str, line: usize, column: usize) -> AppModel {
         },
         scroll_padding: 1, // Default padding for tests
         rectangle_selection: RectangleSelectionState::default(),
+        text_drag: None,
         occurrence_state: None,
         selection_history: Vec::new(),
         view_mode: ViewMode::default(),
         tab_content: TabContent::default(),
         matched_brackets: None,
+        word_highlights: WordHighlights::default(),
+        folds: FoldState::default(),
     };
 
     let editor_area = EditorArea::single_document(document, editor);
@@ -58,6 +61,13 @@ pub fn test_model(text: &str, line: usize, column: usize) -> AppModel {
         terminal: token::terminal::TerminalState::default(),
         outline_panel: token::model::OutlinePanelState::default(),
         recent_files: token::recent_files::RecentFiles::default(),
+        workspace_trust: token::workspace_trust::WorkspaceTrust::default(),
+        file_ops: Default::default(),
+        vim: Default::default(),
+        ai_chat: token::ai::AiChatState::default(),
+        lsp: token::lsp::LspState::default(),
+        todo_panel: token::model::TodoPanelState::default(),
+        search_panel: token::model::SearchPanelState::default(),
         #[cfg(debug_assertions)]
         debug_overlay: None,
     }
@@ -102,11 +112,14 @@ pub fn test_model_with_selection(
         },
         scroll_padding: 1,
         rectangle_selection: RectangleSelectionState::default(),
+        text_drag: None,
         occurrence_state: None,
         selection_history: Vec::new(),
         view_mode: ViewMode::default(),
         tab_content: TabContent::default(),
         matched_brackets: None,
+        word_highlights: WordHighlights::default(),
+        folds: FoldState::default(),
     };
 
     let editor_area = EditorArea::single_document(document, editor);
@@ -125,6 +138,13 @@ pub fn test_model_with_selection(
         terminal: token::terminal::TerminalState::default(),
         outline_panel: token::model::OutlinePanelState::default(),
         recent_files: token::recent_files::RecentFiles::default(),
+        workspace_trust: token::workspace_trust::WorkspaceTrust::default(),
+        file_ops: Default::default(),
+        vim: Default::default(),
+        ai_chat: token::ai::AiChatState::default(),
+        lsp: token::lsp::LspState::default(),
+        todo_panel: token::model::TodoPanelState::default(),
+        search_panel: token::model::SearchPanelState::default(),
         #[cfg(debug_assertions)]
         debug_overlay: None,
     }
@@ -166,11 +186,14 @@ pub fn test_model_multi_cursor(text: &str, positions: &[(usize, usize)]) -> AppM
         },
         scroll_padding: 1,
         rectangle_selection: RectangleSelectionState::default(),
+        text_drag: None,
         occurrence_state: None,
         selection_history: Vec::new(),
         view_mode: ViewMode::default(),
         tab_content: TabContent::default(),
         matched_brackets: None,
+        word_highlights: WordHighlights::default(),
+        folds: FoldState::default(),
     };
 
     let editor_area = EditorArea::single_document(document, editor);
@@ -189,6 +212,13 @@ pub fn test_model_multi_cursor(text: &str, positions: &[(usize, usize)]) -> AppM
         terminal: token::terminal::TerminalState::default(),
         outline_panel: token::model::OutlinePanelState::default(),
         recent_files: token::recent_files::RecentFiles::default(),
+        workspace_trust: token::workspace_trust::WorkspaceTrust::default(),
+        file_ops: Default::default(),
+        vim: Default::default(),
+        ai_chat: token::ai::AiChatState::default(),
+        lsp: token::lsp::LspState::default(),
+        todo_panel: token::model::TodoPanelState::default(),
+        search_panel: token::model::SearchPanelState::default(),
         #[cfg(debug_assertions)]
         debug_overlay: None,
     }
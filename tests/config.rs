@@ -103,12 +103,132 @@ fn test_config_serialize_deserialize() {
         theme: "fleet-dark".to_string(),
         cursor_blink_ms: 600,
         auto_surround: true,
+        auto_close_tags: true,
+        linked_tag_editing: true,
         bracket_matching: true,
+        reindent_on_close_brace: false,
+        indent_style: token::config::IndentStyle::Spaces,
+        indent_width: 2,
+        vim_mode: true,
         show_scrollbar: true,
+        paste_image_path: "img/{timestamp}.png".to_string(),
+        markdown_hybrid: true,
+        check_for_updates: false,
+        restore_session: false,
+        verbose_logging: vec![token::tracing::LogTarget::Syntax],
+        renderer: token::view::RendererBackend::Software,
+        text_antialiasing: token::view::TextAntialiasing::Subpixel,
+        font_size: 16.5,
+        font_family: "Iosevka".to_string(),
+        font_fallbacks: vec!["Noto Sans CJK JP".to_string()],
+        elastic_tabstops: true,
+        breadcrumbs: true,
+        render_whitespace: token::config::RenderWhitespace::Selection,
+        show_indent_guides: false,
+        rulers: vec![80, 120],
+        highlight_occurrences: false,
+        autosave: token::config::AutosaveConfig {
+            on_window_blur: true,
+            on_tab_switch: false,
+            interval_ms: Some(30_000),
+        },
+        undo: token::config::UndoConfig {
+            group_timeout_ms: 250,
+            ..Default::default()
+        },
+        blame: token::config::BlameConfig {
+            current_line_only: true,
+        },
+        external_tools: vec![token::external_tools::ExternalTool {
+            name: "Format".to_string(),
+            command: "rustfmt $FILE".to_string(),
+        }],
+        performance_mode: token::performance::PerformanceMode::Off,
+        ai: token::ai::AiConfig::default(),
+        lsp: token::lsp::LspConfig {
+            enabled: true,
+            ..Default::default()
+        },
     };
     let yaml = serde_yaml::to_string(&config).unwrap();
     let parsed: EditorConfig = serde_yaml::from_str(&yaml).unwrap();
     assert_eq!(parsed.theme, "fleet-dark");
+    assert_eq!(parsed.paste_image_path, "img/{timestamp}.png");
+    assert!(parsed.markdown_hybrid);
+    assert!(parsed.breadcrumbs);
+    assert_eq!(
+        parsed.render_whitespace,
+        token::config::RenderWhitespace::Selection
+    );
+    assert!(!parsed.show_indent_guides);
+    assert_eq!(parsed.rulers, [80, 120]);
+    assert!(!parsed.highlight_occurrences);
+    assert!(!parsed.restore_session);
+    assert!(parsed.elastic_tabstops);
+    assert_eq!(parsed.indent_style, token::config::IndentStyle::Spaces);
+    assert_eq!(parsed.indent_width, 2);
+    assert!(parsed.vim_mode);
+    assert!(parsed.autosave.on_window_blur);
+    assert!(!parsed.autosave.on_tab_switch);
+    assert_eq!(parsed.autosave.interval_ms, Some(30_000));
+    assert_eq!(parsed.undo.group_timeout_ms, 250);
+    assert!(parsed.undo.group_typing);
+    assert!(parsed.blame.current_line_only);
+    assert_eq!(parsed.external_tools[0].command, "rustfmt $FILE");
+    assert_eq!(
+        parsed.performance_mode,
+        token::performance::PerformanceMode::Off
+    );
+    assert_eq!(parsed.renderer, token::view::RendererBackend::Software);
+    assert_eq!(
+        parsed.text_antialiasing,
+        token::view::TextAntialiasing::Subpixel
+    );
+    assert_eq!(parsed.font_size, 16.5);
+    assert_eq!(parsed.font_family, "Iosevka");
+    assert_eq!(parsed.font_fallbacks, ["Noto Sans CJK JP"]);
+    assert!(parsed.lsp.enabled);
+    assert_eq!(parsed.lsp.servers["rust"].command, "rust-analyzer");
+}
+
+#[test]
+fn test_config_font_defaults_to_the_embedded_font() {
+    let config = EditorConfig::default();
+    assert_eq!(config.font_family, token::view::fonts::EMBEDDED_FAMILY);
+    assert!(!config.font_fallbacks.is_empty());
+
+    let config: EditorConfig = serde_yaml::from_str("font_fallbacks: []").unwrap();
+    assert!(config.font_fallbacks.is_empty());
+}
+
+#[test]
+fn test_config_zoomed_font_size_steps_and_stops_at_the_limits() {
+    let mut config = EditorConfig::default();
+    assert_eq!(config.font_size, token::config::DEFAULT_FONT_SIZE);
+    assert_eq!(config.zoomed_font_size(1), 15.0);
+    assert_eq!(config.zoomed_font_size(-1), 13.0);
+
+    // A size set by hand snaps to whole points
+    config.font_size = 16.5;
+    assert_eq!(config.zoomed_font_size(1), 17.0);
+    assert_eq!(config.zoomed_font_size(-1), 16.0);
+
+    config.font_size = token::config::MAX_FONT_SIZE;
+    assert_eq!(config.zoomed_font_size(1), token::config::MAX_FONT_SIZE);
+    config.font_size = 0.0;
+    assert_eq!(config.zoomed_font_size(-1), token::config::MIN_FONT_SIZE);
+}
+
+#[test]
+fn test_config_lsp_servers_replace_defaults() {
+    let yaml = "lsp:\n  enabled: true\n  servers:\n    python:\n      command: pylsp\n";
+    let config: EditorConfig = serde_yaml::from_str(yaml).unwrap();
+    assert!(config.lsp.enabled);
+    assert_eq!(config.lsp.servers.len(), 1);
+    assert_eq!(config.lsp.servers["python"].command_line(), "pylsp");
+
+    let config = EditorConfig::default();
+    assert!(!config.lsp.enabled);
 }
 
 #[test]
@@ -82,6 +82,7 @@ fn test_horizontal_split_layout() {
         editor_id: editor2_id,
         is_pinned: false,
         is_preview: false,
+        color: None,
     };
 
     area.groups.insert(
@@ -93,6 +94,7 @@ fn test_horizontal_split_layout() {
             rect: Rect::default(),
             attached_preview: None,
             tab_scroll: 0,
+            scroll_lock: None,
         },
     );
 
@@ -143,6 +145,7 @@ fn test_vertical_split_layout() {
         editor_id: editor2_id,
         is_pinned: false,
         is_preview: false,
+        color: None,
     };
 
     area.groups.insert(
@@ -154,6 +157,7 @@ fn test_vertical_split_layout() {
             rect: Rect::default(),
             attached_preview: None,
             tab_scroll: 0,
+            scroll_lock: None,
         },
     );
 
@@ -204,6 +208,7 @@ fn test_group_at_point_split() {
         editor_id: editor2_id,
         is_pinned: false,
         is_preview: false,
+        color: None,
     };
 
     area.groups.insert(
@@ -215,6 +220,7 @@ fn test_group_at_point_split() {
             rect: Rect::default(),
             attached_preview: None,
             tab_scroll: 0,
+            scroll_lock: None,
         },
     );
 
@@ -256,6 +262,7 @@ fn test_splitter_at_point() {
         editor_id: editor2_id,
         is_pinned: false,
         is_preview: false,
+        color: None,
     };
 
     area.groups.insert(
@@ -267,6 +274,7 @@ fn test_splitter_at_point() {
             rect: Rect::default(),
             attached_preview: None,
             tab_scroll: 0,
+            scroll_lock: None,
         },
     );
 
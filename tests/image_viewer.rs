@@ -147,3 +147,32 @@ fn test_sync_all_viewports_does_not_override_user_zoomed_image_scale() {
     let image = model.editor().view_mode.as_image().unwrap();
     assert!((image.scale - 2.0).abs() < 1e-9);
 }
+
+#[test]
+fn test_images_on_the_command_line_open_in_the_image_viewer() {
+    use token::model::{AppModel, SegmentContent, SegmentId};
+
+    let dir = tempfile::tempdir().unwrap();
+    let image_path = dir.path().join("dot.png");
+    image::RgbaImage::new(3, 2).save(&image_path).unwrap();
+    let text_path = dir.path().join("notes.txt");
+    std::fs::write(&text_path, "hello").unwrap();
+
+    let mut model = AppModel::new(800, 600, 1.0, vec![image_path.clone(), text_path]);
+    let image = model.editor().view_mode.as_image().unwrap();
+    assert_eq!((image.width, image.height), (3, 2));
+    assert_eq!(model.document().file_path.as_ref(), Some(&image_path));
+    assert!(!model.document().is_modified);
+
+    token::model::sync_status_bar(&mut model);
+    let dims = model.ui.status_bar.get_segment(SegmentId::CursorPosition);
+    assert_eq!(
+        dims.map(|segment| &segment.content),
+        Some(&SegmentContent::Text("3x2".into()))
+    );
+
+    // The text file after it opens as text in the next tab
+    let group = model.editor_area.focused_group().unwrap();
+    let editor_id = group.tabs[1].editor_id;
+    assert!(!model.editor_area.editors[&editor_id].view_mode.is_image());
+}
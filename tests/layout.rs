@@ -3,9 +3,13 @@
 mod common;
 
 use common::test_model;
-use token::commands::Cmd;
-use token::messages::{LayoutMsg, Msg};
-use token::model::{GroupId, LayoutNode, Position, Selection, SplitDirection};
+use token::commands::{Cmd, CommandId};
+use token::messages::{DocumentMsg, EditorMsg, LayoutMsg, ModalMsg, Msg, UiMsg};
+use token::model::editor_area::TabId;
+use token::model::{
+    ContextMenuState, DropEdge, GroupId, LayoutNode, ModalState, Position, Rect, Selection,
+    SplitDirection,
+};
 use token::update::update;
 
 /// Helper to set cursor position and sync selection (maintains invariants)
@@ -300,6 +304,93 @@ fn test_focus_prev_group() {
     assert_eq!(model.editor_area.focused_group_id, group2);
 }
 
+#[test]
+fn test_scroll_other_group_keeps_focus_and_cursor() {
+    let text = "line\n".repeat(100);
+    let mut model = test_model(&text, 0, 0);
+    let group1 = model.editor_area.focused_group_id;
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::SplitFocused(SplitDirection::Horizontal)),
+    );
+    let group2 = model.editor_area.focused_group_id;
+
+    update(&mut model, Msg::Layout(LayoutMsg::ScrollOtherGroup(3)));
+
+    assert_eq!(model.editor_area.focused_group_id, group2);
+    assert_eq!(model.editor().viewport.top_line, 0);
+    let other_editor_id = model.editor_area.groups[&group1]
+        .active_tab()
+        .unwrap()
+        .editor_id;
+    let other = &model.editor_area.editors[&other_editor_id];
+    assert_eq!(other.viewport.top_line, 3);
+    assert_eq!(other.active_cursor().line, 0);
+}
+
+#[test]
+fn test_scroll_lock_keeps_the_offset_between_splits() {
+    let text = "line\n".repeat(100);
+    let mut model = test_model(&text, 0, 0);
+    let group1 = model.editor_area.focused_group_id;
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::SplitFocused(SplitDirection::Horizontal)),
+    );
+    let top_line = |model: &token::model::AppModel, group_id: GroupId| {
+        let editor_id = model.editor_area.groups[&group_id]
+            .active_editor_id()
+            .unwrap();
+        model.editor_area.editors[&editor_id].viewport.top_line
+    };
+
+    // Lock with the other split 20 lines further down
+    update(&mut model, Msg::Layout(LayoutMsg::ScrollOtherGroup(20)));
+    update(&mut model, Msg::Layout(LayoutMsg::ToggleScrollLock));
+    assert!(model.editor_area.groups[&group1].scroll_lock.is_some());
+
+    update(&mut model, Msg::Editor(EditorMsg::Scroll(5)));
+    assert_eq!(model.editor().viewport.top_line, 5);
+    assert_eq!(top_line(&model, group1), 25);
+
+    // Scrolling the other split leads the same way
+    update(&mut model, Msg::Layout(LayoutMsg::ScrollOtherGroup(-10)));
+    assert_eq!(top_line(&model, group1), 15);
+    assert_eq!(model.editor().viewport.top_line, 0);
+
+    // Unlocking the focused split frees the lone partner too
+    update(&mut model, Msg::Layout(LayoutMsg::ToggleScrollLock));
+    assert!(model.editor_area.groups[&group1].scroll_lock.is_none());
+    update(&mut model, Msg::Editor(EditorMsg::Scroll(5)));
+    assert_eq!(top_line(&model, group1), 15);
+}
+
+#[test]
+fn test_scroll_lock_needs_the_document_in_another_split() {
+    let mut model = test_model("hello\nworld\n", 0, 0);
+    update(&mut model, Msg::Layout(LayoutMsg::ToggleScrollLock));
+    assert!(model
+        .editor_area
+        .focused_group()
+        .unwrap()
+        .scroll_lock
+        .is_none());
+}
+
+#[test]
+fn test_scroll_lines_pulls_cursor_only_beyond_a_page() {
+    let text = "line\n".repeat(100);
+    let mut model = test_model(&text, 0, 0);
+
+    update(&mut model, Msg::Editor(EditorMsg::ScrollLines(10)));
+    assert_eq!(model.editor().viewport.top_line, 10);
+    assert_eq!(model.editor().active_cursor().line, 0);
+
+    update(&mut model, Msg::Editor(EditorMsg::ScrollLines(20)));
+    assert_eq!(model.editor().viewport.top_line, 30);
+    assert_eq!(model.editor().active_cursor().line, 30);
+}
+
 #[test]
 fn test_focus_group_by_index() {
     let mut model = test_model("hello\nworld\n", 0, 0);
@@ -753,6 +844,272 @@ fn test_move_tab_to_invalid_group() {
     );
 }
 
+#[test]
+fn test_move_tab_to_new_split_on_left_edge() {
+    let mut model = test_model("hello\nworld\n", 0, 0);
+    let group1 = model.editor_area.focused_group_id;
+    update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+    let tab_id = model.editor_area.groups[&group1].tabs[1].id;
+
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::MoveTabToNewSplit {
+            tab_id,
+            group_id: group1,
+            edge: DropEdge::Left,
+        }),
+    );
+
+    let group2 = model.editor_area.focused_group_id;
+    assert_ne!(group2, group1);
+    assert_eq!(model.editor_area.groups[&group2].tabs[0].id, tab_id);
+    assert_eq!(model.editor_area.groups[&group1].tabs.len(), 1);
+    match &model.editor_area.layout {
+        LayoutNode::Split(container) => {
+            assert_eq!(container.direction, SplitDirection::Horizontal);
+            assert!(matches!(container.children[0], LayoutNode::Group(id) if id == group2));
+            assert!(matches!(container.children[1], LayoutNode::Group(id) if id == group1));
+        }
+        other => panic!("expected a split, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_move_tab_to_new_split_closes_emptied_group() {
+    let mut model = test_model("hello\nworld\n", 0, 0);
+    let group1 = model.editor_area.focused_group_id;
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::SplitFocused(SplitDirection::Horizontal)),
+    );
+    let group2 = model.editor_area.focused_group_id;
+    let tab_id = model.editor_area.groups[&group2].tabs[0].id;
+
+    // The only tab of group2 goes below group1, and group2 closes
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::MoveTabToNewSplit {
+            tab_id,
+            group_id: group1,
+            edge: DropEdge::Bottom,
+        }),
+    );
+
+    assert!(!model.editor_area.groups.contains_key(&group2));
+    assert_eq!(model.editor_area.groups.len(), 2);
+    let group3 = model.editor_area.focused_group_id;
+    assert_eq!(model.editor_area.groups[&group3].tabs[0].id, tab_id);
+}
+
+#[test]
+fn test_move_only_tab_to_new_split_of_its_own_group_is_noop() {
+    let mut model = test_model("hello\nworld\n", 0, 0);
+    let group1 = model.editor_area.focused_group_id;
+    let tab_id = model.editor_area.groups[&group1].tabs[0].id;
+
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::MoveTabToNewSplit {
+            tab_id,
+            group_id: group1,
+            edge: DropEdge::Right,
+        }),
+    );
+
+    assert_eq!(model.editor_area.groups.len(), 1);
+    assert_eq!(model.editor_area.groups[&group1].tabs[0].id, tab_id);
+}
+
+#[test]
+fn test_drop_edge_zones() {
+    let rect = Rect::new(0.0, 0.0, 400.0, 200.0);
+    assert_eq!(DropEdge::at(rect, 20.0, 100.0), Some(DropEdge::Left));
+    assert_eq!(DropEdge::at(rect, 390.0, 100.0), Some(DropEdge::Right));
+    assert_eq!(DropEdge::at(rect, 200.0, 10.0), Some(DropEdge::Top));
+    assert_eq!(DropEdge::at(rect, 200.0, 190.0), Some(DropEdge::Bottom));
+    // The middle and the outside don't split
+    assert_eq!(DropEdge::at(rect, 200.0, 100.0), None);
+    assert_eq!(DropEdge::at(rect, 500.0, 100.0), None);
+    // In a corner the nearer edge wins
+    assert_eq!(DropEdge::at(rect, 5.0, 40.0), Some(DropEdge::Left));
+}
+
+// ============================================================================
+// Tab Context Menu
+// ============================================================================
+
+/// A model with `count` tabs in one group: the file, then untitled tabs
+fn model_with_tabs(count: usize) -> token::model::AppModel {
+    let mut model = test_model("hello\nworld\n", 0, 0);
+    for _ in 1..count {
+        update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+    }
+    model
+}
+
+fn tab_ids(model: &token::model::AppModel) -> Vec<TabId> {
+    let group = model.editor_area.focused_group().unwrap();
+    group.tabs.iter().map(|tab| tab.id).collect()
+}
+
+#[test]
+fn test_tab_context_menu_acts_on_the_clicked_tab() {
+    let mut model = model_with_tabs(3);
+    let tabs = tab_ids(&model);
+
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::ShowTabContextMenu {
+            tab_id: tabs[0],
+            position: (40, 20),
+        }),
+    );
+    assert_eq!(
+        model.editor_area.focused_group().unwrap().active_tab_index,
+        0
+    );
+    let Some(ModalState::ContextMenu(menu)) = &model.ui.active_modal else {
+        panic!("Expected a context menu");
+    };
+    assert_eq!(menu.selected_command(), Some(CommandId::CloseTab));
+
+    // Down, down: past Close Others to Close to the Right
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::SelectNext)));
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::SelectNext)));
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+    assert!(model.ui.active_modal.is_none());
+    assert_eq!(tab_ids(&model), [tabs[0]]);
+}
+
+#[test]
+fn test_context_menu_selection_skips_separators() {
+    let mut menu = ContextMenuState::new((0, 0), token::menu::tab_context_menu(false));
+    for _ in 0..4 {
+        menu.step(true);
+    }
+    assert_eq!(menu.selected_command(), Some(CommandId::TogglePinTab));
+    menu.select(4); // a separator
+    assert_eq!(menu.selected_command(), Some(CommandId::TogglePinTab));
+    for _ in 0..10 {
+        menu.step(true);
+    }
+    assert_eq!(menu.selected_command(), Some(CommandId::RevealInFinder));
+}
+
+#[test]
+fn test_close_other_tabs_keeps_pinned_and_modified_tabs() {
+    let mut model = model_with_tabs(4);
+    let tabs = tab_ids(&model);
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(1)));
+    update(&mut model, Msg::Layout(LayoutMsg::TogglePinTab));
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(3)));
+    model.document_mut().is_modified = true;
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(2)));
+
+    update(&mut model, Msg::Layout(LayoutMsg::CloseOtherTabs));
+
+    // The modified tab stays open behind a prompt to save it
+    assert_eq!(tab_ids(&model), [tabs[1], tabs[2], tabs[3]]);
+    let group = model.editor_area.focused_group().unwrap();
+    assert_eq!(group.active_tab().map(|tab| tab.id), Some(tabs[2]));
+    assert!(matches!(
+        model.ui.active_modal,
+        Some(ModalState::UnsavedChanges(ref state)) if state.tab_id == tabs[3]
+    ));
+}
+
+#[test]
+fn test_close_tabs_to_the_right() {
+    let mut model = model_with_tabs(4);
+    let tabs = tab_ids(&model);
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(1)));
+
+    update(&mut model, Msg::Layout(LayoutMsg::CloseTabsToRight));
+
+    assert_eq!(tab_ids(&model), [tabs[0], tabs[1]]);
+    assert_eq!(
+        model.editor_area.focused_group().unwrap().active_tab_index,
+        1
+    );
+}
+
+#[test]
+fn test_pinned_tabs_stay_first_and_survive_close_all() {
+    let mut model = model_with_tabs(4);
+    let tabs = tab_ids(&model);
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(2)));
+    update(&mut model, Msg::Layout(LayoutMsg::TogglePinTab));
+    assert_eq!(tab_ids(&model), [tabs[2], tabs[0], tabs[1], tabs[3]]);
+    assert_eq!(
+        model.editor_area.focused_group().unwrap().active_tab_index,
+        0
+    );
+
+    // Unpinned tabs can't be dragged before pinned ones
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::ReorderTab {
+            tab_id: tabs[3],
+            to_index: 0,
+        }),
+    );
+    assert_eq!(tab_ids(&model), [tabs[2], tabs[3], tabs[0], tabs[1]]);
+
+    update(&mut model, Msg::Layout(LayoutMsg::CloseAllTabs));
+    assert_eq!(tab_ids(&model), [tabs[2]]);
+
+    // Unpinning moves the tab after the pinned ones
+    update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(1)));
+    update(&mut model, Msg::Layout(LayoutMsg::TogglePinTab));
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(0)));
+    update(&mut model, Msg::Layout(LayoutMsg::TogglePinTab));
+    let group = model.editor_area.focused_group().unwrap();
+    assert_eq!(group.pinned_count(), 1);
+    assert_eq!(group.tabs[1].id, tabs[2]);
+}
+
+#[test]
+fn test_preview_tab_is_replaced_until_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let [a, b, c] = ["a.txt", "b.txt", "c.txt"].map(|name| {
+        let path = dir.path().join(name);
+        std::fs::write(&path, name).unwrap();
+        path
+    });
+    let mut model = test_model("hello\n", 0, 0);
+    update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(0)));
+
+    update(&mut model, Msg::Layout(LayoutMsg::OpenFileInPreviewTab(a)));
+    assert_eq!(tab_ids(&model).len(), 3);
+    let preview = model
+        .editor_area
+        .focused_group()
+        .unwrap()
+        .active_tab()
+        .unwrap();
+    assert!(preview.is_preview);
+
+    // The next preview takes its place; opening it for good keeps it
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::OpenFileInPreviewTab(b.clone())),
+    );
+    assert_eq!(tab_ids(&model).len(), 3);
+    assert_eq!(model.document().file_path.as_deref(), Some(b.as_path()));
+    update(&mut model, Msg::Layout(LayoutMsg::OpenFileInNewTab(b)));
+    let group = model.editor_area.focused_group().unwrap();
+    assert!(group.tabs.iter().all(|tab| !tab.is_preview));
+
+    // Editing a preview keeps it too
+    update(&mut model, Msg::Layout(LayoutMsg::OpenFileInPreviewTab(c)));
+    assert_eq!(tab_ids(&model).len(), 4);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+    let group = model.editor_area.focused_group().unwrap();
+    assert!(group.tabs.iter().all(|tab| !tab.is_preview));
+}
+
 // ============================================================================
 // Close Tab Operations
 // ============================================================================
@@ -1616,13 +1973,13 @@ fn test_cursor_sync_indent_lines() {
 
     update(&mut model, Msg::Layout(LayoutMsg::FocusGroup(group1)));
     set_cursor_at(&mut model, 0, 0);
-    update(&mut model, Msg::Document(DocumentMsg::IndentLines));
+    update(&mut model, Msg::Document(DocumentMsg::IndentSelection));
 
     update(&mut model, Msg::Layout(LayoutMsg::FocusGroup(group2)));
     assert_eq!(
         model.editor().primary_cursor().column,
         4,
-        "IndentLines on a peer group must shift this cursor's column right by 1"
+        "IndentSelection on a peer group must shift this cursor's column right by 1"
     );
 }
 
@@ -1643,13 +2000,13 @@ fn test_cursor_sync_unindent_lines() {
 
     update(&mut model, Msg::Layout(LayoutMsg::FocusGroup(group1)));
     set_cursor_at(&mut model, 0, 0);
-    update(&mut model, Msg::Document(DocumentMsg::UnindentLines));
+    update(&mut model, Msg::Document(DocumentMsg::DedentSelection));
 
     update(&mut model, Msg::Layout(LayoutMsg::FocusGroup(group2)));
     assert_eq!(
         model.editor().primary_cursor().column,
         2,
-        "UnindentLines on a peer group must shift this cursor's column left by the removed amount"
+        "DedentSelection on a peer group must shift this cursor's column left by the removed amount"
     );
 }
 
@@ -1937,3 +2294,202 @@ fn test_switch_to_tab_scrolls_focused_tab_into_view() {
         "active tab must be present in the visible tab layout"
     );
 }
+
+// ========================================================================
+// Duplicate Open Detection Tests
+// ========================================================================
+
+#[test]
+fn test_open_file_alias_focuses_existing_document() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    let path = dir.path().join("src").join("lib.rs");
+    std::fs::write(&path, "fn main() {}\n").unwrap();
+
+    let mut model = test_model("", 0, 0);
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::OpenFileInNewTab(path.clone())),
+    );
+    let doc_count = model.editor_area.documents.len();
+    let doc_id = model.editor_area.focused_document_id();
+
+    // Switch away, then open the same file through a `..` alias
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(0)));
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::OpenFileInNewTab(
+            dir.path().join("src/../src/lib.rs"),
+        )),
+    );
+
+    assert_eq!(model.editor_area.documents.len(), doc_count);
+    assert_eq!(model.editor_area.focused_document_id(), doc_id);
+    assert_eq!(model.editor_area.focused_group().unwrap().tabs.len(), 2);
+}
+
+#[test]
+fn test_open_file_from_other_group_shares_document() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "hello\n").unwrap();
+
+    let mut model = test_model("", 0, 0);
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::OpenFileInNewTab(path.clone())),
+    );
+    let doc_id = model.editor_area.focused_document_id().unwrap();
+    let first_group = model.editor_area.focused_group_id;
+
+    // A new split shows the file too; move it to an untitled tab first
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::SplitFocused(SplitDirection::Horizontal)),
+    );
+    update(&mut model, Msg::Layout(LayoutMsg::NewTab));
+    let second_group = model.editor_area.focused_group_id;
+    let split_view_tab = model.editor_area.groups[&second_group].tabs[0].id;
+    update(&mut model, Msg::Layout(LayoutMsg::CloseTab(split_view_tab)));
+
+    update(&mut model, Msg::Layout(LayoutMsg::OpenFileInNewTab(path)));
+
+    // Opened as another view in the focused group, backed by the same document
+    assert_eq!(model.editor_area.focused_group_id, second_group);
+    assert_ne!(second_group, first_group);
+    assert_eq!(model.editor_area.focused_document_id(), Some(doc_id));
+    assert_eq!(model.editor_area.editors_for_document(doc_id).len(), 2);
+
+    update(
+        &mut model,
+        Msg::Document(token::messages::DocumentMsg::InsertChar('x')),
+    );
+    assert_eq!(
+        model.editor_area.documents[&doc_id].buffer.to_string(),
+        "xhello\n"
+    );
+}
+
+// ============================================================================
+// Tab Color Labels
+// ============================================================================
+
+#[test]
+fn test_tab_colors_set_clear_and_follow_splits() {
+    use token::model::TabColor;
+
+    let mut model = test_model("hello\n", 0, 0);
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::SetTabColor(Some(TabColor::Green))),
+    );
+    let active = |model: &token::model::AppModel| {
+        model
+            .editor_area
+            .focused_group()
+            .unwrap()
+            .active_tab()
+            .unwrap()
+            .color
+    };
+    assert_eq!(active(&model), Some(TabColor::Green));
+
+    // The split's tab shows the same file, so it keeps the label
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::SplitFocused(SplitDirection::Horizontal)),
+    );
+    assert_eq!(active(&model), Some(TabColor::Green));
+
+    update(&mut model, Msg::Layout(LayoutMsg::SetTabColor(None)));
+    assert_eq!(active(&model), None);
+}
+
+#[test]
+fn test_group_tabs_by_color_keeps_active_tab() {
+    use token::model::TabColor;
+
+    let mut model = test_model("hello\n", 0, 0);
+    let ids = open_tabs(&mut model, 3); // tabs [0,1,2,3], active = 3
+    let colors = [None, Some(TabColor::Blue), None, Some(TabColor::Red)];
+    let group = model.editor_area.focused_group_mut().unwrap();
+    for (tab, color) in group.tabs.iter_mut().zip(colors) {
+        tab.color = color;
+    }
+
+    update(&mut model, Msg::Layout(LayoutMsg::GroupTabsByColor));
+
+    let group = model.editor_area.focused_group().unwrap();
+    let order: Vec<_> = group.tabs.iter().map(|t| t.id).collect();
+    // Labeled tabs first in label order, then the rest in their old order
+    assert_eq!(order, vec![ids[3], ids[1], ids[0], ids[2]]);
+    assert_eq!(group.active_tab().unwrap().id, ids[3]);
+}
+
+#[test]
+fn test_tab_colors_persist_with_session() {
+    use token::model::{AppModel, TabColor};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "hi\n").unwrap();
+
+    let mut model = AppModel::new(800, 600, 1.0, vec![path.clone()]);
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::SetTabColor(Some(TabColor::Purple))),
+    );
+    update(&mut model, Msg::Layout(LayoutMsg::NewTab)); // untitled: not saved
+    let session = model.session();
+    assert_eq!(session.tab_colors.len(), 1);
+    assert_eq!(session.tab_colors.get(&path), Some(&TabColor::Purple));
+
+    let mut restored = AppModel::new(800, 600, 1.0, vec![path]);
+    restored.restore_session(&session);
+    let tab = &restored.editor_area.focused_group().unwrap().tabs[0];
+    assert_eq!(tab.color, Some(TabColor::Purple));
+}
+
+#[test]
+fn test_tabs_and_splits_restore_from_editor_session() {
+    use token::model::AppModel;
+    use token::session::EditorSession;
+
+    let dir = tempfile::tempdir().unwrap();
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+    std::fs::write(&a, "zero\none\ntwo\nthree\n").unwrap();
+    std::fs::write(&b, "b\n").unwrap();
+
+    let mut model = AppModel::new(800, 600, 1.0, vec![a.clone(), b.clone()]);
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(1)));
+    update(&mut model, Msg::Layout(LayoutMsg::SwitchToTab(0)));
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::SplitFocused(SplitDirection::Vertical)),
+    );
+    set_cursor_at(&mut model, 2, 3);
+    update(&mut model, Msg::Layout(LayoutMsg::NewTab)); // untitled: not saved
+
+    let json = serde_json::to_string(&model.editor_area.session()).unwrap();
+    let session: EditorSession = serde_json::from_str(&json).unwrap();
+    std::fs::remove_file(&b).unwrap();
+
+    let mut restored = AppModel::new(800, 600, 1.0, vec![]);
+    assert!(restored.restore_editor_session(&session));
+    let area = &restored.editor_area;
+    match &area.layout {
+        LayoutNode::Split(container) => {
+            assert_eq!(container.direction, SplitDirection::Vertical);
+            assert_eq!(container.children.len(), 2);
+        }
+        _ => panic!("Expected Split"),
+    }
+    // Both groups show a.txt from one shared document; b.txt is gone
+    assert_eq!(area.documents.len(), 1);
+    assert_eq!(area.groups.len(), 2);
+    assert!(area.groups.values().all(|group| group.tabs.len() == 1));
+    assert_eq!(restored.document().file_path.as_deref(), Some(a.as_path()));
+    assert_eq!(restored.editor().cursors[0].line, 2);
+    assert_eq!(restored.editor().cursors[0].column, 3);
+}
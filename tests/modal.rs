@@ -4,12 +4,16 @@
 
 mod common;
 
-use common::test_model;
+use common::{test_model, test_model_with_selection};
 
+use token::commands::Cmd;
 use token::messages::{ModalMsg, Msg, UiMsg};
 use token::model::{
-    CommandPaletteState, FindReplaceState, GotoLineState, ModalId, ModalState, ThemePickerState,
+    CommandPaletteState, Document, FindReplaceState, GotoLineState, ModalId, ModalState,
+    ThemePickerState,
 };
+use token::outline::{OutlineData, OutlineKind, OutlineNode, OutlineRange};
+use token::syntax::LanguageId;
 use token::update::update;
 
 // Helper to create a CommandPaletteState with initial text
@@ -241,6 +245,38 @@ fn test_command_palette_input_resets_selection() {
     }
 }
 
+#[test]
+#[cfg(unix)]
+fn test_command_palette_runs_external_tools() {
+    let mut model = test_model("hello\nworld\n", 1, 2);
+    model.document_mut().file_path = Some("/project/src/main.rs".into());
+    model
+        .config
+        .external_tools
+        .push(token::external_tools::ExternalTool {
+            name: "Open in Zed".to_string(),
+            command: "zed $FILE:$LINE".to_string(),
+        });
+    model
+        .ui
+        .open_modal(ModalState::CommandPalette(command_palette_with_input(
+            "open in zed",
+            0,
+        )));
+
+    let cmd = update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+    let Some(Cmd::Batch(cmds)) = cmd else {
+        panic!("Expected a batch, got {cmd:?}");
+    };
+    let Some(Cmd::RunExternalTool { name, command, cwd }) = cmds.into_iter().next() else {
+        panic!("Expected the tool to run");
+    };
+    assert_eq!(name, "Open in Zed");
+    assert_eq!(command, "zed '/project/src/main.rs':2");
+    assert_eq!(cwd.as_deref(), Some(std::path::Path::new("/project/src")));
+    assert!(model.ui.active_modal.is_none());
+}
+
 // ========================================================================
 // Goto Line Input Tests
 // ========================================================================
@@ -361,9 +397,7 @@ fn test_goto_line_empty_input_goes_to_line_1() {
 #[test]
 fn test_find_replace_insert_char() {
     let mut model = test_model("hello\n", 0, 0);
-    model
-        .ui
-        .open_modal(ModalState::FindReplace(FindReplaceState::default()));
+    model.ui.open_modal(ModalState::FindReplace(Box::default()));
 
     update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::InsertChar('h'))));
     update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::InsertChar('i'))));
@@ -380,7 +414,9 @@ fn test_find_replace_delete_backward() {
     let mut model = test_model("hello\n", 0, 0);
     model
         .ui
-        .open_modal(ModalState::FindReplace(find_replace_with_query("search")));
+        .open_modal(ModalState::FindReplace(Box::new(find_replace_with_query(
+            "search",
+        ))));
 
     update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::DeleteBackward)));
 
@@ -391,6 +427,214 @@ fn test_find_replace_delete_backward() {
     }
 }
 
+#[test]
+fn test_find_in_selection_limits_matches_to_selection() {
+    // Lines 1-2 selected; "x" also appears on lines 0 and 3
+    let mut model = test_model_with_selection("x\na x\nb x\nx\n", 1, 0, 2, 3);
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::ToggleModal(ModalId::FindReplace)),
+    );
+    let Some(ModalState::FindReplace(state)) = &model.ui.active_modal else {
+        panic!("Expected find/replace modal");
+    };
+    assert!(state.in_selection, "multi-line selection scopes the search");
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::InsertChar('x'))));
+    let cursor = model.editor().primary_cursor();
+    assert_eq!(
+        (cursor.line, cursor.column),
+        (1, 3),
+        "typing selects a match"
+    );
+
+    let mut found = Vec::new();
+    for _ in 0..3 {
+        update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::FindNext)));
+        let cursor = model.editor().primary_cursor();
+        found.push((cursor.line, cursor.column));
+    }
+    assert_eq!(found, vec![(2, 3), (1, 3), (2, 3)], "wraps within scope");
+
+    // Alt+L turns it off: the whole document is searched again
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::Modal(ModalMsg::ToggleFindInSelection)),
+    );
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::FindNext)));
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::FindNext)));
+    assert_eq!(model.editor().primary_cursor().line, 3);
+}
+
+#[test]
+fn test_find_counts_matches_in_open_documents() {
+    let mut model = test_model("let value = 1;\nvalue\n", 0, 0);
+    let id = model.editor_area.next_document_id();
+    let mut other = Document::with_text("Value\nno\nvalues and value\n");
+    other.id = Some(id);
+    other.file_path = Some("src/other.rs".into());
+    model.editor_area.documents.insert(id, other);
+
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::ToggleModal(ModalId::FindReplace)),
+    );
+    let counts = |model: &token::model::AppModel| match &model.ui.active_modal {
+        Some(ModalState::FindReplace(state)) => state.match_counts.clone(),
+        _ => panic!("Expected find/replace modal"),
+    };
+
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::Modal(ModalMsg::SetInput("value".to_string()))),
+    );
+    let found = counts(&model);
+    assert_eq!(found.current, 2);
+    assert_eq!(found.other_files, vec![("other.rs".to_string(), 3)]);
+
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::Modal(ModalMsg::ToggleFindReplaceCaseSensitive)),
+    );
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::InsertChar('s'))));
+    let found = counts(&model);
+    assert_eq!(found.current, 0);
+    assert_eq!(found.other_files, vec![("other.rs".to_string(), 1)]);
+}
+
+#[test]
+fn test_find_selects_nearest_match_while_typing() {
+    let mut model = test_model("one fox\ntwo fog\nthree fox\n", 1, 0);
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::ToggleModal(ModalId::FindReplace)),
+    );
+    let selected = |model: &token::model::AppModel| {
+        let selection = model.editor().primary_selection();
+        (
+            (selection.start().line, selection.start().column),
+            (selection.end().line, selection.end().column),
+        )
+    };
+    let current = |model: &token::model::AppModel| match &model.ui.active_modal {
+        Some(ModalState::FindReplace(state)) => (state.current_match, state.matches.len()),
+        _ => panic!("Expected find/replace modal"),
+    };
+
+    // The first match from the cursor, not from the top
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::InsertChar('f'))));
+    assert_eq!(selected(&model), ((1, 4), (1, 5)));
+    assert_eq!(current(&model), (Some(1), 3));
+    assert_eq!(model.ui.search_highlight.as_ref().unwrap().query, "f");
+
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::InsertChar('o'))));
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::InsertChar('x'))));
+    assert_eq!(selected(&model), ((2, 6), (2, 9)));
+    assert_eq!(current(&model), (Some(1), 2));
+
+    // Next / previous move the current match
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::FindNext)));
+    assert_eq!(selected(&model), ((0, 4), (0, 7)));
+    assert_eq!(current(&model), (Some(0), 2));
+
+    // No matches: back to where the search started
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::InsertChar('!'))));
+    assert_eq!(selected(&model), ((1, 0), (1, 0)));
+    assert_eq!(current(&model), (None, 0));
+
+    // Clearing the query clears the highlight
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::Modal(ModalMsg::SetInput(String::new()))),
+    );
+    assert!(model.ui.search_highlight.is_none());
+}
+
+#[test]
+fn test_find_query_is_kept_after_closing() {
+    let mut model = test_model("a b a\n", 0, 0);
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::ToggleModal(ModalId::FindReplace)),
+    );
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::InsertChar('a'))));
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Close)));
+
+    assert_eq!(model.ui.last_find_replace.as_ref().unwrap().query(), "a");
+    update(
+        &mut model,
+        Msg::Editor(token::messages::EditorMsg::FindNextMatch),
+    );
+    assert_eq!(model.editor().primary_selection().start().column, 4);
+}
+
+#[test]
+fn test_find_regex_replaces_with_capture_groups() {
+    let mut model = test_model("key=1 other=22\n", 0, 14);
+    let mut state = find_replace_with_query(r"(\w+)=(\d+)");
+    state.regex = true;
+    state.set_replacement("$2:$1");
+    model
+        .ui
+        .open_modal(ModalState::FindReplace(Box::new(state)));
+
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::FindNext)));
+    let selection = model.editor().primary_selection();
+    assert_eq!((selection.start().column, selection.end().column), (0, 5));
+
+    // Replace the selected match, then move to the next
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::Modal(ModalMsg::ReplaceAndFindNext)),
+    );
+    assert_eq!(model.document().buffer.to_string(), "1:key other=22\n");
+    let selection = model.editor().primary_selection();
+    assert_eq!((selection.start().column, selection.end().column), (6, 14));
+
+    // Replace All goes through the review, and applies as one undo step
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::ReplaceAll)));
+    assert!(matches!(
+        model.ui.active_modal,
+        Some(ModalState::EditReview(_))
+    ));
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+    assert_eq!(model.document().buffer.to_string(), "1:key 22:other\n");
+    update(
+        &mut model,
+        Msg::Document(token::messages::DocumentMsg::Undo),
+    );
+    assert_eq!(model.document().buffer.to_string(), "1:key other=22\n");
+}
+
+#[test]
+fn test_find_toggling_regex_searches_again() {
+    let mut model = test_model("a.c abc\n", 0, 0);
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::ToggleModal(ModalId::FindReplace)),
+    );
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::Modal(ModalMsg::SetInput("a.c".to_string()))),
+    );
+    let match_count = |model: &token::model::AppModel| match &model.ui.active_modal {
+        Some(ModalState::FindReplace(state)) => state.matches.len(),
+        _ => panic!("Expected find/replace modal"),
+    };
+    assert_eq!(match_count(&model), 1);
+
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::ToggleFindRegex)));
+    assert_eq!(match_count(&model), 2);
+
+    // An invalid pattern matches nothing, and Enter doesn't close the modal
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::Modal(ModalMsg::SetInput("a(".to_string()))),
+    );
+    assert_eq!(match_count(&model), 0);
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+    assert!(model.ui.active_modal.is_some());
+}
+
 // ========================================================================
 // Theme Picker Tests
 // ========================================================================
@@ -452,6 +696,153 @@ fn test_theme_picker_select_next_respects_bounds() {
     }
 }
 
+// ========================================================================
+// Language Picker Tests
+// ========================================================================
+
+#[test]
+fn test_language_picker_preselects_current_language() {
+    let mut model = test_model("fn main() {}\n", 0, 0);
+    model.document_mut().language = LanguageId::Rust;
+
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::ToggleModal(ModalId::LanguagePicker)),
+    );
+
+    let Some(ModalState::LanguagePicker(state)) = &model.ui.active_modal else {
+        panic!("Expected language picker modal");
+    };
+    assert_eq!(state.current, LanguageId::Rust);
+    assert_eq!(
+        state.filtered_languages()[state.selected_index],
+        LanguageId::Rust
+    );
+}
+
+#[test]
+fn test_language_picker_overrides_document_language() {
+    let mut model = test_model("{\"a\": 1}\n", 0, 0);
+
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::ToggleModal(ModalId::LanguagePicker)),
+    );
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::Modal(ModalMsg::SetInput("json".to_string()))),
+    );
+    let cmd = update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+
+    assert!(model.ui.active_modal.is_none());
+    assert_eq!(model.document().language, LanguageId::Json);
+    assert!(model.document().language_overridden);
+    assert_eq!(model.ui.status_message, "Language: JSON");
+    // The document is re-parsed with the new grammar right away
+    let Some(Cmd::Batch(cmds)) = cmd else {
+        panic!("Expected a batch of commands");
+    };
+    assert!(cmds
+        .iter()
+        .any(|cmd| matches!(cmd, Cmd::DebouncedSyntaxParse { delay_ms: 0, .. })));
+}
+
+// ========================================================================
+// Go to Symbol Tests
+// ========================================================================
+
+fn symbol(kind: OutlineKind, name: &str, lines: (usize, usize)) -> OutlineNode {
+    OutlineNode {
+        kind,
+        name: name.to_string(),
+        range: OutlineRange {
+            start_line: lines.0,
+            start_col: 4,
+            end_line: lines.1,
+            end_col: 0,
+        },
+        children: Vec::new(),
+    }
+}
+
+/// `impl Renderer` with `render_text_area` and `render_gutter`, then `fn main`
+fn model_with_symbols() -> token::model::AppModel {
+    let mut model = test_model(&"x\n".repeat(20), 0, 0);
+    let mut renderer = symbol(OutlineKind::Impl, "Renderer", (0, 10));
+    renderer.children = vec![
+        symbol(OutlineKind::Method, "render_text_area", (1, 4)),
+        symbol(OutlineKind::Method, "render_gutter", (6, 9)),
+    ];
+    model.document_mut().outline = Some(OutlineData {
+        revision: 0,
+        roots: vec![renderer, symbol(OutlineKind::Function, "main", (12, 15))],
+    });
+    model
+}
+
+#[test]
+fn test_goto_symbol_lists_symbols_in_document_order() {
+    let mut model = model_with_symbols();
+
+    update(&mut model, Msg::Ui(UiMsg::ToggleModal(ModalId::GotoSymbol)));
+
+    let Some(ModalState::GotoSymbol(state)) = &model.ui.active_modal else {
+        panic!("Expected go to symbol modal");
+    };
+    let labels: Vec<_> = state
+        .filtered_entries()
+        .iter()
+        .map(|entry| entry.label.as_str())
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            "impl Renderer",
+            "fn render_text_area",
+            "fn render_gutter",
+            "fn main"
+        ]
+    );
+    assert_eq!(state.entries[1].container.as_deref(), Some("Renderer"));
+    assert_eq!(state.entries[0].container, None);
+}
+
+#[test]
+fn test_goto_symbol_fuzzy_filters_and_jumps() {
+    let mut model = model_with_symbols();
+
+    update(&mut model, Msg::Ui(UiMsg::ToggleModal(ModalId::GotoSymbol)));
+    update(
+        &mut model,
+        Msg::Ui(UiMsg::Modal(ModalMsg::SetInput("rgut".to_string()))),
+    );
+    let Some(ModalState::GotoSymbol(state)) = &model.ui.active_modal else {
+        panic!("Expected go to symbol modal");
+    };
+    let names: Vec<_> = state
+        .filtered_entries()
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect();
+    assert_eq!(names, ["render_gutter"]);
+
+    update(&mut model, Msg::Ui(UiMsg::Modal(ModalMsg::Confirm)));
+
+    assert!(model.ui.active_modal.is_none());
+    assert_eq!(model.editor().active_cursor().line, 6);
+    assert_eq!(model.editor().active_cursor().column, 4);
+}
+
+#[test]
+fn test_goto_symbol_without_outline_shows_status() {
+    let mut model = test_model("plain text\n", 0, 0);
+
+    update(&mut model, Msg::Ui(UiMsg::ToggleModal(ModalId::GotoSymbol)));
+
+    assert!(model.ui.active_modal.is_none());
+    assert_eq!(model.ui.status_message, "No symbols in this document");
+}
+
 // ========================================================================
 // Modal Input Without Active Modal
 // ========================================================================
@@ -528,9 +919,7 @@ fn test_set_input_goto_line() {
 #[test]
 fn test_set_input_find_replace() {
     let mut model = test_model("hello\n", 0, 0);
-    model
-        .ui
-        .open_modal(ModalState::FindReplace(FindReplaceState::default()));
+    model.ui.open_modal(ModalState::FindReplace(Box::default()));
 
     update(
         &mut model,
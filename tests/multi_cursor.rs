@@ -8,7 +8,7 @@
 
 mod common;
 
-use common::test_model;
+use common::{buffer_to_string, test_model};
 use token::messages::{DocumentMsg, EditorMsg, Msg};
 use token::model::{Cursor, Position, Selection};
 use token::update::update;
@@ -486,7 +486,7 @@ fn test_indent_multi_cursor_indents_all_lines() {
 
     assert_eq!(model.editor().cursor_count(), 3);
 
-    update(&mut model, Msg::Document(DocumentMsg::IndentLines));
+    update(&mut model, Msg::Document(DocumentMsg::IndentSelection));
 
     let content: String = model.document().buffer.chars().collect();
     assert_eq!(
@@ -511,7 +511,7 @@ fn test_indent_multi_cursor_non_adjacent() {
 
     assert_eq!(model.editor().cursor_count(), 2);
 
-    update(&mut model, Msg::Document(DocumentMsg::IndentLines));
+    update(&mut model, Msg::Document(DocumentMsg::IndentSelection));
 
     let content: String = model.document().buffer.chars().collect();
     assert_eq!(
@@ -532,7 +532,7 @@ fn test_unindent_multi_cursor_unindents_all_lines() {
 
     assert_eq!(model.editor().cursor_count(), 3);
 
-    update(&mut model, Msg::Document(DocumentMsg::UnindentLines));
+    update(&mut model, Msg::Document(DocumentMsg::DedentSelection));
 
     let content: String = model.document().buffer.chars().collect();
     assert_eq!(
@@ -557,7 +557,7 @@ fn test_unindent_multi_cursor_non_adjacent() {
 
     assert_eq!(model.editor().cursor_count(), 2);
 
-    update(&mut model, Msg::Document(DocumentMsg::UnindentLines));
+    update(&mut model, Msg::Document(DocumentMsg::DedentSelection));
 
     let content: String = model.document().buffer.chars().collect();
     assert_eq!(
@@ -576,7 +576,7 @@ fn test_unindent_spaces_multi_cursor() {
 
     assert_eq!(model.editor().cursor_count(), 2);
 
-    update(&mut model, Msg::Document(DocumentMsg::UnindentLines));
+    update(&mut model, Msg::Document(DocumentMsg::DedentSelection));
 
     let content: String = model.document().buffer.chars().collect();
     assert_eq!(
@@ -585,6 +585,63 @@ fn test_unindent_spaces_multi_cursor() {
     );
 }
 
+#[test]
+fn test_indent_selection_with_spaces_is_one_undo_step() {
+    use token::config::IndentStyle;
+    use token::messages::{DocumentMsg, Msg};
+
+    let mut model = test_model("line 0\nline 1\nline 2\n", 0, 2);
+    model.config.indent_style = IndentStyle::Spaces;
+    model.config.indent_width = 2;
+    model.editor_mut().add_cursor_at(2, 0);
+
+    update(&mut model, Msg::Document(DocumentMsg::IndentSelection));
+
+    let content: String = model.document().buffer.chars().collect();
+    assert_eq!(content, "  line 0\nline 1\n  line 2\n");
+    let columns: Vec<usize> = model.editor().cursors.iter().map(|c| c.column).collect();
+    assert_eq!(columns, vec![4, 2]);
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    let content: String = model.document().buffer.chars().collect();
+    assert_eq!(content, "line 0\nline 1\nline 2\n");
+}
+
+#[test]
+fn test_dedent_selection_removes_one_level_of_spaces() {
+    use token::messages::{DocumentMsg, Msg};
+
+    let mut model = test_model("      line 0\n ine 1\n", 0, 6);
+    model.config.indent_width = 2;
+    model.editor_mut().add_cursor_at(1, 1);
+
+    update(&mut model, Msg::Document(DocumentMsg::DedentSelection));
+
+    let content: String = model.document().buffer.chars().collect();
+    assert_eq!(content, "    line 0\nine 1\n");
+}
+
+#[test]
+fn test_move_lines_up_with_cursors_on_different_lines() {
+    use token::messages::{DocumentMsg, Msg};
+
+    let mut model = test_model("0\n1\n2\n3\n4\n", 1, 0);
+    model.editor_mut().add_cursor_at(3, 0);
+    model.editor_mut().add_cursor_at(4, 0);
+
+    update(&mut model, Msg::Document(DocumentMsg::MoveLinesUp));
+
+    let content: String = model.document().buffer.chars().collect();
+    assert_eq!(content, "1\n0\n3\n4\n2\n");
+    let mut lines: Vec<usize> = model.editor().cursors.iter().map(|c| c.line).collect();
+    lines.sort_unstable();
+    assert_eq!(lines, vec![0, 2, 3]);
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    let content: String = model.document().buffer.chars().collect();
+    assert_eq!(content, "0\n1\n2\n3\n4\n");
+}
+
 // ========================================================================
 // Multi-Cursor Duplicate Tests
 // ========================================================================
@@ -1220,3 +1277,57 @@ fn test_auto_surround_with_overlapping_occurrences_does_not_corrupt_buffer() {
     // the whole overlapping span is wrapped exactly once.
     assert_eq!(text, "(aaaa)");
 }
+
+fn model_with_selections(text: &str, ranges: &[(usize, usize)]) -> token::model::AppModel {
+    let mut model = test_model(text, 0, 0);
+    let editor = model.editor_mut();
+    editor.cursors = ranges.iter().map(|&(_, end)| Cursor::at(0, end)).collect();
+    editor.selections = ranges
+        .iter()
+        .map(|&(start, end)| {
+            Selection::from_anchor_head(Position::new(0, start), Position::new(0, end))
+        })
+        .collect();
+    model
+}
+
+#[test]
+fn test_auto_surround_wraps_adjacent_selections_individually() {
+    // "cd" and " ef" touch at column 5
+    let mut model = model_with_selections("ab cd ef", &[(0, 2), (3, 5), (5, 8)]);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('"')));
+
+    assert_eq!(buffer_to_string(&model), "\"ab\" \"cd\"\" ef\"");
+    let columns: Vec<usize> = model.editor().cursors.iter().map(|c| c.column).collect();
+    assert_eq!(
+        columns,
+        vec![4, 9, 14],
+        "each cursor follows its own closing quote"
+    );
+
+    // One undo step restores the text and all cursors
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "ab cd ef");
+    assert_eq!(model.editor().cursor_count(), 3);
+}
+
+#[test]
+fn test_auto_surround_nested_selection_wraps_outer_once() {
+    let mut model = model_with_selections("één twee", &[(0, 8), (4, 8)]);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('[')));
+
+    assert_eq!(buffer_to_string(&model), "[één twee]");
+    assert_eq!(model.editor().cursor_count(), 1);
+    assert_eq!(model.editor().primary_cursor().column, 10);
+}
+
+#[test]
+fn test_typing_with_cursors_on_same_line_keeps_columns() {
+    let mut model = test_model("ab cd", 0, 1);
+    model.editor_mut().add_cursor_at(0, 4);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+
+    assert_eq!(buffer_to_string(&model), "axb cxd");
+    assert_eq!(model.editor().cursors[0].column, 2);
+    assert_eq!(model.editor().cursors[1].column, 6);
+}
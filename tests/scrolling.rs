@@ -1131,3 +1131,77 @@ fn test_new_editor_gets_correct_viewport_after_open() {
     );
     assert_eq!(model.editor().viewport.visible_lines, 40);
 }
+
+// ========================================================================
+// Viewport-only operations leave cursor state alone
+// ========================================================================
+
+#[test]
+fn test_viewport_only_messages_preserve_cursor_state_in_split_views() {
+    use token::messages::{AppMsg, LayoutMsg};
+    use token::model::{Cursor, Position, Selection, SplitDirection};
+
+    let text = (0..80)
+        .map(|i| format!("{}{}", "x".repeat(i % 30), i))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut model = test_model(&text, 0, 0);
+    update(
+        &mut model,
+        Msg::Layout(LayoutMsg::SplitFocused(SplitDirection::Vertical)),
+    );
+
+    // Two cursors per editor, both with backward selections and a
+    // remembered column, the second one active
+    for editor in model.editor_area.editors.values_mut() {
+        editor.cursors = vec![
+            Cursor {
+                line: 10,
+                column: 2,
+                desired_column: Some(20),
+            },
+            Cursor {
+                line: 40,
+                column: 3,
+                desired_column: Some(25),
+            },
+        ];
+        editor.selections = vec![
+            Selection {
+                anchor: Position::new(12, 5),
+                head: Position::new(10, 2),
+            },
+            Selection {
+                anchor: Position::new(41, 0),
+                head: Position::new(40, 3),
+            },
+        ];
+        editor.active_cursor_index = 1;
+    }
+    let before: Vec<_> = model
+        .editor_area
+        .editors
+        .iter()
+        .map(|(id, e)| (*id, e.cursors.clone(), e.selections.clone()))
+        .collect();
+
+    for msg in [
+        Msg::Editor(EditorMsg::Scroll(30)),
+        Msg::Editor(EditorMsg::ScrollHorizontal(10)),
+        Msg::Layout(LayoutMsg::ScrollOtherGroup(15)),
+        Msg::App(AppMsg::Resize(640, 300)),
+        Msg::App(AppMsg::ScaleFactorChanged(2.0)),
+        Msg::Editor(EditorMsg::Scroll(-50)),
+        Msg::App(AppMsg::Resize(1200, 900)),
+    ] {
+        update(&mut model, msg);
+    }
+
+    for (id, cursors, selections) in before {
+        let editor = &model.editor_area.editors[&id];
+        assert_eq!(editor.cursors, cursors);
+        assert_eq!(editor.selections, selections);
+        assert_eq!(editor.active_cursor_index, 1);
+        assert!(editor.selections[1].is_reversed());
+    }
+}
@@ -5,7 +5,7 @@
 mod common;
 
 use common::{test_model, test_model_with_selection};
-use token::messages::{Direction, EditorMsg, Msg};
+use token::messages::{Direction, DocumentMsg, EditorMsg, Msg};
 use token::model::{Cursor, Position, Selection};
 use token::update::update;
 
@@ -127,6 +127,59 @@ fn test_rectangle_selection_left_to_right_cursor_placement() {
     }
 }
 
+#[test]
+fn test_keyboard_column_selection_grows_from_cursor() {
+    let mut model = test_model("hello world\nfoo\ntest line 3\n", 0, 2);
+    let extend = |model: &mut _, direction| {
+        update(
+            model,
+            Msg::Editor(EditorMsg::ExtendRectangleSelection(direction)),
+        );
+    };
+
+    extend(&mut model, Direction::Down);
+    extend(&mut model, Direction::Down);
+    extend(&mut model, Direction::Right);
+    extend(&mut model, Direction::Right);
+    let state = &model.editor().rectangle_selection;
+    assert!(state.active && state.from_keyboard);
+    assert_eq!((state.top_line(), state.bottom_line()), (0, 2));
+    assert_eq!((state.left_visual_col(), state.right_visual_col()), (2, 4));
+    // "foo" is shorter than the rectangle: its preview cursor is clamped
+    assert_eq!(
+        state.preview_cursors,
+        [
+            Position::new(0, 4),
+            Position::new(1, 3),
+            Position::new(2, 4)
+        ]
+    );
+
+    // Releasing Shift+Alt commits the same cursors a mouse drag would
+    update(&mut model, Msg::Editor(EditorMsg::FinishRectangleSelection));
+    assert!(!model.editor().rectangle_selection.from_keyboard);
+    assert_eq!(model.editor().cursors.len(), 3);
+    assert_eq!(model.editor().selections[0].anchor, Position::new(0, 2));
+    assert_eq!(model.editor().selections[0].head, Position::new(0, 4));
+    assert_eq!(model.editor().selections[1].anchor, Position::new(1, 2));
+    assert_eq!(model.editor().selections[1].head, Position::new(1, 3));
+}
+
+#[test]
+fn test_typing_commits_keyboard_column_selection() {
+    let mut model = test_model("abc\nabc\nabc\n", 0, 1);
+    for _ in 0..2 {
+        update(
+            &mut model,
+            Msg::Editor(EditorMsg::ExtendRectangleSelection(Direction::Down)),
+        );
+    }
+
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+    assert!(!model.editor().rectangle_selection.active);
+    assert_eq!(model.document().buffer.to_string(), "axbc\naxbc\naxbc\n");
+}
+
 // ========================================================================
 // Word Selection Tests (Shift+Option+Arrow)
 // ========================================================================
@@ -531,6 +584,56 @@ fn test_select_next_occurrence_on_whitespace() {
     assert_eq!(model.editor().cursors.len(), 1);
 }
 
+#[test]
+fn test_select_next_occurrence_reveals_new_cursor() {
+    // Second "target" is far below the 25-line viewport
+    let text = format!("target\n{}target\n", "filler\n".repeat(60));
+    let mut model = test_model(&text, 0, 0);
+
+    update(&mut model, Msg::Editor(EditorMsg::SelectNextOccurrence));
+    update(&mut model, Msg::Editor(EditorMsg::SelectNextOccurrence));
+    assert_eq!(model.editor().cursors.len(), 2);
+    assert_eq!(model.editor().active_cursor().line, 61);
+    assert!(
+        model.editor().viewport.top_line > 0,
+        "Viewport should follow"
+    );
+}
+
+#[test]
+fn test_skip_occurrence_moves_last_selection_to_next() {
+    let mut model = test_model("abc abc abc abc", 0, 0);
+
+    update(&mut model, Msg::Editor(EditorMsg::SelectNextOccurrence));
+    update(&mut model, Msg::Editor(EditorMsg::SelectNextOccurrence));
+    // Skip the second "abc" in favour of the third
+    update(&mut model, Msg::Editor(EditorMsg::SkipOccurrence));
+
+    let heads: Vec<Position> = model.editor().selections.iter().map(|s| s.head).collect();
+    assert_eq!(heads, vec![Position::new(0, 3), Position::new(0, 11)]);
+
+    // Selecting next continues after the skipped one, and unselecting
+    // removes the most recent again
+    update(&mut model, Msg::Editor(EditorMsg::SelectNextOccurrence));
+    assert_eq!(model.editor().cursors.len(), 3);
+    assert_eq!(model.editor().selections[2].head, Position::new(0, 15));
+    update(&mut model, Msg::Editor(EditorMsg::UnselectOccurrence));
+    let heads: Vec<Position> = model.editor().selections.iter().map(|s| s.head).collect();
+    assert_eq!(heads, vec![Position::new(0, 3), Position::new(0, 11)]);
+}
+
+#[test]
+fn test_skip_occurrence_replaces_lone_selection() {
+    let mut model = test_model_with_selection("foo bar foo", 0, 0, 0, 3);
+
+    update(&mut model, Msg::Editor(EditorMsg::SkipOccurrence));
+
+    assert_eq!(model.editor().cursors.len(), 1);
+    let sel = model.editor().primary_selection();
+    assert_eq!(sel.anchor, Position::new(0, 8));
+    assert_eq!(sel.head, Position::new(0, 11));
+}
+
 // ========================================================================
 // Cursor/Selection Invariant Tests
 // ========================================================================
@@ -1255,3 +1358,73 @@ fn test_extend_selection_resets_active_cursor_index() {
         "Active cursor index should be reset to 0"
     );
 }
+
+// ========================================================================
+// Selection Drag-and-Drop Tests
+// ========================================================================
+
+fn drag_selection_to(model: &mut token::model::AppModel, line: usize, column: usize, copy: bool) {
+    let origin = model.editor().primary_selection().start();
+    update(
+        model,
+        Msg::Editor(EditorMsg::StartTextDrag {
+            line: origin.line,
+            column: origin.column,
+        }),
+    );
+    update(
+        model,
+        Msg::Editor(EditorMsg::UpdateTextDrag { line, column }),
+    );
+    update(model, Msg::Document(DocumentMsg::DropDraggedText { copy }));
+}
+
+#[test]
+fn test_drag_moves_selection_as_single_undo_step() {
+    // Select "one " and drop it before "three"
+    let mut model = test_model_with_selection("one two three", 0, 0, 0, 4);
+    drag_selection_to(&mut model, 0, 8, false);
+
+    assert_eq!(model.document().buffer.to_string(), "two one three");
+    let selection = model.editor().primary_selection();
+    assert_eq!(selection.start(), Position::new(0, 4));
+    assert_eq!(selection.end(), Position::new(0, 8));
+    assert!(model.editor().text_drag.is_none());
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(model.document().buffer.to_string(), "one two three");
+}
+
+#[test]
+fn test_drag_with_copy_keeps_source() {
+    let mut model = test_model_with_selection("abc\nxyz", 1, 0, 1, 3);
+    drag_selection_to(&mut model, 0, 1, true);
+
+    assert_eq!(model.document().buffer.to_string(), "axyzbc\nxyz");
+    let selection = model.editor().primary_selection();
+    assert_eq!(selection.start(), Position::new(0, 1));
+    assert_eq!(selection.end(), Position::new(0, 4));
+}
+
+#[test]
+fn test_drop_inside_moved_selection_is_noop() {
+    let mut model = test_model_with_selection("hello world", 0, 0, 0, 5);
+    drag_selection_to(&mut model, 0, 2, false);
+
+    assert_eq!(model.document().buffer.to_string(), "hello world");
+    assert!(model.document().undo_stack.is_empty());
+}
+
+#[test]
+fn test_text_drag_without_movement_places_cursor() {
+    let mut model = test_model_with_selection("hello world", 0, 0, 0, 5);
+    update(
+        &mut model,
+        Msg::Editor(EditorMsg::StartTextDrag { line: 0, column: 3 }),
+    );
+    update(&mut model, Msg::Editor(EditorMsg::CancelTextDrag));
+
+    assert!(model.editor().primary_selection().is_empty());
+    assert_eq!(model.editor().primary_cursor().column, 3);
+    assert!(model.editor().text_drag.is_none());
+}
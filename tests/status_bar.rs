@@ -26,6 +26,7 @@ fn test_segment_id_all_variants_exist() {
         SegmentId::LineCount,
         SegmentId::Selection,
         SegmentId::StatusMessage,
+        SegmentId::TaskProgress,
     ];
 }
 
@@ -188,8 +189,9 @@ fn test_all_segments_iteration() {
     let bar = StatusBar::new();
     let all: Vec<_> = bar.all_segments().collect();
 
-    // Should have 7 segments total (including CaretCount)
-    assert_eq!(all.len(), 7);
+    // Should have 13 segments total (including CaretCount, TaskProgress,
+    // PerformanceMode, VimMode, KeyChord, FileLoading and Language)
+    assert_eq!(all.len(), 13);
 }
 
 // =============================================================================
@@ -587,6 +589,29 @@ fn test_layout_separator_positions() {
     }
 }
 
+#[test]
+fn test_layout_segment_at_finds_clicked_segment() {
+    let mut bar = StatusBar::new();
+    bar.update_segment(
+        SegmentId::Language,
+        SegmentContent::Text("Rust".to_string()),
+    );
+    let layout = bar.layout(120);
+
+    let language = layout
+        .right
+        .iter()
+        .find(|s| s.id == SegmentId::Language)
+        .unwrap();
+    assert_eq!(layout.segment_at(language.x), Some(SegmentId::Language));
+    assert_eq!(
+        layout.segment_at(language.x + language.width - 1),
+        Some(SegmentId::Language)
+    );
+    // The gap before it belongs to no segment
+    assert_eq!(layout.segment_at(language.x - 1), None);
+}
+
 #[test]
 fn test_layout_left_segments_ordered_correctly() {
     let mut bar = StatusBar::new();
@@ -684,3 +709,67 @@ fn test_status_bar_syncs_after_edit() {
         "Modified indicator should show after edit"
     );
 }
+
+#[test]
+fn test_task_progress_tracks_checkbox_edits() {
+    let mut model = test_model("- [ ] one\n- [x] two\n- [ ] three\n", 0, 3);
+    model.document_mut().language = token::syntax::LanguageId::Markdown;
+    sync_status_bar(&mut model);
+
+    let task_text = |model: &token::model::AppModel| {
+        model
+            .ui
+            .status_bar
+            .get_segment(SegmentId::TaskProgress)
+            .unwrap()
+            .content
+            .display_text()
+            .to_string()
+    };
+    assert_eq!(task_text(&model), "1/3 tasks");
+
+    // Check the first box: replace the space between the brackets
+    let _ = update(
+        &mut model,
+        Msg::Document(token::messages::DocumentMsg::DeleteForward),
+    );
+    let _ = update(
+        &mut model,
+        Msg::Document(token::messages::DocumentMsg::InsertChar('x')),
+    );
+    assert_eq!(task_text(&model), "2/3 tasks");
+
+    model.document_mut().language = token::syntax::LanguageId::PlainText;
+    sync_status_bar(&mut model);
+    assert_eq!(task_text(&model), "");
+}
+
+#[test]
+fn test_pending_chord_shows_until_resolved() {
+    let mut model = test_model("hello", 0, 0);
+    let chord_text = |model: &token::model::AppModel| {
+        model
+            .ui
+            .status_bar
+            .get_segment(SegmentId::KeyChord)
+            .unwrap()
+            .content
+            .display_text()
+            .to_string()
+    };
+
+    let _ = update(
+        &mut model,
+        Msg::Ui(UiMsg::ChordPending(Some("Ctrl+K".into()))),
+    );
+    assert_eq!(chord_text(&model), "Ctrl+K …");
+
+    let _ = update(&mut model, Msg::Ui(UiMsg::ChordNotBound("Ctrl+K X".into())));
+    assert_eq!(chord_text(&model), "");
+    let message = model
+        .ui
+        .status_bar
+        .get_segment(SegmentId::StatusMessage)
+        .unwrap();
+    assert_eq!(message.content.display_text(), "Ctrl+K X is not bound");
+}
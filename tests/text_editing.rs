@@ -114,6 +114,24 @@ fn test_insert_after_cursor_position_clamped() {
     );
 }
 
+#[test]
+fn test_tab_inserts_spaces_to_next_indent_stop() {
+    let mut model = test_model("ab", 0, 1);
+    model.config.indent_style = token::config::IndentStyle::Spaces;
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('\t')));
+
+    assert_eq!(buffer_to_string(&model), "a   b");
+    assert_eq!(model.editor().primary_cursor().column, 4);
+}
+
+#[test]
+fn test_tab_inserts_hard_tab_by_default() {
+    let mut model = test_model("ab", 0, 1);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('\t')));
+
+    assert_eq!(buffer_to_string(&model), "a\tb");
+}
+
 // ========================================================================
 // InsertNewline tests
 // ========================================================================
@@ -264,6 +282,96 @@ fn test_undo_delete() {
     assert_eq!(model.editor().primary_cursor().column, 5);
 }
 
+fn type_text(model: &mut token::model::AppModel, text: &str) {
+    for ch in text.chars() {
+        update(model, Msg::Document(DocumentMsg::InsertChar(ch)));
+    }
+}
+
+#[test]
+fn test_typed_word_undoes_as_one_step() {
+    let mut model = test_model("", 0, 0);
+    type_text(&mut model, "let value");
+    assert_eq!(model.document().undo_stack.len(), 2);
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "let ");
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "");
+    assert_eq!(model.editor().primary_cursor().column, 0);
+
+    update(&mut model, Msg::Document(DocumentMsg::Redo));
+    update(&mut model, Msg::Document(DocumentMsg::Redo));
+    assert_eq!(buffer_to_string(&model), "let value");
+    assert_eq!(model.editor().primary_cursor().column, 9);
+}
+
+#[test]
+fn test_backspaces_undo_as_one_step() {
+    let mut model = test_model("hello", 0, 5);
+    for _ in 0..3 {
+        update(&mut model, Msg::Document(DocumentMsg::DeleteBackward));
+    }
+    assert_eq!(buffer_to_string(&model), "he");
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "hello");
+    assert_eq!(model.editor().primary_cursor().column, 5);
+}
+
+#[test]
+fn test_undo_group_boundary_splits_typing() {
+    let mut model = test_model("", 0, 0);
+    type_text(&mut model, "ab");
+    update(&mut model, Msg::Document(DocumentMsg::UndoGroupBoundary));
+    type_text(&mut model, "cd");
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "ab");
+}
+
+#[test]
+fn test_moving_the_cursor_ends_the_undo_step() {
+    use token::messages::{Direction, EditorMsg};
+
+    let mut model = test_model("", 0, 0);
+    type_text(&mut model, "ab");
+    update(
+        &mut model,
+        Msg::Editor(EditorMsg::MoveCursor(Direction::Left)),
+    );
+    update(
+        &mut model,
+        Msg::Editor(EditorMsg::MoveCursor(Direction::Right)),
+    );
+    type_text(&mut model, "c");
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "ab");
+}
+
+#[test]
+fn test_typing_after_save_starts_new_undo_step() {
+    let mut model = test_model("", 0, 0);
+    type_text(&mut model, "ab");
+    update(&mut model, Msg::App(AppMsg::SaveCompleted(Ok(()))));
+    type_text(&mut model, "cd");
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "ab");
+    assert!(!model.document().is_modified);
+}
+
+#[test]
+fn test_typing_is_not_grouped_when_disabled() {
+    let mut model = test_model("", 0, 0);
+    model.config.undo.group_typing = false;
+    type_text(&mut model, "ab");
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "a");
+}
+
 // ========================================================================
 // Undo/Redo with selection tests
 // ========================================================================
@@ -354,12 +462,65 @@ fn test_undo_insert_newline_over_selection() {
 }
 
 // ========================================================================
-// Duplicate Line/Selection tests (Cmd+D)
+// Move lines tests (Alt+Up/Down)
+// ========================================================================
+
+#[test]
+fn test_move_line_up_keeps_cursor_column() {
+    let mut model = test_model("one\ntwo\nthree\n", 1, 2);
+    update(&mut model, Msg::Document(DocumentMsg::MoveLinesUp));
+
+    assert_eq!(buffer_to_string(&model), "two\none\nthree\n");
+    let cursor = model.editor().primary_cursor();
+    assert_eq!((cursor.line, cursor.column), (0, 2));
+}
+
+#[test]
+fn test_move_last_line_down_is_a_no_op() {
+    let mut model = test_model("one\ntwo\n", 1, 0);
+    update(&mut model, Msg::Document(DocumentMsg::MoveLinesDown));
+    assert_eq!(buffer_to_string(&model), "one\ntwo\n");
+
+    let mut model = test_model("one\ntwo", 0, 0);
+    update(&mut model, Msg::Document(DocumentMsg::MoveLinesUp));
+    assert_eq!(buffer_to_string(&model), "one\ntwo");
+    assert!(model.document().undo_stack.is_empty());
+}
+
+#[test]
+fn test_move_line_down_without_trailing_newline() {
+    let mut model = test_model("one\ntwo", 0, 1);
+    update(&mut model, Msg::Document(DocumentMsg::MoveLinesDown));
+
+    assert_eq!(buffer_to_string(&model), "two\none");
+    assert_eq!(model.editor().primary_cursor().line, 1);
+}
+
+#[test]
+fn test_move_selected_lines_down_moves_selection_and_undoes() {
+    // Selection of lines 0-1, ending at the start of line 2
+    let mut model = test_model_with_selection("a\nb\nc\nd\n", 0, 0, 2, 0);
+    update(&mut model, Msg::Document(DocumentMsg::MoveLinesDown));
+
+    assert_eq!(buffer_to_string(&model), "c\na\nb\nd\n");
+    let selection = model.editor().primary_selection();
+    assert_eq!(
+        (selection.start().line, selection.end().line),
+        (1, 3),
+        "selection follows the moved lines"
+    );
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "a\nb\nc\nd\n");
+}
+
+// ========================================================================
+// Duplicate Line/Selection tests (Cmd+Shift+D)
 // ========================================================================
 
 #[test]
 fn test_duplicate_line_no_selection() {
-    // When no selection, Cmd+D should duplicate the current line
+    // When no selection, Cmd+Shift+D should duplicate the current line
     let mut model = test_model("hello\nworld\n", 0, 2);
 
     update(&mut model, Msg::Document(DocumentMsg::Duplicate));
@@ -821,3 +982,187 @@ fn test_insert_text_is_a_single_atomic_undo_record() {
         "a single undo should remove the entire inserted string at once"
     );
 }
+
+// ========================================================================
+// Markup tag auto-close and linked tag editing
+// ========================================================================
+
+fn html_model(text: &str, line: usize, column: usize) -> token::model::AppModel {
+    let mut model = test_model(text, line, column);
+    model.document_mut().language = token::syntax::LanguageId::Html;
+    model
+}
+
+#[test]
+fn test_typing_slash_after_lt_closes_open_tag() {
+    let mut model = html_model("<div><p>hi<", 0, 11);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('/')));
+
+    assert_eq!(buffer_to_string(&model), "<div><p>hi</p>");
+    assert_eq!(model.editor().primary_cursor().column, 14);
+
+    // Undo removes the whole completion in one step
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "<div><p>hi<");
+}
+
+#[test]
+fn test_tag_auto_close_respects_config_and_language() {
+    let mut model = html_model("<div><", 0, 6);
+    model.config.auto_close_tags = false;
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('/')));
+    assert_eq!(buffer_to_string(&model), "<div></");
+
+    let mut model = test_model("<div><", 0, 6);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('/')));
+    assert_eq!(buffer_to_string(&model), "<div></");
+}
+
+#[test]
+fn test_renaming_open_tag_updates_close_tag() {
+    let mut model = html_model("<div>text</div>", 0, 4);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+    assert_eq!(buffer_to_string(&model), "<divx>text</divx>");
+    assert_eq!(model.editor().primary_cursor().column, 5);
+
+    update(&mut model, Msg::Document(DocumentMsg::DeleteBackward));
+    update(&mut model, Msg::Document(DocumentMsg::DeleteBackward));
+    assert_eq!(buffer_to_string(&model), "<di>text</di>");
+
+    // Each mirrored keystroke undoes as a single step
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "<div>text</div>");
+}
+
+#[test]
+fn test_renaming_close_tag_updates_open_tag_and_keeps_cursor() {
+    let mut model = html_model("<ul><li>a</li></ul>", 0, 13);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+    assert_eq!(buffer_to_string(&model), "<ul><lix>a</lix></ul>");
+    // Cursor follows its own edit, shifted by the mirrored insert before it
+    assert_eq!(model.editor().primary_cursor().column, 15);
+}
+
+#[test]
+fn test_typing_outside_tag_name_is_not_mirrored() {
+    let mut model = html_model("<div>text</div>", 0, 4);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar(' ')));
+    assert_eq!(buffer_to_string(&model), "<div >text</div>");
+
+    let mut model = html_model("<div>text</div>", 0, 6);
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+    assert_eq!(buffer_to_string(&model), "<div>txext</div>");
+}
+
+// ========================================================================
+// Re-indent on closing brace
+// ========================================================================
+
+#[test]
+fn test_closing_brace_reindents_block_when_enabled() {
+    let text = "fn main() {\n  if x {\n        a();\n  }\n    ";
+    let mut model = test_model(text, 4, 4);
+    model.document_mut().language = token::syntax::LanguageId::Rust;
+    model.config.reindent_on_close_brace = true;
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('}')));
+
+    assert_eq!(
+        buffer_to_string(&model),
+        "fn main() {\n  if x {\n    a();\n  }\n}"
+    );
+    assert_eq!(model.editor().primary_cursor().column, 1);
+
+    // Undo restores the typed indentation, keeping the brace
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), format!("{text}}}"));
+    assert_eq!(model.editor().primary_cursor().column, 5);
+
+    // Off by default
+    let mut model = test_model(text, 4, 4);
+    model.document_mut().language = token::syntax::LanguageId::Rust;
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('}')));
+    assert_eq!(buffer_to_string(&model), format!("{text}}}"));
+}
+
+// ========================================================================
+// Evaluate Selection
+// ========================================================================
+
+#[test]
+fn test_evaluate_selection_replaces_or_appends_result() {
+    use token::commands::CommandId;
+    use token::update::execute_command;
+
+    // Selection is replaced with its value
+    let mut model = test_model_with_selection("size: 2 * 0x10;", 0, 6, 0, 14);
+    execute_command(&mut model, CommandId::EvaluateAndReplace);
+    assert_eq!(buffer_to_string(&model), "size: 32;");
+
+    // Without a selection the whole line is evaluated, and a trailing `=` kept
+    let mut model = test_model("1 GiB in bytes\n3 * 7 =", 1, 0);
+    execute_command(&mut model, CommandId::EvaluateAndAppend);
+    assert_eq!(buffer_to_string(&model), "1 GiB in bytes\n3 * 7 = 21");
+
+    let mut model = test_model("1 GiB in bytes", 0, 3);
+    execute_command(&mut model, CommandId::EvaluateAndAppend);
+    assert_eq!(buffer_to_string(&model), "1 GiB in bytes = 1073741824");
+
+    // Errors leave the text alone
+    let mut model = test_model("1 / 0", 0, 0);
+    execute_command(&mut model, CommandId::EvaluateAndReplace);
+    assert_eq!(buffer_to_string(&model), "1 / 0");
+    assert!(model.ui.transient_message.is_some());
+}
+
+// ========================================================================
+// Replace All
+// ========================================================================
+
+#[test]
+fn test_replace_all_is_one_undo_step() {
+    let mut model = test_model("let a = 1;\nlet bb = 22;\n", 1, 0);
+    update(
+        &mut model,
+        Msg::Document(DocumentMsg::ReplaceAll {
+            query: r"let (\w+) = (\d+);".to_string(),
+            replacement: "const $1: i32 = $2;".to_string(),
+            regex: true,
+            case_sensitive: true,
+        }),
+    );
+    assert_eq!(
+        buffer_to_string(&model),
+        "const a: i32 = 1;\nconst bb: i32 = 22;\n"
+    );
+    assert_eq!(
+        (
+            model.editor().primary_cursor().line,
+            model.editor().primary_cursor().column
+        ),
+        (0, 17)
+    );
+
+    update(&mut model, Msg::Document(DocumentMsg::Undo));
+    assert_eq!(buffer_to_string(&model), "let a = 1;\nlet bb = 22;\n");
+    update(&mut model, Msg::Document(DocumentMsg::Redo));
+    assert_eq!(
+        buffer_to_string(&model),
+        "const a: i32 = 1;\nconst bb: i32 = 22;\n"
+    );
+
+    // Literal queries ignore regex syntax, and invalid patterns change nothing
+    for (query, regex) in [("a.b", false), ("(", true)] {
+        let mut model = test_model("a.b axb\n", 0, 0);
+        update(
+            &mut model,
+            Msg::Document(DocumentMsg::ReplaceAll {
+                query: query.to_string(),
+                replacement: "-".to_string(),
+                regex,
+                case_sensitive: false,
+            }),
+        );
+        let expected = if regex { "a.b axb\n" } else { "- axb\n" };
+        assert_eq!(buffer_to_string(&model), expected);
+    }
+}
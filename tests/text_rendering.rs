@@ -0,0 +1,135 @@
+//! Golden-image tests for text antialiasing
+//!
+//! Renders sample text in each antialiasing mode and compares it with the
+//! PNGs in `tests/golden`. After an intended rendering change, regenerate
+//! them with `UPDATE_GOLDEN=1 cargo test --test text_rendering` and look at
+//! the new images before committing them.
+
+use std::path::PathBuf;
+
+use fontdue::{Font, FontSettings};
+use token::rendering::{blend_pixel_u8, blend_subpixel_u8};
+use token::view::{Frame, GlyphCache, TextPainter};
+
+const WIDTH: usize = 120;
+const HEIGHT: usize = 24;
+/// Per-channel difference allowed for floating point differences between
+/// platforms
+const TOLERANCE: u8 = 2;
+
+fn render_text(subpixel: bool, foreground: u32, background: u32) -> Vec<u32> {
+    let font = Font::from_bytes(
+        include_bytes!("../assets/JetBrainsMono.ttf") as &[u8],
+        FontSettings::default(),
+    )
+    .expect("test font should load");
+    let font_size = 14.0;
+    let line_metrics = font.horizontal_line_metrics(font_size).unwrap();
+    let (metrics, _) = font.rasterize('M', font_size);
+    let mut glyph_cache = GlyphCache::default();
+    let mut painter = TextPainter::new(
+        &font,
+        &mut glyph_cache,
+        font_size,
+        line_metrics.ascent,
+        metrics.advance_width,
+        line_metrics.new_line_size.ceil() as usize,
+    )
+    .with_subpixel(subpixel);
+
+    let mut buffer = vec![background; WIDTH * HEIGHT];
+    let mut frame = Frame::new(&mut buffer, WIDTH, HEIGHT);
+    painter.draw(&mut frame, 4, 3, "Token ag{1}", foreground);
+    buffer
+}
+
+fn assert_matches_golden(name: &str, pixels: &[u32]) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.png"));
+    let rgb: Vec<u8> = pixels
+        .iter()
+        .flat_map(|p| [(p >> 16) as u8, (p >> 8) as u8, *p as u8])
+        .collect();
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        image::RgbImage::from_raw(WIDTH as u32, HEIGHT as u32, rgb)
+            .unwrap()
+            .save(&path)
+            .unwrap();
+        return;
+    }
+
+    let golden = image::open(&path)
+        .unwrap_or_else(|e| panic!("{}: {} (set UPDATE_GOLDEN=1)", path.display(), e))
+        .to_rgb8();
+    assert_eq!(
+        golden.dimensions(),
+        (WIDTH as u32, HEIGHT as u32),
+        "{name}: size"
+    );
+    let mismatches = golden
+        .as_raw()
+        .iter()
+        .zip(&rgb)
+        .filter(|(a, b)| a.abs_diff(**b) > TOLERANCE)
+        .count();
+    assert_eq!(
+        mismatches,
+        0,
+        "{name}: channels differ from {}",
+        path.display()
+    );
+}
+
+#[test]
+fn grayscale_text_matches_golden() {
+    assert_matches_golden(
+        "text_grayscale_light",
+        &render_text(false, 0xFF1E1E1E, 0xFFFAFAFA),
+    );
+    assert_matches_golden(
+        "text_grayscale_dark",
+        &render_text(false, 0xFFD4D4D4, 0xFF1E1E1E),
+    );
+}
+
+#[test]
+fn subpixel_text_matches_golden() {
+    assert_matches_golden(
+        "text_subpixel_light",
+        &render_text(true, 0xFF1E1E1E, 0xFFFAFAFA),
+    );
+    assert_matches_golden(
+        "text_subpixel_dark",
+        &render_text(true, 0xFFD4D4D4, 0xFF1E1E1E),
+    );
+}
+
+#[test]
+fn subpixel_text_has_per_channel_coverage() {
+    let pixels = render_text(true, 0xFF000000, 0xFFFFFFFF);
+    // Edge pixels are covered unevenly across their channels, which
+    // grayscale rendering never does
+    let fringed = pixels.iter().any(|p| {
+        let (r, g, b) = ((p >> 16) & 0xFF, (p >> 8) & 0xFF, p & 0xFF);
+        r != g || g != b
+    });
+    assert!(fringed);
+    let grayscale = render_text(false, 0xFF000000, 0xFFFFFFFF);
+    assert!(grayscale.iter().all(|p| (p >> 16) & 0xFF == p & 0xFF));
+}
+
+#[test]
+fn subpixel_blend_is_gamma_correct() {
+    let (black, white) = (0xFF000000, 0xFFFFFFFF);
+    assert_eq!(blend_subpixel_u8(black, white, [0, 0, 0]), black);
+    assert_eq!(blend_subpixel_u8(black, white, [255, 255, 255]), white);
+    assert_eq!(blend_subpixel_u8(black, white, [255, 0, 0]), 0xFFFF0000);
+
+    // Half coverage is half the light, which is brighter than half the
+    // encoded value
+    let half = blend_subpixel_u8(black, white, [128, 128, 128]) & 0xFF;
+    assert!(half > (blend_pixel_u8(black, white, 128) & 0xFF) + 40);
+}
@@ -0,0 +1,126 @@
+//! Vim emulation tests: normal, insert and visual mode commands
+
+mod common;
+
+use common::{buffer_to_string, test_model};
+use token::messages::{DocumentMsg, Msg, VimMsg};
+use token::model::AppModel;
+use token::update::update;
+use token::vim::VimMode;
+
+fn vim_model(text: &str, line: usize, column: usize) -> AppModel {
+    let mut model = test_model(text, line, column);
+    model.config.vim_mode = true;
+    model
+}
+
+fn keys(model: &mut AppModel, keys: &str) {
+    for ch in keys.chars() {
+        update(model, Msg::Vim(VimMsg::Key(ch)));
+    }
+}
+
+fn cursor(model: &AppModel) -> (usize, usize) {
+    let cursor = model.editor().active_cursor();
+    (cursor.line, cursor.column)
+}
+
+#[test]
+fn test_keys_are_ignored_when_vim_mode_is_off() {
+    let mut model = test_model("hello world", 0, 0);
+    keys(&mut model, "dw");
+    assert_eq!(buffer_to_string(&model), "hello world");
+    assert_eq!(model.vim.pending(), "");
+}
+
+#[test]
+fn test_motions_with_counts() {
+    let mut model = vim_model("one two three four\nfive", 0, 0);
+    keys(&mut model, "2w");
+    assert_eq!(cursor(&model), (0, 8));
+    keys(&mut model, "$");
+    assert_eq!(
+        cursor(&model),
+        (0, 17),
+        "normal mode stays on the last char"
+    );
+    keys(&mut model, "G");
+    assert_eq!(cursor(&model), (1, 0));
+    keys(&mut model, "gg");
+    assert_eq!(cursor(&model), (0, 0));
+}
+
+#[test]
+fn test_delete_word_and_lines() {
+    let mut model = vim_model("one two three\nfour\nfive\nsix", 0, 0);
+    keys(&mut model, "dw");
+    assert_eq!(buffer_to_string(&model), "two three\nfour\nfive\nsix");
+
+    keys(&mut model, "w2dw");
+    assert_eq!(
+        buffer_to_string(&model),
+        "two \nfour\nfive\nsix",
+        "dw stops at the end of the line"
+    );
+
+    keys(&mut model, "j2dd");
+    assert_eq!(buffer_to_string(&model), "two \nsix");
+    assert_eq!(cursor(&model), (1, 0));
+
+    keys(&mut model, "dd");
+    assert_eq!(buffer_to_string(&model), "two ");
+    assert_eq!(cursor(&model), (0, 0));
+}
+
+#[test]
+fn test_change_word_enters_insert_mode() {
+    let mut model = vim_model("foo bar", 0, 0);
+    keys(&mut model, "cw");
+    assert_eq!(buffer_to_string(&model), " bar");
+    assert_eq!(model.vim.mode, VimMode::Insert);
+
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('x')));
+    update(&mut model, Msg::Vim(VimMsg::NormalMode));
+    assert_eq!(buffer_to_string(&model), "x bar");
+    assert_eq!(model.vim.mode, VimMode::Normal);
+    assert_eq!(cursor(&model), (0, 0), "leaving insert mode steps back");
+}
+
+#[test]
+fn test_visual_mode_selects_inclusively() {
+    let mut model = vim_model("hello world", 0, 0);
+    keys(&mut model, "vl");
+    assert_eq!(model.vim.mode, VimMode::Visual);
+    keys(&mut model, "d");
+    assert_eq!(buffer_to_string(&model), "llo world");
+    assert_eq!(model.vim.mode, VimMode::Normal);
+    assert!(model.editor().active_selection().is_empty());
+}
+
+#[test]
+fn test_open_line_below_and_undo() {
+    let mut model = vim_model("one\ntwo", 0, 1);
+    keys(&mut model, "o");
+    assert_eq!(buffer_to_string(&model), "one\n\ntwo");
+    assert_eq!(cursor(&model), (1, 0));
+    assert_eq!(model.vim.mode, VimMode::Insert);
+
+    update(&mut model, Msg::Vim(VimMsg::NormalMode));
+    keys(&mut model, "u");
+    assert_eq!(buffer_to_string(&model), "one\ntwo");
+}
+
+#[test]
+fn test_pending_command_shows_in_status_bar() {
+    let mut model = vim_model("text", 0, 0);
+    keys(&mut model, "2d");
+    let segment = model
+        .ui
+        .status_bar
+        .get_segment(token::model::SegmentId::VimMode)
+        .unwrap();
+    assert_eq!(segment.content.display_text(), "NORMAL 2d");
+
+    update(&mut model, Msg::Vim(VimMsg::NormalMode));
+    assert_eq!(model.vim.pending(), "");
+}
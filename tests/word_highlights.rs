@@ -0,0 +1,68 @@
+//! Highlighting the other occurrences of the word under the cursor
+
+mod common;
+
+use common::test_model;
+use token::messages::{Direction, DocumentMsg, EditorMsg, Msg, UiMsg};
+use token::update::{update, word_highlight_deadline};
+
+const TEXT: &str = "let value = 1;\nprint(value);\nvalues = value + 1;\n";
+
+#[test]
+fn occurrences_are_found_once_the_cursor_rests_in_a_word() {
+    let mut model = test_model(TEXT, 0, 4);
+    update(
+        &mut model,
+        Msg::Editor(EditorMsg::MoveCursor(Direction::Right)),
+    );
+    assert!(word_highlight_deadline(&model).is_some());
+    assert!(model.editor().word_highlights.ranges.is_empty());
+
+    update(&mut model, Msg::Ui(UiMsg::HighlightWordOccurrences));
+    // Whole words only, and not the one under the cursor
+    assert_eq!(
+        model.editor().word_highlights.ranges,
+        [(1, 6, 11), (2, 9, 14)]
+    );
+    assert_eq!(word_highlight_deadline(&model), None);
+
+    // Moving within the word keeps them
+    update(
+        &mut model,
+        Msg::Editor(EditorMsg::MoveCursor(Direction::Right)),
+    );
+    assert_eq!(model.editor().word_highlights.ranges.len(), 2);
+}
+
+#[test]
+fn editing_or_leaving_the_word_drops_the_occurrences() {
+    let mut model = test_model(TEXT, 0, 5);
+    update(
+        &mut model,
+        Msg::Editor(EditorMsg::MoveCursor(Direction::Right)),
+    );
+    update(&mut model, Msg::Ui(UiMsg::HighlightWordOccurrences));
+    assert!(!model.editor().word_highlights.ranges.is_empty());
+
+    update(&mut model, Msg::Document(DocumentMsg::InsertChar('s')));
+    assert!(model.editor().word_highlights.ranges.is_empty());
+    assert!(word_highlight_deadline(&model).is_some());
+
+    // Off a word there is nothing to wait for
+    update(&mut model, Msg::Editor(EditorMsg::MoveCursorLineEnd));
+    assert_eq!(model.editor().word_highlights.key, None);
+    assert_eq!(word_highlight_deadline(&model), None);
+}
+
+#[test]
+fn highlight_occurrences_false_turns_them_off() {
+    let mut model = test_model(TEXT, 0, 4);
+    model.config.highlight_occurrences = false;
+    update(
+        &mut model,
+        Msg::Editor(EditorMsg::MoveCursor(Direction::Right)),
+    );
+    assert_eq!(word_highlight_deadline(&model), None);
+    update(&mut model, Msg::Ui(UiMsg::HighlightWordOccurrences));
+    assert!(model.editor().word_highlights.ranges.is_empty());
+}
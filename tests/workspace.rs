@@ -294,6 +294,8 @@ fn test_workspace() -> Workspace {
         sidebar_visible: true,
         sidebar_width_logical: metrics.sidebar_default_width_logical,
         scroll_offset: 0,
+        config: Default::default(),
+        name_edit: None,
     }
 }
 
@@ -677,6 +679,46 @@ fn test_workspace_open_or_toggle_folder() {
     assert!(!model.workspace.as_ref().unwrap().is_expanded(&folder_path));
 }
 
+#[test]
+fn test_dropping_folder_and_files_opens_workspace_and_tabs() {
+    use common::test_model;
+    use std::fs;
+    use tempfile::tempdir;
+    use token::messages::UiMsg;
+
+    let dir = tempdir().expect("Failed to create temp dir");
+    let folder = dir.path().join("project");
+    fs::create_dir(&folder).expect("Failed to create folder");
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+    fs::write(&a, "a").expect("Failed to write file");
+    fs::write(&b, "b").expect("Failed to write file");
+
+    let mut model = test_model("hello\n", 0, 0);
+    for path in [&folder, &a, &b] {
+        update(&mut model, Msg::Ui(UiMsg::FileHovered(path.clone())));
+    }
+    assert_eq!(
+        model.ui.drop_state.display_text(),
+        "Drop to open folder project and 2 files"
+    );
+
+    // Nothing opens until the last dropped path arrives
+    update(&mut model, Msg::Ui(UiMsg::FileDropped(folder.clone())));
+    update(&mut model, Msg::Ui(UiMsg::FileDropped(a.clone())));
+    assert!(model.workspace.is_none());
+    update(&mut model, Msg::Ui(UiMsg::FileDropped(b.clone())));
+
+    assert!(!model.ui.drop_state.is_hovering);
+    assert_eq!(model.workspace.as_ref().unwrap().root, folder);
+    assert!(model.editor_area.find_document_by_path(&a).is_some());
+    assert!(model.editor_area.find_document_by_path(&b).is_some());
+    assert_eq!(
+        model.ui.transient_message.as_ref().unwrap().text,
+        "Opened 2 files"
+    );
+}
+
 // ============================================================================
 // Sidebar resize tests
 // ============================================================================